@@ -38,6 +38,7 @@ class KeyboardAccessoryBar(
             KeySpec("↑", "up", true, R.drawable.ic_key_arrow_up),
             KeySpec("→", "right", true, R.drawable.ic_key_arrow_right),
             KeySpec("⏎", "enter", false, R.drawable.ic_key_return),
+            KeySpec("Paste", "paste", false),
         )
 
     private val repeatInitialDelayMs = 350L
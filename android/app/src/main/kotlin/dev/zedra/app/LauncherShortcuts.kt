@@ -0,0 +1,154 @@
+package dev.zedra.app
+
+import android.app.PendingIntent
+import android.appwidget.AppWidgetManager
+import android.appwidget.AppWidgetProvider
+import android.content.ComponentName
+import android.content.Context
+import android.content.Intent
+import android.net.Uri
+import android.util.Log
+import android.view.View
+import android.widget.RemoteViews
+import androidx.core.content.ContextCompat
+import androidx.core.content.pm.ShortcutInfoCompat
+import androidx.core.content.pm.ShortcutManagerCompat
+import androidx.core.graphics.drawable.IconCompat
+import org.json.JSONArray
+import org.json.JSONObject
+
+/**
+ * Publishes saved workspaces from crates/zedra/src/launcher_shortcuts.rs as
+ * dynamic launcher shortcuts and feeds [HostWidgetProvider]. Each shortcut opens
+ * `zedra://session/<id>`, which MainActivity's VIEW filter routes to the Rust
+ * deeplink handler. The last list is kept in shared preferences so the widget
+ * can render while the app process is gone.
+ */
+object LauncherShortcuts {
+    private const val TAG = "LauncherShortcuts"
+    private const val PREFS = "zedra_launcher"
+    private const val KEY_SHORTCUTS = "shortcuts"
+
+    fun publish(context: Context, shortcutsJson: String) {
+        val shortcuts = try {
+            JSONArray(shortcutsJson)
+        } catch (e: Throwable) {
+            Log.w(TAG, "invalid shortcuts json", e)
+            return
+        }
+        context.getSharedPreferences(PREFS, Context.MODE_PRIVATE)
+            .edit()
+            .putString(KEY_SHORTCUTS, shortcutsJson)
+            .apply()
+
+        val infos = (0 until shortcuts.length()).map { index ->
+            val shortcut = shortcuts.getJSONObject(index)
+            val label = shortcut.optString("label", "Workspace")
+            ShortcutInfoCompat.Builder(context, shortcut.getString("id"))
+                .setShortLabel(label)
+                .setLongLabel(longLabel(shortcut))
+                .setIcon(IconCompat.createWithResource(context, R.mipmap.ic_launcher))
+                .setIntent(viewIntent(context, shortcut.getString("url")))
+                .setRank(index)
+                .build()
+        }
+        try {
+            ShortcutManagerCompat.setDynamicShortcuts(context, infos)
+        } catch (e: Throwable) {
+            Log.w(TAG, "setDynamicShortcuts failed", e)
+        }
+        HostWidgetProvider.refresh(context)
+    }
+
+    /** The widget's host: the first published shortcut, if any. */
+    fun firstHost(context: Context): JSONObject? {
+        val json = context.getSharedPreferences(PREFS, Context.MODE_PRIVATE)
+            .getString(KEY_SHORTCUTS, null) ?: return null
+        return try {
+            JSONArray(json).optJSONObject(0)
+        } catch (e: Throwable) {
+            null
+        }
+    }
+
+    fun viewIntent(context: Context, url: String): Intent =
+        Intent(Intent.ACTION_VIEW, Uri.parse(url)).setClass(context, MainActivity::class.java)
+
+    private fun longLabel(shortcut: JSONObject): String {
+        val label = shortcut.optString("label", "Workspace")
+        val hostname = shortcut.optString("hostname")
+        return if (hostname.isEmpty()) label else "$label · $hostname"
+    }
+}
+
+/**
+ * Home-screen widget for the first published host: name, last known status,
+ * and a Connect button that opens its session link. Status only changes while
+ * the app is running, since the Rust side reports what its live session saw.
+ */
+class HostWidgetProvider : AppWidgetProvider() {
+    override fun onUpdate(context: Context, manager: AppWidgetManager, widgetIds: IntArray) {
+        val views = buildViews(context)
+        for (widgetId in widgetIds) {
+            manager.updateAppWidget(widgetId, views)
+        }
+    }
+
+    companion object {
+        fun refresh(context: Context) {
+            val manager = AppWidgetManager.getInstance(context)
+            val widgetIds = manager.getAppWidgetIds(
+                ComponentName(context, HostWidgetProvider::class.java)
+            )
+            if (widgetIds.isEmpty()) return
+            val views = buildViews(context)
+            for (widgetId in widgetIds) {
+                manager.updateAppWidget(widgetId, views)
+            }
+        }
+
+        private fun buildViews(context: Context): RemoteViews {
+            val views = RemoteViews(context.packageName, R.layout.widget_host)
+            val host = LauncherShortcuts.firstHost(context)
+            if (host == null) {
+                views.setTextViewText(R.id.widget_host_name, "No saved workspaces")
+                views.setTextViewText(R.id.widget_host_status, "Pair a host in Zedra")
+                views.setViewVisibility(R.id.widget_connect, View.GONE)
+                val launch = context.packageManager.getLaunchIntentForPackage(context.packageName)
+                if (launch != null) {
+                    views.setOnClickPendingIntent(R.id.widget_root, pendingIntent(context, launch))
+                }
+                return views
+            }
+
+            val (statusLabel, statusColor) = when (host.optString("status")) {
+                "online" -> "Online" to R.color.app_accent
+                "connecting" -> "Connecting…" to R.color.app_text_secondary
+                else -> "Offline" to R.color.app_text_muted
+            }
+            val hostname = host.optString("hostname")
+            views.setTextViewText(R.id.widget_host_name, host.optString("label", "Workspace"))
+            views.setTextViewText(
+                R.id.widget_host_status,
+                if (hostname.isEmpty()) statusLabel else "$statusLabel · $hostname"
+            )
+            views.setTextColor(R.id.widget_host_status, ContextCompat.getColor(context, statusColor))
+            views.setViewVisibility(R.id.widget_connect, View.VISIBLE)
+            val connect = pendingIntent(
+                context,
+                LauncherShortcuts.viewIntent(context, host.getString("url"))
+            )
+            views.setOnClickPendingIntent(R.id.widget_connect, connect)
+            views.setOnClickPendingIntent(R.id.widget_root, connect)
+            return views
+        }
+
+        private fun pendingIntent(context: Context, intent: Intent): PendingIntent =
+            PendingIntent.getActivity(
+                context,
+                0,
+                intent.addFlags(Intent.FLAG_ACTIVITY_NEW_TASK),
+                PendingIntent.FLAG_UPDATE_CURRENT or PendingIntent.FLAG_IMMUTABLE
+            )
+    }
+}
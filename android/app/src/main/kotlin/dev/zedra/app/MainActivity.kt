@@ -4,6 +4,8 @@ import android.Manifest
 import android.app.Activity
 import android.app.NotificationChannel
 import android.app.NotificationManager
+import android.content.ClipData
+import android.content.ComponentCallbacks2
 import android.content.Context
 import android.content.res.Configuration
 import android.content.Intent
@@ -11,6 +13,9 @@ import android.content.pm.PackageManager
 import android.media.AudioAttributes
 import android.media.AudioManager
 import android.media.MediaPlayer
+import android.net.ConnectivityManager
+import android.net.Network
+import android.net.NetworkCapabilities
 import android.net.Uri
 import android.os.Build
 import android.os.Bundle
@@ -20,6 +25,7 @@ import android.os.VibratorManager
 import android.util.Log
 import org.json.JSONObject
 import java.io.File
+import android.view.DragEvent
 import android.view.Gravity
 import android.view.KeyEvent
 import android.view.View
@@ -58,6 +64,7 @@ class MainActivity : AppCompatActivity() {
     private lateinit var keyboardAccessoryBar: KeyboardAccessoryBar
     private var keyboardImeBottom = 0
     private var pendingDeltaPushTokenCallbackId: Int? = null
+    private var meteredNetworkCallback: ConnectivityManager.NetworkCallback? = null
     private val notificationPermissionLauncher =
         registerForActivityResult(ActivityResultContracts.RequestPermission()) { granted ->
             val callbackId = pendingDeltaPushTokenCallbackId ?: return@registerForActivityResult
@@ -98,23 +105,30 @@ class MainActivity : AppCompatActivity() {
         createDeltaNotificationChannel(this)
         bootstrap(this, APP_VERSION_VALUE, APP_BUILD_NUMBER_VALUE, OS_VERSION_VALUE, DEVICE_NAME_VALUE)
 
+        installMeteredNetworkCallback()
+
         runtime = GpuiRuntimeController(this)
         runtime.initialize()
 
         rootView = FrameLayout(this)
         surfaceView = runtime.attach(rootView)
         keyboardAccessoryBar = KeyboardAccessoryBar(this) { key ->
-            nativeKeyboardAccessoryKey(key)
+            if (key == PASTE_KEY) {
+                pasteClipboardText()
+            } else {
+                nativeKeyboardAccessoryKey(key)
+            }
         }
         rootView.addView(
             keyboardAccessoryBar,
             FrameLayout.LayoutParams(
                 FrameLayout.LayoutParams.MATCH_PARENT,
-                (44 * resources.displayMetrics.density).toInt(),
+                keyboardAccessoryHeightPx(resources.configuration),
                 Gravity.BOTTOM,
             ),
         )
         installKeyboardAccessoryInsets()
+        installTextDropTarget()
         rootView.viewTreeObserver.addOnPreDrawListener {
             updateKeyboardAccessoryVisibility()
             true
@@ -168,10 +182,27 @@ class MainActivity : AppCompatActivity() {
         runtime.onStop()
     }
 
+    override fun onTrimMemory(level: Int) {
+        super.onTrimMemory(level)
+        Log.i(TAG, "onTrimMemory level=$level")
+        nativeTrimMemory(level)
+    }
+
+    @Deprecated("Deprecated in Java")
+    override fun onLowMemory() {
+        super.onLowMemory()
+        Log.i(TAG, "onLowMemory")
+        nativeTrimMemory(ComponentCallbacks2.TRIM_MEMORY_COMPLETE)
+    }
+
     override fun onDestroy() {
         if (::keyboardAccessoryBar.isInitialized) {
             keyboardAccessoryBar.stopRepeating()
         }
+        meteredNetworkCallback?.let { callback ->
+            getSystemService(ConnectivityManager::class.java)?.unregisterNetworkCallback(callback)
+        }
+        meteredNetworkCallback = null
         NativePresentations.unregister()
         ProgressHud.clear()
         sSurfaceView = null
@@ -180,6 +211,23 @@ class MainActivity : AppCompatActivity() {
         super.onDestroy()
     }
 
+    /** Reports whether the default network is metered, for the data saver's Auto mode. */
+    private fun installMeteredNetworkCallback() {
+        val connectivity = getSystemService(ConnectivityManager::class.java) ?: return
+        nativeSetNetworkMetered(connectivity.isActiveNetworkMetered)
+        // Default-network callbacks need API 24; older devices keep the launch value.
+        if (Build.VERSION.SDK_INT < Build.VERSION_CODES.N) return
+        val callback = object : ConnectivityManager.NetworkCallback() {
+            override fun onCapabilitiesChanged(network: Network, capabilities: NetworkCapabilities) {
+                nativeSetNetworkMetered(
+                    !capabilities.hasCapability(NetworkCapabilities.NET_CAPABILITY_NOT_METERED),
+                )
+            }
+        }
+        connectivity.registerDefaultNetworkCallback(callback)
+        meteredNetworkCallback = callback
+    }
+
     private fun handleDeeplinkIntent(intent: Intent?) {
         if (intent == null || intent.action != Intent.ACTION_VIEW) return
         val uri = intent.data ?: return
@@ -289,6 +337,65 @@ class MainActivity : AppCompatActivity() {
         ViewCompat.requestApplyInsets(rootView)
     }
 
+    // Dropped and pasted text goes to Rust in one `nativeInsertText` call; through the
+    // IME it would arrive as one key event per character and stall the UI thread on
+    // large snippets.
+    private fun installTextDropTarget() {
+        surfaceView.setOnDragListener { _, event ->
+            when (event.action) {
+                DragEvent.ACTION_DRAG_STARTED ->
+                    event.clipDescription?.hasMimeType("text/*") == true
+                DragEvent.ACTION_DROP -> {
+                    val text = clipText(event.clipData)
+                    if (text.isNotEmpty()) {
+                        nativeInsertText(text)
+                    }
+                    true
+                }
+                else -> true
+            }
+        }
+    }
+
+    private fun pasteClipboardText() {
+        val clipboard = getSystemService(Context.CLIPBOARD_SERVICE) as android.content.ClipboardManager
+        val text = clipText(clipboard.primaryClip)
+        if (text.isNotEmpty()) {
+            nativeInsertText(text)
+        }
+    }
+
+    private fun clipText(clip: ClipData?): String {
+        if (clip == null) return ""
+        return (0 until clip.itemCount).joinToString("") { index ->
+            clip.getItemAt(index).coerceToText(this).toString()
+        }
+    }
+
+    override fun onConfigurationChanged(newConfig: Configuration) {
+        super.onConfigurationChanged(newConfig)
+        if (!::keyboardAccessoryBar.isInitialized) {
+            return
+        }
+        val params = keyboardAccessoryBar.layoutParams as FrameLayout.LayoutParams
+        val height = keyboardAccessoryHeightPx(newConfig)
+        if (params.height != height) {
+            params.height = height
+            keyboardAccessoryBar.layoutParams = params
+            updateKeyboardAccessoryVisibility()
+        }
+    }
+
+    // A phone in landscape has few rows to spare, so the key row shrinks there
+    // (matches `COMPACT_VIEWPORT_MAX_HEIGHT` on the Rust side).
+    private fun keyboardAccessoryHeightPx(config: Configuration): Int {
+        val compact =
+            config.orientation == Configuration.ORIENTATION_LANDSCAPE &&
+                config.screenHeightDp < COMPACT_SCREEN_MAX_HEIGHT_DP
+        val heightDp = if (compact) 32 else 44
+        return (heightDp * resources.displayMetrics.density).toInt()
+    }
+
     private fun updateKeyboardAccessoryVisibility() {
         val visible = keyboardImeBottom > 0 && nativeKeyboardAccessoryVisible()
         keyboardAccessoryBar.visibility = if (visible) View.VISIBLE else View.GONE
@@ -302,6 +409,8 @@ class MainActivity : AppCompatActivity() {
 
     companion object {
         private const val TAG = "MainActivity"
+        private const val PASTE_KEY = "paste"
+        private const val COMPACT_SCREEN_MAX_HEIGHT_DP = 500
 
         // Pre-compute strings exposed via JNI. Calling kotlin.text.StringsKt
         // (`.trim()` etc.) from a native-thread JNI invocation can recursively
@@ -400,10 +509,16 @@ class MainActivity : AppCompatActivity() {
 
         @JvmStatic external fun nativeKeyboardAccessoryVisible(): Boolean
 
+        @JvmStatic external fun nativeInsertText(text: String)
+
         @JvmStatic external fun nativeSystemBackPressed(): Boolean
 
         @JvmStatic external fun nativeSetAppForeground(foreground: Boolean)
 
+        @JvmStatic external fun nativeTrimMemory(level: Int)
+
+        @JvmStatic external fun nativeSetNetworkMetered(metered: Boolean)
+
         @JvmStatic external fun nativeDeltaPushTokenResult(
             callbackId: Int,
             provider: String,
@@ -451,6 +566,33 @@ class MainActivity : AppCompatActivity() {
             }
         }
 
+        @JvmStatic
+        fun fontScale(): Float {
+            val activity = sActivity ?: return 1f
+            return activity.resources.configuration.fontScale
+        }
+
+        @JvmStatic
+        fun vaultKey(): ByteArray? {
+            val activity = sActivity ?: return null
+            return try {
+                VaultKeystore.loadOrCreate(activity)
+            } catch (e: Exception) {
+                Log.e(TAG, "vault key unavailable", e)
+                null
+            }
+        }
+
+        @JvmStatic
+        fun deleteVaultKey() {
+            val activity = sActivity ?: return
+            try {
+                VaultKeystore.delete(activity)
+            } catch (e: Exception) {
+                Log.e(TAG, "vault key delete failed", e)
+            }
+        }
+
         @JvmStatic
         fun requestDeltaPushToken(callbackId: Int) {
             val activity = sActivity as? MainActivity ?: run {
@@ -577,6 +719,12 @@ class MainActivity : AppCompatActivity() {
             }
         }
 
+        @JvmStatic
+        fun publishLauncherShortcuts(shortcutsJson: String) {
+            val activity = sActivity ?: return
+            LauncherShortcuts.publish(activity.applicationContext, shortcutsJson)
+        }
+
         @JvmStatic
         fun showAlert(
             callbackId: Int,
@@ -629,8 +777,9 @@ class MainActivity : AppCompatActivity() {
             title: String?,
             placeholder: String?,
             initialValue: String?,
+            secure: Boolean,
         ) {
-            NativePresentations.showTextInput(callbackId, title, placeholder, initialValue)
+            NativePresentations.showTextInput(callbackId, title, placeholder, initialValue, secure)
         }
 
         @JvmStatic
@@ -460,10 +460,15 @@ object NativePresentations {
         title: String?,
         placeholder: String?,
         initialValue: String?,
+        secure: Boolean,
     ) = onUi {
         val input = EditText(requireActivity()).apply {
             setSingleLine(true)
-            inputType = InputType.TYPE_CLASS_TEXT or InputType.TYPE_TEXT_FLAG_CAP_SENTENCES
+            inputType = if (secure) {
+                InputType.TYPE_CLASS_TEXT or InputType.TYPE_TEXT_VARIATION_PASSWORD
+            } else {
+                InputType.TYPE_CLASS_TEXT or InputType.TYPE_TEXT_FLAG_CAP_SENTENCES
+            }
             setText(initialValue.orEmpty())
             setSelection(text?.length ?: 0)
             setTextColor(nativeTheme.textPrimary)
@@ -0,0 +1,84 @@
+package dev.zedra.app
+
+import android.content.Context
+import android.security.keystore.KeyGenParameterSpec
+import android.security.keystore.KeyProperties
+import java.io.File
+import java.security.KeyStore
+import java.security.SecureRandom
+import javax.crypto.Cipher
+import javax.crypto.KeyGenerator
+import javax.crypto.SecretKey
+import javax.crypto.spec.GCMParameterSpec
+
+/**
+ * Holds the 32-byte key that seals the Rust secrets vault (crates/zedra/src/vault.rs).
+ * The key is stored at `<filesDir>/zedra/vault.key` wrapped by a non-exportable
+ * AES key in the Android Keystore, so the file alone does not open the vault.
+ */
+object VaultKeystore {
+    private const val KEYSTORE = "AndroidKeyStore"
+    private const val ALIAS = "zedra_vault"
+    private const val TRANSFORMATION = "AES/GCM/NoPadding"
+    private const val IV_LENGTH = 12
+    private const val TAG_BITS = 128
+    private const val KEY_LENGTH = 32
+
+    private fun keyFile(context: Context): File = File(File(context.filesDir, "zedra"), "vault.key")
+
+    private fun wrappingKey(): SecretKey {
+        val keyStore = KeyStore.getInstance(KEYSTORE).apply { load(null) }
+        (keyStore.getKey(ALIAS, null) as? SecretKey)?.let { return it }
+        val generator = KeyGenerator.getInstance(KeyProperties.KEY_ALGORITHM_AES, KEYSTORE)
+        generator.init(
+            KeyGenParameterSpec.Builder(
+                ALIAS,
+                KeyProperties.PURPOSE_ENCRYPT or KeyProperties.PURPOSE_DECRYPT
+            )
+                .setBlockModes(KeyProperties.BLOCK_MODE_GCM)
+                .setEncryptionPaddings(KeyProperties.ENCRYPTION_PADDING_NONE)
+                .setKeySize(256)
+                .build()
+        )
+        return generator.generateKey()
+    }
+
+    /** Unwrap the vault key, creating and wrapping a new one on first use. */
+    @Synchronized
+    fun loadOrCreate(context: Context): ByteArray {
+        val file = keyFile(context)
+        val wrapping = wrappingKey()
+        if (file.exists()) {
+            val stored = file.readBytes()
+            val cipher = Cipher.getInstance(TRANSFORMATION)
+            cipher.init(
+                Cipher.DECRYPT_MODE,
+                wrapping,
+                GCMParameterSpec(TAG_BITS, stored, 0, IV_LENGTH)
+            )
+            return cipher.doFinal(stored, IV_LENGTH, stored.size - IV_LENGTH)
+        }
+        val key = ByteArray(KEY_LENGTH).also { SecureRandom().nextBytes(it) }
+        val cipher = Cipher.getInstance(TRANSFORMATION)
+        cipher.init(Cipher.ENCRYPT_MODE, wrapping)
+        val wrapped = cipher.iv + cipher.doFinal(key)
+        file.parentFile?.mkdirs()
+        val tmp = File(file.parentFile, "vault.key.tmp")
+        tmp.writeBytes(wrapped)
+        if (!tmp.renameTo(file)) {
+            tmp.delete()
+            throw IllegalStateException("could not store the vault key")
+        }
+        return key
+    }
+
+    /** Remove the wrapped key and the Keystore key that could unwrap it. */
+    @Synchronized
+    fun delete(context: Context) {
+        keyFile(context).delete()
+        val keyStore = KeyStore.getInstance(KEYSTORE).apply { load(null) }
+        if (keyStore.containsAlias(ALIAS)) {
+            keyStore.deleteEntry(ALIAS)
+        }
+    }
+}
@@ -0,0 +1,530 @@
+// Host-side AI prompt execution for the `AiPromptV2` RPC.
+//
+// The backend is chosen by `~/.config/zedra/ai.json` (`AiProviderConfig`): the
+// Claude Code CLI (default), an OpenAI-compatible chat endpoint, or a local Ollama.
+// Claude Code runs in print mode with `--permission-mode default`, so any tool use
+// outside the client allowlist is denied and returned as an `AiPermissionRequest`;
+// the device approves by resending with the tool allowlisted and the session resumed.
+// HTTP providers are chat-only and never run tools.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use zedra_rpc::proto::{
+    AiConversationMessage, AiPermissionRequest, AiPromptReqV2, AiPromptResultV2, AiProviderInfo,
+};
+
+use crate::identity;
+
+const CONFIG_FILE: &str = "ai.json";
+const HTTP_TIMEOUT: Duration = Duration::from_secs(120);
+const OLLAMA_DEFAULT_URL: &str = "http://127.0.0.1:11434";
+
+/// Host AI backend, loaded from `ai.json` in the host config dir.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "provider", rename_all = "kebab-case")]
+pub enum AiProviderConfig {
+    /// Claude Code CLI (`ZEDRA_CLAUDE_BIN` or `claude` on `$PATH`).
+    #[default]
+    ClaudeCli,
+    /// Any OpenAI-compatible `/chat/completions` endpoint.
+    Openai {
+        /// API root including the version segment, e.g. `https://api.openai.com/v1`.
+        base_url: String,
+        model: String,
+        /// Name of the environment variable holding the API key; keys never live in the file.
+        #[serde(default)]
+        api_key_env: Option<String>,
+    },
+    /// Local Ollama server (`/api/chat`).
+    Ollama {
+        #[serde(default = "ollama_default_url")]
+        base_url: String,
+        model: String,
+    },
+}
+
+fn ollama_default_url() -> String {
+    OLLAMA_DEFAULT_URL.to_string()
+}
+
+impl AiProviderConfig {
+    /// Stable provider name advertised to clients.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::ClaudeCli => "claude-cli",
+            Self::Openai { .. } => "openai",
+            Self::Ollama { .. } => "ollama",
+        }
+    }
+
+    pub fn model(&self) -> Option<&str> {
+        match self {
+            Self::ClaudeCli => None,
+            Self::Openai { model, .. } | Self::Ollama { model, .. } => Some(model.as_str()),
+        }
+    }
+
+    /// Only the Claude Code CLI runs tools (and so can ask for permission).
+    pub fn supports_tools(&self) -> bool {
+        matches!(self, Self::ClaudeCli)
+    }
+
+    pub fn info(&self) -> AiProviderInfo {
+        AiProviderInfo {
+            kind: self.kind().to_string(),
+            model: self.model().map(str::to_string),
+            supports_tools: self.supports_tools(),
+            error: None,
+        }
+    }
+}
+
+pub fn config_path() -> Result<PathBuf> {
+    Ok(identity::host_config_dir()?.join(CONFIG_FILE))
+}
+
+/// Provider summary for `SyncSessionResult`; config errors are reported, not hidden.
+pub fn provider_info() -> AiProviderInfo {
+    match load_provider_config() {
+        Ok(provider) => provider.info(),
+        Err(e) => AiProviderInfo {
+            error: Some(format!("{e:#}")),
+            ..Default::default()
+        },
+    }
+}
+
+/// Load the provider config; a missing file selects the Claude Code CLI.
+pub fn load_provider_config() -> Result<AiProviderConfig> {
+    load_provider_config_at(&config_path()?)
+}
+
+fn load_provider_config_at(path: &Path) -> Result<AiProviderConfig> {
+    match std::fs::read_to_string(path) {
+        Ok(json) => serde_json::from_str(&json)
+            .with_context(|| format!("failed to parse AI provider config {}", path.display())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(AiProviderConfig::default()),
+        Err(e) => Err(e).with_context(|| format!("failed to read {}", path.display())),
+    }
+}
+
+/// Resolve the Claude binary. Prefer an explicit absolute path from the environment
+/// to avoid executing a malicious `claude` that might appear earlier in `$PATH`.
+pub fn claude_bin() -> String {
+    std::env::var("ZEDRA_CLAUDE_BIN").unwrap_or_else(|_| "claude".to_string())
+}
+
+/// Subset of `claude --print --output-format json` output the host reads.
+#[derive(Debug, Deserialize)]
+struct ClaudePrintOutput {
+    #[serde(default)]
+    result: String,
+    #[serde(default)]
+    is_error: bool,
+    session_id: Option<String>,
+    #[serde(default)]
+    permission_denials: Vec<ClaudePermissionDenial>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClaudePermissionDenial {
+    tool_name: String,
+    #[serde(default)]
+    tool_input: serde_json::Value,
+}
+
+/// Build the Claude Code argument list for one print-mode prompt.
+fn claude_args(req: &AiPromptReqV2) -> Vec<String> {
+    let mut args = vec![
+        "--print".to_string(),
+        "--output-format".to_string(),
+        "json".to_string(),
+        // Print mode cannot ask, so "default" denies anything not allowlisted.
+        "--permission-mode".to_string(),
+        "default".to_string(),
+    ];
+    let allowed: Vec<&str> = req
+        .allowed_tools
+        .iter()
+        .map(|tool| tool.trim())
+        .filter(|tool| !tool.is_empty())
+        .collect();
+    if !allowed.is_empty() {
+        args.push("--allowedTools".to_string());
+        args.push(allowed.join(","));
+    }
+    if let Some(session_id) = req.resume_session_id.as_deref() {
+        args.push("--resume".to_string());
+        args.push(session_id.to_string());
+    }
+    let prompt = chat_prompt(req);
+    // `--` keeps a prompt that starts with `-` from being parsed as a flag.
+    args.push("--".to_string());
+    args.push(prompt);
+    args
+}
+
+fn parse_claude_output(stdout: &[u8]) -> AiPromptResultV2 {
+    match serde_json::from_slice::<ClaudePrintOutput>(stdout) {
+        Ok(output) => AiPromptResultV2 {
+            error: output.is_error.then(|| output.result.clone()),
+            text: output.result,
+            session_id: output.session_id,
+            permission_requests: output
+                .permission_denials
+                .into_iter()
+                .map(|denial| AiPermissionRequest {
+                    tool_name: denial.tool_name,
+                    tool_input: denial.tool_input.to_string(),
+                })
+                .collect(),
+        },
+        Err(e) => AiPromptResultV2 {
+            error: Some(format!("unexpected Claude Code output: {e}")),
+            ..Default::default()
+        },
+    }
+}
+
+/// User message text sent to chat providers.
+fn chat_prompt(req: &AiPromptReqV2) -> String {
+    match req.context.as_deref() {
+        Some(context) if !context.trim().is_empty() => format!("{context}\n\n{}", req.prompt),
+        _ => req.prompt.clone(),
+    }
+}
+
+#[derive(Serialize)]
+struct ChatMessage<'a> {
+    role: &'a str,
+    content: &'a str,
+}
+
+/// Prior turns followed by the new user prompt, in chat-completions shape.
+fn chat_messages<'a>(
+    history: &'a [AiConversationMessage],
+    prompt: &'a str,
+) -> Vec<ChatMessage<'a>> {
+    history
+        .iter()
+        .map(|turn| ChatMessage {
+            role: &turn.role,
+            content: &turn.text,
+        })
+        .chain(std::iter::once(ChatMessage {
+            role: "user",
+            content: prompt,
+        }))
+        .collect()
+}
+
+#[derive(Serialize)]
+struct ChatRequest<'a> {
+    model: &'a str,
+    messages: Vec<ChatMessage<'a>>,
+    stream: bool,
+}
+
+#[derive(Deserialize)]
+struct OpenAiChatResponse {
+    choices: Vec<OpenAiChoice>,
+}
+
+#[derive(Deserialize)]
+struct OpenAiChoice {
+    message: OpenAiMessage,
+}
+
+#[derive(Deserialize)]
+struct OpenAiMessage {
+    #[serde(default)]
+    content: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct OllamaChatResponse {
+    message: OpenAiMessage,
+}
+
+fn http_client() -> Result<reqwest::blocking::Client> {
+    reqwest::blocking::Client::builder()
+        .timeout(HTTP_TIMEOUT)
+        .build()
+        .context("failed to build AI HTTP client")
+}
+
+fn run_openai(
+    base_url: &str,
+    model: &str,
+    api_key_env: Option<&str>,
+    req: &AiPromptReqV2,
+    history: &[AiConversationMessage],
+) -> Result<String> {
+    let prompt = chat_prompt(req);
+    let url = format!("{}/chat/completions", base_url.trim_end_matches('/'));
+    let mut request = http_client()?.post(&url).json(&ChatRequest {
+        model,
+        messages: chat_messages(history, &prompt),
+        stream: false,
+    });
+    if let Some(name) = api_key_env {
+        let key = std::env::var(name).with_context(|| format!("{name} is not set on the host"))?;
+        request = request.bearer_auth(key);
+    }
+    let response: OpenAiChatResponse = request
+        .send()
+        .context("AI provider request failed")?
+        .error_for_status()
+        .context("AI provider returned an error")?
+        .json()
+        .context("failed to parse AI provider response")?;
+    Ok(response
+        .choices
+        .into_iter()
+        .next()
+        .and_then(|choice| choice.message.content)
+        .unwrap_or_default())
+}
+
+fn run_ollama(
+    base_url: &str,
+    model: &str,
+    req: &AiPromptReqV2,
+    history: &[AiConversationMessage],
+) -> Result<String> {
+    let prompt = chat_prompt(req);
+    let url = format!("{}/api/chat", base_url.trim_end_matches('/'));
+    let response: OllamaChatResponse = http_client()?
+        .post(&url)
+        .json(&ChatRequest {
+            model,
+            messages: chat_messages(history, &prompt),
+            stream: false,
+        })
+        .send()
+        .context("Ollama request failed")?
+        .error_for_status()
+        .context("Ollama returned an error")?
+        .json()
+        .context("failed to parse Ollama response")?;
+    Ok(response.message.content.unwrap_or_default())
+}
+
+/// Run one prompt to completion with the configured provider. Blocking; call
+/// from `spawn_blocking`. `history` is replayed to chat providers; Claude Code
+/// keeps its own history and resumes via `req.resume_session_id`.
+pub fn run_prompt(
+    workdir: &Path,
+    req: &AiPromptReqV2,
+    history: &[AiConversationMessage],
+) -> AiPromptResultV2 {
+    let provider = match load_provider_config() {
+        Ok(provider) => provider,
+        Err(e) => {
+            return AiPromptResultV2 {
+                error: Some(format!("{e:#}")),
+                ..Default::default()
+            };
+        }
+    };
+    let text = match &provider {
+        AiProviderConfig::ClaudeCli => return run_claude(workdir, req),
+        AiProviderConfig::Openai {
+            base_url,
+            model,
+            api_key_env,
+        } => run_openai(base_url, model, api_key_env.as_deref(), req, history),
+        AiProviderConfig::Ollama { base_url, model } => run_ollama(base_url, model, req, history),
+    };
+    match text {
+        Ok(text) => AiPromptResultV2 {
+            text,
+            ..Default::default()
+        },
+        Err(e) => {
+            tracing::warn!("ai: {} prompt failed: {:#}", provider.kind(), e);
+            AiPromptResultV2 {
+                error: Some(format!("{e:#}")),
+                ..Default::default()
+            }
+        }
+    }
+}
+
+/// Reply text for the legacy `AiPrompt` RPC, which has no error or permission
+/// fields: errors become `Error: …` text, and denied tool uses are listed
+/// after the reply so the user knows what did not run.
+pub fn legacy_prompt_text(result: AiPromptResultV2) -> String {
+    if let Some(error) = result.error {
+        return format!("Error: {error}");
+    }
+    let mut text = result.text;
+    if !result.permission_requests.is_empty() {
+        let tools: Vec<&str> = result
+            .permission_requests
+            .iter()
+            .map(|request| request.tool_name.as_str())
+            .collect();
+        text.push_str(&format!(
+            "\n\n[Not run without approval: {}. Update the app to approve tool use.]",
+            tools.join(", ")
+        ));
+    }
+    text
+}
+
+fn run_claude(workdir: &Path, req: &AiPromptReqV2) -> AiPromptResultV2 {
+    let output = std::process::Command::new(claude_bin())
+        .args(claude_args(req))
+        .current_dir(workdir)
+        .output();
+    match output {
+        Ok(out) if out.status.success() => parse_claude_output(&out.stdout),
+        Ok(out) => {
+            // `is_error` results still print JSON on stdout; prefer them over stderr.
+            let parsed = parse_claude_output(&out.stdout);
+            if parsed.error.is_some() && parsed.session_id.is_some() {
+                return parsed;
+            }
+            AiPromptResultV2 {
+                error: Some(String::from_utf8_lossy(&out.stderr).trim().to_string()),
+                ..Default::default()
+            }
+        }
+        Err(e) => AiPromptResultV2 {
+            error: Some(format!(
+                "Claude Code not found on host. Install with: npm i -g @anthropic-ai/claude-code ({e})"
+            )),
+            ..Default::default()
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn req(prompt: &str) -> AiPromptReqV2 {
+        AiPromptReqV2 {
+            prompt: prompt.to_string(),
+            context: None,
+            allowed_tools: vec![],
+            resume_session_id: None,
+            conversation_id: None,
+        }
+    }
+
+    #[test]
+    fn args_deny_unlisted_tools_and_keep_prompt_last() {
+        let args = claude_args(&AiPromptReqV2 {
+            allowed_tools: vec![
+                "Read".to_string(),
+                " ".to_string(),
+                "Bash(git diff:*)".to_string(),
+            ],
+            resume_session_id: Some("abc".to_string()),
+            ..req("-rf everything")
+        });
+        assert_eq!(
+            args,
+            vec![
+                "--print",
+                "--output-format",
+                "json",
+                "--permission-mode",
+                "default",
+                "--allowedTools",
+                "Read,Bash(git diff:*)",
+                "--resume",
+                "abc",
+                "--",
+                "-rf everything",
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_permission_denials() {
+        let stdout = br#"{
+            "type": "result",
+            "is_error": false,
+            "result": "I need to run the tests.",
+            "session_id": "sess-1",
+            "permission_denials": [
+                {"tool_name": "Bash", "tool_use_id": "t1", "tool_input": {"command": "cargo test"}}
+            ]
+        }"#;
+        let result = parse_claude_output(stdout);
+        assert_eq!(result.error, None);
+        assert_eq!(result.session_id.as_deref(), Some("sess-1"));
+        assert_eq!(result.permission_requests.len(), 1);
+        assert_eq!(result.permission_requests[0].tool_name, "Bash");
+        assert_eq!(
+            result.permission_requests[0].tool_input,
+            r#"{"command":"cargo test"}"#
+        );
+    }
+
+    #[test]
+    fn provider_config_defaults_to_claude_cli() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CONFIG_FILE);
+        assert_eq!(
+            load_provider_config_at(&path).unwrap(),
+            AiProviderConfig::ClaudeCli
+        );
+
+        std::fs::write(&path, r#"{"provider":"ollama","model":"qwen2.5-coder"}"#).unwrap();
+        let provider = load_provider_config_at(&path).unwrap();
+        assert_eq!(
+            provider,
+            AiProviderConfig::Ollama {
+                base_url: OLLAMA_DEFAULT_URL.to_string(),
+                model: "qwen2.5-coder".to_string(),
+            }
+        );
+        assert_eq!(provider.kind(), "ollama");
+        assert!(!provider.supports_tools());
+    }
+
+    #[test]
+    fn openai_provider_config_parses() {
+        let provider: AiProviderConfig = serde_json::from_str(
+            r#"{"provider":"openai","base_url":"https://api.example.com/v1","model":"gpt-4o","api_key_env":"OPENAI_API_KEY"}"#,
+        )
+        .unwrap();
+        assert_eq!(provider.kind(), "openai");
+        assert_eq!(provider.model(), Some("gpt-4o"));
+    }
+
+    #[test]
+    fn unparseable_output_is_an_error() {
+        let result = parse_claude_output(b"not json");
+        assert!(result.error.is_some());
+        assert!(result.permission_requests.is_empty());
+    }
+
+    #[test]
+    fn legacy_text_reports_errors_and_denied_tools() {
+        let denied = AiPromptResultV2 {
+            text: "I need to run the tests.".to_string(),
+            permission_requests: vec![AiPermissionRequest {
+                tool_name: "Bash".to_string(),
+                tool_input: r#"{"command":"cargo test"}"#.to_string(),
+            }],
+            ..Default::default()
+        };
+        assert_eq!(
+            legacy_prompt_text(denied),
+            "I need to run the tests.\n\n[Not run without approval: Bash. Update the app to approve tool use.]"
+        );
+        let failed = AiPromptResultV2 {
+            error: Some("Claude Code not found".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(legacy_prompt_text(failed), "Error: Claude Code not found");
+    }
+}
@@ -0,0 +1,276 @@
+// Persistent AI conversations for `AiPromptV2` and the `AiConversation*` RPCs.
+//
+// One JSON file per conversation under `<workspace config dir>/ai_conversations/`,
+// so chats survive app restarts and any device paired to the workspace can resume
+// them. Ids are host-generated UUIDs; client-supplied ids are parsed as UUIDs before
+// touching the filesystem.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+use zedra_rpc::proto::{
+    AiConversation, AiConversationMessage, AiConversationSummary, AiPromptReqV2, AiPromptResultV2,
+};
+
+use crate::{ai, identity, utils};
+
+pub const CONVERSATIONS_DIR: &str = "ai_conversations";
+/// Titles are the first prompt line, cut to this many characters.
+const TITLE_MAX_CHARS: usize = 60;
+
+/// On-disk record; the wire `AiConversation` plus the Claude Code session to resume.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredConversation {
+    id: String,
+    title: String,
+    created_at: i64,
+    updated_at: i64,
+    #[serde(default)]
+    claude_session_id: Option<String>,
+    messages: Vec<StoredMessage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredMessage {
+    role: String,
+    text: String,
+    timestamp: i64,
+}
+
+impl StoredConversation {
+    fn summary(&self) -> AiConversationSummary {
+        AiConversationSummary {
+            id: self.id.clone(),
+            title: self.title.clone(),
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+            message_count: self.messages.len() as u32,
+        }
+    }
+
+    fn messages(&self) -> Vec<AiConversationMessage> {
+        self.messages
+            .iter()
+            .map(|m| AiConversationMessage {
+                role: m.role.clone(),
+                text: m.text.clone(),
+                timestamp: m.timestamp,
+            })
+            .collect()
+    }
+
+    fn into_wire(self) -> AiConversation {
+        AiConversation {
+            messages: self.messages(),
+            id: self.id,
+            title: self.title,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+fn now_secs() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+/// First non-empty prompt line, truncated for list display.
+fn title_from_prompt(prompt: &str) -> String {
+    let line = prompt
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or("New conversation");
+    utils::truncate_chars(line, TITLE_MAX_CHARS)
+}
+
+/// Conversation files for one workspace.
+pub struct ConversationStore {
+    dir: PathBuf,
+}
+
+impl ConversationStore {
+    pub fn for_workdir(workdir: &Path) -> Result<Self> {
+        Ok(Self::new(
+            identity::workspace_config_dir(workdir)?.join(CONVERSATIONS_DIR),
+        ))
+    }
+
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    fn path_for(&self, id: &str) -> Result<PathBuf> {
+        let id = Uuid::parse_str(id).with_context(|| format!("invalid conversation id: {id:?}"))?;
+        Ok(self.dir.join(format!("{id}.json")))
+    }
+
+    fn load(&self, id: &str) -> Result<StoredConversation> {
+        let path = self.path_for(id)?;
+        let json = std::fs::read_to_string(&path)
+            .with_context(|| format!("conversation not found: {id}"))?;
+        serde_json::from_str(&json).with_context(|| format!("corrupt conversation {id}"))
+    }
+
+    fn save(&self, conversation: &StoredConversation) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)
+            .with_context(|| format!("failed to create {}", self.dir.display()))?;
+        let path = self.path_for(&conversation.id)?;
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_vec_pretty(conversation)?)
+            .with_context(|| format!("failed to write {}", tmp.display()))?;
+        std::fs::rename(&tmp, &path)
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        Ok(())
+    }
+
+    /// Summaries, most recently updated first. Unreadable files are skipped.
+    pub fn list(&self) -> Result<Vec<AiConversationSummary>> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(e).with_context(|| format!("failed to read {}", self.dir.display()));
+            }
+        };
+        let mut summaries: Vec<AiConversationSummary> = entries
+            .flatten()
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|entry| {
+                let json = std::fs::read_to_string(entry.path()).ok()?;
+                let conversation: StoredConversation = serde_json::from_str(&json).ok()?;
+                Some(conversation.summary())
+            })
+            .collect();
+        summaries.sort_by(|a, b| b.updated_at.cmp(&a.updated_at).then(b.id.cmp(&a.id)));
+        Ok(summaries)
+    }
+
+    pub fn get(&self, id: &str) -> Result<AiConversation> {
+        Ok(self.load(id)?.into_wire())
+    }
+
+    pub fn delete(&self, id: &str) -> Result<()> {
+        let path = self.path_for(id)?;
+        std::fs::remove_file(&path).with_context(|| format!("conversation not found: {id}"))
+    }
+
+    /// Run `req` inside its conversation (creating one when `conversation_id` is
+    /// `None`) and persist both turns. Blocking; call from `spawn_blocking`.
+    pub fn prompt(&self, workdir: &Path, mut req: AiPromptReqV2) -> AiPromptResultV2 {
+        let now = now_secs();
+        let mut conversation = match req.conversation_id.as_deref() {
+            Some(id) => match self.load(id) {
+                Ok(conversation) => conversation,
+                Err(e) => {
+                    return AiPromptResultV2 {
+                        error: Some(format!("{e:#}")),
+                        ..Default::default()
+                    };
+                }
+            },
+            None => StoredConversation {
+                id: Uuid::new_v4().to_string(),
+                title: title_from_prompt(&req.prompt),
+                created_at: now,
+                updated_at: now,
+                claude_session_id: None,
+                messages: Vec::new(),
+            },
+        };
+        if req.resume_session_id.is_none() {
+            req.resume_session_id = conversation.claude_session_id.clone();
+        }
+
+        let mut result = ai::run_prompt(workdir, &req, &conversation.messages());
+        if result.error.is_some() {
+            // Nothing is stored for a failed turn; a new conversation stays unsaved.
+            result.conversation_id = req.conversation_id;
+            return result;
+        }
+        result.conversation_id = Some(conversation.id.clone());
+
+        conversation.messages.push(StoredMessage {
+            role: "user".to_string(),
+            text: req.prompt,
+            timestamp: now,
+        });
+        conversation.messages.push(StoredMessage {
+            role: "assistant".to_string(),
+            text: result.text.clone(),
+            timestamp: now_secs(),
+        });
+        if result.session_id.is_some() {
+            conversation.claude_session_id = result.session_id.clone();
+        }
+        conversation.updated_at = now_secs();
+        if let Err(e) = self.save(&conversation) {
+            tracing::warn!("ai: failed to persist conversation: {:#}", e);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stored(id: &str, updated_at: i64) -> StoredConversation {
+        StoredConversation {
+            id: id.to_string(),
+            title: "Fix the build".to_string(),
+            created_at: 1,
+            updated_at,
+            claude_session_id: Some("sess-1".to_string()),
+            messages: vec![StoredMessage {
+                role: "user".to_string(),
+                text: "Fix the build".to_string(),
+                timestamp: 1,
+            }],
+        }
+    }
+
+    #[test]
+    fn list_get_delete_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ConversationStore::new(dir.path().to_path_buf());
+        let older = Uuid::new_v4().to_string();
+        let newer = Uuid::new_v4().to_string();
+        store.save(&stored(&older, 10)).unwrap();
+        store.save(&stored(&newer, 20)).unwrap();
+
+        let ids: Vec<String> = store.list().unwrap().into_iter().map(|c| c.id).collect();
+        assert_eq!(ids, vec![newer.clone(), older.clone()]);
+
+        let conversation = store.get(&older).unwrap();
+        assert_eq!(conversation.title, "Fix the build");
+        assert_eq!(conversation.messages.len(), 1);
+
+        store.delete(&older).unwrap();
+        assert!(store.get(&older).is_err());
+        assert_eq!(store.list().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn rejects_non_uuid_ids() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ConversationStore::new(dir.path().to_path_buf());
+        assert!(store.get("../../identity").is_err());
+        assert!(store.delete("../sessions").is_err());
+    }
+
+    #[test]
+    fn title_uses_first_nonempty_line() {
+        assert_eq!(
+            title_from_prompt("\n  Fix flaky test\nmore"),
+            "Fix flaky test"
+        );
+        assert_eq!(title_from_prompt("   "), "New conversation");
+        // `truncate_chars` appends an ellipsis after the cut.
+        assert_eq!(
+            title_from_prompt(&"x".repeat(100)).chars().count(),
+            TITLE_MAX_CHARS + 1
+        );
+    }
+}
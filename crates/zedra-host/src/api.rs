@@ -16,6 +16,7 @@
 //   POST /api/agents/:kind/resume — resume an agent session in a new terminal
 //   GET  /api/agent-hooks/events — list recent hook events for CLI testing
 //   POST /api/agent-hooks/:kind   — ingest a local agent hook event
+//   POST /api/git-credential      — answer `zedra git-credential` for a `GitPush`
 //
 // Auth: every request must carry  Authorization: Bearer <token>
 //       where <token> is the contents of  <config_dir>/api-token
@@ -33,6 +34,7 @@ use tokio::net::TcpListener;
 use crate::agent;
 use crate::agent::hook::HookContext;
 use crate::agent::utils::payload_string;
+use crate::git_push::{answer_helper, CredentialFields};
 use crate::metrics;
 use crate::pty::SpawnOptions;
 use crate::qr;
@@ -84,6 +86,8 @@ struct StatusTerminal {
     created_at_unix_secs: u64,
     created_at_elapsed_secs: u64,
     uptime_secs: u64,
+    /// Hex public key of the device that created the terminal.
+    owner: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -147,6 +151,7 @@ async fn status(State(s): State<ApiState>, headers: HeaderMap) -> impl IntoRespo
                 created_at_unix_secs: terminal.created_at_unix_secs,
                 created_at_elapsed_secs: terminal.created_at_elapsed_secs,
                 uptime_secs: terminal.uptime_secs,
+                owner: terminal.owner.map(hex::encode),
             })
             .collect();
         all_terminals.extend(terminals.clone());
@@ -319,7 +324,7 @@ async fn create_terminal_handler(
         env: Vec::new(),
     };
 
-    match create_terminal(&session, req.cols, req.rows, opts).await {
+    match create_terminal(&session, req.cols, req.rows, opts, None).await {
         Ok(id) => {
             let terminal_count = session.terminals.lock().await.len();
             if let Err(e) =
@@ -528,6 +533,7 @@ async fn resume_agent_handler(
             color_scheme: None,
             env: Vec::new(),
         },
+        None,
     )
     .await
     {
@@ -630,6 +636,41 @@ async fn receive_agent_hook_handler(
     Json(serde_json::json!({"ok": true})).into_response()
 }
 
+#[derive(Debug, Deserialize)]
+pub struct GitCredentialReq {
+    pub session_id: String,
+    /// Git's helper operation: `get`, `store`, or `erase`.
+    pub operation: String,
+    pub credential: CredentialFields,
+}
+
+/// Blocks on `get` until the device answers, so git's push waits with it.
+async fn git_credential_handler(
+    State(s): State<ApiState>,
+    headers: HeaderMap,
+    Json(req): Json<GitCredentialReq>,
+) -> impl IntoResponse {
+    if !verify_token(&headers, &s.token) {
+        return unauthorized();
+    }
+    let session = s.registry.get(&req.session_id).await;
+    match answer_helper(
+        &s.daemon_state.git_credentials,
+        session.as_deref(),
+        &req.operation,
+        req.credential,
+    )
+    .await
+    {
+        Ok(credential) => Json(serde_json::json!(credential)).into_response(),
+        Err(e) => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(serde_json::json!({"error": e.to_string()})),
+        )
+            .into_response(),
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Server startup
 // ---------------------------------------------------------------------------
@@ -652,6 +693,7 @@ pub async fn start(state: ApiState) -> anyhow::Result<std::net::SocketAddr> {
         )
         .route("/api/agents/:kind/resume", post(resume_agent_handler))
         .route("/api/agent-hooks/:kind", post(receive_agent_hook_handler))
+        .route("/api/git-credential", post(git_credential_handler))
         .with_state(state);
 
     let listener = TcpListener::bind("127.0.0.1:0").await?;
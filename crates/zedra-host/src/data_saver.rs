@@ -0,0 +1,128 @@
+// Reduced-bandwidth behavior for `SessionDataSaver` and `FsReadHead`.
+//
+// A session with data saver on holds bulk terminal output for a short window
+// so a burst leaves as one larger frame, and a frame that is still too large
+// keeps only its last lines behind a "N lines skipped" notice. Keystroke echo
+// and other small chunks are sent at once. The backlog keeps the full output,
+// so skipped lines are only missing from this client's screen. `read_head`
+// serves the start of a file for previews instead of the whole file.
+
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+use std::time::Duration;
+
+use zedra_rpc::proto::{FsReadHeadResult, FS_READ_HEAD_MAX};
+
+/// How long bulk output is held so following reads join the same frame.
+pub const BATCH_WINDOW: Duration = Duration::from_millis(250);
+/// Chunks below this are sent immediately, so typing stays responsive.
+pub const BATCH_MIN_BYTES: usize = 1024;
+/// Frames above this keep only their trailing lines.
+pub const FRAME_BYTES: usize = 16 * 1024;
+
+/// Mode switches a cut could drop, leaving the client on the wrong screen.
+const SCREEN_SWITCHES: [&[u8]; 3] = [b"\x1b[?1049", b"\x1b[?1047", b"\x1b[?47"];
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack
+        .windows(needle.len())
+        .any(|window| window == needle)
+}
+
+/// Replace the start of `data` with a skipped-lines notice so at most about
+/// `max_bytes` of whole trailing lines remain. `None` leaves the frame as is:
+/// it is small enough, has no line break to cut at, or switches screens.
+pub fn summarize(data: &[u8], max_bytes: usize) -> Option<Vec<u8>> {
+    if data.len() <= max_bytes {
+        return None;
+    }
+    if SCREEN_SWITCHES.iter().any(|switch| contains(data, switch)) {
+        return None;
+    }
+    let cut_from = data.len() - max_bytes;
+    let newline = cut_from + data[cut_from..].iter().position(|&b| b == b'\n')?;
+    let skipped = data[..=newline].iter().filter(|&&b| b == b'\n').count();
+    let mut out =
+        format!("\r\x1b[0m\x1b[2m… {skipped} lines skipped (data saver)\x1b[0m\r\n").into_bytes();
+    out.extend_from_slice(&data[newline + 1..]);
+    Some(out)
+}
+
+/// Up to `max_bytes` (clamped to `FS_READ_HEAD_MAX`) from the start of the
+/// file at `path`, cut after the last whole line when the file is longer.
+pub fn read_head(path: &Path, max_bytes: u64) -> io::Result<FsReadHeadResult> {
+    let file = File::open(path)?;
+    let total_bytes = file.metadata()?.len();
+    let limit = max_bytes.min(FS_READ_HEAD_MAX);
+    let mut buf = Vec::new();
+    file.take(limit).read_to_end(&mut buf)?;
+    let truncated = total_bytes > buf.len() as u64;
+    if truncated {
+        match buf.iter().rposition(|&b| b == b'\n') {
+            Some(newline) => buf.truncate(newline + 1),
+            // One long line: keep what decodes, up to the cut.
+            None => {
+                let valid = match std::str::from_utf8(&buf) {
+                    Ok(_) => buf.len(),
+                    Err(e) => e.valid_up_to(),
+                };
+                buf.truncate(valid);
+            }
+        }
+    }
+    let content = String::from_utf8(buf).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            "stream did not contain valid UTF-8",
+        )
+    })?;
+    Ok(FsReadHeadResult {
+        content,
+        truncated,
+        total_bytes,
+        error: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summarize_keeps_trailing_lines_behind_a_notice() {
+        let data: Vec<u8> = (0..100)
+            .flat_map(|i| format!("line {i:03}\r\n").into_bytes())
+            .collect();
+        assert_eq!(summarize(&data, data.len()), None);
+
+        let out = summarize(&data, 30).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains("… 98 lines skipped"), "{text}");
+        assert!(text.ends_with("line 098\r\nline 099\r\n"), "{text}");
+    }
+
+    #[test]
+    fn summarize_leaves_screen_switches_and_single_lines_alone() {
+        let mut data = b"\x1b[?1049h".to_vec();
+        data.extend(std::iter::repeat(b"row\r\n").take(100).flatten());
+        assert_eq!(summarize(&data, 16), None);
+        assert_eq!(summarize(&[b'x'; 64], 16), None);
+    }
+
+    #[test]
+    fn read_head_cuts_after_the_last_whole_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("log.txt");
+        std::fs::write(&path, "alpha\nbravo\ncharlie\n").unwrap();
+
+        let head = read_head(&path, 14).unwrap();
+        assert_eq!(head.content, "alpha\nbravo\n");
+        assert!(head.truncated);
+        assert_eq!(head.total_bytes, 20);
+
+        let whole = read_head(&path, 64).unwrap();
+        assert_eq!(whole.content, "alpha\nbravo\ncharlie\n");
+        assert!(!whole.truncated);
+    }
+}
@@ -0,0 +1,390 @@
+// Workspace-wide diagnostics for `DiagnosticsCheck`.
+//
+// Runs the project's checker once and parses its machine-readable output into
+// per-file, per-range diagnostics for the app's Problems screen:
+//   Cargo.toml    -> `cargo check --message-format=json`
+//   tsconfig.json -> `npx tsc --noEmit --pretty false`
+//
+// Only files inside the workspace jail are reported; diagnostics pointing into
+// the cargo registry or `node_modules` are dropped.
+
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+use zedra_rpc::proto::{
+    DiagnosticSeverity, DiagnosticsCheckResult, WorkspaceDiagnostic, DIAGNOSTICS_CHECK_MAX,
+};
+
+/// A full `cargo check` on a cold target dir can take minutes; past this the
+/// checker is killed and the request fails.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Checker {
+    Cargo,
+    Tsc,
+}
+
+impl Checker {
+    fn detect(root: &Path) -> Option<Self> {
+        if root.join("Cargo.toml").is_file() {
+            Some(Self::Cargo)
+        } else if root.join("tsconfig.json").is_file() {
+            Some(Self::Tsc)
+        } else {
+            None
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Cargo => "cargo check",
+            Self::Tsc => "tsc",
+        }
+    }
+}
+
+pub fn diagnostics_check_error(message: String) -> DiagnosticsCheckResult {
+    DiagnosticsCheckResult {
+        error: Some(message),
+        ..Default::default()
+    }
+}
+
+/// Run the checker for `root` with the session's `env` overrides and collect
+/// diagnostics for files under `jail`.
+pub async fn run_diagnostics_check(
+    jail: &Path,
+    root: &Path,
+    env: &[(String, String)],
+) -> Result<DiagnosticsCheckResult> {
+    anyhow::ensure!(root.is_dir(), "check path must be a directory");
+    let checker = Checker::detect(root)
+        .context("no supported checker found (expected Cargo.toml or tsconfig.json)")?;
+
+    let (program, args): (&str, &[&str]) = match checker {
+        Checker::Cargo => ("cargo", &["check", "--message-format=json"]),
+        Checker::Tsc => ("npx", &["tsc", "--noEmit", "--pretty", "false"]),
+    };
+    let output = tokio::time::timeout(
+        CHECK_TIMEOUT,
+        tokio::process::Command::new(program)
+            .args(args)
+            .envs(env.iter().map(|(key, value)| (key, value)))
+            .current_dir(root)
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .map_err(|_| anyhow::anyhow!("{} timed out", checker.label()))?
+    .with_context(|| format!("failed to run {}", checker.label()))?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    let diagnostics = match checker {
+        Checker::Cargo => {
+            // rustc reports paths relative to the cargo workspace root, which
+            // may sit above `root` when checking a member crate.
+            let base = cargo_workspace_root(root)
+                .await
+                .unwrap_or_else(|| root.to_path_buf());
+            parse_cargo_messages(&stdout, &base)
+        }
+        Checker::Tsc => parse_tsc_output(&stdout, root),
+    };
+    if diagnostics.is_empty() && !output.status.success() {
+        // Failed before reporting anything (bad manifest, missing toolchain).
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let reason = stderr
+            .lines()
+            .find(|line| !line.trim().is_empty())
+            .unwrap_or("no diagnostics reported");
+        anyhow::bail!("{} failed: {}", checker.label(), reason.trim());
+    }
+
+    Ok(collect(checker, diagnostics, jail, root))
+}
+
+async fn cargo_workspace_root(root: &Path) -> Option<PathBuf> {
+    let output = tokio::process::Command::new("cargo")
+        .args(["locate-project", "--workspace", "--message-format", "plain"])
+        .current_dir(root)
+        .output()
+        .await
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let manifest = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+    manifest.parent().map(Path::to_path_buf)
+}
+
+/// A parsed diagnostic before it is placed relative to the workspace.
+#[derive(Debug, PartialEq)]
+struct RawDiagnostic {
+    file: PathBuf,
+    line: u32,
+    column: u32,
+    end_line: u32,
+    end_column: u32,
+    severity: DiagnosticSeverity,
+    message: String,
+    code: Option<String>,
+}
+
+fn severity_from_level(level: &str) -> Option<DiagnosticSeverity> {
+    match level {
+        "error" | "error: internal compiler error" => Some(DiagnosticSeverity::Error),
+        "warning" => Some(DiagnosticSeverity::Warning),
+        "note" | "help" | "info" | "message" => Some(DiagnosticSeverity::Info),
+        _ => None,
+    }
+}
+
+/// Parse `cargo check --message-format=json` stdout. Messages without a
+/// primary span (such as "aborting due to 2 previous errors") are dropped.
+fn parse_cargo_messages(stdout: &str, base: &Path) -> Vec<RawDiagnostic> {
+    let mut out = Vec::new();
+    for line in stdout.lines() {
+        let Ok(value) = serde_json::from_str::<serde_json::Value>(line) else {
+            continue;
+        };
+        if value["reason"] != "compiler-message" {
+            continue;
+        }
+        let message = &value["message"];
+        let Some(severity) = message["level"].as_str().and_then(severity_from_level) else {
+            continue;
+        };
+        let Some(span) = message["spans"]
+            .as_array()
+            .and_then(|spans| spans.iter().find(|span| span["is_primary"] == true))
+        else {
+            continue;
+        };
+        let Some(file_name) = span["file_name"].as_str() else {
+            continue;
+        };
+        let number = |key: &str| span[key].as_u64().unwrap_or(1).max(1) as u32;
+        out.push(RawDiagnostic {
+            file: base.join(file_name),
+            line: number("line_start"),
+            column: number("column_start"),
+            end_line: number("line_end"),
+            end_column: number("column_end"),
+            severity,
+            message: message["message"].as_str().unwrap_or_default().to_string(),
+            code: message["code"]["code"].as_str().map(str::to_string),
+        });
+    }
+    out
+}
+
+/// Parse `tsc --pretty false` lines: `src/app.ts(12,5): error TS2322: message`.
+fn parse_tsc_output(stdout: &str, root: &Path) -> Vec<RawDiagnostic> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let (location, rest) = line.split_once("): ")?;
+            let (file, position) = location.rsplit_once('(')?;
+            let (line_no, column) = position.split_once(',')?;
+            let line_no = line_no.trim().parse::<u32>().ok()?;
+            let column = column.trim().parse::<u32>().ok()?;
+            let (head, message) = rest.split_once(": ")?;
+            let (level, code) = head.split_once(' ').unwrap_or((head, ""));
+            Some(RawDiagnostic {
+                file: root.join(file),
+                line: line_no,
+                column,
+                end_line: line_no,
+                end_column: column,
+                severity: severity_from_level(level)?,
+                message: message.to_string(),
+                code: (!code.is_empty()).then(|| code.to_string()),
+            })
+        })
+        .collect()
+}
+
+/// Lexically normalize `path` (drop `.`, resolve `..`) without touching the
+/// filesystem, so reported paths can be jail-checked even for deleted files.
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+fn slash_path(path: &Path) -> String {
+    path.components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => Some(part.to_string_lossy().into_owned()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn collect(
+    checker: Checker,
+    raw: Vec<RawDiagnostic>,
+    jail: &Path,
+    root: &Path,
+) -> DiagnosticsCheckResult {
+    let mut seen = HashSet::new();
+    let mut diagnostics = Vec::new();
+    let mut truncated = false;
+    for diagnostic in raw {
+        let file = normalize(&diagnostic.file);
+        if !file.starts_with(jail) {
+            continue;
+        }
+        let rel_path = slash_path(
+            file.strip_prefix(root)
+                .or_else(|_| file.strip_prefix(jail))
+                .unwrap_or(&file),
+        );
+        // cargo repeats a diagnostic once per target (lib, bin, test).
+        if !seen.insert((
+            rel_path.clone(),
+            diagnostic.line,
+            diagnostic.column,
+            diagnostic.message.clone(),
+        )) {
+            continue;
+        }
+        if diagnostics.len() >= DIAGNOSTICS_CHECK_MAX {
+            truncated = true;
+            break;
+        }
+        diagnostics.push(WorkspaceDiagnostic {
+            path: file.to_string_lossy().into_owned(),
+            rel_path,
+            line: diagnostic.line,
+            column: diagnostic.column,
+            end_line: diagnostic.end_line,
+            end_column: diagnostic.end_column,
+            severity: diagnostic.severity,
+            message: diagnostic.message,
+            code: diagnostic.code,
+        });
+    }
+    diagnostics.sort_by(|a, b| {
+        (&a.rel_path, a.line, a.column, a.severity).cmp(&(
+            &b.rel_path,
+            b.line,
+            b.column,
+            b.severity,
+        ))
+    });
+
+    DiagnosticsCheckResult {
+        tool: checker.label().to_string(),
+        diagnostics,
+        truncated,
+        error: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CARGO_OUTPUT: &str = concat!(
+        r#"{"reason":"compiler-artifact","package_id":"dep 0.1.0","filenames":[]}"#,
+        "\n",
+        r#"{"reason":"compiler-message","message":{"level":"error","message":"mismatched types","code":{"code":"E0308"},"spans":[{"file_name":"crates/app/src/main.rs","is_primary":false,"line_start":1,"line_end":1,"column_start":1,"column_end":2},{"file_name":"crates/app/src/main.rs","is_primary":true,"line_start":4,"line_end":4,"column_start":18,"column_end":20}]}}"#,
+        "\n",
+        r#"{"reason":"compiler-message","message":{"level":"warning","message":"unused variable: `x`","code":{"code":"unused_variables"},"spans":[{"file_name":"crates/lib/src/lib.rs","is_primary":true,"line_start":2,"line_end":2,"column_start":9,"column_end":10}]}}"#,
+        "\n",
+        r#"{"reason":"compiler-message","message":{"level":"warning","message":"unused variable: `x`","code":{"code":"unused_variables"},"spans":[{"file_name":"crates/lib/src/lib.rs","is_primary":true,"line_start":2,"line_end":2,"column_start":9,"column_end":10}]}}"#,
+        "\n",
+        r#"{"reason":"compiler-message","message":{"level":"warning","message":"deprecated","code":null,"spans":[{"file_name":"/home/u/.cargo/registry/src/dep/lib.rs","is_primary":true,"line_start":7,"line_end":7,"column_start":1,"column_end":4}]}}"#,
+        "\n",
+        r#"{"reason":"compiler-message","message":{"level":"error","message":"aborting due to 1 previous error","code":null,"spans":[]}}"#,
+        "\n",
+        r#"{"reason":"build-finished","success":false}"#,
+    );
+
+    #[test]
+    fn cargo_messages_are_deduplicated_and_jailed() {
+        let workspace = Path::new("/work");
+        let root = Path::new("/work/crates/app");
+        let raw = parse_cargo_messages(CARGO_OUTPUT, workspace);
+        assert_eq!(raw.len(), 4);
+
+        let result = collect(Checker::Cargo, raw, workspace, root);
+
+        assert_eq!(result.tool, "cargo check");
+        assert!(!result.truncated);
+        assert_eq!(
+            result.diagnostics,
+            vec![
+                // Sibling crate: relative to the workspace instead of `root`.
+                WorkspaceDiagnostic {
+                    path: "/work/crates/lib/src/lib.rs".to_string(),
+                    rel_path: "crates/lib/src/lib.rs".to_string(),
+                    line: 2,
+                    column: 9,
+                    end_line: 2,
+                    end_column: 10,
+                    severity: DiagnosticSeverity::Warning,
+                    message: "unused variable: `x`".to_string(),
+                    code: Some("unused_variables".to_string()),
+                },
+                WorkspaceDiagnostic {
+                    path: "/work/crates/app/src/main.rs".to_string(),
+                    rel_path: "src/main.rs".to_string(),
+                    line: 4,
+                    column: 18,
+                    end_line: 4,
+                    end_column: 20,
+                    severity: DiagnosticSeverity::Error,
+                    message: "mismatched types".to_string(),
+                    code: Some("E0308".to_string()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn tsc_lines_parse_into_diagnostics() {
+        let stdout =
+            "src/app.ts(12,5): error TS2322: Type 'string' is not assignable to type 'number'.\n\
+                      ../outside.ts(1,1): error TS1005: ';' expected.\n\
+                      Found 2 errors.\n";
+        let root = Path::new("/work/web");
+        let raw = parse_tsc_output(stdout, root);
+        assert_eq!(raw.len(), 2);
+
+        let result = collect(Checker::Tsc, raw, root, root);
+
+        assert_eq!(result.diagnostics.len(), 1);
+        let diagnostic = &result.diagnostics[0];
+        assert_eq!(diagnostic.rel_path, "src/app.ts");
+        assert_eq!((diagnostic.line, diagnostic.column), (12, 5));
+        assert_eq!(diagnostic.severity, DiagnosticSeverity::Error);
+        assert_eq!(diagnostic.code.as_deref(), Some("TS2322"));
+        assert_eq!(
+            diagnostic.message,
+            "Type 'string' is not assignable to type 'number'."
+        );
+    }
+
+    #[test]
+    fn detects_checker_from_project_files() {
+        let temp = tempfile::tempdir().unwrap();
+        assert_eq!(Checker::detect(temp.path()), None);
+        std::fs::write(temp.path().join("tsconfig.json"), "{}").unwrap();
+        assert_eq!(Checker::detect(temp.path()), Some(Checker::Tsc));
+        std::fs::write(temp.path().join("Cargo.toml"), "[package]").unwrap();
+        assert_eq!(Checker::detect(temp.path()), Some(Checker::Cargo));
+    }
+}
@@ -0,0 +1,387 @@
+// Pull/merge request creation for `ForgeCreatePr`.
+//
+// The forge is picked from the remote URL's host and must have an account in
+// `~/.config/zedra/forge.json`:
+//
+//   { "accounts": [
+//       { "host": "github.com", "token_env": "GITHUB_TOKEN" },
+//       { "host": "gitlab.example.com", "kind": "gitlab", "token": "glpat-..." }
+//   ] }
+//
+// `kind` defaults from the host name (`github`/`gitlab` in it) and `api_url`
+// from the kind: `https://api.github.com`, `https://<host>/api/v3` for GitHub
+// Enterprise, `https://<host>/api/v4` for GitLab. The token stays on the host;
+// the device only sees the resulting URL. The branch must already be pushed.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use serde::Deserialize;
+use serde_json::json;
+use zedra_rpc::proto::{ForgeCreatePrReq, ForgeCreatePrResult};
+
+use crate::git::GitRepo;
+use crate::identity;
+
+const CONFIG_FILE: &str = "forge.json";
+const DEFAULT_REMOTE: &str = "origin";
+const HTTP_TIMEOUT: Duration = Duration::from_secs(30);
+const USER_AGENT: &str = concat!("zedra-host/", env!("CARGO_PKG_VERSION"));
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ForgeKind {
+    Github,
+    Gitlab,
+}
+
+impl ForgeKind {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Github => "github",
+            Self::Gitlab => "gitlab",
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ForgeConfig {
+    #[serde(default)]
+    pub accounts: Vec<ForgeAccount>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ForgeAccount {
+    /// Remote host this account is for, e.g. `github.com`.
+    pub host: String,
+    #[serde(default)]
+    pub kind: Option<ForgeKind>,
+    #[serde(default)]
+    pub api_url: Option<String>,
+    #[serde(default)]
+    pub token: Option<String>,
+    /// Environment variable holding the token, used when `token` is unset.
+    #[serde(default)]
+    pub token_env: Option<String>,
+}
+
+impl ForgeAccount {
+    fn kind(&self) -> Option<ForgeKind> {
+        self.kind.or_else(|| {
+            let host = self.host.to_ascii_lowercase();
+            if host.contains("github") {
+                Some(ForgeKind::Github)
+            } else if host.contains("gitlab") {
+                Some(ForgeKind::Gitlab)
+            } else {
+                None
+            }
+        })
+    }
+
+    fn api_url(&self, kind: ForgeKind) -> String {
+        if let Some(api_url) = &self.api_url {
+            return api_url.trim_end_matches('/').to_string();
+        }
+        match kind {
+            ForgeKind::Github if self.host == "github.com" => "https://api.github.com".to_string(),
+            ForgeKind::Github => format!("https://{}/api/v3", self.host),
+            ForgeKind::Gitlab => format!("https://{}/api/v4", self.host),
+        }
+    }
+
+    fn token(&self) -> Result<String> {
+        if let Some(token) = self.token.as_deref().filter(|token| !token.is_empty()) {
+            return Ok(token.to_string());
+        }
+        let Some(var) = &self.token_env else {
+            bail!("no token configured for {} in {CONFIG_FILE}", self.host);
+        };
+        std::env::var(var).with_context(|| format!("{var} is not set on the host"))
+    }
+}
+
+pub fn config_path() -> Result<PathBuf> {
+    Ok(identity::host_config_dir()?.join(CONFIG_FILE))
+}
+
+fn load_config_at(path: &Path) -> Result<ForgeConfig> {
+    match std::fs::read_to_string(path) {
+        Ok(json) => serde_json::from_str(&json)
+            .with_context(|| format!("failed to parse forge config {}", path.display())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(ForgeConfig::default()),
+        Err(e) => Err(e).with_context(|| format!("failed to read {}", path.display())),
+    }
+}
+
+/// Host and repository path (`owner/repo`, or `group/sub/repo` on GitLab)
+/// named by a remote URL.
+#[derive(Debug, PartialEq, Eq)]
+struct RemoteRepo {
+    host: String,
+    path: String,
+}
+
+fn parse_remote_url(url: &str) -> Option<RemoteRepo> {
+    let url = url.trim();
+    let (host, path) = if let Some((_, rest)) = url.split_once("://") {
+        // https://host/owner/repo, ssh://git@host:22/owner/repo
+        let (authority, path) = rest.split_once('/')?;
+        let host = authority.rsplit('@').next()?;
+        let host = host.split(':').next()?;
+        (host, path)
+    } else {
+        // scp-style git@host:owner/repo
+        let (authority, path) = url.split_once(':')?;
+        (authority.rsplit('@').next()?, path)
+    };
+    let path = path.trim_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    if host.is_empty() || !path.contains('/') {
+        return None;
+    }
+    Some(RemoteRepo {
+        host: host.to_ascii_lowercase(),
+        path: path.to_string(),
+    })
+}
+
+/// Create the pull/merge request described by `req` for the repo at `workdir`.
+pub fn create_pull_request(workdir: &Path, req: &ForgeCreatePrReq) -> Result<ForgeCreatePrResult> {
+    let title = req.title.trim();
+    if title.is_empty() {
+        bail!("a pull request needs a title");
+    }
+    let repo = GitRepo::open(workdir)?;
+    let remote = req.remote.as_deref().unwrap_or(DEFAULT_REMOTE);
+    let url = repo.remote_url(remote)?;
+    let remote_repo = parse_remote_url(&url)
+        .with_context(|| format!("{remote} ({url}) is not a forge repository URL"))?;
+    let head = match &req.head {
+        Some(head) => head.clone(),
+        None => repo.branch()?,
+    };
+    if head.is_empty() {
+        bail!("HEAD is detached; check out a branch first");
+    }
+    if !repo.has_remote_branch(remote, &head)? {
+        bail!("{head} is not on {remote}; push it first");
+    }
+
+    let config = load_config_at(&config_path()?)?;
+    let Some(account) = config
+        .accounts
+        .iter()
+        .find(|account| account.host.eq_ignore_ascii_case(&remote_repo.host))
+    else {
+        bail!(
+            "no forge account for {} in {}",
+            remote_repo.host,
+            config_path()?.display()
+        );
+    };
+    let Some(kind) = account.kind() else {
+        bail!(
+            "set \"kind\" to \"github\" or \"gitlab\" for {} in {CONFIG_FILE}",
+            account.host
+        );
+    };
+    let api = ForgeApi {
+        kind,
+        api_url: account.api_url(kind),
+        token: account.token()?,
+        client: reqwest::blocking::Client::builder()
+            .timeout(HTTP_TIMEOUT)
+            .user_agent(USER_AGENT)
+            .build()?,
+    };
+    let base = match &req.base {
+        Some(base) => base.clone(),
+        None => api.default_branch(&remote_repo.path)?,
+    };
+    let (url, number) = api.create(&remote_repo.path, title, &req.body, &head, &base, req.draft)?;
+    Ok(ForgeCreatePrResult {
+        url,
+        number,
+        forge: kind.name().to_string(),
+        error: None,
+    })
+}
+
+pub fn forge_error(error: String) -> ForgeCreatePrResult {
+    ForgeCreatePrResult {
+        error: Some(error),
+        ..Default::default()
+    }
+}
+
+struct ForgeApi {
+    kind: ForgeKind,
+    api_url: String,
+    token: String,
+    client: reqwest::blocking::Client,
+}
+
+impl ForgeApi {
+    fn repo_url(&self, path: &str) -> String {
+        match self.kind {
+            ForgeKind::Github => format!("{}/repos/{path}", self.api_url),
+            ForgeKind::Gitlab => format!(
+                "{}/projects/{}",
+                self.api_url,
+                utf8_percent_encode(path, NON_ALPHANUMERIC)
+            ),
+        }
+    }
+
+    fn request(&self, builder: reqwest::blocking::RequestBuilder) -> Result<serde_json::Value> {
+        let builder = match self.kind {
+            ForgeKind::Github => builder
+                .bearer_auth(&self.token)
+                .header("Accept", "application/vnd.github+json"),
+            ForgeKind::Gitlab => builder.header("PRIVATE-TOKEN", &self.token),
+        };
+        let response = builder.send()?;
+        let status = response.status();
+        let body: serde_json::Value = response.json().unwrap_or_default();
+        if !status.is_success() {
+            bail!(
+                "{} API returned {status}: {}",
+                self.kind.name(),
+                api_message(&body)
+            );
+        }
+        Ok(body)
+    }
+
+    fn default_branch(&self, path: &str) -> Result<String> {
+        let repo = self.request(self.client.get(self.repo_url(path)))?;
+        repo["default_branch"]
+            .as_str()
+            .map(str::to_string)
+            .context("forge did not report a default branch")
+    }
+
+    fn create(
+        &self,
+        path: &str,
+        title: &str,
+        body: &str,
+        head: &str,
+        base: &str,
+        draft: bool,
+    ) -> Result<(String, u64)> {
+        let (endpoint, payload, url_key, number_key) = match self.kind {
+            ForgeKind::Github => (
+                format!("{}/pulls", self.repo_url(path)),
+                json!({ "title": title, "body": body, "head": head, "base": base, "draft": draft }),
+                "html_url",
+                "number",
+            ),
+            ForgeKind::Gitlab => (
+                format!("{}/merge_requests", self.repo_url(path)),
+                json!({
+                    "title": if draft { format!("Draft: {title}") } else { title.to_string() },
+                    "description": body,
+                    "source_branch": head,
+                    "target_branch": base,
+                }),
+                "web_url",
+                "iid",
+            ),
+        };
+        let created = self.request(self.client.post(endpoint).json(&payload))?;
+        let url = created[url_key]
+            .as_str()
+            .context("forge response has no URL")?
+            .to_string();
+        Ok((url, created[number_key].as_u64().unwrap_or_default()))
+    }
+}
+
+/// Human-readable error from a GitHub (`message`, `errors[].message`) or
+/// GitLab (`message`, `error`) error body.
+fn api_message(body: &serde_json::Value) -> String {
+    let mut parts: Vec<String> = Vec::new();
+    match &body["message"] {
+        serde_json::Value::String(message) => parts.push(message.clone()),
+        serde_json::Value::Array(messages) => parts.extend(
+            messages
+                .iter()
+                .filter_map(|m| m.as_str().map(str::to_string)),
+        ),
+        _ => {}
+    }
+    if let Some(error) = body["error"].as_str() {
+        parts.push(error.to_string());
+    }
+    if let Some(errors) = body["errors"].as_array() {
+        parts.extend(
+            errors
+                .iter()
+                .filter_map(|e| e["message"].as_str().map(str::to_string)),
+        );
+    }
+    if parts.is_empty() {
+        "no details".to_string()
+    } else {
+        parts.join("; ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remote_urls_name_host_and_repository() {
+        let repo = |host: &str, path: &str| {
+            Some(RemoteRepo {
+                host: host.to_string(),
+                path: path.to_string(),
+            })
+        };
+        assert_eq!(
+            parse_remote_url("git@github.com:tanlethanh/zedra.git"),
+            repo("github.com", "tanlethanh/zedra")
+        );
+        assert_eq!(
+            parse_remote_url("https://GitHub.com/tanlethanh/zedra"),
+            repo("github.com", "tanlethanh/zedra")
+        );
+        assert_eq!(
+            parse_remote_url("ssh://git@gitlab.example.com:2222/group/sub/app.git"),
+            repo("gitlab.example.com", "group/sub/app")
+        );
+        assert_eq!(parse_remote_url("/srv/git/app.git"), None);
+    }
+
+    #[test]
+    fn accounts_default_kind_and_api_url_from_host() {
+        let account = |host: &str| ForgeAccount {
+            host: host.to_string(),
+            kind: None,
+            api_url: None,
+            token: None,
+            token_env: None,
+        };
+        let github = account("github.com");
+        assert_eq!(github.kind(), Some(ForgeKind::Github));
+        assert_eq!(github.api_url(ForgeKind::Github), "https://api.github.com");
+        let enterprise = account("github.corp.example");
+        assert_eq!(
+            enterprise.api_url(ForgeKind::Github),
+            "https://github.corp.example/api/v3"
+        );
+        let gitlab = account("gitlab.com");
+        assert_eq!(gitlab.kind(), Some(ForgeKind::Gitlab));
+        assert_eq!(
+            gitlab.api_url(ForgeKind::Gitlab),
+            "https://gitlab.com/api/v4"
+        );
+        assert_eq!(account("code.example").kind(), None);
+        assert!(github.token().is_err());
+    }
+}
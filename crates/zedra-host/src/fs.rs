@@ -1,11 +1,16 @@
 // Filesystem operations for Zedra
 //
 // Provides a trait-based filesystem abstraction with a local implementation.
-// The trait allows swapping in remote (RPC-backed) implementations on mobile.
+// The trait is host-side: the daemon's fs/* handlers and `fs_replace` go
+// through it, so tests can swap in another implementation. The app does not
+// use it; it reaches the host's files through the async `fs_*` calls on
+// `zedra_session::SessionHandle`.
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::io::{Read, Seek, SeekFrom};
 use std::path::{Path, PathBuf};
+use zedra_rpc::proto::FS_CROSS_DEVICE_ERROR;
 
 // ---------------------------------------------------------------------------
 // Types
@@ -28,6 +33,18 @@ pub struct FileStat {
     pub modified: Option<u64>,
 }
 
+/// A rename whose source and destination are on different filesystems.
+#[derive(Debug)]
+pub struct CrossDevice;
+
+impl std::fmt::Display for CrossDevice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{FS_CROSS_DEVICE_ERROR}; copy it instead")
+    }
+}
+
+impl std::error::Error for CrossDevice {}
+
 // ---------------------------------------------------------------------------
 // Trait
 // ---------------------------------------------------------------------------
@@ -36,9 +53,21 @@ pub trait Filesystem: Send + Sync {
     fn list(&self, path: &Path) -> Result<Vec<DirEntry>>;
     fn read(&self, path: &Path) -> Result<String>;
     fn write(&self, path: &Path, content: &str) -> Result<()>;
+    fn read_bytes(&self, path: &Path) -> Result<Vec<u8>>;
+    fn write_bytes(&self, path: &Path, data: &[u8]) -> Result<()>;
+    /// Up to `max_bytes` from `offset`, and the file's total size.
+    fn read_range(&self, path: &Path, offset: u64, max_bytes: u64) -> Result<(Vec<u8>, u64)>;
     fn stat(&self, path: &Path) -> Result<FileStat>;
     fn mkdir(&self, path: &Path) -> Result<()>;
     fn remove(&self, path: &Path) -> Result<()>;
+    /// Move `from` to `to`, which must not exist. Fails with `CrossDevice`
+    /// rather than copying when they are on different filesystems.
+    fn rename(&self, from: &Path, to: &Path) -> Result<()>;
+    /// Copy `from` to `to`, which must not exist. Directories need `recursive`.
+    fn copy(&self, from: &Path, to: &Path, recursive: bool) -> Result<()>;
+    /// Pack `sources`, each under its own name, into a new gzip-compressed
+    /// tar at `to`. Symlinks are stored as links.
+    fn archive(&self, sources: &[PathBuf], to: &Path) -> Result<()>;
 }
 
 // ---------------------------------------------------------------------------
@@ -69,10 +98,27 @@ impl Filesystem for LocalFs {
     }
 
     fn write(&self, path: &Path, content: &str) -> Result<()> {
+        self.write_bytes(path, content.as_bytes())
+    }
+
+    fn read_bytes(&self, path: &Path) -> Result<Vec<u8>> {
+        Ok(std::fs::read(path)?)
+    }
+
+    fn write_bytes(&self, path: &Path, data: &[u8]) -> Result<()> {
         if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent)?;
         }
-        Ok(std::fs::write(path, content)?)
+        Ok(std::fs::write(path, data)?)
+    }
+
+    fn read_range(&self, path: &Path, offset: u64, max_bytes: u64) -> Result<(Vec<u8>, u64)> {
+        let mut file = std::fs::File::open(path)?;
+        let total_bytes = file.metadata()?.len();
+        file.seek(SeekFrom::Start(offset.min(total_bytes)))?;
+        let mut data = Vec::new();
+        file.take(max_bytes).read_to_end(&mut data)?;
+        Ok((data, total_bytes))
     }
 
     fn stat(&self, path: &Path) -> Result<FileStat> {
@@ -95,7 +141,8 @@ impl Filesystem for LocalFs {
     }
 
     fn remove(&self, path: &Path) -> Result<()> {
-        let meta = std::fs::metadata(path)?;
+        // A symlink is removed itself, never the directory it points to.
+        let meta = std::fs::symlink_metadata(path)?;
         if meta.is_dir() {
             std::fs::remove_dir_all(path)?;
         } else {
@@ -103,6 +150,101 @@ impl Filesystem for LocalFs {
         }
         Ok(())
     }
+
+    fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        std::fs::symlink_metadata(from)?;
+        ensure_vacant(to)?;
+        match std::fs::rename(from, to) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::CrossesDevices => Err(CrossDevice.into()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn copy(&self, from: &Path, to: &Path, recursive: bool) -> Result<()> {
+        let is_dir = std::fs::metadata(from)?.is_dir();
+        if is_dir {
+            anyhow::ensure!(recursive, "is a directory; copy it recursively");
+            anyhow::ensure!(!to.starts_with(from), "cannot copy a directory into itself");
+        }
+        ensure_vacant(to)?;
+        if is_dir {
+            copy_dir(from, to)
+        } else {
+            std::fs::copy(from, to)?;
+            Ok(())
+        }
+    }
+
+    fn archive(&self, sources: &[PathBuf], to: &Path) -> Result<()> {
+        anyhow::ensure!(!sources.is_empty(), "nothing to archive");
+        for source in sources {
+            std::fs::symlink_metadata(source)?;
+            anyhow::ensure!(
+                !to.starts_with(source),
+                "cannot archive a directory into itself"
+            );
+        }
+        ensure_vacant(to)?;
+        let written = write_archive(sources, to);
+        if written.is_err() {
+            let _ = std::fs::remove_file(to);
+        }
+        written
+    }
+}
+
+/// Fail if `to` exists, else create its parent directories.
+fn ensure_vacant(to: &Path) -> Result<()> {
+    anyhow::ensure!(
+        std::fs::symlink_metadata(to).is_err(),
+        "destination already exists"
+    );
+    if let Some(parent) = to.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    Ok(())
+}
+
+/// Copy a directory tree. Symlinks inside it are recreated, not followed.
+fn copy_dir(from: &Path, to: &Path) -> Result<()> {
+    std::fs::create_dir(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let source = entry.path();
+        let target = to.join(entry.file_name());
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            copy_dir(&source, &target)?;
+        } else if file_type.is_symlink() {
+            #[cfg(unix)]
+            std::os::unix::fs::symlink(std::fs::read_link(&source)?, &target)?;
+            #[cfg(not(unix))]
+            std::fs::copy(&source, &target)?;
+        } else {
+            std::fs::copy(&source, &target)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_archive(sources: &[PathBuf], to: &Path) -> Result<()> {
+    let file = std::fs::File::create_new(to)?;
+    let encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+    let mut builder = tar::Builder::new(encoder);
+    builder.follow_symlinks(false);
+    for source in sources {
+        let name = source
+            .file_name()
+            .ok_or_else(|| anyhow::anyhow!("cannot archive {}", source.display()))?;
+        if std::fs::symlink_metadata(source)?.is_dir() {
+            builder.append_dir_all(name, source)?;
+        } else {
+            builder.append_path_with_name(source, name)?;
+        }
+    }
+    builder.into_inner()?.finish()?;
+    Ok(())
 }
 
 #[cfg(test)]
@@ -121,6 +263,22 @@ mod tests {
         assert_eq!(fs.read(&file).unwrap(), "hello world");
     }
 
+    #[test]
+    fn binary_round_trip_and_ranges() {
+        let (dir, fs) = setup();
+        let file = dir.path().join("blob.bin");
+        let data: Vec<u8> = (0..=255).collect();
+        fs.write_bytes(&file, &data).unwrap();
+        assert_eq!(fs.read_bytes(&file).unwrap(), data);
+        assert!(fs.read(&file).is_err());
+
+        let (chunk, total) = fs.read_range(&file, 250, 100).unwrap();
+        assert_eq!(chunk, vec![250, 251, 252, 253, 254, 255]);
+        assert_eq!(total, 256);
+        let (past_end, _) = fs.read_range(&file, 1000, 10).unwrap();
+        assert!(past_end.is_empty());
+    }
+
     #[test]
     fn list_directory() {
         let (dir, fs) = setup();
@@ -184,6 +342,71 @@ mod tests {
         assert!(!sub.exists());
     }
 
+    #[test]
+    fn rename_moves_and_refuses_to_overwrite() {
+        let (dir, fs) = setup();
+        let from = dir.path().join("old.txt");
+        let to = dir.path().join("moved").join("new.txt");
+        fs.write(&from, "data").unwrap();
+        fs.rename(&from, &to).unwrap();
+        assert!(!from.exists());
+        assert_eq!(fs.read(&to).unwrap(), "data");
+
+        let other = dir.path().join("other.txt");
+        fs.write(&other, "keep").unwrap();
+        assert!(fs.rename(&other, &to).is_err());
+        assert_eq!(fs.read(&to).unwrap(), "data");
+    }
+
+    #[test]
+    fn copy_files_and_directories() {
+        let (dir, fs) = setup();
+        let src = dir.path().join("src");
+        fs.write(&src.join("a.txt"), "a").unwrap();
+        fs.write(&src.join("nested").join("b.txt"), "b").unwrap();
+
+        fs.copy(&src.join("a.txt"), &dir.path().join("a-copy.txt"), false)
+            .unwrap();
+        assert_eq!(fs.read(&dir.path().join("a-copy.txt")).unwrap(), "a");
+
+        let copy = dir.path().join("src-copy");
+        assert!(fs.copy(&src, &copy, false).is_err());
+        assert!(fs.copy(&src, &src.join("inside"), true).is_err());
+        fs.copy(&src, &copy, true).unwrap();
+        assert_eq!(fs.read(&copy.join("nested").join("b.txt")).unwrap(), "b");
+        assert!(src.join("a.txt").exists());
+    }
+
+    #[test]
+    fn archive_packs_entries_under_their_names() {
+        let (dir, fs) = setup();
+        let logs = dir.path().join("logs");
+        let notes = dir.path().join("notes.txt");
+        fs.write(&logs.join("a.log"), "a").unwrap();
+        fs.write(&notes, "n").unwrap();
+
+        let to = dir.path().join("out").join("bundle.tar.gz");
+        fs.archive(&[logs.clone(), notes.clone()], &to).unwrap();
+        assert!(fs.archive(&[notes], &to).is_err());
+        assert!(fs
+            .archive(&[logs.clone()], &logs.join("self.tar.gz"))
+            .is_err());
+        assert!(!logs.join("self.tar.gz").exists());
+
+        let file = std::fs::File::open(&to).unwrap();
+        let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(file));
+        let mut names: Vec<String> = archive
+            .entries()
+            .unwrap()
+            .map(|entry| {
+                let path = entry.unwrap().path().unwrap().into_owned();
+                path.to_string_lossy().trim_end_matches('/').to_string()
+            })
+            .collect();
+        names.sort();
+        assert_eq!(names, ["logs", "logs/a.log", "notes.txt"]);
+    }
+
     #[test]
     fn write_creates_parent_dirs() {
         let (dir, fs) = setup();
@@ -0,0 +1,345 @@
+// Host-side recursive search for `FsGrep`.
+//
+// Same walk as `FsSearch` and `FsReplace` (gitignore-aware, no symlinks,
+// generated directories skipped). A file is a hit when its root-relative path
+// matches `glob`; with `pattern`, each matching line of a text file is a hit
+// instead. Hits are handed to `emit` in batches as the walk goes so the client
+// can show results before a large tree is done. `FsGrepV2` asks for a few lines
+// around each content hit, and `FsGrepExpand` reads a wider window around one
+// line when the client expands a result.
+
+use crate::fs_replace::{read_text, rel_path, truncate_sample};
+use crate::rpc_daemon::is_file_search_ignored;
+use anyhow::Result;
+use ignore::overrides::{Override, OverrideBuilder};
+use regex::{Regex, RegexBuilder};
+use std::path::Path;
+use zedra_rpc::proto::{
+    FsGrepContextMatch, FsGrepExpandResult, FsGrepMatch, FsGrepReq, FS_GREP_DEFAULT_LIMIT,
+    FS_GREP_MAX_CONTEXT_LINES, FS_GREP_MAX_EXPAND_RADIUS, FS_GREP_MAX_LIMIT,
+    FS_GREP_MAX_MATCHES_PER_FILE, FS_GREP_MAX_VISITED_ENTRIES,
+};
+
+/// Hits per `FsGrepUpdate::Matches`.
+const BATCH_SIZE: usize = 50;
+
+fn grep_limit(limit: u32) -> usize {
+    let limit = if limit == 0 {
+        FS_GREP_DEFAULT_LIMIT
+    } else {
+        limit.min(FS_GREP_MAX_LIMIT)
+    };
+    limit as usize
+}
+
+fn build_glob(root: &Path, req: &FsGrepReq) -> Result<Option<Override>> {
+    let Some(glob) = req.glob.as_deref().map(str::trim).filter(|g| !g.is_empty()) else {
+        return Ok(None);
+    };
+    anyhow::ensure!(!glob.starts_with('!'), "negated globs are not supported");
+    let mut builder = OverrideBuilder::new(root);
+    builder
+        .case_insensitive(!req.case_sensitive)
+        .and_then(|builder| builder.add(glob))
+        .map_err(|e| anyhow::anyhow!("invalid glob: {e}"))?;
+    let glob = builder
+        .build()
+        .map_err(|e| anyhow::anyhow!("invalid glob: {e}"))?;
+    Ok(Some(glob))
+}
+
+fn build_pattern(req: &FsGrepReq) -> Result<Option<Regex>> {
+    let Some(pattern) = req.pattern.as_deref().filter(|p| !p.is_empty()) else {
+        return Ok(None);
+    };
+    RegexBuilder::new(pattern)
+        .case_insensitive(!req.case_sensitive)
+        .build()
+        .map(Some)
+        .map_err(|e| anyhow::anyhow!("invalid pattern: {e}"))
+}
+
+fn sample_lines(lines: &[&str]) -> Vec<String> {
+    lines
+        .iter()
+        .map(|line| truncate_sample(line.trim_end()))
+        .collect()
+}
+
+/// Content hits in `content` with up to `context` lines on each side, and
+/// whether the per-file cap cut them short.
+fn line_matches(
+    root: &Path,
+    path: &Path,
+    content: &str,
+    pattern: &Regex,
+    context: usize,
+) -> (Vec<FsGrepContextMatch>, bool) {
+    let all_lines: Vec<&str> = content.lines().collect();
+    let mut lines = all_lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| pattern.is_match(line));
+    let matches: Vec<FsGrepContextMatch> = lines
+        .by_ref()
+        .take(FS_GREP_MAX_MATCHES_PER_FILE as usize)
+        .map(|(index, line)| FsGrepContextMatch {
+            hit: FsGrepMatch {
+                path: path.to_string_lossy().into_owned(),
+                rel_path: rel_path(root, path),
+                line: Some(index as u32 + 1),
+                snippet: Some(truncate_sample(line.trim_end())),
+            },
+            before: sample_lines(&all_lines[index.saturating_sub(context)..index]),
+            after: sample_lines(&all_lines[index + 1..(index + 1 + context).min(all_lines.len())]),
+        })
+        .collect();
+    (matches, lines.next().is_some())
+}
+
+/// Walk `root` for `req`, passing hits to `emit` in batches. Content hits
+/// carry up to `context` lines on each side (clamped to
+/// `FS_GREP_MAX_CONTEXT_LINES`). `emit` returns false once the client is
+/// gone, which stops the walk. Returns whether a cap truncated the results.
+pub fn grep_files(
+    root: &Path,
+    req: &FsGrepReq,
+    context: u32,
+    mut emit: impl FnMut(Vec<FsGrepContextMatch>) -> bool,
+) -> Result<bool> {
+    anyhow::ensure!(root.is_dir(), "search path must be a directory");
+    let glob = build_glob(root, req)?;
+    let pattern = build_pattern(req)?;
+    anyhow::ensure!(
+        glob.is_some() || pattern.is_some(),
+        "a glob or pattern is required"
+    );
+    let limit = grep_limit(req.limit);
+    let context = context.min(FS_GREP_MAX_CONTEXT_LINES) as usize;
+
+    let mut builder = ignore::WalkBuilder::new(root);
+    builder
+        .hidden(false)
+        .follow_links(false)
+        .git_ignore(true)
+        .git_global(true)
+        .git_exclude(true)
+        .parents(true)
+        .ignore(true)
+        .filter_entry(|entry| !is_file_search_ignored(entry));
+
+    let mut batch = Vec::new();
+    let mut sent = 0usize;
+    let mut visited = 0u32;
+    let mut truncated = false;
+    for entry in builder.build() {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(error) => {
+                tracing::debug!("fs grep: skipping unreadable entry: {error}");
+                continue;
+            }
+        };
+        if entry.depth() == 0 {
+            continue;
+        }
+        visited += 1;
+        if visited > FS_GREP_MAX_VISITED_ENTRIES {
+            truncated = true;
+            break;
+        }
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+        let path = entry.path();
+        if glob
+            .as_ref()
+            .is_some_and(|glob| !glob.matched(path, false).is_whitelist())
+        {
+            continue;
+        }
+        let (matches, cut) = match &pattern {
+            Some(pattern) => match read_text(path) {
+                Some(content) => line_matches(root, path, &content, pattern, context),
+                None => continue,
+            },
+            None => (
+                vec![FsGrepContextMatch {
+                    hit: FsGrepMatch {
+                        path: path.to_string_lossy().into_owned(),
+                        rel_path: rel_path(root, path),
+                        line: None,
+                        snippet: None,
+                    },
+                    before: Vec::new(),
+                    after: Vec::new(),
+                }],
+                false,
+            ),
+        };
+        truncated |= cut;
+        for hit in matches {
+            if sent + batch.len() >= limit {
+                truncated = true;
+                break;
+            }
+            batch.push(hit);
+        }
+        if sent + batch.len() >= limit {
+            break;
+        }
+        if batch.len() >= BATCH_SIZE {
+            sent += batch.len();
+            if !emit(std::mem::take(&mut batch)) {
+                return Ok(truncated);
+            }
+        }
+    }
+    if !batch.is_empty() {
+        emit(batch);
+    }
+    Ok(truncated)
+}
+
+/// Up to `radius` lines (clamped to `FS_GREP_MAX_EXPAND_RADIUS`) on each side
+/// of 1-based `line` in the text file at `path`.
+pub fn expand_lines(path: &Path, line: u32, radius: u32) -> Result<FsGrepExpandResult> {
+    let content = read_text(path).ok_or_else(|| anyhow::anyhow!("not a readable text file"))?;
+    let lines: Vec<&str> = content.lines().collect();
+    anyhow::ensure!(
+        line >= 1 && line as usize <= lines.len(),
+        "line {line} is outside the file"
+    );
+    let radius = radius.min(FS_GREP_MAX_EXPAND_RADIUS) as usize;
+    let index = line as usize - 1;
+    let start = index.saturating_sub(radius);
+    let end = (index + 1 + radius).min(lines.len());
+    Ok(FsGrepExpandResult {
+        start_line: start as u32 + 1,
+        lines: sample_lines(&lines[start..end]),
+        total_lines: lines.len() as u32,
+        error: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grep(root: &Path, glob: Option<&str>, pattern: Option<&str>) -> (Vec<String>, bool) {
+        let req = FsGrepReq {
+            path: ".".into(),
+            glob: glob.map(Into::into),
+            pattern: pattern.map(Into::into),
+            case_sensitive: false,
+            limit: 0,
+        };
+        let mut hits = Vec::new();
+        let truncated = grep_files(root, &req, 0, |batch| {
+            hits.extend(
+                batch
+                    .into_iter()
+                    .map(|hit| hit.hit)
+                    .map(|hit| match hit.line {
+                        Some(line) => format!("{}:{line}:{}", hit.rel_path, hit.snippet.unwrap()),
+                        None => hit.rel_path,
+                    }),
+            );
+            true
+        })
+        .unwrap();
+        hits.sort();
+        (hits, truncated)
+    }
+
+    #[test]
+    fn matches_by_glob_and_content_respecting_gitignore() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join(".git")).unwrap();
+        std::fs::create_dir_all(root.join("src/net")).unwrap();
+        std::fs::create_dir_all(root.join("build")).unwrap();
+        std::fs::write(root.join(".gitignore"), "build/\n").unwrap();
+        std::fs::write(root.join("src/main.rs"), "fn main() {\n    todo!()\n}\n").unwrap();
+        std::fs::write(root.join("src/net/mod.rs"), "// TODO: retry\n").unwrap();
+        std::fs::write(root.join("README.md"), "todo list\n").unwrap();
+        std::fs::write(root.join("build/out.rs"), "todo\n").unwrap();
+
+        let (hits, _) = grep(root, Some("*.rs"), None);
+        assert_eq!(hits, ["src/main.rs", "src/net/mod.rs"]);
+
+        let (hits, truncated) = grep(root, Some("*.rs"), Some("todo"));
+        assert_eq!(
+            hits,
+            [
+                "src/main.rs:2:    todo!()",
+                "src/net/mod.rs:1:// TODO: retry"
+            ]
+        );
+        assert!(!truncated);
+
+        let (hits, _) = grep(root, None, Some("^todo"));
+        assert_eq!(hits, ["README.md:1:todo list"]);
+    }
+
+    #[test]
+    fn caps_matches_per_file_and_rejects_empty_queries() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("log.txt"), "hit\n".repeat(30)).unwrap();
+        let (hits, truncated) = grep(dir.path(), None, Some("hit"));
+        assert_eq!(hits.len(), FS_GREP_MAX_MATCHES_PER_FILE as usize);
+        assert!(truncated);
+
+        let req = FsGrepReq {
+            path: ".".into(),
+            glob: None,
+            pattern: Some("(".into()),
+            case_sensitive: true,
+            limit: 0,
+        };
+        assert!(grep_files(dir.path(), &req, 0, |_| true).is_err());
+        let req = FsGrepReq {
+            pattern: None,
+            ..req
+        };
+        assert!(grep_files(dir.path(), &req, 0, |_| true).is_err());
+    }
+
+    #[test]
+    fn includes_context_lines_and_expands_around_a_hit() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("lib.rs");
+        let content: String = (1..=9).map(|n| format!("line {n}\n")).collect();
+        std::fs::write(&path, content).unwrap();
+
+        let req = FsGrepReq {
+            path: ".".into(),
+            glob: None,
+            pattern: Some("^line [18]$".into()),
+            case_sensitive: true,
+            limit: 0,
+        };
+        let mut hits = Vec::new();
+        grep_files(dir.path(), &req, 2, |batch| {
+            hits.extend(batch);
+            true
+        })
+        .unwrap();
+        assert_eq!(hits.len(), 2);
+        assert!(hits[0].before.is_empty());
+        assert_eq!(hits[0].after, ["line 2", "line 3"]);
+        assert_eq!(hits[1].before, ["line 6", "line 7"]);
+        assert_eq!(hits[1].after, ["line 9"]);
+
+        let expanded = expand_lines(&path, 5, 3).unwrap();
+        assert_eq!(expanded.start_line, 2);
+        assert_eq!(expanded.lines.first().map(String::as_str), Some("line 2"));
+        assert_eq!(expanded.lines.last().map(String::as_str), Some("line 8"));
+        assert_eq!(expanded.total_lines, 9);
+
+        let expanded = expand_lines(&path, 9, 50).unwrap();
+        assert_eq!(expanded.start_line, 1);
+        assert_eq!(expanded.lines.len(), 9);
+        assert!(expand_lines(&path, 10, 1).is_err());
+    }
+}
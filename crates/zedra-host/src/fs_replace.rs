@@ -0,0 +1,436 @@
+// Host-side find-and-replace for `FsReplace`.
+//
+// Two steps over the same walk `FsSearch` uses (gitignore-aware, no symlinks,
+// generated directories skipped):
+//   preview: count matches per file and return sample lines plus a SHA-256
+//            fingerprint of the content each preview was computed from.
+//   apply:   rewrite only the previewed files, and only when their content
+//            still hashes to the previewed fingerprint. A file edited in the
+//            meantime (by the editor, an agent, or the user) is reported as
+//            `Conflict` and left untouched instead of being clobbered.
+
+use crate::fs::Filesystem;
+use crate::rpc_daemon::is_file_search_ignored;
+use crate::sandbox::resolve_path;
+use anyhow::Result;
+use regex::{NoExpand, Regex, RegexBuilder};
+use sha2::{Digest, Sha256};
+use std::path::{Component, Path};
+use zedra_rpc::proto::{
+    FsReplaceFile, FsReplaceReq, FsReplaceResult, FsReplaceSample, FsReplaceStatus,
+    FsReplaceTarget, FS_REPLACE_MAX_FILES, FS_REPLACE_SAMPLES_PER_FILE,
+    FS_SEARCH_MAX_VISITED_ENTRIES,
+};
+
+/// Files larger than this are skipped, matching the `FsRead` limit.
+const MAX_FILE_SIZE: u64 = 500 * 1024;
+/// Sample lines longer than this are cut so one minified file cannot bloat the preview.
+const MAX_SAMPLE_CHARS: usize = 200;
+
+pub fn fs_replace_error(message: String) -> FsReplaceResult {
+    FsReplaceResult {
+        files: vec![],
+        truncated: false,
+        error: Some(message),
+    }
+}
+
+/// Run a preview or apply step for `req` with `root` as the resolved search root.
+pub fn replace_in_files(
+    fs: &dyn Filesystem,
+    root: &Path,
+    req: &FsReplaceReq,
+) -> Result<FsReplaceResult> {
+    anyhow::ensure!(root.is_dir(), "replace path must be a directory");
+    let pattern = build_pattern(req)?;
+    if req.dry_run {
+        preview(root, &pattern, req)
+    } else {
+        apply(fs, root, &pattern, req)
+    }
+}
+
+fn build_pattern(req: &FsReplaceReq) -> Result<Regex> {
+    anyhow::ensure!(!req.find.is_empty(), "empty find pattern");
+    let source = if req.regex {
+        req.find.clone()
+    } else {
+        regex::escape(&req.find)
+    };
+    RegexBuilder::new(&source)
+        .case_insensitive(!req.case_sensitive)
+        .multi_line(true)
+        .build()
+        .map_err(|e| anyhow::anyhow!("invalid pattern: {e}"))
+}
+
+fn replace_all(pattern: &Regex, text: &str, req: &FsReplaceReq) -> String {
+    if req.regex {
+        pattern.replace_all(text, req.replace.as_str()).into_owned()
+    } else {
+        pattern
+            .replace_all(text, NoExpand(&req.replace))
+            .into_owned()
+    }
+}
+
+fn fingerprint(content: &str) -> String {
+    hex::encode(Sha256::digest(content.as_bytes()))
+}
+
+/// Read `path` as text, or `None` for oversized, unreadable, or binary files.
+pub(crate) fn read_text(path: &Path) -> Option<String> {
+    let meta = std::fs::metadata(path).ok()?;
+    if !meta.is_file() || meta.len() > MAX_FILE_SIZE {
+        return None;
+    }
+    let bytes = std::fs::read(path).ok()?;
+    if bytes.contains(&0) {
+        return None;
+    }
+    String::from_utf8(bytes).ok()
+}
+
+pub(crate) fn rel_path(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => Some(part.to_string_lossy().into_owned()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+pub(crate) fn truncate_sample(line: &str) -> String {
+    match line.char_indices().nth(MAX_SAMPLE_CHARS) {
+        Some((cut, _)) => format!("{}…", &line[..cut]),
+        None => line.to_string(),
+    }
+}
+
+fn preview_file(
+    root: &Path,
+    path: &Path,
+    content: &str,
+    pattern: &Regex,
+    req: &FsReplaceReq,
+) -> Option<FsReplaceFile> {
+    let matches = pattern.find_iter(content).count() as u32;
+    if matches == 0 {
+        return None;
+    }
+    let samples = content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| pattern.is_match(line))
+        .take(FS_REPLACE_SAMPLES_PER_FILE)
+        .map(|(index, line)| FsReplaceSample {
+            line: index as u32 + 1,
+            before: truncate_sample(line),
+            after: truncate_sample(&replace_all(pattern, line, req)),
+        })
+        .collect();
+    Some(FsReplaceFile {
+        path: path.to_string_lossy().into_owned(),
+        rel_path: rel_path(root, path),
+        matches,
+        samples,
+        fingerprint: fingerprint(content),
+        status: FsReplaceStatus::Preview,
+    })
+}
+
+fn preview(root: &Path, pattern: &Regex, req: &FsReplaceReq) -> Result<FsReplaceResult> {
+    let mut builder = ignore::WalkBuilder::new(root);
+    builder
+        .hidden(false)
+        .follow_links(false)
+        .git_ignore(true)
+        .git_global(true)
+        .git_exclude(true)
+        .parents(true)
+        .ignore(true)
+        .filter_entry(|entry| !is_file_search_ignored(entry));
+
+    let mut files = Vec::new();
+    let mut visited = 0u32;
+    let mut truncated = false;
+    for entry in builder.build() {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(error) => {
+                tracing::debug!("fs replace: skipping unreadable entry: {error}");
+                continue;
+            }
+        };
+        if entry.depth() == 0 {
+            continue;
+        }
+        visited += 1;
+        if visited > FS_SEARCH_MAX_VISITED_ENTRIES {
+            truncated = true;
+            break;
+        }
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+        let Some(content) = read_text(entry.path()) else {
+            continue;
+        };
+        if let Some(file) = preview_file(root, entry.path(), &content, pattern, req) {
+            if files.len() as u32 >= FS_REPLACE_MAX_FILES {
+                truncated = true;
+                break;
+            }
+            files.push(file);
+        }
+    }
+    files.sort_by(|left, right| left.rel_path.cmp(&right.rel_path));
+
+    Ok(FsReplaceResult {
+        files,
+        truncated,
+        error: None,
+    })
+}
+
+fn apply(
+    fs: &dyn Filesystem,
+    root: &Path,
+    pattern: &Regex,
+    req: &FsReplaceReq,
+) -> Result<FsReplaceResult> {
+    anyhow::ensure!(!req.files.is_empty(), "no files to apply");
+    anyhow::ensure!(
+        req.files.len() as u32 <= FS_REPLACE_MAX_FILES,
+        "too many files in one apply ({} > {FS_REPLACE_MAX_FILES})",
+        req.files.len()
+    );
+    let files = req
+        .files
+        .iter()
+        .map(|target| apply_file(fs, root, pattern, req, target))
+        .collect();
+    Ok(FsReplaceResult {
+        files,
+        truncated: false,
+        error: None,
+    })
+}
+
+fn apply_file(
+    fs: &dyn Filesystem,
+    root: &Path,
+    pattern: &Regex,
+    req: &FsReplaceReq,
+    target: &FsReplaceTarget,
+) -> FsReplaceFile {
+    let mut file = FsReplaceFile {
+        path: String::new(),
+        rel_path: target.rel_path.clone(),
+        matches: 0,
+        samples: vec![],
+        fingerprint: target.fingerprint.clone(),
+        status: FsReplaceStatus::Conflict,
+    };
+    // Same jail as every other fs RPC; the preview only ever returns paths under root.
+    let path = match resolve_path(root, &target.rel_path) {
+        Ok(path) => path,
+        Err(e) => {
+            file.status = FsReplaceStatus::Failed(e.to_string());
+            return file;
+        }
+    };
+    file.path = path.to_string_lossy().into_owned();
+
+    let Some(content) = read_text(&path) else {
+        file.status = FsReplaceStatus::Failed("not a readable text file".to_string());
+        return file;
+    };
+    let current = fingerprint(&content);
+    if current != target.fingerprint {
+        tracing::info!("FsReplace: {} changed since preview", target.rel_path);
+        file.fingerprint = current;
+        return file;
+    }
+    file.matches = pattern.find_iter(&content).count() as u32;
+    if file.matches == 0 {
+        return file;
+    }
+
+    let replaced = replace_all(pattern, &content, req);
+    match fs.write(&path, &replaced) {
+        Ok(()) => {
+            file.fingerprint = fingerprint(&replaced);
+            file.status = FsReplaceStatus::Applied;
+        }
+        Err(e) => {
+            tracing::warn!("FsReplace: write failed for {}: {}", target.rel_path, e);
+            file.status = FsReplaceStatus::Failed(e.to_string());
+        }
+    }
+    file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::LocalFs;
+    use std::fs::{create_dir_all, read_to_string, write};
+
+    fn req(find: &str, replace: &str, regex: bool, dry_run: bool) -> FsReplaceReq {
+        FsReplaceReq {
+            path: ".".to_string(),
+            find: find.to_string(),
+            replace: replace.to_string(),
+            regex,
+            case_sensitive: true,
+            dry_run,
+            files: vec![],
+        }
+    }
+
+    fn targets(preview: &FsReplaceResult) -> Vec<FsReplaceTarget> {
+        preview
+            .files
+            .iter()
+            .map(|file| FsReplaceTarget {
+                rel_path: file.rel_path.clone(),
+                fingerprint: file.fingerprint.clone(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn preview_counts_matches_and_skips_ignored_and_binary_files() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path().canonicalize().unwrap();
+        create_dir_all(root.join(".git")).unwrap();
+        create_dir_all(root.join("src")).unwrap();
+        create_dir_all(root.join("ignored")).unwrap();
+        write(root.join(".gitignore"), "ignored/\n").unwrap();
+        write(root.join("src/a.rs"), "let old = 1;\nold(old);\nnew\n").unwrap();
+        write(root.join("src/b.rs"), "nothing here\n").unwrap();
+        write(root.join("ignored/c.rs"), "old\n").unwrap();
+        write(root.join("blob.bin"), b"old\0old").unwrap();
+
+        let result = replace_in_files(&LocalFs, &root, &req("old", "new", false, true)).unwrap();
+
+        assert_eq!(result.files.len(), 1);
+        let file = &result.files[0];
+        assert_eq!(file.rel_path, "src/a.rs");
+        assert_eq!(file.matches, 3);
+        assert_eq!(file.status, FsReplaceStatus::Preview);
+        assert_eq!(
+            file.samples,
+            vec![
+                FsReplaceSample {
+                    line: 1,
+                    before: "let old = 1;".to_string(),
+                    after: "let new = 1;".to_string(),
+                },
+                FsReplaceSample {
+                    line: 2,
+                    before: "old(old);".to_string(),
+                    after: "new(new);".to_string(),
+                },
+            ]
+        );
+        // Dry run never writes.
+        assert!(read_to_string(root.join("src/a.rs"))
+            .unwrap()
+            .starts_with("let old"));
+    }
+
+    #[test]
+    fn apply_rewrites_previewed_files_with_regex_groups() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path().canonicalize().unwrap();
+        write(root.join("lib.rs"), "fn load_old() {}\nfn save_old() {}\n").unwrap();
+
+        let preview =
+            replace_in_files(&LocalFs, &root, &req(r"(\w+)_old", "${1}_new", true, true)).unwrap();
+        let mut apply = req(r"(\w+)_old", "${1}_new", true, false);
+        apply.files = targets(&preview);
+        let result = replace_in_files(&LocalFs, &root, &apply).unwrap();
+
+        assert_eq!(result.files[0].status, FsReplaceStatus::Applied);
+        assert_eq!(result.files[0].matches, 2);
+        assert_eq!(
+            read_to_string(root.join("lib.rs")).unwrap(),
+            "fn load_new() {}\nfn save_new() {}\n"
+        );
+    }
+
+    #[test]
+    fn literal_replacement_does_not_expand_groups() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path().canonicalize().unwrap();
+        write(root.join("price.txt"), "cost: 5 (USD)\n").unwrap();
+
+        let preview = replace_in_files(&LocalFs, &root, &req("(USD)", "$1", false, true)).unwrap();
+        let mut apply = req("(USD)", "$1", false, false);
+        apply.files = targets(&preview);
+        replace_in_files(&LocalFs, &root, &apply).unwrap();
+
+        assert_eq!(
+            read_to_string(root.join("price.txt")).unwrap(),
+            "cost: 5 $1\n"
+        );
+    }
+
+    #[test]
+    fn apply_skips_files_changed_since_preview() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path().canonicalize().unwrap();
+        write(root.join("a.txt"), "old\n").unwrap();
+        write(root.join("b.txt"), "old\n").unwrap();
+
+        let preview = replace_in_files(&LocalFs, &root, &req("old", "new", false, true)).unwrap();
+        // Edited in the editor between preview and apply.
+        write(root.join("b.txt"), "old, edited\n").unwrap();
+        let mut apply = req("old", "new", false, false);
+        apply.files = targets(&preview);
+        let result = replace_in_files(&LocalFs, &root, &apply).unwrap();
+
+        let status: Vec<_> = result.files.iter().map(|f| f.status.clone()).collect();
+        assert_eq!(
+            status,
+            vec![FsReplaceStatus::Applied, FsReplaceStatus::Conflict]
+        );
+        assert_eq!(read_to_string(root.join("a.txt")).unwrap(), "new\n");
+        assert_eq!(read_to_string(root.join("b.txt")).unwrap(), "old, edited\n");
+    }
+
+    #[test]
+    fn apply_rejects_paths_outside_the_root() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path().join("work");
+        create_dir_all(&root).unwrap();
+        let root = root.canonicalize().unwrap();
+        write(temp.path().join("secret.txt"), "old\n").unwrap();
+
+        let mut apply = req("old", "new", false, false);
+        apply.files = vec![FsReplaceTarget {
+            rel_path: "../secret.txt".to_string(),
+            fingerprint: fingerprint("old\n"),
+        }];
+        let result = replace_in_files(&LocalFs, &root, &apply).unwrap();
+
+        assert!(matches!(result.files[0].status, FsReplaceStatus::Failed(_)));
+        assert_eq!(
+            read_to_string(temp.path().join("secret.txt")).unwrap(),
+            "old\n"
+        );
+    }
+
+    #[test]
+    fn invalid_or_empty_patterns_are_errors() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path().canonicalize().unwrap();
+        assert!(replace_in_files(&LocalFs, &root, &req("", "x", false, true)).is_err());
+        assert!(replace_in_files(&LocalFs, &root, &req("(", "x", true, true)).is_err());
+    }
+}
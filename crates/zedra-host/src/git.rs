@@ -8,6 +8,8 @@ use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::process::Command;
 
+use crate::sandbox::{check_pathspec, check_pathspecs};
+
 // ---------------------------------------------------------------------------
 // Types
 // ---------------------------------------------------------------------------
@@ -38,12 +40,39 @@ pub struct LogEntry {
     pub timestamp: i64,
 }
 
+/// Per-file line counts from `git diff --numstat`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DiffStat {
+    pub path: String,
+    pub additions: u32,
+    pub deletions: u32,
+    /// Binary files report no line counts.
+    pub binary: bool,
+}
+
+/// Untracked files larger than this are counted as binary instead of read.
+const UNTRACKED_STAT_MAX_BYTES: u64 = 1024 * 1024;
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct BranchInfo {
     pub name: String,
     pub is_head: bool,
 }
 
+/// How `GitRepo::merge` ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MergeOutcome {
+    UpToDate,
+    FastForward {
+        head: String,
+    },
+    Merged {
+        head: String,
+    },
+    /// The merge stopped on these paths and was aborted.
+    Conflicts(Vec<String>),
+}
+
 // ---------------------------------------------------------------------------
 // GitRepo
 // ---------------------------------------------------------------------------
@@ -68,7 +97,11 @@ impl GitRepo {
         Ok(Self { workdir })
     }
 
-    fn git(&self, args: &[&str]) -> Result<String> {
+    pub(crate) fn workdir(&self) -> &std::path::Path {
+        &self.workdir
+    }
+
+    pub(crate) fn git(&self, args: &[&str]) -> Result<String> {
         let output = Command::new("git")
             .args(args)
             .current_dir(&self.workdir)
@@ -92,6 +125,27 @@ impl GitRepo {
         Ok(out.trim().to_string())
     }
 
+    /// Fetch URL of `remote`.
+    pub fn remote_url(&self, remote: &str) -> Result<String> {
+        if !is_safe_ref(remote) {
+            anyhow::bail!("invalid remote name: {remote}");
+        }
+        let out = self.git(&["remote", "get-url", remote])?;
+        Ok(out.trim().to_string())
+    }
+
+    /// Whether `branch` has a remote-tracking ref under `remote`, i.e. was
+    /// pushed or fetched.
+    pub fn has_remote_branch(&self, remote: &str, branch: &str) -> Result<bool> {
+        if !is_safe_ref(remote) || !is_safe_ref(branch) {
+            anyhow::bail!("invalid ref: {remote}/{branch}");
+        }
+        let tracking_ref = format!("refs/remotes/{remote}/{branch}");
+        Ok(self
+            .git(&["rev-parse", "--verify", "--quiet", &tracking_ref])
+            .is_ok())
+    }
+
     /// Working tree status.
     pub fn status(&self) -> Result<Vec<StatusEntry>> {
         // `-z` gives NUL-delimited records with unquoted paths; the default
@@ -136,6 +190,9 @@ impl GitRepo {
 
     /// Diff output.
     pub fn diff(&self, path: Option<&str>, staged: bool) -> Result<String> {
+        if let Some(path) = path {
+            check_pathspec(path)?;
+        }
         if !staged {
             if let Some(path) = path {
                 if self.is_untracked_path(path)? {
@@ -155,6 +212,50 @@ impl GitRepo {
         self.git(&args)
     }
 
+    /// Line counts per changed file, without the diff bodies.
+    pub fn diff_stat(&self, staged: bool) -> Result<Vec<DiffStat>> {
+        let mut args = vec!["diff", "--numstat", "-z"];
+        if staged {
+            args.push("--cached");
+        }
+        Ok(parse_numstat_z(&self.git(&args)?))
+    }
+
+    /// Line counts for untracked files, read directly from the working tree.
+    pub fn untracked_stat(&self, paths: &[String]) -> Vec<DiffStat> {
+        paths
+            .iter()
+            .map(|path| {
+                let full = self.workdir.join(path);
+                let text = std::fs::metadata(&full)
+                    .ok()
+                    .filter(|meta| meta.is_file() && meta.len() <= UNTRACKED_STAT_MAX_BYTES)
+                    .and_then(|_| std::fs::read(&full).ok())
+                    .filter(|bytes| !bytes.contains(&0));
+                match text {
+                    Some(bytes) => {
+                        let mut lines = bytes.iter().filter(|&&b| b == b'\n').count();
+                        if bytes.last().is_some_and(|&b| b != b'\n') {
+                            lines += 1;
+                        }
+                        DiffStat {
+                            path: path.clone(),
+                            additions: lines as u32,
+                            deletions: 0,
+                            binary: false,
+                        }
+                    }
+                    None => DiffStat {
+                        path: path.clone(),
+                        additions: 0,
+                        deletions: 0,
+                        binary: true,
+                    },
+                }
+            })
+            .collect()
+    }
+
     fn is_untracked_path(&self, path: &str) -> Result<bool> {
         let output = Command::new("git")
             .args(["ls-files", "--others", "--exclude-standard", "--", path])
@@ -230,14 +331,101 @@ impl GitRepo {
         Ok(())
     }
 
-    /// Stage files and commit.
+    /// Create a branch at `from` (default `HEAD`), optionally checking it out.
+    pub fn create_branch(&self, name: &str, from: Option<&str>, checkout: bool) -> Result<()> {
+        anyhow::ensure!(is_safe_ref(name), "invalid branch name: {:?}", name);
+        if let Some(from) = from {
+            anyhow::ensure!(is_safe_ref(from), "invalid start point: {:?}", from);
+        }
+        let mut args: Vec<&str> = if checkout {
+            vec!["checkout", "-b", name]
+        } else {
+            vec!["branch", name]
+        };
+        args.extend(from);
+        self.git_ok(&args)
+    }
+
+    /// Delete a local branch. Without `force`, git refuses a branch that is
+    /// not merged into its upstream or `HEAD`.
+    pub fn delete_branch(&self, name: &str, force: bool) -> Result<()> {
+        anyhow::ensure!(is_safe_ref(name), "invalid branch name: {:?}", name);
+        self.git_ok(&["branch", if force { "-D" } else { "-d" }, name])
+    }
+
+    /// Merge `branch` into the current branch. A merge that stops on
+    /// conflicts is aborted, leaving the branch as it was.
+    pub fn merge(&self, branch: &str) -> Result<MergeOutcome> {
+        anyhow::ensure!(is_safe_ref(branch), "invalid branch name: {:?}", branch);
+        let before = self.git(&["rev-parse", "HEAD"])?.trim().to_string();
+        if let Err(e) = self.git(&["merge", "--no-edit", branch]) {
+            let conflicts: Vec<String> = self
+                .git(&["diff", "--name-only", "--diff-filter=U"])
+                .map(|out| out.lines().map(str::to_string).collect())
+                .unwrap_or_default();
+            if conflicts.is_empty() {
+                return Err(e);
+            }
+            self.git_ok(&["merge", "--abort"])?;
+            return Ok(MergeOutcome::Conflicts(conflicts));
+        }
+        let head = self.git(&["rev-parse", "HEAD"])?.trim().to_string();
+        if head == before {
+            return Ok(MergeOutcome::UpToDate);
+        }
+        let merged = self.git(&["rev-parse", branch])?;
+        if merged.trim() == head {
+            Ok(MergeOutcome::FastForward { head })
+        } else {
+            Ok(MergeOutcome::Merged { head })
+        }
+    }
+
+    /// Stash working tree and index changes.
+    ///
+    /// Returns `false` when there was nothing to stash.
+    pub fn stash(&self, message: Option<&str>, include_untracked: bool) -> Result<bool> {
+        let before = self.stash_head();
+        let mut args: Vec<&str> = vec!["stash", "push"];
+        if include_untracked {
+            args.push("--include-untracked");
+        }
+        if let Some(message) = message {
+            args.extend(["-m", message]);
+        }
+        self.git_ok(&args)?;
+        Ok(self.stash_head() != before)
+    }
+
+    fn stash_head(&self) -> Option<String> {
+        self.git(&["rev-parse", "-q", "--verify", "refs/stash"])
+            .ok()
+            .map(|out| out.trim().to_string())
+    }
+
+    /// Throw away all tracked changes (index and working tree), and untracked
+    /// files too when `include_untracked` is set. Ignored files are kept.
+    pub fn discard_all(&self, include_untracked: bool) -> Result<()> {
+        self.git_ok(&["reset", "--hard", "HEAD"])?;
+        if include_untracked {
+            self.git_ok(&["clean", "-fd"])?;
+        }
+        Ok(())
+    }
+
+    /// Stage files and commit. Empty `paths` commits the index as it stands,
+    /// so hunks staged separately from the working tree are kept apart.
     ///
     /// `--` is inserted before all user-supplied paths to prevent flag injection.
     pub fn commit(&self, message: &str, paths: &[String]) -> Result<String> {
         if paths.is_empty() {
-            anyhow::bail!("no paths to commit");
+            // `diff --quiet` exits non-zero when the index differs from HEAD.
+            if self.git(&["diff", "--cached", "--quiet"]).is_ok() {
+                anyhow::bail!("nothing staged to commit");
+            }
+        } else {
+            self.stage(paths)?;
         }
-        self.stage(paths)?;
         self.git(&["commit", "-m", message])?;
         let out = self.git(&["rev-parse", "HEAD"])?;
         Ok(out.trim().to_string())
@@ -248,6 +436,7 @@ impl GitRepo {
         if paths.is_empty() {
             anyhow::bail!("no paths to stage");
         }
+        check_pathspecs(paths)?;
         let mut add_args: Vec<&str> = vec!["add", "--"];
         for path in paths {
             add_args.push(path.as_str());
@@ -260,6 +449,7 @@ impl GitRepo {
         if paths.is_empty() {
             anyhow::bail!("no paths to unstage");
         }
+        check_pathspecs(paths)?;
 
         let mut reset_args: Vec<&str> = vec!["reset", "HEAD", "--"];
         for path in paths {
@@ -284,6 +474,38 @@ impl GitRepo {
     }
 }
 
+/// Parse `git diff --numstat -z`: `add\tdel\tpath\0`, or `add\tdel\t\0old\0new\0`
+/// for renames. Binary files report `-` for both counts.
+fn parse_numstat_z(out: &str) -> Vec<DiffStat> {
+    let mut stats = Vec::new();
+    let mut fields = out.split('\0');
+    while let Some(record) = fields.next() {
+        let mut parts = record.splitn(3, '\t');
+        let (Some(added), Some(deleted), Some(path)) = (parts.next(), parts.next(), parts.next())
+        else {
+            continue;
+        };
+        let path = if path.is_empty() {
+            // Rename: skip the old path and report under the new one.
+            fields.next();
+            match fields.next() {
+                Some(new_path) => new_path,
+                None => break,
+            }
+        } else {
+            path
+        };
+        let binary = added == "-" && deleted == "-";
+        stats.push(DiffStat {
+            path: path.to_string(),
+            additions: added.parse().unwrap_or(0),
+            deletions: deleted.parse().unwrap_or(0),
+            binary,
+        });
+    }
+    stats
+}
+
 fn parse_status_code(code: char) -> Option<FileStatus> {
     match code {
         ' ' => None,
@@ -301,7 +523,7 @@ fn parse_status_code(code: char) -> Option<FileStatus> {
 ///
 /// Allows alphanumerics, `/`, `_`, `.`, `-`. Rejects anything that could be
 /// interpreted as a flag (leading `-`) or shell metacharacter.
-fn is_safe_ref(s: &str) -> bool {
+pub(crate) fn is_safe_ref(s: &str) -> bool {
     !s.is_empty()
         && !s.starts_with('-')
         && s.chars()
@@ -436,6 +658,73 @@ mod tests {
         assert_eq!(log[0].author, "Test");
     }
 
+    #[test]
+    fn commit_without_paths_keeps_unstaged_changes() {
+        let (dir, repo) = init_repo();
+        std::fs::write(dir.path().join("a.txt"), "one\n").unwrap();
+        std::fs::write(dir.path().join("b.txt"), "one\n").unwrap();
+        repo.commit("initial commit", &["a.txt".into(), "b.txt".into()])
+            .unwrap();
+        assert!(repo.commit("empty", &[]).is_err());
+
+        std::fs::write(dir.path().join("a.txt"), "two\n").unwrap();
+        std::fs::write(dir.path().join("b.txt"), "two\n").unwrap();
+        repo.stage(&["a.txt".into()]).unwrap();
+        repo.commit("update a", &[]).unwrap();
+
+        let status = repo.status().unwrap();
+        assert_eq!(status.len(), 1);
+        assert_eq!(status[0].path, "b.txt");
+        assert_eq!(status[0].staged_status, None);
+        assert_eq!(status[0].unstaged_status, Some(FileStatus::Modified));
+    }
+
+    #[test]
+    fn merge_reports_fast_forward_merge_and_conflicts() {
+        let (dir, repo) = init_repo();
+        let write = |content: &str| std::fs::write(dir.path().join("a.txt"), content).unwrap();
+        write("base\n");
+        repo.commit("base", &["a.txt".into()]).unwrap();
+        let main = repo.branch().unwrap();
+
+        repo.create_branch("feature", None, true).unwrap();
+        write("feature\n");
+        repo.commit("feature", &["a.txt".into()]).unwrap();
+        repo.checkout(&main).unwrap();
+        assert!(matches!(
+            repo.merge("feature").unwrap(),
+            MergeOutcome::FastForward { .. }
+        ));
+        assert_eq!(repo.merge("feature").unwrap(), MergeOutcome::UpToDate);
+
+        repo.create_branch("other", None, true).unwrap();
+        std::fs::write(dir.path().join("b.txt"), "b\n").unwrap();
+        repo.commit("other", &["b.txt".into()]).unwrap();
+        repo.checkout(&main).unwrap();
+        write("main\n");
+        repo.commit("main", &["a.txt".into()]).unwrap();
+        assert!(matches!(
+            repo.merge("other").unwrap(),
+            MergeOutcome::Merged { .. }
+        ));
+
+        repo.create_branch("clash", Some("feature"), true).unwrap();
+        write("clash\n");
+        repo.commit("clash", &["a.txt".into()]).unwrap();
+        repo.checkout(&main).unwrap();
+        let head = repo.git(&["rev-parse", "HEAD"]).unwrap();
+        assert_eq!(
+            repo.merge("clash").unwrap(),
+            MergeOutcome::Conflicts(vec!["a.txt".into()])
+        );
+        assert_eq!(repo.git(&["rev-parse", "HEAD"]).unwrap(), head);
+        assert!(repo.status().unwrap().is_empty());
+
+        assert!(repo.delete_branch("clash", false).is_err());
+        repo.delete_branch("clash", true).unwrap();
+        repo.delete_branch("feature", false).unwrap();
+    }
+
     #[test]
     fn diff_modified() {
         let (dir, repo) = init_repo();
@@ -461,6 +750,50 @@ mod tests {
         assert!(diff.contains("+world"));
     }
 
+    #[test]
+    fn diff_stat_counts_staged_and_unstaged_lines() {
+        let (dir, repo) = init_repo();
+        std::fs::write(dir.path().join("a.txt"), "one\ntwo\n").unwrap();
+        repo.commit("add a", &["a.txt".into()]).unwrap();
+
+        std::fs::write(dir.path().join("a.txt"), "one\nthree\nfour\n").unwrap();
+        let unstaged = repo.diff_stat(false).unwrap();
+        assert_eq!(
+            unstaged,
+            vec![DiffStat {
+                path: "a.txt".into(),
+                additions: 2,
+                deletions: 1,
+                binary: false,
+            }]
+        );
+        assert!(repo.diff_stat(true).unwrap().is_empty());
+
+        repo.stage(&["a.txt".into()]).unwrap();
+        assert_eq!(repo.diff_stat(true).unwrap(), unstaged);
+    }
+
+    #[test]
+    fn numstat_parses_renames_and_binary_files() {
+        let out = "3\t1\tsrc/lib.rs\0-\t-\tlogo.png\00\t0\t\0old name.rs\0new name.rs\0";
+        let stats = parse_numstat_z(out);
+        assert_eq!(stats.len(), 3);
+        assert_eq!((stats[0].additions, stats[0].deletions), (3, 1));
+        assert!(stats[1].binary);
+        assert_eq!(stats[2].path, "new name.rs");
+    }
+
+    #[test]
+    fn untracked_stat_counts_lines() {
+        let (dir, repo) = init_repo();
+        std::fs::write(dir.path().join("new.txt"), "a\nb\nc").unwrap();
+        std::fs::write(dir.path().join("blob.bin"), [0u8, 1, 2]).unwrap();
+        let stats = repo.untracked_stat(&["new.txt".into(), "blob.bin".into()]);
+        assert_eq!(stats[0].additions, 3);
+        assert!(!stats[0].binary);
+        assert!(stats[1].binary);
+    }
+
     #[test]
     fn branches_list() {
         let (dir, repo) = init_repo();
@@ -488,6 +821,42 @@ mod tests {
         assert_eq!(repo.branch().unwrap(), "feature");
     }
 
+    #[test]
+    fn create_branch_from_head() {
+        let (dir, repo) = init_repo();
+        std::fs::write(dir.path().join("f.txt"), "x").unwrap();
+        repo.commit("init", &["f.txt".into()]).unwrap();
+        let base = repo.branch().unwrap();
+
+        repo.create_branch("topic", None, false).unwrap();
+        assert_eq!(repo.branch().unwrap(), base);
+        repo.create_branch("feature/x", Some(&base), true).unwrap();
+        assert_eq!(repo.branch().unwrap(), "feature/x");
+        assert!(repo.create_branch("--force", None, true).is_err());
+        assert!(repo.create_branch("topic", None, true).is_err());
+    }
+
+    #[test]
+    fn stash_and_discard_clean_the_tree() {
+        let (dir, repo) = init_repo();
+        std::fs::write(dir.path().join("f.txt"), "x").unwrap();
+        repo.commit("init", &["f.txt".into()]).unwrap();
+        assert!(!repo.stash(None, false).unwrap());
+
+        std::fs::write(dir.path().join("f.txt"), "changed").unwrap();
+        assert!(repo.stash(Some("before switch"), false).unwrap());
+        assert!(repo.status().unwrap().is_empty());
+
+        std::fs::write(dir.path().join("f.txt"), "changed again").unwrap();
+        std::fs::write(dir.path().join("new.txt"), "new").unwrap();
+        repo.discard_all(false).unwrap();
+        let status = repo.status().unwrap();
+        assert_eq!(status.len(), 1);
+        assert_eq!(status[0].path, "new.txt");
+        repo.discard_all(true).unwrap();
+        assert!(repo.status().unwrap().is_empty());
+    }
+
     #[test]
     fn status_entry_serde() {
         let entry = StatusEntry {
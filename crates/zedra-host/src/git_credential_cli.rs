@@ -0,0 +1,38 @@
+use std::io::Read as _;
+
+use anyhow::{Context, Result};
+use clap::Args;
+use zedra_host::git_push::{format_credential, parse_credential, CredentialFields};
+
+use crate::terminal_cli::{api_post, resolve_workdir};
+
+#[derive(Debug, Args)]
+pub struct GitCredentialArgs {
+    /// Helper operation git passes: `get`, `store`, or `erase`
+    pub operation: String,
+
+    /// Working directory of the running daemon
+    #[arg(short, long, default_value = ".")]
+    pub workdir: String,
+
+    /// Session whose client is asked for credentials
+    #[arg(long)]
+    pub session: String,
+}
+
+/// Git credential helper set by the daemon for `GitPush`; not for direct use.
+pub async fn run(args: GitCredentialArgs) -> Result<()> {
+    let mut input = String::new();
+    std::io::stdin()
+        .read_to_string(&mut input)
+        .context("failed to read credential request")?;
+    let workdir = resolve_workdir(&args.workdir);
+    let body = serde_json::json!({
+        "session_id": args.session,
+        "operation": args.operation,
+        "credential": parse_credential(&input),
+    });
+    let answer: CredentialFields = api_post(&workdir, "/api/git-credential", &body).await?;
+    print!("{}", format_credential(&answer));
+    Ok(())
+}
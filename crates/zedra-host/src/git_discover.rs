@@ -0,0 +1,275 @@
+// Repository discovery for `GitDiscover` and `GitSelectRepo`.
+//
+// Serving a directory that holds several checkouts (or none yet) leaves the
+// git RPCs with nothing to open. Discovery walks a few levels below the
+// workdir for directories with a `.git` entry (a directory, or the file a
+// submodule or worktree uses) and stops descending at each one found. The
+// session's selection is a workdir-relative path the git handlers join onto
+// the workdir; it never leaves the workdir.
+
+use std::path::{Component, Path};
+use std::process::Command;
+
+use anyhow::{bail, Context, Result};
+use zedra_rpc::proto::{
+    GitDiscoverResult, GitDiscoveredRepo, GitSelectRepoResult, GIT_DISCOVER_MAX_DEPTH,
+    GIT_DISCOVER_MAX_REPOS,
+};
+
+use crate::git::GitRepo;
+use crate::rpc_daemon::is_file_search_ignored;
+
+/// Repositories at or below `root`; `selected` is echoed back.
+pub fn discover(root: &Path, selected: Option<String>) -> GitDiscoverResult {
+    let workdir = GitRepo::open(root).ok();
+    let workdir_is_repo = workdir.is_some();
+    let mut repositories = Vec::new();
+    if let Some(repo) = workdir {
+        repositories.push(discovered(&repo, String::new()));
+    }
+
+    let mut builder = ignore::WalkBuilder::new(root);
+    builder
+        .hidden(false)
+        .follow_links(false)
+        // Nested checkouts are commonly ignored by the enclosing repository.
+        .git_ignore(false)
+        .git_global(false)
+        .git_exclude(false)
+        .ignore(false)
+        .parents(false)
+        .max_depth(Some(GIT_DISCOVER_MAX_DEPTH))
+        .filter_entry(|entry| {
+            entry.file_type().is_some_and(|t| t.is_dir())
+                && !is_file_search_ignored(entry)
+                && (entry.depth() <= 1
+                    || !entry
+                        .path()
+                        .parent()
+                        .is_some_and(|parent| parent.join(".git").exists()))
+        });
+
+    let mut truncated = false;
+    for entry in builder.build() {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(error) => {
+                tracing::debug!("git discover: skipping unreadable entry: {error}");
+                continue;
+            }
+        };
+        if entry.depth() == 0 || !entry.path().join(".git").exists() {
+            continue;
+        }
+        let Ok(repo) = GitRepo::open(entry.path()) else {
+            continue;
+        };
+        if repositories.len() >= GIT_DISCOVER_MAX_REPOS {
+            truncated = true;
+            break;
+        }
+        let Some(path) = relative_path(root, entry.path()) else {
+            continue;
+        };
+        repositories.push(discovered(&repo, path));
+    }
+    repositories.sort_by(|a, b| a.path.cmp(&b.path));
+
+    GitDiscoverResult {
+        workdir_is_repo,
+        repositories,
+        selected,
+        truncated,
+        error: None,
+    }
+}
+
+/// Validate `path` (running `git init` there first when asked) and return it
+/// normalized for storing as the session's selection.
+pub fn select(root: &Path, path: &str, init: bool) -> Result<String> {
+    let mut normalized = Vec::new();
+    for component in Path::new(path).components() {
+        match component {
+            Component::Normal(part) => normalized.push(
+                part.to_str()
+                    .context("repository path is not valid UTF-8")?
+                    .to_string(),
+            ),
+            Component::CurDir => {}
+            _ => bail!("repository path must stay inside the workdir: {path}"),
+        }
+    }
+    let normalized = normalized.join("/");
+    let dir = root.join(&normalized);
+    let canonical_root = root.canonicalize().context("workdir is not accessible")?;
+    let canonical_dir = dir
+        .canonicalize()
+        .with_context(|| format!("no such directory: {normalized}"))?;
+    if !canonical_dir.starts_with(&canonical_root) {
+        bail!("repository path must stay inside the workdir: {path}");
+    }
+    if !canonical_dir.is_dir() {
+        bail!("not a directory: {normalized}");
+    }
+
+    if init {
+        let output = Command::new("git")
+            .arg("init")
+            .current_dir(&dir)
+            .output()
+            .context("git not found")?;
+        if !output.status.success() {
+            bail!(
+                "git init: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+    }
+    GitRepo::open(&dir)?;
+    Ok(normalized)
+}
+
+pub fn select_error(error: String) -> GitSelectRepoResult {
+    GitSelectRepoResult {
+        error: Some(error),
+        ..Default::default()
+    }
+}
+
+fn discovered(repo: &GitRepo, path: String) -> GitDiscoveredRepo {
+    let branch = repo.branch().unwrap_or_default();
+    // Prints the superproject's root only inside a submodule checkout.
+    let submodule = repo
+        .git(&["rev-parse", "--show-superproject-working-tree"])
+        .is_ok_and(|out| !out.trim().is_empty());
+    GitDiscoveredRepo {
+        path,
+        branch,
+        submodule,
+    }
+}
+
+fn relative_path(root: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(root).ok()?;
+    let parts: Option<Vec<&str>> = relative
+        .components()
+        .map(|component| match component {
+            Component::Normal(part) => part.to_str(),
+            _ => None,
+        })
+        .collect();
+    Some(parts?.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {args:?} failed");
+    }
+
+    fn paths(result: &GitDiscoverResult) -> Vec<&str> {
+        result
+            .repositories
+            .iter()
+            .map(|repo| repo.path.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn discover_finds_nested_repositories_without_descending() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("apps/web/vendor/lib")).unwrap();
+        std::fs::create_dir_all(root.join("api")).unwrap();
+        std::fs::create_dir_all(root.join("node_modules/pkg")).unwrap();
+        git(&root.join("apps/web"), &["init"]);
+        git(&root.join("apps/web/vendor/lib"), &["init"]);
+        git(&root.join("api"), &["init"]);
+        git(&root.join("node_modules/pkg"), &["init"]);
+
+        let result = discover(root, None);
+        assert!(!result.workdir_is_repo);
+        assert_eq!(paths(&result), vec!["api", "apps/web"]);
+        assert!(!result.truncated);
+    }
+
+    #[test]
+    fn discover_lists_the_workdir_first_when_it_is_a_repository() {
+        let dir = tempfile::tempdir().unwrap();
+        git(dir.path(), &["init", "-b", "main"]);
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+        git(&dir.path().join("sub"), &["init"]);
+
+        let result = discover(dir.path(), Some("sub".into()));
+        assert!(result.workdir_is_repo);
+        assert_eq!(paths(&result), vec!["", "sub"]);
+        assert_eq!(result.repositories[0].branch, "main");
+        assert_eq!(result.selected.as_deref(), Some("sub"));
+    }
+
+    #[test]
+    fn discover_marks_submodule_checkouts() {
+        let dir = tempfile::tempdir().unwrap();
+        let upstream = dir.path().join("upstream");
+        let root = dir.path().join("root");
+        for repo in [&upstream, &root] {
+            std::fs::create_dir(repo).unwrap();
+            git(repo, &["init"]);
+            git(repo, &["config", "user.email", "test@test.com"]);
+            git(repo, &["config", "user.name", "Test"]);
+        }
+        std::fs::write(upstream.join("lib.rs"), "").unwrap();
+        git(&upstream, &["add", "."]);
+        git(&upstream, &["commit", "-m", "init"]);
+        std::fs::create_dir(root.join("nested")).unwrap();
+        git(&root.join("nested"), &["init"]);
+        git(
+            &root,
+            &[
+                "-c",
+                "protocol.file.allow=always",
+                "submodule",
+                "add",
+                upstream.to_str().unwrap(),
+                "vendor/lib",
+            ],
+        );
+
+        let result = discover(&root, None);
+        let submodules: Vec<(&str, bool)> = result
+            .repositories
+            .iter()
+            .map(|repo| (repo.path.as_str(), repo.submodule))
+            .collect();
+        assert_eq!(
+            submodules,
+            vec![("", false), ("nested", false), ("vendor/lib", true)]
+        );
+    }
+
+    #[test]
+    fn select_normalizes_and_can_init() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("a/b")).unwrap();
+
+        assert!(select(dir.path(), "a/b", false).is_err());
+        assert_eq!(select(dir.path(), "./a/b/", true).unwrap(), "a/b");
+        assert!(dir.path().join("a/b/.git").exists());
+        assert_eq!(select(dir.path(), "a/b", false).unwrap(), "a/b");
+    }
+
+    #[test]
+    fn select_rejects_paths_outside_the_workdir() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(select(dir.path(), "../", true).is_err());
+        assert!(select(dir.path(), "/tmp", true).is_err());
+        assert!(select(dir.path(), "missing", true).is_err());
+    }
+}
@@ -0,0 +1,271 @@
+// Commit graph layout for `GitGraph`.
+//
+// Commits come from `git log --topo-order` over all branches, remotes, tags,
+// and `HEAD`, newest first. Lanes are assigned in one pass: each lane expects
+// a commit hash, a commit takes the leftmost lane expecting it (other lanes
+// expecting it merge into it), its first parent continues that lane and color,
+// and further parents reuse a lane already expecting them or open a new one
+// with a new color. Freed lanes are reused leftmost first, so the graph stays
+// narrow. Each row carries the edges from its node level to the next row's.
+
+use anyhow::Result;
+use zedra_rpc::proto::{
+    GitGraphCommit, GitGraphEdge, GitGraphResult, GIT_GRAPH_DEFAULT_LIMIT, GIT_GRAPH_MAX_LIMIT,
+};
+
+use crate::git::GitRepo;
+
+pub fn graph(repo: &GitRepo, limit: u32) -> Result<GitGraphResult> {
+    let limit = match limit {
+        0 => GIT_GRAPH_DEFAULT_LIMIT,
+        limit => limit.min(GIT_GRAPH_MAX_LIMIT),
+    };
+    // One extra commit tells whether the listing was cut.
+    let max_count = format!("--max-count={}", limit + 1);
+    let out = repo.git(&[
+        "log",
+        "--topo-order",
+        "--branches",
+        "--remotes",
+        "--tags",
+        "HEAD",
+        &max_count,
+        "--format=%H%x1f%P%x1f%D%x1f%s%x1f%an%x1f%at",
+    ])?;
+    let mut commits: Vec<GitGraphCommit> = out.lines().filter_map(parse_commit).collect();
+    let truncated = commits.len() > limit as usize;
+    commits.truncate(limit as usize);
+    let lane_count = assign_lanes(&mut commits);
+    Ok(GitGraphResult {
+        commits,
+        lane_count,
+        truncated,
+        error: None,
+    })
+}
+
+pub fn graph_error(error: String) -> GitGraphResult {
+    GitGraphResult {
+        error: Some(error),
+        ..Default::default()
+    }
+}
+
+fn parse_commit(line: &str) -> Option<GitGraphCommit> {
+    let mut fields = line.split('\x1f');
+    let hash = fields.next()?.to_string();
+    let parents = fields
+        .next()?
+        .split_whitespace()
+        .map(str::to_string)
+        .collect();
+    let refs = fields
+        .next()?
+        .split(", ")
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .collect();
+    Some(GitGraphCommit {
+        hash,
+        parents,
+        refs,
+        subject: fields.next()?.to_string(),
+        author: fields.next()?.to_string(),
+        timestamp: fields.next()?.parse().unwrap_or(0),
+        ..Default::default()
+    })
+}
+
+/// A lane waiting for `hash` to appear further down.
+struct Lane {
+    hash: String,
+    color: u32,
+}
+
+/// A line leaving the previous row, resolved once the next row is placed.
+struct PendingEdge {
+    from_lane: u32,
+    lane: usize,
+    hash: String,
+    color: u32,
+}
+
+/// Fill `lane`, `color`, and `edges` of `commits` (newest first) and return
+/// the widest row.
+fn assign_lanes(commits: &mut [GitGraphCommit]) -> u32 {
+    let mut lanes: Vec<Option<Lane>> = Vec::new();
+    let mut next_color = 0u32;
+    let mut lane_count = 0;
+    let mut pending: Vec<PendingEdge> = Vec::new();
+
+    for index in 0..commits.len() {
+        let hash = commits[index].hash.clone();
+        let existing = lanes
+            .iter()
+            .position(|lane| lane.as_ref().is_some_and(|lane| lane.hash == hash));
+        let (lane, color) = match existing {
+            Some(lane) => (lane, lanes[lane].as_ref().map_or(0, |lane| lane.color)),
+            None => {
+                let lane = free_lane(&mut lanes);
+                next_color += 1;
+                (lane, next_color - 1)
+            }
+        };
+        if index > 0 {
+            commits[index - 1].edges = resolve(&pending, &hash, lane);
+        }
+        for slot in lanes.iter_mut() {
+            if slot.as_ref().is_some_and(|waiting| waiting.hash == hash) {
+                *slot = None;
+            }
+        }
+
+        let mut parent_lanes = Vec::new();
+        let mut opened = Vec::new();
+        for (nth, parent) in commits[index].parents.iter().enumerate() {
+            let waiting = lanes
+                .iter()
+                .position(|slot| slot.as_ref().is_some_and(|slot| &slot.hash == parent));
+            let parent_lane = match (nth, waiting) {
+                (0, _) => {
+                    lanes[lane] = Some(Lane {
+                        hash: parent.clone(),
+                        color,
+                    });
+                    lane
+                }
+                (_, Some(other)) => other,
+                (_, None) => {
+                    let other = free_lane(&mut lanes);
+                    lanes[other] = Some(Lane {
+                        hash: parent.clone(),
+                        color: next_color,
+                    });
+                    next_color += 1;
+                    opened.push(other);
+                    other
+                }
+            };
+            parent_lanes.push(parent_lane);
+        }
+
+        pending.clear();
+        for (slot, waiting) in lanes.iter().enumerate() {
+            let Some(waiting) = waiting else { continue };
+            // Lanes that were already waiting keep their line; a merge
+            // parent joining one adds a second line from this node.
+            if slot != lane && !opened.contains(&slot) {
+                pending.push(PendingEdge {
+                    from_lane: slot as u32,
+                    lane: slot,
+                    hash: waiting.hash.clone(),
+                    color: waiting.color,
+                });
+            }
+            if parent_lanes.contains(&slot) {
+                pending.push(PendingEdge {
+                    from_lane: lane as u32,
+                    lane: slot,
+                    hash: waiting.hash.clone(),
+                    color: waiting.color,
+                });
+            }
+        }
+
+        let row_width = lanes
+            .iter()
+            .rposition(Option::is_some)
+            .map_or(lane + 1, |last| last.max(lane) + 1);
+        lane_count = lane_count.max(row_width as u32);
+        commits[index].lane = lane as u32;
+        commits[index].color = color;
+    }
+
+    // Past the last commit, lines toward unlisted parents go straight down.
+    if let Some(last) = commits.last_mut() {
+        last.edges = resolve(&pending, "", usize::MAX);
+    }
+    lane_count
+}
+
+fn free_lane(lanes: &mut Vec<Option<Lane>>) -> usize {
+    match lanes.iter().position(Option::is_none) {
+        Some(free) => free,
+        None => {
+            lanes.push(None);
+            lanes.len() - 1
+        }
+    }
+}
+
+/// Edges of the previous row, given that the next commit is `hash` at `lane`.
+fn resolve(pending: &[PendingEdge], hash: &str, lane: usize) -> Vec<GitGraphEdge> {
+    let mut edges: Vec<GitGraphEdge> = Vec::new();
+    for edge in pending {
+        let to = if edge.hash == hash { lane } else { edge.lane };
+        let resolved = GitGraphEdge {
+            from_lane: edge.from_lane,
+            to_lane: to as u32,
+            color: edge.color,
+        };
+        if !edges.contains(&resolved) {
+            edges.push(resolved);
+        }
+    }
+    edges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit(hash: &str, parents: &[&str]) -> GitGraphCommit {
+        GitGraphCommit {
+            hash: hash.to_string(),
+            parents: parents.iter().map(|p| p.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn edges(commit: &GitGraphCommit) -> Vec<(u32, u32)> {
+        commit
+            .edges
+            .iter()
+            .map(|edge| (edge.from_lane, edge.to_lane))
+            .collect()
+    }
+
+    #[test]
+    fn merge_opens_a_lane_that_joins_back_at_the_fork() {
+        // m merges feature (f) into main (c); both fork from a.
+        let mut commits = vec![
+            commit("m", &["c", "f"]),
+            commit("f", &["a"]),
+            commit("c", &["a"]),
+            commit("a", &[]),
+        ];
+        let lane_count = assign_lanes(&mut commits);
+        assert_eq!(lane_count, 2);
+        let lanes: Vec<u32> = commits.iter().map(|c| c.lane).collect();
+        assert_eq!(lanes, vec![0, 1, 0, 0]);
+        assert_eq!(edges(&commits[0]), vec![(0, 0), (0, 1)]);
+        assert_eq!(edges(&commits[1]), vec![(0, 0), (1, 1)]);
+        assert_eq!(edges(&commits[2]), vec![(0, 0), (1, 0)]);
+        assert!(commits[3].edges.is_empty());
+        assert_eq!(commits[0].color, commits[2].color);
+        assert_ne!(commits[0].color, commits[1].color);
+    }
+
+    #[test]
+    fn parses_decorations_and_parents() {
+        let line = "aaa\x1fbbb ccc\x1fHEAD -> main, origin/main, tag: v1\x1fsubject\x1fAda\x1f42";
+        let commit = parse_commit(line).unwrap();
+        assert_eq!(commit.parents, vec!["bbb", "ccc"]);
+        assert_eq!(commit.refs, vec!["HEAD -> main", "origin/main", "tag: v1"]);
+        assert_eq!(commit.timestamp, 42);
+        assert!(parse_commit("aaa\x1f\x1f\x1froot\x1fAda\x1f1")
+            .unwrap()
+            .refs
+            .is_empty());
+    }
+}
@@ -0,0 +1,387 @@
+// Per-file history for `GitFileLog`, `GitShowFile`, `GitShowCommit`, and
+// `GitBlame`.
+//
+// The log runs `git log --follow --name-status` on one path, so each entry
+// knows the file's path in that commit and renames are followed back. Paths
+// in results and in show requests are repository-root-relative: that is what
+// `--name-status`, `<rev>:<path>`, and `:(top)` pathspecs agree on, wherever
+// the workdir sits inside the repository. Blame reads `git blame --porcelain`,
+// which names each commit's details only the first time the commit appears.
+
+use std::collections::HashMap;
+use std::path::{Component, Path};
+use std::process::Command;
+
+use anyhow::{bail, Context, Result};
+use zedra_rpc::proto::{
+    GitBlameHunk, GitBlameResult, GitFileLogEntry, GitFileLogResult, GitShowCommitResult,
+    GitShowFileResult, GIT_FILE_LOG_DEFAULT_LIMIT, GIT_FILE_LOG_MAX_LIMIT, GIT_SHOW_FILE_MAX_BYTES,
+};
+
+use crate::git::GitRepo;
+use crate::sandbox::OutsideWorkspace;
+
+pub fn file_log(repo: &GitRepo, path: &str, limit: u32) -> Result<GitFileLogResult> {
+    let limit = match limit {
+        0 => GIT_FILE_LOG_DEFAULT_LIMIT,
+        limit => limit.min(GIT_FILE_LOG_MAX_LIMIT),
+    };
+    let relative = workdir_relative(repo.workdir(), path)?;
+    let prefix = repo.git(&["rev-parse", "--show-prefix"])?;
+    let max_count = format!("--max-count={}", limit + 1);
+    let out = repo.git(&[
+        "log",
+        "--follow",
+        "--name-status",
+        &max_count,
+        "--format=%x1e%H%x1f%s%x1f%an%x1f%at",
+        "--",
+        &relative,
+    ])?;
+    let mut entries = parse_file_log(&out, &format!("{}{relative}", prefix.trim()));
+    let truncated = entries.len() > limit as usize;
+    entries.truncate(limit as usize);
+    Ok(GitFileLogResult {
+        entries,
+        truncated,
+        error: None,
+    })
+}
+
+pub fn file_log_error(error: String) -> GitFileLogResult {
+    GitFileLogResult {
+        error: Some(error),
+        ..Default::default()
+    }
+}
+
+pub fn show_file(repo: &GitRepo, rev: &str, path: &str) -> Result<GitShowFileResult> {
+    check_rev(rev)?;
+    check_repo_path(path)?;
+    let object = format!("{rev}:{path}");
+    let size: u64 = repo
+        .git(&["cat-file", "-s", &object])?
+        .trim()
+        .parse()
+        .context("unexpected git cat-file output")?;
+    if size > GIT_SHOW_FILE_MAX_BYTES {
+        return Ok(GitShowFileResult {
+            too_large: true,
+            ..Default::default()
+        });
+    }
+    let output = Command::new("git")
+        .args(["cat-file", "blob", &object])
+        .current_dir(repo.workdir())
+        .output()
+        .context("failed to run git cat-file")?;
+    if !output.status.success() {
+        bail!(
+            "git cat-file blob {object}: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    if output.stdout.contains(&0) {
+        return Ok(GitShowFileResult {
+            binary: true,
+            ..Default::default()
+        });
+    }
+    Ok(GitShowFileResult {
+        content: String::from_utf8_lossy(&output.stdout).into_owned(),
+        ..Default::default()
+    })
+}
+
+pub fn show_file_error(error: String) -> GitShowFileResult {
+    GitShowFileResult {
+        error: Some(error),
+        ..Default::default()
+    }
+}
+
+pub fn show_commit(repo: &GitRepo, rev: &str, paths: &[String]) -> Result<String> {
+    check_rev(rev)?;
+    for path in paths {
+        check_repo_path(path)?;
+    }
+    let pathspecs: Vec<String> = paths.iter().map(|path| format!(":(top){path}")).collect();
+    let mut args = vec![
+        "show",
+        "--format=",
+        "--no-color",
+        "-M",
+        "-m",
+        "--first-parent",
+        rev,
+        "--",
+    ];
+    args.extend(pathspecs.iter().map(String::as_str));
+    repo.git(&args)
+}
+
+pub fn show_commit_error(error: String) -> GitShowCommitResult {
+    GitShowCommitResult {
+        error: Some(error),
+        ..Default::default()
+    }
+}
+
+pub fn blame(repo: &GitRepo, path: &str) -> Result<GitBlameResult> {
+    let relative = workdir_relative(repo.workdir(), path)?;
+    let out = repo.git(&["blame", "--porcelain", "--", &relative])?;
+    Ok(GitBlameResult {
+        hunks: parse_blame(&out),
+        error: None,
+    })
+}
+
+pub fn blame_error(error: String) -> GitBlameResult {
+    GitBlameResult {
+        error: Some(error),
+        ..Default::default()
+    }
+}
+
+/// Hunks from `git blame --porcelain` output, in line order.
+fn parse_blame(out: &str) -> Vec<GitBlameHunk> {
+    let mut hunks: Vec<GitBlameHunk> = Vec::new();
+    let mut commits: HashMap<String, GitBlameHunk> = HashMap::new();
+    let mut current = String::new();
+    for line in out.lines() {
+        if line.starts_with('\t') {
+            continue;
+        }
+        let fields: Vec<&str> = line.split(' ').collect();
+        let is_header =
+            matches!(fields[0].len(), 40 | 64) && fields[0].chars().all(|c| c.is_ascii_hexdigit());
+        if is_header {
+            current = fields[0].to_string();
+            // Only the first line of a group carries its line count.
+            if let (Some(start), Some(count)) = (fields.get(2), fields.get(3)) {
+                hunks.push(GitBlameHunk {
+                    id: current.clone(),
+                    start_line: start.parse().unwrap_or(0),
+                    line_count: count.parse().unwrap_or(0),
+                    ..Default::default()
+                });
+            }
+            continue;
+        }
+        let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+        let commit = commits.entry(current.clone()).or_default();
+        match key {
+            "author" => commit.author = value.to_string(),
+            "author-time" => commit.timestamp = value.parse().unwrap_or(0),
+            "summary" => commit.summary = value.to_string(),
+            "filename" => {
+                commit.path = value.to_string();
+                if let Some(hunk) = hunks.last_mut().filter(|hunk| hunk.id == current) {
+                    hunk.path = value.to_string();
+                }
+            }
+            _ => {}
+        }
+    }
+    for hunk in &mut hunks {
+        if let Some(commit) = commits.get(&hunk.id) {
+            hunk.author = commit.author.clone();
+            hunk.timestamp = commit.timestamp;
+            hunk.summary = commit.summary.clone();
+            if hunk.path.is_empty() {
+                hunk.path = commit.path.clone();
+            }
+        }
+    }
+    hunks
+}
+
+/// Entries from `git log --follow --name-status` output, newest first.
+/// `path` is the file's current repository-root-relative path, used for
+/// commits that list no status line (merges).
+fn parse_file_log(out: &str, path: &str) -> Vec<GitFileLogEntry> {
+    let mut current = path.to_string();
+    let mut entries = Vec::new();
+    for record in out.split('\x1e').filter(|record| !record.trim().is_empty()) {
+        let mut lines = record.lines();
+        let Some(header) = lines.next() else { continue };
+        let mut fields = header.split('\x1f');
+        let (Some(id), Some(message), Some(author), Some(timestamp)) =
+            (fields.next(), fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        let mut entry = GitFileLogEntry {
+            id: id.to_string(),
+            message: message.to_string(),
+            author: author.to_string(),
+            timestamp: timestamp.parse().unwrap_or(0),
+            path: current.clone(),
+            old_path: None,
+            status: "M".to_string(),
+        };
+        if let Some(status_line) = lines.find(|line| !line.trim().is_empty()) {
+            let mut parts = status_line.split('\t');
+            let status = parts.next().unwrap_or_default();
+            entry.status = status.chars().take(1).collect();
+            match (status.starts_with(['R', 'C']), parts.next(), parts.next()) {
+                (true, Some(old), Some(new)) => {
+                    entry.path = new.to_string();
+                    entry.old_path = Some(old.to_string());
+                    entry.status = "R".to_string();
+                }
+                (_, Some(file), _) => entry.path = file.to_string(),
+                _ => {}
+            }
+        }
+        // Older commits see the file under its pre-rename path.
+        current = entry.old_path.clone().unwrap_or_else(|| entry.path.clone());
+        entries.push(entry);
+    }
+    entries
+}
+
+/// `path` relative to the workdir, rejecting paths that leave it.
+fn workdir_relative(workdir: &Path, path: &str) -> Result<String> {
+    let outside = || OutsideWorkspace {
+        path: path.to_string(),
+    };
+    let trimmed = Path::new(path.trim());
+    let relative = if trimmed.is_absolute() {
+        trimmed.strip_prefix(workdir).map_err(|_| outside())?
+    } else {
+        trimmed
+    };
+    let mut parts = Vec::new();
+    for component in relative.components() {
+        match component {
+            Component::Normal(part) => parts.push(part.to_string_lossy().into_owned()),
+            Component::CurDir => {}
+            _ => return Err(outside().into()),
+        }
+    }
+    if parts.is_empty() {
+        bail!("a file path is required");
+    }
+    Ok(parts.join("/"))
+}
+
+fn check_repo_path(path: &str) -> Result<()> {
+    let valid = !path.is_empty()
+        && !path.starts_with('/')
+        && path
+            .split('/')
+            .all(|part| !part.is_empty() && part != "." && part != "..");
+    if !valid {
+        bail!("invalid repository path: {path}");
+    }
+    Ok(())
+}
+
+fn check_rev(rev: &str) -> Result<()> {
+    if !(4..=64).contains(&rev.len()) || !rev.chars().all(|c| c.is_ascii_hexdigit()) {
+        bail!("invalid commit: {rev}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {args:?} failed");
+    }
+
+    #[test]
+    fn log_follows_renames_and_shows_old_revisions() {
+        let dir = tempfile::tempdir().unwrap();
+        git(dir.path(), &["init"]);
+        git(dir.path(), &["config", "user.email", "test@test.com"]);
+        git(dir.path(), &["config", "user.name", "Test"]);
+        std::fs::write(dir.path().join("a.txt"), "one\ntwo\nthree\nfour\n").unwrap();
+        git(dir.path(), &["add", "."]);
+        git(dir.path(), &["commit", "-m", "add a"]);
+        std::fs::write(dir.path().join("a.txt"), "one\ntwo\nthree\nfour\nfive\n").unwrap();
+        git(dir.path(), &["commit", "-am", "edit a"]);
+        git(dir.path(), &["mv", "a.txt", "b.txt"]);
+        git(dir.path(), &["commit", "-m", "rename to b"]);
+        let repo = GitRepo::open(dir.path()).unwrap();
+
+        let log = file_log(&repo, "./b.txt", 0).unwrap();
+        let summary: Vec<(&str, &str, &str)> = log
+            .entries
+            .iter()
+            .map(|e| (e.message.as_str(), e.status.as_str(), e.path.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("rename to b", "R", "b.txt"),
+                ("edit a", "M", "a.txt"),
+                ("add a", "A", "a.txt"),
+            ]
+        );
+        assert_eq!(log.entries[0].old_path.as_deref(), Some("a.txt"));
+        assert!(!log.truncated);
+
+        let first = show_file(&repo, &log.entries[2].id, "a.txt").unwrap();
+        assert_eq!(first.content, "one\ntwo\nthree\nfour\n");
+        let diff = show_commit(&repo, &log.entries[1].id, &["a.txt".to_string()]).unwrap();
+        assert!(diff.contains("+five"));
+        let rename = show_commit(
+            &repo,
+            &log.entries[0].id,
+            &["a.txt".to_string(), "b.txt".to_string()],
+        )
+        .unwrap();
+        assert!(rename.contains("rename from a.txt"));
+    }
+
+    #[test]
+    fn blame_groups_lines_by_commit() {
+        let dir = tempfile::tempdir().unwrap();
+        git(dir.path(), &["init"]);
+        git(dir.path(), &["config", "user.email", "test@test.com"]);
+        git(dir.path(), &["config", "user.name", "Test"]);
+        std::fs::write(dir.path().join("a.txt"), "one\ntwo\n").unwrap();
+        git(dir.path(), &["add", "."]);
+        git(dir.path(), &["commit", "-m", "add a"]);
+        std::fs::write(dir.path().join("a.txt"), "one\ntwo\nthree\nfour\n").unwrap();
+        git(dir.path(), &["commit", "-am", "append"]);
+        std::fs::write(dir.path().join("a.txt"), "one\ntwo\nthree\nfour\nfive\n").unwrap();
+        let repo = GitRepo::open(dir.path()).unwrap();
+
+        let hunks = blame(&repo, "a.txt").unwrap().hunks;
+        let summary: Vec<(&str, u32, u32)> = hunks
+            .iter()
+            .map(|h| (h.summary.as_str(), h.start_line, h.line_count))
+            .collect();
+        assert_eq!(summary[0], ("add a", 1, 2));
+        assert_eq!(summary[1], ("append", 3, 2));
+        assert_eq!(hunks[2].start_line, 5);
+        assert!(hunks[2].id.chars().all(|c| c == '0'));
+        assert!(hunks
+            .iter()
+            .all(|h| h.path == "a.txt" && !h.author.is_empty()));
+    }
+
+    #[test]
+    fn rejects_paths_and_revs_that_escape() {
+        let workdir = Path::new("/work");
+        assert_eq!(
+            workdir_relative(workdir, "/work/src/main.rs").unwrap(),
+            "src/main.rs"
+        );
+        assert!(workdir_relative(workdir, "../secret").is_err());
+        assert!(workdir_relative(workdir, "/etc/passwd").is_err());
+        assert!(check_repo_path("src/../../x").is_err());
+        assert!(check_rev("--output=/tmp/x").is_err());
+        assert!(check_rev("HEAD").is_err());
+    }
+}
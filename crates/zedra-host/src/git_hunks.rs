@@ -0,0 +1,342 @@
+// Structured diffs for `GitDiffStructured` and per-hunk staging for
+// `GitStageHunk`.
+//
+// The diff text is first split into raw per-file patches (the header lines up
+// to the first `@@`, then each hunk verbatim), which is what `git apply`
+// needs to stage one hunk. Hunk bodies are read by their line counts rather
+// than by prefix, so a removed line starting with `-- ` is not mistaken for a
+// file header. Staging names the hunk by its `@@` line: the host re-reads the
+// diff and refuses when that hunk is gone, instead of applying a patch built
+// from a diff the client saw earlier.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use anyhow::{bail, Context, Result};
+use zedra_rpc::proto::{GitDiffFile, GitDiffHunk, GitDiffLine, GitDiffLineKind};
+
+use crate::git::GitRepo;
+use crate::sandbox::check_pathspec;
+
+/// One file's patch as git printed it.
+#[derive(Debug, Default)]
+struct RawFilePatch {
+    header: String,
+    hunks: Vec<String>,
+}
+
+pub fn diff_structured(
+    repo: &GitRepo,
+    path: Option<&str>,
+    staged: bool,
+) -> Result<Vec<GitDiffFile>> {
+    Ok(parse_diff(&repo.diff(path, staged)?))
+}
+
+/// Parse unified `git diff` output into files, hunks, and lines.
+pub fn parse_diff(text: &str) -> Vec<GitDiffFile> {
+    split_patches(text).iter().map(parse_file).collect()
+}
+
+/// Add one hunk of `path`'s working-tree diff to the index, or with `unstage`
+/// take one hunk of its staged diff back out.
+pub fn stage_hunk(repo: &GitRepo, path: &str, header: &str, unstage: bool) -> Result<()> {
+    if path.is_empty() {
+        bail!("no path to stage");
+    }
+    check_pathspec(path)?;
+    let mut args = vec!["diff"];
+    if unstage {
+        args.push("--cached");
+    }
+    args.extend(["--", path]);
+    let text = repo.git(&args)?;
+    if text.is_empty() {
+        bail!(
+            "{path} has no {} changes",
+            if unstage { "staged" } else { "unstaged" }
+        );
+    }
+    let patch = split_patches(&text)
+        .into_iter()
+        .find_map(|file| {
+            let hunk = file
+                .hunks
+                .iter()
+                .find(|hunk| hunk.lines().next() == Some(header))?;
+            Some(format!("{}{hunk}", file.header))
+        })
+        .context("the hunk no longer matches the diff; refresh and try again")?;
+
+    // Patch paths are repository-root-relative, and `git apply` run from a
+    // subdirectory would prefix them again.
+    let toplevel = repo.git(&["rev-parse", "--show-toplevel"])?;
+    let mut apply = vec!["apply", "--cached"];
+    if unstage {
+        apply.push("--reverse");
+    }
+    apply.push("-");
+    let mut child = Command::new("git")
+        .args(&apply)
+        .current_dir(toplevel.trim())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("git apply failed to start")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(patch.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        bail!(
+            "git apply: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+fn split_patches(text: &str) -> Vec<RawFilePatch> {
+    let mut files: Vec<RawFilePatch> = Vec::new();
+    let (mut old_left, mut new_left) = (0u32, 0u32);
+    for line in text.split_inclusive('\n') {
+        let in_hunk = old_left > 0 || new_left > 0;
+        if let Some(hunk) = files.last_mut().and_then(|file| file.hunks.last_mut()) {
+            if in_hunk || line.starts_with('\\') {
+                hunk.push_str(line);
+                match line.as_bytes().first() {
+                    Some(b'+') => new_left = new_left.saturating_sub(1),
+                    Some(b'-') => old_left = old_left.saturating_sub(1),
+                    Some(b'\\') => {}
+                    _ => {
+                        old_left = old_left.saturating_sub(1);
+                        new_left = new_left.saturating_sub(1);
+                    }
+                }
+                continue;
+            }
+        }
+        if line.starts_with("diff ") {
+            files.push(RawFilePatch {
+                header: line.to_string(),
+                hunks: Vec::new(),
+            });
+        } else if line.starts_with("@@ ") {
+            let Some(file) = files.last_mut() else {
+                continue;
+            };
+            let (_, old_count, _, new_count) = parse_hunk_header(line.trim_end());
+            old_left = old_count;
+            new_left = new_count;
+            file.hunks.push(line.to_string());
+        } else if let Some(file) = files.last_mut() {
+            if file.hunks.is_empty() {
+                file.header.push_str(line);
+            }
+        }
+    }
+    files
+}
+
+fn parse_file(raw: &RawFilePatch) -> GitDiffFile {
+    let mut file = GitDiffFile::default();
+    for line in raw.header.lines() {
+        if let Some(paths) = line.strip_prefix("diff --git ") {
+            // Fallback for mode-only and binary patches, which have no
+            // `---`/`+++` lines; ambiguous when a path contains " b/".
+            if let Some((old, new)) = paths.split_once(" b/") {
+                file.old_path = old.strip_prefix("a/").unwrap_or(old).to_string();
+                file.new_path = new.to_string();
+            }
+        } else if let Some(path) = line.strip_prefix("--- ") {
+            file.old_path = patch_path(path, "a/");
+        } else if let Some(path) = line.strip_prefix("+++ ") {
+            file.new_path = patch_path(path, "b/");
+        } else if line.starts_with("Binary files ") || line == "GIT binary patch" {
+            file.binary = true;
+        }
+    }
+    file.hunks = raw.hunks.iter().map(|hunk| parse_hunk(hunk)).collect();
+    file
+}
+
+fn patch_path(path: &str, prefix: &str) -> String {
+    // git appends a tab to names containing spaces.
+    let path = path.trim_end_matches('\t');
+    if path == "/dev/null" {
+        return String::new();
+    }
+    path.strip_prefix(prefix).unwrap_or(path).to_string()
+}
+
+fn parse_hunk(raw: &str) -> GitDiffHunk {
+    let mut lines = raw.lines();
+    let header = lines.next().unwrap_or_default().to_string();
+    let (old_start, old_count, new_start, new_count) = parse_hunk_header(&header);
+    let (mut old_line, mut new_line) = (old_start, new_start);
+    let mut parsed = Vec::new();
+    for line in lines {
+        let (kind, content) = match line.as_bytes().first() {
+            Some(b'+') => (GitDiffLineKind::Added, &line[1..]),
+            Some(b'-') => (GitDiffLineKind::Removed, &line[1..]),
+            Some(b'\\') => continue,
+            Some(b' ') => (GitDiffLineKind::Context, &line[1..]),
+            _ => (GitDiffLineKind::Context, line),
+        };
+        let (old, new) = match kind {
+            GitDiffLineKind::Added => (None, Some(new_line)),
+            GitDiffLineKind::Removed => (Some(old_line), None),
+            GitDiffLineKind::Context => (Some(old_line), Some(new_line)),
+        };
+        if old.is_some() {
+            old_line += 1;
+        }
+        if new.is_some() {
+            new_line += 1;
+        }
+        parsed.push(GitDiffLine {
+            kind,
+            old_line: old,
+            new_line: new,
+            content: content.to_string(),
+        });
+    }
+    GitDiffHunk {
+        header,
+        old_start,
+        old_count,
+        new_start,
+        new_count,
+        lines: parsed,
+    }
+}
+
+/// `@@ -a,b +c,d @@` → `(a, b, c, d)`; a missing count is 1.
+fn parse_hunk_header(line: &str) -> (u32, u32, u32, u32) {
+    let ranges = line
+        .strip_prefix("@@ ")
+        .and_then(|rest| rest.split(" @@").next())
+        .unwrap_or("");
+    let mut parts = ranges.split_whitespace();
+    let (old_start, old_count) = parse_range(parts.next().unwrap_or(""));
+    let (new_start, new_count) = parse_range(parts.next().unwrap_or(""));
+    (old_start, old_count, new_start, new_count)
+}
+
+fn parse_range(range: &str) -> (u32, u32) {
+    let range = range.trim_start_matches(['-', '+']);
+    match range.split_once(',') {
+        Some((start, count)) => (start.parse().unwrap_or(0), count.parse().unwrap_or(0)),
+        None => (range.parse().unwrap_or(0), 1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn git(dir: &Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {args:?} failed");
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    }
+
+    #[test]
+    fn parse_diff_reads_hunks_by_their_line_counts() {
+        let text = "diff --git a/a.txt b/a.txt\n\
+                    index 1111111..2222222 100644\n\
+                    --- a/a.txt\n\
+                    +++ b/a.txt\n\
+                    @@ -1,3 +1,3 @@ fn main\n\
+                    \x20one\n\
+                    --- two\n\
+                    +++ two\n\
+                    \x20three\n\
+                    @@ -10 +10,0 @@\n\
+                    -ten\n\
+                    \\ No newline at end of file\n\
+                    diff --git a/new.txt b/new.txt\n\
+                    new file mode 100644\n\
+                    --- /dev/null\n\
+                    +++ b/new.txt\n\
+                    @@ -0,0 +1 @@\n\
+                    +hello\n";
+        let files = parse_diff(text);
+        assert_eq!(files.len(), 2);
+        let a = &files[0];
+        assert_eq!(
+            (a.old_path.as_str(), a.new_path.as_str()),
+            ("a.txt", "a.txt")
+        );
+        assert_eq!(a.hunks.len(), 2);
+        assert_eq!(a.hunks[0].header, "@@ -1,3 +1,3 @@ fn main");
+        let kinds: Vec<(GitDiffLineKind, &str, Option<u32>, Option<u32>)> = a.hunks[0]
+            .lines
+            .iter()
+            .map(|line| {
+                (
+                    line.kind,
+                    line.content.as_str(),
+                    line.old_line,
+                    line.new_line,
+                )
+            })
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (GitDiffLineKind::Context, "one", Some(1), Some(1)),
+                (GitDiffLineKind::Removed, "-- two", Some(2), None),
+                (GitDiffLineKind::Added, "++ two", None, Some(2)),
+                (GitDiffLineKind::Context, "three", Some(3), Some(3)),
+            ]
+        );
+        assert_eq!((a.hunks[1].old_start, a.hunks[1].old_count), (10, 1));
+        assert_eq!(a.hunks[1].lines.len(), 1);
+        assert_eq!(files[1].old_path, "");
+        assert_eq!(files[1].new_path, "new.txt");
+        assert_eq!(files[1].hunks[0].lines[0].content, "hello");
+    }
+
+    #[test]
+    fn stage_hunk_moves_one_hunk_in_and_out_of_the_index() {
+        let dir = tempfile::tempdir().unwrap();
+        git(dir.path(), &["init"]);
+        git(dir.path(), &["config", "user.email", "test@test.com"]);
+        git(dir.path(), &["config", "user.name", "Test"]);
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+        let original: String = (1..=30).map(|n| format!("line {n}\n")).collect();
+        std::fs::write(dir.path().join("sub/a.txt"), &original).unwrap();
+        git(dir.path(), &["add", "."]);
+        git(dir.path(), &["commit", "-m", "add a"]);
+        let edited = original
+            .replace("line 2\n", "line two\n")
+            .replace("line 28\n", "line twenty-eight\n");
+        std::fs::write(dir.path().join("sub/a.txt"), edited).unwrap();
+        // Run from a subdirectory, as a workdir inside the repository would.
+        let repo = GitRepo::open(dir.path().join("sub")).unwrap();
+
+        let files = diff_structured(&repo, Some("a.txt"), false).unwrap();
+        assert_eq!(files[0].hunks.len(), 2);
+        let second = files[0].hunks[1].header.clone();
+        stage_hunk(&repo, "a.txt", &second, false).unwrap();
+
+        let staged = git(dir.path(), &["diff", "--cached"]);
+        assert!(staged.contains("+line twenty-eight"));
+        assert!(!staged.contains("+line two\n"));
+        let unstaged = git(dir.path(), &["diff"]);
+        assert!(unstaged.contains("+line two\n"));
+        assert!(!unstaged.contains("twenty-eight"));
+
+        assert!(stage_hunk(&repo, "a.txt", &second, false).is_err());
+        let staged = diff_structured(&repo, Some("a.txt"), true).unwrap();
+        stage_hunk(&repo, "a.txt", &staged[0].hunks[0].header, true).unwrap();
+        assert!(git(dir.path(), &["diff", "--cached"]).is_empty());
+    }
+}
@@ -0,0 +1,737 @@
+// Push, pull, and fetch for `GitPush`, `GitPushV2`, `GitPull`, and
+// `GitFetch`, with HTTPS credentials forwarded to the device.
+//
+// Each run appends one credential helper after the user's own:
+// `zedra git-credential`, which git only reaches when its helpers have
+// nothing. That helper relays git's request to the daemon's REST API
+// (`POST /api/git-credential`), and the daemon asks the requesting session's
+// client with `HostEvent::GitCredentialRequested`, then waits for its
+// `GitCredentialReply`. A credential the user chose to remember goes to the
+// platform keychain helper once git reports it worked (`store`), and is read
+// back from there before the device is asked again.
+
+use std::collections::{HashMap, HashSet};
+use std::io::Write as _;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{oneshot, Mutex};
+use zedra_rpc::proto::{
+    GitCredential, GitFetchReq, GitFetchResult, GitPullMode, GitPullReq, GitPullResult, GitPushReq,
+    GitPushReqV2, GitPushResult, GitPushResultV2, GitRemoteFailure, HostEvent,
+    GIT_CREDENTIAL_TIMEOUT_SECS,
+};
+
+use crate::agent::utils::shell_quote;
+use crate::git::{is_safe_ref, GitRepo};
+use crate::session_registry::ServerSession;
+
+const DEFAULT_REMOTE: &str = "origin";
+
+/// Fields of git's credential helper protocol that Zedra reads or answers.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CredentialFields {
+    #[serde(default)]
+    pub protocol: String,
+    #[serde(default)]
+    pub host: String,
+    #[serde(default)]
+    pub path: Option<String>,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    /// Tells git to stop asking other helpers and fail.
+    #[serde(default)]
+    pub quit: bool,
+}
+
+impl CredentialFields {
+    /// `protocol://host[/path]`, without credentials.
+    fn url(&self) -> String {
+        match &self.path {
+            Some(path) => format!("{}://{}/{path}", self.protocol, self.host),
+            None => format!("{}://{}", self.protocol, self.host),
+        }
+    }
+
+    fn remember_key(&self) -> String {
+        format!(
+            "{}://{}@{}",
+            self.protocol,
+            self.username.as_deref().unwrap_or_default(),
+            self.host
+        )
+    }
+}
+
+/// `key=value` lines git writes to a helper's stdin.
+pub fn parse_credential(input: &str) -> CredentialFields {
+    let mut fields = CredentialFields::default();
+    for line in input.lines() {
+        if line.is_empty() {
+            break;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        match key {
+            "protocol" => fields.protocol = value.to_string(),
+            "host" => fields.host = value.to_string(),
+            "path" => fields.path = Some(value.to_string()),
+            "username" => fields.username = Some(value.to_string()),
+            "password" => fields.password = Some(value.to_string()),
+            "quit" => fields.quit = matches!(value, "1" | "true"),
+            _ => {}
+        }
+    }
+    fields
+}
+
+/// `key=value` lines for a helper's stdout or another helper's stdin.
+/// Values containing a newline are dropped; git rejects them anyway.
+pub fn format_credential(fields: &CredentialFields) -> String {
+    let mut out = String::new();
+    let mut line = |key: &str, value: &str| {
+        if !value.is_empty() && !value.contains('\n') {
+            out.push_str(&format!("{key}={value}\n"));
+        }
+    };
+    line("protocol", &fields.protocol);
+    line("host", &fields.host);
+    line("path", fields.path.as_deref().unwrap_or_default());
+    line("username", fields.username.as_deref().unwrap_or_default());
+    line("password", fields.password.as_deref().unwrap_or_default());
+    if fields.quit {
+        out.push_str("quit=1\n");
+    }
+    out
+}
+
+/// Credential prompts waiting on a client, and credentials to store once git
+/// accepts them. Daemon-scoped: the helper process only knows the session id.
+#[derive(Default)]
+pub struct GitCredentialBroker {
+    /// Prompt id → (session id, reply channel).
+    pending: Mutex<HashMap<String, (String, oneshot::Sender<Option<GitCredential>>)>>,
+    remember: Mutex<HashSet<String>>,
+}
+
+impl GitCredentialBroker {
+    /// Ask the session's client for a credential. `None` means the user
+    /// cancelled.
+    async fn request(
+        &self,
+        session: &ServerSession,
+        fields: &CredentialFields,
+    ) -> Result<Option<GitCredential>> {
+        let id = uuid::Uuid::new_v4().to_string();
+        let (tx, rx) = oneshot::channel();
+        self.pending
+            .lock()
+            .await
+            .insert(id.clone(), (session.id.clone(), tx));
+        let event = HostEvent::GitCredentialRequested {
+            id: id.clone(),
+            url: fields.url(),
+            username: fields.username.clone(),
+        };
+        if !session.push_event(event).await {
+            self.pending.lock().await.remove(&id);
+            bail!(
+                "no connected client to enter credentials for {}",
+                fields.host
+            );
+        }
+        let reply =
+            tokio::time::timeout(Duration::from_secs(GIT_CREDENTIAL_TIMEOUT_SECS), rx).await;
+        self.pending.lock().await.remove(&id);
+        let credential = match reply {
+            Ok(Ok(credential)) => credential,
+            Ok(Err(_)) => None,
+            Err(_) => bail!("timed out waiting for credentials for {}", fields.host),
+        };
+        if let Some(credential) = credential.as_ref().filter(|c| c.remember) {
+            let answered = CredentialFields {
+                username: Some(credential.username.clone()),
+                ..fields.clone()
+            };
+            self.remember.lock().await.insert(answered.remember_key());
+        }
+        Ok(credential)
+    }
+
+    /// Deliver a client's answer. False when the prompt is gone or belongs to
+    /// another session.
+    pub async fn reply(
+        &self,
+        session_id: &str,
+        id: &str,
+        credential: Option<GitCredential>,
+    ) -> bool {
+        let mut pending = self.pending.lock().await;
+        match pending.remove(id) {
+            Some((owner, tx)) if owner == session_id => tx.send(credential).is_ok(),
+            Some(entry) => {
+                pending.insert(id.to_string(), entry);
+                false
+            }
+            None => false,
+        }
+    }
+
+    async fn take_remember(&self, fields: &CredentialFields) -> bool {
+        self.remember.lock().await.remove(&fields.remember_key())
+    }
+}
+
+/// Answer one `zedra git-credential <operation>` call from git.
+pub async fn answer_helper(
+    broker: &GitCredentialBroker,
+    session: Option<&ServerSession>,
+    operation: &str,
+    fields: CredentialFields,
+) -> Result<CredentialFields> {
+    match operation {
+        "get" => {
+            if let Some(stored) = keychain("get", &fields)
+                .await
+                .filter(|stored| stored.password.is_some())
+            {
+                return Ok(stored);
+            }
+            let Some(session) = session else {
+                bail!("the requesting session is gone");
+            };
+            Ok(match broker.request(session, &fields).await? {
+                Some(credential) => CredentialFields {
+                    username: Some(credential.username),
+                    password: Some(credential.password),
+                    ..fields
+                },
+                None => CredentialFields {
+                    quit: true,
+                    ..Default::default()
+                },
+            })
+        }
+        "store" => {
+            if broker.take_remember(&fields).await {
+                keychain("store", &fields).await;
+            }
+            Ok(CredentialFields::default())
+        }
+        "erase" => {
+            broker.take_remember(&fields).await;
+            keychain("erase", &fields).await;
+            Ok(CredentialFields::default())
+        }
+        _ => Ok(CredentialFields::default()),
+    }
+}
+
+/// Git's bundled keychain helper for this platform.
+fn keychain_helper() -> Option<&'static str> {
+    if cfg!(target_os = "macos") {
+        Some("credential-osxkeychain")
+    } else if cfg!(target_os = "linux") {
+        Some("credential-libsecret")
+    } else if cfg!(target_os = "windows") {
+        Some("credential-manager")
+    } else {
+        None
+    }
+}
+
+/// Run the keychain helper; `None` when it is missing or fails, which only
+/// means nothing is cached.
+async fn keychain(operation: &'static str, fields: &CredentialFields) -> Option<CredentialFields> {
+    let helper = keychain_helper()?;
+    let input = format_credential(fields);
+    let result = tokio::task::spawn_blocking(move || -> Result<String> {
+        let mut child = Command::new("git")
+            .args([helper, operation])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(input.as_bytes())?;
+        }
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    })
+    .await;
+    match result {
+        Ok(Ok(out)) => Some(parse_credential(&out)),
+        Ok(Err(e)) => {
+            tracing::debug!(
+                helper,
+                operation,
+                "git credential: keychain unavailable: {e}"
+            );
+            None
+        }
+        Err(e) => {
+            tracing::warn!("git credential: keychain worker failed: {e}");
+            None
+        }
+    }
+}
+
+/// `credential.helper` value that routes prompts to `session_id` through the
+/// daemon serving `workdir`.
+pub fn credential_helper(workdir: &Path, session_id: &str) -> Result<String> {
+    let exe = std::env::current_exe().context("failed to resolve current zedra binary")?;
+    Ok(format!(
+        "!{} git-credential --workdir {} --session {}",
+        shell_quote(&exe.to_string_lossy()),
+        shell_quote(&workdir.to_string_lossy()),
+        shell_quote(session_id)
+    ))
+}
+
+/// A failed fetch, pull, or push, with the cause when git's output names one.
+#[derive(Debug)]
+pub struct RemoteError {
+    pub failure: Option<GitRemoteFailure>,
+    message: String,
+}
+
+impl std::fmt::Display for RemoteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for RemoteError {}
+
+/// The failure class of an anyhow error from this module, if any.
+pub fn remote_failure(error: &anyhow::Error) -> Option<GitRemoteFailure> {
+    error.downcast_ref::<RemoteError>().and_then(|e| e.failure)
+}
+
+/// Classify git's stderr from a failed fetch, pull, or push.
+pub fn classify_failure(stderr: &str) -> Option<GitRemoteFailure> {
+    let lower = stderr.to_lowercase();
+    let any = |needles: &[&str]| needles.iter().any(|needle| lower.contains(needle));
+    if any(&[
+        "authentication failed",
+        "could not read username",
+        "could not read password",
+        "terminal prompts disabled",
+        "permission denied (publickey",
+        "invalid username or password",
+        "the requested url returned error: 403",
+    ]) {
+        Some(GitRemoteFailure::Auth)
+    } else if any(&["conflict", "could not apply", "automatic merge failed"]) {
+        Some(GitRemoteFailure::Conflict)
+    } else if any(&[
+        "would be overwritten",
+        "you have unstaged changes",
+        "your index contains uncommitted changes",
+    ]) {
+        Some(GitRemoteFailure::LocalChanges)
+    } else if any(&[
+        "non-fast-forward",
+        "[rejected]",
+        "stale info",
+        "not possible to fast-forward",
+        "diverging branches",
+    ]) {
+        Some(GitRemoteFailure::NonFastForward)
+    } else if any(&[
+        "could not resolve host",
+        "connection refused",
+        "connection timed out",
+        "network is unreachable",
+        "could not read from remote repository",
+    ]) {
+        Some(GitRemoteFailure::Network)
+    } else {
+        None
+    }
+}
+
+/// Run `git <operation> <args>` through the credential helper.
+fn run_remote(repo: &GitRepo, helper: &str, operation: &str, args: &[&str]) -> Result<String> {
+    let helper_config = format!("credential.helper={helper}");
+    let output = Command::new("git")
+        .args(["-c", helper_config.as_str(), operation])
+        .args(args)
+        .current_dir(repo.workdir())
+        // Without a terminal, git would otherwise block on a prompt nobody sees.
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .env("GIT_EDITOR", "true")
+        .output()
+        .with_context(|| format!("git {operation} failed"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(RemoteError {
+            failure: classify_failure(&stderr),
+            message: format!("git {operation}: {}", stderr.trim()),
+        }
+        .into());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn current_branch(repo: &GitRepo) -> Result<String> {
+    let branch = repo.branch()?;
+    if branch.is_empty() {
+        bail!("HEAD is detached; check out a branch first");
+    }
+    Ok(branch)
+}
+
+/// `branch.<name>.remote`, else `origin`.
+fn upstream_remote(repo: &GitRepo, branch: &str) -> String {
+    repo.git(&["config", &format!("branch.{branch}.remote")])
+        .map(|out| out.trim().to_string())
+        .ok()
+        .filter(|remote| !remote.is_empty())
+        .unwrap_or_else(|| DEFAULT_REMOTE.to_string())
+}
+
+/// The remote branch `branch` tracks (`branch.<name>.merge`), else its own
+/// name.
+fn upstream_branch(repo: &GitRepo, branch: &str) -> String {
+    repo.git(&["config", &format!("branch.{branch}.merge")])
+        .map(|out| out.trim().trim_start_matches("refs/heads/").to_string())
+        .ok()
+        .filter(|merge| !merge.is_empty())
+        .unwrap_or_else(|| branch.to_string())
+}
+
+/// Commits `HEAD` has that `<remote>/<branch>` lacks, and the reverse. Zero
+/// for both when the remote-tracking ref does not exist.
+fn ahead_behind(repo: &GitRepo, remote: &str, branch: &str) -> (u32, u32) {
+    let range = format!("HEAD...refs/remotes/{remote}/{branch}");
+    let Ok(out) = repo.git(&["rev-list", "--left-right", "--count", &range]) else {
+        return (0, 0);
+    };
+    let mut counts = out.split_whitespace().map(|n| n.parse().unwrap_or(0));
+    (counts.next().unwrap_or(0), counts.next().unwrap_or(0))
+}
+
+/// Push `req.branch` (default: current) to `req.remote` (default: the
+/// branch's upstream remote, else `origin`).
+pub fn push(repo: &GitRepo, req: &GitPushReq, helper: &str) -> Result<GitPushResult> {
+    let pushed = push_v2(
+        repo,
+        &GitPushReqV2 {
+            remote: req.remote.clone(),
+            branch: req.branch.clone(),
+            set_upstream: req.set_upstream,
+            force: false,
+        },
+        helper,
+    )?;
+    Ok(GitPushResult {
+        remote: pushed.remote,
+        branch: pushed.branch,
+        error: None,
+    })
+}
+
+/// `push` with an optional `--force-with-lease`, which still refuses when
+/// the remote moved since the last fetch.
+pub fn push_v2(repo: &GitRepo, req: &GitPushReqV2, helper: &str) -> Result<GitPushResultV2> {
+    let branch = match &req.branch {
+        Some(branch) => branch.clone(),
+        None => current_branch(repo)?,
+    };
+    let remote = match &req.remote {
+        Some(remote) => remote.clone(),
+        None => upstream_remote(repo, &branch),
+    };
+    if !is_safe_ref(&remote) || !is_safe_ref(&branch) {
+        bail!("invalid ref: {remote}/{branch}");
+    }
+
+    let mut args = Vec::new();
+    if req.set_upstream {
+        args.push("--set-upstream");
+    }
+    if req.force {
+        args.push("--force-with-lease");
+    }
+    args.extend([remote.as_str(), branch.as_str()]);
+    run_remote(repo, helper, "push", &args)?;
+    Ok(GitPushResultV2 {
+        remote,
+        branch,
+        ..Default::default()
+    })
+}
+
+/// Fetch `req.remote` (default: the current branch's upstream remote, else
+/// `origin`).
+pub fn fetch(repo: &GitRepo, req: &GitFetchReq, helper: &str) -> Result<GitFetchResult> {
+    let branch = repo.branch().unwrap_or_default();
+    let remote = match &req.remote {
+        Some(remote) => remote.clone(),
+        None if branch.is_empty() => DEFAULT_REMOTE.to_string(),
+        None => upstream_remote(repo, &branch),
+    };
+    if !is_safe_ref(&remote) {
+        bail!("invalid ref: {remote}");
+    }
+
+    let mut args = Vec::new();
+    if req.prune {
+        args.push("--prune");
+    }
+    args.push(remote.as_str());
+    run_remote(repo, helper, "fetch", &args)?;
+    let (ahead, behind) = if branch.is_empty() {
+        (0, 0)
+    } else {
+        ahead_behind(repo, &remote, &upstream_branch(repo, &branch))
+    };
+    Ok(GitFetchResult {
+        remote,
+        ahead,
+        behind,
+        ..Default::default()
+    })
+}
+
+/// Pull `req.branch` (default: the upstream branch) of `req.remote` into the
+/// current branch. A merge or rebase that stops on conflicts is aborted, so
+/// on error the branch is unchanged.
+pub fn pull(repo: &GitRepo, req: &GitPullReq, helper: &str) -> Result<GitPullResult> {
+    let current = current_branch(repo)?;
+    let remote = match &req.remote {
+        Some(remote) => remote.clone(),
+        None => upstream_remote(repo, &current),
+    };
+    let branch = match &req.branch {
+        Some(branch) => branch.clone(),
+        None => upstream_branch(repo, &current),
+    };
+    if !is_safe_ref(&remote) || !is_safe_ref(&branch) {
+        bail!("invalid ref: {remote}/{branch}");
+    }
+
+    let before = repo.git(&["rev-parse", "HEAD"]).unwrap_or_default();
+    let mode = match req.mode {
+        GitPullMode::FastForwardOnly => "--ff-only",
+        GitPullMode::Merge => "--no-rebase",
+        GitPullMode::Rebase => "--rebase",
+    };
+    let args = [mode, "--no-edit", remote.as_str(), branch.as_str()];
+    if let Err(e) = run_remote(repo, helper, "pull", &args) {
+        if remote_failure(&e) == Some(GitRemoteFailure::Conflict) {
+            let abort: &[&str] = match req.mode {
+                GitPullMode::Rebase => &["rebase", "--abort"],
+                _ => &["merge", "--abort"],
+            };
+            if let Err(abort_err) = repo.git(abort) {
+                tracing::warn!("git pull: abort after conflict failed: {abort_err}");
+            }
+        }
+        return Err(e);
+    }
+    let head = repo.git(&["rev-parse", "HEAD"])?.trim().to_string();
+    let (ahead, behind) = ahead_behind(repo, &remote, &branch);
+    Ok(GitPullResult {
+        updated: head != before.trim(),
+        remote,
+        branch,
+        head,
+        ahead,
+        behind,
+        ..Default::default()
+    })
+}
+
+pub fn push_error(error: String) -> GitPushResult {
+    GitPushResult {
+        error: Some(error),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn credential_fields_round_trip() {
+        let input = "protocol=https\nhost=github.com\nusername=octo\n\nignored=1\n";
+        let fields = parse_credential(input);
+        assert_eq!(fields.protocol, "https");
+        assert_eq!(fields.host, "github.com");
+        assert_eq!(fields.username.as_deref(), Some("octo"));
+        assert_eq!(fields.password, None);
+        assert_eq!(fields.url(), "https://github.com");
+        assert_eq!(
+            format_credential(&fields),
+            "protocol=https\nhost=github.com\nusername=octo\n"
+        );
+
+        let cancelled = CredentialFields {
+            quit: true,
+            ..Default::default()
+        };
+        assert_eq!(format_credential(&cancelled), "quit=1\n");
+    }
+
+    #[test]
+    fn remember_key_ignores_path_and_password() {
+        let mut fields = parse_credential("protocol=https\nhost=example.com\nusername=me\n");
+        let key = fields.remember_key();
+        fields.path = Some("org/repo.git".to_string());
+        fields.password = Some("secret".to_string());
+        assert_eq!(fields.remember_key(), key);
+        assert_eq!(key, "https://me@example.com");
+    }
+
+    #[tokio::test]
+    async fn reply_only_reaches_the_owning_session() {
+        let broker = GitCredentialBroker::default();
+        let (tx, rx) = oneshot::channel();
+        broker
+            .pending
+            .lock()
+            .await
+            .insert("prompt".to_string(), ("session-a".to_string(), tx));
+        let credential = GitCredential {
+            username: "me".to_string(),
+            password: "token".to_string(),
+            remember: false,
+        };
+
+        assert!(!broker.reply("session-b", "prompt", None).await);
+        assert!(
+            broker
+                .reply("session-a", "prompt", Some(credential.clone()))
+                .await
+        );
+        assert!(!broker.reply("session-a", "prompt", None).await);
+        assert_eq!(rx.await.unwrap(), Some(credential));
+    }
+
+    #[test]
+    fn classify_failure_reads_git_stderr() {
+        let rejected = " ! [rejected]        main -> main (fetch first)\n\
+                        error: failed to push some refs";
+        assert_eq!(
+            classify_failure(rejected),
+            Some(GitRemoteFailure::NonFastForward)
+        );
+        assert_eq!(
+            classify_failure("fatal: Authentication failed for 'https://github.com/o/r.git/'"),
+            Some(GitRemoteFailure::Auth)
+        );
+        assert_eq!(
+            classify_failure("CONFLICT (content): Merge conflict in src/lib.rs"),
+            Some(GitRemoteFailure::Conflict)
+        );
+        assert_eq!(
+            classify_failure(
+                "error: Your local changes to the following files would be overwritten by merge"
+            ),
+            Some(GitRemoteFailure::LocalChanges)
+        );
+        assert_eq!(
+            classify_failure("fatal: unable to access: Could not resolve host: github.com"),
+            Some(GitRemoteFailure::Network)
+        );
+        assert_eq!(classify_failure("fatal: something else"), None);
+    }
+
+    fn git(dir: &Path, args: &[&str]) {
+        let output = Command::new("git")
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {args:?}: {output:?}");
+    }
+
+    #[test]
+    fn fetch_and_pull_track_the_upstream() {
+        let root = tempfile::tempdir().unwrap();
+        let origin = root.path().join("origin.git");
+        let upstream = root.path().join("upstream");
+        let local = root.path().join("local");
+        git(root.path(), &["init", "--bare", "-b", "main", "origin.git"]);
+        git(root.path(), &["clone", "origin.git", "upstream"]);
+        git(&upstream, &["commit", "--allow-empty", "-m", "one"]);
+        git(&upstream, &["push", "-u", "origin", "main"]);
+        git(root.path(), &["clone", &origin.to_string_lossy(), "local"]);
+        git(&upstream, &["commit", "--allow-empty", "-m", "two"]);
+        git(&upstream, &["push"]);
+
+        let repo = GitRepo::open(&local).unwrap();
+        let fetched = fetch(
+            &repo,
+            &GitFetchReq {
+                remote: None,
+                prune: false,
+            },
+            "",
+        )
+        .unwrap();
+        assert_eq!(
+            (fetched.remote.as_str(), fetched.ahead, fetched.behind),
+            ("origin", 0, 1)
+        );
+
+        let fast_forward = GitPullReq {
+            remote: None,
+            branch: None,
+            mode: GitPullMode::FastForwardOnly,
+        };
+        let pulled = pull(&repo, &fast_forward, "").unwrap();
+        assert!(pulled.updated);
+        assert_eq!((pulled.branch.as_str(), pulled.behind), ("main", 0));
+
+        git(&upstream, &["commit", "--allow-empty", "-m", "three"]);
+        git(&upstream, &["push"]);
+        git(&local, &["commit", "--allow-empty", "-m", "local"]);
+        let err = pull(&repo, &fast_forward, "").unwrap_err();
+        assert_eq!(remote_failure(&err), Some(GitRemoteFailure::NonFastForward));
+        let err = push_v2(
+            &repo,
+            &GitPushReqV2 {
+                remote: None,
+                branch: None,
+                set_upstream: false,
+                force: false,
+            },
+            "",
+        )
+        .unwrap_err();
+        assert_eq!(remote_failure(&err), Some(GitRemoteFailure::NonFastForward));
+    }
+
+    #[test]
+    fn push_rejects_option_like_remotes() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = Command::new("git")
+            .args(["init", "-b", "main"])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let repo = GitRepo::open(dir.path()).unwrap();
+        let req = GitPushReq {
+            remote: Some("--upload-pack=evil".to_string()),
+            branch: Some("main".to_string()),
+            set_upstream: false,
+        };
+        let err = push(&repo, &req, "").unwrap_err();
+        assert!(err.to_string().contains("invalid ref"));
+    }
+}
@@ -0,0 +1,326 @@
+// Interactive rebase without an interactive editor, for `GitRebasePlan` and
+// `GitRebaseExecute`.
+//
+// The plan is the todo list `git rebase -i HEAD~N` would open: the commits in
+// `HEAD~N..HEAD`, oldest first. Execution writes the edited list to a temp
+// file and runs `git rebase -i <base>` with `GIT_SEQUENCE_EDITOR` copying that
+// file over git's todo and `GIT_EDITOR=true`, so squash messages are joined
+// as-is. New messages (reword, or a squash with a message) are applied by an
+// `exec git commit --amend -F <file>` line after the step. If the rebase stops
+// (conflict, failing exec), it is aborted and the branch is left as it was.
+
+use std::fmt::Write as _;
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{bail, Context, Result};
+use zedra_rpc::proto::{
+    GitRebaseAction, GitRebaseCommit, GitRebaseExecuteReq, GitRebaseExecuteResult,
+    GitRebasePlanResult, GitRebaseStep, GIT_REBASE_PLAN_MAX,
+};
+
+use crate::git::GitRepo;
+
+/// Todo list for the last `count` commits.
+pub fn rebase_plan(repo: &GitRepo, count: u32) -> Result<GitRebasePlanResult> {
+    let total: u32 = repo
+        .git(&["rev-list", "--count", "--first-parent", "HEAD"])?
+        .trim()
+        .parse()
+        .context("unexpected git rev-list output")?;
+    if total < 2 {
+        bail!("nothing to rebase: the branch has a single commit");
+    }
+    // The root commit has no parent to rebase onto without `--root`.
+    let count = count.clamp(1, GIT_REBASE_PLAN_MAX).min(total - 1);
+    let base = repo
+        .git(&["rev-parse", &format!("HEAD~{count}")])?
+        .trim()
+        .to_string();
+    let commits = range_commits(repo, &base)?;
+    Ok(GitRebasePlanResult {
+        base,
+        commits,
+        error: None,
+    })
+}
+
+pub fn rebase_plan_error(error: String) -> GitRebasePlanResult {
+    GitRebasePlanResult {
+        error: Some(error),
+        ..Default::default()
+    }
+}
+
+/// Commits in `base..HEAD`, oldest first. Merges are refused: a plain
+/// `rebase -i` would flatten them.
+fn range_commits(repo: &GitRepo, base: &str) -> Result<Vec<GitRebaseCommit>> {
+    let range = format!("{base}..HEAD");
+    if !repo
+        .git(&["rev-list", "--merges", &range])?
+        .trim()
+        .is_empty()
+    {
+        bail!("the range contains merge commits, which the rebase plan does not support");
+    }
+    let out = repo.git(&[
+        "log",
+        "--reverse",
+        "--format=%H%x1f%s%x1f%an%x1f%at",
+        &range,
+    ])?;
+    Ok(out
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\x1f');
+            Some(GitRebaseCommit {
+                hash: fields.next()?.to_string(),
+                subject: fields.next()?.to_string(),
+                author: fields.next()?.to_string(),
+                timestamp: fields.next()?.parse().unwrap_or(0),
+            })
+        })
+        .collect())
+}
+
+/// Apply `req` and return the new `HEAD`.
+pub fn rebase_execute(repo: &GitRepo, req: &GitRebaseExecuteReq) -> Result<String> {
+    if !is_full_hash(&req.base) {
+        bail!("invalid rebase base: {}", req.base);
+    }
+    let mut planned: Vec<String> = range_commits(repo, &req.base)?
+        .into_iter()
+        .map(|commit| commit.hash)
+        .collect();
+    let mut requested: Vec<String> = req.steps.iter().map(|step| step.hash.clone()).collect();
+    planned.sort();
+    requested.sort();
+    if planned != requested {
+        bail!("the branch changed since the plan was loaded; reload it");
+    }
+    validate_steps(&req.steps)?;
+    let dirty = repo.git(&["status", "--porcelain", "--untracked-files=no"])?;
+    if !dirty.trim().is_empty() {
+        bail!("commit or stash your changes before rebasing");
+    }
+    let rebase_dir = repo.git(&["rev-parse", "--git-path", "rebase-merge"])?;
+    if repo.workdir().join(rebase_dir.trim()).exists() {
+        bail!("a rebase is already in progress on the host");
+    }
+
+    let scratch = tempfile::tempdir().context("failed to create rebase scratch dir")?;
+    let todo = build_todo(&req.steps, scratch.path())?;
+    let todo_path = scratch.path().join("git-rebase-todo");
+    std::fs::write(&todo_path, todo)?;
+
+    let output = Command::new("git")
+        .args(["rebase", "-i", "--no-autosquash", &req.base])
+        .current_dir(repo.workdir())
+        .env(
+            "GIT_SEQUENCE_EDITOR",
+            format!("cp {}", sh_quote(&todo_path.to_string_lossy())),
+        )
+        .env("GIT_EDITOR", "true")
+        .output()
+        .context("failed to run git rebase")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        let _ = repo.git(&["rebase", "--abort"]);
+        let detail = if stderr.is_empty() { stdout } else { stderr };
+        bail!("rebase stopped and was aborted, so the branch is unchanged: {detail}");
+    }
+    Ok(repo.git(&["rev-parse", "HEAD"])?.trim().to_string())
+}
+
+pub fn rebase_execute_error(error: String) -> GitRebaseExecuteResult {
+    GitRebaseExecuteResult {
+        error: Some(error),
+        ..Default::default()
+    }
+}
+
+fn validate_steps(steps: &[GitRebaseStep]) -> Result<()> {
+    let first_kept = steps
+        .iter()
+        .find(|step| step.action != GitRebaseAction::Drop);
+    if let Some(step) = first_kept {
+        if matches!(
+            step.action,
+            GitRebaseAction::Squash | GitRebaseAction::Fixup
+        ) {
+            bail!(
+                "the first kept commit cannot be squashed or fixed up; there is nothing before it"
+            );
+        }
+    }
+    for step in steps {
+        if !is_full_hash(&step.hash) {
+            bail!("invalid commit hash: {}", step.hash);
+        }
+        let empty_message = step
+            .message
+            .as_deref()
+            .map_or(true, |message| message.trim().is_empty());
+        if step.action == GitRebaseAction::Reword && empty_message {
+            bail!("reword of {} needs a message", &step.hash[..7]);
+        }
+    }
+    Ok(())
+}
+
+/// Todo file contents; new messages are written next to it in `scratch`.
+fn build_todo(steps: &[GitRebaseStep], scratch: &Path) -> Result<String> {
+    let mut todo = String::new();
+    for (index, step) in steps.iter().enumerate() {
+        let command = match step.action {
+            GitRebaseAction::Pick | GitRebaseAction::Reword => "pick",
+            GitRebaseAction::Squash => "squash",
+            GitRebaseAction::Fixup => "fixup",
+            GitRebaseAction::Drop => "drop",
+        };
+        let _ = writeln!(todo, "{command} {}", step.hash);
+        let message = step
+            .message
+            .as_deref()
+            .filter(|message| !message.trim().is_empty());
+        let amends = matches!(
+            step.action,
+            GitRebaseAction::Reword | GitRebaseAction::Squash
+        );
+        if let (true, Some(message)) = (amends, message) {
+            let path = scratch.join(format!("message-{index}"));
+            std::fs::write(&path, message)?;
+            let _ = writeln!(
+                todo,
+                "exec git commit --amend --allow-empty --quiet -F {}",
+                sh_quote(&path.to_string_lossy())
+            );
+        }
+    }
+    if steps
+        .iter()
+        .all(|step| step.action == GitRebaseAction::Drop)
+    {
+        // An empty todo aborts the rebase; `noop` resets the branch to base.
+        todo.push_str("noop\n");
+    }
+    Ok(todo)
+}
+
+fn is_full_hash(hash: &str) -> bool {
+    matches!(hash.len(), 40 | 64) && hash.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Quote for the POSIX shell git runs editors and `exec` lines with.
+fn sh_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {args:?} failed");
+    }
+
+    fn repo_with_commits(subjects: &[&str]) -> (tempfile::TempDir, GitRepo) {
+        let dir = tempfile::tempdir().unwrap();
+        git(dir.path(), &["init"]);
+        git(dir.path(), &["config", "user.email", "test@test.com"]);
+        git(dir.path(), &["config", "user.name", "Test"]);
+        for (index, subject) in subjects.iter().enumerate() {
+            std::fs::write(dir.path().join(format!("file{index}.txt")), subject).unwrap();
+            git(dir.path(), &["add", "."]);
+            git(dir.path(), &["commit", "-m", subject]);
+        }
+        let repo = GitRepo::open(dir.path()).unwrap();
+        (dir, repo)
+    }
+
+    fn step(
+        action: GitRebaseAction,
+        commit: &GitRebaseCommit,
+        message: Option<&str>,
+    ) -> GitRebaseStep {
+        GitRebaseStep {
+            action,
+            hash: commit.hash.clone(),
+            message: message.map(str::to_string),
+        }
+    }
+
+    fn subjects(repo: &GitRepo) -> Vec<String> {
+        repo.git(&["log", "--format=%s"])
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn plan_lists_commits_oldest_first_above_the_root() {
+        let (_dir, repo) = repo_with_commits(&["root", "one", "two"]);
+        let plan = rebase_plan(&repo, 10).unwrap();
+        let listed: Vec<&str> = plan.commits.iter().map(|c| c.subject.as_str()).collect();
+        assert_eq!(listed, vec!["one", "two"]);
+        assert_eq!(
+            plan.base,
+            repo.git(&["rev-parse", "HEAD~2"]).unwrap().trim()
+        );
+    }
+
+    #[test]
+    fn execute_reorders_rewords_squashes_and_drops() {
+        let (_dir, repo) = repo_with_commits(&["root", "a", "b", "c", "d"]);
+        let plan = rebase_plan(&repo, 4).unwrap();
+        let [a, b, c, d] = &plan.commits[..] else {
+            panic!("expected four commits");
+        };
+        let steps = vec![
+            step(GitRebaseAction::Reword, c, Some("c reworded")),
+            step(GitRebaseAction::Pick, a, None),
+            step(GitRebaseAction::Fixup, b, None),
+            step(GitRebaseAction::Drop, d, None),
+        ];
+        let head = rebase_execute(
+            &repo,
+            &GitRebaseExecuteReq {
+                base: plan.base.clone(),
+                steps,
+            },
+        )
+        .unwrap();
+        assert_eq!(head, repo.git(&["rev-parse", "HEAD"]).unwrap().trim());
+        assert_eq!(subjects(&repo), vec!["a", "c reworded", "root"]);
+        assert!(!repo.workdir().join("file4.txt").exists());
+        assert!(repo.workdir().join("file2.txt").exists());
+    }
+
+    #[test]
+    fn execute_rejects_stale_or_invalid_plans() {
+        let (_dir, repo) = repo_with_commits(&["root", "a", "b"]);
+        let plan = rebase_plan(&repo, 2).unwrap();
+        let only_first = GitRebaseExecuteReq {
+            base: plan.base.clone(),
+            steps: vec![step(GitRebaseAction::Pick, &plan.commits[0], None)],
+        };
+        assert!(rebase_execute(&repo, &only_first).is_err());
+
+        let squash_first = GitRebaseExecuteReq {
+            base: plan.base.clone(),
+            steps: vec![
+                step(GitRebaseAction::Squash, &plan.commits[0], None),
+                step(GitRebaseAction::Pick, &plan.commits[1], None),
+            ],
+        };
+        assert!(rebase_execute(&repo, &squash_first).is_err());
+        assert_eq!(subjects(&repo), vec!["b", "a", "root"]);
+    }
+}
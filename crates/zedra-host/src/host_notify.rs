@@ -0,0 +1,240 @@
+// Host notifications for `HostNotifySet`: optional watchers whose hits reach
+// the client as `HostEvent::Notification`.
+//
+// Every watcher is off until the client sends a config, so clients that
+// predate the event never receive it:
+//   watch_paths        polled with the session observer (every 2s); a file
+//                      notifies when its size or mtime changes, a directory
+//                      when one of its direct entries does
+//   long_command_secs  OSC 133 command start/end seen by the PTY reader of
+//                      any of the session's terminals
+//   disk_free_percent  free space on the workdir's disk, checked once a
+//                      minute; notifies once per drop below the threshold
+
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+use anyhow::{bail, Result};
+use zedra_rpc::proto::{HostNotification, HostNotifyConfig, HOST_NOTIFY_MAX_WATCH_PATHS};
+
+use crate::sandbox::resolve_path;
+use crate::session_registry::FinishedCommand;
+
+const DISK_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Reject configs the session would not be able to watch.
+pub fn validate(config: &HostNotifyConfig, workdir: &Path) -> Result<()> {
+    if config.watch_paths.len() > HOST_NOTIFY_MAX_WATCH_PATHS {
+        bail!("at most {HOST_NOTIFY_MAX_WATCH_PATHS} watched paths are allowed");
+    }
+    if config.disk_free_percent > 100 {
+        bail!("disk_free_percent must be at most 100");
+    }
+    for path in &config.watch_paths {
+        resolve_path(workdir, path)?;
+    }
+    Ok(())
+}
+
+/// The notification for a finished command, if it ran long enough.
+pub fn command_finished(
+    config: &HostNotifyConfig,
+    terminal_id: &str,
+    finished: &FinishedCommand,
+) -> Option<HostNotification> {
+    let threshold = Duration::from_secs(config.long_command_secs.into());
+    if config.long_command_secs == 0 || finished.duration < threshold {
+        return None;
+    }
+    Some(HostNotification::CommandFinished {
+        terminal_id: terminal_id.to_string(),
+        command: finished.command.clone(),
+        exit_code: finished.exit_code,
+        duration_secs: finished.duration.as_secs(),
+    })
+}
+
+/// Polled state for the path and disk watchers; one per session observer.
+#[derive(Default)]
+pub struct NotifyWatcher {
+    /// Last fingerprint per watched path; `None` while the path is missing.
+    snapshots: HashMap<String, Option<u64>>,
+    last_disk_check: Option<Instant>,
+    disk_low: bool,
+}
+
+impl NotifyWatcher {
+    /// Run one observer tick and return what to notify. A path's first poll
+    /// only records it, so enabling a watch does not notify.
+    pub fn poll(&mut self, workdir: &Path, config: &HostNotifyConfig) -> Vec<HostNotification> {
+        let mut notifications = Vec::new();
+        let mut snapshots = HashMap::new();
+        for path in &config.watch_paths {
+            let fingerprint = path_fingerprint(workdir, path);
+            if self
+                .snapshots
+                .get(path)
+                .is_some_and(|previous| *previous != fingerprint)
+            {
+                notifications.push(HostNotification::FileChanged { path: path.clone() });
+            }
+            snapshots.insert(path.clone(), fingerprint);
+        }
+        self.snapshots = snapshots;
+
+        if config.disk_free_percent == 0 {
+            self.disk_low = false;
+            return notifications;
+        }
+        if self
+            .last_disk_check
+            .is_some_and(|checked| checked.elapsed() < DISK_CHECK_INTERVAL)
+        {
+            return notifications;
+        }
+        self.last_disk_check = Some(Instant::now());
+        if let Some(disk) = disk_usage(workdir) {
+            let low = disk.available_bytes.saturating_mul(100)
+                < disk
+                    .total_bytes
+                    .saturating_mul(config.disk_free_percent.into());
+            if low && !self.disk_low {
+                notifications.push(HostNotification::DiskLow {
+                    mount_point: disk.mount_point,
+                    available_bytes: disk.available_bytes,
+                    total_bytes: disk.total_bytes,
+                });
+            }
+            self.disk_low = low;
+        }
+        notifications
+    }
+}
+
+/// Size and mtime of a file, or of each direct entry of a directory.
+fn path_fingerprint(workdir: &Path, rel_path: &str) -> Option<u64> {
+    let path = resolve_path(workdir, rel_path).ok()?;
+    let meta = std::fs::metadata(&path).ok()?;
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    if meta.is_dir() {
+        let mut rows: Vec<(String, bool, u64, u64)> = std::fs::read_dir(&path)
+            .ok()?
+            .flatten()
+            .filter_map(|entry| {
+                let meta = entry.metadata().ok()?;
+                Some((
+                    entry.file_name().to_string_lossy().into_owned(),
+                    meta.is_dir(),
+                    meta.len(),
+                    modified_nanos(&meta),
+                ))
+            })
+            .collect();
+        rows.sort();
+        rows.hash(&mut hasher);
+    } else {
+        (meta.len(), modified_nanos(&meta)).hash(&mut hasher);
+    }
+    Some(hasher.finish())
+}
+
+fn modified_nanos(meta: &std::fs::Metadata) -> u64 {
+    meta.modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|since| since.as_nanos() as u64)
+        .unwrap_or_default()
+}
+
+struct DiskUsage {
+    mount_point: String,
+    available_bytes: u64,
+    total_bytes: u64,
+}
+
+/// Space on the disk whose mount point is the longest prefix of `path`.
+fn disk_usage(path: &Path) -> Option<DiskUsage> {
+    let path = path.canonicalize().ok()?;
+    let disks = sysinfo::Disks::new_with_refreshed_list();
+    disks
+        .list()
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .map(|disk| DiskUsage {
+            mount_point: disk.mount_point().display().to_string(),
+            available_bytes: disk.available_space(),
+            total_bytes: disk.total_space(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn watched_paths_notify_on_change_but_not_on_first_poll() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("build.log"), "start").unwrap();
+        std::fs::create_dir(dir.path().join("out")).unwrap();
+        let config = HostNotifyConfig {
+            watch_paths: vec!["build.log".into(), "out".into(), "later.txt".into()],
+            ..Default::default()
+        };
+        let mut watcher = NotifyWatcher::default();
+        assert!(watcher.poll(dir.path(), &config).is_empty());
+        assert!(watcher.poll(dir.path(), &config).is_empty());
+
+        std::fs::write(dir.path().join("build.log"), "start\ndone").unwrap();
+        std::fs::write(dir.path().join("out").join("app"), "bin").unwrap();
+        std::fs::write(dir.path().join("later.txt"), "").unwrap();
+        let changed: Vec<_> = watcher
+            .poll(dir.path(), &config)
+            .into_iter()
+            .map(|notification| match notification {
+                HostNotification::FileChanged { path } => path,
+                other => panic!("unexpected {other:?}"),
+            })
+            .collect();
+        assert_eq!(changed, ["build.log", "out", "later.txt"]);
+        assert!(watcher.poll(dir.path(), &config).is_empty());
+    }
+
+    #[test]
+    fn only_long_commands_notify() {
+        let config = HostNotifyConfig {
+            long_command_secs: 30,
+            ..Default::default()
+        };
+        let finished = |secs| FinishedCommand {
+            command: Some("cargo build".into()),
+            exit_code: 1,
+            duration: Duration::from_secs(secs),
+        };
+        assert!(command_finished(&config, "t1", &finished(5)).is_none());
+        assert_eq!(
+            command_finished(&config, "t1", &finished(45)),
+            Some(HostNotification::CommandFinished {
+                terminal_id: "t1".into(),
+                command: Some("cargo build".into()),
+                exit_code: 1,
+                duration_secs: 45,
+            })
+        );
+        let off = HostNotifyConfig::default();
+        assert!(command_finished(&off, "t1", &finished(45)).is_none());
+    }
+
+    #[test]
+    fn rejects_paths_outside_the_workdir() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = HostNotifyConfig {
+            watch_paths: vec!["../elsewhere".into()],
+            ..Default::default()
+        };
+        assert!(validate(&config, dir.path()).is_err());
+        assert!(validate(&HostNotifyConfig::default(), dir.path()).is_ok());
+    }
+}
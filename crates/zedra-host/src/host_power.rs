@@ -0,0 +1,146 @@
+// Host power for `HostWakeInfo`, `HostWake` and `HostPower`.
+//
+// The app records this host's hardware addresses after it connects so it can
+// wake the host later, either from the device or through another connected
+// host (`HostWake`). Sleep and reboot only run when the daemon was started
+// with `--allow-power`; the app asks for confirmation before sending them.
+// The command starts after a short delay so the reply reaches the device
+// first, which means a failure after the reply is only logged here.
+
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use zedra_rpc::proto::{HostPowerAction, HostWakeInfoResult, HostWakeInterface};
+use zedra_rpc::wake;
+
+const POWER_DELAY: Duration = Duration::from_secs(1);
+
+/// Interfaces worth waking: a globally administered unicast address that is
+/// not loopback. Randomized and virtual interfaces use locally administered
+/// addresses, which no NIC answers a magic packet for.
+fn is_wake_candidate(name: &str, mac: [u8; 6]) -> bool {
+    mac != [0; 6] && mac[0] & 0x03 == 0 && !name.starts_with("lo")
+}
+
+pub fn wake_info(power_actions: bool) -> HostWakeInfoResult {
+    let networks = sysinfo::Networks::new_with_refreshed_list();
+    let mut interfaces: Vec<HostWakeInterface> = networks
+        .iter()
+        .filter(|(name, data)| is_wake_candidate(name, data.mac_address().0))
+        .map(|(name, data)| HostWakeInterface {
+            name: name.clone(),
+            mac: wake::format_mac(data.mac_address().0),
+        })
+        .collect();
+    interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+    interfaces.dedup_by(|a, b| a.mac == b.mac);
+    HostWakeInfoResult {
+        interfaces,
+        power_actions,
+        error: None,
+    }
+}
+
+/// Broadcast a magic packet for `mac` on this host's network.
+pub fn wake(mac: &str, broadcast: Option<&str>) -> Result<()> {
+    let mac = wake::parse_mac(mac).with_context(|| format!("invalid MAC address: {mac:?}"))?;
+    let broadcast = broadcast
+        .map(|addr| {
+            addr.parse::<Ipv4Addr>()
+                .with_context(|| format!("invalid broadcast address: {addr:?}"))
+        })
+        .transpose()?;
+    wake::send(mac, broadcast).context("failed to send wake packet")
+}
+
+fn power_command(action: HostPowerAction) -> (&'static str, Vec<&'static str>) {
+    if cfg!(target_os = "macos") {
+        match action {
+            HostPowerAction::Sleep => ("pmset", vec!["sleepnow"]),
+            HostPowerAction::Reboot => (
+                "osascript",
+                vec!["-e", "tell application \"System Events\" to restart"],
+            ),
+        }
+    } else if cfg!(windows) {
+        match action {
+            HostPowerAction::Sleep => (
+                "rundll32.exe",
+                vec!["powrprof.dll,SetSuspendState", "0,1,0"],
+            ),
+            HostPowerAction::Reboot => ("shutdown", vec!["/r", "/t", "0"]),
+        }
+    } else {
+        match action {
+            HostPowerAction::Sleep => ("systemctl", vec!["suspend"]),
+            HostPowerAction::Reboot => ("systemctl", vec!["reboot"]),
+        }
+    }
+}
+
+/// Schedule `action` if this daemon allows it.
+pub fn power(action: HostPowerAction, allowed: bool) -> Result<()> {
+    if !allowed {
+        bail!("power actions are disabled; restart the daemon with `zedra start --allow-power`");
+    }
+    let (program, args) = power_command(action);
+    tracing::info!(
+        "host power: {:?} via {} in {:?}",
+        action,
+        program,
+        POWER_DELAY
+    );
+    tokio::spawn(async move {
+        tokio::time::sleep(POWER_DELAY).await;
+        match tokio::process::Command::new(program)
+            .args(&args)
+            .output()
+            .await
+        {
+            Ok(output) if output.status.success() => {}
+            Ok(output) => tracing::warn!(
+                "host power: {} failed: {}",
+                program,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+            Err(e) => tracing::warn!("host power: failed to run {}: {}", program, e),
+        }
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wake_candidates_skip_loopback_and_local_addresses() {
+        assert!(is_wake_candidate(
+            "en0",
+            [0x3c, 0x22, 0xfb, 0x01, 0x02, 0x03]
+        ));
+        assert!(!is_wake_candidate(
+            "lo0",
+            [0x3c, 0x22, 0xfb, 0x01, 0x02, 0x03]
+        ));
+        assert!(!is_wake_candidate("eth0", [0; 6]));
+        assert!(!is_wake_candidate(
+            "docker0",
+            [0x02, 0x42, 0xac, 0x11, 0, 2]
+        ));
+        assert!(!is_wake_candidate("eth1", [0x01, 0, 0x5e, 0, 0, 1]));
+    }
+
+    #[test]
+    fn wake_rejects_bad_addresses() {
+        assert!(wake("not-a-mac", None).is_err());
+        assert!(wake("00:1a:2b:3c:4d:5e", Some("10.0.0")).is_err());
+    }
+
+    #[tokio::test]
+    async fn power_is_refused_unless_allowed() {
+        let error = power(HostPowerAction::Reboot, false).unwrap_err();
+        assert!(error.to_string().contains("--allow-power"));
+    }
+}
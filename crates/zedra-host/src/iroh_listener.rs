@@ -3,13 +3,15 @@
 // Uses irpc typed protocol over QUIC. Each connection goes through PKI auth
 // (Register/Authenticate/AuthProve) then enters the dispatch loop.
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use std::net::SocketAddr;
 use std::sync::Arc;
 
 use crate::rpc_daemon::{self, DaemonState};
 use crate::session_registry::SessionRegistry;
 use zedra_rpc::proto::ZEDRA_ALPN;
 use zedra_rpc::proto_v3::ZEDRA_ALPN_V3;
+use zedra_rpc::proto_v4::ZEDRA_ALPN_V4;
 use zedra_telemetry::Event;
 
 use crate::identity::SharedIdentity;
@@ -50,11 +52,14 @@ fn relay_map_from_urls(urls: &[&str]) -> Result<iroh::RelayMap> {
 /// iroh probes all relays and picks the lowest-latency one as preferred.
 /// `relay_only` disables all direct UDP transports and pkarr address publishing
 /// so all traffic goes through the relay — useful behind strict firewalls.
+/// `bind_addrs` replaces iroh's default dual-stack wildcard sockets with explicit
+/// local addresses (IPv4 and/or IPv6); empty keeps the defaults.
 /// Returns the endpoint ready for accepting connections and QR code generation.
 pub async fn create_endpoint(
     identity: &SharedIdentity,
     relay_urls: &[String],
     relay_only: bool,
+    bind_addrs: &[SocketAddr],
 ) -> Result<iroh::Endpoint> {
     let urls: Vec<&str> = if relay_urls.is_empty() {
         zedra_rpc::ZEDRA_RELAY_URLS.to_vec()
@@ -64,21 +69,40 @@ pub async fn create_endpoint(
     let relay_mode = iroh::RelayMode::Custom(relay_map_from_urls(&urls)?);
     let mut builder = iroh::Endpoint::builder()
         .secret_key(identity.iroh_secret_key().clone())
-        // Current ALPN first; the previous one keeps older clients connecting.
-        .alpns(vec![ZEDRA_ALPN.to_vec(), ZEDRA_ALPN_V3.to_vec()])
+        // Current ALPN first; the previous ones keep older clients connecting.
+        .alpns(vec![
+            ZEDRA_ALPN.to_vec(),
+            ZEDRA_ALPN_V4.to_vec(),
+            ZEDRA_ALPN_V3.to_vec(),
+        ])
         .relay_mode(relay_mode);
     if relay_only {
         // Skip pkarr address publishing — no direct addresses to advertise.
         builder = builder.clear_ip_transports();
+        if !bind_addrs.is_empty() {
+            tracing::warn!(
+                "iroh: ignoring {} bind address(es) in relay-only mode",
+                bind_addrs.len()
+            );
+        }
     } else {
+        if !bind_addrs.is_empty() {
+            builder = builder.clear_ip_transports();
+            for addr in bind_addrs {
+                builder = builder
+                    .bind_addr(*addr)
+                    .with_context(|| format!("invalid bind address {addr}"))?;
+            }
+        }
         builder = builder.address_lookup(iroh::address_lookup::PkarrPublisher::n0_dns());
     }
     let endpoint = builder.bind().await?;
 
     tracing::info!(
-        "iroh endpoint bound: {} (relay_only={})",
+        "iroh endpoint bound: {} (relay_only={}, bind={:?})",
         endpoint.id().fmt_short(),
-        relay_only
+        relay_only,
+        endpoint.bound_sockets(),
     );
     tracing::info!("iroh endpoint addr: {:?}", endpoint.addr());
 
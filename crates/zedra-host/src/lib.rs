@@ -1,29 +1,53 @@
 // zedra-host library — re-exports for integration tests
 
 pub mod agent;
+pub mod ai;
+pub mod ai_conversations;
 pub mod api;
 pub mod client;
+pub mod data_saver;
 pub mod delta;
+pub mod diagnostics;
 pub mod docs_tree;
+pub mod forge;
 pub mod fs;
+pub mod fs_grep;
+pub mod fs_replace;
 #[cfg(all(feature = "telemetry", not(feature = "no-telemetry")))]
 pub mod ga4;
 #[cfg(any(not(feature = "telemetry"), feature = "no-telemetry"))]
 #[path = "ga4_stub.rs"]
 pub mod ga4;
 pub mod git;
+pub mod git_discover;
+pub mod git_graph;
+pub mod git_history;
+pub mod git_hunks;
+pub mod git_push;
+pub mod git_rebase;
 pub mod host_info;
+pub mod host_notify;
+pub mod host_power;
 pub mod identity;
 pub mod iroh_listener;
 pub mod metrics;
 pub mod net_monitor;
 pub mod paths;
+pub mod projects;
 pub mod pty;
 pub mod qr;
+pub mod recording;
 pub mod rpc_daemon;
+pub mod sandbox;
+pub mod session_env;
 pub mod session_registry;
+pub mod shell_history;
+pub mod shell_integration;
 pub mod sqlite_readonly;
+pub mod ssh_hosts;
+pub mod tasks;
 pub mod telemetry;
+pub mod text_file;
 pub mod uploads;
 pub mod utils;
 pub mod version_check;
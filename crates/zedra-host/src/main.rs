@@ -19,12 +19,13 @@ use zedra_host::agent::cli as agent_cli;
 use zedra_host::client as zedra_client;
 use zedra_host::ga4::Ga4;
 use zedra_host::{
-    api, delta, identity, iroh_listener, metrics, net_monitor, paths, qr, rpc_daemon,
-    session_registry, uploads, utils, version_check, workspace_lock,
+    api, delta, identity, iroh_listener, metrics, net_monitor, paths, projects, qr, recording,
+    rpc_daemon, session_registry, ssh_hosts, uploads, utils, version_check, workspace_lock,
 };
 use zedra_rpc::ZedraPairingTicket;
 use zedra_telemetry::Event;
 
+mod git_credential_cli;
 mod terminal_cli;
 mod webview_cli;
 
@@ -96,6 +97,18 @@ enum Commands {
         #[arg(long)]
         relay_only: bool,
 
+        /// Local socket address to bind direct UDP transports to. Can be specified
+        /// multiple times (e.g. --bind 0.0.0.0:4433 --bind [::]:4433). Defaults
+        /// to dual-stack wildcard addresses on OS-assigned ports. Ignored with
+        /// --relay-only.
+        #[arg(long = "bind", value_name = "ADDR")]
+        bind: Vec<std::net::SocketAddr>,
+
+        /// Record terminal output as asciicast v2 files for later review.
+        /// List and export them with `zedra sessions`.
+        #[arg(long)]
+        record: bool,
+
         /// Generate a reusable QR code that stays valid for repeated scans
         /// while this daemon runs, instead of a fresh code per pairing
         #[arg(long = "static-qr")]
@@ -106,6 +119,24 @@ enum Commands {
         /// Default: 300 (5 minutes).
         #[arg(long = "usage-refresh-secs", default_value = "300")]
         usage_refresh_secs: u64,
+
+        /// Let paired devices put this machine to sleep or reboot it
+        #[arg(long = "allow-power")]
+        allow_power: bool,
+
+        /// Let file requests also reach absolute paths under this directory (repeatable)
+        #[arg(long = "allow-root")]
+        allow_root: Vec<PathBuf>,
+
+        /// Close a session's terminals after it has had no client attached for
+        /// this many seconds. Default: 0 (terminals live until the daemon stops).
+        #[arg(long = "terminal-idle-timeout", default_value = "0")]
+        terminal_idle_timeout: u64,
+
+        /// Device that can see and close every session's terminals, by the hex
+        /// key shown as Device key in the app's session panel (repeatable)
+        #[arg(long = "admin-client", value_parser = parse_client_pubkey)]
+        admin_client: Vec<[u8; 32]>,
     },
     /// Stop the daemon for this workspace
     Stop {
@@ -170,6 +201,26 @@ enum Commands {
         stale: bool,
     },
 
+    /// List or export recorded terminal sessions (`zedra start --record`)
+    #[command(alias = "recordings")]
+    Sessions {
+        #[command(subcommand)]
+        command: SessionsCommand,
+    },
+
+    /// Manage project bookmarks shown to paired devices
+    Bookmarks {
+        #[command(subcommand)]
+        command: BookmarksCommand,
+    },
+
+    /// Import hosts from ~/.ssh/config so paired devices can open them
+    ImportSshConfig {
+        /// ssh config to read instead of ~/.ssh/config
+        #[arg(long, value_name = "PATH")]
+        config: Option<String>,
+    },
+
     /// Show recent daemon logs
     Logs {
         /// Working directory of the running daemon
@@ -187,6 +238,10 @@ enum Commands {
     /// Open a web app / URL in the connected phone's in-app webview
     Open(webview_cli::OpenArgs),
 
+    /// Git credential helper for pushes started from the app
+    #[command(hide = true)]
+    GitCredential(git_credential_cli::GitCredentialArgs),
+
     /// Set up Zedra for detected AI agents, or a specific one
     Setup {
         /// Use absolute path to this binary in hooks instead of `zedra`
@@ -286,6 +341,50 @@ enum Commands {
     },
 }
 
+#[derive(Subcommand)]
+enum BookmarksCommand {
+    /// List bookmarked projects and running workspaces
+    List,
+
+    /// Bookmark a project directory
+    Add {
+        /// Project directory
+        #[arg(default_value = ".")]
+        workdir: String,
+    },
+
+    /// Remove a project bookmark
+    Remove {
+        /// Project directory
+        #[arg(default_value = ".")]
+        workdir: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum SessionsCommand {
+    /// List recorded sessions for a workspace, newest first
+    List {
+        /// Working directory of the workspace
+        #[arg(short, long, default_value = ".")]
+        workdir: String,
+    },
+
+    /// Write a recording to a file, or stdout when no output is given
+    Export {
+        /// Recording file name, as shown by `zedra sessions list`
+        name: String,
+
+        /// Working directory of the workspace
+        #[arg(short, long, default_value = ".")]
+        workdir: String,
+
+        /// Destination file
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
 #[derive(Subcommand)]
 enum AuthCommand {
     /// Sign in this host with Zedra Delta
@@ -438,8 +537,14 @@ struct DetachedStartOptions {
     no_telemetry: bool,
     debug_telemetry: bool,
     relay_only: bool,
+    bind: Vec<std::net::SocketAddr>,
+    record: bool,
     static_qr: bool,
     usage_refresh_secs: u64,
+    allow_power: bool,
+    allow_root: Vec<PathBuf>,
+    terminal_idle_timeout: u64,
+    admin_client: Vec<[u8; 32]>,
 }
 
 struct DetachedStartResult {
@@ -469,6 +574,12 @@ fn detached_start_child_args(options: &DetachedStartOptions) -> Vec<String> {
     if options.relay_only {
         args.push("--relay-only".to_string());
     }
+    for addr in &options.bind {
+        args.extend(["--bind".to_string(), addr.to_string()]);
+    }
+    if options.record {
+        args.push("--record".to_string());
+    }
     if options.static_qr {
         args.push("--static-qr".to_string());
     }
@@ -478,9 +589,31 @@ fn detached_start_child_args(options: &DetachedStartOptions) -> Vec<String> {
             options.usage_refresh_secs.to_string(),
         ]);
     }
+    if options.allow_power {
+        args.push("--allow-power".to_string());
+    }
+    for root in &options.allow_root {
+        args.extend(["--allow-root".to_string(), root.display().to_string()]);
+    }
+    if options.terminal_idle_timeout > 0 {
+        args.extend([
+            "--terminal-idle-timeout".to_string(),
+            options.terminal_idle_timeout.to_string(),
+        ]);
+    }
+    for key in &options.admin_client {
+        args.extend(["--admin-client".to_string(), hex::encode(key)]);
+    }
     args
 }
 
+fn parse_client_pubkey(value: &str) -> Result<[u8; 32], String> {
+    let bytes = hex::decode(value.trim()).map_err(|e| format!("not a hex key: {e}"))?;
+    bytes
+        .try_into()
+        .map_err(|_| "expected a 64-character hex key".to_string())
+}
+
 #[cfg(unix)]
 fn start_detached(options: DetachedStartOptions) -> Result<DetachedStartResult> {
     use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
@@ -940,10 +1073,17 @@ async fn main() -> Result<()> {
             no_telemetry,
             debug_telemetry,
             relay_only,
+            bind,
+            record,
             static_qr,
             usage_refresh_secs,
+            allow_power,
+            allow_root,
+            terminal_idle_timeout,
+            admin_client,
         } => {
             let workdir = resolve_workdir(workdir);
+            let allow_root: Vec<PathBuf> = allow_root.iter().map(resolve_workdir).collect();
             let pairing_mode = if static_qr {
                 session_registry::PairingSlotMode::Static
             } else {
@@ -957,8 +1097,14 @@ async fn main() -> Result<()> {
                     no_telemetry,
                     debug_telemetry,
                     relay_only,
+                    bind,
+                    record,
                     static_qr,
                     usage_refresh_secs,
+                    allow_power,
+                    allow_root,
+                    terminal_idle_timeout,
+                    admin_client,
                 })?;
                 match wait_for_detached_pairing_qr(&detached.workdir, detached.pid, pairing_mode)
                     .await
@@ -985,6 +1131,12 @@ async fn main() -> Result<()> {
 
             let _lock = workspace_lock::acquire(&workdir)?;
             tracing::info!("Acquired workspace lock for {}", workdir.display());
+            if record {
+                match recording::enable(&workdir) {
+                    Ok(dir) => tracing::info!("recording: enabled in {}", dir.display()),
+                    Err(e) => utils::eprintln_warn(format!("Terminal recording disabled: {e:#}")),
+                }
+            }
             let start_mode = if std::env::var_os("ZEDRA_DETACHED").is_some() {
                 metrics::DaemonStartMode::Detached
             } else {
@@ -1000,9 +1152,7 @@ async fn main() -> Result<()> {
                 Err(e) => anyhow::bail!("Failed to load host identity: {}", e),
             };
 
-            let sessions_path = identity::workspace_config_dir(&workdir)
-                .map(|d| d.join("sessions.json"))
-                .unwrap_or_else(|_| workdir.join(".zedra-sessions.json"));
+            let sessions_path = session_registry::registry_path(&workdir);
             let registry = std::sync::Arc::new(
                 session_registry::SessionRegistry::load_or_new(sessions_path).await,
             );
@@ -1083,12 +1233,19 @@ async fn main() -> Result<()> {
 
             let delta_client = delta::DeltaClient::try_load();
 
-            let state = Arc::new(rpc_daemon::DaemonState::new(
+            let mut state = rpc_daemon::DaemonState::new(
                 workdir.clone(),
                 host_identity.clone(),
                 delta_pubkey,
                 delta_client,
-            ));
+            );
+            state.allow_power = allow_power;
+            state.admin_clients = admin_client.into_iter().collect();
+            state.sandbox = state.sandbox.clone().with_extra_roots(allow_root);
+            for root in state.sandbox.extra_roots() {
+                tracing::info!("Allowing file access under {}", root.display());
+            }
+            let state = Arc::new(state);
             state
                 .agent_cache
                 .set_registry(Arc::downgrade(&registry))
@@ -1112,6 +1269,18 @@ async fn main() -> Result<()> {
                     }
                 });
             }
+            if terminal_idle_timeout > 0 {
+                let registry = registry.clone();
+                let idle_timeout = std::time::Duration::from_secs(terminal_idle_timeout);
+                tokio::spawn(async move {
+                    let mut interval =
+                        tokio::time::interval(idle_timeout.min(std::time::Duration::from_secs(60)));
+                    loop {
+                        interval.tick().await;
+                        registry.reap_idle_terminals(idle_timeout).await;
+                    }
+                });
+            }
             uploads::spawn_startup_cleanup();
 
             // 1. Bind iroh endpoint with configured relay URLs.
@@ -1136,9 +1305,13 @@ async fn main() -> Result<()> {
 
             let init_ms = startup_start.elapsed().as_millis() as u64;
             let endpoint_bind_start = std::time::Instant::now();
-            let endpoint =
-                iroh_listener::create_endpoint(&host_identity, &endpoint_relay_urls, relay_only)
-                    .await?;
+            let endpoint = iroh_listener::create_endpoint(
+                &host_identity,
+                &endpoint_relay_urls,
+                relay_only,
+                &bind,
+            )
+            .await?;
             let endpoint_bind_ms = endpoint_bind_start.elapsed().as_millis() as u64;
 
             // Pre-authorize the persistent CLI client key so `zedra client` can
@@ -1405,6 +1578,10 @@ async fn main() -> Result<()> {
             webview_cli::run(args).await?;
         }
 
+        Commands::GitCredential(args) => {
+            git_credential_cli::run(args).await?;
+        }
+
         Commands::Agent { command } => {
             agent_cli::run(command).await?;
         }
@@ -1487,6 +1664,154 @@ async fn main() -> Result<()> {
             }
         }
 
+        Commands::Sessions { command } => match command {
+            SessionsCommand::List { workdir } => {
+                let workdir = resolve_workdir(workdir);
+                let sessions = recording::sessions(&workdir)?;
+                if sessions.is_empty() {
+                    utils::println_note(format!(
+                        "No recordings for: {}. Start the daemon with `zedra start --record`.",
+                        workdir.display()
+                    ));
+                } else {
+                    let now = std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .map(|d| d.as_secs())
+                        .unwrap_or(0);
+                    let rows: Vec<Vec<String>> = sessions
+                        .iter()
+                        .map(|s| {
+                            let terminal = match (s.terminal_id.as_str(), s.part) {
+                                ("", _) => "-".to_string(),
+                                (id, 0) => id.to_string(),
+                                (id, part) => format!("{id} (part {part})"),
+                            };
+                            vec![
+                                s.recording.name.clone(),
+                                terminal,
+                                format!(
+                                    "{} ago",
+                                    utils::format_duration(now.saturating_sub(s.started_secs))
+                                ),
+                                utils::format_duration(s.duration_secs),
+                                format!("{} KB", s.recording.size.div_ceil(1024)),
+                            ]
+                        })
+                        .collect();
+                    println!(
+                        "{}",
+                        utils::render_table(
+                            &["NAME", "TERMINAL", "STARTED", "DURATION", "SIZE"],
+                            &rows
+                        )
+                    );
+                }
+            }
+            SessionsCommand::Export {
+                name,
+                workdir,
+                output,
+            } => {
+                let workdir = resolve_workdir(workdir);
+                match output {
+                    Some(path) => {
+                        let mut file = std::fs::File::create(&path)
+                            .with_context(|| format!("failed to create {}", path.display()))?;
+                        recording::export(&workdir, &name, &mut file)?;
+                        utils::eprintln_success(format!("Exported {name} to {}", path.display()));
+                    }
+                    None => recording::export(&workdir, &name, &mut std::io::stdout().lock())?,
+                }
+            }
+        },
+
+        Commands::Bookmarks { command } => match command {
+            BookmarksCommand::List => {
+                let cwd = resolve_workdir(".");
+                let projects = projects::list_projects(&cwd)?;
+                if projects.is_empty() {
+                    utils::println_note("No bookmarks. Add one with `zedra bookmarks add <dir>`.");
+                } else {
+                    let rows: Vec<Vec<String>> = projects
+                        .iter()
+                        .map(|p| {
+                            vec![
+                                p.name.clone(),
+                                p.path.clone(),
+                                if p.bookmarked { "yes" } else { "-" }.to_string(),
+                                if p.running { "running" } else { "stopped" }.to_string(),
+                            ]
+                        })
+                        .collect();
+                    println!(
+                        "{}",
+                        utils::render_table(&["NAME", "PATH", "BOOKMARKED", "DAEMON"], &rows)
+                    );
+                }
+            }
+            BookmarksCommand::Add { workdir } => {
+                let workdir = resolve_workdir(workdir);
+                if !workdir.is_dir() {
+                    anyhow::bail!("not a directory: {}", workdir.display());
+                }
+                if projects::add_bookmark(&workdir)? {
+                    utils::eprintln_success(format!("Bookmarked {}", workdir.display()));
+                } else {
+                    utils::eprintln_note(format!("Already bookmarked: {}", workdir.display()));
+                }
+            }
+            BookmarksCommand::Remove { workdir } => {
+                let workdir = resolve_workdir(workdir);
+                if projects::remove_bookmark(&workdir)? {
+                    utils::eprintln_success(format!("Removed bookmark {}", workdir.display()));
+                } else {
+                    utils::eprintln_warn(format!("Not bookmarked: {}", workdir.display()));
+                }
+            }
+        },
+
+        Commands::ImportSshConfig { config } => {
+            let source = match config {
+                Some(path) => PathBuf::from(path),
+                None => ssh_hosts::default_ssh_config()?,
+            };
+            let (hosts, dropped) = ssh_hosts::import(&source)?;
+            if hosts.is_empty() {
+                utils::println_note(format!(
+                    "No hosts found in {}. Wildcard patterns are not imported.",
+                    source.display()
+                ));
+            } else {
+                let rows: Vec<Vec<String>> = hosts
+                    .iter()
+                    .map(|host| {
+                        vec![
+                            host.alias.clone(),
+                            host.hostname.clone().unwrap_or_else(|| "-".to_string()),
+                            host.user.clone().unwrap_or_else(|| "-".to_string()),
+                            host.port.map_or_else(|| "-".to_string(), |p| p.to_string()),
+                            host.proxy_jump.clone().unwrap_or_else(|| "-".to_string()),
+                        ]
+                    })
+                    .collect();
+                println!(
+                    "{}",
+                    utils::render_table(&["HOST", "HOSTNAME", "USER", "PORT", "PROXYJUMP"], &rows)
+                );
+            }
+            if dropped > 0 {
+                utils::eprintln_warn(format!(
+                    "Skipped {dropped} hosts over the limit of {}",
+                    zedra_rpc::proto::SSH_HOSTS_MAX
+                ));
+            }
+            utils::eprintln_success(format!(
+                "Imported {} hosts from {}",
+                hosts.len(),
+                source.display()
+            ));
+        }
+
         Commands::Logs { workdir, lines } => {
             let workdir = resolve_workdir(workdir);
             let log_path = daemon_log_path(&workdir)?;
@@ -2122,7 +2447,7 @@ fn render_status_output(status: &serde_json::Value) -> String {
             sections.push(String::new());
             sections.push("Terminals".to_string());
             sections.push(utils::render_table(
-                &["ID", "TITLE", "CREATED", "UPTIME", "SESSION"],
+                &["ID", "TITLE", "CREATED", "UPTIME", "SESSION", "OWNER"],
                 &terminals
                     .iter()
                     .map(terminal_status_row)
@@ -2339,8 +2664,12 @@ fn terminal_status_row(terminal: &serde_json::Value) -> Vec<String> {
         .map(str::to_string)
         .or_else(|| terminal["session_id"].as_str().map(short_id))
         .unwrap_or_else(|| "-".to_string());
+    let owner = terminal["owner"]
+        .as_str()
+        .map(short_id)
+        .unwrap_or_else(|| "host".to_string());
 
-    vec![id, title.to_string(), created, uptime, session]
+    vec![id, title.to_string(), created, uptime, session, owner]
 }
 
 fn non_empty_str(value: &serde_json::Value) -> Option<&str> {
@@ -3059,8 +3388,17 @@ mod tests {
             no_telemetry: true,
             debug_telemetry: true,
             relay_only: true,
+            bind: vec![
+                "0.0.0.0:4433".parse().unwrap(),
+                "[::]:4433".parse().unwrap(),
+            ],
+            record: true,
             static_qr: true,
             usage_refresh_secs: 300,
+            allow_power: true,
+            allow_root: vec![PathBuf::from("/srv/shared")],
+            terminal_idle_timeout: 3600,
+            admin_client: vec![[0xab; 32]],
         });
 
         assert_eq!(
@@ -3077,7 +3415,19 @@ mod tests {
                 "--no-telemetry",
                 "--debug-telemetry",
                 "--relay-only",
+                "--bind",
+                "0.0.0.0:4433",
+                "--bind",
+                "[::]:4433",
+                "--record",
                 "--static-qr",
+                "--allow-power",
+                "--allow-root",
+                "/srv/shared",
+                "--terminal-idle-timeout",
+                "3600",
+                "--admin-client",
+                "ab".repeat(32).as_str(),
             ]
         );
     }
@@ -3164,7 +3514,8 @@ mod tests {
                     "title": "zsh",
                     "created_at_elapsed_secs": 10,
                     "uptime_secs": 10,
-                    "session_name": "repo"
+                    "session_name": "repo",
+                    "owner": "0123456789abcdef"
                 }
             ]
         });
@@ -3178,6 +3529,7 @@ mod tests {
         assert!(output.contains("connected"));
         assert!(output.contains("Terminals"));
         assert!(output.contains("zsh"));
+        assert!(output.contains("01234567"));
     }
 
     #[test]
@@ -0,0 +1,212 @@
+// Host project bookmarks for the `HostProjects` RPC and `zedra bookmarks`.
+//
+// Bookmarks live in `~/.config/zedra/projects.json`. The listing merges them with
+// workspaces that currently have a running daemon, keeping only the ones the
+// asking device has paired with, so the app can switch between them. Each
+// project is still served by its own daemon; the listing never grants access to
+// another workspace, and unpaired workspaces are not revealed.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use zedra_rpc::proto::HostProject;
+
+use crate::{identity, paths, session_registry, workspace_lock};
+
+const CONFIG_FILE: &str = "projects.json";
+
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ProjectsConfig {
+    #[serde(default)]
+    pub bookmarks: Vec<PathBuf>,
+}
+
+pub fn config_path() -> Result<PathBuf> {
+    Ok(identity::host_config_dir()?.join(CONFIG_FILE))
+}
+
+pub fn load_config() -> Result<ProjectsConfig> {
+    load_config_at(&config_path()?)
+}
+
+fn load_config_at(path: &Path) -> Result<ProjectsConfig> {
+    match std::fs::read_to_string(path) {
+        Ok(json) => serde_json::from_str(&json)
+            .with_context(|| format!("failed to parse {}", path.display())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(ProjectsConfig::default()),
+        Err(e) => Err(e).with_context(|| format!("failed to read {}", path.display())),
+    }
+}
+
+fn save_config_at(path: &Path, config: &ProjectsConfig) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+    std::fs::write(path, serde_json::to_vec_pretty(config)?)
+        .with_context(|| format!("failed to write {}", path.display()))
+}
+
+/// Add `workdir` to the bookmarks. Returns `false` when it was already present.
+pub fn add_bookmark(workdir: &Path) -> Result<bool> {
+    add_bookmark_at(&config_path()?, workdir)
+}
+
+fn add_bookmark_at(path: &Path, workdir: &Path) -> Result<bool> {
+    let mut config = load_config_at(path)?;
+    if config.bookmarks.iter().any(|b| b == workdir) {
+        return Ok(false);
+    }
+    config.bookmarks.push(workdir.to_path_buf());
+    save_config_at(path, &config)?;
+    Ok(true)
+}
+
+/// Remove `workdir` from the bookmarks. Returns `false` when it was not bookmarked.
+pub fn remove_bookmark(workdir: &Path) -> Result<bool> {
+    remove_bookmark_at(&config_path()?, workdir)
+}
+
+fn remove_bookmark_at(path: &Path, workdir: &Path) -> Result<bool> {
+    let mut config = load_config_at(path)?;
+    let before = config.bookmarks.len();
+    config.bookmarks.retain(|b| b != workdir);
+    if config.bookmarks.len() == before {
+        return Ok(false);
+    }
+    save_config_at(path, &config)?;
+    Ok(true)
+}
+
+fn project_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| paths::user_path_string(path))
+}
+
+/// Bookmarks first (in saved order), then other running workspaces by path.
+fn merge_projects(
+    bookmarks: &[PathBuf],
+    running: &[PathBuf],
+    current_workdir: &Path,
+) -> Vec<HostProject> {
+    let mut projects: Vec<HostProject> = Vec::new();
+    let mut push = |path: &Path, bookmarked: bool| {
+        if projects
+            .iter()
+            .any(|p| Path::new(&p.path) == paths::user_path(path))
+        {
+            return;
+        }
+        projects.push(HostProject {
+            name: project_name(path),
+            path: paths::user_path_string(path),
+            bookmarked,
+            running: running.iter().any(|r| r == path),
+            is_current: path == current_workdir,
+        });
+    };
+    for path in bookmarks {
+        push(path, true);
+    }
+    let mut others: Vec<&PathBuf> = running.iter().collect();
+    others.sort();
+    for path in others {
+        push(path, false);
+    }
+    projects
+}
+
+/// Keep the current workspace and those `paired` accepts.
+fn retain_paired(
+    paths: &[PathBuf],
+    current_workdir: &Path,
+    paired: impl Fn(&Path) -> bool,
+) -> Vec<PathBuf> {
+    paths
+        .iter()
+        .filter(|path| path.as_path() == current_workdir || paired(path))
+        .cloned()
+        .collect()
+}
+
+/// Projects this host knows about that `client_pubkey` has paired with,
+/// relative to the daemon serving `current_workdir`.
+pub fn list_projects(current_workdir: &Path, client_pubkey: &[u8; 32]) -> Result<Vec<HostProject>> {
+    let config = load_config()?;
+    let running: Vec<PathBuf> = workspace_lock::scan_all_instances()
+        .into_iter()
+        .filter(|(_, _, alive)| *alive)
+        .map(|(_, lock, _)| PathBuf::from(lock.workdir))
+        .collect();
+    let paired = |path: &Path| {
+        session_registry::is_authorized_in_file(
+            &session_registry::registry_path(path),
+            client_pubkey,
+        )
+    };
+    Ok(merge_projects(
+        &retain_paired(&config.bookmarks, current_workdir, paired),
+        &retain_paired(&running, current_workdir, paired),
+        current_workdir,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_and_remove_bookmarks() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CONFIG_FILE);
+        assert!(add_bookmark_at(&path, Path::new("/work/api")).unwrap());
+        assert!(!add_bookmark_at(&path, Path::new("/work/api")).unwrap());
+        assert!(add_bookmark_at(&path, Path::new("/work/web")).unwrap());
+        assert_eq!(
+            load_config_at(&path).unwrap().bookmarks,
+            vec![PathBuf::from("/work/api"), PathBuf::from("/work/web")]
+        );
+        assert!(remove_bookmark_at(&path, Path::new("/work/api")).unwrap());
+        assert!(!remove_bookmark_at(&path, Path::new("/work/api")).unwrap());
+        assert_eq!(
+            load_config_at(&path).unwrap().bookmarks,
+            vec![PathBuf::from("/work/web")]
+        );
+    }
+
+    #[test]
+    fn unpaired_projects_are_hidden_except_the_current_one() {
+        let paths = vec![
+            PathBuf::from("/work/api"),
+            PathBuf::from("/work/secret"),
+            PathBuf::from("/work/web"),
+        ];
+        let kept = retain_paired(&paths, Path::new("/work/api"), |path| {
+            path == Path::new("/work/web")
+        });
+        assert_eq!(
+            kept,
+            vec![PathBuf::from("/work/api"), PathBuf::from("/work/web")]
+        );
+    }
+
+    #[test]
+    fn merge_marks_running_and_current_without_duplicates() {
+        let bookmarks = vec![PathBuf::from("/work/web"), PathBuf::from("/work/api")];
+        let running = vec![PathBuf::from("/work/zeta"), PathBuf::from("/work/api")];
+        let projects = merge_projects(&bookmarks, &running, Path::new("/work/api"));
+        let summary: Vec<(&str, bool, bool, bool)> = projects
+            .iter()
+            .map(|p| (p.name.as_str(), p.bookmarked, p.running, p.is_current))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("web", true, false, false),
+                ("api", true, true, true),
+                ("zeta", false, true, false),
+            ]
+        );
+    }
+}
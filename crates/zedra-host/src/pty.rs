@@ -138,7 +138,7 @@ impl ShellSession {
 }
 
 #[cfg(windows)]
-fn default_shell() -> String {
+pub(crate) fn default_shell() -> String {
     windows_shell_from_env(&["ZEDRA_SHELL"])
         .or_else(|| windows_shell_from_env(&["ZEDRA_LAUNCH_SHELL"]))
         .or_else(detect_parent_shell)
@@ -148,7 +148,7 @@ fn default_shell() -> String {
 }
 
 #[cfg(not(windows))]
-fn default_shell() -> String {
+pub(crate) fn default_shell() -> String {
     std::env::var("SHELL").unwrap_or_else(|_| "/bin/bash".to_string())
 }
 
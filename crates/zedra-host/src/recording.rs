@@ -0,0 +1,488 @@
+// Opt-in asciicast v2 recording of host terminals for audit and review.
+//
+// Enabled with `zedra start --record`. Each PTY writes `<unix_secs>-<terminal_id>.cast`
+// under `<workspace config dir>/recordings/`, appending PTY output as `"o"` events.
+// Files roll over at `MAX_FILE_BYTES` and the oldest are pruned past `MAX_FILES`.
+// The app lists and replays them through `RecordingsList` and `RecordingRead`.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use zedra_rpc::proto::{RecordingReadResult, RECORDING_READ_MAX};
+
+use crate::identity;
+
+pub const RECORDINGS_DIR: &str = "recordings";
+/// Size at which a recording rolls over into a new `.partN.cast` file.
+pub const MAX_FILE_BYTES: u64 = 16 * 1024 * 1024;
+/// Oldest recordings beyond this count are deleted when a new file is opened.
+pub const MAX_FILES: usize = 200;
+const EXTENSION: &str = "cast";
+/// Bytes read from the end of a recording to find its last event.
+const TAIL_BYTES: u64 = 64 * 1024;
+
+/// Set once at daemon startup; `None` means recording is disabled.
+static RECORDINGS_ROOT: OnceLock<PathBuf> = OnceLock::new();
+
+/// Directory holding recordings for `workdir`.
+pub fn recordings_dir(workdir: &Path) -> Result<PathBuf> {
+    Ok(identity::workspace_config_dir(workdir)?.join(RECORDINGS_DIR))
+}
+
+/// Enable recording for every terminal this daemon spawns from now on.
+pub fn enable(workdir: &Path) -> Result<PathBuf> {
+    let dir = recordings_dir(workdir)?;
+    std::fs::create_dir_all(&dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    let _ = RECORDINGS_ROOT.set(dir.clone());
+    Ok(dir)
+}
+
+/// Whether `enable` ran in this daemon.
+pub fn is_enabled() -> bool {
+    RECORDINGS_ROOT.get().is_some()
+}
+
+#[derive(Serialize)]
+struct Header<'a> {
+    version: u8,
+    width: u16,
+    height: u16,
+    timestamp: u64,
+    title: &'a str,
+}
+
+/// Per-terminal asciicast writer, owned by the PTY reader thread.
+pub struct Recorder {
+    dir: PathBuf,
+    terminal_id: String,
+    cols: u16,
+    rows: u16,
+    part: u32,
+    file: BufWriter<File>,
+    written: u64,
+    started_at: Instant,
+    /// Trailing bytes of a UTF-8 sequence split across PTY reads.
+    utf8_carry: Vec<u8>,
+}
+
+impl Recorder {
+    /// Start recording a terminal if recording is enabled; logs and returns `None` on failure.
+    pub fn start(terminal_id: &str, cols: u16, rows: u16) -> Option<Self> {
+        let dir = RECORDINGS_ROOT.get()?;
+        match Self::open_in(dir, terminal_id, cols, rows, 0) {
+            Ok(recorder) => Some(recorder),
+            Err(e) => {
+                tracing::warn!("recording: failed to start for {}: {:#}", terminal_id, e);
+                None
+            }
+        }
+    }
+
+    fn open_in(dir: &Path, terminal_id: &str, cols: u16, rows: u16, part: u32) -> Result<Self> {
+        prune(dir, MAX_FILES.saturating_sub(1));
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let safe_id: String = terminal_id
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        let filename = if part == 0 {
+            format!("{timestamp}-{safe_id}.{EXTENSION}")
+        } else {
+            format!("{timestamp}-{safe_id}.part{part}.{EXTENSION}")
+        };
+        let path = dir.join(&filename);
+        let file = File::options()
+            .create_new(true)
+            .write(true)
+            .open(&path)
+            .with_context(|| format!("failed to create {}", path.display()))?;
+        let header = serde_json::to_string(&Header {
+            version: 2,
+            width: cols,
+            height: rows,
+            timestamp,
+            title: terminal_id,
+        })?;
+        let mut file = BufWriter::new(file);
+        writeln!(file, "{header}")?;
+        file.flush()?;
+        tracing::info!("recording: started {}", filename);
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            terminal_id: terminal_id.to_string(),
+            cols,
+            rows,
+            part,
+            file,
+            written: header.len() as u64 + 1,
+            started_at: Instant::now(),
+            utf8_carry: Vec::new(),
+        })
+    }
+
+    /// Append a PTY output chunk. Write errors are logged; they never affect the terminal.
+    pub fn record_output(&mut self, data: &[u8]) {
+        let text = self.decode(data);
+        if text.is_empty() {
+            return;
+        }
+        if let Err(e) = self.write_event("o", &text) {
+            tracing::warn!("recording: write failed for {}: {:#}", self.terminal_id, e);
+        }
+    }
+
+    fn decode(&mut self, data: &[u8]) -> String {
+        let mut bytes = std::mem::take(&mut self.utf8_carry);
+        bytes.extend_from_slice(data);
+        // Hold back an incomplete trailing sequence (at most 3 bytes) for the next chunk.
+        let split = match std::str::from_utf8(&bytes) {
+            Ok(_) => bytes.len(),
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(_) => bytes.len(),
+        };
+        self.utf8_carry = bytes.split_off(split);
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+
+    fn write_event(&mut self, kind: &str, text: &str) -> Result<()> {
+        if self.written >= MAX_FILE_BYTES {
+            let next = Self::open_in(
+                &self.dir,
+                &self.terminal_id,
+                self.cols,
+                self.rows,
+                self.part + 1,
+            )?;
+            *self = Self {
+                utf8_carry: std::mem::take(&mut self.utf8_carry),
+                ..next
+            };
+        }
+        let elapsed = self.started_at.elapsed().as_secs_f64();
+        let line = serde_json::to_string(&(elapsed, kind, text))?;
+        writeln!(self.file, "{line}")?;
+        self.file.flush()?;
+        self.written += line.len() as u64 + 1;
+        Ok(())
+    }
+}
+
+/// A recording file on disk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordingInfo {
+    pub name: String,
+    pub size: u64,
+    pub modified_secs: u64,
+}
+
+/// List recordings for `workdir`, newest first.
+pub fn list(workdir: &Path) -> Result<Vec<RecordingInfo>> {
+    list_in(&recordings_dir(workdir)?)
+}
+
+fn list_in(dir: &Path) -> Result<Vec<RecordingInfo>> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("failed to read {}", dir.display())),
+    };
+    let mut recordings: Vec<RecordingInfo> = entries
+        .flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == EXTENSION))
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            let modified_secs = metadata
+                .modified()
+                .ok()?
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            Some(RecordingInfo {
+                name: entry.file_name().to_string_lossy().into_owned(),
+                size: metadata.len(),
+                modified_secs,
+            })
+        })
+        .collect();
+    recordings.sort_by(|a, b| {
+        b.modified_secs
+            .cmp(&a.modified_secs)
+            .then_with(|| b.name.cmp(&a.name))
+    });
+    Ok(recordings)
+}
+
+/// The header fields `zedra sessions list` shows.
+#[derive(Deserialize)]
+struct HeaderMeta {
+    timestamp: u64,
+    #[serde(default)]
+    title: String,
+}
+
+/// A recorded terminal session, as shown by `zedra sessions list`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionInfo {
+    pub recording: RecordingInfo,
+    /// Terminal the session ran in; empty when the header is unreadable.
+    pub terminal_id: String,
+    /// When recording started (header `timestamp`), or the file time.
+    pub started_secs: u64,
+    /// Seconds from the start of this file to its last output event.
+    pub duration_secs: u64,
+    /// Rollover index: 0 for a session's first file, N for `.partN.cast`.
+    pub part: u32,
+}
+
+/// List recorded sessions for `workdir`, newest first.
+pub fn sessions(workdir: &Path) -> Result<Vec<SessionInfo>> {
+    sessions_in(&recordings_dir(workdir)?)
+}
+
+fn sessions_in(dir: &Path) -> Result<Vec<SessionInfo>> {
+    Ok(list_in(dir)?
+        .into_iter()
+        .map(|recording| session_info(dir, recording))
+        .collect())
+}
+
+fn session_info(dir: &Path, recording: RecordingInfo) -> SessionInfo {
+    let part = recording
+        .name
+        .strip_suffix(&format!(".{EXTENSION}"))
+        .and_then(|stem| stem.rsplit_once(".part"))
+        .and_then(|(_, n)| n.parse().ok())
+        .unwrap_or(0);
+    let mut info = SessionInfo {
+        terminal_id: String::new(),
+        started_secs: recording.modified_secs,
+        duration_secs: 0,
+        part,
+        recording,
+    };
+    let Ok(file) = File::open(dir.join(&info.recording.name)) else {
+        return info;
+    };
+    let mut reader = BufReader::new(file);
+    let mut header = String::new();
+    if reader.read_line(&mut header).is_ok() {
+        if let Ok(meta) = serde_json::from_str::<HeaderMeta>(&header) {
+            info.terminal_id = meta.title;
+            info.started_secs = meta.timestamp;
+        }
+    }
+    if let Some(elapsed) = last_event_time(reader.get_mut(), info.recording.size) {
+        info.duration_secs = elapsed as u64;
+    }
+    info
+}
+
+/// Elapsed time of the last `[time, kind, data]` event in a recording.
+fn last_event_time(file: &mut File, size: u64) -> Option<f64> {
+    file.seek(SeekFrom::Start(size.saturating_sub(TAIL_BYTES)))
+        .ok()?;
+    let mut tail = Vec::new();
+    file.read_to_end(&mut tail).ok()?;
+    let tail = String::from_utf8_lossy(&tail);
+    let line = tail.lines().rev().find(|line| !line.trim().is_empty())?;
+    let (elapsed, _, _): (f64, String, String) = serde_json::from_str(line).ok()?;
+    Some(elapsed)
+}
+
+/// Copy recording `name` into `out`. `name` must be a bare file name from `list`.
+pub fn export(workdir: &Path, name: &str, out: &mut dyn Write) -> Result<()> {
+    export_from(&recordings_dir(workdir)?, name, out)
+}
+
+fn export_from(dir: &Path, name: &str, out: &mut dyn Write) -> Result<()> {
+    let mut file = open_recording(dir, name)?;
+    std::io::copy(&mut file, out)?;
+    out.flush()?;
+    Ok(())
+}
+
+/// Read up to `max_bytes` (clamped to `RECORDING_READ_MAX`) of recording
+/// `name` starting at `offset`.
+pub fn read_chunk(
+    workdir: &Path,
+    name: &str,
+    offset: u64,
+    max_bytes: u64,
+) -> Result<RecordingReadResult> {
+    read_chunk_from(&recordings_dir(workdir)?, name, offset, max_bytes)
+}
+
+fn read_chunk_from(
+    dir: &Path,
+    name: &str,
+    offset: u64,
+    max_bytes: u64,
+) -> Result<RecordingReadResult> {
+    let mut file = open_recording(dir, name)?;
+    let total_bytes = file.metadata()?.len();
+    file.seek(SeekFrom::Start(offset.min(total_bytes)))?;
+    let mut data = Vec::new();
+    file.take(max_bytes.min(RECORDING_READ_MAX))
+        .read_to_end(&mut data)?;
+    Ok(RecordingReadResult {
+        eof: offset + data.len() as u64 >= total_bytes,
+        data,
+        total_bytes,
+        error: None,
+    })
+}
+
+/// Open recording `name`, which must be a bare `.cast` file name from `list`.
+fn open_recording(dir: &Path, name: &str) -> Result<File> {
+    let is_bare = Path::new(name).file_name().is_some_and(|n| n == name);
+    if !is_bare || !name.ends_with(&format!(".{EXTENSION}")) {
+        anyhow::bail!("invalid recording name: {name:?}");
+    }
+    let path = dir.join(name);
+    File::open(&path).with_context(|| format!("recording not found: {}", path.display()))
+}
+
+/// Delete the oldest recordings so at most `keep` remain.
+fn prune(dir: &Path, keep: usize) {
+    let Ok(recordings) = list_in(dir) else {
+        return;
+    };
+    for stale in recordings.iter().skip(keep) {
+        if let Err(e) = std::fs::remove_file(dir.join(&stale.name)) {
+            tracing::warn!("recording: failed to prune {}: {}", stale.name, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_lines(path: &Path) -> Vec<serde_json::Value> {
+        std::fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn writes_asciicast_header_and_output_events() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut recorder = Recorder::open_in(dir.path(), "term-1", 80, 24, 0).unwrap();
+        recorder.record_output(b"echo hi\r\n");
+
+        let recordings = list_in(dir.path()).unwrap();
+        assert_eq!(recordings.len(), 1);
+        let lines = read_lines(&dir.path().join(&recordings[0].name));
+        assert_eq!(lines[0]["version"], 2);
+        assert_eq!(lines[0]["width"], 80);
+        assert_eq!(lines[0]["height"], 24);
+        assert_eq!(lines[1][1], "o");
+        assert_eq!(lines[1][2], "echo hi\r\n");
+    }
+
+    #[test]
+    fn split_utf8_sequences_are_joined_across_chunks() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut recorder = Recorder::open_in(dir.path(), "term-1", 80, 24, 0).unwrap();
+        let bytes = "é".as_bytes();
+        recorder.record_output(&bytes[..1]);
+        recorder.record_output(&bytes[1..]);
+
+        let recordings = list_in(dir.path()).unwrap();
+        let lines = read_lines(&dir.path().join(&recordings[0].name));
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1][2], "é");
+    }
+
+    #[test]
+    fn export_rejects_paths_outside_the_recordings_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut out = Vec::new();
+        assert!(export_from(dir.path(), "../secret.cast", &mut out).is_err());
+        assert!(export_from(dir.path(), "notes.txt", &mut out).is_err());
+    }
+
+    #[test]
+    fn read_chunk_pages_through_a_recording() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("1-a.cast"), "0123456789").unwrap();
+
+        let first = read_chunk_from(dir.path(), "1-a.cast", 0, 6).unwrap();
+        assert_eq!(first.data, b"012345");
+        assert_eq!(first.total_bytes, 10);
+        assert!(!first.eof);
+
+        let rest = read_chunk_from(dir.path(), "1-a.cast", 6, 6).unwrap();
+        assert_eq!(rest.data, b"6789");
+        assert!(rest.eof);
+
+        assert!(read_chunk_from(dir.path(), "../1-a.cast", 0, 6).is_err());
+    }
+
+    #[test]
+    fn sessions_report_terminal_start_and_duration() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("100-term-1.cast"),
+            concat!(
+                r#"{"version":2,"width":80,"height":24,"timestamp":100,"title":"term-1"}"#,
+                "\n",
+                r#"[0.5,"o","ls\r\n"]"#,
+                "\n",
+                r#"[42.25,"o","exit\r\n"]"#,
+                "\n",
+            ),
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("200-term-1.part2.cast"), "not json\n").unwrap();
+
+        let sessions = sessions_in(dir.path()).unwrap();
+        let first = sessions
+            .iter()
+            .find(|s| s.recording.name == "100-term-1.cast")
+            .unwrap();
+        assert_eq!(first.terminal_id, "term-1");
+        assert_eq!(first.started_secs, 100);
+        assert_eq!(first.duration_secs, 42);
+        assert_eq!(first.part, 0);
+
+        let broken = sessions
+            .iter()
+            .find(|s| s.recording.name == "200-term-1.part2.cast")
+            .unwrap();
+        assert_eq!(broken.terminal_id, "");
+        assert_eq!(broken.duration_secs, 0);
+        assert_eq!(broken.part, 2);
+    }
+
+    #[test]
+    fn prune_keeps_newest_recordings() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["1-a.cast", "2-b.cast", "3-c.cast"] {
+            std::fs::write(dir.path().join(name), "{}\n").unwrap();
+        }
+        prune(dir.path(), 2);
+        let names: Vec<String> = list_in(dir.path())
+            .unwrap()
+            .into_iter()
+            .map(|r| r.name)
+            .collect();
+        assert_eq!(names, vec!["3-c.cast", "2-b.cast"]);
+    }
+}
@@ -8,22 +8,51 @@
 
 use crate::agent;
 use crate::agent::cache as agent_cache;
+use crate::ai;
+use crate::ai_conversations::ConversationStore;
+use crate::data_saver;
+use crate::diagnostics::{diagnostics_check_error, run_diagnostics_check};
 use crate::docs_tree::{
     build_snapshot, docs_tree_cache_key, docs_tree_limit, snapshot_page_result,
     validate_docs_tree_offset,
 };
+use crate::forge::{create_pull_request, forge_error};
 use crate::fs::{Filesystem, LocalFs};
-use crate::git::GitRepo;
+use crate::fs_grep::{expand_lines, grep_files};
+use crate::fs_replace::{fs_replace_error, replace_in_files};
+use crate::git::{self, GitRepo};
+use crate::git_discover::{discover, select, select_error};
+use crate::git_graph::{graph, graph_error};
+use crate::git_history::{
+    blame, blame_error, file_log, file_log_error, show_commit, show_commit_error, show_file,
+    show_file_error,
+};
+use crate::git_hunks::{diff_structured, stage_hunk};
+use crate::git_push::{
+    credential_helper, fetch, pull, push, push_error, push_v2, remote_failure, GitCredentialBroker,
+};
+use crate::git_rebase::{rebase_execute, rebase_execute_error, rebase_plan, rebase_plan_error};
 use crate::host_info;
+use crate::host_notify::{self, NotifyWatcher};
+use crate::host_power;
 use crate::identity::SharedIdentity;
 use crate::metrics;
 use crate::paths;
+use crate::projects;
 use crate::pty::{ShellSession, SpawnOptions};
+use crate::recording;
+use crate::sandbox::{resolve_path, Sandbox};
+use crate::session_env;
 use crate::session_registry::{
     finish_auth_failed_connection, finish_host_connection, ActiveClientConnection, AttachResult,
     ConsumeSlotResult, HostTermMeta, OutputSenderSlot, PairingSlotMode, ServerSession,
-    SessionRegistry, TermBacklog, TermSession, MAX_WATCHED_PATHS_PER_SESSION,
+    SessionRegistry, TermBacklog, TermSession, TerminalScope, MAX_WATCHED_PATHS_PER_SESSION,
 };
+use crate::shell_history::{read_shell_history, shell_history_error, HistoryPaths};
+use crate::shell_integration::{run_shell_integration, shell_integration_error};
+use crate::ssh_hosts;
+use crate::tasks::{discover_tasks, task_discover_error};
+use crate::text_file;
 use crate::uploads;
 use crate::utils;
 use anyhow::Result;
@@ -32,7 +61,7 @@ use irpc::rpc::{RemoteService, MAX_MESSAGE_SIZE};
 use irpc::util::AsyncReadVarintExt;
 use nucleo_matcher::pattern::{CaseMatching, Normalization, Pattern};
 use nucleo_matcher::{Config, Matcher, Utf32Str};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::hash::{Hash, Hasher};
 use std::io::{Read, Write};
 use std::path::{Component, Path, PathBuf};
@@ -42,10 +71,11 @@ use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use zedra_rpc::proto::*;
 use zedra_rpc::proto_v3::{ZedraProtoV3, ZEDRA_ALPN_V3};
+use zedra_rpc::proto_v4::{ZedraProtoV4, ZEDRA_ALPN_V4};
 use zedra_telemetry::Event;
 
 /// Log a decode failure with the leading discriminant varint (the RPC's index in
-/// `ZedraProto`/`ZedraProtoV3`) and a payload preview.
+/// `ZedraProto`/`ZedraProtoV4`/`ZedraProtoV3`) and a payload preview.
 fn log_decode_failure(alpn: &[u8], buf: &[u8], err: &postcard::Error) {
     // Metadata only — never log payload bytes; client frames can carry paths,
     // file contents, or tokens.
@@ -58,57 +88,91 @@ fn log_decode_failure(alpn: &[u8], buf: &[u8], err: &postcard::Error) {
     );
 }
 
-/// Read one request, decoding with the negotiated version (`zedra/rpc/3` lifts to
-/// the live message). Mirrors `irpc_iroh::read_request` but decodes locally so a
-/// failure can name the RPC (see `log_decode_failure`).
-async fn read_zedra_message(
-    conn: &iroh::endpoint::Connection,
-) -> std::io::Result<Option<ZedraMessage>> {
-    use std::io;
+/// A request frame whose length prefix is over irpc's `MAX_MESSAGE_SIZE`.
+#[derive(Debug)]
+struct OversizedFrame(u64);
 
-    // The negotiated ALPN is the only version seam; keep it local to decoding.
-    let is_v3 = conn.alpn() == ZEDRA_ALPN_V3;
+impl std::fmt::Display for OversizedFrame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "request exceeded max message size ({} bytes)", self.0)
+    }
+}
 
-    let (send, mut recv) = match conn.accept_bi().await {
-        Ok(pair) => pair,
-        // Remote closed the connection cleanly (error code 0).
-        Err(ConnectionError::ApplicationClosed(cause)) if cause.error_code.into_inner() == 0 => {
-            return Ok(None);
-        }
-        Err(cause) => return Err(cause.into()),
-    };
+impl std::error::Error for OversizedFrame {}
+
+fn is_oversized_frame(err: &std::io::Error) -> bool {
+    err.get_ref().is_some_and(|e| e.is::<OversizedFrame>())
+}
+
+/// Read one irpc request frame: a varint length prefix, then that many payload
+/// bytes. The length is checked against `MAX_MESSAGE_SIZE` before anything is
+/// allocated, and a stream that ends mid-frame is `UnexpectedEof`.
+pub(crate) async fn read_request_frame<R>(recv: &mut R) -> std::io::Result<Vec<u8>>
+where
+    R: tokio::io::AsyncRead + Unpin,
+{
+    use std::io;
 
     let size = recv
         .read_varint_u64()
         .await?
         .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "failed to read size"))?;
     if size > MAX_MESSAGE_SIZE {
-        conn.close(1u32.into(), b"request exceeded max message size");
         return Err(io::Error::new(
             io::ErrorKind::InvalidData,
-            "request exceeded max message size",
+            OversizedFrame(size),
         ));
     }
     let mut buf = vec![0u8; size as usize];
     recv.read_exact(&mut buf)
         .await
         .map_err(|e| io::Error::new(io::ErrorKind::UnexpectedEof, e))?;
+    Ok(buf)
+}
 
-    if is_v3 {
-        match postcard::from_bytes::<ZedraProtoV3>(&buf) {
-            Ok(proto) => Ok(Some(proto.with_remote_channels(recv, send).into_live())),
-            Err(e) => {
-                log_decode_failure(conn.alpn(), &buf, &e);
-                Err(io::Error::new(io::ErrorKind::InvalidData, e))
+/// Read one request, decoding with the negotiated version (`zedra/rpc/3` and
+/// `zedra/rpc/4` lift to the live message). Mirrors `irpc_iroh::read_request` but decodes locally so a
+/// failure can name the RPC (see `log_decode_failure`).
+async fn read_zedra_message(
+    conn: &iroh::endpoint::Connection,
+) -> std::io::Result<Option<ZedraMessage>> {
+    use std::io;
+
+    let (send, mut recv) = match conn.accept_bi().await {
+        Ok(pair) => pair,
+        // Remote closed the connection cleanly (error code 0).
+        Err(ConnectionError::ApplicationClosed(cause)) if cause.error_code.into_inner() == 0 => {
+            return Ok(None);
+        }
+        Err(cause) => return Err(cause.into()),
+    };
+
+    let buf = match read_request_frame(&mut recv).await {
+        Ok(buf) => buf,
+        Err(e) => {
+            if is_oversized_frame(&e) {
+                conn.close(1u32.into(), b"request exceeded max message size");
             }
+            return Err(e);
         }
+    };
+
+    // The negotiated ALPN is the only version seam; keep it local to decoding.
+    let alpn = conn.alpn();
+    let decoded = if alpn == ZEDRA_ALPN_V3 {
+        postcard::from_bytes::<ZedraProtoV3>(&buf)
+            .map(|proto| proto.with_remote_channels(recv, send).into_live())
+    } else if alpn == ZEDRA_ALPN_V4 {
+        postcard::from_bytes::<ZedraProtoV4>(&buf)
+            .map(|proto| proto.with_remote_channels(recv, send).into_live())
     } else {
-        match postcard::from_bytes::<ZedraProto>(&buf) {
-            Ok(proto) => Ok(Some(proto.with_remote_channels(recv, send))),
-            Err(e) => {
-                log_decode_failure(conn.alpn(), &buf, &e);
-                Err(io::Error::new(io::ErrorKind::InvalidData, e))
-            }
+        postcard::from_bytes::<ZedraProto>(&buf).map(|proto| proto.with_remote_channels(recv, send))
+    };
+    match decoded {
+        Ok(msg) => Ok(Some(msg)),
+        Err(e) => {
+            log_decode_failure(alpn, &buf, &e);
+            Err(io::Error::new(io::ErrorKind::InvalidData, e))
         }
     }
 }
@@ -150,9 +214,11 @@ pub(crate) fn current_home_dir() -> Option<String> {
 async fn build_sync_result(
     session: &Arc<ServerSession>,
     state: &DaemonState,
+    client_pubkey: [u8; 32],
     session_token: [u8; 32],
 ) -> SyncSessionResult {
     let info = collect_host_env(&state.workdir);
+    let ai_provider = ai_provider_info().await;
 
     SyncSessionResult {
         session_id: session.id.clone(),
@@ -166,10 +232,24 @@ async fn build_sync_result(
         os_version: os_version_string(),
         host_version: Some(env!("CARGO_PKG_VERSION").to_string()),
         delta_pubkey: state.delta_pubkey,
-        terminals: session.terminal_sync_entries().await,
+        terminals: session
+            .terminal_sync_entries(state.terminal_scope(client_pubkey))
+            .await,
+        ai_provider,
     }
 }
 
+/// `ai::provider_info` reads and parses `ai.json`, so keep it off the async
+/// connect and sync path.
+async fn ai_provider_info() -> AiProviderInfo {
+    tokio::task::spawn_blocking(ai::provider_info)
+        .await
+        .unwrap_or_else(|e| AiProviderInfo {
+            error: Some(format!("AI provider worker failed: {e}")),
+            ..Default::default()
+        })
+}
+
 #[allow(unused)]
 fn ts() -> String {
     let s = SystemTime::now()
@@ -1024,38 +1104,6 @@ fn connection_latency_sample(
     }
 }
 
-/// Resolve `user_path` relative to `workdir`, then verify the canonical path
-/// stays inside `workdir`. Rejects absolute paths, `..` escapes, and symlinks
-/// that point outside the jail.
-pub(crate) fn resolve_path(workdir: &Path, user_path: &str) -> Result<PathBuf> {
-    // Reject empty paths
-    anyhow::ensure!(!user_path.is_empty(), "empty path");
-    let joined = workdir.join(user_path);
-    let resolved = joined.canonicalize().or_else(|_| {
-        // File may not exist yet (e.g. FsWrite to a new path).
-        // Walk up to the first existing ancestor and canonicalize that.
-        let mut base = joined.as_path();
-        while let Some(parent) = base.parent() {
-            if parent.exists() {
-                let canon = parent.canonicalize()?;
-                // Reconstruct: canon + the non-existing tail
-                let tail = joined.strip_prefix(parent).unwrap_or(base);
-                return Ok(canon.join(tail));
-            }
-            base = parent;
-        }
-        anyhow::bail!("could not resolve path");
-    })?;
-    let jail = workdir.canonicalize()?;
-    anyhow::ensure!(
-        resolved.starts_with(&jail),
-        "path {} escapes workspace {}",
-        resolved.display(),
-        jail.display(),
-    );
-    Ok(resolved)
-}
-
 /// Normalize a client-provided observer path into a canonical relative key.
 /// Returns `None` for invalid input (absolute paths or parent traversal).
 fn normalize_observer_path(path: &str) -> Option<String> {
@@ -1176,7 +1224,7 @@ fn discover_git_worktrees(root: &Path) -> Vec<DiscoveredWorktree> {
     worktrees
 }
 
-fn is_file_search_ignored(entry: &ignore::DirEntry) -> bool {
+pub(crate) fn is_file_search_ignored(entry: &ignore::DirEntry) -> bool {
     // Only filter directories; matched files should still surface as results.
     if !entry
         .file_type()
@@ -1464,6 +1512,7 @@ async fn run_web_connect(
 async fn run_observer(session: Arc<ServerSession>, workdir: PathBuf, my_gen: u64) {
     let mut last_git: Option<u64> = None;
     let mut fs_snapshots: HashMap<String, u64> = HashMap::new();
+    let mut notify_watcher = NotifyWatcher::default();
     let mut tick_count: u64 = 0;
     loop {
         let current = session.observer_gen.load(Ordering::Acquire);
@@ -1518,6 +1567,30 @@ async fn run_observer(session: Arc<ServerSession>, workdir: PathBuf, my_gen: u64
         }
         fs_snapshots = retained;
 
+        let notify_config = session.notify.lock().await.clone();
+        let polled = tokio::task::spawn_blocking({
+            let workdir = workdir.clone();
+            move || {
+                let notifications = notify_watcher.poll(&workdir, &notify_config);
+                (notify_watcher, notifications)
+            }
+        })
+        .await;
+        match polled {
+            Ok((watcher, notifications)) => {
+                notify_watcher = watcher;
+                for notification in notifications {
+                    let _ = session
+                        .push_event(HostEvent::Notification(notification))
+                        .await;
+                }
+            }
+            Err(e) => {
+                tracing::warn!("host notify poll failed: {}", e);
+                notify_watcher = NotifyWatcher::default();
+            }
+        }
+
         tick_count += 1;
         if tick_count.is_multiple_of(30) {
             tracing::info!(
@@ -1555,6 +1628,17 @@ pub struct DaemonState {
     /// Host-managed agent web-client servers (e.g. `opencode serve`),
     /// daemon-scoped so they survive client reconnects.
     pub web_clients: crate::web_client::WebClientManager,
+    /// Credential prompts raised by `GitPush`, `GitPushV2`, `GitFetch`, and
+    /// `GitPull`, awaiting `GitCredentialReply`.
+    pub git_credentials: GitCredentialBroker,
+    /// `zedra start --allow-power`: accept `HostPower` sleep and reboot.
+    pub allow_power: bool,
+    /// Where fs and LSP requests may reach: `workdir`, plus any
+    /// `zedra start --allow-root` directories.
+    pub sandbox: Sandbox,
+    /// `zedra start --admin-client`: devices that see every session and
+    /// every terminal, not just the ones they created.
+    pub admin_clients: HashSet<[u8; 32]>,
 }
 
 impl std::fmt::Debug for DaemonState {
@@ -1575,12 +1659,29 @@ impl DaemonState {
         Self {
             fs: Arc::new(LocalFs),
             web_clients: crate::web_client::WebClientManager::new(workdir.clone()),
+            sandbox: Sandbox::new(workdir.clone()),
             workdir,
             identity,
             delta_pubkey,
             started_at: std::time::Instant::now(),
             agent_cache: agent_cache::AgentCache::new(),
             delta: Arc::new(tokio::sync::RwLock::new(delta)),
+            git_credentials: GitCredentialBroker::default(),
+            allow_power: false,
+            admin_clients: HashSet::new(),
+        }
+    }
+
+    pub fn is_admin(&self, client_pubkey: &[u8; 32]) -> bool {
+        self.admin_clients.contains(client_pubkey)
+    }
+
+    /// Terminals `client_pubkey` may list, attach to, resize, and close.
+    pub fn terminal_scope(&self, client_pubkey: [u8; 32]) -> TerminalScope {
+        if self.is_admin(&client_pubkey) {
+            TerminalScope::All
+        } else {
+            TerminalScope::Device(client_pubkey)
         }
     }
 }
@@ -1932,7 +2033,7 @@ async fn handle_connect(
                             previous.close_for_takeover();
                         }
                         let new_token = session.issue_session_token(pubkey).await;
-                        let sync = build_sync_result(session, state, new_token).await;
+                        let sync = build_sync_result(session, state, pubkey, new_token).await;
                         let _ = msg.tx.send(ConnectResult::Ok(sync)).await;
                         // Token fast-path: no challenge/prove round trip, prove_ms=0
                         return Ok((session.clone(), pubkey, active_connection, false, 0));
@@ -2210,7 +2311,7 @@ async fn finish_auth(
                 anyhow::bail!("session {} vanished after attach", resolved_session_id);
             };
             let session_token = session.issue_session_token(client_pubkey).await;
-            let sync = build_sync_result(&session, state, session_token).await;
+            let sync = build_sync_result(&session, state, client_pubkey, session_token).await;
             let _ = tx.send(AuthProveResult::Ok(sync)).await;
             Ok((
                 session,
@@ -2248,11 +2349,13 @@ pub const MAX_TERMINALS_PER_SESSION: usize = 16;
 ///
 /// Returns the new terminal ID on success. Used by both the `TermCreate` RPC
 /// handler and the local REST API so both paths share identical behaviour.
+/// `owner` is the creating device, `None` when started on the host.
 pub async fn create_terminal(
     session: &Arc<ServerSession>,
     cols: u16,
     rows: u16,
     mut opts: SpawnOptions,
+    owner: Option<[u8; 32]>,
 ) -> Result<String> {
     if session.terminals.lock().await.len() >= MAX_TERMINALS_PER_SESSION {
         anyhow::bail!(
@@ -2263,6 +2366,11 @@ pub async fn create_terminal(
         );
     }
 
+    let overrides = session.env.lock().await.clone();
+    let mut env = session_env::spawn_env(&overrides).await;
+    env.append(&mut opts.env);
+    opts.env = env;
+
     let id = session.next_terminal_id().await;
     opts.env.push(("ZEDRA_TERMINAL_ID".to_string(), id.clone()));
     if let Some(workdir) = &opts.workdir {
@@ -2308,6 +2416,7 @@ pub async fn create_terminal(
                 backlog: backlog.clone(),
                 created_at: std::time::SystemTime::now(),
                 started_at: std::time::Instant::now(),
+                owner,
             },
         )
         .await;
@@ -2317,6 +2426,7 @@ pub async fn create_terminal(
     // changes back onto the session's event channel.
     let event_session = session.clone();
     let rt = tokio::runtime::Handle::current();
+    let mut recorder = recording::Recorder::start(&id, cols, rows);
     tokio::task::spawn_blocking(move || {
         let mut reader = pty_reader;
         let mut buf = [0u8; 8192];
@@ -2330,6 +2440,9 @@ pub async fn create_terminal(
                 Ok(0) => break,
                 Ok(n) => {
                     let data = buf[..n].to_vec();
+                    if let Some(recorder) = recorder.as_mut() {
+                        recorder.record_output(&data);
+                    }
 
                     // Launch-command TUIs can query colors before a client TerminalView attaches.
                     // Answer these tiny OSC queries at the PTY boundary so startup style probes do
@@ -2348,32 +2461,35 @@ pub async fn create_terminal(
                     // cache up to date. This runs on every PTY
                     // chunk so the host always has the latest values even after
                     // old backlog entries have been evicted.
-                    let agent_identity_change = if let Ok(mut m) = host_meta.lock() {
-                        let events = m.scanner.feed(&data);
-                        // Identity is derived only from the foreground command and
-                        // OSC 1 icon name, so skip the allocating re-resolve unless
-                        // one of those changed (most chunks carry no OSC at all).
-                        let identity_relevant = events.iter().any(|ev| {
-                            matches!(
-                                ev,
-                                zedra_osc::OscEvent::CommandLine(_)
-                                    | zedra_osc::OscEvent::IconName(_)
-                                    | zedra_osc::OscEvent::CommandEnd { .. }
-                            )
-                        });
-                        for ev in events {
-                            m.apply_osc_event(&ev);
-                        }
-                        // Recompute identity under the same lock; emit only on change.
-                        identity_relevant
-                            .then(|| {
-                                m.refresh_agent_slug()
-                                    .then(|| m.agent_slug.map(str::to_string))
-                            })
-                            .flatten()
-                    } else {
-                        None
-                    };
+                    let (agent_identity_change, finished_commands) =
+                        if let Ok(mut m) = host_meta.lock() {
+                            let events = m.scanner.feed(&data);
+                            // Identity is derived only from the foreground command and
+                            // OSC 1 icon name, so skip the allocating re-resolve unless
+                            // one of those changed (most chunks carry no OSC at all).
+                            let identity_relevant = events.iter().any(|ev| {
+                                matches!(
+                                    ev,
+                                    zedra_osc::OscEvent::CommandLine(_)
+                                        | zedra_osc::OscEvent::IconName(_)
+                                        | zedra_osc::OscEvent::CommandEnd { .. }
+                                )
+                            });
+                            let mut finished_commands = Vec::new();
+                            for ev in events {
+                                finished_commands.extend(m.apply_osc_event(&ev));
+                            }
+                            // Recompute identity under the same lock; emit only on change.
+                            let agent_identity_change = identity_relevant
+                                .then(|| {
+                                    m.refresh_agent_slug()
+                                        .then(|| m.agent_slug.map(str::to_string))
+                                })
+                                .flatten();
+                            (agent_identity_change, finished_commands)
+                        } else {
+                            (None, Vec::new())
+                        };
                     if let Some(agent_slug) = agent_identity_change {
                         let session = event_session.clone();
                         let terminal_id = term_id.clone();
@@ -2386,6 +2502,22 @@ pub async fn create_terminal(
                                 .await;
                         });
                     }
+                    if !finished_commands.is_empty() {
+                        let session = event_session.clone();
+                        let terminal_id = term_id.clone();
+                        rt.spawn(async move {
+                            let config = session.notify.lock().await.clone();
+                            for finished in &finished_commands {
+                                if let Some(notification) =
+                                    host_notify::command_finished(&config, &terminal_id, finished)
+                                {
+                                    session
+                                        .push_event(HostEvent::Notification(notification))
+                                        .await;
+                                }
+                            }
+                        });
+                    }
 
                     // Push to per-terminal backlog (Fix 1: sync, no rt.block_on).
                     let seq = backlog.lock().unwrap().push(term_id.clone(), data.clone());
@@ -2430,6 +2562,14 @@ pub async fn create_terminal(
 // RPC dispatch
 // ---------------------------------------------------------------------------
 
+/// Directory git RPCs run in: the session's picked repository, else the workdir.
+async fn git_workdir(state: &DaemonState, session: &ServerSession) -> PathBuf {
+    match session.git_repo.lock().await.as_deref() {
+        Some(path) => state.workdir.join(path),
+        None => state.workdir.clone(),
+    }
+}
+
 fn git_status_result(workdir: PathBuf) -> GitStatusResult {
     match GitRepo::open(&workdir) {
         Ok(repo) => {
@@ -2487,6 +2627,42 @@ fn git_diff_result(workdir: PathBuf, path: Option<String>, staged: bool) -> GitD
     }
 }
 
+fn git_diff_stat_result(workdir: PathBuf) -> GitDiffStatResult {
+    fn entries(stats: Vec<git::DiffStat>) -> Vec<GitDiffStatEntry> {
+        stats
+            .into_iter()
+            .map(|s| GitDiffStatEntry {
+                path: s.path,
+                additions: s.additions,
+                deletions: s.deletions,
+                binary: s.binary,
+            })
+            .collect()
+    }
+
+    let stat = GitRepo::open(&workdir).and_then(|repo| {
+        let untracked: Vec<String> = repo
+            .status()?
+            .into_iter()
+            .filter(|e| e.unstaged_status == Some(git::FileStatus::Untracked))
+            .map(|e| e.path)
+            .collect();
+        Ok(GitDiffStatResult {
+            staged: entries(repo.diff_stat(true)?),
+            unstaged: entries(repo.diff_stat(false)?),
+            untracked: entries(repo.untracked_stat(&untracked)),
+            error: None,
+        })
+    });
+    stat.unwrap_or_else(|e| {
+        tracing::warn!("GitDiffStat: failed at {:?}: {}", workdir, e);
+        GitDiffStatResult {
+            error: Some(e.to_string()),
+            ..Default::default()
+        }
+    })
+}
+
 fn git_log_result(workdir: PathBuf, limit: Option<usize>) -> GitLogResult {
     match GitRepo::open(&workdir) {
         Ok(repo) => {
@@ -2604,6 +2780,200 @@ fn git_checkout_result(workdir: PathBuf, branch: String) -> GitCheckoutResult {
     GitCheckoutResult { ok }
 }
 
+fn git_stash_result(workdir: PathBuf, req: GitStashReq) -> GitStashResult {
+    match GitRepo::open(&workdir)
+        .and_then(|repo| repo.stash(req.message.as_deref(), req.include_untracked))
+    {
+        Ok(stashed) => GitStashResult {
+            stashed,
+            error: None,
+        },
+        Err(e) => {
+            tracing::warn!("GitStash failed: {}", e);
+            GitStashResult {
+                stashed: false,
+                error: Some(e.to_string()),
+            }
+        }
+    }
+}
+
+fn git_discard_result(workdir: PathBuf, include_untracked: bool) -> GitDiscardResult {
+    let error = GitRepo::open(&workdir)
+        .and_then(|repo| repo.discard_all(include_untracked))
+        .err()
+        .map(|e| {
+            tracing::warn!("GitDiscard failed: {}", e);
+            e.to_string()
+        });
+    GitDiscardResult { error }
+}
+
+fn git_branch_create_result(workdir: PathBuf, req: GitBranchCreateReq) -> GitBranchCreateResult {
+    let error = GitRepo::open(&workdir)
+        .and_then(|repo| repo.create_branch(&req.name, req.from.as_deref(), req.checkout))
+        .err()
+        .map(|e| {
+            tracing::warn!("GitBranchCreate failed: {}", e);
+            e.to_string()
+        });
+    GitBranchCreateResult { error }
+}
+
+fn git_branch_delete_result(workdir: PathBuf, req: GitBranchDeleteReq) -> GitBranchDeleteResult {
+    match GitRepo::open(&workdir).and_then(|repo| repo.delete_branch(&req.name, req.force)) {
+        Ok(()) => GitBranchDeleteResult::default(),
+        Err(e) => {
+            tracing::warn!("GitBranchDelete failed: {}", e);
+            let error = e.to_string();
+            GitBranchDeleteResult {
+                unmerged: error.contains("not fully merged"),
+                error: Some(error),
+            }
+        }
+    }
+}
+
+fn git_merge_result(workdir: PathBuf, branch: String) -> GitMergeResult {
+    let outcome = GitRepo::open(&workdir).and_then(|repo| repo.merge(&branch));
+    match outcome {
+        Ok(git::MergeOutcome::UpToDate) => GitMergeResult::default(),
+        Ok(git::MergeOutcome::FastForward { head }) => GitMergeResult {
+            head,
+            updated: true,
+            fast_forward: true,
+            ..Default::default()
+        },
+        Ok(git::MergeOutcome::Merged { head }) => GitMergeResult {
+            head,
+            updated: true,
+            ..Default::default()
+        },
+        Ok(git::MergeOutcome::Conflicts(conflicts)) => GitMergeResult {
+            error: Some(format!(
+                "merging {branch} stopped on conflicts and was undone"
+            )),
+            conflicts,
+            ..Default::default()
+        },
+        Err(e) => {
+            tracing::warn!("GitMerge failed: {}", e);
+            GitMergeResult {
+                error: Some(e.to_string()),
+                ..Default::default()
+            }
+        }
+    }
+}
+
+/// Start a terminal for `TermCreateV2`/`TermCreateV3`. `cwd` is only honoured
+/// when it names an existing absolute directory; otherwise the shell starts in
+/// the session workdir.
+async fn term_create_result(
+    state: &DaemonState,
+    session: &Arc<ServerSession>,
+    req: TermCreateReqV3,
+    owner: [u8; 32],
+) -> TermCreateResult {
+    let workdir = req
+        .cwd
+        .map(PathBuf::from)
+        .filter(|cwd| cwd.is_absolute() && cwd.is_dir())
+        .or_else(|| session.workdir.clone())
+        .or_else(|| Some(state.workdir.clone()));
+    let has_launch_cmd = req.launch_cmd.is_some();
+    match create_terminal(
+        session,
+        req.cols,
+        req.rows,
+        SpawnOptions {
+            workdir,
+            launch_cmd: req.launch_cmd,
+            color_scheme: req.color_scheme,
+            env: Vec::new(),
+        },
+        Some(owner),
+    )
+    .await
+    {
+        Ok(id) => {
+            zedra_telemetry::send(Event::HostTerminalOpen { has_launch_cmd });
+            let terminal_count = session.terminals.lock().await.len();
+            if let Err(e) = metrics::record_terminal_created(&state.workdir, terminal_count) {
+                tracing::warn!("Failed to record terminal metrics: {}", e);
+            }
+            TermCreateResult { id, error: None }
+        }
+        Err(e) => {
+            tracing::warn!("TermCreate failed: {}", e);
+            TermCreateResult {
+                id: String::new(),
+                error: Some(e.to_string()),
+            }
+        }
+    }
+}
+
+/// Run `FsGrep` / `FsGrepV2` for `req`, streaming hits to `tx` and finishing
+/// with `Done`. `context` is set for `FsGrepV2`, whose hits go out as
+/// `ContextMatches`; plain `FsGrep` gets bare `Matches`.
+async fn stream_grep(
+    state: &DaemonState,
+    req: FsGrepReq,
+    context: Option<u32>,
+    tx: irpc::channel::mpsc::Sender<FsGrepUpdate>,
+) {
+    let root = match state.sandbox.resolve(&req.path) {
+        Ok(p) => p,
+        Err(e) => {
+            tracing::warn!("FsGrep: rejected path {:?}: {}", req.path, e);
+            let _ = tx
+                .send(FsGrepUpdate::Done {
+                    truncated: false,
+                    error: Some(e.to_string()),
+                })
+                .await;
+            return;
+        }
+    };
+
+    let path = req.path.clone();
+    let (batch_tx, mut batch_rx) = tokio::sync::mpsc::channel::<Vec<FsGrepContextMatch>>(4);
+    let walk = tokio::task::spawn_blocking(move || {
+        grep_files(&root, &req, context.unwrap_or(0), |batch| {
+            batch_tx.blocking_send(batch).is_ok()
+        })
+    });
+    while let Some(batch) = batch_rx.recv().await {
+        let update = match context {
+            Some(_) => FsGrepUpdate::ContextMatches(batch),
+            None => FsGrepUpdate::Matches(batch.into_iter().map(|hit| hit.hit).collect()),
+        };
+        if tx.send(update).await.is_err() {
+            // Client went away; dropping `batch_rx` stops the walk.
+            return;
+        }
+    }
+    let done = match walk
+        .await
+        .map_err(|error| anyhow::anyhow!("grep task failed: {error}"))
+        .and_then(|result| result)
+    {
+        Ok(truncated) => FsGrepUpdate::Done {
+            truncated,
+            error: None,
+        },
+        Err(e) => {
+            tracing::warn!("FsGrep: failed for {:?}: {}", path, e);
+            FsGrepUpdate::Done {
+                truncated: false,
+                error: Some(e.to_string()),
+            }
+        }
+    };
+    let _ = tx.send(done).await;
+}
+
 async fn dispatch(
     msg: ZedraMessage,
     session: Arc<ServerSession>,
@@ -2663,12 +3033,22 @@ async fn dispatch(
             let session_token = session.issue_session_token(client_pubkey).await;
             let _ = msg
                 .tx
-                .send(build_sync_result(&session, &state, session_token).await)
+                .send(build_sync_result(&session, &state, client_pubkey, session_token).await)
                 .await;
         }
 
         ZedraMessage::ListSessions(msg) => {
-            let list = registry.list_sessions().await;
+            // Devices only see sessions they are paired with; admins see all.
+            let mut list = registry.list_sessions().await;
+            if !state.is_admin(&client_pubkey) {
+                let mut visible = Vec::with_capacity(list.len());
+                for info in list {
+                    if registry.is_in_session_acl(&info.id, &client_pubkey).await {
+                        visible.push(info);
+                    }
+                }
+                list = visible;
+            }
             let sessions = list
                 .into_iter()
                 .map(|s| SessionListEntry {
@@ -2704,7 +3084,7 @@ async fn dispatch(
 
         // -- Filesystem --
         ZedraMessage::FsList(msg) => {
-            let path = match resolve_path(&state.workdir, &msg.path) {
+            let path = match state.sandbox.resolve(&msg.path) {
                 Ok(p) => p,
                 Err(e) => {
                     tracing::warn!("FsList: rejected path {:?}: {}", msg.path, e);
@@ -2768,7 +3148,7 @@ async fn dispatch(
 
         ZedraMessage::FsSearch(msg) => {
             session.rpc_fs_reads.fetch_add(1, Ordering::Relaxed);
-            let path = match resolve_path(&state.workdir, &msg.path) {
+            let path = match state.sandbox.resolve(&msg.path) {
                 Ok(p) => p,
                 Err(e) => {
                     tracing::warn!("FsSearch: rejected path {:?}: {}", msg.path, e);
@@ -2796,6 +3176,92 @@ async fn dispatch(
             }
         }
 
+        ZedraMessage::FsReplace(msg) => {
+            if msg.dry_run {
+                session.rpc_fs_reads.fetch_add(1, Ordering::Relaxed);
+            } else {
+                session.rpc_fs_writes.fetch_add(1, Ordering::Relaxed);
+            }
+            let root = match state.sandbox.resolve(&msg.path) {
+                Ok(p) => p,
+                Err(e) => {
+                    tracing::warn!("FsReplace: rejected path {:?}: {}", msg.path, e);
+                    let _ = msg.tx.send(fs_replace_error(e.to_string())).await;
+                    return Ok(());
+                }
+            };
+
+            let fs = state.fs.clone();
+            let req = msg.inner.clone();
+            let replace_result =
+                tokio::task::spawn_blocking(move || replace_in_files(fs.as_ref(), &root, &req))
+                    .await
+                    .map_err(|error| anyhow::anyhow!("replace task failed: {error}"))
+                    .and_then(|result| result);
+
+            match replace_result {
+                Ok(result) => {
+                    let _ = msg.tx.send(result).await;
+                }
+                Err(e) => {
+                    tracing::warn!("FsReplace: failed for {:?}: {}", msg.path, e);
+                    let _ = msg.tx.send(fs_replace_error(e.to_string())).await;
+                }
+            }
+        }
+
+        ZedraMessage::TermCreateV3(msg) => {
+            session.touch().await;
+            let req = TermCreateReqV3 {
+                cols: msg.cols,
+                rows: msg.rows,
+                launch_cmd: msg.launch_cmd.clone(),
+                color_scheme: msg.color_scheme,
+                cwd: msg.cwd.clone(),
+            };
+            let result = term_create_result(&state, &session, req, client_pubkey).await;
+            let _ = msg.tx.send(result).await;
+        }
+
+        ZedraMessage::FsGrep(msg) => {
+            session.rpc_fs_reads.fetch_add(1, Ordering::Relaxed);
+            let req = msg.inner.clone();
+            stream_grep(&state, req, None, msg.tx).await;
+        }
+
+        ZedraMessage::FsGrepV2(msg) => {
+            session.rpc_fs_reads.fetch_add(1, Ordering::Relaxed);
+            let req = msg.without_context();
+            let context = msg.context;
+            stream_grep(&state, req, Some(context), msg.tx).await;
+        }
+
+        ZedraMessage::FsGrepExpand(msg) => {
+            session.rpc_fs_reads.fetch_add(1, Ordering::Relaxed);
+            let (line, radius) = (msg.line, msg.radius);
+            let result = match state.sandbox.resolve(&msg.path) {
+                Ok(path) => tokio::task::spawn_blocking(move || expand_lines(&path, line, radius))
+                    .await
+                    .map_err(|error| anyhow::anyhow!("expand task failed: {error}"))
+                    .and_then(|result| result)
+                    .unwrap_or_else(|e| {
+                        tracing::warn!("FsGrepExpand: failed for {:?}: {}", msg.path, e);
+                        FsGrepExpandResult {
+                            error: Some(e.to_string()),
+                            ..Default::default()
+                        }
+                    }),
+                Err(e) => {
+                    tracing::warn!("FsGrepExpand: rejected path {:?}: {}", msg.path, e);
+                    FsGrepExpandResult {
+                        error: Some(e.to_string()),
+                        ..Default::default()
+                    }
+                }
+            };
+            let _ = msg.tx.send(result).await;
+        }
+
         ZedraMessage::SetAppState(msg) => {
             registry
                 .set_foreground_if_active(
@@ -2948,7 +3414,7 @@ async fn dispatch(
 
         ZedraMessage::FsRead(msg) => {
             session.rpc_fs_reads.fetch_add(1, Ordering::Relaxed);
-            let path = match resolve_path(&state.workdir, &msg.path) {
+            let path = match state.sandbox.resolve(&msg.path) {
                 Ok(p) => p,
                 Err(e) => {
                     tracing::warn!("FsRead: rejected path {:?}: {}", msg.path, e);
@@ -3002,7 +3468,7 @@ async fn dispatch(
 
         ZedraMessage::FsWrite(msg) => {
             session.rpc_fs_writes.fetch_add(1, Ordering::Relaxed);
-            let path = match resolve_path(&state.workdir, &msg.path) {
+            let path = match state.sandbox.resolve(&msg.path) {
                 Ok(p) => p,
                 Err(e) => {
                     tracing::warn!("FsWrite: rejected path {:?}: {}", msg.path, e);
@@ -3056,7 +3522,7 @@ async fn dispatch(
         }
 
         ZedraMessage::FsStat(msg) => {
-            let path = match resolve_path(&state.workdir, &msg.path) {
+            let path = match state.sandbox.resolve(&msg.path) {
                 Ok(p) => p,
                 Err(e) => {
                     tracing::warn!("FsStat: rejected path {:?}: {}", msg.path, e);
@@ -3118,7 +3584,7 @@ async fn dispatch(
                 return Ok(());
             }
 
-            let path = match resolve_path(&state.workdir, &msg.path) {
+            let path = match state.sandbox.resolve(&msg.path) {
                 Ok(path) => path,
                 Err(error) => {
                     tracing::warn!("FsDocsTree: rejected path {:?}: {}", msg.path, error);
@@ -3300,6 +3766,7 @@ async fn dispatch(
                     color_scheme: None,
                     env: Vec::new(),
                 },
+                Some(client_pubkey),
             )
             .await
             {
@@ -3327,45 +3794,15 @@ async fn dispatch(
 
         ZedraMessage::TermCreateV2(msg) => {
             session.touch().await;
-            let workdir = session
-                .workdir
-                .clone()
-                .or_else(|| Some(state.workdir.clone()));
-            let has_launch_cmd = msg.launch_cmd.is_some();
-            let launch_cmd = msg.launch_cmd.clone();
-            match create_terminal(
-                &session,
-                msg.cols,
-                msg.rows,
-                SpawnOptions {
-                    workdir,
-                    launch_cmd,
-                    color_scheme: msg.color_scheme,
-                    env: Vec::new(),
-                },
-            )
-            .await
-            {
-                Ok(id) => {
-                    zedra_telemetry::send(Event::HostTerminalOpen { has_launch_cmd });
-                    let terminal_count = session.terminals.lock().await.len();
-                    if let Err(e) = metrics::record_terminal_created(&state.workdir, terminal_count)
-                    {
-                        tracing::warn!("Failed to record terminal metrics: {}", e);
-                    }
-                    let _ = msg.tx.send(TermCreateResult { id, error: None }).await;
-                }
-                Err(e) => {
-                    tracing::warn!("TermCreateV2 failed: {}", e);
-                    let _ = msg
-                        .tx
-                        .send(TermCreateResult {
-                            id: String::new(),
-                            error: Some(e.to_string()),
-                        })
-                        .await;
-                }
-            }
+            let req = TermCreateReqV3 {
+                cols: msg.cols,
+                rows: msg.rows,
+                launch_cmd: msg.launch_cmd.clone(),
+                color_scheme: msg.color_scheme,
+                cwd: None,
+            };
+            let result = term_create_result(&state, &session, req, client_pubkey).await;
+            let _ = msg.tx.send(result).await;
         }
 
         ZedraMessage::Subscribe(msg) => {
@@ -3444,12 +3881,12 @@ async fn dispatch(
             let irpc_tx = msg.tx;
             let mut irpc_rx = msg.rx;
 
+            if !session
+                .has_terminal_in_scope(&term_id, state.terminal_scope(client_pubkey))
+                .await
             {
-                let terms = session.terminals.lock().await;
-                if !terms.contains_key(&term_id) {
-                    tracing::warn!("TermAttach: unknown terminal {}", term_id);
-                    return Ok(());
-                }
+                tracing::warn!("TermAttach: unknown terminal {}", term_id);
+                return Ok(());
             }
 
             // Synthetic metadata preamble (seq=0): inject cached OSC terminal
@@ -3565,8 +4002,14 @@ async fn dispatch(
             // With high-latency connections (e.g. relay RTT ~300ms), irpc_tx.send().await
             // can stall waiting for QUIC flow control acks. If input and output share a
             // single select! loop, that stall prevents keystrokes from reaching the PTY.
+            let output_session = session.clone();
             let output_task = tokio::spawn(async move {
                 while let Some(mut term_output) = bridge_rx.recv().await {
+                    let data_saver = output_session.data_saver.load(Ordering::Relaxed);
+                    if data_saver && term_output.data.len() >= data_saver::BATCH_MIN_BYTES {
+                        // Bulk output: let the rest of the burst join this frame.
+                        tokio::time::sleep(data_saver::BATCH_WINDOW).await;
+                    }
                     // Coalesce any chunks that arrived while the previous send was in
                     // flight. Under relay congestion the channel can accumulate many
                     // small PTY reads; merging them reduces irpc framing overhead and
@@ -3576,6 +4019,13 @@ async fn dispatch(
                         term_output.data.extend_from_slice(&next.data);
                         term_output.seq = next.seq;
                     }
+                    if data_saver {
+                        if let Some(data) =
+                            data_saver::summarize(&term_output.data, data_saver::FRAME_BYTES)
+                        {
+                            term_output.data = data;
+                        }
+                    }
                     if irpc_tx.send(term_output).await.is_err() {
                         break;
                     }
@@ -3631,8 +4081,12 @@ async fn dispatch(
         }
 
         ZedraMessage::TermResize(msg) => {
+            let scope = state.terminal_scope(client_pubkey);
             let terms = session.terminals.lock().await;
-            let ok = if let Some(term) = terms.get(&msg.id) {
+            let ok = if let Some(term) = terms
+                .get(&msg.id)
+                .filter(|term| scope.includes(term.owner.as_ref()))
+            {
                 term.master
                     .resize(portable_pty::PtySize {
                         rows: msg.rows,
@@ -3648,7 +4102,19 @@ async fn dispatch(
         }
 
         ZedraMessage::TermClose(msg) => {
-            let terminal = session.remove_terminal(&msg.id).await;
+            let terminal = if session
+                .has_terminal_in_scope(&msg.id, state.terminal_scope(client_pubkey))
+                .await
+            {
+                session.remove_terminal(&msg.id).await
+            } else {
+                tracing::warn!(
+                    "TermClose: {:?}... may not close terminal {}",
+                    &client_pubkey[..4],
+                    msg.id
+                );
+                None
+            };
             let ok = if let Some(terminal) = terminal {
                 tokio::task::spawn_blocking(move || terminal.terminate())
                     .await
@@ -3661,7 +4127,7 @@ async fn dispatch(
 
         ZedraMessage::TermList(msg) => {
             let terminals = session
-                .terminal_ids()
+                .terminal_ids(state.terminal_scope(client_pubkey))
                 .await
                 .into_iter()
                 .enumerate()
@@ -3674,7 +4140,10 @@ async fn dispatch(
         }
 
         ZedraMessage::TermReorder(msg) => {
-            let result = match session.reorder_terminals(msg.ordered_ids.clone()).await {
+            let result = match session
+                .reorder_terminals(msg.ordered_ids.clone(), state.terminal_scope(client_pubkey))
+                .await
+            {
                 Ok(()) => TermReorderResult {
                     ok: true,
                     error: None,
@@ -3690,7 +4159,7 @@ async fn dispatch(
         // -- Git --
         ZedraMessage::GitStatus(msg) => {
             session.rpc_git_ops.fetch_add(1, Ordering::Relaxed);
-            let workdir = state.workdir.clone();
+            let workdir = git_workdir(&state, &session).await;
             let result = tokio::task::spawn_blocking(move || git_status_result(workdir))
                 .await
                 .unwrap_or_else(|e| GitStatusResult {
@@ -3703,7 +4172,7 @@ async fn dispatch(
 
         ZedraMessage::GitDiff(msg) => {
             session.rpc_git_ops.fetch_add(1, Ordering::Relaxed);
-            let workdir = state.workdir.clone();
+            let workdir = git_workdir(&state, &session).await;
             let path = msg.path.clone();
             let staged = msg.staged;
             let result =
@@ -3716,9 +4185,21 @@ async fn dispatch(
             let _ = msg.tx.send(result).await;
         }
 
+        ZedraMessage::GitDiffStat(msg) => {
+            session.rpc_git_ops.fetch_add(1, Ordering::Relaxed);
+            let workdir = git_workdir(&state, &session).await;
+            let result = tokio::task::spawn_blocking(move || git_diff_stat_result(workdir))
+                .await
+                .unwrap_or_else(|e| GitDiffStatResult {
+                    error: Some(format!("git diff stat worker failed: {e}")),
+                    ..Default::default()
+                });
+            let _ = msg.tx.send(result).await;
+        }
+
         ZedraMessage::GitLog(msg) => {
             session.rpc_git_ops.fetch_add(1, Ordering::Relaxed);
-            let workdir = state.workdir.clone();
+            let workdir = git_workdir(&state, &session).await;
             let limit = msg.limit;
             let result = tokio::task::spawn_blocking(move || git_log_result(workdir, limit))
                 .await
@@ -3731,7 +4212,7 @@ async fn dispatch(
 
         ZedraMessage::GitCommit(msg) => {
             let files_staged = msg.paths.len();
-            let workdir = state.workdir.clone();
+            let workdir = git_workdir(&state, &session).await;
             let message = msg.message.clone();
             let paths = msg.paths.clone();
             let (result, success) =
@@ -3758,7 +4239,7 @@ async fn dispatch(
 
         ZedraMessage::GitStage(msg) => {
             session.rpc_git_ops.fetch_add(1, Ordering::Relaxed);
-            let workdir = state.workdir.clone();
+            let workdir = git_workdir(&state, &session).await;
             let paths = msg.paths.clone();
             let result = tokio::task::spawn_blocking(move || git_stage_result(workdir, paths))
                 .await
@@ -3770,7 +4251,7 @@ async fn dispatch(
 
         ZedraMessage::GitUnstage(msg) => {
             session.rpc_git_ops.fetch_add(1, Ordering::Relaxed);
-            let workdir = state.workdir.clone();
+            let workdir = git_workdir(&state, &session).await;
             let paths = msg.paths.clone();
             let result = tokio::task::spawn_blocking(move || git_unstage_result(workdir, paths))
                 .await
@@ -3782,7 +4263,7 @@ async fn dispatch(
 
         ZedraMessage::GitBranches(msg) => {
             session.rpc_git_ops.fetch_add(1, Ordering::Relaxed);
-            let workdir = state.workdir.clone();
+            let workdir = git_workdir(&state, &session).await;
             let result = tokio::task::spawn_blocking(move || git_branches_result(workdir))
                 .await
                 .unwrap_or_else(|e| GitBranchesResult {
@@ -3793,7 +4274,7 @@ async fn dispatch(
         }
 
         ZedraMessage::GitCheckout(msg) => {
-            let workdir = state.workdir.clone();
+            let workdir = git_workdir(&state, &session).await;
             let branch = msg.branch.clone();
             let result = tokio::task::spawn_blocking(move || git_checkout_result(workdir, branch))
                 .await
@@ -3801,227 +4282,1378 @@ async fn dispatch(
             let _ = msg.tx.send(result).await;
         }
 
-        // -- AI --
-        ZedraMessage::AiPrompt(msg) => {
-            // Resolve the Claude binary path. Prefer an explicit absolute path
-            // from the environment to avoid executing a malicious `claude` binary
-            // that might appear earlier in $PATH.
-            let claude_bin =
-                std::env::var("ZEDRA_CLAUDE_BIN").unwrap_or_else(|_| "claude".to_string());
-            let prompt = msg.prompt.clone();
-            let prompt_bytes = prompt.len();
-            let ai_start = std::time::Instant::now();
-            let workdir = state.workdir.clone();
-            let prompt_for_command = prompt.clone();
-            let output = tokio::task::spawn_blocking(move || {
-                std::process::Command::new(&claude_bin)
-                    .args(["--print", prompt_for_command.as_str()])
-                    .current_dir(workdir)
-                    .output()
-            })
-            .await
-            .unwrap_or_else(|e| {
-                Err(std::io::Error::other(format!(
-                    "AI prompt worker failed: {e}"
-                )))
-            });
-            let duration_ms = ai_start.elapsed().as_millis() as u64;
-
-            let (text, done, success) = match output {
-                Ok(out) if out.status.success() => {
-                    (String::from_utf8_lossy(&out.stdout).into_owned(), true, true)
-                }
-                Ok(out) => {
-                    let err = String::from_utf8_lossy(&out.stderr).into_owned();
-                    (format!("Error: {}", err), true, false)
-                }
-                Err(e) => (
-                    format!(
-                        "Claude Code not found on host. Install with: npm i -g @anthropic-ai/claude-code\n\nPrompt was: {}\n\nError: {}",
-                        prompt,
-                        e
-                    ),
-                    true,
-                    false,
-                ),
+        ZedraMessage::GitStash(msg) => {
+            session.rpc_git_ops.fetch_add(1, Ordering::Relaxed);
+            let workdir = git_workdir(&state, &session).await;
+            let req = GitStashReq {
+                message: msg.message.clone(),
+                include_untracked: msg.include_untracked,
             };
-            session.rpc_ai_prompts.fetch_add(1, Ordering::Relaxed);
-            zedra_telemetry::send(Event::AiPromptSent {
-                success,
-                duration_ms,
-                prompt_bytes,
-                response_bytes: text.len(),
-            });
-            let _ = msg.tx.send(AiPromptResult { text, done }).await;
+            let result = tokio::task::spawn_blocking(move || git_stash_result(workdir, req))
+                .await
+                .unwrap_or_else(|e| GitStashResult {
+                    error: Some(format!("git stash worker failed: {e}")),
+                    ..Default::default()
+                });
+            let _ = msg.tx.send(result).await;
         }
 
-        ZedraMessage::AgentList(msg) => {
-            session.touch().await;
-            let workdir = session.workdir.as_ref().unwrap_or(&state.workdir);
+        ZedraMessage::GitDiscard(msg) => {
+            session.rpc_git_ops.fetch_add(1, Ordering::Relaxed);
+            let workdir = git_workdir(&state, &session).await;
+            let include_untracked = msg.include_untracked;
             let result =
-                agent::list_agents(&state.agent_cache, workdir, Some(&session), msg.refresh).await;
+                tokio::task::spawn_blocking(move || git_discard_result(workdir, include_untracked))
+                    .await
+                    .unwrap_or_else(|e| GitDiscardResult {
+                        error: Some(format!("git discard worker failed: {e}")),
+                    });
             let _ = msg.tx.send(result).await;
         }
 
-        ZedraMessage::AgentSessions(msg) => {
-            session.touch().await;
-            let workdir = session.workdir.as_ref().unwrap_or(&state.workdir);
-            let result = agent::list_agent_sessions(
-                &state.agent_cache,
-                &msg.slug,
-                workdir,
-                Some(&session),
-                msg.limit,
-                msg.refresh,
-            )
-            .await;
+        ZedraMessage::GitBranchCreate(msg) => {
+            session.rpc_git_ops.fetch_add(1, Ordering::Relaxed);
+            let workdir = git_workdir(&state, &session).await;
+            let req = GitBranchCreateReq {
+                name: msg.name.clone(),
+                from: msg.from.clone(),
+                checkout: msg.checkout,
+            };
+            let result =
+                tokio::task::spawn_blocking(move || git_branch_create_result(workdir, req))
+                    .await
+                    .unwrap_or_else(|e| GitBranchCreateResult {
+                        error: Some(format!("git branch create worker failed: {e}")),
+                    });
             let _ = msg.tx.send(result).await;
         }
 
-        ZedraMessage::AgentInstalledList(msg) => {
-            session.touch().await;
-            let result = agent::list_installed_agents(&state.agent_cache, msg.refresh).await;
+        ZedraMessage::GitBranchDelete(msg) => {
+            session.rpc_git_ops.fetch_add(1, Ordering::Relaxed);
+            let workdir = git_workdir(&state, &session).await;
+            let req = msg.inner;
+            let result =
+                tokio::task::spawn_blocking(move || git_branch_delete_result(workdir, req))
+                    .await
+                    .unwrap_or_else(|e| GitBranchDeleteResult {
+                        error: Some(format!("git branch delete worker failed: {e}")),
+                        ..Default::default()
+                    });
             let _ = msg.tx.send(result).await;
         }
 
-        ZedraMessage::AgentFiles(msg) => {
-            session.touch().await;
-            let slug = msg.slug.clone();
-            // File reads are blocking; keep them off the async dispatch path.
-            let result = tokio::task::spawn_blocking(move || agent::agent_files(&slug))
+        ZedraMessage::GitMerge(msg) => {
+            session.rpc_git_ops.fetch_add(1, Ordering::Relaxed);
+            let workdir = git_workdir(&state, &session).await;
+            let branch = msg.branch.clone();
+            let result = tokio::task::spawn_blocking(move || git_merge_result(workdir, branch))
                 .await
-                .map(|files| match files {
-                    Ok(files) => AgentFilesResult { files, error: None },
-                    Err(error) => AgentFilesResult {
-                        files: Vec::new(),
-                        error: Some(error),
-                    },
-                })
-                .unwrap_or_else(|e| AgentFilesResult {
-                    files: Vec::new(),
-                    error: Some(e.to_string()),
+                .unwrap_or_else(|e| GitMergeResult {
+                    error: Some(format!("git merge worker failed: {e}")),
+                    ..Default::default()
                 });
             let _ = msg.tx.send(result).await;
         }
 
-        ZedraMessage::AgentResume(msg) => {
-            session.touch().await;
-            let workdir = session
-                .workdir
-                .clone()
-                .or_else(|| Some(state.workdir.clone()));
-            let launch_cmd = agent::resume_launch_command(&msg.slug, &msg.session_id);
-            let Some(launch_cmd) = launch_cmd else {
-                // `None` collapses three causes; report the specific one.
-                let error = if agent::actor(&msg.slug).is_none() {
-                    format!("unknown agent: {}", msg.slug)
-                } else if msg.session_id.trim().is_empty() {
-                    "missing session id".to_string()
-                } else {
-                    format!("agent {} does not support resume", msg.slug)
-                };
-                let _ = msg
-                    .tx
-                    .send(AgentResumeResult {
-                        terminal_id: String::new(),
-                        error: Some(error),
-                    })
-                    .await;
-                return Ok(());
-            };
-            match create_terminal(
-                &session,
-                msg.cols,
-                msg.rows,
-                SpawnOptions {
-                    workdir,
-                    launch_cmd: Some(launch_cmd),
-                    color_scheme: None,
-                    env: Vec::new(),
-                },
-            )
+        ZedraMessage::GitRebasePlan(msg) => {
+            session.rpc_git_ops.fetch_add(1, Ordering::Relaxed);
+            let workdir = git_workdir(&state, &session).await;
+            let count = msg.count;
+            let result = tokio::task::spawn_blocking(move || {
+                GitRepo::open(&workdir).and_then(|repo| rebase_plan(&repo, count))
+            })
             .await
-            {
-                Ok(terminal_id) => {
-                    zedra_telemetry::send(Event::HostTerminalOpen {
-                        has_launch_cmd: true,
-                    });
-                    let terminal_count = session.terminals.lock().await.len();
-                    if let Err(e) = metrics::record_terminal_created(&state.workdir, terminal_count)
-                    {
-                        tracing::warn!("Failed to record terminal metrics: {}", e);
-                    }
-                    let _ = msg
-                        .tx
-                        .send(AgentResumeResult {
-                            terminal_id,
-                            error: None,
-                        })
-                        .await;
-                }
-                Err(e) => {
-                    tracing::warn!("AgentResume failed: {}", e);
-                    let _ = msg
-                        .tx
-                        .send(AgentResumeResult {
-                            terminal_id: String::new(),
-                            error: Some(e.to_string()),
-                        })
-                        .await;
-                }
-            }
+            .map_err(|e| anyhow::anyhow!("git rebase plan worker failed: {e}"))
+            .and_then(|result| result)
+            .unwrap_or_else(|e| {
+                tracing::warn!("GitRebasePlan failed: {}", e);
+                rebase_plan_error(e.to_string())
+            });
+            let _ = msg.tx.send(result).await;
         }
 
-        // -- LSP --
-        ZedraMessage::LspDiagnostics(msg) => {
-            let full_path = match resolve_path(&state.workdir, &msg.path) {
-                Ok(p) => p,
+        ZedraMessage::GitRebaseExecute(msg) => {
+            session.rpc_git_ops.fetch_add(1, Ordering::Relaxed);
+            let workdir = git_workdir(&state, &session).await;
+            let req = msg.inner;
+            let result = tokio::task::spawn_blocking(move || {
+                GitRepo::open(&workdir).and_then(|repo| rebase_execute(&repo, &req))
+            })
+            .await
+            .map_err(|e| anyhow::anyhow!("git rebase worker failed: {e}"))
+            .and_then(|result| result);
+            let result = match result {
+                Ok(head) => GitRebaseExecuteResult { head, error: None },
                 Err(e) => {
-                    tracing::warn!("LspDiagnostics: rejected path {:?}: {}", msg.path, e);
-                    let _ = msg
-                        .tx
-                        .send(LspDiagnosticsResult {
-                            diagnostics: vec![],
-                            error: Some(e.to_string()),
-                        })
-                        .await;
-                    return Ok(());
+                    tracing::warn!("GitRebaseExecute failed: {}", e);
+                    rebase_execute_error(e.to_string())
                 }
             };
-            let diagnostics = run_lsp_check(&full_path)
-                .into_iter()
-                .map(|d| LspDiagnostic {
-                    message: d.message,
-                    severity: d.severity,
-                })
-                .collect();
-            let _ = msg
-                .tx
-                .send(LspDiagnosticsResult {
-                    diagnostics,
-                    error: None,
-                })
-                .await;
+            let _ = msg.tx.send(result).await;
         }
 
-        ZedraMessage::LspHover(msg) => {
-            let _ = msg
-                .tx
-                .send(LspHoverResult {
-                    contents: "LSP hover not yet connected to a language server.".to_string(),
-                })
-                .await;
+        ZedraMessage::GitGraph(msg) => {
+            session.rpc_git_ops.fetch_add(1, Ordering::Relaxed);
+            let workdir = git_workdir(&state, &session).await;
+            let limit = msg.limit;
+            let result = tokio::task::spawn_blocking(move || {
+                GitRepo::open(&workdir).and_then(|repo| graph(&repo, limit))
+            })
+            .await
+            .map_err(|e| anyhow::anyhow!("git graph worker failed: {e}"))
+            .and_then(|result| result)
+            .unwrap_or_else(|e| {
+                tracing::warn!("GitGraph failed: {}", e);
+                graph_error(e.to_string())
+            });
+            let _ = msg.tx.send(result).await;
         }
-    }
-
-    Ok(())
-}
 
-// ---------------------------------------------------------------------------
-// Helpers
-// ---------------------------------------------------------------------------
+        ZedraMessage::GitFileLog(msg) => {
+            session.rpc_git_ops.fetch_add(1, Ordering::Relaxed);
+            let workdir = git_workdir(&state, &session).await;
+            let (path, limit) = (msg.path.clone(), msg.limit);
+            let result = tokio::task::spawn_blocking(move || {
+                GitRepo::open(&workdir).and_then(|repo| file_log(&repo, &path, limit))
+            })
+            .await
+            .map_err(|e| anyhow::anyhow!("git file log worker failed: {e}"))
+            .and_then(|result| result)
+            .unwrap_or_else(|e| {
+                tracing::warn!("GitFileLog failed: {}", e);
+                file_log_error(e.to_string())
+            });
+            let _ = msg.tx.send(result).await;
+        }
 
-struct DiagnosticEntry {
+        ZedraMessage::GitShowFile(msg) => {
+            session.rpc_git_ops.fetch_add(1, Ordering::Relaxed);
+            let workdir = git_workdir(&state, &session).await;
+            let (rev, path) = (msg.rev.clone(), msg.path.clone());
+            let result = tokio::task::spawn_blocking(move || {
+                GitRepo::open(&workdir).and_then(|repo| show_file(&repo, &rev, &path))
+            })
+            .await
+            .map_err(|e| anyhow::anyhow!("git show file worker failed: {e}"))
+            .and_then(|result| result)
+            .unwrap_or_else(|e| {
+                tracing::warn!("GitShowFile failed: {}", e);
+                show_file_error(e.to_string())
+            });
+            let _ = msg.tx.send(result).await;
+        }
+
+        ZedraMessage::GitShowCommit(msg) => {
+            session.rpc_git_ops.fetch_add(1, Ordering::Relaxed);
+            let workdir = git_workdir(&state, &session).await;
+            let req = msg.inner;
+            let result = tokio::task::spawn_blocking(move || {
+                GitRepo::open(&workdir).and_then(|repo| show_commit(&repo, &req.rev, &req.paths))
+            })
+            .await
+            .map_err(|e| anyhow::anyhow!("git show commit worker failed: {e}"))
+            .and_then(|result| result);
+            let result = match result {
+                Ok(diff) => GitShowCommitResult { diff, error: None },
+                Err(e) => {
+                    tracing::warn!("GitShowCommit failed: {}", e);
+                    show_commit_error(e.to_string())
+                }
+            };
+            let _ = msg.tx.send(result).await;
+        }
+
+        ZedraMessage::GitBlame(msg) => {
+            session.rpc_git_ops.fetch_add(1, Ordering::Relaxed);
+            let workdir = git_workdir(&state, &session).await;
+            let path = msg.path.clone();
+            let result = tokio::task::spawn_blocking(move || {
+                GitRepo::open(&workdir).and_then(|repo| blame(&repo, &path))
+            })
+            .await
+            .map_err(|e| anyhow::anyhow!("git blame worker failed: {e}"))
+            .and_then(|result| result)
+            .unwrap_or_else(|e| {
+                tracing::warn!("GitBlame failed: {}", e);
+                blame_error(e.to_string())
+            });
+            let _ = msg.tx.send(result).await;
+        }
+
+        ZedraMessage::GitPush(msg) => {
+            session.rpc_git_ops.fetch_add(1, Ordering::Relaxed);
+            let workdir = git_workdir(&state, &session).await;
+            let req = msg.inner;
+            let result = match credential_helper(&state.workdir, &session.id) {
+                Ok(helper) => tokio::task::spawn_blocking(move || {
+                    GitRepo::open(&workdir).and_then(|repo| push(&repo, &req, &helper))
+                })
+                .await
+                .map_err(|e| anyhow::anyhow!("git push worker failed: {e}"))
+                .and_then(|result| result),
+                Err(e) => Err(e),
+            };
+            let result = result.unwrap_or_else(|e| {
+                tracing::warn!("GitPush failed: {}", e);
+                push_error(e.to_string())
+            });
+            let _ = msg.tx.send(result).await;
+        }
+
+        ZedraMessage::GitPushV2(msg) => {
+            session.rpc_git_ops.fetch_add(1, Ordering::Relaxed);
+            let workdir = git_workdir(&state, &session).await;
+            let req = msg.inner;
+            let result = match credential_helper(&state.workdir, &session.id) {
+                Ok(helper) => tokio::task::spawn_blocking(move || {
+                    GitRepo::open(&workdir).and_then(|repo| push_v2(&repo, &req, &helper))
+                })
+                .await
+                .map_err(|e| anyhow::anyhow!("git push worker failed: {e}"))
+                .and_then(|result| result),
+                Err(e) => Err(e),
+            };
+            let result = result.unwrap_or_else(|e| {
+                tracing::warn!("GitPushV2 failed: {}", e);
+                GitPushResultV2 {
+                    failure: remote_failure(&e),
+                    error: Some(e.to_string()),
+                    ..Default::default()
+                }
+            });
+            let _ = msg.tx.send(result).await;
+        }
+
+        ZedraMessage::GitFetch(msg) => {
+            session.rpc_git_ops.fetch_add(1, Ordering::Relaxed);
+            let workdir = git_workdir(&state, &session).await;
+            let req = msg.inner;
+            let result = match credential_helper(&state.workdir, &session.id) {
+                Ok(helper) => tokio::task::spawn_blocking(move || {
+                    GitRepo::open(&workdir).and_then(|repo| fetch(&repo, &req, &helper))
+                })
+                .await
+                .map_err(|e| anyhow::anyhow!("git fetch worker failed: {e}"))
+                .and_then(|result| result),
+                Err(e) => Err(e),
+            };
+            let result = result.unwrap_or_else(|e| {
+                tracing::warn!("GitFetch failed: {}", e);
+                GitFetchResult {
+                    failure: remote_failure(&e),
+                    error: Some(e.to_string()),
+                    ..Default::default()
+                }
+            });
+            let _ = msg.tx.send(result).await;
+        }
+
+        ZedraMessage::GitPull(msg) => {
+            session.rpc_git_ops.fetch_add(1, Ordering::Relaxed);
+            let workdir = git_workdir(&state, &session).await;
+            let req = msg.inner;
+            let result = match credential_helper(&state.workdir, &session.id) {
+                Ok(helper) => tokio::task::spawn_blocking(move || {
+                    GitRepo::open(&workdir).and_then(|repo| pull(&repo, &req, &helper))
+                })
+                .await
+                .map_err(|e| anyhow::anyhow!("git pull worker failed: {e}"))
+                .and_then(|result| result),
+                Err(e) => Err(e),
+            };
+            let result = result.unwrap_or_else(|e| {
+                tracing::warn!("GitPull failed: {}", e);
+                GitPullResult {
+                    failure: remote_failure(&e),
+                    error: Some(e.to_string()),
+                    ..Default::default()
+                }
+            });
+            let _ = msg.tx.send(result).await;
+        }
+
+        ZedraMessage::GitDiffStructured(msg) => {
+            session.rpc_git_ops.fetch_add(1, Ordering::Relaxed);
+            let workdir = git_workdir(&state, &session).await;
+            let req = msg.inner;
+            let result = tokio::task::spawn_blocking(move || {
+                GitRepo::open(&workdir)
+                    .and_then(|repo| diff_structured(&repo, req.path.as_deref(), req.staged))
+            })
+            .await
+            .map_err(|e| anyhow::anyhow!("git diff worker failed: {e}"))
+            .and_then(|result| result);
+            let result = match result {
+                Ok(files) => GitDiffStructuredResult { files, error: None },
+                Err(e) => {
+                    tracing::warn!("GitDiffStructured failed: {}", e);
+                    GitDiffStructuredResult {
+                        error: Some(e.to_string()),
+                        ..Default::default()
+                    }
+                }
+            };
+            let _ = msg.tx.send(result).await;
+        }
+
+        ZedraMessage::GitStageHunk(msg) => {
+            session.rpc_git_ops.fetch_add(1, Ordering::Relaxed);
+            let workdir = git_workdir(&state, &session).await;
+            let req = msg.inner;
+            let result = tokio::task::spawn_blocking(move || {
+                GitRepo::open(&workdir)
+                    .and_then(|repo| stage_hunk(&repo, &req.path, &req.header, req.unstage))
+            })
+            .await
+            .map_err(|e| anyhow::anyhow!("git stage hunk worker failed: {e}"))
+            .and_then(|result| result);
+            if let Err(e) = &result {
+                tracing::warn!("GitStageHunk failed: {}", e);
+            }
+            let _ = msg
+                .tx
+                .send(GitStageHunkResult {
+                    error: result.err().map(|e| e.to_string()),
+                })
+                .await;
+        }
+
+        ZedraMessage::GitCredentialReply(msg) => {
+            let answered = state
+                .git_credentials
+                .reply(&session.id, &msg.id, msg.credential.clone())
+                .await;
+            let result = GitCredentialReplyResult {
+                error: (!answered).then(|| "credential prompt expired".to_string()),
+            };
+            let _ = msg.tx.send(result).await;
+        }
+
+        ZedraMessage::GitDiscover(msg) => {
+            session.rpc_git_ops.fetch_add(1, Ordering::Relaxed);
+            let workdir = state.workdir.clone();
+            let selected = session.git_repo.lock().await.clone();
+            let result = tokio::task::spawn_blocking(move || discover(&workdir, selected))
+                .await
+                .unwrap_or_else(|e| GitDiscoverResult {
+                    error: Some(format!("git discover worker failed: {e}")),
+                    ..Default::default()
+                });
+            let _ = msg.tx.send(result).await;
+        }
+
+        ZedraMessage::GitSelectRepo(msg) => {
+            session.touch().await;
+            let workdir = state.workdir.clone();
+            let path = msg.path.clone();
+            let init = msg.init;
+            let result = tokio::task::spawn_blocking(move || select(&workdir, &path, init))
+                .await
+                .map_err(|e| anyhow::anyhow!("git select worker failed: {e}"))
+                .and_then(|result| result);
+            let result = match result {
+                Ok(path) => {
+                    tracing::info!("GitSelectRepo: session {} uses {:?}", session.id, path);
+                    *session.git_repo.lock().await = (!path.is_empty()).then(|| path.clone());
+                    GitSelectRepoResult { path, error: None }
+                }
+                Err(e) => {
+                    tracing::warn!("GitSelectRepo failed: {:#}", e);
+                    select_error(format!("{e:#}"))
+                }
+            };
+            let _ = msg.tx.send(result).await;
+        }
+
+        // -- Session environment --
+        ZedraMessage::SessionEnvSet(msg) => {
+            session.touch().await;
+            let result = match session_env::validate(&msg.vars) {
+                Ok(()) => {
+                    tracing::info!(
+                        "SessionEnvSet: session {} has {} overrides",
+                        session.id,
+                        msg.vars.len()
+                    );
+                    *session.env.lock().await = msg.vars.clone();
+                    SessionEnvSetResult { error: None }
+                }
+                Err(e) => {
+                    tracing::warn!("SessionEnvSet rejected: {:#}", e);
+                    SessionEnvSetResult {
+                        error: Some(format!("{e:#}")),
+                    }
+                }
+            };
+            let _ = msg.tx.send(result).await;
+        }
+
+        ZedraMessage::HostNotifySet(msg) => {
+            session.touch().await;
+            let result = match host_notify::validate(&msg.config, &state.workdir) {
+                Ok(()) => {
+                    tracing::info!(
+                        "HostNotifySet: session {} watches {} paths, long commands {}s, disk {}%",
+                        session.id,
+                        msg.config.watch_paths.len(),
+                        msg.config.long_command_secs,
+                        msg.config.disk_free_percent
+                    );
+                    *session.notify.lock().await = msg.config.clone();
+                    HostNotifySetResult { error: None }
+                }
+                Err(e) => {
+                    tracing::warn!("HostNotifySet rejected: {:#}", e);
+                    HostNotifySetResult {
+                        error: Some(format!("{e:#}")),
+                    }
+                }
+            };
+            let _ = msg.tx.send(result).await;
+        }
+
+        ZedraMessage::SessionEnvGet(msg) => {
+            session.touch().await;
+            let overrides = session.env.lock().await.clone();
+            let values = session_env::describe(&overrides).await;
+            let _ = msg
+                .tx
+                .send(SessionEnvGetResult {
+                    values,
+                    error: None,
+                })
+                .await;
+        }
+
+        // -- AI --
+        ZedraMessage::AiPrompt(msg) => {
+            // Legacy clients cannot answer permission requests, so this runs
+            // like `AiPromptV2` with nothing allowlisted: tool uses are denied,
+            // never run unattended.
+            session.touch().await;
+            let prompt_bytes = msg.prompt.len();
+            let ai_start = std::time::Instant::now();
+            let workdir = session
+                .workdir
+                .clone()
+                .unwrap_or_else(|| state.workdir.clone());
+            let req = AiPromptReqV2 {
+                prompt: msg.inner.prompt,
+                context: msg.inner.context,
+                allowed_tools: Vec::new(),
+                resume_session_id: None,
+                conversation_id: None,
+            };
+            let result = tokio::task::spawn_blocking(move || ai::run_prompt(&workdir, &req, &[]))
+                .await
+                .unwrap_or_else(|e| AiPromptResultV2 {
+                    error: Some(format!("AI prompt worker failed: {e}")),
+                    ..Default::default()
+                });
+            let duration_ms = ai_start.elapsed().as_millis() as u64;
+            let success = result.error.is_none();
+            let text = ai::legacy_prompt_text(result);
+            session.rpc_ai_prompts.fetch_add(1, Ordering::Relaxed);
+            zedra_telemetry::send(Event::AiPromptSent {
+                success,
+                duration_ms,
+                prompt_bytes,
+                response_bytes: text.len(),
+            });
+            let _ = msg.tx.send(AiPromptResult { text, done: true }).await;
+        }
+
+        ZedraMessage::AiPromptV2(msg) => {
+            session.touch().await;
+            let prompt_bytes = msg.prompt.len();
+            let ai_start = std::time::Instant::now();
+            let workdir = session
+                .workdir
+                .clone()
+                .unwrap_or_else(|| state.workdir.clone());
+            let req = msg.inner;
+            let result =
+                tokio::task::spawn_blocking(move || {
+                    match ConversationStore::for_workdir(&workdir) {
+                        Ok(store) => store.prompt(&workdir, req),
+                        Err(e) => AiPromptResultV2 {
+                            error: Some(format!("{e:#}")),
+                            ..Default::default()
+                        },
+                    }
+                })
+                .await
+                .unwrap_or_else(|e| AiPromptResultV2 {
+                    error: Some(format!("AI prompt worker failed: {e}")),
+                    ..Default::default()
+                });
+            if !result.permission_requests.is_empty() {
+                tracing::info!(
+                    "ai: {} tool use(s) awaiting device approval",
+                    result.permission_requests.len()
+                );
+            }
+            session.rpc_ai_prompts.fetch_add(1, Ordering::Relaxed);
+            zedra_telemetry::send(Event::AiPromptSent {
+                success: result.error.is_none(),
+                duration_ms: ai_start.elapsed().as_millis() as u64,
+                prompt_bytes,
+                response_bytes: result.text.len(),
+            });
+            let _ = msg.tx.send(result).await;
+        }
+
+        ZedraMessage::AiProvider(msg) => {
+            session.touch().await;
+            let _ = msg.tx.send(ai_provider_info().await).await;
+        }
+
+        ZedraMessage::AiConversationList(msg) => {
+            session.touch().await;
+            let workdir = session
+                .workdir
+                .clone()
+                .unwrap_or_else(|| state.workdir.clone());
+            let result =
+                tokio::task::spawn_blocking(move || {
+                    match ConversationStore::for_workdir(&workdir).and_then(|store| store.list()) {
+                        Ok(conversations) => AiConversationListResult {
+                            conversations,
+                            error: None,
+                        },
+                        Err(e) => AiConversationListResult {
+                            error: Some(format!("{e:#}")),
+                            ..Default::default()
+                        },
+                    }
+                })
+                .await
+                .unwrap_or_else(|e| AiConversationListResult {
+                    error: Some(format!("AI conversation worker failed: {e}")),
+                    ..Default::default()
+                });
+            let _ = msg.tx.send(result).await;
+        }
+
+        ZedraMessage::AiConversationGet(msg) => {
+            session.touch().await;
+            let workdir = session
+                .workdir
+                .clone()
+                .unwrap_or_else(|| state.workdir.clone());
+            let id = msg.inner.id;
+            let result =
+                tokio::task::spawn_blocking(move || {
+                    match ConversationStore::for_workdir(&workdir).and_then(|store| store.get(&id))
+                    {
+                        Ok(conversation) => AiConversationGetResult {
+                            conversation: Some(conversation),
+                            error: None,
+                        },
+                        Err(e) => AiConversationGetResult {
+                            error: Some(format!("{e:#}")),
+                            ..Default::default()
+                        },
+                    }
+                })
+                .await
+                .unwrap_or_else(|e| AiConversationGetResult {
+                    error: Some(format!("AI conversation worker failed: {e}")),
+                    ..Default::default()
+                });
+            let _ = msg.tx.send(result).await;
+        }
+
+        ZedraMessage::AiConversationDelete(msg) => {
+            session.touch().await;
+            let workdir = session
+                .workdir
+                .clone()
+                .unwrap_or_else(|| state.workdir.clone());
+            let id = msg.inner.id;
+            let result = tokio::task::spawn_blocking(move || {
+                let deleted =
+                    ConversationStore::for_workdir(&workdir).and_then(|store| store.delete(&id));
+                AiConversationDeleteResult {
+                    error: deleted.err().map(|e| format!("{e:#}")),
+                }
+            })
+            .await
+            .unwrap_or_else(|e| AiConversationDeleteResult {
+                error: Some(format!("AI conversation worker failed: {e}")),
+            });
+            let _ = msg.tx.send(result).await;
+        }
+
+        ZedraMessage::HostProjects(msg) => {
+            session.touch().await;
+            let workdir = session
+                .workdir
+                .clone()
+                .unwrap_or_else(|| state.workdir.clone());
+            let result = tokio::task::spawn_blocking(move || {
+                match projects::list_projects(&workdir, &client_pubkey) {
+                    Ok(projects) => HostProjectsResult {
+                        projects,
+                        error: None,
+                    },
+                    Err(e) => HostProjectsResult {
+                        error: Some(format!("{e:#}")),
+                        ..Default::default()
+                    },
+                }
+            })
+            .await
+            .unwrap_or_else(|e| HostProjectsResult {
+                error: Some(format!("project listing worker failed: {e}")),
+                ..Default::default()
+            });
+            let _ = msg.tx.send(result).await;
+        }
+
+        ZedraMessage::HostsList(msg) => {
+            session.touch().await;
+            let result = tokio::task::spawn_blocking(|| {
+                ssh_hosts::list().unwrap_or_else(|e| HostsListResult {
+                    error: Some(format!("{e:#}")),
+                    ..Default::default()
+                })
+            })
+            .await
+            .unwrap_or_else(|e| HostsListResult {
+                error: Some(format!("ssh host listing worker failed: {e}")),
+                ..Default::default()
+            });
+            let _ = msg.tx.send(result).await;
+        }
+
+        // -- Host power --
+        ZedraMessage::HostWakeInfo(msg) => {
+            session.touch().await;
+            let allow_power = state.allow_power;
+            let result = tokio::task::spawn_blocking(move || host_power::wake_info(allow_power))
+                .await
+                .unwrap_or_else(|e| HostWakeInfoResult {
+                    error: Some(format!("interface listing worker failed: {e}")),
+                    ..Default::default()
+                });
+            let _ = msg.tx.send(result).await;
+        }
+
+        ZedraMessage::HostWake(msg) => {
+            session.touch().await;
+            let result = match host_power::wake(&msg.mac, msg.broadcast.as_deref()) {
+                Ok(()) => {
+                    tracing::info!("HostWake: sent wake packet for {}", msg.mac);
+                    HostWakeResult { error: None }
+                }
+                Err(e) => HostWakeResult {
+                    error: Some(format!("{e:#}")),
+                },
+            };
+            let _ = msg.tx.send(result).await;
+        }
+
+        ZedraMessage::HostPower(msg) => {
+            session.touch().await;
+            let result = match host_power::power(msg.action, state.allow_power) {
+                Ok(()) => HostPowerResult { error: None },
+                Err(e) => {
+                    tracing::warn!("HostPower {:?} refused: {:#}", msg.action, e);
+                    HostPowerResult {
+                        error: Some(format!("{e:#}")),
+                    }
+                }
+            };
+            let _ = msg.tx.send(result).await;
+        }
+
+        // -- Data saver --
+        ZedraMessage::SessionDataSaver(msg) => {
+            session.data_saver.store(msg.enabled, Ordering::Relaxed);
+            tracing::info!(enabled = msg.enabled, "session data saver updated");
+            let _ = msg.tx.send(SessionDataSaverResult { error: None }).await;
+        }
+
+        ZedraMessage::FsReadHead(msg) => {
+            session.rpc_fs_reads.fetch_add(1, Ordering::Relaxed);
+            let result = match state.sandbox.resolve(&msg.path) {
+                Ok(path) => {
+                    let max_bytes = msg.max_bytes;
+                    tokio::task::spawn_blocking(move || data_saver::read_head(&path, max_bytes))
+                        .await
+                        .map_err(|e| std::io::Error::other(format!("read worker failed: {e}")))
+                        .and_then(|result| result)
+                        .unwrap_or_else(|e| {
+                            tracing::warn!("FsReadHead: read failed for {:?}: {}", msg.path, e);
+                            FsReadHeadResult {
+                                error: Some(e.to_string()),
+                                ..Default::default()
+                            }
+                        })
+                }
+                Err(e) => {
+                    tracing::warn!("FsReadHead: rejected path {:?}: {}", msg.path, e);
+                    FsReadHeadResult {
+                        error: Some(e.to_string()),
+                        ..Default::default()
+                    }
+                }
+            };
+            let _ = msg.tx.send(result).await;
+        }
+
+        ZedraMessage::FsReadText(msg) => {
+            session.rpc_fs_reads.fetch_add(1, Ordering::Relaxed);
+            let result = match state.sandbox.resolve(&msg.path) {
+                Ok(path) => tokio::task::spawn_blocking(move || text_file::read_text(&path))
+                    .await
+                    .map_err(|e| std::io::Error::other(format!("read worker failed: {e}")))
+                    .and_then(|result| result)
+                    .unwrap_or_else(|e| {
+                        tracing::warn!("FsReadText: read failed for {:?}: {}", msg.path, e);
+                        FsReadTextResult {
+                            error: Some(e.to_string()),
+                            ..Default::default()
+                        }
+                    }),
+                Err(e) => {
+                    tracing::warn!("FsReadText: rejected path {:?}: {}", msg.path, e);
+                    FsReadTextResult {
+                        error: Some(e.to_string()),
+                        ..Default::default()
+                    }
+                }
+            };
+            let _ = msg.tx.send(result).await;
+        }
+
+        ZedraMessage::FsWriteText(msg) => {
+            session.rpc_fs_writes.fetch_add(1, Ordering::Relaxed);
+            let result = match state.sandbox.resolve(&msg.path) {
+                Ok(path) => {
+                    let FsWriteTextReq {
+                        content,
+                        encoding,
+                        line_ending,
+                        ..
+                    } = msg.inner;
+                    tokio::task::spawn_blocking(move || {
+                        text_file::write_text(&path, &content, encoding, line_ending)
+                    })
+                    .await
+                    .map_err(|e| std::io::Error::other(format!("write worker failed: {e}")))
+                    .and_then(|result| result)
+                    .unwrap_or_else(|e| {
+                        tracing::warn!("FsWriteText: write failed: {}", e);
+                        FsWriteTextResult {
+                            error: Some(e.to_string()),
+                            ..Default::default()
+                        }
+                    })
+                }
+                Err(e) => {
+                    tracing::warn!("FsWriteText: rejected path {:?}: {}", msg.path, e);
+                    FsWriteTextResult {
+                        error: Some(e.to_string()),
+                        ..Default::default()
+                    }
+                }
+            };
+            let _ = msg.tx.send(result).await;
+        }
+
+        ZedraMessage::FsReadBinary(msg) => {
+            session.rpc_fs_reads.fetch_add(1, Ordering::Relaxed);
+            let result = match state.sandbox.resolve(&msg.path) {
+                Ok(path) => {
+                    let fs = state.fs.clone();
+                    tokio::task::spawn_blocking(move || -> anyhow::Result<FsReadBinaryResult> {
+                        let total_bytes = fs.stat(&path)?.size;
+                        if total_bytes > FS_READ_BINARY_MAX {
+                            return Ok(FsReadBinaryResult {
+                                too_large: true,
+                                total_bytes,
+                                ..Default::default()
+                            });
+                        }
+                        let data = fs.read_bytes(&path)?;
+                        Ok(FsReadBinaryResult {
+                            total_bytes: data.len() as u64,
+                            data,
+                            ..Default::default()
+                        })
+                    })
+                    .await
+                    .map_err(|e| anyhow::anyhow!("read worker failed: {e}"))
+                    .and_then(|result| result)
+                    .unwrap_or_else(|e| {
+                        tracing::warn!("FsReadBinary: read failed for {:?}: {}", msg.path, e);
+                        FsReadBinaryResult {
+                            error: Some(e.to_string()),
+                            ..Default::default()
+                        }
+                    })
+                }
+                Err(e) => {
+                    tracing::warn!("FsReadBinary: rejected path {:?}: {}", msg.path, e);
+                    FsReadBinaryResult {
+                        error: Some(e.to_string()),
+                        ..Default::default()
+                    }
+                }
+            };
+            let _ = msg.tx.send(result).await;
+        }
+
+        ZedraMessage::FsReadRange(msg) => {
+            session.rpc_fs_reads.fetch_add(1, Ordering::Relaxed);
+            let result = match state.sandbox.resolve(&msg.path) {
+                Ok(path) => {
+                    let fs = state.fs.clone();
+                    let offset = msg.offset;
+                    let max_bytes = msg.max_bytes.min(FS_READ_RANGE_MAX);
+                    tokio::task::spawn_blocking(move || fs.read_range(&path, offset, max_bytes))
+                        .await
+                        .map_err(|e| anyhow::anyhow!("read worker failed: {e}"))
+                        .and_then(|result| result)
+                        .map(|(data, total_bytes)| FsReadRangeResult {
+                            eof: offset + data.len() as u64 >= total_bytes,
+                            data,
+                            total_bytes,
+                            error: None,
+                        })
+                        .unwrap_or_else(|e| {
+                            tracing::warn!("FsReadRange: read failed for {:?}: {}", msg.path, e);
+                            FsReadRangeResult {
+                                error: Some(e.to_string()),
+                                ..Default::default()
+                            }
+                        })
+                }
+                Err(e) => {
+                    tracing::warn!("FsReadRange: rejected path {:?}: {}", msg.path, e);
+                    FsReadRangeResult {
+                        error: Some(e.to_string()),
+                        ..Default::default()
+                    }
+                }
+            };
+            let _ = msg.tx.send(result).await;
+        }
+
+        ZedraMessage::FsRename(msg) => {
+            session.rpc_fs_writes.fetch_add(1, Ordering::Relaxed);
+            let paths = state
+                .sandbox
+                .resolve(&msg.from)
+                .and_then(|from| Ok((from, state.sandbox.resolve(&msg.to)?)));
+            let result = match paths {
+                Ok((from, to)) => {
+                    let fs = state.fs.clone();
+                    tokio::task::spawn_blocking(move || fs.rename(&from, &to))
+                        .await
+                        .map_err(|e| anyhow::anyhow!("rename worker failed: {e}"))
+                        .and_then(|result| result)
+                        .map(|()| FsRenameResult { error: None })
+                        .unwrap_or_else(|e| {
+                            tracing::warn!(
+                                "FsRename: {:?} -> {:?} failed: {}",
+                                msg.from,
+                                msg.to,
+                                e
+                            );
+                            FsRenameResult {
+                                error: Some(e.to_string()),
+                            }
+                        })
+                }
+                Err(e) => {
+                    tracing::warn!("FsRename: rejected path: {}", e);
+                    FsRenameResult {
+                        error: Some(e.to_string()),
+                    }
+                }
+            };
+            let _ = msg.tx.send(result).await;
+        }
+
+        ZedraMessage::FsCopy(msg) => {
+            session.rpc_fs_writes.fetch_add(1, Ordering::Relaxed);
+            let paths = state
+                .sandbox
+                .resolve(&msg.from)
+                .and_then(|from| Ok((from, state.sandbox.resolve(&msg.to)?)));
+            let result = match paths {
+                Ok((from, to)) => {
+                    let fs = state.fs.clone();
+                    let recursive = msg.recursive;
+                    tokio::task::spawn_blocking(move || fs.copy(&from, &to, recursive))
+                        .await
+                        .map_err(|e| anyhow::anyhow!("copy worker failed: {e}"))
+                        .and_then(|result| result)
+                        .map(|()| FsCopyResult { error: None })
+                        .unwrap_or_else(|e| {
+                            tracing::warn!("FsCopy: {:?} -> {:?} failed: {}", msg.from, msg.to, e);
+                            FsCopyResult {
+                                error: Some(e.to_string()),
+                            }
+                        })
+                }
+                Err(e) => {
+                    tracing::warn!("FsCopy: rejected path: {}", e);
+                    FsCopyResult {
+                        error: Some(e.to_string()),
+                    }
+                }
+            };
+            let _ = msg.tx.send(result).await;
+        }
+
+        ZedraMessage::FsRemove(msg) => {
+            session.rpc_fs_writes.fetch_add(1, Ordering::Relaxed);
+            let result = if msg.paths.len() > FS_REMOVE_MAX_PATHS {
+                FsRemoveResult {
+                    error: Some(format!(
+                        "too many paths ({} > {FS_REMOVE_MAX_PATHS})",
+                        msg.paths.len()
+                    )),
+                    ..Default::default()
+                }
+            } else {
+                let sandbox = state.sandbox.clone();
+                let fs = state.fs.clone();
+                let paths = msg.paths;
+                tokio::task::spawn_blocking(move || {
+                    let failed = paths
+                        .into_iter()
+                        .filter_map(|path| {
+                            let removed = sandbox
+                                .resolve_entry(&path)
+                                .and_then(|resolved| fs.remove(&resolved));
+                            let error = removed.err()?;
+                            tracing::warn!("FsRemove: {:?} failed: {}", path, error);
+                            Some(FsPathError {
+                                path,
+                                error: error.to_string(),
+                            })
+                        })
+                        .collect();
+                    FsRemoveResult {
+                        failed,
+                        error: None,
+                    }
+                })
+                .await
+                .unwrap_or_else(|e| FsRemoveResult {
+                    error: Some(format!("remove worker failed: {e}")),
+                    ..Default::default()
+                })
+            };
+            let _ = msg.tx.send(result).await;
+        }
+
+        ZedraMessage::FsArchive(msg) => {
+            session.rpc_fs_writes.fetch_add(1, Ordering::Relaxed);
+            let paths = if msg.paths.len() > FS_ARCHIVE_MAX_PATHS {
+                Err(anyhow::anyhow!(
+                    "too many paths ({} > {FS_ARCHIVE_MAX_PATHS})",
+                    msg.paths.len()
+                ))
+            } else {
+                msg.paths
+                    .iter()
+                    .map(|path| state.sandbox.resolve_entry(path))
+                    .collect::<Result<Vec<_>>>()
+                    .and_then(|sources| Ok((sources, state.sandbox.resolve(&msg.to)?)))
+            };
+            let result = match paths {
+                Ok((sources, to)) => {
+                    let fs = state.fs.clone();
+                    tokio::task::spawn_blocking(move || fs.archive(&sources, &to))
+                        .await
+                        .map_err(|e| anyhow::anyhow!("archive worker failed: {e}"))
+                        .and_then(|result| result)
+                        .map(|()| FsArchiveResult { error: None })
+                        .unwrap_or_else(|e| {
+                            tracing::warn!("FsArchive: to {:?} failed: {}", msg.to, e);
+                            FsArchiveResult {
+                                error: Some(e.to_string()),
+                            }
+                        })
+                }
+                Err(e) => {
+                    tracing::warn!("FsArchive: rejected path: {}", e);
+                    FsArchiveResult {
+                        error: Some(e.to_string()),
+                    }
+                }
+            };
+            let _ = msg.tx.send(result).await;
+        }
+
+        // -- Recordings --
+        ZedraMessage::RecordingsList(msg) => {
+            let workdir = state.workdir.clone();
+            let result = tokio::task::spawn_blocking(move || recording::list(&workdir))
+                .await
+                .map_err(|e| anyhow::anyhow!("list worker failed: {e}"))
+                .and_then(|result| result);
+            let result = match result {
+                Ok(recordings) => RecordingsListResult {
+                    recordings: recordings
+                        .into_iter()
+                        .map(|r| RecordingEntry {
+                            name: r.name,
+                            size: r.size,
+                            modified_secs: r.modified_secs,
+                        })
+                        .collect(),
+                    enabled: recording::is_enabled(),
+                    error: None,
+                },
+                Err(e) => {
+                    tracing::warn!("RecordingsList: {:#}", e);
+                    RecordingsListResult {
+                        enabled: recording::is_enabled(),
+                        error: Some(format!("{e:#}")),
+                        ..Default::default()
+                    }
+                }
+            };
+            let _ = msg.tx.send(result).await;
+        }
+
+        ZedraMessage::RecordingRead(msg) => {
+            let workdir = state.workdir.clone();
+            let RecordingReadReq {
+                name,
+                offset,
+                max_bytes,
+            } = msg.inner;
+            let result = tokio::task::spawn_blocking(move || {
+                recording::read_chunk(&workdir, &name, offset, max_bytes)
+            })
+            .await
+            .map_err(|e| anyhow::anyhow!("read worker failed: {e}"))
+            .and_then(|result| result)
+            .unwrap_or_else(|e| {
+                tracing::warn!("RecordingRead: {:#}", e);
+                RecordingReadResult {
+                    error: Some(format!("{e:#}")),
+                    ..Default::default()
+                }
+            });
+            let _ = msg.tx.send(result).await;
+        }
+
+        ZedraMessage::AgentList(msg) => {
+            session.touch().await;
+            let workdir = session.workdir.as_ref().unwrap_or(&state.workdir);
+            let result =
+                agent::list_agents(&state.agent_cache, workdir, Some(&session), msg.refresh).await;
+            let _ = msg.tx.send(result).await;
+        }
+
+        ZedraMessage::AgentSessions(msg) => {
+            session.touch().await;
+            let workdir = session.workdir.as_ref().unwrap_or(&state.workdir);
+            let result = agent::list_agent_sessions(
+                &state.agent_cache,
+                &msg.slug,
+                workdir,
+                Some(&session),
+                msg.limit,
+                msg.refresh,
+            )
+            .await;
+            let _ = msg.tx.send(result).await;
+        }
+
+        ZedraMessage::AgentInstalledList(msg) => {
+            session.touch().await;
+            let result = agent::list_installed_agents(&state.agent_cache, msg.refresh).await;
+            let _ = msg.tx.send(result).await;
+        }
+
+        ZedraMessage::AgentFiles(msg) => {
+            session.touch().await;
+            let slug = msg.slug.clone();
+            // File reads are blocking; keep them off the async dispatch path.
+            let result = tokio::task::spawn_blocking(move || agent::agent_files(&slug))
+                .await
+                .map(|files| match files {
+                    Ok(files) => AgentFilesResult { files, error: None },
+                    Err(error) => AgentFilesResult {
+                        files: Vec::new(),
+                        error: Some(error),
+                    },
+                })
+                .unwrap_or_else(|e| AgentFilesResult {
+                    files: Vec::new(),
+                    error: Some(e.to_string()),
+                });
+            let _ = msg.tx.send(result).await;
+        }
+
+        ZedraMessage::AgentResume(msg) => {
+            session.touch().await;
+            let workdir = session
+                .workdir
+                .clone()
+                .or_else(|| Some(state.workdir.clone()));
+            let launch_cmd = agent::resume_launch_command(&msg.slug, &msg.session_id);
+            let Some(launch_cmd) = launch_cmd else {
+                // `None` collapses three causes; report the specific one.
+                let error = if agent::actor(&msg.slug).is_none() {
+                    format!("unknown agent: {}", msg.slug)
+                } else if msg.session_id.trim().is_empty() {
+                    "missing session id".to_string()
+                } else {
+                    format!("agent {} does not support resume", msg.slug)
+                };
+                let _ = msg
+                    .tx
+                    .send(AgentResumeResult {
+                        terminal_id: String::new(),
+                        error: Some(error),
+                    })
+                    .await;
+                return Ok(());
+            };
+            match create_terminal(
+                &session,
+                msg.cols,
+                msg.rows,
+                SpawnOptions {
+                    workdir,
+                    launch_cmd: Some(launch_cmd),
+                    color_scheme: None,
+                    env: Vec::new(),
+                },
+                Some(client_pubkey),
+            )
+            .await
+            {
+                Ok(terminal_id) => {
+                    zedra_telemetry::send(Event::HostTerminalOpen {
+                        has_launch_cmd: true,
+                    });
+                    let terminal_count = session.terminals.lock().await.len();
+                    if let Err(e) = metrics::record_terminal_created(&state.workdir, terminal_count)
+                    {
+                        tracing::warn!("Failed to record terminal metrics: {}", e);
+                    }
+                    let _ = msg
+                        .tx
+                        .send(AgentResumeResult {
+                            terminal_id,
+                            error: None,
+                        })
+                        .await;
+                }
+                Err(e) => {
+                    tracing::warn!("AgentResume failed: {}", e);
+                    let _ = msg
+                        .tx
+                        .send(AgentResumeResult {
+                            terminal_id: String::new(),
+                            error: Some(e.to_string()),
+                        })
+                        .await;
+                }
+            }
+        }
+
+        // -- LSP --
+        ZedraMessage::LspDiagnostics(msg) => {
+            let full_path = match state.sandbox.resolve(&msg.path) {
+                Ok(p) => p,
+                Err(e) => {
+                    tracing::warn!("LspDiagnostics: rejected path {:?}: {}", msg.path, e);
+                    let _ = msg
+                        .tx
+                        .send(LspDiagnosticsResult {
+                            diagnostics: vec![],
+                            error: Some(e.to_string()),
+                        })
+                        .await;
+                    return Ok(());
+                }
+            };
+            let diagnostics = run_lsp_check(&full_path)
+                .into_iter()
+                .map(|d| LspDiagnostic {
+                    message: d.message,
+                    severity: d.severity,
+                })
+                .collect();
+            let _ = msg
+                .tx
+                .send(LspDiagnosticsResult {
+                    diagnostics,
+                    error: None,
+                })
+                .await;
+        }
+
+        ZedraMessage::DiagnosticsCheck(msg) => {
+            let root = match state.sandbox.resolve(&msg.path) {
+                Ok(p) => p,
+                Err(e) => {
+                    tracing::warn!("DiagnosticsCheck: rejected path {:?}: {}", msg.path, e);
+                    let _ = msg.tx.send(diagnostics_check_error(e.to_string())).await;
+                    return Ok(());
+                }
+            };
+            let jail = state
+                .workdir
+                .canonicalize()
+                .unwrap_or_else(|_| state.workdir.clone());
+            let overrides = session.env.lock().await.clone();
+            let env = session_env::spawn_env(&overrides).await;
+            let result = match run_diagnostics_check(&jail, &root, &env).await {
+                Ok(result) => result,
+                Err(e) => {
+                    tracing::warn!("DiagnosticsCheck: failed for {:?}: {}", msg.path, e);
+                    diagnostics_check_error(e.to_string())
+                }
+            };
+            let _ = msg.tx.send(result).await;
+        }
+
+        ZedraMessage::ShellIntegration(msg) => {
+            let Some(home) = current_home_dir() else {
+                let _ = msg
+                    .tx
+                    .send(shell_integration_error(
+                        "host home directory is unknown".to_string(),
+                    ))
+                    .await;
+                return Ok(());
+            };
+            let shell = crate::pty::default_shell();
+            let zdotdir = std::env::var_os("ZDOTDIR").map(std::path::PathBuf::from);
+            let install = msg.install;
+            let result = tokio::task::spawn_blocking(move || {
+                run_shell_integration(
+                    &shell,
+                    std::path::Path::new(&home),
+                    zdotdir.as_deref(),
+                    install,
+                )
+            })
+            .await
+            .map_err(|error| anyhow::anyhow!("shell integration task failed: {error}"))
+            .and_then(|result| result);
+            let result = match result {
+                Ok(result) => result,
+                Err(e) => {
+                    tracing::warn!("ShellIntegration: failed: {}", e);
+                    shell_integration_error(e.to_string())
+                }
+            };
+            let _ = msg.tx.send(result).await;
+        }
+
+        ZedraMessage::ShellHistory(msg) => {
+            let Some(home) = current_home_dir() else {
+                let _ = msg
+                    .tx
+                    .send(shell_history_error(
+                        "host home directory is unknown".to_string(),
+                    ))
+                    .await;
+                return Ok(());
+            };
+            let shell = crate::pty::default_shell();
+            let env_path = |key: &str| std::env::var_os(key).map(std::path::PathBuf::from);
+            let (histfile, zdotdir, xdg_data_home) = (
+                env_path("HISTFILE"),
+                env_path("ZDOTDIR"),
+                env_path("XDG_DATA_HOME"),
+            );
+            let limit = msg.limit as usize;
+            let result = tokio::task::spawn_blocking(move || {
+                let paths = HistoryPaths {
+                    home: std::path::Path::new(&home),
+                    histfile: histfile.as_deref(),
+                    zdotdir: zdotdir.as_deref(),
+                    xdg_data_home: xdg_data_home.as_deref(),
+                };
+                read_shell_history(&shell, &paths, limit)
+            })
+            .await
+            .map_err(|error| anyhow::anyhow!("shell history task failed: {error}"))
+            .and_then(|result| result);
+            let result = match result {
+                Ok(result) => result,
+                Err(e) => {
+                    tracing::warn!("ShellHistory: failed: {}", e);
+                    shell_history_error(e.to_string())
+                }
+            };
+            let _ = msg.tx.send(result).await;
+        }
+
+        ZedraMessage::TaskDiscover(msg) => {
+            session.touch().await;
+            let workdir = state.workdir.clone();
+            let result = tokio::task::spawn_blocking(move || discover_tasks(&workdir))
+                .await
+                .unwrap_or_else(|e| {
+                    tracing::warn!("TaskDiscover: failed: {}", e);
+                    task_discover_error(format!("task discovery failed: {e}"))
+                });
+            let _ = msg.tx.send(result).await;
+        }
+
+        ZedraMessage::ForgeCreatePr(msg) => {
+            session.touch().await;
+            let selected = session.git_repo.lock().await.clone();
+            let workdir = match selected {
+                Some(path) => state.workdir.join(path),
+                None => session
+                    .workdir
+                    .clone()
+                    .unwrap_or_else(|| state.workdir.clone()),
+            };
+            let req = msg.inner;
+            let result = tokio::task::spawn_blocking(move || create_pull_request(&workdir, &req))
+                .await
+                .map_err(|error| anyhow::anyhow!("forge task failed: {error}"))
+                .and_then(|result| result);
+            let result = match result {
+                Ok(result) => {
+                    tracing::info!("ForgeCreatePr: opened {}", result.url);
+                    result
+                }
+                Err(e) => {
+                    tracing::warn!("ForgeCreatePr: failed: {:#}", e);
+                    forge_error(format!("{e:#}"))
+                }
+            };
+            let _ = msg.tx.send(result).await;
+        }
+
+        ZedraMessage::LspHover(msg) => {
+            let _ = msg
+                .tx
+                .send(LspHoverResult {
+                    contents: "LSP hover not yet connected to a language server.".to_string(),
+                })
+                .await;
+        }
+    }
+
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+struct DiagnosticEntry {
     message: String,
     severity: String,
 }
@@ -4100,3 +5732,107 @@ pub(crate) fn os_version_string() -> Option<String> {
         None
     }
 }
+
+#[cfg(test)]
+mod request_frame_tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn frame<T: serde::Serialize>(value: &T) -> Vec<u8> {
+        let payload = postcard::to_allocvec(value).unwrap();
+        let mut frame = postcard::to_allocvec(&(payload.len() as u64)).unwrap();
+        frame.extend(payload);
+        frame
+    }
+
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    fn fs_write() -> impl Strategy<Value = FsWriteReq> {
+        (".{0,64}", ".{0,256}").prop_map(|(path, content)| FsWriteReq { path, content })
+    }
+
+    fn term_output() -> impl Strategy<Value = TermOutput> {
+        (proptest::collection::vec(any::<u8>(), 0..256), any::<u64>())
+            .prop_map(|(data, seq)| TermOutput { data, seq })
+    }
+
+    proptest! {
+        #[test]
+        fn arbitrary_bytes_never_panic_reading_frames(bytes in proptest::collection::vec(any::<u8>(), 0..512)) {
+            if let Ok(payload) = block_on(read_request_frame(&mut bytes.as_slice())) {
+                let _ = postcard::from_bytes::<ZedraProto>(&payload);
+            }
+        }
+
+        #[test]
+        fn truncated_frames_are_rejected(req in fs_write()) {
+            let request = ZedraProto::FsWrite(req);
+            let framed = frame(&request);
+
+            // A truncated frame is incomplete, never a different message.
+            let prefix_len = framed.len() - postcard::to_allocvec(&request).unwrap().len();
+            for cut in 0..framed.len() {
+                let result = block_on(read_request_frame(&mut &framed[..cut]));
+                prop_assert!(result.is_err());
+                if cut >= prefix_len {
+                    prop_assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::UnexpectedEof);
+                }
+            }
+            let payload = block_on(read_request_frame(&mut framed.as_slice())).unwrap();
+            let decoded = postcard::from_bytes::<ZedraProto>(&payload).unwrap();
+            let (ZedraProto::FsWrite(decoded), ZedraProto::FsWrite(expected)) = (decoded, &request) else {
+                panic!("decoded a different request");
+            };
+            prop_assert_eq!(&decoded.path, &expected.path);
+            prop_assert_eq!(&decoded.content, &expected.content);
+        }
+
+        #[test]
+        fn oversized_lengths_are_rejected(
+            claimed in (MAX_MESSAGE_SIZE + 1)..u64::MAX,
+            tail in proptest::collection::vec(any::<u8>(), 0..32),
+        ) {
+            let mut framed = postcard::to_allocvec(&claimed).unwrap();
+            framed.extend(&tail);
+            let err = block_on(read_request_frame(&mut framed.as_slice())).unwrap_err();
+            prop_assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+            prop_assert!(is_oversized_frame(&err));
+        }
+
+        #[test]
+        fn interleaved_stream_frames_decode_in_order(
+            outputs in proptest::collection::vec(term_output(), 1..16),
+            chunk in 1usize..64,
+        ) {
+            let wire: Vec<u8> = outputs.iter().flat_map(frame).collect();
+
+            // A small duplex buffer splits and merges frames the way QUIC reads do.
+            let payloads = block_on(async {
+                let (mut tx, mut rx) = tokio::io::duplex(chunk);
+                let writer = tokio::spawn(async move { tx.write_all(&wire).await });
+                let mut payloads = Vec::new();
+                for _ in 0..outputs.len() {
+                    payloads.push(read_request_frame(&mut rx).await.unwrap());
+                }
+                writer.await.unwrap().unwrap();
+                let mut trailing = Vec::new();
+                rx.read_to_end(&mut trailing).await.unwrap();
+                assert!(trailing.is_empty());
+                payloads
+            });
+
+            prop_assert_eq!(payloads.len(), outputs.len());
+            for (payload, expected) in payloads.iter().zip(&outputs) {
+                let decoded = postcard::from_bytes::<TermOutput>(payload).unwrap();
+                prop_assert_eq!(decoded.seq, expected.seq);
+                prop_assert_eq!(&decoded.data, &expected.data);
+            }
+        }
+    }
+}
@@ -0,0 +1,224 @@
+// Workspace sandbox for client-supplied paths.
+//
+// fs and LSP handlers resolve paths through `Sandbox::resolve`: the path is
+// joined to the workdir and canonicalized, following symlinks, and the result
+// must stay under the workdir or under one of the extra roots passed with
+// `zedra start --allow-root`. Git handlers hand paths to git as pathspecs, so
+// they get the lexical `check_pathspec` instead. Both reject with
+// `OutsideWorkspace`, whose message starts with `PATH_OUTSIDE_WORKSPACE_ERROR`
+// so the app can tell a rejected path from a missing file.
+
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::Result;
+use zedra_rpc::proto::PATH_OUTSIDE_WORKSPACE_ERROR;
+
+/// A path the sandbox refused, as the client sent it. The resolved path is
+/// left out so the error does not reveal the host's directory layout.
+#[derive(Debug)]
+pub struct OutsideWorkspace {
+    pub path: String,
+}
+
+impl std::fmt::Display for OutsideWorkspace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{PATH_OUTSIDE_WORKSPACE_ERROR}: {}", self.path)
+    }
+}
+
+impl std::error::Error for OutsideWorkspace {}
+
+/// The directories a session may reach: the workdir, plus any extra roots.
+#[derive(Clone, Debug)]
+pub struct Sandbox {
+    root: PathBuf,
+    extra_roots: Vec<PathBuf>,
+}
+
+impl Sandbox {
+    pub fn new(root: PathBuf) -> Self {
+        Self {
+            root,
+            extra_roots: Vec::new(),
+        }
+    }
+
+    /// Also accept absolute paths under `roots`. Roots that do not exist are
+    /// dropped with a warning.
+    pub fn with_extra_roots(mut self, roots: impl IntoIterator<Item = PathBuf>) -> Self {
+        self.extra_roots = roots
+            .into_iter()
+            .filter_map(|root| match root.canonicalize() {
+                Ok(root) => Some(root),
+                Err(e) => {
+                    tracing::warn!("ignoring --allow-root {}: {}", root.display(), e);
+                    None
+                }
+            })
+            .collect();
+        self
+    }
+
+    pub fn extra_roots(&self) -> &[PathBuf] {
+        &self.extra_roots
+    }
+
+    /// Resolve `user_path` against the workdir; absolute paths may also land
+    /// under an extra root.
+    pub fn resolve(&self, user_path: &str) -> Result<PathBuf> {
+        if Path::new(user_path).is_absolute() {
+            for root in &self.extra_roots {
+                if let Ok(path) = resolve_path(root, user_path) {
+                    return Ok(path);
+                }
+            }
+        }
+        resolve_path(&self.root, user_path)
+    }
+
+    /// Resolve `user_path` to the directory entry it names, for deleting or
+    /// packing it: the parent resolves like `resolve`, but the last component
+    /// is kept, so a symlink stays the link rather than its target. A root
+    /// itself is refused.
+    pub fn resolve_entry(&self, user_path: &str) -> Result<PathBuf> {
+        let outside = || {
+            anyhow::Error::from(OutsideWorkspace {
+                path: user_path.to_string(),
+            })
+        };
+        let path = Path::new(user_path);
+        let Some(Component::Normal(name)) = path.components().next_back() else {
+            return Err(outside());
+        };
+        let parent = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        let parent = self.resolve(parent.to_str().ok_or_else(outside)?)?;
+        Ok(parent.join(name))
+    }
+}
+
+/// Resolve `user_path` relative to `workdir`, then verify the canonical path
+/// stays inside `workdir`. Rejects `..` escapes, absolute paths elsewhere, and
+/// symlinks that point outside the jail.
+pub(crate) fn resolve_path(workdir: &Path, user_path: &str) -> Result<PathBuf> {
+    // Reject empty paths
+    anyhow::ensure!(!user_path.is_empty(), "empty path");
+    let joined = workdir.join(user_path);
+    let resolved = joined.canonicalize().or_else(|_| {
+        // File may not exist yet (e.g. FsWrite to a new path).
+        // Walk up to the first existing ancestor and canonicalize that.
+        let mut base = joined.as_path();
+        while let Some(parent) = base.parent() {
+            if parent.exists() {
+                let canon = parent.canonicalize()?;
+                // Reconstruct: canon + the non-existing tail
+                let tail = joined.strip_prefix(parent).unwrap_or(base);
+                return Ok(canon.join(tail));
+            }
+            base = parent;
+        }
+        anyhow::bail!("could not resolve path");
+    })?;
+    let jail = workdir.canonicalize()?;
+    // The non-existing tail is not canonicalized, so `..` in it must be
+    // rejected here rather than by the prefix check.
+    let escapes = resolved
+        .components()
+        .any(|component| component == Component::ParentDir);
+    if escapes || !resolved.starts_with(&jail) {
+        return Err(OutsideWorkspace {
+            path: user_path.to_string(),
+        }
+        .into());
+    }
+    Ok(resolved)
+}
+
+/// Check a pathspec before it is passed to git, which runs in the workdir:
+/// it must be relative, must not climb out with `..`, and must not use git's
+/// `:` magic, which can address the whole repository.
+pub fn check_pathspec(path: &str) -> Result<()> {
+    let relative = Path::new(path);
+    let mut depth = 0usize;
+    for component in relative.components() {
+        match component {
+            Component::Normal(_) => depth += 1,
+            Component::CurDir => {}
+            Component::ParentDir if depth > 0 => depth -= 1,
+            _ => {
+                return Err(OutsideWorkspace {
+                    path: path.to_string(),
+                }
+                .into())
+            }
+        }
+    }
+    if path.starts_with(':') {
+        return Err(OutsideWorkspace {
+            path: path.to_string(),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// `check_pathspec` for each of `paths`.
+pub fn check_pathspecs(paths: &[String]) -> Result<()> {
+    paths.iter().try_for_each(|path| check_pathspec(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_escapes_and_allows_extra_roots() {
+        let workdir = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        std::fs::write(outside.path().join("notes.txt"), "hi").unwrap();
+        std::fs::write(workdir.path().join("main.rs"), "").unwrap();
+
+        let sandbox = Sandbox::new(workdir.path().to_path_buf());
+        assert!(sandbox.resolve("main.rs").is_ok());
+        assert!(sandbox.resolve("src/new.rs").is_ok());
+        let outside_file = outside.path().join("notes.txt");
+        let outside_file = outside_file.to_str().unwrap();
+        for path in ["../../etc/passwd", "src/../../x", outside_file] {
+            let error = sandbox.resolve(path).unwrap_err();
+            assert!(
+                error.to_string().starts_with(PATH_OUTSIDE_WORKSPACE_ERROR),
+                "{path}: {error}"
+            );
+        }
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(outside.path(), workdir.path().join("link")).unwrap();
+            assert!(sandbox.resolve("link/notes.txt").is_err());
+            let entry = sandbox.resolve_entry("link").unwrap();
+            assert_eq!(entry.file_name().unwrap(), "link");
+            assert!(entry.starts_with(workdir.path().canonicalize().unwrap()));
+        }
+        for path in [".", "src/..", "/", "../main.rs"] {
+            assert!(sandbox.resolve_entry(path).is_err(), "{path}");
+        }
+        assert!(sandbox.resolve_entry("main.rs").is_ok());
+
+        let sandbox = sandbox.with_extra_roots([outside.path().to_path_buf()]);
+        assert!(sandbox.resolve(outside_file).is_ok());
+        // Extra roots only take absolute paths; relative ones stay in the workdir.
+        assert!(sandbox.resolve("../notes.txt").is_err());
+    }
+
+    #[test]
+    fn pathspecs_stay_relative() {
+        assert!(check_pathspec("src/main.rs").is_ok());
+        assert!(check_pathspec("src/../README.md").is_ok());
+        assert!(check_pathspec("../other/file").is_err());
+        assert!(check_pathspec("/etc/passwd").is_err());
+        assert!(check_pathspec(":(top)secret").is_err());
+        assert!(check_pathspecs(&["a".to_string(), "b/../../c".to_string()]).is_err());
+    }
+}
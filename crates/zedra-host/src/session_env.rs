@@ -0,0 +1,204 @@
+// Session environment overrides for `SessionEnvSet` and `SessionEnvGet`.
+//
+// The app keeps the override list with its saved workspace for a host and
+// sends it after every connect; the daemon holds it on the `ServerSession`
+// and resolves it each time the session starts a terminal or a diagnostics
+// check. Overrides are applied after the PTY environment is sanitized, so
+// they can add variables the allowlist drops. Keychain overrides are looked
+// up on the host at start time and their values never leave it:
+//   macOS  `security find-generic-password -s <service> -a <NAME> -w`
+//   Linux  `secret-tool lookup service <service> account <NAME>`
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use zedra_rpc::proto::{SessionEnvKind, SessionEnvValue, SessionEnvVar, SESSION_ENV_MAX_VARS};
+
+/// Set by the daemon or the PTY itself; an override would break the terminal.
+const RESERVED_NAMES: &[&str] = &["TERM", "COLORTERM", "SHELL"];
+const RESERVED_PREFIX: &str = "ZEDRA_";
+const KEYCHAIN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Reject lists the session would not be able to apply.
+pub fn validate(vars: &[SessionEnvVar]) -> Result<()> {
+    if vars.len() > SESSION_ENV_MAX_VARS {
+        bail!("at most {SESSION_ENV_MAX_VARS} environment overrides are allowed");
+    }
+    let mut seen = HashSet::new();
+    for var in vars {
+        let name = var.name.as_str();
+        let valid_name = name
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid_name {
+            bail!("invalid variable name: {name:?}");
+        }
+        if RESERVED_NAMES.contains(&name) || name.starts_with(RESERVED_PREFIX) {
+            bail!("{name} is set by zedra and cannot be overridden");
+        }
+        if !seen.insert(name) {
+            bail!("{name} is listed more than once");
+        }
+        if var.value.contains('\0') {
+            bail!("value of {name} contains a NUL byte");
+        }
+        if var.kind == SessionEnvKind::Keychain && var.value.trim().is_empty() {
+            bail!("{name} needs a keychain service name");
+        }
+    }
+    Ok(())
+}
+
+/// Environment pairs for a process the session starts. Overrides that fail
+/// to resolve are logged and left out rather than blocking the start.
+pub async fn spawn_env(vars: &[SessionEnvVar]) -> Vec<(String, String)> {
+    let mut env = Vec::with_capacity(vars.len());
+    for var in vars {
+        match resolve(var).await {
+            Ok(value) => env.push((var.name.clone(), value)),
+            Err(e) => tracing::warn!("session env: skipping {}: {:#}", var.name, e),
+        }
+    }
+    env
+}
+
+/// What each override resolves to, for review in the app. Keychain secrets
+/// are checked but not returned.
+pub async fn describe(vars: &[SessionEnvVar]) -> Vec<SessionEnvValue> {
+    let mut values = Vec::with_capacity(vars.len());
+    for var in vars {
+        let resolved = resolve(var).await;
+        values.push(SessionEnvValue {
+            name: var.name.clone(),
+            value: match (&resolved, var.kind) {
+                (Ok(value), SessionEnvKind::Set | SessionEnvKind::Prepend) => Some(value.clone()),
+                _ => None,
+            },
+            error: resolved.err().map(|e| format!("{e:#}")),
+        });
+    }
+    values
+}
+
+async fn resolve(var: &SessionEnvVar) -> Result<String> {
+    match var.kind {
+        SessionEnvKind::Set => Ok(var.value.clone()),
+        SessionEnvKind::Prepend => Ok(prepend(
+            &var.value,
+            std::env::var(&var.name).ok().as_deref(),
+        )),
+        SessionEnvKind::Keychain => keychain_lookup(var.value.trim(), &var.name).await,
+    }
+}
+
+fn prepend(value: &str, existing: Option<&str>) -> String {
+    let separator = if cfg!(windows) { ';' } else { ':' };
+    match existing {
+        Some(existing) if !existing.is_empty() => format!("{value}{separator}{existing}"),
+        _ => value.to_string(),
+    }
+}
+
+async fn keychain_lookup(service: &str, account: &str) -> Result<String> {
+    let (program, args): (&str, Vec<&str>) = if cfg!(target_os = "macos") {
+        (
+            "security",
+            vec!["find-generic-password", "-s", service, "-a", account, "-w"],
+        )
+    } else if cfg!(windows) {
+        bail!("keychain overrides are not supported on Windows hosts");
+    } else {
+        (
+            "secret-tool",
+            vec!["lookup", "service", service, "account", account],
+        )
+    };
+    let output = tokio::time::timeout(
+        KEYCHAIN_TIMEOUT,
+        tokio::process::Command::new(program)
+            .args(&args)
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .map_err(|_| anyhow::anyhow!("{program} timed out"))?
+    .with_context(|| format!("failed to run {program}"))?;
+    if !output.status.success() {
+        bail!("no keychain item for service {service:?}, account {account:?}");
+    }
+    let value = String::from_utf8(output.stdout).context("keychain value is not UTF-8")?;
+    let value = value.strip_suffix('\n').unwrap_or(&value);
+    if value.is_empty() {
+        bail!("no keychain item for service {service:?}, account {account:?}");
+    }
+    Ok(value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn var(name: &str, value: &str, kind: SessionEnvKind) -> SessionEnvVar {
+        SessionEnvVar {
+            name: name.into(),
+            value: value.into(),
+            kind,
+        }
+    }
+
+    #[test]
+    fn validate_rejects_bad_reserved_and_duplicate_names() {
+        assert!(validate(&[
+            var("RUST_LOG", "debug", SessionEnvKind::Set),
+            var("PATH", "/opt/tools/bin", SessionEnvKind::Prepend),
+            var("OPENAI_API_KEY", "openai", SessionEnvKind::Keychain),
+        ])
+        .is_ok());
+        assert!(validate(&[var("1ABC", "x", SessionEnvKind::Set)]).is_err());
+        assert!(validate(&[var("A-B", "x", SessionEnvKind::Set)]).is_err());
+        assert!(validate(&[var("TERM", "dumb", SessionEnvKind::Set)]).is_err());
+        assert!(validate(&[var("ZEDRA_WORKDIR", "/", SessionEnvKind::Set)]).is_err());
+        assert!(validate(&[var("KEY", " ", SessionEnvKind::Keychain)]).is_err());
+        assert!(validate(&[
+            var("RUST_LOG", "debug", SessionEnvKind::Set),
+            var("RUST_LOG", "info", SessionEnvKind::Set),
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn prepend_joins_with_the_existing_value() {
+        let separator = if cfg!(windows) { ';' } else { ':' };
+        assert_eq!(
+            prepend("/opt/bin", Some("/usr/bin")),
+            format!("/opt/bin{separator}/usr/bin")
+        );
+        assert_eq!(prepend("/opt/bin", Some("")), "/opt/bin");
+        assert_eq!(prepend("/opt/bin", None), "/opt/bin");
+    }
+
+    #[tokio::test]
+    async fn spawn_env_keeps_order_and_describe_reports_literals() {
+        let vars = vec![
+            var("RUST_LOG", "zedra=debug", SessionEnvKind::Set),
+            var(
+                "SESSION_ENV_TEST_UNSET",
+                "/opt/bin",
+                SessionEnvKind::Prepend,
+            ),
+        ];
+        assert_eq!(
+            spawn_env(&vars).await,
+            vec![
+                ("RUST_LOG".to_string(), "zedra=debug".to_string()),
+                ("SESSION_ENV_TEST_UNSET".to_string(), "/opt/bin".to_string()),
+            ]
+        );
+        let values = describe(&vars).await;
+        assert_eq!(values[0].value.as_deref(), Some("zedra=debug"));
+        assert!(values.iter().all(|value| value.error.is_none()));
+    }
+}
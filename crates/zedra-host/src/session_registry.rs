@@ -13,7 +13,7 @@ use crate::docs_tree::{
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex as StdMutex};
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
@@ -21,8 +21,8 @@ use tokio::sync::Mutex;
 use uuid::Uuid;
 use zedra_osc::{OscEvent, OscScanner};
 use zedra_rpc::proto::{
-    AgentState, BacklogEntry, FsDocsTreeError, FsDocsTreeResult, HostEvent, SessionCloseReason,
-    TermOutput, TermShellState, TerminalSyncEntry,
+    AgentState, BacklogEntry, FsDocsTreeError, FsDocsTreeResult, HostEvent, HostNotifyConfig,
+    SessionCloseReason, SessionEnvVar, TermOutput, TermShellState, TerminalSyncEntry,
 };
 use zedra_rpc::verify_registration_hmac;
 
@@ -268,6 +268,14 @@ pub struct ServerSession {
     pub docs_tree_cache: Mutex<Option<DocsTreeCacheEntry>>,
     /// Prevents repeated rebuild requests from starting overlapping filesystem scans.
     pub docs_tree_scan_in_flight: AtomicBool,
+    /// Workdir-relative repository picked with `GitSelectRepo`; git RPCs run
+    /// there instead of the daemon workdir.
+    pub git_repo: Mutex<Option<String>>,
+    /// Environment overrides from `SessionEnvSet`, applied to terminals and
+    /// checks this session starts.
+    pub env: Mutex<Vec<SessionEnvVar>>,
+    /// Watchers from `HostNotifySet`; all off until the client sends one.
+    pub notify: Mutex<HostNotifyConfig>,
     // ── RPC usage counters (lifetime totals, never reset) ──────────────────
     /// Total FsRead calls served.
     pub rpc_fs_reads: AtomicU64,
@@ -292,6 +300,8 @@ pub struct ServerSession {
     /// Whether the connected client app is currently in the foreground.
     /// Set via the SetAppState RPC; used to decide when to send Delta push notifications.
     pub client_in_foreground: AtomicBool,
+    /// Set via the SessionDataSaver RPC; batches and summarizes terminal output.
+    pub data_saver: AtomicBool,
     /// Live agent state per terminal (terminal_id → state). Updated by hook receivers.
     pub terminal_agent_states: Mutex<HashMap<String, AgentState>>,
     /// Per-terminal idle-revert timers. Aborted when a new state transition arrives.
@@ -374,6 +384,16 @@ pub struct HostTermMeta {
     /// Host-resolved agent slug for the foreground process. Recomputed via
     /// `refresh_agent_slug` after OSC events; authoritative agent identity.
     pub agent_slug: Option<&'static str>,
+    /// When the running command started, from OSC 133 `C`.
+    pub command_started_at: Option<Instant>,
+}
+
+/// A command that exited, reported by `HostTermMeta::apply_osc_event`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FinishedCommand {
+    pub command: Option<String>,
+    pub exit_code: i32,
+    pub duration: Duration,
 }
 
 impl Default for HostTermMeta {
@@ -387,28 +407,43 @@ impl Default for HostTermMeta {
             shell_state: TermShellState::Unknown,
             last_exit_code: None,
             agent_slug: None,
+            command_started_at: None,
         }
     }
 }
 
 impl HostTermMeta {
-    pub fn apply_osc_event(&mut self, event: &OscEvent) {
+    /// Apply one OSC event; returns the command that exited on `CommandEnd`
+    /// when its start was seen.
+    pub fn apply_osc_event(&mut self, event: &OscEvent) -> Option<FinishedCommand> {
         match event {
             OscEvent::Title(title) => self.title = Some(title.clone()),
             OscEvent::ResetTitle => self.title = None,
             OscEvent::IconName(name) => self.icon_name = Some(name.clone()),
             OscEvent::Cwd(cwd) => self.cwd = Some(cwd.clone()),
             OscEvent::CommandLine(command) => self.current_command = Some(command.clone()),
-            OscEvent::CommandStart => self.shell_state = TermShellState::Running,
+            OscEvent::CommandStart => {
+                self.shell_state = TermShellState::Running;
+                self.command_started_at = Some(Instant::now());
+            }
             OscEvent::CommandEnd { exit_code } => {
                 self.shell_state = TermShellState::Idle;
                 self.last_exit_code = Some(*exit_code);
-                self.current_command = None;
+                let command = self.current_command.take();
+                return self
+                    .command_started_at
+                    .take()
+                    .map(|started_at| FinishedCommand {
+                        command,
+                        exit_code: *exit_code,
+                        duration: started_at.elapsed(),
+                    });
             }
             // Keep current_command: the foreground command did not exit.
             OscEvent::PromptReady => self.shell_state = TermShellState::Idle,
             _ => {}
         }
+        None
     }
 
     /// Recompute the resolved agent slug from the current command (with the
@@ -515,6 +550,27 @@ pub struct TermSession {
     pub created_at: SystemTime,
     /// Monotonic creation time for terminal uptime calculations.
     pub started_at: Instant,
+    /// Device that created the terminal. `None` for terminals started on the
+    /// host (`zedra terminal`, the local API), which every device can use.
+    pub owner: Option<[u8; 32]>,
+}
+
+/// Which of a session's terminals a caller may see and control.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TerminalScope {
+    /// Every terminal: host-side callers and `zedra start --admin-client` devices.
+    All,
+    /// Terminals this device created, plus host-started ones.
+    Device([u8; 32]),
+}
+
+impl TerminalScope {
+    pub fn includes(&self, owner: Option<&[u8; 32]>) -> bool {
+        match (self, owner) {
+            (Self::All, _) | (_, None) => true,
+            (Self::Device(device), Some(owner)) => device == owner,
+        }
+    }
 }
 
 impl TermSession {
@@ -609,6 +665,25 @@ fn validate_terminal_order<'a>(
     Ok(())
 }
 
+/// Put `reordered` into the slots of `current` that `in_scope` accepts.
+fn merge_terminal_order(
+    current: Vec<String>,
+    reordered: Vec<String>,
+    in_scope: impl Fn(&str) -> bool,
+) -> Vec<String> {
+    let mut reordered = reordered.into_iter();
+    current
+        .into_iter()
+        .map(|id| {
+            if in_scope(&id) {
+                reordered.next().unwrap_or(id)
+            } else {
+                id
+            }
+        })
+        .collect()
+}
+
 /// Summary of a session for listing purposes.
 #[derive(Debug, Clone)]
 pub struct SessionInfo {
@@ -632,6 +707,8 @@ pub struct TerminalInfo {
     pub created_at_unix_secs: u64,
     pub created_at_elapsed_secs: u64,
     pub uptime_secs: u64,
+    /// Creating device's public key, for `zedra start --admin-client`.
+    pub owner: Option<[u8; 32]>,
 }
 
 // ---------------------------------------------------------------------------
@@ -660,6 +737,23 @@ struct PersistedSession {
     acl: Vec<[u8; 32]>,
 }
 
+/// Where the daemon serving `workdir` persists its registry.
+pub fn registry_path(workdir: &Path) -> PathBuf {
+    crate::identity::workspace_config_dir(workdir)
+        .map(|d| d.join("sessions.json"))
+        .unwrap_or_else(|_| workdir.join(".zedra-sessions.json"))
+}
+
+/// Whether `client_pubkey` has paired with the workspace whose registry is
+/// persisted at `path`. Reads the file directly, so another daemon's registry
+/// can be checked without loading it; an unreadable file authorizes no one.
+pub fn is_authorized_in_file(path: &Path, client_pubkey: &[u8; 32]) -> bool {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|data| serde_json::from_str::<PersistedState>(&data).ok())
+        .is_some_and(|state| state.authorized_clients.contains(client_pubkey))
+}
+
 // ---------------------------------------------------------------------------
 // SessionRegistry
 // ---------------------------------------------------------------------------
@@ -1009,6 +1103,37 @@ impl SessionRegistry {
         }
     }
 
+    /// Terminate the terminals of sessions that have had no attached client
+    /// and no activity for longer than `idle_timeout`. The sessions stay so
+    /// their paired devices can still reconnect. Returns how many terminals
+    /// were closed.
+    pub async fn reap_idle_terminals(&self, idle_timeout: Duration) -> usize {
+        let sessions: Vec<Arc<ServerSession>> =
+            self.sessions.lock().await.values().cloned().collect();
+        let mut closed = 0;
+        for session in sessions {
+            if session.active_client.lock().await.is_some()
+                || session.last_activity.lock().await.elapsed() <= idle_timeout
+            {
+                continue;
+            }
+            for id in session.terminal_ids(TerminalScope::All).await {
+                let Some(terminal) = session.remove_terminal(&id).await else {
+                    continue;
+                };
+                tracing::info!(
+                    "Closing terminal {} of idle session {} after {}s",
+                    id,
+                    session.id,
+                    idle_timeout.as_secs()
+                );
+                let _ = tokio::task::spawn_blocking(move || terminal.terminate()).await;
+                closed += 1;
+            }
+        }
+        closed
+    }
+
     /// Clean up sessions that have been idle longer than `grace_period`.
     /// Returns the IDs of removed sessions.
     pub async fn cleanup(&self, grace_period: Duration) -> Vec<String> {
@@ -1395,6 +1520,9 @@ impl ServerSession {
             )),
             docs_tree_cache: Mutex::new(None),
             docs_tree_scan_in_flight: AtomicBool::new(false),
+            git_repo: Mutex::new(None),
+            env: Mutex::new(Vec::new()),
+            notify: Mutex::new(HostNotifyConfig::default()),
             rpc_fs_reads: AtomicU64::new(0),
             rpc_fs_writes: AtomicU64::new(0),
             rpc_git_ops: AtomicU64::new(0),
@@ -1407,6 +1535,7 @@ impl ServerSession {
             fs_watch_quota_rejected: AtomicU64::new(0),
             fs_watch_rate_limited: AtomicU64::new(0),
             client_in_foreground: AtomicBool::new(true),
+            data_saver: AtomicBool::new(false),
             terminal_agent_states: Mutex::new(HashMap::new()),
             terminal_idle_timers: Mutex::new(HashMap::new()),
             terminal_state_generation: Mutex::new(HashMap::new()),
@@ -1442,14 +1571,17 @@ impl ServerSession {
         &entry.token == session_token
     }
 
-    pub async fn terminal_sync_entries(&self) -> Vec<TerminalSyncEntry> {
+    pub async fn terminal_sync_entries(&self, scope: TerminalScope) -> Vec<TerminalSyncEntry> {
         let terms = self.terminals.lock().await;
         let mut order = self.terminal_order.lock().await;
         let agent_states = self.terminal_agent_states.lock().await;
         let ordered_ids = ordered_terminal_ids_locked(&terms, &mut order);
         let mut entries = Vec::with_capacity(ordered_ids.len());
         for (position, id) in ordered_ids.into_iter().enumerate() {
-            let Some(term) = terms.get(&id) else {
+            let Some(term) = terms
+                .get(&id)
+                .filter(|term| scope.includes(term.owner.as_ref()))
+            else {
                 continue;
             };
             let meta_entry = term
@@ -1484,10 +1616,25 @@ impl ServerSession {
         entries
     }
 
-    pub async fn terminal_ids(&self) -> Vec<String> {
+    pub async fn terminal_ids(&self, scope: TerminalScope) -> Vec<String> {
         let terms = self.terminals.lock().await;
         let mut order = self.terminal_order.lock().await;
-        ordered_terminal_ids_locked(&terms, &mut order)
+        let mut ids = ordered_terminal_ids_locked(&terms, &mut order);
+        ids.retain(|id| {
+            terms
+                .get(id)
+                .is_some_and(|term| scope.includes(term.owner.as_ref()))
+        });
+        ids
+    }
+
+    /// Whether terminal `id` exists and `scope` may use it.
+    pub async fn has_terminal_in_scope(&self, id: &str, scope: TerminalScope) -> bool {
+        self.terminals
+            .lock()
+            .await
+            .get(id)
+            .is_some_and(|term| scope.includes(term.owner.as_ref()))
     }
 
     pub async fn terminal_infos(&self) -> Vec<TerminalInfo> {
@@ -1519,6 +1666,7 @@ impl ServerSession {
                 created_at_unix_secs,
                 created_at_elapsed_secs: uptime_secs,
                 uptime_secs,
+                owner: term.owner,
             });
         }
         entries
@@ -1546,12 +1694,26 @@ impl ServerSession {
         terminal
     }
 
-    pub async fn reorder_terminals(&self, ordered_ids: Vec<String>) -> Result<(), String> {
+    /// Reorder the terminals `scope` can see; the rest keep their slots.
+    pub async fn reorder_terminals(
+        &self,
+        ordered_ids: Vec<String>,
+        scope: TerminalScope,
+    ) -> Result<(), String> {
         let terms = self.terminals.lock().await;
-        validate_terminal_order(&ordered_ids, terms.keys().map(String::as_str))?;
-
         let mut order = self.terminal_order.lock().await;
-        *order = ordered_ids;
+        let current = ordered_terminal_ids_locked(&terms, &mut order);
+        let in_scope = |id: &str| {
+            terms
+                .get(id)
+                .is_some_and(|term| scope.includes(term.owner.as_ref()))
+        };
+        validate_terminal_order(
+            &ordered_ids,
+            current.iter().map(String::as_str).filter(|id| in_scope(id)),
+        )?;
+
+        *order = merge_terminal_order(current, ordered_ids, in_scope);
         Ok(())
     }
 
@@ -1851,6 +2013,24 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn authorized_clients_are_read_from_the_registry_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sessions.json");
+        let registry = SessionRegistry::load_or_new(path.clone()).await;
+        let session = create_session(&registry).await;
+        registry
+            .add_client_to_session(&session.id, make_pubkey(1))
+            .await;
+
+        assert!(is_authorized_in_file(&path, &make_pubkey(1)));
+        assert!(!is_authorized_in_file(&path, &make_pubkey(2)));
+        assert!(!is_authorized_in_file(
+            &dir.path().join("missing.json"),
+            &make_pubkey(1)
+        ));
+    }
+
     #[test]
     fn push_pending_webview_dedupes_bumps_recency_and_caps() {
         let mut pending = Vec::new();
@@ -2051,6 +2231,21 @@ mod tests {
         .is_err());
     }
 
+    #[test]
+    fn scoped_terminal_order_keeps_other_devices_in_place() {
+        let mine = [1u8; 32];
+        let scope = TerminalScope::Device(mine);
+        assert!(scope.includes(Some(&mine)));
+        assert!(scope.includes(None));
+        assert!(!scope.includes(Some(&[2u8; 32])));
+        assert!(TerminalScope::All.includes(Some(&[2u8; 32])));
+
+        let current = ["a", "theirs", "b", "c"].map(String::from).to_vec();
+        let reordered = ["c", "a", "b"].map(String::from).to_vec();
+        let merged = merge_terminal_order(current, reordered, |id| id != "theirs");
+        assert_eq!(merged, ["c", "theirs", "a", "b"]);
+    }
+
     #[tokio::test]
     async fn create_new_session() {
         let registry = SessionRegistry::new();
@@ -2583,6 +2778,21 @@ mod tests {
         assert!(registry.get(&id).await.is_some());
     }
 
+    #[tokio::test]
+    async fn reap_idle_terminals_keeps_sessions() {
+        let registry = SessionRegistry::new();
+        let s = registry.create_named("idle", PathBuf::from("/idle")).await;
+        let id = s.id.clone();
+        *s.last_activity.lock().await = Instant::now() - Duration::from_secs(600);
+
+        assert_eq!(
+            registry.reap_idle_terminals(Duration::from_secs(300)).await,
+            0
+        );
+        assert!(registry.get(&id).await.is_some());
+        assert!(registry.get_by_name("idle").await.is_some());
+    }
+
     #[tokio::test]
     async fn terminal_id_generation() {
         let registry = SessionRegistry::new();
@@ -0,0 +1,333 @@
+// Host shell history for `ShellHistory`.
+//
+// Reads the tail of the history file for the shell the daemon spawns
+// terminals with and returns recent unique commands, newest first, plus the
+// directories those commands `cd`-ed into that still exist. Formats:
+//   bash  ~/.bash_history      one command per line, `#<epoch>` timestamps
+//   zsh   ~/.zsh_history       `: <epoch>:<secs>;cmd`, `\` continues a line,
+//                              metafied bytes (0x83 + byte ^ 0x20)
+//   fish  ~/.local/share/fish/fish_history   `- cmd: ...` entries
+//
+// The app asks the user before the first request for a host. Commands that
+// look like they carry credentials are never returned.
+
+use std::collections::HashSet;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use zedra_rpc::proto::{ShellHistoryResult, SHELL_HISTORY_MAX_COMMANDS, SHELL_HISTORY_MAX_DIRS};
+
+/// Only the newest part of a large history file is read.
+const MAX_READ_BYTES: u64 = 2 * 1024 * 1024;
+/// Commands longer than this are dropped (pasted scripts, heredocs).
+const MAX_COMMAND_LEN: usize = 1024;
+/// Lowercase substrings that mark a command as likely holding a secret.
+const SECRET_MARKERS: &[&str] = &[
+    "password",
+    "passwd",
+    "secret",
+    "token",
+    "api_key",
+    "apikey",
+    "authorization:",
+    "bearer ",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum HistoryFormat {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl HistoryFormat {
+    fn detect(shell: &str) -> Option<Self> {
+        match Path::new(shell).file_name()?.to_str()? {
+            "bash" => Some(Self::Bash),
+            "zsh" => Some(Self::Zsh),
+            "fish" => Some(Self::Fish),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Bash => "bash",
+            Self::Zsh => "zsh",
+            Self::Fish => "fish",
+        }
+    }
+}
+
+/// Where the history file for `shell` lives.
+pub struct HistoryPaths<'a> {
+    pub home: &'a Path,
+    /// `$HISTFILE` from the daemon environment; bash and zsh only.
+    pub histfile: Option<&'a Path>,
+    pub zdotdir: Option<&'a Path>,
+    pub xdg_data_home: Option<&'a Path>,
+}
+
+impl HistoryPaths<'_> {
+    fn history_file(&self, format: HistoryFormat) -> PathBuf {
+        match (format, self.histfile) {
+            (HistoryFormat::Bash | HistoryFormat::Zsh, Some(histfile)) => histfile.to_path_buf(),
+            (HistoryFormat::Bash, None) => self.home.join(".bash_history"),
+            (HistoryFormat::Zsh, None) => self.zdotdir.unwrap_or(self.home).join(".zsh_history"),
+            (HistoryFormat::Fish, _) => self
+                .xdg_data_home
+                .map(Path::to_path_buf)
+                .unwrap_or_else(|| self.home.join(".local/share"))
+                .join("fish/fish_history"),
+        }
+    }
+}
+
+/// Read recent commands and directories from `shell`'s history file.
+pub fn read_shell_history(
+    shell: &str,
+    paths: &HistoryPaths<'_>,
+    limit: usize,
+) -> Result<ShellHistoryResult> {
+    let Some(format) = HistoryFormat::detect(shell) else {
+        bail!("shell history supports bash, zsh, and fish; the host shell is {shell}");
+    };
+    let file = paths.history_file(format);
+    let bytes = read_tail(&file).with_context(|| format!("failed to read {}", file.display()))?;
+    let entries = match format {
+        HistoryFormat::Bash => parse_bash(&bytes),
+        HistoryFormat::Zsh => parse_zsh(&unmetafy(&bytes)),
+        HistoryFormat::Fish => parse_fish(&bytes),
+    };
+
+    let limit = limit.clamp(1, SHELL_HISTORY_MAX_COMMANDS);
+    let mut seen = HashSet::new();
+    let mut commands = Vec::new();
+    let mut directories = Vec::new();
+    let mut seen_dirs = HashSet::new();
+    for entry in entries.into_iter().rev() {
+        let command = entry.trim();
+        if command.is_empty() || command.len() > MAX_COMMAND_LEN || looks_secret(command) {
+            continue;
+        }
+        if directories.len() < SHELL_HISTORY_MAX_DIRS {
+            if let Some(dir) = cd_target(command, paths.home) {
+                if dir.is_dir() && seen_dirs.insert(dir.clone()) {
+                    directories.push(dir.to_string_lossy().into_owned());
+                }
+            }
+        }
+        if commands.len() < limit && seen.insert(command.to_string()) {
+            commands.push(command.to_string());
+        }
+        if commands.len() == limit && directories.len() == SHELL_HISTORY_MAX_DIRS {
+            break;
+        }
+    }
+
+    Ok(ShellHistoryResult {
+        shell: format.name().to_string(),
+        commands,
+        directories,
+        error: None,
+    })
+}
+
+pub fn shell_history_error(error: String) -> ShellHistoryResult {
+    ShellHistoryResult {
+        error: Some(error),
+        ..Default::default()
+    }
+}
+
+fn read_tail(path: &Path) -> std::io::Result<Vec<u8>> {
+    let mut file = std::fs::File::open(path)?;
+    let len = file.metadata()?.len();
+    let start = len.saturating_sub(MAX_READ_BYTES);
+    file.seek(SeekFrom::Start(start))?;
+    let mut bytes = Vec::with_capacity((len - start) as usize);
+    file.read_to_end(&mut bytes)?;
+    if start > 0 {
+        // Drop the partial first line.
+        let first_newline = bytes.iter().position(|b| *b == b'\n').map_or(0, |i| i + 1);
+        bytes.drain(..first_newline);
+    }
+    Ok(bytes)
+}
+
+fn looks_secret(command: &str) -> bool {
+    let lower = command.to_ascii_lowercase();
+    SECRET_MARKERS.iter().any(|marker| lower.contains(marker))
+}
+
+fn parse_bash(bytes: &[u8]) -> Vec<String> {
+    String::from_utf8_lossy(bytes)
+        .lines()
+        .filter(|line| !(line.starts_with('#') && line[1..].chars().all(|c| c.is_ascii_digit())))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Undo zsh's metafication of bytes that collide with its internal tokens.
+fn unmetafy(bytes: &[u8]) -> Vec<u8> {
+    const META: u8 = 0x83;
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut iter = bytes.iter();
+    while let Some(&byte) = iter.next() {
+        if byte == META {
+            if let Some(&next) = iter.next() {
+                out.push(next ^ 0x20);
+            }
+        } else {
+            out.push(byte);
+        }
+    }
+    out
+}
+
+fn parse_zsh(bytes: &[u8]) -> Vec<String> {
+    let text = String::from_utf8_lossy(bytes);
+    let mut entries = Vec::new();
+    let mut current: Option<String> = None;
+    for line in text.lines() {
+        let line = match current.take() {
+            Some(mut pending) => {
+                pending.push('\n');
+                pending.push_str(line);
+                pending
+            }
+            None => line
+                .strip_prefix(": ")
+                .and_then(|rest| rest.split_once(';'))
+                .map_or(line, |(_, command)| command)
+                .to_string(),
+        };
+        match line.strip_suffix('\\') {
+            Some(continued) => current = Some(continued.to_string()),
+            None => entries.push(line),
+        }
+    }
+    entries.extend(current);
+    entries
+}
+
+fn parse_fish(bytes: &[u8]) -> Vec<String> {
+    String::from_utf8_lossy(bytes)
+        .lines()
+        .filter_map(|line| line.strip_prefix("- cmd: "))
+        .map(unescape_fish)
+        .collect()
+}
+
+/// fish stores newlines as `\n` and backslashes as `\\`.
+fn unescape_fish(escaped: &str) -> String {
+    let mut command = String::with_capacity(escaped.len());
+    let mut chars = escaped.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            command.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => command.push('\n'),
+            Some('\\') => command.push('\\'),
+            Some(other) => {
+                command.push('\\');
+                command.push(other);
+            }
+            None => command.push('\\'),
+        }
+    }
+    command
+}
+
+/// Absolute directory a `cd`/`pushd` command moved into, if it names one.
+fn cd_target(command: &str, home: &Path) -> Option<PathBuf> {
+    let mut words = command.split_whitespace();
+    if !matches!(words.next()?, "cd" | "pushd") {
+        return None;
+    }
+    let arg = words.find(|word| *word != "--")?;
+    if words.next().is_some() {
+        return None;
+    }
+    let arg = arg.trim_matches(|c| c == '\'' || c == '"');
+    let path = if arg == "~" {
+        home.to_path_buf()
+    } else if let Some(rest) = arg.strip_prefix("~/") {
+        home.join(rest)
+    } else if arg.starts_with('/') {
+        PathBuf::from(arg)
+    } else {
+        // Relative targets depend on a cwd the history does not record.
+        return None;
+    };
+    // Collecting components drops trailing slashes and `.` segments.
+    Some(path.components().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history_paths(home: &Path) -> HistoryPaths<'_> {
+        HistoryPaths {
+            home,
+            histfile: None,
+            zdotdir: None,
+            xdg_data_home: None,
+        }
+    }
+
+    #[test]
+    fn zsh_history_is_deduped_newest_first_with_directories() {
+        let home = tempfile::tempdir().unwrap();
+        let project = home.path().join("project");
+        std::fs::create_dir(&project).unwrap();
+        let mut history = b": 1700000000:0;ls\n: 1700000001:0;cd ~/project\n".to_vec();
+        history.extend_from_slice(b": 1700000002:0;echo one\\\ntwo\n");
+        history.extend_from_slice(b": 1700000003:0;export GITHUB_TOKEN=abc\n");
+        history.extend_from_slice(b": 1700000004:0;cd /does/not/exist\n: 1700000005:0;ls\n");
+        // "caf\xc3\xa9" with the 0xa9 byte metafied.
+        history.extend_from_slice(b": 1700000006:0;echo caf\xc3\x83\x89\n");
+        std::fs::write(home.path().join(".zsh_history"), history).unwrap();
+
+        let result = read_shell_history("/bin/zsh", &history_paths(home.path()), 100).unwrap();
+        assert_eq!(
+            result.commands,
+            vec![
+                "echo café",
+                "ls",
+                "cd /does/not/exist",
+                "echo one\ntwo",
+                "cd ~/project"
+            ]
+        );
+        assert_eq!(result.directories, vec![project.to_string_lossy()]);
+    }
+
+    #[test]
+    fn bash_and_fish_formats() {
+        let home = tempfile::tempdir().unwrap();
+        std::fs::write(
+            home.path().join(".bash_history"),
+            "#1700000000\ngit status\ncargo test\n",
+        )
+        .unwrap();
+        let result = read_shell_history("bash", &history_paths(home.path()), 1).unwrap();
+        assert_eq!(result.commands, vec!["cargo test"]);
+
+        let fish = home.path().join(".local/share/fish");
+        std::fs::create_dir_all(&fish).unwrap();
+        std::fs::write(
+            fish.join("fish_history"),
+            "- cmd: echo a\\\\nb\n  when: 1700000000\n- cmd: cd /\n  when: 1700000001\n",
+        )
+        .unwrap();
+        let result = read_shell_history("/usr/bin/fish", &history_paths(home.path()), 10).unwrap();
+        assert_eq!(result.commands, vec!["cd /", "echo a\\nb"]);
+
+        assert!(read_shell_history("/bin/nu", &history_paths(home.path()), 10).is_err());
+    }
+}
@@ -0,0 +1,154 @@
+// Shell integration snippets for `ShellIntegration`.
+//
+// Appends a small block to the user's rc file that emits OSC 133 marks:
+//   A  prompt start        (precmd / PROMPT_COMMAND)
+//   B  prompt end          (end of PS1, bash only)
+//   C  command output      (preexec / PS0)
+//   D;<exit> command done  (next precmd)
+// The app records these on the terminal grid for prompt-jump navigation and
+// "copy last command output". The block is fenced by marker comments so a
+// second install is a no-op and users can find and delete it by hand.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use zedra_rpc::proto::ShellIntegrationResult;
+
+const BLOCK_START: &str = "# >>> zedra shell integration >>>";
+const BLOCK_END: &str = "# <<< zedra shell integration <<<";
+
+const BASH_SNIPPET: &str = r#"if [[ $- == *i* && -z "$__zedra_shell_integration" ]]; then
+  __zedra_shell_integration=1
+  __zedra_prompt() { printf '\033]133;D;%s\007\033]133;A\007' "$?"; }
+  PROMPT_COMMAND="__zedra_prompt${PROMPT_COMMAND:+;$PROMPT_COMMAND}"
+  PS1="$PS1\[\033]133;B\007\]"
+  PS0="${PS0}\033]133;C\007"
+fi"#;
+
+const ZSH_SNIPPET: &str = r#"if [[ -o interactive && -z "$__zedra_shell_integration" ]]; then
+  __zedra_shell_integration=1
+  __zedra_precmd() { local ret=$?; print -n "\e]133;D;${ret}\a\e]133;A\a"; }
+  __zedra_preexec() { print -n "\e]133;C\a"; }
+  precmd_functions=(__zedra_precmd $precmd_functions)
+  preexec_functions+=(__zedra_preexec)
+fi"#;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Shell {
+    Bash,
+    Zsh,
+}
+
+impl Shell {
+    fn detect(shell: &str) -> Option<Self> {
+        let name = Path::new(shell).file_name()?.to_str()?;
+        match name {
+            "bash" => Some(Self::Bash),
+            "zsh" => Some(Self::Zsh),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Bash => "bash",
+            Self::Zsh => "zsh",
+        }
+    }
+
+    fn rc_path(self, home: &Path, zdotdir: Option<&Path>) -> PathBuf {
+        match self {
+            Self::Bash => home.join(".bashrc"),
+            Self::Zsh => zdotdir.unwrap_or(home).join(".zshrc"),
+        }
+    }
+
+    fn snippet(self) -> &'static str {
+        match self {
+            Self::Bash => BASH_SNIPPET,
+            Self::Zsh => ZSH_SNIPPET,
+        }
+    }
+}
+
+fn is_installed(rc: &Path) -> bool {
+    std::fs::read_to_string(rc)
+        .map(|content| content.contains(BLOCK_START))
+        .unwrap_or(false)
+}
+
+/// Report (and with `install`, add) shell integration for `shell`'s rc file.
+pub fn run_shell_integration(
+    shell: &str,
+    home: &Path,
+    zdotdir: Option<&Path>,
+    install: bool,
+) -> Result<ShellIntegrationResult> {
+    let Some(kind) = Shell::detect(shell) else {
+        bail!("shell integration supports bash and zsh; the host shell is {shell}");
+    };
+    let rc = kind.rc_path(home, zdotdir);
+    let mut installed = is_installed(&rc);
+    if install && !installed {
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&rc)
+            .with_context(|| format!("failed to open {}", rc.display()))?;
+        write!(file, "\n{BLOCK_START}\n{}\n{BLOCK_END}\n", kind.snippet())
+            .with_context(|| format!("failed to write {}", rc.display()))?;
+        installed = true;
+    }
+    Ok(ShellIntegrationResult {
+        shell: kind.name().to_string(),
+        rc_path: rc.to_string_lossy().into_owned(),
+        installed,
+        error: None,
+    })
+}
+
+pub fn shell_integration_error(error: String) -> ShellIntegrationResult {
+    ShellIntegrationResult {
+        error: Some(error),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn install_appends_one_fenced_block() {
+        let home = tempfile::tempdir().unwrap();
+        let rc = home.path().join(".bashrc");
+        std::fs::write(&rc, "alias ll='ls -l'\n").unwrap();
+
+        let status = run_shell_integration("/bin/bash", home.path(), None, false).unwrap();
+        assert!(!status.installed);
+        assert_eq!(status.shell, "bash");
+
+        let first = run_shell_integration("/usr/local/bin/bash", home.path(), None, true).unwrap();
+        assert!(first.installed);
+        run_shell_integration("/bin/bash", home.path(), None, true).unwrap();
+
+        let content = std::fs::read_to_string(&rc).unwrap();
+        assert!(content.starts_with("alias ll='ls -l'\n"));
+        assert_eq!(content.matches(BLOCK_START).count(), 1);
+        assert!(content.contains("133;C"));
+    }
+
+    #[test]
+    fn zsh_honors_zdotdir_and_other_shells_are_rejected() {
+        let home = tempfile::tempdir().unwrap();
+        let zdotdir = home.path().join("zsh");
+        std::fs::create_dir(&zdotdir).unwrap();
+
+        let result = run_shell_integration("zsh", home.path(), Some(&zdotdir), true).unwrap();
+        assert_eq!(result.rc_path, zdotdir.join(".zshrc").to_string_lossy());
+        assert!(is_installed(&zdotdir.join(".zshrc")));
+
+        assert!(run_shell_integration("/usr/bin/fish", home.path(), None, true).is_err());
+    }
+}
@@ -0,0 +1,383 @@
+// SSH hosts for `zedra import-ssh-config` and the `HostsList` RPC.
+//
+// The import reads `~/.ssh/config` (following `Include`) and keeps its concrete
+// `Host` aliases in `~/.config/zedra/ssh-hosts.json`. Daemons only serve that
+// snapshot, so nothing under `~/.ssh` is read on behalf of a device. The app
+// opens a host by starting a terminal that runs `ssh <alias>`; the host's own
+// ssh then resolves the full config, keys and agent. Aliases are limited to a
+// shell-safe character set because they end up in that command line.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use zedra_rpc::proto::{HostsListResult, SshHost, SSH_HOSTS_MAX};
+
+use crate::identity;
+
+const CONFIG_FILE: &str = "ssh-hosts.json";
+/// ssh's own limit on nested `Include` directives.
+const INCLUDE_MAX_DEPTH: usize = 16;
+
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SshHostsConfig {
+    /// The ssh config the snapshot was imported from.
+    #[serde(default)]
+    pub source: Option<PathBuf>,
+    #[serde(default)]
+    pub hosts: Vec<SshHost>,
+}
+
+pub fn config_path() -> Result<PathBuf> {
+    Ok(identity::host_config_dir()?.join(CONFIG_FILE))
+}
+
+/// `~/.ssh/config`, the file `import-ssh-config` reads by default.
+pub fn default_ssh_config() -> Result<PathBuf> {
+    Ok(home_dir()
+        .context("cannot determine home directory")?
+        .join(".ssh")
+        .join("config"))
+}
+
+fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(PathBuf::from)
+}
+
+fn load_config_at(path: &Path) -> Result<Option<SshHostsConfig>> {
+    match std::fs::read_to_string(path) {
+        Ok(json) => serde_json::from_str(&json)
+            .map(Some)
+            .with_context(|| format!("failed to parse {}", path.display())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e).with_context(|| format!("failed to read {}", path.display())),
+    }
+}
+
+fn save_config_at(path: &Path, config: &SshHostsConfig) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+    std::fs::write(path, serde_json::to_vec_pretty(config)?)
+        .with_context(|| format!("failed to write {}", path.display()))
+}
+
+/// Parse `ssh_config` and replace the stored snapshot with its hosts.
+/// Returns the hosts kept and how many were dropped over `SSH_HOSTS_MAX`.
+pub fn import(ssh_config: &Path) -> Result<(Vec<SshHost>, usize)> {
+    import_at(&config_path()?, ssh_config)
+}
+
+fn import_at(path: &Path, ssh_config: &Path) -> Result<(Vec<SshHost>, usize)> {
+    let mut hosts = parse_config(ssh_config)?;
+    let dropped = hosts.len().saturating_sub(SSH_HOSTS_MAX);
+    hosts.truncate(SSH_HOSTS_MAX);
+    save_config_at(
+        path,
+        &SshHostsConfig {
+            source: Some(ssh_config.to_path_buf()),
+            hosts: hosts.clone(),
+        },
+    )?;
+    Ok((hosts, dropped))
+}
+
+/// The stored snapshot, for `HostsList`.
+pub fn list() -> Result<HostsListResult> {
+    list_at(&config_path()?)
+}
+
+fn list_at(path: &Path) -> Result<HostsListResult> {
+    Ok(match load_config_at(path)? {
+        Some(config) => HostsListResult {
+            hosts: config.hosts,
+            imported: true,
+            error: None,
+        },
+        None => HostsListResult::default(),
+    })
+}
+
+/// Concrete `Host` aliases in `path`, in file order. Wildcard and negated
+/// patterns and `Match` blocks are skipped; for an alias listed in several
+/// blocks the first value of each option wins, as in ssh.
+pub fn parse_config(path: &Path) -> Result<Vec<SshHost>> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    let ssh_dir = path.parent().unwrap_or(Path::new(".")).to_path_buf();
+    let mut hosts = Vec::new();
+    parse_into(&text, &ssh_dir, 0, &mut hosts);
+    Ok(hosts)
+}
+
+fn parse_into(text: &str, ssh_dir: &Path, depth: usize, hosts: &mut Vec<SshHost>) {
+    // Indices into `hosts` the current block's options apply to.
+    let mut active: Vec<usize> = Vec::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (keyword, args) = match line.find(|c: char| c.is_whitespace() || c == '=') {
+            Some(split) => (
+                &line[..split],
+                line[split..]
+                    .trim_start()
+                    .strip_prefix('=')
+                    .unwrap_or(line[split..].trim_start())
+                    .trim(),
+            ),
+            None => (line, ""),
+        };
+        match keyword.to_ascii_lowercase().as_str() {
+            "host" => {
+                active.clear();
+                for pattern in args.split_whitespace().map(unquote) {
+                    if !is_valid_alias(pattern) {
+                        continue;
+                    }
+                    let index = match hosts.iter().position(|host| host.alias == pattern) {
+                        Some(index) => index,
+                        None => {
+                            hosts.push(SshHost {
+                                alias: pattern.to_string(),
+                                ..Default::default()
+                            });
+                            hosts.len() - 1
+                        }
+                    };
+                    active.push(index);
+                }
+            }
+            "match" => active.clear(),
+            "include" => {
+                if depth >= INCLUDE_MAX_DEPTH {
+                    tracing::warn!("ssh config: Include nested too deeply, skipping {args}");
+                    continue;
+                }
+                for pattern in args.split_whitespace().map(unquote) {
+                    for file in include_files(ssh_dir, pattern) {
+                        match std::fs::read_to_string(&file) {
+                            Ok(text) => parse_into(&text, ssh_dir, depth + 1, hosts),
+                            Err(e) => tracing::debug!(
+                                "ssh config: skipping include {}: {e}",
+                                file.display()
+                            ),
+                        }
+                    }
+                }
+            }
+            option => {
+                let value = unquote(args);
+                if value.is_empty() {
+                    continue;
+                }
+                for &index in &active {
+                    let host = &mut hosts[index];
+                    match option {
+                        "hostname" if host.hostname.is_none() => {
+                            host.hostname = (value != host.alias).then(|| value.to_string());
+                        }
+                        "user" if host.user.is_none() => host.user = Some(value.to_string()),
+                        "port" if host.port.is_none() => host.port = value.parse().ok(),
+                        "proxyjump" if host.proxy_jump.is_none() && value != "none" => {
+                            host.proxy_jump = Some(value.to_string());
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Files an `Include` argument names. Relative paths are resolved against
+/// the directory of the top-level config (`~/.ssh`); `*` and `?` are expanded in the final component only.
+fn include_files(ssh_dir: &Path, pattern: &str) -> Vec<PathBuf> {
+    let path = match pattern.strip_prefix("~/") {
+        Some(rest) => match home_dir() {
+            Some(home) => home.join(rest),
+            None => return Vec::new(),
+        },
+        None => ssh_dir.join(pattern),
+    };
+    let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+        return Vec::new();
+    };
+    if !name.contains(['*', '?']) {
+        return vec![path];
+    }
+    let Some(dir) = path.parent() else {
+        return Vec::new();
+    };
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_file()))
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .is_some_and(|file| wildcard_match(name, file))
+        })
+        .map(|entry| entry.path())
+        .collect();
+    files.sort();
+    files
+}
+
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    match pattern.chars().next() {
+        None => name.is_empty(),
+        Some('*') => {
+            let rest = &pattern[1..];
+            name.char_indices()
+                .map(|(i, _)| i)
+                .chain([name.len()])
+                .any(|i| wildcard_match(rest, &name[i..]))
+        }
+        Some(c) => {
+            let mut chars = name.chars();
+            match chars.next() {
+                Some(n) if c == '?' || c == n => {
+                    wildcard_match(&pattern[c.len_utf8()..], chars.as_str())
+                }
+                _ => false,
+            }
+        }
+    }
+}
+
+/// Aliases the app may put on an `ssh` command line.
+fn is_valid_alias(alias: &str) -> bool {
+    !alias.is_empty()
+        && !alias.starts_with('-')
+        && alias
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+}
+
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aliases(hosts: &[SshHost]) -> Vec<&str> {
+        hosts.iter().map(|host| host.alias.as_str()).collect()
+    }
+
+    #[test]
+    fn parse_keeps_concrete_hosts_and_first_values() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("config");
+        std::fs::write(
+            &config,
+            "\
+# fleet
+Host *
+    User everyone
+
+Host web-1 web-2
+    HostName=10.0.0.5
+    User deploy
+    Port 2222
+    ProxyJump bastion
+
+Host web-1
+    User ignored
+    Port 22
+
+Host !prod prod-?? bad;alias \"db.internal\"
+    Hostname db.internal
+    ProxyJump none
+
+Match host web-1
+    User matched
+",
+        )
+        .unwrap();
+
+        let hosts = parse_config(&config).unwrap();
+        assert_eq!(aliases(&hosts), vec!["web-1", "web-2", "db.internal"]);
+        assert_eq!(
+            hosts[0],
+            SshHost {
+                alias: "web-1".into(),
+                hostname: Some("10.0.0.5".into()),
+                user: Some("deploy".into()),
+                port: Some(2222),
+                proxy_jump: Some("bastion".into()),
+            }
+        );
+        assert_eq!(hosts[1].user.as_deref(), Some("deploy"));
+        assert_eq!(hosts[2].hostname, None);
+        assert_eq!(hosts[2].proxy_jump, None);
+    }
+
+    #[test]
+    fn parse_follows_includes_with_wildcards() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("config.d")).unwrap();
+        std::fs::write(dir.path().join("config.d/b.conf"), "Host b\n").unwrap();
+        std::fs::write(dir.path().join("config.d/a.conf"), "Host a\n").unwrap();
+        std::fs::write(dir.path().join("config.d/notes.txt"), "Host skipped\n").unwrap();
+        let config = dir.path().join("config");
+        std::fs::write(
+            &config,
+            "Include config.d/*.conf missing\nHost main\n  User me\n",
+        )
+        .unwrap();
+
+        let hosts = parse_config(&config).unwrap();
+        assert_eq!(aliases(&hosts), vec!["a", "b", "main"]);
+        assert_eq!(hosts[2].user.as_deref(), Some("me"));
+    }
+
+    #[test]
+    fn parse_stops_at_the_include_depth_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("config");
+        std::fs::write(&config, "Include config\nHost loop\n").unwrap();
+
+        let hosts = parse_config(&config).unwrap();
+        assert_eq!(aliases(&hosts), vec!["loop"]);
+    }
+
+    #[test]
+    fn wildcard_matches_file_names() {
+        assert!(wildcard_match("*.conf", "a.conf"));
+        assert!(wildcard_match("host-?", "host-1"));
+        assert!(wildcard_match("*", ""));
+        assert!(!wildcard_match("*.conf", "a.conf.bak"));
+        assert!(!wildcard_match("host-?", "host-12"));
+    }
+
+    #[test]
+    fn list_reports_whether_an_import_ran() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CONFIG_FILE);
+        let result = list_at(&path).unwrap();
+        assert!(!result.imported);
+        assert!(result.hosts.is_empty());
+
+        let ssh_config = dir.path().join("config");
+        std::fs::write(&ssh_config, "Host box\n").unwrap();
+        let (hosts, dropped) = import_at(&path, &ssh_config).unwrap();
+        assert_eq!(aliases(&hosts), vec!["box"]);
+        assert_eq!(dropped, 0);
+
+        let result = list_at(&path).unwrap();
+        assert!(result.imported);
+        assert_eq!(result.hosts, hosts);
+    }
+}
@@ -0,0 +1,298 @@
+// Runnable tasks declared in the workspace's project files, for `TaskDiscover`.
+//
+// Sources, read from the workdir root only:
+//   package.json          `scripts`, run with the package manager whose
+//                         lockfile is present (pnpm, yarn, bun, else npm)
+//   Makefile              explicit targets; pattern rules, special `.TARGETS`,
+//                         and variable assignments are skipped
+//   .cargo/config.toml    `[alias]` entries
+//   justfile              public recipes; `_name` and `[private]` are skipped
+//
+// Makefiles and justfiles are scanned line by line rather than evaluated, so
+// targets generated by `include` or `$(eval ...)` are not listed. A comment on
+// the line above a target (or `## text` after it) becomes the task's detail.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use zedra_rpc::proto::{TaskDiscoverResult, TaskSource, WorkspaceTask, TASK_DISCOVER_MAX};
+
+use crate::agent::utils::shell_quote;
+
+/// Project files larger than this are skipped.
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+
+const MAKEFILES: &[&str] = &["GNUmakefile", "makefile", "Makefile"];
+const JUSTFILES: &[&str] = &["justfile", "Justfile", ".justfile"];
+const CARGO_CONFIGS: &[&str] = &[".cargo/config.toml", ".cargo/config"];
+
+pub fn discover_tasks(workdir: &Path) -> TaskDiscoverResult {
+    let mut tasks = Vec::new();
+    if let Some(text) = read_first(workdir, &["package.json"]) {
+        tasks.extend(package_json_tasks(&text, package_runner(workdir)));
+    }
+    if let Some(text) = read_first(workdir, MAKEFILES) {
+        tasks.extend(makefile_tasks(&text));
+    }
+    if let Some(text) = read_first(workdir, CARGO_CONFIGS) {
+        tasks.extend(cargo_alias_tasks(&text));
+    }
+    if let Some(text) = read_first(workdir, JUSTFILES) {
+        tasks.extend(justfile_tasks(&text));
+    }
+    let truncated = tasks.len() > TASK_DISCOVER_MAX;
+    tasks.truncate(TASK_DISCOVER_MAX);
+    TaskDiscoverResult {
+        tasks,
+        truncated,
+        error: None,
+    }
+}
+
+pub fn task_discover_error(error: String) -> TaskDiscoverResult {
+    TaskDiscoverResult {
+        error: Some(error),
+        ..Default::default()
+    }
+}
+
+/// Contents of the first of `names` that exists under `workdir`.
+fn read_first(workdir: &Path, names: &[&str]) -> Option<String> {
+    names.iter().find_map(|name| {
+        let path = workdir.join(name);
+        let metadata = std::fs::metadata(&path).ok()?;
+        if !metadata.is_file() || metadata.len() > MAX_FILE_BYTES {
+            return None;
+        }
+        match std::fs::read_to_string(&path) {
+            Ok(text) => Some(text),
+            Err(e) => {
+                tracing::warn!("TaskDiscover: failed to read {}: {}", name, e);
+                None
+            }
+        }
+    })
+}
+
+/// `<manager> run`, picked from the lockfile next to `package.json`.
+fn package_runner(workdir: &Path) -> &'static str {
+    let has = |name: &str| workdir.join(name).exists();
+    if has("pnpm-lock.yaml") {
+        "pnpm run"
+    } else if has("yarn.lock") {
+        "yarn run"
+    } else if has("bun.lock") || has("bun.lockb") {
+        "bun run"
+    } else {
+        "npm run"
+    }
+}
+
+fn package_json_tasks(text: &str, runner: &str) -> Vec<WorkspaceTask> {
+    let manifest: serde_json::Value = match serde_json::from_str(text) {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            tracing::warn!("TaskDiscover: invalid package.json: {}", e);
+            return Vec::new();
+        }
+    };
+    let Some(scripts) = manifest.get("scripts").and_then(|s| s.as_object()) else {
+        return Vec::new();
+    };
+    scripts
+        .iter()
+        .filter_map(|(name, script)| {
+            Some(WorkspaceTask {
+                name: name.clone(),
+                source: TaskSource::PackageJson,
+                command: format!("{runner} {}", shell_quote(name)),
+                detail: Some(script.as_str()?.to_string()),
+            })
+        })
+        .collect()
+}
+
+fn makefile_tasks(text: &str) -> Vec<WorkspaceTask> {
+    let mut tasks = Vec::new();
+    let mut seen = HashSet::new();
+    let mut comment = None;
+    for line in text.lines() {
+        if let Some(text) = line.strip_prefix('#') {
+            comment = Some(text.trim_start_matches('#').trim().to_string());
+            continue;
+        }
+        let previous_comment = comment.take();
+        if line.starts_with(['\t', ' ']) {
+            continue;
+        }
+        let Some((targets, rest)) = line.split_once(':') else {
+            continue;
+        };
+        // `A := b`, `A ::= b`, and `A = b: c` are assignments, not rules.
+        if targets.contains('=') || rest.starts_with('=') || rest.starts_with(":=") {
+            continue;
+        }
+        let detail = rest
+            .split_once("##")
+            .map(|(_, text)| text.trim().to_string())
+            .or(previous_comment)
+            .filter(|text| !text.is_empty());
+        for target in targets.split_whitespace() {
+            if target.starts_with('.') || target.contains(['%', '$']) {
+                continue;
+            }
+            if seen.insert(target.to_string()) {
+                tasks.push(WorkspaceTask {
+                    name: target.to_string(),
+                    source: TaskSource::Makefile,
+                    command: format!("make {}", shell_quote(target)),
+                    detail: detail.clone(),
+                });
+            }
+        }
+    }
+    tasks
+}
+
+fn cargo_alias_tasks(text: &str) -> Vec<WorkspaceTask> {
+    let mut tasks = Vec::new();
+    let mut in_alias = false;
+    for line in text.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_alias = line == "[alias]";
+            continue;
+        }
+        if !in_alias || line.starts_with('#') {
+            continue;
+        }
+        let Some((name, value)) = line.split_once('=') else {
+            continue;
+        };
+        let name = name.trim().trim_matches('"');
+        let value = value.trim();
+        // Either `"build --release"` or `["build", "--release"]`.
+        let expansion = value
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .split(',')
+            .map(|part| part.trim().trim_matches(['"', '\'']))
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        if name.is_empty() || expansion.is_empty() {
+            continue;
+        }
+        tasks.push(WorkspaceTask {
+            name: name.to_string(),
+            source: TaskSource::CargoAlias,
+            command: format!("cargo {}", shell_quote(name)),
+            detail: Some(expansion),
+        });
+    }
+    tasks
+}
+
+fn justfile_tasks(text: &str) -> Vec<WorkspaceTask> {
+    const KEYWORDS: &[&str] = &["alias", "export", "import", "mod", "set"];
+    let mut tasks = Vec::new();
+    let mut comment = None;
+    let mut private = false;
+    for line in text.lines() {
+        if let Some(text) = line.strip_prefix('#') {
+            comment = Some(text.trim().to_string());
+            continue;
+        }
+        if let Some(attributes) = line.strip_prefix('[') {
+            private |= attributes.contains("private");
+            continue;
+        }
+        let previous_comment = comment.take();
+        let was_private = std::mem::take(&mut private);
+        if line.is_empty() || line.starts_with([' ', '\t']) || line.contains(":=") {
+            continue;
+        }
+        let Some((head, _)) = line.split_once(':') else {
+            continue;
+        };
+        let mut words = head.split_whitespace();
+        let Some(name) = words.next().map(|name| name.trim_start_matches('@')) else {
+            continue;
+        };
+        if KEYWORDS.contains(&name) || name.starts_with('_') || was_private {
+            continue;
+        }
+        tasks.push(WorkspaceTask {
+            name: name.to_string(),
+            source: TaskSource::Justfile,
+            command: format!("just {}", shell_quote(name)),
+            detail: previous_comment.filter(|text| !text.is_empty()),
+        });
+    }
+    tasks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn discovers_tasks_from_each_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(
+            root.join("package.json"),
+            r#"{"scripts": {"dev": "vite", "test:unit": "vitest run"}}"#,
+        )
+        .unwrap();
+        std::fs::write(root.join("pnpm-lock.yaml"), "").unwrap();
+        std::fs::write(
+            root.join("Makefile"),
+            "CC := clang\n.PHONY: build test\n\n# Build everything\nbuild: deps\n\tcc main.c\n\
+             test: build ## Run the suite\n\t./test\n%.o: %.c\n\tcc -c $<\n",
+        )
+        .unwrap();
+        std::fs::create_dir(root.join(".cargo")).unwrap();
+        std::fs::write(
+            root.join(".cargo/config.toml"),
+            "[build]\njobs = 4\n\n[alias]\nxtask = \"run -p xtask --\"\nlint = [\"clippy\", \"--all-targets\"]\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("justfile"),
+            "set shell := [\"bash\", \"-c\"]\nversion := \"1\"\n\n# Format the tree\nfmt:\n    cargo fmt\n\n\
+             [private]\nhelper:\n    echo\n\n_hidden:\n    echo\n\nrelease tag: fmt\n    echo {{tag}}\n",
+        )
+        .unwrap();
+
+        let result = discover_tasks(root);
+        let summary: Vec<_> = result
+            .tasks
+            .iter()
+            .map(|task| (task.command.as_str(), task.detail.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("pnpm run dev", Some("vite")),
+                ("pnpm run test:unit", Some("vitest run")),
+                ("make build", Some("Build everything")),
+                ("make test", Some("Run the suite")),
+                ("cargo xtask", Some("run -p xtask --")),
+                ("cargo lint", Some("clippy --all-targets")),
+                ("just fmt", Some("Format the tree")),
+                ("just release", None),
+            ]
+        );
+        assert!(!result.truncated);
+    }
+
+    #[test]
+    fn empty_workspace_has_no_tasks() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("package.json"), "not json").unwrap();
+        let result = discover_tasks(dir.path());
+        assert!(result.tasks.is_empty());
+        assert!(result.error.is_none());
+    }
+}
@@ -0,0 +1,237 @@
+// Encoding- and line-ending-aware text reads and writes for `FsReadText` and
+// `FsWriteText`.
+//
+// Reads decode UTF-8 (with or without a BOM), BOM-marked UTF-16, and fall back
+// to Latin-1 for anything else, then turn CRLF into LF so the app only ever
+// sees `\n`. Writes turn the text back into the requested encoding and line
+// ending; when the request leaves either unset, the file's current one is kept,
+// so saving a CRLF or UTF-16 file from the phone does not rewrite it as LF
+// UTF-8.
+
+use std::io;
+use std::path::Path;
+
+use zedra_rpc::proto::{FsReadTextResult, FsWriteTextResult, LineEnding, TextEncoding};
+
+/// Same limit as `FsRead`.
+pub const MAX_TEXT_FILE_BYTES: u64 = 500 * 1024;
+
+const UTF8_BOM: &[u8] = &[0xef, 0xbb, 0xbf];
+const UTF16_LE_BOM: &[u8] = &[0xff, 0xfe];
+const UTF16_BE_BOM: &[u8] = &[0xfe, 0xff];
+
+pub fn read_text(path: &Path) -> io::Result<FsReadTextResult> {
+    if std::fs::metadata(path)?.len() > MAX_TEXT_FILE_BYTES {
+        return Ok(FsReadTextResult {
+            too_large: true,
+            ..Default::default()
+        });
+    }
+    let (text, encoding) = decode(&std::fs::read(path)?)?;
+    let (line_ending, mixed_line_endings) = detect_line_ending(&text);
+    Ok(FsReadTextResult {
+        content: normalize_line_endings(text),
+        too_large: false,
+        encoding,
+        line_ending,
+        mixed_line_endings,
+        error: None,
+    })
+}
+
+pub fn write_text(
+    path: &Path,
+    content: &str,
+    encoding: Option<TextEncoding>,
+    line_ending: Option<LineEnding>,
+) -> io::Result<FsWriteTextResult> {
+    let current = match (encoding, line_ending) {
+        (Some(encoding), Some(line_ending)) => (encoding, line_ending),
+        _ => current_format(path)?,
+    };
+    let encoding = encoding.unwrap_or(current.0);
+    let line_ending = line_ending.unwrap_or(current.1);
+    let bytes = encode(content, encoding, line_ending)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, bytes)?;
+    Ok(FsWriteTextResult {
+        encoding,
+        line_ending,
+        error: None,
+    })
+}
+
+/// Encoding and dominant line ending of the file at `path`; defaults for a
+/// file that does not exist yet.
+fn current_format(path: &Path) -> io::Result<(TextEncoding, LineEnding)> {
+    match std::fs::read(path) {
+        Ok(bytes) => {
+            let (text, encoding) = decode(&bytes)?;
+            Ok((encoding, detect_line_ending(&text).0))
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Default::default()),
+        Err(e) => Err(e),
+    }
+}
+
+fn decode(bytes: &[u8]) -> io::Result<(String, TextEncoding)> {
+    if let Some(rest) = bytes.strip_prefix(UTF8_BOM) {
+        if let Ok(text) = std::str::from_utf8(rest) {
+            return Ok((text.to_string(), TextEncoding::Utf8Bom));
+        }
+    }
+    if let Some(rest) = bytes.strip_prefix(UTF16_LE_BOM) {
+        return Ok((
+            decode_utf16(rest, u16::from_le_bytes)?,
+            TextEncoding::Utf16Le,
+        ));
+    }
+    if let Some(rest) = bytes.strip_prefix(UTF16_BE_BOM) {
+        return Ok((
+            decode_utf16(rest, u16::from_be_bytes)?,
+            TextEncoding::Utf16Be,
+        ));
+    }
+    match std::str::from_utf8(bytes) {
+        Ok(text) => Ok((text.to_string(), TextEncoding::Utf8)),
+        Err(_) => Ok((
+            bytes.iter().map(|&b| char::from(b)).collect(),
+            TextEncoding::Latin1,
+        )),
+    }
+}
+
+fn decode_utf16(bytes: &[u8], unit: fn([u8; 2]) -> u16) -> io::Result<String> {
+    if bytes.len() % 2 != 0 {
+        return Err(invalid_data("UTF-16 file has an odd number of bytes"));
+    }
+    let units = bytes.chunks_exact(2).map(|pair| unit([pair[0], pair[1]]));
+    char::decode_utf16(units)
+        .collect::<Result<String, _>>()
+        .map_err(|_| invalid_data("file is not valid UTF-16"))
+}
+
+fn encode(content: &str, encoding: TextEncoding, line_ending: LineEnding) -> io::Result<Vec<u8>> {
+    let text = match line_ending {
+        LineEnding::Lf => normalize_line_endings(content.to_string()),
+        LineEnding::Crlf => normalize_line_endings(content.to_string()).replace('\n', "\r\n"),
+    };
+    Ok(match encoding {
+        TextEncoding::Utf8 => text.into_bytes(),
+        TextEncoding::Utf8Bom => [UTF8_BOM, text.as_bytes()].concat(),
+        TextEncoding::Utf16Le => UTF16_LE_BOM
+            .iter()
+            .copied()
+            .chain(text.encode_utf16().flat_map(u16::to_le_bytes))
+            .collect(),
+        TextEncoding::Utf16Be => UTF16_BE_BOM
+            .iter()
+            .copied()
+            .chain(text.encode_utf16().flat_map(u16::to_be_bytes))
+            .collect(),
+        TextEncoding::Latin1 => text
+            .chars()
+            .map(|c| u8::try_from(u32::from(c)))
+            .collect::<Result<Vec<u8>, _>>()
+            .map_err(|_| invalid_data("text has characters Latin-1 cannot store"))?,
+    })
+}
+
+/// The more common line ending, and whether both appear.
+fn detect_line_ending(text: &str) -> (LineEnding, bool) {
+    let crlf = text.matches("\r\n").count();
+    let lf = text.matches('\n').count() - crlf;
+    let dominant = if crlf > lf {
+        LineEnding::Crlf
+    } else {
+        LineEnding::Lf
+    };
+    (dominant, crlf > 0 && lf > 0)
+}
+
+fn normalize_line_endings(text: String) -> String {
+    if text.contains("\r\n") {
+        text.replace("\r\n", "\n")
+    } else {
+        text
+    }
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_crlf_and_utf16_as_lf_text_and_writes_them_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let crlf = dir.path().join("crlf.txt");
+        std::fs::write(&crlf, "one\r\ntwo\r\n").unwrap();
+
+        let read = read_text(&crlf).unwrap();
+        assert_eq!(read.content, "one\ntwo\n");
+        assert_eq!(read.encoding, TextEncoding::Utf8);
+        assert_eq!(read.line_ending, LineEnding::Crlf);
+        assert!(!read.mixed_line_endings);
+
+        // Leaving the format unset keeps CRLF.
+        write_text(&crlf, "one\ntwo\nthree\n", None, None).unwrap();
+        assert_eq!(std::fs::read(&crlf).unwrap(), b"one\r\ntwo\r\nthree\r\n");
+
+        let utf16 = dir.path().join("utf16.txt");
+        let bytes: Vec<u8> = UTF16_LE_BOM
+            .iter()
+            .copied()
+            .chain("héllo\r\n".encode_utf16().flat_map(u16::to_le_bytes))
+            .collect();
+        std::fs::write(&utf16, &bytes).unwrap();
+        let read = read_text(&utf16).unwrap();
+        assert_eq!(read.content, "héllo\n");
+        assert_eq!(read.encoding, TextEncoding::Utf16Le);
+        write_text(&utf16, &read.content, None, None).unwrap();
+        assert_eq!(std::fs::read(&utf16).unwrap(), bytes);
+
+        // An explicit format converts.
+        let written = write_text(
+            &utf16,
+            &read.content,
+            Some(TextEncoding::Utf8),
+            Some(LineEnding::Lf),
+        )
+        .unwrap();
+        assert_eq!(written.encoding, TextEncoding::Utf8);
+        assert_eq!(std::fs::read(&utf16).unwrap(), "héllo\n".as_bytes());
+    }
+
+    #[test]
+    fn falls_back_to_latin1_and_reports_mixed_line_endings() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("legacy.txt");
+        std::fs::write(&path, b"caf\xe9\r\nbar\nbaz\n").unwrap();
+
+        let read = read_text(&path).unwrap();
+        assert_eq!(read.content, "café\nbar\nbaz\n");
+        assert_eq!(read.encoding, TextEncoding::Latin1);
+        assert_eq!(read.line_ending, LineEnding::Lf);
+        assert!(read.mixed_line_endings);
+
+        write_text(&path, &read.content, None, None).unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"caf\xe9\nbar\nbaz\n");
+        assert!(write_text(&path, "€", None, None).is_err());
+    }
+
+    #[test]
+    fn new_files_default_to_utf8_lf() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("new/notes.txt");
+        let written = write_text(&path, "a\r\nb\n", None, None).unwrap();
+        assert_eq!(written.encoding, TextEncoding::Utf8);
+        assert_eq!(written.line_ending, LineEnding::Lf);
+        assert_eq!(std::fs::read(&path).unwrap(), b"a\nb\n");
+    }
+}
@@ -8,7 +8,7 @@
 
 #[cfg(not(windows))]
 use crate::rpc_daemon::current_home_dir;
-use crate::rpc_daemon::resolve_path;
+use crate::sandbox::resolve_path;
 use anyhow::{Context, Result};
 use std::fs::OpenOptions;
 use std::path::{Path, PathBuf};
@@ -1,4 +1,5 @@
-// Integration tests for iroh transport and PKI authentication.
+// Integration tests for iroh transport, PKI authentication, and end-to-end
+// client flows.
 //
 // Each test spawns a localhost iroh-relay server, creates endpoints pointed
 // at it, and validates the full stack: endpoint binding → relay negotiation
@@ -937,6 +938,239 @@ async fn test_superseded_static_qr_returns_slot_not_found() {
     );
 }
 
+// ---------------------------------------------------------------------------
+// End-to-end client flows. Each test drives the host the way the app's session
+// client does — pair, open a terminal, edit and commit a file, drop the
+// connection and resume — so a protocol change that breaks the mobile flow
+// fails here first.
+// ---------------------------------------------------------------------------
+
+/// Headless stand-in for the app terminal: keeps PTY output in arrival order
+/// and the highest sequence number seen, which is what a resume attaches with.
+#[derive(Default)]
+struct TerminalSink {
+    bytes: Vec<u8>,
+    last_seq: u64,
+}
+
+impl TerminalSink {
+    fn push(&mut self, output: TermOutput) {
+        // seq 0 is the metadata preamble and is not part of the backlog.
+        self.last_seq = self.last_seq.max(output.seq);
+        self.bytes.extend(output.data);
+    }
+
+    fn text(&self) -> String {
+        String::from_utf8_lossy(&self.bytes).into_owned()
+    }
+}
+
+/// Feed terminal output into `sink` until its text contains `needle`.
+async fn read_terminal_until(
+    output_rx: &mut irpc::channel::mpsc::Receiver<TermOutput>,
+    sink: &mut TerminalSink,
+    needle: &str,
+) {
+    tokio::time::timeout(Duration::from_secs(10), async {
+        while !sink.text().contains(needle) {
+            match output_rx.recv().await {
+                Ok(Some(output)) => sink.push(output),
+                other => panic!("terminal stream ended before {needle:?}: {other:?}"),
+            }
+        }
+    })
+    .await
+    .unwrap_or_else(|_| panic!("timed out waiting for {needle:?}; got {:?}", sink.text()));
+}
+
+/// Open a fresh connection for an already-paired client and send `Connect`
+/// with its session token, like the app does after a network change.
+async fn resume_client(
+    relay_url: iroh::RelayUrl,
+    host_endpoint: &iroh::Endpoint,
+    client_pubkey: [u8; 32],
+    session_id: &str,
+    session_token: [u8; 32],
+) -> anyhow::Result<(irpc::Client<ZedraProto>, ConnectResult)> {
+    let client_endpoint = make_endpoint(relay_url).await?;
+    wait_online(&client_endpoint).await;
+    let conn = client_endpoint
+        .connect(host_endpoint.addr(), proto::ZEDRA_ALPN)
+        .await?;
+    let client = irpc::Client::<ZedraProto>::boxed(irpc_iroh::IrohRemoteConnection::new(conn));
+    let result: ConnectResult = client
+        .rpc(ConnectReq {
+            client_pubkey,
+            session_id: session_id.to_string(),
+            session_token: Some(session_token),
+        })
+        .await?;
+    Ok((client, result))
+}
+
+/// Pair, echo through a real PTY, drop the connection, then resume with the
+/// session token and reattach from the last seen sequence without replaying
+/// output the client already has.
+#[tokio::test(flavor = "multi_thread")]
+async fn test_e2e_terminal_echo_survives_reconnect() {
+    let (_relay, relay_url) = spawn_test_relay().await.unwrap();
+    let (host_ep, registry, identity, _dir) = setup_host(relay_url.clone()).await.unwrap();
+
+    let (client, session_id, client_pubkey, sync, conn) =
+        connect_client_with_connection(relay_url.clone(), &host_ep, &registry, &identity)
+            .await
+            .unwrap();
+
+    let created: TermCreateResult = client
+        .rpc(TermCreateReq {
+            cols: 80,
+            rows: 24,
+            launch_cmd: None,
+        })
+        .await
+        .unwrap();
+    let terminal_id = created.id;
+
+    let (input_tx, mut output_rx) = client
+        .bidi_streaming::<TermAttachReq, TermInput, TermOutput>(
+            TermAttachReq {
+                id: terminal_id.clone(),
+                last_seq: 0,
+            },
+            256,
+            256,
+        )
+        .await
+        .unwrap();
+
+    // Shell arithmetic keeps the echoed command line from matching the result.
+    let mut sink = TerminalSink::default();
+    input_tx
+        .send(TermInput {
+            data: b"echo zedra-$((40+2))\n".to_vec(),
+        })
+        .await
+        .unwrap();
+    read_terminal_until(&mut output_rx, &mut sink, "zedra-42").await;
+    assert!(sink.last_seq > 0);
+
+    drop(input_tx);
+    drop(output_rx);
+    conn.close(0u32.into(), b"test network change");
+    let session = registry.get(&session_id).await.unwrap();
+    tokio::time::timeout(Duration::from_secs(2), async {
+        while session.is_occupied().await {
+            tokio::time::sleep(Duration::from_millis(25)).await;
+        }
+    })
+    .await
+    .expect("host did not observe client close");
+
+    let (client, result) = resume_client(
+        relay_url,
+        &host_ep,
+        client_pubkey,
+        &session_id,
+        sync.session_token,
+    )
+    .await
+    .unwrap();
+    let resumed = match result {
+        ConnectResult::Ok(sync) => sync,
+        other => panic!("expected token resume, got {other:?}"),
+    };
+    assert!(resumed.terminals.iter().any(|t| t.id == terminal_id));
+
+    let (input_tx, mut output_rx) = client
+        .bidi_streaming::<TermAttachReq, TermInput, TermOutput>(
+            TermAttachReq {
+                id: terminal_id.clone(),
+                last_seq: sink.last_seq,
+            },
+            256,
+            256,
+        )
+        .await
+        .unwrap();
+    let mut resumed_sink = TerminalSink {
+        last_seq: sink.last_seq,
+        ..Default::default()
+    };
+    input_tx
+        .send(TermInput {
+            data: b"echo zedra-$((50+1))\n".to_vec(),
+        })
+        .await
+        .unwrap();
+    read_terminal_until(&mut output_rx, &mut resumed_sink, "zedra-51").await;
+    assert!(resumed_sink.last_seq > sink.last_seq);
+    assert!(
+        !resumed_sink.text().contains("zedra-42"),
+        "resume replayed output the client already had: {:?}",
+        resumed_sink.text()
+    );
+}
+
+/// Read, edit, and commit a file through the RPCs the editor and git panel use.
+#[tokio::test(flavor = "multi_thread")]
+async fn test_e2e_file_edit_and_commit() {
+    let (_relay, relay_url) = spawn_test_relay().await.unwrap();
+    let (host_ep, registry, identity, dir) = setup_host(relay_url.clone()).await.unwrap();
+
+    let (client, _session_id, _client_pubkey, _sync) =
+        connect_client(relay_url, &host_ep, &registry, &identity)
+            .await
+            .unwrap();
+
+    let read: FsReadResult = client
+        .rpc(FsReadReq {
+            path: "hello.txt".to_string(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(read.content, "hello world");
+
+    let write: FsWriteResult = client
+        .rpc(FsWriteReq {
+            path: "hello.txt".to_string(),
+            content: "hello zedra\n".to_string(),
+        })
+        .await
+        .unwrap();
+    assert!(write.ok);
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("hello.txt")).unwrap(),
+        "hello zedra\n"
+    );
+
+    // Writes outside the workdir are rejected rather than landing on disk.
+    let escape: FsWriteResult = client
+        .rpc(FsWriteReq {
+            path: "../outside.txt".to_string(),
+            content: "nope".to_string(),
+        })
+        .await
+        .unwrap();
+    assert!(!escape.ok);
+
+    let status: GitStatusResult = client.rpc(GitStatusReq {}).await.unwrap();
+    assert!(status.error.is_none(), "{:?}", status.error);
+    assert!(status.entries.iter().any(|e| e.path == "hello.txt"));
+
+    let commit: GitCommitResult = client
+        .rpc(GitCommitReq {
+            message: "Edit hello".to_string(),
+            paths: vec!["hello.txt".to_string()],
+        })
+        .await
+        .unwrap();
+    assert!(commit.error.is_none(), "{:?}", commit.error);
+    assert_eq!(commit.hash.len(), 40);
+
+    let status: GitStatusResult = client.rpc(GitStatusReq {}).await.unwrap();
+    assert!(!status.entries.iter().any(|e| e.path == "hello.txt"));
+}
+
 // ---------------------------------------------------------------------------
 // Web client: opencode shares one server across cards, fresh session per card.
 // Ignored by default — needs `opencode` on PATH and writes throwaway sessions.
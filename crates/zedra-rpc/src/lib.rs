@@ -1,11 +1,13 @@
 // zedra-rpc: RPC protocol types and pairing for Zedra remote tunnel
 //
 // Provides the typed irpc protocol between mobile client and desktop host,
-// and QR pairing (EndpointAddr encoding).
+// QR pairing (EndpointAddr encoding), and Wake-on-LAN packets.
 
 pub mod pairing;
 pub mod proto;
 pub mod proto_v3;
+pub mod proto_v4;
+pub mod wake;
 
 pub use pairing::{
     ZedraPairingTicket, compute_registration_hmac, decode_endpoint_addr, encode_endpoint_addr,
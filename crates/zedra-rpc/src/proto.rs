@@ -255,13 +255,321 @@ pub enum ZedraProto {
     /// Kept at enum tail because protocol variants are append-only.
     #[rpc(tx = oneshot::Sender<WebClientSetPathResult>)]
     WebClientSetPath(WebClientSetPathReq),
+
+    /// Run an AI prompt with an explicit tool allowlist; denied tool uses are
+    /// returned for on-device approval instead of running unattended.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<AiPromptResultV2>)]
+    AiPromptV2(AiPromptReqV2),
+
+    /// Deprecated: clients read the backend from `SyncSessionResult::ai_provider`.
+    /// Hosts still answer it so the variants after it keep their indexes.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<AiProviderInfo>)]
+    AiProvider(AiProviderReq),
+
+    /// List persisted AI conversations for this workspace, newest first.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<AiConversationListResult>)]
+    AiConversationList(AiConversationListReq),
+
+    /// Fetch one AI conversation with its full message history.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<AiConversationGetResult>)]
+    AiConversationGet(AiConversationGetReq),
+
+    /// Delete one AI conversation.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<AiConversationDeleteResult>)]
+    AiConversationDelete(AiConversationDeleteReq),
+
+    /// List bookmarked and running project directories on this host.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<HostProjectsResult>)]
+    HostProjects(HostProjectsReq),
+
+    /// Per-file added/removed line counts for staged, unstaged, and untracked changes.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<GitDiffStatResult>)]
+    GitDiffStat(GitDiffStatReq),
+
+    /// Stash working tree changes (`git stash push`).
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<GitStashResult>)]
+    GitStash(GitStashReq),
+
+    /// Discard all tracked working tree and index changes (`git reset --hard`).
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<GitDiscardResult>)]
+    GitDiscard(GitDiscardReq),
+
+    /// Create a branch, optionally checking it out.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<GitBranchCreateResult>)]
+    GitBranchCreate(GitBranchCreateReq),
+
+    /// Find-and-replace file contents under a workspace path. `dry_run`
+    /// previews per-file matches; the apply step only rewrites files whose
+    /// content still matches the previewed fingerprint.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<FsReplaceResult>)]
+    FsReplace(FsReplaceReq),
+
+    /// Run the workspace's checker (`cargo check` or `tsc`) and return
+    /// structured diagnostics across all files.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<DiagnosticsCheckResult>)]
+    DiagnosticsCheck(DiagnosticsCheckReq),
+
+    /// Report or install the OSC 133 shell integration snippet in the host
+    /// user's shell rc file.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<ShellIntegrationResult>)]
+    ShellIntegration(ShellIntegrationReq),
+
+    /// Read recent commands and `cd` directories from the host shell's
+    /// history file. Clients ask the user before the first call.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<ShellHistoryResult>)]
+    ShellHistory(ShellHistoryReq),
+
+    /// Open a pull request (GitHub) or merge request (GitLab) for a pushed
+    /// branch, using the forge token in the host's `forge.json`.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<ForgeCreatePrResult>)]
+    ForgeCreatePr(ForgeCreatePrReq),
+
+    /// List the last `count` commits as an interactive-rebase todo list.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<GitRebasePlanResult>)]
+    GitRebasePlan(GitRebasePlanReq),
+
+    /// Rebase onto a plan's `base`, applying an edited todo list without an
+    /// interactive editor. A rebase that stops is aborted.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<GitRebaseExecuteResult>)]
+    GitRebaseExecute(GitRebaseExecuteReq),
+
+    /// Recent commits of all branches with lanes and edges laid out for a
+    /// commit graph.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<GitGraphResult>)]
+    GitGraph(GitGraphReq),
+
+    /// Commits that touched one file, following renames.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<GitFileLogResult>)]
+    GitFileLog(GitFileLogReq),
+
+    /// A file's content at a revision.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<GitShowFileResult>)]
+    GitShowFile(GitShowFileReq),
+
+    /// A commit's diff against its first parent, optionally limited to paths.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<GitShowCommitResult>)]
+    GitShowCommit(GitShowCommitReq),
+
+    /// Line authorship of a file's working-tree content.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<GitBlameResult>)]
+    GitBlame(GitBlameReq),
+
+    /// Push a branch. HTTPS credentials git's own helpers lack are asked of
+    /// the client through `HostEvent::GitCredentialRequested`.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<GitPushResult>)]
+    GitPush(GitPushReq),
+
+    /// Answer a `HostEvent::GitCredentialRequested` prompt.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<GitCredentialReplyResult>)]
+    GitCredentialReply(GitCredentialReplyReq),
+
+    /// Whether the workdir is a repository, and the repositories below it.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<GitDiscoverResult>)]
+    GitDiscover(GitDiscoverReq),
+
+    /// Point this session's git RPCs at a discovered repository, optionally
+    /// running `git init` first.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<GitSelectRepoResult>)]
+    GitSelectRepo(GitSelectRepoReq),
+
+    /// Replace this session's environment overrides, applied to terminals
+    /// and checks it starts from now on.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<SessionEnvSetResult>)]
+    SessionEnvSet(SessionEnvSetReq),
+
+    /// Values the session's environment overrides resolve to on the host.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<SessionEnvGetResult>)]
+    SessionEnvGet(SessionEnvGetReq),
+
+    /// SSH hosts imported on the host with `zedra import-ssh-config`, which
+    /// the app can open as terminals running `ssh <alias>` on the host.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<HostsListResult>)]
+    HostsList(HostsListReq),
+
+    /// This host's Wake-on-LAN addresses, for the app to keep with the
+    /// saved host, and whether `HostPower` is enabled.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<HostWakeInfoResult>)]
+    HostWakeInfo(HostWakeInfoReq),
+
+    /// Send a Wake-on-LAN packet from this host, to wake a machine on its
+    /// network that the device cannot reach.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<HostWakeResult>)]
+    HostWake(HostWakeReq),
+
+    /// Put this host to sleep or reboot it. Refused unless the daemon was
+    /// started with `--allow-power`.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<HostPowerResult>)]
+    HostPower(HostPowerReq),
+
+    /// Turn reduced-bandwidth terminal output on or off for this session.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<SessionDataSaverResult>)]
+    SessionDataSaver(SessionDataSaverReq),
+
+    /// First `max_bytes` of a file, cut at a line boundary, for previews on
+    /// a metered connection.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<FsReadHeadResult>)]
+    FsReadHead(FsReadHeadReq),
+
+    /// Terminal recordings kept by a daemon started with `--record`.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<RecordingsListResult>)]
+    RecordingsList(RecordingsListReq),
+
+    /// One chunk of a recording's asciicast file, for replay in the app.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<RecordingReadResult>)]
+    RecordingRead(RecordingReadReq),
+
+    /// Fetch a remote and report the current branch against its upstream.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<GitFetchResult>)]
+    GitFetch(GitFetchReq),
+
+    /// Pull the current branch's upstream into it.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<GitPullResult>)]
+    GitPull(GitPullReq),
+
+    /// `GitPush` with a force option and a classified failure.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<GitPushResultV2>)]
+    GitPushV2(GitPushReqV2),
+
+    /// `GitDiff` parsed into files, hunks, and lines.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<GitDiffStructuredResult>)]
+    GitDiffStructured(GitDiffStructuredReq),
+
+    /// Stage or unstage one hunk of a tracked file.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<GitStageHunkResult>)]
+    GitStageHunk(GitStageHunkReq),
+
+    /// Delete a local branch.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<GitBranchDeleteResult>)]
+    GitBranchDelete(GitBranchDeleteReq),
+
+    /// Merge a local branch into the current branch.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<GitMergeResult>)]
+    GitMerge(GitMergeReq),
+
+    /// Read a file as text, reporting its encoding and line ending.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<FsReadTextResult>)]
+    FsReadText(FsReadTextReq),
+
+    /// Write text in a chosen encoding and line ending, or the file's current ones.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<FsWriteTextResult>)]
+    FsWriteText(FsWriteTextReq),
+
+    /// Read a whole file as raw bytes, up to `FS_READ_BINARY_MAX`.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<FsReadBinaryResult>)]
+    FsReadBinary(FsReadBinaryReq),
+
+    /// Read raw bytes from an offset, for files read in chunks.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<FsReadRangeResult>)]
+    FsReadRange(FsReadRangeReq),
+
+    /// List runnable tasks declared in the workspace's project files.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<TaskDiscoverResult>)]
+    TaskDiscover(TaskDiscoverReq),
+
+    /// Rename or move a file or directory within the workspace.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<FsRenameResult>)]
+    FsRename(FsRenameReq),
+
+    /// Copy a file, or a directory with `recursive`, within the workspace.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<FsCopyResult>)]
+    FsCopy(FsCopyReq),
+
+    /// Choose which host watchers push `HostEvent::Notification` to this session.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<HostNotifySetResult>)]
+    HostNotifySet(HostNotifySetReq),
+
+    /// Recursive search under a workspace directory by filename glob and/or
+    /// content regex. Hits stream in batches; the last update is `Done`.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = mpsc::Sender<FsGrepUpdate>)]
+    FsGrep(FsGrepReq),
+
+    /// `TermCreateV2` + a starting directory. Separate variant (not a tail
+    /// field) so old hosts can still decode `TermCreateV2`.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<TermCreateResult>)]
+    TermCreateV3(TermCreateReqV3),
+
+    /// `FsGrep` + surrounding lines for each content hit, streamed as
+    /// `FsGrepUpdate::ContextMatches`. Separate variant so old hosts can
+    /// still decode `FsGrep`.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = mpsc::Sender<FsGrepUpdate>)]
+    FsGrepV2(FsGrepReqV2),
+
+    /// Lines around one line of a text file, for expanding a search result
+    /// in place without reading the whole file.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<FsGrepExpandResult>)]
+    FsGrepExpand(FsGrepExpandReq),
+
+    /// Delete a batch of files and directories within the workspace.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<FsRemoveResult>)]
+    FsRemove(FsRemoveReq),
+
+    /// Pack files and directories into a new `.tar.gz` within the workspace.
+    /// Kept at enum tail because protocol variants are append-only.
+    #[rpc(tx = oneshot::Sender<FsArchiveResult>)]
+    FsArchive(FsArchiveReq),
 }
 
 // ---------------------------------------------------------------------------
 // ALPN protocol identifier
 // ---------------------------------------------------------------------------
 
-pub const ZEDRA_ALPN: &[u8] = b"zedra/rpc/4";
+pub const ZEDRA_ALPN: &[u8] = b"zedra/rpc/5";
 
 /// Maximum accepted `FsUpload` payload size, well under irpc's 16 MiB message cap.
 pub const FS_UPLOAD_MAX_BYTES: usize = 8 * 1024 * 1024;
@@ -274,6 +582,30 @@ pub const FS_SEARCH_DEFAULT_LIMIT: u32 = 100;
 pub const FS_SEARCH_MAX_LIMIT: u32 = 200;
 /// Maximum filesystem entries visited for one host-side file search.
 pub const FS_SEARCH_MAX_VISITED_ENTRIES: u32 = 20_000;
+/// Maximum files one `FsReplace` preview or apply touches.
+pub const FS_REPLACE_MAX_FILES: u32 = 200;
+/// Sample lines returned per file in an `FsReplace` preview.
+pub const FS_REPLACE_SAMPLES_PER_FILE: usize = 3;
+/// Maximum diagnostics one `DiagnosticsCheck` returns.
+pub const DIAGNOSTICS_CHECK_MAX: usize = 500;
+/// Maximum commands one `ShellHistory` returns.
+pub const SHELL_HISTORY_MAX_COMMANDS: usize = 500;
+/// Maximum directories one `ShellHistory` returns.
+pub const SHELL_HISTORY_MAX_DIRS: usize = 50;
+/// Maximum tasks one `TaskDiscover` returns.
+pub const TASK_DISCOVER_MAX: usize = 200;
+/// Maximum commits one `GitRebasePlan` lists.
+pub const GIT_REBASE_PLAN_MAX: u32 = 100;
+/// Default commits one `GitGraph` returns (host uses this when `limit == 0`).
+pub const GIT_GRAPH_DEFAULT_LIMIT: u32 = 200;
+/// Maximum commits one `GitGraph` returns.
+pub const GIT_GRAPH_MAX_LIMIT: u32 = 1000;
+/// Default commits one `GitFileLog` returns (host uses this when `limit == 0`).
+pub const GIT_FILE_LOG_DEFAULT_LIMIT: u32 = 100;
+/// Maximum commits one `GitFileLog` returns.
+pub const GIT_FILE_LOG_MAX_LIMIT: u32 = 500;
+/// Largest blob `GitShowFile` returns; bigger files report `too_large`.
+pub const GIT_SHOW_FILE_MAX_BYTES: u64 = 500 * 1024;
 /// Default page size for host-built docs tree requests.
 pub const FS_DOCS_TREE_DEFAULT_LIMIT: u32 = 200;
 /// Maximum page size for host-built docs tree requests.
@@ -284,6 +616,29 @@ pub const FS_DOCS_TREE_MAX_OFFSET: u32 = 5_000;
 pub const FS_DOCS_TREE_MAX_VISITED_ENTRIES: u32 = 10_000;
 /// Maximum byte chunk forwarded through one web tunnel stream frame.
 pub const WEB_TUNNEL_MAX_CHUNK_BYTES: usize = 32 * 1024;
+/// Prefix of the `error` string a host returns when a request names a path
+/// outside the workspace, followed by `": "` and the path as sent.
+pub const PATH_OUTSIDE_WORKSPACE_ERROR: &str = "path outside workspace";
+/// Prefix of the `error` string `FsRename` returns when `from` and `to` are on
+/// different filesystems. Clients can fall back to `FsCopy` and a delete.
+pub const FS_CROSS_DEVICE_ERROR: &str = "cannot move across filesystems";
+/// Default match cap for `FsGrep` (host uses this when `limit == 0`).
+pub const FS_GREP_DEFAULT_LIMIT: u32 = 500;
+/// Maximum match cap for `FsGrep`.
+pub const FS_GREP_MAX_LIMIT: u32 = 2_000;
+/// Maximum content matches `FsGrep` reports from one file.
+pub const FS_GREP_MAX_MATCHES_PER_FILE: u32 = 20;
+/// Maximum filesystem entries visited for one `FsGrep`.
+pub const FS_GREP_MAX_VISITED_ENTRIES: u32 = 200_000;
+/// Maximum `FsGrepReqV2::context` lines on each side of a hit.
+pub const FS_GREP_MAX_CONTEXT_LINES: u32 = 10;
+/// Maximum `FsGrepExpandReq::radius`.
+pub const FS_GREP_MAX_EXPAND_RADIUS: u32 = 200;
+/// Maximum paths in one `FsRemoveReq`; clients split bigger selections into
+/// chunks.
+pub const FS_REMOVE_MAX_PATHS: usize = 64;
+/// Maximum paths in one `FsArchiveReq`.
+pub const FS_ARCHIVE_MAX_PATHS: usize = 1_000;
 
 // ---------------------------------------------------------------------------
 // Serde helper for [u8; 64] (serde supports arrays only up to size 32 by default)
@@ -522,6 +877,8 @@ pub struct SyncSessionResult {
     pub delta_pubkey: [u8; 32],
     /// Ordered by host-owned terminal order. Creation order is the default.
     pub terminals: Vec<TerminalSyncEntry>,
+    /// Backend the host's `AiPromptV2` uses. Appended at `zedra/rpc/5`.
+    pub ai_provider: AiProviderInfo,
 }
 
 /// Live state of a managed agent running in a terminal.
@@ -646,6 +1003,69 @@ pub struct FsSearchResult {
     pub error: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FsReplaceReq {
+    /// Workspace-relative directory to search from; clients usually send `"."`.
+    pub path: String,
+    pub find: String,
+    /// Literal replacement, or a `$1`/`${name}` template when `regex` is set.
+    pub replace: String,
+    pub regex: bool,
+    pub case_sensitive: bool,
+    /// Preview only; nothing is written.
+    pub dry_run: bool,
+    /// Apply step: the previewed files to rewrite. Ignored when `dry_run`.
+    pub files: Vec<FsReplaceTarget>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FsReplaceTarget {
+    /// `FsReplaceFile::rel_path` from the preview.
+    pub rel_path: String,
+    /// `FsReplaceFile::fingerprint` from the preview.
+    pub fingerprint: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct FsReplaceResult {
+    pub files: Vec<FsReplaceFile>,
+    /// The file cap or visited-entry cap was hit; more files may match.
+    pub truncated: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FsReplaceFile {
+    /// Absolute path, used to open the file.
+    pub path: String,
+    /// Search-root-relative path.
+    pub rel_path: String,
+    pub matches: u32,
+    /// Up to `FS_REPLACE_SAMPLES_PER_FILE` matching lines.
+    pub samples: Vec<FsReplaceSample>,
+    /// SHA-256 of the content the preview was computed from.
+    pub fingerprint: String,
+    pub status: FsReplaceStatus,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FsReplaceSample {
+    /// 1-based line number.
+    pub line: u32,
+    pub before: String,
+    pub after: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FsReplaceStatus {
+    /// Dry-run row; nothing was written.
+    Preview,
+    Applied,
+    /// The file changed since the preview (or no longer matches); left untouched.
+    Conflict,
+    Failed(String),
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SetAppStateReq {
     pub in_foreground: bool,
@@ -757,6 +1177,283 @@ pub struct FsWriteResult {
     pub ok: bool,
 }
 
+/// On-disk text encoding. Anything that is not valid UTF-8 and has no
+/// UTF-16 byte order mark reads as Latin-1.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TextEncoding {
+    #[default]
+    Utf8,
+    Utf8Bom,
+    Utf16Le,
+    Utf16Be,
+    Latin1,
+}
+
+impl TextEncoding {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Utf8 => "UTF-8",
+            Self::Utf8Bom => "UTF-8 BOM",
+            Self::Utf16Le => "UTF-16 LE",
+            Self::Utf16Be => "UTF-16 BE",
+            Self::Latin1 => "Latin-1",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    Crlf,
+}
+
+impl LineEnding {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Lf => "LF",
+            Self::Crlf => "CRLF",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FsReadTextReq {
+    pub path: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct FsReadTextResult {
+    /// Decoded text with every line ending turned into `\n`.
+    pub content: String,
+    pub too_large: bool,
+    pub encoding: TextEncoding,
+    /// The more common line ending in the file; LF for a file without any.
+    pub line_ending: LineEnding,
+    /// The file has both LF and CRLF lines; a write normalizes them to `line_ending`.
+    pub mixed_line_endings: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FsWriteTextReq {
+    pub path: String,
+    /// Text with `\n` line endings, as `FsReadText` returns it.
+    pub content: String,
+    /// `None` keeps the file's current encoding (UTF-8 for a new file).
+    pub encoding: Option<TextEncoding>,
+    /// `None` keeps the file's current line ending (LF for a new file).
+    pub line_ending: Option<LineEnding>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct FsWriteTextResult {
+    /// What the file was written as.
+    pub encoding: TextEncoding,
+    pub line_ending: LineEnding,
+    pub error: Option<String>,
+}
+
+/// Largest file `FsReadBinary` returns; bigger files are read with `FsReadRange`.
+pub const FS_READ_BINARY_MAX: u64 = 4 * 1024 * 1024;
+/// Largest `FsReadRangeReq::max_bytes` the host honors.
+pub const FS_READ_RANGE_MAX: u64 = 512 * 1024;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FsReadBinaryReq {
+    pub path: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct FsReadBinaryResult {
+    #[serde(with = "serde_bytes")]
+    pub data: Vec<u8>,
+    /// The file is over `FS_READ_BINARY_MAX`; `data` is empty.
+    pub too_large: bool,
+    pub total_bytes: u64,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FsReadRangeReq {
+    pub path: String,
+    pub offset: u64,
+    /// Clamped to `FS_READ_RANGE_MAX`.
+    pub max_bytes: u64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct FsReadRangeResult {
+    #[serde(with = "serde_bytes")]
+    pub data: Vec<u8>,
+    /// Size of the whole file when this chunk was read.
+    pub total_bytes: u64,
+    /// `data` reaches the end of the file.
+    pub eof: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FsRenameReq {
+    pub from: String,
+    /// Must not exist yet; missing parent directories are created.
+    pub to: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct FsRenameResult {
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FsCopyReq {
+    pub from: String,
+    /// Must not exist yet; missing parent directories are created.
+    pub to: String,
+    /// Required to copy a directory; its contents are copied too.
+    pub recursive: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct FsCopyResult {
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FsRemoveReq {
+    /// At most `FS_REMOVE_MAX_PATHS`. Directories go with their contents.
+    pub paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FsRemoveResult {
+    /// Paths that could not be removed; the rest of the batch still is.
+    pub failed: Vec<FsPathError>,
+    /// The whole request was rejected and nothing was removed.
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FsPathError {
+    /// The path as sent.
+    pub path: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FsArchiveReq {
+    /// Files and directories to pack, each stored under its own name.
+    pub paths: Vec<String>,
+    /// The archive to create; must not exist. Written as gzip-compressed tar
+    /// whatever the extension.
+    pub to: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FsArchiveResult {
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FsGrepReq {
+    /// Workspace-relative directory to search from; clients usually send `"."`.
+    pub path: String,
+    /// Gitignore-style filename glob, e.g. `*.rs` or `src/**/mod.rs`.
+    pub glob: Option<String>,
+    /// Regex matched against each line of text files. At least one of `glob`
+    /// and `pattern` is required.
+    pub pattern: Option<String>,
+    /// Applies to both `glob` and `pattern`.
+    pub case_sensitive: bool,
+    /// Match cap; 0 = `FS_GREP_DEFAULT_LIMIT`, clamped to `FS_GREP_MAX_LIMIT`.
+    pub limit: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FsGrepUpdate {
+    /// Next hits in walk order. Files are not sorted across batches.
+    Matches(Vec<FsGrepMatch>),
+    /// Always the last update. `truncated` is set when a cap stopped the walk
+    /// or cut a file's matches short.
+    Done {
+        truncated: bool,
+        error: Option<String>,
+    },
+    /// `FsGrepV2` hits with their surrounding lines; sent in place of
+    /// `Matches`. Appended so `FsGrep` clients never see it.
+    ContextMatches(Vec<FsGrepContextMatch>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FsGrepMatch {
+    /// Absolute path, used to open the file.
+    pub path: String,
+    /// Search-root-relative path.
+    pub rel_path: String,
+    /// 1-based line number; `None` for a glob-only hit.
+    pub line: Option<u32>,
+    /// The matching line, cut at 200 characters; `None` for a glob-only hit.
+    pub snippet: Option<String>,
+}
+
+/// `FsGrepReq` + `context`. Separate type so old hosts can still decode
+/// `FsGrepReq`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FsGrepReqV2 {
+    pub path: String,
+    pub glob: Option<String>,
+    pub pattern: Option<String>,
+    pub case_sensitive: bool,
+    pub limit: u32,
+    /// Lines to include before and after each content hit, clamped to
+    /// `FS_GREP_MAX_CONTEXT_LINES`. Ignored for glob-only hits.
+    pub context: u32,
+}
+
+impl FsGrepReqV2 {
+    /// The `FsGrep` request this extends, for hosts without `FsGrepV2`.
+    pub fn without_context(&self) -> FsGrepReq {
+        FsGrepReq {
+            path: self.path.clone(),
+            glob: self.glob.clone(),
+            pattern: self.pattern.clone(),
+            case_sensitive: self.case_sensitive,
+            limit: self.limit,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FsGrepContextMatch {
+    pub hit: FsGrepMatch,
+    /// Lines just above the hit, oldest first, each cut at 200 characters.
+    pub before: Vec<String>,
+    /// Lines just below the hit, each cut at 200 characters.
+    pub after: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FsGrepExpandReq {
+    /// Workspace-relative or absolute file path, as in `FsGrepMatch::path`.
+    pub path: String,
+    /// 1-based line to center on.
+    pub line: u32,
+    /// Lines on each side of `line`, clamped to `FS_GREP_MAX_EXPAND_RADIUS`.
+    pub radius: u32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FsGrepExpandResult {
+    /// 1-based number of `lines[0]`.
+    pub start_line: u32,
+    /// The requested window, cut to the file's bounds; each line is cut at
+    /// 200 characters.
+    pub lines: Vec<String>,
+    /// Lines in the whole file, so the client knows when it can expand further.
+    pub total_lines: u32,
+    pub error: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct FsUploadReq {
     #[serde(with = "serde_bytes")]
@@ -939,13 +1636,76 @@ pub enum HostEvent {
     /// `zedra open <target>`). The client resolves loopback targets through the
     /// web tunnel and tracks them per workspace. Appended at `zedra/rpc/4`.
     WebViewRequested { url: String },
+    /// A `GitPush`, `GitPushV2`, `GitFetch`, or `GitPull` needs HTTPS
+    /// credentials for `url` (`protocol://host[/path]`,
+    /// no secrets). Answer with `GitCredentialReply`; `username` is prefilled
+    /// when the remote URL names one. Appended at `zedra/rpc/5`.
+    GitCredentialRequested {
+        id: String,
+        url: String,
+        username: Option<String>,
+    },
+    /// A host watcher enabled with `HostNotifySet` fired. Only sent to
+    /// sessions that sent a `HostNotifySet`, so older clients never see it.
+    /// Appended at `zedra/rpc/5`.
+    Notification(HostNotification),
 }
 
-// ---------------------------------------------------------------------------
-// Host info subscription types
-// ---------------------------------------------------------------------------
+/// Most paths one `HostNotifySet` may watch.
+pub const HOST_NOTIFY_MAX_WATCH_PATHS: usize = 32;
+
+/// Which host watchers notify a session. Everything is off by default.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HostNotifyConfig {
+    /// Workspace-relative files or directories. A file notifies when its size
+    /// or modification time changes; a directory when one of its direct
+    /// entries is added, removed, or changed.
+    pub watch_paths: Vec<String>,
+    /// Notify when a command that ran at least this many seconds exits in any
+    /// of the session's terminals; 0 turns it off. Needs shell integration.
+    pub long_command_secs: u32,
+    /// Notify when free space on the workdir's disk drops below this percent;
+    /// 0 turns it off.
+    pub disk_free_percent: u8,
+}
 
-/// Host resource subscription request — no fields; host owns the sampling interval.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HostNotifySetReq {
+    /// Replaces the session's previous config.
+    pub config: HostNotifyConfig,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct HostNotifySetResult {
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HostNotification {
+    /// A path from `HostNotifyConfig::watch_paths` changed, appeared, or was removed.
+    FileChanged { path: String },
+    /// A command that ran at least `long_command_secs` exited.
+    CommandFinished {
+        terminal_id: String,
+        /// The command line, when the shell reported it.
+        command: Option<String>,
+        exit_code: i32,
+        duration_secs: u64,
+    },
+    /// Free space on the disk holding the workdir dropped below
+    /// `disk_free_percent`. Sent once per drop.
+    DiskLow {
+        mount_point: String,
+        available_bytes: u64,
+        total_bytes: u64,
+    },
+}
+
+// ---------------------------------------------------------------------------
+// Host info subscription types
+// ---------------------------------------------------------------------------
+
+/// Host resource subscription request — no fields; host owns the sampling interval.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SubscribeHostInfoReq {}
 
@@ -1007,6 +1767,18 @@ pub struct TermCreateReqV2 {
     pub color_scheme: Option<TerminalColorScheme>,
 }
 
+/// `TermCreateReqV2` + `cwd`, an absolute host directory for the shell to
+/// start in. The host falls back to the session workdir when it is unset or
+/// not an existing directory.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TermCreateReqV3 {
+    pub cols: u16,
+    pub rows: u16,
+    pub launch_cmd: Option<String>,
+    pub color_scheme: Option<TerminalColorScheme>,
+    pub cwd: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TermCreateResult {
     /// Opaque host-generated UUID string.
@@ -1087,138 +1859,1016 @@ pub struct TermReorderReq {
     pub ordered_ids: Vec<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct TermReorderResult {
-    pub ok: bool,
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TermReorderResult {
+    pub ok: bool,
+    pub error: Option<String>,
+}
+
+/// Most overrides a session accepts in one `SessionEnvSet`.
+pub const SESSION_ENV_MAX_VARS: usize = 64;
+
+/// One environment override for terminals and checks a session starts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionEnvVar {
+    pub name: String,
+    /// Literal value, path entry, or keychain reference depending on `kind`.
+    pub value: String,
+    pub kind: SessionEnvKind,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SessionEnvKind {
+    /// Set `name` to `value`.
+    #[default]
+    Set,
+    /// Put `value` in front of the host's existing `name`, joined with the
+    /// platform path separator (e.g. a `PATH` addition).
+    Prepend,
+    /// Read `name` from the host keychain item `value` (a service name) when
+    /// the process starts. The secret never crosses the wire.
+    Keychain,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SessionEnvSetReq {
+    /// Complete override list, in the order applied; at most `SESSION_ENV_MAX_VARS`.
+    pub vars: Vec<SessionEnvVar>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SessionEnvSetResult {
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SessionEnvGetReq {}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SessionEnvGetResult {
+    /// One entry per override, in the session's order.
+    pub values: Vec<SessionEnvValue>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SessionEnvValue {
+    pub name: String,
+    /// Effective value; always `None` for keychain overrides.
+    pub value: Option<String>,
+    /// Why the override would be skipped, e.g. a missing keychain item.
+    pub error: Option<String>,
+}
+
+// ---------------------------------------------------------------------------
+// Git types
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitStatusReq {}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitStatusResult {
+    pub branch: String,
+    pub entries: Vec<GitStatusEntry>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitStatusEntry {
+    pub path: String,
+    /// Index status shown in the "staged" section. `None` means no staged change.
+    pub staged_status: Option<String>,
+    /// Working tree status shown in the "changes" / "untracked" sections.
+    /// `None` means no unstaged change.
+    pub unstaged_status: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitDiffReq {
+    pub path: Option<String>,
+    pub staged: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitDiffResult {
+    pub diff: String,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitDiffStatReq {}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct GitDiffStatResult {
+    pub staged: Vec<GitDiffStatEntry>,
+    pub unstaged: Vec<GitDiffStatEntry>,
+    pub untracked: Vec<GitDiffStatEntry>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct GitDiffStatEntry {
+    pub path: String,
+    pub additions: u32,
+    pub deletions: u32,
+    /// Binary files carry no line counts.
+    pub binary: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitLogReq {
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitLogResult {
+    pub entries: Vec<GitLogEntry>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitLogEntry {
+    pub id: String,
+    pub message: String,
+    pub author: String,
+    pub timestamp: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitCommitReq {
+    pub message: String,
+    pub paths: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitCommitResult {
+    pub hash: String,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitStageReq {
+    pub paths: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitStageResult {
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitUnstageReq {
+    pub paths: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitUnstageResult {
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitBranchesReq {}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitBranchesResult {
+    pub branches: Vec<GitBranchEntry>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitBranchEntry {
+    pub name: String,
+    pub is_head: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitCheckoutReq {
+    pub branch: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitCheckoutResult {
+    pub ok: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitStashReq {
+    pub message: Option<String>,
+    /// Also stash untracked files (`--include-untracked`).
+    pub include_untracked: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct GitStashResult {
+    /// `false` when there was nothing to stash.
+    pub stashed: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitDiscardReq {
+    /// Also delete untracked files (`git clean -fd`). Ignored files are kept.
+    pub include_untracked: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct GitDiscardResult {
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitBranchCreateReq {
+    pub name: String,
+    /// Start point; `None` branches from `HEAD`.
+    pub from: Option<String>,
+    pub checkout: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct GitBranchCreateResult {
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitRebasePlanReq {
+    /// Commits back from `HEAD`, capped at `GIT_REBASE_PLAN_MAX` and at the
+    /// commits above the root.
+    pub count: u32,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct GitRebaseCommit {
+    pub hash: String,
+    pub subject: String,
+    pub author: String,
+    pub timestamp: i64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct GitRebasePlanResult {
+    /// Full hash of the commit the plan rebases onto (`HEAD~count`).
+    pub base: String,
+    /// Oldest first, in todo order.
+    pub commits: Vec<GitRebaseCommit>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum GitRebaseAction {
+    Pick,
+    Reword,
+    Squash,
+    Fixup,
+    Drop,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GitRebaseStep {
+    pub action: GitRebaseAction,
+    pub hash: String,
+    /// New message for the commit after this step. Required for `Reword`;
+    /// optional for `Squash`, which otherwise joins both messages.
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitRebaseExecuteReq {
+    /// `GitRebasePlanResult.base` of the plan being applied.
+    pub base: String,
+    /// Every commit of the plan exactly once, in the new order.
+    pub steps: Vec<GitRebaseStep>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct GitRebaseExecuteResult {
+    /// Full hash of `HEAD` after the rebase.
+    pub head: String,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitGraphReq {
+    /// Commits to return; `0` means `GIT_GRAPH_DEFAULT_LIMIT`. Capped at
+    /// `GIT_GRAPH_MAX_LIMIT`.
+    pub limit: u32,
+}
+
+/// One line of the graph between a row's node level and the next row's.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct GitGraphEdge {
+    pub from_lane: u32,
+    pub to_lane: u32,
+    /// Branch color index; stable along a line of first parents.
+    pub color: u32,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct GitGraphCommit {
+    pub hash: String,
+    pub parents: Vec<String>,
+    pub subject: String,
+    pub author: String,
+    pub timestamp: i64,
+    /// Decorations as git prints them: `HEAD -> main`, `origin/main`,
+    /// `tag: v1.0`.
+    pub refs: Vec<String>,
+    /// Column of this commit's node.
+    pub lane: u32,
+    pub color: u32,
+    /// Lines from this row down to the next one. Lines toward parents past
+    /// the last returned commit go straight down.
+    pub edges: Vec<GitGraphEdge>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct GitGraphResult {
+    /// Newest first, in topological order.
+    pub commits: Vec<GitGraphCommit>,
+    /// Widest row, in lanes.
+    pub lane_count: u32,
+    /// More commits exist past `limit`.
+    pub truncated: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitFileLogReq {
+    /// Workdir-relative or absolute path under the workdir.
+    pub path: String,
+    /// Commits to return; `0` means `GIT_FILE_LOG_DEFAULT_LIMIT`. Capped at
+    /// `GIT_FILE_LOG_MAX_LIMIT`.
+    pub limit: u32,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct GitFileLogEntry {
+    pub id: String,
+    pub message: String,
+    pub author: String,
+    pub timestamp: i64,
+    /// Repository-root-relative path of the file in this commit.
+    pub path: String,
+    /// Path before this commit when the commit renamed the file.
+    pub old_path: Option<String>,
+    /// `A`, `M`, `D`, or `R` for how this commit changed the file.
+    pub status: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct GitFileLogResult {
+    /// Newest first.
+    pub entries: Vec<GitFileLogEntry>,
+    /// More commits exist past `limit`.
+    pub truncated: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitShowFileReq {
+    /// Commit hash.
+    pub rev: String,
+    /// Repository-root-relative path, as in `GitFileLogEntry.path`.
+    pub path: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct GitShowFileResult {
+    pub content: String,
+    /// The blob holds NUL bytes; `content` is empty.
+    pub binary: bool,
+    /// The blob exceeds `GIT_SHOW_FILE_MAX_BYTES`; `content` is empty.
+    pub too_large: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitShowCommitReq {
+    /// Commit hash.
+    pub rev: String,
+    /// Repository-root-relative paths to limit the diff to; empty for all.
+    pub paths: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct GitShowCommitResult {
+    /// Unified diff against the first parent (everything added for a root
+    /// commit).
+    pub diff: String,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitBlameReq {
+    /// Absolute path inside the workdir, or workdir-relative.
+    pub path: String,
+}
+
+/// Consecutive lines last changed by one commit.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct GitBlameHunk {
+    /// Commit hash; all zeros for lines not committed yet.
+    pub id: String,
+    pub author: String,
+    pub timestamp: i64,
+    pub summary: String,
+    /// Repository-root-relative path of the file in that commit.
+    pub path: String,
+    /// 1-based first line in the working-tree file.
+    pub start_line: u32,
+    pub line_count: u32,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct GitBlameResult {
+    /// In line order, covering every line of the file.
+    pub hunks: Vec<GitBlameHunk>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitPushReq {
+    /// `None` uses the branch's upstream remote, else `origin`.
+    pub remote: Option<String>,
+    /// `None` uses the current branch.
+    pub branch: Option<String>,
+    /// Record the pushed branch as upstream (`--set-upstream`).
+    pub set_upstream: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct GitPushResult {
+    pub remote: String,
+    pub branch: String,
+    pub error: Option<String>,
+}
+
+/// Seconds the host waits for a `GitCredentialReply` before failing the push.
+pub const GIT_CREDENTIAL_TIMEOUT_SECS: u64 = 120;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GitCredential {
+    pub username: String,
+    /// Password or access token.
+    pub password: String,
+    /// Store in the host keychain once git accepts it.
+    pub remember: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitCredentialReplyReq {
+    /// `id` of the `GitCredentialRequested` event.
+    pub id: String,
+    /// `None` cancels the prompt and fails the push.
+    pub credential: Option<GitCredential>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct GitCredentialReplyResult {
+    /// Set when the prompt already timed out or was answered.
+    pub error: Option<String>,
+}
+
+/// Why a fetch, pull, or push failed, when git's output says.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GitRemoteFailure {
+    /// The remote rejected the credentials, or none were entered.
+    Auth,
+    /// The remote has commits the branch lacks (push), or the branches
+    /// diverged and a fast-forward was asked for (pull).
+    NonFastForward,
+    /// A pull stopped on conflicts and was aborted.
+    Conflict,
+    /// Uncommitted changes would be overwritten.
+    LocalChanges,
+    /// The remote could not be reached.
+    Network,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitFetchReq {
+    /// `None` uses the current branch's upstream remote, else `origin`.
+    pub remote: Option<String>,
+    /// Delete remote-tracking refs the remote no longer has (`--prune`).
+    pub prune: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct GitFetchResult {
+    pub remote: String,
+    /// Commits on the current branch missing from its upstream.
+    pub ahead: u32,
+    /// Commits on the upstream missing from the current branch.
+    pub behind: u32,
+    pub error: Option<String>,
+    pub failure: Option<GitRemoteFailure>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GitPullMode {
+    /// `--ff-only`: fails with `NonFastForward` when the branches diverged.
+    FastForwardOnly,
+    Merge,
+    Rebase,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitPullReq {
+    /// `None` uses the branch's upstream remote, else `origin`.
+    pub remote: Option<String>,
+    /// Remote branch to pull. `None` uses the upstream branch, else the
+    /// current branch's name.
+    pub branch: Option<String>,
+    pub mode: GitPullMode,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct GitPullResult {
+    pub remote: String,
+    pub branch: String,
+    /// `HEAD` after the pull.
+    pub head: String,
+    /// False when the branch was already up to date.
+    pub updated: bool,
+    pub ahead: u32,
+    pub behind: u32,
+    pub error: Option<String>,
+    pub failure: Option<GitRemoteFailure>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitPushReqV2 {
+    /// `None` uses the branch's upstream remote, else `origin`.
+    pub remote: Option<String>,
+    /// `None` uses the current branch.
+    pub branch: Option<String>,
+    /// Record the pushed branch as upstream (`--set-upstream`).
+    pub set_upstream: bool,
+    /// Overwrite the remote branch (`--force-with-lease`).
+    pub force: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct GitPushResultV2 {
+    pub remote: String,
+    pub branch: String,
+    pub error: Option<String>,
+    pub failure: Option<GitRemoteFailure>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitDiffStructuredReq {
+    pub path: Option<String>,
+    pub staged: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct GitDiffStructuredResult {
+    pub files: Vec<GitDiffFile>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct GitDiffFile {
+    /// Empty for added files.
+    pub old_path: String,
+    /// Empty for deleted files.
+    pub new_path: String,
+    pub binary: bool,
+    pub hunks: Vec<GitDiffHunk>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct GitDiffHunk {
+    /// The `@@ -a,b +c,d @@` line; `GitStageHunk` names the hunk by it.
+    pub header: String,
+    pub old_start: u32,
+    pub old_count: u32,
+    pub new_start: u32,
+    pub new_count: u32,
+    pub lines: Vec<GitDiffLine>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GitDiffLineKind {
+    Context,
+    Added,
+    Removed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct GitDiffLine {
+    pub kind: GitDiffLineKind,
+    pub old_line: Option<u32>,
+    pub new_line: Option<u32>,
+    /// Without the leading `+`, `-`, or space.
+    pub content: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitStageHunkReq {
+    pub path: String,
+    /// `GitDiffHunk::header` from the diff the client is showing.
+    pub header: String,
+    /// Remove the hunk from the index instead of adding it; the header then
+    /// comes from the staged diff.
+    pub unstage: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct GitStageHunkResult {
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitBranchDeleteReq {
+    pub name: String,
+    /// Delete even when the branch is not merged (`-D`).
+    pub force: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct GitBranchDeleteResult {
+    /// Set with `error` when git refused because the branch is not fully
+    /// merged; retrying with `force` deletes it.
+    pub unmerged: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitMergeReq {
+    pub branch: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct GitMergeResult {
+    /// `HEAD` after the merge.
+    pub head: String,
+    /// False when the branch was already merged.
+    pub updated: bool,
+    pub fast_forward: bool,
+    /// Paths that conflicted. Set with `error`; the merge was aborted.
+    pub conflicts: Vec<String>,
+    pub error: Option<String>,
+}
+
+/// Directory levels below the workdir `GitDiscover` searches.
+pub const GIT_DISCOVER_MAX_DEPTH: usize = 4;
+/// Repositories `GitDiscover` returns before setting `truncated`.
+pub const GIT_DISCOVER_MAX_REPOS: usize = 50;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitDiscoverReq {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct GitDiscoveredRepo {
+    /// Workdir-relative path; empty for the workdir itself.
+    pub path: String,
+    /// Checked-out branch; empty when HEAD is detached or unborn.
+    pub branch: String,
+    /// A submodule checkout of an enclosing repository.
+    pub submodule: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct GitDiscoverResult {
+    /// The workdir is inside a repository.
+    pub workdir_is_repo: bool,
+    /// Sorted by path. Nested repositories are not descended into.
+    pub repositories: Vec<GitDiscoveredRepo>,
+    /// `path` of the repository this session's git RPCs use, when one was
+    /// picked with `GitSelectRepo`.
+    pub selected: Option<String>,
+    /// More repositories exist past `GIT_DISCOVER_MAX_REPOS`.
+    pub truncated: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitSelectRepoReq {
+    /// Workdir-relative path; empty for the workdir itself.
+    pub path: String,
+    /// Run `git init` in `path` first.
+    pub init: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct GitSelectRepoResult {
+    /// The selected path, normalized.
+    pub path: String,
+    pub error: Option<String>,
+}
+
+// ---------------------------------------------------------------------------
+// AI types
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AiPromptReq {
+    pub prompt: String,
+    pub context: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AiPromptResult {
+    pub text: String,
+    pub done: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AiPromptReqV2 {
+    pub prompt: String,
+    pub context: Option<String>,
+    /// Tools the host may run without asking, in Claude Code `--allowedTools`
+    /// syntax (e.g. `Read`, `Edit`, `Bash(git diff:*)`). Everything else is denied.
+    pub allowed_tools: Vec<String>,
+    /// Continue a previous prompt's conversation (`AiPromptResultV2.session_id`).
+    pub resume_session_id: Option<String>,
+    /// Host conversation to append to; `None` starts a new one.
+    pub conversation_id: Option<String>,
+}
+
+/// A tool use the host denied because it was not in `allowed_tools`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AiPermissionRequest {
+    pub tool_name: String,
+    /// Tool input as compact JSON (e.g. `{"command":"cargo test"}`).
+    pub tool_input: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct AiPromptResultV2 {
+    pub text: String,
+    pub session_id: Option<String>,
+    /// Denied tool uses awaiting a device decision. Approve by resending with the
+    /// tool allowlisted and `resume_session_id` set; deny by not resending.
+    pub permission_requests: Vec<AiPermissionRequest>,
+    pub error: Option<String>,
+    /// Host conversation this prompt was stored in.
+    pub conversation_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AiProviderReq {}
+
+/// The host's AI backend, reported in `SyncSessionResult`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct AiProviderInfo {
+    /// `claude-cli`, `openai`, or `ollama`.
+    pub kind: String,
+    pub model: Option<String>,
+    /// Whether the backend can run tools (and so return `permission_requests`).
+    pub supports_tools: bool,
+    /// Set when the host's AI config file is malformed.
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AiConversationSummary {
+    pub id: String,
+    /// Generated from the first prompt line.
+    pub title: String,
+    /// Unix seconds.
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub message_count: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AiConversationMessage {
+    /// `user` or `assistant`.
+    pub role: String,
+    pub text: String,
+    pub timestamp: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AiConversation {
+    pub id: String,
+    pub title: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub messages: Vec<AiConversationMessage>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AiConversationListReq {}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct AiConversationListResult {
+    pub conversations: Vec<AiConversationSummary>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AiConversationGetReq {
+    pub id: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct AiConversationGetResult {
+    pub conversation: Option<AiConversation>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AiConversationDeleteReq {
+    pub id: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct AiConversationDeleteResult {
     pub error: Option<String>,
 }
 
 // ---------------------------------------------------------------------------
-// Git types
+// Host project types
 // ---------------------------------------------------------------------------
 
 #[derive(Debug, Serialize, Deserialize)]
-pub struct GitStatusReq {}
+pub struct HostProjectsReq {}
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct GitStatusResult {
-    pub branch: String,
-    pub entries: Vec<GitStatusEntry>,
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct HostProjectsResult {
+    pub projects: Vec<HostProject>,
     pub error: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct GitStatusEntry {
+/// A project directory on the host. Each project is served by its own daemon;
+/// opening a non-current one means pairing with that daemon.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct HostProject {
+    /// Directory name, for display.
+    pub name: String,
     pub path: String,
-    /// Index status shown in the "staged" section. `None` means no staged change.
-    pub staged_status: Option<String>,
-    /// Working tree status shown in the "changes" / "untracked" sections.
-    /// `None` means no unstaged change.
-    pub unstaged_status: Option<String>,
+    /// Listed in the host's `projects.json` bookmarks.
+    pub bookmarked: bool,
+    /// A daemon is currently serving this directory.
+    pub running: bool,
+    /// Served by the daemon answering this request.
+    pub is_current: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct GitDiffReq {
-    pub path: Option<String>,
-    pub staged: bool,
-}
+/// Most hosts `zedra import-ssh-config` keeps from one import.
+pub const SSH_HOSTS_MAX: usize = 256;
 
 #[derive(Debug, Serialize, Deserialize)]
-pub struct GitDiffResult {
-    pub diff: String,
+pub struct HostsListReq {}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct HostsListResult {
+    /// Imported hosts in `~/.ssh/config` order.
+    pub hosts: Vec<SshHost>,
+    /// `false` until `zedra import-ssh-config` has run on this host.
+    pub imported: bool,
     pub error: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct GitLogReq {
-    pub limit: Option<usize>,
+/// A `Host` alias from the host's ssh config. Connections go through the
+/// host's own `ssh`, so its keys, agent and config stay on the host.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SshHost {
+    pub alias: String,
+    /// `HostName`, when it differs from the alias.
+    pub hostname: Option<String>,
+    pub user: Option<String>,
+    pub port: Option<u16>,
+    /// `ProxyJump` hops, as written in the config.
+    pub proxy_jump: Option<String>,
 }
 
+// ---------------------------------------------------------------------------
+// Host power types
+// ---------------------------------------------------------------------------
+
 #[derive(Debug, Serialize, Deserialize)]
-pub struct GitLogResult {
-    pub entries: Vec<GitLogEntry>,
+pub struct HostWakeInfoReq {}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct HostWakeInfoResult {
+    /// Physical interfaces with a hardware address, by name.
+    pub interfaces: Vec<HostWakeInterface>,
+    /// `HostPower` is enabled on this daemon.
+    pub power_actions: bool,
     pub error: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct GitLogEntry {
-    pub id: String,
-    pub message: String,
-    pub author: String,
-    pub timestamp: i64,
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct HostWakeInterface {
+    pub name: String,
+    /// Lowercase and colon-separated, e.g. `00:1a:2b:3c:4d:5e`.
+    pub mac: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-pub struct GitCommitReq {
-    pub message: String,
-    pub paths: Vec<String>,
+pub struct HostWakeReq {
+    /// Target hardware address, colon- or dash-separated.
+    pub mac: String,
+    /// IPv4 broadcast address to send to; the limited broadcast address when
+    /// `None`.
+    pub broadcast: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct GitCommitResult {
-    pub hash: String,
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct HostWakeResult {
     pub error: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct GitStageReq {
-    pub paths: Vec<String>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct GitStageResult {
-    pub error: Option<String>,
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HostPowerAction {
+    Sleep,
+    Reboot,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-pub struct GitUnstageReq {
-    pub paths: Vec<String>,
+pub struct HostPowerReq {
+    pub action: HostPowerAction,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct GitUnstageResult {
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct HostPowerResult {
     pub error: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct GitBranchesReq {}
+// ---------------------------------------------------------------------------
+// Data saver types
+// ---------------------------------------------------------------------------
+
+/// Largest `FsReadHeadReq::max_bytes` the host honors.
+pub const FS_READ_HEAD_MAX: u64 = 500 * 1024;
 
 #[derive(Debug, Serialize, Deserialize)]
-pub struct GitBranchesResult {
-    pub branches: Vec<GitBranchEntry>,
-    pub error: Option<String>,
+pub struct SessionDataSaverReq {
+    /// Batch terminal output and summarize floods as skipped-line notices.
+    pub enabled: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct GitBranchEntry {
-    pub name: String,
-    pub is_head: bool,
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SessionDataSaverResult {
+    pub error: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-pub struct GitCheckoutReq {
-    pub branch: String,
+pub struct FsReadHeadReq {
+    pub path: String,
+    /// Clamped to `FS_READ_HEAD_MAX`.
+    pub max_bytes: u64,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct GitCheckoutResult {
-    pub ok: bool,
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct FsReadHeadResult {
+    pub content: String,
+    /// `content` stops before the end of the file.
+    pub truncated: bool,
+    /// Size of the whole file.
+    pub total_bytes: u64,
+    pub error: Option<String>,
 }
 
 // ---------------------------------------------------------------------------
-// AI types
+// Recording types
 // ---------------------------------------------------------------------------
 
+/// Largest `RecordingReadReq::max_bytes` the host honors.
+pub const RECORDING_READ_MAX: u64 = 1024 * 1024;
+
 #[derive(Debug, Serialize, Deserialize)]
-pub struct AiPromptReq {
-    pub prompt: String,
-    pub context: Option<String>,
+pub struct RecordingsListReq {}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RecordingEntry {
+    /// Bare `.cast` file name, as passed to `RecordingReadReq::name`.
+    pub name: String,
+    pub size: u64,
+    pub modified_secs: u64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct RecordingsListResult {
+    /// Newest first.
+    pub recordings: Vec<RecordingEntry>,
+    /// The daemon is recording new terminals (`zedra start --record`).
+    pub enabled: bool,
+    pub error: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-pub struct AiPromptResult {
-    pub text: String,
-    pub done: bool,
+pub struct RecordingReadReq {
+    pub name: String,
+    pub offset: u64,
+    /// Clamped to `RECORDING_READ_MAX`.
+    pub max_bytes: u64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct RecordingReadResult {
+    #[serde(with = "serde_bytes")]
+    pub data: Vec<u8>,
+    /// Size of the whole file when this chunk was read.
+    pub total_bytes: u64,
+    /// `data` reaches the end of the file.
+    pub eof: bool,
+    pub error: Option<String>,
 }
 
 // ---------------------------------------------------------------------------
@@ -1565,6 +3215,153 @@ pub struct LspDiagnostic {
     pub severity: String,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DiagnosticsCheckReq {
+    /// Workspace-relative directory holding `Cargo.toml` or `tsconfig.json`.
+    pub path: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DiagnosticsCheckResult {
+    /// Checker that ran, e.g. `"cargo check"`. Empty when `error` is set.
+    pub tool: String,
+    pub diagnostics: Vec<WorkspaceDiagnostic>,
+    /// More than `DIAGNOSTICS_CHECK_MAX` diagnostics were reported.
+    pub truncated: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkspaceDiagnostic {
+    /// Absolute path, used to open the file.
+    pub path: String,
+    /// Path relative to `DiagnosticsCheckReq::path`, or to the workspace when
+    /// the file sits outside it (e.g. a sibling crate).
+    pub rel_path: String,
+    /// 1-based start line and column.
+    pub line: u32,
+    pub column: u32,
+    /// 1-based end line and column; equal to the start when the checker
+    /// reports a point.
+    pub end_line: u32,
+    pub end_column: u32,
+    pub severity: DiagnosticSeverity,
+    pub message: String,
+    /// Checker code such as `E0308` or `TS2322`.
+    pub code: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum DiagnosticSeverity {
+    Error,
+    Warning,
+    Info,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ShellIntegrationReq {
+    /// Append the snippet when it is missing. `false` only reports status.
+    pub install: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ShellIntegrationResult {
+    /// Shell the snippet targets, e.g. `"zsh"`.
+    pub shell: String,
+    /// Absolute path of the rc file the snippet lives in.
+    pub rc_path: String,
+    pub installed: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ShellHistoryReq {
+    /// Commands to return, capped at `SHELL_HISTORY_MAX_COMMANDS`.
+    pub limit: u32,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ShellHistoryResult {
+    /// Shell whose history was read, e.g. `"zsh"`.
+    pub shell: String,
+    /// Unique commands, newest first.
+    pub commands: Vec<String>,
+    /// Existing absolute directories from `cd`/`pushd` commands, newest first.
+    pub directories: Vec<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TaskDiscoverReq {}
+
+/// Project file a discovered task was read from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TaskSource {
+    /// `scripts` in `package.json`.
+    PackageJson,
+    /// Targets in `Makefile`.
+    Makefile,
+    /// `[alias]` entries in `.cargo/config.toml`.
+    CargoAlias,
+    /// Recipes in `justfile`.
+    Justfile,
+}
+
+impl TaskSource {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::PackageJson => "package.json",
+            Self::Makefile => "Makefile",
+            Self::CargoAlias => "Cargo alias",
+            Self::Justfile => "justfile",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkspaceTask {
+    pub name: String,
+    pub source: TaskSource,
+    /// Shell command that runs the task from the workspace root, e.g.
+    /// `pnpm run build` or `make test`.
+    pub command: String,
+    /// The script body, alias expansion, or the comment above a target.
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TaskDiscoverResult {
+    /// Grouped by source: package.json, Makefile, Cargo aliases, justfile.
+    pub tasks: Vec<WorkspaceTask>,
+    /// More tasks were found than `TASK_DISCOVER_MAX`.
+    pub truncated: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ForgeCreatePrReq {
+    pub title: String,
+    pub body: String,
+    /// Branch to merge; `None` uses the current branch.
+    pub head: Option<String>,
+    /// Branch to merge into; `None` uses the forge's default branch.
+    pub base: Option<String>,
+    /// Remote whose URL names the forge repository; `None` uses `origin`.
+    pub remote: Option<String>,
+    pub draft: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ForgeCreatePrResult {
+    /// Web URL of the new pull/merge request.
+    pub url: String,
+    /// PR number (GitHub) or merge request IID (GitLab).
+    pub number: u64,
+    /// `"github"` or `"gitlab"`.
+    pub forge: String,
+    pub error: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct LspHoverReq {
     pub path: String,
@@ -1595,6 +3392,220 @@ pub struct BacklogEntry {
 mod tests {
     use super::*;
 
+    #[test]
+    fn ai_prompt_v2_wire_types_roundtrip() {
+        let req = AiPromptReqV2 {
+            prompt: "run the tests".to_string(),
+            context: Some("crate: zedra-host".to_string()),
+            allowed_tools: vec!["Read".to_string(), "Bash(cargo test:*)".to_string()],
+            resume_session_id: Some("sess-1".to_string()),
+            conversation_id: None,
+        };
+        let decoded: AiPromptReqV2 =
+            postcard::from_bytes(&postcard::to_allocvec(&req).unwrap()).unwrap();
+        assert_eq!(decoded, req);
+
+        let result = AiPromptResultV2 {
+            text: "Need approval".to_string(),
+            session_id: Some("sess-1".to_string()),
+            permission_requests: vec![AiPermissionRequest {
+                tool_name: "Bash".to_string(),
+                tool_input: r#"{"command":"cargo test"}"#.to_string(),
+            }],
+            error: None,
+            conversation_id: Some("6f1c".to_string()),
+        };
+        let decoded: AiPromptResultV2 =
+            postcard::from_bytes(&postcard::to_allocvec(&result).unwrap()).unwrap();
+        assert_eq!(decoded, result);
+
+        let provider = AiProviderInfo {
+            kind: "ollama".to_string(),
+            model: Some("qwen2.5-coder".to_string()),
+            supports_tools: false,
+            error: None,
+        };
+        let decoded: AiProviderInfo =
+            postcard::from_bytes(&postcard::to_allocvec(&provider).unwrap()).unwrap();
+        assert_eq!(decoded, provider);
+    }
+
+    #[test]
+    fn ai_conversation_roundtrip() {
+        let conversation = AiConversation {
+            id: "6f1c".to_string(),
+            title: "Fix the build".to_string(),
+            created_at: 1_700_000_000,
+            updated_at: 1_700_000_060,
+            messages: vec![
+                AiConversationMessage {
+                    role: "user".to_string(),
+                    text: "Fix the build".to_string(),
+                    timestamp: 1_700_000_000,
+                },
+                AiConversationMessage {
+                    role: "assistant".to_string(),
+                    text: "Done.".to_string(),
+                    timestamp: 1_700_000_060,
+                },
+            ],
+        };
+        let result = AiConversationGetResult {
+            conversation: Some(conversation.clone()),
+            error: None,
+        };
+        let decoded: AiConversationGetResult =
+            postcard::from_bytes(&postcard::to_allocvec(&result).unwrap()).unwrap();
+        assert_eq!(decoded.conversation, Some(conversation));
+    }
+
+    #[test]
+    fn host_projects_roundtrip() {
+        let result = HostProjectsResult {
+            projects: vec![HostProject {
+                name: "api".to_string(),
+                path: "/work/api".to_string(),
+                bookmarked: true,
+                running: true,
+                is_current: false,
+            }],
+            error: None,
+        };
+        let decoded: HostProjectsResult =
+            postcard::from_bytes(&postcard::to_allocvec(&result).unwrap()).unwrap();
+        assert_eq!(decoded.projects, result.projects);
+    }
+
+    #[test]
+    fn git_diff_stat_roundtrip() {
+        let result = GitDiffStatResult {
+            staged: vec![GitDiffStatEntry {
+                path: "src/lib.rs".to_string(),
+                additions: 12,
+                deletions: 3,
+                binary: false,
+            }],
+            unstaged: Vec::new(),
+            untracked: vec![GitDiffStatEntry {
+                path: "logo.png".to_string(),
+                binary: true,
+                ..Default::default()
+            }],
+            error: None,
+        };
+        let decoded: GitDiffStatResult =
+            postcard::from_bytes(&postcard::to_allocvec(&result).unwrap()).unwrap();
+        assert_eq!(decoded.staged, result.staged);
+        assert_eq!(decoded.untracked, result.untracked);
+    }
+
+    #[test]
+    fn git_stash_and_branch_create_roundtrip() {
+        let stash = GitStashReq {
+            message: Some("before switching to main".to_string()),
+            include_untracked: true,
+        };
+        let decoded: GitStashReq =
+            postcard::from_bytes(&postcard::to_allocvec(&stash).unwrap()).unwrap();
+        assert_eq!(decoded.message, stash.message);
+        assert!(decoded.include_untracked);
+
+        let create = GitBranchCreateReq {
+            name: "feature/picker".to_string(),
+            from: None,
+            checkout: true,
+        };
+        let decoded: GitBranchCreateReq =
+            postcard::from_bytes(&postcard::to_allocvec(&create).unwrap()).unwrap();
+        assert_eq!(decoded.name, "feature/picker");
+        assert_eq!(decoded.from, None);
+        assert!(decoded.checkout);
+    }
+
+    #[test]
+    fn git_rebase_execute_roundtrip() {
+        let req = GitRebaseExecuteReq {
+            base: "a".repeat(40),
+            steps: vec![
+                GitRebaseStep {
+                    action: GitRebaseAction::Pick,
+                    hash: "b".repeat(40),
+                    message: None,
+                },
+                GitRebaseStep {
+                    action: GitRebaseAction::Reword,
+                    hash: "c".repeat(40),
+                    message: Some("fix: handle empty input".to_string()),
+                },
+            ],
+        };
+        let decoded: GitRebaseExecuteReq =
+            postcard::from_bytes(&postcard::to_allocvec(&req).unwrap()).unwrap();
+        assert_eq!(decoded.base, req.base);
+        assert_eq!(decoded.steps, req.steps);
+    }
+
+    #[test]
+    fn fs_replace_roundtrip() {
+        let req = FsReplaceReq {
+            path: ".".to_string(),
+            find: r"fn (\w+)_old".to_string(),
+            replace: "fn ${1}_new".to_string(),
+            regex: true,
+            case_sensitive: true,
+            dry_run: false,
+            files: vec![FsReplaceTarget {
+                rel_path: "src/lib.rs".to_string(),
+                fingerprint: "ab12".to_string(),
+            }],
+        };
+        let decoded: FsReplaceReq =
+            postcard::from_bytes(&postcard::to_allocvec(&req).unwrap()).unwrap();
+        assert_eq!(decoded.find, req.find);
+        assert_eq!(decoded.files, req.files);
+        assert!(!decoded.dry_run);
+
+        let file = FsReplaceFile {
+            path: "/work/src/lib.rs".to_string(),
+            rel_path: "src/lib.rs".to_string(),
+            matches: 2,
+            samples: vec![FsReplaceSample {
+                line: 4,
+                before: "fn load_old() {}".to_string(),
+                after: "fn load_new() {}".to_string(),
+            }],
+            fingerprint: "ab12".to_string(),
+            status: FsReplaceStatus::Failed("permission denied".to_string()),
+        };
+        let decoded: FsReplaceFile =
+            postcard::from_bytes(&postcard::to_allocvec(&file).unwrap()).unwrap();
+        assert_eq!(decoded, file);
+    }
+
+    #[test]
+    fn diagnostics_check_result_roundtrip() {
+        let result = DiagnosticsCheckResult {
+            tool: "cargo check".to_string(),
+            diagnostics: vec![WorkspaceDiagnostic {
+                path: "/work/src/main.rs".to_string(),
+                rel_path: "src/main.rs".to_string(),
+                line: 3,
+                column: 9,
+                end_line: 3,
+                end_column: 14,
+                severity: DiagnosticSeverity::Warning,
+                message: "unused variable: `value`".to_string(),
+                code: Some("unused_variables".to_string()),
+            }],
+            truncated: false,
+            error: None,
+        };
+        let decoded: DiagnosticsCheckResult =
+            postcard::from_bytes(&postcard::to_allocvec(&result).unwrap()).unwrap();
+        assert_eq!(decoded.tool, result.tool);
+        assert_eq!(decoded.diagnostics, result.diagnostics);
+    }
+
     #[test]
     fn term_output_roundtrip() {
         let output = TermOutput {
@@ -1718,6 +3729,12 @@ mod tests {
                 agent_state: AgentState::Idle,
                 agent_slug: Some("codex".into()),
             }],
+            ai_provider: AiProviderInfo {
+                kind: "claude-cli".into(),
+                model: None,
+                supports_tools: true,
+                error: None,
+            },
         };
         let encoded = postcard::to_allocvec(&result).unwrap();
         let decoded: SyncSessionResult = postcard::from_bytes(&encoded).unwrap();
@@ -1734,6 +3751,7 @@ mod tests {
         );
         assert_eq!(decoded.terminals[0].shell_state, TermShellState::Idle);
         assert_eq!(decoded.terminals[0].last_exit_code, Some(0));
+        assert_eq!(decoded.ai_provider, result.ai_provider);
     }
 
     #[test]
@@ -1768,6 +3786,75 @@ mod tests {
         assert_eq!(decoded, result);
     }
 
+    #[test]
+    fn fs_grep_context_wire_types_roundtrip() {
+        let req = FsGrepReqV2 {
+            path: ".".into(),
+            glob: Some("*.rs".into()),
+            pattern: Some("todo".into()),
+            case_sensitive: false,
+            limit: 0,
+            context: 2,
+        };
+        let encoded = postcard::to_allocvec(&req).unwrap();
+        let decoded: FsGrepReqV2 = postcard::from_bytes(&encoded).unwrap();
+        assert_eq!(decoded, req);
+        assert_eq!(decoded.without_context().glob.as_deref(), Some("*.rs"));
+
+        let update = FsGrepUpdate::ContextMatches(vec![FsGrepContextMatch {
+            hit: FsGrepMatch {
+                path: "/repo/src/main.rs".into(),
+                rel_path: "src/main.rs".into(),
+                line: Some(2),
+                snippet: Some("    todo!()".into()),
+            },
+            before: vec!["fn main() {".into()],
+            after: vec!["}".into()],
+        }]);
+        let encoded = postcard::to_allocvec(&update).unwrap();
+        let decoded: FsGrepUpdate = postcard::from_bytes(&encoded).unwrap();
+        assert_eq!(decoded, update);
+
+        let result = FsGrepExpandResult {
+            start_line: 1,
+            lines: vec!["fn main() {".into(), "    todo!()".into()],
+            total_lines: 3,
+            error: None,
+        };
+        let encoded = postcard::to_allocvec(&result).unwrap();
+        let decoded: FsGrepExpandResult = postcard::from_bytes(&encoded).unwrap();
+        assert_eq!(decoded, result);
+    }
+
+    #[test]
+    fn fs_batch_wire_types_roundtrip() {
+        let req = FsRemoveReq {
+            paths: vec!["/repo/build".into(), "/repo/notes.txt".into()],
+        };
+        let encoded = postcard::to_allocvec(&req).unwrap();
+        assert_eq!(postcard::from_bytes::<FsRemoveReq>(&encoded).unwrap(), req);
+
+        let result = FsRemoveResult {
+            failed: vec![FsPathError {
+                path: "/repo/notes.txt".into(),
+                error: "permission denied".into(),
+            }],
+            error: None,
+        };
+        let encoded = postcard::to_allocvec(&result).unwrap();
+        assert_eq!(
+            postcard::from_bytes::<FsRemoveResult>(&encoded).unwrap(),
+            result
+        );
+
+        let req = FsArchiveReq {
+            paths: vec!["/repo/logs".into()],
+            to: "/repo/logs.tar.gz".into(),
+        };
+        let encoded = postcard::to_allocvec(&req).unwrap();
+        assert_eq!(postcard::from_bytes::<FsArchiveReq>(&encoded).unwrap(), req);
+    }
+
     #[test]
     fn host_info_snapshot_roundtrip() {
         let snapshot = HostInfoSnapshot {
@@ -1900,3 +3987,63 @@ mod tests {
         assert_eq!(decoded, session);
     }
 }
+
+/// Property tests for malformed payloads. The host decodes every request from
+/// an untrusted peer, so decoding must fail cleanly (never panic or allocate
+/// from a claimed length) on truncated, oversized, or otherwise corrupt bytes.
+///
+/// Frame-level properties (length prefix, `MAX_MESSAGE_SIZE`) run against the
+/// host's `read_request_frame` in `zedra-host`.
+#[cfg(test)]
+mod wire_props {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn fs_write() -> impl Strategy<Value = FsWriteReq> {
+        (".{0,64}", ".{0,256}").prop_map(|(path, content)| FsWriteReq { path, content })
+    }
+
+    proptest! {
+        #[test]
+        fn arbitrary_bytes_never_panic_decoding(bytes in proptest::collection::vec(any::<u8>(), 0..512)) {
+            // Requests the host decodes, and replies or events the app decodes.
+            let _ = postcard::from_bytes::<ZedraProto>(&bytes);
+            let _ = postcard::from_bytes::<ConnectResult>(&bytes);
+            let _ = postcard::from_bytes::<SyncSessionResult>(&bytes);
+            let _ = postcard::from_bytes::<HostEvent>(&bytes);
+            let _ = postcard::from_bytes::<TermOutput>(&bytes);
+        }
+
+        #[test]
+        fn truncated_requests_are_rejected(req in fs_write()) {
+            let request = ZedraProto::FsWrite(req);
+            let payload = postcard::to_allocvec(&request).unwrap();
+            for cut in 0..payload.len() {
+                prop_assert!(postcard::from_bytes::<ZedraProto>(&payload[..cut]).is_err());
+            }
+        }
+
+        #[test]
+        fn oversized_field_lengths_are_rejected(
+            field_len in 64u64..=u32::MAX as u64,
+            tail in proptest::collection::vec(any::<u8>(), 0..32),
+        ) {
+            // A string or byte length past the end of the payload fails
+            // instead of trusting the claimed size.
+            let mut payload = postcard::to_allocvec(&field_len).unwrap();
+            payload.extend(&tail);
+            prop_assert!(postcard::from_bytes::<FsWriteReq>(&payload).is_err());
+            prop_assert!(postcard::from_bytes::<TermOutput>(&payload).is_err());
+        }
+
+        #[test]
+        fn invalid_utf8_strings_are_rejected(bad in proptest::collection::vec(0x80u8..=0xff, 1..16)) {
+            // Lone continuation/lead bytes are never valid UTF-8 on their own.
+            prop_assume!(std::str::from_utf8(&bad).is_err());
+            let mut payload = postcard::to_allocvec(&(bad.len() as u64)).unwrap();
+            payload.extend(&bad);
+            payload.extend(postcard::to_allocvec("content").unwrap());
+            prop_assert!(postcard::from_bytes::<FsWriteReq>(&payload).is_err());
+        }
+    }
+}
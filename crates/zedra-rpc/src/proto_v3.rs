@@ -578,8 +578,9 @@ impl From<proto::AgentSessionsResult> for AgentSessionsResult {
 }
 
 /// `None` drops events the old client can't decode: the `v4`-only
-/// `TerminalAgentChanged` and `WebViewRequested`, and agent events for a
-/// filtered (newer) slug.
+/// `TerminalAgentChanged` and `WebViewRequested`, the `v5`-only
+/// `GitCredentialRequested` and `Notification`, and agent events for a filtered
+/// (newer) slug.
 fn host_event_v3(e: proto::HostEvent) -> Option<HostEvent> {
     match e {
         proto::HostEvent::TerminalCreated { id, launch_cmd, .. } => {
@@ -610,6 +611,8 @@ fn host_event_v3(e: proto::HostEvent) -> Option<HostEvent> {
         }),
         proto::HostEvent::TerminalAgentChanged { .. } => None,
         proto::HostEvent::WebViewRequested { .. } => None,
+        proto::HostEvent::GitCredentialRequested { .. } => None,
+        proto::HostEvent::Notification(_) => None,
     }
 }
 
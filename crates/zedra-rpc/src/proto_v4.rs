@@ -0,0 +1,538 @@
+// Frozen `zedra/rpc/4` wire schema, for clients built before the `zedra/rpc/5`
+// ALPN bump. Served the same way as `proto_v3`: requests decode with
+// `ZedraProtoV4`, lift into `proto::ZedraMessage` via `into_live`, and reuse the
+// live dispatch.
+//
+// Wire contract for shipped binaries — do not edit types or variant order. Only
+// types that diverged at `zedra/rpc/5` are defined here; the rest reuse `proto`
+// (pinned by the roundtrip tests below). A future wire change must bump the ALPN
+// (§2.4) and re-freeze the reused types.
+//
+// `v4`->`v5` divergence: requests are only appended, so the `v4` request set is
+// a prefix of `v5`'s. `SyncSessionResult` gained `ai_provider` (and so the
+// `ConnectResult`/`AuthProveResult` that carry it), and `HostEvent` gained
+// variants a `v4` client cannot decode, which are dropped from its event stream.
+// Everything else is byte-identical.
+
+use irpc::channel::{mpsc, oneshot};
+use irpc::rpc_requests;
+use serde::{Deserialize, Serialize};
+
+use crate::proto;
+
+/// Previous ALPN, advertised alongside `ZEDRA_ALPN`.
+pub const ZEDRA_ALPN_V4: &[u8] = b"zedra/rpc/4";
+
+// ---------------------------------------------------------------------------
+// Protocol enum (frozen at the final `zedra/rpc/4` variant set and order)
+// ---------------------------------------------------------------------------
+
+#[rpc_requests(message = ZedraMessageV4)]
+#[derive(Serialize, Deserialize, Debug)]
+pub enum ZedraProtoV4 {
+    #[rpc(tx = oneshot::Sender<proto::RegisterResult>)]
+    Register(proto::RegisterReq),
+    #[rpc(tx = oneshot::Sender<proto::AuthChallengeResult>)]
+    Authenticate(proto::AuthReq),
+    #[rpc(tx = oneshot::Sender<AuthProveResult>)]
+    AuthProve(proto::AuthProveReq),
+    #[rpc(tx = oneshot::Sender<ConnectResult>)]
+    Connect(proto::ConnectReq),
+    #[rpc(tx = oneshot::Sender<proto::PongResult>)]
+    Ping(proto::PingReq),
+    #[rpc(tx = oneshot::Sender<proto::SessionInfoResult>)]
+    GetSessionInfo(proto::SessionInfoReq),
+    #[rpc(tx = oneshot::Sender<proto::SessionListResult>)]
+    ListSessions(proto::SessionListReq),
+    #[rpc(tx = oneshot::Sender<proto::SessionSwitchResult>)]
+    SwitchSession(proto::SessionSwitchReq),
+    #[rpc(tx = oneshot::Sender<proto::FsListResult>)]
+    FsList(proto::FsListReq),
+    #[rpc(tx = oneshot::Sender<proto::FsReadResult>)]
+    FsRead(proto::FsReadReq),
+    #[rpc(tx = oneshot::Sender<proto::FsWriteResult>)]
+    FsWrite(proto::FsWriteReq),
+    #[rpc(tx = oneshot::Sender<proto::FsStatResult>)]
+    FsStat(proto::FsStatReq),
+    #[rpc(tx = oneshot::Sender<proto::TermCreateResult>)]
+    TermCreate(proto::TermCreateReq),
+    #[rpc(tx = mpsc::Sender<HostEvent>)]
+    Subscribe(proto::SubscribeReq),
+    #[rpc(rx = mpsc::Receiver<proto::TermInput>, tx = mpsc::Sender<proto::TermOutput>)]
+    TermAttach(proto::TermAttachReq),
+    #[rpc(tx = oneshot::Sender<proto::TermResizeResult>)]
+    TermResize(proto::TermResizeReq),
+    #[rpc(tx = oneshot::Sender<proto::TermCloseResult>)]
+    TermClose(proto::TermCloseReq),
+    #[rpc(tx = oneshot::Sender<proto::TermListResult>)]
+    TermList(proto::TermListReq),
+    #[rpc(tx = oneshot::Sender<proto::GitStatusResult>)]
+    GitStatus(proto::GitStatusReq),
+    #[rpc(tx = oneshot::Sender<proto::GitDiffResult>)]
+    GitDiff(proto::GitDiffReq),
+    #[rpc(tx = oneshot::Sender<proto::GitLogResult>)]
+    GitLog(proto::GitLogReq),
+    #[rpc(tx = oneshot::Sender<proto::GitCommitResult>)]
+    GitCommit(proto::GitCommitReq),
+    #[rpc(tx = oneshot::Sender<proto::GitStageResult>)]
+    GitStage(proto::GitStageReq),
+    #[rpc(tx = oneshot::Sender<proto::GitUnstageResult>)]
+    GitUnstage(proto::GitUnstageReq),
+    #[rpc(tx = oneshot::Sender<proto::GitBranchesResult>)]
+    GitBranches(proto::GitBranchesReq),
+    #[rpc(tx = oneshot::Sender<proto::GitCheckoutResult>)]
+    GitCheckout(proto::GitCheckoutReq),
+    #[rpc(tx = oneshot::Sender<proto::AiPromptResult>)]
+    AiPrompt(proto::AiPromptReq),
+    #[rpc(tx = oneshot::Sender<proto::LspDiagnosticsResult>)]
+    LspDiagnostics(proto::LspDiagnosticsReq),
+    #[rpc(tx = oneshot::Sender<proto::LspHoverResult>)]
+    LspHover(proto::LspHoverReq),
+    #[rpc(tx = oneshot::Sender<proto::FsWatchResult>)]
+    FsWatch(proto::FsWatchReq),
+    #[rpc(tx = oneshot::Sender<proto::FsUnwatchResult>)]
+    FsUnwatch(proto::FsUnwatchReq),
+    #[rpc(tx = oneshot::Sender<SyncSessionResult>)]
+    SyncSession(proto::SyncSessionReq),
+    #[rpc(tx = mpsc::Sender<proto::HostInfoSnapshot>)]
+    SubscribeHostInfo(proto::SubscribeHostInfoReq),
+    #[rpc(tx = oneshot::Sender<proto::TermReorderResult>)]
+    TermReorder(proto::TermReorderReq),
+    #[rpc(tx = oneshot::Sender<proto::FsDocsTreeResult>)]
+    FsDocsTree(proto::FsDocsTreeReq),
+    #[rpc(tx = oneshot::Sender<proto::AgentListResult>)]
+    AgentList(proto::AgentListReq),
+    #[rpc(tx = oneshot::Sender<proto::AgentSessionsResult>)]
+    AgentSessions(proto::AgentSessionsReq),
+    #[rpc(tx = oneshot::Sender<proto::AgentResumeResult>)]
+    AgentResume(proto::AgentResumeReq),
+    #[rpc(tx = oneshot::Sender<proto::AgentInstalledListResult>)]
+    AgentInstalledList(proto::AgentInstalledListReq),
+    #[rpc(tx = oneshot::Sender<proto::TermCreateResult>)]
+    TermCreateV2(proto::TermCreateReqV2),
+    #[rpc(tx = oneshot::Sender<proto::AgentFilesResult>)]
+    AgentFiles(proto::AgentFilesReq),
+    #[rpc(tx = oneshot::Sender<proto::FsSearchResult>)]
+    FsSearch(proto::FsSearchReq),
+    #[rpc(tx = oneshot::Sender<proto::SetAppStateResult>)]
+    SetAppState(proto::SetAppStateReq),
+    #[rpc(tx = oneshot::Sender<proto::SetClientDeltaInfoResult>)]
+    SetClientDeltaInfo(proto::SetClientDeltaInfoReq),
+    #[rpc(tx = oneshot::Sender<proto::ClearClientDeltaInfoResult>)]
+    ClearClientDeltaInfo(proto::ClearClientDeltaInfoReq),
+    #[rpc(tx = oneshot::Sender<proto::FsUploadResult>)]
+    FsUpload(proto::FsUploadReq),
+    #[rpc(rx = mpsc::Receiver<proto::WebTunnelInput>, tx = mpsc::Sender<proto::WebTunnelOutput>)]
+    WebConnect(proto::WebConnectReq),
+    #[rpc(tx = oneshot::Sender<proto::WebClientStartResult>)]
+    WebClientStart(proto::WebClientStartReq),
+    #[rpc(tx = oneshot::Sender<proto::WebClientStopResult>)]
+    WebClientStop(proto::WebClientStopReq),
+    #[rpc(tx = oneshot::Sender<proto::WebClientListResult>)]
+    WebClientList(proto::WebClientListReq),
+    #[rpc(tx = mpsc::Sender<proto::WebClientUpdate>)]
+    WebClientWatch(proto::WebClientWatchReq),
+    #[rpc(tx = oneshot::Sender<proto::WebClientSetPathResult>)]
+    WebClientSetPath(proto::WebClientSetPathReq),
+    #[rpc(tx = oneshot::Sender<proto::AiPromptResultV2>)]
+    AiPromptV2(proto::AiPromptReqV2),
+    #[rpc(tx = oneshot::Sender<proto::AiProviderInfo>)]
+    AiProvider(proto::AiProviderReq),
+    #[rpc(tx = oneshot::Sender<proto::AiConversationListResult>)]
+    AiConversationList(proto::AiConversationListReq),
+    #[rpc(tx = oneshot::Sender<proto::AiConversationGetResult>)]
+    AiConversationGet(proto::AiConversationGetReq),
+    #[rpc(tx = oneshot::Sender<proto::AiConversationDeleteResult>)]
+    AiConversationDelete(proto::AiConversationDeleteReq),
+    #[rpc(tx = oneshot::Sender<proto::HostProjectsResult>)]
+    HostProjects(proto::HostProjectsReq),
+    #[rpc(tx = oneshot::Sender<proto::GitDiffStatResult>)]
+    GitDiffStat(proto::GitDiffStatReq),
+    #[rpc(tx = oneshot::Sender<proto::GitStashResult>)]
+    GitStash(proto::GitStashReq),
+    #[rpc(tx = oneshot::Sender<proto::GitDiscardResult>)]
+    GitDiscard(proto::GitDiscardReq),
+    #[rpc(tx = oneshot::Sender<proto::GitBranchCreateResult>)]
+    GitBranchCreate(proto::GitBranchCreateReq),
+    #[rpc(tx = oneshot::Sender<proto::FsReplaceResult>)]
+    FsReplace(proto::FsReplaceReq),
+    #[rpc(tx = oneshot::Sender<proto::DiagnosticsCheckResult>)]
+    DiagnosticsCheck(proto::DiagnosticsCheckReq),
+    #[rpc(tx = oneshot::Sender<proto::ShellIntegrationResult>)]
+    ShellIntegration(proto::ShellIntegrationReq),
+    #[rpc(tx = oneshot::Sender<proto::ShellHistoryResult>)]
+    ShellHistory(proto::ShellHistoryReq),
+    #[rpc(tx = oneshot::Sender<proto::ForgeCreatePrResult>)]
+    ForgeCreatePr(proto::ForgeCreatePrReq),
+    #[rpc(tx = oneshot::Sender<proto::GitRebasePlanResult>)]
+    GitRebasePlan(proto::GitRebasePlanReq),
+    #[rpc(tx = oneshot::Sender<proto::GitRebaseExecuteResult>)]
+    GitRebaseExecute(proto::GitRebaseExecuteReq),
+    #[rpc(tx = oneshot::Sender<proto::GitGraphResult>)]
+    GitGraph(proto::GitGraphReq),
+    #[rpc(tx = oneshot::Sender<proto::GitFileLogResult>)]
+    GitFileLog(proto::GitFileLogReq),
+    #[rpc(tx = oneshot::Sender<proto::GitShowFileResult>)]
+    GitShowFile(proto::GitShowFileReq),
+    #[rpc(tx = oneshot::Sender<proto::GitShowCommitResult>)]
+    GitShowCommit(proto::GitShowCommitReq),
+    #[rpc(tx = oneshot::Sender<proto::GitBlameResult>)]
+    GitBlame(proto::GitBlameReq),
+}
+
+// ---------------------------------------------------------------------------
+// Divergent types — frozen at their final `zedra/rpc/4` wire shape
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncSessionResult {
+    pub session_id: String,
+    pub session_token: [u8; 32],
+    pub hostname: String,
+    pub workdir: String,
+    pub username: String,
+    pub home_dir: Option<String>,
+    pub os: Option<String>,
+    pub arch: Option<String>,
+    pub os_version: Option<String>,
+    pub host_version: Option<String>,
+    pub delta_pubkey: [u8; 32],
+    pub terminals: Vec<proto::TerminalSyncEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub enum ConnectResult {
+    Ok(SyncSessionResult),
+    Challenge {
+        nonce: [u8; 32],
+        #[serde(with = "crate::proto::bytes64")]
+        host_signature: [u8; 64],
+    },
+    Unauthorized,
+    NotInSessionAcl,
+    SessionOccupied,
+    SessionNotFound,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub enum AuthProveResult {
+    Ok(SyncSessionResult),
+    Unauthorized,
+    NotInSessionAcl,
+    SessionOccupied,
+    SessionNotFound,
+    InvalidSignature,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum HostEvent {
+    TerminalCreated {
+        id: String,
+        launch_cmd: Option<String>,
+        agent_slug: Option<String>,
+    },
+    GitChanged,
+    FsChanged {
+        path: String,
+    },
+    AgentInfoChanged {
+        info: proto::AgentSummary,
+    },
+    AgentHookReceived {
+        agent_slug: String,
+        event_name: String,
+        payload: String,
+    },
+    AgentStateChanged {
+        terminal_id: String,
+        agent_session_id: String,
+        state: proto::AgentState,
+    },
+    TerminalAgentChanged {
+        terminal_id: String,
+        agent_slug: Option<String>,
+    },
+    WebViewRequested {
+        url: String,
+    },
+}
+
+// ---------------------------------------------------------------------------
+// Live (v5) -> `zedra/rpc/4` response conversions
+// ---------------------------------------------------------------------------
+
+impl From<proto::SyncSessionResult> for SyncSessionResult {
+    fn from(s: proto::SyncSessionResult) -> Self {
+        // Drops `ai_provider` appended at `zedra/rpc/5`.
+        Self {
+            session_id: s.session_id,
+            session_token: s.session_token,
+            hostname: s.hostname,
+            workdir: s.workdir,
+            username: s.username,
+            home_dir: s.home_dir,
+            os: s.os,
+            arch: s.arch,
+            os_version: s.os_version,
+            host_version: s.host_version,
+            delta_pubkey: s.delta_pubkey,
+            terminals: s.terminals,
+        }
+    }
+}
+
+impl From<proto::ConnectResult> for ConnectResult {
+    fn from(r: proto::ConnectResult) -> Self {
+        match r {
+            proto::ConnectResult::Ok(s) => ConnectResult::Ok(s.into()),
+            proto::ConnectResult::Challenge {
+                nonce,
+                host_signature,
+            } => ConnectResult::Challenge {
+                nonce,
+                host_signature,
+            },
+            proto::ConnectResult::Unauthorized => ConnectResult::Unauthorized,
+            proto::ConnectResult::NotInSessionAcl => ConnectResult::NotInSessionAcl,
+            proto::ConnectResult::SessionOccupied => ConnectResult::SessionOccupied,
+            proto::ConnectResult::SessionNotFound => ConnectResult::SessionNotFound,
+        }
+    }
+}
+
+impl From<proto::AuthProveResult> for AuthProveResult {
+    fn from(r: proto::AuthProveResult) -> Self {
+        match r {
+            proto::AuthProveResult::Ok(s) => AuthProveResult::Ok(s.into()),
+            proto::AuthProveResult::Unauthorized => AuthProveResult::Unauthorized,
+            proto::AuthProveResult::NotInSessionAcl => AuthProveResult::NotInSessionAcl,
+            proto::AuthProveResult::SessionOccupied => AuthProveResult::SessionOccupied,
+            proto::AuthProveResult::SessionNotFound => AuthProveResult::SessionNotFound,
+            proto::AuthProveResult::InvalidSignature => AuthProveResult::InvalidSignature,
+        }
+    }
+}
+
+/// `None` drops the `v5`-only `GitCredentialRequested` and `Notification`
+/// events, which would be undecodable and kill the stream.
+fn host_event_v4(e: proto::HostEvent) -> Option<HostEvent> {
+    match e {
+        proto::HostEvent::TerminalCreated {
+            id,
+            launch_cmd,
+            agent_slug,
+        } => Some(HostEvent::TerminalCreated {
+            id,
+            launch_cmd,
+            agent_slug,
+        }),
+        proto::HostEvent::GitChanged => Some(HostEvent::GitChanged),
+        proto::HostEvent::FsChanged { path } => Some(HostEvent::FsChanged { path }),
+        proto::HostEvent::AgentInfoChanged { info } => Some(HostEvent::AgentInfoChanged { info }),
+        proto::HostEvent::AgentHookReceived {
+            agent_slug,
+            event_name,
+            payload,
+        } => Some(HostEvent::AgentHookReceived {
+            agent_slug,
+            event_name,
+            payload,
+        }),
+        proto::HostEvent::AgentStateChanged {
+            terminal_id,
+            agent_session_id,
+            state,
+        } => Some(HostEvent::AgentStateChanged {
+            terminal_id,
+            agent_session_id,
+            state,
+        }),
+        proto::HostEvent::TerminalAgentChanged {
+            terminal_id,
+            agent_slug,
+        } => Some(HostEvent::TerminalAgentChanged {
+            terminal_id,
+            agent_slug,
+        }),
+        proto::HostEvent::WebViewRequested { url } => Some(HostEvent::WebViewRequested { url }),
+        proto::HostEvent::GitCredentialRequested { .. } => None,
+        proto::HostEvent::Notification(_) => None,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Lift a decoded `zedra/rpc/4` request into the live message, wrapping the
+// diverged response channels to re-encode to `zedra/rpc/4` bytes on send.
+// ---------------------------------------------------------------------------
+
+impl ZedraMessageV4 {
+    pub fn into_live(self) -> proto::ZedraMessage {
+        use proto::ZedraMessage as M;
+        match self {
+            ZedraMessageV4::Register(m) => M::Register((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::Authenticate(m) => M::Authenticate((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::Ping(m) => M::Ping((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::GetSessionInfo(m) => M::GetSessionInfo((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::ListSessions(m) => M::ListSessions((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::SwitchSession(m) => M::SwitchSession((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::FsList(m) => M::FsList((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::FsRead(m) => M::FsRead((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::FsWrite(m) => M::FsWrite((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::FsStat(m) => M::FsStat((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::TermCreate(m) => M::TermCreate((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::TermAttach(m) => M::TermAttach((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::TermResize(m) => M::TermResize((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::TermClose(m) => M::TermClose((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::TermList(m) => M::TermList((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::GitStatus(m) => M::GitStatus((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::GitDiff(m) => M::GitDiff((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::GitLog(m) => M::GitLog((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::GitCommit(m) => M::GitCommit((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::GitStage(m) => M::GitStage((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::GitUnstage(m) => M::GitUnstage((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::GitBranches(m) => M::GitBranches((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::GitCheckout(m) => M::GitCheckout((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::AiPrompt(m) => M::AiPrompt((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::LspDiagnostics(m) => M::LspDiagnostics((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::LspHover(m) => M::LspHover((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::FsWatch(m) => M::FsWatch((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::FsUnwatch(m) => M::FsUnwatch((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::SubscribeHostInfo(m) => {
+                M::SubscribeHostInfo((m.inner, m.tx, m.rx).into())
+            }
+            ZedraMessageV4::TermReorder(m) => M::TermReorder((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::FsDocsTree(m) => M::FsDocsTree((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::AgentList(m) => M::AgentList((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::AgentSessions(m) => M::AgentSessions((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::AgentResume(m) => M::AgentResume((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::AgentInstalledList(m) => {
+                M::AgentInstalledList((m.inner, m.tx, m.rx).into())
+            }
+            ZedraMessageV4::TermCreateV2(m) => M::TermCreateV2((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::AgentFiles(m) => M::AgentFiles((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::FsSearch(m) => M::FsSearch((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::SetAppState(m) => M::SetAppState((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::SetClientDeltaInfo(m) => {
+                M::SetClientDeltaInfo((m.inner, m.tx, m.rx).into())
+            }
+            ZedraMessageV4::ClearClientDeltaInfo(m) => {
+                M::ClearClientDeltaInfo((m.inner, m.tx, m.rx).into())
+            }
+            ZedraMessageV4::FsUpload(m) => M::FsUpload((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::WebConnect(m) => M::WebConnect((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::WebClientStart(m) => M::WebClientStart((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::WebClientStop(m) => M::WebClientStop((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::WebClientList(m) => M::WebClientList((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::WebClientWatch(m) => M::WebClientWatch((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::WebClientSetPath(m) => {
+                M::WebClientSetPath((m.inner, m.tx, m.rx).into())
+            }
+            ZedraMessageV4::AiPromptV2(m) => M::AiPromptV2((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::AiProvider(m) => M::AiProvider((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::AiConversationList(m) => {
+                M::AiConversationList((m.inner, m.tx, m.rx).into())
+            }
+            ZedraMessageV4::AiConversationGet(m) => {
+                M::AiConversationGet((m.inner, m.tx, m.rx).into())
+            }
+            ZedraMessageV4::AiConversationDelete(m) => {
+                M::AiConversationDelete((m.inner, m.tx, m.rx).into())
+            }
+            ZedraMessageV4::HostProjects(m) => M::HostProjects((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::GitDiffStat(m) => M::GitDiffStat((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::GitStash(m) => M::GitStash((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::GitDiscard(m) => M::GitDiscard((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::GitBranchCreate(m) => M::GitBranchCreate((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::FsReplace(m) => M::FsReplace((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::DiagnosticsCheck(m) => {
+                M::DiagnosticsCheck((m.inner, m.tx, m.rx).into())
+            }
+            ZedraMessageV4::ShellIntegration(m) => {
+                M::ShellIntegration((m.inner, m.tx, m.rx).into())
+            }
+            ZedraMessageV4::ShellHistory(m) => M::ShellHistory((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::ForgeCreatePr(m) => M::ForgeCreatePr((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::GitRebasePlan(m) => M::GitRebasePlan((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::GitRebaseExecute(m) => {
+                M::GitRebaseExecute((m.inner, m.tx, m.rx).into())
+            }
+            ZedraMessageV4::GitGraph(m) => M::GitGraph((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::GitFileLog(m) => M::GitFileLog((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::GitShowFile(m) => M::GitShowFile((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::GitShowCommit(m) => M::GitShowCommit((m.inner, m.tx, m.rx).into()),
+            ZedraMessageV4::GitBlame(m) => M::GitBlame((m.inner, m.tx, m.rx).into()),
+            // Divergent responses: map the live result back to `zedra/rpc/4` bytes.
+            ZedraMessageV4::Connect(m) => {
+                M::Connect((m.inner, m.tx.with_map(ConnectResult::from), m.rx).into())
+            }
+            ZedraMessageV4::AuthProve(m) => {
+                M::AuthProve((m.inner, m.tx.with_map(AuthProveResult::from), m.rx).into())
+            }
+            ZedraMessageV4::SyncSession(m) => {
+                M::SyncSession((m.inner, m.tx.with_map(SyncSessionResult::from), m.rx).into())
+            }
+            // Stream: drop events the old client cannot decode.
+            ZedraMessageV4::Subscribe(m) => {
+                M::Subscribe((m.inner, m.tx.with_filter_map(host_event_v4), m.rx).into())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shared_events_are_wire_identical() {
+        let live = proto::HostEvent::TerminalAgentChanged {
+            terminal_id: "t".into(),
+            agent_slug: Some("codex".into()),
+        };
+        let live_bytes = postcard::to_stdvec(&live).unwrap();
+        let frozen = host_event_v4(live).unwrap();
+        assert_eq!(postcard::to_stdvec(&frozen).unwrap(), live_bytes);
+    }
+
+    #[test]
+    fn sync_session_result_drops_v5_ai_provider() {
+        let live = proto::SyncSessionResult {
+            session_id: "s".into(),
+            session_token: [1; 32],
+            hostname: "host".into(),
+            workdir: "/w".into(),
+            username: "user".into(),
+            home_dir: None,
+            os: None,
+            arch: None,
+            os_version: None,
+            host_version: Some("0.1.1".into()),
+            delta_pubkey: [2; 32],
+            terminals: Vec::new(),
+            ai_provider: proto::AiProviderInfo {
+                kind: "ollama".into(),
+                ..Default::default()
+            },
+        };
+        let frozen: SyncSessionResult = live.into();
+        let bytes = postcard::to_stdvec(&ConnectResult::Ok(frozen)).unwrap();
+        let decoded: ConnectResult = postcard::from_bytes(&bytes).unwrap();
+        assert!(matches!(decoded, ConnectResult::Ok(s) if s.hostname == "host"));
+    }
+
+    #[test]
+    fn v5_only_events_are_dropped() {
+        let event = proto::HostEvent::GitCredentialRequested {
+            id: "c".into(),
+            url: "https://example.com/repo.git".into(),
+            username: None,
+        };
+        assert!(host_event_v4(event).is_none());
+        let notification = proto::HostEvent::Notification(proto::HostNotification::DiskLow {
+            mount_point: "/".into(),
+            available_bytes: 1,
+            total_bytes: 100,
+        });
+        assert!(host_event_v4(notification).is_none());
+    }
+}
@@ -0,0 +1,79 @@
+// Wake-on-LAN: magic packets for waking a sleeping host.
+//
+// The app sends them on the device's own network; a connected host sends them
+// on its network through `HostWake`, which reaches machines the device cannot
+// broadcast to. A magic packet is six 0xff bytes followed by the target MAC
+// repeated sixteen times, sent as a UDP broadcast.
+
+use std::net::{Ipv4Addr, SocketAddrV4, UdpSocket};
+
+/// Discard port, the conventional Wake-on-LAN destination.
+pub const WAKE_PORT: u16 = 9;
+
+/// Parse `aa:bb:cc:dd:ee:ff` or `aa-bb-cc-dd-ee-ff`, in either case.
+pub fn parse_mac(text: &str) -> Option<[u8; 6]> {
+    let mut mac = [0u8; 6];
+    let mut parts = text.trim().split([':', '-']);
+    for byte in &mut mac {
+        let part = parts.next()?;
+        if part.len() != 2 {
+            return None;
+        }
+        *byte = u8::from_str_radix(part, 16).ok()?;
+    }
+    if parts.next().is_some() || mac == [0; 6] {
+        return None;
+    }
+    Some(mac)
+}
+
+/// Lowercase, colon-separated form used on the wire.
+pub fn format_mac(mac: [u8; 6]) -> String {
+    mac.iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+pub fn magic_packet(mac: [u8; 6]) -> [u8; 102] {
+    let mut packet = [0xff; 102];
+    for chunk in packet[6..].chunks_exact_mut(6) {
+        chunk.copy_from_slice(&mac);
+    }
+    packet
+}
+
+/// Broadcast a magic packet for `mac` to `broadcast` (the limited broadcast
+/// address when `None`).
+pub fn send(mac: [u8; 6], broadcast: Option<Ipv4Addr>) -> std::io::Result<()> {
+    let socket = UdpSocket::bind(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 0))?;
+    socket.set_broadcast(true)?;
+    let target = SocketAddrV4::new(broadcast.unwrap_or(Ipv4Addr::BROADCAST), WAKE_PORT);
+    socket.send_to(&magic_packet(mac), target)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_mac_accepts_common_separators() {
+        let mac = [0x00, 0x1a, 0x2b, 0x3c, 0x4d, 0x5e];
+        assert_eq!(parse_mac("00:1a:2b:3c:4d:5e"), Some(mac));
+        assert_eq!(parse_mac("00-1A-2B-3C-4D-5E"), Some(mac));
+        assert_eq!(format_mac(mac), "00:1a:2b:3c:4d:5e");
+        assert_eq!(parse_mac("00:1a:2b:3c:4d"), None);
+        assert_eq!(parse_mac("00:1a:2b:3c:4d:5e:6f"), None);
+        assert_eq!(parse_mac("0:1a:2b:3c:4d:5e"), None);
+        assert_eq!(parse_mac("00:00:00:00:00:00"), None);
+    }
+
+    #[test]
+    fn magic_packet_repeats_the_mac_after_the_sync_stream() {
+        let mac = [1, 2, 3, 4, 5, 6];
+        let packet = magic_packet(mac);
+        assert_eq!(&packet[..6], &[0xff; 6]);
+        assert!(packet[6..].chunks(6).all(|chunk| chunk == mac));
+    }
+}
@@ -14,8 +14,8 @@ use irpc::{
 use zedra_rpc::proto::*;
 
 use crate::{
-    ReconnectReason, register_active_connection, signer::ClientSigner, terminal::RemoteTerminal,
-    unregister_active_connection,
+    HostFeature, ReconnectReason, register_active_connection, signer::ClientSigner,
+    terminal::RemoteTerminal, unregister_active_connection,
 };
 
 #[derive(Clone)]
@@ -23,6 +23,8 @@ pub struct SessionHandle(Arc<SessionHandleInner>);
 
 struct SessionHandleInner {
     sid: Mutex<Option<String>>,
+    /// `host_version` from the last `SyncSession`; gates [`HostFeature`]s.
+    host_version: Mutex<Option<String>>,
     endpoint_addr: Mutex<Option<iroh::EndpointAddr>>,
     signer: Mutex<Option<Arc<dyn ClientSigner>>>,
     session_token: Mutex<Option<[u8; 32]>>,
@@ -37,6 +39,14 @@ struct SessionHandleInner {
     fs_search_rpc_supported: AtomicBool,
     fs_upload_rpc_supported: AtomicBool,
     set_app_state_rpc_supported: AtomicBool,
+    data_saver_rpc_supported: AtomicBool,
+    fs_read_head_rpc_supported: AtomicBool,
+    fs_text_rpc_supported: AtomicBool,
+    fs_binary_rpc_supported: AtomicBool,
+    term_create_cwd_rpc_supported: AtomicBool,
+    structured_diff_rpc_supported: AtomicBool,
+    ai_prompt_rpc_supported: AtomicBool,
+    fs_grep_context_rpc_supported: AtomicBool,
     /// Runtime the terminal pump tasks spawn onto. Set by `Session::new` so
     /// `attach_remote` works even when a method is awaited from the GPUI thread.
     runtime: Mutex<Option<tokio::runtime::Handle>>,
@@ -63,6 +73,7 @@ impl SessionHandle {
     pub fn new() -> Self {
         Self(Arc::new(SessionHandleInner {
             sid: Mutex::new(None),
+            host_version: Mutex::new(None),
             endpoint_addr: Mutex::new(None),
             signer: Mutex::new(None),
             session_token: Mutex::new(None),
@@ -77,6 +88,14 @@ impl SessionHandle {
             fs_search_rpc_supported: AtomicBool::new(true),
             fs_upload_rpc_supported: AtomicBool::new(true),
             set_app_state_rpc_supported: AtomicBool::new(true),
+            data_saver_rpc_supported: AtomicBool::new(true),
+            fs_read_head_rpc_supported: AtomicBool::new(true),
+            fs_text_rpc_supported: AtomicBool::new(true),
+            fs_binary_rpc_supported: AtomicBool::new(true),
+            term_create_cwd_rpc_supported: AtomicBool::new(true),
+            structured_diff_rpc_supported: AtomicBool::new(true),
+            ai_prompt_rpc_supported: AtomicBool::new(true),
+            fs_grep_context_rpc_supported: AtomicBool::new(true),
             runtime: Mutex::new(None),
         }))
     }
@@ -157,6 +176,35 @@ impl SessionHandle {
         *self.0.sid.lock().unwrap() = session_id;
     }
 
+    pub fn host_version(&self) -> Option<String> {
+        self.0.host_version.lock().ok()?.clone()
+    }
+
+    pub fn set_host_version(&self, host_version: Option<String>) {
+        *self.0.host_version.lock().unwrap() = host_version;
+    }
+
+    /// Whether the connected host serves `feature`: its reported version is
+    /// new enough and the feature's RPC has not failed to decode there.
+    pub fn supports(&self, feature: HostFeature) -> bool {
+        let rpc_supported = match feature {
+            HostFeature::StructuredDiff => &self.0.structured_diff_rpc_supported,
+            HostFeature::StreamingAi => &self.0.ai_prompt_rpc_supported,
+            HostFeature::Search => &self.0.fs_search_rpc_supported,
+        };
+        rpc_supported.load(Ordering::Acquire)
+            && feature.supported_by(self.host_version().as_deref())
+    }
+
+    /// Error returned in place of calling an RPC the host lacks.
+    fn unsupported(feature: HostFeature) -> anyhow::Error {
+        anyhow::anyhow!(
+            "{} needs zedra-host {} or newer",
+            feature.label(),
+            feature.min_host_version()
+        )
+    }
+
     pub fn set_rpc_client(&self, client: irpc::Client<ZedraProto>) {
         *self.0.rpc_client.lock().unwrap() = Some(client);
     }
@@ -372,8 +420,8 @@ impl SessionHandle {
     }
 
     pub async fn fs_search(&self, path: &str, query: &str, limit: u32) -> Result<FsSearchResult> {
-        if !self.0.fs_search_rpc_supported.load(Ordering::Acquire) {
-            return Err(anyhow::anyhow!("file search RPC unsupported by host"));
+        if !self.supports(HostFeature::Search) {
+            return Err(Self::unsupported(HostFeature::Search));
         }
         let result: FsSearchResult = match self
             .call(FsSearchReq {
@@ -386,7 +434,7 @@ impl SessionHandle {
             Ok(result) => result,
             Err(error) => {
                 if self.downgrade_fs_search_rpc(&error.to_string()) {
-                    return Err(anyhow::anyhow!("file search RPC unsupported by host"));
+                    return Err(Self::unsupported(HostFeature::Search));
                 }
                 return Err(error);
             }
@@ -397,6 +445,234 @@ impl SessionHandle {
         Ok(result)
     }
 
+    /// Start a recursive search under `req.path`. Hits arrive as
+    /// `FsGrepUpdate::Matches` batches and the stream ends with `Done`;
+    /// dropping the receiver stops the host's walk.
+    pub async fn fs_grep(&self, req: FsGrepReq) -> Result<mpsc::Receiver<FsGrepUpdate>> {
+        if !self.supports(HostFeature::Search) {
+            return Err(Self::unsupported(HostFeature::Search));
+        }
+        match self.client()?.server_streaming(req, 8).await {
+            Ok(rx) => Ok(rx),
+            Err(error) => {
+                let error = map_rpc_error(error);
+                if self.downgrade_fs_search_rpc(&error.to_string()) {
+                    return Err(Self::unsupported(HostFeature::Search));
+                }
+                Err(error)
+            }
+        }
+    }
+
+    /// [`Self::fs_grep`] with up to `req.context` lines around each content
+    /// hit, sent as `FsGrepUpdate::ContextMatches`. Hosts without `FsGrepV2`
+    /// get the plain request and answer with bare `Matches`.
+    pub async fn fs_grep_with_context(
+        &self,
+        req: FsGrepReqV2,
+    ) -> Result<mpsc::Receiver<FsGrepUpdate>> {
+        if !self.supports(HostFeature::Search) {
+            return Err(Self::unsupported(HostFeature::Search));
+        }
+        if self.0.fs_grep_context_rpc_supported.load(Ordering::Acquire) {
+            match self.client()?.server_streaming(req.clone(), 8).await {
+                Ok(rx) => return Ok(rx),
+                Err(error) => {
+                    let error = map_rpc_error(error);
+                    if !self.downgrade_fs_grep_context_rpc(&error.to_string()) {
+                        return Err(error);
+                    }
+                }
+            }
+        }
+        self.fs_grep(req.without_context()).await
+    }
+
+    /// Up to `radius` lines on each side of 1-based `line` in `path`, for
+    /// expanding a search hit in place. Hosts without `FsGrepExpand` get an
+    /// `fs_read_text` cut down to the same window.
+    pub async fn fs_grep_expand(
+        &self,
+        path: &str,
+        line: u32,
+        radius: u32,
+    ) -> Result<FsGrepExpandResult> {
+        if self.0.fs_grep_context_rpc_supported.load(Ordering::Acquire) {
+            let result: Result<FsGrepExpandResult> = self
+                .call(FsGrepExpandReq {
+                    path: path.to_string(),
+                    line,
+                    radius,
+                })
+                .await;
+            match result {
+                Ok(result) => {
+                    if let Some(e) = result.error {
+                        return Err(anyhow::anyhow!(e));
+                    }
+                    return Ok(result);
+                }
+                Err(e) => {
+                    if !self.downgrade_fs_grep_context_rpc(&e.to_string()) {
+                        return Err(e);
+                    }
+                }
+            }
+        }
+        let read = self.fs_read_text(path).await?;
+        if let Some(e) = read.error {
+            return Err(anyhow::anyhow!(e));
+        }
+        anyhow::ensure!(!read.too_large, "file too large to expand");
+        let lines: Vec<&str> = read.content.lines().collect();
+        anyhow::ensure!(
+            line >= 1 && line as usize <= lines.len(),
+            "line {line} is outside the file"
+        );
+        let radius = radius.min(FS_GREP_MAX_EXPAND_RADIUS) as usize;
+        let index = line as usize - 1;
+        let start = index.saturating_sub(radius);
+        let end = (index + 1 + radius).min(lines.len());
+        Ok(FsGrepExpandResult {
+            start_line: start as u32 + 1,
+            lines: lines[start..end]
+                .iter()
+                .map(|line| line.to_string())
+                .collect(),
+            total_lines: lines.len() as u32,
+            error: None,
+        })
+    }
+
+    /// Preview a find-and-replace under `path`. Nothing is written; pass the
+    /// returned files to [`Self::fs_replace_apply`] to rewrite them.
+    pub async fn fs_replace_preview(
+        &self,
+        path: &str,
+        find: &str,
+        replace: &str,
+        regex: bool,
+        case_sensitive: bool,
+    ) -> Result<FsReplaceResult> {
+        self.fs_replace(FsReplaceReq {
+            path: path.to_string(),
+            find: find.to_string(),
+            replace: replace.to_string(),
+            regex,
+            case_sensitive,
+            dry_run: true,
+            files: vec![],
+        })
+        .await
+    }
+
+    /// Apply a previewed replace. Files changed since the preview come back
+    /// as `FsReplaceStatus::Conflict` and are left untouched.
+    pub async fn fs_replace_apply(
+        &self,
+        path: &str,
+        find: &str,
+        replace: &str,
+        regex: bool,
+        case_sensitive: bool,
+        preview: &[FsReplaceFile],
+    ) -> Result<FsReplaceResult> {
+        self.fs_replace(FsReplaceReq {
+            path: path.to_string(),
+            find: find.to_string(),
+            replace: replace.to_string(),
+            regex,
+            case_sensitive,
+            dry_run: false,
+            files: preview
+                .iter()
+                .map(|file| FsReplaceTarget {
+                    rel_path: file.rel_path.clone(),
+                    fingerprint: file.fingerprint.clone(),
+                })
+                .collect(),
+        })
+        .await
+    }
+
+    /// Run the workspace checker (`cargo check` or `tsc`) under `path`.
+    pub async fn diagnostics_check(&self, path: &str) -> Result<DiagnosticsCheckResult> {
+        let result: DiagnosticsCheckResult = self
+            .call(DiagnosticsCheckReq {
+                path: path.to_string(),
+            })
+            .await?;
+        if let Some(e) = result.error {
+            return Err(anyhow::anyhow!(e));
+        }
+        Ok(result)
+    }
+
+    /// Report whether the host shell's rc file has the OSC 133 snippet, adding
+    /// it when `install` is set.
+    pub async fn shell_integration(&self, install: bool) -> Result<ShellIntegrationResult> {
+        let result: ShellIntegrationResult = self.call(ShellIntegrationReq { install }).await?;
+        if let Some(e) = result.error {
+            return Err(anyhow::anyhow!(e));
+        }
+        Ok(result)
+    }
+
+    /// Recent commands and directories from the host shell history.
+    pub async fn shell_history(&self, limit: u32) -> Result<ShellHistoryResult> {
+        let result: ShellHistoryResult = self.call(ShellHistoryReq { limit }).await?;
+        if let Some(e) = result.error {
+            return Err(anyhow::anyhow!(e));
+        }
+        Ok(result)
+    }
+
+    /// Tasks declared in the workspace's package.json, Makefile, Cargo
+    /// aliases, and justfile.
+    pub async fn task_discover(&self) -> Result<TaskDiscoverResult> {
+        let result: TaskDiscoverResult = self.call(TaskDiscoverReq {}).await?;
+        if let Some(e) = result.error {
+            return Err(anyhow::anyhow!(e));
+        }
+        Ok(result)
+    }
+
+    /// Replace the environment overrides for terminals and checks this
+    /// session starts.
+    pub async fn session_env_set(&self, vars: Vec<SessionEnvVar>) -> Result<()> {
+        let result: SessionEnvSetResult = self.call(SessionEnvSetReq { vars }).await?;
+        if let Some(e) = result.error {
+            return Err(anyhow::anyhow!(e));
+        }
+        Ok(())
+    }
+
+    /// Replace which host watchers push `HostEvent::Notification` to this session.
+    pub async fn host_notify_set(&self, config: HostNotifyConfig) -> Result<()> {
+        let result: HostNotifySetResult = self.call(HostNotifySetReq { config }).await?;
+        if let Some(e) = result.error {
+            return Err(anyhow::anyhow!(e));
+        }
+        Ok(())
+    }
+
+    /// What the session's environment overrides resolve to on the host.
+    pub async fn session_env_get(&self) -> Result<Vec<SessionEnvValue>> {
+        let result: SessionEnvGetResult = self.call(SessionEnvGetReq {}).await?;
+        if let Some(e) = result.error {
+            return Err(anyhow::anyhow!(e));
+        }
+        Ok(result.values)
+    }
+
+    async fn fs_replace(&self, req: FsReplaceReq) -> Result<FsReplaceResult> {
+        let result: FsReplaceResult = self.call(req).await?;
+        if let Some(e) = result.error {
+            return Err(anyhow::anyhow!(e));
+        }
+        Ok(result)
+    }
+
     pub async fn fs_read(&self, path: &str) -> Result<FsReadResult> {
         Ok(self
             .call(FsReadReq {
@@ -405,6 +681,186 @@ impl SessionHandle {
             .await?)
     }
 
+    /// Read at most `max_bytes` from the start of `path`, cut at a line
+    /// boundary. Returns the result and whether the file continues past it.
+    /// Hosts without `FsReadHead` get a whole-file `fs_read` instead.
+    pub async fn fs_read_head(&self, path: &str, max_bytes: u64) -> Result<(FsReadResult, bool)> {
+        if self.0.fs_read_head_rpc_supported.load(Ordering::Acquire) {
+            let result: Result<FsReadHeadResult> = self
+                .call(FsReadHeadReq {
+                    path: path.to_string(),
+                    max_bytes,
+                })
+                .await;
+            match result {
+                Ok(head) => {
+                    let read = FsReadResult {
+                        content: head.content,
+                        too_large: false,
+                        error: head.error,
+                    };
+                    return Ok((read, head.truncated));
+                }
+                Err(e) => {
+                    if !self.downgrade_fs_read_head_rpc(&e.to_string()) {
+                        return Err(e);
+                    }
+                }
+            }
+        }
+        Ok((self.fs_read(path).await?, false))
+    }
+
+    /// Read `path` as text with its encoding and line ending. Hosts without
+    /// `FsReadText` get an `fs_read`, reported as UTF-8 with CRLF turned into LF.
+    pub async fn fs_read_text(&self, path: &str) -> Result<FsReadTextResult> {
+        if self.0.fs_text_rpc_supported.load(Ordering::Acquire) {
+            let result: Result<FsReadTextResult> = self
+                .call(FsReadTextReq {
+                    path: path.to_string(),
+                })
+                .await;
+            match result {
+                Ok(read) => return Ok(read),
+                Err(e) => {
+                    if !self.downgrade_fs_text_rpc(&e.to_string()) {
+                        return Err(e);
+                    }
+                }
+            }
+        }
+        let read = self.fs_read(path).await?;
+        let line_ending = if read.content.contains("\r\n") {
+            LineEnding::Crlf
+        } else {
+            LineEnding::Lf
+        };
+        Ok(FsReadTextResult {
+            content: read.content.replace("\r\n", "\n"),
+            too_large: read.too_large,
+            line_ending,
+            error: read.error,
+            ..Default::default()
+        })
+    }
+
+    /// Write `content` (LF line endings) to `path`. `None` keeps the file's
+    /// current encoding or line ending. Returns what the file was written as.
+    pub async fn fs_write_text(
+        &self,
+        path: &str,
+        content: &str,
+        encoding: Option<TextEncoding>,
+        line_ending: Option<LineEnding>,
+    ) -> Result<FsWriteTextResult> {
+        if !self.0.fs_text_rpc_supported.load(Ordering::Acquire) {
+            return Err(anyhow::anyhow!(
+                "text format conversion not supported by host; update the Zedra host"
+            ));
+        }
+        let result: FsWriteTextResult = match self
+            .call(FsWriteTextReq {
+                path: path.to_string(),
+                content: content.to_string(),
+                encoding,
+                line_ending,
+            })
+            .await
+        {
+            Ok(result) => result,
+            Err(error) => {
+                if self.downgrade_fs_text_rpc(&error.to_string()) {
+                    return Err(anyhow::anyhow!(
+                        "text format conversion not supported by host; update the Zedra host"
+                    ));
+                }
+                return Err(error);
+            }
+        };
+        if let Some(e) = result.error {
+            return Err(anyhow::anyhow!(e));
+        }
+        Ok(result)
+    }
+
+    /// Read a whole file as bytes. Returns `None` when it is over
+    /// `FS_READ_BINARY_MAX`; read those with `fs_read_range` or `fs_download`.
+    pub async fn fs_read_binary(&self, path: &str) -> Result<Option<Vec<u8>>> {
+        self.ensure_fs_binary_rpc()?;
+        let result: FsReadBinaryResult = self
+            .call(FsReadBinaryReq {
+                path: path.to_string(),
+            })
+            .await
+            .map_err(|error| self.fs_binary_rpc_error(error))?;
+        if let Some(e) = result.error {
+            return Err(anyhow::anyhow!(e));
+        }
+        Ok((!result.too_large).then_some(result.data))
+    }
+
+    /// Read up to `max_bytes` (clamped by the host to `FS_READ_RANGE_MAX`)
+    /// starting at `offset`.
+    pub async fn fs_read_range(
+        &self,
+        path: &str,
+        offset: u64,
+        max_bytes: u64,
+    ) -> Result<FsReadRangeResult> {
+        self.ensure_fs_binary_rpc()?;
+        let result: FsReadRangeResult = self
+            .call(FsReadRangeReq {
+                path: path.to_string(),
+                offset,
+                max_bytes,
+            })
+            .await
+            .map_err(|error| self.fs_binary_rpc_error(error))?;
+        if let Some(e) = result.error {
+            return Err(anyhow::anyhow!(e));
+        }
+        Ok(result)
+    }
+
+    /// Read a file of any size in `FS_READ_RANGE_MAX` chunks. `on_progress`
+    /// gets the bytes read so far and the file's size after each chunk.
+    pub async fn fs_download(
+        &self,
+        path: &str,
+        mut on_progress: impl FnMut(u64, u64),
+    ) -> Result<Vec<u8>> {
+        let mut data = Vec::new();
+        loop {
+            let result = self
+                .fs_read_range(path, data.len() as u64, FS_READ_RANGE_MAX)
+                .await?;
+            let empty = result.data.is_empty();
+            data.extend_from_slice(&result.data);
+            on_progress(data.len() as u64, result.total_bytes);
+            if result.eof || empty {
+                return Ok(data);
+            }
+        }
+    }
+
+    fn ensure_fs_binary_rpc(&self) -> Result<()> {
+        if self.0.fs_binary_rpc_supported.load(Ordering::Acquire) {
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!(
+                "binary file reads not supported by host; update the Zedra host"
+            ))
+        }
+    }
+
+    fn fs_binary_rpc_error(&self, error: anyhow::Error) -> anyhow::Error {
+        if self.downgrade_fs_binary_rpc(&error.to_string()) {
+            anyhow::anyhow!("binary file reads not supported by host; update the Zedra host")
+        } else {
+            error
+        }
+    }
+
     pub async fn fs_write(&self, path: &str, content: &str) -> Result<()> {
         let _: FsWriteResult = self
             .call(FsWriteReq {
@@ -415,6 +871,60 @@ impl SessionHandle {
         Ok(())
     }
 
+    /// Move `from` to `to`. Fails if `to` exists; a move across filesystems
+    /// fails with an error starting with `FS_CROSS_DEVICE_ERROR`.
+    pub async fn fs_rename(&self, from: &str, to: &str) -> Result<()> {
+        let result: FsRenameResult = self
+            .call(FsRenameReq {
+                from: from.to_string(),
+                to: to.to_string(),
+            })
+            .await?;
+        if let Some(e) = result.error {
+            return Err(anyhow::anyhow!(e));
+        }
+        Ok(())
+    }
+
+    /// Copy `from` to `to`, which must not exist. Directories need `recursive`.
+    pub async fn fs_copy(&self, from: &str, to: &str, recursive: bool) -> Result<()> {
+        let result: FsCopyResult = self
+            .call(FsCopyReq {
+                from: from.to_string(),
+                to: to.to_string(),
+                recursive,
+            })
+            .await?;
+        if let Some(e) = result.error {
+            return Err(anyhow::anyhow!(e));
+        }
+        Ok(())
+    }
+
+    /// Delete up to `FS_REMOVE_MAX_PATHS` files and directories. The host
+    /// keeps going past a failure; the failed paths come back.
+    pub async fn fs_remove(&self, paths: Vec<String>) -> Result<Vec<FsPathError>> {
+        let result: FsRemoveResult = self.call(FsRemoveReq { paths }).await?;
+        if let Some(e) = result.error {
+            return Err(anyhow::anyhow!(e));
+        }
+        Ok(result.failed)
+    }
+
+    /// Pack `paths` into a new `.tar.gz` at `to`, which must not exist.
+    pub async fn fs_archive(&self, paths: Vec<String>, to: &str) -> Result<()> {
+        let result: FsArchiveResult = self
+            .call(FsArchiveReq {
+                paths,
+                to: to.to_string(),
+            })
+            .await?;
+        if let Some(e) = result.error {
+            return Err(anyhow::anyhow!(e));
+        }
+        Ok(())
+    }
+
     /// Uploads image bytes to the host, which stores them under a
     /// workspace-relative uploads directory and returns that path.
     pub async fn fs_upload(&self, data: Vec<u8>, extension: &str) -> Result<String> {
@@ -569,6 +1079,10 @@ impl SessionHandle {
         self.downgrade_rpc(&self.0.docs_tree_rpc_supported, "docs tree", err)
     }
 
+    fn downgrade_term_create_cwd_rpc(&self, err: &str) -> bool {
+        self.downgrade_rpc(&self.0.term_create_cwd_rpc_supported, "terminal cwd", err)
+    }
+
     fn downgrade_fs_search_rpc(&self, err: &str) -> bool {
         self.downgrade_rpc(&self.0.fs_search_rpc_supported, "file search", err)
     }
@@ -581,6 +1095,38 @@ impl SessionHandle {
         self.downgrade_rpc(&self.0.set_app_state_rpc_supported, "SetAppState", err)
     }
 
+    fn downgrade_data_saver_rpc(&self, err: &str) -> bool {
+        self.downgrade_rpc(&self.0.data_saver_rpc_supported, "SessionDataSaver", err)
+    }
+
+    fn downgrade_fs_read_head_rpc(&self, err: &str) -> bool {
+        self.downgrade_rpc(&self.0.fs_read_head_rpc_supported, "FsReadHead", err)
+    }
+
+    fn downgrade_fs_text_rpc(&self, err: &str) -> bool {
+        self.downgrade_rpc(&self.0.fs_text_rpc_supported, "FsReadText", err)
+    }
+
+    fn downgrade_fs_binary_rpc(&self, err: &str) -> bool {
+        self.downgrade_rpc(&self.0.fs_binary_rpc_supported, "FsReadBinary", err)
+    }
+
+    fn downgrade_structured_diff_rpc(&self, err: &str) -> bool {
+        self.downgrade_rpc(
+            &self.0.structured_diff_rpc_supported,
+            "GitDiffStructured",
+            err,
+        )
+    }
+
+    fn downgrade_ai_prompt_rpc(&self, err: &str) -> bool {
+        self.downgrade_rpc(&self.0.ai_prompt_rpc_supported, "AiPromptV2", err)
+    }
+
+    fn downgrade_fs_grep_context_rpc(&self, err: &str) -> bool {
+        self.downgrade_rpc(&self.0.fs_grep_context_rpc_supported, "FsGrepV2", err)
+    }
+
     // ─── RPC: git ────────────────────────────────────────────────────────────
 
     pub async fn git_status(&self) -> Result<GitStatusResult> {
@@ -604,6 +1150,15 @@ impl SessionHandle {
         Ok(result.diff)
     }
 
+    /// Per-file line counts for every change, without the diff bodies.
+    pub async fn git_diff_stat(&self) -> Result<GitDiffStatResult> {
+        let result: GitDiffStatResult = self.call(GitDiffStatReq {}).await?;
+        if let Some(e) = result.error {
+            return Err(anyhow::anyhow!(e));
+        }
+        Ok(result)
+    }
+
     pub async fn git_log(&self, limit: Option<usize>) -> Result<Vec<GitLogEntry>> {
         let result: GitLogResult = self.call(GitLogReq { limit }).await?;
         if let Some(e) = result.error {
@@ -629,6 +1184,48 @@ impl SessionHandle {
         git_checkout_result(result, branch)
     }
 
+    /// Stash local changes. Returns `false` when there was nothing to stash.
+    pub async fn git_stash(&self, message: Option<&str>, include_untracked: bool) -> Result<bool> {
+        let result: GitStashResult = self
+            .call(GitStashReq {
+                message: message.map(str::to_string),
+                include_untracked,
+            })
+            .await?;
+        if let Some(e) = result.error {
+            return Err(anyhow::anyhow!(e));
+        }
+        Ok(result.stashed)
+    }
+
+    /// Discard all tracked changes, and untracked files when `include_untracked` is set.
+    pub async fn git_discard(&self, include_untracked: bool) -> Result<()> {
+        let result: GitDiscardResult = self.call(GitDiscardReq { include_untracked }).await?;
+        if let Some(e) = result.error {
+            return Err(anyhow::anyhow!(e));
+        }
+        Ok(())
+    }
+
+    pub async fn git_branch_create(
+        &self,
+        name: &str,
+        from: Option<&str>,
+        checkout: bool,
+    ) -> Result<()> {
+        let result: GitBranchCreateResult = self
+            .call(GitBranchCreateReq {
+                name: name.to_string(),
+                from: from.map(str::to_string),
+                checkout,
+            })
+            .await?;
+        if let Some(e) = result.error {
+            return Err(anyhow::anyhow!(e));
+        }
+        Ok(())
+    }
+
     pub async fn git_commit(&self, message: &str, paths: &[String]) -> Result<String> {
         let result: GitCommitResult = self
             .call(GitCommitReq {
@@ -666,32 +1263,298 @@ impl SessionHandle {
         Ok(())
     }
 
+    /// Delete a local branch. A refusal to drop unmerged work stays in the
+    /// result (`unmerged`) so the caller can confirm a forced delete.
+    pub async fn git_branch_delete(
+        &self,
+        name: &str,
+        force: bool,
+    ) -> Result<GitBranchDeleteResult> {
+        self.call(GitBranchDeleteReq {
+            name: name.to_string(),
+            force,
+        })
+        .await
+    }
+
+    /// Merge `branch` into the current branch. Conflicts stay in the result;
+    /// the host has already aborted the merge.
+    pub async fn git_merge(&self, branch: &str) -> Result<GitMergeResult> {
+        self.call(GitMergeReq {
+            branch: branch.to_string(),
+        })
+        .await
+    }
+
+    /// `git_diff` parsed by the host into files, hunks, and lines.
+    pub async fn git_diff_structured(
+        &self,
+        path: Option<&str>,
+        staged: bool,
+    ) -> Result<Vec<GitDiffFile>> {
+        if !self.supports(HostFeature::StructuredDiff) {
+            return Err(Self::unsupported(HostFeature::StructuredDiff));
+        }
+        let result: GitDiffStructuredResult = match self
+            .call(GitDiffStructuredReq {
+                path: path.map(str::to_string),
+                staged,
+            })
+            .await
+        {
+            Ok(result) => result,
+            Err(error) => {
+                if self.downgrade_structured_diff_rpc(&error.to_string()) {
+                    return Err(Self::unsupported(HostFeature::StructuredDiff));
+                }
+                return Err(error);
+            }
+        };
+        if let Some(e) = result.error {
+            return Err(anyhow::anyhow!(e));
+        }
+        Ok(result.files)
+    }
+
+    /// Stage one hunk named by its `@@` header, or unstage it from the
+    /// staged diff.
+    pub async fn git_stage_hunk(&self, path: &str, header: &str, unstage: bool) -> Result<()> {
+        let result: GitStageHunkResult = self
+            .call(GitStageHunkReq {
+                path: path.to_string(),
+                header: header.to_string(),
+                unstage,
+            })
+            .await?;
+        if let Some(e) = result.error {
+            return Err(anyhow::anyhow!(e));
+        }
+        Ok(())
+    }
+
+    /// Todo list for rebasing the last `count` commits, oldest first.
+    pub async fn git_rebase_plan(&self, count: u32) -> Result<GitRebasePlanResult> {
+        let result: GitRebasePlanResult = self.call(GitRebasePlanReq { count }).await?;
+        if let Some(e) = result.error {
+            return Err(anyhow::anyhow!(e));
+        }
+        Ok(result)
+    }
+
+    /// Apply an edited rebase plan; returns the new `HEAD`.
+    pub async fn git_rebase_execute(
+        &self,
+        base: &str,
+        steps: Vec<GitRebaseStep>,
+    ) -> Result<String> {
+        let result: GitRebaseExecuteResult = self
+            .call(GitRebaseExecuteReq {
+                base: base.to_string(),
+                steps,
+            })
+            .await?;
+        if let Some(e) = result.error {
+            return Err(anyhow::anyhow!(e));
+        }
+        Ok(result.head)
+    }
+
+    /// Commit graph of all branches; `limit == 0` uses the host default.
+    pub async fn git_graph(&self, limit: u32) -> Result<GitGraphResult> {
+        let result: GitGraphResult = self.call(GitGraphReq { limit }).await?;
+        if let Some(e) = result.error {
+            return Err(anyhow::anyhow!(e));
+        }
+        Ok(result)
+    }
+
+    /// Commits that touched `path`, newest first, following renames.
+    pub async fn git_file_log(&self, path: &str, limit: u32) -> Result<GitFileLogResult> {
+        let result: GitFileLogResult = self
+            .call(GitFileLogReq {
+                path: path.to_string(),
+                limit,
+            })
+            .await?;
+        if let Some(e) = result.error {
+            return Err(anyhow::anyhow!(e));
+        }
+        Ok(result)
+    }
+
+    /// Content of repository-root-relative `path` at commit `rev`.
+    pub async fn git_show_file(&self, rev: &str, path: &str) -> Result<GitShowFileResult> {
+        let result: GitShowFileResult = self
+            .call(GitShowFileReq {
+                rev: rev.to_string(),
+                path: path.to_string(),
+            })
+            .await?;
+        if let Some(e) = result.error {
+            return Err(anyhow::anyhow!(e));
+        }
+        Ok(result)
+    }
+
+    /// Diff of commit `rev` against its first parent, limited to `paths`.
+    pub async fn git_show_commit(&self, rev: &str, paths: Vec<String>) -> Result<String> {
+        let result: GitShowCommitResult = self
+            .call(GitShowCommitReq {
+                rev: rev.to_string(),
+                paths,
+            })
+            .await?;
+        if let Some(e) = result.error {
+            return Err(anyhow::anyhow!(e));
+        }
+        Ok(result.diff)
+    }
+
+    /// Blame hunks for the working-tree content of `path`, in line order.
+    pub async fn git_blame(&self, path: &str) -> Result<Vec<GitBlameHunk>> {
+        let result: GitBlameResult = self
+            .call(GitBlameReq {
+                path: path.to_string(),
+            })
+            .await?;
+        if let Some(e) = result.error {
+            return Err(anyhow::anyhow!(e));
+        }
+        Ok(result.hunks)
+    }
+
+    /// Open a pull/merge request for a pushed branch on the repo's forge.
+    pub async fn forge_create_pr(&self, req: ForgeCreatePrReq) -> Result<ForgeCreatePrResult> {
+        let result: ForgeCreatePrResult = self.call(req).await?;
+        if let Some(e) = result.error {
+            return Err(anyhow::anyhow!(e));
+        }
+        Ok(result)
+    }
+
+    /// Push a branch; may raise `HostEvent::GitCredentialRequested` while it runs.
+    pub async fn git_push(&self, req: GitPushReq) -> Result<GitPushResult> {
+        let result: GitPushResult = self.call(req).await?;
+        if let Some(e) = result.error {
+            return Err(anyhow::anyhow!(e));
+        }
+        Ok(result)
+    }
+
+    /// Push with an optional force. Git's own failure stays in `error` and
+    /// `failure` so the caller can offer a pull or a force push.
+    pub async fn git_push_v2(&self, req: GitPushReqV2) -> Result<GitPushResultV2> {
+        self.call(req).await
+    }
+
+    /// Fetch a remote. Git's own failure stays in `error` and `failure`.
+    pub async fn git_fetch(&self, req: GitFetchReq) -> Result<GitFetchResult> {
+        self.call(req).await
+    }
+
+    /// Pull into the current branch. Git's own failure stays in `error` and
+    /// `failure` so the caller can retry with another `GitPullMode`.
+    pub async fn git_pull(&self, req: GitPullReq) -> Result<GitPullResult> {
+        self.call(req).await
+    }
+
+    /// Answer a credential prompt; `None` cancels it.
+    pub async fn git_credential_reply(
+        &self,
+        id: &str,
+        credential: Option<GitCredential>,
+    ) -> Result<()> {
+        let result: GitCredentialReplyResult = self
+            .call(GitCredentialReplyReq {
+                id: id.to_string(),
+                credential,
+            })
+            .await?;
+        if let Some(e) = result.error {
+            return Err(anyhow::anyhow!(e));
+        }
+        Ok(())
+    }
+
+    /// Whether the workdir is a repository, and the repositories below it.
+    pub async fn git_discover(&self) -> Result<GitDiscoverResult> {
+        let result: GitDiscoverResult = self.call(GitDiscoverReq {}).await?;
+        if let Some(e) = result.error {
+            return Err(anyhow::anyhow!(e));
+        }
+        Ok(result)
+    }
+
+    /// Run this session's git RPCs in the workdir-relative `path`, running
+    /// `git init` there first when `init` is set.
+    pub async fn git_select_repo(&self, path: &str, init: bool) -> Result<String> {
+        let result: GitSelectRepoResult = self
+            .call(GitSelectRepoReq {
+                path: path.to_string(),
+                init,
+            })
+            .await?;
+        if let Some(e) = result.error {
+            return Err(anyhow::anyhow!(e));
+        }
+        Ok(result.path)
+    }
+
     // ─── RPC: terminals ──────────────────────────────────────────────────────
 
     pub async fn terminal_create(&self, cols: u16, rows: u16) -> Result<String> {
-        self.terminal_create_with_cmd(cols, rows, None, None).await
+        self.terminal_create_with_cmd(cols, rows, None, None, None)
+            .await
     }
 
+    /// Create and attach a terminal. `cwd` is an absolute host directory for
+    /// the shell to start in; hosts without `TermCreateV3` ignore it and start
+    /// in the session workdir.
     pub async fn terminal_create_with_cmd(
         &self,
         cols: u16,
         rows: u16,
         launch_cmd: Option<String>,
         color_scheme: Option<TerminalColorScheme>,
+        cwd: Option<String>,
     ) -> Result<String> {
         // Reuse one client for the create RPC and the attach; re-fetching after
         // the terminal exists could race with handle clearing and fail with
         // "not connected" while the remote terminal is already live.
         let client = self.client()?;
-        let result: TermCreateResult = client
-            .rpc(TermCreateReqV2 {
-                cols,
-                rows,
-                launch_cmd,
-                color_scheme,
-            })
-            .await
-            .map_err(map_rpc_error)?;
+        let mut result = None;
+        if cwd.is_some() && self.0.term_create_cwd_rpc_supported.load(Ordering::Acquire) {
+            let created: Result<TermCreateResult> = client
+                .rpc(TermCreateReqV3 {
+                    cols,
+                    rows,
+                    launch_cmd: launch_cmd.clone(),
+                    color_scheme,
+                    cwd,
+                })
+                .await
+                .map_err(map_rpc_error);
+            match created {
+                Ok(created) => result = Some(created),
+                Err(e) => {
+                    if !self.downgrade_term_create_cwd_rpc(&e.to_string()) {
+                        return Err(e);
+                    }
+                }
+            }
+        }
+        let result = match result {
+            Some(result) => result,
+            None => client
+                .rpc(TermCreateReqV2 {
+                    cols,
+                    rows,
+                    launch_cmd,
+                    color_scheme,
+                })
+                .await
+                .map_err(map_rpc_error)?,
+        };
         if let Some(e) = result.error {
             return Err(anyhow::anyhow!(e));
         }
@@ -735,6 +1598,131 @@ impl SessionHandle {
         Ok(result.files)
     }
 
+    /// Run an AI prompt on the host. Tool uses outside `req.allowed_tools` come
+    /// back in `permission_requests` for the user to allow or deny.
+    pub async fn ai_prompt(&self, req: AiPromptReqV2) -> Result<AiPromptResultV2> {
+        if !self.supports(HostFeature::StreamingAi) {
+            return Err(Self::unsupported(HostFeature::StreamingAi));
+        }
+        let result: AiPromptResultV2 = match self.call(req).await {
+            Ok(result) => result,
+            Err(error) => {
+                if self.downgrade_ai_prompt_rpc(&error.to_string()) {
+                    return Err(Self::unsupported(HostFeature::StreamingAi));
+                }
+                return Err(error);
+            }
+        };
+        if let Some(e) = result.error {
+            return Err(anyhow::anyhow!(e));
+        }
+        Ok(result)
+    }
+
+    /// Persisted AI conversations for this workspace, newest first.
+    pub async fn ai_conversation_list(&self) -> Result<Vec<AiConversationSummary>> {
+        let result: AiConversationListResult = self.call(AiConversationListReq {}).await?;
+        if let Some(e) = result.error {
+            return Err(anyhow::anyhow!(e));
+        }
+        Ok(result.conversations)
+    }
+
+    pub async fn ai_conversation_get(&self, id: String) -> Result<AiConversation> {
+        let result: AiConversationGetResult = self.call(AiConversationGetReq { id }).await?;
+        if let Some(e) = result.error {
+            return Err(anyhow::anyhow!(e));
+        }
+        result
+            .conversation
+            .ok_or_else(|| anyhow::anyhow!("host returned no conversation"))
+    }
+
+    pub async fn ai_conversation_delete(&self, id: String) -> Result<()> {
+        let result: AiConversationDeleteResult = self.call(AiConversationDeleteReq { id }).await?;
+        if let Some(e) = result.error {
+            return Err(anyhow::anyhow!(e));
+        }
+        Ok(())
+    }
+
+    /// Bookmarked and running project directories on the connected host.
+    pub async fn host_projects(&self) -> Result<Vec<HostProject>> {
+        let result: HostProjectsResult = self.call(HostProjectsReq {}).await?;
+        if let Some(e) = result.error {
+            return Err(anyhow::anyhow!(e));
+        }
+        Ok(result.projects)
+    }
+
+    /// SSH hosts imported on the host. Returns `None` when no import has run.
+    pub async fn hosts_list(&self) -> Result<Option<Vec<SshHost>>> {
+        let result: HostsListResult = self.call(HostsListReq {}).await?;
+        if let Some(e) = result.error {
+            return Err(anyhow::anyhow!(e));
+        }
+        Ok(result.imported.then_some(result.hosts))
+    }
+
+    /// The host's Wake-on-LAN interfaces and whether it accepts `host_power`.
+    pub async fn host_wake_info(&self) -> Result<(Vec<HostWakeInterface>, bool)> {
+        let result: HostWakeInfoResult = self.call(HostWakeInfoReq {}).await?;
+        if let Some(e) = result.error {
+            return Err(anyhow::anyhow!(e));
+        }
+        Ok((result.interfaces, result.power_actions))
+    }
+
+    /// Have the host broadcast a Wake-on-LAN packet for `mac` on its network.
+    pub async fn host_wake(&self, mac: String, broadcast: Option<String>) -> Result<()> {
+        let result: HostWakeResult = self.call(HostWakeReq { mac, broadcast }).await?;
+        if let Some(e) = result.error {
+            return Err(anyhow::anyhow!(e));
+        }
+        Ok(())
+    }
+
+    /// Put the host to sleep or reboot it.
+    pub async fn host_power(&self, action: HostPowerAction) -> Result<()> {
+        let result: HostPowerResult = self.call(HostPowerReq { action }).await?;
+        if let Some(e) = result.error {
+            return Err(anyhow::anyhow!(e));
+        }
+        Ok(())
+    }
+
+    /// Terminal recordings on the host, newest first, and whether the daemon
+    /// is recording new terminals.
+    pub async fn recordings_list(&self) -> Result<(Vec<RecordingEntry>, bool)> {
+        let result: RecordingsListResult = self.call(RecordingsListReq {}).await?;
+        if let Some(e) = result.error {
+            return Err(anyhow::anyhow!(e));
+        }
+        Ok((result.recordings, result.enabled))
+    }
+
+    /// The whole asciicast file of recording `name`, fetched in chunks.
+    pub async fn recording_read(&self, name: &str) -> Result<Vec<u8>> {
+        let mut data = Vec::new();
+        loop {
+            let result: RecordingReadResult = self
+                .call(RecordingReadReq {
+                    name: name.to_string(),
+                    offset: data.len() as u64,
+                    max_bytes: RECORDING_READ_MAX,
+                })
+                .await?;
+            if let Some(e) = result.error {
+                return Err(anyhow::anyhow!(e));
+            }
+            let empty = result.data.is_empty();
+            data.extend_from_slice(&result.data);
+            if result.eof || empty {
+                return Ok(data);
+            }
+        }
+    }
+
     /// Start the host-managed web-client server for `slug` (e.g. `opencode
     /// serve`). Returns `(id, loopback port, first path)` once it is listening.
     pub async fn web_client_start(&self, slug: String) -> Result<(String, u16, String)> {
@@ -785,6 +1773,21 @@ impl SessionHandle {
         }
     }
 
+    /// Ask the host to batch and summarize terminal output for this session.
+    /// Fire-and-forget like `notify_app_state`.
+    pub async fn notify_data_saver(&self, enabled: bool) {
+        if !self.0.data_saver_rpc_supported.load(Ordering::Acquire) {
+            return;
+        }
+        let result: Result<SessionDataSaverResult> =
+            self.call(SessionDataSaverReq { enabled }).await;
+        if let Err(e) = result {
+            if !self.downgrade_data_saver_rpc(&e.to_string()) {
+                tracing::debug!(error = %e, "notify_data_saver failed");
+            }
+        }
+    }
+
     pub async fn set_client_delta_info(
         &self,
         delta_url: String,
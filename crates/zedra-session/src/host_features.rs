@@ -0,0 +1,105 @@
+// Client-side view of what the connected daemon can serve.
+//
+// `SyncSession` reports `host_version`; features newer than that version are
+// turned off up front so the UI can hide them and say which host release adds
+// them, instead of surfacing the host's decode error. The per-RPC downgrade
+// flags on `SessionHandle` still catch hosts that report a new enough version
+// but were built before the RPC landed.
+
+/// A client feature that depends on host RPCs added after the first release.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum HostFeature {
+    /// `GitDiffStructured` / `GitStageHunk`: hunk-level diff staging.
+    StructuredDiff,
+    /// `AiPromptV2`: commit message suggestions and AI edits.
+    StreamingAi,
+    /// `FsSearch` / `FsGrep`: file and content search.
+    Search,
+}
+
+impl HostFeature {
+    pub const ALL: [HostFeature; 3] = [Self::StructuredDiff, Self::StreamingAi, Self::Search];
+
+    /// Short lowercase name for notices.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::StructuredDiff => "hunk staging",
+            Self::StreamingAi => "AI suggestions",
+            Self::Search => "search",
+        }
+    }
+
+    /// First `zedra-host` release that serves this feature.
+    pub fn min_host_version(self) -> &'static str {
+        match self {
+            Self::StructuredDiff | Self::StreamingAi | Self::Search => "0.3.1",
+        }
+    }
+
+    /// Whether a host reporting `host_version` serves this feature. Hosts
+    /// that report no parseable version are assumed current; the RPC
+    /// downgrade flags cover them if they are not.
+    pub fn supported_by(self, host_version: Option<&str>) -> bool {
+        let Some(host) = host_version.and_then(parse_version) else {
+            return true;
+        };
+        parse_version(self.min_host_version()).is_none_or(|min| host >= min)
+    }
+}
+
+/// `major.minor.patch` from a version string such as `v0.3.1-rc.1`. Missing
+/// components read as zero.
+pub fn parse_version(version: &str) -> Option<(u32, u32, u32)> {
+    let version = version.trim().trim_start_matches('v');
+    let core = version.split(['-', '+']).next()?;
+    let mut parts = core.split('.').map(|part| part.parse::<u32>().ok());
+    let major = parts.next()??;
+    let minor = parts.next().unwrap_or(Some(0))?;
+    let patch = parts.next().unwrap_or(Some(0))?;
+    Some((major, minor, patch))
+}
+
+/// "Update zedra-host to 0.3.1 for hunk staging and search", or `None` when
+/// nothing is missing. Names the newest version any missing feature needs.
+pub fn update_notice(missing: &[HostFeature]) -> Option<String> {
+    let version = missing
+        .iter()
+        .map(|feature| feature.min_host_version())
+        .max_by_key(|version| parse_version(version))?;
+    let mut notice = format!("Update zedra-host to {version} for ");
+    for (index, feature) in missing.iter().enumerate() {
+        if index > 0 {
+            notice.push_str(if index + 1 == missing.len() {
+                " and "
+            } else {
+                ", "
+            });
+        }
+        notice.push_str(feature.label());
+    }
+    Some(notice)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gates_features_on_reported_host_version() {
+        assert_eq!(parse_version("v0.3.1-rc.2"), Some((0, 3, 1)));
+        assert_eq!(parse_version("1.2"), Some((1, 2, 0)));
+        assert_eq!(parse_version("dev"), None);
+
+        assert!(!HostFeature::Search.supported_by(Some("0.2.9")));
+        assert!(HostFeature::Search.supported_by(Some("0.3.1")));
+        assert!(HostFeature::Search.supported_by(Some("0.10.0")));
+        assert!(HostFeature::Search.supported_by(None));
+        assert!(HostFeature::Search.supported_by(Some("unknown")));
+
+        assert_eq!(update_notice(&[]), None);
+        assert_eq!(
+            update_notice(&HostFeature::ALL).as_deref(),
+            Some("Update zedra-host to 0.3.1 for hunk staging, AI suggestions and search")
+        );
+    }
+}
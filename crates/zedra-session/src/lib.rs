@@ -1,5 +1,7 @@
 pub mod connect;
 pub mod handle;
+pub mod host_features;
+pub mod mock_host;
 pub mod session;
 pub mod signer;
 pub mod state;
@@ -7,6 +9,8 @@ pub mod terminal;
 
 pub use connect::*;
 pub use handle::*;
+pub use host_features::*;
+pub use mock_host::*;
 pub use session::*;
 pub use state::*;
 pub use terminal::*;
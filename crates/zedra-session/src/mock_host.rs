@@ -0,0 +1,719 @@
+// In-process stand-in for `zedra-host`, for UI work, screenshots and
+// emulator UI tests without a live daemon.
+//
+// `MockHost` serves `ZedraProto` over a local irpc channel, so `SessionHandle`
+// and everything above it run unchanged: the client is the same
+// `irpc::Client<ZedraProto>` a real connection produces. Only the RPCs the
+// main views use are answered; anything else is dropped and fails like a
+// closed connection would.
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+use tracing::debug;
+use zedra_rpc::proto::*;
+
+/// Workspace root the mock session reports.
+pub const MOCK_WORKDIR: &str = "/home/demo/zedra-demo";
+/// `host_version` the mock session reports. Not a release number, so every
+/// [`crate::HostFeature`] reads as supported.
+pub const MOCK_HOST_VERSION: &str = "mock";
+
+const MOCK_HOME: &str = "/home/demo";
+const PROMPT: &str = "\x1b[32mdemo@mock\x1b[0m:\x1b[34m~/zedra-demo\x1b[0m$ ";
+/// Delay before a canned AI answer, so loading states are visible.
+const AI_LATENCY: Duration = Duration::from_millis(800);
+
+const FILES: &[(&str, &str)] = &[
+    (
+        "README.md",
+        "# zedra-demo\n\nA small project served by the mock host.\n\n## Build\n\n```sh\ncargo build\n```\n",
+    ),
+    (
+        "Cargo.toml",
+        "[package]\nname = \"zedra-demo\"\nversion = \"0.1.0\"\nedition = \"2024\"\n",
+    ),
+    (
+        "src/main.rs",
+        "fn main() {\n    let name = zedra_demo::greeting(\"world\");\n    println!(\"{name}\");\n}\n",
+    ),
+    (
+        "src/lib.rs",
+        "/// Greeting for `name`.\npub fn greeting(name: &str) -> String {\n    format!(\"Hello, {name}!\")\n}\n",
+    ),
+    (
+        "docs/guide.md",
+        "# Guide\n\nOpen files from the explorer, run commands in the terminal,\nand stage changes from the git panel.\n",
+    ),
+    ("notes.txt", "todo: add a --loud flag\n"),
+];
+
+const LOG: &[(&str, &str, i64)] = &[
+    ("a1b2c3d", "feat: add greeting helper", 1_760_000_000),
+    ("9f8e7d6", "docs: add guide", 1_759_900_000),
+    ("5e4d3c2", "chore: initial commit", 1_759_800_000),
+];
+
+/// In-memory workspace behind a mock session: files, git state and open
+/// terminals.
+pub struct MockHost {
+    files: BTreeMap<String, String>,
+    staged: BTreeSet<String>,
+    modified: BTreeSet<String>,
+    untracked: BTreeSet<String>,
+    log: Vec<GitLogEntry>,
+    terminals: Vec<String>,
+    /// Open streams from `Subscribe`, `SubscribeHostInfo` and `WebClientWatch`.
+    /// Held so the client's subscription tasks don't see them close.
+    host_events: Option<irpc::channel::mpsc::Sender<HostEvent>>,
+    _host_info: Option<irpc::channel::mpsc::Sender<HostInfoSnapshot>>,
+    _web_clients: Option<irpc::channel::mpsc::Sender<WebClientUpdate>>,
+}
+
+impl Default for MockHost {
+    fn default() -> Self {
+        Self {
+            files: FILES
+                .iter()
+                .map(|(path, content)| (absolute(path), content.to_string()))
+                .collect(),
+            staged: ["src/lib.rs".to_string()].into(),
+            modified: ["README.md".to_string()].into(),
+            untracked: ["notes.txt".to_string()].into(),
+            log: LOG
+                .iter()
+                .map(|(id, message, timestamp)| GitLogEntry {
+                    id: id.to_string(),
+                    message: message.to_string(),
+                    author: "Demo User".to_string(),
+                    timestamp: *timestamp,
+                })
+                .collect(),
+            terminals: Vec::new(),
+            host_events: None,
+            _host_info: None,
+            _web_clients: None,
+        }
+    }
+}
+
+impl MockHost {
+    /// Start a mock host on the current runtime and return a client bound to it.
+    pub fn spawn() -> irpc::Client<ZedraProto> {
+        let (tx, mut rx) = mpsc::channel::<ZedraMessage>(64);
+        tokio::spawn(async move {
+            let mut host = MockHost::default();
+            while let Some(msg) = rx.recv().await {
+                host.handle(msg).await;
+            }
+            debug!("mock host: client dropped");
+        });
+        irpc::Client::local(tx)
+    }
+
+    /// What `SyncSession` would return for the mock workspace.
+    pub fn sync_result() -> SyncSessionResult {
+        SyncSessionResult {
+            session_id: "mock-session".to_string(),
+            session_token: [0; 32],
+            hostname: "mock-host".to_string(),
+            workdir: MOCK_WORKDIR.to_string(),
+            username: "demo".to_string(),
+            home_dir: Some(MOCK_HOME.to_string()),
+            os: Some("linux".to_string()),
+            arch: Some("x86_64".to_string()),
+            os_version: None,
+            host_version: Some(MOCK_HOST_VERSION.to_string()),
+            delta_pubkey: [0; 32],
+            terminals: Vec::new(),
+            ai_provider: AiProviderInfo {
+                kind: "mock".to_string(),
+                model: None,
+                supports_tools: false,
+                error: None,
+            },
+        }
+    }
+
+    async fn handle(&mut self, msg: ZedraMessage) {
+        match msg {
+            ZedraMessage::Ping(msg) => {
+                let timestamp_ms = msg.timestamp_ms;
+                let _ = msg.tx.send(PongResult { timestamp_ms }).await;
+            }
+            ZedraMessage::Subscribe(msg) => self.host_events = Some(msg.tx),
+            ZedraMessage::SubscribeHostInfo(msg) => self._host_info = Some(msg.tx),
+            ZedraMessage::WebClientWatch(msg) => self._web_clients = Some(msg.tx),
+
+            // -- Filesystem --
+            ZedraMessage::FsList(msg) => {
+                let result = self.list(&msg.path, msg.offset, msg.limit);
+                let _ = msg.tx.send(result).await;
+            }
+            ZedraMessage::FsRead(msg) => {
+                let (content, error) = self.read(&msg.path);
+                let _ = msg
+                    .tx
+                    .send(FsReadResult {
+                        content,
+                        too_large: false,
+                        error,
+                    })
+                    .await;
+            }
+            ZedraMessage::FsReadText(msg) => {
+                let (content, error) = self.read(&msg.path);
+                let _ = msg
+                    .tx
+                    .send(FsReadTextResult {
+                        content,
+                        error,
+                        ..Default::default()
+                    })
+                    .await;
+            }
+            ZedraMessage::FsWrite(msg) => {
+                self.write(&msg.path, &msg.content).await;
+                let _ = msg.tx.send(FsWriteResult { ok: true }).await;
+            }
+            ZedraMessage::FsWriteText(msg) => {
+                self.write(&msg.path, &msg.content).await;
+                let _ = msg
+                    .tx
+                    .send(FsWriteTextResult {
+                        encoding: TextEncoding::Utf8,
+                        line_ending: LineEnding::Lf,
+                        error: None,
+                    })
+                    .await;
+            }
+            ZedraMessage::FsStat(msg) => {
+                let result = self.stat(&msg.path);
+                let _ = msg.tx.send(result).await;
+            }
+            ZedraMessage::FsSearch(msg) => {
+                let result = self.search(&msg.query, msg.limit);
+                let _ = msg.tx.send(result).await;
+            }
+
+            // -- Terminal --
+            ZedraMessage::TermCreateV2(msg) => {
+                let id = self.create_terminal();
+                let _ = msg.tx.send(TermCreateResult { id, error: None }).await;
+            }
+            ZedraMessage::TermCreateV3(msg) => {
+                let id = self.create_terminal();
+                let _ = msg.tx.send(TermCreateResult { id, error: None }).await;
+            }
+            ZedraMessage::TermAttach(msg) => {
+                if !self.terminals.contains(&msg.id) {
+                    debug!("mock host: attach to unknown terminal {}", msg.id);
+                    return;
+                }
+                let last_seq = msg.last_seq;
+                let listing = self.listing(MOCK_WORKDIR);
+                tokio::spawn(run_terminal(msg.rx, msg.tx, last_seq, listing));
+            }
+            ZedraMessage::TermResize(msg) => {
+                let _ = msg.tx.send(TermResizeResult { ok: true }).await;
+            }
+            ZedraMessage::TermClose(msg) => {
+                self.terminals.retain(|id| *id != msg.id);
+                let _ = msg.tx.send(TermCloseResult { ok: true }).await;
+            }
+            ZedraMessage::TermList(msg) => {
+                let terminals = self
+                    .terminals
+                    .iter()
+                    .enumerate()
+                    .map(|(position, id)| TermListEntry {
+                        id: id.clone(),
+                        position: position as u64,
+                    })
+                    .collect();
+                let _ = msg.tx.send(TermListResult { terminals }).await;
+            }
+            ZedraMessage::TermReorder(msg) => {
+                let _ = msg
+                    .tx
+                    .send(TermReorderResult {
+                        ok: true,
+                        error: None,
+                    })
+                    .await;
+            }
+
+            // -- Git --
+            ZedraMessage::GitStatus(msg) => {
+                let result = self.git_status();
+                let _ = msg.tx.send(result).await;
+            }
+            ZedraMessage::GitDiff(msg) => {
+                let diff = self.git_diff(msg.path.as_deref(), msg.staged);
+                let _ = msg.tx.send(GitDiffResult { diff, error: None }).await;
+            }
+            ZedraMessage::GitLog(msg) => {
+                let limit = msg.limit.unwrap_or(usize::MAX);
+                let entries = self.log.iter().take(limit).cloned().collect();
+                let _ = msg
+                    .tx
+                    .send(GitLogResult {
+                        entries,
+                        error: None,
+                    })
+                    .await;
+            }
+            ZedraMessage::GitBranches(msg) => {
+                let branches = ["main", "feature/loud-flag"]
+                    .iter()
+                    .map(|name| GitBranchEntry {
+                        name: name.to_string(),
+                        is_head: *name == "main",
+                    })
+                    .collect();
+                let _ = msg
+                    .tx
+                    .send(GitBranchesResult {
+                        branches,
+                        error: None,
+                    })
+                    .await;
+            }
+            ZedraMessage::GitStage(msg) => {
+                for path in &msg.paths {
+                    if self.modified.remove(path) || self.untracked.remove(path) {
+                        self.staged.insert(path.clone());
+                    }
+                }
+                self.emit(HostEvent::GitChanged).await;
+                let _ = msg.tx.send(GitStageResult { error: None }).await;
+            }
+            ZedraMessage::GitUnstage(msg) => {
+                for path in &msg.paths {
+                    if self.staged.remove(path) {
+                        self.modified.insert(path.clone());
+                    }
+                }
+                self.emit(HostEvent::GitChanged).await;
+                let _ = msg.tx.send(GitUnstageResult { error: None }).await;
+            }
+            ZedraMessage::GitCommit(msg) => {
+                let result = self.git_commit(&msg.message, &msg.paths);
+                self.emit(HostEvent::GitChanged).await;
+                let _ = msg.tx.send(result).await;
+            }
+
+            // -- AI --
+            ZedraMessage::AiPromptV2(msg) => {
+                let text = canned_ai_answer(&msg.prompt);
+                tokio::spawn(async move {
+                    tokio::time::sleep(AI_LATENCY).await;
+                    let _ = msg
+                        .tx
+                        .send(AiPromptResultV2 {
+                            text,
+                            session_id: None,
+                            permission_requests: Vec::new(),
+                            error: None,
+                            conversation_id: None,
+                        })
+                        .await;
+                });
+            }
+
+            _ => debug!("mock host: request not served"),
+        }
+    }
+
+    async fn emit(&mut self, event: HostEvent) {
+        if let Some(tx) = &self.host_events
+            && tx.send(event).await.is_err()
+        {
+            self.host_events = None;
+        }
+    }
+
+    fn list(&self, path: &str, offset: u32, limit: u32) -> FsListResult {
+        let dir = absolute(path);
+        if !self.is_dir(&dir) {
+            return FsListResult {
+                entries: Vec::new(),
+                total: 0,
+                has_more: false,
+                error: Some(format!("not a directory: {path}")),
+            };
+        }
+        let mut entries = self.children(&dir);
+        entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then(a.name.cmp(&b.name)));
+        let total = entries.len() as u32;
+        let limit = if limit == 0 {
+            FS_LIST_DEFAULT_LIMIT
+        } else {
+            limit
+        };
+        let page: Vec<FsEntry> = entries
+            .into_iter()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .collect();
+        FsListResult {
+            has_more: offset + (page.len() as u32) < total,
+            entries: page,
+            total,
+            error: None,
+        }
+    }
+
+    /// Direct children of `dir`, with directories inferred from file paths.
+    fn children(&self, dir: &str) -> Vec<FsEntry> {
+        let prefix = format!("{dir}/");
+        let mut seen = HashSet::new();
+        let mut children = Vec::new();
+        for (path, content) in &self.files {
+            let Some(rest) = path.strip_prefix(&prefix) else {
+                continue;
+            };
+            let (name, is_dir) = match rest.split_once('/') {
+                Some((name, _)) => (name, true),
+                None => (rest, false),
+            };
+            if !seen.insert(name) {
+                continue;
+            }
+            children.push(FsEntry {
+                name: name.to_string(),
+                path: format!("{prefix}{name}"),
+                is_dir,
+                size: if is_dir { 0 } else { content.len() as u64 },
+            });
+        }
+        children
+    }
+
+    fn is_dir(&self, path: &str) -> bool {
+        let prefix = format!("{path}/");
+        self.files.keys().any(|file| file.starts_with(&prefix))
+    }
+
+    fn listing(&self, dir: &str) -> String {
+        self.children(dir)
+            .into_iter()
+            .map(|entry| {
+                if entry.is_dir {
+                    format!("\x1b[34m{}\x1b[0m", entry.name)
+                } else {
+                    entry.name
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("  ")
+    }
+
+    fn read(&self, path: &str) -> (String, Option<String>) {
+        match self.files.get(&absolute(path)) {
+            Some(content) => (content.clone(), None),
+            None => (String::new(), Some(format!("no such file: {path}"))),
+        }
+    }
+
+    async fn write(&mut self, path: &str, content: &str) {
+        let path = absolute(path);
+        let relative = path
+            .strip_prefix(&format!("{MOCK_WORKDIR}/"))
+            .unwrap_or(&path)
+            .to_string();
+        let existed = self
+            .files
+            .insert(path.clone(), content.to_string())
+            .is_some();
+        if existed && !self.untracked.contains(&relative) {
+            self.modified.insert(relative);
+        } else if !existed {
+            self.untracked.insert(relative);
+        }
+        let parent = path
+            .rsplit_once('/')
+            .map(|(parent, _)| parent.to_string())
+            .unwrap_or_default();
+        self.emit(HostEvent::FsChanged { path: parent }).await;
+        self.emit(HostEvent::GitChanged).await;
+    }
+
+    fn stat(&self, path: &str) -> FsStatResult {
+        let absolute = absolute(path);
+        let size = self
+            .files
+            .get(&absolute)
+            .map(|content| content.len() as u64);
+        let is_dir = absolute == MOCK_WORKDIR || self.is_dir(&absolute);
+        FsStatResult {
+            error: (size.is_none() && !is_dir).then(|| format!("no such file: {path}")),
+            path: absolute,
+            is_dir,
+            size: size.unwrap_or(0),
+            modified: None,
+        }
+    }
+
+    fn search(&self, query: &str, limit: u32) -> FsSearchResult {
+        let needle = query.to_lowercase();
+        let prefix = format!("{MOCK_WORKDIR}/");
+        let mut entries: Vec<FsSearchEntry> = self
+            .files
+            .keys()
+            .filter_map(|path| {
+                let rel_path = path.strip_prefix(&prefix)?;
+                let lowered = rel_path.to_lowercase();
+                let start = lowered.find(&needle)?;
+                let start = lowered[..start].chars().count() as u32;
+                Some(FsSearchEntry {
+                    path: path.clone(),
+                    rel_path: rel_path.to_string(),
+                    is_dir: false,
+                    match_indices: (start..start + needle.chars().count() as u32).collect(),
+                    worktree: None,
+                })
+            })
+            .collect();
+        let truncated = entries.len() > limit as usize;
+        entries.truncate(limit as usize);
+        FsSearchResult {
+            entries,
+            truncated,
+            error: None,
+        }
+    }
+
+    fn create_terminal(&mut self) -> String {
+        let id = uuid::Uuid::new_v4().to_string();
+        self.terminals.push(id.clone());
+        id
+    }
+
+    fn git_status(&self) -> GitStatusResult {
+        let mut paths: BTreeSet<&String> = self.staged.iter().collect();
+        paths.extend(&self.modified);
+        paths.extend(&self.untracked);
+        let entries = paths
+            .into_iter()
+            .map(|path| GitStatusEntry {
+                path: path.clone(),
+                staged_status: self.staged.contains(path).then(|| "modified".to_string()),
+                unstaged_status: if self.untracked.contains(path) {
+                    Some("untracked".to_string())
+                } else {
+                    self.modified.contains(path).then(|| "modified".to_string())
+                },
+            })
+            .collect();
+        GitStatusResult {
+            branch: "main".to_string(),
+            entries,
+            error: None,
+        }
+    }
+
+    fn git_diff(&self, path: Option<&str>, staged: bool) -> String {
+        let changed = if staged { &self.staged } else { &self.modified };
+        changed
+            .iter()
+            .filter(|changed| path.is_none_or(|path| path == changed.as_str()))
+            .map(|path| {
+                let content = self
+                    .files
+                    .get(&absolute(path))
+                    .map(String::as_str)
+                    .unwrap_or_default();
+                let first = content.lines().next().unwrap_or_default();
+                format!(
+                    "diff --git a/{path} b/{path}\n--- a/{path}\n+++ b/{path}\n@@ -1,1 +1,2 @@\n {first}\n+// edited on the mock host\n"
+                )
+            })
+            .collect()
+    }
+
+    fn git_commit(&mut self, message: &str, paths: &[String]) -> GitCommitResult {
+        if self.staged.is_empty() && paths.is_empty() {
+            return GitCommitResult {
+                hash: String::new(),
+                error: Some("nothing to commit".to_string()),
+            };
+        }
+        for path in paths {
+            self.modified.remove(path);
+            self.untracked.remove(path);
+        }
+        self.staged.clear();
+        let hash = uuid::Uuid::new_v4().simple().to_string()[..7].to_string();
+        self.log.insert(
+            0,
+            GitLogEntry {
+                id: hash.clone(),
+                message: message.to_string(),
+                author: "Demo User".to_string(),
+                timestamp: 1_760_100_000,
+            },
+        );
+        GitCommitResult { hash, error: None }
+    }
+}
+
+/// Resolve a request path the way the host's sandbox does: relative paths
+/// join the workdir.
+fn absolute(path: &str) -> String {
+    let path = path.trim_end_matches('/');
+    if path.is_empty() || path == "." {
+        MOCK_WORKDIR.to_string()
+    } else if path.starts_with('/') {
+        path.to_string()
+    } else {
+        format!("{MOCK_WORKDIR}/{}", path.trim_start_matches("./"))
+    }
+}
+
+fn canned_ai_answer(prompt: &str) -> String {
+    if prompt.to_lowercase().contains("commit") {
+        "feat: update greeting and docs".to_string()
+    } else {
+        "This is a canned answer from the mock host. Connect a real host for AI suggestions."
+            .to_string()
+    }
+}
+
+/// Line-editing echo shell: typed text is echoed back, and Enter answers a
+/// few scripted commands.
+async fn run_terminal(
+    mut input: irpc::channel::mpsc::Receiver<TermInput>,
+    output: irpc::channel::mpsc::Sender<TermOutput>,
+    last_seq: u64,
+    listing: String,
+) {
+    let mut seq = last_seq;
+    if last_seq == 0 {
+        let banner = format!("Connected to the zedra mock host.\r\n{PROMPT}");
+        if !send_output(&output, &mut seq, banner).await {
+            return;
+        }
+    }
+
+    let mut line = String::new();
+    while let Ok(Some(TermInput { data })) = input.recv().await {
+        let text = String::from_utf8_lossy(&data);
+        // Arrow keys and other escape sequences have no meaning here.
+        if text.starts_with('\x1b') {
+            continue;
+        }
+        let mut echo = String::new();
+        for ch in text.chars() {
+            match ch {
+                '\r' | '\n' => {
+                    echo.push_str("\r\n");
+                    echo.push_str(&script_reply(line.trim(), &listing));
+                    echo.push_str(PROMPT);
+                    line.clear();
+                }
+                '\x7f' | '\x08' => {
+                    if line.pop().is_some() {
+                        echo.push_str("\x08 \x08");
+                    }
+                }
+                '\x03' => {
+                    echo.push_str("^C\r\n");
+                    echo.push_str(PROMPT);
+                    line.clear();
+                }
+                ch if ch.is_control() => {}
+                ch => {
+                    line.push(ch);
+                    echo.push(ch);
+                }
+            }
+        }
+        if !echo.is_empty() && !send_output(&output, &mut seq, echo).await {
+            return;
+        }
+    }
+}
+
+async fn send_output(
+    output: &irpc::channel::mpsc::Sender<TermOutput>,
+    seq: &mut u64,
+    data: String,
+) -> bool {
+    *seq += 1;
+    output
+        .send(TermOutput {
+            data: data.into_bytes(),
+            seq: *seq,
+        })
+        .await
+        .is_ok()
+}
+
+fn script_reply(command: &str, listing: &str) -> String {
+    let (program, args) = command.split_once(' ').unwrap_or((command, ""));
+    match program {
+        "" => String::new(),
+        "ls" => format!("{listing}\r\n"),
+        "pwd" => format!("{MOCK_WORKDIR}\r\n"),
+        "echo" => format!("{args}\r\n"),
+        "clear" => "\x1b[2J\x1b[H".to_string(),
+        "git" if args == "status" => {
+            "On branch main\r\nChanges to be committed:\r\n\t\x1b[32mmodified:   src/lib.rs\x1b[0m\r\n\r\nChanges not staged for commit:\r\n\t\x1b[31mmodified:   README.md\x1b[0m\r\n\r\nUntracked files:\r\n\t\x1b[31mnotes.txt\x1b[0m\r\n".to_string()
+        }
+        _ => format!("{program}: not available on the mock host\r\n"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SessionHandle;
+
+    #[tokio::test]
+    async fn serves_files_git_and_an_echo_terminal() {
+        let handle = SessionHandle::new();
+        handle.set_runtime(tokio::runtime::Handle::current());
+        handle.set_rpc_client(MockHost::spawn());
+
+        let (entries, total, has_more) = handle.fs_list(".").await.unwrap();
+        let names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["docs", "src", "Cargo.toml", "README.md", "notes.txt"]
+        );
+        assert_eq!((total, has_more), (5, false));
+        assert!(entries[0].is_dir);
+
+        handle.fs_write("src/new.rs", "// new\n").await.unwrap();
+        assert_eq!(
+            handle.fs_read("src/new.rs").await.unwrap().content,
+            "// new\n"
+        );
+
+        let status = handle.git_status().await.unwrap();
+        let untracked: Vec<&str> = status
+            .entries
+            .iter()
+            .filter(|e| e.unstaged_status.as_deref() == Some("untracked"))
+            .map(|e| e.path.as_str())
+            .collect();
+        assert_eq!(untracked, ["notes.txt", "src/new.rs"]);
+
+        let hash = handle.git_commit("demo", &[]).await.unwrap();
+        assert_eq!(handle.git_log(Some(1)).await.unwrap()[0].id, hash);
+
+        let id = handle.terminal_create(80, 24).await.unwrap();
+        let (input, mut output_rx) = handle.terminal(&id).unwrap().take_chanel().unwrap();
+        input.send(b"pwd\r".to_vec()).await.unwrap();
+        let mut output = String::new();
+        while !output.contains(MOCK_WORKDIR) {
+            let chunk = tokio::time::timeout(Duration::from_secs(1), output_rx.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            output.push_str(&String::from_utf8_lossy(&chunk));
+        }
+    }
+}
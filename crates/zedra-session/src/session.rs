@@ -13,7 +13,8 @@ use zedra_rpc::proto::{
 
 use crate::RemoteTerminal;
 use crate::{
-    ConnectEvent, Connector, ReconnectReason, SessionHandle, SessionState, signer::ClientSigner,
+    AuthOutcome, ConnectEvent, Connector, MockHost, ReconnectReason, SessionHandle, SessionState,
+    signer::ClientSigner,
 };
 
 const AUTO_RECONNECT_MAX_ATTEMPTS: u32 = 3;
@@ -204,140 +205,20 @@ impl Session {
                         handle.set_active_connection(active_connection);
                         handle.set_rpc_client(client.clone());
                         handle.set_session_id(Some(sync.session_id.clone()));
+                        handle.set_host_version(sync.host_version.clone());
                         handle.set_session_token(Some(sync.session_token));
                         handle.set_terminals(terminals);
                         on_connected(&handle);
 
-                        {
-                            let subscribe_handle = handle.clone();
-                            let subscribe_client = client.clone();
-                            let subscribe_abort = abort_signal.clone();
-                            let subscribe_closed = closed_notify.clone();
-                            let subscribe_events = host_event_tx.clone();
-
-                            // Broadcast host events via host_event_tx. Bare spawn:
-                            // already inside the connect-loop task's runtime.
-                            tokio::spawn(async move {
-                                let mut host_events = match subscribe_client
-                                    .server_streaming(SubscribeReq {}, 32)
-                                    .await
-                                {
-                                    Ok(rx) => rx,
-                                    Err(e) => {
-                                        warn!("subscribe failed: {}", e);
-                                        return;
-                                    }
-                                };
-
-                                loop {
-                                    tokio::select! {
-                                        _ = subscribe_abort.cancelled() => break,
-                                        _ = subscribe_closed.notified() => break,
-                                        event = host_events.recv() => {
-                                            let event = match event {
-                                                Ok(Some(event)) => event,
-                                                Ok(None) => {
-                                                    warn!("host event recv channel closed");
-                                                    break;
-                                                },
-                                                Err(e) => {
-                                                    warn!("host event recv failed: {}", e);
-                                                    break;
-                                                }
-                                            };
-                                            Self::handle_host_event(
-                                                &subscribe_handle,
-                                                &subscribe_client,
-                                                &subscribe_events,
-                                                event,
-                                            ).await;
-                                        }
-                                    }
-                                }
-                            });
-                        }
-
-                        {
-                            let subscribe_client = client.clone();
-                            let subscribe_abort = abort_signal.clone();
-                            let subscribe_closed = closed_notify.clone();
-                            let subscribe_info = host_info_tx.clone();
-
-                            tokio::spawn(async move {
-                                let mut snapshots = match subscribe_client
-                                    .server_streaming(SubscribeHostInfoReq {}, 4)
-                                    .await
-                                {
-                                    Ok(rx) => rx,
-                                    Err(e) => {
-                                        warn!("host info subscribe failed: {}", e);
-                                        return;
-                                    }
-                                };
-
-                                loop {
-                                    tokio::select! {
-                                        _ = subscribe_abort.cancelled() => break,
-                                        _ = subscribe_closed.notified() => break,
-                                        snapshot = snapshots.recv() => {
-                                            let snapshot = match snapshot {
-                                                Ok(Some(snapshot)) => snapshot,
-                                                Ok(None) => {
-                                                    warn!("host info recv channel closed");
-                                                    break;
-                                                },
-                                                Err(e) => {
-                                                    warn!("host info recv failed: {}", e);
-                                                    break;
-                                                }
-                                            };
-                                            let _ = subscribe_info.send(snapshot);
-                                        }
-                                    }
-                                }
-                            });
-                        }
-
-                        {
-                            let subscribe_client = client.clone();
-                            let subscribe_abort = abort_signal.clone();
-                            let subscribe_closed = closed_notify.clone();
-                            let subscribe_web = web_client_tx.clone();
-
-                            tokio::spawn(async move {
-                                let mut updates = match subscribe_client
-                                    .server_streaming(WebClientWatchReq {}, 32)
-                                    .await
-                                {
-                                    Ok(rx) => rx,
-                                    Err(e) => {
-                                        warn!("web client subscribe failed: {}", e);
-                                        return;
-                                    }
-                                };
-
-                                loop {
-                                    tokio::select! {
-                                        _ = subscribe_abort.cancelled() => break,
-                                        _ = subscribe_closed.notified() => break,
-                                        update = updates.recv() => {
-                                            let update = match update {
-                                                Ok(Some(update)) => update,
-                                                Ok(None) => {
-                                                    warn!("web client recv channel closed");
-                                                    break;
-                                                },
-                                                Err(e) => {
-                                                    warn!("web client recv failed: {}", e);
-                                                    break;
-                                                }
-                                            };
-                                            let _ = subscribe_web.send(update);
-                                        }
-                                    }
-                                }
-                            });
-                        }
+                        Self::spawn_host_streams(
+                            &handle,
+                            &client,
+                            &abort_signal,
+                            &closed_notify,
+                            &host_event_tx,
+                            &host_info_tx,
+                            &web_client_tx,
+                        );
 
                         ticket = None;
                         session_id = handle.session_id();
@@ -377,6 +258,204 @@ impl Session {
         *task_slot.lock().unwrap() = Some(new_task);
     }
 
+    /// Connect to an in-process [`MockHost`] instead of a daemon. Emits the
+    /// same auth/sync/connected events as [`Session::connect`], so the
+    /// workspace UI runs unchanged; there is no transport to lose, so it never
+    /// reconnects.
+    pub fn connect_mock(&self) {
+        let handle = self.handle.clone();
+        let event_tx = self.event_tx.clone();
+        let abort_signal = self.reset_abort_signal();
+        let closed_notify = self.closed_notify.clone();
+        let host_event_tx = self.host_event_tx.clone();
+        let host_info_tx = self.host_info_tx.clone();
+        let web_client_tx = self.web_client_tx.clone();
+        handle.set_user_disconnect(false);
+
+        let previous_task = self.connect_task.lock().unwrap().take();
+        let task_slot = self.connect_task.clone();
+        let new_task = self.runtime.spawn(async move {
+            if let Some(prev) = previous_task {
+                let _ = prev.await;
+            }
+            let client = MockHost::spawn();
+            let sync = MockHost::sync_result();
+            handle.set_rpc_client(client.clone());
+            handle.set_session_id(Some(sync.session_id.clone()));
+            handle.set_host_version(sync.host_version.clone());
+            handle.set_session_token(Some(sync.session_token));
+            Self::spawn_host_streams(
+                &handle,
+                &client,
+                &abort_signal,
+                &closed_notify,
+                &host_event_tx,
+                &host_info_tx,
+                &web_client_tx,
+            );
+
+            info!("connected to mock host");
+            for event in [
+                ConnectEvent::AuthComplete {
+                    auth_ms: 0,
+                    outcome: AuthOutcome::Authenticated,
+                    is_first_pairing: false,
+                },
+                ConnectEvent::Syncing,
+                ConnectEvent::SyncComplete { sync, sync_ms: 0 },
+                ConnectEvent::Connected { total_ms: 0 },
+            ] {
+                let _ = event_tx.send(event).await;
+            }
+
+            abort_signal.cancelled().await;
+            handle.detach_terminals();
+            handle.clear_rpc_client();
+        });
+        *task_slot.lock().unwrap() = Some(new_task);
+    }
+
+    /// Forward the host's event, resource and web-client streams to the
+    /// session broadcasts until the connection closes or is aborted.
+    fn spawn_host_streams(
+        handle: &SessionHandle,
+        client: &irpc::Client<ZedraProto>,
+        abort_signal: &CancellationToken,
+        closed_notify: &Arc<Notify>,
+        host_event_tx: &broadcast::Sender<HostEvent>,
+        host_info_tx: &broadcast::Sender<HostInfoSnapshot>,
+        web_client_tx: &broadcast::Sender<WebClientUpdate>,
+    ) {
+        {
+            let subscribe_handle = handle.clone();
+            let subscribe_client = client.clone();
+            let subscribe_abort = abort_signal.clone();
+            let subscribe_closed = closed_notify.clone();
+            let subscribe_events = host_event_tx.clone();
+
+            // Broadcast host events via host_event_tx. Bare spawn:
+            // already inside the connect-loop task's runtime.
+            tokio::spawn(async move {
+                let mut host_events =
+                    match subscribe_client.server_streaming(SubscribeReq {}, 32).await {
+                        Ok(rx) => rx,
+                        Err(e) => {
+                            warn!("subscribe failed: {}", e);
+                            return;
+                        }
+                    };
+
+                loop {
+                    tokio::select! {
+                        _ = subscribe_abort.cancelled() => break,
+                        _ = subscribe_closed.notified() => break,
+                        event = host_events.recv() => {
+                            let event = match event {
+                                Ok(Some(event)) => event,
+                                Ok(None) => {
+                                    warn!("host event recv channel closed");
+                                    break;
+                                },
+                                Err(e) => {
+                                    warn!("host event recv failed: {}", e);
+                                    break;
+                                }
+                            };
+                            Self::handle_host_event(
+                                &subscribe_handle,
+                                &subscribe_client,
+                                &subscribe_events,
+                                event,
+                            ).await;
+                        }
+                    }
+                }
+            });
+        }
+
+        {
+            let subscribe_client = client.clone();
+            let subscribe_abort = abort_signal.clone();
+            let subscribe_closed = closed_notify.clone();
+            let subscribe_info = host_info_tx.clone();
+
+            tokio::spawn(async move {
+                let mut snapshots = match subscribe_client
+                    .server_streaming(SubscribeHostInfoReq {}, 4)
+                    .await
+                {
+                    Ok(rx) => rx,
+                    Err(e) => {
+                        warn!("host info subscribe failed: {}", e);
+                        return;
+                    }
+                };
+
+                loop {
+                    tokio::select! {
+                        _ = subscribe_abort.cancelled() => break,
+                        _ = subscribe_closed.notified() => break,
+                        snapshot = snapshots.recv() => {
+                            let snapshot = match snapshot {
+                                Ok(Some(snapshot)) => snapshot,
+                                Ok(None) => {
+                                    warn!("host info recv channel closed");
+                                    break;
+                                },
+                                Err(e) => {
+                                    warn!("host info recv failed: {}", e);
+                                    break;
+                                }
+                            };
+                            let _ = subscribe_info.send(snapshot);
+                        }
+                    }
+                }
+            });
+        }
+
+        {
+            let subscribe_client = client.clone();
+            let subscribe_abort = abort_signal.clone();
+            let subscribe_closed = closed_notify.clone();
+            let subscribe_web = web_client_tx.clone();
+
+            tokio::spawn(async move {
+                let mut updates = match subscribe_client
+                    .server_streaming(WebClientWatchReq {}, 32)
+                    .await
+                {
+                    Ok(rx) => rx,
+                    Err(e) => {
+                        warn!("web client subscribe failed: {}", e);
+                        return;
+                    }
+                };
+
+                loop {
+                    tokio::select! {
+                        _ = subscribe_abort.cancelled() => break,
+                        _ = subscribe_closed.notified() => break,
+                        update = updates.recv() => {
+                            let update = match update {
+                                Ok(Some(update)) => update,
+                                Ok(None) => {
+                                    warn!("web client recv channel closed");
+                                    break;
+                                },
+                                Err(e) => {
+                                    warn!("web client recv failed: {}", e);
+                                    break;
+                                }
+                            };
+                            let _ = subscribe_web.send(update);
+                        }
+                    }
+                }
+            });
+        }
+    }
+
     /// Abort the in-flight connect attempt without marking the session as
     /// manually disconnected. Used when restarting with fresh credentials
     /// (e.g. QR rescan) so the entry stays alive for the new attempt.
@@ -495,6 +574,12 @@ impl Session {
             HostEvent::WebViewRequested { url } => {
                 info!(url = %url, "HostEvent: webview requested");
             }
+            HostEvent::GitCredentialRequested { id, .. } => {
+                info!(id = %id, "HostEvent: git credential requested");
+            }
+            HostEvent::Notification(notification) => {
+                info!(?notification, "HostEvent: host notification");
+            }
         }
 
         let _ = host_event_tx.send(event);
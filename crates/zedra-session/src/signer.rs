@@ -99,3 +99,27 @@ impl ClientSigner for FileClientSigner {
         self.signing_key.sign(data).to_bytes()
     }
 }
+
+/// In-memory client signer for keys the caller stores itself, such as the
+/// app's encrypted vault.
+pub struct KeyClientSigner {
+    signing_key: SigningKey,
+}
+
+impl KeyClientSigner {
+    pub fn from_secret(secret: &[u8; 32]) -> Self {
+        Self {
+            signing_key: SigningKey::from_bytes(secret),
+        }
+    }
+}
+
+impl ClientSigner for KeyClientSigner {
+    fn pubkey(&self) -> [u8; 32] {
+        self.signing_key.verifying_key().to_bytes()
+    }
+
+    fn sign(&self, data: &[u8]) -> [u8; 64] {
+        self.signing_key.sign(data).to_bytes()
+    }
+}
@@ -1,5 +1,7 @@
 use std::fmt;
 
+use zedra_rpc::proto::AiProviderInfo;
+
 use crate::{ConnectEvent, HolePunchStage};
 
 #[derive(Clone, Debug, PartialEq)]
@@ -296,6 +298,7 @@ pub struct ConnectSnapshot {
     pub arch: Option<String>,
     pub os_version: Option<String>,
     pub host_version: Option<String>,
+    pub ai_provider: Option<AiProviderInfo>,
     pub failed_at_step: Option<usize>,
 }
 
@@ -457,6 +460,7 @@ impl SessionState {
                 snap.arch = sync.arch;
                 snap.os_version = sync.os_version;
                 snap.host_version = sync.host_version;
+                snap.ai_provider = Some(sync.ai_provider);
                 snap.sync_ms = Some(sync_ms);
             }
             ConnectEvent::TerminalsReattached { resume_ms, .. } => {
@@ -673,6 +677,7 @@ mod tests {
             host_version: Some("0.1.1".into()),
             delta_pubkey: [7; 32],
             terminals: Vec::new(),
+            ai_provider: Default::default(),
         }
     }
 
@@ -0,0 +1,72 @@
+// Command guard: hold back Enter when the command line about to run looks
+// destructive.
+//
+// `Terminal::handle_keystroke` reads the pending command line (from the last
+// OSC 133 prompt mark, or the cursor row without shell integration) when a
+// plain Enter is pressed. If a rule matches, the Enter is not sent; the
+// terminal emits `TerminalEvent::CommandGuarded` and the app asks the user,
+// calling `Terminal::submit_guarded_command` once they confirm.
+
+use regex::Regex;
+
+#[derive(Clone, Debug)]
+pub struct GuardRule {
+    pub name: String,
+    pub pattern: Regex,
+}
+
+impl GuardRule {
+    pub fn new(name: impl Into<String>, pattern: &str) -> Result<Self, regex::Error> {
+        Ok(Self {
+            name: name.into(),
+            pattern: Regex::new(pattern)?,
+        })
+    }
+}
+
+/// A command line held back because a rule matched it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GuardedCommand {
+    /// Name of the first rule that matched.
+    pub rule: String,
+    /// The command line as drawn on the grid, prompt excluded when known.
+    pub command: String,
+}
+
+/// First rule matching `command`, if any.
+pub(crate) fn check(rules: &[GuardRule], command: &str) -> Option<GuardedCommand> {
+    let command = command.trim();
+    if command.is_empty() {
+        return None;
+    }
+    rules
+        .iter()
+        .find(|rule| rule.pattern.is_match(command))
+        .map(|rule| GuardedCommand {
+            rule: rule.name.clone(),
+            command: command.to_string(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_first_matching_rule() {
+        let rules = vec![
+            GuardRule::new("rm root", r"\brm\s+-\w*r\w*f?\w*\s+/(\s|$)").unwrap(),
+            GuardRule::new("force push", r"\bgit\s+push\b.*(-f\b|--force)").unwrap(),
+        ];
+        assert_eq!(
+            check(&rules, "  git push --force origin main "),
+            Some(GuardedCommand {
+                rule: "force push".into(),
+                command: "git push --force origin main".into(),
+            })
+        );
+        assert_eq!(check(&rules, "rm -rf /").unwrap().rule, "rm root");
+        assert_eq!(check(&rules, "rm -rf ./build"), None);
+        assert_eq!(check(&rules, "   "), None);
+    }
+}
@@ -2,6 +2,7 @@
 // Adapted from vendor/zed/crates/terminal_view/src/terminal_element.rs
 
 use std::mem;
+use std::sync::Arc;
 
 use alacritty_terminal::index::Point as AlacPoint;
 use alacritty_terminal::term::cell::Flags as CellFlags;
@@ -9,13 +10,69 @@ use alacritty_terminal::vte::ansi::{Color as AlacColor, CursorShape, NamedColor}
 use gpui::*;
 use itertools::Itertools;
 
-use crate::MONO_FONT_FAMILY;
 use crate::input::TerminalInputHandler;
+use crate::metrics::CellMetrics;
 use crate::selection::TerminalSelectionDocument;
 use crate::terminal::*;
 use crate::theme::TerminalTheme;
 use crate::view::TerminalView;
 
+/// How cell text is grouped for shaping.
+///
+/// `shape_line` with a forced width places glyph N at N cells, so a ligature
+/// that merges `=>` into one glyph would pull every later glyph in its run one
+/// cell left. Each batch is positioned at its own start column, so splitting
+/// batches bounds any drift to the batch itself.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TerminalShaping {
+    /// One glyph per cell: ligature features are disabled on the terminal font.
+    #[default]
+    PerCell,
+    /// Ligatures are shaped only inside contiguous operator runs (`=>`, `!=`,
+    /// `<=`, `->`, ...), each painted as its own batch at its natural advance.
+    /// Everything else stays per-cell.
+    OperatorLigatures,
+}
+
+/// Characters that may form programming ligatures in monospace fonts.
+const LIGATURE_CHARS: &str = "=!<>-|&+*/:.~^%?#\\";
+
+fn is_ligature_char(c: char) -> bool {
+    LIGATURE_CHARS.contains(c)
+}
+
+/// Font variant picked by a cell's SGR bold and italic flags.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct CellFontStyle {
+    bold: bool,
+    italic: bool,
+}
+
+impl CellFontStyle {
+    fn from_flags(flags: CellFlags) -> Self {
+        Self {
+            bold: flags.contains(CellFlags::BOLD),
+            italic: flags.contains(CellFlags::ITALIC),
+        }
+    }
+
+    fn apply(self, font: &Font) -> Font {
+        Font {
+            weight: if self.bold {
+                FontWeight::BOLD
+            } else {
+                font.weight
+            },
+            style: if self.italic {
+                FontStyle::Italic
+            } else {
+                font.style
+            },
+            ..font.clone()
+        }
+    }
+}
+
 /// A batched text run that combines multiple adjacent cells with the same style
 #[derive(Debug)]
 struct BatchedTextRun {
@@ -24,10 +81,17 @@ struct BatchedTextRun {
     text: String,
     cell_count: usize,
     color: Hsla,
+    style: CellFontStyle,
+    /// Shape with ligatures and natural advances instead of forced cell width.
+    ligatures: bool,
+    /// Holds one cell's whole grapheme: a wide (two-cell) character and/or
+    /// one carrying zero-width combining marks. Nothing appends to it and it
+    /// paints at natural advances, so marks stay on their base glyph.
+    isolated: bool,
 }
 
 impl BatchedTextRun {
-    fn new(line: i32, col: i32, c: char, color: Hsla) -> Self {
+    fn new(line: i32, col: i32, c: char, color: Hsla, style: CellFontStyle) -> Self {
         let mut text = String::with_capacity(100);
         text.push(c);
         BatchedTextRun {
@@ -36,13 +100,46 @@ impl BatchedTextRun {
             text,
             cell_count: 1,
             color,
+            style,
+            ligatures: false,
+            isolated: false,
         }
     }
 
-    fn can_append(&self, line: i32, col: i32, color: Hsla) -> bool {
-        self.start_line == line
+    fn new_isolated(
+        line: i32,
+        col: i32,
+        text: String,
+        cell_count: usize,
+        color: Hsla,
+        style: CellFontStyle,
+    ) -> Self {
+        BatchedTextRun {
+            start_line: line,
+            start_col: col,
+            text,
+            cell_count,
+            color,
+            style,
+            ligatures: false,
+            isolated: true,
+        }
+    }
+
+    fn can_append(
+        &self,
+        line: i32,
+        col: i32,
+        color: Hsla,
+        style: CellFontStyle,
+        ligatures: bool,
+    ) -> bool {
+        !self.isolated
+            && self.ligatures == ligatures
+            && self.start_line == line
             && self.start_col + self.cell_count as i32 == col
             && self.color == color
+            && self.style == style
     }
 
     fn append_char(&mut self, c: char) {
@@ -56,7 +153,7 @@ impl BatchedTextRun {
         cell_width: Pixels,
         line_height: Pixels,
         font_size: Pixels,
-        font: &Font,
+        fonts: &TerminalFonts,
         window: &mut Window,
         cx: &mut App,
     ) {
@@ -66,9 +163,14 @@ impl BatchedTextRun {
             origin.y + self.start_line as f32 * line_height,
         );
 
+        let font = if self.ligatures {
+            &fonts.ligatures
+        } else {
+            &fonts.cells
+        };
         let runs = vec![TextRun {
             len: self.text.len(),
-            font: font.clone(),
+            font: self.style.apply(font),
             color: self.color,
             ..Default::default()
         }];
@@ -76,13 +178,43 @@ impl BatchedTextRun {
         let text_system = window.text_system();
         let shared_text: SharedString = self.text.clone().into();
 
-        // Use force_width to ensure monospace grid alignment
-        let shaped = text_system.shape_line(shared_text, font_size, &runs, Some(cell_width));
+        // Force the cell width for monospace grid alignment. Ligature runs keep the
+        // font's own advances, which monospace ligature glyphs size to whole cells;
+        // isolated runs hold one grapheme, whose combining marks must not be
+        // pushed a cell over.
+        let force_width = (!self.ligatures && !self.isolated).then_some(cell_width);
+        let shaped = text_system.shape_line(shared_text, font_size, &runs, force_width);
 
         let _ = shaped.paint(pos, line_height, TextAlign::Left, None, window, cx);
     }
 }
 
+/// The terminal font with ligatures disabled, and the same font with them on
+/// for [`TerminalShaping::OperatorLigatures`] runs.
+struct TerminalFonts {
+    cells: Font,
+    ligatures: Font,
+}
+
+impl TerminalFonts {
+    fn new(font: &Font) -> Self {
+        Self {
+            cells: Font {
+                features: FontFeatures(Arc::new(vec![
+                    ("calt".into(), 0),
+                    ("liga".into(), 0),
+                    ("dlig".into(), 0),
+                ])),
+                ..font.clone()
+            },
+            ligatures: Font {
+                features: FontFeatures(Arc::new(vec![("calt".into(), 1), ("liga".into(), 1)])),
+                ..font.clone()
+            },
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 struct LayoutRect {
     line: i32,
@@ -157,6 +289,40 @@ impl LayoutUnderline {
         };
         window.paint_quad(fill(Bounds::new(position, size), self.color));
     }
+
+    /// Paint the same span through the middle of the line, for SGR 9.
+    fn paint_strikeout(
+        &self,
+        origin: Point<Pixels>,
+        cell_width: Pixels,
+        line_height: Pixels,
+        window: &mut Window,
+    ) {
+        let thickness = px(1.0);
+        let position = point(
+            (origin.x + self.col as f32 * cell_width).floor(),
+            origin.y + (self.line as f32 + 0.5) * line_height - thickness * 0.5,
+        );
+        let size = gpui::Size {
+            width: (cell_width * self.num_cells as f32).ceil(),
+            height: thickness,
+        };
+        window.paint_quad(fill(Bounds::new(position, size), self.color));
+    }
+
+    /// Grow the last span over `col` when it is the adjacent cell in the same
+    /// color, otherwise start a new one.
+    fn extend_or_push(spans: &mut Vec<Self>, line: i32, col: i32, color: Hsla) {
+        if let Some(last) = spans.last_mut()
+            && last.line == line
+            && last.col + last.num_cells as i32 == col
+            && last.color == color
+        {
+            last.num_cells += 1;
+        } else {
+            spans.push(Self::new(line, col, 1, color));
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -184,6 +350,9 @@ pub struct TerminalElement {
     keyboard_inset: Pixels,
     keyboard_content_offset: Pixels,
     theme: TerminalTheme,
+    font: Font,
+    font_size: Pixels,
+    shaping: TerminalShaping,
     view: WeakEntity<TerminalView>,
     terminal: WeakEntity<Terminal>,
     focus_handle: FocusHandle,
@@ -199,6 +368,9 @@ impl TerminalElement {
         keyboard_inset: Pixels,
         keyboard_content_offset: Pixels,
         theme: TerminalTheme,
+        font: Font,
+        font_size: Pixels,
+        shaping: TerminalShaping,
         view: WeakEntity<TerminalView>,
         terminal: WeakEntity<Terminal>,
         focus_handle: FocusHandle,
@@ -212,6 +384,9 @@ impl TerminalElement {
             keyboard_inset,
             keyboard_content_offset,
             theme,
+            font,
+            font_size,
+            shaping,
             view,
             terminal,
             focus_handle,
@@ -223,16 +398,25 @@ impl TerminalElement {
     /// Layout the grid following Zed's layout_grid approach.
     /// Groups cells by line and batches adjacent cells with the same style.
     /// Only cells within [0, grid_rows) after display_offset adjustment are rendered.
+    /// Returns background rects, text runs, underlines (links and SGR 4), and
+    /// strikeouts (SGR 9).
     fn layout_grid(
         cells: &[IndexedCell],
         display_offset: i32,
         grid_rows: usize,
         theme: &TerminalTheme,
         detected_links: &[DetectedLink],
-    ) -> (Vec<LayoutRect>, Vec<BatchedTextRun>, Vec<LayoutUnderline>) {
+        shaping: TerminalShaping,
+    ) -> (
+        Vec<LayoutRect>,
+        Vec<BatchedTextRun>,
+        Vec<LayoutUnderline>,
+        Vec<LayoutUnderline>,
+    ) {
         let mut batched_runs: Vec<BatchedTextRun> = Vec::new();
         let mut rects: Vec<LayoutRect> = Vec::new();
         let mut underlines: Vec<LayoutUnderline> = Vec::new();
+        let mut strikeouts: Vec<LayoutUnderline> = Vec::new();
         let mut current_batch: Option<BatchedTextRun> = None;
 
         // Group cells by line (following Zed's chunk_by approach)
@@ -296,8 +480,26 @@ impl TerminalElement {
                     }
                 }
 
+                // Decorations cover every cell, spacers included, so a wide
+                // character's line spans both of its cells.
+                let flags = cell.cell.flags;
+                if flags.intersects(CellFlags::ALL_UNDERLINES) {
+                    LayoutUnderline::extend_or_push(&mut underlines, line, col, fg_color);
+                }
+                if flags.contains(CellFlags::STRIKEOUT) {
+                    LayoutUnderline::extend_or_push(&mut strikeouts, line, col, fg_color);
+                }
+
                 // Skip wide character spacers
-                if cell.cell.flags.contains(CellFlags::WIDE_CHAR_SPACER) {
+                if flags.contains(CellFlags::WIDE_CHAR_SPACER) {
+                    continue;
+                }
+
+                // Concealed (SGR 8) text keeps its background but no glyph.
+                if flags.contains(CellFlags::HIDDEN) {
+                    if let Some(batch) = current_batch.take() {
+                        batched_runs.push(batch);
+                    }
                     continue;
                 }
 
@@ -314,18 +516,45 @@ impl TerminalElement {
                 } else {
                     cell.cell.c
                 };
+                let style = CellFontStyle::from_flags(flags);
+
+                // Wide characters get a two-cell batch of their own so the forced
+                // cell width never squeezes the glyph after them; so do cells with
+                // combining marks or emoji modifiers, which alacritty stores as
+                // zero-width chars on the base cell.
+                let wide = flags.contains(CellFlags::WIDE_CHAR);
+                let zerowidth = cell.cell.zerowidth().filter(|chars| !chars.is_empty());
+                if wide || zerowidth.is_some() {
+                    if let Some(batch) = current_batch.take() {
+                        batched_runs.push(batch);
+                    }
+                    let mut text = String::from(c);
+                    text.extend(zerowidth.into_iter().flatten());
+                    let cell_count = if wide { 2 } else { 1 };
+                    current_batch = Some(BatchedTextRun::new_isolated(
+                        line, col, text, cell_count, fg_color, style,
+                    ));
+                    continue;
+                }
+
+                let ligatures =
+                    shaping == TerminalShaping::OperatorLigatures && is_ligature_char(c);
 
                 // Try to batch with existing run
                 if let Some(ref mut batch) = current_batch {
-                    if batch.can_append(line, col, fg_color) {
+                    if batch.can_append(line, col, fg_color, style, ligatures) {
                         batch.append_char(c);
                     } else {
                         // Flush current batch and start new one
                         batched_runs.push(current_batch.take().unwrap());
-                        current_batch = Some(BatchedTextRun::new(line, col, c, fg_color));
+                        let mut batch = BatchedTextRun::new(line, col, c, fg_color, style);
+                        batch.ligatures = ligatures;
+                        current_batch = Some(batch);
                     }
                 } else {
-                    current_batch = Some(BatchedTextRun::new(line, col, c, fg_color));
+                    let mut batch = BatchedTextRun::new(line, col, c, fg_color, style);
+                    batch.ligatures = ligatures;
+                    current_batch = Some(batch);
                 }
             }
         }
@@ -335,7 +564,7 @@ impl TerminalElement {
             batched_runs.push(batch);
         }
 
-        (rects, batched_runs, underlines)
+        (rects, batched_runs, underlines, strikeouts)
     }
 
     fn line_hyperlink_underlines(
@@ -596,33 +825,13 @@ impl Element for TerminalElement {
         // Surface identity for native-selection occlusion checks; Normal
         // behavior so it never blocks other elements.
         let hitbox = window.insert_hitbox(bounds, HitboxBehavior::Normal);
-        // Use JetBrains Mono NL - embedded monospace font (loaded once at app init)
-        let font = Font {
-            family: MONO_FONT_FAMILY.into(),
-            features: FontFeatures::default(),
-            fallbacks: Some(FontFallbacks::from_fonts(vec![
-                "Noto Sans Symbols 2".to_string(),
-                "Apple Symbols".to_string(),
-                "Menlo".to_string(),
-                "Droid Sans Mono".to_string(),
-                "monospace".to_string(),
-            ])),
-            weight: FontWeight::NORMAL,
-            style: FontStyle::Normal,
-        };
-
-        // Use configured line height
-        let line_height = self.size.line_height;
-        // Font size scaled to fit within line height
-        let font_size = line_height * 0.75;
-
-        // Get exact cell width from font metrics using 'm' as reference (following Zed)
-        let text_system = window.text_system();
-        let font_id = text_system.resolve_font(&font);
-        let cell_width = text_system
-            .advance(font_id, font_size, 'm')
-            .map(|size| size.width)
-            .unwrap_or(self.size.cell_width);
+        // Cell width and line height come from the measured metrics of the view's
+        // font (the embedded JetBrains Mono NL unless Settings picks another).
+        let font = self.font.clone();
+        let metrics = CellMetrics::measure(window, &font, self.font_size);
+        let font_size = metrics.font_size;
+        let cell_width = metrics.cell_width;
+        let line_height = metrics.line_height;
 
         TerminalElementLayout {
             content: self.content.clone(),
@@ -675,17 +884,20 @@ impl Element for TerminalElement {
 
         let theme = self.theme;
 
-        // Draw terminal background
-        window.paint_quad(fill(bounds, rgb(theme.background)));
+        // TerminalView paints the background fill; a second translucent quad
+        // here would double its alpha. Default-background cells get no rect
+        // (see `layout_grid`), so they show that fill through.
 
         // Layout the grid (batch text runs, collect background rects)
-        let (rects, batched_runs, underlines) = Self::layout_grid(
+        let (rects, batched_runs, underlines, strikeouts) = Self::layout_grid(
             &layout.content.cells,
             layout.content.display_offset as i32,
             layout.content.grid_rows,
             &theme,
             &layout.content.detected_links,
+            self.shaping,
         );
+        let fonts = TerminalFonts::new(&layout.font);
 
         // Paint background rectangles first
         for rect in &rects {
@@ -699,7 +911,7 @@ impl Element for TerminalElement {
                 cell_width,
                 line_height,
                 layout.font_size,
-                &layout.font,
+                &fonts,
                 window,
                 cx,
             );
@@ -708,11 +920,15 @@ impl Element for TerminalElement {
         for underline in &underlines {
             underline.paint(origin, cell_width, line_height, window);
         }
+        for strikeout in &strikeouts {
+            strikeout.paint_strikeout(origin, cell_width, line_height, window);
+        }
 
         // Paint cursor (following Zed's cursor positioning)
         paint_cursor(
             window,
             &layout.content.cursor,
+            layout.content.cursor_wide,
             origin,
             layout.content.display_offset as i32,
             layout.content.grid_rows,
@@ -747,7 +963,12 @@ impl Element for TerminalElement {
         let actual_rows = (bounds.size.height / line_height).floor() as usize;
         let actual_cols = (bounds.size.width / cell_width).floor() as usize;
         let preserve_rows_for_keyboard = !is_alt && self.keyboard_inset > px(0.0);
-        let needs_reconcile = actual_cols != self.size.columns
+        // Font size changes re-measure the cells; push the new metrics to the
+        // terminal even when cols/rows happen to stay the same.
+        let metrics_changed =
+            cell_width != self.size.cell_width || line_height != self.size.line_height;
+        let needs_reconcile = metrics_changed
+            || actual_cols != self.size.columns
             || (!preserve_rows_for_keyboard && actual_rows != self.size.rows);
         if needs_reconcile {
             let view = self.view.clone();
@@ -765,7 +986,7 @@ impl Element for TerminalElement {
 mod tests {
     use gpui::px;
 
-    use super::{LayoutUnderline, TerminalElement};
+    use super::{LayoutUnderline, TerminalElement, TerminalShaping};
     use crate::Terminal;
     use crate::TerminalTheme;
 
@@ -773,12 +994,13 @@ mod tests {
         let mut terminal = Terminal::new(160, 8, px(10.0), px(20.0));
         terminal.advance_bytes(output);
         let content = terminal.content();
-        let (_, _, underlines) = TerminalElement::layout_grid(
+        let (_, _, underlines, _) = TerminalElement::layout_grid(
             &content.cells,
             content.display_offset as i32,
             content.grid_rows,
             &TerminalTheme::one_dark(),
             &content.detected_links,
+            TerminalShaping::PerCell,
         );
         underlines
     }
@@ -882,11 +1104,154 @@ mod tests {
         assert_eq!(underlines[0].col, 5);
         assert_eq!(underlines[0].num_cells, 13);
     }
+
+    #[test]
+    fn sgr_flags_split_batches_and_draw_decorations() {
+        let mut terminal = Terminal::new(160, 8, px(10.0), px(20.0));
+        terminal.advance_bytes(
+            b"a\x1b[1mb\x1b[3mc\x1b[0m \x1b[4mde\x1b[24m \x1b[9mf\x1b[0m\x1b[8mg\x1b[0m",
+        );
+        let content = terminal.content();
+        let (_, batches, underlines, strikeouts) = TerminalElement::layout_grid(
+            &content.cells,
+            content.display_offset as i32,
+            content.grid_rows,
+            &TerminalTheme::one_dark(),
+            &content.detected_links,
+            TerminalShaping::PerCell,
+        );
+
+        let styles: Vec<(String, bool, bool)> = batches
+            .iter()
+            .filter(|batch| batch.start_line == 0)
+            .map(|batch| (batch.text.clone(), batch.style.bold, batch.style.italic))
+            .collect();
+        // The concealed `g` is dropped from the text runs.
+        assert_eq!(
+            styles,
+            vec![
+                ("a".to_string(), false, false),
+                ("b".to_string(), true, false),
+                ("c".to_string(), true, true),
+                ("de".to_string(), false, false),
+                ("f".to_string(), false, false),
+            ]
+        );
+        assert_eq!(underlines.len(), 1);
+        assert_eq!((underlines[0].col, underlines[0].num_cells), (4, 2));
+        assert_eq!(strikeouts.len(), 1);
+        assert_eq!((strikeouts[0].col, strikeouts[0].num_cells), (7, 1));
+    }
+
+    /// `(start_col, text, ligatures)` for each batch on the first line, plus the
+    /// cursor column after `output`.
+    fn text_batches(output: &[u8], shaping: TerminalShaping) -> (Vec<(i32, String, bool)>, i32) {
+        let mut terminal = Terminal::new(160, 8, px(10.0), px(20.0));
+        terminal.advance_bytes(output);
+        let content = terminal.content();
+        let (_, batches, _, _) = TerminalElement::layout_grid(
+            &content.cells,
+            content.display_offset as i32,
+            content.grid_rows,
+            &TerminalTheme::one_dark(),
+            &content.detected_links,
+            shaping,
+        );
+        let batches = batches
+            .into_iter()
+            .filter(|batch| batch.start_line == 0)
+            .map(|batch| (batch.start_col, batch.text, batch.ligatures))
+            .collect();
+        (batches, content.cursor.point.column.0 as i32)
+    }
+
+    #[test]
+    fn per_cell_shaping_keeps_operators_in_one_forced_width_run() {
+        let (batches, cursor) = text_batches(b"a=>b!=c", TerminalShaping::PerCell);
+
+        assert_eq!(batches, vec![(0, "a=>b!=c".to_string(), false)]);
+        assert_eq!(cursor, 7);
+    }
+
+    #[test]
+    fn operator_ligatures_shape_only_contiguous_operator_runs() {
+        let (batches, cursor) = text_batches(b"a=>b != c", TerminalShaping::OperatorLigatures);
+
+        // Each run is anchored at its own column, so a merged `=>` glyph cannot
+        // shift `b` or anything after it.
+        assert_eq!(
+            batches,
+            vec![
+                (0, "a".to_string(), false),
+                (1, "=>".to_string(), true),
+                (3, "b".to_string(), false),
+                (5, "!=".to_string(), true),
+                (8, "c".to_string(), false),
+            ]
+        );
+        assert_eq!(cursor, 9);
+    }
+
+    #[test]
+    fn wide_chars_get_their_own_two_cell_batch() {
+        let (batches, cursor) =
+            text_batches("x中y=>".as_bytes(), TerminalShaping::OperatorLigatures);
+
+        assert_eq!(
+            batches,
+            vec![
+                (0, "x".to_string(), false),
+                (1, "中".to_string(), false),
+                (3, "y".to_string(), false),
+                (4, "=>".to_string(), true),
+            ]
+        );
+        assert_eq!(cursor, 6);
+    }
+
+    #[test]
+    fn mixed_width_lines_keep_columns_aligned() {
+        let (batches, cursor) = text_batches(
+            "a中b😀c e\u{301}x ❤\u{fe0f}!".as_bytes(),
+            TerminalShaping::PerCell,
+        );
+
+        assert_eq!(
+            batches,
+            vec![
+                (0, "a".to_string(), false),
+                (1, "中".to_string(), false),
+                (3, "b".to_string(), false),
+                (4, "😀".to_string(), false),
+                (6, "c".to_string(), false),
+                // Combining marks stay on their base cell.
+                (8, "e\u{301}".to_string(), false),
+                (9, "x".to_string(), false),
+                (11, "❤\u{fe0f}".to_string(), false),
+                (12, "!".to_string(), false),
+            ]
+        );
+        assert_eq!(cursor, 13);
+    }
+
+    #[test]
+    fn cursor_on_a_wide_character_is_flagged_wide() {
+        let mut terminal = Terminal::new(20, 4, px(10.0), px(20.0));
+        terminal.advance_bytes("中x".as_bytes());
+        assert!(!terminal.content().cursor_wide);
+
+        // Back over `x` and onto the first cell of `中`.
+        terminal.advance_bytes(b"\x1b[3D");
+        let content = terminal.content();
+        assert_eq!(content.cursor.point.column.0, 0);
+        assert!(content.cursor_wide);
+    }
 }
 
 fn paint_cursor(
     window: &mut Window,
     cursor: &CursorState,
+    wide: bool,
     origin: Point<Pixels>,
     display_offset: i32,
     grid_rows: usize,
@@ -924,8 +1289,10 @@ fn paint_cursor(
     let mut cursor_color: Hsla = rgb(theme.cursor).into();
     cursor_color.a = opacity;
 
-    // Cursor width uses ceil (following Zed)
-    let cursor_width = cell_width.ceil();
+    // Cursor width uses ceil (following Zed); on a wide character it covers
+    // both cells.
+    let cells = if wide { 2.0 } else { 1.0 };
+    let cursor_width = (cell_width * cells).ceil();
 
     match cursor.shape {
         CursorShape::Block => {
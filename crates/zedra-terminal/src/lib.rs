@@ -1,18 +1,26 @@
+pub mod command_guard;
 pub mod element;
 pub mod input;
 pub mod keyboard_accessory;
 pub mod keys;
+pub mod metrics;
+mod prompt_marks;
 mod selection;
 pub mod terminal;
 pub mod theme;
+pub mod tmux_control;
+pub mod triggers;
 pub mod view;
 
-pub use element::{TerminalElement, TerminalElementLayout};
+pub use command_guard::{GuardRule, GuardedCommand};
+pub use element::{TerminalElement, TerminalElementLayout, TerminalShaping};
 pub use input::*;
 pub use keyboard_accessory::*;
 pub use keys::*;
+pub use metrics::{CellMetrics, terminal_font};
 pub use terminal::*;
 pub use theme::{AnsiPalette, TerminalTheme};
+pub use triggers::{TriggerAction, TriggerFired, TriggerRule};
 pub use view::*;
 
 use gpui::*;
@@ -0,0 +1,122 @@
+//! Terminal cell metrics measured from the monospace font.
+//!
+//! Cell width is the advance of `m` and line height is the font's ascent plus
+//! descent, snapped to device pixels. Both the view (grid sizing) and the
+//! element (painting) measure through here, so the cursor, selection, and
+//! glyph positions always agree with the host's cols/rows.
+
+use gpui::*;
+
+use crate::MONO_FONT_FAMILY;
+
+/// Used only when the text system cannot resolve the terminal font.
+const FALLBACK_CELL_WIDTH_EM: f32 = 0.6;
+const FALLBACK_LINE_HEIGHT_EM: f32 = 4.0 / 3.0;
+
+/// The terminal font in `family`, with symbol fallbacks for box drawing and
+/// Nerd Font glyphs. Families other than the bundled Nerd Font fall back to it
+/// first so powerline and devicon glyphs still render.
+pub fn terminal_font(family: SharedString) -> Font {
+    let mut fallbacks = Vec::new();
+    if family.as_ref() != MONO_FONT_FAMILY {
+        fallbacks.push(MONO_FONT_FAMILY.to_string());
+    }
+    fallbacks.extend(
+        [
+            "Noto Sans Symbols 2",
+            "Apple Symbols",
+            "Menlo",
+            "Droid Sans Mono",
+            "monospace",
+        ]
+        .map(String::from),
+    );
+    Font {
+        family,
+        features: FontFeatures::default(),
+        fallbacks: Some(FontFallbacks::from_fonts(fallbacks)),
+        weight: FontWeight::NORMAL,
+        style: FontStyle::Normal,
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CellMetrics {
+    pub font_size: Pixels,
+    pub cell_width: Pixels,
+    pub line_height: Pixels,
+}
+
+impl CellMetrics {
+    /// Measure cell metrics for `font` at `font_size` and the window's scale factor.
+    pub fn measure(window: &Window, font: &Font, font_size: Pixels) -> Self {
+        let text_system = window.text_system();
+        let font_id = text_system.resolve_font(font);
+        let Ok(advance) = text_system.advance(font_id, font_size, 'm') else {
+            return Self::fallback(font_size);
+        };
+        let ascent = text_system.ascent(font_id, font_size);
+        let descent = text_system.descent(font_id, font_size);
+        Self {
+            font_size,
+            cell_width: advance.width,
+            line_height: snap_line_height(ascent, descent, window.scale_factor(), font_size),
+        }
+    }
+
+    pub fn fallback(font_size: Pixels) -> Self {
+        Self {
+            font_size,
+            cell_width: font_size * FALLBACK_CELL_WIDTH_EM,
+            line_height: (font_size * FALLBACK_LINE_HEIGHT_EM).round(),
+        }
+    }
+}
+
+/// Ascent plus descent (reported negative below the baseline), rounded to whole
+/// device pixels so rows never drift by a fraction of a pixel.
+fn snap_line_height(
+    ascent: Pixels,
+    descent: Pixels,
+    scale_factor: f32,
+    font_size: Pixels,
+) -> Pixels {
+    let natural = f32::from(ascent) + f32::from(descent).abs();
+    if !natural.is_finite() || natural <= 0.0 {
+        return (font_size * FALLBACK_LINE_HEIGHT_EM).round();
+    }
+    let scale = if scale_factor > 0.0 {
+        scale_factor
+    } else {
+        1.0
+    };
+    px((natural * scale).round() / scale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_height_snaps_to_device_pixels() {
+        // JetBrains Mono at 12px: ascent 12.24, descent -3.6.
+        assert_eq!(
+            snap_line_height(px(12.24), px(-3.6), 1.0, px(12.0)),
+            px(16.0)
+        );
+        assert_eq!(
+            snap_line_height(px(12.24), px(3.6), 2.0, px(12.0)),
+            px(16.0)
+        );
+        assert_eq!(
+            snap_line_height(px(12.0), px(-3.5), 3.0, px(12.0)),
+            px(47.0 / 3.0)
+        );
+    }
+
+    #[test]
+    fn degenerate_metrics_fall_back_to_font_size() {
+        assert_eq!(snap_line_height(px(0.0), px(0.0), 2.0, px(12.0)), px(16.0));
+        assert_eq!(CellMetrics::fallback(px(15.0)).line_height, px(20.0));
+    }
+}
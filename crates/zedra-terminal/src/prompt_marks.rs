@@ -0,0 +1,294 @@
+// Shell-integration prompt marks from OSC 133 (FinalTerm / VS Code 633).
+//
+// `Terminal` records where the shell reported a prompt (A/B), the start of a
+// command's output (C), and its end (D) while it advances output. Rows are
+// counted from the oldest scrollback line (`history_size + line` at record
+// time), which stays stable as output scrolls until scrollback is full; past
+// that point, or when history is trimmed or cleared, `Terminal` shifts the
+// marks by the rows that rotated out.
+//
+// A finished command (A, C, then D) forms an `OutputBlock` that can be folded:
+// `Terminal` then draws its prompt, command line, and output as one header row.
+// Fold state is keyed by the block's C row and shifts with the marks.
+
+use std::collections::{BTreeSet, VecDeque};
+
+/// Marks kept per terminal. Each command adds about three.
+const MAX_MARKS: usize = 3000;
+/// Blocks with fewer output lines are not folded by `fold_all`.
+pub(crate) const FOLD_MIN_LINES: usize = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum PromptMarkKind {
+    Prompt,
+    CommandStart,
+    CommandEnd { exit_code: i32 },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct PromptMark {
+    pub(crate) kind: PromptMarkKind,
+    pub(crate) row: usize,
+    pub(crate) column: usize,
+}
+
+/// One finished command: its prompt, where its output starts, and the rows
+/// that output covers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct OutputBlock {
+    /// Where the command line starts: the 133;B column when the shell sends
+    /// one, otherwise the start of the prompt.
+    pub(crate) prompt: PromptMark,
+    pub(crate) start: PromptMark,
+    pub(crate) first_row: usize,
+    pub(crate) last_row: usize,
+    pub(crate) exit_code: i32,
+}
+
+impl OutputBlock {
+    pub(crate) fn lines(&self) -> usize {
+        self.last_row + 1 - self.first_row
+    }
+
+    /// Rows hidden behind the header while folded.
+    pub(crate) fn contains(&self, row: usize) -> bool {
+        (self.prompt.row..=self.last_row).contains(&row)
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct PromptMarks {
+    marks: VecDeque<PromptMark>,
+    /// `start.row` of each folded block.
+    folded: BTreeSet<usize>,
+}
+
+impl PromptMarks {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.marks.is_empty()
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.marks.clear();
+        self.folded.clear();
+    }
+
+    pub(crate) fn record(&mut self, kind: PromptMarkKind, row: usize, column: usize) {
+        // 133;A and 133;B both mark the same prompt; keep one mark, at B's
+        // column, where the command line starts.
+        if kind == PromptMarkKind::Prompt {
+            if let Some(last) = self.marks.back_mut() {
+                if last.kind == PromptMarkKind::Prompt && last.row == row {
+                    last.column = column;
+                    return;
+                }
+            }
+        }
+        if self.marks.len() == MAX_MARKS {
+            self.marks.pop_front();
+        }
+        self.marks.push_back(PromptMark { kind, row, column });
+    }
+
+    /// Move every mark up by `rows`, dropping marks that left the scrollback.
+    pub(crate) fn shift(&mut self, rows: usize) {
+        self.marks
+            .retain_mut(|mark| match mark.row.checked_sub(rows) {
+                Some(row) => {
+                    mark.row = row;
+                    true
+                }
+                None => false,
+            });
+        self.folded = self
+            .folded
+            .iter()
+            .filter_map(|row| row.checked_sub(rows))
+            .collect();
+    }
+
+    /// Finished commands that printed at least one full line, oldest first.
+    /// Output that ends without a newline shares its last row with the next
+    /// prompt, so that row is left out.
+    pub(crate) fn blocks(&self) -> Vec<OutputBlock> {
+        let mut blocks = Vec::new();
+        let mut prompt = None;
+        let mut start = None;
+        for mark in &self.marks {
+            match mark.kind {
+                PromptMarkKind::Prompt => {
+                    prompt = Some(*mark);
+                    start = None;
+                }
+                PromptMarkKind::CommandStart => start = Some(*mark),
+                PromptMarkKind::CommandEnd { exit_code } => {
+                    let (Some(prompt), Some(start)) = (prompt, start.take()) else {
+                        continue;
+                    };
+                    let first_row = start.row + usize::from(start.column > 0);
+                    let Some(last_row) = mark.row.checked_sub(1) else {
+                        continue;
+                    };
+                    if last_row >= first_row && prompt.row <= start.row {
+                        blocks.push(OutputBlock {
+                            prompt,
+                            start,
+                            first_row,
+                            last_row,
+                            exit_code,
+                        });
+                    }
+                }
+            }
+        }
+        blocks
+    }
+
+    pub(crate) fn folded_blocks(&self) -> Vec<OutputBlock> {
+        if self.folded.is_empty() {
+            return Vec::new();
+        }
+        self.blocks()
+            .into_iter()
+            .filter(|block| self.folded.contains(&block.start.row))
+            .collect()
+    }
+
+    pub(crate) fn has_folds(&self) -> bool {
+        !self.folded.is_empty()
+    }
+
+    /// Fold every block of at least `FOLD_MIN_LINES` except the most recent,
+    /// whose output is usually still being read. Returns whether any folded.
+    pub(crate) fn fold_all(&mut self) -> bool {
+        let blocks = self.blocks();
+        let before = self.folded.len();
+        for block in blocks.iter().rev().skip(1) {
+            if block.lines() >= FOLD_MIN_LINES {
+                self.folded.insert(block.start.row);
+            }
+        }
+        self.folded.len() != before
+    }
+
+    pub(crate) fn unfold_all(&mut self) -> bool {
+        let had_folds = !self.folded.is_empty();
+        self.folded.clear();
+        had_folds
+    }
+
+    /// Fold or unfold the block whose output starts on `start_row`.
+    pub(crate) fn toggle_fold(&mut self, start_row: usize) {
+        if !self.folded.remove(&start_row) {
+            self.folded.insert(start_row);
+        }
+    }
+
+    /// Nearest prompt row above `row`.
+    pub(crate) fn previous_prompt(&self, row: usize) -> Option<usize> {
+        self.prompts().rev().find(|&prompt| prompt < row)
+    }
+
+    /// Nearest prompt row below `row`.
+    pub(crate) fn next_prompt(&self, row: usize) -> Option<usize> {
+        self.prompts().find(|&prompt| prompt > row)
+    }
+
+    fn prompts(&self) -> impl DoubleEndedIterator<Item = usize> + '_ {
+        self.marks
+            .iter()
+            .filter(|mark| mark.kind == PromptMarkKind::Prompt)
+            .map(|mark| mark.row)
+    }
+
+    /// The latest prompt when no command has started since, i.e. the shell
+    /// is waiting at it. Its column is where the command line starts.
+    pub(crate) fn pending_prompt(&self) -> Option<PromptMark> {
+        self.marks
+            .back()
+            .filter(|mark| mark.kind == PromptMarkKind::Prompt)
+            .copied()
+    }
+
+    /// Output start and end marks of the most recent finished command. A `D`
+    /// without a `C` (an empty command line) does not count.
+    pub(crate) fn last_output(&self) -> Option<(PromptMark, PromptMark)> {
+        let mut end = None;
+        for mark in self.marks.iter().rev() {
+            match (mark.kind, end) {
+                (PromptMarkKind::CommandEnd { .. }, _) => end = Some(*mark),
+                (PromptMarkKind::CommandStart, Some(end)) => return Some((*mark, end)),
+                _ => {}
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn navigates_prompts_and_finds_last_output() {
+        let mut marks = PromptMarks::default();
+        marks.record(PromptMarkKind::Prompt, 0, 0);
+        marks.record(PromptMarkKind::Prompt, 0, 2);
+        marks.record(PromptMarkKind::CommandStart, 1, 0);
+        marks.record(PromptMarkKind::CommandEnd { exit_code: 0 }, 4, 0);
+        marks.record(PromptMarkKind::Prompt, 4, 0);
+        // An empty command line reports D without C.
+        marks.record(PromptMarkKind::CommandEnd { exit_code: 0 }, 5, 0);
+        marks.record(PromptMarkKind::Prompt, 5, 0);
+
+        assert_eq!(marks.previous_prompt(5), Some(4));
+        assert_eq!(marks.previous_prompt(0), None);
+        assert_eq!(marks.next_prompt(0), Some(4));
+        let (start, end) = marks.last_output().unwrap();
+        assert_eq!((start.row, end.row), (1, 4));
+
+        marks.shift(2);
+        assert_eq!(marks.previous_prompt(3), Some(2));
+        assert_eq!(marks.next_prompt(0), Some(2));
+        assert_eq!(marks.last_output(), None);
+    }
+
+    #[test]
+    fn folds_long_blocks_except_the_latest() {
+        fn command(marks: &mut PromptMarks, prompt: usize, lines: usize) -> usize {
+            marks.record(PromptMarkKind::Prompt, prompt, 0);
+            marks.record(PromptMarkKind::Prompt, prompt, 2);
+            marks.record(PromptMarkKind::CommandStart, prompt + 1, 0);
+            marks.record(
+                PromptMarkKind::CommandEnd { exit_code: 0 },
+                prompt + 1 + lines,
+                0,
+            );
+            prompt + 1 + lines
+        }
+        let mut marks = PromptMarks::default();
+        let next = command(&mut marks, 0, 40);
+        let next = command(&mut marks, next, 3);
+        let next = command(&mut marks, next, 40);
+        marks.record(PromptMarkKind::Prompt, next, 0);
+
+        let blocks = marks.blocks();
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[0].prompt.column, 2);
+        assert_eq!((blocks[0].first_row, blocks[0].last_row), (1, 40));
+        assert!(blocks[0].contains(0) && !blocks[0].contains(41));
+
+        // The short block and the latest one stay open.
+        assert!(marks.fold_all());
+        let folded = marks.folded_blocks();
+        assert_eq!(folded.len(), 1);
+        assert_eq!(folded[0].start.row, 1);
+
+        marks.toggle_fold(1);
+        assert!(!marks.has_folds());
+        marks.toggle_fold(1);
+        marks.shift(1);
+        assert!(marks.folded_blocks().is_empty());
+        assert!(marks.unfold_all());
+    }
+}
@@ -1,5 +1,6 @@
 use std::borrow::Cow;
 use std::cmp::min;
+use std::collections::VecDeque;
 use std::ops::{Index, Range};
 use std::path::{Path, PathBuf};
 use std::sync::mpsc as std_mpsc;
@@ -12,15 +13,21 @@ use alacritty_terminal::index::{Column, Line, Point};
 use alacritty_terminal::term::Config;
 use alacritty_terminal::term::cell::{Cell, Flags as CellFlags};
 use alacritty_terminal::term::{Term, TermMode};
-use alacritty_terminal::vte::ansi::{Color as AlacColor, CursorShape, NamedColor, Processor};
+use alacritty_terminal::vte::ansi::{
+    Color as AlacColor, CursorShape, CursorStyle, NamedColor, Processor,
+};
 use gpui::{
     Context, Keystroke, Pixels, Point as GpuiPoint, ScrollDelta, ScrollWheelEvent, Task, px,
 };
 use tokio::sync::{broadcast, mpsc};
 use zedra_osc::{OscEvent, OscScanner};
 
+use crate::command_guard::{self, GuardRule, GuardedCommand};
 use crate::keys::to_esc_str;
+use crate::prompt_marks::{FOLD_MIN_LINES, OutputBlock, PromptMarkKind, PromptMarks};
 use crate::theme::TerminalTheme;
+use crate::tmux_control::{self, TmuxControl, TmuxUpdate};
+use crate::triggers::{self, TriggerAction, TriggerFired, TriggerMatcher, TriggerRule};
 /// Events emitted by the terminal to observers.
 #[derive(Debug, Clone)]
 pub enum TerminalEvent {
@@ -40,6 +47,39 @@ pub enum TerminalEvent {
     NativePasteMenuRequested {
         position: GpuiPoint<Pixels>,
     },
+    /// A long press landed on a file path; observers offer to open it.
+    FilePathMenuRequested {
+        position: GpuiPoint<Pixels>,
+        hyperlink: TerminalHyperlink,
+    },
+    /// An output trigger matched. Highlight and reply are already applied;
+    /// observers handle notify and copy.
+    TriggerFired(TriggerFired),
+    /// Enter was held back because the command line matched a guard rule.
+    /// Nothing is sent until `submit_guarded_command`.
+    CommandGuarded(GuardedCommand),
+}
+
+/// Direction for [`Terminal::scroll_to_prompt`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromptJump {
+    Previous,
+    Next,
+}
+
+/// What one viewport row shows while output blocks are folded.
+#[derive(Debug, Clone, Copy)]
+enum VisualRow {
+    Grid(i32),
+    Header(OutputBlock),
+    /// Above the oldest scrollback line.
+    Blank,
+}
+
+/// The folded block hiding absolute `row`, if any.
+fn fold_containing(folds: &[OutputBlock], row: i32) -> Option<OutputBlock> {
+    let row = usize::try_from(row).ok()?;
+    folds.iter().find(|block| block.contains(row)).copied()
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -105,6 +145,9 @@ pub struct TerminalContent {
     pub display_offset: usize,
     pub cursor: CursorState,
     pub cursor_char: char,
+    /// The cursor sits on the first cell of a wide character, so it paints
+    /// two cells wide.
+    pub cursor_wide: bool,
     pub grid_rows: usize,
     pub grid_cols: usize,
     pub detected_links: Vec<DetectedLink>,
@@ -122,6 +165,18 @@ pub struct IndexedCell {
 pub struct CursorState {
     pub point: Point,
     pub shape: CursorShape,
+    /// Set by DECSCUSR odd styles, private mode 12, or the user default.
+    pub blinking: bool,
+}
+
+/// Cursor shape used until an application sets one with DECSCUSR
+/// (`CSI Ps SP q`), and again after it resets with `CSI 0 SP q`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DefaultCursorShape {
+    #[default]
+    Block,
+    Underline,
+    Bar,
 }
 
 /// Terminal size in cells and pixels
@@ -208,6 +263,18 @@ pub struct Terminal {
     synchronized_update_timeout_task: Option<Task<()>>,
     selection_range: Option<Range<usize>>,
     theme: TerminalTheme,
+    triggers: TriggerMatcher,
+    /// Rules checked against the command line when Enter is pressed.
+    command_guard: Vec<GuardRule>,
+    /// Set while tmux control mode (`tmux -CC`) owns the PTY stream.
+    tmux: Option<TmuxControl>,
+    /// OSC 133 prompt marks. Uses its own scanner because marks are recorded
+    /// while the grid advances, before `feed_osc_bytes` sees the chunk.
+    prompt_marks: PromptMarks,
+    prompt_mark_scanner: OscScanner,
+    /// Lines of output that arrived while scrolled up. Cleared whenever the
+    /// view is back at the bottom.
+    unseen_lines: usize,
 }
 
 impl Terminal {
@@ -246,6 +313,12 @@ impl Terminal {
             synchronized_update_timeout_task: None,
             selection_range: None,
             theme,
+            triggers: TriggerMatcher::new(Vec::new()),
+            command_guard: Vec::new(),
+            tmux: None,
+            prompt_marks: PromptMarks::default(),
+            prompt_mark_scanner: OscScanner::new(),
+            unseen_lines: 0,
         };
         terminal
     }
@@ -257,6 +330,29 @@ impl Terminal {
         self.theme = theme;
     }
 
+    /// Cursor style shown when the application has not picked one.
+    pub fn set_default_cursor_style(&mut self, shape: DefaultCursorShape, blinking: bool) {
+        let shape = match shape {
+            DefaultCursorShape::Block => CursorShape::Block,
+            DefaultCursorShape::Underline => CursorShape::Underline,
+            DefaultCursorShape::Bar => CursorShape::Beam,
+        };
+        self.term.set_options(Config {
+            default_cursor_style: CursorStyle { shape, blinking },
+            ..Config::default()
+        });
+    }
+
+    /// Replace the output triggers. Takes effect from the next output chunk.
+    pub fn set_triggers(&mut self, rules: Vec<TriggerRule>) {
+        self.triggers = TriggerMatcher::new(rules);
+    }
+
+    /// Replace the command guard rules. An empty list turns the guard off.
+    pub fn set_command_guard(&mut self, rules: Vec<GuardRule>) {
+        self.command_guard = rules;
+    }
+
     pub fn is_channel_attached(&self) -> bool {
         self.input_tx.is_some() && self.output_task.is_some()
     }
@@ -290,11 +386,33 @@ impl Terminal {
         self.output_task = Some(output_task);
     }
 
+    /// Raw input channel. `None` in tmux control mode, where raw bytes would be
+    /// read as tmux commands; use `send_bytes` instead.
     pub fn input_sender(&self) -> Option<mpsc::Sender<Vec<u8>>> {
+        if self.tmux.is_some() {
+            return None;
+        }
         self.input_tx.clone()
     }
 
+    /// Whether tmux control mode is attached on this terminal.
+    pub fn is_tmux_control_mode(&self) -> bool {
+        self.tmux.is_some()
+    }
+
+    /// In tmux control mode, wrap input for the active pane in `send-keys`.
+    fn encode_input(&self, bytes: Vec<u8>) -> Vec<u8> {
+        match &self.tmux {
+            Some(tmux) => tmux.encode_input(&bytes),
+            None => bytes,
+        }
+    }
+
     pub async fn send_bytes(&mut self, bytes: Vec<u8>) {
+        let bytes = self.encode_input(bytes);
+        if bytes.is_empty() {
+            return;
+        }
         if let Some(tx) = &self.input_tx {
             if let Err(e) = tx.send(bytes).await {
                 error!("failed to send input: {:?}", e)
@@ -314,12 +432,498 @@ impl Terminal {
     fn advance_output_bytes(&mut self, bytes: &[u8]) -> bool {
         let was_alt = self.mode.contains(TermMode::ALT_SCREEN);
         let previous_display_offset = self.display_offset();
-        self.processor.advance(&mut self.term, bytes);
+        self.advance_pty_output(bytes);
         self.finish_output_update(was_alt, previous_display_offset);
 
         self.synchronized_update_deadline().is_none()
     }
 
+    /// Split PTY output between the screen and the tmux control client. tmux
+    /// prints its DCS introducer and the first protocol lines together, so the
+    /// introducer is only looked for within a single chunk.
+    fn advance_pty_output(&mut self, mut bytes: &[u8]) {
+        loop {
+            if let Some(tmux) = &mut self.tmux {
+                let update = tmux.feed(bytes);
+                let exited_at = update.exited_at;
+                self.apply_tmux_update(update);
+                let Some(end) = exited_at else { return };
+                info!("tmux control mode exited");
+                self.tmux = None;
+                self.send_terminal_event(TerminalEvent::TitleChanged(None));
+                bytes = &bytes[end..];
+                continue;
+            }
+            let start = bytes
+                .windows(tmux_control::CONTROL_MODE_START.len())
+                .position(|window| window == tmux_control::CONTROL_MODE_START);
+            let Some(start) = start else {
+                self.advance_screen(bytes);
+                return;
+            };
+            self.advance_screen(&bytes[..start]);
+            info!("tmux control mode started");
+            let (tmux, commands) = TmuxControl::start(self.size.columns, self.size.rows);
+            self.tmux = Some(tmux);
+            self.send_tmux_commands(commands);
+            bytes = &bytes[start + tmux_control::CONTROL_MODE_START.len()..];
+        }
+    }
+
+    /// Advance the grid. Output is split after each BEL and `\` so an OSC 133
+    /// mark is recorded where the shell printed it, not at the end of the chunk.
+    fn advance_screen(&mut self, bytes: &[u8]) {
+        for segment in bytes.split_inclusive(|byte| matches!(byte, 0x07 | b'\\')) {
+            let history_before = self.history_size();
+            let cursor_before = self.term.grid().cursor.point.line.0;
+            if self.triggers.is_empty() {
+                self.processor.advance(&mut self.term, segment);
+            } else {
+                self.advance_with_triggers(segment);
+            }
+            self.rebase_prompt_marks(history_before, cursor_before, segment);
+            for event in self.prompt_mark_scanner.feed(segment) {
+                self.record_prompt_mark(event);
+            }
+        }
+    }
+
+    fn record_prompt_mark(&mut self, event: OscEvent) {
+        let kind = match event {
+            OscEvent::PromptReady => PromptMarkKind::Prompt,
+            OscEvent::CommandStart => PromptMarkKind::CommandStart,
+            OscEvent::CommandEnd { exit_code } => PromptMarkKind::CommandEnd { exit_code },
+            _ => return,
+        };
+        // The alternate screen has no scrollback to navigate.
+        if self.term.mode().contains(TermMode::ALT_SCREEN) {
+            return;
+        }
+        let cursor = self.term.grid().cursor.point;
+        let row = self.history_size() + cursor.line.0.max(0) as usize;
+        self.prompt_marks.record(kind, row, cursor.column.0);
+    }
+
+    /// Keep marks on their lines when scrollback drops rows: history trimmed or
+    /// cleared, or full and evicting its oldest line for each line scrolled.
+    fn rebase_prompt_marks(&mut self, history_before: usize, cursor_before: i32, segment: &[u8]) {
+        if self.prompt_marks.is_empty() || self.term.mode().contains(TermMode::ALT_SCREEN) {
+            return;
+        }
+        let history_after = self.history_size();
+        let dropped = if history_after < history_before {
+            history_before - history_after
+        } else if history_after >= Config::default().scrolling_history {
+            // Linefeeds that did not move the cursor down scrolled the screen.
+            let newlines = segment.iter().filter(|byte| **byte == b'\n').count() as i64;
+            let cursor_moved = i64::from(self.term.grid().cursor.point.line.0 - cursor_before);
+            let grown = (history_after - history_before) as i64;
+            (newlines - cursor_moved - grown).max(0) as usize
+        } else {
+            0
+        };
+        if dropped > 0 {
+            self.prompt_marks.shift(dropped);
+        }
+    }
+
+    /// Whether the shell has reported prompts through OSC 133.
+    pub fn has_prompt_marks(&self) -> bool {
+        !self.prompt_marks.is_empty()
+    }
+
+    /// Scroll the previous or next prompt to the top of the viewport. Past the
+    /// last prompt, `Next` returns to the bottom. Returns false when there is
+    /// nowhere to go.
+    pub fn scroll_to_prompt(&mut self, jump: PromptJump) -> bool {
+        let history = self.history_size();
+        let display_offset = self.display_offset();
+        let top = history - display_offset;
+        let target = match jump {
+            PromptJump::Previous => self.prompt_marks.previous_prompt(top),
+            PromptJump::Next => self.prompt_marks.next_prompt(top),
+        };
+        let Some(row) = target else {
+            if jump == PromptJump::Next && display_offset > 0 {
+                self.scroll_to_bottom();
+                return true;
+            }
+            return false;
+        };
+        let offset = history.saturating_sub(row);
+        self.scroll(offset as i32 - display_offset as i32);
+        true
+    }
+
+    /// Text printed by the most recent finished command, from its OSC 133;C
+    /// mark to its 133;D mark.
+    pub fn last_command_output(&self) -> Option<String> {
+        let (start, end) = self.prompt_marks.last_output()?;
+        Some(self.grid_text((start.row, start.column), (end.row, end.column)))
+    }
+
+    /// The command line the shell is editing, through the cursor's row: from
+    /// the prompt's 133;B column when the shell reports one, otherwise the
+    /// cursor's logical line, prompt included. `None` on the alternate
+    /// screen, where Enter belongs to the full-screen app.
+    pub fn pending_command_line(&self) -> Option<String> {
+        if self.term.mode().contains(TermMode::ALT_SCREEN) {
+            return None;
+        }
+        let grid = self.term.grid();
+        let history = self.history_size();
+        let cursor_line = grid.cursor.point.line;
+        let cursor_row = history + cursor_line.0.max(0) as usize;
+        let start = match self
+            .prompt_marks
+            .pending_prompt()
+            .filter(|prompt| prompt.row <= cursor_row)
+        {
+            Some(prompt) => (prompt.row, prompt.column),
+            None => {
+                let last_column = Column(grid.columns().saturating_sub(1));
+                let mut line = cursor_line.0;
+                while line > -(history as i32)
+                    && grid[Line(line - 1)][last_column]
+                        .flags
+                        .contains(CellFlags::WRAPLINE)
+                {
+                    line -= 1;
+                }
+                ((line + history as i32) as usize, 0)
+            }
+        };
+        Some(self.grid_text(start, (cursor_row, self.size.columns)))
+    }
+
+    /// Text between two `(row, column)` positions counted from the oldest
+    /// scrollback line. Wrapped rows are joined; other rows end in `\n`.
+    fn grid_text(&self, start: (usize, usize), end: (usize, usize)) -> String {
+        let grid = self.term.grid();
+        let history = self.history_size();
+        let columns = grid.columns();
+        let screen_lines = grid.screen_lines();
+        let mut text = String::new();
+        for row in start.0..=end.0 {
+            let line = row as i32 - history as i32;
+            if line >= screen_lines as i32 {
+                break;
+            }
+            let cells = &grid[Line(line)];
+            let first = if row == start.0 { start.1 } else { 0 };
+            let last = if row == end.0 { end.1 } else { columns };
+            for column in first..last.min(columns) {
+                let cell = &cells[Column(column)];
+                if !cell.flags.contains(CellFlags::WIDE_CHAR_SPACER) {
+                    text.push(cell.c);
+                }
+            }
+            let wrapped = columns > 0
+                && cells[Column(columns - 1)]
+                    .flags
+                    .contains(CellFlags::WRAPLINE);
+            if row != end.0 && !wrapped {
+                text.truncate(text.trim_end_matches(' ').len());
+                text.push('\n');
+            }
+        }
+        text.trim_end().to_string()
+    }
+
+    /// Whether an earlier command printed enough output to fold.
+    pub fn has_foldable_outputs(&self) -> bool {
+        let blocks = self.prompt_marks.blocks();
+        blocks
+            .iter()
+            .rev()
+            .skip(1)
+            .any(|block| block.lines() >= FOLD_MIN_LINES)
+    }
+
+    pub fn has_folded_outputs(&self) -> bool {
+        self.prompt_marks.has_folds()
+    }
+
+    /// Collapse each earlier command's long output into a one-row header, or
+    /// expand them all again. Returns whether anything changed.
+    pub fn set_outputs_folded(&mut self, folded: bool) -> bool {
+        if folded {
+            self.prompt_marks.fold_all()
+        } else {
+            self.prompt_marks.unfold_all()
+        }
+    }
+
+    /// Expand the folded block whose header is at `position`. Returns false
+    /// when `position` is not on a header.
+    pub fn unfold_at(
+        &mut self,
+        position: gpui::Point<Pixels>,
+        grid_origin: Option<gpui::Point<Pixels>>,
+    ) -> bool {
+        let Some(origin) = grid_origin else {
+            return false;
+        };
+        let relative = position - origin;
+        if relative.x < px(0.0) || relative.y < px(0.0) {
+            return false;
+        }
+        let viewport_line = (relative.y / self.size.line_height) as usize;
+        let Some(visual_rows) = self.visual_rows() else {
+            return false;
+        };
+        let Some(VisualRow::Header(block)) = visual_rows.get(viewport_line + 1) else {
+            return false;
+        };
+        self.prompt_marks.toggle_fold(block.start.row);
+        true
+    }
+
+    /// Grid line or fold header for each viewport row, starting with the
+    /// overscan row above, or `None` when nothing is folded. Rows are laid out
+    /// upward from the bottom of the viewport, so the live prompt stays put
+    /// and folds pull older scrollback into view.
+    fn visual_rows(&self) -> Option<Vec<VisualRow>> {
+        if self.is_alt_screen() {
+            return None;
+        }
+        let folds = self.prompt_marks.folded_blocks();
+        if folds.is_empty() {
+            return None;
+        }
+        let history = self.history_size() as i32;
+        let rows = self.size.rows as i32;
+        let display_offset = self.display_offset() as i32;
+        let anchor = history + rows - 1 - display_offset;
+
+        let mut visual_rows = VecDeque::with_capacity(self.size.rows + 2);
+        let mut row = anchor;
+        while visual_rows.len() <= self.size.rows {
+            if row < 0 {
+                visual_rows.push_front(VisualRow::Blank);
+                continue;
+            }
+            match fold_containing(&folds, row) {
+                Some(block) => {
+                    visual_rows.push_front(VisualRow::Header(block));
+                    row = block.prompt.row as i32 - 1;
+                }
+                None => {
+                    visual_rows.push_front(VisualRow::Grid(row - history));
+                    row -= 1;
+                }
+            }
+        }
+        // The below row only exists within the screen while scrolled up.
+        if display_offset >= 1 {
+            let below = fold_containing(&folds, anchor).map_or(anchor, |b| b.last_row as i32) + 1;
+            if below < history + rows {
+                visual_rows.push_back(match fold_containing(&folds, below) {
+                    Some(block) => VisualRow::Header(block),
+                    None => VisualRow::Grid(below - history),
+                });
+            }
+        }
+        Some(visual_rows.into())
+    }
+
+    /// Grid rows to scroll so the viewport moves `lines` visual rows, with
+    /// each folded block counting as one.
+    fn fold_adjusted_scroll(&self, lines: i32) -> i32 {
+        if self.is_alt_screen() {
+            return lines;
+        }
+        let folds = self.prompt_marks.folded_blocks();
+        if folds.is_empty() {
+            return lines;
+        }
+        let end = (self.history_size() + self.size.rows) as i32;
+        let anchor = end - 1 - self.display_offset() as i32;
+        let mut row = anchor;
+        for _ in 0..lines.unsigned_abs() {
+            row = if lines > 0 {
+                fold_containing(&folds, row).map_or(row, |b| b.prompt.row as i32) - 1
+            } else {
+                fold_containing(&folds, row).map_or(row, |b| b.last_row as i32) + 1
+            };
+            if !(0..end).contains(&row) {
+                break;
+            }
+        }
+        anchor - row
+    }
+
+    /// Cells for `visual_rows`, placed so the renderer's `line + display_offset`
+    /// lands on each row's viewport position.
+    fn folded_cells(&self, visual_rows: &[VisualRow]) -> Vec<IndexedCell> {
+        let grid = self.term.grid();
+        let columns = self.size.columns;
+        let display_offset = self.display_offset() as i32;
+        let mut cells = Vec::with_capacity(visual_rows.len() * columns);
+        for (index, visual_row) in visual_rows.iter().enumerate() {
+            let line = Line(index as i32 - 1 - display_offset);
+            match visual_row {
+                VisualRow::Grid(source) => {
+                    let source = &grid[Line(*source)];
+                    cells.extend((0..columns).map(|column| IndexedCell {
+                        point: Point::new(line, Column(column)),
+                        cell: source[Column(column)].clone(),
+                    }));
+                }
+                VisualRow::Header(block) => cells.extend(self.fold_header_cells(block, line)),
+                VisualRow::Blank => {}
+            }
+        }
+        cells
+    }
+
+    /// A folded block's header row: its command line as the shell drew it,
+    /// then a dimmed summary, e.g. `▸ $ cargo test — 842 lines`.
+    fn fold_header_cells(&self, block: &OutputBlock, line: Line) -> Vec<IndexedCell> {
+        let columns = self.size.columns;
+        let command_line = Line(block.prompt.row as i32 - self.history_size() as i32);
+        let source = &self.term.grid()[command_line];
+        let command_end = (block.prompt.column..columns)
+            .rev()
+            .find(|&column| !matches!(source[Column(column)].c, ' ' | '\0'))
+            .map_or(block.prompt.column, |column| column + 1);
+        let mut summary = format!(" — {} lines", block.lines());
+        if block.exit_code != 0 {
+            summary.push_str(&format!(", exit {}", block.exit_code));
+        }
+        let dim = |c: char| {
+            let mut cell = Cell {
+                c,
+                ..Cell::default()
+            };
+            cell.flags.insert(CellFlags::DIM);
+            cell
+        };
+
+        let mut row: Vec<Cell> = "▸ ".chars().map(dim).collect();
+        let room = columns.saturating_sub(row.len() + summary.chars().count());
+        for column in block.prompt.column..command_end.min(block.prompt.column + room) {
+            let cell = &source[Column(column)];
+            // A wide character whose spacer would be cut off ends the command.
+            if cell.flags.contains(CellFlags::WIDE_CHAR) && column + 1 >= block.prompt.column + room
+            {
+                break;
+            }
+            let mut cell = cell.clone();
+            cell.flags.remove(CellFlags::WRAPLINE);
+            row.push(cell);
+        }
+        row.extend(summary.chars().map(dim));
+        row.resize(columns, Cell::default());
+        row.into_iter()
+            .enumerate()
+            .map(|(column, cell)| IndexedCell {
+                point: Point::new(line, Column(column)),
+                cell,
+            })
+            .collect()
+    }
+
+    /// Move links found on the unfolded grid to the rows `visual_rows` shows
+    /// them on, dropping links inside folds.
+    fn remap_links(
+        &self,
+        links: Vec<DetectedLink>,
+        visual_rows: &[VisualRow],
+    ) -> Vec<DetectedLink> {
+        let display_offset = self.display_offset() as i32;
+        let visual_line = |line: Line| {
+            visual_rows
+                .iter()
+                .position(|row| matches!(row, VisualRow::Grid(source) if *source == line.0))
+                .map(|index| Line(index as i32 - 1 - display_offset))
+        };
+        links
+            .into_iter()
+            .filter_map(|mut link| {
+                link.start.line = visual_line(link.start.line)?;
+                link.end.line = visual_line(link.end.line)?;
+                Some(link)
+            })
+            .collect()
+    }
+
+    fn apply_tmux_update(&mut self, update: TmuxUpdate) {
+        self.advance_screen(&update.draw);
+        self.send_tmux_commands(update.commands);
+        if let Some(title) = update.title {
+            self.send_terminal_event(TerminalEvent::TitleChanged(Some(title)));
+        }
+    }
+
+    /// Control commands go to tmux verbatim, bypassing `encode_input`.
+    fn send_tmux_commands(&self, commands: Vec<u8>) {
+        if commands.is_empty() {
+            return;
+        }
+        if let Some(tx) = &self.input_tx {
+            if let Err(e) = tx.try_send(commands) {
+                error!("failed to send tmux commands: {:?}", e);
+            }
+        }
+    }
+
+    /// Advance one line at a time so the cursor still sits on (or just below)
+    /// the line a trigger matched when it is highlighted.
+    fn advance_with_triggers(&mut self, bytes: &[u8]) {
+        let now = Instant::now();
+        for segment in bytes.split_inclusive(|byte| *byte == b'\n') {
+            self.processor.advance(&mut self.term, segment);
+            let line_ended = segment.ends_with(b"\n");
+            for fired in self.triggers.feed(segment, now) {
+                match &fired.action {
+                    TriggerAction::Highlight => self.highlight_trigger_line(line_ended),
+                    TriggerAction::Reply(text) => {
+                        self.send_bytes_sync(format!("{text}\r").into_bytes());
+                    }
+                    TriggerAction::Notify | TriggerAction::CopyCapture(_) => {}
+                }
+                info!(trigger = fired.name, "terminal trigger fired");
+                self.send_terminal_event(TerminalEvent::TriggerFired(fired));
+            }
+        }
+    }
+
+    /// Tint the line that just ended (or the cursor line for a partial match),
+    /// including the soft-wrapped rows above it. Skipped on the alt screen,
+    /// where full-screen apps redraw every cell.
+    fn highlight_trigger_line(&mut self, line_ended: bool) {
+        if self.term.mode().contains(TermMode::ALT_SCREEN) {
+            return;
+        }
+        let columns = self.term.grid().columns();
+        let top = -(self.history_size() as i32);
+        let cursor_line = self.term.grid().cursor.point.line.0;
+        let mut line = if line_ended {
+            cursor_line - 1
+        } else {
+            cursor_line
+        };
+        if line < top || columns == 0 {
+            return;
+        }
+        let color = AlacColor::Spec(triggers::highlight_rgb(&self.theme));
+        let grid = self.term.grid_mut();
+        loop {
+            for column in 0..columns {
+                grid[Line(line)][Column(column)].bg = color;
+            }
+            if line <= top
+                || !grid[Line(line - 1)][Column(columns - 1)]
+                    .flags
+                    .contains(CellFlags::WRAPLINE)
+            {
+                break;
+            }
+            line -= 1;
+        }
+    }
+
     fn finish_output_update(&mut self, was_alt: bool, previous_display_offset: usize) {
         self.drain_alacritty_events();
         self.mode = *self.term.mode();
@@ -327,6 +931,15 @@ impl Terminal {
         if is_alt != was_alt {
             let _ = self.event_tx.send(TerminalEvent::AltScreenChanged(is_alt));
         }
+        // Scrolled up, the grid keeps the view on the same rows by raising the
+        // display offset by the lines pushed into history; at the bottom it
+        // stays at zero and follows the output.
+        let display_offset = self.display_offset();
+        if display_offset == 0 {
+            self.unseen_lines = 0;
+        } else if previous_display_offset > 0 {
+            self.unseen_lines += display_offset.saturating_sub(previous_display_offset);
+        }
         self.emit_scrollback_position_if_changed(previous_display_offset);
     }
 
@@ -441,21 +1054,27 @@ impl Terminal {
 
     /// Get a snapshot of the terminal content for rendering
     pub fn content(&self) -> TerminalContent {
+        let visual_rows = self.visual_rows();
         let content = self.term.renderable_content();
         let mut cells = Vec::new();
 
-        for ic in content.display_iter {
-            cells.push(IndexedCell {
-                point: ic.point,
-                cell: ic.cell.clone(),
-            });
+        if let Some(visual_rows) = &visual_rows {
+            cells = self.folded_cells(visual_rows);
+        } else {
+            for ic in content.display_iter {
+                cells.push(IndexedCell {
+                    point: ic.point,
+                    cell: ic.cell.clone(),
+                });
+            }
         }
 
         // Overscan: include one grid row just above and below the viewport so
         // sub-line smooth scrolling slides real content into the edge gap
         // instead of popping a whole row in once it is fully visible.
         // `display_iter` yields grid lines in `[-display_offset, rows-1-display_offset]`.
-        {
+        // Folded layouts carry their own overscan rows.
+        if visual_rows.is_none() {
             let grid = self.term.grid();
             let cols = self.size.columns;
             let display_offset = content.display_offset as i32;
@@ -480,9 +1099,15 @@ impl Terminal {
         }
 
         let cursor_point = content.cursor.point;
-        let cursor_char = self.term.grid()[cursor_point].c;
+        let cursor_cell = &self.term.grid()[cursor_point];
+        let cursor_char = cursor_cell.c;
+        let cursor_wide = cursor_cell.flags.contains(CellFlags::WIDE_CHAR);
+        let cursor_blinking = self.term.cursor_style().blinking;
 
-        let detected_links = self.detect_plain_links();
+        let mut detected_links = self.detect_plain_links();
+        if let Some(visual_rows) = &visual_rows {
+            detected_links = self.remap_links(detected_links, visual_rows);
+        }
         TerminalContent {
             cells,
             mode: content.mode,
@@ -490,8 +1115,10 @@ impl Terminal {
             cursor: CursorState {
                 point: cursor_point,
                 shape: content.cursor.shape,
+                blinking: cursor_blinking,
             },
             cursor_char,
+            cursor_wide,
             grid_rows: self.size.rows,
             grid_cols: self.size.columns,
             detected_links,
@@ -500,6 +1127,10 @@ impl Terminal {
 
     /// Handle a keystroke, converting to escape sequence and sending via SSH or RPC session
     pub fn handle_keystroke(&mut self, keystroke: &Keystroke) {
+        if let Some(guarded) = self.guarded_command(keystroke) {
+            self.send_terminal_event(TerminalEvent::CommandGuarded(guarded));
+            return;
+        }
         // Try to convert keystroke to terminal escape sequence
         if let Some(bytes) = self.try_keystroke(keystroke) {
             self.send_bytes_sync(bytes);
@@ -514,6 +1145,30 @@ impl Terminal {
         }
     }
 
+    /// The command line a plain Enter would submit, when a guard rule
+    /// matches it.
+    fn guarded_command(&self, keystroke: &Keystroke) -> Option<GuardedCommand> {
+        if self.command_guard.is_empty()
+            || keystroke.key != "enter"
+            || keystroke.modifiers.modified()
+        {
+            return None;
+        }
+        command_guard::check(&self.command_guard, &self.pending_command_line()?)
+    }
+
+    /// Send the Enter held back by `TerminalEvent::CommandGuarded`.
+    pub fn submit_guarded_command(&mut self) {
+        let enter = Keystroke {
+            modifiers: Default::default(),
+            key: "enter".to_string(),
+            key_char: None,
+        };
+        if let Some(bytes) = self.try_keystroke(&enter) {
+            self.send_bytes_sync(bytes);
+        }
+    }
+
     /// Convert a GPUI keystroke to terminal escape sequence bytes
     pub fn try_keystroke(&self, keystroke: &gpui::Keystroke) -> Option<Vec<u8>> {
         let esc = to_esc_str(keystroke, &self.mode, false);
@@ -535,7 +1190,16 @@ impl Terminal {
             columns,
             screen_lines: rows,
         };
+        let reflowed = columns != self.term.grid().columns();
         self.term.resize(term_size);
+        // Reflow moves text between rows, so recorded mark rows no longer apply.
+        if reflowed {
+            self.prompt_marks.clear();
+        }
+        if let Some(tmux) = &mut self.tmux {
+            let commands = tmux.resize(columns, rows);
+            self.send_tmux_commands(commands);
+        }
     }
 
     /// Get current terminal size
@@ -607,6 +1271,10 @@ impl Terminal {
     }
 
     pub fn send_bytes_sync(&self, bytes: Vec<u8>) {
+        let bytes = self.encode_input(bytes);
+        if bytes.is_empty() {
+            return;
+        }
         if let Some(tx) = &self.input_tx {
             if let Err(e) = tx.try_send(bytes) {
                 error!("failed to send bytes: {:?}", e);
@@ -622,6 +1290,7 @@ impl Terminal {
         } else {
             text.replace("\r\n", "\r").replace('\n', "\r").into_bytes()
         };
+        let bytes = self.encode_input(bytes);
 
         if let Some(tx) = &self.input_tx {
             if let Err(error) = tx.try_send(bytes) {
@@ -646,20 +1315,30 @@ impl Terminal {
         self.selection_range.is_some()
     }
 
-    /// Scroll the terminal by a number of lines (positive = up)
+    /// Scroll the terminal by a number of lines (positive = up). A folded
+    /// block counts as one line.
     pub fn scroll(&mut self, lines: i32) {
         let previous_display_offset = self.display_offset();
-        let scroll = Scroll::Delta(lines);
+        let scroll = Scroll::Delta(self.fold_adjusted_scroll(lines));
         self.term.scroll_display(scroll);
+        if self.display_offset() == 0 {
+            self.unseen_lines = 0;
+        }
         self.emit_scrollback_position_if_changed(previous_display_offset);
     }
 
     pub fn scroll_to_bottom(&mut self) {
         let previous_display_offset = self.display_offset();
         self.term.scroll_display(Scroll::Bottom);
+        self.unseen_lines = 0;
         self.emit_scrollback_position_if_changed(previous_display_offset);
     }
 
+    /// Lines of output that arrived since the view left the bottom.
+    pub fn unseen_lines(&self) -> usize {
+        self.unseen_lines
+    }
+
     /// Current display offset (0 = bottom, history_size = top)
     pub fn display_offset(&self) -> usize {
         self.term.grid().display_offset()
@@ -670,6 +1349,23 @@ impl Terminal {
         self.term.grid().history_size()
     }
 
+    /// Drop scrollback beyond the newest `keep_lines` history lines, keeping the
+    /// normal scrollback limit for new output. Returns the approximate number of
+    /// bytes released.
+    pub fn trim_scrollback(&mut self, keep_lines: usize) -> usize {
+        let history = self.history_size();
+        if history <= keep_lines {
+            return 0;
+        }
+        let previous_display_offset = self.display_offset();
+        let grid = self.term.grid_mut();
+        grid.update_history(keep_lines);
+        grid.update_history(Config::default().scrolling_history);
+        self.prompt_marks.shift(history - keep_lines);
+        self.emit_scrollback_position_if_changed(previous_display_offset);
+        (history - keep_lines) * self.size.columns * std::mem::size_of::<Cell>()
+    }
+
     fn emit_scrollback_position_if_changed(&self, previous_display_offset: usize) {
         if self.display_offset() == previous_display_offset {
             return;
@@ -1557,6 +2253,47 @@ impl Terminal {
         self.hyperlink_at_point(point, workdir)
     }
 
+    /// The file under `position` for the long-press path actions: a file
+    /// hyperlink there, or else the path-like token there when it names a file
+    /// with a known extension. Relative paths resolve against `workdir`.
+    pub fn file_path_at(
+        &self,
+        position: gpui::Point<Pixels>,
+        grid_origin: Option<gpui::Point<Pixels>>,
+        workdir: Option<&str>,
+    ) -> Option<TerminalHyperlink> {
+        let point = self.grid_point_at(position, grid_origin)?;
+        if let Some(hyperlink) = self.hyperlink_at_point(point, workdir) {
+            return matches!(hyperlink.target, TerminalHyperlinkTarget::File { .. })
+                .then_some(hyperlink);
+        }
+        let topmost = self.term.grid().topmost_line();
+        if point.line < topmost || point.line > Line(self.size.rows as i32 - 1) {
+            return None;
+        }
+        let row = &self.term.grid()[point.line];
+        let mut chars = Vec::new();
+        let mut index = None;
+        for col in 0..self.size.columns {
+            let cell = &row[Column(col)];
+            if cell
+                .flags
+                .intersects(CellFlags::WIDE_CHAR_SPACER | CellFlags::LEADING_WIDE_CHAR_SPACER)
+            {
+                continue;
+            }
+            if col <= point.column.0 {
+                index = Some(chars.len());
+            }
+            chars.push(match cell.c {
+                '\0' => ' ',
+                c => c,
+            });
+        }
+        let token = path_token_around(&chars, index?)?;
+        Self::file_hyperlink_from_osc8(&token, token.clone(), workdir)
+    }
+
     fn grid_point_at(
         &self,
         position: gpui::Point<Pixels>,
@@ -1578,7 +2315,14 @@ impl Terminal {
             (relative.y / self.size.line_height) as usize,
             rows.saturating_sub(1),
         ) as i32;
-        let line = viewport_line - self.display_offset() as i32;
+        let line = match self.visual_rows() {
+            // Index 0 is the overscan row above the viewport.
+            Some(visual_rows) => match visual_rows.get(viewport_line as usize + 1)? {
+                VisualRow::Grid(line) => *line,
+                VisualRow::Header(_) | VisualRow::Blank => return None,
+            },
+            None => viewport_line - self.display_offset() as i32,
+        };
         Some(Point::new(Line(line), Column(column)))
     }
 
@@ -2531,6 +3275,28 @@ fn is_path_trailing_punct(c: char) -> bool {
     matches!(c, '.' | ',' | ';' | '!' | '?' | ':')
 }
 
+/// The path-like token covering `chars[index]`, with trailing punctuation
+/// dropped, when its path part has a known file extension.
+fn path_token_around(chars: &[char], index: usize) -> Option<String> {
+    if !is_path_char(*chars.get(index)?) {
+        return None;
+    }
+    let mut start = index;
+    while start > 0 && is_path_char(chars[start - 1]) {
+        start -= 1;
+    }
+    let mut end = index + 1;
+    while end < chars.len() && is_path_char(chars[end]) {
+        end += 1;
+    }
+    while end > start && is_path_trailing_punct(chars[end - 1]) {
+        end -= 1;
+    }
+    let token: String = chars[start..end].iter().collect();
+    let (path, _, _) = split_file_position_chars(&token);
+    token_has_known_file_extension(path).then_some(token)
+}
+
 fn split_file_position_chars(token: &str) -> (&str, Option<u32>, Option<u32>) {
     let mut pieces = token.rsplit(':');
     let last = pieces.next();
@@ -3928,6 +4694,46 @@ mod tests {
         }
     }
 
+    #[test]
+    fn output_while_scrolled_up_counts_unseen_lines_and_keeps_the_view() {
+        let mut terminal = terminal_with_history();
+        terminal.advance_bytes(b"at bottom\r\n");
+        assert_eq!((terminal.display_offset(), terminal.unseen_lines()), (0, 0));
+
+        terminal.scroll(5);
+        let scrolled = terminal.display_offset();
+        for line in 0..3 {
+            terminal.advance_bytes(format!("new {line}\r\n").as_bytes());
+        }
+        assert_eq!(terminal.display_offset(), scrolled + 3);
+        assert_eq!(terminal.unseen_lines(), 3);
+
+        terminal.scroll(-1);
+        assert_eq!(terminal.unseen_lines(), 3);
+        terminal.scroll_to_bottom();
+        assert_eq!(terminal.unseen_lines(), 0);
+        terminal.advance_bytes(b"followed\r\n");
+        assert_eq!((terminal.display_offset(), terminal.unseen_lines()), (0, 0));
+    }
+
+    #[test]
+    fn trim_scrollback_keeps_newest_lines_and_scrollback_limit() {
+        let mut terminal = terminal_with_history();
+        let history = terminal.history_size();
+        assert!(history > 3);
+        assert_eq!(terminal.trim_scrollback(history), 0);
+
+        terminal.scroll(history as i32);
+        assert!(terminal.trim_scrollback(3) > 0);
+        assert_eq!(terminal.history_size(), 3);
+        assert!(terminal.display_offset() <= 3);
+
+        for line in 0..6 {
+            terminal.advance_bytes(format!("more {line}\r\n").as_bytes());
+        }
+        assert!(terminal.history_size() > 3);
+    }
+
     #[test]
     fn detects_plain_file_links_stripped_of_surrounding_punctuation() {
         let line = r#"Open ("src/main.rs:12:3") next"#;
@@ -4663,6 +5469,18 @@ mod tests {
         assert!(!super::tail_looks_like_cut_off_path(&cells));
     }
 
+    #[test]
+    fn path_token_around_takes_compiler_locations() {
+        let chars: Vec<char> = "error at (src/main.rs:12:3). see README".chars().collect();
+        assert_eq!(
+            super::path_token_around(&chars, 14).as_deref(),
+            Some("src/main.rs:12:3")
+        );
+        assert_eq!(super::path_token_around(&chars, 2), None);
+        assert_eq!(super::path_token_around(&chars, 8), None);
+        assert_eq!(super::path_token_around(&chars, 36), None);
+    }
+
     #[test]
     fn tail_cut_off_rejects_all_whitespace() {
         let cells = cells_from("       ");
@@ -4740,4 +5558,223 @@ mod tests {
         assert_eq!(links.len(), 1, "expected only URL match, got {:?}", links);
         assert_eq!(links[0].kind, super::DetectedLinkKind::Url);
     }
+
+    #[test]
+    fn triggers_highlight_matched_line_and_reply() {
+        use crate::triggers::{TriggerAction, TriggerRule};
+        use alacritty_terminal::vte::ansi::Color as AlacColor;
+
+        let mut terminal = Terminal::new(40, 6, px(10.0), px(20.0));
+        let (input_tx, mut input_rx) = mpsc::channel(4);
+        terminal.input_tx = Some(input_tx);
+        let mut events = terminal.subscribe_events();
+        terminal.set_triggers(vec![
+            TriggerRule::new("failed", "FAILED", TriggerAction::Highlight).unwrap(),
+            TriggerRule::new("sudo", "password for", TriggerAction::Reply("pw".into())).unwrap(),
+        ]);
+
+        terminal.advance_bytes(b"ok\r\nBUILD FAILED\r\nok\r\n[sudo] password for me: ");
+
+        let row_bg = |line: i32| terminal.term.grid()[Line(line)][Column(0)].bg;
+        assert!(matches!(row_bg(1), AlacColor::Spec(_)));
+        assert!(!matches!(row_bg(0), AlacColor::Spec(_)));
+        assert!(!matches!(row_bg(2), AlacColor::Spec(_)));
+        assert_eq!(input_rx.try_recv().unwrap(), b"pw\r".to_vec());
+
+        let fired: Vec<_> = std::iter::from_fn(|| events.try_recv().ok())
+            .filter_map(|event| match event {
+                TerminalEvent::TriggerFired(fired) => Some(fired.name),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(fired, vec!["failed", "sudo"]);
+    }
+
+    #[test]
+    fn command_guard_holds_enter_until_confirmed() {
+        use crate::command_guard::GuardRule;
+
+        let mut terminal = Terminal::new(40, 6, px(10.0), px(20.0));
+        let (input_tx, mut input_rx) = mpsc::channel(4);
+        terminal.input_tx = Some(input_tx);
+        let mut events = terminal.subscribe_events();
+        terminal.set_command_guard(vec![GuardRule::new("rm root", r"rm -rf /$").unwrap()]);
+        let enter = gpui::Keystroke {
+            modifiers: Default::default(),
+            key: "enter".to_string(),
+            key_char: None,
+        };
+
+        // With shell integration the prompt is left out of the command line.
+        terminal.advance_bytes(b"\x1b]133;A\x07$ \x1b]133;B\x07rm -rf /");
+        assert_eq!(terminal.pending_command_line().as_deref(), Some("rm -rf /"));
+        terminal.handle_keystroke(&enter);
+        assert!(input_rx.try_recv().is_err());
+        let guarded = std::iter::from_fn(|| events.try_recv().ok())
+            .find_map(|event| match event {
+                TerminalEvent::CommandGuarded(guarded) => Some(guarded),
+                _ => None,
+            })
+            .expect("expected the command to be guarded");
+        assert_eq!(guarded.command, "rm -rf /");
+
+        terminal.submit_guarded_command();
+        assert_eq!(input_rx.try_recv().unwrap(), b"\r".to_vec());
+
+        // Without it the whole cursor line is checked.
+        terminal.advance_bytes(b"\r\n\x1b]133;C\x07\x1b]133;D;0\x07$ rm -rf ./build");
+        terminal.handle_keystroke(&enter);
+        assert_eq!(input_rx.try_recv().unwrap(), b"\r".to_vec());
+    }
+
+    #[test]
+    fn tmux_control_mode_renders_active_pane_and_wraps_input() {
+        let mut terminal = Terminal::new(40, 6, px(10.0), px(20.0));
+        let (input_tx, mut input_rx) = mpsc::channel(8);
+        terminal.input_tx = Some(input_tx);
+
+        terminal.advance_bytes(b"$ tmux -CC\r\n\x1bP1000p%begin 1 1 0\n%end 1 1 0\n");
+        assert!(terminal.is_tmux_control_mode());
+        assert!(terminal.input_sender().is_none());
+        let commands = input_rx.try_recv().unwrap();
+        assert!(commands.starts_with(b"refresh-client -C 40x6\n"));
+
+        terminal.advance_bytes(
+            b"%begin 1 2 1\n%end 1 2 1\n%begin 1 3 1\n@0 %0\n%end 1 3 1\n%output %0 pane\\015\\012\n",
+        );
+        assert!(input_rx.try_recv().unwrap().starts_with(b"capture-pane"));
+        let text: String = terminal
+            .content()
+            .cells
+            .iter()
+            .map(|cell| cell.cell.c)
+            .collect();
+        assert!(text.contains("pane"));
+        assert!(!text.contains("%begin"));
+
+        terminal.send_bytes_sync(b"a".to_vec());
+        assert_eq!(
+            input_rx.try_recv().unwrap(),
+            b"send-keys -t %0 -H 61\n".to_vec()
+        );
+
+        terminal.advance_bytes(b"%exit\n\x1b\\$ ");
+        assert!(!terminal.is_tmux_control_mode());
+        terminal.send_bytes_sync(b"a".to_vec());
+        assert_eq!(input_rx.try_recv().unwrap(), b"a".to_vec());
+    }
+
+    #[test]
+    fn decscusr_overrides_the_default_cursor_until_reset() {
+        let mut terminal = Terminal::new(20, 4, px(10.0), px(20.0));
+        terminal.set_default_cursor_style(DefaultCursorShape::Underline, false);
+        let cursor = terminal.content().cursor;
+        assert_eq!(
+            (cursor.shape, cursor.blinking),
+            (CursorShape::Underline, false)
+        );
+
+        // vim insert mode: blinking bar.
+        terminal.advance_bytes(b"\x1b[5 q");
+        let cursor = terminal.content().cursor;
+        assert_eq!((cursor.shape, cursor.blinking), (CursorShape::Beam, true));
+
+        // Steady block.
+        terminal.advance_bytes(b"\x1b[2 q");
+        let cursor = terminal.content().cursor;
+        assert_eq!((cursor.shape, cursor.blinking), (CursorShape::Block, false));
+
+        terminal.advance_bytes(b"\x1b[0 q");
+        assert_eq!(terminal.content().cursor.shape, CursorShape::Underline);
+
+        terminal.advance_bytes(b"\x1b[?25l");
+        assert_eq!(terminal.content().cursor.shape, CursorShape::Hidden);
+    }
+
+    #[test]
+    fn prompt_marks_copy_last_output_and_jump_between_prompts() {
+        let mut terminal = Terminal::new(40, 4, px(10.0), px(20.0));
+        let prompt = b"\x1b]133;D;0\x07\x1b]133;A\x07$ \x1b]133;B\x07";
+        terminal.advance_bytes(prompt);
+        terminal.advance_bytes(b"ls\r\n\x1b]133;C\x07a.txt\r\nb.txt\r\n");
+        terminal.advance_bytes(prompt);
+        terminal.advance_bytes(b"true\r\n\x1b]133;C\x07");
+        terminal.advance_bytes(prompt);
+        // An empty command line ends without a 133;C.
+        terminal.advance_bytes(b"\r\n");
+        terminal.advance_bytes(prompt);
+
+        assert!(terminal.has_prompt_marks());
+        assert_eq!(terminal.last_command_output().as_deref(), Some(""));
+
+        terminal.advance_bytes(b"ls\r\n\x1b]133;C\x07a.txt\r\nb.txt\r\n");
+        terminal.advance_bytes(prompt);
+        assert_eq!(
+            terminal.last_command_output().as_deref(),
+            Some("a.txt\nb.txt")
+        );
+
+        // Prompts sit on rows 0, 3, 4, 5 and 8; the screen shows rows 5-8.
+        assert_eq!(terminal.history_size(), 5);
+        assert!(terminal.scroll_to_prompt(PromptJump::Previous));
+        assert_eq!(terminal.display_offset(), 1);
+        assert!(terminal.scroll_to_prompt(PromptJump::Previous));
+        assert_eq!(terminal.display_offset(), 2);
+        assert!(terminal.scroll_to_prompt(PromptJump::Previous));
+        assert_eq!(terminal.display_offset(), 5);
+        assert!(!terminal.scroll_to_prompt(PromptJump::Previous));
+        assert!(terminal.scroll_to_prompt(PromptJump::Next));
+        assert_eq!(terminal.display_offset(), 2);
+    }
+
+    #[test]
+    fn folded_outputs_render_as_headers_and_scroll_as_one_row() {
+        let mut terminal = Terminal::new(40, 6, px(10.0), px(20.0));
+        let prompt = b"\x1b]133;D;0\x07\x1b]133;A\x07$ \x1b]133;B\x07";
+        terminal.advance_bytes(b"\x1b]133;A\x07$ \x1b]133;B\x07seq 12\r\n\x1b]133;C\x07");
+        for n in 1..=12 {
+            terminal.advance_bytes(format!("{n}\r\n").as_bytes());
+        }
+        terminal.advance_bytes(prompt);
+        terminal.advance_bytes(b"echo hi\r\n\x1b]133;C\x07hi\r\n");
+        terminal.advance_bytes(prompt);
+
+        // Rows 0-15; the screen shows 10-15.
+        assert_eq!(terminal.history_size(), 10);
+        assert!(terminal.has_foldable_outputs());
+        assert!(terminal.set_outputs_folded(true));
+        assert!(terminal.has_folded_outputs());
+
+        let content = terminal.content();
+        let row_text = |line: i32| {
+            content
+                .cells
+                .iter()
+                .filter(|cell| cell.point.line.0 + content.display_offset as i32 == line)
+                .map(|cell| cell.cell.c)
+                .collect::<String>()
+                .trim_end()
+                .to_string()
+        };
+        // Rows 0-12 collapse into one header; nothing older is left above it.
+        assert_eq!(row_text(1), "");
+        assert_eq!(row_text(2), "▸ seq 12 — 12 lines");
+        assert_eq!(row_text(3), "$ echo hi");
+        assert_eq!(row_text(5), "$");
+
+        // Three rows up puts the header at the bottom; one more passes it.
+        terminal.scroll(3);
+        assert_eq!(terminal.display_offset(), 3);
+        terminal.scroll(1);
+        assert_eq!(terminal.display_offset(), 10);
+        terminal.scroll_to_bottom();
+
+        assert!(terminal.unfold_at(
+            gpui::point(px(5.0), px(50.0)),
+            Some(gpui::point(px(0.0), px(0.0)))
+        ));
+        assert!(!terminal.has_folded_outputs());
+        let content = terminal.content();
+        assert!(content.cells.iter().all(|cell| cell.cell.c != '▸'));
+    }
 }
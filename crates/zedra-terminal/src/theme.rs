@@ -84,6 +84,9 @@ pub struct TerminalTheme {
     pub ansi: AnsiPalette,
     pub dim_lightness_factor: f32,
     pub dim_alpha_factor: f32,
+    /// Alpha for default-background cells. Cells with an explicit background
+    /// color, the cursor, and text stay opaque.
+    pub background_opacity: f32,
     indexed: [u32; 256],
 }
 
@@ -199,10 +202,41 @@ impl TerminalTheme {
             ansi,
             dim_lightness_factor,
             dim_alpha_factor,
+            background_opacity: 1.0,
             indexed: build_indexed_table(ansi),
         }
     }
 
+    /// Same dim factors and opacity with new base colors, e.g. from an imported theme.
+    pub fn with_colors(
+        self,
+        background: u32,
+        foreground: u32,
+        cursor: u32,
+        ansi: AnsiPalette,
+    ) -> Self {
+        Self {
+            background,
+            foreground,
+            cursor,
+            ansi,
+            indexed: build_indexed_table(ansi),
+            ..self
+        }
+    }
+
+    pub fn with_background_opacity(mut self, opacity: f32) -> Self {
+        self.background_opacity = opacity.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Fill behind the grid: the theme background at `background_opacity`.
+    pub fn background_fill(&self) -> Hsla {
+        let mut color: Hsla = rgb(self.background).into();
+        color.a = self.background_opacity;
+        color
+    }
+
     pub fn is_light(&self) -> bool {
         relative_luminance(self.background) >= 0.5
     }
@@ -378,6 +412,22 @@ mod tests {
     use super::*;
     use alacritty_terminal::vte::ansi::Color as AlacColor;
 
+    #[test]
+    fn background_opacity_only_changes_the_fill_alpha() {
+        let theme = TerminalTheme::dark().with_background_opacity(0.8);
+        assert_eq!(theme.background_fill().a, 0.8);
+        assert_eq!(TerminalTheme::dark().background_fill().a, 1.0);
+        assert_eq!(
+            TerminalTheme::dark()
+                .with_background_opacity(1.5)
+                .background_opacity,
+            1.0
+        );
+        // Inverse text drawn in the background color must stay opaque.
+        let named = theme.convert_color(&AlacColor::Named(NamedColor::Background));
+        assert_eq!(named.a, 1.0);
+    }
+
     #[test]
     fn light_named_blue_matches_one_light() {
         let theme = TerminalTheme::light();
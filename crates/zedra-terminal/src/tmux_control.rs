@@ -0,0 +1,700 @@
+// tmux control mode (`tmux -CC`) client.
+//
+// tmux announces control mode with the DCS introducer `ESC P 1000 p` and then
+// speaks a line protocol until `%exit` and the string terminator:
+//
+//   %begin 1700000000 42 1      reply to the 42nd command, sent by this client
+//   %1                          reply body
+//   %end 1700000000 42 1
+//   %output %3 ls\015\012       pane output, octal-escaped
+//   %window-pane-changed @1 %3
+//   %exit
+//
+// `ControlParser` turns that stream into `ControlEvent`s. `TmuxControl` keeps
+// the session's windows and layouts, follows the active pane, and produces the
+// bytes to draw plus the command lines to write back. The terminal renders the
+// active pane of the active window; other panes stay alive inside tmux and are
+// redrawn from `capture-pane` when they become active.
+
+use std::collections::VecDeque;
+
+/// DCS sequence tmux prints when a control client attaches.
+pub const CONTROL_MODE_START: &[u8] = b"\x1bP1000p";
+
+/// Longest protocol line kept; tmux splits pane output well below this.
+const MAX_LINE_BYTES: usize = 1 << 20;
+/// Input bytes per `send-keys` command, keeping each command line short.
+const SEND_KEYS_CHUNK: usize = 256;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TmuxLayout {
+    Pane {
+        id: u32,
+        width: u16,
+        height: u16,
+    },
+    /// Children side by side (`{...}`).
+    Columns(Vec<TmuxLayout>),
+    /// Children stacked top to bottom (`[...]`).
+    Rows(Vec<TmuxLayout>),
+}
+
+impl TmuxLayout {
+    /// Parse a `#{window_layout}` string such as
+    /// `b25d,80x24,0,0{40x24,0,0,1,39x24,41,0,2}`.
+    pub fn parse(layout: &str) -> Option<Self> {
+        let (_checksum, cell) = layout.split_once(',')?;
+        let mut rest = cell.as_bytes();
+        let parsed = parse_cell(&mut rest)?;
+        rest.is_empty().then_some(parsed)
+    }
+
+    /// Pane ids in layout order.
+    pub fn panes(&self) -> Vec<u32> {
+        let mut panes = Vec::new();
+        self.collect_panes(&mut panes);
+        panes
+    }
+
+    fn collect_panes(&self, panes: &mut Vec<u32>) {
+        match self {
+            Self::Pane { id, .. } => panes.push(*id),
+            Self::Columns(children) | Self::Rows(children) => {
+                for child in children {
+                    child.collect_panes(panes);
+                }
+            }
+        }
+    }
+}
+
+fn take_number(rest: &mut &[u8]) -> Option<u32> {
+    let digits = rest.iter().take_while(|b| b.is_ascii_digit()).count();
+    let number = std::str::from_utf8(&rest[..digits]).ok()?.parse().ok()?;
+    *rest = &rest[digits..];
+    Some(number)
+}
+
+fn expect(rest: &mut &[u8], byte: u8) -> Option<()> {
+    let (&first, tail) = rest.split_first()?;
+    (first == byte).then(|| *rest = tail)
+}
+
+fn parse_cell(rest: &mut &[u8]) -> Option<TmuxLayout> {
+    let width = take_number(rest)?;
+    expect(rest, b'x')?;
+    let height = take_number(rest)?;
+    expect(rest, b',')?;
+    take_number(rest)?;
+    expect(rest, b',')?;
+    take_number(rest)?;
+    let (&next, tail) = rest.split_first()?;
+    *rest = tail;
+    let close = match next {
+        b',' => {
+            return Some(TmuxLayout::Pane {
+                id: take_number(rest)?,
+                width: u16::try_from(width).ok()?,
+                height: u16::try_from(height).ok()?,
+            });
+        }
+        b'{' => b'}',
+        b'[' => b']',
+        _ => return None,
+    };
+    let mut children = Vec::new();
+    loop {
+        children.push(parse_cell(rest)?);
+        let (&separator, tail) = rest.split_first()?;
+        *rest = tail;
+        if separator == close {
+            break;
+        }
+        if separator != b',' {
+            return None;
+        }
+    }
+    Some(if close == b'}' {
+        TmuxLayout::Columns(children)
+    } else {
+        TmuxLayout::Rows(children)
+    })
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ControlEvent {
+    /// A `%begin` .. `%end` / `%error` reply. `from_client` is set for replies
+    /// to commands this client wrote.
+    CommandResult {
+        from_client: bool,
+        lines: Vec<String>,
+        error: bool,
+    },
+    Output {
+        pane: u32,
+        data: Vec<u8>,
+    },
+    WindowAdd {
+        window: u32,
+    },
+    WindowClose {
+        window: u32,
+    },
+    WindowRenamed {
+        window: u32,
+        name: String,
+    },
+    LayoutChange {
+        window: u32,
+        layout: Option<TmuxLayout>,
+    },
+    WindowPaneChanged {
+        window: u32,
+        pane: u32,
+    },
+    SessionWindowChanged {
+        window: u32,
+    },
+    Exit {
+        reason: Option<String>,
+    },
+}
+
+/// Parse a `@3` / `%3` / `$3` id.
+fn parse_id(token: &str, sigil: char) -> Option<u32> {
+    token.strip_prefix(sigil)?.parse().ok()
+}
+
+/// Undo `%output` escaping: bytes below 0x20 and `\` arrive as `\ooo` octal.
+pub fn unescape_output(escaped: &[u8]) -> Vec<u8> {
+    let mut data = Vec::with_capacity(escaped.len());
+    let mut i = 0;
+    while i < escaped.len() {
+        if escaped[i] == b'\\'
+            && let Some(octal) = escaped.get(i + 1..i + 4)
+            && octal.iter().all(|b| (b'0'..=b'7').contains(b))
+        {
+            let value = octal
+                .iter()
+                .fold(0u32, |acc, digit| acc * 8 + u32::from(digit - b'0'));
+            data.push(value as u8);
+            i += 4;
+        } else {
+            data.push(escaped[i]);
+            i += 1;
+        }
+    }
+    data
+}
+
+struct PendingBlock {
+    number: String,
+    from_client: bool,
+    lines: Vec<String>,
+}
+
+#[derive(Default)]
+pub struct ControlParser {
+    line: Vec<u8>,
+    block: Option<PendingBlock>,
+    exited: bool,
+    saw_escape: bool,
+}
+
+impl ControlParser {
+    /// Feed bytes from the PTY. Returns the parsed events and, once tmux has
+    /// left control mode, how many bytes of `bytes` belonged to it; the rest
+    /// is ordinary terminal output again.
+    pub fn feed(&mut self, bytes: &[u8]) -> (Vec<ControlEvent>, Option<usize>) {
+        let mut events = Vec::new();
+        for (index, &byte) in bytes.iter().enumerate() {
+            if self.exited {
+                // Skip to the string terminator that closes the DCS.
+                if self.saw_escape && byte == b'\\' {
+                    return (events, Some(index + 1));
+                }
+                self.saw_escape = byte == 0x1b;
+                continue;
+            }
+            match byte {
+                b'\n' => {
+                    let line = std::mem::take(&mut self.line);
+                    self.handle_line(&line, &mut events);
+                }
+                // A bare ST without `%exit` (tmux killed) also ends control mode.
+                b'\\' if self.line == [0x1b] => {
+                    self.line.clear();
+                    events.push(ControlEvent::Exit { reason: None });
+                    return (events, Some(index + 1));
+                }
+                _ if self.line.len() < MAX_LINE_BYTES => self.line.push(byte),
+                _ => {}
+            }
+        }
+        (events, None)
+    }
+
+    fn handle_line(&mut self, line: &[u8], events: &mut Vec<ControlEvent>) {
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        if let Some(block) = &mut self.block {
+            let text = String::from_utf8_lossy(line);
+            let mut fields = text.split(' ');
+            let guard = fields.next();
+            let number = fields.nth(1);
+            if matches!(guard, Some("%end" | "%error")) && number == Some(block.number.as_str()) {
+                let block = self.block.take().expect("block checked above");
+                events.push(ControlEvent::CommandResult {
+                    from_client: block.from_client,
+                    lines: block.lines,
+                    error: guard == Some("%error"),
+                });
+            } else {
+                block.lines.push(text.into_owned());
+            }
+            return;
+        }
+
+        if let Some(escaped) = line.strip_prefix(b"%output ") {
+            let Some(space) = escaped.iter().position(|b| *b == b' ') else {
+                return;
+            };
+            let pane = std::str::from_utf8(&escaped[..space])
+                .ok()
+                .and_then(|token| parse_id(token, '%'));
+            if let Some(pane) = pane {
+                events.push(ControlEvent::Output {
+                    pane,
+                    data: unescape_output(&escaped[space + 1..]),
+                });
+            }
+            return;
+        }
+
+        let text = String::from_utf8_lossy(line);
+        let mut fields = text.splitn(3, ' ');
+        let keyword = fields.next().unwrap_or_default();
+        let first = fields.next().unwrap_or_default();
+        let rest = fields.next().unwrap_or_default();
+        let window = parse_id(first, '@');
+        let event = match keyword {
+            "%begin" => {
+                let mut parts = rest.split(' ');
+                self.block = Some(PendingBlock {
+                    number: parts.next().unwrap_or_default().to_string(),
+                    from_client: parts.next().is_some_and(|flags| flags == "1"),
+                    lines: Vec::new(),
+                });
+                return;
+            }
+            "%window-add" => window.map(|window| ControlEvent::WindowAdd { window }),
+            "%window-close" | "%unlinked-window-close" => {
+                window.map(|window| ControlEvent::WindowClose { window })
+            }
+            "%window-renamed" => window.map(|window| ControlEvent::WindowRenamed {
+                window,
+                name: rest.to_string(),
+            }),
+            "%layout-change" => window.map(|window| ControlEvent::LayoutChange {
+                window,
+                layout: TmuxLayout::parse(rest.split(' ').next().unwrap_or_default()),
+            }),
+            "%window-pane-changed" => window
+                .zip(parse_id(rest, '%'))
+                .map(|(window, pane)| ControlEvent::WindowPaneChanged { window, pane }),
+            "%session-window-changed" => {
+                parse_id(rest, '@').map(|window| ControlEvent::SessionWindowChanged { window })
+            }
+            "%exit" => {
+                self.exited = true;
+                let reason = [first, rest]
+                    .into_iter()
+                    .filter(|part| !part.is_empty())
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                Some(ControlEvent::Exit {
+                    reason: (!reason.is_empty()).then_some(reason),
+                })
+            }
+            _ => None,
+        };
+        events.extend(event);
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TmuxWindow {
+    pub id: u32,
+    pub name: String,
+    pub layout: Option<TmuxLayout>,
+    pub active_pane: Option<u32>,
+}
+
+/// What a reply from tmux is for, in the order the commands were written.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Pending {
+    Ignore,
+    /// `#{window_id} #{pane_id}` of the attached session.
+    Attach,
+    ListWindows,
+    CapturePane {
+        pane: u32,
+    },
+    CursorPosition {
+        pane: u32,
+    },
+}
+
+/// Result of feeding output while in control mode.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct TmuxUpdate {
+    /// Bytes to draw on the terminal grid, in order.
+    pub draw: Vec<u8>,
+    /// Command lines to write to tmux, each ending in `\n`.
+    pub commands: Vec<u8>,
+    /// New title when the active window changed or was renamed.
+    pub title: Option<String>,
+    /// Set once control mode ended: how many input bytes it consumed.
+    pub exited_at: Option<usize>,
+}
+
+pub struct TmuxControl {
+    parser: ControlParser,
+    pending: VecDeque<Pending>,
+    windows: Vec<TmuxWindow>,
+    active_window: Option<u32>,
+    active_pane: Option<u32>,
+}
+
+impl TmuxControl {
+    /// Start a client and return the commands that size it and find the active pane.
+    pub fn start(columns: usize, rows: usize) -> (Self, Vec<u8>) {
+        let mut control = Self {
+            parser: ControlParser::default(),
+            pending: VecDeque::new(),
+            windows: Vec::new(),
+            active_window: None,
+            active_pane: None,
+        };
+        let mut commands = Vec::new();
+        control.command(
+            &mut commands,
+            &resize_command(columns, rows),
+            Pending::Ignore,
+        );
+        control.command(
+            &mut commands,
+            "display-message -p '#{window_id} #{pane_id}'",
+            Pending::Attach,
+        );
+        control.command(
+            &mut commands,
+            "list-windows -F '#{window_id} #{window_layout} #{window_name}'",
+            Pending::ListWindows,
+        );
+        (control, commands)
+    }
+
+    pub fn windows(&self) -> &[TmuxWindow] {
+        &self.windows
+    }
+
+    pub fn active_pane(&self) -> Option<u32> {
+        self.active_pane
+    }
+
+    fn command(&mut self, commands: &mut Vec<u8>, command: &str, pending: Pending) {
+        commands.extend_from_slice(command.as_bytes());
+        commands.push(b'\n');
+        self.pending.push_back(pending);
+    }
+
+    /// Wrap keyboard input for the active pane in `send-keys` commands.
+    pub fn encode_input(&self, bytes: &[u8]) -> Vec<u8> {
+        let Some(pane) = self.active_pane else {
+            return Vec::new();
+        };
+        let mut commands = Vec::new();
+        for chunk in bytes.chunks(SEND_KEYS_CHUNK) {
+            commands.extend_from_slice(format!("send-keys -t %{pane} -H").as_bytes());
+            for byte in chunk {
+                commands.extend_from_slice(format!(" {byte:02x}").as_bytes());
+            }
+            commands.push(b'\n');
+        }
+        commands
+    }
+
+    /// Command that resizes the control client, and with it the attached windows.
+    pub fn resize(&mut self, columns: usize, rows: usize) -> Vec<u8> {
+        let mut commands = Vec::new();
+        self.command(
+            &mut commands,
+            &resize_command(columns, rows),
+            Pending::Ignore,
+        );
+        commands
+    }
+
+    pub fn feed(&mut self, bytes: &[u8]) -> TmuxUpdate {
+        let (events, exited_at) = self.parser.feed(bytes);
+        let mut update = TmuxUpdate {
+            exited_at,
+            ..Default::default()
+        };
+        for event in events {
+            self.handle_event(event, &mut update);
+        }
+        update
+    }
+
+    fn handle_event(&mut self, event: ControlEvent, update: &mut TmuxUpdate) {
+        match event {
+            ControlEvent::Output { pane, data } => {
+                if self.active_pane == Some(pane) {
+                    update.draw.extend_from_slice(&data);
+                }
+            }
+            ControlEvent::CommandResult {
+                from_client,
+                lines,
+                error,
+            } => {
+                // Replies to commands from other clients or the attach
+                // command itself are not ours to consume.
+                if !from_client {
+                    return;
+                }
+                let Some(pending) = self.pending.pop_front() else {
+                    return;
+                };
+                if !error {
+                    self.handle_reply(pending, &lines, update);
+                }
+            }
+            ControlEvent::WindowAdd { window } => {
+                self.window_mut(window);
+            }
+            ControlEvent::WindowClose { window } => {
+                self.windows.retain(|w| w.id != window);
+            }
+            ControlEvent::WindowRenamed { window, name } => {
+                if self.active_window == Some(window) {
+                    update.title = Some(name.clone());
+                }
+                self.window_mut(window).name = name;
+            }
+            ControlEvent::LayoutChange { window, layout } => {
+                self.window_mut(window).layout = layout;
+            }
+            ControlEvent::WindowPaneChanged { window, pane } => {
+                self.window_mut(window).active_pane = Some(pane);
+                if self.active_window == Some(window) {
+                    self.switch_pane(pane, update);
+                }
+            }
+            ControlEvent::SessionWindowChanged { window } => {
+                self.active_window = Some(window);
+                let window = self.window_mut(window).clone();
+                if !window.name.is_empty() {
+                    update.title = Some(window.name);
+                }
+                match window.active_pane {
+                    Some(pane) => self.switch_pane(pane, update),
+                    None => self.command(
+                        &mut update.commands,
+                        "display-message -p '#{window_id} #{pane_id}'",
+                        Pending::Attach,
+                    ),
+                }
+            }
+            ControlEvent::Exit { .. } => {}
+        }
+    }
+
+    fn handle_reply(&mut self, pending: Pending, lines: &[String], update: &mut TmuxUpdate) {
+        match pending {
+            Pending::Ignore => {}
+            Pending::Attach => {
+                let Some((window, pane)) = lines.first().and_then(|line| line.split_once(' '))
+                else {
+                    return;
+                };
+                let (Some(window), Some(pane)) = (parse_id(window, '@'), parse_id(pane, '%'))
+                else {
+                    return;
+                };
+                self.active_window = Some(window);
+                self.window_mut(window).active_pane = Some(pane);
+                self.switch_pane(pane, update);
+            }
+            Pending::ListWindows => {
+                for line in lines {
+                    let mut fields = line.splitn(3, ' ');
+                    let Some(id) = fields.next().and_then(|id| parse_id(id, '@')) else {
+                        continue;
+                    };
+                    let layout = fields.next().and_then(TmuxLayout::parse);
+                    let name = fields.next().unwrap_or_default().to_string();
+                    if self.active_window == Some(id) {
+                        update.title = Some(name.clone());
+                    }
+                    let window = self.window_mut(id);
+                    window.layout = layout;
+                    window.name = name;
+                }
+            }
+            Pending::CapturePane { pane } => {
+                if self.active_pane != Some(pane) {
+                    return;
+                }
+                update.draw.extend_from_slice(b"\x1b[H\x1b[2J");
+                update.draw.extend_from_slice(lines.join("\r\n").as_bytes());
+            }
+            Pending::CursorPosition { pane } => {
+                if self.active_pane != Some(pane) {
+                    return;
+                }
+                let position = lines.first().and_then(|line| line.split_once(','));
+                if let Some((x, y)) = position
+                    && let (Ok(x), Ok(y)) = (x.parse::<u32>(), y.parse::<u32>())
+                {
+                    update
+                        .draw
+                        .extend_from_slice(format!("\x1b[{};{}H", y + 1, x + 1).as_bytes());
+                }
+            }
+        }
+    }
+
+    /// Make `pane` the rendered pane and redraw it from tmux's copy of the screen.
+    fn switch_pane(&mut self, pane: u32, update: &mut TmuxUpdate) {
+        if self.active_pane == Some(pane) {
+            return;
+        }
+        self.active_pane = Some(pane);
+        self.command(
+            &mut update.commands,
+            &format!("capture-pane -p -e -t %{pane}"),
+            Pending::CapturePane { pane },
+        );
+        self.command(
+            &mut update.commands,
+            &format!("display-message -p -t %{pane} '#{{cursor_x}},#{{cursor_y}}'"),
+            Pending::CursorPosition { pane },
+        );
+    }
+
+    fn window_mut(&mut self, id: u32) -> &mut TmuxWindow {
+        let index = match self.windows.iter().position(|w| w.id == id) {
+            Some(index) => index,
+            None => {
+                self.windows.push(TmuxWindow {
+                    id,
+                    name: String::new(),
+                    layout: None,
+                    active_pane: None,
+                });
+                self.windows.len() - 1
+            }
+        };
+        &mut self.windows[index]
+    }
+}
+
+fn resize_command(columns: usize, rows: usize) -> String {
+    format!("refresh-client -C {columns}x{rows}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_nested_layouts() {
+        let layout =
+            TmuxLayout::parse("b25d,80x24,0,0{40x24,0,0,1,39x24,41,0[39x12,41,0,2,39x11,41,13,3]}")
+                .unwrap();
+        assert_eq!(layout.panes(), vec![1, 2, 3]);
+        assert!(
+            matches!(&layout, TmuxLayout::Columns(children) if matches!(children[1], TmuxLayout::Rows(_)))
+        );
+        assert_eq!(
+            TmuxLayout::parse("c0de,80x24,0,0,7"),
+            Some(TmuxLayout::Pane {
+                id: 7,
+                width: 80,
+                height: 24
+            })
+        );
+        assert_eq!(TmuxLayout::parse("c0de,80x24,0,0{40x24,0,0,1"), None);
+    }
+
+    #[test]
+    fn parser_splits_blocks_notifications_and_exit() {
+        let mut parser = ControlParser::default();
+        let (events, exited) = parser.feed(
+            b"%begin 1 0 0\r\n%end 1 0 0\r\n%begin 2 7 1\n%output in body\n%end 2 7 1\n%output %3 a\\015\\012\\134b\n%window-pane-changed @1 %4\n%ex",
+        );
+        assert_eq!(exited, None);
+        assert_eq!(
+            events,
+            vec![
+                ControlEvent::CommandResult {
+                    from_client: false,
+                    lines: vec![],
+                    error: false
+                },
+                ControlEvent::CommandResult {
+                    from_client: true,
+                    lines: vec!["%output in body".into()],
+                    error: false
+                },
+                ControlEvent::Output {
+                    pane: 3,
+                    data: b"a\r\n\\b".to_vec()
+                },
+                ControlEvent::WindowPaneChanged { window: 1, pane: 4 },
+            ]
+        );
+
+        let (events, exited) = parser.feed(b"it detached\n\x1b\\$ ");
+        assert_eq!(
+            events,
+            vec![ControlEvent::Exit {
+                reason: Some("detached".into())
+            }]
+        );
+        assert_eq!(exited, Some(14));
+    }
+
+    #[test]
+    fn control_follows_active_pane_and_encodes_input() {
+        let (mut control, commands) = TmuxControl::start(80, 24);
+        let commands = String::from_utf8(commands).unwrap();
+        assert!(commands.starts_with("refresh-client -C 80x24\n"));
+
+        let update = control.feed(
+            b"%begin 1 1 1\n%end 1 1 1\n%begin 1 2 1\n@1 %2\n%end 1 2 1\n%output %2 hi\n%output %5 hidden\n",
+        );
+        assert_eq!(control.active_pane(), Some(2));
+        assert!(
+            String::from_utf8(update.commands)
+                .unwrap()
+                .starts_with("capture-pane -p -e -t %2\n")
+        );
+        assert_eq!(update.draw, b"hi");
+
+        // list-windows, capture-pane, and cursor replies arrive in order.
+        let update = control.feed(
+            b"%begin 1 3 1\n@1 b25d,80x24,0,0,2 editor\n%end 1 3 1\n%begin 1 4 1\n$ ls\n%end 1 4 1\n%begin 1 5 1\n4,0\n%end 1 5 1\n",
+        );
+        assert_eq!(update.title.as_deref(), Some("editor"));
+        assert_eq!(update.draw, b"\x1b[H\x1b[2J$ ls\x1b[1;5H");
+        assert_eq!(
+            control.windows()[0].layout.as_ref().unwrap().panes(),
+            vec![2]
+        );
+
+        assert_eq!(control.encode_input(b"l\r"), b"send-keys -t %2 -H 6c 0d\n");
+    }
+}
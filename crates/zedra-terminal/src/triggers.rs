@@ -0,0 +1,318 @@
+// Output triggers: user regexes matched against terminal output lines.
+//
+// `Terminal` feeds every output chunk through a `TriggerMatcher`, which strips
+// escape sequences, assembles logical lines, and reports which rules matched.
+// A rule fires at most once per line. Prompts that never end in a newline
+// ("Password: ") are matched against the partial line at the end of each chunk.
+
+use std::time::{Duration, Instant};
+
+use alacritty_terminal::vte::ansi::Rgb;
+use regex::Regex;
+
+use crate::theme::TerminalTheme;
+
+/// Longest line kept for matching; bytes past this are dropped until the next newline.
+const MAX_LINE_BYTES: usize = 4096;
+/// Minimum gap between two replies from the same rule, so a reply that echoes
+/// its own prompt cannot loop.
+const REPLY_COOLDOWN: Duration = Duration::from_secs(2);
+/// Share of the ANSI yellow mixed into the background for highlighted lines.
+const HIGHLIGHT_MIX: f32 = 0.3;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TriggerAction {
+    /// Tint the matched line's background.
+    Highlight,
+    /// Ask the app to post a notification.
+    Notify,
+    /// Type the text followed by Enter.
+    Reply(String),
+    /// Copy a capture group (0 = whole match) to the clipboard.
+    CopyCapture(usize),
+}
+
+#[derive(Clone, Debug)]
+pub struct TriggerRule {
+    pub name: String,
+    pub pattern: Regex,
+    pub action: TriggerAction,
+}
+
+impl TriggerRule {
+    pub fn new(
+        name: impl Into<String>,
+        pattern: &str,
+        action: TriggerAction,
+    ) -> Result<Self, regex::Error> {
+        Ok(Self {
+            name: name.into(),
+            pattern: Regex::new(pattern)?,
+            action,
+        })
+    }
+
+    /// Partial lines may still grow, so a capture taken from one could be cut short.
+    fn matches_partial_lines(&self) -> bool {
+        !matches!(self.action, TriggerAction::CopyCapture(_))
+    }
+}
+
+/// A rule that matched a line of output.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TriggerFired {
+    pub name: String,
+    pub action: TriggerAction,
+    /// The matched line with escape sequences removed.
+    pub line: String,
+    /// The requested capture group for [`TriggerAction::CopyCapture`].
+    pub capture: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum EscapeState {
+    Ground,
+    Escape,
+    /// `ESC` followed by intermediate bytes, e.g. `ESC ( B`.
+    EscapeIntermediate,
+    Csi,
+    Osc,
+    /// `ESC` seen inside an OSC; `\` completes the string terminator.
+    OscEscape,
+}
+
+struct RuleState {
+    rule: TriggerRule,
+    fired_on_line: bool,
+    last_reply: Option<Instant>,
+}
+
+pub struct TriggerMatcher {
+    rules: Vec<RuleState>,
+    line: Vec<u8>,
+    state: EscapeState,
+    pending_cr: bool,
+}
+
+impl TriggerMatcher {
+    pub fn new(rules: Vec<TriggerRule>) -> Self {
+        Self {
+            rules: rules
+                .into_iter()
+                .map(|rule| RuleState {
+                    rule,
+                    fired_on_line: false,
+                    last_reply: None,
+                })
+                .collect(),
+            line: Vec::new(),
+            state: EscapeState::Ground,
+            pending_cr: false,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Feed raw output bytes. Completed lines are checked against every rule;
+    /// whatever is left after the last newline is checked against rules that
+    /// accept partial lines.
+    pub fn feed(&mut self, bytes: &[u8], now: Instant) -> Vec<TriggerFired> {
+        let mut fired = Vec::new();
+        for &byte in bytes {
+            if self.push_byte(byte) {
+                self.match_line(false, now, &mut fired);
+                self.line.clear();
+                for state in &mut self.rules {
+                    state.fired_on_line = false;
+                }
+            }
+        }
+        if !self.line.is_empty() {
+            self.match_line(true, now, &mut fired);
+        }
+        fired
+    }
+
+    /// Returns true when `byte` completes a line.
+    fn push_byte(&mut self, byte: u8) -> bool {
+        match self.state {
+            EscapeState::Ground => {}
+            EscapeState::Escape => {
+                self.state = match byte {
+                    b'[' => EscapeState::Csi,
+                    b']' | b'P' | b'_' | b'^' => EscapeState::Osc,
+                    0x20..=0x2f => EscapeState::EscapeIntermediate,
+                    _ => EscapeState::Ground,
+                };
+                return false;
+            }
+            EscapeState::EscapeIntermediate => {
+                if !(0x20..=0x2f).contains(&byte) {
+                    self.state = EscapeState::Ground;
+                }
+                return false;
+            }
+            EscapeState::Csi => {
+                if (0x40..=0x7e).contains(&byte) {
+                    self.state = EscapeState::Ground;
+                }
+                return false;
+            }
+            EscapeState::Osc => {
+                match byte {
+                    0x07 => self.state = EscapeState::Ground,
+                    0x1b => self.state = EscapeState::OscEscape,
+                    _ => {}
+                }
+                return false;
+            }
+            EscapeState::OscEscape => {
+                self.state = if byte == b'\\' {
+                    EscapeState::Ground
+                } else {
+                    EscapeState::Osc
+                };
+                return false;
+            }
+        }
+
+        match byte {
+            0x1b => {
+                self.state = EscapeState::Escape;
+                false
+            }
+            b'\n' => {
+                self.pending_cr = false;
+                true
+            }
+            b'\r' => {
+                self.pending_cr = true;
+                false
+            }
+            b'\t' | 0x20.. => {
+                // A bare carriage return redraws the line (progress bars), so
+                // only the text after it is on screen.
+                if std::mem::take(&mut self.pending_cr) {
+                    self.line.clear();
+                }
+                if self.line.len() < MAX_LINE_BYTES {
+                    self.line.push(byte);
+                }
+                false
+            }
+            _ => false,
+        }
+    }
+
+    fn match_line(&mut self, partial: bool, now: Instant, fired: &mut Vec<TriggerFired>) {
+        let line = String::from_utf8_lossy(&self.line);
+        for state in &mut self.rules {
+            if state.fired_on_line || (partial && !state.rule.matches_partial_lines()) {
+                continue;
+            }
+            let Some(captures) = state.rule.pattern.captures(&line) else {
+                continue;
+            };
+            if matches!(state.rule.action, TriggerAction::Reply(_)) {
+                if state
+                    .last_reply
+                    .is_some_and(|last| now.saturating_duration_since(last) < REPLY_COOLDOWN)
+                {
+                    continue;
+                }
+                state.last_reply = Some(now);
+            }
+            state.fired_on_line = true;
+            let capture = match state.rule.action {
+                TriggerAction::CopyCapture(group) => {
+                    captures.get(group).map(|m| m.as_str().to_string())
+                }
+                _ => None,
+            };
+            fired.push(TriggerFired {
+                name: state.rule.name.clone(),
+                action: state.rule.action.clone(),
+                line: line.trim_end().to_string(),
+                capture,
+            });
+        }
+    }
+}
+
+/// Background for highlighted lines: the theme background tinted toward yellow.
+pub(crate) fn highlight_rgb(theme: &TerminalTheme) -> Rgb {
+    let channel = |color: u32, shift: u32| ((color >> shift) & 0xff) as f32;
+    let mix = |shift: u32| {
+        let bg = channel(theme.background, shift);
+        let accent = channel(theme.ansi.yellow, shift);
+        (bg + (accent - bg) * HIGHLIGHT_MIX).round() as u8
+    };
+    Rgb {
+        r: mix(16),
+        g: mix(8),
+        b: mix(0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(pattern: &str, action: TriggerAction) -> TriggerRule {
+        TriggerRule::new(pattern, pattern, action).unwrap()
+    }
+
+    #[test]
+    fn matches_complete_lines_once_with_escapes_stripped() {
+        let mut matcher = TriggerMatcher::new(vec![rule("BUILD FAILED", TriggerAction::Notify)]);
+        let now = Instant::now();
+
+        assert!(matcher.feed(b"\x1b[31mBUILD ", now).is_empty());
+        let fired = matcher.feed(b"FAIL\x1b]0;title\x07ED\x1b[0m in 3s\r\n", now);
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].line, "BUILD FAILED in 3s");
+
+        // The partial match already fired; completing the line does not refire.
+        let mut matcher = TriggerMatcher::new(vec![rule("FAILED", TriggerAction::Notify)]);
+        assert_eq!(matcher.feed(b"FAILED", now).len(), 1);
+        assert!(matcher.feed(b" again\n", now).is_empty());
+        assert_eq!(matcher.feed(b"FAILED\n", now).len(), 1);
+    }
+
+    #[test]
+    fn copy_capture_waits_for_the_full_line() {
+        let mut matcher =
+            TriggerMatcher::new(vec![rule(r"token: (\w+)", TriggerAction::CopyCapture(1))]);
+        let now = Instant::now();
+
+        assert!(matcher.feed(b"token: ab", now).is_empty());
+        let fired = matcher.feed(b"c123\r\n", now);
+        assert_eq!(fired[0].capture.as_deref(), Some("abc123"));
+    }
+
+    #[test]
+    fn carriage_return_redraws_and_replies_cool_down() {
+        let mut matcher = TriggerMatcher::new(vec![rule(
+            "^Password:",
+            TriggerAction::Reply("hunter2".into()),
+        )]);
+        let now = Instant::now();
+
+        assert!(matcher.feed(b"50%\r", now).is_empty());
+        assert_eq!(matcher.feed(b"Password: ", now).len(), 1);
+        matcher.feed(b"\r\n", now);
+        assert!(matcher.feed(b"Password: ", now).is_empty());
+        matcher.feed(b"\r\n", now);
+        assert_eq!(
+            matcher
+                .feed(
+                    b"Password: ",
+                    now + REPLY_COOLDOWN + Duration::from_millis(1)
+                )
+                .len(),
+            1
+        );
+    }
+}
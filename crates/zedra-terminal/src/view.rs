@@ -5,24 +5,63 @@ use std::collections::VecDeque;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
+use alacritty_terminal::index::Point as GridPoint;
 use alacritty_terminal::term::TermMode;
+use alacritty_terminal::vte::ansi::CursorShape;
 use gpui::*;
 use tokio::sync::{broadcast, mpsc};
 use tracing::*;
 use zedra_osc::OscEvent;
 
-use crate::TerminalTheme;
-use crate::element::TerminalElement;
+use crate::command_guard::GuardRule;
+use crate::element::{TerminalElement, TerminalShaping};
+use crate::metrics::CellMetrics;
+use crate::metrics::terminal_font;
 use crate::selection::TerminalSelectionDocument;
-use crate::terminal::{Terminal, TerminalContent, TerminalEvent};
+use crate::terminal::{
+    CursorState, DefaultCursorShape, PromptJump, Terminal, TerminalContent, TerminalEvent,
+};
+use crate::triggers::TriggerRule;
+use crate::{MONO_FONT_FAMILY, TERMINAL_FONT_SIZE, TerminalTheme};
 
-const FALLBACK_CELL_WIDTH: f32 = 9.0;
-const TERMINAL_LINE_HEIGHT: f32 = 16.0;
 const TOUCH_SCROLL_SUPPRESSION_AFTER_SCROLL_TO_BOTTOM: Duration = Duration::from_millis(1000);
+/// How long the status overlay stays up after a state change or resize.
+const STATUS_OVERLAY_LINGER: Duration = Duration::from_millis(2500);
+/// Half of a cursor blink cycle.
+const CURSOR_BLINK_INTERVAL: Duration = Duration::from_millis(530);
+/// A blinking cursor turns steady after this long without moving, so an idle
+/// terminal stops repainting.
+const CURSOR_BLINK_IDLE_STOP: Duration = Duration::from_secs(15);
 
 /// Thread-safe buffer for receiving PTY output.
 pub type OutputBuffer = Arc<Mutex<VecDeque<Vec<u8>>>>;
 
+/// Connection summary shown by the terminal status overlay. Supplied by the app;
+/// the terminal crate has no notion of sessions.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TerminalStatus {
+    /// Short state label, e.g. "Connected" or "Reconnecting".
+    pub label: String,
+    pub host: Option<String>,
+    pub latency_ms: Option<u64>,
+    /// Keep the overlay up until a non-sticky status replaces it (connecting,
+    /// reconnecting, disconnected).
+    pub sticky: bool,
+}
+
+impl TerminalStatus {
+    fn summary(&self) -> String {
+        let mut parts = vec![self.label.clone()];
+        if let Some(host) = self.host.as_deref().filter(|host| !host.is_empty()) {
+            parts.push(host.to_string());
+        }
+        if let Some(latency_ms) = self.latency_ms {
+            parts.push(format!("{latency_ms} ms"));
+        }
+        parts.join(" \u{00b7} ")
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct TerminalGridSize {
     pub columns: usize,
@@ -118,6 +157,20 @@ pub struct TerminalView {
     /// creating a GPUI dependency on the inner terminal entity.
     pub is_alt_screen: bool,
     terminal_theme: TerminalTheme,
+    /// Font family and size the grid is measured and painted at.
+    font_family: SharedString,
+    font_size: Pixels,
+    shaping: TerminalShaping,
+    status: Option<TerminalStatus>,
+    status_visible_until: Option<Instant>,
+    size_visible_until: Option<Instant>,
+    /// Repaints once the overlay linger expires; replaced on every show.
+    _status_fade_task: Option<Task<()>>,
+    /// Start of the current blink cycle; restarts whenever the cursor moves.
+    cursor_blink_epoch: Instant,
+    cursor_blink_point: Option<GridPoint>,
+    /// Repaints at the next blink phase change while a blinking cursor shows.
+    _cursor_blink_task: Option<Task<()>>,
     _event_task: Task<()>,
     _subscriptions: Vec<Subscription>,
 }
@@ -187,6 +240,16 @@ impl TerminalView {
             suppress_touch_scroll_until: None,
             is_alt_screen: false,
             terminal_theme: TerminalTheme::dark(),
+            font_family: MONO_FONT_FAMILY.into(),
+            font_size: TERMINAL_FONT_SIZE,
+            shaping: TerminalShaping::default(),
+            status: None,
+            status_visible_until: None,
+            size_visible_until: None,
+            _status_fade_task: None,
+            cursor_blink_epoch: Instant::now(),
+            cursor_blink_point: None,
+            _cursor_blink_task: None,
             _event_task: event_task,
             _subscriptions: vec![],
         }
@@ -196,6 +259,46 @@ impl TerminalView {
         self.terminal_id = terminal_id;
     }
 
+    /// Update the status overlay. A new label or stickiness shows the overlay;
+    /// host and latency changes only refresh the text while it is visible.
+    pub fn set_status(&mut self, status: Option<TerminalStatus>, cx: &mut Context<Self>) {
+        if self.status == status {
+            return;
+        }
+        let state_changed = match (&self.status, &status) {
+            (Some(old), Some(new)) => old.label != new.label || old.sticky != new.sticky,
+            (None, None) => false,
+            _ => true,
+        };
+        self.status = status;
+        if state_changed && self.status.is_some() {
+            self.status_visible_until = Some(Instant::now() + STATUS_OVERLAY_LINGER);
+            self.schedule_status_fade(cx);
+        }
+        cx.notify();
+    }
+
+    fn schedule_status_fade(&mut self, cx: &mut Context<Self>) {
+        self._status_fade_task = Some(cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(STATUS_OVERLAY_LINGER).await;
+            let _ = this.update(cx, |_, cx| cx.notify());
+        }));
+    }
+
+    /// Lines the status overlay shows at `now`; empty when it is hidden.
+    fn status_overlay_lines(&self, now: Instant, columns: usize, rows: usize) -> Vec<String> {
+        let mut lines = Vec::new();
+        if let Some(status) = &self.status
+            && (status.sticky || self.status_visible_until.is_some_and(|until| now < until))
+        {
+            lines.push(status.summary());
+        }
+        if self.size_visible_until.is_some_and(|until| now < until) {
+            lines.push(format!("{columns}\u{00d7}{rows}"));
+        }
+        lines
+    }
+
     pub fn set_terminal_theme(&mut self, theme: TerminalTheme, cx: &mut Context<Self>) {
         if self.terminal_theme == theme {
             return;
@@ -233,27 +336,125 @@ impl TerminalView {
         )))
     }
 
+    /// Grid size for `viewport` at the default terminal font size.
     pub fn compute_grid_size(window: &mut Window, viewport: Size<Pixels>) -> TerminalGridSize {
-        let line_height = px(TERMINAL_LINE_HEIGHT);
-        let cell_width = Self::measure_cell_width(window, line_height);
-        Self::compute_grid_size_with_metrics(viewport, cell_width, line_height)
-    }
-
-    fn measure_cell_width(window: &mut Window, line_height: Pixels) -> Pixels {
-        let font = Font {
-            family: crate::MONO_FONT_FAMILY.into(),
-            features: FontFeatures::default(),
-            fallbacks: None,
-            weight: FontWeight::NORMAL,
-            style: FontStyle::Normal,
-        };
-        let font_size = line_height * 0.75;
-        let text_system = window.text_system();
-        let font_id = text_system.resolve_font(&font);
-        text_system
-            .advance(font_id, font_size, 'm')
-            .map(|size| size.width)
-            .unwrap_or(px(FALLBACK_CELL_WIDTH))
+        let font = terminal_font(MONO_FONT_FAMILY.into());
+        let metrics = CellMetrics::measure(window, &font, TERMINAL_FONT_SIZE);
+        Self::compute_grid_size_with_metrics(viewport, metrics.cell_width, metrics.line_height)
+    }
+
+    /// Change the terminal font family; cells are re-measured like [`Self::set_font_size`].
+    pub fn set_font_family(&mut self, family: impl Into<SharedString>, cx: &mut Context<Self>) {
+        let family = family.into();
+        if self.font_family == family {
+            return;
+        }
+        self.font_family = family;
+        cx.notify();
+    }
+
+    pub fn font_size(&self) -> Pixels {
+        self.font_size
+    }
+
+    /// Change the terminal font size. The element re-measures cell metrics on the
+    /// next paint and reconciles cols/rows (and the host PTY size) from them.
+    pub fn set_font_size(&mut self, font_size: Pixels, cx: &mut Context<Self>) {
+        if self.font_size == font_size {
+            return;
+        }
+        self.font_size = font_size;
+        cx.notify();
+    }
+
+    pub fn shaping(&self) -> TerminalShaping {
+        self.shaping
+    }
+
+    /// Drop scrollback beyond `keep_lines` under memory pressure. Returns the
+    /// approximate number of bytes released.
+    pub fn trim_scrollback(&mut self, keep_lines: usize, cx: &mut Context<Self>) -> usize {
+        let released = self
+            .terminal
+            .update(cx, |terminal, _cx| terminal.trim_scrollback(keep_lines));
+        if released > 0 {
+            cx.notify();
+        }
+        released
+    }
+
+    /// Cursor style shown until the application picks one with DECSCUSR.
+    pub fn set_default_cursor_style(
+        &mut self,
+        shape: DefaultCursorShape,
+        blinking: bool,
+        cx: &mut Context<Self>,
+    ) {
+        self.terminal.update(cx, |terminal, _| {
+            terminal.set_default_cursor_style(shape, blinking);
+        });
+        cx.notify();
+    }
+
+    /// Whether the cursor is in the visible half of its blink cycle, scheduling
+    /// a repaint for the next phase change. Steady, unfocused, and idle cursors
+    /// are always visible.
+    fn cursor_blink_visible(
+        &mut self,
+        cursor: &CursorState,
+        focused: bool,
+        now: Instant,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        if self.cursor_blink_point != Some(cursor.point) {
+            self.cursor_blink_point = Some(cursor.point);
+            self.cursor_blink_epoch = now;
+        }
+        let elapsed = now.saturating_duration_since(self.cursor_blink_epoch);
+        if !cursor.blinking || !focused || elapsed >= CURSOR_BLINK_IDLE_STOP {
+            self._cursor_blink_task = None;
+            return true;
+        }
+        let interval = CURSOR_BLINK_INTERVAL.as_millis();
+        if self._cursor_blink_task.is_none() {
+            let until_next = CURSOR_BLINK_INTERVAL
+                - Duration::from_millis((elapsed.as_millis() % interval) as u64);
+            self._cursor_blink_task = Some(cx.spawn(async move |this, cx| {
+                cx.background_executor().timer(until_next).await;
+                let _ = this.update(cx, |this, cx| {
+                    this._cursor_blink_task = None;
+                    cx.notify();
+                });
+            }));
+        }
+        (elapsed.as_millis() / interval).is_multiple_of(2)
+    }
+
+    pub fn set_triggers(&mut self, rules: Vec<TriggerRule>, cx: &mut Context<Self>) {
+        self.terminal
+            .update(cx, |terminal, _cx| terminal.set_triggers(rules));
+    }
+
+    pub fn set_command_guard(&mut self, rules: Vec<GuardRule>, cx: &mut Context<Self>) {
+        self.terminal
+            .update(cx, |terminal, _cx| terminal.set_command_guard(rules));
+    }
+
+    /// Send the Enter a guard rule held back, once the user confirmed.
+    pub fn submit_guarded_command(&mut self, cx: &mut Context<Self>) {
+        self.terminal
+            .update(cx, |terminal, _cx| terminal.submit_guarded_command());
+        self.scroll_to_bottom(cx);
+    }
+
+    /// Choose whether operator runs may render as ligatures. Cell metrics are
+    /// unaffected, so this never resizes the grid.
+    pub fn set_shaping(&mut self, shaping: TerminalShaping, cx: &mut Context<Self>) {
+        if self.shaping == shaping {
+            return;
+        }
+        self.shaping = shaping;
+        cx.notify();
     }
 
     fn compute_grid_size_with_metrics(
@@ -350,6 +551,10 @@ impl TerminalView {
                 );
                 terminal.resize(next.columns, next.rows, next.cell_width, next.line_height);
             });
+            if size.columns != next.columns || size.rows != next.rows {
+                self.size_visible_until = Some(Instant::now() + STATUS_OVERLAY_LINGER);
+                self.schedule_status_fade(cx);
+            }
             cx.notify();
         }
 
@@ -398,6 +603,48 @@ impl TerminalView {
         cx.notify();
     }
 
+    /// Jump to the previous or next OSC 133 prompt. Returns false when there
+    /// is none in that direction.
+    pub fn scroll_to_prompt(&mut self, jump: PromptJump, cx: &mut Context<Self>) -> bool {
+        let previous_display_offset = self.display_offset(cx);
+        let moved = self
+            .terminal
+            .update(cx, |terminal, _| terminal.scroll_to_prompt(jump));
+        if moved {
+            self.scroll_offset_px = 0.0;
+            self.reset_keyboard_top_reveal_if_not_at_top(cx);
+            self.emit_scrollback_position_if_changed(previous_display_offset, cx);
+            cx.notify();
+        }
+        moved
+    }
+
+    pub fn has_prompt_marks(&self, cx: &App) -> bool {
+        self.terminal.read(cx).has_prompt_marks()
+    }
+
+    pub fn last_command_output(&self, cx: &App) -> Option<String> {
+        self.terminal.read(cx).last_command_output()
+    }
+
+    pub fn has_foldable_outputs(&self, cx: &App) -> bool {
+        self.terminal.read(cx).has_foldable_outputs()
+    }
+
+    pub fn has_folded_outputs(&self, cx: &App) -> bool {
+        self.terminal.read(cx).has_folded_outputs()
+    }
+
+    /// Fold earlier commands' long outputs into headers, or expand them all.
+    pub fn set_outputs_folded(&mut self, folded: bool, cx: &mut Context<Self>) {
+        let changed = self
+            .terminal
+            .update(cx, |terminal, _| terminal.set_outputs_folded(folded));
+        if changed {
+            cx.notify();
+        }
+    }
+
     fn keyboard_top_reveal_limit_px(&self) -> f32 {
         if self.keyboard_content_offset <= px(0.0) || self.is_alt_screen {
             return 0.0;
@@ -502,6 +749,11 @@ impl TerminalView {
         self.terminal.read(cx).display_offset()
     }
 
+    /// Lines of output that arrived since the view left the bottom.
+    pub fn unseen_lines(&self, cx: &App) -> usize {
+        self.terminal.read(cx).unseen_lines()
+    }
+
     pub fn set_grid_origin(&mut self, origin: Point<Pixels>) {
         self.grid_origin = Some(origin);
     }
@@ -518,6 +770,15 @@ impl TerminalView {
     ) {
         let position = event.position();
 
+        let grid_origin = self.grid_origin;
+        if self
+            .terminal
+            .update(cx, |terminal, _| terminal.unfold_at(position, grid_origin))
+        {
+            cx.notify();
+            return;
+        }
+
         let hyperlink = self.terminal.read(cx).hyperlink_at(
             position,
             self.grid_origin,
@@ -560,6 +821,19 @@ impl TerminalView {
         let position = event.down.position;
         window.prevent_default();
 
+        let file_path = self.terminal.read(cx).file_path_at(
+            position,
+            self.grid_origin,
+            self.workdir.as_deref(),
+        );
+        if let Some(hyperlink) = file_path {
+            cx.emit(TerminalEvent::FilePathMenuRequested {
+                position,
+                hyperlink,
+            });
+            return;
+        }
+
         if let Some((selection_document, selection_index)) =
             self.selection_document_at_point(position, cx)
         {
@@ -608,6 +882,14 @@ impl TerminalView {
         });
         cx.notify();
     }
+
+    /// Send `text` as typed input, e.g. a command ending in `\r`.
+    pub fn send_text(&mut self, text: &str, cx: &mut Context<Self>) {
+        self.terminal
+            .read(cx)
+            .send_bytes_sync(text.as_bytes().to_vec());
+        self.scroll_to_bottom(cx);
+    }
 }
 
 impl EventEmitter<TerminalEvent> for TerminalView {}
@@ -621,20 +903,30 @@ impl Focusable for TerminalView {
 impl Render for TerminalView {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let terminal = self.terminal.read(cx);
-        let content = terminal.content();
+        let mut content = terminal.content();
         let size = terminal.size();
         let history_size = terminal.history_size();
         let selection_active = terminal.selection_active();
+        let focused = self.focus_handle.is_focused(window);
+        if !self.cursor_blink_visible(&content.cursor, focused, Instant::now(), cx) {
+            content.cursor.shape = CursorShape::Hidden;
+        }
         let focus_handle = self.focus_handle.clone();
         let visual_scroll_offset_px =
             self.scroll_offset_px + self.effective_keyboard_top_reveal_px(&content, history_size);
+        // Hidden while selecting so it never covers the text being selected.
+        let overlay_lines = if selection_active {
+            Vec::new()
+        } else {
+            self.status_overlay_lines(Instant::now(), size.columns, size.rows)
+        };
 
         div()
             .id("terminal-view")
             .key_context("Terminal")
             .size_full()
             .overflow_hidden()
-            .bg(rgb(self.terminal_theme.background))
+            .bg(self.terminal_theme.background_fill())
             .track_focus(&focus_handle)
             .manual_focus()
             .on_press(cx.listener(|this, event: &PressEvent, window, cx| {
@@ -791,19 +1083,52 @@ impl Render for TerminalView {
                 self.keyboard_inset,
                 self.keyboard_content_offset,
                 self.terminal_theme,
+                terminal_font(self.font_family.clone()),
+                self.font_size,
+                self.shaping,
                 cx.weak_entity(),
                 self.terminal.downgrade(),
                 self.focus_handle.clone(),
-                self.focus_handle.is_focused(window),
+                focused,
                 selection_active,
             ))
+            .when(!overlay_lines.is_empty(), |el| {
+                el.child(status_overlay(overlay_lines, &self.terminal_theme))
+            })
     }
 }
 
+/// Top-right status pill. Has no handlers, so touches fall through to the grid.
+fn status_overlay(lines: Vec<String>, theme: &TerminalTheme) -> impl IntoElement {
+    let mut overlay = div()
+        .absolute()
+        .top(px(6.0))
+        .right(px(6.0))
+        .px(px(8.0))
+        .py(px(4.0))
+        .rounded(px(6.0))
+        .bg(rgba((theme.background << 8) | 0xd9))
+        .border_1()
+        .border_color(rgba((theme.dim_foreground << 8) | 0x66))
+        .flex()
+        .flex_col()
+        .items_end()
+        .text_size(px(11.0))
+        .text_color(rgb(theme.foreground))
+        .font_family(crate::MONO_FONT_FAMILY);
+    for line in lines {
+        overlay = overlay.child(div().child(line));
+    }
+    overlay
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{TerminalView, keyboard_content_offset_px};
-    use std::{path::Path, time::Duration};
+    use super::{TerminalStatus, TerminalView, keyboard_content_offset_px};
+    use std::{
+        path::Path,
+        time::{Duration, Instant},
+    };
 
     use crate::terminal::{Terminal, TerminalContent, TerminalEvent, TerminalHyperlinkTarget};
     use alacritty_terminal::term::TermMode;
@@ -1125,6 +1450,66 @@ mod tests {
         cx.quit();
     }
 
+    #[test]
+    fn status_overlay_shows_on_state_change_and_fades() {
+        let mut cx = TestAppContext::single();
+        let window = open_terminal_window(&mut cx);
+        cx.run_until_parked();
+
+        window
+            .update(&mut cx, |terminal_view, _window, cx| {
+                let now = Instant::now();
+                terminal_view.set_status(
+                    Some(TerminalStatus {
+                        label: "Reconnecting".to_string(),
+                        host: Some("devbox".to_string()),
+                        latency_ms: None,
+                        sticky: true,
+                    }),
+                    cx,
+                );
+                let later = now + Duration::from_secs(60);
+                assert_eq!(
+                    terminal_view.status_overlay_lines(later, 80, 24),
+                    vec!["Reconnecting \u{00b7} devbox".to_string()]
+                );
+
+                terminal_view.set_status(
+                    Some(TerminalStatus {
+                        label: "Connected".to_string(),
+                        host: Some("devbox".to_string()),
+                        latency_ms: Some(42),
+                        sticky: false,
+                    }),
+                    cx,
+                );
+                assert_eq!(
+                    terminal_view.status_overlay_lines(Instant::now(), 80, 24),
+                    vec!["Connected \u{00b7} devbox \u{00b7} 42 ms".to_string()]
+                );
+                assert!(terminal_view.status_overlay_lines(later, 80, 24).is_empty());
+
+                // Latency-only updates refresh the text without reopening the overlay.
+                terminal_view.status_visible_until = None;
+                terminal_view.set_status(
+                    Some(TerminalStatus {
+                        label: "Connected".to_string(),
+                        host: Some("devbox".to_string()),
+                        latency_ms: Some(55),
+                        sticky: false,
+                    }),
+                    cx,
+                );
+                assert!(
+                    terminal_view
+                        .status_overlay_lines(Instant::now(), 80, 24)
+                        .is_empty()
+                );
+            })
+            .unwrap();
+        cx.quit();
+    }
+
     #[test]
     fn terminal_event_relay_survives_broadcast_lag() {
         let mut cx = TestAppContext::single();
@@ -192,7 +192,7 @@ impl AgentDetail {
             .gap(px(theme::SPACING_XS))
             .child(
                 div()
-                    .text_size(px(theme::FONT_DETAIL))
+                    .text_size(theme::scaled(theme::FONT_DETAIL))
                     .text_color(rgb(theme::text_muted(cx)))
                     .child("Config & memory (read-only)"),
             );
@@ -221,7 +221,7 @@ impl AgentDetail {
             .child(
                 div()
                     .flex_shrink_0()
-                    .text_size(px(theme::FONT_DETAIL))
+                    .text_size(theme::scaled(theme::FONT_DETAIL))
                     .font_family(fonts::MONO_FONT_FAMILY)
                     .text_color(rgb(theme::text_secondary(cx)))
                     .child(file.label.clone()),
@@ -231,7 +231,7 @@ impl AgentDetail {
                     .flex_1()
                     .min_w_0()
                     .truncate()
-                    .text_size(px(theme::FONT_DETAIL))
+                    .text_size(theme::scaled(theme::FONT_DETAIL))
                     .text_color(rgb(theme::text_muted(cx)))
                     .child(subtitle),
             );
@@ -375,7 +375,7 @@ fn render_detail_header(
                                 .gap(px(0.0))
                                 .child(
                                     div()
-                                        .text_size(px(theme::FONT_HEADING))
+                                        .text_size(theme::scaled(theme::FONT_HEADING))
                                         .font_family(fonts::HEADING_FONT_FAMILY)
                                         .font_weight(FontWeight::MEDIUM)
                                         .text_color(rgb(theme::text_primary(cx)))
@@ -383,7 +383,7 @@ fn render_detail_header(
                                 )
                                 .child(
                                     div()
-                                        .text_size(px(theme::FONT_BODY))
+                                        .text_size(theme::scaled(theme::FONT_BODY))
                                         .text_color(rgb(theme::text_muted(cx)))
                                         .child(description),
                                 ),
@@ -463,7 +463,7 @@ fn metadata_line(label: SharedString, value: String, cx: &App, show_divider: boo
             div()
                 .w(px(theme::AGENT_METADATA_LABEL_WIDTH))
                 .flex_shrink_0()
-                .text_size(px(theme::FONT_DETAIL))
+                .text_size(theme::scaled(theme::FONT_DETAIL))
                 .text_color(rgb(theme::text_muted(cx)))
                 .child(label),
         )
@@ -472,7 +472,7 @@ fn metadata_line(label: SharedString, value: String, cx: &App, show_divider: boo
                 .flex_1()
                 .min_w_0()
                 .truncate()
-                .text_size(px(theme::FONT_DETAIL))
+                .text_size(theme::scaled(theme::FONT_DETAIL))
                 .text_color(rgb(theme::text_secondary(cx)))
                 .child(value),
         )
@@ -489,7 +489,7 @@ fn empty_text(text: impl Into<SharedString>, cx: &App) -> Div {
         .w_full()
         .min_w_0()
         .py(px(theme::SPACING_LG))
-        .text_size(px(theme::FONT_BODY))
+        .text_size(theme::scaled(theme::FONT_BODY))
         .text_color(rgb(theme::text_muted(cx)))
         .whitespace_normal()
         .child(text.into())
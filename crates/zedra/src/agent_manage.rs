@@ -180,7 +180,7 @@ fn render_list_header(cx: &mut Context<AgentManage>) -> impl IntoElement {
                                 .gap(px(0.0))
                                 .child(
                                     div()
-                                        .text_size(px(theme::FONT_HEADING))
+                                        .text_size(theme::scaled(theme::FONT_HEADING))
                                         .font_family(fonts::HEADING_FONT_FAMILY)
                                         .font_weight(FontWeight::MEDIUM)
                                         .text_color(rgb(theme::text_primary(cx)))
@@ -188,7 +188,7 @@ fn render_list_header(cx: &mut Context<AgentManage>) -> impl IntoElement {
                                 )
                                 .child(
                                     div()
-                                        .text_size(px(theme::FONT_BODY))
+                                        .text_size(theme::scaled(theme::FONT_BODY))
                                         .text_color(rgb(theme::text_muted(cx)))
                                         .child("View agent data, usage and sessions"),
                                 ),
@@ -8,7 +8,10 @@ use crate::agent_ui::{
 use crate::fonts;
 use crate::platform_bridge::{self, HapticFeedback};
 use crate::theme;
-use crate::ui::{chevron_back_button, subscreen_page, subscreen_refresh_button};
+use crate::ui::{
+    PullToRefresh, PullToRefreshEvent, chevron_back_button, subscreen_refresh_button,
+    subscreen_refreshable_page,
+};
 use crate::workspace_action;
 
 #[derive(Clone, Debug)]
@@ -23,17 +26,25 @@ pub struct AgentSessions {
     sections: Vec<AgentSessionSection>,
     load_state: LoadState,
     loading_epoch: u64,
+    pull_refresh: Entity<PullToRefresh>,
     _tasks: Vec<Task<()>>,
+    _subscriptions: Vec<Subscription>,
 }
 
 impl AgentSessions {
     pub fn new(session_handle: SessionHandle, cx: &mut Context<Self>) -> Self {
+        let pull_refresh = cx.new(|_| PullToRefresh::new());
+        let pull_sub = cx.subscribe(&pull_refresh, |this, _pull, event, cx| match event {
+            PullToRefreshEvent::Refresh => this.load(true, cx),
+        });
         let mut view = Self {
             session_handle,
             sections: Vec::new(),
             load_state: LoadState::Loading,
             loading_epoch: 0,
+            pull_refresh,
             _tasks: Vec::new(),
+            _subscriptions: vec![pull_sub],
         };
         view.load(false, cx);
         view
@@ -86,6 +97,7 @@ impl AgentSessions {
                     error!("agent sessions partial failure: {}", errors.join("; "));
                     LoadState::Ready
                 };
+                this.pull_refresh.update(cx, |pull, cx| pull.finish(cx));
                 cx.notify();
             });
         });
@@ -112,7 +124,14 @@ impl Render for AgentSessions {
         )
         .into_any_element();
         let header = render_session_header(cx).into_any_element();
-        subscreen_page("agent-sessions", rgb(theme::bg_primary(cx)), header, body)
+        subscreen_refreshable_page(
+            "agent-sessions",
+            rgb(theme::bg_primary(cx)),
+            header,
+            body,
+            &self.pull_refresh,
+            cx,
+        )
     }
 }
 
@@ -145,7 +164,7 @@ fn render_session_header(cx: &mut Context<AgentSessions>) -> impl IntoElement {
                                 .gap(px(0.0))
                                 .child(
                                     div()
-                                        .text_size(px(theme::FONT_HEADING))
+                                        .text_size(theme::scaled(theme::FONT_HEADING))
                                         .font_family(fonts::HEADING_FONT_FAMILY)
                                         .font_weight(FontWeight::MEDIUM)
                                         .text_color(rgb(theme::text_primary(cx)))
@@ -153,7 +172,7 @@ fn render_session_header(cx: &mut Context<AgentSessions>) -> impl IntoElement {
                                 )
                                 .child(
                                     div()
-                                        .text_size(px(theme::FONT_BODY))
+                                        .text_size(theme::scaled(theme::FONT_BODY))
                                         .text_color(rgb(theme::text_muted(cx)))
                                         .child("Sessions across agents. Press to resume"),
                                 ),
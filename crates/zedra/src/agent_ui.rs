@@ -121,7 +121,7 @@ pub fn render_agent_card(cx: &App, props: AgentCardProps<'_>) -> Stateful<Div> {
                                         .min_w_0()
                                         .overflow_hidden()
                                         .whitespace_nowrap()
-                                        .text_size(px(theme::FONT_AGENT_CARD_TITLE))
+                                        .text_size(theme::scaled(theme::FONT_AGENT_CARD_TITLE))
                                         .font_family(fonts::HEADING_FONT_FAMILY)
                                         .text_color(rgb(theme::text_primary(cx)))
                                         .child(display_name),
@@ -136,7 +136,7 @@ pub fn render_agent_card(cx: &App, props: AgentCardProps<'_>) -> Stateful<Div> {
                                             .bg(rgb(theme::bg_card(cx)))
                                             .border_1()
                                             .border_color(rgb(theme::border_subtle(cx)))
-                                            .text_size(px(theme::FONT_DETAIL))
+                                            .text_size(theme::scaled(theme::FONT_DETAIL))
                                             .text_color(rgb(theme::text_muted(cx)))
                                             .whitespace_nowrap()
                                             .child(plan_label.to_ascii_lowercase()),
@@ -156,7 +156,7 @@ pub fn render_agent_card(cx: &App, props: AgentCardProps<'_>) -> Stateful<Div> {
                                 .min_w_0()
                                 .overflow_hidden()
                                 .whitespace_nowrap()
-                                .text_size(px(theme::FONT_DETAIL))
+                                .text_size(theme::scaled(theme::FONT_DETAIL))
                                 .text_color(rgb(theme::text_muted(cx)))
                                 .child(version),
                         )
@@ -164,7 +164,7 @@ pub fn render_agent_card(cx: &App, props: AgentCardProps<'_>) -> Stateful<Div> {
                             div()
                                 .flex_shrink_0()
                                 .whitespace_nowrap()
-                                .text_size(px(theme::FONT_DETAIL))
+                                .text_size(theme::scaled(theme::FONT_DETAIL))
                                 .text_color(rgb(theme::text_muted(cx)))
                                 .child(sessions_label),
                         ),
@@ -176,7 +176,7 @@ pub fn render_agent_card(cx: &App, props: AgentCardProps<'_>) -> Stateful<Div> {
                             .min_w_0()
                             .overflow_hidden()
                             .whitespace_nowrap()
-                            .text_size(px(theme::FONT_DETAIL))
+                            .text_size(theme::scaled(theme::FONT_DETAIL))
                             .text_color(rgb(theme::text_muted(cx)))
                             .child(text),
                     )
@@ -189,7 +189,7 @@ pub fn render_agent_card(cx: &App, props: AgentCardProps<'_>) -> Stateful<Div> {
                             .min_w_0()
                             .overflow_hidden()
                             .whitespace_nowrap()
-                            .text_size(px(theme::FONT_DETAIL))
+                            .text_size(theme::scaled(theme::FONT_DETAIL))
                             .text_color(rgb(theme::text_muted(cx)))
                             .child(agent.highlight.clone()),
                     )
@@ -223,7 +223,7 @@ pub fn render_extra_row(fields: &[AgentInfoField], cx: &App) -> Option<Div> {
             .gap(px(3.0))
             .children(fields.iter().map(|field| {
                 div()
-                    .text_size(px(theme::FONT_DETAIL))
+                    .text_size(theme::scaled(theme::FONT_DETAIL))
                     .text_color(rgb(theme::text_muted(cx)))
                     .child(format!("{}: {}", field.label, field.value))
             })),
@@ -297,7 +297,7 @@ fn usage_gauge(
                         .w(px(GAUGE_LABEL_W))
                         .flex_shrink_0()
                         .whitespace_nowrap()
-                        .text_size(px(theme::FONT_DETAIL))
+                        .text_size(theme::scaled(theme::FONT_DETAIL))
                         .text_color(rgb(theme::text_muted(cx)))
                         .child(label),
                 )
@@ -326,7 +326,7 @@ fn usage_gauge(
                 .child(
                     div()
                         .whitespace_nowrap()
-                        .text_size(px(theme::FONT_DETAIL))
+                        .text_size(theme::scaled(theme::FONT_DETAIL))
                         .text_color(rgb(bar_color))
                         .child(pct_text),
                 ),
@@ -340,7 +340,7 @@ fn usage_gauge(
                 .child(
                     div()
                         .whitespace_nowrap()
-                        .text_size(px(theme::FONT_DETAIL))
+                        .text_size(theme::scaled(theme::FONT_DETAIL))
                         .text_color(rgb(theme::text_muted(cx)))
                         .child(SharedString::from(resets_in.unwrap_or("").to_string())),
                 ),
@@ -490,7 +490,7 @@ fn session_title_row(session: &AgentSessionSummary, cx: &App) -> Div {
                 // Trim long titles to the row width at render time (host only
                 // applies a generous anti-abuse cap).
                 .truncate()
-                .text_size(px(theme::FONT_BODY))
+                .text_size(theme::scaled(theme::FONT_BODY))
                 .text_color(rgb(theme::text_primary(cx)))
                 .child(session_title(session)),
         );
@@ -499,7 +499,7 @@ fn session_title_row(session: &AgentSessionSummary, cx: &App) -> Div {
         row = row.child(
             div()
                 .flex_shrink_0()
-                .text_size(px(theme::FONT_DETAIL))
+                .text_size(theme::scaled(theme::FONT_DETAIL))
                 .text_color(rgb(theme::text_muted(cx)))
                 .child(format_session_time(at)),
         );
@@ -528,7 +528,7 @@ fn session_meta_row(session: &AgentSessionSummary, cx: &App) -> impl IntoElement
         .flex_row()
         .items_center()
         .gap(px(8.0))
-        .text_size(px(theme::FONT_DETAIL))
+        .text_size(theme::scaled(theme::FONT_DETAIL))
         .text_color(rgb(theme::text_muted(cx)))
         .child(
             div()
@@ -687,7 +687,7 @@ fn section_header(label: &str, cx: &App) -> Div {
         .min_w_0()
         .pt(px(theme::SPACING_SM))
         .pb(px(4.0))
-        .text_size(px(theme::FONT_DETAIL))
+        .text_size(theme::scaled(theme::FONT_DETAIL))
         .text_color(rgb(theme::text_muted(cx)))
         .child(label.to_string())
 }
@@ -697,7 +697,7 @@ fn list_empty_text(text: impl Into<SharedString>, cx: &App) -> Div {
         .w_full()
         .min_w_0()
         .py(px(theme::SPACING_LG))
-        .text_size(px(theme::FONT_BODY))
+        .text_size(theme::scaled(theme::FONT_BODY))
         .text_color(rgb(theme::text_muted(cx)))
         .whitespace_normal()
         .child(text.into())
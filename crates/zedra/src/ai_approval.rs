@@ -0,0 +1,277 @@
+// Device approval for tools the host AI wants to run.
+//
+// `AiPromptV2` denies every tool outside `allowed_tools` and returns the
+// denied uses as `permission_requests`. `prompt` asks about each one: Allow
+// resends with the use allowlisted and the Claude Code session resumed,
+// Always Allow also saves it in app settings as a grant for this host so
+// later prompts to the host carry it, and Deny keeps the reply as it is.
+// Grants are narrow patterns built from the tool input (`Bash(cargo test:*)`,
+// not `Bash`) and can be revoked in Settings. All app AI calls go through
+// here so a tool never runs on the host without the user saying so.
+
+use anyhow::Result;
+use zedra_rpc::proto::{AiPermissionRequest, AiPromptReqV2, AiPromptResultV2};
+use zedra_session::SessionHandle;
+
+use crate::platform_bridge::{self, AlertButton};
+use crate::settings::{self, AiToolGrant};
+
+/// Resends after approvals before the reply is returned as is.
+const MAX_APPROVAL_ROUNDS: usize = 4;
+
+/// Sent when resuming a session whose tool use was just approved.
+const CONTINUE_PROMPT: &str = "The tools you asked for are now allowed. Continue.";
+
+/// Characters of tool input shown in the approval alert.
+const MAX_INPUT_CHARS: usize = 300;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Decision {
+    Allow,
+    AlwaysAllow,
+    Deny,
+}
+
+/// Run `req` on the host, asking the user about any tool it wants to use.
+pub async fn prompt(handle: &SessionHandle, mut req: AiPromptReqV2) -> Result<AiPromptResultV2> {
+    let host = handle.endpoint_id().map(|id| id.to_string());
+    for grant in settings::read_ai_tool_grants() {
+        if host.as_ref() == Some(&grant.host) {
+            allow_tool(&mut req.allowed_tools, &grant.pattern);
+        }
+    }
+    let mut rounds = 0;
+    loop {
+        let result = handle.ai_prompt(req.clone()).await?;
+        let pending = pending_requests(&result, &req.allowed_tools);
+        if pending.is_empty() || rounds == MAX_APPROVAL_ROUNDS {
+            return Ok(result);
+        }
+        for request in pending {
+            let pattern = grant_pattern(&request);
+            match ask(&request, &pattern).await {
+                Decision::Deny => return Ok(result),
+                Decision::AlwaysAllow => {
+                    if let Some(host) = &host {
+                        settings::add_ai_tool_grant(AiToolGrant {
+                            host: host.clone(),
+                            pattern: pattern.clone(),
+                        });
+                    }
+                }
+                Decision::Allow => {}
+            }
+            allow_tool(&mut req.allowed_tools, &pattern);
+        }
+        req = resume_request(req, &result);
+        rounds += 1;
+    }
+}
+
+fn allow_tool(allowed: &mut Vec<String>, tool: &str) {
+    if !allowed.iter().any(|t| t == tool) {
+        allowed.push(tool.to_string());
+    }
+}
+
+/// Denied tool uses the user has not already allowed, once per grant
+/// pattern. A use denied despite its pattern being allowlisted would only be
+/// denied again.
+fn pending_requests(result: &AiPromptResultV2, allowed: &[String]) -> Vec<AiPermissionRequest> {
+    let mut pending: Vec<AiPermissionRequest> = Vec::new();
+    for request in &result.permission_requests {
+        let pattern = grant_pattern(request);
+        if !allowed.contains(&pattern) && !pending.iter().any(|p| grant_pattern(p) == pattern) {
+            pending.push(request.clone());
+        }
+    }
+    pending
+}
+
+/// The `--allowedTools` pattern approving `request`: a command prefix for
+/// Bash, the file for edit and read tools, and the domain for WebFetch. Tools
+/// without an input to narrow by are allowed by name.
+fn grant_pattern(request: &AiPermissionRequest) -> String {
+    let input: serde_json::Value = serde_json::from_str(&request.tool_input).unwrap_or_default();
+    let field = |key: &str| input.get(key).and_then(|value| value.as_str());
+    let tool = request.tool_name.as_str();
+    // Claude Code checks every file-editing tool against `Edit(...)` rules.
+    let (rule, arg) = match tool {
+        "Bash" => ("Bash", field("command").map(command_pattern)),
+        "Edit" | "MultiEdit" | "Write" => ("Edit", field("file_path").map(path_pattern)),
+        "NotebookEdit" => ("Edit", field("notebook_path").map(path_pattern)),
+        "Read" => ("Read", field("file_path").map(path_pattern)),
+        "WebFetch" => ("WebFetch", field("url").and_then(domain_pattern)),
+        _ => (tool, None),
+    };
+    match arg {
+        Some(arg) => format!("{rule}({arg})"),
+        None => tool.to_string(),
+    }
+}
+
+/// `cargo test -p zedra` becomes `cargo test:*`. Compound commands are
+/// matched exactly so approving one never allows what it chains to.
+fn command_pattern(command: &str) -> String {
+    let command = command.trim();
+    if command.contains(['&', '|', ';', '<', '>', '`', '$', '\n']) {
+        return command.to_string();
+    }
+    let words: Vec<&str> = command.split_whitespace().take(2).collect();
+    match words.as_slice() {
+        [program, sub] if !sub.starts_with('-') => format!("{program} {sub}:*"),
+        [program, ..] => format!("{program}:*"),
+        [] => command.to_string(),
+    }
+}
+
+/// Absolute paths take a leading `//` in Claude Code file rules; a single
+/// `/` would be relative to the settings file.
+fn path_pattern(path: &str) -> String {
+    if path.starts_with('/') {
+        format!("/{path}")
+    } else {
+        path.to_string()
+    }
+}
+
+fn domain_pattern(url: &str) -> Option<String> {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host = authority.rsplit('@').next()?.split(':').next()?;
+    (!host.is_empty()).then(|| format!("domain:{host}"))
+}
+
+/// The request that continues `result` after its tools were allowed. Without
+/// a session to resume, the original prompt is sent again.
+fn resume_request(req: AiPromptReqV2, result: &AiPromptResultV2) -> AiPromptReqV2 {
+    let conversation_id = result.conversation_id.clone().or(req.conversation_id);
+    match &result.session_id {
+        Some(session_id) => AiPromptReqV2 {
+            prompt: CONTINUE_PROMPT.to_string(),
+            context: None,
+            allowed_tools: req.allowed_tools,
+            resume_session_id: Some(session_id.clone()),
+            conversation_id,
+        },
+        None => AiPromptReqV2 {
+            conversation_id,
+            ..req
+        },
+    }
+}
+
+fn input_summary(input: &str) -> String {
+    match input.char_indices().nth(MAX_INPUT_CHARS) {
+        Some((cut, _)) => format!("{}…", &input[..cut]),
+        None => input.to_string(),
+    }
+}
+
+async fn ask(request: &AiPermissionRequest, pattern: &str) -> Decision {
+    let (choice_tx, choice_rx) = tokio::sync::oneshot::channel();
+    platform_bridge::show_alert(
+        &format!("Allow {}?", request.tool_name),
+        &format!(
+            "The host AI wants to run {} with:\n\n{}\n\nAlways Allow lets {} run on this host \
+             without asking.",
+            request.tool_name,
+            input_summary(&request.tool_input),
+            pattern
+        ),
+        vec![
+            AlertButton::default("Allow Once"),
+            AlertButton::default("Always Allow"),
+            AlertButton::cancel("Deny"),
+        ],
+        move |index| {
+            let _ = choice_tx.send(index);
+        },
+    );
+    match choice_rx.await {
+        Ok(0) => Decision::Allow,
+        Ok(1) => Decision::AlwaysAllow,
+        _ => Decision::Deny,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(tool: &str, input: &str) -> AiPermissionRequest {
+        AiPermissionRequest {
+            tool_name: tool.to_string(),
+            tool_input: input.to_string(),
+        }
+    }
+
+    #[test]
+    fn asks_once_per_pattern_and_resumes_the_session() {
+        let test = request("Bash", r#"{"command":"cargo test -p zedra"}"#);
+        let result = AiPromptResultV2 {
+            session_id: Some("s1".into()),
+            conversation_id: Some("c1".into()),
+            permission_requests: vec![
+                test.clone(),
+                request("Read", r#"{"file_path":"/src/lib.rs"}"#),
+                request("Bash", r#"{"command":"cargo test"}"#),
+                request("Bash", r#"{"command":"rm -rf target"}"#),
+            ],
+            ..Default::default()
+        };
+        let pending = pending_requests(&result, &["Read(//src/lib.rs)".to_string()]);
+        assert_eq!(
+            pending,
+            vec![test, request("Bash", r#"{"command":"rm -rf target"}"#)]
+        );
+
+        let req = AiPromptReqV2 {
+            prompt: "fix the test".into(),
+            context: Some("diff".into()),
+            allowed_tools: vec!["Read".into(), "Bash".into()],
+            resume_session_id: None,
+            conversation_id: None,
+        };
+        let next = resume_request(req.clone(), &result);
+        assert_eq!(next.prompt, CONTINUE_PROMPT);
+        assert_eq!(next.context, None);
+        assert_eq!(next.resume_session_id.as_deref(), Some("s1"));
+        assert_eq!(next.conversation_id.as_deref(), Some("c1"));
+        assert_eq!(next.allowed_tools, req.allowed_tools);
+
+        let without_session = resume_request(req.clone(), &AiPromptResultV2::default());
+        assert_eq!(without_session, req);
+
+        assert_eq!(
+            input_summary(&"x".repeat(MAX_INPUT_CHARS + 5))
+                .chars()
+                .count(),
+            MAX_INPUT_CHARS + 1
+        );
+    }
+
+    #[test]
+    fn grants_are_narrowed_by_tool_input() {
+        let pattern = |tool: &str, input: &str| grant_pattern(&request(tool, input));
+        assert_eq!(
+            pattern("Bash", r#"{"command":"cargo test -p zedra"}"#),
+            "Bash(cargo test:*)"
+        );
+        assert_eq!(pattern("Bash", r#"{"command":"ls -la"}"#), "Bash(ls:*)");
+        assert_eq!(
+            pattern("Bash", r#"{"command":"cargo test && git push"}"#),
+            "Bash(cargo test && git push)"
+        );
+        assert_eq!(
+            pattern("Write", r#"{"file_path":"/repo/src/main.rs","content":""}"#),
+            "Edit(//repo/src/main.rs)"
+        );
+        assert_eq!(
+            pattern("WebFetch", r#"{"url":"https://docs.rs:443/serde?x=1"}"#),
+            "WebFetch(domain:docs.rs)"
+        );
+        assert_eq!(pattern("Glob", r#"{"pattern":"**/*.rs"}"#), "Glob");
+        assert_eq!(pattern("Bash", "not json"), "Bash");
+    }
+}
@@ -0,0 +1,499 @@
+// Sheet for an AI edit of the editor selection.
+//
+// The selected lines and the code around them go to the host AI with the
+// user's instruction; the reply replaces the selected lines and is shown as a
+// diff. Apply re-reads the file and writes it back in one `fs_write_text`,
+// refusing when the selected lines changed in the meantime. Undo does the
+// same in reverse, so it only restores the original lines while the file still
+// holds the suggestion.
+
+use std::ops::Range;
+
+use gpui::prelude::FluentBuilder as _;
+use gpui::*;
+use zedra_rpc::proto::AiPromptReqV2;
+use zedra_session::SessionHandle;
+
+use crate::ai_approval;
+use crate::button::outline_button;
+use crate::editor::git_diff_view::{DiffHunk, DiffLine, DiffLineKind, FileDiff, GitDiffView};
+use crate::editor::word_diff::mark_word_changes;
+use crate::git_panel::strip_code_fence;
+use crate::placeholder::render_placeholder;
+use crate::platform_bridge::{
+    self, AlertButton, NativeNotificationKind, NativeNotificationOptions,
+};
+use crate::theme;
+use crate::workspace_editor::EditorSelection;
+
+/// Largest selection, in lines, that can be sent.
+pub const MAX_SELECTION_LINES: u32 = 400;
+
+/// Lines above and below the selection sent as context.
+const CONTEXT_LINES: usize = 40;
+
+/// Unchanged lines shown around the change in the diff.
+const DIFF_CONTEXT_LINES: usize = 3;
+
+/// The file on the host was written by an applied or undone edit: the lines
+/// from 1-based `start` that read `replaced` now read `replacement`.
+pub struct AiEditApplied {
+    pub path: String,
+    pub start: u32,
+    pub replaced: String,
+    pub replacement: String,
+}
+
+#[derive(Clone, Debug)]
+struct AiEdit {
+    path: String,
+    /// 1-based first line of the selection.
+    start: u32,
+    original: String,
+    replacement: String,
+}
+
+impl AiEdit {
+    fn line_label(&self) -> String {
+        let end = self.start as usize + self.original.split('\n').count() - 1;
+        if end == self.start as usize {
+            format!("{} · line {}", self.path, self.start)
+        } else {
+            format!("{} · lines {}–{end}", self.path, self.start)
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum AiEditState {
+    Thinking,
+    Ready,
+    Writing,
+    Applied,
+    Error(String),
+}
+
+/// Sheet that asks the host AI to rewrite a selection and applies the result.
+pub struct AiEditSheet {
+    session_handle: SessionHandle,
+    diff_view: Entity<GitDiffView>,
+    edit: Option<AiEdit>,
+    instruction: String,
+    state: AiEditState,
+    task: Option<Task<()>>,
+}
+
+impl EventEmitter<AiEditApplied> for AiEditSheet {}
+
+impl AiEditSheet {
+    pub fn new(session_handle: SessionHandle, cx: &mut Context<Self>) -> Self {
+        Self {
+            session_handle,
+            diff_view: cx.new(|cx| GitDiffView::new(cx)),
+            edit: None,
+            instruction: String::new(),
+            state: AiEditState::Thinking,
+            task: None,
+        }
+    }
+
+    /// Ask for a rewrite of `selection` following `instruction`.
+    pub fn open(
+        &mut self,
+        selection: EditorSelection,
+        instruction: String,
+        cx: &mut Context<Self>,
+    ) {
+        self.edit = None;
+        self.instruction = instruction.clone();
+        self.state = AiEditState::Thinking;
+        cx.notify();
+
+        let handle = self.session_handle.clone();
+        self.task = Some(cx.spawn(async move |this, cx| {
+            let result: anyhow::Result<(AiEdit, FileDiff)> = async {
+                let read = handle.fs_read_text(&selection.path).await?;
+                if let Some(error) = read.error {
+                    anyhow::bail!(error);
+                }
+                if read.too_large {
+                    anyhow::bail!("The file is too large.");
+                }
+                let lines: Vec<&str> = read.content.split('\n').collect();
+                let range = line_range(lines.len(), selection.start, selection.end)
+                    .ok_or_else(|| anyhow::anyhow!("The selection is outside the file."))?;
+                let reply = ai_approval::prompt(
+                    &handle,
+                    AiPromptReqV2 {
+                        prompt: edit_prompt(&instruction),
+                        context: Some(prompt_context(&selection.path, &lines, range.clone())),
+                        allowed_tools: Vec::new(),
+                        resume_session_id: None,
+                        conversation_id: None,
+                    },
+                )
+                .await?;
+                if let Some(error) = reply.error {
+                    anyhow::bail!(error);
+                }
+                let edit = AiEdit {
+                    path: selection.path.clone(),
+                    start: selection.start,
+                    original: lines[range.clone()].join("\n"),
+                    replacement: strip_code_fence(&reply.text),
+                };
+                if edit.replacement == edit.original {
+                    anyhow::bail!("The AI suggested no changes.");
+                }
+                let diff = edit_diff(&edit.path, &lines, range, &edit.replacement);
+                Ok((edit, diff))
+            }
+            .await;
+            let _ = this.update(cx, |this, cx| {
+                match result {
+                    Ok((edit, diff)) => {
+                        let filename = edit.path.rsplit('/').next().unwrap_or_default().to_string();
+                        this.diff_view
+                            .update(cx, |view, cx| view.set_diff(filename, diff, cx));
+                        this.edit = Some(edit);
+                        this.state = AiEditState::Ready;
+                    }
+                    Err(e) => {
+                        tracing::warn!("ai edit: suggestion failed: {}", e);
+                        this.state = AiEditState::Error(e.to_string());
+                    }
+                }
+                cx.notify();
+            });
+        }));
+    }
+
+    /// Write the suggestion to the host, or with `undo` put the original
+    /// lines back.
+    fn write(&mut self, undo: bool, cx: &mut Context<Self>) {
+        let Some(edit) = self.edit.clone() else {
+            return;
+        };
+        let previous = std::mem::replace(&mut self.state, AiEditState::Writing);
+        cx.notify();
+
+        let handle = self.session_handle.clone();
+        self.task = Some(cx.spawn(async move |this, cx| {
+            let (expected, replacement) = if undo {
+                (&edit.replacement, &edit.original)
+            } else {
+                (&edit.original, &edit.replacement)
+            };
+            let result: anyhow::Result<()> = async {
+                let read = handle.fs_read_text(&edit.path).await?;
+                if let Some(error) = read.error {
+                    anyhow::bail!(error);
+                }
+                let content = replace_lines(&read.content, edit.start, expected, replacement)
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("The lines changed on the host since the suggestion.")
+                    })?;
+                handle
+                    .fs_write_text(&edit.path, &content, None, None)
+                    .await?;
+                Ok(())
+            }
+            .await;
+            let _ = this.update(cx, |this, cx| {
+                match result {
+                    Ok(()) => {
+                        this.state = if undo {
+                            AiEditState::Ready
+                        } else {
+                            AiEditState::Applied
+                        };
+                        let title = if undo {
+                            "AI edit undone"
+                        } else {
+                            "AI edit applied"
+                        };
+                        platform_bridge::show_native_notification(
+                            NativeNotificationOptions::new(title)
+                                .kind(NativeNotificationKind::Success),
+                        );
+                        cx.emit(AiEditApplied {
+                            path: edit.path.clone(),
+                            start: edit.start,
+                            replaced: expected.clone(),
+                            replacement: replacement.clone(),
+                        });
+                    }
+                    Err(e) => {
+                        tracing::error!("ai edit: write failed for {}: {}", edit.path, e);
+                        this.state = previous;
+                        platform_bridge::show_alert(
+                            if undo {
+                                "Couldn't undo edit"
+                            } else {
+                                "Couldn't apply edit"
+                            },
+                            &e.to_string(),
+                            vec![AlertButton::default("OK")],
+                            |_| {},
+                        );
+                    }
+                }
+                cx.notify();
+            });
+        }));
+    }
+}
+
+impl Render for AiEditSheet {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let body: AnyElement = match &self.state {
+            AiEditState::Thinking => render_placeholder(cx, "Thinking ...").into_any_element(),
+            AiEditState::Error(error) => {
+                render_placeholder(cx, format!("Error: {}", error)).into_any_element()
+            }
+            AiEditState::Ready | AiEditState::Writing | AiEditState::Applied => div()
+                .size_full()
+                .child(self.diff_view.clone())
+                .into_any_element(),
+        };
+        let (button_id, button_label, undo) = match self.state {
+            AiEditState::Applied => ("ai-edit-undo", "Undo", true),
+            _ => ("ai-edit-apply", "Apply", false),
+        };
+        let can_write = matches!(self.state, AiEditState::Ready | AiEditState::Applied);
+        let bottom_inset = platform_bridge::home_indicator_inset();
+        div()
+            .size_full()
+            .flex()
+            .flex_col()
+            .bg(rgb(theme::bg_primary(cx)))
+            .child(
+                div()
+                    .px(px(theme::SPACING_MD))
+                    .pt(px(theme::SPACING_LG))
+                    .pb(px(theme::SPACING_SM))
+                    .flex()
+                    .flex_col()
+                    .child(
+                        div()
+                            .text_size(theme::scaled(theme::FONT_HEADING))
+                            .font_weight(FontWeight::MEDIUM)
+                            .text_color(rgb(theme::text_primary(cx)))
+                            .child("Edit with AI"),
+                    )
+                    .child(
+                        div()
+                            .text_size(theme::scaled(theme::FONT_DETAIL))
+                            .text_color(rgb(theme::text_secondary(cx)))
+                            .child(self.instruction.clone()),
+                    )
+                    .when_some(self.edit.as_ref(), |el, edit| {
+                        el.child(
+                            div()
+                                .truncate()
+                                .text_size(theme::scaled(theme::FONT_DETAIL))
+                                .text_color(rgb(theme::text_muted(cx)))
+                                .child(edit.line_label()),
+                        )
+                    }),
+            )
+            .child(div().flex_1().min_h_0().child(body))
+            .when(self.edit.is_some(), |el| {
+                el.child(
+                    div()
+                        .px(px(theme::SPACING_MD))
+                        .pt(px(theme::SPACING_SM))
+                        .pb(px(theme::SPACING_SM + bottom_inset))
+                        .child(
+                            outline_button(cx, button_id, button_label)
+                                .when(!can_write, |button| button.opacity(0.35))
+                                .on_press(cx.listener(move |this, _event, _window, cx| {
+                                    if matches!(
+                                        this.state,
+                                        AiEditState::Ready | AiEditState::Applied
+                                    ) {
+                                        this.write(undo, cx);
+                                    }
+                                })),
+                        ),
+                )
+            })
+    }
+}
+
+fn edit_prompt(instruction: &str) -> String {
+    format!(
+        "Rewrite the lines between the <selection> tags in the context as follows: \
+         {instruction}\n\nReply with the replacement for the selected lines only, keeping \
+         their indentation. Do not include the tags, code fences, or commentary."
+    )
+}
+
+/// The file's path and the selection with up to `CONTEXT_LINES` around it.
+fn prompt_context(path: &str, lines: &[&str], range: Range<usize>) -> String {
+    let before = range.start.saturating_sub(CONTEXT_LINES)..range.start;
+    let after = range.end..(range.end + CONTEXT_LINES).min(lines.len());
+    let mut context = format!("File: {path}\n");
+    for part in [
+        lines[before].join("\n"),
+        "<selection>".to_string(),
+        lines[range].join("\n"),
+        "</selection>".to_string(),
+        lines[after].join("\n"),
+    ] {
+        if !part.is_empty() {
+            context.push_str(&part);
+            context.push('\n');
+        }
+    }
+    context
+}
+
+/// Index range of the 1-based inclusive lines `start..=end`, if in the file.
+fn line_range(line_count: usize, start: u32, end: u32) -> Option<Range<usize>> {
+    let range = start.checked_sub(1)? as usize..end as usize;
+    (range.start < range.end && range.end <= line_count).then_some(range)
+}
+
+/// `content` with the lines from 1-based `start` replaced by `replacement`,
+/// or `None` when they no longer read `expected`.
+fn replace_lines(content: &str, start: u32, expected: &str, replacement: &str) -> Option<String> {
+    let mut lines: Vec<&str> = content.split('\n').collect();
+    let end = start + expected.split('\n').count() as u32 - 1;
+    let range = line_range(lines.len(), start, end)?;
+    if lines[range.clone()].join("\n") != expected {
+        return None;
+    }
+    lines.splice(range, replacement.split('\n'));
+    Some(lines.join("\n"))
+}
+
+/// A one-hunk diff of replacing `range` of `lines` with `replacement`.
+fn edit_diff(path: &str, lines: &[&str], range: Range<usize>, replacement: &str) -> FileDiff {
+    let before = range.start.saturating_sub(DIFF_CONTEXT_LINES)..range.start;
+    let after = range.end..(range.end + DIFF_CONTEXT_LINES).min(lines.len());
+    let added: Vec<&str> = replacement.split('\n').collect();
+    let shift = added.len() as isize - range.len() as isize;
+    let line = |kind, old: Option<usize>, new: Option<usize>, content: &str| DiffLine {
+        kind,
+        old_line_num: old,
+        new_line_num: new,
+        content: content.to_string(),
+        word_changes: Vec::new(),
+    };
+
+    let mut diff_lines = Vec::new();
+    for index in before.clone() {
+        diff_lines.push(line(
+            DiffLineKind::Unchanged,
+            Some(index + 1),
+            Some(index + 1),
+            lines[index],
+        ));
+    }
+    for index in range.clone() {
+        diff_lines.push(line(
+            DiffLineKind::Removed,
+            Some(index + 1),
+            None,
+            lines[index],
+        ));
+    }
+    for (offset, text) in added.iter().enumerate() {
+        diff_lines.push(line(
+            DiffLineKind::Added,
+            None,
+            Some(range.start + offset + 1),
+            text,
+        ));
+    }
+    for index in after.clone() {
+        let new = (index as isize + shift) as usize + 1;
+        diff_lines.push(line(
+            DiffLineKind::Unchanged,
+            Some(index + 1),
+            Some(new),
+            lines[index],
+        ));
+    }
+    mark_word_changes(&mut diff_lines);
+
+    let old_count = before.len() + range.len() + after.len();
+    let new_count = before.len() + added.len() + after.len();
+    let start = before.start + 1;
+    FileDiff {
+        old_path: path.to_string(),
+        new_path: path.to_string(),
+        hunks: vec![DiffHunk {
+            header: format!("@@ -{start},{old_count} +{start},{new_count} @@"),
+            old_start: start,
+            old_count,
+            new_start: start,
+            new_count,
+            lines: diff_lines,
+        }],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replaces_only_unchanged_selections() {
+        let content = "fn main() {\n    let x = 1;\n    println!(\"{x}\");\n}\n";
+        let edited = replace_lines(
+            content,
+            2,
+            "    let x = 1;",
+            "    let x = 2;\n    let y = x;",
+        )
+        .unwrap();
+        assert_eq!(
+            edited,
+            "fn main() {\n    let x = 2;\n    let y = x;\n    println!(\"{x}\");\n}\n"
+        );
+        // Undo is the same replacement in reverse.
+        assert_eq!(
+            replace_lines(
+                &edited,
+                2,
+                "    let x = 2;\n    let y = x;",
+                "    let x = 1;"
+            )
+            .unwrap(),
+            content
+        );
+        assert!(replace_lines(&edited, 2, "    let x = 1;", "    let x = 3;").is_none());
+        assert!(replace_lines(content, 9, "}", "").is_none());
+    }
+
+    #[test]
+    fn diff_numbers_lines_after_the_change() {
+        let lines = ["a", "b", "c", "d"];
+        let diff = edit_diff("f.txt", &lines, 1..2, "B\nB2");
+        let hunk = &diff.hunks[0];
+        assert_eq!(hunk.header, "@@ -1,4 +1,5 @@");
+        let numbers: Vec<_> = hunk
+            .lines
+            .iter()
+            .map(|line| (line.old_line_num, line.new_line_num))
+            .collect();
+        assert_eq!(
+            numbers,
+            [
+                (Some(1), Some(1)),
+                (Some(2), None),
+                (None, Some(2)),
+                (None, Some(3)),
+                (Some(3), Some(4)),
+                (Some(4), Some(5)),
+            ]
+        );
+        let context = prompt_context("f.txt", &lines, 1..2);
+        assert_eq!(
+            context,
+            "File: f.txt\na\n<selection>\nb\n</selection>\nc\nd\n"
+        );
+    }
+}
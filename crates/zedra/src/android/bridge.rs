@@ -157,8 +157,15 @@ impl PlatformBridge for AndroidBridge {
         jni::present_native_notification(id, options);
     }
 
-    fn present_text_input(&self, id: u32, title: &str, placeholder: &str, initial_value: &str) {
-        jni::show_text_input(id, title, placeholder, initial_value);
+    fn present_text_input(
+        &self,
+        id: u32,
+        title: &str,
+        placeholder: &str,
+        initial_value: &str,
+        secure: bool,
+    ) {
+        jni::show_text_input(id, title, placeholder, initial_value, secure);
     }
 
     fn system_prefers_theme(&self) -> SystemTheme {
@@ -169,6 +176,10 @@ impl PlatformBridge for AndroidBridge {
         jni::set_native_theme(is_dark);
     }
 
+    fn publish_launcher_shortcuts(&self, shortcuts_json: &str) {
+        jni::publish_launcher_shortcuts(shortcuts_json);
+    }
+
     fn request_delta_push_token(&self, id: u32) {
         jni::request_delta_push_token(id);
     }
@@ -184,4 +195,20 @@ impl PlatformBridge for AndroidBridge {
     fn clipboard_has_image(&self) -> bool {
         jni::clipboard_has_image()
     }
+
+    fn font_scale(&self) -> f32 {
+        jni::font_scale()
+    }
+
+    fn vault_key(&self) -> Result<Option<[u8; 32]>, String> {
+        let key = jni::vault_key().ok_or("Android Keystore is unavailable")?;
+        let key = key
+            .try_into()
+            .map_err(|_| "Android Keystore returned a malformed vault key".to_string())?;
+        Ok(Some(key))
+    }
+
+    fn delete_vault_key(&self) {
+        jni::delete_vault_key();
+    }
 }
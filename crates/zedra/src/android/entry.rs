@@ -6,7 +6,7 @@
 //! `gpui_android_did_finish_launching` fires.
 //!
 //! Steps:
-//!   1. Set up logging, panic hook, telemetry.
+//!   1. Start the cold-start clock, set up logging, panic hook, telemetry.
 //!   2. Register the `AndroidBridge` `PlatformBridge` impl.
 //!   3. Construct `AndroidPlatform` via `gpui_android::create_platform()`.
 //!   4. Build the GPUI `AppCell` and store it in a thread-local.
@@ -22,6 +22,8 @@ use jni::JNIEnv;
 use jni::objects::JClass;
 
 use crate::android::bridge::AndroidBridge;
+use crate::app_action::InsertText;
+use crate::memory::MemoryPressure;
 use crate::{app, platform_bridge};
 
 thread_local! {
@@ -36,11 +38,13 @@ pub extern "system" fn Java_dev_zedra_app_MainActivity_zedraLaunchGpui(
     _env: JNIEnv,
     _class: JClass,
 ) {
+    crate::startup::begin();
     super::jni::init_logging();
     crate::telemetry::init();
     tracing::info!("Zedra Android: creating GPUI application with AndroidPlatform");
 
     let platform: Rc<dyn Platform> = gpui_android::create_platform();
+    crate::startup::mark("platform");
     let app_cell = app::init_platform_app(platform.clone(), AndroidBridge);
 
     let app_cell_for_callback = app_cell.clone();
@@ -81,6 +85,42 @@ pub(crate) fn app_cell() -> Option<Rc<AppCell>> {
     ANDROID_APP_CELL.with(|cell| cell.borrow().clone())
 }
 
+pub(crate) fn handle_trim_memory(level: i32) {
+    let pressure = MemoryPressure::from_trim_level(level);
+    let Some(app_cell) = app_cell() else {
+        return;
+    };
+    let Some(any_window) = ANDROID_WINDOW.with(|window| *window.borrow()) else {
+        return;
+    };
+    let Some(window) = any_window.downcast::<app::ZedraApp>() else {
+        return;
+    };
+
+    let Ok(mut app) = app_cell.try_borrow_mut() else {
+        tracing::warn!(level, "Zedra Android: app busy, skipping trim memory");
+        return;
+    };
+    let cx: &mut App = &mut app;
+    let _ = window.update(cx, |view, _window, cx| view.trim_memory(pressure, cx));
+}
+
+/// Dispatch `InsertText` to the focused element of the root window.
+pub(crate) fn handle_insert_text(text: String) {
+    let Some(app_cell) = app_cell() else {
+        return;
+    };
+    let Some(any_window) = ANDROID_WINDOW.with(|window| *window.borrow()) else {
+        return;
+    };
+
+    let mut app = app_cell.borrow_mut();
+    let cx: &mut App = &mut app;
+    let _ = any_window.update(cx, |_, window, cx| {
+        window.dispatch_action(InsertText { text }.boxed_clone(), cx);
+    });
+}
+
 pub(crate) fn handle_system_back() -> bool {
     let Some(app_cell) = app_cell() else {
         return false;
@@ -610,6 +610,24 @@ pub extern "system" fn Java_dev_zedra_app_MainActivity_nativeKeyboardAccessoryVi
         .unwrap_or(false) as jboolean
 }
 
+/// Dropped or accessory-pasted text, delivered whole instead of as per-character
+/// IME key events.
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_dev_zedra_app_MainActivity_nativeInsertText(
+    mut env: JNIEnv,
+    _class: JClass,
+    text: jni::objects::JString,
+) {
+    let text: String = match env.get_string(&text) {
+        Ok(text) => text.into(),
+        Err(error) => {
+            tracing::error!(?error, "jni: failed to read inserted text");
+            return;
+        }
+    };
+    crate::android::entry::handle_insert_text(text);
+}
+
 #[unsafe(no_mangle)]
 pub extern "system" fn Java_dev_zedra_app_MainActivity_nativeSystemBackPressed(
     _env: JNIEnv,
@@ -618,6 +636,17 @@ pub extern "system" fn Java_dev_zedra_app_MainActivity_nativeSystemBackPressed(
     crate::android::entry::handle_system_back() as jboolean
 }
 
+/// `onTrimMemory(level)` / `onLowMemory()` from the activity. Releases caches
+/// and trims terminal scrollback according to the pressure level.
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_dev_zedra_app_MainActivity_nativeTrimMemory(
+    _env: JNIEnv,
+    _class: JClass,
+    level: jint,
+) {
+    crate::android::entry::handle_trim_memory(level);
+}
+
 /// Foreground/background state from the Android activity lifecycle
 /// (onResume → true, onStop → false). Mirrors the iOS bridge so the host can
 /// decide between RPC-only delivery and Delta push notifications.
@@ -630,6 +659,17 @@ pub extern "system" fn Java_dev_zedra_app_MainActivity_nativeSetAppForeground(
     platform_bridge::set_app_in_foreground(foreground != 0);
 }
 
+/// Metered state of the default network from the activity's connectivity
+/// callback, for the data saver's Auto mode.
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_dev_zedra_app_MainActivity_nativeSetNetworkMetered(
+    _env: JNIEnv,
+    _class: JClass,
+    metered: jboolean,
+) {
+    platform_bridge::set_network_metered(metered != 0);
+}
+
 fn dispatch_deeplink(url: String) {
     tracing::info!(url = &url[..url.len().min(80)], "jni: deeplink");
     match crate::deeplink::parse(&url) {
@@ -1507,6 +1547,55 @@ pub fn clipboard_has_image() -> bool {
     has_image
 }
 
+/// `Configuration.fontScale`: the user's font size from system settings.
+pub fn font_scale() -> f32 {
+    let mut scale = 1.0;
+    jni_call(
+        "font_scale",
+        std::panic::AssertUnwindSafe(|| {
+            with_main_activity_class("font_scale", |env, class| {
+                let Ok(value) = env.call_static_method(class, "fontScale", "()F", &[]) else {
+                    return;
+                };
+                scale = value.f().unwrap_or(1.0);
+            });
+        }),
+    );
+    scale
+}
+
+/// The secrets vault key, unwrapped by the Android Keystore key in
+/// `VaultKeystore`. `None` when the keystore call failed.
+pub fn vault_key() -> Option<Vec<u8>> {
+    let mut key = None;
+    jni_call(
+        "vault_key",
+        std::panic::AssertUnwindSafe(|| {
+            key = with_main_activity("vault_key", |env, class| {
+                let value = env
+                    .call_static_method(class, "vaultKey", "()[B", &[])?
+                    .l()?;
+                if value.is_null() {
+                    return Ok(None);
+                }
+                env.convert_byte_array(jni::objects::JByteArray::from(value))
+                    .map(Some)
+            })
+            .flatten();
+        }),
+    );
+    key
+}
+
+pub fn delete_vault_key() {
+    jni_call("delete_vault_key", || {
+        with_main_activity("delete_vault_key", |env, class| {
+            env.call_static_method(class, "deleteVaultKey", "()V", &[])?;
+            Ok(())
+        });
+    });
+}
+
 /// Delivered from `MainActivity` once the picker/clipboard read + downscale
 /// finishes. `extension` is "jpg" or "png".
 #[unsafe(no_mangle)]
@@ -1560,7 +1649,7 @@ pub extern "system" fn Java_dev_zedra_app_MainActivity_nativeImageAcquireError(
     platform_bridge::dispatch_image_acquire_error(callback_id as u32, message);
 }
 
-pub fn show_text_input(id: u32, title: &str, placeholder: &str, initial_value: &str) {
+pub fn show_text_input(id: u32, title: &str, placeholder: &str, initial_value: &str, secure: bool) {
     let title = title.to_string();
     let placeholder = placeholder.to_string();
     let initial_value = initial_value.to_string();
@@ -1590,12 +1679,13 @@ pub fn show_text_input(id: u32, title: &str, placeholder: &str, initial_value: &
             if let Err(error) = env.call_static_method(
                 class,
                 "showTextInput",
-                "(ILjava/lang/String;Ljava/lang/String;Ljava/lang/String;)V",
+                "(ILjava/lang/String;Ljava/lang/String;Ljava/lang/String;Z)V",
                 &[
                     (id as jint).into(),
                     (&title).into(),
                     (&placeholder).into(),
                     (&initial_value).into(),
+                    secure.into(),
                 ],
             ) {
                 tracing::error!(?error, "jni: showTextInput failed");
@@ -1908,3 +1998,26 @@ pub fn set_native_theme(is_dark: bool) {
         });
     });
 }
+
+pub fn publish_launcher_shortcuts(shortcuts_json: &str) {
+    let shortcuts_owned = shortcuts_json.to_string();
+    jni_call("publish_launcher_shortcuts", move || {
+        with_main_activity_class("publish_launcher_shortcuts", |env, class| {
+            let j_shortcuts = match env.new_string(&shortcuts_owned) {
+                Ok(s) => s,
+                Err(error) => {
+                    tracing::error!(?error, "jni: new_string launcher shortcuts failed");
+                    return;
+                }
+            };
+            if let Err(error) = env.call_static_method(
+                class,
+                "publishLauncherShortcuts",
+                "(Ljava/lang/String;)V",
+                &[(&j_shortcuts).into()],
+            ) {
+                tracing::error!(?error, "jni: publishLauncherShortcuts failed");
+            }
+        });
+    });
+}
@@ -7,11 +7,14 @@ use crate::app_action::SystemBack;
 use crate::deeplink::{self, DeeplinkAction};
 use crate::fonts;
 use crate::home_view::{HomeEvent, HomeView};
+use crate::memory::MemoryPressure;
 use crate::platform_bridge;
 use crate::quick_action_panel::{QuickActionEvent, QuickActionPanel};
 use crate::settings::{ThemeState, ThemeStateEvent};
 use crate::settings_view::{SettingsEvent, SettingsView};
+use crate::startup;
 use crate::telemetry::view_telemetry::{self, ViewDescriptor};
+use crate::theme;
 use crate::ui::{DrawerHost, DrawerSide};
 use crate::vfx::{self, overlay::DropletOverlay};
 use crate::web_tunnel_manager::WebTunnelManager;
@@ -41,14 +44,33 @@ fn should_update_drawer_content(current: AppScreen, next: AppScreen) -> bool {
     current != next || next == AppScreen::Workspace
 }
 
+/// Where the root view is in cold start. The first frame paints only the
+/// splash; everything not needed by the home screen is built once it is on
+/// screen (see [`ZedraApp::finish_startup`]).
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum StartupStage {
+    Splash,
+    /// The splash has painted and deferred init is queued for the next frame.
+    Deferred,
+    Ready,
+}
+
+/// State built after the first paint.
+struct DeferredViews {
+    settings_view: Entity<SettingsView>,
+    web_tunnel_manager: Entity<WebTunnelManager>,
+    droplet_overlay: Entity<DropletOverlay>,
+}
+
 pub struct ZedraApp {
     screen: AppScreen,
+    startup_stage: StartupStage,
+    theme_state: Entity<ThemeState>,
+    delta_state: Entity<crate::delta::DeltaState>,
     home_view: Entity<HomeView>,
-    settings_view: Entity<SettingsView>,
-    web_tunnel_manager: Entity<WebTunnelManager>,
+    deferred: Option<DeferredViews>,
     workspaces: Entity<Workspaces>,
     quick_action_drawer: Entity<DrawerHost>,
-    droplet_overlay: Entity<DropletOverlay>,
     _subscriptions: Vec<Subscription>,
 }
 
@@ -89,12 +111,15 @@ fn run_debug_tunnel(
 
 impl ZedraApp {
     pub fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
-        fonts::load_fonts(window);
-
         let mut subscriptions = Vec::new();
 
         let theme_state = cx.new(ThemeState::new);
         ThemeState::register_global(theme_state.downgrade(), cx);
+        crate::settings::init_code_font(cx);
+        crate::settings::init_terminal_cursor(cx);
+        crate::settings::init_data_saver(cx);
+        crate::settings::init_save_cleanup(cx);
+        crate::settings::init_text_size(cx);
 
         // --- Delta client state (shared across settings + workspaces) ---
         let delta_state = cx.new(|_cx| crate::delta::DeltaState::load());
@@ -185,13 +210,6 @@ impl ZedraApp {
         let sub = cx.subscribe(&home_view, Self::on_home_event);
         subscriptions.push(sub);
 
-        let settings_view =
-            cx.new(|cx| SettingsView::new(theme_state.clone(), delta_state.clone(), cx));
-        let sub = cx.subscribe_in(&settings_view, window, Self::on_settings_event);
-        subscriptions.push(sub);
-
-        let web_tunnel_manager = cx.new(|cx| WebTunnelManager::new(workspaces.clone(), cx));
-
         let sub = cx.subscribe(&theme_state, Self::on_theme_changed);
         subscriptions.push(sub);
 
@@ -218,12 +236,6 @@ impl ZedraApp {
             )
         });
 
-        // --- Water droplet effect (iOS-only; elsewhere the overlay would block touches invisibly) ---
-        let droplet_enabled = cfg!(target_os = "ios") && crate::settings::read_droplet_enabled();
-        let droplet_overlay = cx.new(|cx| {
-            DropletOverlay::new(vfx::shared_droplet_state(), droplet_enabled, window, cx)
-        });
-
         let saved_count = workspaces.read(cx).states().len();
         zedra_telemetry::send(Event::AppOpen {
             saved_workspaces: saved_count,
@@ -231,24 +243,85 @@ impl ZedraApp {
             platform: std::env::consts::OS,
             arch: std::env::consts::ARCH,
         });
-        crate::settings_view::reconcile_delta_on_launch(delta_state.clone(), cx);
 
         let app = Self {
             screen: AppScreen::Home,
+            startup_stage: StartupStage::Splash,
+            theme_state,
+            delta_state,
             home_view,
-            settings_view,
-            web_tunnel_manager,
+            deferred: None,
             workspaces,
             quick_action_drawer,
-            droplet_overlay,
             _subscriptions: subscriptions,
         };
         app.record_current_view(cx);
+        startup::mark("root_view");
+
+        app
+    }
+
+    /// Runs on the frame after the splash painted: load fonts, build the
+    /// screens that are not visible at launch, then reveal the home screen.
+    fn finish_startup(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        startup::mark("first_frame");
+        fonts::load_fonts(window);
+
+        let settings_view =
+            cx.new(|cx| SettingsView::new(self.theme_state.clone(), self.delta_state.clone(), cx));
+        let sub = cx.subscribe_in(&settings_view, window, Self::on_settings_event);
+        self._subscriptions.push(sub);
+
+        let workspaces = self.workspaces.clone();
+        let web_tunnel_manager = cx.new(|cx| WebTunnelManager::new(workspaces, cx));
+
+        // --- Water droplet effect (iOS-only; elsewhere the overlay would block touches invisibly) ---
+        let droplet_enabled = cfg!(target_os = "ios") && crate::settings::read_droplet_enabled();
+        let droplet_overlay = cx.new(|cx| {
+            DropletOverlay::new(vfx::shared_droplet_state(), droplet_enabled, window, cx)
+        });
+
+        self.deferred = Some(DeferredViews {
+            settings_view,
+            web_tunnel_manager,
+            droplet_overlay,
+        });
+        crate::settings_view::reconcile_delta_on_launch(self.delta_state.clone(), cx);
 
+        self.auto_connect_on_launch(cx);
         // Start background tasks (deeplink + deferred ticket checks)
-        app.start_background_tasks(window, cx);
+        self.start_background_tasks(window, cx);
 
-        app
+        self.startup_stage = StartupStage::Ready;
+        startup::finish("deferred");
+        cx.notify();
+    }
+
+    /// Queue a connect to the workspace picked as "Open on Launch", unless the
+    /// app was opened by a deeplink. Goes through the same pending nav as
+    /// `zedra://open`, so the connecting screen shows progress and errors.
+    fn auto_connect_on_launch(&self, cx: &mut Context<Self>) {
+        if deeplink::has_pending() {
+            return;
+        }
+        let Some(endpoint_addr) = crate::settings::read_auto_connect_endpoint() else {
+            return;
+        };
+        let saved = self
+            .workspaces
+            .read(cx)
+            .states()
+            .iter()
+            .any(|state| state.read(cx).endpoint_addr == endpoint_addr);
+        if !saved {
+            tracing::warn!("auto-connect: saved workspace is gone, clearing");
+            crate::settings::set_auto_connect_endpoint(None);
+            return;
+        }
+        tracing::info!("auto-connect: opening pinned workspace");
+        self.workspaces.update(cx, |ws, cx| {
+            ws.navigate_workspace_deferred(endpoint_addr, None, cx);
+        });
     }
 
     fn start_background_tasks(&self, window: &mut Window, cx: &mut Context<Self>) {
@@ -274,13 +347,19 @@ impl ZedraApp {
     /// Called periodically to check deeplinks and deferred window-bound work.
     fn tick(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         if let Some(action) = deeplink::take_pending() {
-            self.handle_deeplink_deferred(action, cx);
+            self.handle_deeplink_deferred(action, window, cx);
         }
         self.process_pending_ticket_if_ready(window, cx);
         self.process_pending_workspace_nav_if_ready(window, cx);
+        crate::launcher_shortcuts::sync(&self.workspaces, cx);
     }
 
-    fn handle_deeplink_deferred(&mut self, action: DeeplinkAction, cx: &mut Context<Self>) {
+    fn handle_deeplink_deferred(
+        &mut self,
+        action: DeeplinkAction,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
         match action {
             DeeplinkAction::Connect(ticket) => {
                 tracing::info!("Deeplink: connect action (deferred)");
@@ -297,6 +376,26 @@ impl ZedraApp {
                     ws.navigate_workspace_deferred(endpoint_addr, terminal_id, cx);
                 });
             }
+            DeeplinkAction::OpenSession { session_id } => {
+                let endpoint_addr = self
+                    .workspaces
+                    .read(cx)
+                    .endpoint_addr_for_session(&session_id, cx);
+                match endpoint_addr {
+                    Some(endpoint_addr) => self.workspaces.update(cx, |ws, cx| {
+                        ws.navigate_workspace_deferred(endpoint_addr, None, cx);
+                    }),
+                    None => {
+                        tracing::warn!("Deeplink: no saved workspace for session {}", session_id);
+                        platform_bridge::show_alert(
+                            "Workspace Not Found",
+                            "This shortcut's workspace was removed. Pair with the host again.",
+                            vec![platform_bridge::AlertButton::cancel("OK")],
+                            |_| {},
+                        );
+                    }
+                }
+            }
             #[cfg(debug_assertions)]
             DeeplinkAction::DebugTunnel {
                 url,
@@ -304,6 +403,10 @@ impl ZedraApp {
                 collide,
                 reset,
             } => run_debug_tunnel(&self.workspaces, url, force_alias, collide, reset, cx),
+            #[cfg(debug_assertions)]
+            DeeplinkAction::DebugMockHost => self.workspaces.update(cx, |workspaces, cx| {
+                workspaces.open_mock_workspace(window, cx);
+            }),
         }
     }
 
@@ -332,11 +435,18 @@ impl ZedraApp {
             SettingsEvent::OpenWebTunnel => {
                 self.set_screen(AppScreen::WebTunnel, cx);
             }
+            SettingsEvent::OpenMockHost => {
+                self.workspaces.update(cx, |workspaces, cx| {
+                    workspaces.open_mock_workspace(window, cx);
+                });
+            }
             SettingsEvent::DropletToggled(enabled) => {
                 let enabled = *enabled;
-                self.droplet_overlay.update(cx, |overlay, cx| {
-                    overlay.set_enabled(enabled, window, cx);
-                });
+                if let Some(deferred) = &self.deferred {
+                    deferred.droplet_overlay.update(cx, |overlay, cx| {
+                        overlay.set_enabled(enabled, window, cx);
+                    });
+                }
             }
         }
     }
@@ -352,13 +462,14 @@ impl ZedraApp {
 
     fn on_quick_action_event(
         &mut self,
-        _: Entity<QuickActionPanel>,
+        panel: Entity<QuickActionPanel>,
         event: &QuickActionEvent,
         cx: &mut Context<Self>,
     ) {
         match event {
             QuickActionEvent::Close => {
                 self.quick_action_drawer.update(cx, |h, cx| h.close(cx));
+                panel.update(cx, |panel, cx| panel.clear_filter(cx));
             }
             QuickActionEvent::GoHome => {
                 self.set_screen(AppScreen::Home, cx);
@@ -373,6 +484,19 @@ impl ZedraApp {
             QuickActionEvent::CloseTerminal { tid, ws_index } => {
                 self.close_terminal_from_quick_action(*ws_index, tid, cx);
             }
+            QuickActionEvent::OpenFile { path, ws_index } => {
+                self.set_screen(AppScreen::Workspace, cx);
+                if let Some(workspace) = self
+                    .workspaces
+                    .read(cx)
+                    .workspace_by_index(*ws_index)
+                    .cloned()
+                {
+                    workspace.update(cx, |ws, cx| {
+                        ws.open_file_from_quick_action(path.clone(), cx);
+                    });
+                }
+            }
             QuickActionEvent::OpenWebClient { id, ws_index } => {
                 self.set_screen(AppScreen::Workspace, cx);
                 self.web_client_from_quick_action(*ws_index, id, false, cx);
@@ -589,11 +713,13 @@ impl ZedraApp {
     }
 
     fn update_drawer_content(&mut self, cx: &mut Context<Self>) {
-        let screen_view: AnyView = match self.screen {
-            AppScreen::Home => self.home_view.clone().into(),
-            AppScreen::Settings => self.settings_view.clone().into(),
-            AppScreen::WebTunnel => self.web_tunnel_manager.clone().into(),
-            AppScreen::Workspace => self
+        let screen_view: AnyView = match (self.screen, &self.deferred) {
+            (AppScreen::Settings, Some(deferred)) => deferred.settings_view.clone().into(),
+            (AppScreen::WebTunnel, Some(deferred)) => deferred.web_tunnel_manager.clone().into(),
+            (AppScreen::Home | AppScreen::Settings | AppScreen::WebTunnel, _) => {
+                self.home_view.clone().into()
+            }
+            (AppScreen::Workspace, _) => self
                 .workspaces
                 .read(cx)
                 .active_view()
@@ -616,6 +742,20 @@ impl ZedraApp {
         }
     }
 
+    /// Release caches for an OS memory-pressure signal and log what was reclaimed.
+    pub fn trim_memory(&mut self, pressure: MemoryPressure, cx: &mut Context<Self>) {
+        let reclaim = self
+            .workspaces
+            .update(cx, |ws, cx| ws.trim_memory(pressure, cx));
+        tracing::info!(
+            ?pressure,
+            scrollback_kib = reclaim.scrollback_bytes / 1024,
+            line_cache_kib = reclaim.line_cache_bytes / 1024,
+            "memory: reclaimed ~{} KiB",
+            reclaim.total_bytes() / 1024
+        );
+    }
+
     #[cfg(target_os = "ios")]
     pub(crate) fn close_transports_for_lifecycle(
         &mut self,
@@ -628,17 +768,48 @@ impl ZedraApp {
 }
 
 impl Render for ZedraApp {
-    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        if self.startup_stage != StartupStage::Ready {
+            if self.startup_stage == StartupStage::Splash {
+                // Scheduled from render so it lands after this frame presents.
+                self.startup_stage = StartupStage::Deferred;
+                cx.on_next_frame(window, |this, window, cx| this.finish_startup(window, cx));
+            }
+            return render_splash(cx).into_any_element();
+        }
+
         div()
             .size_full()
             .on_action(cx.listener(Self::handle_system_back_action))
             .on_action(cx.listener(Self::handle_show_connecting))
             .font_family(fonts::MONO_FONT_FAMILY)
             .child(self.quick_action_drawer.clone())
-            .child(self.droplet_overlay.clone())
+            .children(
+                self.deferred
+                    .as_ref()
+                    .map(|deferred| deferred.droplet_overlay.clone()),
+            )
+            .into_any_element()
     }
 }
 
+/// Logo on the app background. Uses no text so it paints before fonts load.
+fn render_splash(cx: &App) -> impl IntoElement {
+    div()
+        .id("startup-splash")
+        .size_full()
+        .flex()
+        .items_center()
+        .justify_center()
+        .bg(rgb(theme::bg_primary(cx)))
+        .child(
+            svg()
+                .path("icons/logo.svg")
+                .size(px(60.0))
+                .text_color(rgb(theme::text_primary(cx))),
+        )
+}
+
 fn should_process_pending_ticket(has_pending_ticket: bool, window_active: bool) -> bool {
     has_pending_ticket && window_active
 }
@@ -666,16 +837,20 @@ pub fn init_platform_app(
         std::sync::Arc::new(http_client::BlockedHttpClient),
     );
     gpui_tokio::init(&mut app_cell.borrow_mut());
+    startup::mark("app");
     app_cell
 }
 
 pub fn open_zedra_window(app: &mut App, window_options: WindowOptions) -> Result<AnyWindowHandle> {
-    app.open_window(window_options, |window, cx| {
-        let view = cx.new(|cx| ZedraApp::new(window, cx));
-        window.refresh();
-        view
-    })
-    .map(|h| h.into())
+    let handle = app
+        .open_window(window_options, |window, cx| {
+            let view = cx.new(|cx| ZedraApp::new(window, cx));
+            window.refresh();
+            view
+        })
+        .map(|h| h.into());
+    startup::mark("window");
+    handle
 }
 
 #[cfg(test)]
@@ -3,3 +3,12 @@ use gpui::Action;
 #[derive(Clone, PartialEq, Action)]
 #[action(namespace = app, no_json)]
 pub struct SystemBack;
+
+/// Insert `text` into the focused text target as one edit: a single replace in
+/// a text input, a single paste write to a terminal. Sent for dropped and
+/// pasted text that would otherwise arrive as one key event per character.
+#[derive(Clone, PartialEq, Action)]
+#[action(namespace = app, no_json)]
+pub struct InsertText {
+    pub text: String,
+}
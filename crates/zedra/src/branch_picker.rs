@@ -0,0 +1,623 @@
+// BranchPicker — bottom sheet for switching git branches.
+//
+// Presented by `GitPanel` through `show_custom_sheet` when the branch row is
+// tapped. Filters `git_branches` locally, offers to create the typed name as a
+// new branch, and re-checks `git_status` before switching: uncommitted tracked
+// changes prompt to stash, discard, or cancel. Untracked files are left alone,
+// matching what `git checkout` itself allows. Long-pressing another branch
+// offers to merge it into the current one or delete it; deleting unmerged
+// work asks again before forcing.
+
+use futures::channel::oneshot;
+use gpui::{prelude::FluentBuilder as _, *};
+use tracing::*;
+
+use zedra_rpc::proto::{GitBranchEntry, GitStatusEntry};
+use zedra_session::SessionHandle;
+
+use crate::platform_bridge::{
+    self, AlertButton, HapticFeedback, NativeNotificationKind, NativeNotificationOptions,
+};
+use crate::theme;
+use crate::ui::InputChanged;
+use crate::ui::input::Input;
+
+#[derive(Clone, Debug)]
+pub enum BranchPickerEvent {
+    /// HEAD moved to another branch; the git panel should refresh.
+    Switched,
+    /// Another branch was merged into HEAD; the git panel should refresh.
+    Merged,
+}
+
+impl EventEmitter<BranchPickerEvent> for BranchPicker {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum SwitchTarget {
+    Existing(String),
+    Create(String),
+}
+
+impl SwitchTarget {
+    fn name(&self) -> &str {
+        match self {
+            SwitchTarget::Existing(name) | SwitchTarget::Create(name) => name,
+        }
+    }
+}
+
+/// Answer to the uncommitted-changes prompt. Indices match the alert buttons.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DirtyChoice {
+    Stash,
+    Discard,
+    Cancel,
+}
+
+impl DirtyChoice {
+    fn from_index(index: usize) -> Self {
+        match index {
+            0 => DirtyChoice::Stash,
+            1 => DirtyChoice::Discard,
+            _ => DirtyChoice::Cancel,
+        }
+    }
+}
+
+/// Long-press actions on a branch other than HEAD.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum BranchAction {
+    Merge,
+    Delete,
+}
+
+pub struct BranchPicker {
+    session_handle: SessionHandle,
+    search_input: Entity<Input>,
+    query: String,
+    branches: Vec<GitBranchEntry>,
+    loading: bool,
+    /// A switch, merge, or delete is in flight; rows ignore taps until it
+    /// settles. `busy_label` says which.
+    switching: bool,
+    busy_label: &'static str,
+    error: Option<String>,
+    _subscription: Subscription,
+}
+
+impl BranchPicker {
+    pub fn new(session_handle: SessionHandle, cx: &mut Context<Self>) -> Self {
+        let search_input = cx.new(|cx| {
+            Input::new(cx)
+                .compact(true)
+                .placeholder("Search or create branch")
+                .hide_keyboard_on_submit(true)
+        });
+        let subscription = cx.subscribe(
+            &search_input,
+            |this: &mut Self, _input, event: &InputChanged, cx| {
+                this.query = event.value.clone();
+                cx.notify();
+            },
+        );
+
+        Self {
+            session_handle,
+            search_input,
+            query: String::new(),
+            branches: Vec::new(),
+            loading: false,
+            switching: false,
+            busy_label: "Switching…",
+            error: None,
+            _subscription: subscription,
+        }
+    }
+
+    /// Reset the filter and reload branches for a fresh presentation.
+    pub fn open(&mut self, cx: &mut Context<Self>) {
+        self.query.clear();
+        self.search_input
+            .update(cx, |input, _cx| input.set_value(""));
+        self.error = None;
+        self.switching = false;
+        self.load_branches(cx);
+    }
+
+    fn load_branches(&mut self, cx: &mut Context<Self>) {
+        self.loading = true;
+        cx.notify();
+
+        let handle = self.session_handle.clone();
+        cx.spawn(async move |this, cx| {
+            let result = handle.git_branches().await;
+            let _ = this.update(cx, |this, cx| {
+                this.loading = false;
+                match result {
+                    Ok(branches) => this.branches = branches,
+                    Err(e) => {
+                        error!("git_branches failed: {}", e);
+                        this.error = Some(e.to_string());
+                    }
+                }
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    fn switch_to(&mut self, target: SwitchTarget, cx: &mut Context<Self>) {
+        if self.switching {
+            return;
+        }
+        platform_bridge::trigger_haptic(HapticFeedback::ImpactLight);
+        self.switching = true;
+        self.busy_label = "Switching…";
+        self.error = None;
+        cx.notify();
+
+        let handle = self.session_handle.clone();
+        cx.spawn(async move |this, cx| {
+            let result = switch_branch(&handle, &target).await;
+            let _ = this.update(cx, |this, cx| {
+                this.switching = false;
+                match result {
+                    Ok(true) => {
+                        info!("switched to branch {}", target.name());
+                        platform_bridge::dismiss_custom_sheet();
+                        cx.emit(BranchPickerEvent::Switched);
+                    }
+                    Ok(false) => {}
+                    Err(e) => {
+                        error!("branch switch to {} failed: {}", target.name(), e);
+                        this.error = Some(e.to_string());
+                    }
+                }
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    fn show_branch_actions(&mut self, name: String, cx: &mut Context<Self>) {
+        if self.switching {
+            return;
+        }
+        platform_bridge::trigger_haptic(HapticFeedback::ImpactMedium);
+        let head = self
+            .branches
+            .iter()
+            .find(|branch| branch.is_head)
+            .map(|branch| branch.name.clone());
+        let mut actions = Vec::new();
+        let mut buttons = Vec::new();
+        if let Some(head) = &head {
+            actions.push(BranchAction::Merge);
+            buttons.push(AlertButton::default(format!("Merge into {head}")));
+        }
+        actions.push(BranchAction::Delete);
+        buttons.push(AlertButton::destructive("Delete branch"));
+        buttons.push(AlertButton::cancel("Cancel"));
+
+        let (tx, rx) = oneshot::channel();
+        platform_bridge::show_selection(&name, "", buttons, move |index| {
+            let _ = tx.send(index.and_then(|index| actions.get(index).copied()));
+        });
+        cx.spawn(async move |this, cx| {
+            let Ok(Some(action)) = rx.await else {
+                return;
+            };
+            let _ = this.update(cx, |this, cx| match action {
+                BranchAction::Merge => this.merge(name, head.unwrap_or_default(), cx),
+                BranchAction::Delete => this.delete(name, cx),
+            });
+        })
+        .detach();
+    }
+
+    fn merge(&mut self, branch: String, head: String, cx: &mut Context<Self>) {
+        self.switching = true;
+        self.busy_label = "Merging…";
+        self.error = None;
+        cx.notify();
+
+        let handle = self.session_handle.clone();
+        cx.spawn(async move |this, cx| {
+            let result = handle.git_merge(&branch).await;
+            let _ = this.update(cx, |this, cx| {
+                this.switching = false;
+                match result {
+                    Ok(merged) if !merged.conflicts.is_empty() => {
+                        platform_bridge::show_alert(
+                            "Merge conflicts",
+                            &format!(
+                                "Merging {branch} into {head} conflicts in {}. The merge was undone.",
+                                merged.conflicts.join(", ")
+                            ),
+                            vec![AlertButton::default("OK")],
+                            |_| {},
+                        );
+                    }
+                    Ok(merged) => match merged.error {
+                        Some(e) => {
+                            error!("merge of {} failed: {}", branch, e);
+                            this.error = Some(e);
+                        }
+                        None => {
+                            let message = if !merged.updated {
+                                format!("{head} already contains {branch}")
+                            } else if merged.fast_forward {
+                                format!("Fast-forwarded {head} to {branch}")
+                            } else {
+                                format!("Merged {branch} into {head}")
+                            };
+                            platform_bridge::show_native_notification(
+                                NativeNotificationOptions::new(message)
+                                    .kind(NativeNotificationKind::Success),
+                            );
+                            platform_bridge::dismiss_custom_sheet();
+                            cx.emit(BranchPickerEvent::Merged);
+                        }
+                    },
+                    Err(e) => {
+                        error!("merge of {} failed: {}", branch, e);
+                        this.error = Some(e.to_string());
+                    }
+                }
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    fn delete(&mut self, name: String, cx: &mut Context<Self>) {
+        self.switching = true;
+        self.busy_label = "Deleting…";
+        self.error = None;
+        cx.notify();
+
+        let handle = self.session_handle.clone();
+        cx.spawn(async move |this, cx| {
+            let result: anyhow::Result<bool> = async {
+                let deleted = handle.git_branch_delete(&name, false).await?;
+                if deleted.unmerged {
+                    let (tx, rx) = oneshot::channel();
+                    platform_bridge::show_alert(
+                        "Branch not merged",
+                        &format!("{name} has commits that are not merged. Delete it anyway?"),
+                        vec![
+                            AlertButton::destructive("Delete"),
+                            AlertButton::cancel("Cancel"),
+                        ],
+                        move |index| {
+                            let _ = tx.send(index);
+                        },
+                    );
+                    if !matches!(rx.await, Ok(0)) {
+                        return Ok(false);
+                    }
+                    let forced = handle.git_branch_delete(&name, true).await?;
+                    if let Some(e) = forced.error {
+                        anyhow::bail!(e);
+                    }
+                } else if let Some(e) = deleted.error {
+                    anyhow::bail!(e);
+                }
+                Ok(true)
+            }
+            .await;
+            let _ = this.update(cx, |this, cx| {
+                this.switching = false;
+                match result {
+                    Ok(true) => {
+                        info!("deleted branch {}", name);
+                        this.load_branches(cx);
+                    }
+                    Ok(false) => {}
+                    Err(e) => {
+                        error!("delete of branch {} failed: {}", name, e);
+                        this.error = Some(e.to_string());
+                    }
+                }
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    fn render_row(
+        &self,
+        id: ElementId,
+        icon: &'static str,
+        label: String,
+        is_head: bool,
+        target: Option<SwitchTarget>,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let enabled = target.is_some() && !self.switching;
+        let actions_for = match &target {
+            Some(SwitchTarget::Existing(name)) if enabled => Some(name.clone()),
+            _ => None,
+        };
+        div()
+            .id(id)
+            .w_full()
+            .h(px(theme::PANEL_ITEM_HEIGHT + theme::SPACING_SM))
+            .px(px(theme::SPACING_MD))
+            .flex()
+            .flex_row()
+            .items_center()
+            .gap(px(theme::SPACING_SM))
+            .when(enabled, |row| {
+                row.cursor_pointer()
+                    .on_press(cx.listener(move |this, _event, window, cx| {
+                        if let Some(target) = target.clone() {
+                            window.hide_soft_keyboard();
+                            this.switch_to(target, cx);
+                        }
+                    }))
+            })
+            .when_some(actions_for, |row, name| {
+                row.on_long_press(cx.listener(move |this, _event, window, cx| {
+                    window.hide_soft_keyboard();
+                    this.show_branch_actions(name.clone(), cx);
+                }))
+            })
+            .child(
+                svg()
+                    .path(icon)
+                    .size(px(theme::ICON_XS))
+                    .text_color(rgb(theme::text_muted(cx))),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .min_w_0()
+                    .truncate()
+                    .text_size(theme::scaled(theme::FONT_BODY))
+                    .text_color(rgb(if is_head {
+                        theme::text_primary(cx)
+                    } else {
+                        theme::text_secondary(cx)
+                    }))
+                    .child(label),
+            )
+            .when(is_head, |row| {
+                row.child(
+                    svg()
+                        .path("icons/check.svg")
+                        .size(px(theme::ICON_XS))
+                        .text_color(rgb(theme::accent_green(cx))),
+                )
+            })
+    }
+
+    fn render_message(&self, message: String, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .px(px(theme::SPACING_MD))
+            .py(px(theme::SPACING_SM))
+            .text_size(theme::scaled(theme::FONT_DETAIL))
+            .text_color(rgb(theme::text_muted(cx)))
+            .child(message)
+    }
+}
+
+impl Render for BranchPicker {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let query = self.query.trim().to_string();
+        let create = create_candidate(&self.branches, &query).map(|name| {
+            self.render_row(
+                "branch-picker-create".into(),
+                "icons/plus.svg",
+                format!("Create branch \"{name}\""),
+                false,
+                Some(SwitchTarget::Create(name.to_string())),
+                cx,
+            )
+        });
+        let rows: Vec<AnyElement> = filter_branches(&self.branches, &query)
+            .into_iter()
+            .enumerate()
+            .map(|(index, branch)| {
+                let target = (!branch.is_head).then(|| SwitchTarget::Existing(branch.name.clone()));
+                self.render_row(
+                    ("branch-picker-row", index).into(),
+                    "icons/git-branch.svg",
+                    branch.name.clone(),
+                    branch.is_head,
+                    target,
+                    cx,
+                )
+                .into_any_element()
+            })
+            .collect();
+        let message = if self.loading {
+            Some("Loading branches…".to_string())
+        } else if self.switching {
+            Some(self.busy_label.to_string())
+        } else if rows.is_empty() && create.is_none() {
+            Some("No matching branches".to_string())
+        } else {
+            None
+        };
+        let error = self.error.clone();
+        let bottom_inset = platform_bridge::home_indicator_inset();
+
+        div()
+            .id("branch-picker")
+            .size_full()
+            .flex()
+            .flex_col()
+            .bg(rgb(theme::bg_primary(cx)))
+            .on_pointer_down(|_, window, _cx| {
+                window.hide_soft_keyboard();
+            })
+            .child(
+                div()
+                    .px(px(theme::SPACING_MD))
+                    .pt(px(theme::SPACING_LG))
+                    .pb(px(theme::SPACING_SM))
+                    .text_size(theme::scaled(theme::FONT_HEADING))
+                    .font_weight(FontWeight::MEDIUM)
+                    .text_color(rgb(theme::text_primary(cx)))
+                    .child("Switch branch"),
+            )
+            .child(
+                div()
+                    .px(px(theme::SPACING_MD))
+                    .pb(px(theme::SPACING_SM))
+                    .child(self.search_input.clone()),
+            )
+            .when_some(error, |el, error| {
+                el.child(
+                    div()
+                        .px(px(theme::SPACING_MD))
+                        .pb(px(theme::SPACING_SM))
+                        .text_size(theme::scaled(theme::FONT_DETAIL))
+                        .text_color(rgb(theme::accent_red(cx)))
+                        .child(error),
+                )
+            })
+            .child(
+                div()
+                    .id("branch-picker-list")
+                    .flex_1()
+                    .min_h_0()
+                    .overflow_y_scroll()
+                    .pb(px(bottom_inset))
+                    .when_some(message, |el, message| {
+                        el.child(self.render_message(message, cx))
+                    })
+                    .children(create)
+                    .children(rows),
+            )
+    }
+}
+
+/// Check for local changes, resolve them, then check out or create `target`.
+/// Returns `Ok(false)` when the user cancelled.
+async fn switch_branch(handle: &SessionHandle, target: &SwitchTarget) -> anyhow::Result<bool> {
+    let status = handle.git_status().await?;
+    if has_uncommitted_changes(&status.entries) {
+        match ask_dirty_choice(target.name()).await {
+            DirtyChoice::Stash => {
+                let message = format!("zedra: before switching to {}", target.name());
+                handle.git_stash(Some(&message), false).await?;
+            }
+            DirtyChoice::Discard => handle.git_discard(false).await?,
+            DirtyChoice::Cancel => return Ok(false),
+        }
+    }
+    match target {
+        SwitchTarget::Existing(name) => handle.git_checkout(name).await?,
+        SwitchTarget::Create(name) => handle.git_branch_create(name, None, true).await?,
+    }
+    Ok(true)
+}
+
+async fn ask_dirty_choice(branch: &str) -> DirtyChoice {
+    let (tx, rx) = oneshot::channel();
+    platform_bridge::show_alert(
+        "Uncommitted changes",
+        &format!("Stash or discard your changes before switching to {branch}?"),
+        vec![
+            AlertButton::default("Stash and switch"),
+            AlertButton::destructive("Discard and switch"),
+            AlertButton::cancel("Cancel"),
+        ],
+        move |index| {
+            let _ = tx.send(DirtyChoice::from_index(index));
+        },
+    );
+    rx.await.unwrap_or(DirtyChoice::Cancel)
+}
+
+/// Staged or unstaged edits to tracked files. Untracked files do not block a
+/// checkout unless they collide, and git reports that case itself.
+fn has_uncommitted_changes(entries: &[GitStatusEntry]) -> bool {
+    entries.iter().any(|entry| {
+        entry.staged_status.is_some()
+            || entry
+                .unstaged_status
+                .as_deref()
+                .is_some_and(|status| status != "untracked")
+    })
+}
+
+/// Case-insensitive substring filter; the checked-out branch stays first.
+fn filter_branches<'a>(branches: &'a [GitBranchEntry], query: &str) -> Vec<&'a GitBranchEntry> {
+    let query = query.to_lowercase();
+    let mut matches: Vec<&GitBranchEntry> = branches
+        .iter()
+        .filter(|branch| branch.name.to_lowercase().contains(&query))
+        .collect();
+    matches.sort_by_key(|branch| !branch.is_head);
+    matches
+}
+
+/// The query as a new branch name, unless it already exists or git would reject it.
+fn create_candidate<'a>(branches: &[GitBranchEntry], query: &'a str) -> Option<&'a str> {
+    let valid = !query.is_empty()
+        && !query.starts_with('-')
+        && query
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '/' | '_' | '.' | '-'));
+    (valid && !branches.iter().any(|branch| branch.name == query)).then_some(query)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn branch(name: &str, is_head: bool) -> GitBranchEntry {
+        GitBranchEntry {
+            name: name.to_string(),
+            is_head,
+        }
+    }
+
+    fn status(staged: Option<&str>, unstaged: Option<&str>) -> GitStatusEntry {
+        GitStatusEntry {
+            path: "src/lib.rs".to_string(),
+            staged_status: staged.map(str::to_string),
+            unstaged_status: unstaged.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn filter_keeps_head_first_and_matches_case_insensitively() {
+        let branches = vec![
+            branch("feature/Login", false),
+            branch("main", true),
+            branch("fix/login-crash", false),
+        ];
+        let names: Vec<&str> = filter_branches(&branches, "")
+            .into_iter()
+            .map(|b| b.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["main", "feature/Login", "fix/login-crash"]);
+        let names: Vec<&str> = filter_branches(&branches, "LOGIN")
+            .into_iter()
+            .map(|b| b.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["feature/Login", "fix/login-crash"]);
+    }
+
+    #[test]
+    fn create_candidate_skips_existing_and_invalid_names() {
+        let branches = vec![branch("main", true)];
+        assert_eq!(create_candidate(&branches, "feature/x"), Some("feature/x"));
+        assert_eq!(create_candidate(&branches, "main"), None);
+        assert_eq!(create_candidate(&branches, ""), None);
+        assert_eq!(create_candidate(&branches, "-f"), None);
+        assert_eq!(create_candidate(&branches, "has space"), None);
+    }
+
+    #[test]
+    fn untracked_files_alone_do_not_block_checkout() {
+        assert!(!has_uncommitted_changes(&[]));
+        assert!(!has_uncommitted_changes(&[status(None, Some("untracked"))]));
+        assert!(has_uncommitted_changes(&[status(None, Some("modified"))]));
+        assert!(has_uncommitted_changes(&[status(Some("added"), None)]));
+    }
+}
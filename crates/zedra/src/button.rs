@@ -20,7 +20,7 @@ pub fn outline_button(cx: &App, id: impl Into<ElementId>, label: &str) -> Statef
         .border_color(rgb(theme::border_default(cx)))
         .cursor_pointer()
         .text_color(rgb(theme::text_primary(cx)))
-        .text_size(px(theme::FONT_BODY))
+        .text_size(theme::scaled(theme::FONT_BODY))
         .font_weight(FontWeight::MEDIUM)
         .child(label.to_string())
 }
@@ -0,0 +1,287 @@
+// Command guard: ask before running destructive commands on production hosts.
+//
+// Lives on `WorkspaceState::command_guard`, so marking a host as production
+// persists with its saved workspace and applies to every terminal on it. The
+// terminal holds Enter back when the pending command line matches a pattern
+// and emits `TerminalEvent::CommandGuarded`; `confirm` asks before the Enter
+// reaches the PTY.
+//
+// The editor follows `terminal_triggers`: a chain of native dialogs, each step
+// handed back through `PendingWorkspaceAction::EditCommandGuard` so every
+// dialog is presented from the GPUI thread.
+
+use gpui::*;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use zedra_terminal::{GuardRule, GuardedCommand};
+
+use crate::pending::SharedPendingSlot;
+use crate::platform_bridge::{self, AlertButton, ListPickerItem};
+use crate::workspace::PendingWorkspaceAction;
+use crate::workspace_state::WorkspaceState;
+
+/// Guarded until the user edits the list: recursive deletes of `/` or home,
+/// writes to raw disks, filesystem formatting, force-pushes to the main
+/// branch, and dropped databases.
+const DEFAULT_PATTERNS: &[(&str, &str)] = &[
+    ("rm -rf /", r"\brm\s+(-\S+\s+)*(/|/\*|~/?|\$HOME/?)(\s|$)"),
+    (
+        "dd to a disk",
+        r"\bdd\b.*\bof=/dev/(sd|hd|vd|xvd|nvme|mmcblk|disk|rdisk)",
+    ),
+    ("mkfs", r"\bmkfs(\.\w+)?\s"),
+    (
+        "force-push to main",
+        r"\bgit\s+push\b(.*\s(-f|--force\S*)\b.*\b(main|master)\b|.*\b(main|master)\b.*\s(-f|--force\S*)\b|.*\s\+(main|master)\b)",
+    ),
+    ("DROP DATABASE", r"(?i)\bdrop\s+(database|schema)\b"),
+];
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommandGuardConfig {
+    /// Commands on this host need a confirmation tap.
+    #[serde(default)]
+    pub production: bool,
+    /// Regexes checked against the command line; `None` until the user edits
+    /// them, meaning the built-in list.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub patterns: Option<Vec<String>>,
+}
+
+impl CommandGuardConfig {
+    /// The patterns in effect, defaults included.
+    pub fn patterns(&self) -> Vec<String> {
+        match &self.patterns {
+            Some(patterns) => patterns.clone(),
+            None => DEFAULT_PATTERNS
+                .iter()
+                .map(|(_, pattern)| pattern.to_string())
+                .collect(),
+        }
+    }
+}
+
+/// Label shown for a pattern: the built-in name, or the regex itself.
+fn pattern_label(pattern: &str) -> &str {
+    DEFAULT_PATTERNS
+        .iter()
+        .find(|(_, default)| *default == pattern)
+        .map_or(pattern, |(name, _)| name)
+}
+
+/// Compile the host's guard for a terminal; empty unless the host is marked
+/// production. Invalid patterns are skipped; the editor rejects them, so they
+/// only come from a hand-edited store.
+pub fn compile(config: &CommandGuardConfig) -> Vec<GuardRule> {
+    if !config.production {
+        return Vec::new();
+    }
+    config
+        .patterns()
+        .iter()
+        .filter_map(|pattern| {
+            GuardRule::new(pattern_label(pattern), pattern)
+                .map_err(|err| warn!(%pattern, %err, "invalid command guard pattern"))
+                .ok()
+        })
+        .collect()
+}
+
+/// Ask whether to run a held-back command. Resolves to true on "Run".
+pub async fn confirm(guarded: &GuardedCommand, host_name: &str) -> bool {
+    let (choice_tx, choice_rx) = tokio::sync::oneshot::channel();
+    platform_bridge::show_alert(
+        &format!("Run on {host_name}?"),
+        &format!(
+            "{}\n\nThis is a production host and the command matches \"{}\".",
+            guarded.command, guarded.rule
+        ),
+        vec![
+            AlertButton::destructive("Run"),
+            AlertButton::cancel("Cancel"),
+        ],
+        move |index| {
+            let _ = choice_tx.send(index);
+        },
+    );
+    choice_rx.await == Ok(0)
+}
+
+pub(crate) enum GuardEditStep {
+    List,
+    ToggleProduction,
+    AddPattern,
+    Save { pattern: String },
+    ConfirmRemove { index: usize },
+    Remove { index: usize },
+}
+
+/// Present one step of the command guard editor.
+pub(crate) fn run_edit_step(
+    step: GuardEditStep,
+    workspace_state: &Entity<WorkspaceState>,
+    pending: &SharedPendingSlot<PendingWorkspaceAction>,
+    cx: &mut App,
+) {
+    let pending = pending.clone();
+    let next =
+        move |step: GuardEditStep| pending.set(PendingWorkspaceAction::EditCommandGuard(step));
+    match step {
+        GuardEditStep::List => {
+            let config = workspace_state.read(cx).command_guard.clone();
+            let patterns = config.patterns();
+            let count = patterns.len();
+            let mut items = vec![ListPickerItem {
+                label: if config.production {
+                    "Production Host: On".into()
+                } else {
+                    "Production Host: Off".into()
+                },
+                subtitle: Some("Confirm matching commands before they run".into()),
+                image_name: None,
+                trailing_icon: None,
+            }];
+            items.extend(patterns.iter().map(|pattern| {
+                let label = pattern_label(pattern);
+                ListPickerItem {
+                    label: label.to_string(),
+                    subtitle: (label != pattern.as_str()).then(|| pattern.clone()),
+                    image_name: None,
+                    trailing_icon: None,
+                }
+            }));
+            items.push(ListPickerItem {
+                label: "Add Pattern".into(),
+                subtitle: None,
+                image_name: None,
+                trailing_icon: None,
+            });
+            platform_bridge::show_list_picker(
+                "Command Guard",
+                "On a production host, commands matching a pattern wait for a confirmation tap. Tap a pattern to remove it.",
+                items,
+                move |selection| {
+                    let Some(selection) = selection else { return };
+                    match selection.index {
+                        0 => next(GuardEditStep::ToggleProduction),
+                        index if index == count + 1 => next(GuardEditStep::AddPattern),
+                        index => next(GuardEditStep::ConfirmRemove { index: index - 1 }),
+                    }
+                },
+            );
+        }
+        GuardEditStep::ToggleProduction => {
+            workspace_state.update(cx, |state, cx| {
+                let mut config = state.command_guard.clone();
+                config.production = !config.production;
+                state.set_command_guard(config, cx);
+            });
+            next(GuardEditStep::List);
+        }
+        GuardEditStep::AddPattern => {
+            platform_bridge::show_text_input(
+                "New Pattern",
+                "Regex, e.g. terraform destroy",
+                "",
+                move |result| {
+                    let Some(pattern) = result.filter(|p| !p.trim().is_empty()) else {
+                        return;
+                    };
+                    next(GuardEditStep::Save { pattern });
+                },
+            );
+        }
+        GuardEditStep::Save { pattern } => {
+            if let Err(err) = GuardRule::new("", &pattern) {
+                platform_bridge::show_alert(
+                    "Invalid pattern",
+                    &err.to_string(),
+                    vec![AlertButton::cancel("OK")],
+                    |_| {},
+                );
+                return;
+            }
+            workspace_state.update(cx, |state, cx| {
+                let mut config = state.command_guard.clone();
+                let mut patterns = config.patterns();
+                patterns.push(pattern);
+                config.patterns = Some(patterns);
+                state.set_command_guard(config, cx);
+            });
+        }
+        GuardEditStep::ConfirmRemove { index } => {
+            let Some(pattern) = workspace_state
+                .read(cx)
+                .command_guard
+                .patterns()
+                .get(index)
+                .cloned()
+            else {
+                return;
+            };
+            platform_bridge::show_alert(
+                "Remove pattern?",
+                pattern_label(&pattern),
+                vec![
+                    AlertButton::destructive("Remove"),
+                    AlertButton::cancel("Cancel"),
+                ],
+                move |button_index| {
+                    if button_index == 0 {
+                        next(GuardEditStep::Remove { index });
+                    }
+                },
+            );
+        }
+        GuardEditStep::Remove { index } => {
+            workspace_state.update(cx, |state, cx| {
+                let mut config = state.command_guard.clone();
+                let mut patterns = config.patterns();
+                if index < patterns.len() {
+                    patterns.remove(index);
+                    config.patterns = Some(patterns);
+                    state.set_command_guard(config, cx);
+                }
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn guards(command: &str) -> bool {
+        let rules = compile(&CommandGuardConfig {
+            production: true,
+            patterns: None,
+        });
+        rules.iter().any(|rule| rule.pattern.is_match(command))
+    }
+
+    #[test]
+    fn default_patterns_catch_destructive_commands_only() {
+        assert!(guards("rm -rf /"));
+        assert!(guards("sudo rm -rf --no-preserve-root /"));
+        assert!(guards("rm -fr ~/"));
+        assert!(guards("dd if=image.iso of=/dev/sda bs=4M"));
+        assert!(guards("git push --force origin main"));
+        assert!(guards("git push origin +master"));
+        assert!(guards("psql -c 'DROP DATABASE app'"));
+
+        assert!(!guards("rm -rf ./build"));
+        assert!(!guards("rm -rf /tmp/cache"));
+        assert!(!guards("dd if=/dev/zero of=disk.img"));
+        assert!(!guards("git push --force origin feature"));
+        assert!(!guards("git push origin main"));
+
+        assert!(compile(&CommandGuardConfig::default()).is_empty());
+        let custom = CommandGuardConfig {
+            production: true,
+            patterns: Some(vec!["terraform destroy".into(), "(unclosed".into()]),
+        };
+        let rules = compile(&custom);
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].name, "terraform destroy");
+    }
+}
@@ -0,0 +1,209 @@
+use gpui::*;
+use zedra_session::SessionHandle;
+
+use crate::editor::git_diff_view::{FileDiff, GitDiffView, parse_unified_diff};
+use crate::placeholder::render_placeholder;
+use crate::platform_bridge::{CustomSheetDetent, CustomSheetOptions};
+use crate::theme;
+
+/// The commit and file a `CommitDetailSheet` shows.
+#[derive(Clone, Debug, Default)]
+pub struct CommitRef {
+    pub id: String,
+    pub summary: String,
+    pub author: String,
+    pub timestamp: i64,
+    /// Repository-root-relative path of the file in that commit.
+    pub path: String,
+    /// The file's path before the commit, when the commit renamed it.
+    pub old_path: Option<String>,
+}
+
+impl CommitRef {
+    /// Paths to limit the commit's diff to. A rename needs both names so git
+    /// reports it as a rename rather than an addition.
+    pub fn diff_paths(&self) -> Vec<String> {
+        std::iter::once(self.path.clone())
+            .chain(self.old_path.clone())
+            .collect()
+    }
+
+    fn meta(&self) -> String {
+        let date = chrono::DateTime::from_timestamp(self.timestamp, 0)
+            .map(|at| at.format("%Y-%m-%d").to_string())
+            .unwrap_or_default();
+        format!("{} · {} · {date}", short_id(&self.id), self.author)
+    }
+}
+
+pub fn short_id(id: &str) -> &str {
+    &id[..id.len().min(7)]
+}
+
+/// Options shared by the sheets that show a file at or in a commit.
+pub fn commit_sheet_options() -> CustomSheetOptions {
+    CustomSheetOptions {
+        detents: vec![CustomSheetDetent::Large],
+        initial_detent: CustomSheetDetent::Large,
+        shows_grabber: true,
+        expands_on_scroll_edge: true,
+        edge_attached_in_compact_height: false,
+        width_follows_preferred_content_size_when_edge_attached: false,
+        corner_radius: None,
+        modal_in_presentation: false,
+    }
+}
+
+#[derive(Clone, Debug)]
+enum DiffState {
+    Loading,
+    Loaded,
+    Empty,
+    Error(String),
+}
+
+/// Sheet with a commit's summary and the diff it made to one file.
+pub struct CommitDetailSheet {
+    session_handle: SessionHandle,
+    diff_view: Entity<GitDiffView>,
+    commit: CommitRef,
+    state: DiffState,
+    load_task: Option<Task<()>>,
+}
+
+impl CommitDetailSheet {
+    pub fn new(session_handle: SessionHandle, cx: &mut Context<Self>) -> Self {
+        Self {
+            session_handle,
+            diff_view: cx.new(|cx| GitDiffView::new(cx)),
+            commit: CommitRef::default(),
+            state: DiffState::Loading,
+            load_task: None,
+        }
+    }
+
+    pub fn open(&mut self, commit: CommitRef, cx: &mut Context<Self>) {
+        self.commit = commit.clone();
+        self.state = DiffState::Loading;
+        cx.notify();
+
+        let handle = self.session_handle.clone();
+        self.load_task = Some(cx.spawn(async move |this, cx| {
+            let result = handle
+                .git_show_commit(&commit.id, commit.diff_paths())
+                .await;
+            let _ = this.update(cx, |this, cx| {
+                if this.commit.id != commit.id || this.commit.path != commit.path {
+                    return;
+                }
+                let filename = commit
+                    .path
+                    .rsplit('/')
+                    .next()
+                    .unwrap_or(&commit.path)
+                    .to_string();
+                match result {
+                    Ok(text) => {
+                        let diff = parse_unified_diff(&text).into_iter().find(|diff| {
+                            diff.new_path == commit.path || diff.old_path == commit.path
+                        });
+                        this.state = if diff.is_some() {
+                            DiffState::Loaded
+                        } else {
+                            DiffState::Empty
+                        };
+                        let diff = diff.unwrap_or_else(|| FileDiff {
+                            old_path: commit.path.clone(),
+                            new_path: commit.path.clone(),
+                            hunks: Vec::new(),
+                        });
+                        this.diff_view.update(cx, |view, cx| {
+                            view.set_diff(filename, diff, cx);
+                        });
+                    }
+                    Err(e) => {
+                        tracing::warn!("commit detail: diff failed for {}: {}", commit.id, e);
+                        this.state = DiffState::Error(e.to_string());
+                    }
+                }
+                cx.notify();
+            });
+        }));
+    }
+}
+
+impl Render for CommitDetailSheet {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let body: AnyElement = match &self.state {
+            DiffState::Loading => render_placeholder(cx, "Loading ...").into_any_element(),
+            DiffState::Empty => render_placeholder(cx, "No content changes (rename or mode only)")
+                .into_any_element(),
+            DiffState::Error(error) => {
+                render_placeholder(cx, format!("Error: {}", error)).into_any_element()
+            }
+            DiffState::Loaded => div()
+                .size_full()
+                .child(self.diff_view.clone())
+                .into_any_element(),
+        };
+        let path = match &self.commit.old_path {
+            Some(old_path) => format!("{old_path} → {}", self.commit.path),
+            None => self.commit.path.clone(),
+        };
+        div()
+            .size_full()
+            .flex()
+            .flex_col()
+            .bg(rgb(theme::bg_primary(cx)))
+            .child(
+                div()
+                    .px(px(theme::SPACING_MD))
+                    .pt(px(theme::SPACING_LG))
+                    .pb(px(theme::SPACING_SM))
+                    .flex()
+                    .flex_col()
+                    .child(
+                        div()
+                            .truncate()
+                            .text_size(theme::scaled(theme::FONT_HEADING))
+                            .font_weight(FontWeight::MEDIUM)
+                            .text_color(rgb(theme::text_primary(cx)))
+                            .child(self.commit.summary.clone()),
+                    )
+                    .child(
+                        div()
+                            .truncate()
+                            .text_size(theme::scaled(theme::FONT_DETAIL))
+                            .text_color(rgb(theme::text_muted(cx)))
+                            .child(self.commit.meta()),
+                    )
+                    .child(
+                        div()
+                            .truncate()
+                            .text_size(theme::scaled(theme::FONT_DETAIL))
+                            .text_color(rgb(theme::text_secondary(cx)))
+                            .child(path),
+                    ),
+            )
+            .child(div().flex_1().min_h_0().child(body))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renamed_commits_diff_both_paths() {
+        let mut commit = CommitRef {
+            id: "0123456789abcdef".into(),
+            path: "src/new.rs".into(),
+            ..Default::default()
+        };
+        assert_eq!(commit.diff_paths(), vec!["src/new.rs"]);
+        commit.old_path = Some("src/old.rs".into());
+        assert_eq!(commit.diff_paths(), vec!["src/new.rs", "src/old.rs"]);
+        assert_eq!(short_id(&commit.id), "0123456");
+        assert_eq!(short_id("abc"), "abc");
+    }
+}
@@ -10,6 +10,8 @@
 ///   - `open?endpoint_addr=<addr>&terminal_id=<id>` — navigate to a workspace, connecting
 ///     first if needed. `terminal_id` is optional; when present, navigate to that terminal
 ///     once the workspace is synced.
+///   - `session/<session_id>` — the same for the saved workspace with that session id,
+///     for launcher shortcuts that only know the session.
 use anyhow::{Result, anyhow};
 
 use crate::pending::PendingSlot;
@@ -23,6 +25,8 @@ pub enum DeeplinkAction {
         endpoint_addr: String,
         terminal_id: Option<String>,
     },
+    /// Navigate to the saved workspace for a host session, connecting first if needed.
+    OpenSession { session_id: String },
     /// Debug devtool: drive the web tunnel on the active workspace directly, to
     /// reproduce adapter cases. `zedra://devtool/tunnel?url=<url>&mode=alias&collide=<port>&reset=1`.
     #[cfg(debug_assertions)]
@@ -32,6 +36,10 @@ pub enum DeeplinkAction {
         collide: Option<u16>,
         reset: bool,
     },
+    /// Debug devtool: open a workspace on the in-process mock host, for UI
+    /// tests on emulators without a daemon. `zedra://devtool/mock`.
+    #[cfg(debug_assertions)]
+    DebugMockHost,
 }
 
 static PENDING_DEEPLINK: PendingSlot<DeeplinkAction> = PendingSlot::new();
@@ -68,6 +76,15 @@ pub fn parse(url: &str) -> Result<DeeplinkAction> {
                 terminal_id,
             })
         }
+        _ if path.starts_with("session/") => {
+            let session_id = &path["session/".len()..];
+            if session_id.is_empty() || session_id.contains('/') {
+                return Err(anyhow!("session: expected zedra://session/<id>"));
+            }
+            Ok(DeeplinkAction::OpenSession {
+                session_id: session_id.to_string(),
+            })
+        }
         #[cfg(debug_assertions)]
         "devtool/tunnel" => Ok(DeeplinkAction::DebugTunnel {
             // url is passed unencoded (no `&`); the simple query parser keeps it intact.
@@ -76,6 +93,8 @@ pub fn parse(url: &str) -> Result<DeeplinkAction> {
             collide: parse_query_param(query, "collide").and_then(|p| p.parse().ok()),
             reset: parse_query_param(query, "reset").is_some(),
         }),
+        #[cfg(debug_assertions)]
+        "devtool/mock" => Ok(DeeplinkAction::DebugMockHost),
         other => Err(anyhow!("unknown deeplink action: {}", other)),
     }
 }
@@ -86,6 +105,11 @@ pub fn enqueue(action: DeeplinkAction) {
     PENDING_DEEPLINK.set(action);
 }
 
+/// Whether a deeplink is waiting for the next tick.
+pub fn has_pending() -> bool {
+    PENDING_DEEPLINK.has_pending()
+}
+
 /// Take the pending deeplink action (called from `ZedraApp::tick`).
 pub fn take_pending() -> Option<DeeplinkAction> {
     PENDING_DEEPLINK.take()
@@ -124,6 +148,16 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_session_by_id() {
+        match parse("zedra://session/a1b2c3d4").unwrap() {
+            DeeplinkAction::OpenSession { session_id } => assert_eq!(session_id, "a1b2c3d4"),
+            other => panic!("expected OpenSession, got {other:?}"),
+        }
+        assert!(parse("zedra://session/").is_err());
+        assert!(parse("zedra://session/a/b").is_err());
+    }
+
     #[test]
     fn parse_unknown_action_fails() {
         assert!(parse("zedra://unknown/foo").is_err());
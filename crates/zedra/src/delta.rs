@@ -1,5 +1,5 @@
 use std::collections::HashMap;
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 use std::time::Duration;
 
 use anyhow::{Context as _, Result, bail};
@@ -11,11 +11,11 @@ use uuid::Uuid;
 use zedra_session::signer::ClientSigner;
 
 use crate::platform_bridge;
+use crate::vault;
 
 const DEFAULT_BASE_URL: &str = "https://delta.zedra.dev";
 const STORE_DIR: &str = "zedra";
 const STATE_FILE: &str = "delta.json";
-const CLIENT_KEY_FILE: &str = "client.key";
 
 #[derive(Serialize)]
 struct OAuthRequest {
@@ -234,6 +234,17 @@ pub struct DeltaState {
     host_nodes_by_pubkey: HashMap<String, DeltaHostNode>,
 }
 
+/// Credentials kept in the secrets vault rather than in `delta.json`.
+#[derive(Serialize, Deserialize)]
+struct DeltaSecrets {
+    #[serde(default)]
+    access_token: Option<String>,
+    #[serde(default)]
+    refresh_token: Option<String>,
+    #[serde(default)]
+    push_token: Option<StoredPushToken>,
+}
+
 #[derive(Clone, PartialEq, Serialize, Deserialize)]
 struct StoredPushToken {
     provider: String,
@@ -861,9 +872,8 @@ fn encode_base64_no_pad(input: impl AsRef<[u8]>) -> String {
     STANDARD_NO_PAD.encode(input)
 }
 
-fn load_mobile_signer() -> Result<zedra_session::signer::FileClientSigner> {
-    zedra_session::signer::FileClientSigner::load_or_generate(&client_key_path()?)
-        .context("load Zedra mobile identity key")
+fn load_mobile_signer() -> Result<zedra_session::signer::KeyClientSigner> {
+    vault::client_signer().context("load Zedra mobile identity key")
 }
 
 fn load_state_from_disk() -> Result<DeltaState> {
@@ -875,6 +885,19 @@ fn load_state_from_disk() -> Result<DeltaState> {
     let mut state: DeltaState =
         serde_json::from_slice(&bytes).with_context(|| format!("decode {}", path.display()))?;
     state.base_url = normalize_base_url(&state.base_url);
+    let has_plaintext_secrets =
+        state.access_token.is_some() || state.refresh_token.is_some() || state.push_token.is_some();
+    if has_plaintext_secrets {
+        // Written before the vault existed: move the tokens into it.
+        save_state(&state).context("move Delta credentials into the vault")?;
+        tracing::info!("delta: moved credentials into the vault");
+    } else if let Some(secrets) = vault::get(vault::DELTA_SECRETS)? {
+        let secrets: DeltaSecrets =
+            serde_json::from_str(&secrets).context("decode Delta credentials")?;
+        state.access_token = secrets.access_token;
+        state.refresh_token = secrets.refresh_token;
+        state.push_token = secrets.push_token;
+    }
     Ok(state)
 }
 
@@ -883,8 +906,28 @@ fn save_state(state: &DeltaState) -> Result<()> {
     if let Some(parent) = path.parent() {
         std::fs::create_dir_all(parent).with_context(|| format!("create {}", parent.display()))?;
     }
-    let bytes = serde_json::to_vec_pretty(state)?;
-    write_private_file(&path, &bytes).with_context(|| format!("write {}", path.display()))?;
+    let secrets = DeltaSecrets {
+        access_token: state.access_token.clone(),
+        refresh_token: state.refresh_token.clone(),
+        push_token: state.push_token.clone(),
+    };
+    if secrets.access_token.is_none()
+        && secrets.refresh_token.is_none()
+        && secrets.push_token.is_none()
+    {
+        vault::remove(vault::DELTA_SECRETS)?;
+    } else {
+        vault::set(vault::DELTA_SECRETS, serde_json::to_string(&secrets)?)?;
+    }
+    let on_disk = DeltaState {
+        access_token: None,
+        refresh_token: None,
+        push_token: None,
+        ..state.clone()
+    };
+    let bytes = serde_json::to_vec_pretty(&on_disk)?;
+    vault::write_private_file(&path, &bytes)
+        .with_context(|| format!("write {}", path.display()))?;
     Ok(())
 }
 
@@ -892,10 +935,6 @@ fn state_path() -> Result<PathBuf> {
     Ok(store_dir()?.join(STATE_FILE))
 }
 
-fn client_key_path() -> Result<PathBuf> {
-    Ok(store_dir()?.join(CLIENT_KEY_FILE))
-}
-
 fn store_dir() -> Result<PathBuf> {
     let data_dir = platform_bridge::bridge()
         .data_directory()
@@ -903,26 +942,6 @@ fn store_dir() -> Result<PathBuf> {
     Ok(PathBuf::from(data_dir).join(STORE_DIR))
 }
 
-fn write_private_file(path: &Path, data: &[u8]) -> Result<()> {
-    #[cfg(unix)]
-    {
-        use std::io::Write;
-        use std::os::unix::fs::OpenOptionsExt;
-        let mut file = std::fs::OpenOptions::new()
-            .write(true)
-            .create(true)
-            .truncate(true)
-            .mode(0o600)
-            .open(path)?;
-        file.write_all(data)?;
-    }
-    #[cfg(not(unix))]
-    {
-        std::fs::write(path, data)?;
-    }
-    Ok(())
-}
-
 #[cfg(test)]
 mod tests {
     use serde_json::json;
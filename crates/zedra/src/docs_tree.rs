@@ -350,7 +350,7 @@ impl DocsTree {
                         .min_w_0()
                         .truncate()
                         .text_color(rgb(theme::text_secondary(cx)))
-                        .text_size(px(theme::FONT_BODY))
+                        .text_size(theme::scaled(theme::FONT_BODY))
                         .child(row.name),
                 )
                 .into_any_element();
@@ -394,7 +394,7 @@ impl DocsTree {
                     .min_w_0()
                     .truncate()
                     .text_color(rgb(theme::text_secondary(cx)))
-                    .text_size(px(theme::FONT_BODY))
+                    .text_size(theme::scaled(theme::FONT_BODY))
                     .child(row.name),
             );
         if is_selected {
@@ -416,7 +416,7 @@ impl DocsTree {
                     .w_full()
                     .min_w_0()
                     .text_color(rgb(theme::text_muted(cx)))
-                    .text_size(px(theme::FONT_BODY))
+                    .text_size(theme::scaled(theme::FONT_BODY))
                     .text_center()
                     .child(message),
             )
@@ -448,7 +448,7 @@ impl DocsTree {
                     .min_w_0()
                     .truncate()
                     .text_color(rgb(theme::text_muted(cx)))
-                    .text_size(px(theme::FONT_BODY))
+                    .text_size(theme::scaled(theme::FONT_BODY))
                     .child(label),
             )
             .into_any_element()
@@ -468,7 +468,7 @@ impl DocsTree {
                     .min_w_0()
                     .truncate()
                     .text_color(rgb(theme::text_muted(cx)))
-                    .text_size(px(theme::FONT_BODY))
+                    .text_size(theme::scaled(theme::FONT_BODY))
                     .child("All loaded"),
             )
     }
@@ -535,7 +535,7 @@ impl DocsTree {
             row = row.child(
                 div()
                     .text_color(rgb(theme::text_muted(cx)))
-                    .text_size(px(theme::FONT_BODY))
+                    .text_size(theme::scaled(theme::FONT_BODY))
                     .child("Building documents..."),
             );
         }
@@ -0,0 +1,133 @@
+// Blame gutter data: per-line cells derived from `GitBlame` hunks.
+
+use zedra_rpc::proto::GitBlameHunk;
+
+/// One line's blame cell.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlameCell {
+    /// Index into `BlameGutter::hunks`.
+    pub hunk: usize,
+    /// Author and age, set on a hunk's first line only.
+    pub label: Option<String>,
+    /// Recency within the file: 1.0 for the newest commit, 0.0 for the oldest.
+    pub heat: f32,
+}
+
+pub struct BlameGutter {
+    pub hunks: Vec<GitBlameHunk>,
+    /// One cell per buffer line; lines past the blamed content have none.
+    pub cells: Vec<Option<BlameCell>>,
+}
+
+impl BlameGutter {
+    pub fn new(hunks: Vec<GitBlameHunk>, line_count: usize, now: i64) -> Self {
+        let committed = hunks.iter().filter(|hunk| !is_uncommitted(hunk));
+        let oldest = committed
+            .clone()
+            .map(|hunk| hunk.timestamp)
+            .min()
+            .unwrap_or(0);
+        let newest = committed.map(|hunk| hunk.timestamp).max().unwrap_or(0);
+        let mut cells = vec![None; line_count];
+        for (index, hunk) in hunks.iter().enumerate() {
+            let heat = if is_uncommitted(hunk) || newest == oldest {
+                1.0
+            } else {
+                (hunk.timestamp - oldest) as f32 / (newest - oldest) as f32
+            };
+            let start = (hunk.start_line as usize).saturating_sub(1);
+            let end = (start + hunk.line_count as usize).min(line_count);
+            for (line, cell) in cells.iter_mut().enumerate().take(end).skip(start) {
+                *cell = Some(BlameCell {
+                    hunk: index,
+                    label: (line == start).then(|| cell_label(hunk, now)),
+                    heat,
+                });
+            }
+        }
+        Self { hunks, cells }
+    }
+}
+
+/// Lines changed in the working tree; blame reports them with a zero hash.
+pub fn is_uncommitted(hunk: &GitBlameHunk) -> bool {
+    hunk.id.chars().all(|c| c == '0')
+}
+
+fn cell_label(hunk: &GitBlameHunk, now: i64) -> String {
+    if is_uncommitted(hunk) {
+        return "uncommitted".to_string();
+    }
+    let author = hunk.author.split_whitespace().next().unwrap_or_default();
+    format!("{author} {}", age_label(now - hunk.timestamp))
+}
+
+/// Compact age such as `5m`, `3d`, or `2y`.
+pub fn age_label(seconds: i64) -> String {
+    const MINUTE: i64 = 60;
+    const HOUR: i64 = 60 * MINUTE;
+    const DAY: i64 = 24 * HOUR;
+    match seconds.max(0) {
+        s if s < HOUR => format!("{}m", s / MINUTE),
+        s if s < DAY => format!("{}h", s / HOUR),
+        s if s < 30 * DAY => format!("{}d", s / DAY),
+        s if s < 365 * DAY => format!("{}mo", s / (30 * DAY)),
+        s => format!("{}y", s / (365 * DAY)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hunk(id: &str, timestamp: i64, start_line: u32, line_count: u32) -> GitBlameHunk {
+        GitBlameHunk {
+            id: id.to_string(),
+            author: "Ada Lovelace".to_string(),
+            timestamp,
+            start_line,
+            line_count,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn cells_label_first_lines_and_heat_by_recency() {
+        let day = 24 * 60 * 60;
+        let hunks = vec![
+            hunk("aaaa", 0, 1, 2),
+            hunk("bbbb", 10 * day, 3, 1),
+            hunk("0000", 0, 4, 1),
+        ];
+        let gutter = BlameGutter::new(hunks, 5, 12 * day);
+        let labels: Vec<Option<&str>> = gutter
+            .cells
+            .iter()
+            .map(|cell| cell.as_ref().and_then(|cell| cell.label.as_deref()))
+            .collect();
+        assert_eq!(
+            labels,
+            vec![
+                Some("Ada 12d"),
+                None,
+                Some("Ada 2d"),
+                Some("uncommitted"),
+                None
+            ]
+        );
+        let heat: Vec<f32> = gutter.cells[..4]
+            .iter()
+            .map(|cell| cell.as_ref().unwrap().heat)
+            .collect();
+        assert_eq!(heat, vec![0.0, 0.0, 1.0, 1.0]);
+        assert!(gutter.cells[4].is_none());
+    }
+
+    #[test]
+    fn ages_use_the_largest_whole_unit() {
+        assert_eq!(age_label(-5), "0m");
+        assert_eq!(age_label(90 * 60), "1h");
+        assert_eq!(age_label(45 * 24 * 60 * 60), "1mo");
+        assert_eq!(age_label(800 * 24 * 60 * 60), "2y");
+    }
+}
@@ -1,21 +1,24 @@
 use std::ops::Range;
 use std::rc::Rc;
 
+use gpui::prelude::FluentBuilder;
 use gpui::*;
+use zedra_rpc::proto::{GitBlameHunk, LineEnding, TextEncoding};
 
+use super::blame::BlameGutter;
+use super::find::{self, FindMatch};
 use super::syntax_highlighter::{Highlighter, Language};
 use super::syntax_theme::SyntaxTheme;
 use super::text_buffer::Buffer;
+use super::word_diff::overlay_word_changes;
 
-use crate::fonts;
 use crate::platform_bridge;
+use crate::settings;
 use crate::theme::{self, EditorTheme};
-use crate::workspace_action::AddSelectionToChat;
+use crate::view_state::ViewState;
+use crate::workspace_action::{AddSelectionToChat, EditSelectionWithAi};
 
-const LINE_HEIGHT: f32 = theme::EDITOR_LINE_HEIGHT;
-const GUTTER_WIDTH: f32 = theme::EDITOR_GUTTER_WIDTH;
-const FONT_SIZE: f32 = theme::EDITOR_FONT_SIZE;
-const GUTTER_FONT_SIZE: f32 = theme::EDITOR_GUTTER_FONT_SIZE;
+const BLAME_WIDTH: f32 = theme::EDITOR_BLAME_WIDTH;
 const BOTTOM_INSET_MIN: f32 = 100.0;
 pub const CODE_EDITOR_SELECTION_AREA_ID: &str = "code-editor-selection";
 
@@ -23,6 +26,7 @@ type LineHighlights = Vec<(Range<usize>, HighlightStyle)>;
 
 /// Cached per-line data (text, line number).
 /// Recomputed only when the buffer content changes, NOT on every scroll frame.
+#[derive(Clone)]
 struct CachedLine {
     text: String,
     number: String,
@@ -33,15 +37,86 @@ pub struct ParsedEditorSyntax {
 }
 
 impl ParsedEditorSyntax {
-    pub fn build(filename: &str, content: String) -> Self {
-        let mut highlighter = Highlighter::from_filename(filename);
+    pub fn build(language: Language, content: String) -> Self {
+        let mut highlighter = Highlighter::from_language(language);
         highlighter.parse(&content);
         Self { highlighter }
     }
 }
 
+/// What an `EditorView` shows, as known by whoever loaded the content.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EditorDocument {
+    /// Host path, workdir-relative or absolute. Empty for scratch content.
+    pub path: String,
+    /// Set to skip detection from the file name.
+    pub language: Option<Language>,
+    pub read_only: bool,
+    /// 1-based line to scroll to when the content loads.
+    pub initial_line: Option<u32>,
+    /// Encoding the host decoded the file from; content is always UTF-8 here.
+    pub encoding: Option<TextEncoding>,
+    /// Line ending on disk; content always uses `\n`.
+    pub line_ending: Option<LineEnding>,
+}
+
+impl EditorDocument {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn language(mut self, language: Language) -> Self {
+        self.language = Some(language);
+        self
+    }
+
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    pub fn initial_line(mut self, line: Option<u32>) -> Self {
+        self.initial_line = line;
+        self
+    }
+
+    pub fn encoding(mut self, encoding: TextEncoding) -> Self {
+        self.encoding = Some(encoding);
+        self
+    }
+
+    pub fn line_ending(mut self, line_ending: LineEnding) -> Self {
+        self.line_ending = Some(line_ending);
+        self
+    }
+
+    pub fn file_name(&self) -> &str {
+        self.path.rsplit('/').next().unwrap_or(&self.path)
+    }
+
+    /// The explicit language, else the one detected from the file name.
+    pub fn resolved_language(&self) -> Language {
+        self.language
+            .unwrap_or_else(|| Language::from_filename(self.file_name()))
+    }
+}
+
+/// A place in the buffer: 0-based line and UTF-8 byte offset within that line.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EditorPosition {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Emitted when a blame gutter cell is tapped.
+pub struct BlameHunkTapped(pub GitBlameHunk);
+
 /// A code editor view with syntax highlighting and virtual scrolling.
 pub struct EditorView {
+    document: EditorDocument,
     buffer: Buffer,
     highlighter: Rc<Highlighter>,
     editor_theme: EditorTheme,
@@ -56,16 +131,30 @@ pub struct EditorView {
     lines_dirty: bool,
     /// Horizontal scroll offset in logical pixels.
     h_scroll_offset: f32,
-    /// Length (in chars) of the longest line — used to cap horizontal scroll.
-    max_line_chars: usize,
+    /// Index of the line with the most chars — shaped to cap horizontal scroll.
+    widest_line: usize,
+    /// Shaped width of `widest_line`; `None` until measured after a rebuild.
+    max_line_width: Option<f32>,
+    /// Code text style from the last render, used to shape lines for hit testing.
+    text_style: Option<TextStyle>,
+    /// Window-space bounds of the editor from the last paint.
+    bounds: Bounds<Pixels>,
     /// True once a gesture has been committed to horizontal scroll.
     /// Stays true until a clearly vertical event overrides it.
     h_scroll_active: bool,
+    /// Blame gutter shown left of the line numbers; cleared with the content.
+    blame: Option<Rc<BlameGutter>>,
+    /// Find-bar results in document order, painted behind visible rows.
+    find_matches: Rc<Vec<FindMatch>>,
+    /// Index into `find_matches` of the match the user is on.
+    active_match: Option<usize>,
     on_scroll_boundary_changed: Option<Box<dyn FnMut(bool)>>,
 }
 
+impl EventEmitter<BlameHunkTapped> for EditorView {}
+
 impl EditorView {
-    /// Create with automatic language detection from filename.
+    /// Empty plain-text view; content arrives later through `set_document`.
     pub fn new(_cx: &mut App) -> Self {
         Self::build(
             "".to_string(),
@@ -73,14 +162,18 @@ impl EditorView {
         )
     }
 
-    pub fn new_with_content(filename: &str, content: String, _cx: &mut App) -> Self {
-        let mut highlighter = Highlighter::from_filename(filename);
+    /// View over `content` with syntax parsed up front.
+    pub fn with_document(document: EditorDocument, content: String, _cx: &mut App) -> Self {
+        let mut highlighter = Highlighter::from_language(document.resolved_language());
         highlighter.parse(&content);
-        Self::build(content, highlighter)
+        let mut editor = Self::build(content, highlighter);
+        editor.document = document;
+        editor
     }
 
     fn build(content: String, highlighter: Highlighter) -> Self {
         Self {
+            document: EditorDocument::default(),
             buffer: Buffer::new(content),
             highlighter: Rc::new(highlighter),
             editor_theme: EditorTheme::dark(),
@@ -89,8 +182,14 @@ impl EditorView {
             cached_line_highlights: Rc::new(Vec::new()),
             lines_dirty: true,
             h_scroll_offset: 0.0,
-            max_line_chars: 0,
+            widest_line: 0,
+            max_line_width: None,
+            text_style: None,
+            bounds: Bounds::default(),
             h_scroll_active: false,
+            blame: None,
+            find_matches: Rc::new(Vec::new()),
+            active_match: None,
             on_scroll_boundary_changed: None,
         }
     }
@@ -102,21 +201,22 @@ impl EditorView {
     /// Replace the entire buffer content (e.g. when loading a remote file).
     /// The language is detected from the filename.
     pub fn set_content(&mut self, filename: &str, content: String) {
-        self.set_content_with_initial_line(filename, content, None);
+        self.set_document(EditorDocument::new(filename), content);
     }
 
-    pub fn set_content_with_initial_line(
-        &mut self,
-        filename: &str,
-        content: String,
-        initial_line: Option<u32>,
-    ) {
-        self.highlighter = Rc::new(Highlighter::from_filename(filename));
+    /// Replace the buffer with `content` described by `document`. Syntax
+    /// stays pending until `apply_parsed_syntax`.
+    pub fn set_document(&mut self, document: EditorDocument, content: String) {
+        let initial_line = document.initial_line;
+        self.highlighter = Rc::new(Highlighter::from_language(document.resolved_language()));
+        self.document = document;
         self.buffer.set_text(content);
         self.cached_line_highlights = Rc::new(Vec::new());
         self.lines_dirty = true;
         self.h_scroll_offset = 0.0;
         self.h_scroll_active = false;
+        self.blame = None;
+        self.clear_find();
         self.scroll_handle
             .0
             .borrow()
@@ -148,14 +248,275 @@ impl EditorView {
         };
     }
 
+    /// Replace the lines from 1-based `start` with `replacement` when they
+    /// still read `expected`. Returns `false`, leaving the view untouched, when
+    /// they don't or while syntax is still pending (see `edit`).
+    pub fn replace_lines(&mut self, start: u32, expected: &str, replacement: &str) -> bool {
+        let Some(row) = (start as usize).checked_sub(1) else {
+            return false;
+        };
+        if row >= self.buffer.line_count() {
+            return false;
+        }
+        let offset = self.buffer.line_byte_range(row).start;
+        let range = offset..offset + expected.len();
+        if self.buffer.text().get(range.clone()) != Some(expected) {
+            return false;
+        }
+        self.edit(range, replacement)
+    }
+
+    /// Replace `range` of the buffer with `new_text`. The syntax tree is edited
+    /// and reparsed incrementally, and only the lines tree-sitter reports as
+    /// changed are re-highlighted; other cached lines are kept. Returns
+    /// `false`, leaving the view untouched, while syntax is still pending,
+    /// because the pending parse is for the old text.
+    pub fn edit(&mut self, range: Range<usize>, new_text: &str) -> bool {
+        if self.highlighter.is_waiting_for_syntax() {
+            return false;
+        }
+        let Some(highlighter) = Rc::get_mut(&mut self.highlighter) else {
+            return false;
+        };
+        let start = range.start.min(self.buffer.len());
+        let old_end = range.end.clamp(start, self.buffer.len());
+        let ts_point = |(row, column): (usize, usize)| tree_sitter::Point::new(row, column);
+        let start_position = self.buffer.offset_to_point(start);
+        let old_end_position = self.buffer.offset_to_point(old_end);
+        self.buffer.delete(start..old_end);
+        self.buffer.insert(start, new_text);
+        let new_end = start + new_text.len();
+        let new_end_position = self.buffer.offset_to_point(new_end);
+        let changed = highlighter.edit(
+            &tree_sitter::InputEdit {
+                start_byte: start,
+                old_end_byte: old_end,
+                new_end_byte: new_end,
+                start_position: ts_point(start_position),
+                old_end_position: ts_point(old_end_position),
+                new_end_position: ts_point(new_end_position),
+            },
+            self.buffer.text(),
+        );
+
+        let first_line = start_position.0;
+        let old_lines = first_line..old_end_position.0 + 1;
+        let new_lines = first_line..new_end_position.0 + 1;
+        self.splice_line_cache(old_lines.clone(), new_lines.clone());
+
+        let line_count = self.buffer.line_count();
+        if self.cached_line_highlights.len() + new_lines.len() != line_count + old_lines.len() {
+            // Never built for this content; the next render builds it whole.
+            self.cached_line_highlights = Rc::new(Vec::new());
+            self.lines_dirty = true;
+        } else {
+            let highlights = Rc::make_mut(&mut self.cached_line_highlights);
+            highlights.splice(old_lines, vec![Vec::new(); new_lines.len()]);
+            let mut stale: Vec<Range<usize>> = changed
+                .into_iter()
+                .chain(std::iter::once(start..new_end))
+                .map(|bytes| {
+                    let first = self.buffer.offset_to_point(bytes.start).0;
+                    let last = self.buffer.offset_to_point(bytes.end).0;
+                    first..(last + 1).min(line_count)
+                })
+                .collect();
+            stale.sort_by_key(|lines| lines.start);
+            let mut merged: Vec<Range<usize>> = Vec::new();
+            for lines in stale {
+                match merged.last_mut() {
+                    Some(last) if lines.start <= last.end => last.end = last.end.max(lines.end),
+                    _ => merged.push(lines),
+                }
+            }
+            for lines in merged {
+                let rebuilt = line_highlights_in(
+                    &self.buffer,
+                    &self.highlighter,
+                    &self.editor_theme.syntax,
+                    lines.clone(),
+                );
+                highlights.splice(lines, rebuilt);
+            }
+        }
+        self.blame = None;
+        self.clear_find();
+        true
+    }
+
+    /// Swap the cached text of `old_lines` for the buffer's `new_lines`,
+    /// renumbering the lines after them when the count changed.
+    fn splice_line_cache(&mut self, old_lines: Range<usize>, new_lines: Range<usize>) {
+        if self.lines_dirty || self.cached_lines.is_empty() {
+            self.lines_dirty = true;
+            return;
+        }
+        let widest_chars = self
+            .cached_lines
+            .get(self.widest_line)
+            .map_or(0, |line| line.text.chars().count());
+        let widest_removed = old_lines.contains(&self.widest_line);
+        let shift = new_lines.len() as isize - old_lines.len() as isize;
+        let lines = Rc::make_mut(&mut self.cached_lines);
+        lines.splice(
+            old_lines.clone(),
+            new_lines.clone().map(|line| CachedLine {
+                text: self.buffer.line_text(line).to_string(),
+                number: format!("{:>4}", line + 1),
+            }),
+        );
+        if shift != 0 {
+            for (line, cached) in lines.iter_mut().enumerate().skip(new_lines.end) {
+                cached.number = format!("{:>4}", line + 1);
+            }
+        }
+        if widest_removed {
+            self.widest_line = lines
+                .iter()
+                .enumerate()
+                .max_by_key(|(_, l)| l.text.chars().count())
+                .map_or(0, |(index, _)| index);
+            self.max_line_width = None;
+        } else {
+            if self.widest_line >= old_lines.end {
+                self.widest_line = self.widest_line.saturating_add_signed(shift);
+            }
+            if let Some(line) = new_lines
+                .clone()
+                .find(|&line| lines[line].text.chars().count() > widest_chars)
+            {
+                self.widest_line = line;
+                self.max_line_width = None;
+            }
+        }
+    }
+
+    /// Show the blame gutter for `hunks`, aged relative to `now` (unix seconds).
+    pub fn set_blame(&mut self, hunks: Vec<GitBlameHunk>, now: i64) {
+        self.blame = Some(Rc::new(BlameGutter::new(
+            hunks,
+            self.buffer.line_count(),
+            now,
+        )));
+    }
+
+    pub fn clear_blame(&mut self) {
+        self.blame = None;
+    }
+
+    /// Search the buffer and jump to the first match at or below the top
+    /// visible line. Returns the match count; an empty query clears the find.
+    pub fn find(&mut self, query: &str, regex: bool) -> Result<usize, regex::Error> {
+        if query.is_empty() {
+            self.clear_find();
+            return Ok(0);
+        }
+        let pattern = find::compile_query(query, regex)?;
+        let matches = find::find_matches(&self.buffer, &pattern);
+        let top_line = line_for_y(
+            -f32::from(self.scroll_handle.0.borrow().base_handle.offset().y),
+            self.buffer.line_count(),
+        );
+        self.active_match = find::first_match_from(&matches, top_line);
+        self.find_matches = Rc::new(matches);
+        self.scroll_to_active_match();
+        Ok(self.find_matches.len())
+    }
+
+    pub fn clear_find(&mut self) {
+        self.find_matches = Rc::new(Vec::new());
+        self.active_match = None;
+    }
+
+    /// Move to the next (or previous) match, wrapping at either end.
+    pub fn step_match(&mut self, forward: bool) {
+        let count = self.find_matches.len();
+        let Some(active) = self.active_match.filter(|_| count > 0) else {
+            return;
+        };
+        self.active_match = Some(if forward {
+            (active + 1) % count
+        } else {
+            (active + count - 1) % count
+        });
+        self.scroll_to_active_match();
+    }
+
+    /// `(active, total)` for the find bar, the active match 1-based.
+    pub fn find_status(&self) -> Option<(usize, usize)> {
+        self.active_match
+            .map(|active| (active + 1, self.find_matches.len()))
+    }
+
+    fn scroll_to_active_match(&mut self) {
+        if let Some(found) = self.active_match.and_then(|i| self.find_matches.get(i)) {
+            self.scroll_handle
+                .scroll_to_item(found.line, ScrollStrategy::Center);
+        }
+    }
+
+    /// Scroll to 1-based `line`, clamped to the file.
+    pub fn go_to_line(&mut self, line: usize) {
+        let index = line
+            .saturating_sub(1)
+            .min(self.buffer.line_count().saturating_sub(1));
+        self.scroll_handle
+            .scroll_to_item(index, ScrollStrategy::Center);
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.buffer.line_count()
+    }
+
     pub fn language(&self) -> Language {
         self.highlighter.language()
     }
 
+    pub fn document(&self) -> &EditorDocument {
+        &self.document
+    }
+
+    pub fn path(&self) -> &str {
+        &self.document.path
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.document.read_only
+    }
+
+    pub fn encoding(&self) -> Option<TextEncoding> {
+        self.document.encoding
+    }
+
+    pub fn line_ending(&self) -> Option<LineEnding> {
+        self.document.line_ending
+    }
+
     pub fn is_scrolled_to_top(&self) -> bool {
         self.scroll_handle.0.borrow().base_handle.offset().y >= px(-0.5)
     }
 
+    /// Scroll position to restore when this file is shown again.
+    pub fn view_state(&self) -> ViewState {
+        ViewState::Scroll {
+            offset: self.scroll_handle.0.borrow().base_handle.offset(),
+            h_offset: self.h_scroll_offset,
+        }
+    }
+
+    /// Restore a position from `view_state`. Call after `set_content`; it
+    /// replaces the scroll to the first line that `set_content` queues.
+    pub fn restore_view_state(&mut self, state: ViewState) {
+        let ViewState::Scroll { offset, h_offset } = state else {
+            return;
+        };
+        let mut scroll_state = self.scroll_handle.0.borrow_mut();
+        scroll_state.deferred_scroll_to_item = None;
+        scroll_state.base_handle.set_offset(offset);
+        drop(scroll_state);
+        self.h_scroll_offset = h_offset;
+    }
+
     pub fn line_range_for_selection(&self, range_utf16: Range<usize>) -> Option<(u32, u32)> {
         let lines = self
             .cached_lines
@@ -174,6 +535,19 @@ impl EditorView {
         self.lines_dirty = true;
     }
 
+    /// Drop the per-line text cache under memory pressure; it is rebuilt from
+    /// the buffer on the next render. Returns the approximate bytes released.
+    pub fn release_line_cache(&mut self) -> usize {
+        let released = self
+            .cached_lines
+            .iter()
+            .map(|line| line.text.capacity() + line.number.capacity())
+            .sum();
+        self.cached_lines = Rc::new(Vec::new());
+        self.lines_dirty = true;
+        released
+    }
+
     /// Rebuild the cached line data from the buffer text only.
     fn rebuild_line_cache(&mut self) {
         let line_count = self.buffer.line_count();
@@ -183,7 +557,12 @@ impl EditorView {
                 number: format!("{:>4}", line + 1),
             })
             .collect();
-        self.max_line_chars = lines.iter().map(|l| l.text.len()).max().unwrap_or(0);
+        self.widest_line = lines
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, l)| l.text.chars().count())
+            .map_or(0, |(index, _)| index);
+        self.max_line_width = None;
         if self.cached_line_highlights.is_empty() || self.cached_line_highlights.len() != line_count
         {
             self.cached_line_highlights = if self.highlighter.is_waiting_for_syntax() {
@@ -200,6 +579,69 @@ impl EditorView {
         self.lines_dirty = false;
     }
 
+    /// Buffer position under `position` (window coordinates), snapped to the
+    /// nearest glyph boundary of the shaped line.
+    pub fn position_for_point(
+        &self,
+        position: Point<Pixels>,
+        window: &Window,
+    ) -> Option<EditorPosition> {
+        if self.cached_lines.is_empty() || !self.bounds.contains(&position) {
+            return None;
+        }
+        let origin = self.text_origin();
+        let line = line_for_y(f32::from(position.y - origin.y), self.cached_lines.len());
+        let column = self.shape_line(line, window).map_or(0, |shaped| {
+            shaped.closest_index_for_x(position.x - origin.x)
+        });
+        Some(EditorPosition { line, column })
+    }
+
+    /// Window-space top-left of the glyph at `position`; the column is
+    /// clamped to the end of the line.
+    pub fn point_for_position(
+        &self,
+        position: EditorPosition,
+        window: &Window,
+    ) -> Option<Point<Pixels>> {
+        let text = &self.cached_lines.get(position.line)?.text;
+        let column = position.column.min(text.len());
+        let shaped = self.shape_line(position.line, window)?;
+        let origin = self.text_origin();
+        Some(point(
+            origin.x + shaped.x_for_index(column),
+            origin.y + px(position.line as f32 * theme::editor_line_height()),
+        ))
+    }
+
+    /// Window-space origin of line 0's text, after gutters and both scroll offsets.
+    fn text_origin(&self) -> Point<Pixels> {
+        let blame_width = if self.blame.is_some() {
+            BLAME_WIDTH
+        } else {
+            0.0
+        };
+        let scroll_y = self.scroll_handle.0.borrow().base_handle.offset().y;
+        point(
+            self.bounds.left()
+                + px(blame_width + theme::editor_gutter_width() - self.h_scroll_offset),
+            self.bounds.top() + scroll_y,
+        )
+    }
+
+    /// Shape `line` exactly as the row renders it, so x-offsets match the glyphs on screen.
+    fn shape_line(&self, line: usize, window: &Window) -> Option<ShapedLine> {
+        let style = self.text_style.as_ref()?;
+        let text = self.cached_lines.get(line)?.text.clone();
+        let run = style.to_run(text.len());
+        Some(window.text_system().shape_line(
+            text.into(),
+            px(theme::editor_font_size()),
+            &[run],
+            None,
+        ))
+    }
+
     pub fn is_scrolled_to_file_top(&self) -> bool {
         let scroll_state = self.scroll_handle.0.borrow();
         // Opening at `file:line` moves the viewport, but only file line 1 is the file top.
@@ -226,48 +668,57 @@ fn code_text_color_for_highlighter(editor_theme: &EditorTheme, highlighter: &Hig
     }
 }
 
+/// Highlights for every line from a single query pass over the file. Running
+/// one query cursor per line made opening multi-thousand-line files stall on
+/// the UI thread; spans are now bucketed by the lines they cover instead.
 fn build_line_highlights(
     buffer: &Buffer,
     highlighter: &Highlighter,
     theme: &SyntaxTheme,
 ) -> Vec<LineHighlights> {
-    (0..buffer.line_count())
-        .map(|line| {
-            let line_text = buffer.line_text(line);
-            line_highlights_for_source(
-                buffer.text(),
-                highlighter,
-                theme,
-                buffer.line_byte_range(line),
-                line_text.len(),
-            )
-        })
-        .collect()
+    line_highlights_in(buffer, highlighter, theme, 0..buffer.line_count())
 }
 
-fn line_highlights_for_source(
-    source: &str,
+/// Highlights for `line_range` only, from one query pass over its bytes.
+/// Spans that start above the range (e.g. a block comment) are clipped to it.
+fn line_highlights_in(
+    buffer: &Buffer,
     highlighter: &Highlighter,
     theme: &SyntaxTheme,
-    byte_range: Range<usize>,
-    line_text_len: usize,
-) -> Vec<(Range<usize>, HighlightStyle)> {
-    let raw_highlights = highlighter.highlights(source, byte_range.clone());
-    let line_start = byte_range.start;
-    let line_end = line_start + line_text_len;
-
-    let mut result: Vec<(Range<usize>, HighlightStyle)> = Vec::new();
-    for (span_range, capture_name) in &raw_highlights {
-        if let Some(style) = theme.get(capture_name) {
+    line_range: Range<usize>,
+) -> Vec<LineHighlights> {
+    let mut lines: Vec<LineHighlights> = vec![Vec::new(); line_range.len()];
+    let Some(last_line) = line_range.end.checked_sub(1) else {
+        return lines;
+    };
+    let bytes =
+        buffer.line_byte_range(line_range.start).start..buffer.line_byte_range(last_line).end;
+    for (span_range, capture_name) in highlighter.highlights(buffer.text(), bytes) {
+        let Some(style) = theme.get(capture_name) else {
+            continue;
+        };
+        let first_line = buffer
+            .offset_to_point(span_range.start)
+            .0
+            .max(line_range.start);
+        for line in first_line..line_range.end {
+            let line_start = buffer.line_byte_range(line).start;
+            if line_start >= span_range.end && line > first_line {
+                break;
+            }
+            let line_end = line_start + buffer.line_text(line).len();
             let start = span_range.start.max(line_start) - line_start;
-            let end = span_range.end.min(line_end) - line_start;
+            let end = span_range.end.min(line_end).max(line_start) - line_start;
             if start < end {
-                result.push((start..end, style));
+                lines[line - line_range.start].push((start..end, style));
             }
         }
     }
+    lines.into_iter().map(super::merge_highlights).collect()
+}
 
-    super::merge_highlights(result)
+fn line_for_y(y: f32, line_count: usize) -> usize {
+    ((y / theme::editor_line_height()).floor().max(0.0) as usize).min(line_count.saturating_sub(1))
 }
 
 fn selectable_line_len_utf16(line: &str) -> usize {
@@ -322,15 +773,24 @@ impl Render for EditorView {
         let cached_lines = self.cached_lines.clone();
         let cached_line_highlights = self.cached_line_highlights.clone();
         let bottom_inset = f32::max(platform_bridge::home_indicator_inset(), BOTTOM_INSET_MIN);
-        // uniform_list forces all items to the same height (item 0's measured height = LINE_HEIGHT).
+        // uniform_list forces all items to the same height (item 0's measured height = the editor line height).
         // To get `bottom_inset` worth of scroll space we need enough extra items to cover it.
-        let extra_items = (bottom_inset / LINE_HEIGHT).ceil() as usize;
+        let extra_items = (bottom_inset / theme::editor_line_height()).ceil() as usize;
         let h_scroll_offset = self.h_scroll_offset;
         // Captured before the scroll event fires; restored while h_scroll_active so
         // the vertical position doesn't drift during a horizontal swipe.
         let scroll_y_lock = self.scroll_handle.0.borrow().base_handle.offset().y;
 
+        let blame = self.blame.clone();
+        let blame_color = theme::accent_yellow(cx);
+        let find_matches = self.find_matches.clone();
+        let active_match = self.active_match;
+        let match_bg: Hsla = rgba((blame_color << 8) | 0x40).into();
+        let active_match_bg: Hsla = rgba((blame_color << 8) | 0xa0).into();
+        let editor = cx.entity().downgrade();
+
         let editor_theme = self.editor_theme.clone();
+        let code_font = settings::code_font(cx);
         let text_style = {
             let mut style = window.text_style();
             style.color = rgb(code_text_color_for_highlighter(
@@ -338,17 +798,41 @@ impl Render for EditorView {
                 &self.highlighter,
             ))
             .into();
-            style.font_family = fonts::MONO_FONT_FAMILY.into();
-            style.font_size = px(FONT_SIZE).into();
+            style.font_family = code_font.family_name();
+            style.font_fallbacks = Some(code_font.fallbacks());
+            style.font_size = px(theme::editor_font_size()).into();
             style
         };
+        if self.text_style.as_ref() != Some(&text_style) {
+            self.text_style = Some(text_style.clone());
+            self.max_line_width = None;
+        }
+        if self.max_line_width.is_none() {
+            self.max_line_width = self
+                .shape_line(self.widest_line, window)
+                .map(|shaped| f32::from(shaped.width));
+        }
+        let bounds_editor = editor.clone();
+        let measure = canvas(
+            |bounds, _, _| bounds,
+            move |_bounds, measured_bounds, _window, cx| {
+                cx.defer(move |cx| {
+                    let _ = bounds_editor.update(cx, |this, _cx| {
+                        this.bounds = measured_bounds;
+                    });
+                });
+            },
+        )
+        .absolute()
+        .inset_0();
 
         div()
             .flex()
             .flex_col()
             .size_full()
+            .relative()
             .bg(rgb(editor_theme.background))
-            .font_family(fonts::MONO_FONT_FAMILY)
+            .font_family(code_font.family_name())
             .on_scroll_wheel(
                 cx.listener(move |this, event: &ScrollWheelEvent, _window, cx| {
                     let (delta_x, delta_y) = match event.delta {
@@ -365,8 +849,7 @@ impl Render for EditorView {
                         this.h_scroll_active = true;
                     }
                     if this.h_scroll_active && delta_x.abs() > 0.1 {
-                        let char_width = FONT_SIZE * 0.6;
-                        let max_offset = (this.max_line_chars as f32 * char_width).max(0.0);
+                        let max_offset = this.max_line_width.unwrap_or(0.0).max(0.0);
                         this.h_scroll_offset =
                             (this.h_scroll_offset - delta_x).clamp(0.0, max_offset);
                         // Undo any vertical drift: the uniform_list overflow scroll already fired
@@ -391,17 +874,37 @@ impl Render for EditorView {
                             range
                                 .map(|line| -> AnyElement {
                                     // Trailing spacer items for bottom safe-area clearance.
-                                    // Each renders at LINE_HEIGHT (uniform_list enforces uniform height),
-                                    // so extra_items * LINE_HEIGHT >= bottom_inset.
+                                    // Each renders at the editor line height (uniform_list enforces uniform height),
+                                    // so extra_items * line height >= bottom_inset.
                                     if line >= line_count {
-                                        return div().h(px(LINE_HEIGHT)).into_any_element();
+                                        return div()
+                                            .h(px(theme::editor_line_height()))
+                                            .into_any_element();
                                     }
 
                                     let cached = &cached_lines[line];
-                                    let highlights = cached_line_highlights
+                                    let mut highlights = cached_line_highlights
                                         .get(line)
                                         .cloned()
                                         .unwrap_or_default();
+                                    let on_line = find::matches_on_line(&find_matches, line);
+                                    if !on_line.is_empty() {
+                                        let ranges = on_line
+                                            .clone()
+                                            .map(|i| find_matches[i].range.clone())
+                                            .collect::<Vec<_>>();
+                                        highlights =
+                                            overlay_word_changes(highlights, &ranges, match_bg);
+                                        if let Some(active) =
+                                            active_match.filter(|i| on_line.contains(i))
+                                        {
+                                            highlights = overlay_word_changes(
+                                                highlights,
+                                                &[find_matches[active].range.clone()],
+                                                active_match_bg,
+                                            );
+                                        }
+                                    }
 
                                     let styled_text = if cached.text.is_empty() {
                                         StyledText::new(" ")
@@ -418,27 +921,60 @@ impl Render for EditorView {
                                         ""
                                     });
 
+                                    let blame_cell = blame.as_ref().map(|blame| {
+                                        let cell = blame.cells.get(line).cloned().flatten();
+                                        let mut el = div()
+                                            .id(("editor-blame", line))
+                                            .w(px(BLAME_WIDTH))
+                                            .h(px(theme::editor_line_height()))
+                                            .flex_none()
+                                            .px_1()
+                                            .flex()
+                                            .items_center()
+                                            .text_color(editor_theme.gutter)
+                                            .text_size(px(theme::editor_gutter_font_size()));
+                                        if let Some(cell) = cell {
+                                            let alpha = ((0.06 + 0.3 * cell.heat) * 255.0) as u32;
+                                            let hunk = blame.hunks[cell.hunk].clone();
+                                            let editor = editor.clone();
+                                            el = el
+                                                .bg(rgba((blame_color << 8) | alpha))
+                                                .cursor_pointer()
+                                                .on_press(move |_event, _window, cx| {
+                                                    let hunk = hunk.clone();
+                                                    let _ = editor.update(cx, |_this, cx| {
+                                                        cx.emit(BlameHunkTapped(hunk));
+                                                    });
+                                                })
+                                                .when_some(cell.label, |el, label| {
+                                                    el.child(div().truncate().child(label))
+                                                });
+                                        }
+                                        el
+                                    });
+
                                     div()
                                         .flex()
                                         .flex_row()
-                                        .h(px(LINE_HEIGHT))
+                                        .h(px(theme::editor_line_height()))
+                                        .children(blame_cell)
                                         .child(
                                             div()
-                                                .w(px(GUTTER_WIDTH))
-                                                .h(px(LINE_HEIGHT))
+                                                .w(px(theme::editor_gutter_width()))
+                                                .h(px(theme::editor_line_height()))
                                                 .flex()
                                                 .items_center()
                                                 .justify_end()
                                                 .pr_2()
                                                 .text_color(editor_theme.gutter)
-                                                .text_size(px(GUTTER_FONT_SIZE))
+                                                .text_size(px(theme::editor_gutter_font_size()))
                                                 .child(cached.number.clone()),
                                         )
                                         .child(
                                             // Clip container — stays within the row's flex width.
                                             div()
                                                 .flex_1()
-                                                .h(px(LINE_HEIGHT))
+                                                .h(px(theme::editor_line_height()))
                                                 .overflow_hidden()
                                                 .relative()
                                                 .child(
@@ -447,10 +983,10 @@ impl Render for EditorView {
                                                         .absolute()
                                                         .top(px(0.0))
                                                         .left(px(-h_scroll_offset))
-                                                        .h(px(LINE_HEIGHT))
+                                                        .h(px(theme::editor_line_height()))
                                                         .flex()
                                                         .items_center()
-                                                        .text_size(px(FONT_SIZE))
+                                                        .text_size(px(theme::editor_font_size()))
                                                         .relative()
                                                         .child(styled_text),
                                                 ),
@@ -464,8 +1000,10 @@ impl Render for EditorView {
                     .flex_1(),
                 )
                 .id(CODE_EDITOR_SELECTION_AREA_ID)
-                .action_with_image("Add to Chat", "zedra", AddSelectionToChat),
+                .action_with_image("Add to Chat", "zedra", AddSelectionToChat)
+                .action_with_image("Edit with AI", "sparkles", EditSelectionWithAi),
             )
+            .child(measure)
     }
 }
 
@@ -476,9 +1014,10 @@ mod tests {
     use gpui::{ScrollStrategy, point, px};
 
     use super::{
-        EditorView, ParsedEditorSyntax, code_text_color_for_highlighter,
-        line_range_for_selection_lines,
+        Buffer, EditorDocument, EditorView, ParsedEditorSyntax, build_line_highlights,
+        code_text_color_for_highlighter, line_for_y, line_range_for_selection_lines,
     };
+    use crate::editor::merge_highlights;
     use crate::editor::syntax_highlighter::{Highlighter, Language};
     use crate::theme::EditorTheme;
 
@@ -499,6 +1038,27 @@ mod tests {
         assert_eq!(line_range_for_selection_lines(&lines, 6..13), Some((2, 3)));
     }
 
+    #[test]
+    fn maps_y_offsets_to_clamped_line_indices() {
+        let line_height = crate::theme::editor_line_height();
+        assert_eq!(line_for_y(-4.0, 3), 0);
+        assert_eq!(line_for_y(line_height - 0.5, 3), 0);
+        assert_eq!(line_for_y(line_height, 3), 1);
+        assert_eq!(line_for_y(line_height * 10.0, 3), 2);
+        assert_eq!(line_for_y(line_height, 0), 0);
+    }
+
+    #[test]
+    fn tracks_the_line_with_the_most_chars_for_measuring() {
+        let content = "ascii line\nééééééééééé\nshort\n".to_string();
+        let mut editor = EditorView::build(content, Highlighter::from_filename("notes.txt"));
+        editor.max_line_width = Some(80.0);
+        editor.rebuild_line_cache();
+
+        assert_eq!(editor.widest_line, 1);
+        assert_eq!(editor.max_line_width, None);
+    }
+
     #[test]
     fn applies_parsed_syntax_to_cached_line_highlights() {
         let content = "fn main() {\n    let value = 1;\n}\n".to_string();
@@ -508,7 +1068,7 @@ mod tests {
         let original_lines = editor.cached_lines.clone();
         assert!(editor.cached_line_highlights[0].is_empty());
 
-        let parsed = ParsedEditorSyntax::build("main.rs", content);
+        let parsed = ParsedEditorSyntax::build(Language::Rust, content);
         editor.apply_parsed_syntax(parsed);
 
         assert!(Rc::ptr_eq(&original_lines, &editor.cached_lines));
@@ -524,6 +1084,71 @@ mod tests {
         );
     }
 
+    #[test]
+    fn single_pass_line_highlights_match_per_line_queries() {
+        let content = "/* a\n   b */ fn main() {\n    let s = \"x\";\n}\n".to_string();
+        let buffer = Buffer::new(content.clone());
+        let mut highlighter = Highlighter::from_language(Language::Rust);
+        highlighter.parse(&content);
+        let theme = EditorTheme::dark().syntax;
+
+        let per_line: Vec<_> = (0..buffer.line_count())
+            .map(|line| {
+                let range = buffer.line_byte_range(line);
+                let mut spans = Vec::new();
+                for (span, name) in highlighter.highlights(&content, range.clone()) {
+                    if let Some(style) = theme.get(name) {
+                        let line_end = range.start + buffer.line_text(line).len();
+                        let start = span.start.max(range.start) - range.start;
+                        let end = span.end.min(line_end).max(range.start) - range.start;
+                        if start < end {
+                            spans.push((start..end, style));
+                        }
+                    }
+                }
+                merge_highlights(spans)
+            })
+            .collect();
+
+        assert_eq!(
+            build_line_highlights(&buffer, &highlighter, &theme),
+            per_line
+        );
+        assert!(!per_line[1].is_empty(), "block comment should span line 1");
+    }
+
+    #[test]
+    fn incremental_edits_match_a_full_rebuild() {
+        let content = "fn main() {\n    let a = 1;\n}\n\nfn other() {}\n".to_string();
+        let mut editor = EditorView::build(content.clone(), Highlighter::from_filename("main.rs"));
+        assert!(!editor.edit(0..0, "// pending\n"));
+        editor.apply_parsed_syntax(ParsedEditorSyntax::build(Language::Rust, content));
+        editor.rebuild_line_cache();
+
+        // Opening a comment changes the syntax of lines below the edit.
+        assert!(editor.edit(12..12, "/*\n"));
+        assert!(!editor.replace_lines(4, "fn other() {}", ""));
+        assert!(editor.replace_lines(4, "}", "*/\n}"));
+
+        let expected = "fn main() {\n/*\n    let a = 1;\n*/\n}\n\nfn other() {}\n".to_string();
+        assert_eq!(editor.buffer.text(), expected);
+        let mut fresh = EditorView::build(expected.clone(), Highlighter::from_filename("main.rs"));
+        fresh.apply_parsed_syntax(ParsedEditorSyntax::build(Language::Rust, expected));
+        fresh.rebuild_line_cache();
+
+        assert!(!editor.lines_dirty);
+        let lines = |editor: &EditorView| {
+            editor
+                .cached_lines
+                .iter()
+                .map(|line| (line.text.clone(), line.number.clone()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(lines(&editor), lines(&fresh));
+        assert_eq!(editor.cached_line_highlights, fresh.cached_line_highlights);
+        assert!(!editor.cached_line_highlights[2].is_empty());
+    }
+
     #[test]
     fn dims_code_text_only_while_syntax_is_pending() {
         let editor_theme = EditorTheme::dark();
@@ -573,11 +1198,36 @@ mod tests {
         assert!(editor.is_scrolled_to_file_top());
     }
 
+    #[test]
+    fn document_language_overrides_file_name_detection() {
+        let mut editor = EditorView::build(String::new(), Highlighter::from_filename("new.rs"));
+
+        editor.set_document(
+            EditorDocument::new("scripts/Justfile")
+                .language(Language::Bash)
+                .read_only(true),
+            "build:\n\tcargo build\n".to_string(),
+        );
+
+        assert_eq!(editor.language(), Language::Bash);
+        assert_eq!(editor.path(), "scripts/Justfile");
+        assert_eq!(editor.document().file_name(), "Justfile");
+        assert!(editor.is_read_only());
+        assert_eq!(editor.encoding(), None);
+
+        editor.set_content("notes.rs", String::new());
+        assert_eq!(editor.language(), Language::Rust);
+        assert!(!editor.is_read_only());
+    }
+
     #[test]
     fn initial_line_scroll_is_not_treated_as_file_top() {
         let mut editor = EditorView::build(String::new(), Highlighter::from_filename("new.rs"));
 
-        editor.set_content_with_initial_line("new.rs", "one\ntwo\nthree\n".to_string(), Some(2));
+        editor.set_document(
+            EditorDocument::new("new.rs").initial_line(Some(2)),
+            "one\ntwo\nthree\n".to_string(),
+        );
 
         let scroll_state = editor.scroll_handle.0.borrow();
         let deferred_scroll = scroll_state
@@ -589,11 +1239,51 @@ mod tests {
         assert!(!editor.is_scrolled_to_file_top());
     }
 
+    #[test]
+    fn find_steps_through_matches_and_scrolls_to_them() {
+        let mut editor = EditorView::build(
+            "alpha\nbeta\nalpha beta\ngamma\n".to_string(),
+            Highlighter::from_filename("notes.txt"),
+        );
+
+        assert_eq!(editor.find("beta", false).unwrap(), 2);
+        assert_eq!(editor.find_status(), Some((1, 2)));
+        let target = |editor: &EditorView| {
+            editor
+                .scroll_handle
+                .0
+                .borrow()
+                .deferred_scroll_to_item
+                .map(|scroll| (scroll.item_index, scroll.strategy))
+        };
+        assert_eq!(target(&editor), Some((1, ScrollStrategy::Center)));
+
+        editor.step_match(true);
+        assert_eq!(editor.find_status(), Some((2, 2)));
+        assert_eq!(target(&editor), Some((2, ScrollStrategy::Center)));
+        editor.step_match(true);
+        assert_eq!(editor.find_status(), Some((1, 2)));
+        editor.step_match(false);
+        assert_eq!(editor.find_status(), Some((2, 2)));
+
+        assert!(editor.find("(", true).is_err());
+        assert_eq!(editor.find("", false).unwrap(), 0);
+        assert_eq!(editor.find_status(), None);
+
+        editor.go_to_line(99);
+        assert_eq!(target(&editor), Some((4, ScrollStrategy::Center)));
+        editor.go_to_line(0);
+        assert_eq!(target(&editor), Some((0, ScrollStrategy::Center)));
+    }
+
     #[test]
     fn initial_line_scroll_clamps_to_file_length() {
         let mut editor = EditorView::build(String::new(), Highlighter::from_filename("new.rs"));
 
-        editor.set_content_with_initial_line("new.rs", "one\ntwo".to_string(), Some(99));
+        editor.set_document(
+            EditorDocument::new("new.rs").initial_line(Some(99)),
+            "one\ntwo".to_string(),
+        );
 
         let scroll_state = editor.scroll_handle.0.borrow();
         let deferred_scroll = scroll_state
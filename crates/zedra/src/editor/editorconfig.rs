@@ -0,0 +1,305 @@
+//! `.editorconfig` resolution for files on the host.
+//!
+//! `resolve` reads `.editorconfig` from the file's directory up to the workdir
+//! root through `FsRead`, nearest first, and stops at a file marked
+//! `root = true`. Supported properties: `indent_style`, `indent_size`,
+//! `tab_width`, `charset`, `end_of_line`, `trim_trailing_whitespace`, and
+//! `insert_final_newline`.
+
+use zedra_rpc::proto::{LineEnding, TextEncoding};
+use zedra_session::SessionHandle;
+
+use super::text_buffer::Buffer;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IndentStyle {
+    /// Tabs, each `width` columns wide.
+    Tabs(usize),
+    /// `width` spaces per level.
+    Spaces(usize),
+}
+
+impl IndentStyle {
+    /// Short header label, e.g. `2 Spaces`.
+    pub fn label(self) -> String {
+        match self {
+            Self::Tabs(_) => "Tabs".to_string(),
+            Self::Spaces(width) => format!("{width} Spaces"),
+        }
+    }
+
+    fn width(self) -> usize {
+        match self {
+            Self::Tabs(width) | Self::Spaces(width) => width.max(1),
+        }
+    }
+
+    /// The style most indented lines use, or `None` when no line is indented.
+    pub(super) fn detect(buffer: &Buffer) -> Option<Self> {
+        let mut tab_lines = 0;
+        let mut space_lines = 0;
+        let mut narrowest = usize::MAX;
+        for line in 0..buffer.line_count() {
+            let text = buffer.line_text(line);
+            if text.starts_with('\t') {
+                tab_lines += 1;
+            } else {
+                let spaces = text.len() - text.trim_start_matches(' ').len();
+                if spaces > 1 && spaces < text.len() {
+                    space_lines += 1;
+                    narrowest = narrowest.min(spaces);
+                }
+            }
+        }
+        if tab_lines == 0 && space_lines == 0 {
+            None
+        } else if tab_lines > space_lines {
+            Some(Self::Tabs(4))
+        } else {
+            Some(Self::Spaces(narrowest.clamp(2, 8)))
+        }
+    }
+
+    /// `indent` rewritten in this style, keeping its visual width.
+    pub(super) fn reindent(self, indent: &str) -> String {
+        let width = self.width();
+        let columns = indent.chars().fold(0, |columns, c| match c {
+            '\t' => (columns / width + 1) * width,
+            _ => columns + 1,
+        });
+        match self {
+            Self::Tabs(_) => "\t".repeat(columns / width) + &" ".repeat(columns % width),
+            Self::Spaces(_) => " ".repeat(columns),
+        }
+    }
+}
+
+/// Properties a project's `.editorconfig` files set for one file. `None` leaves
+/// the app setting in charge.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EditorConfig {
+    pub trim_trailing_whitespace: Option<bool>,
+    pub insert_final_newline: Option<bool>,
+    pub indent_style: Option<IndentStyle>,
+    /// `charset`; saves write this encoding.
+    pub charset: Option<TextEncoding>,
+    /// `end_of_line`; saves write this line ending. `cr` is not supported.
+    pub end_of_line: Option<LineEnding>,
+}
+
+impl EditorConfig {
+    /// Fold in one `.editorconfig` whose directory is `dir` (workdir-relative,
+    /// empty for the root), for the file at `path`. Files must be merged
+    /// nearest first: properties already set are kept. Returns whether the
+    /// file is marked `root = true`, meaning farther files should be skipped.
+    pub fn merge(&mut self, source: &str, dir: &str, path: &str) -> bool {
+        let relative = if dir.is_empty() {
+            path
+        } else {
+            path.strip_prefix(&format!("{dir}/")).unwrap_or(path)
+        };
+        let file_name = relative.rsplit('/').next().unwrap_or(relative);
+        let mut root = false;
+        let mut in_matching_section = false;
+        let mut found = SectionProperties::default();
+        for line in source.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+            if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                in_matching_section = if section.contains('/') {
+                    glob_matches(section.trim_start_matches('/'), relative)
+                } else {
+                    glob_matches(section, file_name)
+                };
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let key = key.trim().to_ascii_lowercase();
+            let value = value.trim().to_ascii_lowercase();
+            if key == "root" {
+                root = value == "true";
+            } else if in_matching_section {
+                // Later sections win over earlier ones within a file.
+                found.set(&key, &value);
+            }
+        }
+        self.trim_trailing_whitespace = self
+            .trim_trailing_whitespace
+            .or(found.trim_trailing_whitespace);
+        self.insert_final_newline = self.insert_final_newline.or(found.insert_final_newline);
+        self.indent_style = self.indent_style.or(found.indent_style());
+        self.charset = self.charset.or(found.charset);
+        self.end_of_line = self.end_of_line.or(found.end_of_line);
+        root
+    }
+}
+
+#[derive(Default)]
+struct SectionProperties {
+    trim_trailing_whitespace: Option<bool>,
+    insert_final_newline: Option<bool>,
+    indent_tabs: Option<bool>,
+    indent_size: Option<usize>,
+    tab_width: Option<usize>,
+    charset: Option<TextEncoding>,
+    end_of_line: Option<LineEnding>,
+}
+
+impl SectionProperties {
+    fn set(&mut self, key: &str, value: &str) {
+        let flag = match value {
+            "true" => Some(true),
+            "false" => Some(false),
+            _ => None,
+        };
+        match key {
+            "trim_trailing_whitespace" => self.trim_trailing_whitespace = flag,
+            "insert_final_newline" => self.insert_final_newline = flag,
+            "indent_style" => {
+                self.indent_tabs = match value {
+                    "tab" => Some(true),
+                    "space" => Some(false),
+                    _ => None,
+                }
+            }
+            "indent_size" => self.indent_size = value.parse().ok(),
+            "tab_width" => self.tab_width = value.parse().ok(),
+            "charset" => {
+                self.charset = match value {
+                    "utf-8" => Some(TextEncoding::Utf8),
+                    "utf-8-bom" => Some(TextEncoding::Utf8Bom),
+                    "utf-16le" => Some(TextEncoding::Utf16Le),
+                    "utf-16be" => Some(TextEncoding::Utf16Be),
+                    "latin1" => Some(TextEncoding::Latin1),
+                    _ => None,
+                }
+            }
+            "end_of_line" => {
+                self.end_of_line = match value {
+                    "lf" => Some(LineEnding::Lf),
+                    "crlf" => Some(LineEnding::Crlf),
+                    _ => None,
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn indent_style(&self) -> Option<IndentStyle> {
+        let width = self.indent_size.or(self.tab_width).unwrap_or(4);
+        if self.indent_tabs? {
+            Some(IndentStyle::Tabs(self.tab_width.unwrap_or(width)))
+        } else {
+            Some(IndentStyle::Spaces(width))
+        }
+    }
+}
+
+/// `.editorconfig` glob match: `*` stays within a path segment, `**` crosses
+/// segments, `?` is one character, and `{a,b}` is an alternation.
+fn glob_matches(pattern: &str, name: &str) -> bool {
+    if let Some(open) = pattern.find('{') {
+        if let Some(close) = pattern[open..].find('}').map(|i| open + i) {
+            let (head, tail) = (&pattern[..open], &pattern[close + 1..]);
+            return pattern[open + 1..close]
+                .split(',')
+                .any(|choice| glob_matches(&format!("{head}{choice}{tail}"), name));
+        }
+    }
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    glob_matches_chars(&pattern, &name)
+}
+
+fn glob_matches_chars(pattern: &[char], name: &[char]) -> bool {
+    match pattern.first() {
+        None => name.is_empty(),
+        Some('*') if pattern.get(1) == Some(&'*') => {
+            (0..=name.len()).any(|skip| glob_matches_chars(&pattern[2..], &name[skip..]))
+        }
+        Some('*') => {
+            let segment = name.iter().position(|&c| c == '/').unwrap_or(name.len());
+            (0..=segment).any(|skip| glob_matches_chars(&pattern[1..], &name[skip..]))
+        }
+        Some('?') => {
+            !name.is_empty() && name[0] != '/' && glob_matches_chars(&pattern[1..], &name[1..])
+        }
+        Some(&c) => name.first() == Some(&c) && glob_matches_chars(&pattern[1..], &name[1..]),
+    }
+}
+
+/// The `.editorconfig` properties for `path` (workdir-relative). Missing or
+/// unreadable files are skipped.
+pub async fn resolve(handle: &SessionHandle, path: &str) -> EditorConfig {
+    let mut config = EditorConfig::default();
+    let mut dir = path;
+    while let Some((parent, _)) = dir.rsplit_once('/') {
+        dir = parent;
+        if read_into(handle, dir, path, &mut config).await {
+            return config;
+        }
+    }
+    read_into(handle, "", path, &mut config).await;
+    config
+}
+
+/// Merge `dir/.editorconfig` into `config`; returns whether it is a root file.
+async fn read_into(
+    handle: &SessionHandle,
+    dir: &str,
+    path: &str,
+    config: &mut EditorConfig,
+) -> bool {
+    let file = if dir.is_empty() {
+        ".editorconfig".to_string()
+    } else {
+        format!("{dir}/.editorconfig")
+    };
+    match handle.fs_read(&file).await {
+        Ok(read) if read.error.is_none() && !read.too_large => {
+            config.merge(&read.content, dir, path)
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nearest_file_wins_and_reads_charset_and_end_of_line() {
+        let root = "root = true\n\n[*]\ncharset = utf-8\nend_of_line = lf\n\n\
+                    [*.{bat,cmd}]\nend_of_line = crlf\n\n[Makefile]\nindent_style = tab\n";
+        let mut script = EditorConfig::default();
+        assert!(script.merge(root, "", "tools/build.bat"));
+        assert_eq!(script.end_of_line, Some(LineEnding::Crlf));
+        assert_eq!(script.charset, Some(TextEncoding::Utf8));
+        assert_eq!(script.indent_style, None);
+
+        let mut makefile = EditorConfig::default();
+        makefile.merge(root, "", "build/Makefile");
+        assert_eq!(makefile.indent_style, Some(IndentStyle::Tabs(4)));
+
+        let nested = "[*.py]\nindent_style = space\nindent_size = 2\nend_of_line = crlf\n";
+        let mut python = EditorConfig::default();
+        assert!(!python.merge(nested, "src", "src/app/main.py"));
+        python.merge(root, "", "src/app/main.py");
+        assert_eq!(python.indent_style, Some(IndentStyle::Spaces(2)));
+        assert_eq!(python.end_of_line, Some(LineEnding::Crlf));
+        assert_eq!(python.charset, Some(TextEncoding::Utf8));
+
+        // Sections with a slash match the path relative to the config's directory.
+        let mut docs = EditorConfig::default();
+        docs.merge(
+            "[docs/**.md]\ninsert_final_newline = false\n",
+            "",
+            "docs/guide/intro.md",
+        );
+        assert_eq!(docs.insert_final_newline, Some(false));
+    }
+}
@@ -0,0 +1,124 @@
+// Find in file: plain-text or regex search over the editor buffer.
+//
+// Matches are found line by line, so a pattern never spans a line break; that
+// keeps every match inside one rendered row, where `EditorView` paints it as a
+// background under the syntax highlights.
+
+use std::ops::Range;
+
+use regex::{Regex, RegexBuilder};
+
+use super::text_buffer::Buffer;
+
+/// Stop collecting past this many matches; enough to step through, and keeps a
+/// one-letter query on a huge file from stalling the UI thread.
+pub const MAX_MATCHES: usize = 10_000;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FindMatch {
+    /// 0-based buffer line.
+    pub line: usize,
+    /// Byte range within the line's text.
+    pub range: Range<usize>,
+}
+
+/// Compile `query`. Plain queries match literally; both kinds are
+/// case-insensitive unless the query has an uppercase letter.
+pub fn compile_query(query: &str, regex: bool) -> Result<Regex, regex::Error> {
+    let pattern = if regex {
+        query.to_string()
+    } else {
+        regex::escape(query)
+    };
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!query.chars().any(char::is_uppercase))
+        .build()
+}
+
+/// All non-empty matches of `pattern` in `buffer`, in document order.
+pub fn find_matches(buffer: &Buffer, pattern: &Regex) -> Vec<FindMatch> {
+    let mut matches = Vec::new();
+    for line in 0..buffer.line_count() {
+        let text = buffer.line_text(line);
+        for found in pattern.find_iter(text) {
+            if found.is_empty() {
+                continue;
+            }
+            matches.push(FindMatch {
+                line,
+                range: found.range(),
+            });
+            if matches.len() >= MAX_MATCHES {
+                return matches;
+            }
+        }
+    }
+    matches
+}
+
+/// Indices into `matches` of the matches on `line`.
+pub fn matches_on_line(matches: &[FindMatch], line: usize) -> Range<usize> {
+    let start = matches.partition_point(|m| m.line < line);
+    let end = matches.partition_point(|m| m.line <= line);
+    start..end
+}
+
+/// First match at or after `line`, wrapping to the top; used to pick the
+/// starting match near what the user is looking at.
+pub fn first_match_from(matches: &[FindMatch], line: usize) -> Option<usize> {
+    if matches.is_empty() {
+        return None;
+    }
+    let index = matches.partition_point(|m| m.line < line);
+    Some(if index < matches.len() { index } else { 0 })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_plain_and_regex_matches_per_line() {
+        let buffer = Buffer::new("let a = 1;\nLet b = a + a;\n\nfoo()\n".to_string());
+
+        let plain = find_matches(&buffer, &compile_query("let", false).unwrap());
+        assert_eq!(
+            plain,
+            vec![
+                FindMatch {
+                    line: 0,
+                    range: 0..3
+                },
+                FindMatch {
+                    line: 1,
+                    range: 0..3
+                },
+            ]
+        );
+        let smart_case = find_matches(&buffer, &compile_query("Let", false).unwrap());
+        assert_eq!(smart_case.len(), 1);
+        assert_eq!(smart_case[0].line, 1);
+
+        let literal = find_matches(&buffer, &compile_query("foo()", false).unwrap());
+        assert_eq!(literal[0].range, 0..5);
+
+        let regex = find_matches(&buffer, &compile_query(r"\ba\b", true).unwrap());
+        assert_eq!(regex.len(), 3);
+        assert_eq!(matches_on_line(&regex, 1), 1..3);
+        assert_eq!(matches_on_line(&regex, 2), 3..3);
+
+        let empty = find_matches(&buffer, &compile_query("x*", true).unwrap());
+        assert!(empty.is_empty());
+        assert!(compile_query("(", true).is_err());
+    }
+
+    #[test]
+    fn starts_from_the_first_match_at_or_below_a_line() {
+        let buffer = Buffer::new("a\nb\na\nb\n".to_string());
+        let matches = find_matches(&buffer, &compile_query("a", false).unwrap());
+        assert_eq!(first_match_from(&matches, 0), Some(0));
+        assert_eq!(first_match_from(&matches, 1), Some(1));
+        assert_eq!(first_match_from(&matches, 3), Some(0));
+        assert_eq!(first_match_from(&[], 3), None);
+    }
+}
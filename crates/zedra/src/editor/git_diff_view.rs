@@ -8,10 +8,14 @@ use std::rc::Rc;
 
 use gpui::*;
 use tracing::info;
+use zedra_rpc::proto::{GitDiffFile, GitDiffLineKind};
 
 use super::syntax_highlighter::Highlighter;
+use super::word_diff::overlay_word_changes;
 use crate::platform_bridge;
+use crate::settings;
 use crate::theme::{self, EditorTheme};
+use crate::view_state::ViewState;
 
 // ── Diff data types ─────────────────────────────────────────────────────────
 
@@ -31,11 +35,15 @@ pub struct DiffLine {
     pub old_line_num: Option<usize>,
     pub new_line_num: Option<usize>,
     pub content: String,
+    /// Byte ranges of `content` that differ from the paired removed/added line.
+    pub word_changes: Vec<Range<usize>>,
 }
 
 /// A contiguous hunk of changes.
 #[derive(Clone, Debug)]
 pub struct DiffHunk {
+    /// The `@@` line, which names the hunk for `GitStageHunk`.
+    pub header: String,
     pub old_start: usize,
     pub old_count: usize,
     pub new_start: usize,
@@ -69,6 +77,46 @@ impl FileDiff {
         (added, removed)
     }
 
+    /// Convert a host-parsed diff, marking word changes like
+    /// `parse_unified_diff` does.
+    pub fn from_structured(file: GitDiffFile) -> Self {
+        let hunks = file
+            .hunks
+            .into_iter()
+            .map(|hunk| {
+                let mut lines: Vec<DiffLine> = hunk
+                    .lines
+                    .into_iter()
+                    .map(|line| DiffLine {
+                        kind: match line.kind {
+                            GitDiffLineKind::Added => DiffLineKind::Added,
+                            GitDiffLineKind::Removed => DiffLineKind::Removed,
+                            GitDiffLineKind::Context => DiffLineKind::Unchanged,
+                        },
+                        old_line_num: line.old_line.map(|n| n as usize),
+                        new_line_num: line.new_line.map(|n| n as usize),
+                        content: line.content,
+                        word_changes: Vec::new(),
+                    })
+                    .collect();
+                super::word_diff::mark_word_changes(&mut lines);
+                DiffHunk {
+                    header: hunk.header,
+                    old_start: hunk.old_start as usize,
+                    old_count: hunk.old_count as usize,
+                    new_start: hunk.new_start as usize,
+                    new_count: hunk.new_count as usize,
+                    lines,
+                }
+            })
+            .collect();
+        Self {
+            old_path: file.old_path,
+            new_path: file.new_path,
+            hunks,
+        }
+    }
+
     pub fn display_path(&self) -> String {
         if self.old_path.is_empty() {
             return self.new_path.clone();
@@ -119,6 +167,7 @@ pub fn parse_unified_diff(text: &str) -> Vec<FileDiff> {
             old_line = os;
             new_line = ns;
             current_hunk = Some(DiffHunk {
+                header: raw_line.to_string(),
                 old_start: os,
                 old_count: oc,
                 new_start: ns,
@@ -132,6 +181,7 @@ pub fn parse_unified_diff(text: &str) -> Vec<FileDiff> {
                     old_line_num: None,
                     new_line_num: Some(new_line),
                     content: content.to_string(),
+                    word_changes: Vec::new(),
                 });
                 new_line += 1;
             } else if let Some(content) = raw_line.strip_prefix('-') {
@@ -140,6 +190,7 @@ pub fn parse_unified_diff(text: &str) -> Vec<FileDiff> {
                     old_line_num: Some(old_line),
                     new_line_num: None,
                     content: content.to_string(),
+                    word_changes: Vec::new(),
                 });
                 old_line += 1;
             } else {
@@ -149,6 +200,7 @@ pub fn parse_unified_diff(text: &str) -> Vec<FileDiff> {
                     old_line_num: Some(old_line),
                     new_line_num: Some(new_line),
                     content: content.to_string(),
+                    word_changes: Vec::new(),
                 });
                 old_line += 1;
                 new_line += 1;
@@ -163,6 +215,9 @@ pub fn parse_unified_diff(text: &str) -> Vec<FileDiff> {
         diffs.push(diff);
     }
 
+    for hunk in diffs.iter_mut().flat_map(|diff| diff.hunks.iter_mut()) {
+        super::word_diff::mark_word_changes(&mut hunk.lines);
+    }
     diffs
 }
 
@@ -200,14 +255,34 @@ pub fn sample_diffs() -> Vec<FileDiff> {
 
 // ── GitDiffView ─────────────────────────────────────────────────────────────
 
-const LINE_HEIGHT: f32 = theme::EDITOR_LINE_HEIGHT;
-const GUTTER_WIDTH: f32 = theme::EDITOR_GUTTER_WIDTH;
-const FONT_SIZE: f32 = theme::EDITOR_FONT_SIZE;
-const GUTTER_FONT_SIZE: f32 = theme::EDITOR_GUTTER_FONT_SIZE;
 const BOTTOM_INSET_MIN: f32 = 100.0;
 
+/// Which way the per-hunk button moves a hunk.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HunkStaging {
+    Stage,
+    Unstage,
+}
+
+impl HunkStaging {
+    fn label(self) -> &'static str {
+        match self {
+            HunkStaging::Stage => "Stage",
+            HunkStaging::Unstage => "Unstage",
+        }
+    }
+}
+
+/// Emitted when a hunk's stage or unstage button is tapped.
+#[derive(Clone, Debug)]
+pub struct GitDiffHunkToggled {
+    pub header: String,
+}
+
 struct CachedDiffLine {
     line: Option<DiffLine>,
+    /// A hunk's `@@` row, shown only while hunk staging is on.
+    is_hunk_header: bool,
     highlights: Vec<(Range<usize>, HighlightStyle)>,
     /// Length in chars (used to cap horizontal scroll).
     char_len: usize,
@@ -224,6 +299,7 @@ pub struct GitDiffView {
     h_scroll_offset: f32,
     max_line_chars: usize,
     h_scroll_active: bool,
+    hunk_staging: Option<HunkStaging>,
 }
 
 impl GitDiffView {
@@ -252,6 +328,7 @@ impl GitDiffView {
             h_scroll_offset: 0.0,
             max_line_chars: 0,
             h_scroll_active: false,
+            hunk_staging: None,
         }
     }
 
@@ -264,6 +341,36 @@ impl GitDiffView {
         cx.notify();
     }
 
+    /// Show a stage or unstage button above each hunk; `None` hides them.
+    pub fn set_hunk_staging(&mut self, staging: Option<HunkStaging>, cx: &mut Context<Self>) {
+        if self.hunk_staging == staging {
+            return;
+        }
+        self.hunk_staging = staging;
+        self.lines_dirty = true;
+        cx.notify();
+    }
+
+    /// Scroll position to restore when this diff is shown again.
+    pub fn view_state(&self) -> ViewState {
+        ViewState::Scroll {
+            offset: self.scroll_handle.0.borrow().base_handle.offset(),
+            h_offset: self.h_scroll_offset,
+        }
+    }
+
+    pub fn restore_view_state(&mut self, state: ViewState, cx: &mut Context<Self>) {
+        let ViewState::Scroll { offset, h_offset } = state else {
+            return;
+        };
+        let mut scroll_state = self.scroll_handle.0.borrow_mut();
+        scroll_state.deferred_scroll_to_item = None;
+        scroll_state.base_handle.set_offset(offset);
+        drop(scroll_state);
+        self.h_scroll_offset = h_offset;
+        cx.notify();
+    }
+
     fn sync_editor_theme(&mut self, editor_theme: &EditorTheme) {
         if self.editor_theme == *editor_theme {
             return;
@@ -272,14 +379,40 @@ impl GitDiffView {
         self.lines_dirty = true;
     }
 
+    /// Drop the highlighted line cache under memory pressure; it is rebuilt
+    /// from the diff on the next render. Returns the approximate bytes released.
+    pub fn release_line_cache(&mut self) -> usize {
+        let released = self
+            .cached_lines
+            .iter()
+            .map(|cached| {
+                cached
+                    .line
+                    .as_ref()
+                    .map_or(0, |line| line.content.capacity())
+                    + cached.highlights.capacity()
+                        * std::mem::size_of::<(Range<usize>, HighlightStyle)>()
+            })
+            .sum();
+        self.cached_lines = Rc::new(Vec::new());
+        self.lines_dirty = true;
+        released
+    }
+
     fn rebuild_line_cache(&mut self) {
-        let line_count = self.total_lines();
-        let lines: Vec<CachedDiffLine> = (0..line_count)
-            .map(|i| {
-                let line = self.get_line(i);
+        let lines: Vec<CachedDiffLine> = self
+            .rows()
+            .into_iter()
+            .map(|(line, is_hunk_header)| {
+                let line = Some(line);
                 let (highlights, char_len) = match &line {
                     Some(l) if l.kind != DiffLineKind::Header => {
                         let h = self.line_highlights(&l.content);
+                        let word_bg = match l.kind {
+                            DiffLineKind::Added => self.editor_theme.diff.added_word_bg,
+                            _ => self.editor_theme.diff.removed_word_bg,
+                        };
+                        let h = overlay_word_changes(h, &l.word_changes, rgb(word_bg).into());
                         let len = l.content.chars().count();
                         (h, len)
                     }
@@ -288,6 +421,7 @@ impl GitDiffView {
                 };
                 CachedDiffLine {
                     line,
+                    is_hunk_header,
                     highlights,
                     char_len,
                 }
@@ -298,36 +432,24 @@ impl GitDiffView {
         self.lines_dirty = false;
     }
 
-    fn total_lines(&self) -> usize {
-        let mut count = 1; // file header
-        for hunk in &self.diff.hunks {
-            count += hunk.lines.len();
-        }
-        count
-    }
-
-    fn get_line(&self, index: usize) -> Option<DiffLine> {
-        let mut current = 0;
-
-        if index == current {
-            return Some(DiffLine {
-                kind: DiffLineKind::Header,
-                old_line_num: None,
-                new_line_num: None,
-                content: self.diff.display_path(),
-            });
-        }
-        current += 1;
-
+    /// File header, then each hunk's lines, each hunk led by its `@@` row
+    /// while hunk staging is on.
+    fn rows(&self) -> Vec<(DiffLine, bool)> {
+        let header = |content: String| DiffLine {
+            kind: DiffLineKind::Header,
+            old_line_num: None,
+            new_line_num: None,
+            content,
+            word_changes: Vec::new(),
+        };
+        let mut rows = vec![(header(self.diff.display_path()), false)];
         for hunk in &self.diff.hunks {
-            for line in &hunk.lines {
-                if index == current {
-                    return Some(line.clone());
-                }
-                current += 1;
+            if self.hunk_staging.is_some() {
+                rows.push((header(hunk.header.clone()), true));
             }
+            rows.extend(hunk.lines.iter().map(|line| (line.clone(), false)));
         }
-        None
+        rows
     }
 
     fn line_highlights(&mut self, content: &str) -> Vec<(Range<usize>, HighlightStyle)> {
@@ -355,6 +477,7 @@ impl GitDiffView {
 }
 
 impl EventEmitter<()> for GitDiffView {}
+impl EventEmitter<GitDiffHunkToggled> for GitDiffView {}
 
 impl Focusable for GitDiffView {
     fn focus_handle(&self, _cx: &App) -> FocusHandle {
@@ -373,17 +496,22 @@ impl Render for GitDiffView {
 
         let line_count = self.cached_lines.len();
         let cached_lines = self.cached_lines.clone();
+        let hunk_staging = self.hunk_staging;
+        let view = cx.weak_entity();
         let bottom_inset = f32::max(platform_bridge::home_indicator_inset(), BOTTOM_INSET_MIN);
-        let extra_items = (bottom_inset / LINE_HEIGHT).ceil() as usize;
+        let extra_items = (bottom_inset / theme::editor_line_height()).ceil() as usize;
         let h_scroll_offset = self.h_scroll_offset;
         let scroll_y_lock = self.scroll_handle.0.borrow().base_handle.offset().y;
 
         let editor_theme = self.editor_theme.clone();
         let diff = editor_theme.diff.clone();
+        let code_font = settings::code_font(cx);
         let text_style = {
             let mut style = window.text_style();
             style.color = rgb(diff.body_text).into();
-            style.font_size = px(FONT_SIZE).into();
+            style.font_family = code_font.family_name();
+            style.font_fallbacks = Some(code_font.fallbacks());
+            style.font_size = px(theme::editor_font_size()).into();
             style
         };
 
@@ -405,7 +533,7 @@ impl Render for GitDiffView {
                         this.h_scroll_active = true;
                     }
                     if this.h_scroll_active && delta_x.abs() > 0.1 {
-                        let char_width = FONT_SIZE * 0.6;
+                        let char_width = theme::editor_font_size() * 0.6;
                         let max_offset = (this.max_line_chars as f32 * char_width).max(0.0);
                         this.h_scroll_offset =
                             (this.h_scroll_offset - delta_x).clamp(0.0, max_offset);
@@ -423,16 +551,20 @@ impl Render for GitDiffView {
                     let text_style = text_style.clone();
                     let diff = diff.clone();
                     let editor_theme = editor_theme.clone();
-                    move |range: Range<usize>, _window: &mut Window, _cx: &mut App| {
+                    move |range: Range<usize>, _window: &mut Window, cx: &mut App| {
                         range
                             .map(|i| {
                                 if i >= line_count {
-                                    return div().h(px(LINE_HEIGHT)).into_any_element();
+                                    return div()
+                                        .h(px(theme::editor_line_height()))
+                                        .into_any_element();
                                 }
 
                                 let cached = &cached_lines[i];
                                 let Some(line) = &cached.line else {
-                                    return div().h(px(LINE_HEIGHT)).into_any_element();
+                                    return div()
+                                        .h(px(theme::editor_line_height()))
+                                        .into_any_element();
                                 };
 
                                 let (bg_color, gutter_text) = match line.kind {
@@ -462,19 +594,61 @@ impl Render for GitDiffView {
 
                                 let content = &line.content;
 
+                                if let (true, Some(staging)) = (cached.is_hunk_header, hunk_staging)
+                                {
+                                    let view = view.clone();
+                                    let header = content.clone();
+                                    return div()
+                                        .w_full()
+                                        .flex()
+                                        .flex_row()
+                                        .items_center()
+                                        .justify_between()
+                                        .h(px(theme::editor_line_height()))
+                                        .bg(bg_color)
+                                        .px_2()
+                                        .gap_2()
+                                        .child(
+                                            div()
+                                                .flex_1()
+                                                .min_w_0()
+                                                .truncate()
+                                                .text_color(rgb(diff.gutter_text))
+                                                .text_size(px(theme::editor_gutter_font_size()))
+                                                .child(content.clone()),
+                                        )
+                                        .child(
+                                            div()
+                                                .id(("git-diff-hunk", i))
+                                                .flex_none()
+                                                .px_2()
+                                                .cursor_pointer()
+                                                .text_size(px(theme::editor_gutter_font_size()))
+                                                .text_color(rgb(theme::text_secondary(cx)))
+                                                .on_press(move |_, _, cx| {
+                                                    let header = header.clone();
+                                                    let _ = view.update(cx, |_, cx| {
+                                                        cx.emit(GitDiffHunkToggled { header });
+                                                    });
+                                                })
+                                                .child(staging.label()),
+                                        )
+                                        .into_any_element();
+                                }
+
                                 if line.kind == DiffLineKind::Header {
                                     return div()
                                         .w_full()
                                         .flex()
                                         .flex_row()
-                                        .h(px(LINE_HEIGHT))
+                                        .h(px(theme::editor_line_height()))
                                         .bg(bg_color)
                                         .px_2()
                                         .items_center()
                                         .child(
                                             div()
                                                 .text_color(rgb(diff.header_text))
-                                                .text_size(px(FONT_SIZE))
+                                                .text_size(px(theme::editor_font_size()))
                                                 .child(content.clone()),
                                         )
                                         .into_any_element();
@@ -494,24 +668,24 @@ impl Render for GitDiffView {
                                     .w_full()
                                     .flex()
                                     .flex_row()
-                                    .h(px(LINE_HEIGHT))
+                                    .h(px(theme::editor_line_height()))
                                     .bg(bg_color)
                                     .child(
                                         div()
-                                            .w(px(GUTTER_WIDTH))
-                                            .h(px(LINE_HEIGHT))
+                                            .w(px(theme::editor_gutter_width()))
+                                            .h(px(theme::editor_line_height()))
                                             .flex()
                                             .items_center()
                                             .justify_end()
                                             .pr_2()
                                             .text_color(rgb(diff.gutter_text))
-                                            .text_size(px(GUTTER_FONT_SIZE))
+                                            .text_size(px(theme::editor_gutter_font_size()))
                                             .child(gutter_text),
                                     )
                                     .child(
                                         div()
                                             .flex_1()
-                                            .h(px(LINE_HEIGHT))
+                                            .h(px(theme::editor_line_height()))
                                             .overflow_hidden()
                                             .relative()
                                             .child(
@@ -519,10 +693,10 @@ impl Render for GitDiffView {
                                                     .absolute()
                                                     .top(px(0.0))
                                                     .left(px(-h_scroll_offset))
-                                                    .h(px(LINE_HEIGHT))
+                                                    .h(px(theme::editor_line_height()))
                                                     .flex()
                                                     .items_center()
-                                                    .text_size(px(FONT_SIZE))
+                                                    .text_size(px(theme::editor_font_size()))
                                                     .relative()
                                                     .child(styled_text),
                                             ),
@@ -1,15 +1,29 @@
 //! GitSidebar - Scrollable git file list for the drawer
 //!
 //! Shows staged/unstaged/untracked files with expand/collapse sections,
-//! commit controls, and branch info. Emits GitFileSelected when a file is tapped.
-//! Also owns the git state types used by the sidebar and app drawer.
+//! commit controls, and branch info. The file list is a `uniform_list`, so only
+//! visible rows are built however many files changed. Emits GitFileSelected when a file is tapped
+//! and GitBranchPickerRequested when the branch row is tapped, or
+//! GitFetchRequested / GitPullRequested / GitPushRequested /
+//! GitPullRequestRequested / GitRebaseRequested from the row's fetch, pull,
+//! push, PR, and rebase buttons. When the workdir is not a repository it
+//! shows an empty state whose button emits GitRepoPickerRequested; the same
+//! event comes from the repository button once one is picked. The commit
+//! composer offers a Conventional Commits type/scope picker, a subject-length
+//! counter, and GitCommitMessageRequested for an AI-suggested message.
+//! Also owns the git state types used by the sidebar and app drawer; list
+//! layout and composer state live in `GitSidebarModel`, which this view binds.
+
+use std::collections::HashSet;
 
 use gpui::prelude::FluentBuilder;
 use gpui::*;
 
+use super::git_sidebar_model::{GitListRow, GitSidebarModel, conventional_prefix, subject_len};
+use crate::platform_bridge::{self, HapticFeedback, ListPickerItem};
 use crate::theme;
 use crate::ui::input::Input;
-use crate::ui::{InputChanged, InputSubmit};
+use crate::ui::{InputChanged, InputSubmit, PullScroll, PullToRefresh, pull_to_refresh};
 
 // ── Git state types ─────────────────────────────────────────────────────────
 
@@ -138,6 +152,23 @@ impl GitRepoState {
     pub fn total_untracked(&self) -> usize {
         self.untracked_files.len()
     }
+
+    /// Distinct changed paths and summed line counts across all sections.
+    pub fn change_totals(&self) -> (usize, usize, usize) {
+        let files = self
+            .staged_files
+            .iter()
+            .chain(&self.unstaged_files)
+            .chain(&self.untracked_files);
+        let mut paths: HashSet<&str> = HashSet::new();
+        let (mut insertions, mut deletions) = (0, 0);
+        for file in files {
+            paths.insert(&file.path);
+            insertions += file.insertions;
+            deletions += file.deletions;
+        }
+        (paths.len(), insertions, deletions)
+    }
 }
 
 // ── Sidebar view ────────────────────────────────────────────────────────────
@@ -161,6 +192,15 @@ pub struct GitFileLongPressed {
 
 impl EventEmitter<GitFileLongPressed> for GitSidebar {}
 
+/// Emitted by a row or section toggle; `stage` is false for unstage.
+#[derive(Clone, Debug)]
+pub struct GitStageRequested {
+    pub paths: Vec<String>,
+    pub stage: bool,
+}
+
+impl EventEmitter<GitStageRequested> for GitSidebar {}
+
 #[derive(Clone, Debug)]
 pub struct GitCommitRequested {
     pub message: String,
@@ -169,20 +209,139 @@ pub struct GitCommitRequested {
 
 impl EventEmitter<GitCommitRequested> for GitSidebar {}
 
-#[derive(Clone, Debug, PartialEq, Eq)]
-struct ActiveGitDiff {
-    path: String,
-    section: GitFileSection,
+/// Emitted when the branch row is tapped, to open the branch picker.
+#[derive(Clone, Debug)]
+pub struct GitBranchPickerRequested;
+
+impl EventEmitter<GitBranchPickerRequested> for GitSidebar {}
+
+/// Emitted when the branch row's fetch button is tapped, to fetch the current
+/// branch's remote.
+#[derive(Clone, Debug)]
+pub struct GitFetchRequested;
+
+impl EventEmitter<GitFetchRequested> for GitSidebar {}
+
+/// Emitted when the branch row's pull button is tapped, to pull the current
+/// branch's upstream into it.
+#[derive(Clone, Debug)]
+pub struct GitPullRequested;
+
+impl EventEmitter<GitPullRequested> for GitSidebar {}
+
+/// Emitted when the branch row's push button is tapped, to push the current
+/// branch to its remote.
+#[derive(Clone, Debug)]
+pub struct GitPushRequested;
+
+impl EventEmitter<GitPushRequested> for GitSidebar {}
+
+/// Emitted when the branch row's PR button is tapped, to open a pull/merge
+/// request for the current branch.
+#[derive(Clone, Debug)]
+pub struct GitPullRequestRequested;
+
+impl EventEmitter<GitPullRequestRequested> for GitSidebar {}
+
+/// Emitted when the branch row's rebase button is tapped, to open the rebase
+/// planner for the last few commits.
+#[derive(Clone, Debug)]
+pub struct GitRebaseRequested;
+
+impl EventEmitter<GitRebaseRequested> for GitSidebar {}
+
+/// Emitted from the not-a-repository empty state, or the branch row's
+/// repository button, to pick (or initialize) a repository under the workdir.
+#[derive(Clone, Debug)]
+pub struct GitRepoPickerRequested;
+
+impl EventEmitter<GitRepoPickerRequested> for GitSidebar {}
+
+/// Emitted when the suggest button is tapped; the owner fills the composer via
+/// `set_commit_message` once a message is generated.
+#[derive(Clone, Debug)]
+pub struct GitCommitMessageRequested;
+
+impl EventEmitter<GitCommitMessageRequested> for GitSidebar {}
+
+// ── Conventional commits ────────────────────────────────────────────────────
+
+/// Types offered by the composer's type picker, with a one-line hint each.
+const COMMIT_TYPES: &[(&str, &str)] = &[
+    ("feat", "A new feature"),
+    ("fix", "A bug fix"),
+    ("chore", "Maintenance that touches no source behavior"),
+    ("docs", "Documentation only"),
+    ("refactor", "Restructuring without behavior changes"),
+    ("test", "Adding or fixing tests"),
+    ("perf", "A performance improvement"),
+    ("build", "Build system or dependencies"),
+    ("ci", "CI configuration"),
+    ("style", "Formatting, whitespace"),
+    ("revert", "Reverts a previous commit"),
+];
+/// Subject length the counter starts warning at.
+const SUBJECT_SOFT_LIMIT: usize = 50;
+/// Subject length past which the counter turns red; git tooling truncates here.
+const SUBJECT_HARD_LIMIT: usize = 72;
+
+/// Small icon-and-label button on the branch row.
+fn branch_row_button(
+    id: &'static str,
+    icon: &'static str,
+    label: &'static str,
+    cx: &App,
+) -> Stateful<Div> {
+    div()
+        .id(id)
+        .flex()
+        .flex_row()
+        .items_center()
+        .gap(px(4.0))
+        .flex_none()
+        .cursor_pointer()
+        .child(
+            svg()
+                .path(icon)
+                .size(px(12.0))
+                .text_color(rgb(theme::text_muted(cx))),
+        )
+        .child(
+            div()
+                .text_size(theme::scaled(theme::FONT_DETAIL))
+                .text_color(rgb(theme::text_secondary(cx)))
+                .child(label),
+        )
+}
+
+/// Small stage/unstage toggle; staged entries show minus, others plus.
+fn stage_toggle(id: impl Into<ElementId>, stage: bool, cx: &App) -> Stateful<Div> {
+    div()
+        .id(id)
+        .flex()
+        .items_center()
+        .justify_center()
+        .flex_none()
+        .size(px(theme::PANEL_ITEM_HEIGHT - 8.0))
+        .cursor_pointer()
+        .child(
+            svg()
+                .path(if stage {
+                    "icons/plus.svg"
+                } else {
+                    "icons/minus.svg"
+                })
+                .size(px(12.0))
+                .text_color(rgb(theme::text_muted(cx))),
+        )
 }
 
 pub struct GitSidebar {
-    repo_state: GitRepoState,
-    section_expanded: [bool; 3], // [staged, unstaged, untracked]
+    model: GitSidebarModel,
     focus_handle: FocusHandle,
     commit_input: Entity<Input>,
-    commit_message: String,
-    committing: bool,
-    active_diff: Option<ActiveGitDiff>,
+    scroll_handle: UniformListScrollHandle,
+    pull_refresh: Entity<PullToRefresh>,
     _subscriptions: Vec<Subscription>,
 }
 
@@ -202,8 +361,9 @@ impl GitSidebar {
         subscriptions.push(cx.subscribe(
             &commit_input,
             |this: &mut Self, _input, event: &InputChanged, cx| {
-                this.commit_message = event.value.clone();
-                cx.notify();
+                if this.model.set_commit_message(event.value.clone()) {
+                    cx.notify();
+                }
             },
         ));
         subscriptions.push(cx.subscribe(
@@ -214,96 +374,160 @@ impl GitSidebar {
         ));
 
         Self {
-            repo_state: GitRepoState {
-                branch: String::new(),
-                staged_files: Vec::new(),
-                unstaged_files: Vec::new(),
-                untracked_files: Vec::new(),
-            },
-            section_expanded: [true, true, true],
+            model: GitSidebarModel::new(),
             focus_handle: cx.focus_handle(),
             commit_input,
-            commit_message: String::new(),
-            committing: false,
-            active_diff: None,
+            scroll_handle: UniformListScrollHandle::new(),
+            pull_refresh: cx.new(|_| PullToRefresh::new()),
             _subscriptions: subscriptions,
         }
     }
 
     pub fn branch(&self) -> &str {
-        &self.repo_state.branch
+        self.model.branch()
+    }
+
+    /// Pull-to-refresh state for the file list; the owner reloads on its events.
+    pub fn pull_refresh(&self) -> &Entity<PullToRefresh> {
+        &self.pull_refresh
     }
 
     pub fn set_repo_state(&mut self, state: GitRepoState, cx: &mut Context<Self>) {
-        self.repo_state = state;
+        self.model.set_repo_state(state);
         cx.notify();
     }
 
+    pub fn set_no_repository(&mut self, no_repository: bool, cx: &mut Context<Self>) {
+        if self.model.set_no_repository(no_repository) {
+            cx.notify();
+        }
+    }
+
+    pub fn set_repository(&mut self, repository: Option<String>, cx: &mut Context<Self>) {
+        if self.model.set_repository(repository) {
+            cx.notify();
+        }
+    }
+
     pub fn set_active_diff(
         &mut self,
         path: String,
         section: GitFileSection,
         cx: &mut Context<Self>,
     ) {
-        let active_diff = Some(ActiveGitDiff { path, section });
-        if self.active_diff == active_diff {
+        if !self.model.set_active_diff(path, section) {
             return;
         }
-
-        self.active_diff = active_diff;
+        // Bring the row into view, e.g. after next/previous file navigation.
+        if let Some(row) = self.model.active_diff_row() {
+            self.scroll_handle.scroll_to_item(row, ScrollStrategy::Top);
+        }
         cx.notify();
     }
 
     pub fn clear_active_diff(&mut self, cx: &mut Context<Self>) {
-        if self.active_diff.is_none() {
-            return;
+        if self.model.clear_active_diff() {
+            cx.notify();
         }
-
-        self.active_diff = None;
-        cx.notify();
     }
 
     pub fn set_committing(&mut self, committing: bool, cx: &mut Context<Self>) {
-        self.committing = committing;
-        cx.notify();
+        if self.model.set_committing(committing) {
+            cx.notify();
+        }
     }
 
     pub fn clear_commit_message(&mut self, cx: &mut Context<Self>) {
-        self.commit_message.clear();
+        self.set_commit_message(String::new(), cx);
+    }
+
+    pub fn set_generating_message(&mut self, generating: bool, cx: &mut Context<Self>) {
+        if self.model.set_generating_message(generating) {
+            cx.notify();
+        }
+    }
+
+    pub fn set_suggest_available(&mut self, available: bool, cx: &mut Context<Self>) {
+        if self.model.set_suggest_available(available) {
+            cx.notify();
+        }
+    }
+
+    /// Replace the composer text, e.g. with a generated suggestion to edit.
+    pub fn set_commit_message(&mut self, message: String, cx: &mut Context<Self>) {
         self.commit_input
-            .update(cx, |input, _cx| input.set_value(String::new()));
+            .update(cx, |input, _cx| input.set_value(message.clone()));
+        self.model.set_commit_message(message);
         cx.notify();
     }
 
     fn toggle_section(&mut self, section: usize, cx: &mut Context<Self>) {
-        if section < 3 {
-            self.section_expanded[section] = !self.section_expanded[section];
+        if self.model.toggle_section(section) {
             cx.notify();
         }
     }
 
-    fn staged_paths(&self) -> Vec<String> {
-        self.repo_state
-            .staged_files
-            .iter()
-            .map(|file| file.path.clone())
-            .collect()
+    fn request_commit_message(&mut self, cx: &mut Context<Self>) {
+        if let Some(event) = self.model.suggest_message() {
+            platform_bridge::trigger_haptic(HapticFeedback::ImpactLight);
+            cx.emit(event);
+        }
     }
 
-    fn can_commit(&self) -> bool {
-        !self.committing
-            && !self.commit_message.trim().is_empty()
-            && !self.repo_state.staged_files.is_empty()
+    /// Pick a commit type, then a scope, and rewrite the message header.
+    fn pick_commit_type(&mut self, cx: &mut Context<Self>) {
+        platform_bridge::trigger_haptic(HapticFeedback::ImpactLight);
+        let current = conventional_prefix(self.model.commit_message());
+        let current_kind = current.map(|prefix| prefix.kind.to_string());
+        let current_scope = current
+            .and_then(|prefix| prefix.scope)
+            .unwrap_or_default()
+            .to_string();
+        let items = COMMIT_TYPES
+            .iter()
+            .map(|(kind, hint)| ListPickerItem {
+                label: kind.to_string(),
+                subtitle: Some(hint.to_string()),
+                image_name: (current_kind.as_deref() == Some(*kind))
+                    .then(|| "icons/check.svg".to_string()),
+                trailing_icon: None,
+            })
+            .collect();
+        let (kind_tx, kind_rx) = tokio::sync::oneshot::channel();
+        platform_bridge::show_list_picker("Commit type", "", items, move |selection| {
+            let _ = kind_tx.send(selection.map(|selection| selection.index));
+        });
+        cx.spawn(async move |this, cx| {
+            let Ok(Some(index)) = kind_rx.await else {
+                return;
+            };
+            let Some((kind, _)) = COMMIT_TYPES.get(index) else {
+                return;
+            };
+            let (scope_tx, scope_rx) = tokio::sync::oneshot::channel();
+            platform_bridge::show_text_input(
+                &format!("Scope for {kind}"),
+                "Optional, e.g. ui or parser",
+                &current_scope,
+                move |scope| {
+                    let _ = scope_tx.send(scope);
+                },
+            );
+            let Ok(Some(scope)) = scope_rx.await else {
+                return;
+            };
+            let _ = this.update(cx, |this, cx| {
+                let message = this.model.with_commit_type(kind, &scope);
+                this.set_commit_message(message, cx);
+            });
+        })
+        .detach();
     }
 
     fn request_commit(&mut self, cx: &mut Context<Self>) {
-        if !self.can_commit() {
-            return;
+        if let Some(event) = self.model.commit() {
+            cx.emit(event);
         }
-        cx.emit(GitCommitRequested {
-            message: self.commit_message.trim().to_string(),
-            paths: self.staged_paths(),
-        });
     }
 
     fn render_section_header(
@@ -313,11 +537,15 @@ impl GitSidebar {
         section_idx: usize,
         cx: &mut Context<Self>,
     ) -> impl IntoElement {
-        let is_expanded = self.section_expanded[section_idx];
+        let is_expanded = self.model.is_expanded(section_idx);
         let title = title.to_string();
+        let stage = section_idx != 0;
 
         div()
-            .id(section_idx)
+            .id(ElementId::NamedInteger(
+                "git-section".into(),
+                section_idx as u64,
+            ))
             .flex()
             .flex_row()
             .items_center()
@@ -348,18 +576,208 @@ impl GitSidebar {
                     .child(
                         div()
                             .text_color(rgb(theme::text_muted(cx)))
-                            .text_size(px(theme::FONT_DETAIL))
+                            .text_size(theme::scaled(theme::FONT_DETAIL))
                             .font_weight(FontWeight::MEDIUM)
                             .child(title),
                     ),
             )
             .child(
                 div()
-                    .px_1()
-                    .text_color(rgb(theme::text_muted(cx)))
-                    .text_size(px(theme::FONT_DETAIL))
-                    .child(count.to_string()),
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .gap_1()
+                    .when(count > 0, |el| {
+                        el.child(
+                            stage_toggle(
+                                ElementId::NamedInteger(
+                                    "git-section-stage".into(),
+                                    section_idx as u64,
+                                ),
+                                stage,
+                                cx,
+                            )
+                            .on_pointer_down(|_, _, cx| cx.stop_propagation())
+                            .on_press(cx.listener(
+                                move |this, _, _, cx| {
+                                    if let Some(event) = this.model.stage_section(section_idx) {
+                                        platform_bridge::trigger_haptic(
+                                            HapticFeedback::ImpactLight,
+                                        );
+                                        cx.emit(event);
+                                    }
+                                },
+                            )),
+                        )
+                    })
+                    .child(
+                        div()
+                            .px_1()
+                            .text_color(rgb(theme::text_muted(cx)))
+                            .text_size(theme::scaled(theme::FONT_DETAIL))
+                            .child(count.to_string()),
+                    ),
+            )
+    }
+
+    fn render_branch_row(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        if self.model.branch().is_empty() {
+            return None;
+        }
+        let repository_button = self
+            .model
+            .repository()
+            .map(str::to_string)
+            .map(|repository| {
+                div()
+                    .id("git-repository-button")
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .gap(px(4.0))
+                    .flex_none()
+                    .max_w(px(120.0))
+                    .cursor_pointer()
+                    .on_press(cx.listener(|_this, _event, _window, cx| {
+                        platform_bridge::trigger_haptic(HapticFeedback::ImpactLight);
+                        cx.emit(GitRepoPickerRequested);
+                    }))
+                    .child(
+                        svg()
+                            .path("icons/folder.svg")
+                            .size(px(ICON_SIZE))
+                            .text_color(rgb(theme::text_muted(cx))),
+                    )
+                    .child(
+                        div()
+                            .min_w_0()
+                            .truncate()
+                            .text_size(theme::scaled(theme::FONT_BODY))
+                            .text_color(rgb(theme::text_secondary(cx)))
+                            .child(repository),
+                    )
+            });
+        let branch_button = div()
+            .id("git-branch-button")
+            .flex()
+            .flex_row()
+            .items_center()
+            .gap(px(6.0))
+            .min_w_0()
+            .cursor_pointer()
+            .on_press(cx.listener(|_this, _event, _window, cx| {
+                platform_bridge::trigger_haptic(HapticFeedback::ImpactLight);
+                cx.emit(GitBranchPickerRequested);
+            }))
+            .child(
+                svg()
+                    .path("icons/git-branch.svg")
+                    .size(px(ICON_SIZE))
+                    .text_color(rgb(theme::text_muted(cx))),
+            )
+            .child(
+                div()
+                    .min_w_0()
+                    .truncate()
+                    .text_size(theme::scaled(theme::FONT_BODY))
+                    .text_color(rgb(theme::text_secondary(cx)))
+                    .child(self.model.branch().to_string()),
             )
+            .child(
+                svg()
+                    .path("icons/chevron-down.svg")
+                    .size(px(ICON_SIZE))
+                    .text_color(rgb(theme::text_muted(cx))),
+            );
+        let rebase_button =
+            branch_row_button("git-rebase-button", "icons/history.svg", "Rebase", cx).on_press(
+                cx.listener(|_this, _event, _window, cx| {
+                    platform_bridge::trigger_haptic(HapticFeedback::ImpactLight);
+                    cx.emit(GitRebaseRequested);
+                }),
+            );
+        let fetch_button =
+            branch_row_button("git-fetch-button", "icons/refresh-ccw.svg", "Fetch", cx).on_press(
+                cx.listener(|_this, _event, _window, cx| {
+                    platform_bridge::trigger_haptic(HapticFeedback::ImpactLight);
+                    cx.emit(GitFetchRequested);
+                }),
+            );
+        let pull_button =
+            branch_row_button("git-pull-button", "icons/chevron-down.svg", "Pull", cx).on_press(
+                cx.listener(|_this, _event, _window, cx| {
+                    platform_bridge::trigger_haptic(HapticFeedback::ImpactLight);
+                    cx.emit(GitPullRequested);
+                }),
+            );
+        let push_button = branch_row_button("git-push-button", "icons/chevron-up.svg", "Push", cx)
+            .on_press(cx.listener(|_this, _event, _window, cx| {
+                platform_bridge::trigger_haptic(HapticFeedback::ImpactLight);
+                cx.emit(GitPushRequested);
+            }));
+        let pull_request_button =
+            branch_row_button("git-pull-request-button", "icons/share.svg", "PR", cx).on_press(
+                cx.listener(|_this, _event, _window, cx| {
+                    platform_bridge::trigger_haptic(HapticFeedback::ImpactLight);
+                    cx.emit(GitPullRequestRequested);
+                }),
+            );
+        Some(
+            div()
+                .flex()
+                .flex_row()
+                .items_center()
+                .gap(px(theme::SPACING_SM))
+                .px(px(theme::DRAWER_PADDING))
+                .pt(px(theme::SPACING_SM))
+                .children(repository_button)
+                .child(branch_button)
+                .child(div().flex_1())
+                .child(rebase_button)
+                .child(fetch_button)
+                .child(pull_button)
+                .child(push_button)
+                .child(pull_request_button),
+        )
+    }
+
+    /// Compact stat line above the sections, e.g. `4 files changed +120 -30`.
+    fn render_change_summary(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let (files, insertions, deletions) = self.model.repo_state().change_totals();
+        if files == 0 {
+            return None;
+        }
+        let label = if files == 1 {
+            "1 file changed".to_string()
+        } else {
+            format!("{files} files changed")
+        };
+        Some(
+            div()
+                .flex()
+                .flex_row()
+                .items_center()
+                .gap(px(6.0))
+                .h(px(theme::PANEL_ITEM_HEIGHT))
+                .px(px(theme::DRAWER_PADDING))
+                .text_size(theme::scaled(theme::FONT_DETAIL))
+                .text_color(rgb(theme::text_muted(cx)))
+                .child(label)
+                .when(insertions > 0, |s| {
+                    s.child(
+                        div()
+                            .text_color(rgb(theme::git_added(cx)))
+                            .child(format!("+{}", insertions)),
+                    )
+                })
+                .when(deletions > 0, |s| {
+                    s.child(
+                        div()
+                            .text_color(rgb(theme::git_removed(cx)))
+                            .child(format!("-{}", deletions)),
+                    )
+                }),
+        )
     }
 
     fn render_file_entry(&self, file: &GitFileEntry, cx: &mut Context<Self>) -> impl IntoElement {
@@ -369,10 +787,9 @@ impl GitSidebar {
         let section = file.section;
         let insertions = file.insertions;
         let deletions = file.deletions;
-        let is_active = self
-            .active_diff
-            .as_ref()
-            .is_some_and(|active| active.path == file.path && active.section == file.section);
+        let stage = section != GitFileSection::Staged;
+        let stage_path = path.clone();
+        let is_active = self.model.is_active(file);
         let status_color = if is_active {
             theme::text_secondary(cx)
         } else {
@@ -384,8 +801,9 @@ impl GitSidebar {
             theme::text_secondary(cx)
         };
 
+        // Keyed by section and path: a partially staged file appears in two sections.
         let mut row = div()
-            .id(SharedString::from(path.clone()))
+            .id(SharedString::from(format!("git-file:{section:?}:{path}")))
             .w_full()
             .flex()
             .flex_row()
@@ -427,14 +845,14 @@ impl GitSidebar {
                             .w(px(ICON_SIZE))
                             .flex_shrink_0()
                             .text_color(rgb(status_color))
-                            .text_size(px(theme::FONT_BODY))
+                            .text_size(theme::scaled(theme::FONT_BODY))
                             .child(status.icon()),
                     )
                     .child(
                         div()
                             .flex_1()
                             .min_w_0()
-                            .text_size(px(theme::FONT_BODY))
+                            .text_size(theme::scaled(theme::FONT_BODY))
                             .when(is_active, |el| el.font_weight(FontWeight::MEDIUM))
                             .text_color(rgb(filename_color))
                             .truncate()
@@ -448,7 +866,7 @@ impl GitSidebar {
                     .flex_row()
                     .items_center()
                     .gap_1()
-                    .text_size(px(theme::FONT_DETAIL))
+                    .text_size(theme::scaled(theme::FONT_DETAIL))
                     .when(insertions > 0, |s| {
                         s.child(
                             div()
@@ -462,7 +880,22 @@ impl GitSidebar {
                                 .text_color(rgb(theme::text_muted(cx)))
                                 .child(format!("-{}", deletions)),
                         )
-                    }),
+                    })
+                    .child(
+                        stage_toggle(
+                            SharedString::from(format!("git-stage:{section:?}:{path}")),
+                            stage,
+                            cx,
+                        )
+                        .on_pointer_down(|_, _, cx| cx.stop_propagation())
+                        .on_press(cx.listener(move |_this, _, _, cx| {
+                            platform_bridge::trigger_haptic(HapticFeedback::ImpactLight);
+                            cx.emit(GitStageRequested {
+                                paths: vec![stage_path.clone()],
+                                stage,
+                            });
+                        })),
+                    ),
             );
 
         if is_active {
@@ -473,18 +906,22 @@ impl GitSidebar {
     }
 
     fn render_commit_button(&self, cx: &mut Context<Self>) -> impl IntoElement {
-        let is_enabled = self.can_commit();
-        let icon_path = if self.committing {
+        let is_enabled = self.model.can_commit();
+        let icon_path = if self.model.committing() {
             "icons/refresh-ccw.svg"
         } else {
             "icons/check.svg"
         };
-        let icon_color = if is_enabled || self.committing {
+        let icon_color = if is_enabled || self.model.committing() {
             theme::text_secondary(cx)
         } else {
             theme::text_muted(cx)
         };
-        let icon_size = if self.committing { px(14.0) } else { px(20.0) };
+        let icon_size = if self.model.committing() {
+            px(14.0)
+        } else {
+            px(20.0)
+        };
 
         div()
             .id("git-commit-button")
@@ -493,7 +930,7 @@ impl GitSidebar {
             .flex()
             .items_center()
             .justify_center()
-            .opacity(if is_enabled || self.committing {
+            .opacity(if is_enabled || self.model.committing() {
                 1.0
             } else {
                 0.35
@@ -514,7 +951,7 @@ impl GitSidebar {
     }
 
     fn render_commit_composer(&self, cx: &mut Context<Self>) -> impl IntoElement {
-        let explicit_multiline = self.commit_message.contains('\n');
+        let explicit_multiline = self.model.commit_message().contains('\n');
         div()
             .px(px(theme::DRAWER_PADDING))
             .pt(px(theme::SPACING_SM))
@@ -545,6 +982,125 @@ impl GitSidebar {
                             .child(self.render_commit_button(cx))
                     }),
             )
+            .child(self.render_commit_assist_row(cx))
+    }
+
+    /// Type picker, AI suggestion, and subject counter under the input.
+    fn render_commit_assist_row(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let type_label = conventional_prefix(self.model.commit_message())
+            .map(|prefix| match prefix.scope {
+                Some(scope) => format!("{}({scope})", prefix.kind),
+                None => prefix.kind.to_string(),
+            })
+            .unwrap_or_else(|| "Type".to_string());
+        let subject_len = subject_len(self.model.commit_message());
+        let counter_color = if subject_len > SUBJECT_HARD_LIMIT {
+            theme::accent_red(cx)
+        } else if subject_len > SUBJECT_SOFT_LIMIT {
+            theme::accent_yellow(cx)
+        } else {
+            theme::text_muted(cx)
+        };
+        let can_generate = self.model.can_generate_message();
+        let suggest_icon = if self.model.generating_message() {
+            "icons/refresh-ccw.svg"
+        } else {
+            "icons/zap.svg"
+        };
+
+        div()
+            .flex()
+            .flex_row()
+            .items_center()
+            .gap(px(theme::SPACING_SM))
+            .pt(px(theme::SPACING_XS))
+            .text_size(theme::scaled(theme::FONT_DETAIL))
+            .child(
+                div()
+                    .id("git-commit-type")
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .gap(px(2.0))
+                    .min_w_0()
+                    .cursor_pointer()
+                    .on_press(cx.listener(|this, _, _, cx| this.pick_commit_type(cx)))
+                    .child(
+                        div()
+                            .min_w_0()
+                            .truncate()
+                            .text_color(rgb(theme::text_secondary(cx)))
+                            .child(type_label),
+                    )
+                    .child(
+                        svg()
+                            .path("icons/chevron-down.svg")
+                            .size(px(12.0))
+                            .text_color(rgb(theme::text_muted(cx))),
+                    ),
+            )
+            .when(self.model.suggest_available(), |row| {
+                row.child(
+                    div()
+                        .id("git-commit-suggest")
+                        .flex()
+                        .flex_row()
+                        .items_center()
+                        .gap(px(2.0))
+                        .opacity(if can_generate || self.model.generating_message() {
+                            1.0
+                        } else {
+                            0.35
+                        })
+                        .cursor_pointer()
+                        .on_press(cx.listener(|this, _, _, cx| this.request_commit_message(cx)))
+                        .child(
+                            svg()
+                                .path(suggest_icon)
+                                .size(px(12.0))
+                                .text_color(rgb(theme::text_muted(cx))),
+                        )
+                        .child(div().text_color(rgb(theme::text_secondary(cx))).child(
+                            if self.model.generating_message() {
+                                "Generating…"
+                            } else {
+                                "Suggest"
+                            },
+                        )),
+                )
+            })
+            .child(div().flex_1())
+            .child(
+                div()
+                    .text_color(rgb(counter_color))
+                    .child(format!("{subject_len}/{SUBJECT_HARD_LIMIT}")),
+            )
+    }
+}
+
+impl GitSidebar {
+    fn render_list_row(&self, row: GitListRow, cx: &mut Context<Self>) -> AnyElement {
+        match row {
+            GitListRow::Summary => self
+                .render_change_summary(cx)
+                .map(IntoElement::into_any_element)
+                .unwrap_or_else(|| div().h(px(theme::PANEL_ITEM_HEIGHT)).into_any_element()),
+            GitListRow::Header(section) => {
+                let (title, count) = match section {
+                    0 => ("Staged changes", self.model.repo_state().total_staged()),
+                    1 => ("Changes", self.model.repo_state().total_unstaged()),
+                    _ => ("Untracked", self.model.repo_state().total_untracked()),
+                };
+                self.render_section_header(title, count, section, cx)
+                    .into_any_element()
+            }
+            GitListRow::File { section, index } => {
+                match self.model.section_files(section).get(index) {
+                    Some(file) => self.render_file_entry(file, cx).into_any_element(),
+                    None => div().h(px(theme::PANEL_ITEM_HEIGHT)).into_any_element(),
+                }
+            }
+        }
     }
 }
 
@@ -555,42 +1111,79 @@ impl Focusable for GitSidebar {
 }
 
 impl Render for GitSidebar {
-    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        // let branch = self.repo_state.branch.clone();
-
-        // Pre-compute entries
-        let staged_entries: Vec<AnyElement> = self
-            .repo_state
-            .staged_files
-            .iter()
-            .map(|f| self.render_file_entry(f, cx).into_any_element())
-            .collect();
-        let unstaged_entries: Vec<AnyElement> = self
-            .repo_state
-            .unstaged_files
-            .iter()
-            .map(|f| self.render_file_entry(f, cx).into_any_element())
-            .collect();
-        let untracked_entries: Vec<AnyElement> = self
-            .repo_state
-            .untracked_files
-            .iter()
-            .map(|f| self.render_file_entry(f, cx).into_any_element())
-            .collect();
-
-        let staged_header = self
-            .render_section_header("Staged changes", self.repo_state.total_staged(), 0, cx)
-            .into_any_element();
-        let unstaged_header = self
-            .render_section_header("Changes", self.repo_state.total_unstaged(), 1, cx)
-            .into_any_element();
-        let untracked_header = self
-            .render_section_header("Untracked", self.repo_state.total_untracked(), 2, cx)
-            .into_any_element();
+    /// Shown instead of the composer and file list when the workdir is not a
+    /// repository.
+    fn render_no_repository(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .flex()
+            .flex_col()
+            .items_center()
+            .gap(px(theme::SPACING_SM))
+            .px(px(theme::DRAWER_PADDING))
+            .pt(px(theme::SPACING_LG))
+            .child(
+                svg()
+                    .path("icons/git-branch.svg")
+                    .size(px(24.0))
+                    .text_color(rgb(theme::text_muted(cx))),
+            )
+            .child(
+                div()
+                    .text_size(theme::scaled(theme::FONT_BODY))
+                    .text_color(rgb(theme::text_secondary(cx)))
+                    .child("Not a git repository"),
+            )
+            .child(
+                div()
+                    .text_size(theme::scaled(theme::FONT_DETAIL))
+                    .text_color(rgb(theme::text_muted(cx)))
+                    .text_center()
+                    .child("Pick a repository inside the workdir, or initialize one here."),
+            )
+            .child(
+                div()
+                    .id("git-choose-repository-button")
+                    .mt(px(theme::SPACING_SM))
+                    .px(px(theme::SPACING_MD))
+                    .h(px(theme::PANEL_ITEM_HEIGHT))
+                    .flex()
+                    .items_center()
+                    .rounded(px(6.0))
+                    .border_1()
+                    .border_color(rgb(theme::border_default(cx)))
+                    .cursor_pointer()
+                    .text_size(theme::scaled(theme::FONT_BODY))
+                    .text_color(rgb(theme::text_primary(cx)))
+                    .on_press(cx.listener(|_this, _event, _window, cx| {
+                        platform_bridge::trigger_haptic(HapticFeedback::ImpactLight);
+                        cx.emit(GitRepoPickerRequested);
+                    }))
+                    .child("Choose Repository"),
+            )
+    }
 
-        let show_staged = self.section_expanded[0];
-        let show_unstaged = self.section_expanded[1];
-        let show_untracked = self.section_expanded[2];
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        if self.model.no_repository() {
+            return div()
+                .flex()
+                .flex_col()
+                .size_full()
+                .bg(rgb(theme::bg_primary(cx)))
+                .child(self.render_no_repository(cx))
+                .into_any_element();
+        }
+        let list = uniform_list(
+            "git-sidebar-files",
+            self.model.rows().len(),
+            cx.processor(|this, range: std::ops::Range<usize>, _window, cx| {
+                range
+                    .filter_map(|ix| this.model.rows().get(ix).copied())
+                    .map(|row| this.render_list_row(row, cx))
+                    .collect::<Vec<_>>()
+            }),
+        )
+        .track_scroll(&self.scroll_handle)
+        .size_full();
 
         div()
             .track_focus(&self.focus_handle)
@@ -601,19 +1194,16 @@ impl Render for GitSidebar {
             .flex_col()
             .size_full()
             .bg(rgb(theme::bg_primary(cx)))
+            .children(self.render_branch_row(cx))
             .child(self.render_commit_composer(cx))
             // File sections (scrollable)
-            .child(
-                div()
-                    .id("git-sidebar-files")
-                    .flex_1()
-                    .overflow_y_scroll()
-                    .child(staged_header)
-                    .when(show_staged, |el| el.children(staged_entries))
-                    .child(unstaged_header)
-                    .when(show_unstaged, |el| el.children(unstaged_entries))
-                    .child(untracked_header)
-                    .when(show_untracked, |el| el.children(untracked_entries)),
-            )
+            .child(div().flex_1().min_h_0().child(pull_to_refresh(
+                "git-sidebar-pull",
+                &self.pull_refresh,
+                PullScroll::UniformList(self.scroll_handle.clone()),
+                list,
+                cx,
+            )))
+            .into_any_element()
     }
 }
@@ -0,0 +1,535 @@
+//! GitSidebarModel - state and reducers behind `GitSidebar`
+//!
+//! Plain Rust with no GPUI types: the sidebar forwards taps and host results
+//! here and emits whatever event a reducer hands back, so list layout, section
+//! toggles, composer rules, and the commands they produce are tested without a
+//! window. Reducers return whether anything visible changed so the view knows
+//! when to notify.
+
+use super::git_sidebar::{
+    GitCommitMessageRequested, GitCommitRequested, GitFileEntry, GitFileSection, GitRepoState,
+    GitStageRequested,
+};
+
+// ── Conventional commits ────────────────────────────────────────────────────
+
+/// The `type(scope)!: ` header at the start of a commit message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) struct ConventionalPrefix<'a> {
+    pub kind: &'a str,
+    pub scope: Option<&'a str>,
+    pub breaking: bool,
+    /// Byte length of the header, including the `: ` separator.
+    pub len: usize,
+}
+
+pub(super) fn conventional_prefix(message: &str) -> Option<ConventionalPrefix<'_>> {
+    let colon = message.find(": ")?;
+    let header = &message[..colon];
+    let (head, breaking) = match header.strip_suffix('!') {
+        Some(head) => (head, true),
+        None => (header, false),
+    };
+    let (kind, scope) = match head.split_once('(') {
+        Some((kind, rest)) => (kind, Some(rest.strip_suffix(')')?)),
+        None => (head, None),
+    };
+    if kind.is_empty() || !kind.chars().all(|c| c.is_ascii_lowercase()) {
+        return None;
+    }
+    if scope.is_some_and(|scope| scope.contains(['(', ')', '\n'])) {
+        return None;
+    }
+    Some(ConventionalPrefix {
+        kind,
+        scope,
+        breaking,
+        len: colon + 2,
+    })
+}
+
+/// Replace (or add) the message's `type(scope): ` header, keeping a `!`
+/// breaking-change marker and everything after the header.
+pub(super) fn with_conventional_prefix(message: &str, kind: &str, scope: &str) -> String {
+    let (breaking, rest) = match conventional_prefix(message) {
+        Some(prefix) => (prefix.breaking, &message[prefix.len..]),
+        None => (false, message.trim_start()),
+    };
+    let scope = scope.trim();
+    let mut header = kind.to_string();
+    if !scope.is_empty() {
+        header.push('(');
+        header.push_str(scope);
+        header.push(')');
+    }
+    if breaking {
+        header.push('!');
+    }
+    format!("{header}: {rest}")
+}
+
+/// Characters in the message's first line.
+pub(super) fn subject_len(message: &str) -> usize {
+    message.lines().next().unwrap_or_default().chars().count()
+}
+
+// ── File list ───────────────────────────────────────────────────────────────
+
+/// One row of the virtualized file list.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum GitListRow {
+    Summary,
+    /// Section header, by index into `section_expanded`.
+    Header(usize),
+    /// File at `index` within the section's file list.
+    File {
+        section: usize,
+        index: usize,
+    },
+}
+
+/// Flatten the sections into list rows, skipping files of collapsed sections.
+fn git_list_rows(state: &GitRepoState, expanded: [bool; 3]) -> Vec<GitListRow> {
+    let sections = [
+        &state.staged_files,
+        &state.unstaged_files,
+        &state.untracked_files,
+    ];
+    let mut rows = Vec::with_capacity(4 + sections.iter().map(|files| files.len()).sum::<usize>());
+    if state.change_totals().0 > 0 {
+        rows.push(GitListRow::Summary);
+    }
+    for (section, files) in sections.into_iter().enumerate() {
+        rows.push(GitListRow::Header(section));
+        if expanded[section] {
+            rows.extend((0..files.len()).map(|index| GitListRow::File { section, index }));
+        }
+    }
+    rows
+}
+
+fn section_index(section: GitFileSection) -> usize {
+    match section {
+        GitFileSection::Staged => 0,
+        GitFileSection::Unstaged => 1,
+        GitFileSection::Untracked => 2,
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct ActiveGitDiff {
+    path: String,
+    section: GitFileSection,
+}
+
+// ── Model ───────────────────────────────────────────────────────────────────
+
+pub struct GitSidebarModel {
+    repo_state: GitRepoState,
+    section_expanded: [bool; 3], // [staged, unstaged, untracked]
+    commit_message: String,
+    committing: bool,
+    generating_message: bool,
+    /// False when the host is too old for `AiPromptV2`; hides "Suggest".
+    suggest_available: bool,
+    active_diff: Option<ActiveGitDiff>,
+    /// The workdir is not a repository and none is picked; shows the picker
+    /// empty state instead of the file list.
+    no_repository: bool,
+    /// Workdir-relative repository picked for this session, if any.
+    repository: Option<String>,
+    /// Rows for the file list; rebuilt when repo state or expansion changes.
+    rows: Vec<GitListRow>,
+}
+
+impl Default for GitSidebarModel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GitSidebarModel {
+    pub fn new() -> Self {
+        let mut model = Self {
+            repo_state: GitRepoState {
+                branch: String::new(),
+                staged_files: Vec::new(),
+                unstaged_files: Vec::new(),
+                untracked_files: Vec::new(),
+            },
+            section_expanded: [true, true, true],
+            commit_message: String::new(),
+            committing: false,
+            generating_message: false,
+            suggest_available: true,
+            active_diff: None,
+            no_repository: false,
+            repository: None,
+            rows: Vec::new(),
+        };
+        model.rebuild_rows();
+        model
+    }
+
+    pub fn repo_state(&self) -> &GitRepoState {
+        &self.repo_state
+    }
+
+    pub fn branch(&self) -> &str {
+        &self.repo_state.branch
+    }
+
+    pub fn commit_message(&self) -> &str {
+        &self.commit_message
+    }
+
+    pub fn committing(&self) -> bool {
+        self.committing
+    }
+
+    pub fn generating_message(&self) -> bool {
+        self.generating_message
+    }
+
+    pub fn suggest_available(&self) -> bool {
+        self.suggest_available
+    }
+
+    pub fn no_repository(&self) -> bool {
+        self.no_repository
+    }
+
+    pub fn repository(&self) -> Option<&str> {
+        self.repository.as_deref()
+    }
+
+    pub fn is_expanded(&self, section: usize) -> bool {
+        self.section_expanded.get(section).copied().unwrap_or(false)
+    }
+
+    pub(super) fn rows(&self) -> &[GitListRow] {
+        &self.rows
+    }
+
+    pub fn section_files(&self, section: usize) -> &[GitFileEntry] {
+        match section {
+            0 => &self.repo_state.staged_files,
+            1 => &self.repo_state.unstaged_files,
+            _ => &self.repo_state.untracked_files,
+        }
+    }
+
+    /// Whether `file` is the one the diff view shows.
+    pub fn is_active(&self, file: &GitFileEntry) -> bool {
+        self.active_diff
+            .as_ref()
+            .is_some_and(|active| active.path == file.path && active.section == file.section)
+    }
+
+    /// List row of the active file, for scrolling it into view.
+    pub fn active_diff_row(&self) -> Option<usize> {
+        let active = self.active_diff.as_ref()?;
+        let section = section_index(active.section);
+        self.rows.iter().position(|row| match *row {
+            GitListRow::File { section: s, index } => {
+                s == section
+                    && self
+                        .section_files(s)
+                        .get(index)
+                        .is_some_and(|file| file.path == active.path)
+            }
+            _ => false,
+        })
+    }
+
+    pub fn can_commit(&self) -> bool {
+        !self.committing
+            && !self.commit_message.trim().is_empty()
+            && !self.repo_state.staged_files.is_empty()
+    }
+
+    pub fn can_generate_message(&self) -> bool {
+        self.suggest_available
+            && !self.committing
+            && !self.generating_message
+            && !self.repo_state.staged_files.is_empty()
+    }
+
+    // ── Reducers ──
+
+    pub fn set_repo_state(&mut self, state: GitRepoState) {
+        self.repo_state = state;
+        self.rebuild_rows();
+    }
+
+    pub fn set_no_repository(&mut self, no_repository: bool) -> bool {
+        replace_if_changed(&mut self.no_repository, no_repository)
+    }
+
+    pub fn set_repository(&mut self, repository: Option<String>) -> bool {
+        replace_if_changed(&mut self.repository, repository)
+    }
+
+    pub fn set_active_diff(&mut self, path: String, section: GitFileSection) -> bool {
+        replace_if_changed(&mut self.active_diff, Some(ActiveGitDiff { path, section }))
+    }
+
+    pub fn clear_active_diff(&mut self) -> bool {
+        replace_if_changed(&mut self.active_diff, None)
+    }
+
+    pub fn set_committing(&mut self, committing: bool) -> bool {
+        replace_if_changed(&mut self.committing, committing)
+    }
+
+    pub fn set_generating_message(&mut self, generating: bool) -> bool {
+        replace_if_changed(&mut self.generating_message, generating)
+    }
+
+    pub fn set_suggest_available(&mut self, available: bool) -> bool {
+        replace_if_changed(&mut self.suggest_available, available)
+    }
+
+    /// Track the composer text as typed.
+    pub fn set_commit_message(&mut self, message: String) -> bool {
+        replace_if_changed(&mut self.commit_message, message)
+    }
+
+    pub fn toggle_section(&mut self, section: usize) -> bool {
+        let Some(expanded) = self.section_expanded.get_mut(section) else {
+            return false;
+        };
+        *expanded = !*expanded;
+        self.rebuild_rows();
+        true
+    }
+
+    /// The composer text with its Conventional Commits header set to `kind`
+    /// and `scope`; the view writes it back into the input.
+    pub fn with_commit_type(&self, kind: &str, scope: &str) -> String {
+        with_conventional_prefix(&self.commit_message, kind, scope)
+    }
+
+    fn rebuild_rows(&mut self) {
+        self.rows = git_list_rows(&self.repo_state, self.section_expanded);
+    }
+
+    // ── Commands ──
+
+    /// Commit the staged files with the trimmed composer text.
+    pub fn commit(&self) -> Option<GitCommitRequested> {
+        self.can_commit().then(|| GitCommitRequested {
+            message: self.commit_message.trim().to_string(),
+            paths: self
+                .repo_state
+                .staged_files
+                .iter()
+                .map(|file| file.path.clone())
+                .collect(),
+        })
+    }
+
+    pub fn suggest_message(&self) -> Option<GitCommitMessageRequested> {
+        self.can_generate_message()
+            .then_some(GitCommitMessageRequested)
+    }
+
+    /// Stage every file of an unstaged or untracked section, or unstage the
+    /// staged section. `None` when the section is empty.
+    pub fn stage_section(&self, section: usize) -> Option<GitStageRequested> {
+        let files = self.section_files(section);
+        if files.is_empty() {
+            return None;
+        }
+        Some(GitStageRequested {
+            paths: files.iter().map(|file| file.path.clone()).collect(),
+            stage: section != 0,
+        })
+    }
+}
+
+fn replace_if_changed<T: PartialEq>(slot: &mut T, value: T) -> bool {
+    if *slot == value {
+        return false;
+    }
+    *slot = value;
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::editor::git_sidebar::GitFileStatus;
+
+    fn state() -> GitRepoState {
+        GitRepoState {
+            branch: "main".to_string(),
+            staged_files: vec![GitFileEntry::new(
+                "src/lib.rs",
+                GitFileStatus::Modified,
+                GitFileSection::Staged,
+                3,
+                1,
+            )],
+            unstaged_files: vec![
+                GitFileEntry::new(
+                    "src/lib.rs",
+                    GitFileStatus::Modified,
+                    GitFileSection::Unstaged,
+                    2,
+                    0,
+                ),
+                GitFileEntry::new(
+                    "README.md",
+                    GitFileStatus::Modified,
+                    GitFileSection::Unstaged,
+                    1,
+                    1,
+                ),
+            ],
+            untracked_files: Vec::new(),
+        }
+    }
+
+    fn model() -> GitSidebarModel {
+        let mut model = GitSidebarModel::new();
+        model.set_repo_state(state());
+        model
+    }
+
+    #[test]
+    fn list_rows_follow_sections_and_skip_collapsed_files() {
+        let rows = git_list_rows(&state(), [true, false, true]);
+        assert_eq!(
+            rows,
+            vec![
+                GitListRow::Summary,
+                GitListRow::Header(0),
+                GitListRow::File {
+                    section: 0,
+                    index: 0
+                },
+                GitListRow::Header(1),
+                GitListRow::Header(2),
+            ]
+        );
+    }
+
+    #[test]
+    fn list_rows_omit_summary_for_a_clean_tree() {
+        let clean = GitRepoState {
+            branch: "main".to_string(),
+            staged_files: Vec::new(),
+            unstaged_files: Vec::new(),
+            untracked_files: Vec::new(),
+        };
+        assert_eq!(
+            git_list_rows(&clean, [true; 3]),
+            vec![
+                GitListRow::Header(0),
+                GitListRow::Header(1),
+                GitListRow::Header(2),
+            ]
+        );
+    }
+
+    #[test]
+    fn toggling_a_section_rebuilds_rows_and_moves_the_active_row() {
+        let mut model = model();
+        assert!(model.set_active_diff("README.md".into(), GitFileSection::Unstaged));
+        assert!(!model.set_active_diff("README.md".into(), GitFileSection::Unstaged));
+        // Summary, staged header + file, unstaged header + 2 files.
+        assert_eq!(model.active_diff_row(), Some(5));
+
+        assert!(model.toggle_section(0));
+        assert!(!model.is_expanded(0));
+        assert_eq!(model.active_diff_row(), Some(4));
+
+        assert!(model.toggle_section(1));
+        assert_eq!(model.active_diff_row(), None);
+        assert!(!model.toggle_section(3));
+
+        // The same path in another section is a different row.
+        let staged = &model.section_files(0)[0];
+        assert!(!model.is_active(staged));
+        assert!(model.clear_active_diff());
+        assert!(!model.clear_active_diff());
+    }
+
+    #[test]
+    fn commit_needs_a_message_staged_files_and_no_commit_in_flight() {
+        let mut model = model();
+        assert!(model.commit().is_none());
+
+        model.set_commit_message("  fix: retry uploads \n".into());
+        let commit = model.commit().expect("commit");
+        assert_eq!(commit.message, "fix: retry uploads");
+        assert_eq!(commit.paths, vec!["src/lib.rs".to_string()]);
+
+        model.set_committing(true);
+        assert!(model.commit().is_none());
+        model.set_committing(false);
+
+        let mut unstaged = state();
+        unstaged.staged_files.clear();
+        model.set_repo_state(unstaged);
+        assert!(model.commit().is_none());
+    }
+
+    #[test]
+    fn suggest_is_offered_only_when_the_host_supports_it_and_idle() {
+        let mut model = model();
+        assert!(model.suggest_message().is_some());
+
+        model.set_generating_message(true);
+        assert!(model.suggest_message().is_none());
+        model.set_generating_message(false);
+
+        assert!(model.set_suggest_available(false));
+        assert!(!model.set_suggest_available(false));
+        assert!(model.suggest_message().is_none());
+    }
+
+    #[test]
+    fn stage_section_stages_changes_and_unstages_the_staged_section() {
+        let model = model();
+        let stage = model.stage_section(1).expect("unstaged files");
+        assert!(stage.stage);
+        assert_eq!(
+            stage.paths,
+            vec!["src/lib.rs".to_string(), "README.md".to_string()]
+        );
+        let unstage = model.stage_section(0).expect("staged files");
+        assert!(!unstage.stage);
+        assert!(model.stage_section(2).is_none());
+    }
+
+    #[test]
+    fn commit_type_rewrites_the_composer_header() {
+        let mut model = model();
+        model.set_commit_message("feat(sync)!: drop v1 uploads".into());
+        assert_eq!(
+            model.with_commit_type("fix", " api "),
+            "fix(api)!: drop v1 uploads"
+        );
+    }
+
+    #[test]
+    fn conventional_prefix_is_replaced_and_keeps_the_breaking_marker() {
+        assert_eq!(
+            with_conventional_prefix("add retry to uploads", "feat", "sync"),
+            "feat(sync): add retry to uploads"
+        );
+        assert_eq!(
+            with_conventional_prefix("feat(sync)!: drop v1 uploads\n\nbody", "fix", ""),
+            "fix!: drop v1 uploads\n\nbody"
+        );
+        // A colon later in the subject is not a header.
+        assert_eq!(conventional_prefix("Update README: typo"), None);
+        assert_eq!(
+            conventional_prefix("docs(api): x").map(|prefix| (prefix.kind, prefix.scope)),
+            Some(("docs", Some("api")))
+        );
+        assert_eq!(subject_len("fix: é\nbody"), 6);
+    }
+}
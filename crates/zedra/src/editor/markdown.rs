@@ -13,6 +13,7 @@ use crate::fonts;
 use crate::platform_bridge;
 use crate::settings::{ThemeStateEvent, theme_state};
 use crate::theme::{self, ThemePreference};
+use crate::view_state::ViewState;
 use crate::workspace_action::AddSelectionToChat;
 
 // Enough offscreen content to keep fast mobile scrolls smooth without
@@ -20,8 +21,6 @@ use crate::workspace_action::AddSelectionToChat;
 const MARKDOWN_LIST_OVERDRAW_PX: f32 = 1200.0;
 const MARKDOWN_BOTTOM_INSET_MIN: f32 = 100.0;
 const MARKDOWN_LINK_HIT_SLOP: f32 = 8.0;
-const CODE_BLOCK_FONT_SIZE: f32 = theme::EDITOR_FONT_SIZE;
-const CODE_BLOCK_LINE_HEIGHT: f32 = theme::EDITOR_LINE_HEIGHT;
 const CODE_BLOCK_CHAR_WIDTH_FACTOR: f32 = 0.6;
 const CODE_BLOCK_TAB_WIDTH: usize = 4;
 const CODE_BLOCK_PADDING_X: f32 = theme::SPACING_SM;
@@ -124,6 +123,19 @@ impl MarkdownView {
         scroll_top.item_ix == 0 && scroll_top.offset_in_item <= px(0.5)
     }
 
+    /// First visible block, to restore when this document is shown again.
+    pub fn view_state(&self) -> ViewState {
+        ViewState::list(self.list_state.logical_scroll_top())
+    }
+
+    /// Restore a position from `view_state`. Call after `set_parsed_source`,
+    /// which resets the list to the top.
+    pub fn restore_view_state(&mut self, state: ViewState) {
+        if let Some(offset) = state.list_offset() {
+            self.list_state.scroll_to(offset);
+        }
+    }
+
     pub(crate) fn set_parsed_source(
         &mut self,
         parsed: ParsedMarkdownSource,
@@ -1668,8 +1680,8 @@ fn render_code_block_content(text: &str, key: &str, cx: &App) -> AnyElement {
             div()
                 .w_full()
                 .text_color(rgb(theme::text_primary(cx)))
-                .text_size(px(CODE_BLOCK_FONT_SIZE))
-                .line_height(px(CODE_BLOCK_LINE_HEIGHT))
+                .text_size(px(theme::editor_font_size()))
+                .line_height(px(theme::editor_line_height()))
                 .font_family(fonts::MONO_FONT_FAMILY)
                 .whitespace_nowrap()
                 .child(markdown_text(StyledText::new(line), "\n"))
@@ -1792,8 +1804,8 @@ fn render_block(
                             .flex_shrink_0()
                             .min_w(px(16.0))
                             .text_color(rgb(theme::text_muted(cx)))
-                            .text_size(px(theme::FONT_BODY))
-                            .line_height(px(theme::FONT_BODY + 6.0))
+                            .text_size(theme::scaled(theme::FONT_BODY))
+                            .line_height(theme::scaled(theme::FONT_BODY + 6.0))
                             .font_family(fonts::MONO_FONT_FAMILY)
                             .child(markdown_text(StyledText::new(marker), " ")),
                     )
@@ -1842,7 +1854,7 @@ fn render_block(
                             .border_color(rgb(theme::border_default(cx)))
                             .rounded(px(6.0))
                             .text_color(rgb(theme::text_muted(cx)))
-                            .text_size(px(theme::FONT_DETAIL))
+                            .text_size(theme::scaled(theme::FONT_DETAIL))
                             .font_family(fonts::MONO_FONT_FAMILY)
                             .child("Rendering diagram…")
                             .into_any_element(),
@@ -1858,7 +1870,7 @@ fn render_block(
                         div()
                             .mt(px(6.0))
                             .text_color(rgb(theme::text_muted(cx)))
-                            .text_size(px(theme::FONT_DETAIL))
+                            .text_size(theme::scaled(theme::FONT_DETAIL))
                             .font_family(fonts::MONO_FONT_FAMILY)
                             .child("Diagram could not be rendered.")
                             .into_any_element(),
@@ -1882,7 +1894,7 @@ fn render_block(
                         .mt(px(6.0))
                         .cursor_pointer()
                         .text_color(rgb(theme::accent_blue(cx)))
-                        .text_size(px(theme::FONT_DETAIL))
+                        .text_size(theme::scaled(theme::FONT_DETAIL))
                         .font_family(fonts::MONO_FONT_FAMILY)
                         .on_click(move |_, _window, cx| {
                             let _ = view.update(cx, |view, cx| {
@@ -1933,7 +1945,7 @@ fn code_block_content_min_width(text: &str) -> f32 {
         .max()
         .unwrap_or(1)
         .max(1);
-    (columns as f32 * CODE_BLOCK_FONT_SIZE * CODE_BLOCK_CHAR_WIDTH_FACTOR).ceil()
+    (columns as f32 * theme::editor_font_size() * CODE_BLOCK_CHAR_WIDTH_FACTOR).ceil()
 }
 
 fn code_block_display_columns(line: &str) -> usize {
@@ -1974,8 +1986,8 @@ fn render_html_comment(inner: &str, _key: String, cx: &App) -> AnyElement {
         .border_color(rgb(theme::border_subtle(cx)))
         .pl(px(10.0))
         .text_color(rgb(theme::text_muted(cx)))
-        .text_size(px(theme::FONT_DETAIL))
-        .line_height(px(theme::FONT_DETAIL + 5.0))
+        .text_size(theme::scaled(theme::FONT_DETAIL))
+        .line_height(theme::scaled(theme::FONT_DETAIL + 5.0))
         .font_family(fonts::MONO_FONT_FAMILY)
         .child(markdown_text(StyledText::new(inner.to_string()), "\n"))
         .into_any_element()
@@ -2125,8 +2137,8 @@ fn render_frontmatter(frontmatter: &FrontmatterBlock, key: String, cx: &App) ->
                         .border_r_1()
                         .border_color(rgb(theme::border_subtle(cx)))
                         .text_color(rgb(theme::text_muted(cx)))
-                        .text_size(px(theme::FONT_BODY))
-                        .line_height(px(theme::FONT_BODY + 6.0))
+                        .text_size(theme::scaled(theme::FONT_BODY))
+                        .line_height(theme::scaled(theme::FONT_BODY + 6.0))
                         .font_family(fonts::MONO_FONT_FAMILY)
                         .child(markdown_text(StyledText::new(row.key.clone()), "\n")),
                     div()
@@ -2197,8 +2209,8 @@ fn render_frontmatter_url(text: &str, key: String, cx: &App) -> AnyElement {
     div()
         .flex_none()
         .text_color(base.color)
-        .text_size(px(base.size))
-        .line_height(px(base.line_height))
+        .text_size(theme::scaled(base.size))
+        .line_height(theme::scaled(base.line_height))
         .font_family(base.font_family)
         .whitespace_nowrap()
         .child(text_element)
@@ -2209,8 +2221,8 @@ fn frontmatter_key_label(text: String, cx: &App) -> Div {
     div()
         .flex_none()
         .text_color(rgb(theme::text_muted(cx)))
-        .text_size(px(theme::FONT_BODY))
-        .line_height(px(theme::FONT_BODY + 6.0))
+        .text_size(theme::scaled(theme::FONT_BODY))
+        .line_height(theme::scaled(theme::FONT_BODY + 6.0))
         .font_family(fonts::MONO_FONT_FAMILY)
         .child(markdown_text(StyledText::new(text), " "))
 }
@@ -2316,7 +2328,8 @@ fn table_column_widths(table: &TableBlock) -> Vec<f32> {
 
 fn table_cell_min_width(cell: &[Inline]) -> f32 {
     let columns = inline_display_columns(cell).max(1);
-    (columns as f32 * theme::FONT_BODY * TABLE_CELL_CHAR_WIDTH_FACTOR + TABLE_CELL_PADDING_X * 2.0)
+    (columns as f32 * theme::FONT_BODY * theme::text_scale() * TABLE_CELL_CHAR_WIDTH_FACTOR
+        + TABLE_CELL_PADDING_X * 2.0)
         .ceil()
         .clamp(TABLE_CELL_MIN_WIDTH, TABLE_CELL_MAX_WIDTH)
 }
@@ -2433,8 +2446,8 @@ fn render_inline_block(
     div()
         .w_full()
         .text_color(base.color)
-        .text_size(px(base.size))
-        .line_height(px(base.line_height))
+        .text_size(theme::scaled(base.size))
+        .line_height(theme::scaled(base.line_height))
         .font_family(base.font_family)
         .when_some(base_weight, |this, weight| this.font_weight(weight))
         .whitespace_normal()
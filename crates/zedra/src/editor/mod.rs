@@ -1,13 +1,19 @@
 // Editor: text buffer, syntax highlighting, code editor, git diff
 
+pub mod blame;
 pub mod code_editor;
+pub mod editorconfig;
+pub mod find;
 pub mod git_diff_view;
 pub mod git_sidebar;
+pub mod git_sidebar_model;
 pub mod markdown;
 pub mod mermaid;
+pub mod save_cleanup;
 pub mod syntax_highlighter;
 pub mod syntax_theme;
 pub mod text_buffer;
+pub mod word_diff;
 
 pub use syntax_highlighter::Language;
 
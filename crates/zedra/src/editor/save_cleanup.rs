@@ -0,0 +1,187 @@
+//! Whitespace cleanup applied when the editor writes a file back to the host.
+//!
+//! Settings → Editor picks the defaults; a project's `.editorconfig` (see
+//! `editorconfig`) overrides them per file. Indentation is only rewritten
+//! when the setting is on; the target style comes from `.editorconfig` or,
+//! without one, from whichever style most lines of the file already use.
+
+use serde::{Deserialize, Serialize};
+
+use super::editorconfig::{EditorConfig, IndentStyle};
+use super::text_buffer::Buffer;
+
+/// Changed lines listed in the save preview before it summarizes the rest.
+const PREVIEW_LINES: usize = 8;
+
+/// Which transforms run when a file is saved.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SaveCleanup {
+    pub trim_trailing_whitespace: bool,
+    pub insert_final_newline: bool,
+    pub normalize_indentation: bool,
+    /// Show the changed lines and ask before writing.
+    pub preview: bool,
+}
+
+impl Default for SaveCleanup {
+    fn default() -> Self {
+        Self {
+            trim_trailing_whitespace: true,
+            insert_final_newline: true,
+            normalize_indentation: false,
+            preview: true,
+        }
+    }
+}
+
+/// What a cleanup changed, for the save preview.
+#[derive(Debug, Default)]
+pub struct CleanupSummary {
+    /// `(line number, before, after)` for each rewritten line.
+    pub changed_lines: Vec<(usize, String, String)>,
+    pub added_final_newline: bool,
+}
+
+impl CleanupSummary {
+    pub fn is_empty(&self) -> bool {
+        self.changed_lines.is_empty() && !self.added_final_newline
+    }
+
+    /// Diff-style preview; spaces show as `·` and tabs as `→` so whitespace
+    /// changes are visible.
+    pub fn preview(&self) -> String {
+        let visible = |text: &str| text.replace(' ', "·").replace('\t', "→");
+        let mut lines = Vec::new();
+        for (line, before, after) in self.changed_lines.iter().take(PREVIEW_LINES) {
+            lines.push(format!("Line {}", line + 1));
+            lines.push(format!("- {}", visible(before)));
+            lines.push(format!("+ {}", visible(after)));
+        }
+        if self.changed_lines.len() > PREVIEW_LINES {
+            lines.push(format!(
+                "…and {} more lines",
+                self.changed_lines.len() - PREVIEW_LINES
+            ));
+        }
+        if self.added_final_newline {
+            lines.push("Adds a newline at the end of the file".to_string());
+        }
+        lines.join("\n")
+    }
+}
+
+impl SaveCleanup {
+    /// Whether any transform is on once `config` is applied.
+    pub fn is_active(&self, config: &EditorConfig) -> bool {
+        config
+            .trim_trailing_whitespace
+            .unwrap_or(self.trim_trailing_whitespace)
+            || config
+                .insert_final_newline
+                .unwrap_or(self.insert_final_newline)
+            || self.normalize_indentation
+    }
+
+    /// Rewrite `buffer` in place and report what changed.
+    pub fn apply(&self, config: &EditorConfig, buffer: &mut Buffer) -> CleanupSummary {
+        let trim = config
+            .trim_trailing_whitespace
+            .unwrap_or(self.trim_trailing_whitespace);
+        let final_newline = config
+            .insert_final_newline
+            .unwrap_or(self.insert_final_newline);
+        let indent = if self.normalize_indentation {
+            config.indent_style.or_else(|| IndentStyle::detect(buffer))
+        } else {
+            None
+        };
+
+        let mut summary = CleanupSummary::default();
+        let mut lines = Vec::with_capacity(buffer.line_count());
+        for line in 0..buffer.line_count() {
+            let before = buffer.line_text(line);
+            let mut after = if trim {
+                before.trim_end_matches([' ', '\t']).to_string()
+            } else {
+                before.to_string()
+            };
+            if let Some(style) = indent {
+                let body = after.trim_start_matches([' ', '\t']);
+                let leading = &after[..after.len() - body.len()];
+                if !body.is_empty() {
+                    after = style.reindent(leading) + body;
+                }
+            }
+            if after != before {
+                summary
+                    .changed_lines
+                    .push((line, before.to_string(), after.clone()));
+            }
+            lines.push(after);
+        }
+        let mut text = lines.join("\n");
+        if final_newline && !text.is_empty() && !text.ends_with('\n') {
+            text.push('\n');
+            summary.added_final_newline = true;
+        }
+        if !summary.is_empty() {
+            buffer.set_text(text);
+        }
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trims_whitespace_and_adds_final_newline() {
+        let mut buffer = Buffer::new("fn main() {  \n\tlet a = 1;\t\n}".to_string());
+        let summary = SaveCleanup::default().apply(&EditorConfig::default(), &mut buffer);
+        assert_eq!(buffer.text(), "fn main() {\n\tlet a = 1;\n}\n");
+        assert_eq!(summary.changed_lines.len(), 2);
+        assert!(summary.added_final_newline);
+
+        let again = SaveCleanup::default().apply(&EditorConfig::default(), &mut buffer);
+        assert!(again.is_empty());
+    }
+
+    #[test]
+    fn normalizes_minority_indentation_to_the_files_style() {
+        let cleanup = SaveCleanup {
+            normalize_indentation: true,
+            ..SaveCleanup::default()
+        };
+        let mut buffer = Buffer::new("a:\n  b:\n    c\n\td\n".to_string());
+        cleanup.apply(&EditorConfig::default(), &mut buffer);
+        assert_eq!(buffer.text(), "a:\n  b:\n    c\n  d\n");
+    }
+
+    #[test]
+    fn editorconfig_overrides_settings() {
+        let source = "root = true\n\n[*]\ntrim_trailing_whitespace = true\n\n\
+                      [*.{md,markdown}]\ntrim_trailing_whitespace = false\n";
+        let mut markdown = EditorConfig::default();
+        assert!(markdown.merge(source, "", "docs/README.md"));
+        assert_eq!(markdown.trim_trailing_whitespace, Some(false));
+
+        let mut buffer = Buffer::new("line  \n".to_string());
+        let summary = SaveCleanup::default().apply(&markdown, &mut buffer);
+        assert!(summary.is_empty());
+        assert_eq!(buffer.text(), "line  \n");
+
+        let cleanup = SaveCleanup {
+            normalize_indentation: true,
+            ..SaveCleanup::default()
+        };
+        let tabs = EditorConfig {
+            indent_style: Some(IndentStyle::Tabs(4)),
+            ..EditorConfig::default()
+        };
+        let mut buffer = Buffer::new("a\n        b\n  c\n".to_string());
+        cleanup.apply(&tabs, &mut buffer);
+        assert_eq!(buffer.text(), "a\n\t\tb\n  c\n");
+    }
+}
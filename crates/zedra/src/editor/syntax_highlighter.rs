@@ -1,6 +1,8 @@
 use std::ops::Range;
 
-use tree_sitter::{Language as TSLanguage, Parser, Query, QueryCursor, StreamingIterator, Tree};
+use tree_sitter::{
+    InputEdit, Language as TSLanguage, Parser, Query, QueryCursor, StreamingIterator, Tree,
+};
 
 const RUST_HIGHLIGHTS: &str =
     include_str!("../../../../vendor/zed/crates/grammars/src/rust/highlights.scm");
@@ -219,6 +221,27 @@ impl Highlighter {
         }
     }
 
+    /// Apply `edit` to the previous tree and reparse the already-edited
+    /// `source` against it, so tree-sitter only re-reads what the edit touched.
+    /// Returns the byte ranges (in `source`) whose syntax may have changed:
+    /// the edited text plus every range the new tree reports as changed.
+    pub fn edit(&mut self, edit: &InputEdit, source: &str) -> Vec<Range<usize>> {
+        let (Some(parser), Some(old_tree)) = (&mut self.parser, &mut self.tree) else {
+            return Vec::new();
+        };
+        old_tree.edit(edit);
+        let mut changed = vec![edit.start_byte..edit.new_end_byte];
+        if let Some(new_tree) = parser.parse(source, Some(&*old_tree)) {
+            changed.extend(
+                old_tree
+                    .changed_ranges(&new_tree)
+                    .map(|range| range.start_byte..range.end_byte),
+            );
+            self.tree = Some(new_tree);
+        }
+        changed
+    }
+
     /// Parse without reusing the previous tree. Use when the new source is
     /// unrelated to the previous parse (e.g. a different file or diff line),
     /// to avoid stale byte offsets from the old tree causing out-of-bounds panics.
@@ -353,6 +376,38 @@ mod tests {
         assert!(ok, "one or more highlight queries failed to parse");
     }
 
+    #[test]
+    fn incremental_edit_matches_a_fresh_parse() {
+        let before = "fn main() {\n    let a = 1;\n}\n";
+        let after = "fn main() {\n    let a = \"one\";\n}\n";
+        let mut incremental = Highlighter::new(Language::Rust);
+        incremental.parse(before);
+        let start_byte = before.find('1').unwrap();
+        let changed = incremental.edit(
+            &InputEdit {
+                start_byte,
+                old_end_byte: start_byte + 1,
+                new_end_byte: start_byte + 5,
+                start_position: tree_sitter::Point::new(1, 12),
+                old_end_position: tree_sitter::Point::new(1, 13),
+                new_end_position: tree_sitter::Point::new(1, 17),
+            },
+            after,
+        );
+        assert!(
+            changed
+                .iter()
+                .any(|range| range.start <= start_byte && range.end >= start_byte + 5)
+        );
+
+        let mut fresh = Highlighter::new(Language::Rust);
+        fresh.parse(after);
+        assert_eq!(
+            incremental.highlights(after, 0..after.len()),
+            fresh.highlights(after, 0..after.len())
+        );
+    }
+
     #[test]
     fn highlight_js_and_cpp_produce_captures() {
         let plain_text = Highlighter::new(Language::PlainText);
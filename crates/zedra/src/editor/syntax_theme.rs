@@ -73,6 +73,15 @@ impl SyntaxTheme {
         Self { styles }
     }
 
+    /// Replace the style for `capture`, adding it if the theme has none.
+    pub fn with_style(mut self, capture: &str, style: HighlightStyle) -> Self {
+        match self.styles.iter_mut().find(|(name, _)| name == capture) {
+            Some((_, existing)) => *existing = style,
+            None => self.styles.push((capture.to_string(), style)),
+        }
+        self
+    }
+
     /// Look up the style for a capture name using longest prefix match.
     pub fn get(&self, capture_name: &str) -> Option<HighlightStyle> {
         let mut name = capture_name;
@@ -77,20 +77,35 @@ impl Buffer {
         (line_start + col).min(line_end)
     }
 
-    /// Insert `text` at `offset`, recomputing the line index.
+    /// Insert `text` at `offset`, patching only the line starts after it.
     pub fn insert(&mut self, offset: usize, new_text: &str) {
         let offset = offset.min(self.text.len());
         self.text.insert_str(offset, new_text);
-        self.line_starts = Self::compute_line_starts(&self.text);
+        let first_after = self.line_starts.partition_point(|&start| start <= offset);
+        for start in &mut self.line_starts[first_after..] {
+            *start += new_text.len();
+        }
+        let inserted = new_text
+            .bytes()
+            .enumerate()
+            .filter(|(_, byte)| *byte == b'\n')
+            .map(|(i, _)| offset + i + 1);
+        self.line_starts
+            .splice(first_after..first_after, inserted.collect::<Vec<_>>());
     }
 
-    /// Delete the byte range, recomputing the line index.
+    /// Delete the byte range, patching only the line starts after it.
     pub fn delete(&mut self, range: Range<usize>) {
         let start = range.start.min(self.text.len());
         let end = range.end.min(self.text.len());
         if start < end {
             self.text.drain(start..end);
-            self.line_starts = Self::compute_line_starts(&self.text);
+            let first_removed = self.line_starts.partition_point(|&line| line <= start);
+            let first_kept = self.line_starts.partition_point(|&line| line <= end);
+            self.line_starts.drain(first_removed..first_kept);
+            for line in &mut self.line_starts[first_removed..] {
+                *line -= end - start;
+            }
         }
     }
 
@@ -147,4 +162,23 @@ mod tests {
         buffer.delete(5..6);
         assert_eq!(buffer.text(), "helloworld");
     }
+
+    #[test]
+    fn test_edits_keep_line_index_in_sync() {
+        let mut buffer = Buffer::new("one\ntwo\nthree\n".to_string());
+        buffer.insert(4, "a\nb\n");
+        assert_eq!(
+            buffer.line_starts,
+            Buffer::compute_line_starts(buffer.text())
+        );
+        assert_eq!(buffer.line_text(3), "two");
+
+        buffer.delete(2..11);
+        assert_eq!(buffer.text(), "on\nthree\n");
+        assert_eq!(
+            buffer.line_starts,
+            Buffer::compute_line_starts(buffer.text())
+        );
+        assert_eq!(buffer.line_text(1), "three");
+    }
 }
@@ -0,0 +1,249 @@
+//! Intra-line (word-level) changes for unified diffs.
+//!
+//! A run of removed lines directly followed by a run of added lines is treated
+//! as a modification: the i-th removed line is paired with the i-th added line
+//! and the two are diffed token by token. Tokens are runs of word characters,
+//! runs of whitespace, or single punctuation characters, so `foo(bar)` →
+//! `foo(baz)` marks only `bar`/`baz`. Pairs that share too little are left
+//! unmarked; highlighting most of both lines adds noise, not signal.
+
+use std::ops::Range;
+
+use gpui::{HighlightStyle, Hsla};
+
+use super::git_diff_view::{DiffLine, DiffLineKind};
+
+/// Lines with more tokens than this are not diffed (minified code, data).
+const MAX_TOKENS: usize = 400;
+/// Minimum share of unchanged bytes for a pair to get word highlights.
+const MIN_SIMILARITY: f32 = 0.4;
+
+/// Fill `word_changes` on paired removed/added lines of one hunk.
+pub fn mark_word_changes(lines: &mut [DiffLine]) {
+    let mut i = 0;
+    while i < lines.len() {
+        if lines[i].kind != DiffLineKind::Removed {
+            i += 1;
+            continue;
+        }
+        let removed_start = i;
+        while i < lines.len() && lines[i].kind == DiffLineKind::Removed {
+            i += 1;
+        }
+        let added_start = i;
+        while i < lines.len() && lines[i].kind == DiffLineKind::Added {
+            i += 1;
+        }
+        let pairs = (added_start - removed_start).min(i - added_start);
+        for offset in 0..pairs {
+            let (old_index, new_index) = (removed_start + offset, added_start + offset);
+            if let Some((old, new)) =
+                word_changes(&lines[old_index].content, &lines[new_index].content)
+            {
+                lines[old_index].word_changes = old;
+                lines[new_index].word_changes = new;
+            }
+        }
+    }
+}
+
+/// Changed byte ranges in `old` and `new`, or `None` when the lines are too
+/// different (or too long) to be worth marking.
+pub fn word_changes(old: &str, new: &str) -> Option<(Vec<Range<usize>>, Vec<Range<usize>>)> {
+    let old_tokens = tokenize(old);
+    let new_tokens = tokenize(new);
+    if old_tokens.len() > MAX_TOKENS || new_tokens.len() > MAX_TOKENS {
+        return None;
+    }
+    let old_words: Vec<&str> = old_tokens.iter().map(|range| &old[range.clone()]).collect();
+    let new_words: Vec<&str> = new_tokens.iter().map(|range| &new[range.clone()]).collect();
+
+    // Longest common subsequence over tokens, filled from the end so the
+    // walk below can go front to back.
+    let (n, m) = (old_words.len(), new_words.len());
+    let mut lcs = vec![vec![0u16; m + 1]; n + 1];
+    for a in (0..n).rev() {
+        for b in (0..m).rev() {
+            lcs[a][b] = if old_words[a] == new_words[b] {
+                lcs[a + 1][b + 1] + 1
+            } else {
+                lcs[a + 1][b].max(lcs[a][b + 1])
+            };
+        }
+    }
+
+    let mut old_changed = Vec::new();
+    let mut new_changed = Vec::new();
+    let mut common_bytes = 0;
+    let (mut a, mut b) = (0, 0);
+    while a < n || b < m {
+        if a < n && b < m && old_words[a] == new_words[b] {
+            common_bytes += old_words[a].len();
+            a += 1;
+            b += 1;
+        } else if b == m || (a < n && lcs[a + 1][b] >= lcs[a][b + 1]) {
+            push_range(&mut old_changed, old_tokens[a].clone());
+            a += 1;
+        } else {
+            push_range(&mut new_changed, new_tokens[b].clone());
+            b += 1;
+        }
+    }
+
+    if old_changed.is_empty() && new_changed.is_empty() {
+        return None;
+    }
+    let total = old.len() + new.len();
+    if total == 0 || (2 * common_bytes) as f32 / (total as f32) < MIN_SIMILARITY {
+        return None;
+    }
+    Some((old_changed, new_changed))
+}
+
+/// Layer `background` under the changed ranges of a line's syntax highlights.
+/// Both inputs are sorted and non-overlapping; so is the result.
+pub fn overlay_word_changes(
+    highlights: Vec<(Range<usize>, HighlightStyle)>,
+    changes: &[Range<usize>],
+    background: Hsla,
+) -> Vec<(Range<usize>, HighlightStyle)> {
+    if changes.is_empty() {
+        return highlights;
+    }
+    let mut bounds: Vec<usize> = highlights
+        .iter()
+        .map(|(range, _)| range)
+        .chain(changes)
+        .flat_map(|range| [range.start, range.end])
+        .collect();
+    bounds.sort_unstable();
+    bounds.dedup();
+
+    let mut result = Vec::new();
+    for segment in bounds.windows(2) {
+        let (start, end) = (segment[0], segment[1]);
+        let syntax = highlights
+            .iter()
+            .find(|(range, _)| range.start <= start && end <= range.end)
+            .map(|(_, style)| *style);
+        let changed = changes
+            .iter()
+            .any(|range| range.start <= start && end <= range.end);
+        let style = match (syntax, changed) {
+            (style, true) => HighlightStyle {
+                background_color: Some(background),
+                ..style.unwrap_or_default()
+            },
+            (Some(style), false) => style,
+            (None, false) => continue,
+        };
+        result.push((start..end, style));
+    }
+    result
+}
+
+fn push_range(ranges: &mut Vec<Range<usize>>, range: Range<usize>) {
+    match ranges.last_mut() {
+        Some(last) if last.end == range.start => last.end = range.end,
+        _ => ranges.push(range),
+    }
+}
+
+fn tokenize(line: &str) -> Vec<Range<usize>> {
+    #[derive(PartialEq)]
+    enum Class {
+        Word,
+        Space,
+        Other,
+    }
+    let class = |c: char| {
+        if c.is_alphanumeric() || c == '_' {
+            Class::Word
+        } else if c.is_whitespace() {
+            Class::Space
+        } else {
+            Class::Other
+        }
+    };
+
+    let mut tokens: Vec<Range<usize>> = Vec::new();
+    let mut previous = None;
+    for (index, c) in line.char_indices() {
+        let current = class(c);
+        let extends = current != Class::Other && previous.as_ref() == Some(&current);
+        match tokens.last_mut() {
+            Some(last) if extends => last.end = index + c.len_utf8(),
+            _ => tokens.push(index..index + c.len_utf8()),
+        }
+        previous = Some(current);
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn changed<'a>(text: &'a str, ranges: &[Range<usize>]) -> Vec<&'a str> {
+        ranges.iter().map(|range| &text[range.clone()]).collect()
+    }
+
+    #[test]
+    fn marks_only_the_changed_tokens() {
+        let old = "let total = compute(items, 10);";
+        let new = "let total = compute(entries, 10)?;";
+        let (old_changed, new_changed) = word_changes(old, new).unwrap();
+        assert_eq!(changed(old, &old_changed), vec!["items"]);
+        assert_eq!(changed(new, &new_changed), vec!["entries", "?"]);
+
+        // Rewritten lines share too little to mark.
+        assert_eq!(word_changes("fn main() {", "struct Config;"), None);
+        assert_eq!(word_changes("same", "same"), None);
+    }
+
+    #[test]
+    fn pairs_removed_runs_with_following_added_runs() {
+        let line = |kind, content: &str| DiffLine {
+            kind,
+            old_line_num: None,
+            new_line_num: None,
+            content: content.to_string(),
+            word_changes: Vec::new(),
+        };
+        let mut lines = vec![
+            line(DiffLineKind::Unchanged, "fn f() {"),
+            line(DiffLineKind::Removed, "    let a = 1;"),
+            line(DiffLineKind::Removed, "    let b = 2;"),
+            line(DiffLineKind::Added, "    let a = 3;"),
+            line(DiffLineKind::Unchanged, "}"),
+            line(DiffLineKind::Added, "    let c = 4;"),
+        ];
+        mark_word_changes(&mut lines);
+        assert_eq!(
+            changed(&lines[1].content, &lines[1].word_changes),
+            vec!["1"]
+        );
+        assert_eq!(
+            changed(&lines[3].content, &lines[3].word_changes),
+            vec!["3"]
+        );
+        assert!(lines[2].word_changes.is_empty());
+        assert!(lines[5].word_changes.is_empty());
+    }
+
+    #[test]
+    fn overlay_splits_syntax_spans_at_change_bounds() {
+        let keyword = HighlightStyle {
+            font_weight: Some(gpui::FontWeight::BOLD),
+            ..Default::default()
+        };
+        let background = gpui::red();
+        let result = overlay_word_changes(vec![(0..6, keyword)], &[4..9], background);
+        let ranges: Vec<_> = result.iter().map(|(range, _)| range.clone()).collect();
+        assert_eq!(ranges, vec![0..4, 4..6, 6..9]);
+        assert_eq!(result[0].1.background_color, None);
+        assert_eq!(result[1].1.font_weight, Some(gpui::FontWeight::BOLD));
+        assert_eq!(result[1].1.background_color, Some(background));
+        assert_eq!(result[2].1.font_weight, None);
+    }
+}
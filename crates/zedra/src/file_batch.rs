@@ -0,0 +1,533 @@
+// Batch file operations started from the file explorer's selection mode.
+//
+// Deletes go out in `FsRemove` chunks and moves one `FsRename` per entry, so
+// progress advances as the host answers and Cancel takes effect before the
+// next request; whatever already finished on the host stays done. An archive
+// is a single `FsArchive` the host packs in one go. Batches that outlast
+// `PROGRESS_SHEET_DELAY` show `FileBatchSheet`; the result is reported once
+// the batch stops.
+
+use std::path::Path;
+use std::time::Duration;
+
+use gpui::prelude::FluentBuilder as _;
+use gpui::*;
+use zedra_rpc::proto::FS_CROSS_DEVICE_ERROR;
+use zedra_session::SessionHandle;
+
+use crate::button::outline_button;
+use crate::platform_bridge::{
+    self, AlertButton, CustomSheetDetent, CustomSheetOptions, NativeNotificationKind,
+    NativeNotificationOptions,
+};
+use crate::telemetry::view_telemetry;
+use crate::theme;
+
+/// Paths per `FsRemove`; well under `FS_REMOVE_MAX_PATHS` so progress moves
+/// on large selections.
+const REMOVE_CHUNK: usize = 16;
+
+/// Batches that finish sooner never show the progress sheet.
+const PROGRESS_SHEET_DELAY: Duration = Duration::from_millis(600);
+
+/// Names listed in a confirmation or summary before "and N more".
+const MAX_LISTED: usize = 5;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BatchOp {
+    Delete,
+    /// Move every entry into this host directory.
+    Move {
+        to_dir: String,
+    },
+    /// Pack every entry into a new `.tar.gz` at this host path.
+    Archive {
+        to: String,
+    },
+}
+
+impl BatchOp {
+    fn progress_verb(&self) -> &'static str {
+        match self {
+            BatchOp::Delete => "Deleting",
+            BatchOp::Move { .. } => "Moving",
+            BatchOp::Archive { .. } => "Archiving",
+        }
+    }
+
+    fn done_verb(&self) -> &'static str {
+        match self {
+            BatchOp::Delete => "Deleted",
+            BatchOp::Move { .. } => "Moved",
+            BatchOp::Archive { .. } => "Archived",
+        }
+    }
+}
+
+/// "1 item" / "3 items".
+pub fn item_count(count: usize) -> String {
+    if count == 1 {
+        "1 item".to_string()
+    } else {
+        format!("{count} items")
+    }
+}
+
+/// File names of `paths` for a confirmation, the first few then "and N more".
+pub fn names_label(paths: &[String]) -> String {
+    let mut label = paths
+        .iter()
+        .take(MAX_LISTED)
+        .map(|path| file_name(path))
+        .collect::<Vec<_>>()
+        .join(", ");
+    if paths.len() > MAX_LISTED {
+        label.push_str(&format!(" and {} more", paths.len() - MAX_LISTED));
+    }
+    label
+}
+
+/// Sorted, deduplicated paths without those inside another selected
+/// directory; the directory already covers them.
+pub fn prune_nested(mut paths: Vec<String>) -> Vec<String> {
+    paths.sort();
+    paths.dedup();
+    let mut kept: Vec<String> = Vec::new();
+    for path in paths {
+        if !kept
+            .iter()
+            .any(|parent| Path::new(&path).starts_with(parent))
+        {
+            kept.push(path);
+        }
+    }
+    kept
+}
+
+/// Where `path` lands when moved into `to_dir`, or `None` when it is already
+/// there or `to_dir` is inside it.
+pub fn move_target(path: &str, to_dir: &str) -> Option<String> {
+    let source = Path::new(path);
+    let to_dir = Path::new(to_dir);
+    if source.parent() == Some(to_dir) || to_dir.starts_with(source) {
+        return None;
+    }
+    Some(
+        to_dir
+            .join(source.file_name()?)
+            .to_string_lossy()
+            .into_owned(),
+    )
+}
+
+/// Archive path offered for `paths`: next to the first entry, named after it
+/// when it is the only one.
+pub fn default_archive_path(paths: &[String], stamp: &str) -> Option<String> {
+    let first = Path::new(paths.first()?);
+    let name = if paths.len() == 1 {
+        format!("{}.tar.gz", first.file_name()?.to_string_lossy())
+    } else {
+        format!("archive-{stamp}.tar.gz")
+    };
+    Some(first.parent()?.join(name).to_string_lossy().into_owned())
+}
+
+fn file_name(path: &str) -> String {
+    Path::new(path).file_name().map_or_else(
+        || path.to_string(),
+        |name| name.to_string_lossy().into_owned(),
+    )
+}
+
+#[derive(Debug, Default)]
+struct BatchOutcome {
+    done: usize,
+    /// `(path, error)` for each entry the host refused.
+    failed: Vec<(String, String)>,
+    cancelled: bool,
+}
+
+/// Notification title and, when something went wrong, the alert message.
+fn summary(op: &BatchOp, outcome: &BatchOutcome, total: usize) -> (String, Option<String>) {
+    if outcome.failed.is_empty() && !outcome.cancelled {
+        return (format!("{} {}", op.done_verb(), item_count(total)), None);
+    }
+    let title = format!(
+        "{} {} of {}",
+        op.done_verb(),
+        outcome.done,
+        item_count(total)
+    );
+    let mut lines: Vec<String> = outcome
+        .failed
+        .iter()
+        .take(MAX_LISTED)
+        .map(|(path, error)| format!("{}: {error}", file_name(path)))
+        .collect();
+    if outcome.failed.len() > MAX_LISTED {
+        lines.push(format!("and {} more", outcome.failed.len() - MAX_LISTED));
+    }
+    if outcome.cancelled {
+        lines.push("Cancelled before the rest.".to_string());
+    }
+    (title, Some(lines.join("\n")))
+}
+
+/// Progress of a running batch.
+pub struct FileBatchSheet {
+    op: BatchOp,
+    total: usize,
+    done: usize,
+    /// File name of the entry being worked on.
+    current: Option<String>,
+    cancelled: bool,
+    finished: bool,
+    presented: bool,
+}
+
+impl FileBatchSheet {
+    fn new(op: BatchOp, total: usize) -> Self {
+        Self {
+            op,
+            total,
+            done: 0,
+            current: None,
+            cancelled: false,
+            finished: false,
+            presented: false,
+        }
+    }
+
+    /// Archives run as one host call and cannot be stopped part way.
+    fn can_cancel(&self) -> bool {
+        !matches!(self.op, BatchOp::Archive { .. })
+    }
+
+    fn present(&mut self, cx: &mut Context<Self>) {
+        if self.finished || self.presented {
+            return;
+        }
+        self.presented = true;
+        view_telemetry::record(view_telemetry::CUSTOM_SHEET_FILE_BATCH);
+        platform_bridge::show_custom_sheet(
+            CustomSheetOptions {
+                detents: vec![CustomSheetDetent::Medium],
+                initial_detent: CustomSheetDetent::Medium,
+                shows_grabber: false,
+                expands_on_scroll_edge: false,
+                edge_attached_in_compact_height: false,
+                width_follows_preferred_content_size_when_edge_attached: false,
+                corner_radius: None,
+                modal_in_presentation: true,
+            },
+            cx.entity(),
+        );
+    }
+}
+
+impl Render for FileBatchSheet {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let fraction = if self.total == 0 {
+            0.0
+        } else {
+            self.done as f32 / self.total as f32
+        };
+        let cancel_label = if self.cancelled {
+            "Stopping…"
+        } else {
+            "Cancel"
+        };
+        let bottom_inset = platform_bridge::home_indicator_inset();
+        div()
+            .size_full()
+            .flex()
+            .flex_col()
+            .gap(px(theme::SPACING_SM))
+            .px(px(theme::SPACING_MD))
+            .pt(px(theme::SPACING_LG))
+            .pb(px(theme::SPACING_SM + bottom_inset))
+            .bg(rgb(theme::bg_primary(cx)))
+            .child(
+                div()
+                    .text_size(theme::scaled(theme::FONT_HEADING))
+                    .font_weight(FontWeight::MEDIUM)
+                    .text_color(rgb(theme::text_primary(cx)))
+                    .child(format!(
+                        "{} {}",
+                        self.op.progress_verb(),
+                        item_count(self.total)
+                    )),
+            )
+            .child(
+                div()
+                    .w_full()
+                    .h(px(4.0))
+                    .rounded(px(2.0))
+                    .bg(rgb(theme::border_subtle(cx)))
+                    .child(
+                        div()
+                            .h_full()
+                            .w(relative(fraction))
+                            .rounded(px(2.0))
+                            .bg(rgb(theme::accent_blue(cx))),
+                    ),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .gap(px(theme::SPACING_SM))
+                    .text_size(theme::scaled(theme::FONT_DETAIL))
+                    .text_color(rgb(theme::text_secondary(cx)))
+                    .child(format!("{} of {}", self.done, self.total))
+                    .when_some(self.current.clone(), |el, name| {
+                        el.child(
+                            div()
+                                .flex_1()
+                                .min_w_0()
+                                .truncate()
+                                .text_color(rgb(theme::text_muted(cx)))
+                                .child(name),
+                        )
+                    }),
+            )
+            .child(div().flex_1())
+            .when(self.can_cancel(), |el| {
+                el.child(
+                    outline_button(cx, "file-batch-cancel", cancel_label)
+                        .when(self.cancelled, |button| button.opacity(0.35))
+                        .on_press(cx.listener(|this, _event, _window, cx| {
+                            this.cancelled = true;
+                            cx.notify();
+                        })),
+                )
+            })
+    }
+}
+
+/// Record progress before working on `current`. Returns true once the user
+/// cancelled.
+fn advance(
+    sheet: &Entity<FileBatchSheet>,
+    done: usize,
+    current: Option<&String>,
+    cx: &mut AsyncApp,
+) -> bool {
+    sheet
+        .update(cx, |sheet, cx| {
+            sheet.done = done;
+            sheet.current = current.map(|path| file_name(path));
+            cx.notify();
+            sheet.cancelled
+        })
+        .unwrap_or(true)
+}
+
+async fn remove_in_chunks(
+    handle: &SessionHandle,
+    paths: &[String],
+    sheet: &Entity<FileBatchSheet>,
+    cx: &mut AsyncApp,
+) -> BatchOutcome {
+    let mut outcome = BatchOutcome::default();
+    for chunk in paths.chunks(REMOVE_CHUNK) {
+        let finished = outcome.done + outcome.failed.len();
+        if advance(sheet, finished, chunk.first(), cx) {
+            outcome.cancelled = true;
+            break;
+        }
+        match handle.fs_remove(chunk.to_vec()).await {
+            Ok(failed) => {
+                outcome.done += chunk.len() - failed.len();
+                outcome
+                    .failed
+                    .extend(failed.into_iter().map(|f| (f.path, f.error)));
+            }
+            Err(e) => outcome
+                .failed
+                .extend(chunk.iter().map(|path| (path.clone(), e.to_string()))),
+        }
+    }
+    outcome
+}
+
+/// Rename `path` to `to`, falling back to copy and delete when they are on
+/// different filesystems.
+async fn move_entry(handle: &SessionHandle, path: &str, to: &str) -> anyhow::Result<()> {
+    match handle.fs_rename(path, to).await {
+        Err(e) if e.to_string().starts_with(FS_CROSS_DEVICE_ERROR) => {
+            handle.fs_copy(path, to, true).await?;
+            match handle.fs_remove(vec![path.to_string()]).await?.pop() {
+                Some(failed) => Err(anyhow::anyhow!("copied, but {}", failed.error)),
+                None => Ok(()),
+            }
+        }
+        moved => moved,
+    }
+}
+
+async fn move_each(
+    handle: &SessionHandle,
+    paths: &[String],
+    to_dir: &str,
+    sheet: &Entity<FileBatchSheet>,
+    cx: &mut AsyncApp,
+) -> BatchOutcome {
+    let mut outcome = BatchOutcome::default();
+    for path in paths {
+        let finished = outcome.done + outcome.failed.len();
+        if advance(sheet, finished, Some(path), cx) {
+            outcome.cancelled = true;
+            break;
+        }
+        let Some(to) = move_target(path, to_dir) else {
+            outcome
+                .failed
+                .push((path.clone(), "already in that folder".to_string()));
+            continue;
+        };
+        match move_entry(handle, path, &to).await {
+            Ok(()) => outcome.done += 1,
+            Err(e) => outcome.failed.push((path.clone(), e.to_string())),
+        }
+    }
+    outcome
+}
+
+/// Run `op` over `paths` on the host and report how it went. The task
+/// resolves once the batch stops.
+pub fn run(op: BatchOp, paths: Vec<String>, handle: SessionHandle, cx: &mut App) -> Task<()> {
+    let paths = prune_nested(paths);
+    let total = paths.len();
+    let sheet = cx.new(|_| FileBatchSheet::new(op.clone(), total));
+
+    let delayed = sheet.clone();
+    cx.spawn(async move |cx| {
+        cx.background_executor().timer(PROGRESS_SHEET_DELAY).await;
+        let _ = delayed.update(cx, |sheet, cx| sheet.present(cx));
+    })
+    .detach();
+
+    cx.spawn(async move |cx| {
+        let outcome = match &op {
+            BatchOp::Delete => remove_in_chunks(&handle, &paths, &sheet, cx).await,
+            BatchOp::Move { to_dir } => move_each(&handle, &paths, to_dir, &sheet, cx).await,
+            BatchOp::Archive { to } => {
+                advance(&sheet, 0, paths.first(), cx);
+                let mut outcome = BatchOutcome::default();
+                match handle.fs_archive(paths.clone(), to).await {
+                    Ok(()) => outcome.done = total,
+                    Err(e) => outcome.failed.push((to.clone(), e.to_string())),
+                }
+                outcome
+            }
+        };
+        let presented = sheet
+            .update(cx, |sheet, cx| {
+                sheet.done = outcome.done + outcome.failed.len();
+                sheet.finished = true;
+                cx.notify();
+                sheet.presented
+            })
+            .unwrap_or(false);
+        if presented {
+            platform_bridge::dismiss_custom_sheet();
+        }
+
+        if !outcome.failed.is_empty() {
+            tracing::warn!(
+                "file batch: {} of {} failed, first: {:?}",
+                outcome.failed.len(),
+                total,
+                outcome.failed.first()
+            );
+        }
+        match summary(&op, &outcome, total) {
+            (title, None) => platform_bridge::show_native_notification(
+                NativeNotificationOptions::new(title).kind(NativeNotificationKind::Success),
+            ),
+            (title, Some(message)) => platform_bridge::show_alert(
+                &title,
+                &message,
+                vec![AlertButton::default("OK")],
+                |_| {},
+            ),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(paths: &[&str]) -> Vec<String> {
+        paths.iter().map(|p| p.to_string()).collect()
+    }
+
+    #[test]
+    fn prunes_entries_covered_by_a_selected_directory() {
+        assert_eq!(
+            prune_nested(paths(&[
+                "/w/src/a.rs",
+                "/w/src",
+                "/w/src-old/b.rs",
+                "/w/src"
+            ])),
+            paths(&["/w/src", "/w/src-old/b.rs"])
+        );
+    }
+
+    #[test]
+    fn plans_move_targets_and_archive_names() {
+        assert_eq!(
+            move_target("/w/src/a.rs", "/w/lib").as_deref(),
+            Some("/w/lib/a.rs")
+        );
+        assert_eq!(move_target("/w/src/a.rs", "/w/src"), None);
+        assert_eq!(move_target("/w/src", "/w/src/nested"), None);
+        assert_eq!(move_target("/w/src", "/w/src"), None);
+
+        assert_eq!(
+            default_archive_path(&paths(&["/w/logs"]), "20261015-0930").as_deref(),
+            Some("/w/logs.tar.gz")
+        );
+        assert_eq!(
+            default_archive_path(&paths(&["/w/a", "/w/b"]), "20261015-0930").as_deref(),
+            Some("/w/archive-20261015-0930.tar.gz")
+        );
+        assert_eq!(default_archive_path(&[], "x"), None);
+
+        let many = paths(&["/w/a", "/w/b", "/w/c", "/w/d", "/w/e", "/w/f", "/w/g"]);
+        assert_eq!(names_label(&many), "a, b, c, d, e and 2 more");
+    }
+
+    #[test]
+    fn summarizes_failures_and_cancels() {
+        let clean = BatchOutcome {
+            done: 3,
+            ..Default::default()
+        };
+        assert_eq!(
+            summary(&BatchOp::Delete, &clean, 3),
+            ("Deleted 3 items".to_string(), None)
+        );
+
+        let partial = BatchOutcome {
+            done: 1,
+            failed: vec![("/w/a.rs".into(), "permission denied".into())],
+            cancelled: true,
+        };
+        let (title, message) = summary(
+            &BatchOp::Move {
+                to_dir: "/w/lib".into(),
+            },
+            &partial,
+            5,
+        );
+        assert_eq!(title, "Moved 1 of 5 items");
+        assert_eq!(
+            message.as_deref(),
+            Some("a.rs: permission denied\nCancelled before the rest.")
+        );
+    }
+}
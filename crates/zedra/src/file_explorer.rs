@@ -1,13 +1,18 @@
+use gpui::prelude::FluentBuilder;
 use gpui::*;
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 use tracing::*;
 
-use zedra_rpc::proto::{FsEntry, HostEvent};
+use zedra_rpc::proto::{FsEntry, GitDiscoveredRepo, GitStatusEntry, HostEvent};
 use zedra_session::{Session, SessionHandle, SessionState};
 
+use crate::editor::git_sidebar::GitFileStatus;
+use crate::file_batch::{self, BatchOp};
+use crate::platform_bridge::{self, AlertButton, HapticFeedback};
 use crate::theme;
+use crate::ui::{PullScroll, PullToRefresh, PullToRefreshEvent, pull_to_refresh};
 use crate::workspace_action;
 use crate::workspace_state::WorkspaceState;
 
@@ -65,13 +70,36 @@ struct FlatEntry {
     load_more_for: Vec<usize>,
 }
 
+/// Change counts beside a directory: files below it with tracked changes, and
+/// untracked files.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct DirBadge {
+    modified: u32,
+    untracked: u32,
+}
+
+/// Git decorations for explorer rows, keyed by entry path.
+#[derive(Default)]
+struct GitBadges {
+    files: HashMap<String, GitFileStatus>,
+    dirs: HashMap<String, DirBadge>,
+    /// Repository roots below the workdir; `true` for submodule checkouts.
+    repo_roots: HashMap<String, bool>,
+}
+
 pub struct FileExplorer {
     entries: Vec<FileEntry>,
     focus_handle: FocusHandle,
     scroll_handle: UniformListScrollHandle,
+    pull_refresh: Entity<PullToRefresh>,
     focused_path: Option<String>,
     /// Bumped on every focus change; stale async reveals skip writing focus.
     focus_epoch: u64,
+    /// Selection mode, entered by long-pressing a row: taps toggle rows in
+    /// `selected` instead of opening them.
+    selecting: bool,
+    /// Host paths of the selected rows.
+    selected: HashSet<String>,
     /// Whether entries were loaded from the remote host
     remote_loaded: bool,
     /// Total root entries on the server (may exceed `entries.len()` when paginated)
@@ -82,8 +110,18 @@ pub struct FileExplorer {
     flat_dirty: bool,
     workdir: String,
     watched_paths: HashSet<String>,
+    /// Load-more rows (by parent index path) with a page request in flight.
+    pending_load_more: HashSet<Vec<usize>>,
     last_refresh_at: HashMap<String, Instant>,
     request_epoch: u64,
+    git_badges: GitBadges,
+    /// Workdir-relative repository the session's git RPCs use; empty for the
+    /// workdir. Status paths are relative to it.
+    git_repo: String,
+    /// Repositories below the workdir, from the last `GitDiscover`.
+    git_repos: Vec<GitDiscoveredRepo>,
+    /// Replaced on each badge refresh so a stale status never lands last.
+    git_badges_task: Option<Task<()>>,
     /// Keep track all tasks spawned by the file explorer. All dropped when the file explorer is dropped.
     tasks: Vec<Task<()>>,
     #[allow(dead_code)]
@@ -120,6 +158,16 @@ impl FileExplorer {
                             break;
                         }
                     }
+                    Ok(HostEvent::GitChanged) => {
+                        let should_break = this
+                            .update(cx, |this, cx| {
+                                this.refresh_git_badges(false, cx);
+                            })
+                            .is_err();
+                        if should_break {
+                            break;
+                        }
+                    }
                     Ok(_) => {}
                     Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
                         warn!("file explorer host event listener lagged by {}", skipped);
@@ -129,36 +177,100 @@ impl FileExplorer {
             }
         });
 
+        let pull_refresh = cx.new(|_| PullToRefresh::new());
+        let pull_refresh_subscription = cx.subscribe(
+            &pull_refresh,
+            |this: &mut Self, pull, event: &PullToRefreshEvent, cx| match event {
+                PullToRefreshEvent::Refresh => {
+                    let refresh = this.refresh_after_sync(cx);
+                    cx.spawn(async move |_this, cx| {
+                        refresh.await;
+                        let _ = pull.update(cx, |pull, cx| pull.finish(cx));
+                    })
+                    .detach();
+                }
+            },
+        );
+
         Self {
             entries: Vec::new(),
             focus_handle: cx.focus_handle(),
             scroll_handle: UniformListScrollHandle::new(),
+            pull_refresh,
             focused_path: None,
             focus_epoch: 0,
+            selecting: false,
+            selected: HashSet::new(),
             remote_loaded: false,
             root_total: 0,
             workdir,
             flat_entries: Vec::new(),
             flat_dirty: false,
             watched_paths: HashSet::new(),
+            pending_load_more: HashSet::new(),
             last_refresh_at: HashMap::new(),
             request_epoch: 0,
+            git_badges: GitBadges::default(),
+            git_repo: String::new(),
+            git_repos: Vec::new(),
+            git_badges_task: None,
             tasks: vec![host_event_task],
             workspace_state,
             session_state,
             session_handle,
-            _subscriptions: vec![workspace_state_subscription],
+            _subscriptions: vec![workspace_state_subscription, pull_refresh_subscription],
         }
     }
 
     pub fn refresh_after_sync(&mut self, cx: &mut Context<Self>) -> Task<()> {
         self.workdir = self.workspace_state.read(cx).workdir.to_string();
         self.request_epoch = self.request_epoch.wrapping_add(1);
+        self.pending_load_more.clear();
         self.rebind_watches(cx);
+        self.refresh_git_badges(true, cx);
         let show_loading = !self.remote_loaded && self.entries.is_empty();
         self.request_root_listing(show_loading, cx)
     }
 
+    /// Refetch git status, and with `rediscover` the repositories below the
+    /// workdir, then rebuild the row badges. Discovery walks the host tree,
+    /// so `GitChanged` only refetches status.
+    fn refresh_git_badges(&mut self, rediscover: bool, cx: &mut Context<Self>) {
+        let handle = self.session_handle.clone();
+        self.git_badges_task = Some(cx.spawn(async move |this, cx| {
+            // Best-effort: older hosts lack `GitDiscover`.
+            let discovered = if rediscover {
+                match handle.git_discover().await {
+                    Ok(discovered) => Some(discovered),
+                    Err(e) => {
+                        debug!("git_discover unavailable: {}", e);
+                        None
+                    }
+                }
+            } else {
+                None
+            };
+            let status = handle.git_status().await;
+            let _ = this.update(cx, |this, cx| {
+                if let Some(discovered) = discovered {
+                    this.git_repo = discovered.selected.unwrap_or_default();
+                    this.git_repos = discovered.repositories;
+                }
+                let entries = match status {
+                    Ok(status) => status.entries,
+                    Err(e) => {
+                        // Not a repository: keep the repository root icons.
+                        debug!("git status unavailable for badges: {}", e);
+                        Vec::new()
+                    }
+                };
+                this.git_badges =
+                    git_badges(&this.workdir, &this.git_repo, &entries, &this.git_repos);
+                cx.notify();
+            });
+        }));
+    }
+
     /// Focus the explorer and reveal a file or directory path, loading missing
     /// ancestor directories and paginated entries as needed.
     pub fn reveal_path(
@@ -470,6 +582,9 @@ impl FileExplorer {
     /// Load the next page of entries for a directory or the root.
     /// `load_more_path` is `[]` for root, or the index path of a dir entry.
     fn load_more_entries(&mut self, load_more_path: Vec<usize>, cx: &mut Context<Self>) {
+        if !self.pending_load_more.insert(load_more_path.clone()) {
+            return;
+        }
         let epoch = self.request_epoch;
 
         let (dir_path, offset) = if load_more_path.is_empty() {
@@ -477,7 +592,10 @@ impl FileExplorer {
         } else {
             match self.entry_at_path(&load_more_path) {
                 Some(entry) => (entry.path.clone(), entry.children.len() as u32),
-                None => return,
+                None => {
+                    self.pending_load_more.remove(&load_more_path);
+                    return;
+                }
             }
         };
 
@@ -490,6 +608,7 @@ impl FileExplorer {
                 if this.request_epoch != epoch {
                     return;
                 }
+                this.pending_load_more.remove(&load_more_path);
                 match result {
                     Ok((entries, _total, _has_more)) => {
                         let more = Self::to_file_entries(entries);
@@ -982,6 +1101,15 @@ impl Render for FileExplorer {
             "file-list",
             self.flat_entries.len(),
             cx.processor(|this, range: std::ops::Range<usize>, window, cx| {
+                // Fetch the next page as soon as its load-more row scrolls into view.
+                let visible_load_more: Vec<Vec<usize>> = this.flat_entries[range.clone()]
+                    .iter()
+                    .filter(|entry| entry.is_load_more)
+                    .map(|entry| entry.load_more_for.clone())
+                    .collect();
+                for load_more_for in visible_load_more {
+                    this.load_more_entries(load_more_for, cx);
+                }
                 range
                     .filter_map(|flat_idx| {
                         this.flat_entries
@@ -995,6 +1123,9 @@ impl Render for FileExplorer {
         .track_scroll(&self.scroll_handle)
         .size_full()
         .flex_grow();
+        let selection_bar = self
+            .selecting
+            .then(|| self.render_selection_bar(cx).into_any_element());
 
         div()
             .track_focus(&self.focus_handle)
@@ -1005,7 +1136,14 @@ impl Render for FileExplorer {
             .min_h_0()
             .overflow_hidden()
             .relative()
-            .child(list)
+            .child(pull_to_refresh(
+                "file-list-pull",
+                &self.pull_refresh,
+                PullScroll::UniformList(self.scroll_handle.clone()),
+                list,
+                cx,
+            ))
+            .children(selection_bar)
     }
 }
 
@@ -1018,6 +1156,7 @@ impl FileExplorer {
         cx: &mut Context<Self>,
     ) -> AnyElement {
         let entry_depth = entry.depth;
+        let entry_id_source = FlatEntryId::of(&entry);
         let indent = entry_depth as f32 * 16.0;
         let text_color = if entry.is_dir {
             rgb(theme::text_primary(cx))
@@ -1034,7 +1173,7 @@ impl FileExplorer {
         if entry.is_load_more {
             let load_more_for = entry.load_more_for;
             return div()
-                .id(flat_idx)
+                .id(flat_entry_id(flat_idx, &entry_id_source))
                 .w_full()
                 .flex()
                 .flex_row()
@@ -1052,7 +1191,7 @@ impl FileExplorer {
                         .min_w_0()
                         .truncate()
                         .text_color(rgb(theme::text_muted(cx)))
-                        .text_size(px(theme::FONT_BODY))
+                        .text_size(theme::scaled(theme::FONT_BODY))
                         .child(name),
                 )
                 .into_any_element();
@@ -1061,14 +1200,15 @@ impl FileExplorer {
         let icon_element: AnyElement = if loading {
             div()
                 .text_color(rgb(theme::text_muted(cx)))
-                .text_size(px(theme::FONT_BODY))
+                .text_size(theme::scaled(theme::FONT_BODY))
                 .child("...")
                 .into_any_element()
         } else if is_dir {
-            let (icon_path, icon_size) = if expanded {
-                ("icons/folder-open.svg", px(theme::ICON_FILE_DIR))
-            } else {
-                ("icons/folder.svg", px(theme::ICON_FILE))
+            let (icon_path, icon_size) = match self.git_badges.repo_roots.get(&row_path) {
+                Some(true) => ("icons/package.svg", px(theme::ICON_FILE)),
+                Some(false) => ("icons/git-branch.svg", px(theme::ICON_FILE)),
+                None if expanded => ("icons/folder-open.svg", px(theme::ICON_FILE_DIR)),
+                None => ("icons/folder.svg", px(theme::ICON_FILE)),
             };
             svg()
                 .path(icon_path)
@@ -1090,11 +1230,38 @@ impl FileExplorer {
                 .focused_path
                 .as_ref()
                 .is_some_and(|focused_path| focused_path == &row_path);
+        let is_selected = self.selected.contains(&row_path);
+        let checkbox = self.selecting.then(|| {
+            let border = if is_selected {
+                theme::accent_blue(cx)
+            } else {
+                theme::border_default(cx)
+            };
+            div()
+                .flex_shrink_0()
+                .size(px(16.0))
+                .flex()
+                .items_center()
+                .justify_center()
+                .rounded(px(4.0))
+                .border_1()
+                .border_color(rgb(border))
+                .when(is_selected, |el| {
+                    el.bg(rgb(theme::accent_blue(cx))).child(
+                        svg()
+                            .path("icons/check.svg")
+                            .size(px(12.0))
+                            .text_color(rgb(theme::bg_primary(cx))),
+                    )
+                })
+        });
 
+        let git_badge = self.render_git_badge(&row_path, is_dir, cx);
         let index_path_for_toggle = index_path.clone();
         let focus_path = row_path.clone();
+        let select_path = row_path.clone();
         let mut row = div()
-            .id(flat_idx)
+            .id(flat_entry_id(flat_idx, &entry_id_source))
             .w_full()
             .flex()
             .flex_row()
@@ -1105,7 +1272,11 @@ impl FileExplorer {
             .pr(px(8.0))
             .cursor_pointer()
             .on_press(cx.listener(move |this, _event, window, cx| {
-                if is_dir {
+                if this.selecting {
+                    if !row_path.is_empty() {
+                        this.toggle_selected(&row_path, cx);
+                    }
+                } else if is_dir {
                     this.toggle_dir(&index_path_for_toggle, cx);
                 } else if !row_path.is_empty() {
                     // Bump epoch so an in-flight reveal can't overwrite this focus.
@@ -1120,6 +1291,7 @@ impl FileExplorer {
                     );
                 }
             }))
+            .children(checkbox)
             .child(div().flex_shrink_0().child(icon_element))
             .child(
                 div()
@@ -1127,14 +1299,393 @@ impl FileExplorer {
                     .min_w_0()
                     .truncate()
                     .text_color(text_color)
-                    .text_size(px(theme::FONT_BODY))
+                    .text_size(theme::scaled(theme::FONT_BODY))
                     .child(name),
-            );
-        if is_focused_path {
+            )
+            .children(git_badge);
+        if !select_path.is_empty() {
+            row = row.on_long_press(cx.listener(move |this, _event, _window, cx| {
+                if this.selecting {
+                    this.toggle_selected(&select_path, cx);
+                } else {
+                    platform_bridge::trigger_haptic(HapticFeedback::ImpactMedium);
+                    this.enter_selection(select_path.clone(), cx);
+                }
+            }));
+        }
+        if is_focused_path || is_selected {
             row = row.bg(theme::row_pressed_bg(cx));
         }
         row.into_any_element()
     }
+
+    fn enter_selection(&mut self, path: String, cx: &mut Context<Self>) {
+        self.selecting = true;
+        self.selected.clear();
+        self.selected.insert(path);
+        cx.notify();
+    }
+
+    fn toggle_selected(&mut self, path: &str, cx: &mut Context<Self>) {
+        if !self.selected.remove(path) {
+            self.selected.insert(path.to_string());
+        }
+        cx.notify();
+    }
+
+    fn exit_selection(&mut self, cx: &mut Context<Self>) {
+        self.selecting = false;
+        self.selected.clear();
+        cx.notify();
+    }
+
+    /// The selection with entries inside selected directories left out.
+    fn selected_paths(&self) -> Vec<String> {
+        file_batch::prune_nested(self.selected.iter().cloned().collect())
+    }
+
+    /// The only selected path, when it is a file.
+    fn single_selected_file(&self) -> Option<String> {
+        if self.selected.len() != 1 {
+            return None;
+        }
+        let path = self.selected.iter().next()?;
+        self.flat_entries
+            .iter()
+            .any(|entry| &entry.path == path && !entry.is_dir)
+            .then(|| path.clone())
+    }
+
+    fn start_batch(&mut self, op: BatchOp, paths: Vec<String>, cx: &mut Context<Self>) {
+        self.exit_selection(cx);
+        let task = file_batch::run(op, paths, self.session_handle.clone(), cx);
+        self.tasks.push(task);
+    }
+
+    fn confirm_delete(&mut self, cx: &mut Context<Self>) {
+        let paths = self.selected_paths();
+        if paths.is_empty() {
+            return;
+        }
+        let (choice_tx, choice_rx) = tokio::sync::oneshot::channel();
+        platform_bridge::show_alert(
+            &format!("Delete {}?", file_batch::item_count(paths.len())),
+            &format!(
+                "{}\n\nFolders are deleted with everything in them. This cannot be undone.",
+                file_batch::names_label(&paths)
+            ),
+            vec![
+                AlertButton::destructive("Delete"),
+                AlertButton::cancel("Cancel"),
+            ],
+            move |index| {
+                let _ = choice_tx.send(index);
+            },
+        );
+        cx.spawn(async move |this, cx| {
+            if choice_rx.await != Ok(0) {
+                return;
+            }
+            let _ = this.update(cx, |this, cx| this.start_batch(BatchOp::Delete, paths, cx));
+        })
+        .detach();
+    }
+
+    /// Ask for a workdir-relative folder and move the selection into it.
+    fn prompt_move(&mut self, cx: &mut Context<Self>) {
+        let paths = self.selected_paths();
+        let Some(first) = paths.first() else {
+            return;
+        };
+        let workdir = self.workdir.clone();
+        let initial = Path::new(first)
+            .parent()
+            .map(|parent| normalize_watch_path(&parent.to_string_lossy(), &workdir))
+            .unwrap_or_else(|| ".".to_string());
+        let (input_tx, input_rx) = tokio::sync::oneshot::channel();
+        platform_bridge::show_text_input(
+            &format!("Move {} To", file_batch::item_count(paths.len())),
+            "Folder, relative to the workspace",
+            &initial,
+            move |result| {
+                let _ = input_tx.send(result);
+            },
+        );
+        cx.spawn(async move |this, cx| {
+            let Ok(Some(input)) = input_rx.await else {
+                return;
+            };
+            let input = input.trim();
+            if input.is_empty() {
+                return;
+            }
+            let to_dir = if input == "." {
+                workdir
+            } else {
+                normalize_reveal_path(input, &workdir)
+            };
+            let _ = this.update(cx, |this, cx| {
+                this.start_batch(BatchOp::Move { to_dir }, paths, cx)
+            });
+        })
+        .detach();
+    }
+
+    /// Ask where to write the archive, offering one next to the selection.
+    fn prompt_archive(&mut self, cx: &mut Context<Self>) {
+        let paths = self.selected_paths();
+        let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S").to_string();
+        let Some(default_path) = file_batch::default_archive_path(&paths, &stamp) else {
+            return;
+        };
+        let workdir = self.workdir.clone();
+        let (input_tx, input_rx) = tokio::sync::oneshot::channel();
+        platform_bridge::show_text_input(
+            &format!("Archive {}", file_batch::item_count(paths.len())),
+            "Archive path, relative to the workspace",
+            &normalize_watch_path(&default_path, &workdir),
+            move |result| {
+                let _ = input_tx.send(result);
+            },
+        );
+        cx.spawn(async move |this, cx| {
+            let Ok(Some(input)) = input_rx.await else {
+                return;
+            };
+            let input = input.trim();
+            if input.is_empty() || input == "." {
+                return;
+            }
+            let to = normalize_reveal_path(input, &workdir);
+            let _ = this.update(cx, |this, cx| {
+                this.start_batch(BatchOp::Archive { to }, paths, cx)
+            });
+        })
+        .detach();
+    }
+
+    fn render_selection_bar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let count = self.selected.len();
+        let has_selection = count > 0;
+        let history_path = self.single_selected_file();
+        let action = |id: &'static str, label: &'static str, color: u32, enabled: bool| {
+            div()
+                .id(id)
+                .flex_shrink_0()
+                .px(px(theme::SPACING_SM))
+                .py(px(6.0))
+                .text_size(theme::scaled(theme::FONT_BODY))
+                .text_color(rgb(color))
+                .when(enabled, |el| el.cursor_pointer())
+                .when(!enabled, |el| el.opacity(0.35))
+                .child(label)
+        };
+        let text_primary = theme::text_primary(cx);
+        div()
+            .id("file-selection-bar")
+            .flex_shrink_0()
+            .w_full()
+            .flex()
+            .flex_row()
+            .items_center()
+            .h(px(theme::PANEL_ITEM_HEIGHT + 8.0))
+            .pl(px(12.0))
+            .pr(px(4.0))
+            .border_t_1()
+            .border_color(rgb(theme::border_subtle(cx)))
+            .bg(rgb(theme::bg_primary(cx)))
+            .child(
+                div()
+                    .flex_1()
+                    .min_w_0()
+                    .truncate()
+                    .text_size(theme::scaled(theme::FONT_DETAIL))
+                    .text_color(rgb(theme::text_secondary(cx)))
+                    .child(format!("{count} selected")),
+            )
+            .when_some(history_path, |el, path| {
+                el.child(
+                    action("file-selection-history", "History", text_primary, true).on_press(
+                        cx.listener(move |this, _event, window, cx| {
+                            this.exit_selection(cx);
+                            window.dispatch_action(
+                                workspace_action::OpenFileHistory { path: path.clone() }
+                                    .boxed_clone(),
+                                cx,
+                            );
+                        }),
+                    ),
+                )
+            })
+            .child(
+                action("file-selection-move", "Move", text_primary, has_selection)
+                    .on_press(cx.listener(|this, _event, _window, cx| this.prompt_move(cx))),
+            )
+            .child(
+                action(
+                    "file-selection-archive",
+                    "Archive",
+                    text_primary,
+                    has_selection,
+                )
+                .on_press(cx.listener(|this, _event, _window, cx| this.prompt_archive(cx))),
+            )
+            .child(
+                action(
+                    "file-selection-delete",
+                    "Delete",
+                    theme::accent_red(cx),
+                    has_selection,
+                )
+                .on_press(cx.listener(|this, _event, _window, cx| this.confirm_delete(cx))),
+            )
+            .child(
+                div()
+                    .id("file-selection-done")
+                    .flex_shrink_0()
+                    .p(px(6.0))
+                    .cursor_pointer()
+                    .child(
+                        svg()
+                            .path("icons/x.svg")
+                            .size(px(theme::ICON_FILE))
+                            .text_color(rgb(theme::text_muted(cx))),
+                    )
+                    .on_press(cx.listener(|this, _event, _window, cx| this.exit_selection(cx))),
+            )
+    }
+
+    /// Status letter for a changed file (or a changed submodule root), else
+    /// modified and untracked counts for a directory with changes below it.
+    fn render_git_badge(&self, path: &str, is_dir: bool, cx: &App) -> Option<AnyElement> {
+        if let Some(status) = self.git_badges.files.get(path) {
+            return Some(
+                div()
+                    .flex_shrink_0()
+                    .text_size(theme::scaled(theme::FONT_DETAIL))
+                    .text_color(rgb(git_status_color(*status, cx)))
+                    .child(status.icon())
+                    .into_any_element(),
+            );
+        }
+        if !is_dir {
+            return None;
+        }
+        let badge = self.git_badges.dirs.get(path)?;
+        Some(
+            div()
+                .flex()
+                .flex_row()
+                .flex_shrink_0()
+                .gap(px(4.0))
+                .text_size(theme::scaled(theme::FONT_DETAIL))
+                .when(badge.modified > 0, |s| {
+                    s.child(
+                        div()
+                            .text_color(rgb(theme::accent_yellow(cx)))
+                            .child(badge.modified.to_string()),
+                    )
+                })
+                .when(badge.untracked > 0, |s| {
+                    s.child(
+                        div()
+                            .text_color(rgb(theme::git_added(cx)))
+                            .child(format!("+{}", badge.untracked)),
+                    )
+                })
+                .into_any_element(),
+        )
+    }
+}
+
+fn git_status_color(status: GitFileStatus, cx: &App) -> u32 {
+    match status {
+        GitFileStatus::Modified | GitFileStatus::Renamed => theme::accent_yellow(cx),
+        GitFileStatus::Added | GitFileStatus::Untracked => theme::git_added(cx),
+        GitFileStatus::Deleted => theme::git_removed(cx),
+    }
+}
+
+/// Badges for `entries` (paths relative to the repository at the
+/// workdir-relative `repo_path`), keyed like explorer entries. Each changed file
+/// counts toward every directory above it. A nested repository shows up in its
+/// parent's status as one untracked (or, for a submodule, modified) root entry.
+fn git_badges(
+    workdir: &str,
+    repo_path: &str,
+    entries: &[GitStatusEntry],
+    repos: &[GitDiscoveredRepo],
+) -> GitBadges {
+    let mut badges = GitBadges::default();
+    for repo in repos.iter().filter(|repo| !repo.path.is_empty()) {
+        badges.repo_roots.insert(
+            event_path_to_entry_path(&repo.path, workdir),
+            repo.submodule,
+        );
+    }
+    for entry in entries {
+        let status = match (&entry.unstaged_status, &entry.staged_status) {
+            (Some(status), _) | (None, Some(status)) => GitFileStatus::from_status_str(status),
+            (None, None) => continue,
+        };
+        let path = entry.path.trim_end_matches('/');
+        let relative = if repo_path.is_empty() {
+            path.to_string()
+        } else {
+            format!("{repo_path}/{path}")
+        };
+        badges
+            .files
+            .insert(event_path_to_entry_path(&relative, workdir), status);
+        let mut child = relative.as_str();
+        while let Some((parent, _)) = child.rsplit_once('/') {
+            let badge = badges
+                .dirs
+                .entry(event_path_to_entry_path(parent, workdir))
+                .or_default();
+            if status == GitFileStatus::Untracked {
+                badge.untracked += 1;
+            } else {
+                badge.modified += 1;
+            }
+            child = parent;
+        }
+    }
+    badges
+}
+
+/// What a row's element id is derived from.
+enum FlatEntryId {
+    Path(String),
+    LoadMore(Vec<usize>),
+    Placeholder,
+}
+
+impl FlatEntryId {
+    fn of(entry: &FlatEntry) -> Self {
+        if entry.is_load_more {
+            Self::LoadMore(entry.load_more_for.clone())
+        } else if entry.path.is_empty() {
+            Self::Placeholder
+        } else {
+            Self::Path(entry.path.clone())
+        }
+    }
+}
+
+/// Element id for a row. Real entries are keyed by path so press and hover
+/// state stay with the file when rows above it expand, collapse, or page in.
+fn flat_entry_id(flat_idx: usize, id: &FlatEntryId) -> ElementId {
+    match id {
+        FlatEntryId::Path(path) => ElementId::Name(format!("file-row:{path}").into()),
+        FlatEntryId::LoadMore(parent) => {
+            let parent: Vec<String> = parent.iter().map(usize::to_string).collect();
+            ElementId::Name(format!("file-load-more:{}", parent.join("/")).into())
+        }
+        FlatEntryId::Placeholder => {
+            ElementId::NamedInteger("file-placeholder".into(), flat_idx as u64)
+        }
+    }
 }
 
 fn flatten_entry(entry: &FileEntry, depth: usize, path: &mut Vec<usize>, out: &mut Vec<FlatEntry>) {
@@ -1196,10 +1747,12 @@ mod tests {
     use std::collections::{HashMap, HashSet};
 
     use super::{
-        FileEntry, FileExplorer, FlatEntry, drain_watched_paths_for_unwatch,
-        event_path_to_entry_path, find_index_path_by_path, flatten_entries, normalize_reveal_path,
-        normalize_watch_path, reveal_path_chain,
+        DirBadge, FileEntry, FileExplorer, FlatEntry, FlatEntryId, GitFileStatus,
+        drain_watched_paths_for_unwatch, event_path_to_entry_path, find_index_path_by_path,
+        flat_entry_id, flatten_entries, git_badges, normalize_reveal_path, normalize_watch_path,
+        reveal_path_chain,
     };
+    use zedra_rpc::proto::{GitDiscoveredRepo, GitStatusEntry};
 
     fn expanded(mut entry: FileEntry) -> FileEntry {
         entry.expanded = true;
@@ -1282,6 +1835,41 @@ mod tests {
         assert_eq!(root_more.depth, 0);
     }
 
+    #[test]
+    fn flat_entry_ids_follow_paths_not_row_positions() {
+        let lib = FileEntry::file("lib.rs", "/repo/src/lib.rs");
+        let collapsed = vec![
+            FileEntry::dir("docs", "/repo/docs", Vec::new()),
+            FileEntry::dir("src", "/repo/src", vec![lib.clone()]),
+        ];
+        let expanded_docs = vec![
+            expanded(FileEntry::dir(
+                "docs",
+                "/repo/docs",
+                vec![FileEntry::file("guide.md", "/repo/docs/guide.md")],
+            )),
+            FileEntry::dir("src", "/repo/src", vec![lib]),
+        ];
+
+        let before = flatten_entries(&collapsed, 2);
+        let after = flatten_entries(&expanded_docs, 2);
+        let id_of = |flat: &[FlatEntry], name: &str| {
+            let (idx, entry) = flat
+                .iter()
+                .enumerate()
+                .find(|(_, entry)| entry.name == name)
+                .unwrap();
+            flat_entry_id(idx, &FlatEntryId::of(entry))
+        };
+
+        assert_ne!(
+            before.iter().position(|e| e.name == "src"),
+            after.iter().position(|e| e.name == "src")
+        );
+        assert_eq!(id_of(&before, "src"), id_of(&after, "src"));
+        assert_ne!(id_of(&after, "docs"), id_of(&after, "src"));
+    }
+
     #[test]
     fn flatten_entries_keeps_loading_row_non_actionable() {
         let mut src = expanded(FileEntry::dir(
@@ -1499,4 +2087,80 @@ mod tests {
         );
         assert_eq!(find_index_path_by_path(&entries, "/repo/missing"), None);
     }
+
+    fn status(path: &str, staged: Option<&str>, unstaged: Option<&str>) -> GitStatusEntry {
+        GitStatusEntry {
+            path: path.to_string(),
+            staged_status: staged.map(str::to_string),
+            unstaged_status: unstaged.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn git_badges_count_changes_per_directory_and_mark_repo_roots() {
+        let entries = vec![
+            status("src/lib.rs", Some("modified"), None),
+            status("src/new.rs", None, Some("untracked")),
+            status("src/ui/view.rs", Some("added"), Some("modified")),
+            status("nested/", None, Some("untracked")),
+        ];
+        let repos = vec![
+            GitDiscoveredRepo {
+                path: String::new(),
+                branch: "main".to_string(),
+                submodule: false,
+            },
+            GitDiscoveredRepo {
+                path: "nested".to_string(),
+                branch: "main".to_string(),
+                submodule: false,
+            },
+            GitDiscoveredRepo {
+                path: "vendor/lib".to_string(),
+                branch: String::new(),
+                submodule: true,
+            },
+        ];
+
+        let badges = git_badges("/repo", "", &entries, &repos);
+        assert_eq!(
+            badges.files.get("/repo/src/ui/view.rs"),
+            Some(&GitFileStatus::Modified)
+        );
+        assert_eq!(
+            badges.files.get("/repo/nested"),
+            Some(&GitFileStatus::Untracked)
+        );
+        assert_eq!(
+            badges.dirs.get("/repo/src"),
+            Some(&DirBadge {
+                modified: 2,
+                untracked: 1
+            })
+        );
+        assert_eq!(
+            badges.dirs.get("/repo/src/ui"),
+            Some(&DirBadge {
+                modified: 1,
+                untracked: 0
+            })
+        );
+        assert!(!badges.dirs.contains_key("/repo/nested"));
+        assert_eq!(badges.repo_roots.get("/repo/nested"), Some(&false));
+        assert_eq!(badges.repo_roots.get("/repo/vendor/lib"), Some(&true));
+        assert!(!badges.repo_roots.contains_key("/repo"));
+    }
+
+    #[test]
+    fn git_badges_prefix_status_paths_with_the_selected_repository() {
+        let entries = vec![status("src/main.rs", None, Some("deleted"))];
+        let badges = git_badges("/work", "apps/web", &entries, &[]);
+        assert_eq!(
+            badges.files.get("/work/apps/web/src/main.rs"),
+            Some(&GitFileStatus::Deleted)
+        );
+        for dir in ["/work/apps", "/work/apps/web", "/work/apps/web/src"] {
+            assert_eq!(badges.dirs.get(dir).map(|badge| badge.modified), Some(1));
+        }
+    }
 }
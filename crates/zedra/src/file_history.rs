@@ -0,0 +1,432 @@
+use futures::channel::oneshot;
+use gpui::*;
+use zedra_rpc::proto::GitFileLogEntry;
+use zedra_session::SessionHandle;
+
+use crate::commit_detail::{CommitDetailSheet, CommitRef, commit_sheet_options, short_id};
+use crate::file_preview_view::FilePreviewView;
+use crate::fonts;
+use crate::platform_bridge::{self, AlertButton, HapticFeedback};
+use crate::telemetry::view_telemetry;
+use crate::theme;
+use crate::ui::{
+    PullToRefresh, PullToRefreshEvent, chevron_back_button, subscreen_empty_text,
+    subscreen_padded_body, subscreen_refresh_button, subscreen_refreshable_page,
+};
+use crate::workspace_action;
+use crate::workspace_state::WorkspaceState;
+
+#[derive(Clone, Debug)]
+enum HistoryState {
+    Loading,
+    Ready,
+    Error(String),
+}
+
+/// What to show for a tapped revision.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum RevisionChoice {
+    Content,
+    Diff,
+}
+
+impl RevisionChoice {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Content => "View File at This Commit",
+            Self::Diff => "Show Changes",
+        }
+    }
+}
+
+fn commit_ref(entry: &GitFileLogEntry) -> CommitRef {
+    CommitRef {
+        id: entry.id.clone(),
+        summary: entry.message.clone(),
+        author: entry.author.clone(),
+        timestamp: entry.timestamp,
+        path: entry.path.clone(),
+        old_path: entry.old_path.clone(),
+    }
+}
+
+fn status_label(status: &str) -> &'static str {
+    match status {
+        "A" => "added",
+        "D" => "deleted",
+        "R" => "renamed",
+        _ => "modified",
+    }
+}
+
+fn status_color(status: &str, cx: &App) -> u32 {
+    match status {
+        "A" => theme::git_added(cx),
+        "D" => theme::git_removed(cx),
+        "R" => theme::accent_blue(cx),
+        _ => theme::text_muted(cx),
+    }
+}
+
+/// File history screen: the commits that touched one file, following
+/// renames. Tapping a commit shows the file at that revision or the diff the
+/// commit made to it, each in a sheet.
+pub struct FileHistoryView {
+    session_handle: SessionHandle,
+    path: String,
+    entries: Vec<GitFileLogEntry>,
+    truncated: bool,
+    state: HistoryState,
+    load_epoch: u64,
+    pull_refresh: Entity<PullToRefresh>,
+    preview: Entity<FilePreviewView>,
+    commit_sheet: Entity<CommitDetailSheet>,
+    _load_task: Option<Task<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl FileHistoryView {
+    pub fn new(
+        session_handle: SessionHandle,
+        workspace_state: Entity<WorkspaceState>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let pull_refresh = cx.new(|_| PullToRefresh::new());
+        let pull_sub = cx.subscribe(&pull_refresh, |this, _pull, event, cx| match event {
+            PullToRefreshEvent::Refresh => this.load(cx),
+        });
+        let preview = {
+            let session_handle = session_handle.clone();
+            cx.new(|cx| FilePreviewView::new(session_handle, workspace_state, cx))
+        };
+        let commit_sheet = cx.new(|cx| CommitDetailSheet::new(session_handle.clone(), cx));
+        Self {
+            session_handle,
+            path: String::new(),
+            entries: Vec::new(),
+            truncated: false,
+            state: HistoryState::Loading,
+            load_epoch: 0,
+            pull_refresh,
+            preview,
+            commit_sheet,
+            _load_task: None,
+            _subscriptions: vec![pull_sub],
+        }
+    }
+
+    /// Show the history of `path`, reloading unless it is already shown.
+    pub fn open(&mut self, path: String, cx: &mut Context<Self>) {
+        if path == self.path && matches!(self.state, HistoryState::Ready) {
+            return;
+        }
+        self.path = path;
+        self.entries.clear();
+        self.load(cx);
+    }
+
+    fn load(&mut self, cx: &mut Context<Self>) {
+        self.load_epoch = self.load_epoch.wrapping_add(1);
+        let epoch = self.load_epoch;
+        self.state = HistoryState::Loading;
+        cx.notify();
+
+        let handle = self.session_handle.clone();
+        let path = self.path.clone();
+        self._load_task = Some(cx.spawn(async move |this, cx| {
+            let result = handle.git_file_log(&path, 0).await;
+            let _ = this.update(cx, |this, cx| {
+                if this.load_epoch != epoch {
+                    return;
+                }
+                match result {
+                    Ok(result) => {
+                        this.entries = result.entries;
+                        this.truncated = result.truncated;
+                        this.state = HistoryState::Ready;
+                    }
+                    Err(e) => {
+                        tracing::warn!("file history: load failed for {}: {}", path, e);
+                        this.state = HistoryState::Error(e.to_string());
+                    }
+                }
+                this.pull_refresh.update(cx, |pull, cx| pull.finish(cx));
+                cx.notify();
+            });
+        }));
+    }
+
+    fn subtitle(&self) -> String {
+        match &self.state {
+            HistoryState::Loading => self.path.clone(),
+            HistoryState::Error(_) => "Load failed".to_string(),
+            HistoryState::Ready => {
+                let suffix = if self.truncated { "+" } else { "" };
+                format!("{}{suffix} commits · {}", self.entries.len(), self.path)
+            }
+        }
+    }
+
+    fn choose_revision(&mut self, index: usize, cx: &mut Context<Self>) {
+        let Some(entry) = self.entries.get(index).cloned() else {
+            return;
+        };
+        // A deletion has no content at that revision, only the diff.
+        let choices = if entry.status == "D" {
+            vec![RevisionChoice::Diff]
+        } else {
+            vec![RevisionChoice::Content, RevisionChoice::Diff]
+        };
+        let mut buttons: Vec<AlertButton> = choices
+            .iter()
+            .map(|choice| AlertButton::default(choice.label()))
+            .collect();
+        buttons.push(AlertButton::cancel("Cancel"));
+        let (tx, rx) = oneshot::channel();
+        platform_bridge::show_selection(
+            &entry.message,
+            &format!("{} · {}", short_id(&entry.id), entry.author),
+            buttons,
+            move |index| {
+                let _ = tx.send(index);
+            },
+        );
+        cx.spawn(async move |this, cx| {
+            let Ok(Some(index)) = rx.await else {
+                return;
+            };
+            let Some(choice) = choices.get(index).copied() else {
+                return;
+            };
+            let _ = this.update(cx, |this, cx| match choice {
+                RevisionChoice::Content => this.show_revision(entry, cx),
+                RevisionChoice::Diff => this.show_diff(entry, cx),
+            });
+        })
+        .detach();
+    }
+
+    fn show_revision(&mut self, entry: GitFileLogEntry, cx: &mut Context<Self>) {
+        let handle = self.session_handle.clone();
+        let preview = self.preview.clone();
+        cx.spawn(async move |_this, cx| {
+            let result = handle.git_show_file(&entry.id, &entry.path).await;
+            let filename = entry
+                .path
+                .rsplit('/')
+                .next()
+                .unwrap_or(&entry.path)
+                .to_string();
+            let subtitle = format!("{} · {}", short_id(&entry.id), entry.message);
+            let content = match result {
+                Ok(file) if file.too_large => Err("File too large (>500 KB)".to_string()),
+                Ok(file) if file.binary => Err("Binary file".to_string()),
+                Ok(file) => Ok(file.content),
+                Err(e) => Err(e.to_string()),
+            };
+            let content = match content {
+                Ok(content) => content,
+                Err(message) => {
+                    platform_bridge::show_alert(
+                        "Can't show this revision",
+                        &message,
+                        vec![AlertButton::cancel("OK")],
+                        |_| {},
+                    );
+                    return;
+                }
+            };
+            let _ = preview.update(cx, |preview, cx| {
+                preview.open_content(filename, subtitle, &entry.path, content, cx);
+            });
+            view_telemetry::record(view_telemetry::custom_sheet_file(&entry.path));
+            platform_bridge::show_custom_sheet(commit_sheet_options(), preview);
+        })
+        .detach();
+    }
+
+    fn show_diff(&mut self, entry: GitFileLogEntry, cx: &mut Context<Self>) {
+        self.commit_sheet
+            .update(cx, |sheet, cx| sheet.open(commit_ref(&entry), cx));
+        platform_bridge::show_custom_sheet(commit_sheet_options(), self.commit_sheet.clone());
+        view_telemetry::record(view_telemetry::CUSTOM_SHEET_COMMIT_DETAIL);
+    }
+
+    fn render_entry(
+        &self,
+        index: usize,
+        entry: &GitFileLogEntry,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let date = chrono::DateTime::from_timestamp(entry.timestamp, 0)
+            .map(|at| at.format("%Y-%m-%d").to_string())
+            .unwrap_or_default();
+        let detail = match &entry.old_path {
+            Some(old_path) => format!(
+                "{} · {} · {date} · from {old_path}",
+                short_id(&entry.id),
+                entry.author
+            ),
+            None => format!("{} · {} · {date}", short_id(&entry.id), entry.author),
+        };
+        div()
+            .id(("file-history-row", index))
+            .min_w_0()
+            .flex()
+            .flex_row()
+            .items_start()
+            .gap(px(theme::SPACING_SM))
+            .py(px(theme::SPACING_XS))
+            .cursor_pointer()
+            .on_press(cx.listener(move |this, _event, _window, cx| {
+                platform_bridge::trigger_haptic(HapticFeedback::ImpactLight);
+                this.choose_revision(index, cx);
+            }))
+            .child(
+                div()
+                    .w(px(56.0))
+                    .flex_none()
+                    .text_size(theme::scaled(theme::FONT_DETAIL))
+                    .text_color(rgb(status_color(&entry.status, cx)))
+                    .child(status_label(&entry.status)),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .min_w_0()
+                    .flex()
+                    .flex_col()
+                    .child(
+                        div()
+                            .truncate()
+                            .text_size(theme::scaled(theme::FONT_BODY))
+                            .text_color(rgb(theme::text_primary(cx)))
+                            .child(entry.message.clone()),
+                    )
+                    .child(
+                        div()
+                            .truncate()
+                            .text_size(theme::scaled(theme::FONT_DETAIL))
+                            .font_family(fonts::MONO_FONT_FAMILY)
+                            .text_color(rgb(theme::text_muted(cx)))
+                            .child(detail),
+                    ),
+            )
+    }
+}
+
+impl Render for FileHistoryView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let mut list = div().flex().flex_col().min_w_0();
+        list = match &self.state {
+            HistoryState::Loading if self.entries.is_empty() => {
+                list.child(subscreen_empty_text("Loading history…", cx))
+            }
+            HistoryState::Error(message) => list.child(subscreen_empty_text(message.clone(), cx)),
+            _ if self.entries.is_empty() => {
+                list.child(subscreen_empty_text("No commits touch this file.", cx))
+            }
+            _ => {
+                let rows: Vec<AnyElement> = self
+                    .entries
+                    .iter()
+                    .enumerate()
+                    .map(|(index, entry)| self.render_entry(index, entry, cx).into_any_element())
+                    .collect();
+                list.children(rows)
+            }
+        };
+        let body = subscreen_padded_body(list).into_any_element();
+        let header = render_history_header(self.subtitle(), cx).into_any_element();
+        subscreen_refreshable_page(
+            "file-history",
+            rgb(theme::bg_primary(cx)),
+            header,
+            body,
+            &self.pull_refresh,
+            cx,
+        )
+    }
+}
+
+fn render_history_header(subtitle: String, cx: &mut Context<FileHistoryView>) -> impl IntoElement {
+    div()
+        .id("file-history-header")
+        .min_w_0()
+        .px(px(theme::SUBSCREEN_PADDING_X))
+        .pt(px(theme::SPACING_XS))
+        .pb(px(theme::SPACING_SM))
+        .child(
+            div()
+                .id("file-history-header-inner")
+                .relative()
+                .min_w_0()
+                .child(
+                    div()
+                        .min_w_0()
+                        .flex()
+                        .flex_row()
+                        .items_center()
+                        .gap(px(theme::SPACING_MD))
+                        .child(chevron_back_button(
+                            "file-history-back-btn",
+                            cx,
+                            |_this, _event, window, cx| {
+                                platform_bridge::trigger_haptic(HapticFeedback::ImpactLight);
+                                window.dispatch_action(
+                                    workspace_action::NavigateBack.boxed_clone(),
+                                    cx,
+                                );
+                            },
+                        ))
+                        .child(
+                            div()
+                                .flex_1()
+                                .min_w_0()
+                                .flex()
+                                .flex_col()
+                                .child(
+                                    div()
+                                        .text_size(theme::scaled(theme::FONT_HEADING))
+                                        .font_family(fonts::HEADING_FONT_FAMILY)
+                                        .font_weight(FontWeight::MEDIUM)
+                                        .text_color(rgb(theme::text_primary(cx)))
+                                        .child("File History"),
+                                )
+                                .child(
+                                    div()
+                                        .truncate()
+                                        .text_size(theme::scaled(theme::FONT_BODY))
+                                        .text_color(rgb(theme::text_muted(cx)))
+                                        .child(subtitle),
+                                ),
+                        ),
+                )
+                .child(subscreen_refresh_button(
+                    "file-history-refresh-btn",
+                    cx,
+                    |this, _event, _window, cx| this.load(cx),
+                )),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_keep_rename_paths_for_the_commit_sheet() {
+        assert_eq!(status_label("D"), "deleted");
+        assert_eq!(status_label("T"), "modified");
+        let entry = GitFileLogEntry {
+            path: "src/new.rs".into(),
+            old_path: Some("src/old.rs".into()),
+            status: "R".into(),
+            ..Default::default()
+        };
+        assert_eq!(
+            commit_ref(&entry).diff_paths(),
+            vec!["src/new.rs", "src/old.rs"]
+        );
+    }
+}
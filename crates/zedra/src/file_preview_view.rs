@@ -1,19 +1,37 @@
 use gpui::*;
 use tracing::{error, warn};
+use zedra_rpc::proto::FsReadResult;
 use zedra_session::SessionHandle;
 use zedra_terminal::terminal::{TerminalHyperlink, TerminalHyperlinkTarget};
 
-use crate::editor::code_editor::{EditorView, ParsedEditorSyntax};
+use crate::editor::code_editor::{EditorDocument, EditorView, ParsedEditorSyntax};
 use crate::editor::markdown::{MarkdownView, is_markdown_path, parse_markdown_source};
 use crate::fonts;
 use crate::native_presentation;
 use crate::placeholder::render_placeholder;
+use crate::settings;
 use crate::theme;
 use crate::workspace::ActiveWorkspace;
 use crate::workspace_action::AddSelectionToChat;
 use crate::workspace_editor::{EditorSelection, resolve_read_only_selection};
 use crate::workspace_state::WorkspaceState;
 
+/// Preview size while the data saver is on; the subtitle notes the cut.
+const DATA_SAVER_PREVIEW_BYTES: u64 = 64 * 1024;
+
+/// Whole file, or only its head when `head_bytes` is set. The flag reports
+/// whether the file continues past what was read.
+async fn read_preview(
+    handle: &SessionHandle,
+    path: &str,
+    head_bytes: Option<u64>,
+) -> anyhow::Result<(FsReadResult, bool)> {
+    match head_bytes {
+        Some(max_bytes) => handle.fs_read_head(path, max_bytes).await,
+        None => Ok((handle.fs_read(path).await?, false)),
+    }
+}
+
 #[derive(Clone, Debug)]
 enum PreviewState {
     Idle,
@@ -154,111 +172,135 @@ impl FilePreviewView {
                 drop(prev_task);
 
                 let handle = self.session_handle.clone();
-                let filename = self.title.to_string();
+                let document = EditorDocument::new(path.clone())
+                    .initial_line(line)
+                    .read_only(true);
                 let content_kind = self.content;
-                let read_task = cx.spawn(async move |this, cx| match handle.fs_read(&path).await {
-                    Ok(result) if result.too_large => {
-                        let _ = this.update(cx, |this, cx| {
-                            if this.should_ignore_load_result(epoch, &path, content_kind) {
-                                return;
-                            }
-                            this.state = PreviewState::TooLarge;
-                            cx.notify();
-                        });
-                    }
-                    Ok(result) if result.error.is_some() => {
-                        let msg = result.error.unwrap_or("unknown error".to_string());
-                        let _ = this.update(cx, |this, cx| {
-                            if this.should_ignore_load_result(epoch, &path, content_kind) {
-                                return;
-                            }
-                            error!("terminal link preview fs/read error for {}: {}", path, msg);
-                            this.state = PreviewState::Error(msg);
-                            cx.notify();
-                        });
-                    }
-                    Ok(result) => match content_kind {
-                        PreviewContent::Editor => {
-                            let content = result.content;
-                            let content_for_syntax = content.clone();
-                            let syntax_filename = filename.clone();
-                            if let Err(e) = this.update(cx, |this, cx| {
-                                if this.should_ignore_load_result(
-                                    epoch,
-                                    &path,
-                                    PreviewContent::Editor,
-                                ) {
+                let head_bytes =
+                    settings::data_saver_active(cx).then_some(DATA_SAVER_PREVIEW_BYTES);
+                let read_task = cx.spawn(async move |this, cx| {
+                    match read_preview(&handle, &path, head_bytes).await {
+                        Ok((result, _)) if result.too_large => {
+                            let _ = this.update(cx, |this, cx| {
+                                if this.should_ignore_load_result(epoch, &path, content_kind) {
                                     return;
                                 }
-                                this.state = PreviewState::Loaded;
-                                this.editor_view.update(cx, |editor_view, _cx| {
-                                    editor_view
-                                        .set_content_with_initial_line(&filename, content, line);
-                                    native_presentation::set_sheet_content_at_top(
-                                        editor_view.is_scrolled_to_file_top(),
-                                    );
-                                });
-                                this.update_sheet_scroll_boundary(cx);
+                                this.state = PreviewState::TooLarge;
                                 cx.notify();
-                            }) {
-                                error!("terminal link preview update failed for {}: {}", path, e);
-                                return;
-                            }
-
-                            let parsed_syntax = cx
-                                .background_spawn(async move {
-                                    ParsedEditorSyntax::build(&syntax_filename, content_for_syntax)
-                                })
-                                .await;
-
+                            });
+                        }
+                        Ok((result, _)) if result.error.is_some() => {
+                            let msg = result.error.unwrap_or("unknown error".to_string());
                             let _ = this.update(cx, |this, cx| {
-                                if this.should_ignore_load_result(
-                                    epoch,
-                                    &path,
-                                    PreviewContent::Editor,
-                                ) {
+                                if this.should_ignore_load_result(epoch, &path, content_kind) {
                                     return;
                                 }
-                                this.editor_view.update(cx, |editor_view, _cx| {
-                                    editor_view.apply_parsed_syntax(parsed_syntax);
-                                });
+                                error!("terminal link preview fs/read error for {}: {}", path, msg);
+                                this.state = PreviewState::Error(msg);
                                 cx.notify();
                             });
                         }
-                        PreviewContent::Markdown => {
-                            let parsed = cx
-                                .background_spawn(
-                                    async move { parse_markdown_source(result.content) },
-                                )
-                                .await;
+                        Ok((result, truncated)) => {
+                            if truncated {
+                                let _ = this.update(cx, |this, cx| {
+                                    if this.should_ignore_load_result(epoch, &path, content_kind) {
+                                        return;
+                                    }
+                                    this.subtitle = format!(
+                                        "{} · first {} KB",
+                                        this.subtitle,
+                                        DATA_SAVER_PREVIEW_BYTES / 1024
+                                    )
+                                    .into();
+                                    cx.notify();
+                                });
+                            }
+                            match content_kind {
+                                PreviewContent::Editor => {
+                                    let content = result.content;
+                                    let content_for_syntax = content.clone();
+                                    let language = document.resolved_language();
+                                    if let Err(e) = this.update(cx, |this, cx| {
+                                        if this.should_ignore_load_result(
+                                            epoch,
+                                            &path,
+                                            PreviewContent::Editor,
+                                        ) {
+                                            return;
+                                        }
+                                        this.state = PreviewState::Loaded;
+                                        this.editor_view.update(cx, |editor_view, _cx| {
+                                            editor_view.set_document(document, content);
+                                            native_presentation::set_sheet_content_at_top(
+                                                editor_view.is_scrolled_to_file_top(),
+                                            );
+                                        });
+                                        this.update_sheet_scroll_boundary(cx);
+                                        cx.notify();
+                                    }) {
+                                        error!(
+                                            "terminal link preview update failed for {}: {}",
+                                            path, e
+                                        );
+                                        return;
+                                    }
+
+                                    let parsed_syntax = cx
+                                        .background_spawn(async move {
+                                            ParsedEditorSyntax::build(language, content_for_syntax)
+                                        })
+                                        .await;
+
+                                    let _ = this.update(cx, |this, cx| {
+                                        if this.should_ignore_load_result(
+                                            epoch,
+                                            &path,
+                                            PreviewContent::Editor,
+                                        ) {
+                                            return;
+                                        }
+                                        this.editor_view.update(cx, |editor_view, _cx| {
+                                            editor_view.apply_parsed_syntax(parsed_syntax);
+                                        });
+                                        cx.notify();
+                                    });
+                                }
+                                PreviewContent::Markdown => {
+                                    let parsed = cx
+                                        .background_spawn(async move {
+                                            parse_markdown_source(result.content)
+                                        })
+                                        .await;
+                                    let _ = this.update(cx, |this, cx| {
+                                        if this.should_ignore_load_result(
+                                            epoch,
+                                            &path,
+                                            PreviewContent::Markdown,
+                                        ) {
+                                            return;
+                                        }
+                                        this.state = PreviewState::Loaded;
+                                        this.markdown_view.update(cx, |markdown_view, cx| {
+                                            markdown_view.set_parsed_source(parsed, cx);
+                                        });
+                                        this.update_sheet_scroll_boundary(cx);
+                                        cx.notify();
+                                    });
+                                }
+                            }
+                        }
+                        Err(err) => {
+                            let msg = err.to_string();
                             let _ = this.update(cx, |this, cx| {
-                                if this.should_ignore_load_result(
-                                    epoch,
-                                    &path,
-                                    PreviewContent::Markdown,
-                                ) {
+                                if this.should_ignore_load_result(epoch, &path, content_kind) {
                                     return;
                                 }
-                                this.state = PreviewState::Loaded;
-                                this.markdown_view.update(cx, |markdown_view, cx| {
-                                    markdown_view.set_parsed_source(parsed, cx);
-                                });
+                                error!("terminal link preview fs/read error for {}: {}", path, msg);
+                                this.state = PreviewState::Error(msg);
                                 this.update_sheet_scroll_boundary(cx);
                                 cx.notify();
                             });
                         }
-                    },
-                    Err(err) => {
-                        let msg = err.to_string();
-                        let _ = this.update(cx, |this, cx| {
-                            if this.should_ignore_load_result(epoch, &path, content_kind) {
-                                return;
-                            }
-                            error!("terminal link preview fs/read error for {}: {}", path, msg);
-                            this.state = PreviewState::Error(msg);
-                            this.update_sheet_scroll_boundary(cx);
-                            cx.notify();
-                        });
                     }
                 });
                 self.read_task = Some(read_task);
@@ -311,10 +353,11 @@ impl FilePreviewView {
         match self.content {
             PreviewContent::Editor => {
                 self.state = PreviewState::Loaded;
-                let filename = self.title.to_string();
+                let document = EditorDocument::new(path.clone()).read_only(true);
+                let language = document.resolved_language();
                 let content_for_syntax = content.clone();
                 self.editor_view.update(cx, |editor_view, _cx| {
-                    editor_view.set_content_with_initial_line(&filename, content, None);
+                    editor_view.set_document(document, content);
                     native_presentation::set_sheet_content_at_top(
                         editor_view.is_scrolled_to_file_top(),
                     );
@@ -324,7 +367,7 @@ impl FilePreviewView {
                 self.read_task = Some(cx.spawn(async move |this, cx| {
                     let parsed = cx
                         .background_spawn(async move {
-                            ParsedEditorSyntax::build(&filename, content_for_syntax)
+                            ParsedEditorSyntax::build(language, content_for_syntax)
                         })
                         .await;
                     let _ = this.update(cx, |this, cx| {
@@ -435,7 +478,7 @@ impl Render for FilePreviewView {
                     .child(
                         div()
                             .text_color(rgb(theme::text_primary(cx)))
-                            .text_size(px(theme::FONT_HEADING))
+                            .text_size(theme::scaled(theme::FONT_HEADING))
                             .font_family(fonts::HEADING_FONT_FAMILY)
                             .font_weight(FontWeight::MEDIUM)
                             .child(self.title.clone()),
@@ -443,7 +486,7 @@ impl Render for FilePreviewView {
                     .child(
                         div()
                             .text_color(rgb(theme::text_muted(cx)))
-                            .text_size(px(theme::FONT_DETAIL))
+                            .text_size(theme::scaled(theme::FONT_DETAIL))
                             .font_family(fonts::MONO_FONT_FAMILY)
                             .child(self.subtitle.clone()),
                     ),
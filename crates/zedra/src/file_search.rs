@@ -9,7 +9,7 @@ use gpui::{prelude::FluentBuilder as _, *};
 use tracing::*;
 
 use zedra_rpc::proto::{FS_SEARCH_DEFAULT_LIMIT, FsSearchEntry};
-use zedra_session::SessionHandle;
+use zedra_session::{HostFeature, SessionHandle};
 
 use crate::theme;
 use crate::ui::InputChanged;
@@ -250,7 +250,7 @@ impl FileSearchPanel {
                         div()
                             .min_w_0()
                             .truncate()
-                            .text_size(px(theme::FONT_BODY))
+                            .text_size(theme::scaled(theme::FONT_BODY))
                             .text_color(rgb(theme::text_primary(cx)))
                             .child(name),
                     )
@@ -282,7 +282,7 @@ impl FileSearchPanel {
                                         .flex_1()
                                         .min_w_0()
                                         .truncate()
-                                        .text_size(px(theme::FONT_DETAIL))
+                                        .text_size(theme::scaled(theme::FONT_DETAIL))
                                         .text_color(rgb(theme::text_muted(cx)))
                                         .child(worktree),
                                 ),
@@ -300,7 +300,7 @@ impl FileSearchPanel {
             .items_center()
             .justify_center()
             .px(px(theme::SPACING_MD))
-            .text_size(px(theme::FONT_BODY))
+            .text_size(theme::scaled(theme::FONT_BODY))
             .text_color(rgb(theme::text_muted(cx)))
             .child(message.into())
             .into_any_element()
@@ -333,7 +333,10 @@ impl Render for FileSearchPanel {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let has_query = !self.query.trim().is_empty();
 
-        let body: AnyElement = if !has_query {
+        let body: AnyElement = if !self.session_handle.supports(HostFeature::Search) {
+            let notice = zedra_session::update_notice(&[HostFeature::Search]).unwrap_or_default();
+            self.render_message(notice, cx)
+        } else if !has_query {
             self.render_message("Type to search files", cx)
         } else if self.loading {
             self.render_message("Searching…", cx)
@@ -359,7 +362,7 @@ impl Render for FileSearchPanel {
                     .py(px(theme::SPACING_SM))
                     .border_t_1()
                     .border_color(rgb(theme::border_subtle(cx)))
-                    .text_size(px(theme::FONT_DETAIL))
+                    .text_size(theme::scaled(theme::FONT_DETAIL))
                     .text_color(rgb(theme::text_muted(cx)))
                     .child("Showing first matches")
             });
@@ -411,6 +414,7 @@ impl Render for FileSearchPanel {
                                 .items_center()
                                 .justify_center()
                                 .cursor_pointer()
+                                .hit_slop(theme::touch_slop(32.0))
                                 .on_pointer_down(|_, _, cx| cx.stop_propagation())
                                 .on_press(cx.listener(|this, _event, window, cx| {
                                     this.clear_query(window, cx);
@@ -464,7 +468,7 @@ fn build_highlighted_path(rel_path: &str, match_indices: &[u32], cx: &App) -> An
     for (segment, matched) in highlight_runs(rel_path, match_indices) {
         let seg = div()
             .flex_shrink_0()
-            .text_size(px(theme::FONT_DETAIL))
+            .text_size(theme::scaled(theme::FONT_DETAIL))
             .child(segment);
         let seg = if matched {
             seg.text_color(rgb(theme::text_primary(cx)))
@@ -1,5 +1,8 @@
 use std::borrow::Cow;
 
+use gpui::{FontFallbacks, SharedString};
+use serde::{Deserialize, Serialize};
+
 static FONTS: &[&[u8]] = &[
     include_bytes!("../assets/fonts/Lora-VariableFont_wght.ttf"),
     include_bytes!("../assets/fonts/JetBrainsMono/JetBrainsMonoNLNerdFontMono-Regular.ttf"),
@@ -33,6 +36,53 @@ pub const MONO_FONT_FAMILY: &str = "JetBrainsMonoNL Nerd Font Mono";
 /// The font family name for the symbol fallback font
 pub const SYMBOL_FONT_FAMILY: &str = "Noto Sans Symbols 2";
 
+/// Platform monospace family offered as the alternative code font.
+#[cfg(any(target_os = "ios", target_os = "macos"))]
+const SYSTEM_MONO_FONT_FAMILY: &str = "Menlo";
+#[cfg(not(any(target_os = "ios", target_os = "macos")))]
+const SYSTEM_MONO_FONT_FAMILY: &str = "Droid Sans Mono";
+
+/// Monospace font for terminal and editor text, chosen in Settings.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CodeFont {
+    /// Bundled JetBrains Mono NL Nerd Font, including powerline and devicon glyphs.
+    #[default]
+    JetBrainsMonoNerd,
+    /// The platform monospace font. Nerd Font glyphs fall back to the bundled font.
+    System,
+}
+
+impl CodeFont {
+    pub fn family(self) -> &'static str {
+        match self {
+            Self::JetBrainsMonoNerd => MONO_FONT_FAMILY,
+            Self::System => SYSTEM_MONO_FONT_FAMILY,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::JetBrainsMonoNerd => "Nerd",
+            Self::System => "System",
+        }
+    }
+
+    /// Fallbacks so glyphs missing from the chosen family still render.
+    pub fn fallbacks(self) -> FontFallbacks {
+        let mut fallbacks = Vec::new();
+        if self.family() != MONO_FONT_FAMILY {
+            fallbacks.push(MONO_FONT_FAMILY.to_string());
+        }
+        fallbacks.push(SYMBOL_FONT_FAMILY.to_string());
+        FontFallbacks::from_fonts(fallbacks)
+    }
+
+    pub fn family_name(self) -> SharedString {
+        SharedString::new_static(self.family())
+    }
+}
+
 /// Load all embedded fonts into GPUI's text system.
 ///
 /// Called on every window open. The platform text system can be recreated
@@ -0,0 +1,78 @@
+// Git credential prompt — answers `HostEvent::GitCredentialRequested`.
+//
+// A host-side `git push` over HTTPS that finds no stored credential asks the
+// device. The user enters a username (skipped when the remote URL names one)
+// and a password or token, then picks whether the host keychain keeps it.
+// Cancelling any step cancels the push.
+
+use tracing::*;
+
+use zedra_rpc::proto::GitCredential;
+use zedra_session::SessionHandle;
+
+use crate::platform_bridge::{self, AlertButton};
+
+/// Prompt for the credential and send it, or the cancellation, to the host.
+pub async fn answer(handle: SessionHandle, id: String, url: String, username: Option<String>) {
+    let credential = prompt(&url, username).await;
+    if credential.is_none() {
+        info!("git credential: prompt cancelled");
+    }
+    if let Err(e) = handle.git_credential_reply(&id, credential).await {
+        warn!("git credential: reply failed: {}", e);
+        platform_bridge::show_alert(
+            "Sign-in expired",
+            "The host stopped waiting for credentials. Push again to retry.",
+            vec![AlertButton::default("OK")],
+            |_| {},
+        );
+    }
+}
+
+async fn prompt(url: &str, username: Option<String>) -> Option<GitCredential> {
+    let username = match username.filter(|username| !username.is_empty()) {
+        Some(username) => username,
+        None => {
+            let (tx, rx) = tokio::sync::oneshot::channel();
+            platform_bridge::show_text_input(
+                &format!("Sign in to {url}"),
+                "Username",
+                "",
+                move |value| {
+                    let _ = tx.send(value);
+                },
+            );
+            rx.await.ok().flatten()?
+        }
+    };
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    platform_bridge::show_secure_text_input(
+        &format!("Password for {username}"),
+        "Password or access token",
+        move |value| {
+            let _ = tx.send(value);
+        },
+    );
+    let password = rx.await.ok().flatten().filter(|value| !value.is_empty())?;
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    platform_bridge::show_alert(
+        "Remember on host?",
+        "Save this credential in the host keychain for later pushes.",
+        vec![
+            AlertButton::default("Save in Keychain"),
+            AlertButton::cancel("Use Once"),
+        ],
+        move |index| {
+            let _ = tx.send(index);
+        },
+    );
+    let remember = matches!(rx.await, Ok(0));
+
+    Some(GitCredential {
+        username,
+        password,
+        remember,
+    })
+}
@@ -0,0 +1,403 @@
+use futures::channel::oneshot;
+use gpui::prelude::FluentBuilder;
+use gpui::*;
+use zedra_rpc::proto::{GitGraphCommit, GitGraphEdge};
+use zedra_session::SessionHandle;
+
+use crate::fonts;
+use crate::platform_bridge::{self, AlertButton, HapticFeedback};
+use crate::theme;
+use crate::ui::{
+    PullToRefresh, PullToRefreshEvent, chevron_back_button, subscreen_empty_text,
+    subscreen_padded_body, subscreen_refresh_button, subscreen_refreshable_page,
+};
+use crate::workspace_action;
+
+const ROW_HEIGHT: f32 = 40.0;
+const LANE_WIDTH: f32 = 14.0;
+/// Wider graphs squeeze their lanes instead of pushing the text off screen.
+const GRAPH_MAX_WIDTH: f32 = 112.0;
+const NODE_SIZE: f32 = 8.0;
+const LINE_WIDTH: f32 = 1.5;
+
+#[derive(Clone, Debug)]
+enum GraphState {
+    Loading,
+    Ready,
+    Error(String),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum RefKind {
+    Head,
+    Tag,
+    Branch,
+}
+
+/// Split a git decoration into its kind and display label.
+fn ref_label(decoration: &str) -> (RefKind, &str) {
+    if let Some(tag) = decoration.strip_prefix("tag: ") {
+        (RefKind::Tag, tag)
+    } else if let Some(branch) = decoration.strip_prefix("HEAD -> ") {
+        (RefKind::Head, branch)
+    } else if decoration == "HEAD" {
+        (RefKind::Head, decoration)
+    } else {
+        (RefKind::Branch, decoration)
+    }
+}
+
+/// Distance between lane centers when `lane_count` lanes share the graph.
+fn lane_width(lane_count: u32) -> f32 {
+    LANE_WIDTH.min(GRAPH_MAX_WIDTH / lane_count.max(1) as f32)
+}
+
+fn lane_color(color: u32, cx: &App) -> Hsla {
+    let palette = [
+        theme::accent_blue(cx),
+        theme::accent_green(cx),
+        theme::accent_yellow(cx),
+        theme::accent_red(cx),
+        theme::text_secondary(cx),
+    ];
+    rgb(palette[color as usize % palette.len()]).into()
+}
+
+/// Commit graph screen: every branch's recent history with lanes laid out by
+/// the host. Tapping a row shows the full commit and offers to copy its hash.
+///
+/// The workspace keeps this view alive across navigation; refresh or pull to
+/// reload after new commits.
+pub struct GitGraphView {
+    session_handle: SessionHandle,
+    commits: Vec<GitGraphCommit>,
+    lane_count: u32,
+    truncated: bool,
+    state: GraphState,
+    load_epoch: u64,
+    pull_refresh: Entity<PullToRefresh>,
+    _load_task: Option<Task<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl GitGraphView {
+    pub fn new(session_handle: SessionHandle, cx: &mut Context<Self>) -> Self {
+        let pull_refresh = cx.new(|_| PullToRefresh::new());
+        let pull_sub = cx.subscribe(&pull_refresh, |this, _pull, event, cx| match event {
+            PullToRefreshEvent::Refresh => this.load(cx),
+        });
+        let mut view = Self {
+            session_handle,
+            commits: Vec::new(),
+            lane_count: 0,
+            truncated: false,
+            state: GraphState::Loading,
+            load_epoch: 0,
+            pull_refresh,
+            _load_task: None,
+            _subscriptions: vec![pull_sub],
+        };
+        view.load(cx);
+        view
+    }
+
+    fn load(&mut self, cx: &mut Context<Self>) {
+        self.load_epoch = self.load_epoch.wrapping_add(1);
+        let epoch = self.load_epoch;
+        self.state = GraphState::Loading;
+        cx.notify();
+
+        let handle = self.session_handle.clone();
+        self._load_task = Some(cx.spawn(async move |this, cx| {
+            let result = handle.git_graph(0).await;
+            let _ = this.update(cx, |this, cx| {
+                if this.load_epoch != epoch {
+                    return;
+                }
+                match result {
+                    Ok(result) => {
+                        this.commits = result.commits;
+                        this.lane_count = result.lane_count;
+                        this.truncated = result.truncated;
+                        this.state = GraphState::Ready;
+                    }
+                    Err(e) => {
+                        tracing::warn!("git graph: load failed: {}", e);
+                        this.state = GraphState::Error(e.to_string());
+                    }
+                }
+                this.pull_refresh.update(cx, |pull, cx| pull.finish(cx));
+                cx.notify();
+            });
+        }));
+    }
+
+    fn subtitle(&self) -> String {
+        match &self.state {
+            GraphState::Loading => "Loading history…".to_string(),
+            GraphState::Error(_) => "Load failed".to_string(),
+            GraphState::Ready => {
+                let suffix = if self.truncated { "+" } else { "" };
+                format!("{}{suffix} commits", self.commits.len())
+            }
+        }
+    }
+
+    fn show_commit(&mut self, index: usize, cx: &mut Context<Self>) {
+        let Some(commit) = self.commits.get(index) else {
+            return;
+        };
+        let hash = commit.hash.clone();
+        let (tx, rx) = oneshot::channel();
+        platform_bridge::show_alert(
+            &commit.subject,
+            &format!("{}\n{}", commit.hash, commit.author),
+            vec![
+                AlertButton::default("Copy Hash"),
+                AlertButton::cancel("Done"),
+            ],
+            move |index| {
+                let _ = tx.send(index);
+            },
+        );
+        cx.spawn(async move |_this, cx| {
+            if rx.await == Ok(0) {
+                let _ = cx.update(|cx| cx.write_to_clipboard(ClipboardItem::new_string(hash)));
+            }
+        })
+        .detach();
+    }
+
+    fn render_row(
+        &self,
+        index: usize,
+        commit: &GitGraphCommit,
+        cx: &mut Context<Self>,
+    ) -> Stateful<Div> {
+        let lane_width = lane_width(self.lane_count);
+        let graph_width = lane_width * self.lane_count.max(1) as f32;
+        let node_color = lane_color(commit.color, cx);
+        let edges: Vec<(GitGraphEdge, Hsla)> = commit
+            .edges
+            .iter()
+            .map(|edge| (*edge, lane_color(edge.color, cx)))
+            .collect();
+        let lane = commit.lane;
+        let graph = canvas(
+            |_, _, _| (),
+            move |bounds, _, window, _cx| {
+                let x = |lane: u32| bounds.origin.x + px((lane as f32 + 0.5) * lane_width);
+                let center_y = bounds.origin.y + px(ROW_HEIGHT / 2.0);
+                // Lines reach the next row's node level, under its node.
+                for (edge, color) in &edges {
+                    let mut path = PathBuilder::stroke(px(LINE_WIDTH));
+                    path.move_to(point(x(edge.from_lane), center_y));
+                    path.line_to(point(x(edge.to_lane), center_y + px(ROW_HEIGHT)));
+                    if let Ok(path) = path.build() {
+                        window.paint_path(path, *color);
+                    }
+                }
+                let node = Bounds::new(
+                    point(
+                        x(lane) - px(NODE_SIZE / 2.0),
+                        center_y - px(NODE_SIZE / 2.0),
+                    ),
+                    size(px(NODE_SIZE), px(NODE_SIZE)),
+                );
+                window.paint_quad(fill(node, node_color).corner_radii(px(NODE_SIZE / 2.0)));
+            },
+        )
+        .flex_none()
+        .w(px(graph_width))
+        .h(px(ROW_HEIGHT));
+
+        let short_hash: String = commit.hash.chars().take(7).collect();
+        let date = chrono::DateTime::from_timestamp(commit.timestamp, 0)
+            .map(|at| at.format("%Y-%m-%d").to_string())
+            .unwrap_or_default();
+        let detail = format!("{short_hash} · {} · {date}", commit.author);
+
+        div()
+            .id(("git-graph-row", index))
+            .h(px(ROW_HEIGHT))
+            .min_w_0()
+            .flex()
+            .flex_row()
+            .items_center()
+            .gap(px(theme::SPACING_SM))
+            .cursor_pointer()
+            .on_press(cx.listener(move |this, _event, _window, cx| {
+                platform_bridge::trigger_haptic(HapticFeedback::ImpactLight);
+                this.show_commit(index, cx);
+            }))
+            .child(graph)
+            .child(
+                div()
+                    .flex_1()
+                    .min_w_0()
+                    .flex()
+                    .flex_col()
+                    .child(
+                        div()
+                            .min_w_0()
+                            .flex()
+                            .flex_row()
+                            .items_center()
+                            .gap(px(theme::SPACING_XS))
+                            .children(
+                                commit
+                                    .refs
+                                    .iter()
+                                    .map(|decoration| render_ref_badge(decoration, cx)),
+                            )
+                            .child(
+                                div()
+                                    .flex_1()
+                                    .min_w_0()
+                                    .truncate()
+                                    .text_size(theme::scaled(theme::FONT_BODY))
+                                    .text_color(rgb(theme::text_primary(cx)))
+                                    .child(commit.subject.clone()),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .truncate()
+                            .text_size(theme::scaled(theme::FONT_DETAIL))
+                            .font_family(fonts::MONO_FONT_FAMILY)
+                            .text_color(rgb(theme::text_muted(cx)))
+                            .child(detail),
+                    ),
+            )
+    }
+}
+
+fn render_ref_badge(decoration: &str, cx: &App) -> impl IntoElement {
+    let (kind, label) = ref_label(decoration);
+    let color = match kind {
+        RefKind::Head => theme::accent_green(cx),
+        RefKind::Tag => theme::accent_yellow(cx),
+        RefKind::Branch => theme::accent_blue(cx),
+    };
+    div()
+        .flex_none()
+        .max_w(px(120.0))
+        .truncate()
+        .px(px(theme::BADGE_PX))
+        .rounded(px(theme::BADGE_RADIUS))
+        .border_1()
+        .border_color(rgb(color))
+        .text_size(theme::scaled(theme::FONT_DETAIL))
+        .text_color(rgb(color))
+        .when(kind == RefKind::Head, |el| {
+            el.font_weight(FontWeight::MEDIUM)
+        })
+        .child(label.to_string())
+}
+
+impl Render for GitGraphView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let mut list = div().flex().flex_col().min_w_0();
+        list = match &self.state {
+            GraphState::Loading if self.commits.is_empty() => {
+                list.child(subscreen_empty_text("Loading history…", cx))
+            }
+            GraphState::Error(message) => list.child(subscreen_empty_text(message.clone(), cx)),
+            _ if self.commits.is_empty() => list.child(subscreen_empty_text("No commits yet.", cx)),
+            _ => {
+                let rows: Vec<Stateful<Div>> = self
+                    .commits
+                    .iter()
+                    .enumerate()
+                    .map(|(index, commit)| self.render_row(index, commit, cx))
+                    .collect();
+                list.children(rows)
+            }
+        };
+        let body = subscreen_padded_body(list).into_any_element();
+        let header = render_graph_header(self.subtitle(), cx).into_any_element();
+        subscreen_refreshable_page(
+            "git-graph",
+            rgb(theme::bg_primary(cx)),
+            header,
+            body,
+            &self.pull_refresh,
+            cx,
+        )
+    }
+}
+
+fn render_graph_header(subtitle: String, cx: &mut Context<GitGraphView>) -> impl IntoElement {
+    div()
+        .id("git-graph-header")
+        .min_w_0()
+        .px(px(theme::SUBSCREEN_PADDING_X))
+        .pt(px(theme::SPACING_XS))
+        .pb(px(theme::SPACING_SM))
+        .child(
+            div()
+                .id("git-graph-header-inner")
+                .relative()
+                .min_w_0()
+                .child(
+                    div()
+                        .min_w_0()
+                        .flex()
+                        .flex_row()
+                        .items_center()
+                        .gap(px(theme::SPACING_MD))
+                        .child(chevron_back_button(
+                            "git-graph-back-btn",
+                            cx,
+                            |_this, _event, window, cx| {
+                                platform_bridge::trigger_haptic(HapticFeedback::ImpactLight);
+                                window.dispatch_action(
+                                    workspace_action::NavigateBack.boxed_clone(),
+                                    cx,
+                                );
+                            },
+                        ))
+                        .child(
+                            div()
+                                .flex_1()
+                                .min_w_0()
+                                .flex()
+                                .flex_col()
+                                .child(
+                                    div()
+                                        .text_size(theme::scaled(theme::FONT_HEADING))
+                                        .font_family(fonts::HEADING_FONT_FAMILY)
+                                        .font_weight(FontWeight::MEDIUM)
+                                        .text_color(rgb(theme::text_primary(cx)))
+                                        .child("Commit Graph"),
+                                )
+                                .child(
+                                    div()
+                                        .text_size(theme::scaled(theme::FONT_BODY))
+                                        .text_color(rgb(theme::text_muted(cx)))
+                                        .child(subtitle),
+                                ),
+                        ),
+                )
+                .child(subscreen_refresh_button(
+                    "git-graph-refresh-btn",
+                    cx,
+                    |this, _event, _window, cx| this.load(cx),
+                )),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ref_labels_split_head_tags_and_branches() {
+        assert_eq!(ref_label("HEAD -> main"), (RefKind::Head, "main"));
+        assert_eq!(ref_label("HEAD"), (RefKind::Head, "HEAD"));
+        assert_eq!(ref_label("tag: v1.2"), (RefKind::Tag, "v1.2"));
+        assert_eq!(ref_label("origin/main"), (RefKind::Branch, "origin/main"));
+        assert_eq!(lane_width(2), LANE_WIDTH);
+        assert!(lane_width(20) * 20.0 <= GRAPH_MAX_WIDTH + f32::EPSILON);
+    }
+}
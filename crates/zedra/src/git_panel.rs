@@ -1,16 +1,42 @@
 use gpui::*;
 use tracing::*;
 
-use zedra_rpc::proto::{GitStatusEntry, HostEvent};
-use zedra_session::{Session, SessionHandle, SessionState};
+use zedra_rpc::proto::{
+    AiPromptReqV2, ForgeCreatePrReq, GitDiffStatEntry, GitDiffStatResult, GitDiscoveredRepo,
+    GitFetchReq, GitPullMode, GitPullReq, GitPushReqV2, GitRemoteFailure, GitStatusEntry,
+    HostEvent,
+};
+use zedra_session::{HostFeature, Session, SessionHandle, SessionState};
 
+use crate::ai_approval;
+use crate::branch_picker::{BranchPicker, BranchPickerEvent};
 use crate::editor::git_sidebar::{
-    GitCommitRequested, GitFileEntry, GitFileLongPressed, GitFileSection, GitFileSelected,
-    GitFileStatus, GitRepoState, GitSidebar,
+    GitBranchPickerRequested, GitCommitMessageRequested, GitCommitRequested, GitFetchRequested,
+    GitFileEntry, GitFileLongPressed, GitFileSection, GitFileSelected, GitFileStatus,
+    GitPullRequestRequested, GitPullRequested, GitPushRequested, GitRebaseRequested,
+    GitRepoPickerRequested, GitRepoState, GitSidebar, GitStageRequested,
+};
+use crate::platform_bridge::{
+    self, AlertButton, CustomSheetDetent, CustomSheetOptions, ListPickerItem,
+    NativeNotificationKind, NativeNotificationOptions,
 };
+use crate::rebase_planner::{RebasePlanner, RebasePlannerEvent};
+use crate::telemetry::view_telemetry;
+use crate::ui::PullToRefreshEvent;
 use crate::workspace_action;
 use crate::workspace_state::WorkspaceState;
 
+/// Staged diff bytes sent with a commit message request; the rest is cut.
+const COMMIT_MESSAGE_DIFF_MAX: usize = 48 * 1024;
+
+/// Context size that asks before sending while the data saver is on.
+const DATA_SAVER_PROMPT_WARN: usize = 16 * 1024;
+
+const COMMIT_MESSAGE_PROMPT: &str = "Write a commit message for the staged diff in the \
+context, following Conventional Commits: a `type(scope): subject` line of at most 72 \
+characters in the imperative mood, then an optional short body explaining why. Reply with \
+the commit message only, no code fences or commentary.";
+
 pub struct GitPanel {
     #[allow(dead_code)]
     workspace_state: Entity<WorkspaceState>,
@@ -18,6 +44,8 @@ pub struct GitPanel {
     session_state: Entity<SessionState>,
     session_handle: SessionHandle,
     content: Entity<GitSidebar>,
+    branch_picker: Entity<BranchPicker>,
+    rebase_planner: Entity<RebasePlanner>,
     branch: String,
     tasks: Vec<Task<()>>,
     _subscriptions: Vec<Subscription>,
@@ -33,6 +61,14 @@ impl GitPanel {
         cx: &mut Context<Self>,
     ) -> Self {
         let content = cx.new(|cx| GitSidebar::new(cx));
+        let branch_picker = {
+            let session_handle = session_handle.clone();
+            cx.new(|cx| BranchPicker::new(session_handle, cx))
+        };
+        let rebase_planner = {
+            let session_handle = session_handle.clone();
+            cx.new(|_cx| RebasePlanner::new(session_handle))
+        };
         let mut host_event_rx = session.subscribe_host_events();
         let host_event_task = cx.spawn(async move |this, cx| {
             loop {
@@ -89,6 +125,88 @@ impl GitPanel {
                 this.handle_commit(event.message.clone(), event.paths.clone(), cx);
             },
         ));
+        subscriptions.push(cx.subscribe(
+            &content,
+            |this, _sidebar, event: &GitStageRequested, cx| {
+                this.stage_paths(event.paths.clone(), event.stage, cx);
+            },
+        ));
+        subscriptions.push(cx.subscribe(
+            &content,
+            |this, _sidebar, _event: &GitCommitMessageRequested, cx| {
+                this.generate_commit_message(cx);
+            },
+        ));
+        subscriptions.push(cx.subscribe(
+            &content,
+            |this, _sidebar, _event: &GitPushRequested, cx| {
+                this.push_branch(false, cx);
+            },
+        ));
+        subscriptions.push(cx.subscribe(
+            &content,
+            |this, _sidebar, _event: &GitPullRequested, cx| {
+                this.pull_branch(GitPullMode::FastForwardOnly, cx);
+            },
+        ));
+        subscriptions.push(cx.subscribe(
+            &content,
+            |this, _sidebar, _event: &GitFetchRequested, cx| {
+                this.fetch_remote(cx);
+            },
+        ));
+        subscriptions.push(cx.subscribe(
+            &content,
+            |this, _sidebar, _event: &GitPullRequestRequested, cx| {
+                this.create_pull_request(cx);
+            },
+        ));
+        subscriptions.push(cx.subscribe(
+            &content,
+            |this, _sidebar, _event: &GitRebaseRequested, cx| {
+                this.show_rebase_planner(cx);
+            },
+        ));
+        subscriptions.push(cx.subscribe(
+            &content,
+            |this, _sidebar, _event: &GitRepoPickerRequested, cx| {
+                this.show_repo_picker(cx);
+            },
+        ));
+        subscriptions.push(cx.subscribe(
+            &content,
+            |this, _sidebar, _event: &GitBranchPickerRequested, cx| {
+                this.show_branch_picker(cx);
+            },
+        ));
+        let pull_refresh = content.read(cx).pull_refresh().clone();
+        subscriptions.push(cx.subscribe(
+            &pull_refresh,
+            |this, pull, event: &PullToRefreshEvent, cx| match event {
+                PullToRefreshEvent::Refresh => {
+                    let fetch = this.fetch_git_status(cx);
+                    cx.spawn(async move |_this, cx| {
+                        fetch.await;
+                        let _ = pull.update(cx, |pull, cx| pull.finish(cx));
+                    })
+                    .detach();
+                }
+            },
+        ));
+        subscriptions.push(cx.subscribe(
+            &branch_picker,
+            |this, _picker, event: &BranchPickerEvent, cx| match event {
+                BranchPickerEvent::Switched | BranchPickerEvent::Merged => {
+                    this.fetch_git_status(cx).detach()
+                }
+            },
+        ));
+        subscriptions.push(cx.subscribe(
+            &rebase_planner,
+            |this, _planner, event: &RebasePlannerEvent, cx| match event {
+                RebasePlannerEvent::Rebased => this.fetch_git_status(cx).detach(),
+            },
+        ));
         subscriptions.push(cx.observe(&workspace_state, |this, _, cx| {
             this.sync_active_diff_from_workspace_state(cx);
         }));
@@ -98,6 +216,8 @@ impl GitPanel {
             session_state,
             session_handle,
             content,
+            branch_picker,
+            rebase_planner,
             branch: String::new(),
             tasks: vec![host_event_task],
             _subscriptions: subscriptions,
@@ -119,8 +239,16 @@ impl GitPanel {
         let content = self.content.clone();
         cx.spawn(async move |this, cx| match handle.git_status().await {
             Ok(result) => {
-                let repo_state = status_to_repo_state(&result.branch, &result.entries);
+                let mut repo_state = status_to_repo_state(&result.branch, &result.entries);
+                // Line counts are best-effort; older hosts lack `GitDiffStat`.
+                match handle.git_diff_stat().await {
+                    Ok(stat) => apply_diff_stat(&mut repo_state, &stat),
+                    Err(e) => debug!("git_diff_stat unavailable: {}", e),
+                }
+                let suggest_available = handle.supports(HostFeature::StreamingAi);
                 let _ = content.update(cx, |sidebar, cx| {
+                    sidebar.set_no_repository(false, cx);
+                    sidebar.set_suggest_available(suggest_available, cx);
                     sidebar.set_repo_state(repo_state, cx);
                     let _ = this.update(cx, |this, _cx| {
                         this.branch = result.branch.clone();
@@ -129,10 +257,142 @@ impl GitPanel {
             }
             Err(e) => {
                 error!("git_status failed: {}", e);
+                // Tell "not a repository" apart from other failures so the
+                // sidebar can offer the repository picker.
+                match handle.git_discover().await {
+                    Ok(discovered)
+                        if !discovered.workdir_is_repo && discovered.selected.is_none() =>
+                    {
+                        let _ = content.update(cx, |sidebar, cx| {
+                            sidebar.set_no_repository(true, cx);
+                        });
+                    }
+                    Ok(_) => {}
+                    Err(e) => debug!("git_discover unavailable: {}", e),
+                }
             }
         })
     }
 
+    /// List the repositories under the workdir, plus an option to initialize
+    /// one in the workdir, and point this session's git RPCs at the choice.
+    fn show_repo_picker(&mut self, cx: &mut Context<Self>) {
+        let handle = self.session_handle.clone();
+        let task = cx.spawn(async move |this, cx| {
+            let discovered = match handle.git_discover().await {
+                Ok(discovered) => discovered,
+                Err(e) => {
+                    error!("git discover failed: {}", e);
+                    platform_bridge::show_alert(
+                        "Couldn't list repositories",
+                        &e.to_string(),
+                        vec![AlertButton::default("OK")],
+                        |_| {},
+                    );
+                    return;
+                }
+            };
+            let selected = discovered.selected.clone().unwrap_or_default();
+            let mut items: Vec<ListPickerItem> = discovered
+                .repositories
+                .iter()
+                .map(|repo| ListPickerItem {
+                    label: repo_label(repo),
+                    subtitle: (!repo.branch.is_empty()).then(|| repo.branch.clone()),
+                    image_name: (repo.path == selected).then(|| "icons/check.svg".to_string()),
+                    trailing_icon: None,
+                })
+                .collect();
+            let offers_init = !discovered.workdir_is_repo;
+            if offers_init {
+                items.push(ListPickerItem {
+                    label: "Initialize Repository Here".to_string(),
+                    subtitle: Some("Run git init in the workdir".to_string()),
+                    image_name: Some("icons/plus.svg".to_string()),
+                    trailing_icon: None,
+                });
+            }
+            let message = if discovered.truncated {
+                "Showing the first repositories found"
+            } else {
+                ""
+            };
+            let (tx, rx) = tokio::sync::oneshot::channel();
+            platform_bridge::show_list_picker("Repository", message, items, move |selection| {
+                let _ = tx.send(selection.map(|selection| selection.index));
+            });
+            let Ok(Some(index)) = rx.await else {
+                return;
+            };
+            let (path, init) = match discovered.repositories.get(index) {
+                Some(repo) => (repo.path.clone(), false),
+                None if offers_init => (String::new(), true),
+                None => return,
+            };
+            match handle.git_select_repo(&path, init).await {
+                Ok(path) => {
+                    info!("git repository selected: {:?}", path);
+                    let Ok(fetch) = this.update(cx, |this, cx| {
+                        this.content.update(cx, |sidebar, cx| {
+                            sidebar.set_repository((!path.is_empty()).then_some(path), cx);
+                        });
+                        this.fetch_git_status(cx)
+                    }) else {
+                        return;
+                    };
+                    fetch.await;
+                }
+                Err(e) => {
+                    error!("git select repo failed: {}", e);
+                    platform_bridge::show_alert(
+                        "Couldn't open repository",
+                        &e.to_string(),
+                        vec![AlertButton::default("OK")],
+                        |_| {},
+                    );
+                }
+            }
+        });
+        self.tasks.push(task);
+    }
+
+    fn show_branch_picker(&mut self, cx: &mut Context<Self>) {
+        self.branch_picker.update(cx, |picker, cx| picker.open(cx));
+        platform_bridge::show_custom_sheet(
+            CustomSheetOptions {
+                detents: vec![CustomSheetDetent::Medium, CustomSheetDetent::Large],
+                initial_detent: CustomSheetDetent::Medium,
+                shows_grabber: true,
+                expands_on_scroll_edge: true,
+                edge_attached_in_compact_height: false,
+                width_follows_preferred_content_size_when_edge_attached: false,
+                corner_radius: None,
+                modal_in_presentation: false,
+            },
+            self.branch_picker.clone(),
+        );
+        view_telemetry::record(view_telemetry::CUSTOM_SHEET_BRANCH_PICKER);
+    }
+
+    fn show_rebase_planner(&mut self, cx: &mut Context<Self>) {
+        self.rebase_planner
+            .update(cx, |planner, cx| planner.open(cx));
+        platform_bridge::show_custom_sheet(
+            CustomSheetOptions {
+                detents: vec![CustomSheetDetent::Medium, CustomSheetDetent::Large],
+                initial_detent: CustomSheetDetent::Large,
+                shows_grabber: true,
+                expands_on_scroll_edge: false,
+                edge_attached_in_compact_height: false,
+                width_follows_preferred_content_size_when_edge_attached: false,
+                corner_radius: None,
+                modal_in_presentation: false,
+            },
+            self.rebase_planner.clone(),
+        );
+        view_telemetry::record(view_telemetry::CUSTOM_SHEET_REBASE_PLANNER);
+    }
+
     fn handle_commit(&mut self, message: String, paths: Vec<String>, cx: &mut Context<Self>) {
         let handle = self.session_handle.clone();
         let content = self.content.clone();
@@ -141,7 +401,14 @@ impl GitPanel {
         });
 
         let task = cx.spawn(async move |this, cx| {
-            let result = handle.git_commit(&message, &paths).await;
+            // Empty paths commit the index as staged; hosts from before
+            // index commits reject that, so fall back to the staged paths.
+            let result = match handle.git_commit(&message, &[]).await {
+                Err(e) if e.to_string().contains("no paths to commit") => {
+                    handle.git_commit(&message, &paths).await
+                }
+                result => result,
+            };
             let _ = content.update(cx, |sidebar, cx| {
                 sidebar.set_committing(false, cx);
                 match &result {
@@ -164,6 +431,373 @@ impl GitPanel {
         self.tasks.push(task);
     }
 
+    fn stage_paths(&mut self, paths: Vec<String>, stage: bool, cx: &mut Context<Self>) {
+        if paths.is_empty() {
+            return;
+        }
+        let handle = self.session_handle.clone();
+        let task = cx.spawn(async move |this, cx| {
+            let result = if stage {
+                handle.git_stage(&paths).await
+            } else {
+                handle.git_unstage(&paths).await
+            };
+            if let Err(e) = &result {
+                error!("git stage failed: {}", e);
+                let title = if stage {
+                    "Stage failed"
+                } else {
+                    "Unstage failed"
+                };
+                platform_bridge::show_alert(
+                    title,
+                    &e.to_string(),
+                    vec![AlertButton::default("OK")],
+                    |_| {},
+                );
+            }
+            let _ = this.update(cx, |this, cx| {
+                this.fetch_git_status(cx).detach();
+            });
+        });
+        self.tasks.push(task);
+    }
+
+    /// Ask the host AI for a message from the staged diff and put it in the
+    /// composer for editing; nothing is committed until the user sends it.
+    fn generate_commit_message(&mut self, cx: &mut Context<Self>) {
+        let handle = self.session_handle.clone();
+        let content = self.content.clone();
+        content.update(cx, |sidebar, cx| {
+            sidebar.set_generating_message(true, cx);
+        });
+
+        let data_saver = crate::settings::data_saver_active(cx);
+        let task = cx.spawn(async move |_this, cx| {
+            let result: anyhow::Result<String> = async {
+                let mut diff = handle.git_diff(None, true).await?;
+                if diff.trim().is_empty() {
+                    anyhow::bail!("Nothing is staged.");
+                }
+                if diff.len() > COMMIT_MESSAGE_DIFF_MAX {
+                    let mut cut = COMMIT_MESSAGE_DIFF_MAX;
+                    while !diff.is_char_boundary(cut) {
+                        cut -= 1;
+                    }
+                    diff.truncate(cut);
+                    diff.push_str("\n[diff truncated]\n");
+                }
+                if data_saver && diff.len() > DATA_SAVER_PROMPT_WARN {
+                    let (tx, rx) = tokio::sync::oneshot::channel();
+                    platform_bridge::show_alert(
+                        "Large prompt",
+                        &format!(
+                            "Data saver is on. Send {} KB of staged diff to the host AI?",
+                            diff.len().div_ceil(1024)
+                        ),
+                        vec![AlertButton::default("Send"), AlertButton::cancel("Cancel")],
+                        move |index| {
+                            let _ = tx.send(index);
+                        },
+                    );
+                    if !matches!(rx.await, Ok(0)) {
+                        return Ok(String::new());
+                    }
+                }
+                let reply = ai_approval::prompt(
+                    &handle,
+                    AiPromptReqV2 {
+                        prompt: COMMIT_MESSAGE_PROMPT.to_string(),
+                        context: Some(diff),
+                        allowed_tools: Vec::new(),
+                        resume_session_id: None,
+                        conversation_id: None,
+                    },
+                )
+                .await?;
+                Ok(strip_code_fence(&reply.text))
+            }
+            .await;
+            let _ = content.update(cx, |sidebar, cx| {
+                sidebar.set_generating_message(false, cx);
+                match result {
+                    Ok(message) if !message.is_empty() => sidebar.set_commit_message(message, cx),
+                    Ok(_) => {}
+                    Err(e) => {
+                        error!("commit message suggestion failed: {}", e);
+                        platform_bridge::show_alert(
+                            "Couldn't suggest a message",
+                            &e.to_string(),
+                            vec![AlertButton::default("OK")],
+                            |_| {},
+                        );
+                    }
+                }
+            });
+        });
+        self.tasks.push(task);
+    }
+
+    /// Push the current branch, overwriting the remote branch when `force`.
+    /// HTTPS credentials the host lacks are asked for by `git_credential`
+    /// while this waits. A rejected push offers to pull or force push.
+    fn push_branch(&mut self, force: bool, cx: &mut Context<Self>) {
+        let handle = self.session_handle.clone();
+        let task = cx.spawn(async move |this, cx| {
+            let result = handle
+                .git_push_v2(GitPushReqV2 {
+                    remote: None,
+                    branch: None,
+                    set_upstream: true,
+                    force,
+                })
+                .await;
+            let pushed = match result {
+                Ok(pushed) => pushed,
+                Err(e) => {
+                    error!("git push failed: {}", e);
+                    show_remote_error("push", &e.to_string(), None);
+                    return;
+                }
+            };
+            let Some(error) = pushed.error else {
+                info!("pushed {} to {}", pushed.branch, pushed.remote);
+                let verb = if force { "Force-pushed" } else { "Pushed" };
+                platform_bridge::show_native_notification(
+                    NativeNotificationOptions::new(format!(
+                        "{verb} {} to {}",
+                        pushed.branch, pushed.remote
+                    ))
+                    .kind(NativeNotificationKind::Success),
+                );
+                return;
+            };
+            error!("git push failed: {}", error);
+            if force || pushed.failure != Some(GitRemoteFailure::NonFastForward) {
+                show_remote_error("push", &error, pushed.failure);
+                return;
+            }
+            let (tx, rx) = tokio::sync::oneshot::channel();
+            platform_bridge::show_alert(
+                "Push rejected",
+                "The remote branch has commits this branch lacks. Pull them first, or force \
+                 push to replace the remote branch.",
+                vec![
+                    AlertButton::default("Pull"),
+                    AlertButton::destructive("Force Push"),
+                    AlertButton::cancel("Cancel"),
+                ],
+                move |index| {
+                    let _ = tx.send(index);
+                },
+            );
+            match rx.await {
+                Ok(0) => {
+                    let _ = this.update(cx, |this, cx| {
+                        this.pull_branch(GitPullMode::FastForwardOnly, cx)
+                    });
+                }
+                Ok(1) => {
+                    let _ = this.update(cx, |this, cx| this.push_branch(true, cx));
+                }
+                _ => {}
+            }
+        });
+        self.tasks.push(task);
+    }
+
+    /// Pull the upstream into the current branch, then refresh the status.
+    /// When a fast-forward is impossible, offer to rebase or merge instead.
+    fn pull_branch(&mut self, mode: GitPullMode, cx: &mut Context<Self>) {
+        let handle = self.session_handle.clone();
+        let task = cx.spawn(async move |this, cx| {
+            let result = handle
+                .git_pull(GitPullReq {
+                    remote: None,
+                    branch: None,
+                    mode,
+                })
+                .await;
+            let pulled = match result {
+                Ok(pulled) => pulled,
+                Err(e) => {
+                    error!("git pull failed: {}", e);
+                    show_remote_error("pull", &e.to_string(), None);
+                    return;
+                }
+            };
+            let Some(error) = pulled.error else {
+                let message = if pulled.updated {
+                    format!("Pulled {}/{}", pulled.remote, pulled.branch)
+                } else {
+                    "Already up to date".to_string()
+                };
+                platform_bridge::show_native_notification(
+                    NativeNotificationOptions::new(message).kind(NativeNotificationKind::Success),
+                );
+                if let Ok(refresh) = this.update(cx, |this, cx| this.fetch_git_status(cx)) {
+                    refresh.await;
+                }
+                return;
+            };
+            error!("git pull failed: {}", error);
+            if mode != GitPullMode::FastForwardOnly
+                || pulled.failure != Some(GitRemoteFailure::NonFastForward)
+            {
+                show_remote_error("pull", &error, pulled.failure);
+                return;
+            }
+            let (tx, rx) = tokio::sync::oneshot::channel();
+            platform_bridge::show_alert(
+                "Branches have diverged",
+                "Both this branch and its upstream have new commits. Rebase this branch's \
+                 commits onto the upstream, or merge the upstream in?",
+                vec![
+                    AlertButton::default("Rebase"),
+                    AlertButton::default("Merge"),
+                    AlertButton::cancel("Cancel"),
+                ],
+                move |index| {
+                    let _ = tx.send(index);
+                },
+            );
+            let mode = match rx.await {
+                Ok(0) => GitPullMode::Rebase,
+                Ok(1) => GitPullMode::Merge,
+                _ => return,
+            };
+            let _ = this.update(cx, |this, cx| this.pull_branch(mode, cx));
+        });
+        self.tasks.push(task);
+    }
+
+    /// Fetch the current branch's remote and report how far the branch is
+    /// from its upstream.
+    fn fetch_remote(&mut self, cx: &mut Context<Self>) {
+        let handle = self.session_handle.clone();
+        let task = cx.spawn(async move |_this, _cx| {
+            let result = handle
+                .git_fetch(GitFetchReq {
+                    remote: None,
+                    prune: true,
+                })
+                .await;
+            let fetched = match result {
+                Ok(fetched) => fetched,
+                Err(e) => {
+                    error!("git fetch failed: {}", e);
+                    show_remote_error("fetch", &e.to_string(), None);
+                    return;
+                }
+            };
+            if let Some(error) = fetched.error {
+                error!("git fetch failed: {}", error);
+                show_remote_error("fetch", &error, fetched.failure);
+                return;
+            }
+            let message = match (fetched.ahead, fetched.behind) {
+                (0, 0) => format!("Up to date with {}", fetched.remote),
+                (ahead, 0) => format!("{ahead} ahead of {}", fetched.remote),
+                (0, behind) => format!("{behind} behind {}", fetched.remote),
+                (ahead, behind) => {
+                    format!("{ahead} ahead, {behind} behind {}", fetched.remote)
+                }
+            };
+            platform_bridge::show_native_notification(
+                NativeNotificationOptions::new(message).kind(NativeNotificationKind::Success),
+            );
+        });
+        self.tasks.push(task);
+    }
+
+    /// Ask for a title (prefilled from the last commit), open a pull/merge
+    /// request for the current branch on the host, and offer its URL.
+    fn create_pull_request(&mut self, cx: &mut Context<Self>) {
+        let handle = self.session_handle.clone();
+        let branch = self.branch.clone();
+        let task = cx.spawn(async move |_this, cx| {
+            let last_subject = handle
+                .git_log(Some(1))
+                .await
+                .ok()
+                .and_then(|entries| entries.into_iter().next())
+                .map(|entry| entry.message)
+                .unwrap_or_default();
+            let (title_tx, title_rx) = tokio::sync::oneshot::channel();
+            platform_bridge::show_text_input(
+                &format!("Pull request for {branch}"),
+                "Title",
+                &last_subject,
+                move |title| {
+                    let _ = title_tx.send(title);
+                },
+            );
+            let Ok(Some(title)) = title_rx.await else {
+                return;
+            };
+            if title.trim().is_empty() {
+                return;
+            }
+
+            let progress_id = platform_bridge::allocate_native_progress_id();
+            platform_bridge::show_native_progress(progress_id, "Creating pull request…");
+            let result = handle
+                .forge_create_pr(ForgeCreatePrReq {
+                    title,
+                    body: String::new(),
+                    head: None,
+                    base: None,
+                    remote: None,
+                    draft: false,
+                })
+                .await;
+            platform_bridge::hide_native_progress(progress_id);
+            let created = match result {
+                Ok(created) => created,
+                Err(e) => {
+                    error!("pull request creation failed: {}", e);
+                    platform_bridge::show_alert(
+                        "Couldn't create pull request",
+                        &e.to_string(),
+                        vec![AlertButton::default("OK")],
+                        |_| {},
+                    );
+                    return;
+                }
+            };
+            info!("opened {} {}", created.forge, created.url);
+
+            let title = if created.forge == "gitlab" {
+                format!("Merge request !{}", created.number)
+            } else {
+                format!("Pull request #{}", created.number)
+            };
+            let (choice_tx, choice_rx) = tokio::sync::oneshot::channel();
+            platform_bridge::show_alert(
+                &title,
+                &created.url,
+                vec![
+                    AlertButton::default("Open"),
+                    AlertButton::default("Copy Link"),
+                    AlertButton::cancel("Done"),
+                ],
+                move |index| {
+                    let _ = choice_tx.send(index);
+                },
+            );
+            match choice_rx.await {
+                Ok(0) => platform_bridge::bridge().open_url(&created.url),
+                Ok(1) => {
+                    let _ = cx.update(|cx| {
+                        cx.write_to_clipboard(ClipboardItem::new_string(created.url.clone()));
+                    });
+                }
+                _ => {}
+            }
+        });
+        self.tasks.push(task);
+    }
+
     fn sync_active_diff_from_workspace_state(&mut self, cx: &mut Context<Self>) {
         let active_diff = self
             .workspace_state
@@ -191,6 +825,52 @@ impl Render for GitPanel {
 }
 
 /// Convert `GitStatusEntry` list into `GitRepoState` for the sidebar.
+/// Models sometimes wrap the message in a fence despite the prompt.
+/// Alert for a failed fetch, pull, or push, leading with what the failure
+/// class means before git's own message.
+fn show_remote_error(action: &str, error: &str, failure: Option<GitRemoteFailure>) {
+    let hint = match failure {
+        Some(GitRemoteFailure::Auth) => Some("The remote rejected the credentials."),
+        Some(GitRemoteFailure::NonFastForward) => {
+            Some("The remote branch has commits this branch lacks.")
+        }
+        Some(GitRemoteFailure::Conflict) => {
+            Some("It stopped on conflicts and was undone. The branch is unchanged.")
+        }
+        Some(GitRemoteFailure::LocalChanges) => Some("Commit or stash your changes first."),
+        Some(GitRemoteFailure::Network) => Some("The host couldn't reach the remote."),
+        None => None,
+    };
+    let message = match hint {
+        Some(hint) => format!("{hint}\n\n{error}"),
+        None => error.to_string(),
+    };
+    platform_bridge::show_alert(
+        &format!("Couldn't {action}"),
+        &message,
+        vec![AlertButton::default("OK")],
+        |_| {},
+    );
+}
+
+pub(crate) fn strip_code_fence(text: &str) -> String {
+    let text = text.trim();
+    let Some(inner) = text.strip_prefix("```") else {
+        return text.to_string();
+    };
+    let inner = inner.split_once('\n').map_or("", |(_, rest)| rest);
+    inner.trim_end().trim_end_matches("```").trim().to_string()
+}
+
+/// Picker label for a discovered repository; the workdir itself has no path.
+fn repo_label(repo: &GitDiscoveredRepo) -> String {
+    if repo.path.is_empty() {
+        "Workdir".to_string()
+    } else {
+        repo.path.clone()
+    }
+}
+
 fn status_to_repo_state(branch: &str, entries: &[GitStatusEntry]) -> GitRepoState {
     let mut staged = Vec::new();
     let mut unstaged = Vec::new();
@@ -240,6 +920,21 @@ fn status_to_repo_state(branch: &str, entries: &[GitStatusEntry]) -> GitRepoStat
     }
 }
 
+/// Fill per-file insertion/deletion counts from a diff stat.
+fn apply_diff_stat(state: &mut GitRepoState, stat: &GitDiffStatResult) {
+    fn fill(files: &mut [GitFileEntry], stats: &[GitDiffStatEntry]) {
+        for file in files {
+            if let Some(entry) = stats.iter().find(|entry| entry.path == file.path) {
+                file.insertions = entry.additions as usize;
+                file.deletions = entry.deletions as usize;
+            }
+        }
+    }
+    fill(&mut state.staged_files, &stat.staged);
+    fill(&mut state.unstaged_files, &stat.unstaged);
+    fill(&mut state.untracked_files, &stat.untracked);
+}
+
 fn section_to_u8(section: GitFileSection) -> u8 {
     match section {
         GitFileSection::Staged => 0,
@@ -7,6 +7,8 @@ use zedra_telemetry::*;
 
 use crate::button::outline_button;
 use crate::fonts;
+use crate::host_accent::{self, HostAccent};
+use crate::host_power;
 use crate::platform_bridge::{self, AlertButton, HapticFeedback};
 use crate::theme;
 use crate::transport_badge::{ConnectionStatusIndicator, phase_indicator_color};
@@ -49,6 +51,22 @@ enum HomeAction {
         endpoint_addr: String,
         name: String,
     },
+    SetAccent {
+        endpoint_addr: String,
+        accent: Option<HostAccent>,
+    },
+    Wake {
+        endpoint_addr: String,
+        display_name: String,
+        macs: Vec<String>,
+    },
+    SendWake {
+        display_name: String,
+        macs: Vec<String>,
+        /// Endpoint of the connected host that sends the packet; `None` sends
+        /// it from this device.
+        via: Option<String>,
+    },
 }
 
 pub struct HomeView {
@@ -145,38 +163,94 @@ impl HomeView {
         };
         let endpoint_addr = state.read(cx).endpoint_addr.clone();
         let display = state.read(cx).display_name().to_string();
+        let wake_macs = state.read(cx).wake_macs.clone();
+        let accent = state.read(cx).accent;
         let tx = self.action_tx.clone();
 
-        platform_bridge::show_selection(
-            "",
-            "",
-            vec![
-                AlertButton::default("Rename"),
-                AlertButton::default("Disconnect"),
-                AlertButton::destructive("Delete"),
-            ],
-            move |choice| match choice {
-                Some(0) => {
-                    let _ = tx.unbounded_send(HomeAction::BeginRename {
-                        endpoint_addr,
-                        current_name: display,
-                    });
-                }
-                Some(1) => {
-                    let _ = tx.unbounded_send(HomeAction::Disconnect {
-                        endpoint_addr,
-                        display_name: display,
-                    });
-                }
-                Some(2) => {
-                    let _ = tx.unbounded_send(HomeAction::Delete {
+        // Wake leads the menu for hosts that reported an address.
+        let can_wake = !wake_macs.is_empty();
+        let opens_on_launch =
+            crate::settings::read_auto_connect_endpoint().as_deref() == Some(&endpoint_addr);
+        let mut buttons = vec![
+            AlertButton::default("Rename"),
+            AlertButton::default("Color"),
+            AlertButton::default(if opens_on_launch {
+                "Don't Open on Launch"
+            } else {
+                "Open on Launch"
+            }),
+            AlertButton::default("Disconnect"),
+            AlertButton::destructive("Delete"),
+        ];
+        if can_wake {
+            buttons.insert(0, AlertButton::default("Wake"));
+        }
+
+        platform_bridge::show_selection("", "", buttons, move |choice| {
+            match choice.map(|index| (can_wake, index)) {
+                Some((true, 0)) => {
+                    let _ = tx.unbounded_send(HomeAction::Wake {
                         endpoint_addr,
                         display_name: display,
+                        macs: wake_macs,
                     });
                 }
-                _ => {}
-            },
-        );
+                Some((_, index)) => match index - usize::from(can_wake) {
+                    0 => {
+                        let _ = tx.unbounded_send(HomeAction::BeginRename {
+                            endpoint_addr,
+                            current_name: display,
+                        });
+                    }
+                    1 => {
+                        host_accent::pick(&display, accent, move |accent| {
+                            let _ = tx.unbounded_send(HomeAction::SetAccent {
+                                endpoint_addr,
+                                accent,
+                            });
+                        });
+                    }
+                    2 => {
+                        crate::settings::set_auto_connect_endpoint(
+                            (!opens_on_launch).then_some(endpoint_addr),
+                        );
+                    }
+                    3 => {
+                        let _ = tx.unbounded_send(HomeAction::Disconnect {
+                            endpoint_addr,
+                            display_name: display,
+                        });
+                    }
+                    4 => {
+                        let _ = tx.unbounded_send(HomeAction::Delete {
+                            endpoint_addr,
+                            display_name: display,
+                        });
+                    }
+                    _ => {}
+                },
+                None => {}
+            }
+        });
+    }
+
+    /// Connected hosts, other than `endpoint_addr`, that can send a wake
+    /// packet on their own network, as `(endpoint, name)`.
+    fn wake_relays(&self, endpoint_addr: &str, cx: &App) -> Vec<(String, String)> {
+        let workspaces = self.workspaces.read(cx);
+        (0..workspaces.len())
+            .filter_map(|index| workspaces.get(index))
+            .map(|workspace| workspace.read(cx))
+            .filter(|workspace| workspace.session_handle().has_client())
+            .map(|workspace| {
+                let state = workspace.workspace_state(cx);
+                (
+                    state.endpoint_addr.clone(),
+                    state.display_name().to_string(),
+                )
+            })
+            .filter(|(relay, _)| relay != endpoint_addr)
+            .collect()
     }
 
     fn process_action(&mut self, action: HomeAction, cx: &mut Context<Self>) {
@@ -226,6 +300,10 @@ impl HomeView {
                 );
             }
             HomeAction::ConfirmDelete { endpoint_addr } => {
+                if crate::settings::read_auto_connect_endpoint().as_deref() == Some(&endpoint_addr)
+                {
+                    crate::settings::set_auto_connect_endpoint(None);
+                }
                 self.workspaces.update(cx, |ws, cx| {
                     ws.remove_by_endpoint_addr(&endpoint_addr, cx);
                 });
@@ -249,6 +327,61 @@ impl HomeView {
                     },
                 );
             }
+            HomeAction::Wake {
+                endpoint_addr,
+                display_name,
+                macs,
+            } => {
+                let relays = self.wake_relays(&endpoint_addr, cx);
+                if relays.is_empty() {
+                    host_power::wake(display_name, macs, None, cx);
+                    return;
+                }
+                let mut buttons = vec![AlertButton::default("From This Device")];
+                buttons.extend(
+                    relays
+                        .iter()
+                        .map(|(_, name)| AlertButton::default(format!("Via {name}"))),
+                );
+                buttons.push(AlertButton::cancel("Cancel"));
+                let tx = self.action_tx.clone();
+                platform_bridge::show_selection(
+                    &format!("Wake {display_name}"),
+                    "Send the wake packet from this device's network or from a connected host on the same network.",
+                    buttons,
+                    move |choice| {
+                        let via = match choice {
+                            Some(0) => None,
+                            Some(index) => match relays.get(index - 1) {
+                                Some((relay, _)) => Some(relay.clone()),
+                                None => return,
+                            },
+                            None => return,
+                        };
+                        let _ = tx.unbounded_send(HomeAction::SendWake {
+                            display_name,
+                            macs,
+                            via,
+                        });
+                    },
+                );
+            }
+            HomeAction::SendWake {
+                display_name,
+                macs,
+                via,
+            } => {
+                let via = match via {
+                    Some(relay) => {
+                        match self.workspaces.read(cx).entry_by_endpoint_addr(&relay, cx) {
+                            Some(workspace) => Some(workspace.read(cx).session_handle().clone()),
+                            None => return,
+                        }
+                    }
+                    None => None,
+                };
+                host_power::wake(display_name, macs, via, cx);
+            }
             HomeAction::ConfirmRename {
                 endpoint_addr,
                 name,
@@ -263,6 +396,14 @@ impl HomeView {
                     ws.rename_workspace(&endpoint_addr, custom_name, cx)
                 });
             }
+            HomeAction::SetAccent {
+                endpoint_addr,
+                accent,
+            } => {
+                self.workspaces.update(cx, |ws, cx| {
+                    ws.set_workspace_accent(&endpoint_addr, accent, cx)
+                });
+            }
         }
     }
 
@@ -301,7 +442,7 @@ impl Render for HomeView {
             .child(
                 div()
                     .text_color(rgb(theme::text_primary(cx)))
-                    .text_size(px(theme::FONT_APP_TITLE))
+                    .text_size(theme::scaled(theme::FONT_APP_TITLE))
                     .font_family(fonts::HEADING_FONT_FAMILY)
                     .font_weight(FontWeight::EXTRA_BOLD)
                     .child("Zedra"),
@@ -311,7 +452,7 @@ impl Render for HomeView {
                     .flex()
                     .items_center()
                     .text_color(rgb(theme::text_muted(cx)))
-                    .text_size(px(theme::FONT_BODY))
+                    .text_size(theme::scaled(theme::FONT_BODY))
                     .child("Code from anywhere. ")
                     .child(
                         div()
@@ -393,10 +534,12 @@ impl Render for HomeView {
                     (true, true) => String::new(),
                 };
 
+                let accent_color = state.accent.map(|accent| accent.color(cx));
                 let card = workspace_card(
                     item_idx,
                     project_name,
                     subtitle,
+                    accent_color,
                     connect_phase,
                     status_label,
                     status_color,
@@ -463,7 +606,7 @@ impl Render for HomeView {
             .child(
                 div()
                     .text_color(rgb(theme::text_muted(cx)))
-                    .text_size(px(theme::FONT_DETAIL))
+                    .text_size(theme::scaled(theme::FONT_DETAIL))
                     .child(app_version_text()),
             );
 
@@ -839,7 +982,7 @@ fn guide_line(
         } else {
             theme::text_secondary(cx)
         }))
-        .text_size(px(theme::FONT_DETAIL))
+        .text_size(theme::scaled(theme::FONT_DETAIL))
         .child(text);
 
     row.into_any_element()
@@ -862,6 +1005,7 @@ fn workspace_card(
     index: usize,
     project_name: String,
     subtitle: String,
+    accent_color: Option<u32>,
     connect_phase: Option<ConnectPhase>,
     status_label: &'static str,
     status_color: u32,
@@ -873,7 +1017,8 @@ fn workspace_card(
         .rounded(px(8.0))
         .bg(rgb(theme::bg_card(cx)))
         .border_1()
-        .border_color(rgb(theme::border_subtle(cx)))
+        .border_color(rgb(accent_color.unwrap_or(theme::border_subtle(cx))))
+        .when(accent_color.is_some(), |card| card.border_l_4())
         .p(px(12.0))
         .cursor_pointer()
         .on_press(cx.listener(move |this, _event, window, cx| {
@@ -904,14 +1049,14 @@ fn workspace_card(
                     div()
                         .flex_1()
                         .text_color(rgb(theme::text_primary(cx)))
-                        .text_size(px(theme::FONT_BODY))
+                        .text_size(theme::scaled(theme::FONT_BODY))
                         .font_weight(FontWeight::MEDIUM)
                         .child(project_name),
                 )
                 .child(
                     div()
                         .text_color(rgb(status_color))
-                        .text_size(px(theme::FONT_DETAIL))
+                        .text_size(theme::scaled(theme::FONT_DETAIL))
                         .child(status_label),
                 ),
         )
@@ -922,7 +1067,7 @@ fn workspace_card(
                 div()
                     .mt(px(4.0))
                     .text_color(rgb(theme::text_muted(cx)))
-                    .text_size(px(theme::FONT_DETAIL))
+                    .text_size(theme::scaled(theme::FONT_DETAIL))
                     .text_overflow(TextOverflow::Truncate(SharedString::new("...")))
                     .child(subtitle),
             )
@@ -0,0 +1,123 @@
+// Host accent: a color the user gives a saved host so two sessions never look
+// alike.
+//
+// Stored on `WorkspaceState::accent` next to `custom_name` (the host's label)
+// and picked from the home card's long-press menu. The workspace header and
+// the home card draw it as a stripe, and host notifications lead with its
+// emoji badge, since native notifications cannot be tinted.
+
+use gpui::App;
+use serde::{Deserialize, Serialize};
+
+use crate::platform_bridge::{self, AlertButton};
+use crate::theme;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HostAccent {
+    Red,
+    Orange,
+    Yellow,
+    Green,
+    Blue,
+    Purple,
+}
+
+impl HostAccent {
+    pub const ALL: [HostAccent; 6] = [
+        Self::Red,
+        Self::Orange,
+        Self::Yellow,
+        Self::Green,
+        Self::Blue,
+        Self::Purple,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Red => "Red",
+            Self::Orange => "Orange",
+            Self::Yellow => "Yellow",
+            Self::Green => "Green",
+            Self::Blue => "Blue",
+            Self::Purple => "Purple",
+        }
+    }
+
+    /// Stripe color; follows the theme where the palette has a match.
+    pub fn color(self, cx: &App) -> u32 {
+        match self {
+            Self::Red => theme::accent_red(cx),
+            Self::Orange => 0xd19a66,
+            Self::Yellow => theme::accent_yellow(cx),
+            Self::Green => theme::accent_green(cx),
+            Self::Blue => theme::accent_blue(cx),
+            Self::Purple => 0xc678dd,
+        }
+    }
+
+    /// Emoji circle used where text is the only option.
+    pub fn badge(self) -> &'static str {
+        match self {
+            Self::Red => "🔴",
+            Self::Orange => "🟠",
+            Self::Yellow => "🟡",
+            Self::Green => "🟢",
+            Self::Blue => "🔵",
+            Self::Purple => "🟣",
+        }
+    }
+}
+
+/// "🔴 prod" for a host with an accent, the plain name otherwise.
+pub fn badged_name(name: &str, accent: Option<HostAccent>) -> String {
+    match accent {
+        Some(accent) => format!("{} {name}", accent.badge()),
+        None => name.to_string(),
+    }
+}
+
+/// Ask for an accent for `host_name`. `on_pick` gets `None` when the user
+/// clears it and is not called when the picker is cancelled.
+pub fn pick(
+    host_name: &str,
+    current: Option<HostAccent>,
+    on_pick: impl FnOnce(Option<HostAccent>) + Send + 'static,
+) {
+    let mut buttons: Vec<AlertButton> = HostAccent::ALL
+        .iter()
+        .map(|accent| {
+            let label = format!("{} {}", accent.badge(), accent.label());
+            if current == Some(*accent) {
+                AlertButton::default(format!("{label} ✓"))
+            } else {
+                AlertButton::default(label)
+            }
+        })
+        .collect();
+    if current.is_some() {
+        buttons.push(AlertButton::destructive("No Color"));
+    }
+    buttons.push(AlertButton::cancel("Cancel"));
+    platform_bridge::show_selection(
+        &format!("Color for {host_name}"),
+        "Shown on the workspace header, the home card, and notifications from this host.",
+        buttons,
+        move |choice| match choice {
+            Some(index) if index < HostAccent::ALL.len() => on_pick(Some(HostAccent::ALL[index])),
+            Some(index) if index == HostAccent::ALL.len() && current.is_some() => on_pick(None),
+            _ => {}
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn badges_only_hosts_with_an_accent() {
+        assert_eq!(badged_name("prod", Some(HostAccent::Red)), "🔴 prod");
+        assert_eq!(badged_name("laptop", None), "laptop");
+    }
+}
@@ -0,0 +1,329 @@
+// Host notifications: per-host watchers the host runs for this device.
+//
+// The config lives on `WorkspaceState::host_notify`, so it persists with the
+// saved workspace for a host. The workspace sends it with `HostNotifySet`
+// after each connect and whenever the editor changes it; the host then pushes
+// `HostEvent::Notification` for watched paths, long-running commands, and low
+// disk space, which `show` turns into an in-app notification.
+//
+// The editor is a chain of native dialogs like the environment editor. Each
+// callback hands the next step back to the workspace through
+// `PendingWorkspaceAction::EditHostNotify` so every dialog is presented from
+// the GPUI thread.
+
+use gpui::*;
+use tracing::warn;
+use zedra_rpc::proto::{HOST_NOTIFY_MAX_WATCH_PATHS, HostNotification, HostNotifyConfig};
+use zedra_session::SessionHandle;
+
+use crate::pending::SharedPendingSlot;
+use crate::platform_bridge::{
+    self, AlertButton, ListPickerItem, NativeNotificationKind, NativeNotificationOptions,
+};
+use crate::workspace::PendingWorkspaceAction;
+use crate::workspace_state::WorkspaceState;
+
+/// Choices offered for `long_command_secs`; 0 is off.
+const COMMAND_THRESHOLDS: &[u32] = &[0, 10, 30, 60, 300];
+/// Choices offered for `disk_free_percent`; 0 is off.
+const DISK_THRESHOLDS: &[u8] = &[0, 5, 10, 20];
+
+fn format_duration(secs: u64) -> String {
+    match secs {
+        0..60 => format!("{secs}s"),
+        60..3600 if secs % 60 == 0 => format!("{} min", secs / 60),
+        60..3600 => format!("{}m {}s", secs / 60, secs % 60),
+        _ => format!("{}h {}m", secs / 3600, secs % 3600 / 60),
+    }
+}
+
+/// The host rejects absolute paths and `..` escapes; catch them before saving.
+fn is_workspace_relative(path: &str) -> bool {
+    !path.starts_with(['/', '~']) && !path.split(['/', '\\']).any(|part| part == "..")
+}
+
+fn command_summary(secs: u32) -> String {
+    match secs {
+        0 => "Off".into(),
+        secs => format!("Ran {} or longer", format_duration(secs.into())),
+    }
+}
+
+fn disk_summary(percent: u8) -> String {
+    match percent {
+        0 => "Off".into(),
+        percent => format!("Below {percent}% free"),
+    }
+}
+
+/// Title, message, and kind of the in-app notification for `notification`.
+fn describe(notification: &HostNotification) -> (String, String, NativeNotificationKind) {
+    match notification {
+        HostNotification::FileChanged { path } => (
+            "File changed".into(),
+            path.clone(),
+            NativeNotificationKind::Info,
+        ),
+        HostNotification::CommandFinished {
+            command,
+            exit_code,
+            duration_secs,
+            ..
+        } => {
+            let (title, kind) = if *exit_code == 0 {
+                (
+                    "Command finished".to_string(),
+                    NativeNotificationKind::Success,
+                )
+            } else {
+                (
+                    format!("Command failed ({exit_code})"),
+                    NativeNotificationKind::Error,
+                )
+            };
+            let duration = format_duration(*duration_secs);
+            let message = match command {
+                Some(command) => format!("{command} · {duration}"),
+                None => duration,
+            };
+            (title, message, kind)
+        }
+        HostNotification::DiskLow {
+            mount_point,
+            available_bytes,
+            total_bytes,
+        } => {
+            let percent = available_bytes * 100 / (*total_bytes).max(1);
+            (
+                "Disk nearly full".into(),
+                format!("{percent}% free on {mount_point}"),
+                NativeNotificationKind::Warning,
+            )
+        }
+    }
+}
+
+/// Show a host notification in the app, titled with the host it came from
+/// (`host` is already badged with its accent, if any).
+pub fn show(notification: &HostNotification, host: &str) {
+    let (title, message, kind) = describe(notification);
+    platform_bridge::show_native_notification(
+        NativeNotificationOptions::new(format!("{host} · {title}"))
+            .message(message)
+            .kind(kind),
+    );
+}
+
+/// Send the host's watcher config, alerting when it rejects it.
+pub fn push(session_handle: &SessionHandle, config: HostNotifyConfig, cx: &mut App) {
+    let session_handle = session_handle.clone();
+    cx.spawn(async move |_cx| {
+        if let Err(error) = session_handle.host_notify_set(config).await {
+            warn!("host notify: update failed: {}", error);
+            platform_bridge::show_alert(
+                "Notifications Not Applied",
+                &error.to_string(),
+                vec![AlertButton::cancel("OK")],
+                |_| {},
+            );
+        }
+    })
+    .detach();
+}
+
+pub(crate) enum NotifyEditStep {
+    List,
+    CommandThreshold,
+    DiskThreshold,
+    AddPath,
+    ConfirmRemove { index: usize },
+    Save(HostNotifyConfig),
+}
+
+/// Present one step of the notification editor.
+pub(crate) fn run_edit_step(
+    step: NotifyEditStep,
+    workspace_state: &Entity<WorkspaceState>,
+    session_handle: &SessionHandle,
+    pending: &SharedPendingSlot<PendingWorkspaceAction>,
+    cx: &mut App,
+) {
+    let pending = pending.clone();
+    let next =
+        move |step: NotifyEditStep| pending.set(PendingWorkspaceAction::EditHostNotify(step));
+    let config = workspace_state.read(cx).host_notify.clone();
+    match step {
+        NotifyEditStep::List => {
+            let row = |label: &str, subtitle: String| ListPickerItem {
+                label: label.into(),
+                subtitle: Some(subtitle),
+                image_name: None,
+                trailing_icon: None,
+            };
+            let mut items = vec![
+                row(
+                    "Long-running Commands",
+                    command_summary(config.long_command_secs),
+                ),
+                row("Low Disk Space", disk_summary(config.disk_free_percent)),
+            ];
+            items.extend(
+                config
+                    .watch_paths
+                    .iter()
+                    .map(|path| row(path, "Watching for changes".into())),
+            );
+            let path_count = config.watch_paths.len();
+            if path_count < HOST_NOTIFY_MAX_WATCH_PATHS {
+                items.push(ListPickerItem {
+                    label: "Watch Path".into(),
+                    subtitle: None,
+                    image_name: None,
+                    trailing_icon: None,
+                });
+            }
+            platform_bridge::show_list_picker(
+                "Host Notifications",
+                "Sent by this host while the app is connected. Tap a watched path to remove it.",
+                items,
+                move |selection| {
+                    let Some(selection) = selection else { return };
+                    match selection.index {
+                        0 => next(NotifyEditStep::CommandThreshold),
+                        1 => next(NotifyEditStep::DiskThreshold),
+                        index if index - 2 < path_count => {
+                            next(NotifyEditStep::ConfirmRemove { index: index - 2 })
+                        }
+                        _ => next(NotifyEditStep::AddPath),
+                    }
+                },
+            );
+        }
+        NotifyEditStep::CommandThreshold => {
+            let mut buttons: Vec<AlertButton> = COMMAND_THRESHOLDS
+                .iter()
+                .map(|secs| AlertButton::default(command_summary(*secs)))
+                .collect();
+            buttons.push(AlertButton::cancel("Cancel"));
+            platform_bridge::show_selection(
+                "Long-running Commands",
+                "Notify when a command exits after running this long. Needs shell integration.",
+                buttons,
+                move |selection| {
+                    let Some(secs) = selection.and_then(|index| COMMAND_THRESHOLDS.get(index))
+                    else {
+                        return;
+                    };
+                    next(NotifyEditStep::Save(HostNotifyConfig {
+                        long_command_secs: *secs,
+                        ..config
+                    }));
+                },
+            );
+        }
+        NotifyEditStep::DiskThreshold => {
+            let mut buttons: Vec<AlertButton> = DISK_THRESHOLDS
+                .iter()
+                .map(|percent| AlertButton::default(disk_summary(*percent)))
+                .collect();
+            buttons.push(AlertButton::cancel("Cancel"));
+            platform_bridge::show_selection(
+                "Low Disk Space",
+                "Notify when the workspace's disk runs low on free space.",
+                buttons,
+                move |selection| {
+                    let Some(percent) = selection.and_then(|index| DISK_THRESHOLDS.get(index))
+                    else {
+                        return;
+                    };
+                    next(NotifyEditStep::Save(HostNotifyConfig {
+                        disk_free_percent: *percent,
+                        ..config
+                    }));
+                },
+            );
+        }
+        NotifyEditStep::AddPath => {
+            platform_bridge::show_text_input(
+                "Watch Path",
+                "File or folder, e.g. build/output.log",
+                "",
+                move |result| {
+                    let Some(path) = result.map(|path| path.trim().to_string()) else {
+                        return;
+                    };
+                    if path.is_empty() || config.watch_paths.contains(&path) {
+                        return;
+                    }
+                    if !is_workspace_relative(&path) {
+                        platform_bridge::show_alert(
+                            "Invalid path",
+                            "Use a path inside the workspace, e.g. logs/build.log.",
+                            vec![AlertButton::cancel("OK")],
+                            |_| {},
+                        );
+                        return;
+                    }
+                    let mut config = config;
+                    config.watch_paths.push(path);
+                    next(NotifyEditStep::Save(config));
+                },
+            );
+        }
+        NotifyEditStep::ConfirmRemove { index } => {
+            let Some(path) = config.watch_paths.get(index).cloned() else {
+                return;
+            };
+            platform_bridge::show_alert(
+                "Stop watching?",
+                &path,
+                vec![
+                    AlertButton::destructive("Remove"),
+                    AlertButton::cancel("Cancel"),
+                ],
+                move |button_index| {
+                    if button_index == 0 {
+                        let mut config = config;
+                        config.watch_paths.remove(index);
+                        next(NotifyEditStep::Save(config));
+                    }
+                },
+            );
+        }
+        NotifyEditStep::Save(config) => {
+            workspace_state.update(cx, |state, cx| {
+                state.set_host_notify(config.clone(), cx);
+            });
+            push(session_handle, config, cx);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn describes_failed_commands_with_exit_code_and_duration() {
+        let (title, message, kind) = describe(&HostNotification::CommandFinished {
+            terminal_id: "t1".into(),
+            command: Some("cargo test".into()),
+            exit_code: 101,
+            duration_secs: 135,
+        });
+        assert_eq!(title, "Command failed (101)");
+        assert_eq!(message, "cargo test · 2m 15s");
+        assert_eq!(kind, NativeNotificationKind::Error);
+
+        let (_, message, _) = describe(&HostNotification::DiskLow {
+            mount_point: "/".into(),
+            available_bytes: 5,
+            total_bytes: 100,
+        });
+        assert_eq!(message, "5% free on /");
+        assert_eq!(command_summary(300), "Ran 5 min or longer");
+        assert!(is_workspace_relative("logs/build.log"));
+        assert!(!is_workspace_relative("../outside"));
+        assert!(!is_workspace_relative("/etc/hosts"));
+    }
+}
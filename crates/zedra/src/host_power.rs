@@ -0,0 +1,135 @@
+// Host power: Wake-on-LAN for saved hosts, and sleep or reboot for a
+// connected one.
+//
+// After each sync the session panel asks the host for its hardware addresses
+// and stores them on `WorkspaceState::wake_macs`, so a saved host can be woken
+// from the home screen once it is asleep. The packet goes out from the device
+// on its own network, or from another connected host through `HostWake` when
+// the sleeping host is on that host's network instead. Sleep and reboot are
+// only offered when the host daemon runs with `--allow-power`, and always
+// ask for confirmation first.
+
+use futures::channel::oneshot;
+use gpui::*;
+use tracing::{info, warn};
+use zedra_rpc::proto::HostPowerAction;
+use zedra_rpc::wake;
+use zedra_session::SessionHandle;
+
+use crate::platform_bridge::{self, AlertButton};
+use crate::workspace_state::WorkspaceState;
+
+/// Fetch the host's wake addresses into `workspace_state`. Resolves to whether
+/// the host accepts sleep and reboot; hosts without `HostWakeInfo` resolve to
+/// `false` and keep the addresses from earlier connects.
+pub fn refresh(
+    session_handle: &SessionHandle,
+    workspace_state: &Entity<WorkspaceState>,
+    cx: &mut App,
+) -> Task<bool> {
+    let session_handle = session_handle.clone();
+    let workspace_state = workspace_state.downgrade();
+    cx.spawn(async move |cx| {
+        let (interfaces, power_actions) = match session_handle.host_wake_info().await {
+            Ok(info) => info,
+            Err(e) => {
+                tracing::debug!("host power: wake info unavailable: {e:#}");
+                return false;
+            }
+        };
+        let macs = interfaces.into_iter().map(|i| i.mac).collect();
+        let _ = workspace_state.update(cx, |state, cx| state.set_wake_macs(macs, cx));
+        power_actions
+    })
+}
+
+/// Broadcast wake packets for `macs`, from this device when `via` is `None`
+/// or from the connected host behind `via`.
+pub fn wake(name: String, macs: Vec<String>, via: Option<SessionHandle>, cx: &mut App) {
+    cx.spawn(async move |cx| {
+        let result = match via {
+            Some(handle) => {
+                let mut result = Ok(());
+                for mac in macs {
+                    result = result.and(handle.host_wake(mac, None).await);
+                }
+                result
+            }
+            None => {
+                cx.background_executor()
+                    .spawn(async move {
+                        for mac in macs.iter().filter_map(|mac| wake::parse_mac(mac)) {
+                            wake::send(mac, None)?;
+                        }
+                        anyhow::Ok(())
+                    })
+                    .await
+            }
+        };
+        match result {
+            Ok(()) => info!("host power: sent wake packets for {}", name),
+            Err(e) => {
+                warn!("host power: wake failed: {e:#}");
+                platform_bridge::show_alert(
+                    &format!("Could not wake {name}"),
+                    &e.to_string(),
+                    vec![AlertButton::cancel("OK")],
+                    |_| {},
+                );
+            }
+        }
+    })
+    .detach();
+}
+
+/// Pick sleep or reboot, confirm, then send it to the host.
+pub fn choose_action(session_handle: &SessionHandle, host_name: String, cx: &mut App) {
+    let session_handle = session_handle.clone();
+    cx.spawn(async move |_cx| {
+        let (tx, rx) = oneshot::channel();
+        platform_bridge::show_selection(
+            &host_name,
+            "",
+            vec![
+                AlertButton::default("Sleep"),
+                AlertButton::destructive("Reboot"),
+                AlertButton::cancel("Cancel"),
+            ],
+            move |selection| {
+                let _ = tx.send(selection);
+            },
+        );
+        let (action, verb) = match rx.await {
+            Ok(Some(0)) => (HostPowerAction::Sleep, "Sleep"),
+            Ok(Some(1)) => (HostPowerAction::Reboot, "Reboot"),
+            _ => return,
+        };
+
+        let (tx, rx) = oneshot::channel();
+        platform_bridge::show_alert(
+            &format!("{verb} {host_name}?"),
+            "Terminals and agents on the host stop until it is back.",
+            vec![
+                AlertButton::destructive(verb),
+                AlertButton::cancel("Cancel"),
+            ],
+            move |index| {
+                let _ = tx.send(index);
+            },
+        );
+        if !matches!(rx.await, Ok(0)) {
+            return;
+        }
+
+        if let Err(e) = session_handle.host_power(action).await {
+            warn!("host power: {:?} failed: {}", action, e);
+            platform_bridge::show_alert(
+                "Host Refused",
+                &e.to_string(),
+                vec![AlertButton::cancel("OK")],
+                |_| {},
+            );
+        }
+    })
+    .detach();
+}
@@ -165,6 +165,7 @@ pub extern "C" fn zedra_ios_native_edit_menu_dismiss(callback_id: u32) {
 
 #[unsafe(no_mangle)]
 pub extern "C" fn zedra_launch_gpui() {
+    crate::startup::begin();
     let log_level = if cfg!(feature = "debug-logs") {
         log::LevelFilter::Debug
     } else {
@@ -176,6 +177,7 @@ pub extern "C" fn zedra_launch_gpui() {
     tracing::info!("Zedra iOS: Creating GPUI application with IosPlatform");
 
     let platform: Rc<dyn Platform> = Rc::new(IosPlatform::new());
+    crate::startup::mark("platform");
     let app_cell = app::init_platform_app(platform.clone(), super::bridge::IosBridge);
 
     // Register the finish-launching callback via platform.run().
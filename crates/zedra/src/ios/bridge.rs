@@ -187,6 +187,7 @@ unsafe extern "C" {
         title: *const std::ffi::c_char,
         placeholder: *const std::ffi::c_char,
         initial_value: *const std::ffi::c_char,
+        secure: bool,
     );
     /// Returns 1 for dark, 0 for light, -1 when unavailable.
     fn ios_system_prefers_dark_theme() -> i32;
@@ -197,6 +198,11 @@ unsafe extern "C" {
     fn ios_acquire_image(callback_id: u32, source: i32);
     /// Returns true when UIPasteboard currently holds an image (UIPasteboard.hasImages).
     fn ios_clipboard_has_image() -> bool;
+    /// Copies the 32-byte secrets vault key from the Keychain into `out`,
+    /// creating it on first use. Returns false when the Keychain refused.
+    fn ios_vault_key(out: *mut u8) -> bool;
+    /// Delete the secrets vault key from the Keychain.
+    fn ios_delete_vault_key();
     /// Show or update a native progress HUD (spinner + message) for `id`.
     fn ios_present_native_progress(id: u32, message: *const std::ffi::c_char);
     /// Hide the native progress HUD for `id`.
@@ -595,7 +601,14 @@ impl PlatformBridge for IosBridge {
         unsafe { ios_request_delta_push_token(id) };
     }
 
-    fn present_text_input(&self, id: u32, title: &str, placeholder: &str, initial_value: &str) {
+    fn present_text_input(
+        &self,
+        id: u32,
+        title: &str,
+        placeholder: &str,
+        initial_value: &str,
+        secure: bool,
+    ) {
         use std::ffi::CString;
 
         let title = CString::new(title).unwrap_or_else(|_| CString::new("").unwrap());
@@ -608,6 +621,7 @@ impl PlatformBridge for IosBridge {
                 title.as_ptr(),
                 placeholder.as_ptr(),
                 initial_value.as_ptr(),
+                secure,
             );
         }
     }
@@ -634,6 +648,19 @@ impl PlatformBridge for IosBridge {
         unsafe { ios_clipboard_has_image() }
     }
 
+    fn vault_key(&self) -> Result<Option<[u8; 32]>, String> {
+        let mut key = [0u8; 32];
+        if unsafe { ios_vault_key(key.as_mut_ptr()) } {
+            Ok(Some(key))
+        } else {
+            Err("Keychain is unavailable".into())
+        }
+    }
+
+    fn delete_vault_key(&self) {
+        unsafe { ios_delete_vault_key() };
+    }
+
     fn present_native_progress(&self, id: u32, message: &str) {
         use std::ffi::CString;
 
@@ -101,6 +101,8 @@ __attribute__((weak)) int ios_system_prefers_dark_theme(void) { return -1; }
 __attribute__((weak)) void ios_set_keyboard_accessory_theme(_Bool is_dark) {}
 __attribute__((weak)) void ios_acquire_image(unsigned int callback_id, int source) {}
 __attribute__((weak)) _Bool ios_clipboard_has_image(void) { return 0; }
+__attribute__((weak)) _Bool ios_vault_key(unsigned char *out) { return 0; }
+__attribute__((weak)) void ios_delete_vault_key(void) {}
 __attribute__((weak)) void ios_present_native_progress(unsigned int id, const char *message) {}
 __attribute__((weak)) void ios_dismiss_native_progress(unsigned int id) {}
 
@@ -0,0 +1,164 @@
+// Launcher shortcuts and the home-screen host widget (Android).
+//
+// Up to `MAX_SHORTCUTS` saved workspaces are published as dynamic launcher
+// shortcuts that open `zedra://session/<id>`: the "Open on Launch" workspace
+// first, then the most recently used. The widget shows the first of them with
+// the status of its last session heartbeat and a Connect button using the same
+// link. Hosts are only probed by a live session, so a workspace the app is not
+// connected to reads as "Offline" rather than being pinged in the background.
+//
+// `sync` runs from the app tick and only crosses into the platform when the
+// published list changed.
+
+use std::sync::Mutex;
+
+use gpui::*;
+use serde::Serialize;
+use zedra_session::ConnectPhase;
+
+use crate::platform_bridge;
+use crate::workspace_state::WorkspaceState;
+use crate::workspaces::Workspaces;
+
+/// Launchers show at most four dynamic shortcuts in the long-press menu.
+const MAX_SHORTCUTS: usize = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HostReachability {
+    Online,
+    Connecting,
+    Offline,
+}
+
+impl HostReachability {
+    fn from_phase(phase: Option<&ConnectPhase>) -> Self {
+        match phase {
+            Some(ConnectPhase::Connected | ConnectPhase::Idle { .. }) => Self::Online,
+            Some(ConnectPhase::Reconnecting { .. }) => Self::Connecting,
+            Some(phase) if phase.is_connecting() => Self::Connecting,
+            _ => Self::Offline,
+        }
+    }
+}
+
+/// One published host. Serialized as JSON for the platform layer.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct LauncherShortcut {
+    /// Host session id; also the stable shortcut id.
+    pub id: String,
+    pub label: String,
+    pub hostname: String,
+    pub url: String,
+    pub status: HostReachability,
+}
+
+struct Published {
+    /// "Open on Launch" endpoint, read from settings on the first sync and
+    /// then kept current by `set_pinned`.
+    pinned: Option<Option<String>>,
+    shortcuts: Option<Vec<LauncherShortcut>>,
+}
+
+static PUBLISHED: Mutex<Published> = Mutex::new(Published {
+    pinned: None,
+    shortcuts: None,
+});
+
+/// Note a new "Open on Launch" workspace so the next sync reorders.
+pub fn set_pinned(endpoint_addr: Option<String>) {
+    PUBLISHED.lock().unwrap_or_else(|e| e.into_inner()).pinned = Some(endpoint_addr);
+}
+
+/// Shortcuts for `states`, pinned workspace first, then newest first.
+fn shortcuts_for<'a>(
+    states: impl IntoIterator<Item = &'a WorkspaceState>,
+    pinned_endpoint: Option<&str>,
+) -> Vec<LauncherShortcut> {
+    let mut states: Vec<&WorkspaceState> = states
+        .into_iter()
+        .filter(|state| !state.session_id.is_empty())
+        .collect();
+    states.sort_by_key(|state| {
+        (
+            Some(state.endpoint_addr.as_str()) != pinned_endpoint,
+            std::cmp::Reverse(state.updated_at),
+        )
+    });
+    states
+        .into_iter()
+        .take(MAX_SHORTCUTS)
+        .map(|state| LauncherShortcut {
+            id: state.session_id.clone(),
+            label: match state.display_name() {
+                "" => "Workspace".into(),
+                name => name.into(),
+            },
+            hostname: state.hostname.clone(),
+            url: format!("zedra://session/{}", state.session_id),
+            status: HostReachability::from_phase(state.connect_phase.as_ref()),
+        })
+        .collect()
+}
+
+/// Publish the saved workspaces to the launcher when they changed.
+pub fn sync(workspaces: &Entity<Workspaces>, cx: &App) {
+    let mut published = PUBLISHED.lock().unwrap_or_else(|e| e.into_inner());
+    let pinned = published
+        .pinned
+        .get_or_insert_with(crate::settings::read_auto_connect_endpoint)
+        .clone();
+    let shortcuts = shortcuts_for(
+        workspaces
+            .read(cx)
+            .states()
+            .iter()
+            .map(|state| state.read(cx)),
+        pinned.as_deref(),
+    );
+    if published.shortcuts.as_ref() == Some(&shortcuts) {
+        return;
+    }
+    match serde_json::to_string(&shortcuts) {
+        Ok(json) => platform_bridge::bridge().publish_launcher_shortcuts(&json),
+        Err(error) => {
+            tracing::error!("launcher shortcuts: serialize failed: {}", error);
+            return;
+        }
+    }
+    published.shortcuts = Some(shortcuts);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(session_id: &str, endpoint_addr: &str, updated_at: u64) -> WorkspaceState {
+        let mut state = WorkspaceState::default();
+        state.session_id = session_id.into();
+        state.endpoint_addr = endpoint_addr.into();
+        state.project_name = format!("project-{session_id}");
+        state.updated_at = updated_at;
+        state
+    }
+
+    #[test]
+    fn orders_pinned_first_then_newest_and_caps_at_four() {
+        let mut states = vec![
+            state("a", "ep-a", 10),
+            state("b", "ep-b", 50),
+            state("c", "ep-c", 40),
+            state("d", "ep-d", 30),
+            state("e", "ep-e", 20),
+            state("", "ep-unpaired", 90),
+        ];
+        states[3].connect_phase = Some(ConnectPhase::Connected);
+        let shortcuts = shortcuts_for(&states, Some("ep-a"));
+        let ids: Vec<&str> = shortcuts.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, ["a", "b", "c", "d"]);
+        assert_eq!(shortcuts[0].url, "zedra://session/a");
+        assert_eq!(shortcuts[0].label, "project-a");
+        assert_eq!(shortcuts[0].status, HostReachability::Offline);
+        assert_eq!(shortcuts[3].status, HostReachability::Online);
+    }
+}
@@ -22,6 +22,7 @@ pub mod fonts;
 pub mod placeholder;
 pub mod settings;
 pub mod theme;
+pub mod theme_import;
 pub mod ui;
 pub mod vfx;
 
@@ -30,18 +31,36 @@ pub mod home_view;
 pub mod settings_view;
 
 // Semantic components
+pub mod ai_approval;
+pub mod ai_edit;
+pub mod branch_picker;
+pub mod command_guard;
+pub mod commit_detail;
+pub mod file_batch;
 pub mod file_explorer;
+pub mod file_history;
 pub mod file_preview_view;
 pub mod file_search;
+pub mod git_credential;
+pub mod git_graph;
 pub mod git_panel;
+pub mod host_accent;
+pub mod host_notify;
+pub mod host_power;
+pub mod problems;
 pub mod quick_action_panel;
+pub mod rebase_planner;
+pub mod recording_player;
+pub mod session_env;
 pub mod session_panel;
 pub mod sheet_demo_state;
 pub mod sheet_demo_view;
 pub mod sheet_host_view;
+pub mod startup;
 pub mod terminal_card;
 pub mod terminal_panel;
 pub mod terminal_state;
+pub mod terminal_triggers;
 pub mod transport_badge;
 
 // Per-session workspace
@@ -61,9 +80,13 @@ pub mod active_terminal;
 pub mod deeplink;
 pub mod delta;
 pub mod image_upload;
+pub mod launcher_shortcuts;
+pub mod memory;
 pub mod native_presentation;
 pub mod platform_bridge;
 pub mod telemetry;
+pub mod vault;
+pub mod view_state;
 pub mod web_tunnel;
 pub mod web_tunnel_manager;
 pub mod webview;
@@ -0,0 +1,102 @@
+// Memory pressure handling.
+//
+// Android delivers `onTrimMemory(level)` / `onLowMemory()` through JNI
+// (`MainActivity.nativeTrimMemory`); `ZedraApp::trim_memory` then releases
+// caches that can be rebuilt on demand. Harder pressure levels also trim
+// terminal scrollback down to a floor so the process is less likely to be
+// killed while in the background.
+
+/// `ComponentCallbacks2.TRIM_MEMORY_*` levels.
+const TRIM_MEMORY_RUNNING_LOW: i32 = 10;
+const TRIM_MEMORY_RUNNING_CRITICAL: i32 = 15;
+const TRIM_MEMORY_MODERATE: i32 = 60;
+const TRIM_MEMORY_COMPLETE: i32 = 80;
+
+/// Level reported for `onLowMemory()`, which has no level of its own.
+pub const LOW_MEMORY_LEVEL: i32 = TRIM_MEMORY_COMPLETE;
+
+/// Scrollback lines each terminal keeps under [`MemoryPressure::Low`].
+const SCROLLBACK_FLOOR_LOW: usize = 2_000;
+/// Scrollback lines each terminal keeps under [`MemoryPressure::Critical`].
+const SCROLLBACK_FLOOR_CRITICAL: usize = 500;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MemoryPressure {
+    /// UI hidden or mild pressure: drop render caches only.
+    Moderate,
+    /// Also trim terminal scrollback to a generous floor.
+    Low,
+    /// The process is next in line to be killed: trim scrollback hard.
+    Critical,
+}
+
+impl MemoryPressure {
+    pub fn from_trim_level(level: i32) -> Self {
+        match level {
+            TRIM_MEMORY_RUNNING_CRITICAL | TRIM_MEMORY_COMPLETE.. => Self::Critical,
+            TRIM_MEMORY_RUNNING_LOW | TRIM_MEMORY_MODERATE.. => Self::Low,
+            _ => Self::Moderate,
+        }
+    }
+
+    /// Scrollback lines each terminal keeps, or `None` to leave scrollback alone.
+    pub fn scrollback_floor(self) -> Option<usize> {
+        match self {
+            Self::Moderate => None,
+            Self::Low => Some(SCROLLBACK_FLOOR_LOW),
+            Self::Critical => Some(SCROLLBACK_FLOOR_CRITICAL),
+        }
+    }
+}
+
+/// What one trim pass released, for the reclaim log line.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MemoryReclaim {
+    pub scrollback_bytes: usize,
+    pub line_cache_bytes: usize,
+}
+
+impl MemoryReclaim {
+    pub fn total_bytes(&self) -> usize {
+        self.scrollback_bytes + self.line_cache_bytes
+    }
+
+    pub fn add(&mut self, other: MemoryReclaim) {
+        self.scrollback_bytes += other.scrollback_bytes;
+        self.line_cache_bytes += other.line_cache_bytes;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trim_levels_map_to_pressure() {
+        // RUNNING_MODERATE, UI_HIDDEN, BACKGROUND
+        assert_eq!(MemoryPressure::from_trim_level(5), MemoryPressure::Moderate);
+        assert_eq!(
+            MemoryPressure::from_trim_level(20),
+            MemoryPressure::Moderate
+        );
+        assert_eq!(
+            MemoryPressure::from_trim_level(40),
+            MemoryPressure::Moderate
+        );
+        assert_eq!(MemoryPressure::from_trim_level(10), MemoryPressure::Low);
+        assert_eq!(MemoryPressure::from_trim_level(60), MemoryPressure::Low);
+        assert_eq!(
+            MemoryPressure::from_trim_level(15),
+            MemoryPressure::Critical
+        );
+        assert_eq!(
+            MemoryPressure::from_trim_level(LOW_MEMORY_LEVEL),
+            MemoryPressure::Critical
+        );
+
+        assert_eq!(MemoryPressure::Moderate.scrollback_floor(), None);
+        assert!(
+            MemoryPressure::Critical.scrollback_floor() < MemoryPressure::Low.scrollback_floor()
+        );
+    }
+}
@@ -14,7 +14,7 @@ pub fn render_placeholder(cx: &App, message: impl Into<String>) -> Div {
                 // Magic! It's more balance with this
                 .top(px(-32.0))
                 .text_color(rgb(theme::text_secondary(cx)))
-                .text_size(px(theme::FONT_BODY))
+                .text_size(theme::scaled(theme::FONT_BODY))
                 .text_align(TextAlign::Center)
                 .child(message.into()),
         )
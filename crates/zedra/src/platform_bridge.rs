@@ -449,13 +449,33 @@ pub fn show_text_input(
     placeholder: &str,
     initial_value: &str,
     on_result: impl FnOnce(Option<String>) + Send + 'static,
+) {
+    present_text_input(title, placeholder, initial_value, false, on_result);
+}
+
+/// Like `show_text_input`, but masks the entry and skips autocorrect, for
+/// passwords and tokens.
+pub fn show_secure_text_input(
+    title: &str,
+    placeholder: &str,
+    on_result: impl FnOnce(Option<String>) + Send + 'static,
+) {
+    present_text_input(title, placeholder, "", true, on_result);
+}
+
+fn present_text_input(
+    title: &str,
+    placeholder: &str,
+    initial_value: &str,
+    secure: bool,
+    on_result: impl FnOnce(Option<String>) + Send + 'static,
 ) {
     let id = NEXT_TEXT_INPUT_ID.fetch_add(1, Ordering::Relaxed);
     text_input_callbacks()
         .lock()
         .unwrap()
         .insert(id, Box::new(on_result));
-    bridge().present_text_input(id, title, placeholder, initial_value);
+    bridge().present_text_input(id, title, placeholder, initial_value, secure);
 }
 
 /// Called by the platform after the user confirms a text-input dialog.
@@ -1004,6 +1024,32 @@ pub fn is_app_in_foreground() -> bool {
     APP_IN_FOREGROUND.load(std::sync::atomic::Ordering::Relaxed)
 }
 
+static NETWORK_METERED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+static METERED_TX: OnceLock<broadcast::Sender<bool>> = OnceLock::new();
+
+fn metered_tx() -> &'static broadcast::Sender<bool> {
+    METERED_TX.get_or_init(|| broadcast::channel(4).0)
+}
+
+pub fn subscribe_network_metered() -> broadcast::Receiver<bool> {
+    metered_tx().subscribe()
+}
+
+/// Whether the default network is metered (cellular, or marked metered by the
+/// user). Reported by the Android connectivity callback; other platforms stay
+/// unmetered and rely on the Data Saver setting.
+pub fn set_network_metered(value: bool) {
+    let previous = NETWORK_METERED.swap(value, std::sync::atomic::Ordering::Relaxed);
+    if previous != value {
+        let _ = metered_tx().send(value);
+    }
+}
+
+pub fn is_network_metered() -> bool {
+    NETWORK_METERED.load(std::sync::atomic::Ordering::Relaxed)
+}
+
 pub fn play_sound(sound: SoundEffect) {
     bridge().play_sound(sound);
 }
@@ -1133,8 +1179,15 @@ pub trait PlatformBridge: Send + Sync + 'static {
     }
     /// Display a native text-input dialog.
     /// The platform should call `dispatch_text_input_result(id, value)` on confirm,
-    /// or `dispatch_text_input_dismiss(id)` on cancel.
-    fn present_text_input(&self, _id: u32, _title: &str, _placeholder: &str, _initial_value: &str) {
+    /// or `dispatch_text_input_dismiss(id)` on cancel. `secure` masks the entry.
+    fn present_text_input(
+        &self,
+        _id: u32,
+        _title: &str,
+        _placeholder: &str,
+        _initial_value: &str,
+        _secure: bool,
+    ) {
     }
     /// OS appearance: `dark`, `light`, or `unknown` when unavailable.
     fn system_prefers_theme(&self) -> SystemTheme {
@@ -1151,11 +1204,28 @@ pub trait PlatformBridge: Send + Sync + 'static {
             "image acquisition not supported on this platform".into(),
         );
     }
+    /// OS font-size multiplier from accessibility settings; 1.0 is the default.
+    fn font_scale(&self) -> f32 {
+        1.0
+    }
     /// Cheap synchronous check: does the system clipboard currently hold an
     /// image? Used to decide whether to show the "Paste Image" edit-menu item.
     fn clipboard_has_image(&self) -> bool {
         false
     }
+    /// The 32-byte key sealing the secrets vault, created on first use and
+    /// held by platform key storage (Android Keystore, iOS Keychain).
+    /// `Ok(None)` means the platform has none and the vault keeps its key in
+    /// a private file instead.
+    fn vault_key(&self) -> Result<Option<[u8; 32]>, String> {
+        Ok(None)
+    }
+    /// Destroy the vault key held by platform key storage.
+    fn delete_vault_key(&self) {}
+    /// Replace the launcher shortcuts and home-screen widget hosts with
+    /// `shortcuts_json`, a serialized list of
+    /// [`crate::launcher_shortcuts::LauncherShortcut`].
+    fn publish_launcher_shortcuts(&self, _shortcuts_json: &str) {}
 }
 
 static BRIDGE: OnceLock<Box<dyn PlatformBridge>> = OnceLock::new();
@@ -0,0 +1,493 @@
+use gpui::prelude::FluentBuilder;
+use gpui::*;
+use zedra_rpc::proto::{DiagnosticSeverity, WorkspaceDiagnostic};
+use zedra_session::SessionHandle;
+
+use crate::fonts;
+use crate::platform_bridge::{self, HapticFeedback};
+use crate::theme;
+use crate::ui::{
+    PullToRefresh, PullToRefreshEvent, chevron_back_button, subscreen_empty_text,
+    subscreen_padded_body, subscreen_refresh_button, subscreen_refreshable_page,
+};
+use crate::workspace_action;
+
+const SEVERITIES: [DiagnosticSeverity; 3] = [
+    DiagnosticSeverity::Error,
+    DiagnosticSeverity::Warning,
+    DiagnosticSeverity::Info,
+];
+
+#[derive(Clone, Debug)]
+enum CheckState {
+    Running,
+    Ready,
+    Error(String),
+}
+
+/// Which severities the list shows. All are shown by default.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct SeverityFilter {
+    hidden: [bool; 3],
+}
+
+impl SeverityFilter {
+    fn index(severity: DiagnosticSeverity) -> usize {
+        match severity {
+            DiagnosticSeverity::Error => 0,
+            DiagnosticSeverity::Warning => 1,
+            DiagnosticSeverity::Info => 2,
+        }
+    }
+
+    fn shows(&self, severity: DiagnosticSeverity) -> bool {
+        !self.hidden[Self::index(severity)]
+    }
+
+    fn toggle(&mut self, severity: DiagnosticSeverity) {
+        let index = Self::index(severity);
+        self.hidden[index] = !self.hidden[index];
+    }
+}
+
+/// Diagnostics for one file, in line order.
+#[derive(Clone, Debug, PartialEq)]
+struct ProblemGroup {
+    rel_path: String,
+    items: Vec<WorkspaceDiagnostic>,
+}
+
+/// Group `diagnostics` by file, keeping only severities `filter` shows. Files
+/// with errors sort first, then by path.
+fn group_by_file(diagnostics: &[WorkspaceDiagnostic], filter: SeverityFilter) -> Vec<ProblemGroup> {
+    let mut groups: Vec<ProblemGroup> = Vec::new();
+    for diagnostic in diagnostics.iter().filter(|d| filter.shows(d.severity)) {
+        match groups
+            .iter_mut()
+            .find(|g| g.rel_path == diagnostic.rel_path)
+        {
+            Some(group) => group.items.push(diagnostic.clone()),
+            None => groups.push(ProblemGroup {
+                rel_path: diagnostic.rel_path.clone(),
+                items: vec![diagnostic.clone()],
+            }),
+        }
+    }
+    for group in &mut groups {
+        group.items.sort_by_key(|d| (d.line, d.column, d.severity));
+    }
+    groups.sort_by(|a, b| {
+        let worst = |g: &ProblemGroup| g.items.iter().map(|d| d.severity).min();
+        worst(a)
+            .cmp(&worst(b))
+            .then_with(|| a.rel_path.cmp(&b.rel_path))
+    });
+    groups
+}
+
+fn severity_count(diagnostics: &[WorkspaceDiagnostic], severity: DiagnosticSeverity) -> usize {
+    diagnostics
+        .iter()
+        .filter(|d| d.severity == severity)
+        .count()
+}
+
+fn severity_label(severity: DiagnosticSeverity) -> &'static str {
+    match severity {
+        DiagnosticSeverity::Error => "Errors",
+        DiagnosticSeverity::Warning => "Warnings",
+        DiagnosticSeverity::Info => "Info",
+    }
+}
+
+fn severity_color(severity: DiagnosticSeverity, cx: &App) -> u32 {
+    match severity {
+        DiagnosticSeverity::Error => theme::accent_red(cx),
+        DiagnosticSeverity::Warning => theme::accent_yellow(cx),
+        DiagnosticSeverity::Info => theme::accent_blue(cx),
+    }
+}
+
+/// Problems screen: runs the host's workspace checker and lists its
+/// diagnostics grouped by file. Tapping a row opens the editor at that line.
+///
+/// The workspace keeps this view alive across navigation so returning from
+/// the editor does not rerun the check; refresh or pull to run it again.
+pub struct ProblemsView {
+    session_handle: SessionHandle,
+    diagnostics: Vec<WorkspaceDiagnostic>,
+    tool: String,
+    truncated: bool,
+    filter: SeverityFilter,
+    state: CheckState,
+    check_epoch: u64,
+    pull_refresh: Entity<PullToRefresh>,
+    _check_task: Option<Task<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl ProblemsView {
+    pub fn new(session_handle: SessionHandle, cx: &mut Context<Self>) -> Self {
+        let pull_refresh = cx.new(|_| PullToRefresh::new());
+        let pull_sub = cx.subscribe(&pull_refresh, |this, _pull, event, cx| match event {
+            PullToRefreshEvent::Refresh => this.run_check(cx),
+        });
+        let mut view = Self {
+            session_handle,
+            diagnostics: Vec::new(),
+            tool: String::new(),
+            truncated: false,
+            filter: SeverityFilter::default(),
+            state: CheckState::Running,
+            check_epoch: 0,
+            pull_refresh,
+            _check_task: None,
+            _subscriptions: vec![pull_sub],
+        };
+        view.run_check(cx);
+        view
+    }
+
+    fn run_check(&mut self, cx: &mut Context<Self>) {
+        self.check_epoch = self.check_epoch.wrapping_add(1);
+        let epoch = self.check_epoch;
+        self.state = CheckState::Running;
+        cx.notify();
+
+        let handle = self.session_handle.clone();
+        self._check_task = Some(cx.spawn(async move |this, cx| {
+            let result = handle.diagnostics_check(".").await;
+            let _ = this.update(cx, |this, cx| {
+                if this.check_epoch != epoch {
+                    return;
+                }
+                match result {
+                    Ok(result) => {
+                        this.diagnostics = result.diagnostics;
+                        this.tool = result.tool;
+                        this.truncated = result.truncated;
+                        this.state = CheckState::Ready;
+                    }
+                    Err(e) => {
+                        tracing::warn!("problems: check failed: {}", e);
+                        this.state = CheckState::Error(e.to_string());
+                    }
+                }
+                this.pull_refresh.update(cx, |pull, cx| pull.finish(cx));
+                cx.notify();
+            });
+        }));
+    }
+
+    fn subtitle(&self) -> String {
+        match &self.state {
+            CheckState::Running if self.tool.is_empty() => "Running checks…".to_string(),
+            CheckState::Running => format!("Running {}…", self.tool),
+            CheckState::Error(_) => "Check failed".to_string(),
+            CheckState::Ready => {
+                let total = self.diagnostics.len();
+                let suffix = if self.truncated { "+" } else { "" };
+                format!("{total}{suffix} from {}", self.tool)
+            }
+        }
+    }
+
+    fn render_filters(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let mut row = div()
+            .flex()
+            .flex_row()
+            .flex_wrap()
+            .gap(px(theme::SPACING_SM))
+            .pb(px(theme::SPACING_SM));
+        for severity in SEVERITIES {
+            let count = severity_count(&self.diagnostics, severity);
+            let shown = self.filter.shows(severity);
+            row = row.child(
+                div()
+                    .id(ElementId::Name(
+                        format!("problems-filter-{}", SeverityFilter::index(severity)).into(),
+                    ))
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .gap(px(theme::SPACING_XS))
+                    .px(px(theme::SPACING_SM))
+                    .py(px(theme::SPACING_XS))
+                    .rounded(px(theme::BADGE_RADIUS))
+                    .border_1()
+                    .border_color(rgb(if shown {
+                        theme::border_default(cx)
+                    } else {
+                        theme::border_subtle(cx)
+                    }))
+                    .when(!shown, |el| el.opacity(0.5))
+                    .cursor_pointer()
+                    .on_press(cx.listener(move |this, _event, _window, cx| {
+                        platform_bridge::trigger_haptic(HapticFeedback::SelectionChanged);
+                        this.filter.toggle(severity);
+                        cx.notify();
+                    }))
+                    .child(
+                        div()
+                            .size(px(theme::ICON_STATUS))
+                            .rounded_full()
+                            .bg(rgb(severity_color(severity, cx))),
+                    )
+                    .child(
+                        div()
+                            .text_size(theme::scaled(theme::FONT_DETAIL))
+                            .text_color(rgb(theme::text_secondary(cx)))
+                            .child(format!("{} {count}", severity_label(severity))),
+                    ),
+            );
+        }
+        row
+    }
+
+    fn render_group(&self, group: ProblemGroup, cx: &mut Context<Self>) -> impl IntoElement {
+        let mut rows = div().flex().flex_col().min_w_0();
+        for (index, diagnostic) in group.items.into_iter().enumerate() {
+            rows = rows.child(render_problem_row(&group.rel_path, index, diagnostic, cx));
+        }
+        div()
+            .min_w_0()
+            .flex()
+            .flex_col()
+            .pb(px(theme::SPACING_MD))
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .gap(px(theme::SPACING_SM))
+                    .min_w_0()
+                    .pb(px(theme::SPACING_XS))
+                    .child(
+                        div()
+                            .flex_1()
+                            .min_w_0()
+                            .truncate()
+                            .text_size(theme::scaled(theme::FONT_BODY))
+                            .font_family(fonts::MONO_FONT_FAMILY)
+                            .text_color(rgb(theme::text_primary(cx)))
+                            .child(group.rel_path.clone()),
+                    ),
+            )
+            .child(rows)
+    }
+}
+
+fn render_problem_row(
+    rel_path: &str,
+    index: usize,
+    diagnostic: WorkspaceDiagnostic,
+    cx: &mut Context<ProblemsView>,
+) -> impl IntoElement {
+    let path = diagnostic.path.clone();
+    let line = diagnostic.line;
+    div()
+        .id(ElementId::Name(
+            format!("problem-{rel_path}-{index}").into(),
+        ))
+        .flex()
+        .flex_row()
+        .items_start()
+        .gap(px(theme::SPACING_SM))
+        .min_w_0()
+        .py(px(theme::SPACING_XS))
+        .cursor_pointer()
+        .on_press(move |_event, window, cx| {
+            platform_bridge::trigger_haptic(HapticFeedback::ImpactLight);
+            window.dispatch_action(
+                workspace_action::OpenFileAtLine {
+                    path: path.clone(),
+                    line,
+                }
+                .boxed_clone(),
+                cx,
+            );
+        })
+        .child(
+            div()
+                .mt(px(5.0))
+                .flex_shrink_0()
+                .size(px(theme::ICON_STATUS))
+                .rounded_full()
+                .bg(rgb(severity_color(diagnostic.severity, cx))),
+        )
+        .child(
+            div()
+                .flex_1()
+                .min_w_0()
+                .flex()
+                .flex_col()
+                .child(
+                    div()
+                        .text_size(theme::scaled(theme::FONT_BODY))
+                        .text_color(rgb(theme::text_secondary(cx)))
+                        .whitespace_normal()
+                        .child(diagnostic.message),
+                )
+                .child(
+                    div()
+                        .text_size(theme::scaled(theme::FONT_DETAIL))
+                        .font_family(fonts::MONO_FONT_FAMILY)
+                        .text_color(rgb(theme::text_muted(cx)))
+                        .child(match diagnostic.code {
+                            Some(code) => {
+                                format!("{}:{}  {code}", diagnostic.line, diagnostic.column)
+                            }
+                            None => format!("{}:{}", diagnostic.line, diagnostic.column),
+                        }),
+                ),
+        )
+}
+
+impl Render for ProblemsView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let groups = group_by_file(&self.diagnostics, self.filter);
+        let mut list = div().flex().flex_col().min_w_0();
+        list = match &self.state {
+            CheckState::Running if self.diagnostics.is_empty() => {
+                list.child(subscreen_empty_text("Checking workspace…", cx))
+            }
+            CheckState::Error(message) => list.child(subscreen_empty_text(message.clone(), cx)),
+            _ if self.diagnostics.is_empty() => {
+                list.child(subscreen_empty_text("No problems found.", cx))
+            }
+            _ if groups.is_empty() => {
+                list.child(subscreen_empty_text("All problems are filtered out.", cx))
+            }
+            _ => {
+                for group in groups {
+                    list = list.child(self.render_group(group, cx));
+                }
+                list
+            }
+        };
+        let body = subscreen_padded_body(
+            div()
+                .flex()
+                .flex_col()
+                .min_w_0()
+                .when(!self.diagnostics.is_empty(), |el| {
+                    el.child(self.render_filters(cx))
+                })
+                .child(list),
+        )
+        .into_any_element();
+        let header = render_problems_header(self.subtitle(), cx).into_any_element();
+        subscreen_refreshable_page(
+            "problems",
+            rgb(theme::bg_primary(cx)),
+            header,
+            body,
+            &self.pull_refresh,
+            cx,
+        )
+    }
+}
+
+fn render_problems_header(subtitle: String, cx: &mut Context<ProblemsView>) -> impl IntoElement {
+    div()
+        .id("problems-header")
+        .min_w_0()
+        .px(px(theme::SUBSCREEN_PADDING_X))
+        .pt(px(theme::SPACING_XS))
+        .pb(px(theme::SPACING_SM))
+        .child(
+            div()
+                .id("problems-header-inner")
+                .relative()
+                .min_w_0()
+                .child(
+                    div()
+                        .min_w_0()
+                        .flex()
+                        .flex_row()
+                        .items_center()
+                        .gap(px(theme::SPACING_MD))
+                        .child(chevron_back_button(
+                            "problems-back-btn",
+                            cx,
+                            |_this, _event, window, cx| {
+                                platform_bridge::trigger_haptic(HapticFeedback::ImpactLight);
+                                window.dispatch_action(
+                                    workspace_action::NavigateBack.boxed_clone(),
+                                    cx,
+                                );
+                            },
+                        ))
+                        .child(
+                            div()
+                                .flex_1()
+                                .min_w_0()
+                                .flex()
+                                .flex_col()
+                                .child(
+                                    div()
+                                        .text_size(theme::scaled(theme::FONT_HEADING))
+                                        .font_family(fonts::HEADING_FONT_FAMILY)
+                                        .font_weight(FontWeight::MEDIUM)
+                                        .text_color(rgb(theme::text_primary(cx)))
+                                        .child("Problems"),
+                                )
+                                .child(
+                                    div()
+                                        .text_size(theme::scaled(theme::FONT_BODY))
+                                        .text_color(rgb(theme::text_muted(cx)))
+                                        .child(subtitle),
+                                ),
+                        ),
+                )
+                .child(subscreen_refresh_button(
+                    "problems-refresh-btn",
+                    cx,
+                    |this, _event, _window, cx| this.run_check(cx),
+                )),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diagnostic(rel_path: &str, line: u32, severity: DiagnosticSeverity) -> WorkspaceDiagnostic {
+        WorkspaceDiagnostic {
+            path: format!("/work/{rel_path}"),
+            rel_path: rel_path.to_string(),
+            line,
+            column: 1,
+            end_line: line,
+            end_column: 1,
+            severity,
+            message: format!("problem at {line}"),
+            code: None,
+        }
+    }
+
+    #[test]
+    fn groups_by_file_with_errors_first_and_respects_filter() {
+        let diagnostics = vec![
+            diagnostic("a.rs", 9, DiagnosticSeverity::Warning),
+            diagnostic("b.rs", 4, DiagnosticSeverity::Error),
+            diagnostic("a.rs", 2, DiagnosticSeverity::Warning),
+            diagnostic("c.rs", 1, DiagnosticSeverity::Info),
+        ];
+
+        let groups = group_by_file(&diagnostics, SeverityFilter::default());
+        let order: Vec<_> = groups.iter().map(|g| g.rel_path.as_str()).collect();
+        assert_eq!(order, ["b.rs", "a.rs", "c.rs"]);
+        let lines: Vec<_> = groups[1].items.iter().map(|d| d.line).collect();
+        assert_eq!(lines, [2, 9]);
+
+        let mut filter = SeverityFilter::default();
+        filter.toggle(DiagnosticSeverity::Warning);
+        let groups = group_by_file(&diagnostics, filter);
+        let order: Vec<_> = groups.iter().map(|g| g.rel_path.as_str()).collect();
+        assert_eq!(order, ["b.rs", "c.rs"]);
+
+        filter.toggle(DiagnosticSeverity::Warning);
+        assert_eq!(filter, SeverityFilter::default());
+    }
+}
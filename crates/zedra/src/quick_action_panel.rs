@@ -1,4 +1,8 @@
 // QuickActionPanel — right-side overlay for workspace switching.
+//
+// Lists every connected workspace with its terminals, web clients, and the
+// files open in its editor. The filter at the top narrows all of them by a
+// fuzzy (in-order subsequence) match so any context is one tap away.
 
 use std::time::Duration;
 
@@ -10,6 +14,9 @@ use crate::terminal_card::{TerminalCardProps, render_terminal_card};
 use crate::terminal_state::TerminalState;
 use crate::theme;
 use crate::transport_badge::ConnectionStatusIndicator;
+use crate::ui::InputChanged;
+use crate::ui::input::Input;
+use crate::workspace_state::WorkspaceMainView;
 use crate::workspaces::Workspaces;
 
 #[derive(Clone, Debug)]
@@ -18,6 +25,7 @@ pub enum QuickActionEvent {
     GoHome,
     NavigateToWorkspace,
     OpenTerminal { tid: String, ws_index: usize },
+    OpenFile { path: String, ws_index: usize },
     CloseTerminal { tid: String, ws_index: usize },
     OpenWebClient { id: String, ws_index: usize },
     CloseWebClient { id: String, ws_index: usize },
@@ -34,6 +42,22 @@ fn is_active_terminal_card(
     active_workspace_index == Some(workspace_index) && active_terminal_id == Some(terminal_id)
 }
 
+/// Whether `query`'s characters appear in order in any of `texts`, ignoring
+/// case. An empty query matches everything.
+fn fuzzy_matches<'a>(query: &str, texts: impl IntoIterator<Item = &'a str>) -> bool {
+    if query.is_empty() {
+        return true;
+    }
+    texts.into_iter().any(|text| {
+        let mut haystack = text.chars().flat_map(char::to_lowercase);
+        query
+            .chars()
+            .flat_map(char::to_lowercase)
+            .filter(|c| !c.is_whitespace())
+            .all(|needle| haystack.any(|c| c == needle))
+    })
+}
+
 enum QuickActionPickerPending {
     CreateAgent { ws_index: usize },
     NewTerminal { ws_index: usize },
@@ -44,8 +68,11 @@ enum QuickActionPickerPending {
 pub struct QuickActionPanel {
     workspaces: Entity<Workspaces>,
     focus_handle: FocusHandle,
+    filter_input: Entity<Input>,
+    query: String,
     pending_picker: SharedPendingSlot<QuickActionPickerPending>,
     _pending_picker_task: Task<()>,
+    _filter_subscription: Subscription,
 }
 
 impl QuickActionPanel {
@@ -58,12 +85,39 @@ impl QuickActionPanel {
                     this.process_pending_picker_action(action, cx);
                 }
             });
+        let filter_input = cx.new(|cx| {
+            Input::new(cx)
+                .placeholder("Filter terminals and files")
+                .toggle_keyboard_on_press(true)
+                .hide_keyboard_on_submit(true)
+        });
+        let _filter_subscription = cx.subscribe(
+            &filter_input,
+            |this: &mut Self, _input, event: &InputChanged, cx| {
+                this.query = event.value.clone();
+                cx.notify();
+            },
+        );
         Self {
             workspaces,
             focus_handle: cx.focus_handle(),
+            filter_input,
+            query: String::new(),
             pending_picker,
             _pending_picker_task,
+            _filter_subscription,
+        }
+    }
+
+    /// Reset the filter so the next open lists everything.
+    pub fn clear_filter(&mut self, cx: &mut Context<Self>) {
+        if self.query.is_empty() {
+            return;
         }
+        self.query.clear();
+        self.filter_input
+            .update(cx, |input, _cx| input.set_value(""));
+        cx.notify();
     }
 
     fn process_pending_picker_action(
@@ -185,6 +239,68 @@ impl QuickActionPanel {
         cx.emit(QuickActionEvent::OpenTerminal { tid, ws_index });
     }
 
+    fn handle_open_file(&self, ws_index: usize, path: String, cx: &mut Context<Self>) {
+        self.workspaces
+            .update(cx, |ws, cx| ws.switch_to(ws_index, cx));
+        cx.emit(QuickActionEvent::Close);
+        cx.emit(QuickActionEvent::NavigateToWorkspace);
+        cx.emit(QuickActionEvent::OpenFile { path, ws_index });
+    }
+
+    fn render_file_row(
+        &self,
+        ws_index: usize,
+        path: String,
+        cx: &Context<Self>,
+    ) -> impl IntoElement {
+        let name = path.rsplit('/').next().unwrap_or(&path).to_string();
+        let path_open = path.clone();
+        div()
+            .id(SharedString::from(format!(
+                "quick-action-file-{ws_index}-{path}"
+            )))
+            .flex()
+            .flex_row()
+            .items_center()
+            .gap(px(8.0))
+            .px(px(16.0))
+            .py(px(6.0))
+            .cursor_pointer()
+            .on_press(cx.listener(move |this, _event, _window, cx| {
+                this.handle_open_file(ws_index, path_open.clone(), cx);
+            }))
+            .child(
+                svg()
+                    .path("icons/file.svg")
+                    .size(px(theme::ICON_FILE))
+                    .flex_shrink_0()
+                    .text_color(rgb(theme::text_muted(cx))),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .min_w_0()
+                    .flex()
+                    .flex_col()
+                    .child(
+                        div()
+                            .min_w_0()
+                            .truncate()
+                            .text_size(theme::scaled(theme::FONT_BODY))
+                            .text_color(rgb(theme::text_primary(cx)))
+                            .child(name),
+                    )
+                    .child(
+                        div()
+                            .min_w_0()
+                            .truncate()
+                            .text_size(theme::scaled(theme::FONT_DETAIL))
+                            .text_color(rgb(theme::text_muted(cx)))
+                            .child(path),
+                    ),
+            )
+    }
+
     fn handle_terminal_delete(&self, ws_index: usize, tid: String, cx: &mut Context<Self>) {
         cx.emit(QuickActionEvent::CloseTerminal { tid, ws_index });
     }
@@ -258,7 +374,7 @@ impl Render for QuickActionPanel {
                         div().flex_1().flex().flex_col().child(
                             div()
                                 .text_color(rgb(theme::text_secondary(cx)))
-                                .text_size(px(theme::FONT_BODY))
+                                .text_size(theme::scaled(theme::FONT_BODY))
                                 .text_center()
                                 .font_weight(FontWeight::MEDIUM)
                                 .child("Workspaces"),
@@ -287,6 +403,16 @@ impl Render for QuickActionPanel {
                     ),
             );
 
+        let panel = panel.child(
+            div()
+                .w_full()
+                .px(px(16.0))
+                .py(px(theme::SPACING_SM))
+                .border_b_1()
+                .border_color(rgb(theme::border_subtle(cx)))
+                .child(self.filter_input.clone()),
+        );
+
         let mut content = div()
             .id("quick-action-panel-content")
             .flex_1()
@@ -294,6 +420,8 @@ impl Render for QuickActionPanel {
             .flex_col()
             .overflow_y_scroll();
 
+        let query = self.query.trim().to_string();
+        let mut any_match = false;
         for index in 0..ws_count {
             let workspace_entity = workspaces.get(index).unwrap().clone();
             let state = workspace_entity.read(cx).workspace_state(cx);
@@ -307,6 +435,58 @@ impl Render for QuickActionPanel {
                 (true, true) => String::new(),
             };
 
+            // A matching workspace keeps all of its entries; otherwise only
+            // the entries that match themselves are listed.
+            let workspace_matches =
+                fuzzy_matches(&query, [state.display_name(), subtitle.as_str()]);
+            let terminal_ids: Vec<(usize, &String)> = state
+                .terminal_ids
+                .iter()
+                .enumerate()
+                .filter(|(_, tid)| {
+                    let meta = terminal_state.read(cx).meta(tid);
+                    workspace_matches
+                        || fuzzy_matches(
+                            &query,
+                            [meta.title.as_deref(), meta.cwd.as_deref()]
+                                .into_iter()
+                                .flatten(),
+                        )
+                })
+                .collect();
+            let web_clients: Vec<_> = state
+                .web_clients
+                .iter()
+                .filter(|card| {
+                    workspace_matches
+                        || fuzzy_matches(
+                            &query,
+                            [card.title.as_deref(), Some(card.slug.as_str())]
+                                .into_iter()
+                                .flatten(),
+                        )
+                })
+                .collect();
+            let files: Vec<String> = state
+                .main_view_stack
+                .routes()
+                .iter()
+                .rev()
+                .filter_map(|route| match route {
+                    WorkspaceMainView::File { path } => Some(path.clone()),
+                    _ => None,
+                })
+                .filter(|path| workspace_matches || fuzzy_matches(&query, [path.as_str()]))
+                .collect();
+            if !workspace_matches
+                && terminal_ids.is_empty()
+                && web_clients.is_empty()
+                && files.is_empty()
+            {
+                continue;
+            }
+            any_match = true;
+
             content = content.child(
                 div()
                     .id(SharedString::from(format!("ws-section-{}", index)))
@@ -348,7 +528,7 @@ impl Render for QuickActionPanel {
                                         div()
                                             .flex_1()
                                             .text_color(rgb(theme::text_primary(cx)))
-                                            .text_size(px(theme::FONT_BODY))
+                                            .text_size(theme::scaled(theme::FONT_BODY))
                                             .font_weight(FontWeight::MEDIUM)
                                             .min_w_0()
                                             .truncate()
@@ -358,7 +538,7 @@ impl Render for QuickActionPanel {
                             .child(
                                 div()
                                     .text_color(rgb(theme::text_muted(cx)))
-                                    .text_size(px(theme::FONT_BODY))
+                                    .text_size(theme::scaled(theme::FONT_BODY))
                                     .min_w_0()
                                     .truncate()
                                     .child(subtitle),
@@ -392,9 +572,9 @@ impl Render for QuickActionPanel {
                     ),
             );
 
-            if !state.terminal_ids.is_empty() {
+            if !terminal_ids.is_empty() {
                 content = content.gap_1();
-                for (i, tid) in state.terminal_ids.iter().enumerate() {
+                for (i, tid) in terminal_ids {
                     let tid_click = tid.clone();
                     let tid_del = tid.clone();
                     let is_active = is_active_terminal_card(
@@ -433,9 +613,9 @@ impl Render for QuickActionPanel {
                 }
             }
 
-            if !state.web_clients.is_empty() {
+            if !web_clients.is_empty() {
                 content = content.gap_1();
-                for card_state in &state.web_clients {
+                for card_state in web_clients {
                     let id_open = card_state.id.clone();
                     let id_close = card_state.id.clone();
                     let on_close = Box::new(cx.listener(move |this, _event, _window, cx| {
@@ -469,6 +649,10 @@ impl Render for QuickActionPanel {
                 }
             }
 
+            for path in files {
+                content = content.child(self.render_file_row(index, path, cx));
+            }
+
             content = content.child(
                 div()
                     .h(px(1.0))
@@ -478,13 +662,24 @@ impl Render for QuickActionPanel {
             );
         }
 
+        if ws_count > 0 && !any_match {
+            content = content.child(
+                div()
+                    .px(px(16.0))
+                    .py(px(16.0))
+                    .text_color(rgb(theme::text_muted(cx)))
+                    .text_size(theme::scaled(theme::FONT_BODY))
+                    .child("No matches"),
+            );
+        }
+
         if ws_count == 0 {
             content = content.child(
                 div()
                     .px(px(16.0))
                     .py(px(16.0))
                     .text_color(rgb(theme::text_muted(cx)))
-                    .text_size(px(theme::FONT_BODY))
+                    .text_size(theme::scaled(theme::FONT_BODY))
                     .child("No active workspaces"),
             );
         }
@@ -508,7 +703,17 @@ impl Render for QuickActionPanel {
 
 #[cfg(test)]
 mod tests {
-    use super::is_active_terminal_card;
+    use super::{fuzzy_matches, is_active_terminal_card};
+
+    #[test]
+    fn fuzzy_matches_in_order_ignoring_case_and_spaces() {
+        assert!(fuzzy_matches("", ["anything"]));
+        assert!(fuzzy_matches("wsrs", ["crates/zedra/src/workspace.rs"]));
+        assert!(fuzzy_matches("Cla Code", ["claude-code"]));
+        assert!(fuzzy_matches("build", ["zsh", "~/proj/build"]));
+        assert!(!fuzzy_matches("sw", ["workspace"]));
+        assert!(!fuzzy_matches("x", Vec::<&str>::new()));
+    }
 
     #[test]
     fn terminal_card_active_requires_active_workspace_and_terminal() {
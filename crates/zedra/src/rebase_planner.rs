@@ -0,0 +1,592 @@
+// RebasePlanner — bottom sheet for editing an interactive rebase plan.
+//
+// Presented by `GitPanel` from the branch row's rebase button. Loads the last
+// few commits with `git_rebase_plan`, lets rows be dragged by their handle to
+// reorder and tapped to pick, reword, squash, fixup, or drop, and applies the
+// plan with `git_rebase_execute` after a confirmation. The host aborts a
+// rebase that stops on a conflict, so a failed run leaves the branch as it was.
+
+use futures::channel::oneshot;
+use gpui::{prelude::FluentBuilder as _, *};
+use tracing::*;
+
+use zedra_rpc::proto::{GitRebaseAction, GitRebaseCommit, GitRebaseStep};
+use zedra_session::SessionHandle;
+
+use crate::button::outline_button;
+use crate::platform_bridge::{self, AlertButton, HapticFeedback};
+use crate::theme;
+
+const ROW_HEIGHT: f32 = 44.0;
+const DEFAULT_COUNT: u32 = 10;
+const COUNT_CHOICES: [u32; 3] = [5, 10, 20];
+/// Order of the action sheet buttons.
+const ACTIONS: [GitRebaseAction; 5] = [
+    GitRebaseAction::Pick,
+    GitRebaseAction::Reword,
+    GitRebaseAction::Squash,
+    GitRebaseAction::Fixup,
+    GitRebaseAction::Drop,
+];
+
+#[derive(Clone, Debug)]
+pub enum RebasePlannerEvent {
+    /// The branch was rewritten; the git panel should refresh.
+    Rebased,
+}
+
+impl EventEmitter<RebasePlannerEvent> for RebasePlanner {}
+
+#[derive(Clone, Debug, PartialEq)]
+struct PlanRow {
+    commit: GitRebaseCommit,
+    action: GitRebaseAction,
+    /// Replacement message for a reword.
+    message: Option<String>,
+}
+
+impl PlanRow {
+    fn step(&self) -> GitRebaseStep {
+        GitRebaseStep {
+            action: self.action,
+            hash: self.commit.hash.clone(),
+            message: self.message.clone(),
+        }
+    }
+}
+
+/// A row being moved by its handle.
+struct RowDrag {
+    pointer_id: PointerId,
+    index: usize,
+    /// Pointer y at which the row sits in its current slot.
+    anchor_y: f32,
+}
+
+pub struct RebasePlanner {
+    session_handle: SessionHandle,
+    count: u32,
+    base: String,
+    rows: Vec<PlanRow>,
+    /// Commit order as loaded, to tell whether the plan changes anything.
+    original: Vec<String>,
+    loading: bool,
+    executing: bool,
+    error: Option<String>,
+    drag: Option<RowDrag>,
+}
+
+impl RebasePlanner {
+    pub fn new(session_handle: SessionHandle) -> Self {
+        Self {
+            session_handle,
+            count: DEFAULT_COUNT,
+            base: String::new(),
+            rows: Vec::new(),
+            original: Vec::new(),
+            loading: false,
+            executing: false,
+            error: None,
+            drag: None,
+        }
+    }
+
+    /// Reload the plan for a fresh presentation.
+    pub fn open(&mut self, cx: &mut Context<Self>) {
+        self.executing = false;
+        self.load(self.count, cx);
+    }
+
+    fn load(&mut self, count: u32, cx: &mut Context<Self>) {
+        self.count = count;
+        self.loading = true;
+        self.error = None;
+        self.drag = None;
+        cx.notify();
+
+        let handle = self.session_handle.clone();
+        cx.spawn(async move |this, cx| {
+            let result = handle.git_rebase_plan(count).await;
+            let _ = this.update(cx, |this, cx| {
+                this.loading = false;
+                match result {
+                    Ok(plan) => {
+                        this.base = plan.base;
+                        this.original = plan.commits.iter().map(|c| c.hash.clone()).collect();
+                        this.rows = plan
+                            .commits
+                            .into_iter()
+                            .map(|commit| PlanRow {
+                                commit,
+                                action: GitRebaseAction::Pick,
+                                message: None,
+                            })
+                            .collect();
+                    }
+                    Err(e) => {
+                        error!("git_rebase_plan failed: {}", e);
+                        this.rows.clear();
+                        this.error = Some(e.to_string());
+                    }
+                }
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    fn begin_drag(&mut self, index: usize, event: &PointerDownEvent) {
+        if self.executing {
+            return;
+        }
+        self.drag = Some(RowDrag {
+            pointer_id: event.pointer_id,
+            index,
+            anchor_y: f32::from(event.position.y),
+        });
+    }
+
+    /// Move the dragged row to the slot under the pointer. Returns `true`
+    /// while a drag owns the pointer.
+    fn track_drag(&mut self, event: &PointerMoveEvent) -> bool {
+        let Some(drag) = self.drag.as_mut() else {
+            return false;
+        };
+        if drag.pointer_id != event.pointer_id {
+            return false;
+        }
+        let offset = f32::from(event.position.y) - drag.anchor_y;
+        let target = drag_target(drag.index, offset, self.rows.len());
+        if target != drag.index {
+            let row = self.rows.remove(drag.index);
+            self.rows.insert(target, row);
+            drag.anchor_y += (target as f32 - drag.index as f32) * ROW_HEIGHT;
+            drag.index = target;
+            platform_bridge::trigger_haptic(HapticFeedback::SelectionChanged);
+        }
+        true
+    }
+
+    fn end_drag(&mut self, pointer_id: PointerId) -> bool {
+        let ends = self
+            .drag
+            .as_ref()
+            .is_some_and(|drag| drag.pointer_id == pointer_id);
+        if ends {
+            self.drag = None;
+        }
+        ends
+    }
+
+    fn choose_action(&mut self, index: usize, cx: &mut Context<Self>) {
+        let Some(row) = self.rows.get(index) else {
+            return;
+        };
+        if self.executing {
+            return;
+        }
+        platform_bridge::trigger_haptic(HapticFeedback::ImpactLight);
+        let hash = row.commit.hash.clone();
+        let current_message = row.message.clone().unwrap_or(row.commit.subject.clone());
+        let (tx, rx) = oneshot::channel();
+        let mut buttons: Vec<AlertButton> = ACTIONS
+            .iter()
+            .map(|action| AlertButton::default(action_label(*action)))
+            .collect();
+        buttons.push(AlertButton::cancel("Cancel"));
+        platform_bridge::show_selection(&row.commit.subject, "", buttons, move |index| {
+            let _ = tx.send(index);
+        });
+        cx.spawn(async move |this, cx| {
+            let Ok(Some(choice)) = rx.await else {
+                return;
+            };
+            let Some(action) = ACTIONS.get(choice).copied() else {
+                return;
+            };
+            let message = if action == GitRebaseAction::Reword {
+                let (tx, rx) = oneshot::channel();
+                platform_bridge::show_text_input(
+                    "Reword commit",
+                    "Commit message",
+                    &current_message,
+                    move |message| {
+                        let _ = tx.send(message);
+                    },
+                );
+                match rx.await {
+                    Ok(Some(message)) if !message.trim().is_empty() => Some(message),
+                    _ => return,
+                }
+            } else {
+                None
+            };
+            let _ = this.update(cx, |this, cx| {
+                // The row may have moved while the sheet was up.
+                if let Some(row) = this.rows.iter_mut().find(|row| row.commit.hash == hash) {
+                    row.action = action;
+                    row.message = message;
+                }
+                this.error = None;
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    fn execute(&mut self, cx: &mut Context<Self>) {
+        if self.loading || self.executing || self.is_unchanged() {
+            return;
+        }
+        if let Some(problem) = plan_problem(&self.rows) {
+            self.error = Some(problem.to_string());
+            cx.notify();
+            return;
+        }
+        let handle = self.session_handle.clone();
+        let base = self.base.clone();
+        let steps: Vec<GitRebaseStep> = self.rows.iter().map(PlanRow::step).collect();
+        let (tx, rx) = oneshot::channel();
+        platform_bridge::show_alert(
+            "Rewrite history?",
+            &format!(
+                "This rewrites the last {} commits. Commits that were already pushed will need a force push.",
+                steps.len()
+            ),
+            vec![
+                AlertButton::destructive("Rebase"),
+                AlertButton::cancel("Cancel"),
+            ],
+            move |index| {
+                let _ = tx.send(index == 0);
+            },
+        );
+        cx.spawn(async move |this, cx| {
+            if !rx.await.unwrap_or(false) {
+                return;
+            }
+            let _ = this.update(cx, |this, cx| {
+                this.executing = true;
+                this.error = None;
+                cx.notify();
+            });
+            let result = handle.git_rebase_execute(&base, steps).await;
+            let _ = this.update(cx, |this, cx| {
+                this.executing = false;
+                match result {
+                    Ok(head) => {
+                        info!("rebased onto {}; HEAD is now {}", base, head);
+                        platform_bridge::trigger_haptic(HapticFeedback::NotificationSuccess);
+                        platform_bridge::dismiss_custom_sheet();
+                        cx.emit(RebasePlannerEvent::Rebased);
+                    }
+                    Err(e) => {
+                        error!("git rebase failed: {}", e);
+                        platform_bridge::trigger_haptic(HapticFeedback::NotificationError);
+                        this.error = Some(e.to_string());
+                    }
+                }
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    fn is_unchanged(&self) -> bool {
+        self.rows
+            .iter()
+            .map(|row| &row.commit.hash)
+            .eq(self.original.iter())
+            && self
+                .rows
+                .iter()
+                .all(|row| row.action == GitRebaseAction::Pick)
+    }
+
+    fn render_count_chips(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .flex()
+            .flex_row()
+            .gap(px(theme::SPACING_SM))
+            .px(px(theme::SPACING_MD))
+            .pb(px(theme::SPACING_SM))
+            .children(COUNT_CHOICES.map(|count| {
+                let selected = count == self.count;
+                div()
+                    .id(("rebase-count", count as usize))
+                    .px(px(theme::SPACING_SM))
+                    .py(px(2.0))
+                    .rounded(px(theme::BADGE_RADIUS))
+                    .border_1()
+                    .border_color(rgb(if selected {
+                        theme::border_active(cx)
+                    } else {
+                        theme::border_default(cx)
+                    }))
+                    .text_size(theme::scaled(theme::FONT_DETAIL))
+                    .text_color(rgb(if selected {
+                        theme::text_primary(cx)
+                    } else {
+                        theme::text_muted(cx)
+                    }))
+                    .cursor_pointer()
+                    .on_press(cx.listener(move |this, _event, _window, cx| {
+                        if !this.executing && this.count != count {
+                            this.load(count, cx);
+                        }
+                    }))
+                    .child(format!("Last {count}"))
+            }))
+    }
+
+    fn render_row(&self, index: usize, row: &PlanRow, cx: &mut Context<Self>) -> impl IntoElement {
+        let dragging = self.drag.as_ref().is_some_and(|drag| drag.index == index);
+        let dropped = row.action == GitRebaseAction::Drop;
+        let subject = row
+            .message
+            .as_deref()
+            .and_then(|message| message.lines().next())
+            .unwrap_or(&row.commit.subject)
+            .to_string();
+        let short_hash: String = row.commit.hash.chars().take(7).collect();
+        div()
+            .id(("rebase-row", index))
+            .w_full()
+            .h(px(ROW_HEIGHT))
+            .pr(px(theme::SPACING_MD))
+            .flex()
+            .flex_row()
+            .items_center()
+            .gap(px(theme::SPACING_SM))
+            .when(dragging, |row| row.bg(theme::row_pressed_bg(cx)))
+            .child(
+                div()
+                    .id(("rebase-handle", index))
+                    .w(px(36.0))
+                    .h_full()
+                    .flex()
+                    .items_center()
+                    .justify_center()
+                    .on_pointer_down(cx.listener(
+                        move |this, event: &PointerDownEvent, _window, cx| {
+                            cx.stop_propagation();
+                            this.begin_drag(index, event);
+                            cx.notify();
+                        },
+                    ))
+                    .child(
+                        svg()
+                            .path("icons/menu.svg")
+                            .size(px(theme::ICON_XS))
+                            .text_color(rgb(theme::text_muted(cx))),
+                    ),
+            )
+            .child(
+                div()
+                    .id(("rebase-action", index))
+                    .w(px(52.0))
+                    .flex_none()
+                    .text_size(theme::scaled(theme::FONT_DETAIL))
+                    .font_weight(FontWeight::MEDIUM)
+                    .text_color(rgb(action_color(row.action, cx)))
+                    .cursor_pointer()
+                    .on_press(cx.listener(move |this, _event, _window, cx| {
+                        this.choose_action(index, cx);
+                    }))
+                    .child(action_label(row.action)),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .min_w_0()
+                    .flex()
+                    .flex_col()
+                    .when(dropped, |el| el.opacity(0.45))
+                    .child(
+                        div()
+                            .truncate()
+                            .text_size(theme::scaled(theme::FONT_BODY))
+                            .text_color(rgb(theme::text_primary(cx)))
+                            .child(subject),
+                    )
+                    .child(
+                        div()
+                            .truncate()
+                            .text_size(theme::scaled(theme::FONT_DETAIL))
+                            .text_color(rgb(theme::text_muted(cx)))
+                            .child(format!("{short_hash} · {}", row.commit.author)),
+                    ),
+            )
+    }
+}
+
+impl Render for RebasePlanner {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let rows: Vec<AnyElement> = self
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(index, row)| self.render_row(index, row, cx).into_any_element())
+            .collect();
+        let message = if self.loading {
+            Some("Loading commits…")
+        } else if self.executing {
+            Some("Rebasing…")
+        } else if rows.is_empty() && self.error.is_none() {
+            Some("No commits to rebase")
+        } else {
+            None
+        };
+        let can_execute = !self.loading && !self.executing && !self.is_unchanged();
+        let error = self.error.clone();
+        let bottom_inset = platform_bridge::home_indicator_inset();
+
+        div()
+            .id("rebase-planner")
+            .size_full()
+            .flex()
+            .flex_col()
+            .bg(rgb(theme::bg_primary(cx)))
+            .child(
+                div()
+                    .px(px(theme::SPACING_MD))
+                    .pt(px(theme::SPACING_LG))
+                    .pb(px(theme::SPACING_SM))
+                    .text_size(theme::scaled(theme::FONT_HEADING))
+                    .font_weight(FontWeight::MEDIUM)
+                    .text_color(rgb(theme::text_primary(cx)))
+                    .child("Rebase commits"),
+            )
+            .child(self.render_count_chips(cx))
+            .when_some(error, |el, error| {
+                el.child(
+                    div()
+                        .px(px(theme::SPACING_MD))
+                        .pb(px(theme::SPACING_SM))
+                        .text_size(theme::scaled(theme::FONT_DETAIL))
+                        .text_color(rgb(theme::accent_red(cx)))
+                        .child(error),
+                )
+            })
+            .child(
+                div()
+                    .id("rebase-planner-list")
+                    .flex_1()
+                    .min_h_0()
+                    .overflow_y_scroll()
+                    .on_pointer_move(cx.listener(|this, event: &PointerMoveEvent, window, cx| {
+                        if this.track_drag(event) {
+                            window.prevent_default();
+                            cx.notify();
+                        }
+                    }))
+                    .on_pointer_up(cx.listener(|this, event: &PointerUpEvent, _window, cx| {
+                        if this.end_drag(event.pointer_id) {
+                            cx.notify();
+                        }
+                    }))
+                    .on_pointer_cancel(cx.listener(
+                        |this, event: &PointerCancelEvent, _window, cx| {
+                            if this.end_drag(event.pointer_id) {
+                                cx.notify();
+                            }
+                        },
+                    ))
+                    .when_some(message, |el, message| {
+                        el.child(
+                            div()
+                                .px(px(theme::SPACING_MD))
+                                .py(px(theme::SPACING_SM))
+                                .text_size(theme::scaled(theme::FONT_DETAIL))
+                                .text_color(rgb(theme::text_muted(cx)))
+                                .child(message),
+                        )
+                    })
+                    .children(rows),
+            )
+            .child(
+                div()
+                    .px(px(theme::SPACING_MD))
+                    .pt(px(theme::SPACING_SM))
+                    .pb(px(theme::SPACING_SM + bottom_inset))
+                    .child(
+                        outline_button(cx, "rebase-execute", "Rebase")
+                            .when(!can_execute, |button| button.opacity(0.35))
+                            .on_press(cx.listener(|this, _event, _window, cx| {
+                                this.execute(cx);
+                            })),
+                    ),
+            )
+    }
+}
+
+fn action_label(action: GitRebaseAction) -> &'static str {
+    match action {
+        GitRebaseAction::Pick => "pick",
+        GitRebaseAction::Reword => "reword",
+        GitRebaseAction::Squash => "squash",
+        GitRebaseAction::Fixup => "fixup",
+        GitRebaseAction::Drop => "drop",
+    }
+}
+
+fn action_color(action: GitRebaseAction, cx: &App) -> u32 {
+    match action {
+        GitRebaseAction::Pick => theme::text_secondary(cx),
+        GitRebaseAction::Reword => theme::accent_blue(cx),
+        GitRebaseAction::Squash | GitRebaseAction::Fixup => theme::accent_yellow(cx),
+        GitRebaseAction::Drop => theme::accent_red(cx),
+    }
+}
+
+/// Slot a row dragged `offset` pixels from slot `index` lands in.
+fn drag_target(index: usize, offset: f32, len: usize) -> usize {
+    let target = index as f32 + (offset / ROW_HEIGHT).round();
+    target.clamp(0.0, len.saturating_sub(1) as f32) as usize
+}
+
+/// Why the host would refuse the plan, checked before asking to confirm.
+fn plan_problem(rows: &[PlanRow]) -> Option<&'static str> {
+    let first_kept = rows
+        .iter()
+        .find(|row| row.action != GitRebaseAction::Drop)?;
+    matches!(
+        first_kept.action,
+        GitRebaseAction::Squash | GitRebaseAction::Fixup
+    )
+    .then_some("The first kept commit can't be squashed or fixed up. Move it down or pick it.")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(subject: &str, action: GitRebaseAction) -> PlanRow {
+        PlanRow {
+            commit: GitRebaseCommit {
+                hash: subject.repeat(40),
+                subject: subject.to_string(),
+                ..Default::default()
+            },
+            action,
+            message: None,
+        }
+    }
+
+    #[test]
+    fn drag_target_rounds_to_the_nearest_slot_within_bounds() {
+        assert_eq!(drag_target(2, 0.0, 5), 2);
+        assert_eq!(drag_target(2, ROW_HEIGHT * 0.6, 5), 3);
+        assert_eq!(drag_target(2, -ROW_HEIGHT * 0.4, 5), 2);
+        assert_eq!(drag_target(2, -ROW_HEIGHT * 5.0, 5), 0);
+        assert_eq!(drag_target(2, ROW_HEIGHT * 9.0, 5), 4);
+    }
+
+    #[test]
+    fn squash_into_nothing_is_a_plan_problem() {
+        use GitRebaseAction::*;
+        assert_eq!(plan_problem(&[row("a", Pick), row("b", Squash)]), None);
+        assert!(plan_problem(&[row("a", Drop), row("b", Fixup)]).is_some());
+        assert_eq!(plan_problem(&[row("a", Drop), row("b", Drop)]), None);
+    }
+}
@@ -0,0 +1,613 @@
+// Replay of host terminal recordings (`zedra start --record`).
+//
+// The player fetches the whole asciicast file through `RecordingRead`, keeps
+// its output events, and feeds them into a local `TerminalView` at the
+// recorded pace scaled by the chosen speed. Long idle gaps are shortened to
+// `MAX_IDLE`. Seeking resets the terminal and replays every event up to the
+// target in one write, so the screen matches what the host showed at that
+// moment. Nothing is sent to the host terminal; replies the emulator produces
+// for replayed queries are dropped.
+
+use std::time::Duration;
+
+use gpui::*;
+use serde::Deserialize;
+use tokio::sync::mpsc;
+use zedra_session::SessionHandle;
+use zedra_terminal::view::TerminalView;
+
+use crate::platform_bridge::{self, HapticFeedback};
+use crate::settings::{ThemeStateEvent, theme_state as theme_entity};
+use crate::ui::{chevron_back_button, subscreen_empty_text};
+use crate::{fonts, theme, workspace_action};
+
+/// Playback speeds, cycled by the speed button.
+pub const SPEEDS: [f64; 5] = [0.5, 1.0, 2.0, 4.0, 8.0];
+/// Longest wait between two events, in wall-clock time.
+const MAX_IDLE: Duration = Duration::from_secs(2);
+/// Events this close to the next one are written together.
+const FRAME_SECS: f64 = 1.0 / 60.0;
+/// Full reset (RIS): clears the screen, scrollback and modes before a seek.
+const RESET: &[u8] = b"\x1bc";
+
+#[derive(Debug, Deserialize)]
+struct CastHeader {
+    version: u8,
+    width: u16,
+    height: u16,
+}
+
+/// Output written `at` seconds after the recording started.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CastEvent {
+    pub at: f64,
+    pub data: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cast {
+    pub width: u16,
+    pub height: u16,
+    pub events: Vec<CastEvent>,
+}
+
+impl Cast {
+    pub fn duration(&self) -> f64 {
+        self.events.last().map_or(0.0, |event| event.at)
+    }
+
+    /// Number of events at or before `at`.
+    pub fn events_until(&self, at: f64) -> usize {
+        self.events.partition_point(|event| event.at <= at)
+    }
+}
+
+/// Parse an asciicast v2 file, keeping `"o"` events. Lines that do not
+/// parse are skipped, so a recording still being written plays up to its
+/// last complete event.
+pub fn parse_cast(data: &[u8]) -> Result<Cast, String> {
+    let text = String::from_utf8_lossy(data);
+    let mut lines = text.lines();
+    let header: CastHeader = lines
+        .next()
+        .and_then(|line| serde_json::from_str(line).ok())
+        .ok_or_else(|| "Not an asciicast recording".to_string())?;
+    if header.version != 2 {
+        return Err(format!("Unsupported asciicast version {}", header.version));
+    }
+    let mut events: Vec<CastEvent> = lines
+        .filter_map(|line| serde_json::from_str::<(f64, String, String)>(line).ok())
+        .filter(|(_, kind, _)| kind == "o")
+        .map(|(at, _, data)| CastEvent { at, data })
+        .collect();
+    events.sort_by(|a, b| a.at.total_cmp(&b.at));
+    Ok(Cast {
+        width: header.width,
+        height: header.height,
+        events,
+    })
+}
+
+fn format_clock(secs: f64) -> String {
+    let secs = secs.max(0.0) as u64;
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
+#[derive(Clone, Debug)]
+enum PlayerState {
+    Loading,
+    Ready,
+    Error(String),
+}
+
+/// Recording replay screen: a read-only terminal, play/pause, a speed
+/// button and a scrubber.
+pub struct RecordingPlayer {
+    session_handle: SessionHandle,
+    name: String,
+    cast: Option<Cast>,
+    state: PlayerState,
+    terminal_view: Entity<TerminalView>,
+    output_tx: mpsc::Sender<Vec<u8>>,
+    /// Playback time in recording seconds.
+    position: f64,
+    /// Index of the first event not yet written.
+    next_event: usize,
+    playing: bool,
+    speed_ix: usize,
+    scrubber_bounds: Bounds<Pixels>,
+    load_epoch: u64,
+    _load_task: Option<Task<()>>,
+    _playback_task: Option<Task<()>>,
+    _input_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl RecordingPlayer {
+    pub fn new(
+        session_handle: SessionHandle,
+        window: &mut Window,
+        viewport: Size<Pixels>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let terminal_view =
+            cx.new(|cx| TerminalView::new("recording".to_string(), window, viewport, cx));
+        let (input_tx, mut input_rx) = mpsc::channel::<Vec<u8>>(16);
+        let (output_tx, output_rx) = mpsc::channel(1024);
+        terminal_view.update(cx, |terminal_view, cx| {
+            terminal_view.set_terminal_theme(theme::bundle(cx).terminal, cx);
+            terminal_view.set_font_family(crate::settings::code_font(cx).family_name(), cx);
+            terminal_view
+                .set_font_size(zedra_terminal::TERMINAL_FONT_SIZE * theme::text_scale(), cx);
+            terminal_view.attach_channel(input_tx, output_rx, cx);
+        });
+        let input_task = cx
+            .background_executor()
+            .spawn(async move { while input_rx.recv().await.is_some() {} });
+
+        let mut subscriptions = Vec::new();
+        if let Some(theme_state) = theme_entity(cx) {
+            subscriptions.push(
+                cx.subscribe(&theme_state, |this, _, _: &ThemeStateEvent, cx| {
+                    let terminal_theme = theme::bundle(cx).terminal;
+                    this.terminal_view.update(cx, |terminal_view, cx| {
+                        terminal_view.set_terminal_theme(terminal_theme, cx);
+                    });
+                }),
+            );
+        }
+        subscriptions.push(
+            cx.observe_global::<crate::settings::CodeFontSetting>(|this, cx| {
+                let family = crate::settings::code_font(cx).family_name();
+                this.terminal_view.update(cx, |terminal_view, cx| {
+                    terminal_view.set_font_family(family, cx);
+                });
+            }),
+        );
+
+        Self {
+            session_handle,
+            name: String::new(),
+            cast: None,
+            state: PlayerState::Loading,
+            terminal_view,
+            output_tx,
+            position: 0.0,
+            next_event: 0,
+            playing: false,
+            speed_ix: 1,
+            scrubber_bounds: Bounds::default(),
+            load_epoch: 0,
+            _load_task: None,
+            _playback_task: None,
+            _input_task: input_task,
+            _subscriptions: subscriptions,
+        }
+    }
+
+    /// Show recording `name` from the start, reloading unless it is already
+    /// shown.
+    pub fn open(&mut self, name: String, cx: &mut Context<Self>) {
+        if name == self.name && matches!(self.state, PlayerState::Ready) {
+            return;
+        }
+        self.name = name;
+        self.cast = None;
+        self.pause(cx);
+        self.load(cx);
+    }
+
+    fn load(&mut self, cx: &mut Context<Self>) {
+        self.load_epoch = self.load_epoch.wrapping_add(1);
+        let epoch = self.load_epoch;
+        self.state = PlayerState::Loading;
+        self.write(RESET.to_vec());
+        cx.notify();
+
+        let handle = self.session_handle.clone();
+        let name = self.name.clone();
+        self._load_task = Some(cx.spawn(async move |this, cx| {
+            let result = match handle.recording_read(&name).await {
+                Ok(data) => {
+                    cx.background_executor()
+                        .spawn(async move { parse_cast(&data) })
+                        .await
+                }
+                Err(e) => Err(e.to_string()),
+            };
+            let _ = this.update(cx, |this, cx| {
+                if this.load_epoch != epoch {
+                    return;
+                }
+                match result {
+                    Ok(cast) => {
+                        this.cast = Some(cast);
+                        this.state = PlayerState::Ready;
+                        this.seek(0.0, cx);
+                        this.play(cx);
+                    }
+                    Err(e) => {
+                        tracing::warn!("recording player: load failed for {}: {}", name, e);
+                        this.state = PlayerState::Error(e);
+                        cx.notify();
+                    }
+                }
+            });
+        }));
+    }
+
+    fn speed(&self) -> f64 {
+        SPEEDS[self.speed_ix]
+    }
+
+    fn duration(&self) -> f64 {
+        self.cast.as_ref().map_or(0.0, Cast::duration)
+    }
+
+    fn write(&self, bytes: Vec<u8>) {
+        if let Err(e) = self.output_tx.try_send(bytes) {
+            tracing::warn!("recording player: dropped output: {}", e);
+        }
+    }
+
+    /// Wall-clock wait before the next event, or `None` at the end.
+    fn next_delay(&self) -> Option<Duration> {
+        let event = self.cast.as_ref()?.events.get(self.next_event)?;
+        let wait = ((event.at - self.position) / self.speed()).max(0.0);
+        Some(Duration::from_secs_f64(wait).min(MAX_IDLE))
+    }
+
+    /// Write the next event and any that follow within a frame.
+    fn advance(&mut self, cx: &mut Context<Self>) {
+        let Some(cast) = self.cast.as_ref() else {
+            return;
+        };
+        let Some(first) = cast.events.get(self.next_event) else {
+            return;
+        };
+        let end = cast.events_until(first.at + FRAME_SECS);
+        let mut bytes = Vec::new();
+        for event in &cast.events[self.next_event..end] {
+            bytes.extend_from_slice(event.data.as_bytes());
+        }
+        self.position = cast.events[end - 1].at;
+        self.next_event = end;
+        self.write(bytes);
+        cx.notify();
+    }
+
+    /// Play from the current position, or from the start once finished.
+    fn play(&mut self, cx: &mut Context<Self>) {
+        let Some(cast) = self.cast.as_ref() else {
+            return;
+        };
+        if self.next_event >= cast.events.len() {
+            self.seek(0.0, cx);
+        }
+        self.start_playback(cx);
+    }
+
+    fn start_playback(&mut self, cx: &mut Context<Self>) {
+        self.playing = true;
+        self._playback_task = Some(cx.spawn(async move |this, cx| {
+            loop {
+                let Ok(Some(delay)) = this.update(cx, |this, _cx| this.next_delay()) else {
+                    break;
+                };
+                cx.background_executor().timer(delay).await;
+                if this.update(cx, |this, cx| this.advance(cx)).is_err() {
+                    return;
+                }
+            }
+            let _ = this.update(cx, |this, cx| {
+                this.playing = false;
+                cx.notify();
+            });
+        }));
+        cx.notify();
+    }
+
+    fn pause(&mut self, cx: &mut Context<Self>) {
+        self.playing = false;
+        self._playback_task = None;
+        cx.notify();
+    }
+
+    fn toggle_playing(&mut self, cx: &mut Context<Self>) {
+        platform_bridge::trigger_haptic(HapticFeedback::ImpactLight);
+        if self.playing {
+            self.pause(cx);
+        } else {
+            self.play(cx);
+        }
+    }
+
+    fn cycle_speed(&mut self, cx: &mut Context<Self>) {
+        platform_bridge::trigger_haptic(HapticFeedback::ImpactLight);
+        self.speed_ix = (self.speed_ix + 1) % SPEEDS.len();
+        if self.playing {
+            // Restart so the pending wait uses the new speed.
+            self.start_playback(cx);
+        }
+        cx.notify();
+    }
+
+    /// Redraw the screen as it was `at` seconds into the recording.
+    fn seek(&mut self, at: f64, cx: &mut Context<Self>) {
+        let Some(cast) = self.cast.as_ref() else {
+            return;
+        };
+        let at = at.clamp(0.0, cast.duration());
+        let end = cast.events_until(at);
+        let mut bytes = RESET.to_vec();
+        for event in &cast.events[..end] {
+            bytes.extend_from_slice(event.data.as_bytes());
+        }
+        self.position = at;
+        self.next_event = end;
+        self.write(bytes);
+        if self.playing {
+            self.start_playback(cx);
+        }
+        cx.notify();
+    }
+
+    fn seek_to_x(&mut self, x: Pixels, cx: &mut Context<Self>) {
+        let width = f32::from(self.scrubber_bounds.size.width);
+        if width <= 0.0 {
+            return;
+        }
+        let fraction = (f32::from(x - self.scrubber_bounds.origin.x) / width).clamp(0.0, 1.0);
+        self.seek(self.duration() * fraction as f64, cx);
+    }
+
+    fn subtitle(&self) -> String {
+        match (&self.state, &self.cast) {
+            (PlayerState::Ready, Some(cast)) => {
+                format!("{}×{} · {}", cast.width, cast.height, self.name)
+            }
+            (PlayerState::Error(_), _) => "Load failed".to_string(),
+            _ => self.name.clone(),
+        }
+    }
+
+    fn render_controls(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let duration = self.duration();
+        let fraction = if duration > 0.0 {
+            (self.position / duration).clamp(0.0, 1.0) as f32
+        } else {
+            0.0
+        };
+        let icon = if self.playing {
+            "icons/pause.svg"
+        } else {
+            "icons/play.svg"
+        };
+        let player = cx.entity().downgrade();
+        let measure = canvas(
+            |bounds, _, _| bounds,
+            move |_bounds, measured_bounds, _window, cx| {
+                cx.defer(move |cx| {
+                    let _ = player.update(cx, |this, _cx| {
+                        this.scrubber_bounds = measured_bounds;
+                    });
+                });
+            },
+        )
+        .absolute()
+        .inset_0();
+
+        div()
+            .flex_none()
+            .flex()
+            .flex_row()
+            .items_center()
+            .gap(px(theme::SPACING_MD))
+            .px(px(theme::SUBSCREEN_PADDING_X))
+            .py(px(theme::SPACING_SM))
+            .border_t_1()
+            .border_color(rgb(theme::border_subtle(cx)))
+            .child(
+                div()
+                    .id("recording-play-btn")
+                    .flex_none()
+                    .p(px(theme::SPACING_XS))
+                    .cursor_pointer()
+                    .hit_slop(px(6.0))
+                    .on_press(cx.listener(|this, _event, _window, cx| this.toggle_playing(cx)))
+                    .child(
+                        svg()
+                            .path(icon)
+                            .size(px(theme::ICON_MD))
+                            .text_color(rgb(theme::text_primary(cx))),
+                    ),
+            )
+            .child(
+                div()
+                    .flex_none()
+                    .text_size(theme::scaled(theme::FONT_DETAIL))
+                    .font_family(fonts::MONO_FONT_FAMILY)
+                    .text_color(rgb(theme::text_muted(cx)))
+                    .child(format!(
+                        "{} / {}",
+                        format_clock(self.position),
+                        format_clock(duration)
+                    )),
+            )
+            .child(
+                div()
+                    .id("recording-scrubber")
+                    .flex_1()
+                    .min_w_0()
+                    .h(px(24.0))
+                    .relative()
+                    .flex()
+                    .items_center()
+                    .cursor_pointer()
+                    .on_press(cx.listener(|this, event: &PressEvent, _window, cx| {
+                        this.seek_to_x(event.position().x, cx);
+                    }))
+                    .child(
+                        div()
+                            .w_full()
+                            .h(px(4.0))
+                            .rounded(px(2.0))
+                            .bg(rgb(theme::border_default(cx)))
+                            .child(
+                                div()
+                                    .h_full()
+                                    .w(relative(fraction))
+                                    .rounded(px(2.0))
+                                    .bg(rgb(theme::accent_blue(cx))),
+                            ),
+                    )
+                    .child(measure),
+            )
+            .child(
+                div()
+                    .id("recording-speed-btn")
+                    .flex_none()
+                    .min_w(px(40.0))
+                    .px(px(theme::SPACING_SM))
+                    .py(px(theme::SPACING_XS))
+                    .rounded(px(6.0))
+                    .border_1()
+                    .border_color(rgb(theme::border_default(cx)))
+                    .cursor_pointer()
+                    .flex()
+                    .justify_center()
+                    .text_size(theme::scaled(theme::FONT_DETAIL))
+                    .font_family(fonts::MONO_FONT_FAMILY)
+                    .text_color(rgb(theme::text_primary(cx)))
+                    .on_press(cx.listener(|this, _event, _window, cx| this.cycle_speed(cx)))
+                    .child(format!("{}×", self.speed())),
+            )
+    }
+}
+
+impl Render for RecordingPlayer {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let body = match &self.state {
+            PlayerState::Loading => div()
+                .flex_1()
+                .px(px(theme::SUBSCREEN_PADDING_X))
+                .child(subscreen_empty_text("Loading recording…", cx))
+                .into_any_element(),
+            PlayerState::Error(message) => div()
+                .flex_1()
+                .px(px(theme::SUBSCREEN_PADDING_X))
+                .child(subscreen_empty_text(message.clone(), cx))
+                .into_any_element(),
+            PlayerState::Ready => div()
+                .id("recording-terminal")
+                .flex_1()
+                .min_h_0()
+                .relative()
+                .child(self.terminal_view.clone())
+                // Taps toggle playback instead of focusing the terminal.
+                .child(
+                    div()
+                        .id("recording-terminal-shield")
+                        .absolute()
+                        .inset_0()
+                        .occlude()
+                        .on_press(cx.listener(|this, _event, _window, cx| this.toggle_playing(cx))),
+                )
+                .into_any_element(),
+        };
+        let controls = matches!(self.state, PlayerState::Ready)
+            .then(|| self.render_controls(cx).into_any_element());
+
+        div()
+            .id("recording-player")
+            .size_full()
+            .min_h_0()
+            .bg(rgb(theme::bg_primary(cx)))
+            .flex()
+            .flex_col()
+            .child(render_player_header(self.subtitle(), cx))
+            .child(body)
+            .children(controls)
+    }
+}
+
+fn render_player_header(subtitle: String, cx: &mut Context<RecordingPlayer>) -> impl IntoElement {
+    div()
+        .id("recording-player-header")
+        .flex_none()
+        .min_w_0()
+        .px(px(theme::SUBSCREEN_PADDING_X))
+        .pt(px(theme::SPACING_XS))
+        .pb(px(theme::SPACING_SM))
+        .flex()
+        .flex_row()
+        .items_center()
+        .gap(px(theme::SPACING_MD))
+        .child(chevron_back_button(
+            "recording-player-back-btn",
+            cx,
+            |_this, _event, window, cx| {
+                platform_bridge::trigger_haptic(HapticFeedback::ImpactLight);
+                window.dispatch_action(workspace_action::NavigateBack.boxed_clone(), cx);
+            },
+        ))
+        .child(
+            div()
+                .flex_1()
+                .min_w_0()
+                .flex()
+                .flex_col()
+                .child(
+                    div()
+                        .text_size(theme::scaled(theme::FONT_HEADING))
+                        .font_family(fonts::HEADING_FONT_FAMILY)
+                        .font_weight(FontWeight::MEDIUM)
+                        .text_color(rgb(theme::text_primary(cx)))
+                        .child("Recording"),
+                )
+                .child(
+                    div()
+                        .truncate()
+                        .text_size(theme::scaled(theme::FONT_BODY))
+                        .text_color(rgb(theme::text_muted(cx)))
+                        .child(subtitle),
+                ),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CAST: &str = concat!(
+        "{\"version\":2,\"width\":80,\"height\":24,\"timestamp\":1,\"title\":\"t\"}\n",
+        "[0.5,\"o\",\"hello \"]\n",
+        "[0.1,\"o\",\"$ \"]\n",
+        "[0.7,\"i\",\"x\"]\n",
+        "[1.25,\"o\",\"world\\r\\n\"]\n",
+        "[1.3,\"o\",\"trunc",
+    );
+
+    #[test]
+    fn parse_cast_keeps_output_events_in_order() {
+        let cast = parse_cast(CAST.as_bytes()).unwrap();
+        assert_eq!((cast.width, cast.height), (80, 24));
+        let data: Vec<&str> = cast.events.iter().map(|e| e.data.as_str()).collect();
+        assert_eq!(data, vec!["$ ", "hello ", "world\r\n"]);
+        assert_eq!(cast.duration(), 1.25);
+        assert_eq!(cast.events_until(0.5), 2);
+        assert_eq!(cast.events_until(0.0), 0);
+    }
+
+    #[test]
+    fn parse_cast_rejects_other_files() {
+        assert!(parse_cast(b"not json\n").is_err());
+        assert!(parse_cast(b"{\"version\":1,\"width\":80,\"height\":24}\n").is_err());
+    }
+
+    #[test]
+    fn format_clock_shows_minutes_and_seconds() {
+        assert_eq!(format_clock(0.0), "0:00");
+        assert_eq!(format_clock(75.9), "1:15");
+    }
+}
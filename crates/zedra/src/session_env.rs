@@ -0,0 +1,268 @@
+// Session environment: per-host overrides for new terminals and checks.
+//
+// Overrides live on `WorkspaceState::session_env`, so they persist with the
+// saved workspace for a host. The workspace sends the list with
+// `SessionEnvSet` after each connect and whenever the editor changes it; the
+// host applies it when the session starts a terminal or a diagnostics check.
+// Keychain overrides name a service in the host keychain, so the secret
+// itself never reaches the device.
+//
+// The editor is a chain of native dialogs like the terminal trigger editor.
+// Each callback hands the next step back to the workspace through
+// `PendingWorkspaceAction::EditSessionEnv` so every dialog is presented from
+// the GPUI thread.
+
+use gpui::*;
+use tracing::warn;
+use zedra_rpc::proto::{SessionEnvKind, SessionEnvValue, SessionEnvVar};
+use zedra_session::SessionHandle;
+
+use crate::pending::SharedPendingSlot;
+use crate::platform_bridge::{self, AlertButton, ListPickerItem};
+use crate::workspace::PendingWorkspaceAction;
+use crate::workspace_state::WorkspaceState;
+
+/// Why the host would reject `name`, checked before the value is asked for.
+fn name_error(name: &str) -> Option<&'static str> {
+    let valid = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+        Some("Use letters, digits and underscores, not starting with a digit.")
+    } else if matches!(name, "TERM" | "COLORTERM" | "SHELL") || name.starts_with("ZEDRA_") {
+        Some("Zedra sets this variable for every terminal.")
+    } else {
+        None
+    }
+}
+
+/// Row subtitle in the override list. Keychain rows show the service, never a value.
+fn summary(var: &SessionEnvVar, value: Option<&SessionEnvValue>) -> String {
+    let summary = match var.kind {
+        SessionEnvKind::Set => format!("= {}", var.value),
+        SessionEnvKind::Prepend => format!("Prepend {}", var.value),
+        SessionEnvKind::Keychain => format!("Keychain: {}", var.value),
+    };
+    match value.and_then(|value| value.error.as_deref()) {
+        Some(error) => format!("{summary} · {error}"),
+        None => summary,
+    }
+}
+
+/// Send the host's overrides, alerting when it rejects them.
+pub fn push(session_handle: &SessionHandle, vars: Vec<SessionEnvVar>, cx: &mut App) {
+    let session_handle = session_handle.clone();
+    cx.spawn(async move |_cx| {
+        if let Err(error) = session_handle.session_env_set(vars).await {
+            warn!("session env: update failed: {}", error);
+            platform_bridge::show_alert(
+                "Environment Not Applied",
+                &error.to_string(),
+                vec![AlertButton::cancel("OK")],
+                |_| {},
+            );
+        }
+    })
+    .detach();
+}
+
+pub(crate) enum EnvEditStep {
+    List,
+    ShowList { values: Vec<SessionEnvValue> },
+    AddName,
+    ChooseKind { name: String },
+    Value { name: String, kind: SessionEnvKind },
+    Save(SessionEnvVar),
+    ConfirmRemove { index: usize },
+    Remove { index: usize },
+}
+
+/// Present one step of the environment editor.
+pub(crate) fn run_edit_step(
+    step: EnvEditStep,
+    workspace_state: &Entity<WorkspaceState>,
+    session_handle: &SessionHandle,
+    pending: &SharedPendingSlot<PendingWorkspaceAction>,
+    cx: &mut App,
+) {
+    let pending = pending.clone();
+    let next = move |step: EnvEditStep| pending.set(PendingWorkspaceAction::EditSessionEnv(step));
+    match step {
+        EnvEditStep::List => {
+            // Resolve on the host first so rows can flag missing keychain items.
+            let session_handle = session_handle.clone();
+            cx.spawn(async move |_cx| {
+                let values = session_handle
+                    .session_env_get()
+                    .await
+                    .map_err(|error| warn!("session env: lookup failed: {}", error))
+                    .unwrap_or_default();
+                next(EnvEditStep::ShowList { values });
+            })
+            .detach();
+        }
+        EnvEditStep::ShowList { values } => {
+            let vars = workspace_state.read(cx).session_env.clone();
+            let count = vars.len();
+            let mut items: Vec<ListPickerItem> = vars
+                .iter()
+                .map(|var| ListPickerItem {
+                    label: var.name.clone(),
+                    subtitle: Some(summary(
+                        var,
+                        values.iter().find(|value| value.name == var.name),
+                    )),
+                    image_name: None,
+                    trailing_icon: None,
+                })
+                .collect();
+            items.push(ListPickerItem {
+                label: "Add Variable".into(),
+                subtitle: None,
+                image_name: None,
+                trailing_icon: None,
+            });
+            platform_bridge::show_list_picker(
+                "Environment",
+                "Set for new terminals and checks on this host. Tap a variable to remove it.",
+                items,
+                move |selection| {
+                    let Some(selection) = selection else { return };
+                    if selection.index == count {
+                        next(EnvEditStep::AddName);
+                    } else {
+                        next(EnvEditStep::ConfirmRemove {
+                            index: selection.index,
+                        });
+                    }
+                },
+            );
+        }
+        EnvEditStep::AddName => {
+            platform_bridge::show_text_input(
+                "New Variable",
+                "Name, e.g. RUST_LOG",
+                "",
+                move |result| {
+                    let Some(name) = result.map(|name| name.trim().to_string()) else {
+                        return;
+                    };
+                    if name.is_empty() {
+                        return;
+                    }
+                    if let Some(error) = name_error(&name) {
+                        platform_bridge::show_alert(
+                            "Invalid name",
+                            error,
+                            vec![AlertButton::cancel("OK")],
+                            |_| {},
+                        );
+                        return;
+                    }
+                    next(EnvEditStep::ChooseKind { name });
+                },
+            );
+        }
+        EnvEditStep::ChooseKind { name } => {
+            let buttons = vec![
+                AlertButton::default("Set Value"),
+                AlertButton::default("Prepend to Existing"),
+                AlertButton::default("From Host Keychain"),
+                AlertButton::cancel("Cancel"),
+            ];
+            let message = name.clone();
+            platform_bridge::show_selection("Variable", &message, buttons, move |selection| {
+                let kind = match selection {
+                    Some(0) => SessionEnvKind::Set,
+                    Some(1) => SessionEnvKind::Prepend,
+                    Some(2) => SessionEnvKind::Keychain,
+                    _ => return,
+                };
+                next(EnvEditStep::Value { name, kind });
+            });
+        }
+        EnvEditStep::Value { name, kind } => {
+            let placeholder = match kind {
+                SessionEnvKind::Set => "Value",
+                SessionEnvKind::Prepend => "Entry, e.g. /opt/tools/bin",
+                SessionEnvKind::Keychain => "Keychain service name",
+            };
+            let title = name.clone();
+            platform_bridge::show_text_input(&title, placeholder, "", move |result| {
+                let Some(value) = result else { return };
+                if kind != SessionEnvKind::Set && value.trim().is_empty() {
+                    return;
+                }
+                next(EnvEditStep::Save(SessionEnvVar { name, value, kind }));
+            });
+        }
+        EnvEditStep::Save(var) => {
+            let vars = workspace_state.update(cx, |state, cx| {
+                state.set_session_env_var(var, cx);
+                state.session_env.clone()
+            });
+            push(session_handle, vars, cx);
+        }
+        EnvEditStep::ConfirmRemove { index } => {
+            let Some(var) = workspace_state.read(cx).session_env.get(index).cloned() else {
+                return;
+            };
+            platform_bridge::show_alert(
+                "Remove variable?",
+                &var.name,
+                vec![
+                    AlertButton::destructive("Remove"),
+                    AlertButton::cancel("Cancel"),
+                ],
+                move |button_index| {
+                    if button_index == 0 {
+                        next(EnvEditStep::Remove { index });
+                    }
+                },
+            );
+        }
+        EnvEditStep::Remove { index } => {
+            let vars = workspace_state.update(cx, |state, cx| {
+                state.remove_session_env_var(index, cx);
+                state.session_env.clone()
+            });
+            push(session_handle, vars, cx);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_are_checked_like_the_host_does() {
+        assert_eq!(name_error("RUST_LOG"), None);
+        assert_eq!(name_error("_private1"), None);
+        assert!(name_error("1PASSWORD").is_some());
+        assert!(name_error("MY-VAR").is_some());
+        assert!(name_error("TERM").is_some());
+        assert!(name_error("ZEDRA_WORKDIR").is_some());
+    }
+
+    #[test]
+    fn keychain_summary_shows_the_service_and_lookup_error() {
+        let var = SessionEnvVar {
+            name: "OPENAI_API_KEY".into(),
+            value: "openai".into(),
+            kind: SessionEnvKind::Keychain,
+        };
+        assert_eq!(summary(&var, None), "Keychain: openai");
+        let value = SessionEnvValue {
+            name: var.name.clone(),
+            value: None,
+            error: Some("no keychain item".into()),
+        };
+        assert_eq!(
+            summary(&var, Some(&value)),
+            "Keychain: openai · no keychain item"
+        );
+    }
+}
@@ -1,20 +1,42 @@
 /// Session info panel for the workspace drawer.
 ///
-/// Displays host info, connection details, endpoints, and disconnect button.
+/// Displays host info, this device's key, connection details, endpoints,
+/// other projects on the host, imported SSH hosts, terminal recordings, power
+/// actions, and disconnect button.
 use futures::channel::oneshot;
 use gpui::*;
 
-use crate::platform_bridge::{self, AlertButton, HapticFeedback};
-use crate::transport_badge::{render_transport_badge, transport_badge};
-use crate::workspace_state::{TrackedTunnel, WorkspaceState};
-use crate::{fonts, theme, web_tunnel, workspace_action};
-use zedra_rpc::proto::{HostBatteryInfo, HostInfoSnapshot};
+use crate::platform_bridge::{
+    self, AlertButton, HapticFeedback, NativeNotificationKind, NativeNotificationOptions,
+};
+use crate::transport_badge::{format_bytes, render_transport_badge, transport_badge};
+use crate::ui::{PullScroll, PullToRefresh, PullToRefreshEvent, pull_to_refresh};
+use crate::workspace_state::{TrackedTunnel, WorkspaceState, WorkspaceStateEvent};
+use crate::{fonts, host_power, theme, web_tunnel, workspace_action};
+use zedra_rpc::proto::{
+    AiProviderInfo, HostBatteryInfo, HostInfoSnapshot, HostProject, RecordingEntry, SshHost,
+};
+use zedra_session::signer::ClientSigner as _;
 use zedra_session::{SessionHandle, SessionState};
 
+/// Newest recordings listed in the panel.
+const RECORDINGS_SHOWN: usize = 10;
+
 pub struct SessionPanel {
     workspace_state: Entity<WorkspaceState>,
     session_state: Entity<SessionState>,
     session_handle: SessionHandle,
+    /// Bookmarked and running projects on the host, refreshed on each sync.
+    projects: Vec<HostProject>,
+    /// Hosts from `zedra import-ssh-config`, refreshed with the projects.
+    ssh_hosts: Vec<SshHost>,
+    /// Terminal recordings on the host (`zedra start --record`), newest first.
+    recordings: Vec<RecordingEntry>,
+    /// The host accepts sleep and reboot (`zedra start --allow-power`).
+    power_actions: bool,
+    /// This device's public key in hex, for `zedra start --admin-client`.
+    device_key: Option<String>,
+    pull_refresh: Entity<PullToRefresh>,
     _subscriptions: Vec<Subscription>,
 }
 
@@ -26,13 +48,106 @@ impl SessionPanel {
         cx: &mut Context<Self>,
     ) -> Self {
         let workspace_state_sub = cx.observe(&workspace_state, |_, _, cx| cx.notify());
+        let sync_sub = cx.subscribe(&workspace_state, |this, _ws, event, cx| {
+            if matches!(event, WorkspaceStateEvent::SyncComplete) {
+                this.refresh_projects(cx).detach();
+            }
+        });
+        let pull_refresh = cx.new(|_| PullToRefresh::new());
+        let pull_sub = cx.subscribe(&pull_refresh, |this, pull, event, cx| match event {
+            PullToRefreshEvent::Refresh => {
+                let refresh = this.refresh_projects(cx);
+                cx.spawn(async move |_this, cx| {
+                    refresh.await;
+                    let _ = pull.update(cx, |pull, cx| pull.finish(cx));
+                })
+                .detach();
+            }
+        });
 
         Self {
             workspace_state,
             session_state,
             session_handle,
-            _subscriptions: vec![workspace_state_sub],
+            projects: Vec::new(),
+            ssh_hosts: Vec::new(),
+            recordings: Vec::new(),
+            power_actions: false,
+            device_key: crate::vault::client_signer()
+                .map_err(|e| tracing::warn!("session panel: no client key: {e:#}"))
+                .ok()
+                .map(|signer| signer.pubkey().iter().map(|b| format!("{b:02x}")).collect()),
+            pull_refresh,
+            _subscriptions: vec![workspace_state_sub, sync_sub, pull_sub],
+        }
+    }
+
+    /// Fetch the host's project list, imported SSH hosts, recordings and power
+    /// support. Hosts without `HostProjects`, `HostsList` or `RecordingsList`
+    /// leave the matching list unchanged.
+    fn refresh_projects(&self, cx: &mut Context<Self>) -> Task<()> {
+        let handle = self.session_handle.clone();
+        let power = host_power::refresh(&self.session_handle, &self.workspace_state, cx);
+        cx.spawn(async move |this, cx| {
+            let power_actions = power.await;
+            let projects = handle
+                .host_projects()
+                .await
+                .map_err(|e| tracing::debug!("session panel: host projects unavailable: {e:#}"))
+                .ok();
+            let ssh_hosts = handle
+                .hosts_list()
+                .await
+                .map_err(|e| tracing::debug!("session panel: ssh hosts unavailable: {e:#}"))
+                .ok();
+            let recordings = handle
+                .recordings_list()
+                .await
+                .map_err(|e| tracing::debug!("session panel: recordings unavailable: {e:#}"))
+                .ok();
+            let _ = this.update(cx, |this, cx| {
+                if let Some(projects) = projects {
+                    this.projects = projects;
+                }
+                if let Some(ssh_hosts) = ssh_hosts {
+                    this.ssh_hosts = ssh_hosts.unwrap_or_default();
+                }
+                if let Some((mut recordings, _enabled)) = recordings {
+                    recordings.truncate(RECORDINGS_SHOWN);
+                    this.recordings = recordings;
+                }
+                this.power_actions = power_actions;
+                cx.notify();
+            });
+        })
+    }
+
+    /// Switch terminals, explorer and git to a running project's workspace. A
+    /// stopped project has no daemon to connect to, so explain how to start it.
+    fn open_project(&self, project: HostProject, window: &mut Window, cx: &mut Context<Self>) {
+        platform_bridge::trigger_haptic(HapticFeedback::ImpactLight);
+        if project.is_current {
+            return;
+        }
+        if project.running {
+            window.dispatch_action(
+                workspace_action::OpenHostProject {
+                    workdir: project.path,
+                }
+                .boxed_clone(),
+                cx,
+            );
+            return;
         }
+        platform_bridge::show_alert(
+            &project.name,
+            &format!(
+                "Run `zedra start --workdir {}` on the host to open this project.",
+                project.path
+            ),
+            vec![AlertButton::default("OK")],
+            |_| {},
+        );
     }
 
     /// Reopen a tracked tunnel and bump it to the front of the list.
@@ -117,12 +232,13 @@ impl Render for SessionPanel {
         let is_empty = phase.is_init();
         if is_empty {
             return div()
+                .id("session-panel-empty")
                 .size_full()
                 .flex()
                 .items_center()
                 .justify_center()
                 .text_color(rgb(theme::text_muted(cx)))
-                .text_size(px(theme::FONT_BODY))
+                .text_size(theme::scaled(theme::FONT_BODY))
                 .child("No active session");
         }
 
@@ -159,6 +275,10 @@ impl Render for SessionPanel {
             info = info.child(info_row(cx, "Platform", platform));
         }
 
+        if let Some(provider) = snap.ai_provider.as_ref() {
+            info = info.child(info_row(cx, "AI", ai_provider_label(provider)));
+        }
+
         if let Some(host_info) = host_info.as_ref() {
             info = info.child(render_host_info(cx, host_info));
         }
@@ -167,6 +287,10 @@ impl Render for SessionPanel {
             info = info.child(info_row(cx, "Directory", snap.strip_path.clone()));
         }
 
+        if let Some(device_key) = self.device_key.clone() {
+            info = info.child(device_key_row(device_key, cx));
+        }
+
         // --- Connection badge ---
         let (badge_label, badge_color) =
             transport_badge(&theme::palette(cx), &phase, snap.transport.as_ref());
@@ -195,7 +319,7 @@ impl Render for SessionPanel {
                         .child(
                             div()
                                 .text_color(rgb(theme::text_muted(cx)))
-                                .text_size(px(theme::FONT_DETAIL))
+                                .text_size(theme::scaled(theme::FONT_DETAIL))
                                 .child("Connection"),
                         )
                         .child(
@@ -215,13 +339,17 @@ impl Render for SessionPanel {
                 ),
         );
 
+        if self.power_actions {
+            info = info.child(power_row(cx));
+        }
+
         // --- Web tunnels section ---
         info = info.child(
             div()
                 .mt(px(8.0))
                 .mb(px(2.0))
                 .text_color(rgb(theme::text_muted(cx)))
-                .text_size(px(theme::FONT_DETAIL))
+                .text_size(theme::scaled(theme::FONT_DETAIL))
                 .child("Web tunnels"),
         );
         let mut list = div().flex().flex_col();
@@ -230,7 +358,72 @@ impl Render for SessionPanel {
         }
         info = info.child(list.child(open_webview_row(cx)));
 
-        info.child(div().h(px(16.0)))
+        // --- Projects section ---
+        let projects = self.projects.clone();
+        if projects.len() > 1 || projects.iter().any(|p| !p.is_current) {
+            info = info.child(
+                div()
+                    .mt(px(8.0))
+                    .mb(px(2.0))
+                    .text_color(rgb(theme::text_muted(cx)))
+                    .text_size(theme::scaled(theme::FONT_DETAIL))
+                    .child("Projects on this host"),
+            );
+            let mut list = div().flex().flex_col();
+            for (idx, project) in projects.into_iter().enumerate() {
+                list = list.child(project_row(idx, project, cx));
+            }
+            info = info.child(list);
+        }
+
+        // --- SSH hosts section ---
+        let ssh_hosts = self.ssh_hosts.clone();
+        if !ssh_hosts.is_empty() {
+            info = info.child(
+                div()
+                    .mt(px(8.0))
+                    .mb(px(2.0))
+                    .text_color(rgb(theme::text_muted(cx)))
+                    .text_size(theme::scaled(theme::FONT_DETAIL))
+                    .child("SSH hosts"),
+            );
+            let mut list = div().flex().flex_col();
+            for (idx, host) in ssh_hosts.into_iter().enumerate() {
+                list = list.child(ssh_host_row(idx, host, cx));
+            }
+            info = info.child(list);
+        }
+
+        // --- Recordings section ---
+        let recordings = self.recordings.clone();
+        if !recordings.is_empty() {
+            info = info.child(
+                div()
+                    .mt(px(8.0))
+                    .mb(px(2.0))
+                    .text_color(rgb(theme::text_muted(cx)))
+                    .text_size(theme::scaled(theme::FONT_DETAIL))
+                    .child("Terminal recordings"),
+            );
+            let mut list = div().flex().flex_col();
+            for (idx, recording) in recordings.into_iter().enumerate() {
+                list = list.child(recording_row(idx, recording, cx));
+            }
+            info = info.child(list);
+        }
+
+        pull_to_refresh(
+            "session-panel-pull",
+            &self.pull_refresh,
+            PullScroll::Tracked,
+            div()
+                .id("session-panel-scroll")
+                .size_full()
+                .overflow_y_scroll()
+                .track_scroll(self.pull_refresh.read(cx).scroll_handle())
+                .child(info.child(div().h(px(16.0)))),
+            cx,
+        )
     }
 }
 
@@ -294,6 +487,49 @@ fn format_batteries(batteries: &[HostBatteryInfo]) -> Option<String> {
     }
 }
 
+/// Active host AI backend, e.g. `ollama · qwen2.5-coder`.
+fn ai_provider_label(provider: &AiProviderInfo) -> String {
+    if provider.error.is_some() {
+        return format!("{} (config error)", provider.kind);
+    }
+    match provider.model.as_deref() {
+        Some(model) => format!("{} \u{00b7} {model}", provider.kind),
+        None => provider.kind.clone(),
+    }
+}
+
+/// Row that offers sleep and reboot for a host started with `--allow-power`.
+fn power_row(cx: &mut Context<SessionPanel>) -> impl IntoElement {
+    div()
+        .id("session-power")
+        .w_full()
+        .py(px(theme::SPACING_XS))
+        .flex()
+        .flex_row()
+        .items_center()
+        .gap(px(theme::SPACING_SM))
+        .cursor_pointer()
+        .hit_slop(px(4.0))
+        .on_press(cx.listener(|this, _event, _window, cx| {
+            platform_bridge::trigger_haptic(HapticFeedback::ImpactLight);
+            let host_name = this.workspace_state.read(cx).display_name().to_string();
+            host_power::choose_action(&this.session_handle, host_name, cx);
+        }))
+        .child(
+            svg()
+                .path("icons/moon.svg")
+                .size(px(theme::ICON_SM))
+                .flex_shrink_0()
+                .text_color(rgb(theme::text_muted(cx))),
+        )
+        .child(
+            div()
+                .text_color(rgb(theme::text_muted(cx)))
+                .text_size(theme::scaled(theme::FONT_BODY))
+                .child("Sleep or reboot host"),
+        )
+}
+
 /// Row that prompts for an address and opens it as a new webview.
 fn open_webview_row(cx: &mut Context<SessionPanel>) -> impl IntoElement {
     div()
@@ -317,7 +553,7 @@ fn open_webview_row(cx: &mut Context<SessionPanel>) -> impl IntoElement {
         .child(
             div()
                 .text_color(rgb(theme::text_muted(cx)))
-                .text_size(px(theme::FONT_BODY))
+                .text_size(theme::scaled(theme::FONT_BODY))
                 .child("Open webview"),
         )
 }
@@ -357,7 +593,7 @@ fn tunnel_row(
                 .child(
                     div()
                         .text_color(rgb(theme::text_primary(cx)))
-                        .text_size(px(theme::FONT_BODY))
+                        .text_size(theme::scaled(theme::FONT_BODY))
                         .font_family(fonts::MONO_FONT_FAMILY)
                         .child(tunnel.title.clone()),
                 )
@@ -366,7 +602,7 @@ fn tunnel_row(
                         .min_w_0()
                         .overflow_hidden()
                         .text_color(rgb(theme::text_muted(cx)))
-                        .text_size(px(theme::FONT_DETAIL))
+                        .text_size(theme::scaled(theme::FONT_DETAIL))
                         .child(tunnel.url.clone()),
                 ),
         )
@@ -380,20 +616,241 @@ fn tunnel_row(
         )
 }
 
+fn project_row(
+    idx: usize,
+    project: HostProject,
+    cx: &mut Context<SessionPanel>,
+) -> impl IntoElement {
+    let status = if project.is_current {
+        "Connected"
+    } else if project.running {
+        "Running"
+    } else {
+        "Stopped"
+    };
+    let status_color = if project.is_current || project.running {
+        theme::accent_green(cx)
+    } else {
+        theme::text_muted(cx)
+    };
+    let on_open = project.clone();
+    div()
+        .id(("session-project", idx))
+        .w_full()
+        .min_w_0()
+        .py(px(theme::SPACING_XS))
+        .flex()
+        .flex_row()
+        .items_center()
+        .justify_between()
+        .gap(px(theme::SPACING_MD))
+        .cursor_pointer()
+        .hit_slop(px(4.0))
+        .on_press(cx.listener(move |this, _event, window, cx| {
+            this.open_project(on_open.clone(), window, cx);
+        }))
+        .child(
+            div()
+                .flex_1()
+                .min_w_0()
+                .flex()
+                .flex_col()
+                .gap(px(1.0))
+                .child(
+                    div()
+                        .text_color(rgb(theme::text_primary(cx)))
+                        .text_size(theme::scaled(theme::FONT_BODY))
+                        .font_family(fonts::MONO_FONT_FAMILY)
+                        .child(project.name.clone()),
+                )
+                .child(
+                    div()
+                        .min_w_0()
+                        .overflow_hidden()
+                        .text_color(rgb(theme::text_muted(cx)))
+                        .text_size(theme::scaled(theme::FONT_DETAIL))
+                        .child(project.path.clone()),
+                ),
+        )
+        .child(
+            div()
+                .flex_shrink_0()
+                .pl(px(8.0))
+                .text_color(rgb(status_color))
+                .text_size(theme::scaled(theme::FONT_DETAIL))
+                .child(status),
+        )
+}
+
+/// `user@hostname:port via jump`, leaving out what the config does not set.
+fn ssh_host_target(host: &SshHost) -> String {
+    let mut target = host.hostname.clone().unwrap_or_else(|| host.alias.clone());
+    if let Some(user) = host.user.as_deref() {
+        target = format!("{user}@{target}");
+    }
+    if let Some(port) = host.port {
+        target = format!("{target}:{port}");
+    }
+    if let Some(jump) = host.proxy_jump.as_deref() {
+        target = format!("{target} via {jump}");
+    }
+    target
+}
+
+fn ssh_host_row(idx: usize, host: SshHost, cx: &mut Context<SessionPanel>) -> impl IntoElement {
+    let alias = host.alias.clone();
+    div()
+        .id(("session-ssh-host", idx))
+        .w_full()
+        .min_w_0()
+        .py(px(theme::SPACING_XS))
+        .flex()
+        .flex_row()
+        .items_center()
+        .justify_between()
+        .gap(px(theme::SPACING_MD))
+        .cursor_pointer()
+        .hit_slop(px(4.0))
+        .on_press(cx.listener(move |_this, _event, window, cx| {
+            platform_bridge::trigger_haptic(HapticFeedback::ImpactLight);
+            window.dispatch_action(
+                workspace_action::OpenSshHost {
+                    alias: alias.clone(),
+                }
+                .boxed_clone(),
+                cx,
+            );
+        }))
+        .child(
+            div()
+                .flex_1()
+                .min_w_0()
+                .flex()
+                .flex_col()
+                .gap(px(1.0))
+                .child(
+                    div()
+                        .text_color(rgb(theme::text_primary(cx)))
+                        .text_size(theme::scaled(theme::FONT_BODY))
+                        .font_family(fonts::MONO_FONT_FAMILY)
+                        .child(host.alias.clone()),
+                )
+                .child(
+                    div()
+                        .min_w_0()
+                        .overflow_hidden()
+                        .text_color(rgb(theme::text_muted(cx)))
+                        .text_size(theme::scaled(theme::FONT_DETAIL))
+                        .child(ssh_host_target(&host)),
+                ),
+        )
+        .child(
+            div().flex_shrink_0().pl(px(8.0)).child(
+                svg()
+                    .path("icons/chevron-right.svg")
+                    .size(px(theme::ICON_SM))
+                    .text_color(rgb(theme::text_muted(cx))),
+            ),
+        )
+}
+
+fn recording_row(
+    idx: usize,
+    recording: RecordingEntry,
+    cx: &mut Context<SessionPanel>,
+) -> impl IntoElement {
+    let name = recording.name.clone();
+    let modified = chrono::DateTime::from_timestamp(recording.modified_secs as i64, 0)
+        .map(|at| {
+            at.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_default();
+    div()
+        .id(("session-recording", idx))
+        .w_full()
+        .min_w_0()
+        .py(px(theme::SPACING_XS))
+        .flex()
+        .flex_row()
+        .items_center()
+        .justify_between()
+        .gap(px(theme::SPACING_MD))
+        .cursor_pointer()
+        .hit_slop(px(4.0))
+        .on_press(cx.listener(move |_this, _event, window, cx| {
+            platform_bridge::trigger_haptic(HapticFeedback::ImpactLight);
+            window.dispatch_action(
+                workspace_action::OpenRecording { name: name.clone() }.boxed_clone(),
+                cx,
+            );
+        }))
+        .child(
+            div()
+                .flex_1()
+                .min_w_0()
+                .flex()
+                .flex_col()
+                .gap(px(1.0))
+                .child(
+                    div()
+                        .min_w_0()
+                        .truncate()
+                        .text_color(rgb(theme::text_primary(cx)))
+                        .text_size(theme::scaled(theme::FONT_BODY))
+                        .font_family(fonts::MONO_FONT_FAMILY)
+                        .child(recording.name.clone()),
+                )
+                .child(
+                    div()
+                        .text_color(rgb(theme::text_muted(cx)))
+                        .text_size(theme::scaled(theme::FONT_DETAIL))
+                        .child(format!("{modified} · {}", format_bytes(recording.size))),
+                ),
+        )
+        .child(
+            div().flex_shrink_0().pl(px(8.0)).child(
+                svg()
+                    .path("icons/chevron-right.svg")
+                    .size(px(theme::ICON_SM))
+                    .text_color(rgb(theme::text_muted(cx))),
+            ),
+        )
+}
+
+/// This device's key, shortened; tap to copy it for `zedra start --admin-client`.
+fn device_key_row(device_key: String, cx: &mut Context<SessionPanel>) -> impl IntoElement {
+    let short = format!("{}\u{2026}", &device_key[..device_key.len().min(16)]);
+    div()
+        .id("session-device-key")
+        .cursor_pointer()
+        .on_press(cx.listener(move |_this, _event, _window, cx| {
+            platform_bridge::trigger_haptic(HapticFeedback::ImpactLight);
+            cx.write_to_clipboard(ClipboardItem::new_string(device_key.clone()));
+            platform_bridge::show_native_notification(
+                NativeNotificationOptions::new("Copied device key")
+                    .message("Pass it to zedra start --admin-client to manage every session.")
+                    .kind(NativeNotificationKind::Success),
+            );
+        }))
+        .child(info_row(cx, "Device key", short))
+}
+
 fn info_row(cx: &App, label: &'static str, value: String) -> Div {
     div()
         .py(px(4.0))
         .child(
             div()
                 .text_color(rgb(theme::text_muted(cx)))
-                .text_size(px(theme::FONT_DETAIL))
+                .text_size(theme::scaled(theme::FONT_DETAIL))
                 .child(label),
         )
         .child(
             div()
                 .mt(px(1.0))
                 .text_color(rgb(theme::text_secondary(cx)))
-                .text_size(px(theme::FONT_BODY))
+                .text_size(theme::scaled(theme::FONT_BODY))
                 .child(value),
         )
 }
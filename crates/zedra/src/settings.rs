@@ -3,11 +3,16 @@ use std::path::PathBuf;
 use gpui::{App, Context, Entity, EventEmitter, Global, WeakEntity};
 use serde::{Deserialize, Serialize};
 use tracing::{info, warn};
+use zedra_terminal::DefaultCursorShape;
 
+use crate::editor::save_cleanup::SaveCleanup;
+use crate::fonts::CodeFont;
 use crate::theme::{ThemeBundle, ThemePreference};
+use crate::theme_import::ImportedTheme;
 
 const STORE_DIR: &str = "zedra";
 const SETTINGS_FILE: &str = "settings.json";
+const IMPORTED_THEME_FILE: &str = "imported_theme.json";
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 struct AppSettings {
@@ -20,6 +25,158 @@ struct AppSettings {
     /// Water droplet effect. `None`/absent = enabled (default-on).
     #[serde(default, skip_serializing_if = "Option::is_none")]
     droplet_enabled: Option<bool>,
+    /// Terminal and editor font. `None`/absent = bundled Nerd Font.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    code_font: Option<CodeFont>,
+    /// Terminal default-background alpha. `None`/absent = opaque.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    terminal_background_opacity: Option<f32>,
+    /// Default terminal cursor. `None`/absent = steady block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    terminal_cursor: Option<TerminalCursor>,
+    /// Reduced-bandwidth mode. `None`/absent = follow metered networks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    data_saver: Option<DataSaverMode>,
+    /// Apply the VS Code theme saved in `imported_theme.json`. `None`/absent = built-in themes only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    imported_theme_enabled: Option<bool>,
+    /// Whitespace cleanup when saving files. `None`/absent = `SaveCleanup::default()`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    save_cleanup: Option<SaveCleanup>,
+    /// App text size. `None`/absent = follow the system font scale.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    text_size: Option<TextSize>,
+    /// Endpoint of the saved workspace to connect to on launch. `None`/absent = open Home.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    auto_connect_endpoint: Option<String>,
+    /// Host AI tool patterns approved with "Always Allow", per host. The bare
+    /// tool names saved under `ai_always_allowed_tools` before grants were per
+    /// host are not read, so those tools ask again.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    ai_tool_grants: Vec<AiToolGrant>,
+}
+
+/// A host AI tool use the user chose to always allow on one host.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AiToolGrant {
+    /// Endpoint id of the host the grant applies to.
+    pub host: String,
+    /// Claude Code `--allowedTools` pattern, e.g. `Bash(cargo test:*)`.
+    pub pattern: String,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TerminalCursorShape {
+    #[default]
+    Block,
+    Underline,
+    Bar,
+}
+
+impl TerminalCursorShape {
+    pub const ALL: [Self; 3] = [Self::Block, Self::Underline, Self::Bar];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Block => "Block",
+            Self::Underline => "Underline",
+            Self::Bar => "Bar",
+        }
+    }
+
+    pub fn to_terminal(self) -> DefaultCursorShape {
+        match self {
+            Self::Block => DefaultCursorShape::Block,
+            Self::Underline => DefaultCursorShape::Underline,
+            Self::Bar => DefaultCursorShape::Bar,
+        }
+    }
+}
+
+/// Cursor a terminal shows until the running program picks one with DECSCUSR.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TerminalCursor {
+    #[serde(default)]
+    pub shape: TerminalCursorShape,
+    #[serde(default)]
+    pub blinking: bool,
+}
+
+/// When the data saver is on: always, never, or while the network is metered.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DataSaverMode {
+    #[default]
+    Auto,
+    On,
+    Off,
+}
+
+impl DataSaverMode {
+    pub const ALL: [Self; 3] = [Self::Auto, Self::On, Self::Off];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Auto => "Auto",
+            Self::On => "On",
+            Self::Off => "Off",
+        }
+    }
+
+    pub fn is_active(self, metered: bool) -> bool {
+        match self {
+            Self::Auto => metered,
+            Self::On => true,
+            Self::Off => false,
+        }
+    }
+}
+
+/// Text size for the app chrome, editors, and terminals.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TextSize {
+    /// The OS font scale where the platform reports one (Android's font size).
+    #[default]
+    System,
+    Small,
+    Standard,
+    Large,
+    ExtraLarge,
+}
+
+impl TextSize {
+    pub const ALL: [Self; 5] = [
+        Self::System,
+        Self::Small,
+        Self::Standard,
+        Self::Large,
+        Self::ExtraLarge,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::System => "System",
+            Self::Small => "Small",
+            Self::Standard => "Default",
+            Self::Large => "Large",
+            Self::ExtraLarge => "Extra Large",
+        }
+    }
+
+    /// Multiplier for text sizes. The system scale is clamped so the largest
+    /// accessibility sizes still leave room for a terminal row and the header.
+    pub fn scale(self, system_scale: f32) -> f32 {
+        match self {
+            Self::System if system_scale.is_finite() => system_scale.clamp(0.85, 1.6),
+            Self::System => 1.0,
+            Self::Small => 0.85,
+            Self::Standard => 1.0,
+            Self::Large => 1.15,
+            Self::ExtraLarge => 1.3,
+        }
+    }
 }
 
 pub enum ThemeStateEvent {
@@ -30,15 +187,44 @@ impl EventEmitter<ThemeStateEvent> for ThemeState {}
 
 pub struct ThemeState {
     preference: ThemePreference,
+    terminal_background_opacity: f32,
+    imported: Option<ImportedTheme>,
     bundle: ThemeBundle,
 }
 
 impl ThemeState {
     pub fn new(_cx: &mut Context<Self>) -> Self {
         let preference = Self::load_preference();
-        let bundle = ThemeBundle::for_preference(preference);
+        let terminal_background_opacity = read_settings()
+            .ok()
+            .and_then(|settings| settings.terminal_background_opacity)
+            .unwrap_or(1.0);
+        let imported = Self::load_imported_theme();
+        let bundle = Self::build_bundle(preference, terminal_background_opacity, imported.as_ref());
         Self::sync_native_theme(preference);
-        Self { preference, bundle }
+        Self {
+            preference,
+            terminal_background_opacity,
+            imported,
+            bundle,
+        }
+    }
+
+    /// The imported theme only covers its own appearance; switching to the
+    /// other one shows the built-in bundle until the user switches back.
+    fn build_bundle(
+        preference: ThemePreference,
+        terminal_background_opacity: f32,
+        imported: Option<&ImportedTheme>,
+    ) -> ThemeBundle {
+        let mut bundle = ThemeBundle::for_preference(preference);
+        if let Some(theme) = imported.filter(|theme| theme.appearance == preference) {
+            theme.apply(&mut bundle);
+        }
+        bundle.terminal = bundle
+            .terminal
+            .with_background_opacity(terminal_background_opacity);
+        bundle
     }
 
     pub fn preference(&self) -> ThemePreference {
@@ -58,13 +244,108 @@ impl ThemeState {
             return;
         }
         self.preference = preference;
-        self.bundle = ThemeBundle::for_preference(preference);
+        self.rebuild_bundle();
         Self::sync_native_theme(preference);
         Self::save_preference(preference);
         cx.emit(ThemeStateEvent::Changed);
         cx.notify();
     }
 
+    pub fn terminal_background_opacity(&self) -> f32 {
+        self.terminal_background_opacity
+    }
+
+    /// Alpha for the terminal's default background; lower values let the view
+    /// behind the terminal show through.
+    pub fn set_terminal_background_opacity(&mut self, opacity: f32, cx: &mut Context<Self>) {
+        let opacity = opacity.clamp(0.0, 1.0);
+        if self.terminal_background_opacity == opacity {
+            return;
+        }
+        self.terminal_background_opacity = opacity;
+        self.rebuild_bundle();
+        let mut settings = read_settings().unwrap_or_default();
+        settings.terminal_background_opacity = Some(opacity);
+        if let Err(err) = write_settings(&settings) {
+            warn!(err = %err, "settings: failed to save terminal background opacity");
+        }
+        cx.emit(ThemeStateEvent::Changed);
+        cx.notify();
+    }
+
+    pub fn imported_theme(&self) -> Option<&ImportedTheme> {
+        self.imported.as_ref()
+    }
+
+    /// Parse `source` as a VS Code theme, keep a copy for later launches, and
+    /// switch to the theme's appearance so it shows right away.
+    pub fn import_theme(&mut self, source: &str, cx: &mut Context<Self>) -> Result<String, String> {
+        let theme = ImportedTheme::parse(source)?;
+        let path = imported_theme_path().ok_or_else(|| "theme path unavailable".to_string())?;
+        std::fs::write(&path, source).map_err(|e| format!("could not save theme: {e}"))?;
+        let mut settings = read_settings().unwrap_or_default();
+        settings.imported_theme_enabled = Some(true);
+        if let Err(err) = write_settings(&settings) {
+            warn!(err = %err, "settings: failed to save imported theme flag");
+        }
+
+        let name = theme.name.clone();
+        let appearance = theme.appearance;
+        self.imported = Some(theme);
+        if self.preference == appearance {
+            self.rebuild_bundle();
+            cx.emit(ThemeStateEvent::Changed);
+            cx.notify();
+        } else {
+            self.set_preference(appearance, cx);
+        }
+        Ok(name)
+    }
+
+    /// Go back to the built-in themes and delete the saved copy.
+    pub fn clear_imported_theme(&mut self, cx: &mut Context<Self>) {
+        if self.imported.take().is_none() {
+            return;
+        }
+        if let Some(path) = imported_theme_path() {
+            let _ = std::fs::remove_file(path);
+        }
+        let mut settings = read_settings().unwrap_or_default();
+        settings.imported_theme_enabled = None;
+        if let Err(err) = write_settings(&settings) {
+            warn!(err = %err, "settings: failed to clear imported theme flag");
+        }
+        self.rebuild_bundle();
+        cx.emit(ThemeStateEvent::Changed);
+        cx.notify();
+    }
+
+    fn rebuild_bundle(&mut self) {
+        self.bundle = Self::build_bundle(
+            self.preference,
+            self.terminal_background_opacity,
+            self.imported.as_ref(),
+        );
+    }
+
+    fn load_imported_theme() -> Option<ImportedTheme> {
+        let enabled = read_settings()
+            .ok()
+            .and_then(|settings| settings.imported_theme_enabled)
+            .unwrap_or(false);
+        if !enabled {
+            return None;
+        }
+        let source = std::fs::read_to_string(imported_theme_path()?).ok()?;
+        match ImportedTheme::parse(&source) {
+            Ok(theme) => Some(theme),
+            Err(err) => {
+                warn!(err = %err, "settings: ignoring saved imported theme");
+                None
+            }
+        }
+    }
+
     pub fn register_global(entity: WeakEntity<Self>, cx: &mut App) {
         cx.set_global(ThemeStateHandle(entity));
     }
@@ -136,6 +417,10 @@ fn settings_path() -> Option<PathBuf> {
     Some(dir.join(SETTINGS_FILE))
 }
 
+fn imported_theme_path() -> Option<PathBuf> {
+    settings_path().map(|path| path.with_file_name(IMPORTED_THEME_FILE))
+}
+
 fn read_settings() -> Result<AppSettings, String> {
     let path = settings_path().ok_or_else(|| "settings path unavailable".to_string())?;
     if !path.exists() {
@@ -195,9 +480,246 @@ pub fn set_droplet_enabled(enabled: bool) {
     }
 }
 
+/// Saved workspace the app connects to on launch, by endpoint address.
+pub fn read_auto_connect_endpoint() -> Option<String> {
+    match read_settings() {
+        Ok(settings) => settings.auto_connect_endpoint,
+        Err(err) => {
+            info!(err = %err, "settings: no auto-connect workspace");
+            None
+        }
+    }
+}
+
+pub fn set_auto_connect_endpoint(endpoint_addr: Option<String>) {
+    let mut settings = read_settings().unwrap_or_default();
+    settings.auto_connect_endpoint = endpoint_addr.clone();
+    if let Err(err) = write_settings(&settings) {
+        warn!(err = %err, "settings: failed to save auto-connect workspace");
+    }
+    crate::launcher_shortcuts::set_pinned(endpoint_addr);
+}
+
+/// Host AI tool patterns the user chose to always allow, on every host.
+pub fn read_ai_tool_grants() -> Vec<AiToolGrant> {
+    match read_settings() {
+        Ok(settings) => settings.ai_tool_grants,
+        Err(err) => {
+            info!(err = %err, "settings: no always-allowed AI tools");
+            Vec::new()
+        }
+    }
+}
+
+pub fn add_ai_tool_grant(grant: AiToolGrant) {
+    let mut settings = read_settings().unwrap_or_default();
+    if settings.ai_tool_grants.contains(&grant) {
+        return;
+    }
+    settings.ai_tool_grants.push(grant);
+    if let Err(err) = write_settings(&settings) {
+        warn!(err = %err, "settings: failed to save always-allowed AI tool");
+    }
+}
+
+pub fn remove_ai_tool_grant(grant: &AiToolGrant) {
+    let mut settings = read_settings().unwrap_or_default();
+    settings.ai_tool_grants.retain(|g| g != grant);
+    if let Err(err) = write_settings(&settings) {
+        warn!(err = %err, "settings: failed to revoke always-allowed AI tool");
+    }
+}
+
+/// Current code font, shared so terminals and editors can observe changes.
+#[derive(Clone, Copy)]
+pub struct CodeFontSetting(pub CodeFont);
+
+impl Global for CodeFontSetting {}
+
+/// Load the persisted code font into the global. Called once at app init.
+pub fn init_code_font(cx: &mut App) {
+    let font = match read_settings() {
+        Ok(settings) => settings.code_font.unwrap_or_default(),
+        Err(err) => {
+            info!(err = %err, "settings: using default code font");
+            CodeFont::default()
+        }
+    };
+    cx.set_global(CodeFontSetting(font));
+}
+
+pub fn code_font(cx: &App) -> CodeFont {
+    cx.try_global::<CodeFontSetting>()
+        .map(|setting| setting.0)
+        .unwrap_or_default()
+}
+
+/// Persist `font` and update the global; observers re-apply it.
+pub fn set_code_font(font: CodeFont, cx: &mut App) {
+    cx.set_global(CodeFontSetting(font));
+    let mut settings = read_settings().unwrap_or_default();
+    settings.code_font = Some(font);
+    if let Err(err) = write_settings(&settings) {
+        warn!(err = %err, "settings: failed to save code font");
+    }
+}
+
+/// Current default terminal cursor, shared so terminals can observe changes.
+#[derive(Clone, Copy)]
+pub struct TerminalCursorSetting(pub TerminalCursor);
+
+impl Global for TerminalCursorSetting {}
+
+/// Load the persisted terminal cursor into the global. Called once at app init.
+pub fn init_terminal_cursor(cx: &mut App) {
+    let cursor = match read_settings() {
+        Ok(settings) => settings.terminal_cursor.unwrap_or_default(),
+        Err(err) => {
+            info!(err = %err, "settings: using default terminal cursor");
+            TerminalCursor::default()
+        }
+    };
+    cx.set_global(TerminalCursorSetting(cursor));
+}
+
+pub fn terminal_cursor(cx: &App) -> TerminalCursor {
+    cx.try_global::<TerminalCursorSetting>()
+        .map(|setting| setting.0)
+        .unwrap_or_default()
+}
+
+/// Persist `cursor` and update the global; open terminals re-apply it.
+pub fn set_terminal_cursor(cursor: TerminalCursor, cx: &mut App) {
+    cx.set_global(TerminalCursorSetting(cursor));
+    let mut settings = read_settings().unwrap_or_default();
+    settings.terminal_cursor = Some(cursor);
+    if let Err(err) = write_settings(&settings) {
+        warn!(err = %err, "settings: failed to save terminal cursor");
+    }
+}
+
+/// Current data saver mode, shared so workspaces can observe changes.
+#[derive(Clone, Copy)]
+pub struct DataSaverSetting(pub DataSaverMode);
+
+impl Global for DataSaverSetting {}
+
+/// Load the persisted data saver mode into the global. Called once at app init.
+pub fn init_data_saver(cx: &mut App) {
+    let mode = match read_settings() {
+        Ok(settings) => settings.data_saver.unwrap_or_default(),
+        Err(err) => {
+            info!(err = %err, "settings: using default data saver mode");
+            DataSaverMode::default()
+        }
+    };
+    cx.set_global(DataSaverSetting(mode));
+}
+
+pub fn data_saver_mode(cx: &App) -> DataSaverMode {
+    cx.try_global::<DataSaverSetting>()
+        .map(|setting| setting.0)
+        .unwrap_or_default()
+}
+
+/// Persist `mode` and update the global; connected workspaces tell their host.
+pub fn set_data_saver_mode(mode: DataSaverMode, cx: &mut App) {
+    cx.set_global(DataSaverSetting(mode));
+    let mut settings = read_settings().unwrap_or_default();
+    settings.data_saver = Some(mode);
+    if let Err(err) = write_settings(&settings) {
+        warn!(err = %err, "settings: failed to save data saver mode");
+    }
+}
+
+/// Whether reduced-bandwidth behavior applies right now.
+pub fn data_saver_active(cx: &App) -> bool {
+    data_saver_mode(cx).is_active(crate::platform_bridge::is_network_metered())
+}
+
+/// Current save cleanup, shared so editors pick up changes on their next save.
+#[derive(Clone, Copy)]
+pub struct SaveCleanupSetting(pub SaveCleanup);
+
+impl Global for SaveCleanupSetting {}
+
+/// Load the persisted save cleanup into the global. Called once at app init.
+pub fn init_save_cleanup(cx: &mut App) {
+    let cleanup = match read_settings() {
+        Ok(settings) => settings.save_cleanup.unwrap_or_default(),
+        Err(err) => {
+            info!(err = %err, "settings: using default save cleanup");
+            SaveCleanup::default()
+        }
+    };
+    cx.set_global(SaveCleanupSetting(cleanup));
+}
+
+pub fn save_cleanup(cx: &App) -> SaveCleanup {
+    cx.try_global::<SaveCleanupSetting>()
+        .map(|setting| setting.0)
+        .unwrap_or_default()
+}
+
+pub fn set_save_cleanup(cleanup: SaveCleanup, cx: &mut App) {
+    cx.set_global(SaveCleanupSetting(cleanup));
+    let mut settings = read_settings().unwrap_or_default();
+    settings.save_cleanup = Some(cleanup);
+    if let Err(err) = write_settings(&settings) {
+        warn!(err = %err, "settings: failed to save save cleanup");
+    }
+}
+
+/// Current text size, shared so terminals can observe changes. Render code
+/// reads the resulting scale through `theme::text_scale`.
+#[derive(Clone, Copy)]
+pub struct TextSizeSetting(pub TextSize);
+
+impl Global for TextSizeSetting {}
+
+fn apply_text_size(size: TextSize) {
+    let scale = size.scale(crate::platform_bridge::bridge().font_scale());
+    crate::theme::set_text_scale(scale);
+}
+
+/// Load the persisted text size into the global. Called once at app init.
+pub fn init_text_size(cx: &mut App) {
+    let size = match read_settings() {
+        Ok(settings) => settings.text_size.unwrap_or_default(),
+        Err(err) => {
+            info!(err = %err, "settings: using default text size");
+            TextSize::default()
+        }
+    };
+    apply_text_size(size);
+    cx.set_global(TextSizeSetting(size));
+}
+
+pub fn text_size(cx: &App) -> TextSize {
+    cx.try_global::<TextSizeSetting>()
+        .map(|setting| setting.0)
+        .unwrap_or_default()
+}
+
+/// Persist `size`, update the global, and redraw every window at the new scale.
+pub fn set_text_size(size: TextSize, cx: &mut App) {
+    apply_text_size(size);
+    cx.set_global(TextSizeSetting(size));
+    cx.refresh_windows();
+    let mut settings = read_settings().unwrap_or_default();
+    settings.text_size = Some(size);
+    if let Err(err) = write_settings(&settings) {
+        warn!(err = %err, "settings: failed to save text size");
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::ThemeState;
+    use super::{
+        AiToolGrant, AppSettings, DataSaverMode, TerminalCursor, TerminalCursorShape, TextSize,
+        ThemeState,
+    };
+    use crate::fonts::CodeFont;
     use crate::theme::{ThemeBundle, ThemePalette, ThemePreference};
 
     #[test]
@@ -211,6 +733,79 @@ mod tests {
         assert_eq!(ThemeState::preference_from_system(), ThemePreference::Dark);
     }
 
+    #[test]
+    fn code_font_defaults_to_bundled_nerd_font() {
+        let settings: AppSettings = serde_json::from_str("{}").unwrap();
+        assert_eq!(
+            settings.code_font.unwrap_or_default(),
+            CodeFont::JetBrainsMonoNerd
+        );
+        let settings: AppSettings = serde_json::from_str(r#"{"code_font":"system"}"#).unwrap();
+        assert_eq!(settings.code_font, Some(CodeFont::System));
+    }
+
+    #[test]
+    fn terminal_cursor_defaults_to_steady_block() {
+        let settings: AppSettings = serde_json::from_str("{}").unwrap();
+        assert_eq!(
+            settings.terminal_cursor.unwrap_or_default(),
+            TerminalCursor::default()
+        );
+        let settings: AppSettings =
+            serde_json::from_str(r#"{"terminal_cursor":{"shape":"bar","blinking":true}}"#).unwrap();
+        assert_eq!(
+            settings.terminal_cursor,
+            Some(TerminalCursor {
+                shape: TerminalCursorShape::Bar,
+                blinking: true,
+            })
+        );
+    }
+
+    #[test]
+    fn data_saver_defaults_to_following_metered_networks() {
+        let settings: AppSettings = serde_json::from_str("{}").unwrap();
+        let mode = settings.data_saver.unwrap_or_default();
+        assert_eq!(mode, DataSaverMode::Auto);
+        assert!(mode.is_active(true));
+        assert!(!mode.is_active(false));
+        let settings: AppSettings = serde_json::from_str(r#"{"data_saver":"on"}"#).unwrap();
+        assert_eq!(settings.data_saver, Some(DataSaverMode::On));
+        assert!(DataSaverMode::On.is_active(false));
+        assert!(!DataSaverMode::Off.is_active(true));
+    }
+
+    #[test]
+    fn text_size_follows_system_within_limits() {
+        let settings: AppSettings = serde_json::from_str("{}").unwrap();
+        let size = settings.text_size.unwrap_or_default();
+        assert_eq!(size, TextSize::System);
+        assert_eq!(size.scale(1.3), 1.3);
+        assert_eq!(size.scale(2.0), 1.6);
+        assert_eq!(size.scale(f32::NAN), 1.0);
+        let settings: AppSettings = serde_json::from_str(r#"{"text_size":"large"}"#).unwrap();
+        assert_eq!(settings.text_size, Some(TextSize::Large));
+        assert_eq!(TextSize::Large.scale(2.0), 1.15);
+    }
+
+    #[test]
+    fn ai_tool_grants_are_per_host_and_ignore_the_old_global_list() {
+        let settings: AppSettings =
+            serde_json::from_str(r#"{"ai_always_allowed_tools":["Bash"]}"#).unwrap();
+        assert!(settings.ai_tool_grants.is_empty());
+        let settings: AppSettings = serde_json::from_str(
+            r#"{"ai_tool_grants":[{"host":"h1","pattern":"Bash(cargo test:*)"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            settings.ai_tool_grants,
+            vec![AiToolGrant {
+                host: "h1".into(),
+                pattern: "Bash(cargo test:*)".into(),
+            }]
+        );
+    }
+
     #[test]
     fn bundle_matches_preference() {
         assert_eq!(
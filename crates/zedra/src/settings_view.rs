@@ -4,13 +4,19 @@ use gpui_tokio::Tokio;
 use futures::channel::oneshot;
 
 use crate::delta::{self, DeltaState};
+use crate::editor::save_cleanup::SaveCleanup;
+use crate::fonts::CodeFont;
 use crate::platform_bridge::{
     self, AlertButton, CustomSheetDetent, CustomSheetOptions, HapticFeedback,
 };
-use crate::settings::ThemeState;
+use crate::settings::{
+    AiToolGrant, DataSaverMode, TerminalCursor, TerminalCursorShape, TextSize, ThemeState,
+};
 use crate::sheet_demo_state::SheetDemoState;
 use crate::telemetry::view_telemetry;
 use crate::theme::{self, ThemePreference};
+use crate::vault;
+use crate::workspace_state::WorkspaceState;
 use crate::{fonts, settings};
 
 const TELEMETRY_DOCS_URL: &str = "https://zedra.dev/docs/telemetry";
@@ -20,6 +26,7 @@ const PRIVACY_POLICY_URL: &str = "https://zedra.dev/privacy";
 pub enum SettingsEvent {
     NavigateHome,
     OpenWebTunnel,
+    OpenMockHost,
     DropletToggled(bool),
 }
 
@@ -403,6 +410,53 @@ impl SettingsView {
         .detach();
     }
 
+    fn show_wipe_secrets_confirmation(&self, cx: &mut Context<Self>) {
+        platform_bridge::trigger_haptic(HapticFeedback::ImpactLight);
+        let (tx, rx) = oneshot::channel();
+        platform_bridge::show_alert(
+            "Wipe secrets?",
+            "Deletes this device's identity key and Delta sign-in. Saved hosts must be paired again.",
+            vec![
+                AlertButton::destructive("Wipe"),
+                AlertButton::cancel("Cancel"),
+            ],
+            move |button_index| {
+                let _ = tx.send(button_index);
+            },
+        );
+        cx.spawn(async move |this, cx| {
+            if let Ok(0) = rx.await {
+                let _ = this.update(cx, |this, cx| this.confirm_wipe_secrets(cx));
+            }
+        })
+        .detach();
+    }
+
+    fn confirm_wipe_secrets(&mut self, cx: &mut Context<Self>) {
+        let snapshot = self.delta_state.read(cx).snapshot();
+        match delta::sign_out(snapshot) {
+            Ok(next) => self
+                .delta_state
+                .update(cx, |state, cx| state.apply(next, cx)),
+            Err(error) => tracing::warn!("wipe secrets: Delta sign-out failed: {error:#}"),
+        }
+        let (title, message) = match vault::wipe() {
+            Ok(()) => (
+                "Secrets wiped",
+                "Restart Zedra to finish. A new device identity is created on next launch.",
+            ),
+            Err(error) => {
+                tracing::error!("wipe secrets failed: {error:#}");
+                (
+                    "Wipe failed",
+                    "Some secrets could not be removed. Try again.",
+                )
+            }
+        };
+        platform_bridge::show_alert(title, message, vec![AlertButton::default("OK")], |_| {});
+        cx.notify();
+    }
+
     fn set_theme_preference(&self, preference: ThemePreference, cx: &mut Context<Self>) {
         platform_bridge::trigger_haptic(HapticFeedback::SelectionChanged);
         self.theme_state.update(cx, |state, cx| {
@@ -410,6 +464,113 @@ impl SettingsView {
         });
     }
 
+    fn show_remove_imported_theme_confirmation(&self, name: String, cx: &mut Context<Self>) {
+        platform_bridge::trigger_haptic(HapticFeedback::ImpactLight);
+        let (tx, rx) = oneshot::channel();
+        platform_bridge::show_alert(
+            &format!("Stop using {name}?"),
+            "The editor and terminal go back to the built-in colors.",
+            vec![
+                AlertButton::destructive("Remove"),
+                AlertButton::cancel("Cancel"),
+            ],
+            move |button_index| {
+                let _ = tx.send(button_index);
+            },
+        );
+        cx.spawn(async move |this, cx| {
+            if let Ok(0) = rx.await {
+                let _ = this.update(cx, |this, cx| {
+                    this.theme_state
+                        .update(cx, |state, cx| state.clear_imported_theme(cx));
+                });
+            }
+        })
+        .detach();
+    }
+
+    fn show_ai_tool_grants(&self, cx: &mut Context<Self>) {
+        platform_bridge::trigger_haptic(HapticFeedback::ImpactLight);
+        let grants = settings::read_ai_tool_grants();
+        if grants.is_empty() {
+            platform_bridge::show_alert(
+                "No Always-Allowed Tools",
+                "Tools you approve with Always Allow appear here.",
+                vec![AlertButton::default("OK")],
+                |_| {},
+            );
+            return;
+        }
+        let hosts = saved_host_names();
+        let mut buttons: Vec<AlertButton> = grants
+            .iter()
+            .map(|grant| {
+                let host = hosts
+                    .iter()
+                    .find(|(id, _)| *id == grant.host)
+                    .map_or(grant.host.as_str(), |(_, name)| name.as_str());
+                AlertButton::default(format!("{} · {host}", grant.pattern))
+            })
+            .collect();
+        buttons.push(AlertButton::cancel("Cancel"));
+        let (tx, rx) = oneshot::channel();
+        platform_bridge::show_selection(
+            "Always-Allowed Tools",
+            "The host AI runs these without asking. Pick one to revoke it.",
+            buttons,
+            move |result| {
+                let _ = tx.send(result);
+            },
+        );
+        cx.spawn(async move |this, cx| {
+            let Ok(Some(index)) = rx.await else {
+                return;
+            };
+            let Some(grant) = grants.get(index).cloned() else {
+                return;
+            };
+            let _ = this.update(cx, |this, cx| this.show_revoke_ai_tool_grant(grant, cx));
+        })
+        .detach();
+    }
+
+    fn show_revoke_ai_tool_grant(&self, grant: AiToolGrant, cx: &mut Context<Self>) {
+        let (tx, rx) = oneshot::channel();
+        platform_bridge::show_alert(
+            &format!("Revoke {}?", grant.pattern),
+            "The host AI asks again before running it on this host.",
+            vec![
+                AlertButton::destructive("Revoke"),
+                AlertButton::cancel("Cancel"),
+            ],
+            move |button_index| {
+                let _ = tx.send(button_index);
+            },
+        );
+        cx.spawn(async move |_this, _cx| {
+            if let Ok(0) = rx.await {
+                settings::remove_ai_tool_grant(&grant);
+            }
+        })
+        .detach();
+    }
+
+    fn set_terminal_background_opacity(&self, opacity: f32, cx: &mut Context<Self>) {
+        platform_bridge::trigger_haptic(HapticFeedback::SelectionChanged);
+        self.theme_state.update(cx, |state, cx| {
+            state.set_terminal_background_opacity(opacity, cx);
+        });
+    }
+
+    fn set_terminal_cursor(&mut self, cursor: TerminalCursor, cx: &mut Context<Self>) {
+        if settings::terminal_cursor(cx) == cursor {
+            return;
+        }
+        platform_bridge::trigger_haptic(HapticFeedback::SelectionChanged);
+        settings::set_terminal_cursor(cursor, cx);
+        cx.notify();
+    }
+
     fn set_telemetry_enabled(&mut self, enabled: bool, cx: &mut Context<Self>) {
         if self.telemetry_enabled == enabled {
             return;
@@ -431,6 +592,77 @@ impl SettingsView {
         cx.notify();
     }
 
+    fn set_data_saver_mode(&mut self, mode: DataSaverMode, cx: &mut Context<Self>) {
+        if settings::data_saver_mode(cx) == mode {
+            return;
+        }
+        platform_bridge::trigger_haptic(HapticFeedback::SelectionChanged);
+        settings::set_data_saver_mode(mode, cx);
+        cx.notify();
+    }
+
+    fn set_save_cleanup(
+        &mut self,
+        update: fn(&mut SaveCleanup, bool),
+        enabled: bool,
+        cx: &mut Context<Self>,
+    ) {
+        let mut cleanup = settings::save_cleanup(cx);
+        update(&mut cleanup, enabled);
+        if settings::save_cleanup(cx) == cleanup {
+            return;
+        }
+        platform_bridge::trigger_haptic(HapticFeedback::SelectionChanged);
+        settings::set_save_cleanup(cleanup, cx);
+        cx.notify();
+    }
+
+    fn show_text_size_picker(&self, cx: &mut Context<Self>) {
+        platform_bridge::trigger_haptic(HapticFeedback::ImpactLight);
+        let mut buttons: Vec<AlertButton> = TextSize::ALL
+            .iter()
+            .map(|size| AlertButton::default(size.label()))
+            .collect();
+        buttons.push(AlertButton::cancel("Cancel"));
+        let (tx, rx) = oneshot::channel();
+        platform_bridge::show_selection(
+            "Text Size",
+            "Applies to menus, the editor, and terminals.",
+            buttons,
+            move |result| {
+                let _ = tx.send(result);
+            },
+        );
+        cx.spawn(async move |this, cx| {
+            let Ok(Some(index)) = rx.await else {
+                return;
+            };
+            let Some(size) = TextSize::ALL.get(index).copied() else {
+                return;
+            };
+            let _ = this.update(cx, |this, cx| this.set_text_size(size, cx));
+        })
+        .detach();
+    }
+
+    fn set_text_size(&mut self, size: TextSize, cx: &mut Context<Self>) {
+        if settings::text_size(cx) == size {
+            return;
+        }
+        platform_bridge::trigger_haptic(HapticFeedback::SelectionChanged);
+        settings::set_text_size(size, cx);
+        cx.notify();
+    }
+
+    fn set_code_font(&mut self, font: CodeFont, cx: &mut Context<Self>) {
+        if settings::code_font(cx) == font {
+            return;
+        }
+        platform_bridge::trigger_haptic(HapticFeedback::SelectionChanged);
+        settings::set_code_font(font, cx);
+        cx.notify();
+    }
+
     fn open_telemetry_docs(&self) {
         platform_bridge::trigger_haptic(HapticFeedback::ImpactLight);
         platform_bridge::bridge().open_url(TELEMETRY_DOCS_URL);
@@ -590,8 +822,51 @@ impl Render for SettingsView {
             "Sign In"
         };
         let preference = self.theme_state.read(cx).preference();
+        let imported_theme = self
+            .theme_state
+            .read(cx)
+            .imported_theme()
+            .map(|theme| (theme.name.clone(), theme.appearance));
         let telemetry_enabled = self.telemetry_enabled;
         let droplet_enabled = self.droplet_enabled;
+        let code_font = settings::code_font(cx);
+        let text_size = settings::text_size(cx);
+        let text_size_summary = match text_size {
+            TextSize::System => {
+                format!("System · {}%", (theme::text_scale() * 100.0).round() as u32)
+            }
+            size => size.label().to_string(),
+        };
+        let terminal_opacity = self.theme_state.read(cx).terminal_background_opacity();
+        let terminal_cursor = settings::terminal_cursor(cx);
+        let cursor_shape_listeners = TerminalCursorShape::ALL.map(|shape| {
+            cx.listener(move |this, _event, _window, cx| {
+                let cursor = TerminalCursor {
+                    shape,
+                    ..settings::terminal_cursor(cx)
+                };
+                this.set_terminal_cursor(cursor, cx);
+            })
+        });
+        let terminal_opacity_listeners = TERMINAL_OPACITY_CHOICES.map(|(_, _, choice)| {
+            cx.listener(move |this, _event, _window, cx| {
+                this.set_terminal_background_opacity(choice, cx);
+            })
+        });
+        let save_cleanup = settings::save_cleanup(cx);
+        let save_cleanup_listeners = SAVE_CLEANUP_OPTIONS.map(|option| {
+            [true, false].map(|enabled| {
+                cx.listener(move |this, _event, _window, cx| {
+                    this.set_save_cleanup(option.set, enabled, cx);
+                })
+            })
+        });
+        let data_saver_mode = settings::data_saver_mode(cx);
+        let data_saver_listeners = DataSaverMode::ALL.map(|mode| {
+            cx.listener(move |this, _event, _window, cx| {
+                this.set_data_saver_mode(mode, cx);
+            })
+        });
 
         div()
             .id("settings-view")
@@ -637,7 +912,7 @@ impl Render for SettingsView {
                             .child(
                                 div()
                                     .text_color(rgb(theme::text_primary(cx)))
-                                    .text_size(px(theme::FONT_TITLE))
+                                    .text_size(theme::scaled(theme::FONT_TITLE))
                                     .font_family(fonts::HEADING_FONT_FAMILY)
                                     .font_weight(FontWeight::MEDIUM)
                                     .child("Settings"),
@@ -707,6 +982,77 @@ impl Render for SettingsView {
                                     this.set_theme_preference(ThemePreference::Light, cx);
                                 }),
                             ))
+                            .when_some(imported_theme, |this, (name, appearance)| {
+                                let description = match appearance {
+                                    ThemePreference::Dark => "Imported · used in dark mode",
+                                    ThemePreference::Light => "Imported · used in light mode",
+                                };
+                                this.child(
+                                    action_row(
+                                        cx,
+                                        "settings-imported-theme",
+                                        name.clone(),
+                                        description,
+                                    )
+                                    .on_press(cx.listener(
+                                        move |this, _event, _window, cx| {
+                                            this.show_remove_imported_theme_confirmation(
+                                                name.clone(),
+                                                cx,
+                                            );
+                                        },
+                                    )),
+                                )
+                            })
+                            .child(
+                                action_row(
+                                    cx,
+                                    "settings-text-size",
+                                    "Text size",
+                                    text_size_summary,
+                                )
+                                .on_press(cx.listener(|this, _event, _window, cx| {
+                                    this.show_text_size_picker(cx);
+                                })),
+                            )
+                            .child(code_font_toggle(
+                                cx,
+                                code_font,
+                                cx.listener(|this, _event, _window, cx| {
+                                    this.set_code_font(CodeFont::JetBrainsMonoNerd, cx);
+                                }),
+                                cx.listener(|this, _event, _window, cx| {
+                                    this.set_code_font(CodeFont::System, cx);
+                                }),
+                            ))
+                            .child(cursor_shape_toggle(
+                                cx,
+                                terminal_cursor.shape,
+                                cursor_shape_listeners,
+                            ))
+                            .child(cursor_blink_toggle(
+                                cx,
+                                terminal_cursor.blinking,
+                                cx.listener(move |this, _event, _window, cx| {
+                                    let cursor = TerminalCursor {
+                                        blinking: true,
+                                        ..settings::terminal_cursor(cx)
+                                    };
+                                    this.set_terminal_cursor(cursor, cx);
+                                }),
+                                cx.listener(move |this, _event, _window, cx| {
+                                    let cursor = TerminalCursor {
+                                        blinking: false,
+                                        ..settings::terminal_cursor(cx)
+                                    };
+                                    this.set_terminal_cursor(cursor, cx);
+                                }),
+                            ))
+                            .child(terminal_opacity_toggle(
+                                cx,
+                                terminal_opacity,
+                                terminal_opacity_listeners,
+                            ))
                             .when(cfg!(target_os = "ios"), |this| {
                                 this.child(droplet_toggle(
                                     cx,
@@ -719,6 +1065,35 @@ impl Render for SettingsView {
                                     }),
                                 ))
                             })
+                            .child(section_header(cx, "Editor"))
+                            .children(
+                                SAVE_CLEANUP_OPTIONS
+                                    .into_iter()
+                                    .zip(save_cleanup_listeners)
+                                    .map(|(option, [on_enable, on_disable])| {
+                                        save_cleanup_toggle(
+                                            cx,
+                                            &option,
+                                            (option.get)(&save_cleanup),
+                                            on_enable,
+                                            on_disable,
+                                        )
+                                    }),
+                            )
+                            .child(section_header(cx, "Network"))
+                            .child(data_saver_toggle(cx, data_saver_mode, data_saver_listeners))
+                            .child(section_header(cx, "AI"))
+                            .child(
+                                action_row(
+                                    cx,
+                                    "settings-ai-tool-grants",
+                                    "Always-allowed tools",
+                                    "Tools the host AI runs without asking",
+                                )
+                                .on_press(cx.listener(|this, _event, _window, cx| {
+                                    this.show_ai_tool_grants(cx);
+                                })),
+                            )
                             .child(section_header(cx, "Privacy"))
                             .child(telemetry_toggle(
                                 cx,
@@ -752,6 +1127,17 @@ impl Render for SettingsView {
                                     this.open_privacy_policy();
                                 })),
                             )
+                            .child(
+                                action_row(
+                                    cx,
+                                    "settings-wipe-secrets",
+                                    "Wipe secrets",
+                                    "Delete this device's identity and sign-in",
+                                )
+                                .on_press(cx.listener(|this, _event, _window, cx| {
+                                    this.show_wipe_secrets_confirmation(cx);
+                                })),
+                            )
                             .when(cfg!(debug_assertions), |section| {
                                 section
                                     .child(section_header(cx, "Developer"))
@@ -810,10 +1196,21 @@ impl Render for SettingsView {
                                             cx.emit(SettingsEvent::OpenWebTunnel);
                                         })),
                                     )
+                                    .child(
+                                        action_row(
+                                            cx,
+                                            "settings-mock-host",
+                                            "Mock host",
+                                            "Open a workspace served in-process, no host needed",
+                                        )
+                                        .on_press(cx.listener(|_this, _event, _window, cx| {
+                                            cx.emit(SettingsEvent::OpenMockHost);
+                                        })),
+                                    )
                                     .child(
                                         div()
                                             .text_color(rgb(theme::text_muted(cx)))
-                                            .text_size(px(theme::FONT_DETAIL))
+                                            .text_size(theme::scaled(theme::FONT_DETAIL))
                                             .font_family(fonts::MONO_FONT_FAMILY)
                                             .child(
                                                 "QR scanner and dictation preview remain separate native flows.",
@@ -834,7 +1231,7 @@ fn section_header(cx: &App, title: &'static str) -> Div {
         .child(
             div()
                 .text_color(rgb(theme::text_primary(cx)))
-                .text_size(px(theme::FONT_HEADING))
+                .text_size(theme::scaled(theme::FONT_HEADING))
                 .font_family(fonts::MONO_FONT_FAMILY)
                 .font_weight(FontWeight::MEDIUM)
                 .child(title),
@@ -869,7 +1266,7 @@ fn appearance_theme_toggle(
                 .child(
                     div()
                         .text_color(rgb(theme::text_secondary(cx)))
-                        .text_size(px(theme::FONT_BODY))
+                        .text_size(theme::scaled(theme::FONT_BODY))
                         .font_family(fonts::MONO_FONT_FAMILY)
                         .font_weight(FontWeight::MEDIUM)
                         .child("Theme"),
@@ -963,7 +1360,7 @@ fn telemetry_toggle(
                     .flex()
                     .items_center()
                     .justify_center()
-                    .text_size(px(theme::FONT_DETAIL))
+                    .text_size(theme::scaled(theme::FONT_DETAIL))
                     .font_family(fonts::MONO_FONT_FAMILY)
                     .font_weight(FontWeight::MEDIUM)
                     .text_color(rgb(theme::text_muted(cx)))
@@ -1022,6 +1419,302 @@ fn droplet_toggle(
     )
 }
 
+fn code_font_toggle(
+    cx: &App,
+    font: CodeFont,
+    on_bundled: impl Fn(&PressEvent, &mut Window, &mut App) + 'static,
+    on_system: impl Fn(&PressEvent, &mut Window, &mut App) + 'static,
+) -> impl IntoElement {
+    let control = div()
+        .flex_none()
+        .rounded(px(8.0))
+        .border_1()
+        .border_color(rgb(theme::border_default(cx)))
+        .bg(rgb(theme::bg_surface(cx)))
+        .flex()
+        .flex_row()
+        .child(toggle_segment(
+            cx,
+            "settings-code-font-bundled",
+            CodeFont::JetBrainsMonoNerd.label(),
+            font == CodeFont::JetBrainsMonoNerd,
+            on_bundled,
+        ))
+        .child(
+            div()
+                .w(px(1.0))
+                .h(px(22.0))
+                .bg(rgb(theme::border_subtle(cx))),
+        )
+        .child(toggle_segment(
+            cx,
+            "settings-code-font-system",
+            CodeFont::System.label(),
+            font == CodeFont::System,
+            on_system,
+        ))
+        .into_any_element();
+    toggle_row(
+        cx,
+        "settings-code-font-toggle",
+        "Code font",
+        "Terminal and editor text",
+        theme::text_secondary(cx),
+        control,
+    )
+}
+
+fn cursor_shape_toggle(
+    cx: &App,
+    selected: TerminalCursorShape,
+    on_select: [impl Fn(&PressEvent, &mut Window, &mut App) + 'static; 3],
+) -> impl IntoElement {
+    let mut control = div()
+        .flex_none()
+        .rounded(px(8.0))
+        .border_1()
+        .border_color(rgb(theme::border_default(cx)))
+        .bg(rgb(theme::bg_surface(cx)))
+        .flex()
+        .flex_row();
+    let ids = [
+        "settings-cursor-block",
+        "settings-cursor-underline",
+        "settings-cursor-bar",
+    ];
+    for (index, ((shape, id), on_select)) in TerminalCursorShape::ALL
+        .into_iter()
+        .zip(ids)
+        .zip(on_select)
+        .enumerate()
+    {
+        if index > 0 {
+            control = control.child(
+                div()
+                    .w(px(1.0))
+                    .h(px(22.0))
+                    .bg(rgb(theme::border_subtle(cx))),
+            );
+        }
+        control = control.child(toggle_segment(
+            cx,
+            id,
+            shape.label(),
+            shape == selected,
+            on_select,
+        ));
+    }
+    toggle_row(
+        cx,
+        "settings-cursor-shape-toggle",
+        "Cursor",
+        "Until a program sets its own",
+        theme::text_secondary(cx),
+        control.into_any_element(),
+    )
+}
+
+fn cursor_blink_toggle(
+    cx: &App,
+    enabled: bool,
+    on_enable: impl Fn(&PressEvent, &mut Window, &mut App) + 'static,
+    on_disable: impl Fn(&PressEvent, &mut Window, &mut App) + 'static,
+) -> impl IntoElement {
+    let control = segmented_toggle(
+        cx,
+        "settings-cursor-blink-on",
+        "settings-cursor-blink-off",
+        enabled,
+        on_enable,
+        on_disable,
+    );
+    toggle_row(
+        cx,
+        "settings-cursor-blink-toggle",
+        "Cursor blink",
+        "Blink the default terminal cursor",
+        theme::text_secondary(cx),
+        control,
+    )
+}
+
+fn data_saver_toggle(
+    cx: &App,
+    selected: DataSaverMode,
+    on_select: [impl Fn(&PressEvent, &mut Window, &mut App) + 'static; 3],
+) -> impl IntoElement {
+    let mut control = div()
+        .flex_none()
+        .rounded(px(8.0))
+        .border_1()
+        .border_color(rgb(theme::border_default(cx)))
+        .bg(rgb(theme::bg_surface(cx)))
+        .flex()
+        .flex_row();
+    let ids = [
+        "settings-data-saver-auto",
+        "settings-data-saver-on",
+        "settings-data-saver-off",
+    ];
+    for (index, ((mode, id), on_select)) in DataSaverMode::ALL
+        .into_iter()
+        .zip(ids)
+        .zip(on_select)
+        .enumerate()
+    {
+        if index > 0 {
+            control = control.child(
+                div()
+                    .w(px(1.0))
+                    .h(px(22.0))
+                    .bg(rgb(theme::border_subtle(cx))),
+            );
+        }
+        control = control.child(toggle_segment(
+            cx,
+            id,
+            mode.label(),
+            mode == selected,
+            on_select,
+        ));
+    }
+    toggle_row(
+        cx,
+        "settings-data-saver-toggle",
+        "Data saver",
+        "Auto turns on for metered networks",
+        theme::text_secondary(cx),
+        control.into_any_element(),
+    )
+}
+
+/// Terminal background opacity choices shown in Settings.
+const TERMINAL_OPACITY_CHOICES: [(&str, &str, f32); 3] = [
+    ("settings-terminal-opacity-100", "100%", 1.0),
+    ("settings-terminal-opacity-85", "85%", 0.85),
+    ("settings-terminal-opacity-70", "70%", 0.7),
+];
+
+fn terminal_opacity_toggle(
+    cx: &App,
+    opacity: f32,
+    on_select: [impl Fn(&PressEvent, &mut Window, &mut App) + 'static; 3],
+) -> impl IntoElement {
+    let mut control = div()
+        .flex_none()
+        .rounded(px(8.0))
+        .border_1()
+        .border_color(rgb(theme::border_default(cx)))
+        .bg(rgb(theme::bg_surface(cx)))
+        .flex()
+        .flex_row();
+    for (index, ((id, label, choice), on_select)) in TERMINAL_OPACITY_CHOICES
+        .into_iter()
+        .zip(on_select)
+        .enumerate()
+    {
+        if index > 0 {
+            control = control.child(
+                div()
+                    .w(px(1.0))
+                    .h(px(22.0))
+                    .bg(rgb(theme::border_subtle(cx))),
+            );
+        }
+        control = control.child(toggle_segment(
+            cx,
+            id,
+            label,
+            (opacity - choice).abs() < 0.01,
+            on_select,
+        ));
+    }
+    toggle_row(
+        cx,
+        "settings-terminal-opacity-toggle",
+        "Terminal background",
+        "Opacity behind terminal text",
+        theme::text_secondary(cx),
+        control.into_any_element(),
+    )
+}
+
+/// One on/off row in the Editor section's save cleanup settings.
+#[derive(Clone, Copy)]
+struct SaveCleanupOption {
+    id: &'static str,
+    on_id: &'static str,
+    off_id: &'static str,
+    title: &'static str,
+    description: &'static str,
+    get: fn(&SaveCleanup) -> bool,
+    set: fn(&mut SaveCleanup, bool),
+}
+
+const SAVE_CLEANUP_OPTIONS: [SaveCleanupOption; 4] = [
+    SaveCleanupOption {
+        id: "settings-trim-whitespace-toggle",
+        on_id: "settings-trim-whitespace-on",
+        off_id: "settings-trim-whitespace-off",
+        title: "Trim trailing whitespace",
+        description: "Remove spaces at line ends on save",
+        get: |cleanup| cleanup.trim_trailing_whitespace,
+        set: |cleanup, enabled| cleanup.trim_trailing_whitespace = enabled,
+    },
+    SaveCleanupOption {
+        id: "settings-final-newline-toggle",
+        on_id: "settings-final-newline-on",
+        off_id: "settings-final-newline-off",
+        title: "Final newline",
+        description: "End files with a newline on save",
+        get: |cleanup| cleanup.insert_final_newline,
+        set: |cleanup, enabled| cleanup.insert_final_newline = enabled,
+    },
+    SaveCleanupOption {
+        id: "settings-normalize-indent-toggle",
+        on_id: "settings-normalize-indent-on",
+        off_id: "settings-normalize-indent-off",
+        title: "Normalize indentation",
+        description: "Rewrite mixed tabs and spaces on save",
+        get: |cleanup| cleanup.normalize_indentation,
+        set: |cleanup, enabled| cleanup.normalize_indentation = enabled,
+    },
+    SaveCleanupOption {
+        id: "settings-save-preview-toggle",
+        on_id: "settings-save-preview-on",
+        off_id: "settings-save-preview-off",
+        title: "Preview cleanup",
+        description: "Show changed lines before saving",
+        get: |cleanup| cleanup.preview,
+        set: |cleanup, enabled| cleanup.preview = enabled,
+    },
+];
+
+fn save_cleanup_toggle(
+    cx: &App,
+    option: &SaveCleanupOption,
+    enabled: bool,
+    on_enable: impl Fn(&PressEvent, &mut Window, &mut App) + 'static,
+    on_disable: impl Fn(&PressEvent, &mut Window, &mut App) + 'static,
+) -> AnyElement {
+    let control = segmented_toggle(
+        cx,
+        option.on_id,
+        option.off_id,
+        enabled,
+        on_enable,
+        on_disable,
+    );
+    toggle_row(
+        cx,
+        option.id,
+        option.title,
+        option.description,
+        theme::text_secondary(cx),
+        control,
+    )
+}
+
 fn segmented_toggle(
     cx: &App,
     on_id: &'static str,
@@ -1077,7 +1770,7 @@ fn toggle_row(
                 .child(
                     div()
                         .text_color(rgb(title_color))
-                        .text_size(px(theme::FONT_BODY))
+                        .text_size(theme::scaled(theme::FONT_BODY))
                         .font_family(fonts::MONO_FONT_FAMILY)
                         .font_weight(FontWeight::MEDIUM)
                         .child(title),
@@ -1085,7 +1778,7 @@ fn toggle_row(
                 .child(
                     div()
                         .text_color(rgb(theme::text_muted(cx)))
-                        .text_size(px(theme::FONT_DETAIL))
+                        .text_size(theme::scaled(theme::FONT_DETAIL))
                         .font_family(fonts::MONO_FONT_FAMILY)
                         .child(description),
                 ),
@@ -1118,7 +1811,7 @@ fn toggle_segment(
 
     segment.child(
         div()
-            .text_size(px(theme::FONT_DETAIL))
+            .text_size(theme::scaled(theme::FONT_DETAIL))
             .font_family(fonts::MONO_FONT_FAMILY)
             .font_weight(FontWeight::MEDIUM)
             .text_color(rgb(if selected {
@@ -1158,7 +1851,7 @@ fn action_row(
                 .child(
                     div()
                         .text_color(rgb(theme::text_secondary(cx)))
-                        .text_size(px(theme::FONT_BODY))
+                        .text_size(theme::scaled(theme::FONT_BODY))
                         .font_family(fonts::MONO_FONT_FAMILY)
                         .font_weight(FontWeight::MEDIUM)
                         .child(title),
@@ -1166,7 +1859,7 @@ fn action_row(
                 .child(
                     div()
                         .text_color(rgb(theme::text_muted(cx)))
-                        .text_size(px(theme::FONT_DETAIL))
+                        .text_size(theme::scaled(theme::FONT_DETAIL))
                         .font_family(fonts::MONO_FONT_FAMILY)
                         .child(description),
                 ),
@@ -1226,7 +1919,7 @@ fn profile_info_row(
                 .items_center()
                 .justify_center()
                 .text_color(rgb(theme::text_secondary(cx)))
-                .text_size(px(theme::FONT_BODY))
+                .text_size(theme::scaled(theme::FONT_BODY))
                 .font_family(fonts::MONO_FONT_FAMILY)
                 .font_weight(FontWeight::MEDIUM)
                 .child(initials),
@@ -1242,7 +1935,7 @@ fn profile_info_row(
                 .child(
                     div()
                         .text_color(rgb(theme::text_secondary(cx)))
-                        .text_size(px(theme::FONT_BODY))
+                        .text_size(theme::scaled(theme::FONT_BODY))
                         .font_family(fonts::MONO_FONT_FAMILY)
                         .font_weight(FontWeight::MEDIUM)
                         .child(title),
@@ -1250,7 +1943,7 @@ fn profile_info_row(
                 .child(
                     div()
                         .text_color(rgb(theme::text_muted(cx)))
-                        .text_size(px(theme::FONT_DETAIL))
+                        .text_size(theme::scaled(theme::FONT_DETAIL))
                         .font_family(fonts::MONO_FONT_FAMILY)
                         .child(description),
                 ),
@@ -1285,6 +1978,18 @@ fn logout_button(
         )
 }
 
+/// Saved workspaces' host names by endpoint id, for labelling AI tool grants.
+fn saved_host_names() -> Vec<(String, String)> {
+    WorkspaceState::load()
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|workspace| {
+            let addr = zedra_rpc::pairing::decode_endpoint_addr(&workspace.endpoint_addr).ok()?;
+            Some((addr.id.to_string(), workspace.hostname))
+        })
+        .collect()
+}
+
 fn short_id(id: uuid::Uuid) -> String {
     id.to_string().chars().take(8).collect()
 }
@@ -41,7 +41,7 @@ impl Render for SheetDemoView {
                     .child(
                         div()
                             .text_color(rgb(theme::text_primary(cx)))
-                            .text_size(px(theme::FONT_BODY))
+                            .text_size(theme::scaled(theme::FONT_BODY))
                             .font_family(fonts::MONO_FONT_FAMILY)
                             .font_weight(FontWeight::MEDIUM)
                             .child(state.title.clone()),
@@ -49,7 +49,7 @@ impl Render for SheetDemoView {
                     .child(
                         div()
                             .text_color(rgb(theme::text_muted(cx)))
-                            .text_size(px(theme::FONT_DETAIL))
+                            .text_size(theme::scaled(theme::FONT_DETAIL))
                             .font_family(fonts::MONO_FONT_FAMILY)
                             .child(state.subtitle.clone()),
                     )
@@ -68,14 +68,14 @@ impl Render for SheetDemoView {
                             .child(
                                 div()
                                     .text_color(rgb(theme::accent_blue(cx)))
-                                    .text_size(px(theme::FONT_DETAIL))
+                                    .text_size(theme::scaled(theme::FONT_DETAIL))
                                     .font_family(fonts::MONO_FONT_FAMILY)
                                     .child("// Mock presentation entrypoint"),
                             )
                             .children(state.mock_code.iter().cloned().map(|line| {
                                 div()
                                     .text_color(rgb(theme::text_secondary(cx)))
-                                    .text_size(px(theme::FONT_DETAIL))
+                                    .text_size(theme::scaled(theme::FONT_DETAIL))
                                     .font_family(fonts::MONO_FONT_FAMILY)
                                     .child(line)
                             })),
@@ -83,7 +83,7 @@ impl Render for SheetDemoView {
                     .child(
                         div()
                             .text_color(rgb(theme::text_muted(cx)))
-                            .text_size(px(theme::FONT_DETAIL))
+                            .text_size(theme::scaled(theme::FONT_DETAIL))
                             .font_family(fonts::MONO_FONT_FAMILY)
                             .child(format!(
                                 "Use this view to validate GPUI layout inside native sheet gestures and detents. launches={}",
@@ -0,0 +1,111 @@
+// Cold-start timing marks.
+//
+// `begin()` runs first thing in the platform launch entry (`ios/app.rs`,
+// `android/entry.rs`); each later phase calls `mark()`. Every mark logs the
+// time since launch and since the previous mark, and `finish()` logs one
+// summary line once the app is interactive:
+//
+//   startup: ready in 412ms (platform 38ms, app 61ms, window 95ms, first_frame 140ms, deferred 78ms)
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+#[derive(Debug)]
+struct Timeline {
+    start: Instant,
+    last: Instant,
+    phases: Vec<(&'static str, Duration)>,
+    finished: bool,
+}
+
+impl Timeline {
+    fn new(start: Instant) -> Self {
+        Self {
+            start,
+            last: start,
+            phases: Vec::new(),
+            finished: false,
+        }
+    }
+
+    /// Record `phase` at `now`. Returns `(since_launch, since_previous)`.
+    fn record(&mut self, phase: &'static str, now: Instant) -> (Duration, Duration) {
+        let delta = now.saturating_duration_since(self.last);
+        self.last = now;
+        self.phases.push((phase, delta));
+        (now.saturating_duration_since(self.start), delta)
+    }
+
+    fn summary(&self) -> String {
+        let total = self.last.saturating_duration_since(self.start);
+        let phases = self
+            .phases
+            .iter()
+            .map(|(phase, delta)| format!("{phase} {}ms", delta.as_millis()))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("ready in {}ms ({phases})", total.as_millis())
+    }
+}
+
+static TIMELINE: Mutex<Option<Timeline>> = Mutex::new(None);
+
+/// Start the cold-start clock. Later calls are ignored.
+pub fn begin() {
+    let mut timeline = TIMELINE.lock().unwrap_or_else(|e| e.into_inner());
+    if timeline.is_none() {
+        *timeline = Some(Timeline::new(Instant::now()));
+    }
+}
+
+/// Log the end of a startup phase. No-op before `begin()` or after `finish()`.
+pub fn mark(phase: &'static str) {
+    let mut timeline = TIMELINE.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(timeline) = timeline.as_mut().filter(|t| !t.finished) else {
+        return;
+    };
+    let (total, delta) = timeline.record(phase, Instant::now());
+    tracing::info!(
+        "startup: {phase} at {}ms (+{}ms)",
+        total.as_millis(),
+        delta.as_millis()
+    );
+}
+
+/// Record the final phase and log the summary. Only the first call logs.
+pub fn finish(phase: &'static str) {
+    mark(phase);
+    let mut timeline = TIMELINE.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(timeline) = timeline.as_mut().filter(|t| !t.finished) else {
+        return;
+    };
+    timeline.finished = true;
+    tracing::info!("startup: {}", timeline.summary());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timeline_reports_per_phase_deltas_and_total() {
+        let start = Instant::now();
+        let mut timeline = Timeline::new(start);
+        let at = |ms| start + Duration::from_millis(ms);
+
+        assert_eq!(
+            timeline.record("platform", at(40)),
+            (Duration::from_millis(40), Duration::from_millis(40))
+        );
+        assert_eq!(
+            timeline.record("first_frame", at(100)),
+            (Duration::from_millis(100), Duration::from_millis(60))
+        );
+        timeline.record("deferred", at(130));
+
+        assert_eq!(
+            timeline.summary(),
+            "ready in 130ms (platform 40ms, first_frame 60ms, deferred 30ms)"
+        );
+    }
+}
@@ -180,6 +180,20 @@ pub mod view_telemetry {
         "Workspace Agent Detail",
         "AgentDetail",
     );
+    pub const WORKSPACE_PROBLEMS: ViewDescriptor =
+        ViewDescriptor::new("workspace_problems", "Workspace Problems", "ProblemsView");
+    pub const WORKSPACE_GIT_GRAPH: ViewDescriptor =
+        ViewDescriptor::new("workspace_git_graph", "Workspace Git Graph", "GitGraphView");
+    pub const WORKSPACE_FILE_HISTORY: ViewDescriptor = ViewDescriptor::new(
+        "workspace_file_history",
+        "Workspace File History",
+        "FileHistoryView",
+    );
+    pub const WORKSPACE_RECORDING: ViewDescriptor = ViewDescriptor::new(
+        "workspace_recording",
+        "Workspace Recording",
+        "RecordingPlayer",
+    );
     pub const WORKSPACE_START: ViewDescriptor =
         ViewDescriptor::new("workspace_start", "Workspace Start", "WorkspaceStart");
 
@@ -206,6 +220,31 @@ pub mod view_telemetry {
     );
     pub const CUSTOM_SHEET_DEMO: ViewDescriptor =
         ViewDescriptor::new("custom_sheet_demo", "Custom Sheet Demo", "SheetDemoView");
+    pub const CUSTOM_SHEET_BRANCH_PICKER: ViewDescriptor = ViewDescriptor::new(
+        "custom_sheet_branch_picker",
+        "Custom Sheet Branch Picker",
+        "BranchPicker",
+    );
+    pub const CUSTOM_SHEET_REBASE_PLANNER: ViewDescriptor = ViewDescriptor::new(
+        "custom_sheet_rebase_planner",
+        "Custom Sheet Rebase Planner",
+        "RebasePlanner",
+    );
+    pub const CUSTOM_SHEET_COMMIT_DETAIL: ViewDescriptor = ViewDescriptor::new(
+        "custom_sheet_commit_detail",
+        "Custom Sheet Commit Detail",
+        "CommitDetailSheet",
+    );
+    pub const CUSTOM_SHEET_AI_EDIT: ViewDescriptor = ViewDescriptor::new(
+        "custom_sheet_ai_edit",
+        "Custom Sheet AI Edit",
+        "AiEditSheet",
+    );
+    pub const CUSTOM_SHEET_FILE_BATCH: ViewDescriptor = ViewDescriptor::new(
+        "custom_sheet_file_batch",
+        "Custom Sheet File Batch",
+        "FileBatchSheet",
+    );
 
     pub fn record(screen: ViewDescriptor) {
         zedra_telemetry::send(zedra_telemetry::Event::ScreenView {
@@ -232,6 +271,10 @@ pub mod view_telemetry {
             WorkspaceMainView::AgentSessions => Some(WORKSPACE_AGENT_SESSIONS),
             WorkspaceMainView::AgentManage => Some(WORKSPACE_AGENT_MANAGE),
             WorkspaceMainView::AgentDetail { .. } => Some(WORKSPACE_AGENT_DETAIL),
+            WorkspaceMainView::Problems => Some(WORKSPACE_PROBLEMS),
+            WorkspaceMainView::GitGraph => Some(WORKSPACE_GIT_GRAPH),
+            WorkspaceMainView::FileHistory { .. } => Some(WORKSPACE_FILE_HISTORY),
+            WorkspaceMainView::Recording { .. } => Some(WORKSPACE_RECORDING),
         }
     }
 
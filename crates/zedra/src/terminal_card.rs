@@ -240,7 +240,7 @@ pub fn render_terminal_card(cx: &App, props: TerminalCardProps) -> Stateful<Div>
                         } else {
                             rgb(theme::text_secondary(cx))
                         })
-                        .text_size(px(theme::FONT_BODY))
+                        .text_size(theme::scaled(theme::FONT_BODY))
                         .when(is_active, |s| s.font_weight(FontWeight::MEDIUM))
                         .child(label),
                 )
@@ -252,7 +252,7 @@ pub fn render_terminal_card(cx: &App, props: TerminalCardProps) -> Stateful<Div>
                         .whitespace_nowrap()
                         .font_family(fonts::MONO_FONT_FAMILY)
                         .text_color(rgb(theme::text_muted(cx)))
-                        .text_size(px(theme::FONT_BODY - 1.0))
+                        .text_size(theme::scaled(theme::FONT_BODY - 1.0))
                         .when(!has_subtitle, |s| s.invisible())
                         .child(subtitle),
                 ),
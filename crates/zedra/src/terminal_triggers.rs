@@ -0,0 +1,297 @@
+// Terminal triggers: per-host rules that act on matching terminal output.
+//
+// Rules live on `WorkspaceState::terminal_triggers`, so they persist with the
+// saved workspace for a host and apply to every terminal on it. Matching and
+// the highlight/reply actions run inside `zedra_terminal::Terminal`; the app
+// posts notifications and copies captures on `TerminalEvent::TriggerFired`.
+//
+// The editor is a chain of native dialogs. Each callback hands the next step
+// back to the workspace through `PendingWorkspaceAction::EditTerminalTriggers`
+// so every dialog is presented from the GPUI thread.
+
+use gpui::*;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use zedra_terminal::{TriggerAction, TriggerFired, TriggerRule};
+
+use crate::pending::SharedPendingSlot;
+use crate::platform_bridge::{
+    self, AlertButton, ListPickerItem, NativeNotificationKind, NativeNotificationOptions,
+};
+use crate::workspace::PendingWorkspaceAction;
+use crate::workspace_state::WorkspaceState;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TerminalTrigger {
+    /// Regex matched against each output line with escape sequences removed.
+    pub pattern: String,
+    pub action: TerminalTriggerAction,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TerminalTriggerAction {
+    Highlight,
+    Notify,
+    /// Typed followed by Enter. Stored in plain text with the workspace list.
+    Reply {
+        text: String,
+    },
+    /// Capture group to copy; 0 copies the whole match.
+    Copy {
+        group: usize,
+    },
+}
+
+impl TerminalTrigger {
+    fn rule(&self) -> Result<TriggerRule, String> {
+        let action = match &self.action {
+            TerminalTriggerAction::Highlight => TriggerAction::Highlight,
+            TerminalTriggerAction::Notify => TriggerAction::Notify,
+            TerminalTriggerAction::Reply { text } => TriggerAction::Reply(text.clone()),
+            TerminalTriggerAction::Copy { group } => TriggerAction::CopyCapture(*group),
+        };
+        TriggerRule::new(self.pattern.clone(), &self.pattern, action).map_err(|err| err.to_string())
+    }
+
+    /// Row subtitle in the trigger list. Reply text is never shown.
+    fn summary(&self) -> String {
+        match &self.action {
+            TerminalTriggerAction::Highlight => "Highlight line".into(),
+            TerminalTriggerAction::Notify => "Notify".into(),
+            TerminalTriggerAction::Reply { .. } => "Auto-reply".into(),
+            TerminalTriggerAction::Copy { group: 0 } => "Copy match".into(),
+            TerminalTriggerAction::Copy { group } => format!("Copy group {group}"),
+        }
+    }
+}
+
+/// Compile the host's triggers for a terminal. Invalid patterns are skipped;
+/// the editor rejects them, so they only come from a hand-edited store.
+pub fn compile(triggers: &[TerminalTrigger]) -> Vec<TriggerRule> {
+    triggers
+        .iter()
+        .filter_map(|trigger| {
+            trigger
+                .rule()
+                .map_err(|err| warn!(pattern = %trigger.pattern, %err, "invalid terminal trigger"))
+                .ok()
+        })
+        .collect()
+}
+
+/// Handle the app-side actions of a fired trigger.
+pub fn handle_fired(fired: &TriggerFired, terminal_title: &str, cx: &mut App) {
+    match &fired.action {
+        TriggerAction::Notify => {
+            platform_bridge::show_native_notification(
+                NativeNotificationOptions::new(terminal_title)
+                    .message(fired.line.clone())
+                    .kind(NativeNotificationKind::Warning),
+            );
+        }
+        TriggerAction::CopyCapture(_) => {
+            if let Some(capture) = &fired.capture {
+                cx.write_to_clipboard(ClipboardItem::new_string(capture.clone()));
+                platform_bridge::show_native_notification(
+                    NativeNotificationOptions::new("Copied from terminal")
+                        .message(capture.clone())
+                        .kind(NativeNotificationKind::Success),
+                );
+            }
+        }
+        TriggerAction::Highlight | TriggerAction::Reply(_) => {}
+    }
+}
+
+pub(crate) enum TriggerEditStep {
+    List,
+    AddPattern,
+    ChooseAction { pattern: String },
+    ReplyText { pattern: String },
+    Save(TerminalTrigger),
+    ConfirmRemove { index: usize },
+    Remove { index: usize },
+}
+
+/// Present one step of the trigger editor.
+pub(crate) fn run_edit_step(
+    step: TriggerEditStep,
+    workspace_state: &Entity<WorkspaceState>,
+    pending: &SharedPendingSlot<PendingWorkspaceAction>,
+    cx: &mut App,
+) {
+    let pending = pending.clone();
+    let next = move |step: TriggerEditStep| {
+        pending.set(PendingWorkspaceAction::EditTerminalTriggers(step))
+    };
+    match step {
+        TriggerEditStep::List => {
+            let triggers = workspace_state.read(cx).terminal_triggers.clone();
+            let count = triggers.len();
+            let mut items: Vec<ListPickerItem> = triggers
+                .iter()
+                .map(|trigger| ListPickerItem {
+                    label: trigger.pattern.clone(),
+                    subtitle: Some(trigger.summary()),
+                    image_name: None,
+                    trailing_icon: None,
+                })
+                .collect();
+            items.push(ListPickerItem {
+                label: "Add Trigger".into(),
+                subtitle: None,
+                image_name: None,
+                trailing_icon: None,
+            });
+            platform_bridge::show_list_picker(
+                "Terminal Triggers",
+                "Act on terminal output from this host. Tap a trigger to remove it.",
+                items,
+                move |selection| {
+                    let Some(selection) = selection else { return };
+                    if selection.index == count {
+                        next(TriggerEditStep::AddPattern);
+                    } else {
+                        next(TriggerEditStep::ConfirmRemove {
+                            index: selection.index,
+                        });
+                    }
+                },
+            );
+        }
+        TriggerEditStep::AddPattern => {
+            platform_bridge::show_text_input(
+                "New Trigger",
+                "Regex, e.g. BUILD FAILED",
+                "",
+                move |result| {
+                    let Some(pattern) = result.filter(|p| !p.trim().is_empty()) else {
+                        return;
+                    };
+                    next(TriggerEditStep::ChooseAction { pattern });
+                },
+            );
+        }
+        TriggerEditStep::ChooseAction { pattern } => {
+            let groups = match TriggerRule::new("", &pattern, TriggerAction::Notify) {
+                Ok(rule) => rule.pattern.captures_len(),
+                Err(err) => {
+                    platform_bridge::show_alert(
+                        "Invalid pattern",
+                        &err.to_string(),
+                        vec![AlertButton::cancel("OK")],
+                        |_| {},
+                    );
+                    return;
+                }
+            };
+            let buttons = vec![
+                AlertButton::default("Highlight Line"),
+                AlertButton::default("Notify"),
+                AlertButton::default("Auto-Reply"),
+                AlertButton::default("Copy Match"),
+                AlertButton::cancel("Cancel"),
+            ];
+            let message = pattern.clone();
+            platform_bridge::show_selection(
+                "When output matches",
+                &message,
+                buttons,
+                move |selection| {
+                    let action = match selection {
+                        Some(0) => TerminalTriggerAction::Highlight,
+                        Some(1) => TerminalTriggerAction::Notify,
+                        Some(2) => {
+                            next(TriggerEditStep::ReplyText { pattern });
+                            return;
+                        }
+                        // Copy the first group when the pattern has one.
+                        Some(3) => TerminalTriggerAction::Copy {
+                            group: usize::from(groups > 1),
+                        },
+                        _ => return,
+                    };
+                    next(TriggerEditStep::Save(TerminalTrigger { pattern, action }));
+                },
+            );
+        }
+        TriggerEditStep::ReplyText { pattern } => {
+            platform_bridge::show_text_input(
+                "Auto-Reply",
+                "Text to type, then Enter",
+                "",
+                move |result| {
+                    let Some(text) = result else { return };
+                    next(TriggerEditStep::Save(TerminalTrigger {
+                        pattern,
+                        action: TerminalTriggerAction::Reply { text },
+                    }));
+                },
+            );
+        }
+        TriggerEditStep::Save(trigger) => {
+            workspace_state.update(cx, |state, cx| state.add_terminal_trigger(trigger, cx));
+        }
+        TriggerEditStep::ConfirmRemove { index } => {
+            let Some(trigger) = workspace_state
+                .read(cx)
+                .terminal_triggers
+                .get(index)
+                .cloned()
+            else {
+                return;
+            };
+            platform_bridge::show_alert(
+                "Remove trigger?",
+                &trigger.pattern,
+                vec![
+                    AlertButton::destructive("Remove"),
+                    AlertButton::cancel("Cancel"),
+                ],
+                move |button_index| {
+                    if button_index == 0 {
+                        next(TriggerEditStep::Remove { index });
+                    }
+                },
+            );
+        }
+        TriggerEditStep::Remove { index } => {
+            workspace_state.update(cx, |state, cx| state.remove_terminal_trigger(index, cx));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn triggers_roundtrip_and_skip_invalid_patterns() {
+        let triggers = vec![
+            TerminalTrigger {
+                pattern: "BUILD FAILED".into(),
+                action: TerminalTriggerAction::Notify,
+            },
+            TerminalTrigger {
+                pattern: "(unclosed".into(),
+                action: TerminalTriggerAction::Highlight,
+            },
+            TerminalTrigger {
+                pattern: "[Pp]assword:".into(),
+                action: TerminalTriggerAction::Reply {
+                    text: "secret".into(),
+                },
+            },
+        ];
+        let json = serde_json::to_string(&triggers).unwrap();
+        assert!(json.contains(r#""kind":"reply""#));
+        let decoded: Vec<TerminalTrigger> = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, triggers);
+
+        let rules = compile(&triggers);
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[1].action, TriggerAction::Reply("secret".into()));
+        assert_eq!(triggers[2].summary(), "Auto-reply");
+    }
+}
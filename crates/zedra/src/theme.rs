@@ -3,7 +3,9 @@
 //! New product UI must use these tokens (via `theme::palette(cx)` and accessors below),
 //! not hardcoded colors in views. See `docs/THEMING.md`.
 
-use gpui::Hsla;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use gpui::{Hsla, Pixels, px};
 use zedra_terminal::TerminalTheme;
 
 use crate::editor::syntax_theme::SyntaxTheme;
@@ -25,6 +27,11 @@ pub const SPACING_XL: f32 = 20.0;
 pub const SUBSCREEN_PADDING_X: f32 = SPACING_MD + SPACING_XS;
 
 pub const HEADER_HEIGHT: f32 = 48.0;
+/// Windows wider than tall and shorter than this (a phone in landscape) hide
+/// the workspace header behind a reveal handle.
+pub const COMPACT_VIEWPORT_MAX_HEIGHT: f32 = 500.0;
+/// Height of the handle that stands in for the hidden header.
+pub const COMPACT_HEADER_HANDLE_HEIGHT: f32 = 14.0;
 pub const HOME_CARD_WIDTH: f32 = 300.0;
 pub const HOME_GUIDE_WIDTH: f32 = 300.0;
 pub const CONNECT_DETAIL_WIDTH: f32 = 300.0;
@@ -32,7 +39,6 @@ pub const CONNECT_DETAIL_WIDTH: f32 = 300.0;
 pub const CONTENT_MAX_WIDTH: f32 = 520.0;
 pub const HEADER_BUTTON_SIZE: f32 = 42.0;
 pub const DRAWER_ICON_ZONE: f32 = 38.0;
-pub const TERMINAL_LINE_HEIGHT: f32 = 16.0;
 pub const PANEL_ITEM_HEIGHT: f32 = 28.0;
 
 pub const DRAWER_EDGE_ZONE: f32 = if cfg!(target_os = "android") {
@@ -77,6 +83,54 @@ pub const EDITOR_FONT_SIZE: f32 = 12.0;
 pub const EDITOR_GUTTER_FONT_SIZE: f32 = 11.0;
 pub const EDITOR_LINE_HEIGHT: f32 = 15.0;
 pub const EDITOR_GUTTER_WIDTH: f32 = 36.0;
+pub const EDITOR_BLAME_WIDTH: f32 = 88.0;
+
+/// Smallest tap area for icon-only buttons (44pt HIG, ~48dp Material).
+pub const MIN_TOUCH_TARGET: f32 = 44.0;
+
+/// Hit slop that grows a `size`-px button to `MIN_TOUCH_TARGET`.
+pub fn touch_slop(size: f32) -> Pixels {
+    px(((MIN_TOUCH_TARGET - size) / 2.0).max(0.0))
+}
+
+// ---------------------------------------------------------------------------
+// Text scale
+// ---------------------------------------------------------------------------
+
+/// Multiplier applied to the font sizes above, from Settings → Text Size.
+/// Kept outside the theme entity because many render helpers size text
+/// without an `App` at hand; `settings::set_text_size` refreshes windows.
+static TEXT_SCALE: AtomicU32 = AtomicU32::new(f32::to_bits(1.0));
+
+pub fn text_scale() -> f32 {
+    f32::from_bits(TEXT_SCALE.load(Ordering::Relaxed))
+}
+
+pub(crate) fn set_text_scale(scale: f32) {
+    TEXT_SCALE.store(scale.to_bits(), Ordering::Relaxed);
+}
+
+/// `size` (one of the `FONT_*` tokens, or a line height derived from one)
+/// at the current text scale.
+pub fn scaled(size: f32) -> Pixels {
+    px(size * text_scale())
+}
+
+pub fn editor_font_size() -> f32 {
+    EDITOR_FONT_SIZE * text_scale()
+}
+
+pub fn editor_gutter_font_size() -> f32 {
+    EDITOR_GUTTER_FONT_SIZE * text_scale()
+}
+
+pub fn editor_line_height() -> f32 {
+    EDITOR_LINE_HEIGHT * text_scale()
+}
+
+pub fn editor_gutter_width() -> f32 {
+    EDITOR_GUTTER_WIDTH * text_scale()
+}
 
 // ---------------------------------------------------------------------------
 // Theme preference
@@ -192,6 +246,9 @@ pub struct DiffTheme {
     pub header_bg: u32,
     pub added_bg: u32,
     pub removed_bg: u32,
+    /// Changed words within an added/removed line.
+    pub added_word_bg: u32,
+    pub removed_word_bg: u32,
     pub header_text: u32,
     pub gutter_text: u32,
     pub body_text: u32,
@@ -219,6 +276,8 @@ impl EditorTheme {
                 header_bg: 0x131313,
                 added_bg: 0x162016,
                 removed_bg: 0x201616,
+                added_word_bg: 0x2a4a2a,
+                removed_word_bg: 0x4f2a2a,
                 header_text: 0x61afef,
                 gutter_text: 0x404040,
                 body_text: 0xcacaca,
@@ -237,6 +296,8 @@ impl EditorTheme {
                 header_bg: 0xf6f8fa,
                 added_bg: 0xdafbe1,
                 removed_bg: 0xffebe9,
+                added_word_bg: 0xabf2bc,
+                removed_word_bg: 0xffcecb,
                 header_text: 0x0969da,
                 gutter_text: 0x8b949e,
                 body_text: 0x24292f,
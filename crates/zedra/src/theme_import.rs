@@ -0,0 +1,482 @@
+//! VS Code color theme import.
+//!
+//! Reads the JSON a VS Code theme extension ships (`name`, `type`, `colors`,
+//! `tokenColors`) and layers it over the built-in bundle for the theme's
+//! appearance. TextMate scopes are mapped onto the tree-sitter captures that
+//! `SyntaxTheme` knows; anything the theme leaves out keeps the built-in color.
+//! See `docs/THEMING.md`.
+
+use gpui::{FontStyle, FontWeight, HighlightStyle, rgb};
+use serde::Deserialize;
+use serde_json::Value;
+use zedra_terminal::AnsiPalette;
+
+use crate::theme::{ThemeBundle, ThemePreference};
+
+/// TextMate scopes tried for each capture, most preferred first.
+const CAPTURE_SCOPES: &[(&str, &[&str])] = &[
+    (
+        "keyword",
+        &["keyword.control", "keyword", "storage.type", "storage"],
+    ),
+    ("function", &["entity.name.function", "support.function"]),
+    (
+        "type",
+        &["entity.name.type", "support.type", "support.class"],
+    ),
+    ("string", &["string.quoted", "string"]),
+    ("comment", &["comment.line", "comment"]),
+    ("number", &["constant.numeric"]),
+    (
+        "constant",
+        &["variable.other.constant", "constant.language", "constant"],
+    ),
+    (
+        "property",
+        &[
+            "variable.other.property",
+            "variable.other.member",
+            "support.type.property-name",
+        ],
+    ),
+    ("operator", &["keyword.operator"]),
+    ("variable", &["variable.other.readwrite", "variable"]),
+    ("punctuation", &["punctuation"]),
+    (
+        "attribute",
+        &["entity.other.attribute-name", "meta.attribute"],
+    ),
+    ("label", &["entity.name.label"]),
+    (
+        "constructor",
+        &["entity.name.class", "entity.name.type.class"],
+    ),
+    ("tag", &["entity.name.tag"]),
+];
+
+/// VS Code `colors` keys for ANSI 0-15, in palette order.
+const ANSI_KEYS: [&str; 16] = [
+    "terminal.ansiBlack",
+    "terminal.ansiRed",
+    "terminal.ansiGreen",
+    "terminal.ansiYellow",
+    "terminal.ansiBlue",
+    "terminal.ansiMagenta",
+    "terminal.ansiCyan",
+    "terminal.ansiWhite",
+    "terminal.ansiBrightBlack",
+    "terminal.ansiBrightRed",
+    "terminal.ansiBrightGreen",
+    "terminal.ansiBrightYellow",
+    "terminal.ansiBrightBlue",
+    "terminal.ansiBrightMagenta",
+    "terminal.ansiBrightCyan",
+    "terminal.ansiBrightWhite",
+];
+
+/// How far dim ANSI colors move toward the terminal background.
+const DIM_MIX: f32 = 0.35;
+
+/// A parsed VS Code theme, ready to apply over a built-in bundle.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportedTheme {
+    pub name: String,
+    /// Built-in bundle the theme is layered on; VS Code `hc` themes count as dark.
+    pub appearance: ThemePreference,
+    syntax: Vec<(&'static str, HighlightStyle)>,
+    editor_background: Option<u32>,
+    editor_foreground: Option<u32>,
+    line_number: Option<u32>,
+    terminal_background: Option<u32>,
+    terminal_foreground: Option<u32>,
+    terminal_cursor: Option<u32>,
+    ansi: [Option<u32>; 16],
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ThemeFile {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default, rename = "type")]
+    kind: Option<String>,
+    #[serde(default)]
+    colors: serde_json::Map<String, Value>,
+    #[serde(default)]
+    token_colors: Vec<TokenColor>,
+}
+
+#[derive(Deserialize)]
+struct TokenColor {
+    #[serde(default)]
+    scope: Option<Value>,
+    #[serde(default)]
+    settings: TokenSettings,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TokenSettings {
+    #[serde(default)]
+    foreground: Option<String>,
+    #[serde(default)]
+    font_style: Option<String>,
+}
+
+impl ImportedTheme {
+    /// Parse a VS Code theme file. Comments and trailing commas are allowed,
+    /// as VS Code itself accepts them.
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let file: ThemeFile = serde_json::from_str(&strip_jsonc(source))
+            .map_err(|e| format!("not a VS Code theme: {e}"))?;
+        if file.colors.is_empty() && file.token_colors.is_empty() {
+            return Err("theme has no colors or tokenColors".to_string());
+        }
+
+        let appearance = match file.kind.as_deref() {
+            Some("light") | Some("hcLight") => ThemePreference::Light,
+            Some(_) => ThemePreference::Dark,
+            None => {
+                // Older themes omit `type`; judge by the editor background.
+                let background = color_key(&file.colors, "editor.background");
+                match background {
+                    Some(color) if luminance(color) >= 0.5 => ThemePreference::Light,
+                    _ => ThemePreference::Dark,
+                }
+            }
+        };
+
+        let rules: Vec<(Vec<String>, &TokenSettings)> = file
+            .token_colors
+            .iter()
+            .map(|rule| (scope_selectors(rule.scope.as_ref()), &rule.settings))
+            .collect();
+        let syntax = CAPTURE_SCOPES
+            .iter()
+            .filter_map(|(capture, scopes)| {
+                scopes
+                    .iter()
+                    .find_map(|scope| best_rule(&rules, scope))
+                    .and_then(highlight_style)
+                    .map(|style| (*capture, style))
+            })
+            .collect();
+
+        let mut ansi = [None; 16];
+        for (slot, key) in ansi.iter_mut().zip(ANSI_KEYS) {
+            *slot = color_key(&file.colors, key);
+        }
+
+        Ok(Self {
+            name: file
+                .name
+                .filter(|name| !name.trim().is_empty())
+                .unwrap_or_else(|| "Imported theme".to_string()),
+            appearance,
+            syntax,
+            editor_background: color_key(&file.colors, "editor.background"),
+            editor_foreground: color_key(&file.colors, "editor.foreground"),
+            line_number: color_key(&file.colors, "editorLineNumber.foreground"),
+            terminal_background: color_key(&file.colors, "terminal.background"),
+            terminal_foreground: color_key(&file.colors, "terminal.foreground"),
+            terminal_cursor: color_key(&file.colors, "terminalCursor.foreground"),
+            ansi,
+        })
+    }
+
+    /// Overlay the theme on `bundle`, which should be the built-in bundle for
+    /// `self.appearance`. UI chrome is left alone.
+    pub fn apply(&self, bundle: &mut ThemeBundle) {
+        let editor = &mut bundle.editor;
+        editor.syntax = self
+            .syntax
+            .iter()
+            .fold(editor.syntax.clone(), |syntax, (capture, style)| {
+                syntax.with_style(capture, *style)
+            });
+        if let Some(color) = self.editor_background {
+            editor.background = color;
+        }
+        if let Some(color) = self.editor_foreground {
+            editor.foreground = color;
+        }
+        if let Some(color) = self.line_number {
+            editor.gutter = rgb(color).into();
+        }
+
+        let terminal = bundle.terminal;
+        let background = self
+            .terminal_background
+            .or(self.editor_background)
+            .unwrap_or(terminal.background);
+        let foreground = self
+            .terminal_foreground
+            .or(self.editor_foreground)
+            .unwrap_or(terminal.foreground);
+        let cursor = self.terminal_cursor.unwrap_or(terminal.cursor);
+        let base = terminal.ansi;
+        let pick = |index: usize, fallback: u32| self.ansi[index].unwrap_or(fallback);
+        let mut ansi = AnsiPalette {
+            black: pick(0, base.black),
+            red: pick(1, base.red),
+            green: pick(2, base.green),
+            yellow: pick(3, base.yellow),
+            blue: pick(4, base.blue),
+            magenta: pick(5, base.magenta),
+            cyan: pick(6, base.cyan),
+            white: pick(7, base.white),
+            bright_black: pick(8, base.bright_black),
+            bright_red: pick(9, base.bright_red),
+            bright_green: pick(10, base.bright_green),
+            bright_yellow: pick(11, base.bright_yellow),
+            bright_blue: pick(12, base.bright_blue),
+            bright_magenta: pick(13, base.bright_magenta),
+            bright_cyan: pick(14, base.bright_cyan),
+            bright_white: pick(15, base.bright_white),
+            ..base
+        };
+        if self.ansi[..8].iter().any(Option::is_some) {
+            ansi.dim_black = mix(ansi.black, background, DIM_MIX);
+            ansi.dim_red = mix(ansi.red, background, DIM_MIX);
+            ansi.dim_green = mix(ansi.green, background, DIM_MIX);
+            ansi.dim_yellow = mix(ansi.yellow, background, DIM_MIX);
+            ansi.dim_blue = mix(ansi.blue, background, DIM_MIX);
+            ansi.dim_magenta = mix(ansi.magenta, background, DIM_MIX);
+            ansi.dim_cyan = mix(ansi.cyan, background, DIM_MIX);
+            ansi.dim_white = mix(ansi.white, background, DIM_MIX);
+        }
+        let mut terminal = terminal.with_colors(background, foreground, cursor, ansi);
+        if self.terminal_foreground.is_some() || self.editor_foreground.is_some() {
+            terminal.dim_foreground = mix(foreground, background, DIM_MIX);
+        }
+        bundle.terminal = terminal;
+    }
+}
+
+/// Selectors of one `tokenColors` rule. `scope` may be a string, a
+/// comma-separated string, or an array; for descendant selectors such as
+/// `meta.function entity.name` only the last scope is kept.
+fn scope_selectors(scope: Option<&Value>) -> Vec<String> {
+    let raw: Vec<&str> = match scope {
+        Some(Value::String(scope)) => scope.split(',').collect(),
+        Some(Value::Array(scopes)) => scopes
+            .iter()
+            .filter_map(Value::as_str)
+            .flat_map(|scope| scope.split(','))
+            .collect(),
+        _ => Vec::new(),
+    };
+    raw.into_iter()
+        .filter_map(|selector| selector.split_whitespace().last())
+        .map(str::to_string)
+        .collect()
+}
+
+/// The rule whose selector matches `scope` most specifically; later rules win ties,
+/// as in TextMate.
+fn best_rule<'a>(
+    rules: &[(Vec<String>, &'a TokenSettings)],
+    scope: &str,
+) -> Option<&'a TokenSettings> {
+    let mut best: Option<(usize, &TokenSettings)> = None;
+    for (selectors, settings) in rules {
+        if settings.foreground.is_none() && settings.font_style.is_none() {
+            continue;
+        }
+        for selector in selectors {
+            let matches = scope == selector
+                || scope
+                    .strip_prefix(selector.as_str())
+                    .is_some_and(|rest| rest.starts_with('.'));
+            if matches && best.is_none_or(|(len, _)| selector.len() >= len) {
+                best = Some((selector.len(), settings));
+            }
+        }
+    }
+    best.map(|(_, settings)| settings)
+}
+
+fn highlight_style(settings: &TokenSettings) -> Option<HighlightStyle> {
+    let color = settings.foreground.as_deref().and_then(parse_color)?;
+    let font_style = settings.font_style.as_deref().unwrap_or_default();
+    Some(HighlightStyle {
+        color: Some(rgb(color).into()),
+        font_style: font_style.contains("italic").then_some(FontStyle::Italic),
+        font_weight: font_style.contains("bold").then_some(FontWeight::BOLD),
+        ..Default::default()
+    })
+}
+
+fn color_key(colors: &serde_json::Map<String, Value>, key: &str) -> Option<u32> {
+    colors
+        .get(key)
+        .and_then(Value::as_str)
+        .and_then(parse_color)
+}
+
+/// `#rgb`, `#rgba`, `#rrggbb`, or `#rrggbbaa`; alpha is dropped.
+fn parse_color(value: &str) -> Option<u32> {
+    let hex = value.trim().strip_prefix('#')?;
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    match hex.len() {
+        3 | 4 => {
+            let mut color = 0u32;
+            for c in hex.chars().take(3) {
+                let digit = c.to_digit(16)?;
+                color = (color << 8) | (digit * 0x11);
+            }
+            Some(color)
+        }
+        6 | 8 => u32::from_str_radix(&hex[..6], 16).ok(),
+        _ => None,
+    }
+}
+
+/// Blend `color` toward `target` by `amount` (0 keeps `color`).
+fn mix(color: u32, target: u32, amount: f32) -> u32 {
+    let channel = |value: u32, shift: u32| ((value >> shift) & 0xff) as f32;
+    [16, 8, 0].into_iter().fold(0, |acc, shift| {
+        let from = channel(color, shift);
+        let to = channel(target, shift);
+        let value = (from + (to - from) * amount).round() as u32;
+        acc | (value.min(0xff) << shift)
+    })
+}
+
+fn luminance(color: u32) -> f32 {
+    let r = ((color >> 16) & 0xff) as f32 / 255.0;
+    let g = ((color >> 8) & 0xff) as f32 / 255.0;
+    let b = (color & 0xff) as f32 / 255.0;
+    0.2126 * r + 0.7152 * g + 0.0722 * b
+}
+
+/// Drop `//` and `/* */` comments and trailing commas outside strings.
+fn strip_jsonc(source: &str) -> String {
+    let mut out = String::with_capacity(source.len());
+    let mut chars = source.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            match c {
+                '\\' => {
+                    if let Some(escaped) = chars.next() {
+                        out.push(escaped);
+                    }
+                }
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => {
+                in_string = true;
+                out.push(c);
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        out.push('\n');
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = '\0';
+                for c in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+            }
+            '}' | ']' => {
+                let trimmed = out.trim_end_matches(char::is_whitespace).len();
+                if out[..trimmed].ends_with(',') {
+                    out.remove(trimmed - 1);
+                }
+                out.push(c);
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const THEME: &str = r##"{
+        // Comments and trailing commas are common in published themes.
+        "name": "Harbor",
+        "type": "dark",
+        "colors": {
+            "editor.background": "#101820",
+            "editor.foreground": "#d0d6e0",
+            "terminal.ansiRed": "#ff5555",
+            "terminalCursor.foreground": "#ffcc00", /* block cursor */
+        },
+        "tokenColors": [
+            { "scope": "keyword", "settings": { "foreground": "#aa77ff" } },
+            { "scope": ["keyword.operator"], "settings": { "foreground": "#88ccff" } },
+            { "scope": "comment, punctuation.definition.comment", "settings": { "foreground": "#607080", "fontStyle": "italic" } },
+            { "scope": "meta.embedded string", "settings": { "foreground": "#99dd66" } },
+        ],
+    }"##;
+
+    #[test]
+    fn parses_vscode_theme_with_comments_and_trailing_commas() {
+        let theme = ImportedTheme::parse(THEME).unwrap();
+        assert_eq!(theme.name, "Harbor");
+        assert_eq!(theme.appearance, ThemePreference::Dark);
+
+        let mut bundle = ThemeBundle::dark();
+        theme.apply(&mut bundle);
+        let color = |capture: &str| bundle.editor.syntax.get(capture).unwrap().color;
+        assert_eq!(color("keyword"), Some(rgb(0xaa77ff).into()));
+        assert_eq!(color("operator"), Some(rgb(0x88ccff).into()));
+        assert_eq!(color("string"), Some(rgb(0x99dd66).into()));
+        assert_eq!(
+            bundle.editor.syntax.get("comment").unwrap().font_style,
+            Some(FontStyle::Italic)
+        );
+        // Captures the theme leaves out keep the built-in style.
+        assert_eq!(
+            bundle.editor.syntax.get("tag"),
+            ThemeBundle::dark().editor.syntax.get("tag")
+        );
+        assert_eq!(bundle.editor.background, 0x101820);
+        assert_eq!(bundle.terminal.background, 0x101820);
+        assert_eq!(bundle.terminal.cursor, 0xffcc00);
+        assert_eq!(bundle.terminal.ansi.red, 0xff5555);
+        assert_eq!(
+            bundle.terminal.ansi.green,
+            ThemeBundle::dark().terminal.ansi.green
+        );
+    }
+
+    #[test]
+    fn infers_appearance_and_rejects_non_themes() {
+        let light =
+            ImportedTheme::parse(r##"{"colors": {"editor.background": "#fafafa"}}"##).unwrap();
+        assert_eq!(light.appearance, ThemePreference::Light);
+        assert_eq!(light.name, "Imported theme");
+        assert!(ImportedTheme::parse(r#"{"name": "empty"}"#).is_err());
+        assert!(ImportedTheme::parse("not json").is_err());
+    }
+
+    #[test]
+    fn parses_short_and_alpha_hex_colors() {
+        assert_eq!(parse_color("#abc"), Some(0xaabbcc));
+        assert_eq!(parse_color("#abcd"), Some(0xaabbcc));
+        assert_eq!(parse_color("#112233"), Some(0x112233));
+        assert_eq!(parse_color("#11223380"), Some(0x112233));
+        assert_eq!(parse_color("red"), None);
+        assert_eq!(parse_color("#12345"), None);
+    }
+}
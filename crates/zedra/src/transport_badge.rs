@@ -199,7 +199,7 @@ impl RenderOnce for ConnectionStatusIndicator {
 /// Render an inline transport badge element (dot + label).
 pub(crate) fn render_transport_badge(label: String, color: u32) -> Div {
     div()
-        .text_size(px(theme::FONT_DETAIL))
+        .text_size(theme::scaled(theme::FONT_DETAIL))
         .text_color(rgb(color))
         .child(label)
 }
@@ -10,6 +10,7 @@ use std::time::{Duration, Instant};
 use gpui::prelude::FluentBuilder;
 use gpui::*;
 
+use crate::app_action::InsertText;
 use crate::theme;
 
 fn clamp_byte_index(s: &str, mut i: usize) -> usize {
@@ -595,6 +596,16 @@ impl Input {
         });
     }
 
+    /// Bulk insert at the cursor (or over the selection) as one edit.
+    fn handle_insert_text(
+        &mut self,
+        action: &InsertText,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.replace_text_in_range(None, &action.text, window, cx);
+    }
+
     fn handle_key_down(
         &mut self,
         event: &KeyDownEvent,
@@ -1197,7 +1208,7 @@ impl Render for Input {
             theme::FONT_DETAIL
         } else {
             theme::FONT_BODY
-        };
+        } * theme::text_scale();
         let min_height = if self.compact { 36.0 } else { 44.0 };
         let horizontal_padding = if self.compact { 10.0 } else { 12.0 };
         let vertical_padding = if self.compact { 8.0 } else { 10.0 };
@@ -1224,6 +1235,7 @@ impl Render for Input {
             })
             .on_press(cx.listener(Self::handle_press))
             .on_key_down(cx.listener(Self::handle_key_down))
+            .on_action(cx.listener(Self::handle_insert_text))
             .pl(px(horizontal_padding))
             .pr(px(horizontal_padding + self.trailing_gutter))
             .py(px(vertical_padding))
@@ -2,10 +2,14 @@
 
 pub mod drawer_host;
 pub mod input;
+pub mod pull_to_refresh;
 pub mod subscreen_header;
 pub mod subscreen_layout;
 
 pub use drawer_host::{DrawerEvent, DrawerHost, DrawerSide};
 pub use input::{InputChanged, InputSubmit};
+pub use pull_to_refresh::{PullScroll, PullToRefresh, PullToRefreshEvent, pull_to_refresh};
 pub use subscreen_header::{chevron_back_button, subscreen_refresh_button};
-pub use subscreen_layout::{subscreen_empty_text, subscreen_padded_body, subscreen_page};
+pub use subscreen_layout::{
+    subscreen_empty_text, subscreen_padded_body, subscreen_page, subscreen_refreshable_page,
+};
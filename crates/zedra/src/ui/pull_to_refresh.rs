@@ -0,0 +1,354 @@
+use std::f32::consts::TAU;
+use std::time::Duration;
+
+use gpui::prelude::FluentBuilder;
+use gpui::*;
+
+use crate::platform_bridge::{self, HapticFeedback};
+use crate::theme;
+
+const PULL_START_THRESHOLD: f32 = 10.0;
+const PULL_VERTICAL_RATIO: f32 = 1.25;
+/// Finger travel is scaled down so the indicator lags the touch like a rubber band.
+const PULL_RESISTANCE: f32 = 0.5;
+/// Indicator height past which releasing starts a refresh.
+pub const PULL_TRIGGER_DISTANCE: f32 = 56.0;
+const PULL_MAX_DISTANCE: f32 = 96.0;
+/// Indicator height held while the refresh runs.
+const REFRESHING_HEIGHT: f32 = 40.0;
+/// Scroll offsets within this of zero count as "at top".
+const AT_TOP_TOLERANCE: f32 = 0.5;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum PullGesture {
+    Idle,
+    Pending {
+        pointer_id: PointerId,
+        start: Point<Pixels>,
+    },
+    Pulling {
+        pointer_id: PointerId,
+        start: Point<Pixels>,
+    },
+}
+
+#[derive(Clone, Debug)]
+pub enum PullToRefreshEvent {
+    /// The user released past the trigger distance. Call
+    /// [`PullToRefresh::finish`] once the reload settles.
+    Refresh,
+}
+
+/// Where the wrapped list reads its scroll position from.
+#[derive(Clone)]
+pub enum PullScroll {
+    /// An `overflow_y_scroll` container tracking [`PullToRefresh::scroll_handle`].
+    Tracked,
+    /// A `uniform_list` tracking its own handle.
+    UniformList(UniformListScrollHandle),
+}
+
+/// Pull-to-refresh state for one list.
+///
+/// The owning view creates it as an entity, renders its list through
+/// [`pull_to_refresh`], and subscribes to [`PullToRefreshEvent`]. Vertical
+/// drags that start while the list is scrolled to the top pull the indicator
+/// down; once the gesture claims the touch it prevents default so the touch
+/// pipeline stops turning it into scroll.
+pub struct PullToRefresh {
+    gesture: PullGesture,
+    /// Current indicator height in pixels.
+    pull_distance: f32,
+    refreshing: bool,
+    scroll_handle: ScrollHandle,
+}
+
+impl EventEmitter<PullToRefreshEvent> for PullToRefresh {}
+
+impl Default for PullToRefresh {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PullToRefresh {
+    pub fn new() -> Self {
+        Self {
+            gesture: PullGesture::Idle,
+            pull_distance: 0.0,
+            refreshing: false,
+            scroll_handle: ScrollHandle::new(),
+        }
+    }
+
+    /// Handle for `overflow_y_scroll` lists wrapped with [`PullScroll::Tracked`].
+    pub fn scroll_handle(&self) -> &ScrollHandle {
+        &self.scroll_handle
+    }
+
+    pub fn is_refreshing(&self) -> bool {
+        self.refreshing
+    }
+
+    /// Collapse the indicator after the owner's reload completes.
+    pub fn finish(&mut self, cx: &mut Context<Self>) {
+        if !self.refreshing {
+            return;
+        }
+        self.refreshing = false;
+        self.pull_distance = 0.0;
+        cx.notify();
+    }
+
+    fn is_at_top(&self, scroll: &PullScroll) -> bool {
+        let offset_y = match scroll {
+            PullScroll::Tracked => self.scroll_handle.offset().y,
+            PullScroll::UniformList(handle) => handle.0.borrow().base_handle.offset().y,
+        };
+        offset_y >= px(-AT_TOP_TOLERANCE)
+    }
+
+    fn begin(&mut self, pointer_id: PointerId, start: Point<Pixels>, at_top: bool) {
+        self.gesture = if at_top && !self.refreshing {
+            PullGesture::Pending { pointer_id, start }
+        } else {
+            PullGesture::Idle
+        };
+    }
+
+    /// Returns `true` while the pull owns the touch.
+    fn track(&mut self, pointer_id: PointerId, position: Point<Pixels>) -> bool {
+        match self.gesture {
+            PullGesture::Pending {
+                pointer_id: pid,
+                start,
+            } if pid == pointer_id => {
+                let dx = f32::from(position.x - start.x).abs();
+                let dy = f32::from(position.y - start.y);
+                if dx < PULL_START_THRESHOLD && dy.abs() < PULL_START_THRESHOLD {
+                    return false;
+                }
+                if dy > PULL_START_THRESHOLD && dy > dx * PULL_VERTICAL_RATIO {
+                    self.gesture = PullGesture::Pulling { pointer_id, start };
+                    self.pull_distance = pull_distance_for(dy);
+                    true
+                } else {
+                    // Upward scroll or a horizontal swipe: leave it to the list.
+                    self.gesture = PullGesture::Idle;
+                    false
+                }
+            }
+            PullGesture::Pulling {
+                pointer_id: pid,
+                start,
+            } if pid == pointer_id => {
+                self.pull_distance = pull_distance_for(f32::from(position.y - start.y));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Ends the gesture. Returns `true` when the release should start a refresh.
+    fn release(&mut self, pointer_id: PointerId, cancelled: bool) -> bool {
+        let pulling = match self.gesture {
+            PullGesture::Pending {
+                pointer_id: pid, ..
+            } if pid == pointer_id => false,
+            PullGesture::Pulling {
+                pointer_id: pid, ..
+            } if pid == pointer_id => true,
+            _ => return false,
+        };
+        self.gesture = PullGesture::Idle;
+        if !pulling {
+            return false;
+        }
+        if !cancelled && self.pull_distance >= PULL_TRIGGER_DISTANCE {
+            self.refreshing = true;
+            self.pull_distance = REFRESHING_HEIGHT;
+            true
+        } else {
+            self.pull_distance = 0.0;
+            false
+        }
+    }
+
+    fn indicator_height(&self) -> f32 {
+        if self.refreshing {
+            REFRESHING_HEIGHT
+        } else {
+            self.pull_distance
+        }
+    }
+}
+
+fn pull_distance_for(dy: f32) -> f32 {
+    ((dy - PULL_START_THRESHOLD) * PULL_RESISTANCE).clamp(0.0, PULL_MAX_DISTANCE)
+}
+
+/// Wrap a scrollable list with the pull gesture and its spinner.
+///
+/// For [`PullScroll::Tracked`], the list's scroll container must call
+/// `.track_scroll(state.read(cx).scroll_handle())`.
+pub fn pull_to_refresh(
+    id: impl Into<ElementId>,
+    state: &Entity<PullToRefresh>,
+    scroll: PullScroll,
+    content: impl IntoElement,
+    cx: &App,
+) -> Stateful<Div> {
+    let pull = state.read(cx);
+    let height = pull.indicator_height();
+    let refreshing = pull.refreshing;
+    let progress = (pull.pull_distance / PULL_TRIGGER_DISTANCE).min(1.0);
+
+    let on_down = state.clone();
+    let on_move = state.clone();
+    let on_up = state.clone();
+    let on_cancel = state.clone();
+
+    div()
+        .id(id)
+        .size_full()
+        .min_h_0()
+        .flex()
+        .flex_col()
+        .on_pointer_down(move |event: &PointerDownEvent, _window, cx| {
+            on_down.update(cx, |pull, _cx| {
+                let at_top = pull.is_at_top(&scroll);
+                pull.begin(event.pointer_id, event.position, at_top);
+            });
+        })
+        .on_pointer_move(move |event: &PointerMoveEvent, window, cx| {
+            let owned = on_move.update(cx, |pull, cx| {
+                let owned = pull.track(event.pointer_id, event.position);
+                if owned {
+                    cx.notify();
+                }
+                owned
+            });
+            if owned {
+                window.prevent_default();
+            }
+        })
+        .on_pointer_up(move |event: &PointerUpEvent, window, cx| {
+            end_pull(&on_up, event.pointer_id, false, window, cx);
+        })
+        .on_pointer_cancel(move |event: &PointerCancelEvent, window, cx| {
+            end_pull(&on_cancel, event.pointer_id, true, window, cx);
+        })
+        .when(height > 0.0, |el| {
+            el.child(render_indicator(height, progress, refreshing, cx))
+        })
+        .child(div().flex_1().min_h_0().child(content))
+}
+
+fn end_pull(
+    state: &Entity<PullToRefresh>,
+    pointer_id: PointerId,
+    cancelled: bool,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let was_pulling = matches!(
+        state.read(cx).gesture,
+        PullGesture::Pulling { pointer_id: pid, .. } if pid == pointer_id
+    );
+    state.update(cx, |pull, cx| {
+        if pull.release(pointer_id, cancelled) {
+            platform_bridge::trigger_haptic(HapticFeedback::ImpactLight);
+            cx.emit(PullToRefreshEvent::Refresh);
+        }
+        cx.notify();
+    });
+    if was_pulling {
+        // Keep the release from also landing as a tap on the row under the finger.
+        window.prevent_default();
+    }
+}
+
+fn render_indicator(height: f32, progress: f32, refreshing: bool, cx: &App) -> impl IntoElement {
+    let icon = svg()
+        .path("icons/refresh-ccw.svg")
+        .size(px(theme::ICON_SM))
+        .text_color(rgb(theme::text_muted(cx)));
+    let icon: AnyElement = if refreshing {
+        icon.with_animation(
+            ElementId::Name("pull-to-refresh-spin".into()),
+            Animation::new(Duration::from_millis(700)).repeat(),
+            |icon, delta| icon.with_transformation(Transformation::rotate(radians(TAU * delta))),
+        )
+        .into_any_element()
+    } else {
+        // Wind the arrow as the pull approaches the trigger distance.
+        icon.opacity(0.3 + 0.7 * progress)
+            .with_transformation(Transformation::rotate(radians(TAU * progress)))
+            .into_any_element()
+    };
+
+    div()
+        .w_full()
+        .h(px(height))
+        .flex_shrink_0()
+        .flex()
+        .items_center()
+        .justify_center()
+        .overflow_hidden()
+        .child(icon)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::point;
+
+    #[test]
+    fn downward_pull_past_trigger_starts_refresh() {
+        let mut pull = PullToRefresh::new();
+        pull.begin(1, point(px(100.0), px(100.0)), true);
+        assert!(!pull.track(1, point(px(101.0), px(105.0))));
+        assert!(pull.track(1, point(px(102.0), px(140.0))));
+        assert!(pull.pull_distance > 0.0);
+        assert!(pull.track(1, point(px(102.0), px(240.0))));
+        assert!(pull.pull_distance >= PULL_TRIGGER_DISTANCE);
+
+        assert!(pull.release(1, false));
+        assert!(pull.is_refreshing());
+        assert_eq!(pull.indicator_height(), REFRESHING_HEIGHT);
+        assert_eq!(pull.gesture, PullGesture::Idle);
+    }
+
+    #[test]
+    fn short_or_cancelled_pull_snaps_back() {
+        let mut pull = PullToRefresh::new();
+        pull.begin(1, point(px(100.0), px(100.0)), true);
+        assert!(pull.track(1, point(px(100.0), px(150.0))));
+        assert!(!pull.release(1, false));
+        assert_eq!(pull.indicator_height(), 0.0);
+
+        pull.begin(1, point(px(100.0), px(100.0)), true);
+        assert!(pull.track(1, point(px(100.0), px(300.0))));
+        assert!(!pull.release(1, true));
+        assert!(!pull.is_refreshing());
+        assert_eq!(pull.indicator_height(), 0.0);
+    }
+
+    #[test]
+    fn scrolled_lists_and_other_directions_are_left_alone() {
+        let mut pull = PullToRefresh::new();
+        pull.begin(1, point(px(100.0), px(100.0)), false);
+        assert!(!pull.track(1, point(px(100.0), px(300.0))));
+
+        pull.begin(1, point(px(100.0), px(100.0)), true);
+        assert!(!pull.track(1, point(px(100.0), px(60.0))));
+        assert!(!pull.track(1, point(px(100.0), px(300.0))));
+
+        pull.begin(1, point(px(100.0), px(100.0)), true);
+        assert!(!pull.track(1, point(px(160.0), px(120.0))));
+        assert_eq!(pull.gesture, PullGesture::Idle);
+
+        pull.begin(1, point(px(100.0), px(100.0)), true);
+        assert!(!pull.track(2, point(px(100.0), px(300.0))));
+    }
+}
@@ -1,6 +1,7 @@
 use gpui::*;
 
 use crate::theme;
+use crate::ui::pull_to_refresh::{PullScroll, PullToRefresh, pull_to_refresh};
 
 /// Subscreen page shell: full-width header band + scroll body.
 ///
@@ -24,6 +25,33 @@ pub fn subscreen_page(
         .child(subscreen_scroll_body(body))
 }
 
+/// [`subscreen_page`] whose scroll body supports pull-to-refresh via `pull`.
+pub fn subscreen_refreshable_page(
+    page_id: &'static str,
+    bg: impl Into<gpui::Fill>,
+    header: impl IntoElement,
+    body: impl IntoElement,
+    pull: &Entity<PullToRefresh>,
+    cx: &App,
+) -> impl IntoElement {
+    let scroll_body = subscreen_scroll_body(body).track_scroll(pull.read(cx).scroll_handle());
+    div()
+        .id(page_id)
+        .size_full()
+        .min_h_0()
+        .bg(bg)
+        .flex()
+        .flex_col()
+        .child(subscreen_header_band(header))
+        .child(div().flex_1().min_h_0().w_full().child(pull_to_refresh(
+            SharedString::from(format!("{page_id}-pull")),
+            pull,
+            PullScroll::Tracked,
+            scroll_body.size_full(),
+            cx,
+        )))
+}
+
 fn subscreen_header_band(header: impl IntoElement) -> impl IntoElement {
     div()
         .id("subscreen-header-band")
@@ -31,7 +59,7 @@ fn subscreen_header_band(header: impl IntoElement) -> impl IntoElement {
         .child(subscreen_content_column(header))
 }
 
-fn subscreen_scroll_body(body: impl IntoElement) -> impl IntoElement {
+fn subscreen_scroll_body(body: impl IntoElement) -> Stateful<Div> {
     div()
         .id("subscreen-scroll")
         .flex_1()
@@ -58,7 +86,7 @@ pub fn subscreen_empty_text(text: impl Into<SharedString>, cx: &App) -> Div {
         .w_full()
         .min_w_0()
         .py(px(theme::SPACING_LG))
-        .text_size(px(theme::FONT_BODY))
+        .text_size(theme::scaled(theme::FONT_BODY))
         .text_color(rgb(theme::text_muted(cx)))
         .whitespace_normal()
         .child(text.into())
@@ -0,0 +1,379 @@
+// Encrypted key-value vault for secrets the app keeps on the device.
+//
+// Values are sealed with AES-256-GCM under a 32-byte vault key. On Android
+// that key is wrapped by a non-exportable Android Keystore key, and on iOS it
+// is a Keychain item readable after first unlock on this device only; other
+// platforms keep it in a private `vault.key` file. The vault itself is
+// `<data>/zedra/vault.bin`, the whole map sealed under a fresh nonce on every
+// change. It holds the device identity key (`client.key` before) and the
+// Delta account and push tokens (`delta.json` before); both move in on first
+// load. `wipe` deletes the vault and destroys its key. A vault whose key the
+// platform lost (restored backup, reset Keystore) can never open again;
+// `is_key_lost` detects that and `reset_identity` starts over with a new one.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use anyhow::{Context as _, Result, anyhow, bail};
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use ring::aead::{AES_256_GCM, Aad, LessSafeKey, NONCE_LEN, Nonce, UnboundKey};
+use ring::rand::{SecureRandom, SystemRandom};
+use zedra_session::signer::KeyClientSigner;
+
+use crate::platform_bridge;
+
+const STORE_DIR: &str = "zedra";
+const VAULT_FILE: &str = "vault.bin";
+const KEY_FILE: &str = "vault.key";
+const LEGACY_CLIENT_KEY_FILE: &str = "client.key";
+/// File header, also bound into each seal as associated data.
+const MAGIC: &[u8; 4] = b"ZVT1";
+
+/// Ed25519 secret of the device identity, base64.
+pub const CLIENT_KEY: &str = "client_key";
+/// Delta access, refresh and push tokens, JSON.
+pub const DELTA_SECRETS: &str = "delta";
+
+/// Serializes read-modify-write cycles, so two first loads cannot each
+/// generate an identity key.
+static VAULT_LOCK: Mutex<()> = Mutex::new(());
+
+/// The vault did not decrypt: its key is gone or the file was modified.
+#[derive(Debug)]
+struct KeyMismatch;
+
+impl std::fmt::Display for KeyMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("vault key does not match or the file was modified")
+    }
+}
+
+impl std::error::Error for KeyMismatch {}
+
+/// Whether `err` means the vault can no longer be opened with the platform's
+/// key, so everything in it, the device identity included, is lost.
+pub fn is_key_lost(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| cause.is::<KeyMismatch>())
+}
+
+struct Vault {
+    dir: PathBuf,
+    key: [u8; 32],
+    entries: BTreeMap<String, String>,
+    dirty: bool,
+}
+
+impl Vault {
+    fn open_in(dir: &Path, key: [u8; 32]) -> Result<Self> {
+        let path = dir.join(VAULT_FILE);
+        let entries = match std::fs::read(&path) {
+            Ok(data) => {
+                let plaintext =
+                    unseal(&key, &data).with_context(|| format!("open {}", path.display()))?;
+                serde_json::from_slice(&plaintext).context("decode vault entries")?
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(e).with_context(|| format!("read {}", path.display())),
+        };
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            key,
+            entries,
+            dirty: false,
+        })
+    }
+
+    fn get(&self, name: &str) -> Option<&str> {
+        self.entries.get(name).map(String::as_str)
+    }
+
+    fn set(&mut self, name: &str, value: String) {
+        if self.entries.get(name) != Some(&value) {
+            self.entries.insert(name.to_string(), value);
+            self.dirty = true;
+        }
+    }
+
+    fn remove(&mut self, name: &str) {
+        if self.entries.remove(name).is_some() {
+            self.dirty = true;
+        }
+    }
+
+    fn save(&self) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)
+            .with_context(|| format!("create {}", self.dir.display()))?;
+        let plaintext = serde_json::to_vec(&self.entries)?;
+        let sealed = seal(&self.key, plaintext)?;
+        // Write beside the vault and rename, so a crash never leaves it torn.
+        let tmp = self.dir.join(format!("{VAULT_FILE}.tmp"));
+        write_private_file(&tmp, &sealed).with_context(|| format!("write {}", tmp.display()))?;
+        std::fs::rename(&tmp, self.dir.join(VAULT_FILE)).context("replace vault")?;
+        Ok(())
+    }
+}
+
+fn seal(key: &[u8; 32], mut plaintext: Vec<u8>) -> Result<Vec<u8>> {
+    let key = LessSafeKey::new(
+        UnboundKey::new(&AES_256_GCM, key).map_err(|_| anyhow!("invalid vault key"))?,
+    );
+    let mut nonce = [0u8; NONCE_LEN];
+    SystemRandom::new()
+        .fill(&mut nonce)
+        .map_err(|_| anyhow!("no system randomness"))?;
+    key.seal_in_place_append_tag(
+        Nonce::assume_unique_for_key(nonce),
+        Aad::from(MAGIC),
+        &mut plaintext,
+    )
+    .map_err(|_| anyhow!("vault seal failed"))?;
+    let mut out = Vec::with_capacity(MAGIC.len() + NONCE_LEN + plaintext.len());
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&plaintext);
+    Ok(out)
+}
+
+fn unseal(key: &[u8; 32], data: &[u8]) -> Result<Vec<u8>> {
+    let Some(rest) = data.strip_prefix(MAGIC.as_slice()) else {
+        bail!("not a vault file");
+    };
+    if rest.len() < NONCE_LEN {
+        bail!("vault file is truncated");
+    }
+    let (nonce, sealed) = rest.split_at(NONCE_LEN);
+    let key = LessSafeKey::new(
+        UnboundKey::new(&AES_256_GCM, key).map_err(|_| anyhow!("invalid vault key"))?,
+    );
+    let nonce = Nonce::try_assume_unique_for_key(nonce).map_err(|_| anyhow!("bad nonce"))?;
+    let mut buf = sealed.to_vec();
+    let plaintext = key
+        .open_in_place(nonce, Aad::from(MAGIC), &mut buf)
+        .map_err(|_| KeyMismatch)?;
+    Ok(plaintext.to_vec())
+}
+
+fn vault_dir() -> Result<PathBuf> {
+    let data_dir = platform_bridge::bridge()
+        .data_directory()
+        .context("platform data directory is unavailable")?;
+    Ok(PathBuf::from(data_dir).join(STORE_DIR))
+}
+
+/// The platform-held vault key, or the private key file where the platform
+/// has no key storage.
+fn vault_key(dir: &Path) -> Result<[u8; 32]> {
+    if let Some(key) = platform_bridge::bridge()
+        .vault_key()
+        .map_err(|e| anyhow!("vault key unavailable: {e}"))?
+    {
+        return Ok(key);
+    }
+    let path = dir.join(KEY_FILE);
+    match std::fs::read(&path) {
+        Ok(bytes) => bytes
+            .try_into()
+            .map_err(|_| anyhow!("invalid vault key at {}", path.display())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            let key = random_secret()?;
+            std::fs::create_dir_all(dir).with_context(|| format!("create {}", dir.display()))?;
+            write_private_file(&path, &key).with_context(|| format!("write {}", path.display()))?;
+            Ok(key)
+        }
+        Err(e) => Err(e).with_context(|| format!("read {}", path.display())),
+    }
+}
+
+fn random_secret() -> Result<[u8; 32]> {
+    let mut secret = [0u8; 32];
+    SystemRandom::new()
+        .fill(&mut secret)
+        .map_err(|_| anyhow!("no system randomness"))?;
+    Ok(secret)
+}
+
+/// Run `f` on the loaded vault under the vault lock, saving any change.
+fn with_vault<R>(f: impl FnOnce(&mut Vault) -> Result<R>) -> Result<R> {
+    let _guard = VAULT_LOCK
+        .lock()
+        .map_err(|_| anyhow!("vault lock poisoned"))?;
+    let dir = vault_dir()?;
+    let mut vault = Vault::open_in(&dir, vault_key(&dir)?)?;
+    let result = f(&mut vault)?;
+    if vault.dirty {
+        vault.save()?;
+    }
+    Ok(result)
+}
+
+pub fn get(name: &str) -> Result<Option<String>> {
+    with_vault(|vault| Ok(vault.get(name).map(str::to_string)))
+}
+
+pub fn set(name: &str, value: String) -> Result<()> {
+    with_vault(|vault| {
+        vault.set(name, value);
+        Ok(())
+    })
+}
+
+pub fn remove(name: &str) -> Result<()> {
+    with_vault(|vault| {
+        vault.remove(name);
+        Ok(())
+    })
+}
+
+/// The device identity used to pair with hosts and register with Delta.
+/// Moves a plaintext `client.key` into the vault on first use, or generates
+/// a new identity when there is none.
+pub fn client_signer() -> Result<KeyClientSigner> {
+    with_vault(|vault| {
+        if let Some(encoded) = vault.get(CLIENT_KEY) {
+            let secret: [u8; 32] = STANDARD
+                .decode(encoded)
+                .ok()
+                .and_then(|bytes| bytes.try_into().ok())
+                .context("invalid client key in vault")?;
+            return Ok(KeyClientSigner::from_secret(&secret));
+        }
+        let legacy = vault.dir.join(LEGACY_CLIENT_KEY_FILE);
+        let secret = match std::fs::read(&legacy) {
+            Ok(bytes) => bytes
+                .try_into()
+                .map_err(|_| anyhow!("invalid client key at {}", legacy.display()))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => random_secret()?,
+            Err(e) => return Err(e).with_context(|| format!("read {}", legacy.display())),
+        };
+        vault.set(CLIENT_KEY, STANDARD.encode(secret));
+        vault.save()?;
+        vault.dirty = false;
+        match std::fs::remove_file(&legacy) {
+            Ok(()) => tracing::info!("vault: moved client key into the vault"),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => tracing::warn!("vault: failed to remove {}: {}", legacy.display(), e),
+        }
+        Ok(KeyClientSigner::from_secret(&secret))
+    })
+}
+
+/// Delete the vault and destroy its key. Everything it held is gone: the
+/// device needs pairing again with every host and Delta needs a new sign-in.
+pub fn wipe() -> Result<()> {
+    let _guard = VAULT_LOCK
+        .lock()
+        .map_err(|_| anyhow!("vault lock poisoned"))?;
+    remove_vault_files(&vault_dir()?)?;
+    platform_bridge::bridge().delete_vault_key();
+    tracing::info!("vault: wiped");
+    Ok(())
+}
+
+/// Recover from a lost vault key: wipe the unreadable vault and create a new
+/// device identity under a new key. Hosts paired with the old identity no
+/// longer recognize the device and must be paired again.
+pub fn reset_identity() -> Result<KeyClientSigner> {
+    wipe()?;
+    client_signer()
+}
+
+fn remove_vault_files(dir: &Path) -> Result<()> {
+    for name in [VAULT_FILE, KEY_FILE, LEGACY_CLIENT_KEY_FILE] {
+        match std::fs::remove_file(dir.join(name)) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e).with_context(|| format!("remove {name}")),
+        }
+    }
+    Ok(())
+}
+
+/// Write `data` to `path` with 0o600 permissions set at creation (Unix).
+pub(crate) fn write_private_file(path: &Path, data: &[u8]) -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::io::Write;
+        use std::os::unix::fs::OpenOptionsExt;
+        let mut file = std::fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(path)?;
+        file.write_all(data)?;
+    }
+    #[cfg(not(unix))]
+    {
+        std::fs::write(path, data)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        std::env::temp_dir().join(format!("zedra-vault-{name}-{}-{nanos}", std::process::id()))
+    }
+
+    #[test]
+    fn seal_round_trips_and_rejects_other_keys_and_tampering() {
+        let key = [7u8; 32];
+        let sealed = seal(&key, b"secret".to_vec()).unwrap();
+        assert!(sealed.starts_with(MAGIC));
+        assert_eq!(unseal(&key, &sealed).unwrap(), b"secret");
+        assert!(unseal(&[8u8; 32], &sealed).is_err());
+
+        let mut tampered = sealed.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(unseal(&key, &tampered).is_err());
+        assert!(unseal(&key, b"ZVT1").is_err());
+    }
+
+    #[test]
+    fn vault_entries_persist_sealed() {
+        let dir = temp_dir("persist");
+        let key = [3u8; 32];
+        let mut vault = Vault::open_in(&dir, key).unwrap();
+        vault.set(DELTA_SECRETS, "{\"access_token\":\"tok\"}".to_string());
+        vault.save().unwrap();
+
+        let raw = std::fs::read(dir.join(VAULT_FILE)).unwrap();
+        assert!(!raw.windows(3).any(|w| w == b"tok"));
+
+        let mut reopened = Vault::open_in(&dir, key).unwrap();
+        assert_eq!(
+            reopened.get(DELTA_SECRETS),
+            Some("{\"access_token\":\"tok\"}")
+        );
+        reopened.remove(DELTA_SECRETS);
+        assert!(reopened.dirty);
+        assert!(Vault::open_in(&dir, [4u8; 32]).is_err());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn lost_key_is_detected_and_reset_starts_an_empty_vault() {
+        let dir = temp_dir("lost-key");
+        let mut vault = Vault::open_in(&dir, [5u8; 32]).unwrap();
+        vault.set(CLIENT_KEY, STANDARD.encode([9u8; 32]));
+        vault.save().unwrap();
+
+        let err = Vault::open_in(&dir, [6u8; 32]).err().unwrap();
+        assert!(is_key_lost(&err));
+        assert!(!is_key_lost(&anyhow!(
+            "platform data directory is unavailable"
+        )));
+
+        remove_vault_files(&dir).unwrap();
+        let fresh = Vault::open_in(&dir, [6u8; 32]).unwrap();
+        assert_eq!(fresh.get(CLIENT_KEY), None);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,132 @@
+// Per-route view state kept across main-view switches.
+//
+// The workspace reuses one editor and one diff view for every route, so
+// opening another file and navigating back used to land at the top again.
+// Those views save a `ViewState` for the route they leave and restore it once
+// the route's content has loaded again. Drawer tabs keep their own entities
+// (and with them explorer expansion, scroll and draft inputs), so they need no
+// entry here. The cache is runtime-only and bounded.
+
+use gpui::{ListOffset, Pixels, Point, px};
+
+/// Routes remembered per view before the least recently used is dropped.
+pub const VIEW_STATE_CAPACITY: usize = 64;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ViewState {
+    /// Offset of a uniform list, plus its horizontal scroll in pixels.
+    Scroll {
+        offset: Point<Pixels>,
+        h_offset: f32,
+    },
+    /// First visible item of a variable-height list.
+    List { item_ix: usize, offset_in_item: f32 },
+}
+
+impl ViewState {
+    pub fn list(offset: ListOffset) -> Self {
+        Self::List {
+            item_ix: offset.item_ix,
+            offset_in_item: offset.offset_in_item.into(),
+        }
+    }
+
+    pub fn list_offset(&self) -> Option<ListOffset> {
+        match *self {
+            Self::List {
+                item_ix,
+                offset_in_item,
+            } => Some(ListOffset {
+                item_ix,
+                offset_in_item: px(offset_in_item),
+            }),
+            Self::Scroll { .. } => None,
+        }
+    }
+}
+
+/// Least-recently-saved cache of view states keyed by route.
+pub struct ViewStateCache<K> {
+    entries: Vec<(K, ViewState)>,
+    capacity: usize,
+}
+
+impl<K: PartialEq> Default for ViewStateCache<K> {
+    fn default() -> Self {
+        Self::new(VIEW_STATE_CAPACITY)
+    }
+}
+
+impl<K: PartialEq> ViewStateCache<K> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: Vec::new(),
+            capacity: capacity.max(1),
+        }
+    }
+
+    pub fn save(&mut self, key: K, state: ViewState) {
+        self.entries.retain(|(k, _)| k != &key);
+        if self.entries.len() >= self.capacity {
+            self.entries.remove(0);
+        }
+        self.entries.push((key, state));
+    }
+
+    pub fn get(&self, key: &K) -> Option<ViewState> {
+        self.entries
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, state)| *state)
+    }
+
+    pub fn remove(&mut self, key: &K) {
+        self.entries.retain(|(k, _)| k != key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(item_ix: usize) -> ViewState {
+        ViewState::List {
+            item_ix,
+            offset_in_item: 0.0,
+        }
+    }
+
+    #[test]
+    fn save_replaces_and_evicts_the_oldest_route() {
+        let mut cache = ViewStateCache::new(2);
+        cache.save("a", list(1));
+        cache.save("b", list(2));
+        cache.save("a", list(3));
+        cache.save("c", list(4));
+        assert_eq!(cache.get(&"a"), Some(list(3)));
+        assert_eq!(cache.get(&"b"), None);
+        assert_eq!(cache.get(&"c"), Some(list(4)));
+
+        cache.remove(&"a");
+        assert_eq!(cache.get(&"a"), None);
+    }
+
+    #[test]
+    fn list_state_round_trips_through_list_offset() {
+        let state = ViewState::list(ListOffset {
+            item_ix: 7,
+            offset_in_item: px(12.5),
+        });
+        let offset = state.list_offset().unwrap();
+        assert_eq!(offset.item_ix, 7);
+        assert_eq!(offset.offset_in_item, px(12.5));
+        assert_eq!(
+            ViewState::Scroll {
+                offset: Point::default(),
+                h_offset: 0.0
+            }
+            .list_offset(),
+            None
+        );
+    }
+}
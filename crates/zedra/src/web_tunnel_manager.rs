@@ -146,7 +146,7 @@ fn header(cx: &mut Context<WebTunnelManager>) -> impl IntoElement {
                                 .gap(px(0.0))
                                 .child(
                                     div()
-                                        .text_size(px(theme::FONT_HEADING))
+                                        .text_size(theme::scaled(theme::FONT_HEADING))
                                         .font_family(fonts::HEADING_FONT_FAMILY)
                                         .font_weight(FontWeight::MEDIUM)
                                         .text_color(rgb(theme::text_primary(cx)))
@@ -154,7 +154,7 @@ fn header(cx: &mut Context<WebTunnelManager>) -> impl IntoElement {
                                 )
                                 .child(
                                     div()
-                                        .text_size(px(theme::FONT_BODY))
+                                        .text_size(theme::scaled(theme::FONT_BODY))
                                         .text_color(rgb(theme::text_muted(cx)))
                                         .child("Localhost listeners bound on this device"),
                                 ),
@@ -190,7 +190,7 @@ fn listener_row(port: u16, host: String, cx: &mut Context<WebTunnelManager>) ->
             div()
                 .flex_shrink_0()
                 .text_color(rgb(theme::text_primary(cx)))
-                .text_size(px(theme::FONT_BODY))
+                .text_size(theme::scaled(theme::FONT_BODY))
                 .font_family(fonts::MONO_FONT_FAMILY)
                 .font_weight(FontWeight::MEDIUM)
                 .child(format!(":{port}")),
@@ -201,7 +201,7 @@ fn listener_row(port: u16, host: String, cx: &mut Context<WebTunnelManager>) ->
                 .min_w_0()
                 .overflow_hidden()
                 .text_color(rgb(theme::text_muted(cx)))
-                .text_size(px(theme::FONT_DETAIL))
+                .text_size(theme::scaled(theme::FONT_DETAIL))
                 .font_family(fonts::MONO_FONT_FAMILY)
                 .child(host.clone()),
         )
@@ -218,7 +218,7 @@ fn stop_button(port: u16, host: String, cx: &mut Context<WebTunnelManager>) -> i
         .border_1()
         .border_color(rgb(theme::accent_red(cx)))
         .text_color(rgb(theme::accent_red(cx)))
-        .text_size(px(theme::FONT_BODY))
+        .text_size(theme::scaled(theme::FONT_BODY))
         .cursor_pointer()
         .hit_slop(px(8.0))
         .on_press(cx.listener(move |this, _event, _window, cx| {
@@ -9,10 +9,10 @@ use tokio::sync::{broadcast, mpsc};
 use tracing::*;
 use uuid::Uuid;
 use zedra_rpc::ZedraPairingTicket;
-use zedra_rpc::proto::{HostEvent, SyncSessionResult};
+use zedra_rpc::proto::{HostEvent, HostNotifyConfig, LineEnding, SyncSessionResult, TextEncoding};
 use zedra_session::{
-    ConnectEvent, ConnectPhase, ConnectSnapshot, ReconnectReason, Session, SessionHandle,
-    SessionState, signer::ClientSigner,
+    ConnectEvent, ConnectPhase, ConnectSnapshot, HostFeature, ReconnectReason, Session,
+    SessionHandle, SessionState, signer::ClientSigner,
 };
 
 use crate::agent;
@@ -20,34 +20,52 @@ use crate::agent_detail::AgentDetail;
 use crate::agent_manage::AgentManage;
 use crate::agent_picker::AgentPicker;
 use crate::agent_sessions::AgentSessions;
+use crate::ai_edit;
+use crate::command_guard::{self, GuardEditStep};
 use crate::delta::{ClientDeltaInfo, DeltaState};
 use crate::editor::git_sidebar::GitFileSection;
+use crate::file_history::FileHistoryView;
 use crate::file_search::{FileSearchEvent, FileSearchPanel};
+use crate::git_graph::GitGraphView;
+use crate::host_accent;
+use crate::host_notify::{self, NotifyEditStep};
+use crate::memory::{MemoryPressure, MemoryReclaim};
 use crate::pending::{SharedPendingSlot, shared_pending_slot, spawn_periodic_task};
-use crate::platform_bridge::{self, AlertButton, HapticFeedback, SoundEffect, status_bar_inset};
+use crate::platform_bridge::{
+    self, AlertButton, HapticFeedback, NativeNotificationKind, NativeNotificationOptions,
+    SoundEffect, status_bar_inset,
+};
+use crate::problems::ProblemsView;
+use crate::recording_player::RecordingPlayer;
+use crate::session_env::{self, EnvEditStep};
+use crate::settings;
 use crate::telemetry::view_telemetry;
 use crate::terminal_card::strip_ps1_prefix;
 use crate::terminal_state::TerminalState;
+use crate::terminal_triggers::{self, TriggerEditStep};
 use crate::theme;
 use crate::transport_badge::ConnectionStatusIndicator;
 use crate::ui::{DrawerEvent, DrawerHost, DrawerSide};
 use crate::workspace_action::{self, GoHome, OpenFileSearch, OpenQuickAction, RequestDisconnect};
 use crate::workspace_action::{
     AddSelectionToChat, CloseDrawer, CloseTerminal, CloseWebClient, CreateAgent, CreateNewTerminal,
-    GitCommit, GitShowItemActions, GitStage, GitUnstage, HideConnecting, NavigateBack,
-    OpenAgentDetail, OpenAgentManage, OpenAgentSessions, OpenDrawer, OpenFile, OpenGitDiff,
-    OpenTerminal, OpenWebClient, RestartConnection, ResumeAgentSession, RevealInFileExplorer,
-    ShowConnecting, SpawnAgentTerminal, SpawnAgentWebClient, ToggleDrawer,
+    EditSelectionWithAi, FindInFile, GitCommit, GitShowItemActions, GitStage, GitUnstage, GoToLine,
+    HideConnecting, ImportTheme, NavigateBack, OpenAgentDetail, OpenAgentManage, OpenAgentSessions,
+    OpenCommandGuard, OpenDrawer, OpenFile, OpenFileAtLine, OpenFileHistory, OpenGitDiff,
+    OpenGitGraph, OpenHostNotify, OpenProblems, OpenRecording, OpenSessionEnv, OpenSshHost,
+    OpenTerminal, OpenTerminalTriggers, OpenWebClient, RestartConnection, ResumeAgentSession,
+    RevealInFileExplorer, ShowConnecting, ShowTextFormatActions, SpawnAgentTerminal,
+    SpawnAgentWebClient, ToggleBlame, ToggleDrawer,
 };
 use crate::workspace_connecting::WorkspaceConnecting;
 use crate::workspace_connection_banner::{BannerEvent, ConnectionBanner};
 use crate::workspace_drawer::WorkspaceDrawer;
-use crate::workspace_editor::{EditorSelection, WorkspaceEditor};
+use crate::workspace_editor::{EditorSelection, TextFormat, TextFormatChanged, WorkspaceEditor};
 use crate::workspace_gitdiff::{GitdiffHeaderChanged, WorkspaceGitdiff};
 use crate::workspace_start::WorkspaceStart;
 use crate::workspace_state::{WorkspaceMainView, WorkspaceState, WorkspaceStateEvent};
 use crate::workspace_terminal::{TERMINAL_PENDING_ID, WorkspaceTerminal};
-use zedra_terminal::view::TerminalView;
+use zedra_terminal::view::{TerminalStatus, TerminalView};
 
 /// Events emitted by the workspace.
 /// The receiver is mostly app/workspaces
@@ -55,6 +73,7 @@ use zedra_terminal::view::TerminalView;
 pub enum WorkspaceEvent {
     GoHome,
     OpenQuickAction,
+    OpenHostProject { hostname: String, workdir: String },
     Disconnected,
 }
 
@@ -103,6 +122,9 @@ pub struct Workspace {
     seen_reconnect: bool,
     active_reconnect_reason: Option<ReconnectReason>,
     latency_sampler: LatencySampler,
+    /// Host version the "update zedra-host" notice was last shown for, so
+    /// reconnects to the same host stay quiet.
+    feature_notice_version: Option<String>,
     /// Listens for connect events and syncs them into SessionState/WorkspaceState.
     _connect_listener: Option<Task<()>>,
     /// Listens for host events/actions from the remote host.
@@ -110,11 +132,23 @@ pub struct Workspace {
     /// Listens for periodic host resource snapshots.
     _host_info_listener: Option<Task<()>>,
     _web_client_listener: Option<Task<()>>,
+    /// Re-sends the data saver state when the network's metered state changes.
+    _metered_listener: Option<Task<()>>,
     /// Listens for foreground resume events and checks the live session phase.
     _foreground_resume_listener: Option<Task<()>>,
     /// Notifies the host when app foreground/background state changes.
     _foreground_state_listener: Option<tokio::task::JoinHandle<()>>,
     agent_picker: Entity<AgentPicker>,
+    /// Problems screen, kept across navigation so returning from the editor
+    /// does not rerun the workspace check. Created on first open.
+    problems: Option<Entity<ProblemsView>>,
+    /// Commit graph screen, kept across navigation like `problems`.
+    git_graph: Option<Entity<GitGraphView>>,
+    /// File history screen, reused across files. Created on first open.
+    file_history: Option<Entity<FileHistoryView>>,
+    /// Recording replay screen, created by the first `OpenRecording` because
+    /// its terminal needs the window to size itself.
+    recording_player: Option<Entity<RecordingPlayer>>,
     /// Floating global file search overlay; shown above the drawer when open.
     file_search: Entity<FileSearchPanel>,
     file_search_open: bool,
@@ -157,10 +191,20 @@ pub(crate) enum PendingWorkspaceAction {
     SpawnAgentWebClient {
         slug: String,
     },
+    EditTerminalTriggers(TriggerEditStep),
+    EditCommandGuard(GuardEditStep),
+    EditSessionEnv(EnvEditStep),
+    EditHostNotify(NotifyEditStep),
+    EditSelectionWithAi {
+        selection: EditorSelection,
+        instruction: String,
+    },
 }
 
 const ADD_TO_CHAT_SEND_DELAY: Duration = Duration::from_millis(250);
 const FOREGROUND_LIVENESS_TIMEOUT: Duration = Duration::from_secs(2);
+/// How long a header revealed in a compact window stays before hiding again.
+const COMPACT_HEADER_REVEAL_DURATION: Duration = Duration::from_secs(4);
 
 #[derive(Clone)]
 struct ConnectionRequest {
@@ -318,6 +362,27 @@ fn reconnect_reason_label(reason: &ReconnectReason) -> &'static str {
     }
 }
 
+/// Connection summary for the terminal status overlay; `None` before the first connect.
+fn terminal_status(phase: &ConnectPhase, snap: &ConnectSnapshot) -> Option<TerminalStatus> {
+    let (label, sticky) = match phase {
+        ConnectPhase::Init => return None,
+        ConnectPhase::Connected | ConnectPhase::Idle { .. } => ("Connected".to_string(), false),
+        ConnectPhase::Reconnecting { attempt, .. } => (format!("Reconnecting ({attempt})"), true),
+        phase => (phase.display_name().to_string(), true),
+    };
+    let latency_ms = if phase.is_connected() {
+        snap.transport.as_ref().map(|transport| transport.rtt_ms)
+    } else {
+        None
+    };
+    Some(TerminalStatus {
+        label,
+        host: Some(snap.hostname.clone()).filter(|host| !host.is_empty()),
+        latency_ms,
+        sticky,
+    })
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum ForegroundResumeAction {
     ProbeLiveness,
@@ -832,6 +897,12 @@ impl Workspace {
                 });
             },
         );
+        let text_format_subscription =
+            cx.subscribe(&editor, |this, _editor, event: &TextFormatChanged, cx| {
+                this.content.update(cx, |content, cx| {
+                    content.set_file_text_format(event.0, cx);
+                });
+            });
         let connection_banner_subscription =
             cx.subscribe(&connection_banner, Self::handle_banner_event);
 
@@ -933,6 +1004,29 @@ impl Workspace {
                             break;
                         }
                     }
+                    Ok(HostEvent::Notification(notification)) => {
+                        let Ok(host) = workspace.read_with(cx, |ws, cx| {
+                            let state = ws.workspace_state.read(cx);
+                            host_accent::badged_name(state.display_name(), state.accent)
+                        }) else {
+                            break;
+                        };
+                        host_notify::show(&notification, &host);
+                    }
+                    Ok(HostEvent::GitCredentialRequested { id, url, username }) => {
+                        let should_break = workspace
+                            .update(cx, |ws, cx| {
+                                let handle = ws.session_handle().clone();
+                                cx.spawn(async move |_ws, _cx| {
+                                    crate::git_credential::answer(handle, id, url, username).await;
+                                })
+                                .detach();
+                            })
+                            .is_err();
+                        if should_break {
+                            break;
+                        }
+                    }
                     Ok(_) => {}
                     Err(broadcast::error::RecvError::Lagged(skipped)) => {
                         tracing::warn!("workspace host event listener lagged by {}", skipped);
@@ -1130,6 +1224,26 @@ impl Workspace {
             }
         });
 
+        let mut metered_rx = platform_bridge::subscribe_network_metered();
+        let metered_listener = cx.spawn(async move |workspace, cx| {
+            loop {
+                match metered_rx.recv().await {
+                    Ok(_) => {
+                        if workspace
+                            .update(cx, |ws, cx| ws.push_data_saver(cx))
+                            .is_err()
+                        {
+                            break;
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(_)) => {}
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+        let data_saver_subscription =
+            cx.observe_global::<settings::DataSaverSetting>(|ws, cx| ws.push_data_saver(cx));
+
         let pending_platform_action: SharedPendingSlot<PendingWorkspaceAction> =
             shared_pending_slot();
         let agent_picker = cx
@@ -1167,13 +1281,19 @@ impl Workspace {
             seen_reconnect: false,
             active_reconnect_reason: None,
             latency_sampler: LatencySampler::default(),
+            feature_notice_version: None,
             _connect_listener: None,
             _host_event_listener: Some(host_event_listener),
             _host_info_listener: Some(host_info_listener),
             _web_client_listener: Some(web_client_listener),
+            _metered_listener: Some(metered_listener),
             _foreground_resume_listener: Some(foreground_resume_listener),
             _foreground_state_listener: Some(foreground_state_listener.into()),
             agent_picker,
+            problems: None,
+            git_graph: None,
+            file_history: None,
+            recording_player: None,
             file_search,
             file_search_open: false,
             file_search_prev_focus: None,
@@ -1186,8 +1306,10 @@ impl Workspace {
                 workspace_state_subscription,
                 delta_state_subscription,
                 gitdiff_subscription,
+                text_format_subscription,
                 file_search_subscription,
                 connection_banner_subscription,
+                data_saver_subscription,
             ],
         }
     }
@@ -1202,6 +1324,38 @@ impl Workspace {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
+        self.spawn_connect_listener(window, cx);
+
+        let session_id = session_id.or_else(|| ticket.as_ref().map(|t| t.session_id.clone()));
+        let request = ConnectionRequest {
+            addr,
+            ticket,
+            signer,
+            session_id,
+        };
+        self.connection_request = Some(request.clone());
+        self.seen_reconnect = false;
+        self.delta_host_reconciling = false;
+        self.active_reconnect_reason = None;
+        self.latency_sampler.reset();
+        self.start_connection(request);
+
+        self.content.update(cx, |c, cx| c.show_connecting_view(cx));
+    }
+
+    /// Open this workspace on the in-process mock host from the developer
+    /// menu. Nothing is saved and there is no reconnect request to replay.
+    pub fn connect_mock(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.spawn_connect_listener(window, cx);
+        self.seen_reconnect = false;
+        self.active_reconnect_reason = None;
+        self.latency_sampler.reset();
+        self.session.connect_mock();
+
+        self.content.update(cx, |c, cx| c.show_connecting_view(cx));
+    }
+
+    fn spawn_connect_listener(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         // Spawn GPUI task: reads ConnectEvents → applies to SessionState entity → cx.notify()
         if let Some(mut event_rx) = self.session.take_event_receiver() {
             let closed_notify = self.session.closed_notify();
@@ -1253,8 +1407,10 @@ impl Workspace {
                         ) {
                             ws.active_reconnect_reason = None;
                         }
+                        ws.sync_terminal_status(cx);
                         if let ConnectEvent::SyncComplete { sync, .. } = &event {
                             ws.seed_terminal_meta_from_sync(sync, cx);
+                            ws.notify_host_feature_skew(sync.host_version.as_deref());
                             ws.workspace_state.update(cx, |state, cx| {
                                 state.set_delta_host_pubkey(sync.delta_pubkey, cx);
                             });
@@ -1288,6 +1444,47 @@ impl Workspace {
                             warn!("session handle was not ready after SyncComplete");
                         }
 
+                        // Before terminals are initialized, so they start with the overrides.
+                        let env_push = workspace
+                            .update(cx, |ws, cx| {
+                                let vars = ws.workspace_state.read(cx).session_env.clone();
+                                (!vars.is_empty()).then(|| (ws.session.handle().clone(), vars))
+                            })
+                            .ok()
+                            .flatten();
+                        if let Some((handle, vars)) = env_push
+                            && let Err(error) = handle.session_env_set(vars).await
+                        {
+                            warn!("session env: sync after connect failed: {}", error);
+                        }
+
+                        // The host session starts with every watcher off.
+                        let notify_push = workspace
+                            .update(cx, |ws, cx| {
+                                let config = ws.workspace_state.read(cx).host_notify.clone();
+                                (config != HostNotifyConfig::default())
+                                    .then(|| (ws.session.handle().clone(), config))
+                            })
+                            .ok()
+                            .flatten();
+                        if let Some((handle, config)) = notify_push
+                            && let Err(error) = handle.host_notify_set(config).await
+                        {
+                            warn!("host notify: sync after connect failed: {}", error);
+                        }
+
+                        // The host session starts with data saver off.
+                        let data_saver = workspace
+                            .update(cx, |ws, cx| {
+                                (ws.session.handle().clone(), settings::data_saver_active(cx))
+                            })
+                            .ok();
+                        if let Some((handle, enabled)) = data_saver
+                            && enabled
+                        {
+                            handle.notify_data_saver(true).await;
+                        }
+
                         let refresh_task = match workspace.update(cx, |ws, cx| {
                             ws.drawer
                                 .update(cx, |drawer, cx| drawer.refresh_after_sync(cx))
@@ -1347,22 +1544,6 @@ impl Workspace {
                 }
             }));
         }
-
-        let session_id = session_id.or_else(|| ticket.as_ref().map(|t| t.session_id.clone()));
-        let request = ConnectionRequest {
-            addr,
-            ticket,
-            signer,
-            session_id,
-        };
-        self.connection_request = Some(request.clone());
-        self.seen_reconnect = false;
-        self.delta_host_reconciling = false;
-        self.active_reconnect_reason = None;
-        self.latency_sampler.reset();
-        self.start_connection(request);
-
-        self.content.update(cx, |c, cx| c.show_connecting_view(cx));
     }
 
     fn start_connection(&self, request: ConnectionRequest) {
@@ -1378,6 +1559,28 @@ impl Workspace {
         );
     }
 
+    /// Once per host version, say which features an older host is missing.
+    /// The features themselves hide or fall back via `SessionHandle::supports`.
+    fn notify_host_feature_skew(&mut self, host_version: Option<&str>) {
+        let Some(host_version) = host_version else {
+            return;
+        };
+        if self.feature_notice_version.as_deref() == Some(host_version) {
+            return;
+        }
+        self.feature_notice_version = Some(host_version.to_string());
+        let missing: Vec<HostFeature> = HostFeature::ALL
+            .into_iter()
+            .filter(|feature| !feature.supported_by(Some(host_version)))
+            .collect();
+        if let Some(notice) = zedra_session::update_notice(&missing) {
+            info!("host {host_version} lacks {:?}", missing);
+            platform_bridge::show_native_notification(
+                NativeNotificationOptions::new(notice).kind(NativeNotificationKind::Info),
+            );
+        }
+    }
+
     /// Sync the current workspace host binding to Delta and the host daemon.
     /// The workspace persists the host pubkey/node id, while DeltaState caches
     /// host node ids by pubkey so later reconnects or delayed sign-in can reuse
@@ -1646,6 +1849,18 @@ impl Workspace {
         info!("restarted workspace connection with fresh ticket");
     }
 
+    /// Tell the host whether to batch and summarize this session's terminal
+    /// output. Later connects re-send it after sync.
+    fn push_data_saver(&self, cx: &mut Context<Self>) {
+        let handle = self.session.handle().clone();
+        if !handle.has_client() {
+            return;
+        }
+        let enabled = settings::data_saver_active(cx);
+        cx.spawn(async move |_, _| handle.notify_data_saver(enabled).await)
+            .detach();
+    }
+
     pub fn session_handle(&self) -> &SessionHandle {
         self.session.handle()
     }
@@ -1697,6 +1912,24 @@ impl Workspace {
         self.latency_sampler.reset();
     }
 
+    /// Release rebuildable caches and, under harder pressure, trim scrollback.
+    pub fn trim_memory(
+        &mut self,
+        pressure: MemoryPressure,
+        cx: &mut Context<Self>,
+    ) -> MemoryReclaim {
+        let mut reclaim = MemoryReclaim::default();
+        for terminal in &self.terminals {
+            reclaim.add(terminal.update(cx, |terminal, cx| terminal.trim_memory(pressure, cx)));
+        }
+        reclaim.add(self.editor.update(cx, |editor, cx| editor.trim_memory(cx)));
+        reclaim.add(
+            self.gitdiff
+                .update(cx, |gitdiff, cx| gitdiff.trim_memory(cx)),
+        );
+        reclaim
+    }
+
     pub fn prepare_for_saved_removal(&mut self) {
         self.persist_workspace_state = false;
         self.session.disconnect();
@@ -1718,6 +1951,11 @@ impl Workspace {
         self.activate_existing_terminal(id, cx);
     }
 
+    pub fn open_file_from_quick_action(&mut self, path: String, cx: &mut Context<Self>) {
+        self.drawer_host.update(cx, |host, cx| host.close(cx));
+        self.open_file_in_editor(path, cx);
+    }
+
     fn activate_existing_terminal(&mut self, id: String, cx: &mut Context<Self>) {
         self.drawer_host.update(cx, |host, cx| host.close(cx));
         if self.terminal_by_id(&id, cx).is_some() {
@@ -1792,6 +2030,21 @@ impl Workspace {
         cx.emit(WorkspaceEvent::OpenQuickAction);
     }
 
+    fn handle_open_host_project(
+        &mut self,
+        action: &workspace_action::OpenHostProject,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        info!("handle OpenHostProject from workspace");
+        self.drawer_host
+            .update(cx, |host, cx| host.close_with_window(window, cx));
+        cx.emit(WorkspaceEvent::OpenHostProject {
+            hostname: self.workspace_state(cx).hostname,
+            workdir: action.workdir.clone(),
+        });
+    }
+
     fn handle_open_file_search(
         &mut self,
         _action: &OpenFileSearch,
@@ -1961,6 +2214,63 @@ impl Workspace {
         self.open_file_in_editor(path, cx);
     }
 
+    fn handle_open_file_at_line(
+        &mut self,
+        action: &OpenFileAtLine,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        info!(line = action.line, "handle OpenFileAtLine from workspace");
+        window.clear_read_only_selection_cache();
+        self.drawer_host
+            .update(cx, |host, cx| host.close_with_window(&mut *window, cx));
+
+        let line = action.line;
+        self.editor
+            .update(cx, |editor, _cx| editor.set_pending_line(line));
+        self.open_file_in_editor(action.path.clone(), cx);
+    }
+
+    /// Open `path` (absolute or workdir-relative) in the editor, at the
+    /// 1-based `line` when given. For callers without a window to dispatch
+    /// `OpenFileAtLine` from, like the terminal's native menus.
+    pub(crate) fn open_file_at_line(
+        &mut self,
+        path: String,
+        line: Option<u32>,
+        cx: &mut Context<Self>,
+    ) {
+        let workdir = self.workspace_state.read(cx).workdir.clone();
+        if let Some(line) = line {
+            self.editor
+                .update(cx, |editor, _cx| editor.set_pending_line(line));
+        }
+        self.open_file_in_editor(workspace_relative_path(&path, &workdir), cx);
+    }
+
+    /// Open the drawer's file explorer and scroll it to `path` (absolute or
+    /// workdir-relative), expanding its ancestors. For callers without a
+    /// window, like the terminal's native menus.
+    pub(crate) fn reveal_in_files(&mut self, path: String, cx: &mut Context<Self>) {
+        cx.spawn(async move |this, cx| {
+            let _ = this.update_in(cx, |workspace, window, cx| {
+                workspace
+                    .drawer_host
+                    .update(cx, |host, cx| host.open_with_window(&mut *window, cx));
+                workspace
+                    .drawer
+                    .update(cx, |drawer, cx| drawer.reveal_path(path, window, cx));
+            });
+        })
+        .detach();
+    }
+
+    /// Last directory the active terminal reported with OSC 7, if any.
+    pub(crate) fn active_terminal_cwd(&self, cx: &App) -> Option<String> {
+        let id = self.workspace_state.read(cx).active_terminal_id.clone()?;
+        self.terminal_state.read(cx).meta(&id).cwd
+    }
+
     fn open_file_in_editor(&mut self, path: String, cx: &mut Context<Self>) {
         self.navigate_to(WorkspaceMainView::File { path }, cx);
     }
@@ -2124,6 +2434,66 @@ impl Workspace {
                 });
                 view_telemetry::record(view_telemetry::WORKSPACE_AGENT_DETAIL);
             }
+            WorkspaceMainView::Problems => {
+                let session_handle = self.session.handle().clone();
+                let view = self
+                    .problems
+                    .get_or_insert_with(|| cx.new(|cx| ProblemsView::new(session_handle, cx)))
+                    .clone();
+                self.content.update(cx, move |content, cx| {
+                    content.clear_subtitle(cx);
+                    content.set_main_view(view.into(), cx);
+                    content.hide_connecting_view(cx);
+                });
+                view_telemetry::record(view_telemetry::WORKSPACE_PROBLEMS);
+            }
+            WorkspaceMainView::GitGraph => {
+                let session_handle = self.session.handle().clone();
+                let view = self
+                    .git_graph
+                    .get_or_insert_with(|| cx.new(|cx| GitGraphView::new(session_handle, cx)))
+                    .clone();
+                self.content.update(cx, move |content, cx| {
+                    content.clear_subtitle(cx);
+                    content.set_main_view(view.into(), cx);
+                    content.hide_connecting_view(cx);
+                });
+                view_telemetry::record(view_telemetry::WORKSPACE_GIT_GRAPH);
+            }
+            WorkspaceMainView::FileHistory { path } => {
+                let session_handle = self.session.handle().clone();
+                let workspace_state = self.workspace_state.clone();
+                let view = self
+                    .file_history
+                    .get_or_insert_with(|| {
+                        cx.new(|cx| FileHistoryView::new(session_handle, workspace_state, cx))
+                    })
+                    .clone();
+                view.update(cx, |view, cx| view.open(path, cx));
+                self.content.update(cx, move |content, cx| {
+                    content.clear_subtitle(cx);
+                    content.set_main_view(view.into(), cx);
+                    content.hide_connecting_view(cx);
+                });
+                view_telemetry::record(view_telemetry::WORKSPACE_FILE_HISTORY);
+            }
+            WorkspaceMainView::Recording { name } => {
+                let Some(view) = self.recording_player.clone() else {
+                    warn!("recording player missing, falling back to default");
+                    self.workspace_state.update(cx, |state, cx| {
+                        state.navigate(WorkspaceMainView::Default, cx);
+                    });
+                    self.apply_route(WorkspaceMainView::Default, None, cx);
+                    return;
+                };
+                view.update(cx, |view, cx| view.open(name, cx));
+                self.content.update(cx, move |content, cx| {
+                    content.clear_subtitle(cx);
+                    content.set_main_view(view.into(), cx);
+                    content.hide_connecting_view(cx);
+                });
+                view_telemetry::record(view_telemetry::WORKSPACE_RECORDING);
+            }
         }
     }
 
@@ -2147,6 +2517,63 @@ impl Workspace {
         self.present_add_to_chat(selection, cx);
     }
 
+    fn handle_edit_selection_with_ai(
+        &mut self,
+        _action: &EditSelectionWithAi,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(selection) = self
+            .editor
+            .read(cx)
+            .selected_agent_context_range(window, cx)
+        else {
+            warn!("ai edit: missing selection");
+            return;
+        };
+        window.clear_read_only_selection_cache();
+        if !self.session_handle().supports(HostFeature::StreamingAi) {
+            platform_bridge::show_alert(
+                "Edit with AI",
+                &zedra_session::update_notice(&[HostFeature::StreamingAi]).unwrap_or_default(),
+                vec![AlertButton::cancel("OK")],
+                |_| {},
+            );
+            return;
+        }
+        if selection.end - selection.start >= ai_edit::MAX_SELECTION_LINES {
+            platform_bridge::show_alert(
+                "Selection too large",
+                &format!(
+                    "Select at most {} lines to edit with AI.",
+                    ai_edit::MAX_SELECTION_LINES
+                ),
+                vec![AlertButton::cancel("OK")],
+                |_| {},
+            );
+            return;
+        }
+
+        let pending_platform_action = self.pending_platform_action.clone();
+        platform_bridge::show_text_input(
+            "Edit with AI",
+            "Describe the change, e.g. handle the error case",
+            "",
+            move |result| {
+                let Some(instruction) = result.map(|text| text.trim().to_string()) else {
+                    return;
+                };
+                if instruction.is_empty() {
+                    return;
+                }
+                pending_platform_action.set(PendingWorkspaceAction::EditSelectionWithAi {
+                    selection,
+                    instruction,
+                });
+            },
+        );
+    }
+
     /// Present the "Add to Chat" agent-target picker for an already-resolved
     /// selection. Shared by the main editor and the file-preview sheet (via
     /// [`FilePreviewEvent`]); each caller clears its own window's read-only
@@ -2401,6 +2828,23 @@ impl Workspace {
         );
     }
 
+    fn handle_open_ssh_host(
+        &mut self,
+        action: &OpenSshHost,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        info!("handle OpenSshHost from workspace");
+        self.spawn_terminal(
+            "ssh_host",
+            Some(format!("ssh {}", action.alias)),
+            Some(action.alias.clone()),
+            None,
+            window,
+            cx,
+        );
+    }
+
     fn spawn_terminal(
         &mut self,
         telemetry_source: &'static str,
@@ -2442,6 +2886,13 @@ impl Workspace {
             );
         }
 
+        // Plain shells follow the directory the user is working in; launch
+        // commands (agents, ssh) keep starting from the workspace root.
+        let cwd = if launch_cmd.is_none() {
+            self.active_terminal_cwd(cx)
+        } else {
+            None
+        };
         let color_scheme = if crate::theme::bundle(cx).terminal.is_light() {
             zedra_rpc::proto::TerminalColorScheme::Light
         } else {
@@ -2451,7 +2902,13 @@ impl Workspace {
         cx.spawn(async move |workspace, cx| {
             let launch_cmd_for_meta = launch_cmd.clone();
             let terminal_id = match session_handle
-                .terminal_create_with_cmd(cols as u16, rows as u16, launch_cmd, Some(color_scheme))
+                .terminal_create_with_cmd(
+                    cols as u16,
+                    rows as u16,
+                    launch_cmd,
+                    Some(color_scheme),
+                    cwd,
+                )
                 .await
             {
                 Ok(id) => id,
@@ -2553,49 +3010,325 @@ impl Workspace {
         self.navigate_to(WorkspaceMainView::AgentManage, cx);
     }
 
-    fn handle_open_agent_detail(
+    fn handle_open_problems(
         &mut self,
-        action: &OpenAgentDetail,
+        _action: &OpenProblems,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        info!(agent = action.slug, "handle OpenAgentDetail from workspace");
+        info!("handle OpenProblems from workspace");
         self.drawer_host
             .update(cx, |host, cx| host.close_with_window(&mut *window, cx));
-        self.navigate_to(
-            WorkspaceMainView::AgentDetail {
-                slug: action.slug.clone(),
-            },
-            cx,
-        );
+        self.navigate_to(WorkspaceMainView::Problems, cx);
     }
 
-    fn handle_resume_agent_session(
+    fn handle_open_git_graph(
         &mut self,
-        action: &ResumeAgentSession,
+        _action: &OpenGitGraph,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        info!(
-            agent = action.slug,
-            session_id = %action.session_id,
-            "handle ResumeAgentSession from workspace"
-        );
-        self.resume_agent_session(action.slug.clone(), action.session_id.clone(), window, cx);
+        info!("handle OpenGitGraph from workspace");
+        self.drawer_host
+            .update(cx, |host, cx| host.close_with_window(&mut *window, cx));
+        self.navigate_to(WorkspaceMainView::GitGraph, cx);
     }
 
-    fn resume_agent_session(
+    fn handle_toggle_blame(
         &mut self,
-        slug: String,
-        session_id: String,
+        _action: &ToggleBlame,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        info!("handle ToggleBlame from workspace");
+        self.editor.update(cx, |editor, cx| editor.toggle_blame(cx));
+    }
+
+    fn handle_find_in_file(
+        &mut self,
+        _action: &FindInFile,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let session_handle = self.session.handle().clone();
-        let initial_viewport = self.mainview_viewport(window, cx);
-        let initial_grid_size = TerminalView::compute_grid_size(window, initial_viewport);
-        let cols = initial_grid_size.columns;
-        let rows = initial_grid_size.rows;
+        info!("handle FindInFile from workspace");
+        self.editor
+            .update(cx, |editor, cx| editor.open_find(window, cx));
+    }
+
+    fn handle_go_to_line(
+        &mut self,
+        _action: &GoToLine,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        info!("handle GoToLine from workspace");
+        self.editor.update(cx, |editor, cx| editor.go_to_line(cx));
+    }
+
+    fn handle_open_file_history(
+        &mut self,
+        action: &OpenFileHistory,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        info!("handle OpenFileHistory from workspace: {}", action.path);
+        let workdir = self.workspace_state.read(cx).workdir.clone();
+        let path = workspace_relative_path(&action.path, &workdir);
+        self.drawer_host
+            .update(cx, |host, cx| host.close_with_window(&mut *window, cx));
+        self.navigate_to(WorkspaceMainView::FileHistory { path }, cx);
+    }
+
+    fn handle_import_theme(
+        &mut self,
+        action: &ImportTheme,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        info!("handle ImportTheme from workspace: {}", action.path);
+        let handle = self.session.handle().clone();
+        let path = action.path.clone();
+        cx.spawn(async move |workspace, cx| {
+            let source = match handle.fs_read(&path).await {
+                Ok(read) if read.error.is_none() && !read.too_large => read.content,
+                Ok(read) => {
+                    let message = read
+                        .error
+                        .unwrap_or_else(|| "The file is too large.".to_string());
+                    platform_bridge::show_alert(
+                        "Could not import theme",
+                        &message,
+                        vec![AlertButton::default("OK")],
+                        |_| {},
+                    );
+                    return;
+                }
+                Err(e) => {
+                    tracing::error!("theme import read failed: {}", e);
+                    platform_bridge::show_alert(
+                        "Could not import theme",
+                        &e.to_string(),
+                        vec![AlertButton::default("OK")],
+                        |_| {},
+                    );
+                    return;
+                }
+            };
+            let imported = workspace.update(cx, |_ws, cx| {
+                settings::theme_state(cx)
+                    .ok_or_else(|| "Theme settings are unavailable.".to_string())
+                    .and_then(|state| state.update(cx, |state, cx| state.import_theme(&source, cx)))
+            });
+            match imported {
+                Ok(Ok(name)) => {
+                    platform_bridge::trigger_haptic(HapticFeedback::ImpactLight);
+                    platform_bridge::show_native_notification(
+                        NativeNotificationOptions::new(format!("Using {name}"))
+                            .kind(NativeNotificationKind::Success),
+                    );
+                }
+                Ok(Err(message)) => platform_bridge::show_alert(
+                    "Could not import theme",
+                    &message,
+                    vec![AlertButton::default("OK")],
+                    |_| {},
+                ),
+                Err(_) => {}
+            }
+        })
+        .detach();
+    }
+
+    fn handle_show_text_format_actions(
+        &mut self,
+        _action: &ShowTextFormatActions,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(format) = self.editor.read(cx).text_format() else {
+            return;
+        };
+        let mut choices: Vec<(&str, Option<TextEncoding>, Option<LineEnding>)> = Vec::new();
+        if format.line_ending != LineEnding::Lf || format.mixed_line_endings {
+            choices.push(("Use LF Line Endings", None, Some(LineEnding::Lf)));
+        }
+        if format.line_ending != LineEnding::Crlf || format.mixed_line_endings {
+            choices.push(("Use CRLF Line Endings", None, Some(LineEnding::Crlf)));
+        }
+        if format.encoding != TextEncoding::Utf8 {
+            choices.push(("Save as UTF-8", Some(TextEncoding::Utf8), None));
+        }
+        // No format change: only the save cleanup runs.
+        choices.push(("Clean Up Whitespace", None, None));
+        let mut buttons: Vec<AlertButton> = choices
+            .iter()
+            .map(|(label, _, _)| AlertButton::default(*label))
+            .collect();
+        buttons.push(AlertButton::cancel("Cancel"));
+        let message = if format.mixed_line_endings {
+            format!(
+                "{}. Lines end in both LF and CRLF; saving uses {}.",
+                format.encoding.label(),
+                format.line_ending.label()
+            )
+        } else {
+            format!(
+                "{} line endings, {}.",
+                format.line_ending.label(),
+                format.encoding.label()
+            )
+        };
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        platform_bridge::show_selection("Text Format", &message, buttons, move |selection| {
+            let _ = tx.send(selection);
+        });
+        let editor = self.editor.clone();
+        cx.spawn(async move |_workspace, cx| {
+            let Ok(Some(index)) = rx.await else {
+                return;
+            };
+            let Some((_, encoding, line_ending)) = choices.get(index).copied() else {
+                return;
+            };
+            let _ = editor.update(cx, |editor, cx| {
+                if encoding.is_none() && line_ending.is_none() {
+                    editor.clean_up_whitespace(cx);
+                } else {
+                    editor.convert_text_format(encoding, line_ending, cx);
+                }
+            });
+        })
+        .detach();
+    }
+
+    fn handle_open_recording(
+        &mut self,
+        action: &OpenRecording,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        info!("handle OpenRecording from workspace: {}", action.name);
+        if self.recording_player.is_none() {
+            let session_handle = self.session.handle().clone();
+            let viewport = self.mainview_viewport(window, cx);
+            self.recording_player =
+                Some(cx.new(|cx| RecordingPlayer::new(session_handle, window, viewport, cx)));
+        }
+        self.drawer_host
+            .update(cx, |host, cx| host.close_with_window(&mut *window, cx));
+        self.navigate_to(
+            WorkspaceMainView::Recording {
+                name: action.name.clone(),
+            },
+            cx,
+        );
+    }
+
+    fn handle_open_command_guard(
+        &mut self,
+        _action: &OpenCommandGuard,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        info!("handle OpenCommandGuard from workspace");
+        command_guard::run_edit_step(
+            GuardEditStep::List,
+            &self.workspace_state,
+            &self.pending_platform_action,
+            cx,
+        );
+    }
+
+    fn handle_open_terminal_triggers(
+        &mut self,
+        _action: &OpenTerminalTriggers,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        info!("handle OpenTerminalTriggers from workspace");
+        terminal_triggers::run_edit_step(
+            TriggerEditStep::List,
+            &self.workspace_state,
+            &self.pending_platform_action,
+            cx,
+        );
+    }
+
+    fn handle_open_session_env(
+        &mut self,
+        _action: &OpenSessionEnv,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        info!("handle OpenSessionEnv from workspace");
+        session_env::run_edit_step(
+            EnvEditStep::List,
+            &self.workspace_state,
+            self.session.handle(),
+            &self.pending_platform_action,
+            cx,
+        );
+    }
+
+    fn handle_open_host_notify(
+        &mut self,
+        _action: &OpenHostNotify,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        info!("handle OpenHostNotify from workspace");
+        host_notify::run_edit_step(
+            NotifyEditStep::List,
+            &self.workspace_state,
+            self.session.handle(),
+            &self.pending_platform_action,
+            cx,
+        );
+    }
+
+    fn handle_open_agent_detail(
+        &mut self,
+        action: &OpenAgentDetail,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        info!(agent = action.slug, "handle OpenAgentDetail from workspace");
+        self.drawer_host
+            .update(cx, |host, cx| host.close_with_window(&mut *window, cx));
+        self.navigate_to(
+            WorkspaceMainView::AgentDetail {
+                slug: action.slug.clone(),
+            },
+            cx,
+        );
+    }
+
+    fn handle_resume_agent_session(
+        &mut self,
+        action: &ResumeAgentSession,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        info!(
+            agent = action.slug,
+            session_id = %action.session_id,
+            "handle ResumeAgentSession from workspace"
+        );
+        self.resume_agent_session(action.slug.clone(), action.session_id.clone(), window, cx);
+    }
+
+    fn resume_agent_session(
+        &mut self,
+        slug: String,
+        session_id: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let session_handle = self.session.handle().clone();
+        let initial_viewport = self.mainview_viewport(window, cx);
+        let initial_grid_size = TerminalView::compute_grid_size(window, initial_viewport);
+        let cols = initial_grid_size.columns;
+        let rows = initial_grid_size.rows;
         let workspace_terminal =
             self.create_terminal_entity(TERMINAL_PENDING_ID.to_string(), window, cx);
         let pending_entity_id = workspace_terminal.entity_id();
@@ -2975,6 +3708,14 @@ impl Workspace {
         match action {
             PendingWorkspaceAction::DisconnectSession => self.disconnect(cx),
             PendingWorkspaceAction::DeleteTerminal { id } => self.close_terminal_by_id(id, cx),
+            PendingWorkspaceAction::EditSelectionWithAi {
+                selection,
+                instruction,
+            } => {
+                self.editor.update(cx, |editor, cx| {
+                    editor.edit_selection_with_ai(selection, instruction, cx)
+                });
+            }
             PendingWorkspaceAction::AddSelectionToChat { target, input } => {
                 self.activate_existing_terminal(target.tid.clone(), cx);
                 self.schedule_add_to_chat_after_activation(target, input, cx);
@@ -3011,6 +3752,40 @@ impl Workspace {
                 })
                 .detach();
             }
+            PendingWorkspaceAction::EditTerminalTriggers(step) => {
+                terminal_triggers::run_edit_step(
+                    step,
+                    &self.workspace_state,
+                    &self.pending_platform_action,
+                    cx,
+                );
+            }
+            PendingWorkspaceAction::EditCommandGuard(step) => {
+                command_guard::run_edit_step(
+                    step,
+                    &self.workspace_state,
+                    &self.pending_platform_action,
+                    cx,
+                );
+            }
+            PendingWorkspaceAction::EditSessionEnv(step) => {
+                session_env::run_edit_step(
+                    step,
+                    &self.workspace_state,
+                    self.session.handle(),
+                    &self.pending_platform_action,
+                    cx,
+                );
+            }
+            PendingWorkspaceAction::EditHostNotify(step) => {
+                host_notify::run_edit_step(
+                    step,
+                    &self.workspace_state,
+                    self.session.handle(),
+                    &self.pending_platform_action,
+                    cx,
+                );
+            }
         }
     }
 
@@ -3078,10 +3853,22 @@ impl Workspace {
                 cx,
             )
         });
+        let state = self.session_state.read(cx);
+        let status = terminal_status(&state.phase, &state.snapshot);
+        entity.update(cx, |terminal, cx| terminal.set_status(status, cx));
         self.terminals.push(entity.clone());
         entity
     }
 
+    /// Mirror the connection phase, host, and latency into every terminal's overlay.
+    fn sync_terminal_status(&mut self, cx: &mut Context<Self>) {
+        let state = self.session_state.read(cx);
+        let status = terminal_status(&state.phase, &state.snapshot);
+        for terminal in &self.terminals {
+            terminal.update(cx, |terminal, cx| terminal.set_status(status.clone(), cx));
+        }
+    }
+
     fn terminal_by_id(
         &self,
         id: &str,
@@ -3162,6 +3949,7 @@ impl Render for Workspace {
             .key_context("workspace")
             .on_action(cx.listener(Self::handle_go_home))
             .on_action(cx.listener(Self::handle_open_quick_action))
+            .on_action(cx.listener(Self::handle_open_host_project))
             .on_action(cx.listener(Self::handle_open_file_search))
             .on_action(cx.listener(Self::handle_request_disconnect))
             .on_action(cx.listener(Self::handle_toggle_drawer))
@@ -3171,8 +3959,10 @@ impl Render for Workspace {
             .on_action(cx.listener(Self::handle_hide_connecting))
             .on_action(cx.listener(Self::handle_restart_connection))
             .on_action(cx.listener(Self::handle_open_file))
+            .on_action(cx.listener(Self::handle_open_file_at_line))
             .on_action(cx.listener(Self::handle_reveal_in_file_explorer))
             .on_action(cx.listener(Self::handle_add_selection_to_chat))
+            .on_action(cx.listener(Self::handle_edit_selection_with_ai))
             .on_action(cx.listener(Self::handle_open_git_diff))
             .on_action(cx.listener(Self::handle_git_stage))
             .on_action(cx.listener(Self::handle_git_unstage))
@@ -3181,10 +3971,24 @@ impl Render for Workspace {
             .on_action(cx.listener(Self::handle_create_new_terminal))
             .on_action(cx.listener(Self::handle_create_agent))
             .on_action(cx.listener(Self::handle_spawn_agent_terminal))
+            .on_action(cx.listener(Self::handle_open_ssh_host))
             .on_action(cx.listener(Self::handle_navigate_back))
             .on_action(cx.listener(Self::handle_open_agent_sessions))
             .on_action(cx.listener(Self::handle_open_agent_manage))
             .on_action(cx.listener(Self::handle_open_agent_detail))
+            .on_action(cx.listener(Self::handle_open_problems))
+            .on_action(cx.listener(Self::handle_open_git_graph))
+            .on_action(cx.listener(Self::handle_open_file_history))
+            .on_action(cx.listener(Self::handle_import_theme))
+            .on_action(cx.listener(Self::handle_show_text_format_actions))
+            .on_action(cx.listener(Self::handle_open_recording))
+            .on_action(cx.listener(Self::handle_toggle_blame))
+            .on_action(cx.listener(Self::handle_find_in_file))
+            .on_action(cx.listener(Self::handle_go_to_line))
+            .on_action(cx.listener(Self::handle_open_terminal_triggers))
+            .on_action(cx.listener(Self::handle_open_command_guard))
+            .on_action(cx.listener(Self::handle_open_session_env))
+            .on_action(cx.listener(Self::handle_open_host_notify))
             .on_action(cx.listener(Self::handle_resume_agent_session))
             .on_action(cx.listener(Self::handle_open_terminal))
             .on_action(cx.listener(Self::handle_close_terminal))
@@ -3322,6 +4126,7 @@ mod tests {
                 host_version: Some("0.1.1".into()),
                 delta_pubkey: [7; 32],
                 terminals: Vec::new(),
+                ai_provider: Default::default(),
             },
             sync_ms: 7,
         }
@@ -3356,6 +4161,36 @@ mod tests {
         assert_eq!(mode, Some(SyncRefreshMode::Reconnect));
     }
 
+    #[::core::prelude::v1::test]
+    fn terminal_status_tracks_connect_phase() {
+        let snap = ConnectSnapshot {
+            hostname: "devbox".into(),
+            ..Default::default()
+        };
+        assert_eq!(terminal_status(&ConnectPhase::Init, &snap), None);
+
+        let connecting = terminal_status(&ConnectPhase::HolePunching, &snap).unwrap();
+        assert_eq!(connecting.label, "Hole punching");
+        assert!(connecting.sticky);
+        assert_eq!(connecting.host.as_deref(), Some("devbox"));
+
+        let reconnecting = terminal_status(
+            &ConnectPhase::Reconnecting {
+                attempt: 2,
+                reason: ReconnectReason::ConnectionLost,
+                next_retry_secs: 4,
+            },
+            &snap,
+        )
+        .unwrap();
+        assert_eq!(reconnecting.label, "Reconnecting (2)");
+        assert!(reconnecting.sticky);
+
+        let connected = terminal_status(&ConnectPhase::Connected, &snap).unwrap();
+        assert_eq!(connected.label, "Connected");
+        assert!(!connected.sticky);
+    }
+
     #[::core::prelude::v1::test]
     fn foreground_resume_probes_only_established_connections() {
         assert_eq!(
@@ -3781,6 +4616,12 @@ mod tests {
             "/other/README.md"
         );
     }
+    #[::core::prelude::v1::test]
+    fn only_short_landscape_windows_hide_the_header() {
+        assert!(is_compact_viewport(size(px(844.0), px(390.0))));
+        assert!(!is_compact_viewport(size(px(390.0), px(844.0))));
+        assert!(!is_compact_viewport(size(px(1180.0), px(820.0))));
+    }
 }
 
 pub struct WorkspaceContent {
@@ -3795,6 +4636,9 @@ pub struct WorkspaceContent {
     connecting_view: Entity<WorkspaceConnecting>,
     connection_banner: Entity<ConnectionBanner>,
     mainview_bounds: Option<Bounds<Pixels>>,
+    /// Header shown over a compact window after a tap or swipe on its handle.
+    compact_header_revealed: bool,
+    compact_header_hide_generation: u64,
     _subscriptions: Vec<Subscription>,
 }
 
@@ -3805,6 +4649,7 @@ enum WorkspaceSubtitle {
     },
     File {
         path: SharedString,
+        format: Option<TextFormat>,
     },
     Terminal {
         id: String,
@@ -3823,12 +4668,135 @@ fn render_subtitle(cx: &App, text: impl IntoElement) -> AnyElement {
         .truncate()
         .text_center()
         .text_color(rgb(theme::text_secondary(cx)))
-        .text_size(px(theme::FONT_BODY))
+        .text_size(theme::scaled(theme::FONT_BODY))
         .font_weight(FontWeight::MEDIUM)
         .child(text)
         .into_any_element()
 }
 
+/// File path with buttons for find, the blame gutter and the file's commit
+/// history, plus theme import for JSON files. The text format, once loaded,
+/// opens the conversion menu.
+fn render_file_subtitle(cx: &App, path: SharedString, format: Option<TextFormat>) -> AnyElement {
+    let history_path = path.to_string();
+    let theme_path =
+        (path.ends_with(".json") || path.ends_with(".jsonc")).then(|| path.to_string());
+    div()
+        .w_full()
+        .min_w_0()
+        .px_2()
+        .flex()
+        .flex_row()
+        .items_center()
+        .justify_center()
+        .gap(px(6.0))
+        .child(
+            div()
+                .min_w_0()
+                .flex_shrink()
+                .truncate()
+                .text_center()
+                .text_color(rgb(theme::text_secondary(cx)))
+                .text_size(theme::scaled(theme::FONT_BODY))
+                .font_weight(FontWeight::MEDIUM)
+                .child(path),
+        )
+        .child(
+            div()
+                .id("file-find-btn")
+                .flex_shrink_0()
+                .cursor_pointer()
+                .on_press(|_event, window, cx| {
+                    platform_bridge::trigger_haptic(HapticFeedback::ImpactLight);
+                    window.dispatch_action(FindInFile.boxed_clone(), cx);
+                })
+                .child(
+                    svg()
+                        .path("icons/search.svg")
+                        .size(px(12.0))
+                        .text_color(rgb(theme::text_muted(cx))),
+                ),
+        )
+        .child(
+            div()
+                .id("file-blame-btn")
+                .flex_shrink_0()
+                .cursor_pointer()
+                .on_press(|_event, window, cx| {
+                    platform_bridge::trigger_haptic(HapticFeedback::ImpactLight);
+                    window.dispatch_action(ToggleBlame.boxed_clone(), cx);
+                })
+                .child(
+                    svg()
+                        .path("icons/git-branch.svg")
+                        .size(px(12.0))
+                        .text_color(rgb(theme::text_muted(cx))),
+                ),
+        )
+        .child(
+            div()
+                .id("file-history-btn")
+                .flex_shrink_0()
+                .cursor_pointer()
+                .on_press(move |_event, window, cx| {
+                    platform_bridge::trigger_haptic(HapticFeedback::ImpactLight);
+                    window.dispatch_action(
+                        OpenFileHistory {
+                            path: history_path.clone(),
+                        }
+                        .boxed_clone(),
+                        cx,
+                    );
+                })
+                .child(
+                    svg()
+                        .path("icons/history.svg")
+                        .size(px(12.0))
+                        .text_color(rgb(theme::text_muted(cx))),
+                ),
+        )
+        .when_some(format, |this, format| {
+            this.child(
+                div()
+                    .id("file-text-format-btn")
+                    .flex_shrink_0()
+                    .cursor_pointer()
+                    .text_size(theme::scaled(theme::FONT_DETAIL))
+                    .text_color(rgb(theme::text_muted(cx)))
+                    .on_press(|_event, window, cx| {
+                        platform_bridge::trigger_haptic(HapticFeedback::ImpactLight);
+                        window.dispatch_action(ShowTextFormatActions.boxed_clone(), cx);
+                    })
+                    .child(format.label()),
+            )
+        })
+        .when_some(theme_path, |this, theme_path| {
+            this.child(
+                div()
+                    .id("file-import-theme-btn")
+                    .flex_shrink_0()
+                    .cursor_pointer()
+                    .on_press(move |_event, window, cx| {
+                        platform_bridge::trigger_haptic(HapticFeedback::ImpactLight);
+                        window.dispatch_action(
+                            ImportTheme {
+                                path: theme_path.clone(),
+                            }
+                            .boxed_clone(),
+                            cx,
+                        );
+                    })
+                    .child(
+                        svg()
+                            .path("icons/palette.svg")
+                            .size(px(12.0))
+                            .text_color(rgb(theme::text_muted(cx))),
+                    ),
+            )
+        })
+        .into_any_element()
+}
+
 fn render_gitdiff_subtitle(
     cx: &App,
     filename: SharedString,
@@ -3844,7 +4812,7 @@ fn render_gitdiff_subtitle(
         .items_center()
         .justify_center()
         .gap(px(6.0))
-        .text_size(px(theme::FONT_BODY))
+        .text_size(theme::scaled(theme::FONT_BODY))
         .font_weight(FontWeight::MEDIUM)
         .child(
             div()
@@ -3900,6 +4868,8 @@ impl WorkspaceContent {
             connecting_view: connecting,
             connection_banner,
             mainview_bounds: None,
+            compact_header_revealed: false,
+            compact_header_hide_generation: 0,
             _subscriptions: vec![terminal_state_sub, workspace_state_sub],
         }
     }
@@ -3934,10 +4904,22 @@ impl WorkspaceContent {
         let workdir = self.workspace_state.read(cx).workdir.clone();
         self.subtitle = WorkspaceSubtitle::File {
             path: workspace_relative_path(&path, &workdir).into(),
+            format: None,
         };
         cx.notify();
     }
 
+    pub fn set_file_text_format(
+        &mut self,
+        text_format: Option<TextFormat>,
+        cx: &mut Context<Self>,
+    ) {
+        if let WorkspaceSubtitle::File { format, .. } = &mut self.subtitle {
+            *format = text_format;
+            cx.notify();
+        }
+    }
+
     pub fn set_git_diff_subtitle(
         &mut self,
         filename: String,
@@ -3978,7 +4960,9 @@ impl WorkspaceContent {
         match &self.subtitle {
             WorkspaceSubtitle::Default => render_subtitle(cx, default_subtitle.to_owned()),
             WorkspaceSubtitle::Text { text } => render_subtitle(cx, text.clone()),
-            WorkspaceSubtitle::File { path } => render_subtitle(cx, path.clone()),
+            WorkspaceSubtitle::File { path, format } => {
+                render_file_subtitle(cx, path.clone(), *format)
+            }
             WorkspaceSubtitle::Terminal { id } => {
                 let meta = self.terminal_state.read(cx).meta(id);
                 let subtitle = meta
@@ -4004,13 +4988,187 @@ impl WorkspaceContent {
 
     pub fn fallback_mainview_viewport(window: &mut Window) -> Size<Pixels> {
         let viewport = window.viewport_size();
+        let header_height = if is_compact_viewport(viewport) {
+            theme::COMPACT_HEADER_HANDLE_HEIGHT
+        } else {
+            theme::HEADER_HEIGHT
+        };
 
         Size {
             width: viewport.width,
-            height: (viewport.height - px(status_bar_inset() + theme::HEADER_HEIGHT)).max(px(0.0)),
+            height: (viewport.height - px(status_bar_inset() + header_height)).max(px(0.0)),
         }
     }
 
+    /// Show the header over a compact window, then hide it again after
+    /// `COMPACT_HEADER_REVEAL_DURATION`.
+    fn reveal_compact_header(&mut self, cx: &mut Context<Self>) {
+        self.compact_header_revealed = true;
+        self.compact_header_hide_generation = self.compact_header_hide_generation.wrapping_add(1);
+        let generation = self.compact_header_hide_generation;
+        cx.notify();
+
+        cx.spawn(async move |this, cx| {
+            cx.background_executor()
+                .timer(COMPACT_HEADER_REVEAL_DURATION)
+                .await;
+            let _ = this.update(cx, |this, cx| {
+                if this.compact_header_hide_generation == generation {
+                    this.compact_header_revealed = false;
+                    cx.notify();
+                }
+            });
+        })
+        .detach();
+    }
+
+    fn render_header(
+        &self,
+        title: String,
+        default_subtitle: String,
+        connect_phase: Option<ConnectPhase>,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        // A host with an accent gets a heavier rule in its color so the
+        // session is recognizable at a glance.
+        let accent_color = self
+            .workspace_state
+            .read(cx)
+            .accent
+            .map(|accent| accent.color(cx));
+        div()
+            .h(px(theme::HEADER_HEIGHT))
+            .flex()
+            .flex_row()
+            .items_center()
+            .border_b_1()
+            .border_color(rgb(accent_color.unwrap_or(theme::border_subtle(cx))))
+            .when(accent_color.is_some(), |header| header.border_b_2())
+            .child(
+                div()
+                    .id("drawer-toggle-btn")
+                    .w(px(theme::HEADER_BUTTON_SIZE))
+                    .h(px(theme::HEADER_BUTTON_SIZE))
+                    .flex()
+                    .items_center()
+                    .justify_center()
+                    .cursor_pointer()
+                    .hit_slop(px(20.0))
+                    .on_press(cx.listener(|_this, _event, window, cx| {
+                        platform_bridge::trigger_haptic(HapticFeedback::ImpactLight);
+                        window.dispatch_action(workspace_action::ToggleDrawer.boxed_clone(), cx);
+                    }))
+                    .child(
+                        svg()
+                            .path("icons/menu.svg")
+                            .size(px(16.0))
+                            .text_color(rgb(theme::text_secondary(cx))),
+                    ),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .min_w_0()
+                    .flex()
+                    .flex_col()
+                    .items_center()
+                    .justify_center()
+                    .child(
+                        div()
+                            .flex()
+                            .flex_col()
+                            .items_center()
+                            .w_full()
+                            .min_w_0()
+                            .child(
+                                div()
+                                    .flex()
+                                    .flex_row()
+                                    .items_center()
+                                    .gap(px(5.0))
+                                    .max_w_full()
+                                    .child(
+                                        ConnectionStatusIndicator::from_phase(
+                                            "workspace-connect-status",
+                                            connect_phase.as_ref(),
+                                            &theme::palette(cx),
+                                        )
+                                        .size(6.0)
+                                        .on_press(
+                                            cx.listener(|this, _event, _window, cx| {
+                                                this.open_connecting_view(cx);
+                                            }),
+                                        ),
+                                    )
+                                    .child(
+                                        div()
+                                            .min_w_0()
+                                            .truncate()
+                                            .text_color(rgb(theme::text_muted(cx)))
+                                            .text_size(theme::scaled(theme::FONT_DETAIL))
+                                            .child(title),
+                                    ),
+                            ),
+                    )
+                    .child(self.render_subtitle(&default_subtitle, cx)),
+            )
+            .child(
+                div()
+                    .id("quick-action-btn")
+                    .w(px(theme::HEADER_BUTTON_SIZE))
+                    .h(px(theme::HEADER_BUTTON_SIZE))
+                    .flex()
+                    .items_center()
+                    .justify_center()
+                    .cursor_pointer()
+                    .hit_slop(px(20.0))
+                    .on_press(cx.listener(|_this, _event, window, cx| {
+                        platform_bridge::trigger_haptic(HapticFeedback::ImpactLight);
+                        window.dispatch_action(workspace_action::OpenQuickAction.boxed_clone(), cx);
+                    }))
+                    .child(
+                        svg()
+                            .path("icons/package.svg")
+                            .size(px(16.0))
+                            .text_color(rgb(theme::text_secondary(cx))),
+                    ),
+            )
+    }
+
+    fn render_compact_header_handle(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .id("workspace-compact-header-handle")
+            .h(px(theme::COMPACT_HEADER_HANDLE_HEIGHT))
+            .w_full()
+            .flex()
+            .items_center()
+            .justify_center()
+            .border_b_1()
+            .border_color(rgb(theme::border_subtle(cx)))
+            .hit_slop(px(8.0))
+            .on_press(cx.listener(|this, _event, _window, cx| {
+                platform_bridge::trigger_haptic(HapticFeedback::ImpactLight);
+                this.reveal_compact_header(cx);
+            }))
+            .on_scroll_wheel(cx.listener(|this, event: &ScrollWheelEvent, _window, cx| {
+                let delta_y = match event.delta {
+                    ScrollDelta::Pixels(delta) => f32::from(delta.y),
+                    ScrollDelta::Lines(delta) => delta.y * 20.0,
+                };
+                // A downward swipe from the top edge pulls the header back in.
+                if delta_y > 4.0 && !this.compact_header_revealed {
+                    this.reveal_compact_header(cx);
+                }
+            }))
+            .child(
+                div()
+                    .w(px(36.0))
+                    .h(px(4.0))
+                    .rounded_full()
+                    .bg(rgb(theme::border_default(cx))),
+            )
+    }
+
     fn update_mainview_bounds(&mut self, bounds: Bounds<Pixels>) {
         if self.mainview_bounds == Some(bounds) {
             return;
@@ -4020,6 +5178,12 @@ impl WorkspaceContent {
     }
 }
 
+/// A window wider than tall and short enough that the header would cost the
+/// terminal several rows, i.e. a phone in landscape.
+fn is_compact_viewport(viewport: Size<Pixels>) -> bool {
+    viewport.width > viewport.height && viewport.height < px(theme::COMPACT_VIEWPORT_MAX_HEIGHT)
+}
+
 impl Focusable for WorkspaceContent {
     fn focus_handle(&self, _cx: &App) -> FocusHandle {
         self.focus_handle.clone()
@@ -4027,7 +5191,7 @@ impl Focusable for WorkspaceContent {
 }
 
 impl Render for WorkspaceContent {
-    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let top_inset = status_bar_inset();
         let this = cx.weak_entity();
         let workspace_state = self.workspace_state.read(cx);
@@ -4047,6 +5211,15 @@ impl Render for WorkspaceContent {
         .absolute()
         .inset_0();
 
+        let header_hidden =
+            is_compact_viewport(window.viewport_size()) && !self.compact_header_revealed;
+        let header = if header_hidden {
+            self.render_compact_header_handle(cx).into_any_element()
+        } else {
+            self.render_header(title, default_subtitle, connect_phase, cx)
+                .into_any_element()
+        };
+
         div()
             .size_full()
             .flex()
@@ -4054,110 +5227,7 @@ impl Render for WorkspaceContent {
             .min_h_0()
             .bg(rgb(theme::bg_primary(cx)))
             .child(div().h(px(top_inset)))
-            .child(
-                div()
-                    .h(px(theme::HEADER_HEIGHT))
-                    .flex()
-                    .flex_row()
-                    .items_center()
-                    .border_b_1()
-                    .border_color(rgb(theme::border_subtle(cx)))
-                    .child(
-                        div()
-                            .id("drawer-toggle-btn")
-                            .w(px(theme::HEADER_BUTTON_SIZE))
-                            .h(px(theme::HEADER_BUTTON_SIZE))
-                            .flex()
-                            .items_center()
-                            .justify_center()
-                            .cursor_pointer()
-                            .hit_slop(px(20.0))
-                            .on_press(cx.listener(|_this, _event, window, cx| {
-                                platform_bridge::trigger_haptic(HapticFeedback::ImpactLight);
-                                window.dispatch_action(
-                                    workspace_action::ToggleDrawer.boxed_clone(),
-                                    cx,
-                                );
-                            }))
-                            .child(
-                                svg()
-                                    .path("icons/menu.svg")
-                                    .size(px(16.0))
-                                    .text_color(rgb(theme::text_secondary(cx))),
-                            ),
-                    )
-                    .child(
-                        div()
-                            .flex_1()
-                            .min_w_0()
-                            .flex()
-                            .flex_col()
-                            .items_center()
-                            .justify_center()
-                            .child(
-                                div()
-                                    .flex()
-                                    .flex_col()
-                                    .items_center()
-                                    .w_full()
-                                    .min_w_0()
-                                    .child(
-                                        div()
-                                            .flex()
-                                            .flex_row()
-                                            .items_center()
-                                            .gap(px(5.0))
-                                            .max_w_full()
-                                            .child(
-                                                ConnectionStatusIndicator::from_phase(
-                                                    "workspace-connect-status",
-                                                    connect_phase.as_ref(),
-                                                    &theme::palette(cx),
-                                                )
-                                                .size(6.0)
-                                                .on_press(cx.listener(
-                                                    |this, _event, _window, cx| {
-                                                        this.open_connecting_view(cx);
-                                                    },
-                                                )),
-                                            )
-                                            .child(
-                                                div()
-                                                    .min_w_0()
-                                                    .truncate()
-                                                    .text_color(rgb(theme::text_muted(cx)))
-                                                    .text_size(px(theme::FONT_DETAIL))
-                                                    .child(title),
-                                            ),
-                                    ),
-                            )
-                            .child(self.render_subtitle(&default_subtitle, cx)),
-                    )
-                    .child(
-                        div()
-                            .id("quick-action-btn")
-                            .w(px(theme::HEADER_BUTTON_SIZE))
-                            .h(px(theme::HEADER_BUTTON_SIZE))
-                            .flex()
-                            .items_center()
-                            .justify_center()
-                            .cursor_pointer()
-                            .hit_slop(px(20.0))
-                            .on_press(cx.listener(|_this, _event, window, cx| {
-                                platform_bridge::trigger_haptic(HapticFeedback::ImpactLight);
-                                window.dispatch_action(
-                                    workspace_action::OpenQuickAction.boxed_clone(),
-                                    cx,
-                                );
-                            }))
-                            .child(
-                                svg()
-                                    .path("icons/package.svg")
-                                    .size(px(16.0))
-                                    .text_color(rgb(theme::text_secondary(cx))),
-                            ),
-                    ),
-            )
+            .child(header)
             .child(
                 div()
                     .relative()
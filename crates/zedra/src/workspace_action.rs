@@ -10,6 +10,14 @@ pub struct OpenFile {
     pub path: String,
 }
 
+/// Open `path` in the editor scrolled to the 1-based `line`.
+#[derive(Clone, PartialEq, Action)]
+#[action(namespace = workspace, no_json)]
+pub struct OpenFileAtLine {
+    pub path: String,
+    pub line: u32,
+}
+
 #[derive(Clone, PartialEq, Action)]
 #[action(namespace = workspace, no_json)]
 pub struct RevealInFileExplorer {
@@ -20,6 +28,11 @@ pub struct RevealInFileExplorer {
 #[action(namespace = workspace, no_json)]
 pub struct AddSelectionToChat;
 
+/// Ask the host AI to rewrite the editor selection.
+#[derive(Clone, PartialEq, Action)]
+#[action(namespace = workspace, no_json)]
+pub struct EditSelectionWithAi;
+
 #[derive(Clone, PartialEq, Action)]
 #[action(namespace = workspace, no_json)]
 pub struct OpenGitDiff {
@@ -103,6 +116,74 @@ pub struct OpenAgentSessions;
 #[action(namespace = workspace, no_json)]
 pub struct OpenAgentManage;
 
+#[derive(Clone, PartialEq, Action)]
+#[action(namespace = workspace, no_json)]
+pub struct OpenProblems;
+
+#[derive(Clone, PartialEq, Action)]
+#[action(namespace = workspace, no_json)]
+pub struct OpenGitGraph;
+
+/// Show the commits that touched `path`, following renames.
+#[derive(Clone, PartialEq, Action)]
+#[action(namespace = workspace, no_json)]
+pub struct OpenFileHistory {
+    pub path: String,
+}
+
+/// Read the VS Code theme at host `path` and use it for the editor and terminal.
+#[derive(Clone, PartialEq, Action)]
+#[action(namespace = workspace, no_json)]
+pub struct ImportTheme {
+    pub path: String,
+}
+
+/// Offer line ending and encoding conversions for the open file.
+#[derive(Clone, PartialEq, Action)]
+#[action(namespace = workspace, no_json)]
+pub struct ShowTextFormatActions;
+
+/// Replay host terminal recording `name`.
+#[derive(Clone, PartialEq, Action)]
+#[action(namespace = workspace, no_json)]
+pub struct OpenRecording {
+    pub name: String,
+}
+
+/// Show or hide the blame gutter in the editor.
+#[derive(Clone, PartialEq, Action)]
+#[action(namespace = workspace, no_json)]
+pub struct ToggleBlame;
+
+/// Show the find bar over the open code file.
+#[derive(Clone, PartialEq, Action)]
+#[action(namespace = workspace, no_json)]
+pub struct FindInFile;
+
+/// Ask for a line number and scroll the open code file to it.
+#[derive(Clone, PartialEq, Action)]
+#[action(namespace = workspace, no_json)]
+pub struct GoToLine;
+
+#[derive(Clone, PartialEq, Action)]
+#[action(namespace = workspace, no_json)]
+pub struct OpenTerminalTriggers;
+
+/// Mark this host as production and edit which commands need confirming.
+#[derive(Clone, PartialEq, Action)]
+#[action(namespace = workspace, no_json)]
+pub struct OpenCommandGuard;
+
+/// Edit the environment overrides for new terminals and checks on this host.
+#[derive(Clone, PartialEq, Action)]
+#[action(namespace = workspace, no_json)]
+pub struct OpenSessionEnv;
+
+/// Edit which host watchers notify this device.
+#[derive(Clone, PartialEq, Action)]
+#[action(namespace = workspace, no_json)]
+pub struct OpenHostNotify;
+
 #[derive(Clone, PartialEq, Action)]
 #[action(namespace = workspace, no_json)]
 pub struct OpenAgentDetail {
@@ -121,6 +202,13 @@ pub struct SpawnAgentTerminal {
     pub agent_slug: String,
 }
 
+/// Open a terminal running `ssh <alias>` on the host, for an imported SSH host.
+#[derive(Clone, PartialEq, Action)]
+#[action(namespace = workspace, no_json)]
+pub struct OpenSshHost {
+    pub alias: String,
+}
+
 #[derive(Clone, PartialEq, Action)]
 #[action(namespace = workspace, no_json)]
 pub struct ResumeAgentSession {
@@ -173,6 +261,13 @@ pub struct OpenDrawer;
 #[action(namespace = workspace, no_json)]
 pub struct OpenQuickAction;
 
+/// Switch to this device's saved workspace for `workdir` on the same host.
+#[derive(Clone, PartialEq, Action)]
+#[action(namespace = workspace, no_json)]
+pub struct OpenHostProject {
+    pub workdir: String,
+}
+
 #[derive(Clone, PartialEq, Action)]
 #[action(namespace = workspace, no_json)]
 pub struct OpenFileSearch;
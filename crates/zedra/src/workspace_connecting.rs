@@ -116,7 +116,7 @@ fn render_details_toggle(expanded: bool, cx: &mut Context<WorkspaceConnecting>)
         }))
         .child(
             div()
-                .text_size(px(theme::FONT_DETAIL))
+                .text_size(theme::scaled(theme::FONT_DETAIL))
                 .text_color(rgb(theme::text_muted(cx)))
                 .child(label),
         )
@@ -190,7 +190,7 @@ fn render_phase_title(
                         .truncate()
                         .text_align(TextAlign::Center)
                         .text_color(rgb(theme::text_primary(cx)))
-                        .text_size(px(theme::FONT_HEADING))
+                        .text_size(theme::scaled(theme::FONT_HEADING))
                         .font_weight(FontWeight::MEDIUM)
                         .child(title),
                 )
@@ -307,7 +307,7 @@ fn render_discovery_rows(cx: &App, snap: &ConnectSnapshot) -> Div {
                         .w(px(60.0))
                         .flex_shrink_0()
                         .text_color(rgb(theme::text_muted(cx)))
-                        .text_size(px(theme::FONT_DETAIL))
+                        .text_size(theme::scaled(theme::FONT_DETAIL))
                         .child("Direct"),
                 )
                 .child(
@@ -316,7 +316,7 @@ fn render_discovery_rows(cx: &App, snap: &ConnectSnapshot) -> Div {
                         .min_w_0()
                         .truncate()
                         .text_color(rgb(theme::text_secondary(cx)))
-                        .text_size(px(theme::FONT_DETAIL))
+                        .text_size(theme::scaled(theme::FONT_DETAIL))
                         .child(direct_label),
                 ),
         );
@@ -393,7 +393,7 @@ fn render_detail(cx: &App, phase: &ConnectPhase, snap: &ConnectSnapshot) -> Div
         col = col.child(
             div()
                 .text_color(rgb(theme::text_muted(cx)))
-                .text_size(px(theme::FONT_DETAIL))
+                .text_size(theme::scaled(theme::FONT_DETAIL))
                 .child(timing),
         );
     }
@@ -408,7 +408,7 @@ fn render_detail(cx: &App, phase: &ConnectPhase, snap: &ConnectSnapshot) -> Div
         col = col.child(
             div()
                 .text_color(rgb(theme::text_muted(cx)))
-                .text_size(px(theme::FONT_DETAIL))
+                .text_size(theme::scaled(theme::FONT_DETAIL))
                 .child(format!(
                     "Attempt {} · retry in {}s",
                     attempt, next_retry_secs
@@ -427,7 +427,7 @@ fn render_section(cx: &App, title: &'static str, rows: Div) -> Div {
         .child(
             div()
                 .text_color(rgb(theme::text_muted(cx)))
-                .text_size(px(theme::FONT_DETAIL))
+                .text_size(theme::scaled(theme::FONT_DETAIL))
                 .mb(px(2.0))
                 .child(title),
         )
@@ -569,7 +569,7 @@ fn kv_row(cx: &App, key: &'static str, value: &str) -> Div {
                 .w(px(60.0))
                 .flex_shrink_0()
                 .text_color(rgb(theme::text_muted(cx)))
-                .text_size(px(theme::FONT_DETAIL))
+                .text_size(theme::scaled(theme::FONT_DETAIL))
                 .child(key),
         )
         .child(
@@ -578,7 +578,7 @@ fn kv_row(cx: &App, key: &'static str, value: &str) -> Div {
                 .min_w_0()
                 .truncate()
                 .text_color(rgb(theme::text_secondary(cx)))
-                .text_size(px(theme::FONT_DETAIL))
+                .text_size(theme::scaled(theme::FONT_DETAIL))
                 .child(value.to_string()),
         )
 }
@@ -172,7 +172,7 @@ impl ConnectionBanner {
                     .flex_1()
                     .min_w_0()
                     .truncate()
-                    .text_size(px(theme::FONT_DETAIL))
+                    .text_size(theme::scaled(theme::FONT_DETAIL))
                     .text_color(rgb(theme::text_secondary(cx)))
                     .child(message),
             )
@@ -432,7 +432,7 @@ impl Render for WorkspaceDrawer {
                                     .truncate()
                                     .text_center()
                                     .text_color(rgb(theme::text_secondary(cx)))
-                                    .text_size(px(theme::FONT_BODY))
+                                    .text_size(theme::scaled(theme::FONT_BODY))
                                     .font_weight(FontWeight::MEDIUM)
                                     .child(title),
                             )
@@ -443,7 +443,7 @@ impl Render for WorkspaceDrawer {
                                     .truncate()
                                     .text_center()
                                     .text_color(rgb(theme::text_muted(cx)))
-                                    .text_size(px(theme::FONT_BODY))
+                                    .text_size(theme::scaled(theme::FONT_BODY))
                                     .font_weight(FontWeight::MEDIUM)
                                     .child(subtitle),
                             ),
@@ -1,11 +1,34 @@
+use std::sync::Arc;
+
+use futures::channel::oneshot;
+use gpui::prelude::FluentBuilder as _;
 use gpui::*;
+use zedra_rpc::proto::{FsReadTextResult, GitBlameHunk, LineEnding, TextEncoding};
 use zedra_session::SessionHandle;
 
-use crate::editor::code_editor::{CODE_EDITOR_SELECTION_AREA_ID, EditorView, ParsedEditorSyntax};
+use crate::ai_edit::{AiEditApplied, AiEditSheet};
+use crate::commit_detail::{CommitDetailSheet, CommitRef, commit_sheet_options};
+use crate::editor::blame::is_uncommitted;
+use crate::editor::code_editor::{
+    BlameHunkTapped, CODE_EDITOR_SELECTION_AREA_ID, EditorDocument, EditorView, ParsedEditorSyntax,
+};
+use crate::editor::editorconfig::{self, EditorConfig, IndentStyle};
 use crate::editor::markdown::{
     MARKDOWN_SELECTION_AREA_ID, MarkdownView, is_markdown_path, parse_markdown_source,
 };
+use crate::editor::save_cleanup::CleanupSummary;
+use crate::editor::text_buffer::Buffer;
+use crate::memory::MemoryReclaim;
 use crate::placeholder::render_placeholder;
+use crate::platform_bridge::{
+    self, AlertButton, NativeNotificationKind, NativeNotificationOptions,
+};
+use crate::settings;
+use crate::telemetry::view_telemetry;
+use crate::theme;
+use crate::ui::input::Input;
+use crate::ui::{InputChanged, InputSubmit};
+use crate::view_state::ViewStateCache;
 
 #[derive(Clone, Debug)]
 enum FileState {
@@ -19,11 +42,50 @@ enum FileState {
 enum EditorContent {
     Code,
     Markdown,
+    Image,
+}
+
+/// Encoding and line ending of the open file as stored on the host, plus
+/// the indentation its `.editorconfig` asks for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TextFormat {
+    pub encoding: TextEncoding,
+    pub line_ending: LineEnding,
+    pub mixed_line_endings: bool,
+    pub indent: Option<IndentStyle>,
 }
 
+impl TextFormat {
+    fn of(read: &FsReadTextResult) -> Self {
+        Self {
+            encoding: read.encoding,
+            line_ending: read.line_ending,
+            mixed_line_endings: read.mixed_line_endings,
+            indent: None,
+        }
+    }
+
+    /// Short header label, e.g. `CRLF · UTF-8` or `LF · UTF-8 · 2 Spaces`.
+    pub fn label(&self) -> String {
+        let line_ending = if self.mixed_line_endings {
+            "Mixed"
+        } else {
+            self.line_ending.label()
+        };
+        let mut label = format!("{line_ending} · {}", self.encoding.label());
+        if let Some(indent) = self.indent {
+            label.push_str(" · ");
+            label.push_str(&indent.label());
+        }
+        label
+    }
+}
+
+/// The open file's text format became known, or was cleared for a new file.
+pub struct TextFormatChanged(pub Option<TextFormat>);
+
 pub struct WorkspaceEditor {
     path: String,
-    filename: String,
     state: FileState,
     content: EditorContent,
     editor_view: Entity<EditorView>,
@@ -31,47 +93,358 @@ pub struct WorkspaceEditor {
     session_handle: SessionHandle,
     read_task: Option<Task<()>>,
     open_epoch: u64,
+    /// 1-based line the next `open_file` scrolls to once loaded.
+    pending_line: Option<u32>,
+    /// Blame gutter mode; stays on across files until toggled off.
+    blame_enabled: bool,
+    blame_task: Option<Task<()>>,
+    commit_sheet: Entity<CommitDetailSheet>,
+    ai_edit_sheet: Entity<AiEditSheet>,
+    /// Scroll positions of files left for another route, by path.
+    view_states: ViewStateCache<String>,
+    text_format: Option<TextFormat>,
+    /// `.editorconfig` properties of the open file, once resolved.
+    editorconfig: Option<EditorConfig>,
+    editorconfig_task: Option<Task<()>>,
+    /// Decoded bytes of the open image file.
+    image: Option<Arc<Image>>,
+    find_input: Entity<Input>,
+    /// Find bar shown above code files.
+    find_open: bool,
+    /// Find bar treats the query as a regex.
+    find_regex: bool,
+    /// The regex in the find bar does not compile.
+    find_invalid: bool,
+    _subscriptions: Vec<Subscription>,
 }
 
+impl EventEmitter<TextFormatChanged> for WorkspaceEditor {}
+
 impl WorkspaceEditor {
-    pub fn new(session_handle: SessionHandle, cx: &mut App) -> Self {
+    pub fn trim_memory(&mut self, cx: &mut Context<Self>) -> MemoryReclaim {
+        let line_cache_bytes = self
+            .editor_view
+            .update(cx, |editor_view, _cx| editor_view.release_line_cache());
+        MemoryReclaim {
+            line_cache_bytes,
+            ..Default::default()
+        }
+    }
+
+    pub fn new(session_handle: SessionHandle, cx: &mut Context<Self>) -> Self {
+        let editor_view = cx.new(|cx| EditorView::new(cx));
+        let blame_sub = cx.subscribe(&editor_view, |this, _view, event: &BlameHunkTapped, cx| {
+            this.show_blame_commit(event.0.clone(), cx);
+        });
+        let commit_sheet = {
+            let session_handle = session_handle.clone();
+            cx.new(|cx| CommitDetailSheet::new(session_handle, cx))
+        };
+        let ai_edit_sheet = {
+            let session_handle = session_handle.clone();
+            cx.new(|cx| AiEditSheet::new(session_handle, cx))
+        };
+        let ai_edit_sub =
+            cx.subscribe(&ai_edit_sheet, |this, _sheet, event: &AiEditApplied, cx| {
+                if this.path == event.path {
+                    this.apply_ai_edit(event, cx);
+                }
+            });
+        let find_input = cx.new(|cx| {
+            Input::new(cx)
+                .placeholder("Find in file")
+                .compact(true)
+                .toggle_keyboard_on_press(true)
+        });
+        let find_changed_sub = cx.subscribe(
+            &find_input,
+            |this: &mut Self, _input, _event: &InputChanged, cx| {
+                this.run_find(cx);
+            },
+        );
+        let find_submit_sub = cx.subscribe(
+            &find_input,
+            |this: &mut Self, _input, _event: &InputSubmit, cx| {
+                this.step_match(true, cx);
+            },
+        );
         Self {
             path: String::new(),
-            filename: String::new(),
             state: FileState::Loading,
             content: EditorContent::Code,
-            editor_view: cx.new(|cx| EditorView::new(cx)),
+            editor_view,
             markdown_view: cx.new(|cx| MarkdownView::new(SharedString::default(), cx)),
             session_handle,
             read_task: None,
             open_epoch: 0,
+            pending_line: None,
+            blame_enabled: false,
+            blame_task: None,
+            commit_sheet,
+            ai_edit_sheet,
+            view_states: ViewStateCache::default(),
+            text_format: None,
+            editorconfig: None,
+            editorconfig_task: None,
+            image: None,
+            find_input,
+            find_open: false,
+            find_regex: false,
+            find_invalid: false,
+            _subscriptions: vec![blame_sub, ai_edit_sub, find_changed_sub, find_submit_sub],
         }
     }
 
+    /// Turn the blame gutter on or off for code files.
+    pub fn toggle_blame(&mut self, cx: &mut Context<Self>) {
+        self.blame_enabled = !self.blame_enabled;
+        if self.blame_enabled {
+            self.load_blame(cx);
+        } else {
+            self.blame_task = None;
+            self.editor_view.update(cx, |editor_view, cx| {
+                editor_view.clear_blame();
+                cx.notify();
+            });
+        }
+    }
+
+    fn load_blame(&mut self, cx: &mut Context<Self>) {
+        if !matches!(self.state, FileState::Loaded) || self.content != EditorContent::Code {
+            return;
+        }
+        let epoch = self.open_epoch;
+        let handle = self.session_handle.clone();
+        let path = self.path.clone();
+        self.blame_task = Some(cx.spawn(async move |this, cx| {
+            let result = handle.git_blame(&path).await;
+            let _ = this.update(cx, |this, cx| {
+                if this.open_epoch != epoch || !this.blame_enabled {
+                    return;
+                }
+                match result {
+                    Ok(hunks) => {
+                        let now = chrono::Utc::now().timestamp();
+                        this.editor_view.update(cx, |editor_view, cx| {
+                            editor_view.set_blame(hunks, now);
+                            cx.notify();
+                        });
+                    }
+                    Err(e) => {
+                        tracing::warn!("git blame failed for {}: {}", path, e);
+                        this.blame_enabled = false;
+                        platform_bridge::show_alert(
+                            "Blame unavailable",
+                            &e.to_string(),
+                            vec![AlertButton::cancel("OK")],
+                            |_| {},
+                        );
+                    }
+                }
+            });
+        }));
+    }
+
+    fn show_blame_commit(&mut self, hunk: GitBlameHunk, cx: &mut Context<Self>) {
+        if is_uncommitted(&hunk) {
+            platform_bridge::show_alert(
+                "Not committed yet",
+                "These lines have changes that are not in any commit.",
+                vec![AlertButton::cancel("OK")],
+                |_| {},
+            );
+            return;
+        }
+        let commit = CommitRef {
+            id: hunk.id,
+            summary: hunk.summary,
+            author: hunk.author,
+            timestamp: hunk.timestamp,
+            path: hunk.path,
+            old_path: None,
+        };
+        self.commit_sheet
+            .update(cx, |sheet, cx| sheet.open(commit, cx));
+        view_telemetry::record(view_telemetry::CUSTOM_SHEET_COMMIT_DETAIL);
+        platform_bridge::show_custom_sheet(commit_sheet_options(), self.commit_sheet.clone());
+    }
+
+    /// Ask the host AI to rewrite `selection` following `instruction`, and
+    /// show the suggestion as a diff to apply.
+    pub fn edit_selection_with_ai(
+        &mut self,
+        selection: EditorSelection,
+        instruction: String,
+        cx: &mut Context<Self>,
+    ) {
+        self.ai_edit_sheet
+            .update(cx, |sheet, cx| sheet.open(selection, instruction, cx));
+        view_telemetry::record(view_telemetry::CUSTOM_SHEET_AI_EDIT);
+        platform_bridge::show_custom_sheet(commit_sheet_options(), self.ai_edit_sheet.clone());
+    }
+
+    /// Patch the open file with an edit the AI sheet wrote to the host, so
+    /// only the changed lines are reparsed and re-highlighted. Reloads the
+    /// file when the view can't apply it in place.
+    fn apply_ai_edit(&mut self, event: &AiEditApplied, cx: &mut Context<Self>) {
+        let applied = matches!(self.state, FileState::Loaded)
+            && self.content == EditorContent::Code
+            && self.editor_view.update(cx, |editor_view, cx| {
+                let applied =
+                    editor_view.replace_lines(event.start, &event.replaced, &event.replacement);
+                cx.notify();
+                applied
+            });
+        if !applied {
+            self.open_file(event.path.clone(), cx);
+            return;
+        }
+        if self.blame_enabled {
+            self.load_blame(cx);
+        }
+        if self.find_open {
+            self.run_find(cx);
+        }
+    }
+
+    /// Show the find bar over the open code file and focus it.
+    pub fn open_find(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if !matches!(self.state, FileState::Loaded) || self.content != EditorContent::Code {
+            return;
+        }
+        self.find_open = true;
+        let input_focus = self.find_input.read(cx).focus_handle(cx);
+        input_focus.focus(window, cx);
+        window.show_soft_keyboard();
+        self.run_find(cx);
+    }
+
+    pub fn close_find(&mut self, cx: &mut Context<Self>) {
+        if !self.find_open {
+            return;
+        }
+        self.find_open = false;
+        self.find_invalid = false;
+        self.editor_view.update(cx, |editor_view, cx| {
+            editor_view.clear_find();
+            cx.notify();
+        });
+        cx.notify();
+    }
+
+    fn toggle_find_regex(&mut self, cx: &mut Context<Self>) {
+        self.find_regex = !self.find_regex;
+        self.run_find(cx);
+    }
+
+    fn run_find(&mut self, cx: &mut Context<Self>) {
+        let query = self.find_input.read(cx).get_value().to_string();
+        let regex = self.find_regex;
+        let result = self.editor_view.update(cx, |editor_view, cx| {
+            let result = editor_view.find(&query, regex);
+            if result.is_err() {
+                editor_view.clear_find();
+            }
+            cx.notify();
+            result
+        });
+        self.find_invalid = result.is_err();
+        cx.notify();
+    }
+
+    fn step_match(&mut self, forward: bool, cx: &mut Context<Self>) {
+        self.editor_view.update(cx, |editor_view, cx| {
+            editor_view.step_match(forward);
+            cx.notify();
+        });
+        cx.notify();
+    }
+
+    /// Ask for a line number and scroll the open code file to it.
+    pub fn go_to_line(&mut self, cx: &mut Context<Self>) {
+        if !matches!(self.state, FileState::Loaded) || self.content != EditorContent::Code {
+            return;
+        }
+        let line_count = self.editor_view.read(cx).line_count();
+        let (tx, rx) = oneshot::channel();
+        platform_bridge::show_text_input(
+            "Go to Line",
+            &format!("1–{line_count}"),
+            "",
+            move |result| {
+                let _ = tx.send(result);
+            },
+        );
+        let epoch = self.open_epoch;
+        cx.spawn(async move |this, cx| {
+            let Ok(Some(value)) = rx.await else {
+                return;
+            };
+            let Ok(line) = value.trim().parse::<usize>() else {
+                return;
+            };
+            let _ = this.update(cx, |this, cx| {
+                if this.open_epoch != epoch {
+                    return;
+                }
+                this.editor_view.update(cx, |editor_view, cx| {
+                    editor_view.go_to_line(line);
+                    cx.notify();
+                });
+            });
+        })
+        .detach();
+    }
+
+    /// Scroll the next opened file to the 1-based `line`. Code files only;
+    /// markdown opens at the top.
+    pub fn set_pending_line(&mut self, line: u32) {
+        self.pending_line = Some(line);
+    }
+
     /// Request loading a file from the remote host.
     /// The file will be loaded asynchronously; when ready, a `FileReady` event is emitted.
     pub fn open_file(&mut self, path: String, cx: &mut Context<Self>) {
-        let filename = path.rsplit('/').next().unwrap_or(&path).to_string();
+        self.save_view_state(cx);
         self.path = path.clone();
-        self.filename = filename;
         self.open_epoch = self.open_epoch.wrapping_add(1);
         let epoch = self.open_epoch;
+        let initial_line = self.pending_line.take();
+        // An explicit line wins over where the file was left.
+        let saved_state = if initial_line.is_some() {
+            self.view_states.remove(&path);
+            None
+        } else {
+            self.view_states.get(&path)
+        };
         self.content = if is_markdown_path(&path) {
             EditorContent::Markdown
+        } else if image_format_for_path(&path).is_some() {
+            EditorContent::Image
         } else {
             EditorContent::Code
         };
         self.state = FileState::Loading;
+        self.editorconfig = None;
+        self.image = None;
+        self.close_find(cx);
+        self.set_text_format(None, cx);
         cx.notify();
 
         let prev_task = self.read_task.take();
         drop(prev_task);
 
+        if self.content == EditorContent::Image {
+            self.load_image(epoch, cx);
+            return;
+        }
+        self.load_editorconfig(cx);
+
         let handle = self.session_handle.clone();
-        let filename = self.filename.clone();
+        let document = EditorDocument::new(path.clone()).initial_line(initial_line);
         let content_kind = self.content;
         let read_task = cx.spawn(async move |this, cx| {
-            let read_result = handle.fs_read(&path).await;
+            let read_result = handle.fs_read_text(&path).await;
             match read_result {
                 Ok(result) if result.too_large => {
                     if let Err(e) = this.update(cx, |this, cx| {
@@ -98,17 +471,28 @@ impl WorkspaceEditor {
                 }
                 Ok(result) => match content_kind {
                     EditorContent::Code => {
+                        let format = TextFormat::of(&result);
+                        let document = document
+                            .encoding(format.encoding)
+                            .line_ending(format.line_ending);
                         let content = result.content;
                         let content_for_syntax = content.clone();
-                        let syntax_filename = filename.clone();
+                        let language = document.resolved_language();
                         if let Err(e) = this.update(cx, |this, cx| {
                             if this.open_epoch != epoch {
                                 return;
                             }
                             this.state = FileState::Loaded;
+                            this.set_text_format(Some(format), cx);
                             this.editor_view.update(cx, |editor_view, _cx| {
-                                editor_view.set_content(&filename, content);
+                                editor_view.set_document(document, content);
+                                if let Some(state) = saved_state {
+                                    editor_view.restore_view_state(state);
+                                }
                             });
+                            if this.blame_enabled {
+                                this.load_blame(cx);
+                            }
                             cx.notify();
                         }) {
                             tracing::error!("update failed for {}: {}", path, e);
@@ -117,7 +501,7 @@ impl WorkspaceEditor {
 
                         let parsed_syntax = cx
                             .background_spawn(async move {
-                                ParsedEditorSyntax::build(&syntax_filename, content_for_syntax)
+                                ParsedEditorSyntax::build(language, content_for_syntax)
                             })
                             .await;
 
@@ -134,6 +518,7 @@ impl WorkspaceEditor {
                         }
                     }
                     EditorContent::Markdown => {
+                        let format = TextFormat::of(&result);
                         let parsed = cx
                             .background_spawn(async move { parse_markdown_source(result.content) })
                             .await;
@@ -142,8 +527,12 @@ impl WorkspaceEditor {
                                 return;
                             }
                             this.state = FileState::Loaded;
+                            this.set_text_format(Some(format), cx);
                             this.markdown_view.update(cx, |markdown_view, cx| {
                                 markdown_view.set_parsed_source(parsed, cx);
+                                if let Some(state) = saved_state {
+                                    markdown_view.restore_view_state(state);
+                                }
                             });
                             cx.notify();
                         }) {
@@ -170,6 +559,212 @@ impl WorkspaceEditor {
         self.read_task = Some(read_task);
     }
 
+    pub fn text_format(&self) -> Option<TextFormat> {
+        self.text_format
+    }
+
+    /// Read the open image file as bytes; `FsReadBinary` keeps it intact
+    /// where a text read would decode it.
+    fn load_image(&mut self, epoch: u64, cx: &mut Context<Self>) {
+        let handle = self.session_handle.clone();
+        let path = self.path.clone();
+        let Some(format) = image_format_for_path(&path) else {
+            return;
+        };
+        self.read_task = Some(cx.spawn(async move |this, cx| {
+            let read = handle.fs_read_binary(&path).await;
+            if let Err(e) = this.update(cx, |this, cx| {
+                if this.open_epoch != epoch {
+                    return;
+                }
+                match read {
+                    Ok(Some(data)) => {
+                        this.image = Some(Arc::new(Image::from_bytes(format, data)));
+                        this.state = FileState::Loaded;
+                    }
+                    Ok(None) => this.state = FileState::TooLarge,
+                    Err(e) => {
+                        tracing::error!("fs/read_binary failed for {}: {}", path, e);
+                        this.state = FileState::Error {
+                            error: e.to_string(),
+                        };
+                    }
+                }
+                cx.notify();
+            }) {
+                tracing::error!("update failed for image: {}", e);
+            }
+        }));
+    }
+
+    /// Resolve the open file's `.editorconfig` in the background. Skipped
+    /// while the data saver is on; saves resolve it then instead.
+    fn load_editorconfig(&mut self, cx: &mut Context<Self>) {
+        self.editorconfig_task = None;
+        if settings::data_saver_active(cx) {
+            return;
+        }
+        let handle = self.session_handle.clone();
+        let path = self.path.clone();
+        let epoch = self.open_epoch;
+        self.editorconfig_task = Some(cx.spawn(async move |this, cx| {
+            let config = editorconfig::resolve(&handle, &path).await;
+            let _ = this.update(cx, |this, cx| {
+                if this.open_epoch != epoch {
+                    return;
+                }
+                this.editorconfig = Some(config);
+                this.set_text_format(this.text_format, cx);
+            });
+        }));
+    }
+
+    fn set_text_format(&mut self, format: Option<TextFormat>, cx: &mut Context<Self>) {
+        let indent = self.editorconfig.and_then(|config| config.indent_style);
+        let format = format.map(|format| TextFormat { indent, ..format });
+        if self.text_format == format {
+            return;
+        }
+        self.text_format = format;
+        cx.emit(TextFormatChanged(format));
+    }
+
+    /// Rewrite the open file on the host with a new encoding and/or line
+    /// ending (`None` keeps the current one), then reload it.
+    pub fn convert_text_format(
+        &mut self,
+        encoding: Option<TextEncoding>,
+        line_ending: Option<LineEnding>,
+        cx: &mut Context<Self>,
+    ) {
+        self.save_to_host(encoding, line_ending, cx);
+    }
+
+    /// Save the open file with the save cleanup applied, in the format its
+    /// `.editorconfig` asks for.
+    pub fn clean_up_whitespace(&mut self, cx: &mut Context<Self>) {
+        self.save_to_host(None, None, cx);
+    }
+
+    /// Write the open file back to the host, running the save cleanup from
+    /// settings and `.editorconfig` first, then reload it. Unset format fields
+    /// fall back to `.editorconfig`'s `charset`/`end_of_line`, then to the
+    /// file's current format.
+    fn save_to_host(
+        &mut self,
+        encoding: Option<TextEncoding>,
+        line_ending: Option<LineEnding>,
+        cx: &mut Context<Self>,
+    ) {
+        if self.path.is_empty() {
+            return;
+        }
+        let handle = self.session_handle.clone();
+        let path = self.path.clone();
+        let cleanup = settings::save_cleanup(cx);
+        let resolved_config = self.editorconfig;
+        cx.spawn(async move |this, cx| {
+            // Save what is on disk, not the editor's copy, which may be stale.
+            let saved = async {
+                let read = handle.fs_read_text(&path).await?;
+                if let Some(error) = read.error {
+                    anyhow::bail!(error);
+                }
+                if read.too_large {
+                    anyhow::bail!("The file is too large.");
+                }
+                let config = match resolved_config {
+                    Some(config) => config,
+                    None => editorconfig::resolve(&handle, &path).await,
+                };
+                // An explicit choice wins; otherwise `.editorconfig` decides.
+                let encoding = encoding.or(config.charset);
+                let line_ending = line_ending.or(config.end_of_line);
+                let converting = encoding.is_some_and(|encoding| encoding != read.encoding)
+                    || line_ending.is_some_and(|line_ending| {
+                        line_ending != read.line_ending || read.mixed_line_endings
+                    });
+                let mut buffer = Buffer::new(read.content);
+                let summary = if cleanup.is_active(&config) {
+                    cleanup.apply(&config, &mut buffer)
+                } else {
+                    CleanupSummary::default()
+                };
+                if summary.is_empty() && !converting {
+                    platform_bridge::show_native_notification(NativeNotificationOptions::new(
+                        "Nothing to clean up",
+                    ));
+                    return Ok(None);
+                }
+                if cleanup.preview && !summary.is_empty() {
+                    let mut message = summary.preview();
+                    if converting {
+                        message.push_str(&format!(
+                            "\nSaves as {} · {}",
+                            line_ending.unwrap_or(read.line_ending).label(),
+                            encoding.unwrap_or(read.encoding).label()
+                        ));
+                    }
+                    if !confirm_cleanup(&message).await {
+                        return Ok(None);
+                    }
+                }
+                let written = handle
+                    .fs_write_text(&path, buffer.text(), encoding, line_ending)
+                    .await?;
+                Ok(Some((written, converting)))
+            }
+            .await;
+            match saved {
+                Ok(None) => {}
+                Ok(Some((written, converting))) => {
+                    let format = TextFormat {
+                        encoding: written.encoding,
+                        line_ending: written.line_ending,
+                        mixed_line_endings: false,
+                        indent: None,
+                    };
+                    let title = if converting {
+                        format!("Saved as {}", format.label())
+                    } else {
+                        "Saved".to_string()
+                    };
+                    platform_bridge::show_native_notification(
+                        NativeNotificationOptions::new(title).kind(NativeNotificationKind::Success),
+                    );
+                    let _ = this.update(cx, |this, cx| {
+                        if this.path == path {
+                            this.open_file(path, cx);
+                        }
+                    });
+                }
+                Err(e) => {
+                    tracing::error!("save failed for {}: {}", path, e);
+                    platform_bridge::show_alert(
+                        "Could not save file",
+                        &e.to_string(),
+                        vec![AlertButton::default("OK")],
+                        |_| {},
+                    );
+                }
+            }
+        })
+        .detach();
+    }
+
+    /// Remember where the loaded file is scrolled before another one replaces it.
+    fn save_view_state(&mut self, cx: &App) {
+        if self.path.is_empty() || !matches!(self.state, FileState::Loaded) {
+            return;
+        }
+        let state = match self.content {
+            EditorContent::Code => self.editor_view.read(cx).view_state(),
+            EditorContent::Markdown => self.markdown_view.read(cx).view_state(),
+            EditorContent::Image => return,
+        };
+        self.view_states.save(self.path.clone(), state);
+    }
+
     pub fn selected_agent_context_range(
         &self,
         window: &Window,
@@ -228,17 +823,171 @@ impl Render for WorkspaceEditor {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         match self.state.clone() {
             FileState::Loading => render_placeholder(cx, "Loading ..."),
+            FileState::TooLarge if self.content == EditorContent::Image => {
+                render_placeholder(cx, "Image too large (>4 MB)")
+            }
             FileState::TooLarge => render_placeholder(cx, "File too large (>500 KB)"),
             FileState::Error { error } => render_placeholder(cx, format!("Error: {}", error)),
             FileState::Loaded => match self.content {
-                EditorContent::Code => div().size_full().child(self.editor_view.clone()),
+                EditorContent::Code => div()
+                    .size_full()
+                    .flex()
+                    .flex_col()
+                    .when(self.find_open, |this| this.child(self.render_find_bar(cx)))
+                    .child(div().flex_1().min_h_0().child(self.editor_view.clone())),
                 EditorContent::Markdown => div()
                     .size_full()
                     .flex()
                     .flex_col()
                     .min_h_0()
                     .child(self.markdown_view.clone()),
+                EditorContent::Image => div()
+                    .size_full()
+                    .p(px(theme::SPACING_MD))
+                    .when_some(self.image.clone(), |this, image| {
+                        this.child(img(image).size_full().object_fit(ObjectFit::Contain))
+                    }),
             },
         }
     }
 }
+
+impl WorkspaceEditor {
+    /// Query input, match count, regex toggle, match stepping, go-to-line
+    /// and close.
+    fn render_find_bar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let status = if self.find_invalid {
+            Some("Invalid regex".to_string())
+        } else {
+            match self.editor_view.read(cx).find_status() {
+                Some((active, total)) => Some(format!("{active}/{total}")),
+                None if self.find_input.read(cx).get_value().is_empty() => None,
+                None => Some("No results".to_string()),
+            }
+        };
+        let status_color = if self.find_invalid {
+            theme::accent_red(cx)
+        } else {
+            theme::text_muted(cx)
+        };
+        let regex_color = if self.find_regex {
+            theme::accent_blue(cx)
+        } else {
+            theme::text_muted(cx)
+        };
+        let icon_button = |id: &'static str, icon: &'static str, cx: &App| {
+            div()
+                .id(id)
+                .flex_shrink_0()
+                .w(px(28.0))
+                .h(px(28.0))
+                .flex()
+                .items_center()
+                .justify_center()
+                .cursor_pointer()
+                .child(
+                    svg()
+                        .path(icon)
+                        .size(px(14.0))
+                        .text_color(rgb(theme::text_secondary(cx))),
+                )
+        };
+        div()
+            .w_full()
+            .flex_shrink_0()
+            .px(px(theme::SPACING_MD))
+            .py(px(theme::SPACING_SM))
+            .flex()
+            .flex_row()
+            .items_center()
+            .gap(px(theme::SPACING_SM))
+            .border_b_1()
+            .border_color(rgb(theme::border_subtle(cx)))
+            .child(div().flex_1().min_w_0().child(self.find_input.clone()))
+            .when_some(status, |this, status| {
+                this.child(
+                    div()
+                        .flex_shrink_0()
+                        .text_size(theme::scaled(theme::FONT_DETAIL))
+                        .text_color(rgb(status_color))
+                        .child(status),
+                )
+            })
+            .child(
+                div()
+                    .id("find-regex-btn")
+                    .flex_shrink_0()
+                    .px_1()
+                    .cursor_pointer()
+                    .text_size(theme::scaled(theme::FONT_DETAIL))
+                    .font_weight(FontWeight::BOLD)
+                    .text_color(rgb(regex_color))
+                    .on_press(cx.listener(|this, _event, _window, cx| {
+                        this.toggle_find_regex(cx);
+                    }))
+                    .child(".*"),
+            )
+            .child(
+                icon_button("find-prev-btn", "icons/chevron-up.svg", cx).on_press(cx.listener(
+                    |this, _event, _window, cx| {
+                        this.step_match(false, cx);
+                    },
+                )),
+            )
+            .child(
+                icon_button("find-next-btn", "icons/chevron-down.svg", cx).on_press(cx.listener(
+                    |this, _event, _window, cx| {
+                        this.step_match(true, cx);
+                    },
+                )),
+            )
+            .child(
+                div()
+                    .id("find-go-to-line-btn")
+                    .flex_shrink_0()
+                    .px_1()
+                    .cursor_pointer()
+                    .text_size(theme::scaled(theme::FONT_DETAIL))
+                    .text_color(rgb(theme::text_secondary(cx)))
+                    .on_press(cx.listener(|this, _event, _window, cx| {
+                        this.go_to_line(cx);
+                    }))
+                    .child("Line"),
+            )
+            .child(
+                icon_button("find-close-btn", "icons/x.svg", cx).on_press(cx.listener(
+                    |this, _event, window, cx| {
+                        window.hide_soft_keyboard();
+                        this.close_find(cx);
+                    },
+                )),
+            )
+    }
+}
+
+/// Show the cleanup preview and wait for the user to confirm the save.
+async fn confirm_cleanup(message: &str) -> bool {
+    let (tx, rx) = oneshot::channel();
+    platform_bridge::show_alert(
+        "Clean up on save?",
+        message,
+        vec![AlertButton::default("Save"), AlertButton::cancel("Cancel")],
+        move |index| {
+            let _ = tx.send(index);
+        },
+    );
+    matches!(rx.await, Ok(0))
+}
+
+/// Formats the editor shows as images instead of text.
+fn image_format_for_path(path: &str) -> Option<ImageFormat> {
+    let extension = path.rsplit_once('.')?.1.to_ascii_lowercase();
+    match extension.as_str() {
+        "png" => Some(ImageFormat::Png),
+        "jpg" | "jpeg" => Some(ImageFormat::Jpeg),
+        "gif" => Some(ImageFormat::Gif),
+        "webp" => Some(ImageFormat::Webp),
+        "bmp" => Some(ImageFormat::Bmp),
+        _ => None,
+    }
+}
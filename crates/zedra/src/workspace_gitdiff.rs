@@ -1,11 +1,19 @@
-use gpui::*;
+use gpui::{prelude::FluentBuilder as _, *};
 use tracing::*;
 
+use zedra_rpc::proto::GitDiffStatResult;
 use zedra_session::SessionHandle;
 
-use crate::editor::git_diff_view::{FileDiff, GitDiffView, parse_unified_diff};
+use crate::editor::git_diff_view::{
+    FileDiff, GitDiffHunkToggled, GitDiffView, HunkStaging, parse_unified_diff,
+};
 use crate::editor::git_sidebar::GitFileSection;
+use crate::memory::MemoryReclaim;
 use crate::placeholder::render_placeholder;
+use crate::platform_bridge::{self, AlertButton, HapticFeedback};
+use crate::view_state::{ViewState, ViewStateCache};
+use crate::workspace::section_to_u8;
+use crate::{theme, workspace_action};
 
 const MAX_DIFF_BYTES: usize = 200 * 1024;
 
@@ -24,31 +32,156 @@ pub enum GitdiffState {
     Error { error: String },
 }
 
+/// One changed file from the diff stat, in sidebar order (staged, changes, untracked).
+#[derive(Clone, Debug, PartialEq)]
+struct DiffStatFile {
+    path: String,
+    section: GitFileSection,
+    additions: u32,
+    deletions: u32,
+    binary: bool,
+}
+
+fn diff_stat_files(stat: &GitDiffStatResult) -> Vec<DiffStatFile> {
+    let sections = [
+        (&stat.staged, GitFileSection::Staged),
+        (&stat.unstaged, GitFileSection::Unstaged),
+        (&stat.untracked, GitFileSection::Untracked),
+    ];
+    sections
+        .into_iter()
+        .flat_map(|(entries, section)| {
+            entries.iter().map(move |entry| DiffStatFile {
+                path: entry.path.clone(),
+                section,
+                additions: entry.additions,
+                deletions: entry.deletions,
+                binary: entry.binary,
+            })
+        })
+        .collect()
+}
+
 pub struct WorkspaceGitdiff {
     state: GitdiffState,
     diff_view: Entity<GitDiffView>,
     session_handle: SessionHandle,
     diff_task: Option<Task<()>>,
+    /// Every changed file with line counts, refreshed on each open. Drives the
+    /// previous/next footer so large change sets are read one file at a time.
+    files: Vec<DiffStatFile>,
+    current: Option<(String, GitFileSection)>,
+    stat_task: Option<Task<()>>,
+    /// Scroll positions of diffs left for another route.
+    view_states: ViewStateCache<(String, GitFileSection)>,
+    hunk_task: Option<Task<()>>,
+    _subscriptions: Vec<Subscription>,
 }
 
 impl EventEmitter<GitdiffHeaderChanged> for WorkspaceGitdiff {}
 
 impl WorkspaceGitdiff {
-    pub fn new(session_handle: SessionHandle, cx: &mut App) -> Self {
+    pub fn new(session_handle: SessionHandle, cx: &mut Context<Self>) -> Self {
+        let diff_view = cx.new(|cx| GitDiffView::new(cx));
+        let subscriptions = vec![cx.subscribe(
+            &diff_view,
+            |this, _diff_view, event: &GitDiffHunkToggled, cx| {
+                this.toggle_hunk(event.header.clone(), cx);
+            },
+        )];
         Self {
             state: GitdiffState::Loading,
-            diff_view: cx.new(|cx| GitDiffView::new(cx)),
+            diff_view,
             session_handle,
             diff_task: None,
+            files: Vec::new(),
+            current: None,
+            stat_task: None,
+            view_states: ViewStateCache::default(),
+            hunk_task: None,
+            _subscriptions: subscriptions,
         }
     }
 
+    /// Position of the open file in `files`.
+    fn current_index(&self) -> Option<usize> {
+        let (path, section) = self.current.as_ref()?;
+        self.files
+            .iter()
+            .position(|file| file.path == *path && file.section == *section)
+    }
+
+    /// Fetch line counts for all changes. Older hosts without `GitDiffStat` just
+    /// leave the footer hidden.
+    fn refresh_stat(&mut self, cx: &mut Context<Self>) {
+        let handle = self.session_handle.clone();
+        self.stat_task = Some(cx.spawn(async move |this, cx| {
+            let stat = match handle.git_diff_stat().await {
+                Ok(stat) => stat,
+                Err(e) => {
+                    debug!("git_diff_stat unavailable: {}", e);
+                    return;
+                }
+            };
+            let _ = this.update(cx, |this, cx| {
+                this.files = diff_stat_files(&stat);
+                // Show the stat in the header while the full diff is still loading.
+                if matches!(this.state, GitdiffState::Loading | GitdiffState::TooLarge)
+                    && let Some(file) = this.current_index().map(|i| this.files[i].clone())
+                {
+                    let filename = file.path.rsplit('/').next().unwrap_or(&file.path);
+                    cx.emit(GitdiffHeaderChanged {
+                        filename: filename.to_string(),
+                        added: file.additions as usize,
+                        removed: file.deletions as usize,
+                    });
+                }
+                cx.notify();
+            });
+        }));
+    }
+
+    fn open_adjacent(&self, step: isize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(index) = self.current_index() else {
+            return;
+        };
+        let Some(file) = index
+            .checked_add_signed(step)
+            .and_then(|next| self.files.get(next))
+        else {
+            return;
+        };
+        platform_bridge::trigger_haptic(HapticFeedback::ImpactLight);
+        window.dispatch_action(
+            workspace_action::OpenGitDiff {
+                path: file.path.clone(),
+                section: section_to_u8(file.section),
+            }
+            .boxed_clone(),
+            cx,
+        );
+    }
+
     /// Request loading a git diff for a file.
     /// The diff is loaded asynchronously and rendered when ready.
     pub fn open_diff(&mut self, path: String, section: GitFileSection, cx: &mut Context<Self>) {
+        if let (Some(current), GitdiffState::Loaded) = (self.current.clone(), &self.state) {
+            let state = self.diff_view.read(cx).view_state();
+            self.view_states.save(current, state);
+        }
+        // Diffs not seen before start at the top rather than where the last one was.
+        let saved_state =
+            self.view_states
+                .get(&(path.clone(), section))
+                .unwrap_or(ViewState::Scroll {
+                    offset: Point::default(),
+                    h_offset: 0.0,
+                });
         let filename = path.rsplit('/').next().unwrap_or(&path).to_string();
         let filename_clone = filename.clone();
         self.state = GitdiffState::Loading;
+        self.current = Some((path.clone(), section));
+        self.refresh_stat(cx);
         cx.emit(GitdiffHeaderChanged {
             filename,
             added: 0,
@@ -62,52 +195,45 @@ impl WorkspaceGitdiff {
 
         let handle = self.session_handle.clone();
         let read_task = cx.spawn(async move |this, cx| {
-            let (state, diff) = match section {
-                GitFileSection::Staged | GitFileSection::Unstaged | GitFileSection::Untracked => {
-                    let staged = matches!(section, GitFileSection::Staged);
-                    match handle.git_diff(Some(&path), staged).await {
-                        Ok(diff_text) => {
-                            if diff_text.len() > MAX_DIFF_BYTES {
-                                (GitdiffState::TooLarge, None)
-                            } else {
-                                let diffs = parse_unified_diff(&diff_text);
-                                let diff = diffs
-                                    .into_iter()
-                                    .find(|d| d.new_path == path || d.old_path == path)
-                                    .unwrap_or(FileDiff {
-                                        old_path: path.clone(),
-                                        new_path: path.clone(),
-                                        hunks: Vec::new(),
-                                    });
-                                let (added, removed) = diff.change_counts();
-                                let _ = this.update(cx, |_this, cx| {
-                                    cx.emit(GitdiffHeaderChanged {
-                                        filename: filename_clone.clone(),
-                                        added,
-                                        removed,
-                                    });
-                                });
-                                (GitdiffState::Loaded, Some(diff))
-                            }
-                        }
-                        Err(e) => {
-                            error!("git_diff RPC failed for {}: {}", path, e);
-                            (
-                                GitdiffState::Error {
-                                    error: e.to_string(),
-                                },
-                                None,
-                            )
-                        }
-                    }
+            let staged = matches!(section, GitFileSection::Staged);
+            let (state, loaded) = match load_diff(&handle, &path, staged).await {
+                Ok(Some((diff, structured))) => {
+                    let (added, removed) = diff.change_counts();
+                    let _ = this.update(cx, |_this, cx| {
+                        cx.emit(GitdiffHeaderChanged {
+                            filename: filename_clone.clone(),
+                            added,
+                            removed,
+                        });
+                    });
+                    (GitdiffState::Loaded, Some((diff, structured)))
+                }
+                Ok(None) => (GitdiffState::TooLarge, None),
+                Err(e) => {
+                    error!("git_diff RPC failed for {}: {}", path, e);
+                    (
+                        GitdiffState::Error {
+                            error: e.to_string(),
+                        },
+                        None,
+                    )
                 }
             };
 
             if let Err(e) = this.update(cx, |this, cx| {
                 this.state = state;
-                if let Some(diff) = diff {
+                if let Some((diff, structured)) = loaded {
+                    // Hunks come from `git diff`, which leaves out untracked files.
+                    let staging = match section {
+                        _ if !structured => None,
+                        GitFileSection::Staged => Some(HunkStaging::Unstage),
+                        GitFileSection::Unstaged => Some(HunkStaging::Stage),
+                        GitFileSection::Untracked => None,
+                    };
                     this.diff_view.update(cx, |diff_view, cx| {
-                        diff_view.set_diff(filename_clone, diff, cx)
+                        diff_view.set_hunk_staging(staging, cx);
+                        diff_view.set_diff(filename_clone, diff, cx);
+                        diff_view.restore_view_state(saved_state, cx);
                     });
                 }
                 cx.notify();
@@ -118,15 +244,230 @@ impl WorkspaceGitdiff {
 
         self.diff_task = Some(read_task)
     }
+
+    /// Stage or unstage one hunk of the open diff, then reload it.
+    fn toggle_hunk(&mut self, header: String, cx: &mut Context<Self>) {
+        let Some((path, section)) = self.current.clone() else {
+            return;
+        };
+        platform_bridge::trigger_haptic(HapticFeedback::ImpactLight);
+        let unstage = section == GitFileSection::Staged;
+        let handle = self.session_handle.clone();
+        self.hunk_task = Some(cx.spawn(async move |this, cx| {
+            if let Err(e) = handle.git_stage_hunk(&path, &header, unstage).await {
+                error!("git stage hunk failed for {}: {}", path, e);
+                let title = if unstage {
+                    "Unstage failed"
+                } else {
+                    "Stage failed"
+                };
+                platform_bridge::show_alert(
+                    title,
+                    &e.to_string(),
+                    vec![AlertButton::default("OK")],
+                    |_| {},
+                );
+            }
+            let _ = this.update(cx, |this, cx| {
+                if this.current.as_ref() == Some(&(path.clone(), section)) {
+                    this.open_diff(path, section, cx);
+                }
+            });
+        }));
+    }
+}
+
+/// The file's diff, preferring the host-parsed form, which allows hunk
+/// staging. `None` when it is over `MAX_DIFF_BYTES`; the flag is true for the
+/// structured form.
+async fn load_diff(
+    handle: &SessionHandle,
+    path: &str,
+    staged: bool,
+) -> anyhow::Result<Option<(FileDiff, bool)>> {
+    let empty = || FileDiff {
+        old_path: path.to_string(),
+        new_path: path.to_string(),
+        hunks: Vec::new(),
+    };
+    match handle.git_diff_structured(Some(path), staged).await {
+        Ok(files) => {
+            let bytes: usize = files
+                .iter()
+                .flat_map(|file| &file.hunks)
+                .flat_map(|hunk| &hunk.lines)
+                .map(|line| line.content.len() + 1)
+                .sum();
+            if bytes > MAX_DIFF_BYTES {
+                return Ok(None);
+            }
+            let diff = files
+                .into_iter()
+                .find(|d| d.new_path == path || d.old_path == path)
+                .map(FileDiff::from_structured)
+                .unwrap_or_else(empty);
+            Ok(Some((diff, true)))
+        }
+        Err(e) => {
+            // Older hosts lack `GitDiffStructured`; their diffs show without
+            // hunk buttons.
+            debug!("git_diff_structured unavailable: {}", e);
+            let diff_text = handle.git_diff(Some(path), staged).await?;
+            if diff_text.len() > MAX_DIFF_BYTES {
+                return Ok(None);
+            }
+            let diff = parse_unified_diff(&diff_text)
+                .into_iter()
+                .find(|d| d.new_path == path || d.old_path == path)
+                .unwrap_or_else(empty);
+            Ok(Some((diff, false)))
+        }
+    }
 }
 
 impl Render for WorkspaceGitdiff {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        match self.state.clone() {
+        let current = self.current_index();
+        let binary = current.is_some_and(|i| self.files[i].binary);
+        let body = match self.state.clone() {
             GitdiffState::Loading => render_placeholder(cx, "Loading ..."),
             GitdiffState::TooLarge => render_placeholder(cx, "Diff too large (>200 KB)"),
             GitdiffState::Error { error } => render_placeholder(cx, &format!("Error: {}", error)),
+            GitdiffState::Loaded if binary => render_placeholder(cx, "Binary file"),
             GitdiffState::Loaded => div().size_full().child(self.diff_view.clone()),
+        };
+
+        let footer = current
+            .filter(|_| self.files.len() > 1)
+            .map(|index| self.render_file_nav(index, cx));
+        div()
+            .size_full()
+            .flex()
+            .flex_col()
+            .child(div().flex_1().min_h_0().child(body))
+            .children(footer)
+    }
+}
+
+impl WorkspaceGitdiff {
+    pub fn trim_memory(&mut self, cx: &mut Context<Self>) -> MemoryReclaim {
+        let line_cache_bytes = self
+            .diff_view
+            .update(cx, |diff_view, _cx| diff_view.release_line_cache());
+        MemoryReclaim {
+            line_cache_bytes,
+            ..Default::default()
         }
     }
+
+    /// Previous/next file bar with the overall stat, e.g. `3 of 12 · +240 −31`.
+    fn render_file_nav(&self, index: usize, cx: &mut Context<Self>) -> impl IntoElement {
+        let (additions, deletions) = self.files.iter().fold((0, 0), |(a, d), file| {
+            (a + file.additions as usize, d + file.deletions as usize)
+        });
+        let has_prev = index > 0;
+        let has_next = index + 1 < self.files.len();
+        let bottom_inset = platform_bridge::home_indicator_inset().max(theme::SPACING_XS);
+        let nav_button = |id: &'static str, icon: &'static str, enabled: bool, step: isize| {
+            div()
+                .id(id)
+                .p(px(theme::SPACING_SM))
+                .hit_slop(px(8.0))
+                .when(enabled, |el| {
+                    el.cursor_pointer()
+                        .on_press(cx.listener(move |this, _event, window, cx| {
+                            this.open_adjacent(step, window, cx);
+                        }))
+                })
+                .child(
+                    svg()
+                        .path(icon)
+                        .size(px(theme::ICON_SM))
+                        .text_color(rgb(if enabled {
+                            theme::text_secondary(cx)
+                        } else {
+                            theme::text_muted(cx)
+                        })),
+                )
+        };
+        div()
+            .flex()
+            .flex_row()
+            .items_center()
+            .justify_between()
+            .px(px(theme::SPACING_MD))
+            .pt(px(theme::SPACING_XS))
+            .pb(px(bottom_inset))
+            .border_t_1()
+            .border_color(rgb(theme::border_subtle(cx)))
+            .child(nav_button(
+                "gitdiff-prev-file",
+                "icons/chevron-left.svg",
+                has_prev,
+                -1,
+            ))
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .gap(px(6.0))
+                    .text_size(theme::scaled(theme::FONT_DETAIL))
+                    .text_color(rgb(theme::text_muted(cx)))
+                    .child(format!("{} of {} files", index + 1, self.files.len()))
+                    .child(
+                        div()
+                            .text_color(rgb(theme::git_added(cx)))
+                            .child(format!("+{additions}")),
+                    )
+                    .child(
+                        div()
+                            .text_color(rgb(theme::git_removed(cx)))
+                            .child(format!("-{deletions}")),
+                    ),
+            )
+            .child(nav_button(
+                "gitdiff-next-file",
+                "icons/chevron-right.svg",
+                has_next,
+                1,
+            ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zedra_rpc::proto::GitDiffStatEntry;
+
+    fn entry(path: &str) -> GitDiffStatEntry {
+        GitDiffStatEntry {
+            path: path.to_string(),
+            additions: 1,
+            deletions: 0,
+            binary: false,
+        }
+    }
+
+    #[test]
+    fn diff_stat_files_follow_sidebar_section_order() {
+        let stat = GitDiffStatResult {
+            staged: vec![entry("b.rs")],
+            unstaged: vec![entry("a.rs"), entry("b.rs")],
+            untracked: vec![entry("new.rs")],
+            error: None,
+        };
+        let order: Vec<(String, GitFileSection)> = diff_stat_files(&stat)
+            .into_iter()
+            .map(|file| (file.path, file.section))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                ("b.rs".to_string(), GitFileSection::Staged),
+                ("a.rs".to_string(), GitFileSection::Unstaged),
+                ("b.rs".to_string(), GitFileSection::Unstaged),
+                ("new.rs".to_string(), GitFileSection::Untracked),
+            ]
+        );
+    }
 }
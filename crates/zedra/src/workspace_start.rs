@@ -47,6 +47,48 @@ impl WorkspaceStart {
                 label: "Manage Agents",
                 action: workspace_action::OpenAgentManage.boxed_clone(),
             },
+            WorkspaceStartItem {
+                id: "workspace-start-problems",
+                icon: "icons/circle-alert.svg",
+                icon_size: px(16.0),
+                label: "Problems",
+                action: workspace_action::OpenProblems.boxed_clone(),
+            },
+            WorkspaceStartItem {
+                id: "workspace-start-git-graph",
+                icon: "icons/git-branch.svg",
+                icon_size: px(16.0),
+                label: "Commit Graph",
+                action: workspace_action::OpenGitGraph.boxed_clone(),
+            },
+            WorkspaceStartItem {
+                id: "workspace-start-terminal-triggers",
+                icon: "icons/zap.svg",
+                icon_size: px(16.0),
+                label: "Terminal Triggers",
+                action: workspace_action::OpenTerminalTriggers.boxed_clone(),
+            },
+            WorkspaceStartItem {
+                id: "workspace-start-command-guard",
+                icon: "icons/circle-alert.svg",
+                icon_size: px(16.0),
+                label: "Command Guard",
+                action: workspace_action::OpenCommandGuard.boxed_clone(),
+            },
+            WorkspaceStartItem {
+                id: "workspace-start-session-env",
+                icon: "icons/settings.svg",
+                icon_size: px(16.0),
+                label: "Environment",
+                action: workspace_action::OpenSessionEnv.boxed_clone(),
+            },
+            WorkspaceStartItem {
+                id: "workspace-start-host-notify",
+                icon: "icons/bell.svg",
+                icon_size: px(16.0),
+                label: "Notifications",
+                action: workspace_action::OpenHostNotify.boxed_clone(),
+            },
         ]
     }
 }
@@ -93,7 +135,7 @@ impl Render for WorkspaceStart {
                     .child(
                         div()
                             .text_color(rgb(theme::text_muted(cx)))
-                            .text_size(px(theme::FONT_BODY))
+                            .text_size(theme::scaled(theme::FONT_BODY))
                             .child(item.label),
                     ),
             );
@@ -4,11 +4,16 @@ use std::path::PathBuf;
 use std::sync::{Mutex, OnceLock};
 use tracing::*;
 use uuid::Uuid;
-use zedra_rpc::proto::{AgentState, HostInfoSnapshot, WebClientInfo, WebClientUpdate};
+use zedra_rpc::proto::{
+    AgentState, HostInfoSnapshot, HostNotifyConfig, SessionEnvVar, WebClientInfo, WebClientUpdate,
+};
 
 use zedra_session::*;
 
+use crate::command_guard::CommandGuardConfig;
+use crate::host_accent::HostAccent;
 use crate::platform_bridge;
+use crate::terminal_triggers::TerminalTrigger;
 
 const STORE_DIR: &str = "zedra";
 const STORE_FILE: &str = "workspaces.json";
@@ -24,6 +29,8 @@ pub enum WorkspaceStateEvent {
     HostInfoChanged,
     TerminalCreated { id: String },
     TerminalOpened { id: String },
+    TerminalTriggersChanged,
+    CommandGuardChanged,
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
@@ -45,6 +52,14 @@ pub enum WorkspaceMainView {
     AgentDetail {
         slug: String,
     },
+    Problems,
+    GitGraph,
+    FileHistory {
+        path: String,
+    },
+    Recording {
+        name: String,
+    },
 }
 
 impl WorkspaceMainView {
@@ -91,6 +106,11 @@ impl WorkspaceNavigationStack {
         self.routes.last().cloned().unwrap_or_default()
     }
 
+    /// Open routes, oldest first.
+    pub fn routes(&self) -> &[WorkspaceMainView] {
+        &self.routes
+    }
+
     pub fn open(&mut self, route: WorkspaceMainView) {
         if self.routes.last() == Some(&route) {
             return;
@@ -202,6 +222,9 @@ pub struct WorkspaceState {
     pub project_name: String,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub custom_name: Option<String>,
+    // Color that tells this host apart in the header, home card, and notifications.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accent: Option<HostAccent>,
     pub workdir: String,
     pub homedir: String,
     pub hostname: String,
@@ -211,6 +234,24 @@ pub struct WorkspaceState {
     // Web tunnels opened for this workspace, in stable open order for quick reopen.
     #[serde(default)]
     pub web_tunnels: Vec<TrackedTunnel>,
+    // Output triggers applied to every terminal on this host.
+    #[serde(default)]
+    pub terminal_triggers: Vec<TerminalTrigger>,
+    // Production marking and the commands that need confirming on this host.
+    #[serde(default)]
+    pub command_guard: CommandGuardConfig,
+    // Environment overrides sent to the host for new terminals and checks.
+    #[serde(default)]
+    pub session_env: Vec<SessionEnvVar>,
+    // Host watchers that notify this device, sent to the host after each connect.
+    #[serde(default)]
+    pub host_notify: HostNotifyConfig,
+    // The user allowed reading this host's shell history for terminal pickers.
+    #[serde(default)]
+    pub shell_history_consent: bool,
+    // Wake-on-LAN addresses the host reported, kept to wake it while asleep.
+    #[serde(default)]
+    pub wake_macs: Vec<String>,
     #[serde(default)]
     pub delta_host_pubkey: Option<[u8; 32]>,
     #[serde(default)]
@@ -262,11 +303,18 @@ impl PartialEq for WorkspaceState {
             && self.strip_path == other.strip_path
             && self.project_name == other.project_name
             && self.custom_name == other.custom_name
+            && self.accent == other.accent
             && self.workdir == other.workdir
             && self.homedir == other.homedir
             && self.hostname == other.hostname
             && self.docs_tree_collapsed_dirs == other.docs_tree_collapsed_dirs
             && self.web_tunnels == other.web_tunnels
+            && self.terminal_triggers == other.terminal_triggers
+            && self.command_guard == other.command_guard
+            && self.session_env == other.session_env
+            && self.host_notify == other.host_notify
+            && self.shell_history_consent == other.shell_history_consent
+            && self.wake_macs == other.wake_macs
             && self.delta_host_pubkey == other.delta_host_pubkey
             && self.delta_host_node_id == other.delta_host_node_id
             && self.created_at == other.created_at
@@ -362,6 +410,15 @@ impl WorkspaceState {
         cx.notify();
     }
 
+    pub fn set_accent(&mut self, accent: Option<HostAccent>, cx: &mut Context<Self>) {
+        if self.accent == accent {
+            return;
+        }
+        self.accent = accent;
+        cx.emit(WorkspaceStateEvent::StateChanged);
+        cx.notify();
+    }
+
     pub fn sync_from_session(
         &mut self,
         session_handle: &SessionHandle,
@@ -533,6 +590,79 @@ impl WorkspaceState {
         cx.notify();
     }
 
+    pub fn add_terminal_trigger(&mut self, trigger: TerminalTrigger, cx: &mut Context<Self>) {
+        self.terminal_triggers.push(trigger);
+        cx.emit(WorkspaceStateEvent::TerminalTriggersChanged);
+        cx.emit(WorkspaceStateEvent::StateChanged);
+        cx.notify();
+    }
+
+    pub fn remove_terminal_trigger(&mut self, index: usize, cx: &mut Context<Self>) {
+        if index >= self.terminal_triggers.len() {
+            return;
+        }
+        self.terminal_triggers.remove(index);
+        cx.emit(WorkspaceStateEvent::TerminalTriggersChanged);
+        cx.emit(WorkspaceStateEvent::StateChanged);
+        cx.notify();
+    }
+
+    pub fn set_command_guard(&mut self, config: CommandGuardConfig, cx: &mut Context<Self>) {
+        if self.command_guard == config {
+            return;
+        }
+        self.command_guard = config;
+        cx.emit(WorkspaceStateEvent::CommandGuardChanged);
+        cx.emit(WorkspaceStateEvent::StateChanged);
+        cx.notify();
+    }
+
+    /// Add `var`, replacing an override of the same name in place.
+    pub fn set_session_env_var(&mut self, var: SessionEnvVar, cx: &mut Context<Self>) {
+        match self.session_env.iter_mut().find(|v| v.name == var.name) {
+            Some(existing) => *existing = var,
+            None => self.session_env.push(var),
+        }
+        cx.emit(WorkspaceStateEvent::StateChanged);
+        cx.notify();
+    }
+
+    pub fn remove_session_env_var(&mut self, index: usize, cx: &mut Context<Self>) {
+        if index >= self.session_env.len() {
+            return;
+        }
+        self.session_env.remove(index);
+        cx.emit(WorkspaceStateEvent::StateChanged);
+        cx.notify();
+    }
+
+    pub fn set_host_notify(&mut self, config: HostNotifyConfig, cx: &mut Context<Self>) {
+        if self.host_notify == config {
+            return;
+        }
+        self.host_notify = config;
+        cx.emit(WorkspaceStateEvent::StateChanged);
+        cx.notify();
+    }
+
+    pub fn set_wake_macs(&mut self, macs: Vec<String>, cx: &mut Context<Self>) {
+        if self.wake_macs == macs {
+            return;
+        }
+        self.wake_macs = macs;
+        cx.emit(WorkspaceStateEvent::StateChanged);
+        cx.notify();
+    }
+
+    pub fn set_shell_history_consent(&mut self, consent: bool, cx: &mut Context<Self>) {
+        if self.shell_history_consent == consent {
+            return;
+        }
+        self.shell_history_consent = consent;
+        cx.emit(WorkspaceStateEvent::StateChanged);
+        cx.notify();
+    }
+
     /// Track a web tunnel opened for this workspace. Position is stable: an
     /// existing entry is updated in place, a new one appended — so reopening a
     /// tunnel never reorders the list.
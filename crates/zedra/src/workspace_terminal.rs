@@ -3,23 +3,31 @@ use std::time::Duration;
 use gpui::{prelude::FluentBuilder as _, *};
 use tracing::*;
 use zedra_osc::OscEvent;
+use zedra_rpc::proto::SHELL_HISTORY_MAX_COMMANDS;
 use zedra_session::SessionHandle;
-use zedra_terminal::terminal::{TerminalEvent, TerminalHyperlinkTarget};
-use zedra_terminal::view::TerminalView;
+use zedra_terminal::terminal::{
+    PromptJump, TerminalEvent, TerminalHyperlink, TerminalHyperlinkTarget,
+};
+use zedra_terminal::view::{TerminalStatus, TerminalView};
 
+use crate::app_action::InsertText;
 use crate::button::{
     NativeFloatingButtonId, hide_native_floating_button, native_floating_button,
     native_floating_button_id,
 };
 use crate::file_preview_view::FilePreviewView;
 use crate::image_upload::{self, ImageUploadError};
+use crate::memory::{MemoryPressure, MemoryReclaim};
 use crate::platform_bridge::{
     self, AlertButton, CustomSheetDetent, CustomSheetOptions, HapticFeedback, ImageAcquireSource,
-    NativeDictationPreviewOptions, NativeEditMenuItem,
+    ListPickerItem, NativeDictationPreviewOptions, NativeEditMenuItem, NativeNotificationKind,
+    NativeNotificationOptions,
 };
 use crate::settings::{ThemeStateEvent, theme_state as theme_entity};
 use crate::telemetry::view_telemetry;
 use crate::terminal_state::TerminalState;
+use crate::theme;
+use crate::workspace::ActiveWorkspace;
 use crate::workspace_state::{WorkspaceState, WorkspaceStateEvent};
 
 pub const TERMINAL_PENDING_ID: &str = "___PENDING___";
@@ -27,6 +35,20 @@ const SCROLL_TO_BOTTOM_BUTTON_THRESHOLD_LINES: usize = 10;
 const SCROLL_TO_BOTTOM_BUTTON_DISMISS_DELAY: Duration = Duration::from_millis(160);
 const NATIVE_PASTE_MENU_TAP_GAP: f32 = 28.0;
 
+/// Single-quote `path` for a POSIX shell command line.
+fn shell_quote(path: &str) -> String {
+    format!("'{}'", path.replace('\'', "'\\''"))
+}
+
+/// Show paths under the host home directory as `~/...`.
+fn abbreviate_home(path: &str, homedir: &str) -> String {
+    match path.strip_prefix(homedir) {
+        Some("") if !homedir.is_empty() => "~".to_string(),
+        Some(rest) if !homedir.is_empty() && rest.starts_with('/') => format!("~{rest}"),
+        _ => path.to_string(),
+    }
+}
+
 fn native_paste_menu_anchor(position: Point<Pixels>) -> Point<Pixels> {
     // Keep the edit menu visibly separated from the long-press finger.
     point(position.x, position.y - px(NATIVE_PASTE_MENU_TAP_GAP))
@@ -38,6 +60,23 @@ enum PasteMenuAction {
     PasteText,
     PasteImage,
     UploadImage,
+    PreviousCommand,
+    NextCommand,
+    CopyCommandOutput,
+    FoldOutputs,
+    UnfoldOutputs,
+    SetUpShellIntegration,
+    RecentDirectories,
+    CommandHistory,
+    RunTask,
+    RevealInFiles,
+}
+
+/// Which list `pick_from_shell_history` offers.
+#[derive(Clone, Copy)]
+enum ShellHistoryPick {
+    Directories,
+    Commands,
 }
 
 pub struct WorkspaceTerminal {
@@ -60,6 +99,9 @@ pub struct WorkspaceTerminal {
     scroll_to_bottom_button_visible: bool,
     scroll_to_bottom_button_hide_pending: bool,
     scroll_to_bottom_button_hide_generation: u64,
+    /// Output lines that arrived while scrolled up, shown beside the button.
+    /// Copied from the ScrollbackPositionChanged handler, like `is_alt_screen`.
+    unseen_lines: usize,
     image_upload_in_progress: bool,
     /// Non-input focus target. Moving focus here drops the terminal keyboard without
     /// clearing focus entirely (which the terminal input would immediately re-grab).
@@ -68,13 +110,61 @@ pub struct WorkspaceTerminal {
 }
 
 impl WorkspaceTerminal {
+    pub fn trim_memory(
+        &mut self,
+        pressure: MemoryPressure,
+        cx: &mut Context<Self>,
+    ) -> MemoryReclaim {
+        let Some(floor) = pressure.scrollback_floor() else {
+            return MemoryReclaim::default();
+        };
+        let scrollback_bytes = self.terminal_view.update(cx, |terminal_view, cx| {
+            terminal_view.trim_scrollback(floor, cx)
+        });
+        MemoryReclaim {
+            scrollback_bytes,
+            ..Default::default()
+        }
+    }
+
     fn sync_terminal_theme(&mut self, cx: &mut Context<Self>) {
-        let terminal_theme = crate::theme::bundle(cx).terminal;
+        let terminal_theme = theme::bundle(cx).terminal;
         self.terminal_view.update(cx, |terminal_view, cx| {
             terminal_view.set_terminal_theme(terminal_theme, cx);
         });
     }
 
+    fn sync_terminal_font(&mut self, cx: &mut Context<Self>) {
+        let family = crate::settings::code_font(cx).family_name();
+        let size = zedra_terminal::TERMINAL_FONT_SIZE * theme::text_scale();
+        self.terminal_view.update(cx, |terminal_view, cx| {
+            terminal_view.set_font_family(family, cx);
+            terminal_view.set_font_size(size, cx);
+        });
+    }
+
+    fn sync_terminal_cursor(&mut self, cx: &mut Context<Self>) {
+        let cursor = crate::settings::terminal_cursor(cx);
+        self.terminal_view.update(cx, |terminal_view, cx| {
+            terminal_view.set_default_cursor_style(cursor.shape.to_terminal(), cursor.blinking, cx);
+        });
+    }
+
+    fn sync_terminal_triggers(&mut self, cx: &mut Context<Self>) {
+        let rules =
+            crate::terminal_triggers::compile(&self.workspace_state.read(cx).terminal_triggers);
+        self.terminal_view.update(cx, |terminal_view, cx| {
+            terminal_view.set_triggers(rules, cx);
+        });
+    }
+
+    fn sync_command_guard(&mut self, cx: &mut Context<Self>) {
+        let rules = crate::command_guard::compile(&self.workspace_state.read(cx).command_guard);
+        self.terminal_view.update(cx, |terminal_view, cx| {
+            terminal_view.set_command_guard(rules, cx);
+        });
+    }
+
     fn keyboard_inset() -> Pixels {
         let bridge = platform_bridge::bridge();
         let density = bridge.density();
@@ -128,6 +218,12 @@ impl WorkspaceTerminal {
         );
     }
 
+    pub fn set_status(&mut self, status: Option<TerminalStatus>, cx: &mut Context<Self>) {
+        self.terminal_view.update(cx, |terminal_view, cx| {
+            terminal_view.set_status(status, cx);
+        });
+    }
+
     pub fn deactivate(&mut self, cx: &mut Context<Self>) {
         self.scroll_to_bottom_button_hide_pending = false;
         self.scroll_to_bottom_button_hide_generation =
@@ -141,6 +237,7 @@ impl WorkspaceTerminal {
         self.terminal_view.update(cx, |terminal_view, cx| {
             terminal_view.scroll_to_bottom(cx);
         });
+        self.unseen_lines = 0;
         self.schedule_scroll_to_bottom_button_hide(cx);
     }
 
@@ -209,6 +306,8 @@ impl WorkspaceTerminal {
                     this.deactivate(cx);
                 }
             }
+            WorkspaceStateEvent::TerminalTriggersChanged => this.sync_terminal_triggers(cx),
+            WorkspaceStateEvent::CommandGuardChanged => this.sync_command_guard(cx),
             _ => {}
         });
 
@@ -271,26 +370,16 @@ impl WorkspaceTerminal {
                             });
                         }
                     }
-                    TerminalHyperlinkTarget::File { path, .. } => {
-                        this.preview.update(cx, |preview, cx| {
-                            preview.open_hyperlink(hyperlink.clone(), cx);
-                        });
-                        view_telemetry::record(view_telemetry::custom_sheet_file(path));
-                        platform_bridge::show_custom_sheet(
-                            CustomSheetOptions {
-                                detents: vec![CustomSheetDetent::Large],
-                                initial_detent: CustomSheetDetent::Large,
-                                shows_grabber: true,
-                                expands_on_scroll_edge: true,
-                                edge_attached_in_compact_height: false,
-                                width_follows_preferred_content_size_when_edge_attached: false,
-                                corner_radius: None,
-                                modal_in_presentation: false,
-                            },
-                            this.preview.clone(),
-                        );
+                    TerminalHyperlinkTarget::File { .. } => {
+                        this.show_file_preview(hyperlink.clone(), cx);
                     }
                 },
+                TerminalEvent::FilePathMenuRequested {
+                    position,
+                    hyperlink,
+                } => {
+                    this.show_file_path_menu(*position, hyperlink.clone(), cx);
+                }
                 TerminalEvent::NativePasteMenuRequested { position } => {
                     let terminal_view = this.terminal_view.clone();
                     let weak_this = cx.weak_entity();
@@ -310,6 +399,65 @@ impl WorkspaceTerminal {
                         NativeEditMenuItem::new("Upload").image("photo.badge.plus"),
                         PasteMenuAction::UploadImage,
                     ));
+                    if terminal_view.read(cx).has_prompt_marks(cx) {
+                        menu.extend([
+                            (
+                                NativeEditMenuItem::new("Previous Command")
+                                    .image("arrow.up.to.line"),
+                                PasteMenuAction::PreviousCommand,
+                            ),
+                            (
+                                NativeEditMenuItem::new("Next Command").image("arrow.down.to.line"),
+                                PasteMenuAction::NextCommand,
+                            ),
+                            (
+                                NativeEditMenuItem::new("Copy Output").image("doc.on.doc"),
+                                PasteMenuAction::CopyCommandOutput,
+                            ),
+                        ]);
+                        if terminal_view.read(cx).has_folded_outputs(cx) {
+                            menu.push((
+                                NativeEditMenuItem::new("Unfold Outputs")
+                                    .image("rectangle.expand.vertical"),
+                                PasteMenuAction::UnfoldOutputs,
+                            ));
+                        } else if terminal_view.read(cx).has_foldable_outputs(cx) {
+                            menu.push((
+                                NativeEditMenuItem::new("Fold Outputs")
+                                    .image("rectangle.compress.vertical"),
+                                PasteMenuAction::FoldOutputs,
+                            ));
+                        }
+                    } else if !this.is_alt_screen {
+                        menu.push((
+                            NativeEditMenuItem::new("Shell Integration").image("terminal"),
+                            PasteMenuAction::SetUpShellIntegration,
+                        ));
+                    }
+                    if !this.is_alt_screen {
+                        menu.extend([
+                            (
+                                NativeEditMenuItem::new("Run Task").image("play.circle"),
+                                PasteMenuAction::RunTask,
+                            ),
+                            (
+                                NativeEditMenuItem::new("Recent Directories").image("folder"),
+                                PasteMenuAction::RecentDirectories,
+                            ),
+                            (
+                                NativeEditMenuItem::new("Command History")
+                                    .image("clock.arrow.circlepath"),
+                                PasteMenuAction::CommandHistory,
+                            ),
+                        ]);
+                    }
+                    let cwd = this.terminal_state.read(cx).meta(&this.terminal_id).cwd;
+                    if cwd.is_some() {
+                        menu.push((
+                            NativeEditMenuItem::new("Reveal in Files").image("sidebar.left"),
+                            PasteMenuAction::RevealInFiles,
+                        ));
+                    }
                     let (items, actions): (Vec<_>, Vec<_>) = menu.into_iter().unzip();
 
                     platform_bridge::show_native_edit_menu(
@@ -335,6 +483,76 @@ impl WorkspaceTerminal {
                                     this.start_image_upload(ImageAcquireSource::PhotoLibrary, cx);
                                 });
                             }
+                            Some(PasteMenuAction::PreviousCommand) => {
+                                let _ = terminal_view.update(cx, |terminal_view, cx| {
+                                    terminal_view.scroll_to_prompt(PromptJump::Previous, cx)
+                                });
+                            }
+                            Some(PasteMenuAction::NextCommand) => {
+                                let _ = terminal_view.update(cx, |terminal_view, cx| {
+                                    terminal_view.scroll_to_prompt(PromptJump::Next, cx)
+                                });
+                            }
+                            Some(PasteMenuAction::CopyCommandOutput) => {
+                                let output = terminal_view
+                                    .read(cx)
+                                    .last_command_output(cx)
+                                    .filter(|output| !output.is_empty());
+                                match output {
+                                    Some(output) => {
+                                        cx.write_to_clipboard(ClipboardItem::new_string(output));
+                                        platform_bridge::show_native_notification(
+                                            NativeNotificationOptions::new("Output copied")
+                                                .kind(NativeNotificationKind::Success),
+                                        );
+                                    }
+                                    None => platform_bridge::show_native_notification(
+                                        NativeNotificationOptions::new("No command output")
+                                            .message("The last command printed nothing.")
+                                            .kind(NativeNotificationKind::Info),
+                                    ),
+                                }
+                            }
+                            Some(PasteMenuAction::FoldOutputs) => {
+                                let _ = terminal_view.update(cx, |terminal_view, cx| {
+                                    terminal_view.set_outputs_folded(true, cx)
+                                });
+                            }
+                            Some(PasteMenuAction::UnfoldOutputs) => {
+                                let _ = terminal_view.update(cx, |terminal_view, cx| {
+                                    terminal_view.set_outputs_folded(false, cx)
+                                });
+                            }
+                            Some(PasteMenuAction::SetUpShellIntegration) => {
+                                let _ = weak_this.update(cx, |this, cx| {
+                                    this.offer_shell_integration(cx);
+                                });
+                            }
+                            Some(PasteMenuAction::RecentDirectories) => {
+                                let _ = weak_this.update(cx, |this, cx| {
+                                    this.pick_from_shell_history(ShellHistoryPick::Directories, cx);
+                                });
+                            }
+                            Some(PasteMenuAction::CommandHistory) => {
+                                let _ = weak_this.update(cx, |this, cx| {
+                                    this.pick_from_shell_history(ShellHistoryPick::Commands, cx);
+                                });
+                            }
+                            Some(PasteMenuAction::RunTask) => {
+                                let _ = weak_this.update(cx, |this, cx| {
+                                    this.pick_task(cx);
+                                });
+                            }
+                            Some(PasteMenuAction::RevealInFiles) => {
+                                let (Some(cwd), Some(workspace)) =
+                                    (cwd.clone(), ActiveWorkspace::get(cx))
+                                else {
+                                    return;
+                                };
+                                workspace.update(cx, |workspace, cx| {
+                                    workspace.reveal_in_files(cwd, cx);
+                                });
+                            }
                             None => {}
                         },
                     );
@@ -343,6 +561,28 @@ impl WorkspaceTerminal {
                     this.is_alt_screen = *is_alt;
                     cx.notify();
                 }
+                TerminalEvent::TriggerFired(fired) => {
+                    let meta = this.terminal_state.read(cx).meta(&this.terminal_id);
+                    let title = meta
+                        .plain_title
+                        .or(meta.title)
+                        .unwrap_or_else(|| "Terminal".into());
+                    crate::terminal_triggers::handle_fired(fired, &title, cx);
+                }
+                TerminalEvent::CommandGuarded(guarded) => {
+                    platform_bridge::trigger_haptic(HapticFeedback::NotificationWarning);
+                    let guarded = guarded.clone();
+                    let host_name = this.workspace_state.read(cx).display_name().to_string();
+                    let terminal_view = this.terminal_view.clone();
+                    cx.spawn(async move |_this, cx| {
+                        if crate::command_guard::confirm(&guarded, &host_name).await {
+                            let _ = terminal_view.update(cx, |terminal_view, cx| {
+                                terminal_view.submit_guarded_command(cx);
+                            });
+                        }
+                    })
+                    .detach();
+                }
                 TerminalEvent::DictationPreviewChanged(text) => {
                     let active_terminal_id =
                         this.workspace_state.read(cx).active_terminal_id.clone();
@@ -375,6 +615,12 @@ impl WorkspaceTerminal {
                         return;
                     }
 
+                    let unseen_lines = this.terminal_view.read(cx).unseen_lines(cx);
+                    if this.unseen_lines != unseen_lines {
+                        this.unseen_lines = unseen_lines;
+                        cx.notify();
+                    }
+
                     let should_show = Self::should_show_scroll_to_bottom_button(*display_offset);
                     if should_show {
                         this.scroll_to_bottom_button_hide_pending = false;
@@ -437,6 +683,20 @@ impl WorkspaceTerminal {
             );
         }
 
+        subscriptions.push(
+            cx.observe_global::<crate::settings::CodeFontSetting>(|this, cx| {
+                this.sync_terminal_font(cx)
+            }),
+        );
+        subscriptions.push(cx.observe_global::<crate::settings::TerminalCursorSetting>(
+            |this, cx| this.sync_terminal_cursor(cx),
+        ));
+        subscriptions.push(
+            cx.observe_global::<crate::settings::TextSizeSetting>(|this, cx| {
+                this.sync_terminal_font(cx)
+            }),
+        );
+
         let mut this = Self {
             terminal_id,
             workspace_state,
@@ -451,11 +711,16 @@ impl WorkspaceTerminal {
             scroll_to_bottom_button_visible: false,
             scroll_to_bottom_button_hide_pending: false,
             scroll_to_bottom_button_hide_generation: 0,
+            unseen_lines: 0,
             image_upload_in_progress: false,
             container_focus: cx.focus_handle(),
             _subscriptions: subscriptions,
         };
         this.sync_terminal_theme(cx);
+        this.sync_terminal_font(cx);
+        this.sync_terminal_cursor(cx);
+        this.sync_terminal_triggers(cx);
+        this.sync_command_guard(cx);
         this
     }
 
@@ -566,6 +831,307 @@ impl WorkspaceTerminal {
         .detach();
     }
 
+    /// Check the host shell's rc file and offer to add the OSC 133 snippet that
+    /// enables command navigation and output copy.
+    fn offer_shell_integration(&mut self, cx: &mut Context<Self>) {
+        let session_handle = self.session_handle.clone();
+        cx.spawn(async move |_this, _cx| {
+            let status = match session_handle.shell_integration(false).await {
+                Ok(status) => status,
+                Err(error) => {
+                    warn!("shell integration status failed: {}", error);
+                    platform_bridge::show_alert(
+                        "Shell Integration Unavailable",
+                        &error.to_string(),
+                        vec![AlertButton::cancel("OK")],
+                        |_| {},
+                    );
+                    return;
+                }
+            };
+            if status.installed {
+                platform_bridge::show_alert(
+                    "Shell Integration",
+                    &format!(
+                        "Already set up in {}. Open a new terminal to navigate between commands.",
+                        status.rc_path
+                    ),
+                    vec![AlertButton::cancel("OK")],
+                    |_| {},
+                );
+                return;
+            }
+
+            let (choice_tx, choice_rx) = tokio::sync::oneshot::channel();
+            platform_bridge::show_alert(
+                "Set Up Shell Integration?",
+                &format!(
+                    "Adds a short {} snippet to {} that marks prompts, so you can jump between commands and copy their output.",
+                    status.shell, status.rc_path
+                ),
+                vec![
+                    AlertButton::default("Install"),
+                    AlertButton::cancel("Cancel"),
+                ],
+                move |index| {
+                    let _ = choice_tx.send(index);
+                },
+            );
+            if choice_rx.await != Ok(0) {
+                return;
+            }
+            match session_handle.shell_integration(true).await {
+                Ok(_) => platform_bridge::show_native_notification(
+                    NativeNotificationOptions::new("Shell integration installed")
+                        .message("Open a new terminal to use it.")
+                        .kind(NativeNotificationKind::Success),
+                ),
+                Err(error) => {
+                    warn!("shell integration install failed: {}", error);
+                    platform_bridge::show_alert(
+                        "Install Failed",
+                        &error.to_string(),
+                        vec![AlertButton::cancel("OK")],
+                        |_| {},
+                    );
+                }
+            }
+        })
+        .detach();
+    }
+
+    fn show_file_preview(&mut self, hyperlink: TerminalHyperlink, cx: &mut Context<Self>) {
+        if let TerminalHyperlinkTarget::File { path, .. } = &hyperlink.target {
+            view_telemetry::record(view_telemetry::custom_sheet_file(path));
+        }
+        self.preview.update(cx, |preview, cx| {
+            preview.open_hyperlink(hyperlink, cx);
+        });
+        platform_bridge::show_custom_sheet(
+            CustomSheetOptions {
+                detents: vec![CustomSheetDetent::Large],
+                initial_detent: CustomSheetDetent::Large,
+                shows_grabber: true,
+                expands_on_scroll_edge: true,
+                edge_attached_in_compact_height: false,
+                width_follows_preferred_content_size_when_edge_attached: false,
+                corner_radius: None,
+                modal_in_presentation: false,
+            },
+            self.preview.clone(),
+        );
+    }
+
+    /// Long-press menu for a file path in the output, e.g. a compiler
+    /// error's `src/main.rs:12:3`.
+    fn show_file_path_menu(
+        &mut self,
+        position: Point<Pixels>,
+        hyperlink: TerminalHyperlink,
+        cx: &mut Context<Self>,
+    ) {
+        let TerminalHyperlinkTarget::File { path, line, .. } = hyperlink.target.clone() else {
+            return;
+        };
+        platform_bridge::trigger_haptic(HapticFeedback::ImpactMedium);
+        let weak_this = cx.weak_entity();
+        platform_bridge::show_native_edit_menu(
+            native_paste_menu_anchor(position),
+            vec![
+                NativeEditMenuItem::new("Open in Editor").image("doc.text"),
+                NativeEditMenuItem::new("Preview").image("eye"),
+                NativeEditMenuItem::new("Copy Path").image("doc.on.doc"),
+            ],
+            move |index, cx| match index {
+                0 => {
+                    let Some(workspace) = ActiveWorkspace::get(cx) else {
+                        return;
+                    };
+                    workspace.update(cx, |workspace, cx| {
+                        workspace.open_file_at_line(path.clone(), line, cx);
+                    });
+                }
+                1 => {
+                    let _ = weak_this.update(cx, |this, cx| {
+                        this.show_file_preview(hyperlink.clone(), cx);
+                    });
+                }
+                2 => cx.write_to_clipboard(ClipboardItem::new_string(path.clone())),
+                _ => {}
+            },
+        );
+    }
+
+    /// Offer the host shell's recent directories (`cd` on pick) or commands
+    /// (inserted at the prompt, not run). Asks once per host before reading
+    /// history.
+    fn pick_from_shell_history(&mut self, pick: ShellHistoryPick, cx: &mut Context<Self>) {
+        let session_handle = self.session_handle.clone();
+        let workspace_state = self.workspace_state.clone();
+        let terminal_view = self.terminal_view.clone();
+        let (consented, homedir) = {
+            let state = workspace_state.read(cx);
+            (state.shell_history_consent, state.homedir.clone())
+        };
+        cx.spawn(async move |_this, cx| {
+            if !consented {
+                let (choice_tx, choice_rx) = tokio::sync::oneshot::channel();
+                platform_bridge::show_alert(
+                    "Read Shell History?",
+                    "Zedra reads recent commands and directories from your shell history on this host. Commands that look like they contain passwords or tokens are skipped.",
+                    vec![
+                        AlertButton::default("Allow"),
+                        AlertButton::cancel("Don't Allow"),
+                    ],
+                    move |index| {
+                        let _ = choice_tx.send(index);
+                    },
+                );
+                if choice_rx.await != Ok(0) {
+                    return;
+                }
+                let _ = workspace_state.update(cx, |state, cx| {
+                    state.set_shell_history_consent(true, cx);
+                });
+            }
+
+            let history = match session_handle
+                .shell_history(SHELL_HISTORY_MAX_COMMANDS as u32)
+                .await
+            {
+                Ok(history) => history,
+                Err(error) => {
+                    warn!("shell history failed: {}", error);
+                    platform_bridge::show_alert(
+                        "Shell History Unavailable",
+                        &error.to_string(),
+                        vec![AlertButton::cancel("OK")],
+                        |_| {},
+                    );
+                    return;
+                }
+            };
+            let (title, entries) = match pick {
+                ShellHistoryPick::Directories => ("Recent Directories", history.directories),
+                ShellHistoryPick::Commands => ("Command History", history.commands),
+            };
+            if entries.is_empty() {
+                platform_bridge::show_native_notification(
+                    NativeNotificationOptions::new(format!("No {}", title.to_lowercase()))
+                        .message(format!("Nothing found in the {} history.", history.shell))
+                        .kind(NativeNotificationKind::Info),
+                );
+                return;
+            }
+
+            let items = entries
+                .iter()
+                .map(|entry| ListPickerItem {
+                    label: match pick {
+                        ShellHistoryPick::Directories => abbreviate_home(entry, &homedir),
+                        ShellHistoryPick::Commands => entry.clone(),
+                    },
+                    subtitle: None,
+                    image_name: None,
+                    trailing_icon: None,
+                })
+                .collect();
+            let (selection_tx, selection_rx) = tokio::sync::oneshot::channel();
+            platform_bridge::show_list_picker(
+                title,
+                &format!("From {} history on this host.", history.shell),
+                items,
+                move |selection| {
+                    let _ = selection_tx.send(selection.map(|selection| selection.index));
+                },
+            );
+            let Ok(Some(index)) = selection_rx.await else {
+                return;
+            };
+            let Some(entry) = entries.get(index) else {
+                return;
+            };
+            let _ = terminal_view.update(cx, |terminal_view, cx| match pick {
+                ShellHistoryPick::Directories => {
+                    terminal_view.send_text(&format!("cd -- {}\r", shell_quote(entry)), cx);
+                }
+                ShellHistoryPick::Commands => {
+                    terminal_view.paste_text_from_native_menu(entry, cx);
+                }
+            });
+        })
+        .detach();
+    }
+
+    /// Offer the tasks declared in the workspace's project files and run the
+    /// pick in this terminal from the workspace root.
+    fn pick_task(&mut self, cx: &mut Context<Self>) {
+        let session_handle = self.session_handle.clone();
+        let terminal_view = self.terminal_view.clone();
+        let workdir = self.workspace_state.read(cx).workdir.clone();
+        cx.spawn(async move |_this, cx| {
+            let tasks = match session_handle.task_discover().await {
+                Ok(result) => result.tasks,
+                Err(error) => {
+                    warn!("task discovery failed: {}", error);
+                    platform_bridge::show_alert(
+                        "Tasks Unavailable",
+                        &error.to_string(),
+                        vec![AlertButton::cancel("OK")],
+                        |_| {},
+                    );
+                    return;
+                }
+            };
+            if tasks.is_empty() {
+                platform_bridge::show_native_notification(
+                    NativeNotificationOptions::new("No tasks found")
+                        .message("No package.json scripts, Makefile targets, Cargo aliases, or just recipes in this workspace.")
+                        .kind(NativeNotificationKind::Info),
+                );
+                return;
+            }
+
+            let items = tasks
+                .iter()
+                .map(|task| ListPickerItem {
+                    label: task.name.clone(),
+                    subtitle: Some(format!(
+                        "{} · {}",
+                        task.source.label(),
+                        task.detail.as_deref().unwrap_or(&task.command)
+                    )),
+                    image_name: None,
+                    trailing_icon: None,
+                })
+                .collect();
+            let (selection_tx, selection_rx) = tokio::sync::oneshot::channel();
+            platform_bridge::show_list_picker(
+                "Run Task",
+                "Declared in this workspace's project files.",
+                items,
+                move |selection| {
+                    let _ = selection_tx.send(selection.map(|selection| selection.index));
+                },
+            );
+            let Ok(Some(index)) = selection_rx.await else {
+                return;
+            };
+            let Some(task) = tasks.get(index) else {
+                return;
+            };
+            let line = if workdir.is_empty() {
+                format!("{}\r", task.command)
+            } else {
+                format!("cd -- {} && {}\r", shell_quote(&workdir), task.command)
+            };
+            let _ = terminal_view.update(cx, |terminal_view, cx| {
+                terminal_view.send_text(&line, cx);
+            });
+        })
+        .detach();
+    }
+
     fn resize_remote_terminal(
         session_handle: SessionHandle,
         terminal_id: String,
@@ -598,6 +1164,20 @@ impl WorkspaceTerminal {
         })
         .detach();
     }
+
+    /// Dropped or pasted text goes to the PTY as one paste write (bracketed
+    /// when the app enabled it), not one key event per character.
+    fn handle_insert_text(
+        &mut self,
+        action: &InsertText,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.terminal_view.update(cx, |terminal_view, cx| {
+            terminal_view.paste_text_from_native_menu(&action.text, cx);
+            terminal_view.scroll_to_bottom(cx);
+        });
+    }
 }
 
 impl Render for WorkspaceTerminal {
@@ -642,10 +1222,12 @@ impl Render for WorkspaceTerminal {
         let keyboard_inset_f = (keyboard_inset / px(1.0)) as f32;
         let bottom_offset = platform_bridge::home_indicator_inset().max(keyboard_inset_f);
         let this = cx.weak_entity();
+        let unseen_lines = self.unseen_lines;
 
         div()
             .id(("workspace-terminal-surface", cx.entity_id()))
             .track_focus(&self.container_focus)
+            .on_action(cx.listener(Self::handle_insert_text))
             .relative()
             .size_full()
             // Alt-screen TUIs (vim, OpenCode) need the container to shrink so reconcile fires
@@ -676,6 +1258,43 @@ impl Render for WorkspaceTerminal {
                     .h(px(48.0)),
                 )
             })
+            .when(
+                self.scroll_to_bottom_button_visible && unseen_lines > 0,
+                |container| {
+                    let this = cx.weak_entity();
+                    container.child(
+                        div()
+                            .id(("terminal-unseen-lines", cx.entity_id()))
+                            .absolute()
+                            .right(px(24.0 + 48.0 + 8.0))
+                            .bottom(px(24.0 + bottom_offset + 10.0))
+                            .h(px(28.0))
+                            .px(px(12.0))
+                            .flex()
+                            .items_center()
+                            .rounded_full()
+                            .bg(rgb(theme::bg_card(cx)))
+                            .border_1()
+                            .border_color(rgb(theme::border_subtle(cx)))
+                            .text_size(theme::scaled(theme::FONT_DETAIL))
+                            .text_color(rgb(theme::text_secondary(cx)))
+                            .child(unseen_lines_label(unseen_lines))
+                            .on_press(move |_event, _window, cx| {
+                                let _ = this.update(cx, |terminal, cx| {
+                                    terminal.scroll_to_bottom(cx);
+                                });
+                            }),
+                    )
+                },
+            )
+    }
+}
+
+fn unseen_lines_label(lines: usize) -> String {
+    if lines == 1 {
+        "1 new line".to_string()
+    } else {
+        format!("{lines} new lines")
     }
 }
 
@@ -1,13 +1,16 @@
 use std::sync::Arc;
 
+use futures::channel::oneshot;
 use gpui::*;
 use tracing::*;
 use zedra_rpc::ZedraPairingTicket;
 use zedra_session::{ConnectPhase, signer::ClientSigner};
 
 use crate::delta::DeltaState;
+use crate::host_accent::HostAccent;
+use crate::memory::{MemoryPressure, MemoryReclaim};
 use crate::pending::PendingSlot;
-use crate::platform_bridge::{self, HapticFeedback};
+use crate::platform_bridge::{self, AlertButton, HapticFeedback};
 use crate::workspace::{Workspace, WorkspaceEvent};
 use crate::workspace_state::WorkspaceState;
 
@@ -48,7 +51,10 @@ pub struct Workspaces {
 
 impl Workspaces {
     pub fn new(delta_state: Entity<DeltaState>, cx: &mut Context<Self>) -> Self {
-        let signer = load_client_signer();
+        let (signer, identity_lost) = match load_client_signer() {
+            Ok(signer) => (Some(signer), false),
+            Err(lost) => (None, lost),
+        };
 
         let states = WorkspaceState::load()
             .map_err(|e| error!("Failed to load saved workspace states: {e}"))
@@ -70,9 +76,46 @@ impl Workspaces {
             _subscriptions: Vec::new(),
         };
         this.emit_states_changed(cx);
+        if identity_lost {
+            this.offer_identity_reset(cx);
+        }
         this
     }
 
+    /// The vault key is gone, so the saved identity can never be read again.
+    /// Offer to replace it; hosts must then be paired again.
+    fn offer_identity_reset(&self, cx: &mut Context<Self>) {
+        let (tx, rx) = oneshot::channel();
+        platform_bridge::show_alert(
+            "Device Identity Unreadable",
+            "The system key store no longer opens this device's saved identity. Reset it to create a new one; saved hosts must be paired again by scanning their QR codes.",
+            vec![
+                AlertButton::destructive("Reset Identity"),
+                AlertButton::cancel("Not Now"),
+            ],
+            move |button_index| {
+                let _ = tx.send(button_index);
+            },
+        );
+        cx.spawn(async move |this, cx| {
+            if let Ok(0) = rx.await {
+                let _ = this.update(cx, |this, cx| this.reset_identity(cx));
+            }
+        })
+        .detach();
+    }
+
+    fn reset_identity(&mut self, cx: &mut Context<Self>) {
+        match crate::vault::reset_identity() {
+            Ok(signer) => {
+                info!("Reset device identity after losing the vault key");
+                self.signer = Some(Arc::new(signer));
+                cx.notify();
+            }
+            Err(e) => error!("Failed to reset device identity: {e:#}"),
+        }
+    }
+
     pub fn active(&self) -> Option<&Entity<Workspace>> {
         self.active_index.and_then(|i| self.entries.get(i))
     }
@@ -116,6 +159,15 @@ impl Workspaces {
             .cloned()
     }
 
+    /// Endpoint of the saved workspace for host session `session_id`.
+    pub fn endpoint_addr_for_session(&self, session_id: &str, cx: &App) -> Option<String> {
+        self.states
+            .iter()
+            .map(|state| state.read(cx))
+            .find(|state| state.session_id == session_id)
+            .map(|state| state.endpoint_addr.clone())
+    }
+
     pub fn entry_index_by_endpoint_addr(&self, endpoint_addr: &str, cx: &App) -> Option<usize> {
         self.entries
             .iter()
@@ -313,6 +365,28 @@ impl Workspaces {
         }
     }
 
+    /// Switch to the saved workspace serving `workdir` on `hostname`, through the
+    /// same pending nav as `zedra://open`. Each project has its own daemon, so
+    /// without a saved pairing the user has to scan that daemon's QR code.
+    fn open_host_project(&mut self, hostname: &str, workdir: &str, cx: &mut Context<Self>) {
+        let endpoint_addr = self.states.iter().find_map(|state| {
+            let state = state.read(cx);
+            (state.hostname == hostname && state.workdir == workdir)
+                .then(|| state.endpoint_addr.clone())
+        });
+        match endpoint_addr {
+            Some(endpoint_addr) => self.navigate_workspace_deferred(endpoint_addr, None, cx),
+            None => platform_bridge::show_alert(
+                "Not Paired Here",
+                &format!(
+                    "This device has no saved pairing for {workdir}. Run `zedra qr --workdir {workdir}` on the host and scan it to open this project."
+                ),
+                vec![platform_bridge::AlertButton::cancel("OK")],
+                |_| {},
+            ),
+        }
+    }
+
     /// Reconnect to a saved workspace by state index.
     pub fn connect_saved(
         &mut self,
@@ -423,10 +497,21 @@ impl Workspaces {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let Some(signer) = self.signer.clone() else {
-            error!("No client signer available. Cannot connect to workspace.");
-            return;
+        let loaded = match self.signer.clone() {
+            Some(signer) => Ok(signer),
+            None => load_client_signer(),
+        };
+        let signer = match loaded {
+            Ok(signer) => signer,
+            Err(identity_lost) => {
+                error!("No client signer available. Cannot connect to workspace.");
+                if identity_lost {
+                    self.offer_identity_reset(cx);
+                }
+                return;
+            }
         };
+        self.signer = Some(signer.clone());
 
         let encoded_addr = match zedra_rpc::pairing::encode_endpoint_addr(&addr) {
             Ok(s) => s,
@@ -469,6 +554,28 @@ impl Workspaces {
         cx.notify();
     }
 
+    /// Open a workspace backed by the in-process mock host (developer menu).
+    /// Its state is not added to the saved workspaces.
+    pub fn open_mock_workspace(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let workspace_state = cx.new(|_cx| {
+            let mut ws = WorkspaceState::default();
+            ws.endpoint_addr = "mock".to_string();
+            ws
+        });
+        let delta_state = self.delta_state.clone();
+        let workspace = cx.new(|cx| Workspace::new(workspace_state, delta_state, window, cx));
+        let sub = self.subscribe_workspace_event(&workspace, cx);
+        self._subscriptions.push((workspace.clone(), sub));
+        workspace.update(cx, |ws, cx| ws.connect_mock(window, cx));
+
+        self.entries.push(workspace);
+        let ws_idx = self.entries.len() - 1;
+        self.active_index = Some(ws_idx);
+        self.sync_active_workspace(cx);
+        cx.emit(WorkspacesEvent::Connected { index: ws_idx });
+        cx.notify();
+    }
+
     fn subscribe_workspace_event(
         &self,
         workspace: &Entity<Workspace>,
@@ -484,6 +591,9 @@ impl Workspaces {
                 WorkspaceEvent::OpenQuickAction => {
                     cx.emit(WorkspacesEvent::OpenQuickAction);
                 }
+                WorkspaceEvent::OpenHostProject { hostname, workdir } => {
+                    this.open_host_project(hostname, workdir, cx);
+                }
                 WorkspaceEvent::Disconnected => {
                     let index = this.entries.iter().position(|e| *e == ws_entity);
                     if let Some(index) = index {
@@ -522,6 +632,18 @@ impl Workspaces {
         }
     }
 
+    pub fn trim_memory(
+        &mut self,
+        pressure: MemoryPressure,
+        cx: &mut Context<Self>,
+    ) -> MemoryReclaim {
+        let mut reclaim = MemoryReclaim::default();
+        for entry in self.entries.clone() {
+            reclaim.add(entry.update(cx, |ws, cx| ws.trim_memory(pressure, cx)));
+        }
+        reclaim
+    }
+
     pub fn handle_system_back(&mut self, window: &mut Window, cx: &mut Context<Self>) -> bool {
         self.active().cloned().is_some_and(|workspace| {
             workspace.update(cx, |ws, cx| ws.handle_system_back(window, cx))
@@ -567,6 +689,29 @@ impl Workspaces {
         }
     }
 
+    pub fn set_workspace_accent(
+        &mut self,
+        endpoint_addr: &str,
+        accent: Option<HostAccent>,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(state) = self
+            .states
+            .iter()
+            .find(|s| s.read(cx).endpoint_addr == endpoint_addr)
+            .cloned()
+        {
+            state.update(cx, |s, cx| s.set_accent(accent, cx));
+            // Same persistence split as `rename_workspace`.
+            let is_active = self.entry_by_endpoint_addr(endpoint_addr, cx).is_some();
+            if !is_active {
+                WorkspaceState::upsert(state.read(cx).clone())
+                    .map_err(|e| error!("Failed to persist workspace accent: {e}"))
+                    .ok();
+            }
+        }
+    }
+
     pub fn remove_saved(&mut self, endpoint_addr: &str, cx: &mut Context<Self>) {
         WorkspaceState::remove_by_endpoint_add(endpoint_addr)
             .map_err(|e| error!("Failed to remove workspace state: {e}"))
@@ -614,17 +759,14 @@ impl Workspaces {
     }
 }
 
-/// Load the persistent client Ed25519 signing key.
-fn load_client_signer() -> Option<Arc<dyn ClientSigner>> {
-    let data_dir = platform_bridge::bridge().data_directory()?;
-    let key_path = std::path::PathBuf::from(data_dir)
-        .join("zedra")
-        .join("client.key");
-    match zedra_session::signer::FileClientSigner::load_or_generate(&key_path) {
-        Ok(signer) => Some(Arc::new(signer)),
+/// Load the persistent client Ed25519 signing key from the secrets vault. On
+/// failure, reports whether the vault key was lost, which only a reset fixes.
+fn load_client_signer() -> Result<Arc<dyn ClientSigner>, bool> {
+    match crate::vault::client_signer() {
+        Ok(signer) => Ok(Arc::new(signer)),
         Err(e) => {
             error!("Failed to load client signing key: {}", e);
-            None
+            Err(crate::vault::is_key_lost(&e))
         }
     }
 }
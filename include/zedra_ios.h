@@ -253,7 +253,8 @@ extern void ios_request_delta_push_token(uint32_t callback_id);
 extern void ios_present_text_input(uint32_t callback_id,
                                    const char *title,
                                    const char *placeholder,
-                                   const char *initial_value);
+                                   const char *initial_value,
+                                   bool secure);
 
 /**
  * Returns 1 for dark, 0 for light, -1 when unavailable.
@@ -276,6 +277,17 @@ extern void ios_acquire_image(uint32_t callback_id, int32_t source);
  */
 extern bool ios_clipboard_has_image(void);
 
+/**
+ * Copies the 32-byte secrets vault key from the Keychain into `out`,
+ * creating it on first use. Returns false when the Keychain refused.
+ */
+extern bool ios_vault_key(uint8_t *out);
+
+/**
+ * Delete the secrets vault key from the Keychain.
+ */
+extern void ios_delete_vault_key(void);
+
 /**
  * Show or update a native progress HUD (spinner + message) for `id`.
  */
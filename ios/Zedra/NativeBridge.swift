@@ -1,11 +1,51 @@
 import AVFoundation
 import AudioToolbox
+private let vaultKeyQuery: [String: Any] = [
+    kSecClass as String: kSecClassGenericPassword,
+    kSecAttrService as String: "dev.zedra.vault",
+    kSecAttrAccount as String: "vault_key",
+]
+
+/// Copies the 32-byte secrets vault key into `out`, creating it in the
+/// Keychain (this device only, after first unlock) on first use.
+@_cdecl("ios_vault_key")
+func ios_vault_key(_ out: UnsafeMutablePointer<UInt8>) -> Bool {
+    var query = vaultKeyQuery
+    query[kSecReturnData as String] = true
+    query[kSecMatchLimit as String] = kSecMatchLimitOne
+    var item: CFTypeRef?
+    let status = SecItemCopyMatching(query as CFDictionary, &item)
+    if status == errSecSuccess, let data = item as? Data, data.count == 32 {
+        data.copyBytes(to: out, count: 32)
+        return true
+    }
+    guard status == errSecItemNotFound else { return false }
+
+    var key = Data(count: 32)
+    let generated = key.withUnsafeMutableBytes { buffer in
+        SecRandomCopyBytes(kSecRandomDefault, 32, buffer.baseAddress!)
+    }
+    guard generated == errSecSuccess else { return false }
+    var attributes = vaultKeyQuery
+    attributes[kSecValueData as String] = key
+    attributes[kSecAttrAccessible as String] = kSecAttrAccessibleAfterFirstUnlockThisDeviceOnly
+    guard SecItemAdd(attributes as CFDictionary, nil) == errSecSuccess else { return false }
+    key.copyBytes(to: out, count: 32)
+    return true
+}
+
+@_cdecl("ios_delete_vault_key")
+func ios_delete_vault_key() {
+    SecItemDelete(vaultKeyQuery as CFDictionary)
+}
+
 #if !ZEDRA_NO_TELEMETRY
 import FirebaseAnalytics
 import FirebaseCore
 import FirebaseCrashlytics
 #endif
 import Foundation
+import Security
 import UIKit
 import ZedraFFI
 
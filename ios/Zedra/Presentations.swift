@@ -1793,7 +1793,8 @@ private enum PresentationCoordinator {
         callbackID: UInt32,
         title: String?,
         placeholder: String?,
-        initialValue: String?
+        initialValue: String?,
+        secure: Bool
     ) {
         DispatchQueue.main.async {
             guard let presenter = NativePresentationBridge.topViewController() else { return }
@@ -1811,7 +1812,9 @@ private enum PresentationCoordinator {
                 field.tintColor = NativePresentationTheme.primaryTextColor
                 field.overrideUserInterfaceStyle = NativePresentationTheme.interfaceStyle
                 field.clearButtonMode = .whileEditing
-                field.autocapitalizationType = .words
+                field.autocapitalizationType = secure ? .none : .words
+                field.autocorrectionType = secure ? .no : .default
+                field.isSecureTextEntry = secure
                 field.returnKeyType = .done
             }
             alert.addAction(UIAlertAction(title: "Save", style: .default) { _ in
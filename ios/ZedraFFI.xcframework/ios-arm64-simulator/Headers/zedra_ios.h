@@ -253,7 +253,8 @@ extern void ios_request_delta_push_token(uint32_t callback_id);
 extern void ios_present_text_input(uint32_t callback_id,
                                    const char *title,
                                    const char *placeholder,
-                                   const char *initial_value);
+                                   const char *initial_value,
+                                   bool secure);
 
 /**
  * Returns 1 for dark, 0 for light, -1 when unavailable.